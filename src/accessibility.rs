@@ -0,0 +1,136 @@
+//! Accessibility mode: a 16-colour high-contrast palette (see
+//! [`crate::theme::ACCESSIBLE`]) plus a final pass over each rendered frame
+//! that swaps box drawing, arrows and other symbols for ASCII and drops emoji,
+//! so every screen works on limited terminals and with screen readers.
+
+use crate::db::Db;
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::text::Span;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Turns the mode on from `--accessible`, `RUSTORIA_ACCESSIBLE=1` or the
+/// saved setting, in that order.
+pub fn load(db: &Db) {
+    let from_env = std::env::var("RUSTORIA_ACCESSIBLE")
+        .ok()
+        .map(|value| value == "1" || value.eq_ignore_ascii_case("on"));
+    let saved = crate::db::get_setting(db, "accessibility")
+        .ok()
+        .flatten()
+        .map(|value| value == "on");
+    let flag = std::env::args().any(|arg| arg == "--accessible");
+    set_enabled(flag || from_env.or(saved).unwrap_or(false));
+}
+
+fn is_emoji(symbol: &str) -> bool {
+    symbol.chars().any(|c| {
+        let c = c as u32;
+        c == 0xFE0F || c >= 0x1F000 || ((0x2600..=0x27BF).contains(&c) && c != 0x2713)
+    })
+}
+
+fn is_border(symbol: &str) -> bool {
+    symbol
+        .chars()
+        .next()
+        .is_some_and(|c| ('\u{2500}'..='\u{257F}').contains(&c))
+}
+
+fn ascii(c: char) -> Option<&'static str> {
+    let plain = match c {
+        '─' | '━' | '═' | '╌' | '┄' | '—' | '–' | '↔' => "-",
+        '│' | '┃' | '║' | '╎' | '┆' => "|",
+        '\u{2500}'..='\u{257F}' => "+",
+        '►' | '▶' | '→' | '»' | '⇒' => ">",
+        '◄' | '◀' | '←' | '«' => "<",
+        '↑' | '▲' => "^",
+        '↓' | '▼' => "v",
+        '●' | '•' | '■' | '◆' | '★' | '✓' | '✔' => "*",
+        '○' | '◯' => "o",
+        '·' | '…' => ".",
+        '×' | '✗' | '✘' => "x",
+        '⚠' => "!",
+        '░' => " ",
+        '\u{2580}'..='\u{259F}' => "#",
+        '\u{2800}'..='\u{28FF}' => SPINNER[c as usize % SPINNER.len()],
+        '\u{2000}'..='\u{200A}' | '\u{202F}' | '\u{205F}' => " ",
+        '‘' | '’' | '‚' | '‛' => "'",
+        '“' | '”' | '„' | '‟' => "\"",
+        // Currency signs, such as ₹ and €, are kept: amounts need them.
+        '\u{20A0}'..='\u{20CF}' => return None,
+        '\u{2000}'..='\u{2BFF}' => "*",
+        _ => return None,
+    };
+    Some(plain)
+}
+
+/// Removes the emoji at `row[start]`, plus the space after it, by pulling the
+/// rest of the text run left. The gap left at the end of the run is filled
+/// with the border it ran into, or blanks.
+fn remove_emoji(row: &mut [Cell], start: usize) {
+    let width = Span::raw(row[start].symbol()).width().max(1);
+    let mut removed = width.min(row.len() - start);
+    if row.get(start + removed).is_some_and(|c| c.symbol() == " ") {
+        removed += 1;
+    }
+
+    let mut end = start + removed;
+    while end < row.len()
+        && !is_border(row[end].symbol())
+        && !(row[end].symbol() == " " && row.get(end + 1).is_none_or(|c| c.symbol() == " "))
+    {
+        end += 1;
+    }
+
+    let filler = match row.get(end) {
+        Some(cell) if is_border(cell.symbol()) => cell.clone(),
+        _ => {
+            let mut blank = row[start].clone();
+            blank.set_symbol(" ");
+            blank
+        }
+    };
+    row[start..end].rotate_left(removed);
+    for cell in &mut row[end - removed..end] {
+        *cell = filler.clone();
+    }
+}
+
+/// Rewrites a rendered frame in place for accessibility mode.
+pub fn simplify(buffer: &mut Buffer) {
+    let width = buffer.area.width as usize;
+    if width == 0 {
+        return;
+    }
+    for row in buffer.content.chunks_mut(width) {
+        let mut x = 0;
+        while x < row.len() {
+            let symbol = row[x].symbol();
+            if symbol.is_ascii() {
+                x += 1;
+                continue;
+            }
+            if is_emoji(symbol) {
+                remove_emoji(row, x);
+                continue;
+            }
+            let replacement = symbol.chars().next().and_then(ascii);
+            if let Some(plain) = replacement {
+                row[x].set_symbol(plain);
+            }
+            x += 1;
+        }
+    }
+}
@@ -1,34 +1,125 @@
-use crate::auth::{login, Credentials};
+use crate::auth::{self, login, Credentials};
+use crate::components::database_error::{DatabaseError, DatabaseErrorAction};
+use crate::components::help::render_help;
+use crate::components::hospital::appointments::AppointmentsState;
 use crate::components::hospital::finance::FinanceState;
+use crate::components::hospital::handover::HandoverState;
+use crate::components::hospital::inventory::InventoryState;
+use crate::components::hospital::maternity::MaternityState;
+use crate::components::hospital::queue::QueueState;
 use crate::components::hospital::records::delete::DeleteRecord;
+use crate::components::hospital::records::integrity::{Entity, Finding};
 use crate::components::hospital::records::update::UpdateRecord;
 use crate::components::hospital::records::RecordsState;
+use crate::components::hospital::registry::RegistryState;
 use crate::components::hospital::staff::delete::DeleteStaff;
 use crate::components::hospital::staff::update::UpdateStaff;
+use crate::components::hospital::telemedicine::TelemedicineState;
+use crate::components::hospital::waitlist::WaitlistState;
+use crate::components::hospital::worklist::WorklistState;
 use crate::components::hospital::{self, HospitalState};
-use crate::components::{home::Home, login::Login, register::Register, Component};
+use crate::components::quick_actions;
+use crate::components::responsive::{is_too_small, render_too_small};
+use crate::components::schema_prompt::{SchemaChoice, SchemaPrompt};
+use crate::components::tabs::{module_of, render_tab_bar, MODULE_TABS};
+use crate::components::toast::{self, render_toasts, Toast};
+use crate::components::{
+    home::Home,
+    kiosk::Kiosk,
+    login::Login,
+    palette::CommandPalette,
+    quick_vitals::QuickVitals,
+    register::Register,
+    scan::{ScanAction, ScanPrompt, ScanTarget},
+    settings::Settings,
+    webhook_log::WebhookLog,
+    Component,
+};
+use crate::crash;
+use crate::db::{self, Db, SchemaStatus};
+use crate::i18n::{t, tf};
+use crate::keymap::keymap_for;
+use crate::locks;
+use crate::notify;
+use crate::plugins;
+use crate::theme::theme;
 use crate::tui::{self, Tui};
+use crate::ui_state::{self, Recent, RecentKind};
+use crate::utils::config::{self, key_matches};
+use crate::webhooks;
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::Paragraph;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SelectedApp {
     PatientAdd,
     PatientList,
     PatientDelete,
+    PatientCompliance,
+    PatientImport,
     PatientUpdate,
     StaffAdd,
     StaffAssign,
     StaffList,
     StaffDelete,
     StaffUpdate,
+    StaffPerformance,
     RecordStore,
     RecordRetrieve,
     RecordUpdate,
     RecordDelete,
+    RecordStatistics,
+    RecordExpirations,
+    RecordIsolations,
+    RecordStays,
+    RecordIntegrity,
+    RecordWaiting,
+    RecordForecast,
+    RecordSatisfaction,
+    RecordCampaign,
     BillingInvoice,
     BillingView,
     BillingUpdate,
+    BillingExpenses,
+    BillingExpenseReport,
+    BillingBudget,
+    BillingCashier,
+    BillingCashierHistory,
+    BillingInsurance,
+    BillingApprovals,
+    WaitlistAdd,
+    WaitlistList,
+    RegistryBirths,
+    RegistryDeaths,
+    TeleconsultSchedule,
+    TeleconsultUpcoming,
+    TeleconsultUtilization,
+    QueueIssue,
+    QueueConsole,
+    QueueDisplay,
+    HandoverRecord,
+    HandoverHistory,
+    WorklistMyDay,
+    WorklistWardRound,
+    WorklistFollowUps,
+    MaternityPregnancies,
+    MaternityDue,
+    AppointmentBook,
+    AppointmentList,
+    AppointmentUpdate,
+    InventoryStock,
+    InventoryStocktake,
+    Settings,
+    /// Settings, with a database backup started on arrival.
+    SettingsBackup,
+    WebhookLog,
+    /// A screen from [`crate::plugins`], by registry position.
+    Plugin(usize),
     Hospital,
     None,
     Quit,
@@ -46,23 +137,57 @@ pub enum AppState {
 }
 
 pub struct App {
+    /// The database every screen works on, shared with them as clones.
+    db: Db,
     pub state: AppState,
     pub should_quit: bool,
     pub home: Home,
     pub login: Login,
     pub register: Register,
     pub hospital: Option<hospital::HospitalApp>,
+    pub settings: Settings,
+    pub webhook_log: WebhookLog,
+    pub show_help: bool,
+    pub palette: Option<CommandPalette>,
+    /// Takes wristband and sample label scans, over any screen.
+    pub scan: Option<ScanPrompt>,
+    /// Takes a patient's vital signs, over any screen.
+    pub vitals: Option<QuickVitals>,
+    pub plugin: Option<Box<dyn Component>>,
+    /// The read-only screens of `--kiosk`, replacing everything else.
+    pub kiosk: Option<Kiosk>,
+    /// Covers every other screen while the database cannot be used.
+    pub database_error: Option<DatabaseError>,
+    schema_prompt: Option<SchemaPrompt>,
+    last_input: Instant,
+    /// Keys typed since macro recording started, while it is on.
+    recording: Option<Vec<KeyEvent>>,
+    last_macro: Vec<KeyEvent>,
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
         Self {
+            db: db.clone(),
             state: AppState::Init,
             should_quit: false,
-            home: Home::new(),
+            home: Home::new(db),
             login: Login::new(),
-            register: Register::new(),
+            register: Register::new(db),
             hospital: None,
+            settings: Settings::new(db),
+            webhook_log: WebhookLog::new(db),
+            show_help: false,
+            palette: None,
+            scan: None,
+            vitals: None,
+            plugin: None,
+            kiosk: None,
+            database_error: None,
+            schema_prompt: None,
+            last_input: Instant::now(),
+            recording: None,
+            last_macro: Vec::new(),
         }
     }
 
@@ -70,6 +195,55 @@ impl App {
         self.state = AppState::Login;
 
         while !self.should_quit {
+            self.expire_session();
+            crash::set_context(self.summary());
+            tui.draw(|frame| self.render_ui(frame))?;
+
+            self.handle_input(tui)?;
+        }
+        self.save_view();
+        // Losing the remembered layout is not worth failing the exit over.
+        let _ = ui_state::save();
+        Ok(())
+    }
+
+    pub fn run_display_board(&mut self, tui: &mut Tui) -> Result<()> {
+        while !self.should_quit {
+            // Opens the board once the database is usable, which may take a
+            // retry from the error screen.
+            if self.database_error.is_none()
+                && self.schema_prompt.is_none()
+                && self.state == AppState::Init
+            {
+                self.open_app(SelectedApp::QueueDisplay)?;
+            }
+            crash::set_context(self.summary());
+            tui.draw(|frame| self.render_ui(frame))?;
+
+            self.handle_input(tui)?;
+            if self.database_error.is_none()
+                && self.schema_prompt.is_none()
+                && self.state != AppState::Init
+                && self.state != AppState::Running(SelectedApp::QueueDisplay)
+            {
+                self.should_quit = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// Shows the kiosk screens until quit. The database is opened read-only
+    /// and nobody logs in, so nothing can be changed from the display.
+    pub fn run_kiosk(&mut self, tui: &mut Tui) -> Result<()> {
+        while !self.should_quit {
+            // Starts once the database is usable, which may take an upgrade
+            // or a retry first.
+            if self.kiosk.is_none() && self.database_error.is_none() && self.schema_prompt.is_none()
+            {
+                self.db.set_read_only();
+                self.kiosk = Some(Kiosk::new(&self.db));
+            }
+            crash::set_context(self.summary());
             tui.draw(|frame| self.render_ui(frame))?;
 
             self.handle_input(tui)?;
@@ -77,365 +251,1279 @@ impl App {
         Ok(())
     }
 
+    /// A short description of what is on screen, for crash reports.
+    fn summary(&self) -> String {
+        let screen = match &self.hospital {
+            Some(hospital) if matches!(self.state, AppState::Running(_)) => {
+                format!(" ({:?} module)", hospital.state)
+            }
+            _ => String::new(),
+        };
+        format!(
+            "State: {:?}{}\nHelp open: {}\nPalette open: {}\nLocale: {}\nAccessibility mode: {}",
+            self.state,
+            screen,
+            self.show_help,
+            self.palette.is_some(),
+            crate::i18n::locale(),
+            crate::accessibility::enabled(),
+        )
+    }
+
+    fn requested_tab(&self, key: KeyEvent, selected_app: SelectedApp) -> Option<usize> {
+        let current = module_of(selected_app);
+        match key.code {
+            KeyCode::Char(c) if key.modifiers == KeyModifiers::ALT => c
+                .to_digit(10)
+                .map(|d| d as usize)
+                .filter(|d| (1..=MODULE_TABS.len()).contains(d))
+                .map(|d| d - 1),
+            KeyCode::Tab if key.modifiers == KeyModifiers::CONTROL => {
+                current.map(|i| (i + 1) % MODULE_TABS.len())
+            }
+            KeyCode::BackTab if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                current.map(|i| (i + MODULE_TABS.len() - 1) % MODULE_TABS.len())
+            }
+            _ => None,
+        }
+        .filter(|tab| current.is_some() && Some(*tab) != current)
+    }
+
+    /// Remembers the open list screen before it is closed or replaced.
+    /// Logs out once the configured number of minutes pass without input. The
+    /// last screen stays remembered, so logging back in resumes it.
+    fn expire_session(&mut self) {
+        let minutes = config::config().session_timeout;
+        let logged_in = matches!(self.state, AppState::Home | AppState::Running(_));
+        if minutes == 0
+            || !logged_in
+            || self.last_input.elapsed() < Duration::from_secs(minutes * 60)
+        {
+            return;
+        }
+        self.save_view();
+        let _ = ui_state::save();
+        self.hospital = None;
+        self.palette = None;
+        self.scan = None;
+        self.vitals = None;
+        self.show_help = false;
+        self.database_error = None;
+        self.login.password.clear();
+        toast::clear();
+        toast::push(Toast::error(tf(
+            "app.session_expired",
+            &[("minutes", &minutes)],
+        )));
+        self.state = AppState::Login;
+    }
+
+    fn save_view(&self) {
+        if let Some(hospital) = &self.hospital {
+            hospital.save_view();
+        }
+    }
+
+    /// Opens `selected_app`, switching to the database error screen when its
+    /// data cannot be loaded.
+    fn open_app(&mut self, selected_app: SelectedApp) -> Result<()> {
+        if let Err(e) = self.try_open_app(selected_app) {
+            self.database_error = Some(DatabaseError::new(&self.db, &e, Some(selected_app)));
+        }
+        Ok(())
+    }
+
+    /// Prepares the database for the session, asking first when it was
+    /// written by another version and reporting it when it cannot be opened.
+    pub fn open_database(&mut self) {
+        match db::init_db(&self.db) {
+            Ok(SchemaStatus::Current { created_root }) => {
+                if created_root {
+                    toast::push(Toast::success(t("database_error.root_created")));
+                }
+            }
+            Ok(status) => self.schema_prompt = Some(SchemaPrompt::new(&self.db, status)),
+            Err(e) => self.database_error = Some(DatabaseError::new(&self.db, &e, None)),
+        }
+    }
+
+    /// Repeats whatever failed: start-up, or opening a screen.
+    fn retry_database(&mut self) -> Result<()> {
+        let Some(error) = self.database_error.take() else {
+            return Ok(());
+        };
+        match error.retry {
+            Some(selected_app) => self.open_app(selected_app)?,
+            None => self.open_database(),
+        }
+        if self.database_error.is_none() && self.schema_prompt.is_none() {
+            toast::push(Toast::success(t("database_error.recovered")));
+        }
+        Ok(())
+    }
+
+    fn choose_schema(&mut self, choice: SchemaChoice) {
+        self.schema_prompt = None;
+        match choice {
+            SchemaChoice::Upgrade => match db::upgrade_db(&self.db) {
+                Ok(created_root) => {
+                    toast::push(Toast::success(tf(
+                        "schema.upgraded",
+                        &[("version", &db::SCHEMA_VERSION)],
+                    )));
+                    if created_root {
+                        toast::push(Toast::success(t("database_error.root_created")));
+                    }
+                }
+                Err(e) => self.database_error = Some(DatabaseError::new(&self.db, &e, None)),
+            },
+            SchemaChoice::ReadOnly => {
+                self.db.set_read_only();
+                toast::push(Toast::success(t("schema.opened_read_only")));
+            }
+            SchemaChoice::Quit => self.should_quit = true,
+        }
+    }
+
+    /// Shows the patient or imaging study a scanned label belongs to in the
+    /// patient list.
+    fn open_scanned(&mut self, target: ScanTarget) -> Result<()> {
+        self.open_app(SelectedApp::PatientList)?;
+        let Some(hospital) = &mut self.hospital else {
+            return Ok(());
+        };
+        let list = &mut hospital.patients.list_patients;
+        match target {
+            ScanTarget::Patient(patient_id) => list.show_patient(patient_id),
+            ScanTarget::Study {
+                patient_id,
+                accession_number,
+            } => list.show_study(patient_id, &accession_number),
+        }
+    }
+
+    /// Reopens a patient, record or invoices picked from the recently opened
+    /// items on the home screen.
+    fn open_recent(&mut self, recent: Recent) -> Result<()> {
+        let app = match recent.kind {
+            RecentKind::Patient => SelectedApp::PatientList,
+            RecentKind::Record => SelectedApp::RecordRetrieve,
+            RecentKind::Invoices => SelectedApp::BillingView,
+        };
+        self.open_app(app)?;
+        let Some(hospital) = &mut self.hospital else {
+            return Ok(());
+        };
+        match recent.kind {
+            RecentKind::Patient => hospital.patients.list_patients.show_patient(recent.id)?,
+            RecentKind::Record => hospital.records.retrieve_records.show_record(recent.id),
+            RecentKind::Invoices => hospital.finance.view_invoices.show_patient(recent.id),
+        }
+        Ok(())
+    }
+
+    /// Opens the update screen for an integrity finding with its row
+    /// already loaded.
+    fn open_fix(&mut self, fix: Finding) -> Result<()> {
+        let Some(app) = fix.entity.fix_app() else {
+            return Ok(());
+        };
+        self.open_app(app)?;
+        let Some(hospital) = &mut self.hospital else {
+            return Ok(());
+        };
+        // A row that has gone since the scan is reported by the load itself.
+        let _ = match fix.entity {
+            Entity::Patient => hospital
+                .patients
+                .update_patient
+                .as_mut()
+                .map(|update| update.load_patient_by_id(fix.id)),
+            Entity::Staff => hospital
+                .staff
+                .update_staff
+                .as_mut()
+                .map(|update| update.load_staff_by_id(fix.id)),
+            Entity::Record => hospital
+                .records
+                .update_record
+                .as_mut()
+                .map(|update| update.load_record_by_id(fix.id)),
+            Entity::Invoice => Some(hospital.finance.update_invoice.load_invoice_by_id(fix.id)),
+            Entity::Shift => None,
+        };
+        Ok(())
+    }
+
+    fn try_open_app(&mut self, selected_app: SelectedApp) -> Result<()> {
+        self.save_view();
+        if !matches!(
+            selected_app,
+            SelectedApp::Hospital | SelectedApp::None | SelectedApp::Quit
+        ) {
+            ui_state::set_last_app(Some(selected_app));
+        }
+        match selected_app {
+            SelectedApp::PatientAdd => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_patients_state(hospital::patients::PatientsState::AddPatient);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::PatientList => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_patients_state(hospital::patients::PatientsState::ListPatients);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::PatientDelete => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_patients_state(hospital::patients::PatientsState::DeletePatient);
+                    hospital.patients.delete_patient =
+                        Some(hospital::patients::delete::DeletePatient::new(&self.db));
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::PatientUpdate => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_patients_state(hospital::patients::PatientsState::UpdatePatient);
+                    hospital.patients.update_patient =
+                        Some(hospital::patients::update::UpdatePatient::new(&self.db));
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::PatientCompliance => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_patients_state(hospital::patients::PatientsState::Compliance);
+                    hospital.patients.initialize_list()?;
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::PatientImport => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_patients_state(hospital::patients::PatientsState::Import);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+
+            SelectedApp::StaffAdd => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Staff);
+
+                    hospital.set_staff_state(hospital::staff::StaffState::AddStaff);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::StaffAssign => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Staff);
+                    hospital.set_staff_state(
+                        crate::components::hospital::staff::StaffState::AssignStaff,
+                    );
+
+                    if hospital.staff.assign_staff.is_none() {
+                        let mut assign_staff =
+                            crate::components::hospital::staff::assign::AssignStaff::new(&self.db);
+                        assign_staff.fetch_staff()?;
+                        hospital.staff.assign_staff = Some(assign_staff);
+                    }
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::StaffList => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Staff);
+                    hospital.set_staff_state(hospital::staff::StaffState::ListStaff);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::StaffPerformance => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Staff);
+                    hospital.set_staff_state(hospital::staff::StaffState::Performance);
+                    hospital.staff.initialize_list()?;
+                }
+                self.state = AppState::Running(selected_app);
+            }
+
+            SelectedApp::StaffUpdate => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Staff);
+                    hospital.set_staff_state(hospital::staff::StaffState::UpdateStaff);
+                    hospital.staff.update_staff = Some(UpdateStaff::new(&self.db));
+                    if let Some(update_staff) = &mut hospital.staff.update_staff {
+                        update_staff.fetch_staff()?; // <--- KEY CHANGE
+                    }
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::StaffDelete => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Staff);
+                    hospital.set_staff_state(hospital::staff::StaffState::DeleteStaff);
+                    hospital.staff.delete_staff = Some(DeleteStaff::new(&self.db));
+                    if let Some(delete_staff) = &mut hospital.staff.delete_staff {
+                        delete_staff.fetch_staff()?;
+                    }
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RecordStore => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Records);
+                    hospital.set_records_state(RecordsState::StoreRecord);
+                    hospital.records.initialize_list()?;
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RecordRetrieve => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Records);
+                    hospital.set_records_state(RecordsState::RetrieveRecords);
+                    hospital.records.initialize_list()?;
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RecordUpdate => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Records);
+                    hospital.set_records_state(RecordsState::UpdateRecord);
+                    hospital.records.update_record = Some(UpdateRecord::new(&self.db));
+                    if let Some(update_record) = &mut hospital.records.update_record {
+                        update_record.fetch_records()?;
+                    }
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RecordDelete => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Records);
+                    hospital.set_records_state(RecordsState::DeleteRecord);
+                    hospital.records.delete_record = Some(DeleteRecord::new(&self.db));
+                    if let Some(delete_record) = &mut hospital.records.delete_record {
+                        delete_record.fetch_records()?;
+                    }
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RecordStatistics => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Records);
+                    hospital.set_records_state(RecordsState::Statistics);
+                    hospital.records.initialize_list()?;
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RecordExpirations => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Records);
+                    hospital.set_records_state(RecordsState::Expirations);
+                    hospital.records.initialize_list()?;
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RecordIsolations => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Records);
+                    hospital.set_records_state(RecordsState::Isolations);
+                    hospital.records.initialize_list()?;
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RecordStays => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Records);
+                    hospital.set_records_state(RecordsState::Stays);
+                    hospital.records.initialize_list()?;
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RecordIntegrity => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Records);
+                    hospital.set_records_state(RecordsState::Integrity);
+                    hospital.records.initialize_list()?;
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RecordWaiting => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Records);
+                    hospital.set_records_state(RecordsState::Waiting);
+                    hospital.records.initialize_list()?;
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RecordForecast => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Records);
+                    hospital.set_records_state(RecordsState::Forecast);
+                    hospital.records.initialize_list()?;
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RecordSatisfaction => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Records);
+                    hospital.set_records_state(RecordsState::Satisfaction);
+                    hospital.records.initialize_list()?;
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RecordCampaign => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(hospital::HospitalState::Records);
+                    hospital.set_records_state(RecordsState::Campaign);
+                    hospital.records.initialize_list()?;
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::BillingInvoice => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Finance);
+                    hospital.set_finance_state(FinanceState::Invoice);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::BillingView => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Finance);
+                    hospital.set_finance_state(FinanceState::View);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::BillingUpdate => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Finance);
+                    hospital.set_finance_state(FinanceState::Update);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::BillingExpenses => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Finance);
+                    hospital.set_finance_state(FinanceState::Expenses);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::BillingExpenseReport => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Finance);
+                    hospital.set_finance_state(FinanceState::ExpenseReport);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::BillingBudget => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Finance);
+                    hospital.set_finance_state(FinanceState::Budget);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::BillingCashier => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Finance);
+                    hospital.set_finance_state(FinanceState::Cashier);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::BillingCashierHistory => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Finance);
+                    hospital.set_finance_state(FinanceState::CashierHistory);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::BillingInsurance => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Finance);
+                    hospital.set_finance_state(FinanceState::Insurance);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::BillingApprovals => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Finance);
+                    hospital.set_finance_state(FinanceState::Approvals);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::WaitlistAdd => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Waitlist);
+                    hospital.set_waitlist_state(WaitlistState::AddEntry);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::WaitlistList => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Waitlist);
+                    hospital.set_waitlist_state(WaitlistState::ListEntries);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RegistryBirths => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Registry);
+                    hospital.set_registry_state(RegistryState::Births);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::RegistryDeaths => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Registry);
+                    hospital.set_registry_state(RegistryState::Deaths);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::TeleconsultSchedule => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Telemedicine);
+                    hospital.set_telemedicine_state(TelemedicineState::Schedule);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::TeleconsultUpcoming => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Telemedicine);
+                    hospital.set_telemedicine_state(TelemedicineState::Upcoming);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::TeleconsultUtilization => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Telemedicine);
+                    hospital.set_telemedicine_state(TelemedicineState::Utilization);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::QueueIssue => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Queue);
+                    hospital.set_queue_state(QueueState::Reception);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::QueueConsole => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Queue);
+                    hospital.set_queue_state(QueueState::Console);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::QueueDisplay => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Queue);
+                    hospital.set_queue_state(QueueState::Display);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::HandoverRecord => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Handover);
+                    hospital.set_handover_state(HandoverState::Record);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::HandoverHistory => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Handover);
+                    hospital.set_handover_state(HandoverState::History);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::WorklistMyDay => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Worklist);
+                    hospital.set_worklist_state(WorklistState::MyDay);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::WorklistWardRound => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Worklist);
+                    hospital.set_worklist_state(WorklistState::WardRound);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::WorklistFollowUps => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Worklist);
+                    hospital.set_worklist_state(WorklistState::FollowUps);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::MaternityPregnancies => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Maternity);
+                    hospital.set_maternity_state(MaternityState::Pregnancies);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::MaternityDue => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Maternity);
+                    hospital.set_maternity_state(MaternityState::Due);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::AppointmentBook => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Appointments);
+                    hospital.set_appointments_state(AppointmentsState::Book);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::AppointmentList => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Appointments);
+                    hospital.set_appointments_state(AppointmentsState::List);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::AppointmentUpdate => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Appointments);
+                    hospital.set_appointments_state(AppointmentsState::Update);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::InventoryStock => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Inventory);
+                    hospital.set_inventory_state(InventoryState::Stock);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::InventoryStocktake => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                if let Some(hospital) = &mut self.hospital {
+                    hospital.set_state(HospitalState::Inventory);
+                    hospital.set_inventory_state(InventoryState::Stocktake);
+                }
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::Settings => {
+                self.settings.load();
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::SettingsBackup => {
+                self.settings.load();
+                self.settings.start_backup();
+                self.state = AppState::Running(SelectedApp::Settings);
+            }
+            SelectedApp::WebhookLog => {
+                self.webhook_log.load();
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::Plugin(id) => {
+                if let Some(plugin) = plugins::get(id) {
+                    self.plugin = Some((plugin.create)(&self.db));
+                    self.state = AppState::Running(selected_app);
+                }
+            }
+            SelectedApp::Hospital => {
+                self.hospital = Some(hospital::HospitalApp::new(&self.db)?);
+                self.state = AppState::Running(selected_app);
+            }
+            SelectedApp::None | SelectedApp::Quit => {}
+        }
+        Ok(())
+    }
+
     fn handle_input(&mut self, tui: &mut Tui) -> Result<()> {
         match tui.next_event()? {
             tui::Event::Input(event) => {
-                if let crossterm::event::Event::Key(KeyEvent {
-                    code: KeyCode::Char('q'),
-                    modifiers: crossterm::event::KeyModifiers::CONTROL,
-                    ..
-                }) = event
-                {
-                    self.should_quit = true;
+                if let crossterm::event::Event::Key(key) = event {
+                    if self.handle_macro_key(key)? {
+                        return Ok(());
+                    }
+                    if let Some(keys) = &mut self.recording {
+                        keys.push(key);
+                    }
+                }
+                self.handle_event(event)?;
+                if !self.logged_in() {
+                    // Keys typed at the login screen, passwords included,
+                    // are never kept.
+                    self.recording = None;
+                    self.last_macro.clear();
+                }
+            }
+            tui::Event::Tick => {
+                toast::expire();
+                if let Some(kiosk) = &mut self.kiosk {
+                    kiosk.tick();
                     return Ok(());
                 }
-
+                if self.logged_in() {
+                    webhooks::tick(&self.db);
+                    notify::tick(&self.db);
+                    locks::tick(&self.db);
+                }
                 match self.state {
-                    AppState::Init => {
-                        self.state = AppState::Login;
+                    AppState::Home => self.home.tick(),
+                    AppState::Running(SelectedApp::Settings) => self.settings.tick(),
+                    AppState::Running(SelectedApp::WebhookLog) => self.webhook_log.tick(),
+                    AppState::Running(SelectedApp::Plugin(_)) => {
+                        if let Some(plugin) = &mut self.plugin {
+                            plugin.tick();
+                        }
                     }
-                    AppState::Login => {
-                        if let crossterm::event::Event::Key(key) = event {
-                            let result = self.login.handle_input(key)?;
-                            if let Some(selected_app) = result {
-                                match selected_app {
-                                    SelectedApp::Quit => {
-                                        self.should_quit = true;
-                                        return Ok(());
-                                    }
-                                    SelectedApp::None => {
-                                        let credentials = Credentials {
-                                            username: self.login.username.clone(),
-                                            password: self.login.password.clone(),
-                                        };
-
-                                        match login(credentials) {
-                                            Ok(user_id) => {
-                                                self.home.load_username(user_id)?;
-                                                self.state = AppState::Home;
-                                            }
-                                            Err(err) => {
-                                                self.login.error_message = Some(format!("{}", err));
-                                            }
+                    AppState::Running(_) => {
+                        if let Some(hospital) = &mut self.hospital {
+                            hospital.tick();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn logged_in(&self) -> bool {
+        matches!(self.state, AppState::Home | AppState::Running(_))
+    }
+
+    /// Starts or stops recording on the macro record key and replays the
+    /// last recording on the play key. Returns whether `key` was either.
+    ///
+    /// On screens without text entry plain `q` and `@` work as well; `@` is
+    /// typed with Shift on most keyboards, so Shift does not matter.
+    fn handle_macro_key(&mut self, key: KeyEvent) -> Result<bool> {
+        if !self.logged_in() {
+            return Ok(false);
+        }
+        let config = config::config();
+        let keymap = &config.keymap;
+        let plain = self.palette.is_none()
+            && self.scan.is_none()
+            && self.vitals.is_none()
+            && !self.show_help
+            && !keymap_for(self.state).text_entry
+            && !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+
+        if key_matches(&keymap.macro_record, &key) || (plain && key.code == KeyCode::Char('q')) {
+            match self.recording.take() {
+                None => {
+                    self.recording = Some(Vec::new());
+                    toast::push(Toast::success(t("macro.recording")));
+                }
+                Some(keys) if keys.is_empty() => {
+                    toast::push(Toast::success(t("macro.cancelled")));
+                }
+                Some(keys) => {
+                    toast::push(Toast::success(tf(
+                        "macro.recorded",
+                        &[("count", &keys.len())],
+                    )));
+                    self.last_macro = keys;
+                }
+            }
+            return Ok(true);
+        }
+
+        if key_matches(&keymap.macro_play, &key) || (plain && key.code == KeyCode::Char('@')) {
+            if self.recording.is_some() {
+                toast::push(Toast::error(t("macro.play_while_recording")));
+            } else if self.last_macro.is_empty() {
+                toast::push(Toast::error(t("macro.none")));
+            } else {
+                let keys = self.last_macro.clone();
+                for key in &keys {
+                    self.handle_event(crossterm::event::Event::Key(*key))?;
+                    if self.should_quit || !self.logged_in() {
+                        break;
+                    }
+                }
+                toast::push(Toast::success(tf(
+                    "macro.replayed",
+                    &[("count", &keys.len())],
+                )));
+            }
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Handles one terminal event, whether typed or replayed from a macro.
+    fn handle_event(&mut self, event: crossterm::event::Event) -> Result<()> {
+        self.last_input = Instant::now();
+        let config = config::config();
+        let keymap = &config.keymap;
+        if let crossterm::event::Event::Key(key) = event {
+            if key_matches(&keymap.quit, &key) {
+                self.should_quit = true;
+                return Ok(());
+            }
+        }
+
+        if let Ok((width, height)) = crossterm::terminal::size() {
+            if is_too_small(Rect::new(0, 0, width, height)) {
+                return Ok(());
+            }
+        }
+
+        if let Some(error) = &mut self.database_error {
+            if let crossterm::event::Event::Key(key) = event {
+                match error.handle_key(key) {
+                    Some(DatabaseErrorAction::Retry) => self.retry_database()?,
+                    Some(DatabaseErrorAction::Exit) => self.should_quit = true,
+                    None => {}
+                }
+            }
+            return Ok(());
+        }
+        if let Some(prompt) = &mut self.schema_prompt {
+            if let crossterm::event::Event::Key(key) = event {
+                if let Some(choice) = prompt.handle_key(key) {
+                    self.choose_schema(choice);
+                }
+            }
+            return Ok(());
+        }
+
+        if let crossterm::event::Event::Key(key) = event {
+            if let Some(kiosk) = &mut self.kiosk {
+                kiosk.handle_input(key)?;
+                return Ok(());
+            }
+            if let Some(palette) = &mut self.palette {
+                match palette.handle_input(key)? {
+                    Some(SelectedApp::None) => self.palette = None,
+                    Some(selected_app) => {
+                        self.palette = None;
+                        self.open_app(selected_app)?;
+                    }
+                    None => {}
+                }
+                return Ok(());
+            }
+            if let Some(scan) = &mut self.scan {
+                match scan.handle_input(key)? {
+                    Some(ScanAction::Open(target)) => {
+                        self.scan = None;
+                        self.open_scanned(target)?;
+                    }
+                    Some(ScanAction::Close) => self.scan = None,
+                    None => {}
+                }
+                return Ok(());
+            }
+            if let Some(vitals) = &mut self.vitals {
+                if vitals.handle_key(key) {
+                    self.vitals = None;
+                }
+                return Ok(());
+            }
+            let logged_in = matches!(self.state, AppState::Home | AppState::Running(_));
+            if logged_in && key_matches(&keymap.palette, &key) {
+                self.show_help = false;
+                self.palette = Some(CommandPalette::new());
+                return Ok(());
+            }
+            if logged_in && key_matches(&keymap.scan, &key) {
+                self.show_help = false;
+                self.scan = Some(ScanPrompt::new(&self.db));
+                return Ok(());
+            }
+            if logged_in && key_matches(&keymap.vitals, &key) {
+                self.show_help = false;
+                match QuickVitals::new(&self.db) {
+                    Ok(vitals) => self.vitals = Some(vitals),
+                    Err(e) => toast::push(Toast::error(tf("vitals.failed", &[("error", &e)]))),
+                }
+                return Ok(());
+            }
+            if self.show_help {
+                self.show_help = false;
+                return Ok(());
+            }
+            if let AppState::Running(selected_app) = self.state {
+                if let Some(tab) = self.requested_tab(key, selected_app) {
+                    self.open_app(MODULE_TABS[tab].1)?;
+                    return Ok(());
+                }
+            }
+            let help_requested = key_matches(&keymap.help, &key)
+                || (key.code == KeyCode::Char('?') && !keymap_for(self.state).text_entry);
+            if help_requested {
+                self.show_help = true;
+                return Ok(());
+            }
+        }
+
+        match self.state {
+            AppState::Init => {
+                self.state = AppState::Login;
+            }
+            AppState::Login => {
+                if let crossterm::event::Event::Key(key) = event {
+                    let result = self.login.handle_input(key)?;
+                    if let Some(selected_app) = result {
+                        match selected_app {
+                            SelectedApp::Quit => {
+                                self.should_quit = true;
+                                return Ok(());
+                            }
+                            SelectedApp::None => {
+                                let credentials = Credentials {
+                                    username: self.login.username.value().to_string(),
+                                    password: self.login.password.value().to_string(),
+                                };
+
+                                let username = credentials.username.clone();
+                                match login(&self.db, credentials) {
+                                    Ok(user_id) => {
+                                        self.home.load_username(user_id)?;
+                                        self.state = AppState::Home;
+                                        ui_state::load(&username);
+                                        // The screen left open last time comes
+                                        // first, then the one for the user's role.
+                                        if let Some(app) = ui_state::last_app()
+                                            .or_else(|| auth::landing_app(&self.db, &username))
+                                        {
+                                            self.open_app(app)?;
                                         }
                                     }
-                                    SelectedApp::Hospital => {
-                                        self.state = AppState::Register;
-                                    }
-                                    SelectedApp::PatientAdd
-                                    | SelectedApp::PatientList
-                                    | SelectedApp::PatientDelete
-                                    | SelectedApp::PatientUpdate
-                                    | SelectedApp::StaffAdd
-                                    | SelectedApp::StaffAssign
-                                    | SelectedApp::StaffList
-                                    | SelectedApp::StaffDelete
-                                    | SelectedApp::StaffUpdate
-                                    | SelectedApp::RecordStore
-                                    | SelectedApp::RecordRetrieve
-                                    | SelectedApp::RecordUpdate
-                                    | SelectedApp::RecordDelete
-                                    | SelectedApp::BillingInvoice
-                                    | SelectedApp::BillingView
-                                    | SelectedApp::BillingUpdate => {
-                                        self.login.error_message =
-                                            Some("Please log in first.".to_string());
+                                    Err(err) => {
+                                        toast::push(Toast::error(format!("{}", err)));
                                     }
                                 }
                             }
+                            SelectedApp::Hospital => {
+                                self.state = AppState::Register;
+                            }
+                            SelectedApp::PatientAdd
+                            | SelectedApp::PatientList
+                            | SelectedApp::PatientDelete
+                            | SelectedApp::PatientCompliance
+                            | SelectedApp::PatientImport
+                            | SelectedApp::PatientUpdate
+                            | SelectedApp::StaffAdd
+                            | SelectedApp::StaffAssign
+                            | SelectedApp::StaffList
+                            | SelectedApp::StaffDelete
+                            | SelectedApp::StaffUpdate
+                            | SelectedApp::StaffPerformance
+                            | SelectedApp::RecordStore
+                            | SelectedApp::RecordRetrieve
+                            | SelectedApp::RecordUpdate
+                            | SelectedApp::RecordDelete
+                            | SelectedApp::RecordStatistics
+                            | SelectedApp::RecordExpirations
+                            | SelectedApp::RecordIsolations
+                            | SelectedApp::RecordStays
+                            | SelectedApp::RecordIntegrity
+                            | SelectedApp::RecordWaiting
+                            | SelectedApp::RecordForecast
+                            | SelectedApp::RecordSatisfaction
+                            | SelectedApp::RecordCampaign
+                            | SelectedApp::BillingInvoice
+                            | SelectedApp::BillingView
+                            | SelectedApp::BillingUpdate
+                            | SelectedApp::BillingExpenses
+                            | SelectedApp::BillingExpenseReport
+                            | SelectedApp::BillingBudget
+                            | SelectedApp::BillingCashier
+                            | SelectedApp::BillingCashierHistory
+                            | SelectedApp::BillingInsurance
+                            | SelectedApp::BillingApprovals
+                            | SelectedApp::WaitlistAdd
+                            | SelectedApp::WaitlistList
+                            | SelectedApp::RegistryBirths
+                            | SelectedApp::RegistryDeaths
+                            | SelectedApp::TeleconsultSchedule
+                            | SelectedApp::TeleconsultUpcoming
+                            | SelectedApp::TeleconsultUtilization
+                            | SelectedApp::QueueIssue
+                            | SelectedApp::QueueConsole
+                            | SelectedApp::QueueDisplay
+                            | SelectedApp::HandoverRecord
+                            | SelectedApp::HandoverHistory
+                            | SelectedApp::WorklistMyDay
+                            | SelectedApp::WorklistWardRound
+                            | SelectedApp::WorklistFollowUps
+                            | SelectedApp::MaternityPregnancies
+                            | SelectedApp::MaternityDue
+                            | SelectedApp::AppointmentBook
+                            | SelectedApp::AppointmentList
+                            | SelectedApp::AppointmentUpdate
+                            | SelectedApp::InventoryStock
+                            | SelectedApp::InventoryStocktake
+                            | SelectedApp::Settings
+                            | SelectedApp::SettingsBackup
+                            | SelectedApp::WebhookLog
+                            | SelectedApp::Plugin(_) => {
+                                toast::push(Toast::error(t("app.login_first")));
+                            }
                         }
                     }
-                    AppState::Register => {
-                        if let crossterm::event::Event::Key(key) = event {
-                            if let Some(_selected_app) = self.register.handle_input(key)? {
-                                self.state = AppState::Login;
+                }
+            }
+            AppState::Register => {
+                if let crossterm::event::Event::Key(key) = event {
+                    if let Some(_selected_app) = self.register.handle_input(key)? {
+                        self.state = AppState::Login;
 
-                                if self.register.registration_success {
-                                    self.login.username.clear();
-                                    self.login.password.clear();
-                                    self.login.error_message = None;
-                                    self.login.set_success_message(
-                                        "Registration successful! Please log in.".to_string(),
-                                    );
-                                }
-                            }
+                        if self.register.registration_success {
+                            self.login.username.clear();
+                            self.login.password.clear();
+                            toast::push(Toast::success(t("app.registration_success")));
                         }
                     }
+                }
+            }
 
-                    AppState::Home => {
+            AppState::Home => {
+                if let crossterm::event::Event::Key(key) = event {
+                    quick_actions::clear();
+                    if let Some(selected_app) = self.home.handle_input(key)? {
+                        match selected_app {
+                            SelectedApp::Quit => {
+                                self.should_quit = true;
+                                return Ok(());
+                            }
+                            SelectedApp::None => {
+                                // Go back to login (logout)
+                                toast::clear();
+                                let _ = ui_state::save();
+                                self.state = AppState::Login;
+                            }
+                            _ => self.open_app(selected_app)?,
+                        }
+                    }
+                    if let Some(recent) = self.home.take_recent() {
+                        self.open_recent(recent)?;
+                    }
+                }
+            }
+            AppState::Running(selected_app) => match selected_app {
+                SelectedApp::PatientAdd
+                | SelectedApp::PatientList
+                | SelectedApp::PatientDelete
+                | SelectedApp::PatientCompliance
+                | SelectedApp::PatientImport
+                | SelectedApp::PatientUpdate
+                | SelectedApp::StaffAdd
+                | SelectedApp::StaffList
+                | SelectedApp::StaffDelete
+                | SelectedApp::StaffUpdate
+                | SelectedApp::StaffPerformance
+                | SelectedApp::RecordStore
+                | SelectedApp::RecordRetrieve
+                | SelectedApp::RecordUpdate
+                | SelectedApp::RecordDelete
+                | SelectedApp::RecordStatistics
+                | SelectedApp::RecordExpirations
+                | SelectedApp::RecordIsolations
+                | SelectedApp::RecordStays
+                | SelectedApp::RecordIntegrity
+                | SelectedApp::RecordWaiting
+                | SelectedApp::RecordForecast
+                | SelectedApp::RecordSatisfaction
+                | SelectedApp::RecordCampaign
+                | SelectedApp::BillingInvoice
+                | SelectedApp::BillingView
+                | SelectedApp::BillingUpdate
+                | SelectedApp::BillingExpenses
+                | SelectedApp::BillingExpenseReport
+                | SelectedApp::BillingBudget
+                | SelectedApp::BillingCashier
+                | SelectedApp::BillingCashierHistory
+                | SelectedApp::BillingInsurance
+                | SelectedApp::BillingApprovals
+                | SelectedApp::WaitlistAdd
+                | SelectedApp::WaitlistList
+                | SelectedApp::RegistryBirths
+                | SelectedApp::RegistryDeaths
+                | SelectedApp::TeleconsultSchedule
+                | SelectedApp::TeleconsultUpcoming
+                | SelectedApp::TeleconsultUtilization
+                | SelectedApp::QueueIssue
+                | SelectedApp::QueueConsole
+                | SelectedApp::QueueDisplay
+                | SelectedApp::HandoverRecord
+                | SelectedApp::HandoverHistory
+                | SelectedApp::WorklistMyDay
+                | SelectedApp::WorklistWardRound
+                | SelectedApp::WorklistFollowUps
+                | SelectedApp::MaternityPregnancies
+                | SelectedApp::MaternityDue
+                | SelectedApp::AppointmentBook
+                | SelectedApp::AppointmentList
+                | SelectedApp::AppointmentUpdate
+                | SelectedApp::InventoryStock
+                | SelectedApp::InventoryStocktake => {
+                    if let Some(hospital) = &mut self.hospital {
                         if let crossterm::event::Event::Key(key) = event {
-                            if let Some(selected_app) = self.home.handle_input(key)? {
-                                match selected_app {
-                                    SelectedApp::PatientAdd => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_patients_state(
-                                                hospital::patients::PatientsState::AddPatient,
-                                            );
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::PatientList => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_patients_state(
-                                                hospital::patients::PatientsState::ListPatients,
-                                            );
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::PatientDelete => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_patients_state(
-                                                hospital::patients::PatientsState::DeletePatient,
-                                            );
-                                            hospital.patients.delete_patient = Some(
-                                                hospital::patients::delete::DeletePatient::new(),
-                                            );
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::PatientUpdate => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_patients_state(
-                                                hospital::patients::PatientsState::UpdatePatient,
-                                            );
-                                            hospital.patients.update_patient = Some(
-                                                hospital::patients::update::UpdatePatient::new(),
-                                            );
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-
-                                    SelectedApp::StaffAdd => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_state(hospital::HospitalState::Staff);
-
-                                            hospital.set_staff_state(
-                                                hospital::staff::StaffState::AddStaff,
-                                            );
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::StaffAssign => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_state(hospital::HospitalState::Staff);
-                                            hospital.set_staff_state(crate::components::hospital::staff::StaffState::AssignStaff);
-
-                                            if hospital.staff.assign_staff.is_none() {
-                                                let mut assign_staff = crate::components::hospital::staff::assign::AssignStaff::new();
-                                                assign_staff.fetch_staff()?;
-                                                hospital.staff.assign_staff = Some(assign_staff);
-                                            }
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::StaffList => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_state(hospital::HospitalState::Staff);
-                                            hospital.set_staff_state(
-                                                hospital::staff::StaffState::ListStaff,
-                                            );
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-
-                                    SelectedApp::StaffUpdate => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_state(hospital::HospitalState::Staff);
-                                            hospital.set_staff_state(
-                                                hospital::staff::StaffState::UpdateStaff,
-                                            );
-                                            hospital.staff.update_staff = Some(UpdateStaff::new());
-                                            if let Some(update_staff) =
-                                                &mut hospital.staff.update_staff
-                                            {
-                                                update_staff.fetch_staff()?; // <--- KEY CHANGE
-                                            }
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::StaffDelete => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_state(hospital::HospitalState::Staff);
-                                            hospital.set_staff_state(
-                                                hospital::staff::StaffState::DeleteStaff,
-                                            );
-                                            hospital.staff.delete_staff = Some(DeleteStaff::new());
-                                            if let Some(delete_staff) =
-                                                &mut hospital.staff.delete_staff
-                                            {
-                                                delete_staff.fetch_staff()?;
-                                            }
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::RecordStore => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_state(hospital::HospitalState::Records);
-                                            hospital.set_records_state(RecordsState::StoreRecord);
-                                            hospital.records.initialize_list()?;
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::RecordRetrieve => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_state(hospital::HospitalState::Records);
-                                            hospital
-                                                .set_records_state(RecordsState::RetrieveRecords);
-                                            hospital.records.initialize_list()?;
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::RecordUpdate => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_state(hospital::HospitalState::Records);
-                                            hospital.set_records_state(RecordsState::UpdateRecord);
-                                            hospital.records.update_record =
-                                                Some(UpdateRecord::new());
-                                            if let Some(update_record) =
-                                                &mut hospital.records.update_record
-                                            {
-                                                update_record.fetch_records()?;
-                                            }
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::RecordDelete => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_state(hospital::HospitalState::Records);
-                                            hospital.set_records_state(RecordsState::DeleteRecord);
-                                            hospital.records.delete_record =
-                                                Some(DeleteRecord::new());
-                                            if let Some(delete_record) =
-                                                &mut hospital.records.delete_record
-                                            {
-                                                delete_record.fetch_records()?;
-                                            }
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::BillingInvoice => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_state(HospitalState::Finance);
-                                            hospital.set_finance_state(FinanceState::Invoice);
-                                        }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::BillingView => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_state(HospitalState::Finance);
-                                            hospital.set_finance_state(FinanceState::View);
-                                        }
-                                        self.state = AppState::Running(selected_app);
+                            let action = hospital
+                                .handle_input(key)?
+                                .or_else(|| quick_actions::finished().then_some(SelectedApp::None));
+                            if let Some(action) = action {
+                                match action {
+                                    SelectedApp::None => {
+                                        self.save_view();
+                                        ui_state::set_last_app(None);
+                                        self.state = AppState::Home;
+                                        self.hospital = None;
                                     }
-                                    SelectedApp::BillingUpdate => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        if let Some(hospital) = &mut self.hospital {
-                                            hospital.set_state(HospitalState::Finance);
-                                            hospital.set_finance_state(FinanceState::Update);
+                                    // A worklist item or integrity finding
+                                    // opening the screen that deals with it.
+                                    selected_app => {
+                                        let fix = hospital.records.integrity.take_fix();
+                                        self.save_view();
+                                        match fix {
+                                            Some(fix) => self.open_fix(fix)?,
+                                            None => self.open_app(selected_app)?,
                                         }
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::Hospital => {
-                                        self.hospital = Some(hospital::HospitalApp::new());
-                                        self.state = AppState::Running(selected_app);
-                                    }
-                                    SelectedApp::Quit => {
-                                        self.should_quit = true;
-                                        return Ok(());
-                                    }
-                                    SelectedApp::None => {
-                                        // Go back to login (logout)
-                                        self.state = AppState::Login;
                                     }
                                 }
                             }
                         }
+                    } else {
+                        self.state = AppState::Home;
                     }
-                    AppState::Running(selected_app) => match selected_app {
-                        SelectedApp::PatientAdd
-                        | SelectedApp::PatientList
-                        | SelectedApp::PatientDelete
-                        | SelectedApp::PatientUpdate
-                        | SelectedApp::StaffAdd
-                        | SelectedApp::StaffList
-                        | SelectedApp::StaffDelete
-                        | SelectedApp::StaffUpdate
-                        | SelectedApp::RecordStore
-                        | SelectedApp::RecordRetrieve
-                        | SelectedApp::RecordUpdate
-                        | SelectedApp::RecordDelete
-                        | SelectedApp::BillingInvoice
-                        | SelectedApp::BillingView
-                        | SelectedApp::BillingUpdate => {
-                            if let Some(hospital) = &mut self.hospital {
-                                if let crossterm::event::Event::Key(key) = event {
-                                    if let Some(action) = hospital.handle_input(key)? {
-                                        match action {
-                                            SelectedApp::None => {
-                                                self.state = AppState::Home;
-                                                self.hospital = None;
-                                            }
-                                            _ => {}
-                                        }
-                                    }
-                                }
-                            } else {
+                }
+                SelectedApp::Settings => {
+                    if let crossterm::event::Event::Key(key) = event {
+                        if let Some(SelectedApp::None) = self.settings.handle_input(key)? {
+                            ui_state::set_last_app(None);
+                            self.state = AppState::Home;
+                        }
+                    }
+                }
+                SelectedApp::WebhookLog => {
+                    if let crossterm::event::Event::Key(key) = event {
+                        if let Some(SelectedApp::None) = self.webhook_log.handle_input(key)? {
+                            ui_state::set_last_app(None);
+                            self.state = AppState::Home;
+                        }
+                    }
+                }
+                SelectedApp::Plugin(_) => {
+                    if let (Some(plugin), crossterm::event::Event::Key(key)) =
+                        (&mut self.plugin, event)
+                    {
+                        match plugin.handle_input(key)? {
+                            Some(SelectedApp::None) => {
+                                ui_state::set_last_app(None);
                                 self.state = AppState::Home;
+                                self.plugin = None;
                             }
+                            Some(selected_app) => self.open_app(selected_app)?,
+                            None => {}
                         }
-                        SelectedApp::StaffAssign => {
-                            if let Some(hospital) = &mut self.hospital {
-                                if let crossterm::event::Event::Key(key_event) = event {
-                                    if let Some(selected_app) = hospital.handle_input(key_event)? {
-                                        match selected_app {
-                                            SelectedApp::None => {
-                                                self.state = AppState::Home;
-                                                self.hospital = None;
-                                            }
-                                            _ => {}
-                                        }
+                    }
+                }
+                SelectedApp::StaffAssign => {
+                    if let Some(hospital) = &mut self.hospital {
+                        if let crossterm::event::Event::Key(key_event) = event {
+                            if let Some(selected_app) = hospital.handle_input(key_event)? {
+                                match selected_app {
+                                    SelectedApp::None => {
+                                        self.save_view();
+                                        ui_state::set_last_app(None);
+                                        self.state = AppState::Home;
+                                        self.hospital = None;
                                     }
+                                    _ => {}
                                 }
                             }
                         }
-                        _ => {
-                            self.state = AppState::Home;
-                        }
-                    },
-
-                    AppState::Quitting => {
-                        self.should_quit = true;
                     }
                 }
-            }
-            tui::Event::Tick => {
-                if let AppState::Login = self.state {
-                    self.login.check_error_timeout();
-                }
-                if let AppState::Register = self.state {
-                    self.register.check_error_timeout();
+                _ => {
+                    self.state = AppState::Home;
                 }
+            },
+
+            AppState::Quitting => {
+                self.should_quit = true;
             }
         }
         Ok(())
     }
 
     fn render_ui(&self, frame: &mut crate::tui::Frame<'_>) {
+        if is_too_small(frame.area()) {
+            render_too_small(frame);
+            return;
+        }
+        if let Some(error) = &self.database_error {
+            error.render(frame);
+            render_toasts(frame);
+            return;
+        }
+        if let Some(prompt) = &self.schema_prompt {
+            prompt.render(frame);
+            return;
+        }
+        if let Some(kiosk) = &self.kiosk {
+            kiosk.render(frame);
+            render_toasts(frame);
+            return;
+        }
+
         match self.state {
             AppState::Init => {}
             AppState::Login => self.login.render(frame),
@@ -444,23 +1532,73 @@ impl App {
             AppState::Running(SelectedApp::PatientAdd)
             | AppState::Running(SelectedApp::PatientList)
             | AppState::Running(SelectedApp::PatientDelete)
+            | AppState::Running(SelectedApp::PatientCompliance)
+            | AppState::Running(SelectedApp::PatientImport)
             | AppState::Running(SelectedApp::PatientUpdate)
             | AppState::Running(SelectedApp::StaffAdd)
             | AppState::Running(SelectedApp::StaffAssign)
             | AppState::Running(SelectedApp::StaffList)
             | AppState::Running(SelectedApp::StaffDelete)
             | AppState::Running(SelectedApp::StaffUpdate)
+            | AppState::Running(SelectedApp::StaffPerformance)
             | AppState::Running(SelectedApp::RecordStore)
             | AppState::Running(SelectedApp::RecordRetrieve)
             | AppState::Running(SelectedApp::RecordUpdate)
             | AppState::Running(SelectedApp::RecordDelete)
+            | AppState::Running(SelectedApp::RecordStatistics)
+            | AppState::Running(SelectedApp::RecordExpirations)
+            | AppState::Running(SelectedApp::RecordIsolations)
+            | AppState::Running(SelectedApp::RecordStays)
+            | AppState::Running(SelectedApp::RecordIntegrity)
+            | AppState::Running(SelectedApp::RecordWaiting)
+            | AppState::Running(SelectedApp::RecordForecast)
+            | AppState::Running(SelectedApp::RecordSatisfaction)
+            | AppState::Running(SelectedApp::RecordCampaign)
             | AppState::Running(SelectedApp::BillingInvoice)
             | AppState::Running(SelectedApp::BillingUpdate)
-            | AppState::Running(SelectedApp::BillingView) => {
+            | AppState::Running(SelectedApp::BillingExpenses)
+            | AppState::Running(SelectedApp::BillingExpenseReport)
+            | AppState::Running(SelectedApp::BillingBudget)
+            | AppState::Running(SelectedApp::BillingCashier)
+            | AppState::Running(SelectedApp::BillingCashierHistory)
+            | AppState::Running(SelectedApp::BillingInsurance)
+            | AppState::Running(SelectedApp::BillingApprovals)
+            | AppState::Running(SelectedApp::BillingView)
+            | AppState::Running(SelectedApp::WaitlistAdd)
+            | AppState::Running(SelectedApp::WaitlistList)
+            | AppState::Running(SelectedApp::RegistryBirths)
+            | AppState::Running(SelectedApp::RegistryDeaths)
+            | AppState::Running(SelectedApp::TeleconsultSchedule)
+            | AppState::Running(SelectedApp::TeleconsultUpcoming)
+            | AppState::Running(SelectedApp::TeleconsultUtilization)
+            | AppState::Running(SelectedApp::QueueIssue)
+            | AppState::Running(SelectedApp::QueueConsole)
+            | AppState::Running(SelectedApp::QueueDisplay)
+            | AppState::Running(SelectedApp::HandoverRecord)
+            | AppState::Running(SelectedApp::HandoverHistory)
+            | AppState::Running(SelectedApp::WorklistMyDay)
+            | AppState::Running(SelectedApp::WorklistWardRound)
+            | AppState::Running(SelectedApp::WorklistFollowUps)
+            | AppState::Running(SelectedApp::MaternityPregnancies)
+            | AppState::Running(SelectedApp::MaternityDue)
+            | AppState::Running(SelectedApp::AppointmentBook)
+            | AppState::Running(SelectedApp::AppointmentList)
+            | AppState::Running(SelectedApp::AppointmentUpdate)
+            | AppState::Running(SelectedApp::InventoryStock)
+            | AppState::Running(SelectedApp::InventoryStocktake) => {
                 if let Some(hospital) = &self.hospital {
                     hospital.render(frame);
                 }
             }
+            AppState::Running(SelectedApp::Settings | SelectedApp::SettingsBackup) => {
+                self.settings.render(frame)
+            }
+            AppState::Running(SelectedApp::WebhookLog) => self.webhook_log.render(frame),
+            AppState::Running(SelectedApp::Plugin(_)) => {
+                if let Some(plugin) = &self.plugin {
+                    plugin.render(frame);
+                }
+            }
             AppState::Running(SelectedApp::Hospital) => {
                 if let Some(hospital) = &self.hospital {
                     hospital.render(frame);
@@ -470,11 +1608,46 @@ impl App {
             AppState::Running(SelectedApp::None) | AppState::Running(SelectedApp::Quit) => todo!(),
             AppState::Quitting => todo!(),
         }
+
+        if let AppState::Running(selected_app) = self.state {
+            if let Some(active) = module_of(selected_app) {
+                render_tab_bar(frame, Some(active));
+            }
+        }
+        if self.recording.is_some() {
+            render_marker(frame, t("macro.indicator"));
+        } else if self.db.is_read_only() {
+            render_marker(frame, t("schema.read_only_marker"));
+        }
+        render_toasts(frame);
+        if self.show_help {
+            render_help(frame, &keymap_for(self.state));
+        }
+        if let Some(palette) = &self.palette {
+            palette.render(frame);
+        }
+        if let Some(scan) = &self.scan {
+            scan.render(frame);
+        }
+        if let Some(vitals) = &self.vitals {
+            vitals.render(frame);
+        }
     }
 }
 
-impl Default for App {
-    fn default() -> Self {
-        Self::new()
-    }
+/// A marker in the top-right corner for a mode that stays on, such as macro
+/// recording or a read-only database.
+fn render_marker(frame: &mut crate::tui::Frame<'_>, text: &str) {
+    let label = format!(" ● {} ", text);
+    let area = frame.area();
+    let width = (label.chars().count() as u16).min(area.width);
+    let marker = Rect::new(area.right() - width, area.y, width, 1);
+    frame.render_widget(
+        Paragraph::new(label).style(
+            Style::default()
+                .fg(theme().error)
+                .add_modifier(Modifier::BOLD),
+        ),
+        marker,
+    );
 }
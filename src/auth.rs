@@ -1,4 +1,6 @@
-use crate::db;
+use crate::app::SelectedApp;
+use crate::db::{self, Db};
+use crate::models::StaffRole;
 use anyhow::{Context, Result};
 
 #[derive(Debug, Clone)]
@@ -7,7 +9,42 @@ pub struct Credentials {
     pub password: String,
 }
 
-pub fn login(credentials: Credentials) -> Result<i64> {
-    db::authenticate_user(&credentials.username, &credentials.password)
+pub fn login(db: &Db, credentials: Credentials) -> Result<i64> {
+    db::authenticate_user(db, &credentials.username, &credentials.password)
         .context("⚠️ Authentication failed")
 }
+
+/// Whether `username` may carry out administrative actions: the built-in
+/// root account, or an account linked to a staff member with the Admin role.
+pub fn is_admin(db: &Db, username: &str) -> bool {
+    username == "root" || staff_role(db, username) == Some(StaffRole::Admin)
+}
+
+/// Whether `username` may bill below the catalog price beyond the discount
+/// limit, and approve others' requests to: administrators and accountants.
+pub fn may_approve_discounts(db: &Db, username: &str) -> bool {
+    is_admin(db, username) || staff_role(db, username) == Some(StaffRole::Accountant)
+}
+
+/// The role of the staff member linked to `username`, if any.
+fn staff_role(db: &Db, username: &str) -> Option<StaffRole> {
+    let staff_id = db::get_user_staff_id(db, username).ok().flatten()?;
+    Some(db::get_staff(db, staff_id).ok()?.role)
+}
+
+/// The screen `username` starts on after logging in, chosen by the role of
+/// the linked staff member: doctors get their day's list, nurses the ward
+/// round, accountants the invoices and administrators, root included, the
+/// settings. `None` is the home menu, for technicians and unlinked accounts.
+pub fn landing_app(db: &Db, username: &str) -> Option<SelectedApp> {
+    if username == "root" {
+        return Some(SelectedApp::Settings);
+    }
+    match staff_role(db, username)? {
+        StaffRole::Doctor => Some(SelectedApp::WorklistMyDay),
+        StaffRole::Nurse => Some(SelectedApp::WorklistWardRound),
+        StaffRole::Accountant => Some(SelectedApp::BillingView),
+        StaffRole::Admin => Some(SelectedApp::Settings),
+        StaffRole::Technician => None,
+    }
+}
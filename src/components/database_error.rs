@@ -0,0 +1,142 @@
+use crate::app::SelectedApp;
+use crate::db::Db;
+use crate::i18n::{t, tf};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+const RETRY: usize = 0;
+const EXIT: usize = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseErrorAction {
+    Retry,
+    Exit,
+}
+
+/// Shown in place of every other screen when the database cannot be opened
+/// or read, typically because another program holds a lock on it or the file
+/// is damaged.
+pub struct DatabaseError {
+    db: Db,
+    message: String,
+    /// The screen that failed to open, or `None` when start-up itself failed.
+    pub retry: Option<SelectedApp>,
+    selected: usize,
+}
+
+impl DatabaseError {
+    pub fn new(db: &Db, error: &anyhow::Error, retry: Option<SelectedApp>) -> Self {
+        Self {
+            db: db.clone(),
+            message: format!("{:#}", error),
+            retry,
+            selected: RETRY,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<DatabaseErrorAction> {
+        match key.code {
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => {
+                self.selected = 1 - self.selected;
+            }
+            KeyCode::Enter if self.selected == RETRY => return Some(DatabaseErrorAction::Retry),
+            KeyCode::Enter => return Some(DatabaseErrorAction::Exit),
+            KeyCode::Char('r') | KeyCode::Char('R') => return Some(DatabaseErrorAction::Retry),
+            KeyCode::Esc => return Some(DatabaseErrorAction::Exit),
+            _ => {}
+        }
+        None
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let width = 72.min(area.width);
+        let height = 16.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        let block = Block::default()
+            .title(format!(" {} ", t("database_error.title")))
+            .title_style(
+                Style::default()
+                    .fg(theme().error)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().error))
+            .style(Style::default().bg(theme().dialog))
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let [intro, details, buttons, help] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(2),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .areas(inner);
+
+        frame.render_widget(
+            Paragraph::new(tf(
+                "database_error.message",
+                &[("path", &self.db.path().display())],
+            ))
+            .style(Style::default().fg(theme().text))
+            .wrap(Wrap { trim: true }),
+            intro,
+        );
+        frame.render_widget(
+            Paragraph::new(self.message.as_str())
+                .style(Style::default().fg(theme().error))
+                .wrap(Wrap { trim: true }),
+            details,
+        );
+
+        let [retry, exit] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(buttons);
+        for (index, label, color, button_area) in [
+            (RETRY, t("database_error.retry"), theme().success, retry),
+            (EXIT, t("database_error.exit"), theme().error, exit),
+        ] {
+            let (text, style) = if self.selected == index {
+                (
+                    format!("► {} ◄", label),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                )
+            } else {
+                (
+                    format!("  {}  ", label),
+                    Style::default().fg(theme().inactive),
+                )
+            };
+            frame.render_widget(
+                Paragraph::new(text)
+                    .style(style)
+                    .alignment(Alignment::Center),
+                button_area,
+            );
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("database_error.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            help,
+        );
+    }
+}
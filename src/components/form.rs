@@ -0,0 +1,312 @@
+use crate::components::widgets::text_input::TextInput;
+use crate::db::{self, Db};
+use crate::hooks;
+use crate::i18n::{t, tf};
+use crate::models::Gender;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{contact, datetime, procedures};
+use crossterm::event::KeyEvent;
+use ratatui::{prelude::*, widgets::*};
+
+pub type Validator = Box<dyn Fn(&str) -> Result<(), String>>;
+
+pub struct Field {
+    pub label: &'static str,
+    pub input: TextInput,
+    pub required: bool,
+    pub error: Option<String>,
+    validators: Vec<Validator>,
+}
+
+impl Field {
+    pub fn new(label: &'static str) -> Self {
+        Self {
+            label,
+            input: TextInput::new(),
+            required: false,
+            error: None,
+            validators: Vec::new(),
+        }
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn validate_with(mut self, validator: Validator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    pub fn value(&self) -> &str {
+        self.input.value()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let handled = self.input.handle_key(key);
+        if handled {
+            self.error = None;
+        }
+        handled
+    }
+
+    pub fn set(&mut self, value: impl Into<String>) {
+        self.input.set_value(value);
+        self.error = None;
+    }
+
+    pub fn optional_value(&self) -> Option<String> {
+        let value = self.value().trim();
+        (!value.is_empty()).then(|| value.to_string())
+    }
+
+    pub fn validate(&mut self) -> bool {
+        let value = self.input.value().trim();
+        self.error = if value.is_empty() {
+            self.required
+                .then(|| tf("form.required", &[("field", &self.label)]))
+        } else {
+            self.validators.iter().find_map(|v| v(value).err())
+        };
+        self.error.is_none()
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, focused: bool) {
+        let (title, title_color) = if self.required {
+            (format!(" {}* ", self.label), theme().title)
+        } else {
+            (
+                format!(" {} ", tf("form.optional", &[("field", &self.label)])),
+                theme().inactive,
+            )
+        };
+        let border_color = if self.error.is_some() {
+            theme().error
+        } else if focused {
+            theme().focus
+        } else {
+            theme().unfocused
+        };
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(Span::styled(title, Style::default().fg(title_color)))
+            .border_style(Style::default().fg(border_color))
+            .style(Style::default().bg(theme().input));
+        if let Some(error) = &self.error {
+            block = block.title_bottom(Line::styled(
+                format!(" ⚠ {} ", error),
+                Style::default().fg(theme().error),
+            ));
+        }
+
+        let input = Paragraph::new(self.input.line(focused))
+            .style(Style::default().fg(theme().text).bg(theme().input))
+            .block(block)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(input, area);
+    }
+}
+
+pub struct Form {
+    pub fields: Vec<Field>,
+    /// The values when the form was built or last marked clean.
+    saved: Vec<String>,
+}
+
+impl Form {
+    pub fn new(fields: Vec<Field>) -> Self {
+        let mut form = Self {
+            fields,
+            saved: Vec::new(),
+        };
+        form.mark_clean();
+        form
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn value(&self, index: usize) -> String {
+        self.fields[index].value().trim().to_string()
+    }
+
+    pub fn optional_value(&self, index: usize) -> Option<String> {
+        self.fields[index].optional_value()
+    }
+
+    /// Forwards an editing key to the field at `index`; `false` if it was not used.
+    pub fn handle_key(&mut self, index: usize, key: KeyEvent) -> bool {
+        self.fields
+            .get_mut(index)
+            .is_some_and(|field| field.handle_key(key))
+    }
+
+    pub fn validate(&mut self) -> Option<usize> {
+        let mut first_invalid = None;
+        for (index, field) in self.fields.iter_mut().enumerate() {
+            if !field.validate() && first_invalid.is_none() {
+                first_invalid = Some(index);
+            }
+        }
+        first_invalid
+    }
+
+    pub fn clear(&mut self) {
+        for field in &mut self.fields {
+            field.input.clear();
+            field.error = None;
+        }
+        self.mark_clean();
+    }
+
+    /// Whether any value differs from when the form was built, cleared or
+    /// last marked clean.
+    pub fn is_dirty(&self) -> bool {
+        self.fields
+            .iter()
+            .map(Field::value)
+            .ne(self.saved.iter().map(String::as_str))
+    }
+
+    /// Takes the current values as the saved ones, after filling the form in
+    /// from a record or storing it.
+    pub fn mark_clean(&mut self) {
+        self.saved = self
+            .fields
+            .iter()
+            .map(|field| field.value().to_string())
+            .collect();
+    }
+
+    /// Fills whichever of the `city` and `region` fields are still blank from
+    /// the postcode lookup hook; called as focus leaves the `postcode` field.
+    pub fn fill_from_postcode(&mut self, postcode: usize, city: usize, region: usize) {
+        let Some((found_city, found_region)) =
+            hooks::lookup_postcode(self.fields[postcode].value())
+        else {
+            return;
+        };
+        for (index, found) in [(city, found_city), (region, found_region)] {
+            if self.fields[index].value().trim().is_empty() && !found.is_empty() {
+                self.fields[index].set(found);
+            }
+        }
+    }
+
+    pub fn render_field(&self, frame: &mut Frame, index: usize, area: Rect, focus_index: usize) {
+        self.fields[index].render(frame, area, index == focus_index);
+    }
+}
+
+pub fn email(value: &str) -> Result<(), String> {
+    if contact::is_valid_email(value) {
+        Ok(())
+    } else {
+        Err(t("form.email").to_string())
+    }
+}
+
+pub fn phone(value: &str) -> Result<(), String> {
+    if contact::is_valid_phone(value) {
+        Ok(())
+    } else {
+        Err(t("form.phone").to_string())
+    }
+}
+
+pub fn gender(value: &str) -> Result<(), String> {
+    if Gender::parse(value).is_some() {
+        return Ok(());
+    }
+    let options: Vec<_> = Gender::options()
+        .iter()
+        .map(|gender| gender.label().to_string())
+        .collect();
+    Err(tf("form.one_of", &[("options", &options.join(", "))]))
+}
+
+pub fn date(value: &str) -> Result<(), String> {
+    datetime::parse_date(value)
+        .map(|_| ())
+        .ok_or(t("form.date").to_string())
+}
+
+/// A `YYYY-MM-DD HH:MM` time.
+pub fn date_time(value: &str) -> Result<(), String> {
+    datetime::parse_date_time(value)
+        .map(|_| ())
+        .ok_or(t("form.date_time").to_string())
+}
+
+pub fn past_date(value: &str) -> Result<(), String> {
+    let date = datetime::parse_date(value).ok_or(t("form.date").to_string())?;
+    if date > datetime::today() {
+        return Err(t("form.future_date").to_string());
+    }
+    Ok(())
+}
+
+/// Every code is in the procedure catalog.
+pub fn procedure_codes(db: &Db, value: &str) -> Result<(), String> {
+    let catalog =
+        db::get_procedures(db).map_err(|e| tf("form.procedures_failed", &[("error", &e)]))?;
+    let unknown = procedures::unknown_codes(value, &catalog);
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(tf(
+            "form.unknown_procedures",
+            &[("codes", &unknown.join(", "))],
+        ))
+    }
+}
+
+/// A single code from the procedure catalog.
+pub fn procedure_code(db: &Db, value: &str) -> Result<(), String> {
+    if procedures::codes(value).len() > 1 {
+        return Err(t("form.single_procedure").to_string());
+    }
+    procedure_codes(db, value)
+}
+
+pub fn blood_pressure(value: &str) -> Result<(), String> {
+    let valid = value.split_once('/').is_some_and(|(systolic, diastolic)| {
+        systolic.trim().parse::<u16>().is_ok() && diastolic.trim().parse::<u16>().is_ok()
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(t("form.blood_pressure").to_string())
+    }
+}
+
+pub fn one_of(options: &'static [&'static str]) -> Validator {
+    Box::new(move |value| {
+        if options.contains(&value) {
+            Ok(())
+        } else {
+            Err(tf("form.one_of", &[("options", &options.join(", "))]))
+        }
+    })
+}
+
+pub fn integer_range(min: i64, max: i64) -> Validator {
+    Box::new(move |value| match value.parse::<i64>() {
+        Ok(n) if (min..=max).contains(&n) => Ok(()),
+        Ok(_) => Err(tf("form.between", &[("min", &min), ("max", &max)])),
+        Err(_) => Err(t("form.whole_number").to_string()),
+    })
+}
+
+pub fn number_range(min: f64, max: f64) -> Validator {
+    Box::new(move |value| match value.parse::<f64>() {
+        Ok(n) if n.is_finite() && n >= min && n <= max => Ok(()),
+        Ok(_) => Err(tf("form.between", &[("min", &min), ("max", &max)])),
+        Err(_) => Err(t("form.number").to_string()),
+    })
+}
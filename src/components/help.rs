@@ -0,0 +1,81 @@
+use crate::components::table::{cell, column_widths};
+use crate::i18n::{t, tf};
+use crate::keymap::{global_bindings, KeyBinding, Keymap};
+use crate::theme::theme;
+use crate::tui::Frame;
+use ratatui::{prelude::*, widgets::*};
+
+fn binding_rows<'a>(bindings: &'a [KeyBinding], columns: &[usize]) -> Vec<Row<'a>> {
+    bindings
+        .iter()
+        .map(|b| {
+            Row::new(vec![
+                cell(&b.keys, columns, 0).style(
+                    Style::default()
+                        .fg(theme().focus)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                cell(t(b.action), columns, 1).style(Style::default().fg(theme().text)),
+            ])
+        })
+        .collect()
+}
+
+pub fn render_help(frame: &mut Frame, keymap: &Keymap) {
+    let area = frame.area();
+    let global = global_bindings();
+    let height = (keymap.bindings.len() + global.len() + 7) as u16;
+    let dialog_area = Rect::new(
+        area.width.saturating_sub(64) / 2,
+        area.height.saturating_sub(height) / 2,
+        64.min(area.width),
+        height.min(area.height),
+    );
+    frame.render_widget(Clear, dialog_area);
+    let block = Block::default()
+        .title(format!(
+            " ❓ {} ",
+            tf("help.title", &[("screen", &t(keymap.title))])
+        ))
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme().focus))
+        .style(Style::default().bg(theme().dialog));
+    let inner = block.inner(dialog_area);
+    frame.render_widget(block, dialog_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(keymap.bindings.len() as u16),
+            Constraint::Length(1),
+            Constraint::Length(global.len() as u16 + 1),
+            Constraint::Min(1),
+        ])
+        .horizontal_margin(1)
+        .split(inner);
+
+    let widths = [Constraint::Length(18), Constraint::Min(20)];
+    let columns = column_widths(chunks[0], &widths, 1, "");
+    frame.render_widget(
+        Table::new(binding_rows(keymap.bindings, &columns), widths),
+        chunks[0],
+    );
+    frame.render_widget(
+        Table::new(binding_rows(&global, &columns), widths).header(
+            Row::new(vec![t("help.everywhere")]).style(
+                Style::default()
+                    .fg(theme().title)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ),
+        chunks[2],
+    );
+    frame.render_widget(
+        Paragraph::new(t("help.close"))
+            .style(Style::default().fg(theme().help))
+            .alignment(Alignment::Center),
+        chunks[3],
+    );
+}
@@ -1,15 +1,30 @@
 use crate::app::SelectedApp;
+use crate::components::quick_actions;
 use crate::components::Component;
-use crate::db;
+use crate::db::worker::{self, Task};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::DashboardMetrics;
+use crate::plugins;
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::ui_state::{self, Recent};
+use crate::utils::{datetime, money};
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     prelude::*,
-    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Padding, Paragraph},
+    widgets::{
+        Block, BorderType, Borders, Clear, List, ListItem, ListState, Padding, Paragraph, Wrap,
+    },
 };
+use std::time::{Duration, Instant};
+
+const DASHBOARD_REFRESH: Duration = Duration::from_secs(5);
+const TILE_COUNT: usize = 6;
 
 pub struct Home {
+    db: Db,
     username: Option<String>,
     selection_mode: usize,
     show_logout_dialog: bool,
@@ -19,43 +34,110 @@ pub struct Home {
     submenu_states: Vec<ListState>,
     features: Vec<&'static str>,
     submenu_options: Vec<Vec<&'static str>>,
+    metrics: DashboardMetrics,
+    metrics_error: Option<String>,
+    selected_tile: usize,
+    last_refresh: Option<Instant>,
+    pending_metrics: Option<Task<DashboardMetrics>>,
+    /// A recently opened item picked with its digit, for the app to open.
+    opened_recent: Option<Recent>,
 }
 
 impl Home {
-    pub fn new() -> Self {
-        let features = vec![
-            "Billing & Finance",
-            "Medical Records",
-            "Patient Management",
-            "Staff Management",
+    pub fn new(db: &Db) -> Self {
+        let mut features = vec![
+            "home.finance",
+            "home.records",
+            "home.patients",
+            "home.staff",
+            "home.waitlist",
+            "home.registry",
+            "home.telemedicine",
+            "home.queue",
+            "home.handover",
+            "home.worklists",
+            "home.maternity",
+            "home.appointments",
+            "home.inventory",
+            "home.settings",
         ];
 
-        let submenu_options = vec![
+        let mut submenu_options = vec![
+            vec![
+                "home.finance.create",
+                "home.finance.view",
+                "home.finance.update",
+                "home.finance.expenses",
+                "home.finance.expense_report",
+                "home.finance.budget",
+                "home.finance.cashier",
+                "home.finance.cashier_history",
+                "home.finance.insurance",
+                "home.finance.approvals",
+            ],
+            vec![
+                "home.records.store",
+                "home.records.list",
+                "home.records.update",
+                "home.records.delete",
+                "home.records.statistics",
+                "home.records.expirations",
+                "home.records.isolations",
+                "home.records.stays",
+                "home.records.integrity",
+                "home.records.waiting",
+                "home.records.forecast",
+                "home.records.satisfaction",
+                "home.records.campaign",
+            ],
+            vec![
+                "home.patients.add",
+                "home.patients.list",
+                "home.patients.update",
+                "home.patients.delete",
+                "home.patients.compliance",
+                "home.patients.import",
+            ],
+            vec![
+                "home.staff.add",
+                "home.staff.assign",
+                "home.staff.delete",
+                "home.staff.list",
+                "home.staff.update",
+                "home.staff.performance",
+            ],
+            vec!["home.waitlist.add", "home.waitlist.list"],
+            vec!["home.registry.births", "home.registry.deaths"],
             vec![
-                "Create Bill/Invoice",
-                "View Bill/Invoices",
-                "Update Bill/Invoice",
+                "home.telemedicine.schedule",
+                "home.telemedicine.upcoming",
+                "home.telemedicine.utilization",
             ],
             vec![
-                "Add/Store Medical Record",
-                "Retrieve/List Medical Records",
-                "Update Medical Record",
-                "Delete Medical Record",
+                "home.queue.issue",
+                "home.queue.console",
+                "home.queue.display",
             ],
+            vec!["home.handover.record", "home.handover.history"],
             vec![
-                "Add New Patient",
-                "List All Patients",
-                "Update Patient Details",
-                "Delete Patient",
+                "home.worklists.my_day",
+                "home.worklists.ward_round",
+                "home.worklists.follow_ups",
             ],
+            vec!["home.maternity.pregnancies", "home.maternity.due"],
             vec![
-                "Add New Staff",
-                "Assign/View Staff Shift",
-                "Delete Staff",
-                "List All Staffs",
-                "Update Staff Details",
+                "home.appointments.book",
+                "home.appointments.list",
+                "home.appointments.update",
             ],
+            vec!["home.inventory.stock", "home.inventory.stocktake"],
+            vec!["home.settings.appearance", "home.settings.webhooks"],
         ];
+        let plugins = plugins::plugins();
+        if !plugins.is_empty() {
+            features.push("home.plugins");
+            submenu_options.push(plugins.iter().map(|plugin| plugin.name).collect());
+        }
 
         let mut submenu_states = Vec::new();
         for _ in 0..features.len() {
@@ -65,6 +147,7 @@ impl Home {
         }
 
         Self {
+            db: db.clone(),
             username: None,
             selection_mode: 0,
             show_logout_dialog: false,
@@ -74,22 +157,108 @@ impl Home {
             submenu_states,
             features,
             submenu_options,
+            metrics: DashboardMetrics::default(),
+            metrics_error: None,
+            selected_tile: 0,
+            last_refresh: None,
+            pending_metrics: None,
+            opened_recent: None,
         }
     }
 
     pub fn load_username(&mut self, user_id: i64) -> Result<()> {
-        self.username = Some(db::get_username(user_id)?);
+        self.username = Some(db::get_username(&self.db, user_id)?);
+        self.refresh_metrics();
         Ok(())
     }
 
+    fn refresh_metrics(&mut self) {
+        self.last_refresh = Some(Instant::now());
+        let now = datetime::local_now();
+        let today = datetime::format_date(now.date());
+        let current_shift = match now.hour() {
+            6..=13 => "Morning",
+            14..=21 => "Afternoon",
+            _ => "Night",
+        };
+        let username = self.username.clone();
+        let db = self.db.clone();
+        self.pending_metrics = Some(worker::spawn(move || {
+            db::get_dashboard_metrics(&db, &today, current_shift, username.as_deref())
+        }));
+    }
+
+    pub fn tick(&mut self) {
+        if let Some(pending) = &self.pending_metrics {
+            let Some(result) = pending.poll() else {
+                return;
+            };
+            self.pending_metrics = None;
+            match result {
+                Ok(metrics) => {
+                    self.metrics = metrics;
+                    self.metrics_error = None;
+                }
+                Err(e) => self.metrics_error = Some(tf("home.dashboard_error", &[("error", &e)])),
+            }
+        }
+        if self
+            .last_refresh
+            .is_none_or(|t| t.elapsed() >= DASHBOARD_REFRESH)
+        {
+            self.refresh_metrics();
+        }
+    }
+
+    fn tile_target(&self) -> SelectedApp {
+        match self.selected_tile {
+            0 => SelectedApp::QueueConsole,
+            1 => SelectedApp::PatientList,
+            2 => SelectedApp::StaffAssign,
+            3 => SelectedApp::BillingView,
+            4 => SelectedApp::HandoverHistory,
+            _ => SelectedApp::PatientList,
+        }
+    }
+
+    /// The recently opened item chosen by the last key, if any.
+    pub fn take_recent(&mut self) -> Option<Recent> {
+        self.opened_recent.take()
+    }
+
     pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
         if self.show_logout_dialog {
             return self.handle_logout_dialog_input(key);
         }
+        if let Some(app) = quick_actions::open(key) {
+            return Ok(Some(app));
+        }
+        if let KeyCode::Char(digit @ '1'..='9') = key.code {
+            if !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            {
+                let index = digit as usize - '1' as usize;
+                self.opened_recent = ui_state::recent().into_iter().nth(index);
+                return Ok(None);
+            }
+        }
 
         match key.code {
             KeyCode::Tab => {
-                self.selection_mode = (self.selection_mode + 1) % 2;
+                self.selection_mode = (self.selection_mode + 1) % 3;
+            }
+            KeyCode::BackTab => {
+                self.selection_mode = (self.selection_mode + 2) % 3;
+            }
+            KeyCode::Left if self.selection_mode == 1 => {
+                self.selected_tile = (self.selected_tile + TILE_COUNT - 1) % TILE_COUNT;
+            }
+            KeyCode::Right if self.selection_mode == 1 => {
+                self.selected_tile = (self.selected_tile + 1) % TILE_COUNT;
+            }
+            KeyCode::Enter if self.selection_mode == 1 => {
+                return Ok(Some(self.tile_target()));
             }
             KeyCode::Left => {
                 if self.selection_mode == 0 && self.active_panel == 1 {
@@ -147,6 +316,13 @@ impl Home {
                                 0 => SelectedApp::BillingInvoice,
                                 1 => SelectedApp::BillingView,
                                 2 => SelectedApp::BillingUpdate,
+                                3 => SelectedApp::BillingExpenses,
+                                4 => SelectedApp::BillingExpenseReport,
+                                5 => SelectedApp::BillingBudget,
+                                6 => SelectedApp::BillingCashier,
+                                7 => SelectedApp::BillingCashierHistory,
+                                8 => SelectedApp::BillingInsurance,
+                                9 => SelectedApp::BillingApprovals,
                                 _ => SelectedApp::Hospital,
                             },
 
@@ -155,6 +331,15 @@ impl Home {
                                 1 => SelectedApp::RecordRetrieve,
                                 2 => SelectedApp::RecordUpdate,
                                 3 => SelectedApp::RecordDelete,
+                                4 => SelectedApp::RecordStatistics,
+                                5 => SelectedApp::RecordExpirations,
+                                6 => SelectedApp::RecordIsolations,
+                                7 => SelectedApp::RecordStays,
+                                8 => SelectedApp::RecordIntegrity,
+                                9 => SelectedApp::RecordWaiting,
+                                10 => SelectedApp::RecordForecast,
+                                11 => SelectedApp::RecordSatisfaction,
+                                12 => SelectedApp::RecordCampaign,
                                 _ => SelectedApp::Hospital,
                             },
 
@@ -163,6 +348,8 @@ impl Home {
                                 1 => SelectedApp::PatientList,
                                 2 => SelectedApp::PatientUpdate,
                                 3 => SelectedApp::PatientDelete,
+                                4 => SelectedApp::PatientCompliance,
+                                5 => SelectedApp::PatientImport,
                                 _ => SelectedApp::Hospital,
                             },
 
@@ -172,8 +359,73 @@ impl Home {
                                 2 => SelectedApp::StaffDelete,
                                 3 => SelectedApp::StaffList,
                                 4 => SelectedApp::StaffUpdate,
+                                5 => SelectedApp::StaffPerformance,
+                                _ => SelectedApp::Hospital,
+                            },
+
+                            4 => match submenu_idx {
+                                0 => SelectedApp::WaitlistAdd,
+                                1 => SelectedApp::WaitlistList,
+                                _ => SelectedApp::Hospital,
+                            },
+
+                            5 => match submenu_idx {
+                                0 => SelectedApp::RegistryBirths,
+                                1 => SelectedApp::RegistryDeaths,
+                                _ => SelectedApp::Hospital,
+                            },
+
+                            6 => match submenu_idx {
+                                0 => SelectedApp::TeleconsultSchedule,
+                                1 => SelectedApp::TeleconsultUpcoming,
+                                2 => SelectedApp::TeleconsultUtilization,
+                                _ => SelectedApp::Hospital,
+                            },
+
+                            7 => match submenu_idx {
+                                0 => SelectedApp::QueueIssue,
+                                1 => SelectedApp::QueueConsole,
+                                2 => SelectedApp::QueueDisplay,
+                                _ => SelectedApp::Hospital,
+                            },
+
+                            8 => match submenu_idx {
+                                0 => SelectedApp::HandoverRecord,
+                                1 => SelectedApp::HandoverHistory,
                                 _ => SelectedApp::Hospital,
                             },
+
+                            9 => match submenu_idx {
+                                0 => SelectedApp::WorklistMyDay,
+                                1 => SelectedApp::WorklistWardRound,
+                                2 => SelectedApp::WorklistFollowUps,
+                                _ => SelectedApp::Hospital,
+                            },
+
+                            10 => match submenu_idx {
+                                0 => SelectedApp::MaternityPregnancies,
+                                1 => SelectedApp::MaternityDue,
+                                _ => SelectedApp::Hospital,
+                            },
+
+                            11 => match submenu_idx {
+                                0 => SelectedApp::AppointmentBook,
+                                1 => SelectedApp::AppointmentList,
+                                2 => SelectedApp::AppointmentUpdate,
+                                _ => SelectedApp::Hospital,
+                            },
+
+                            12 => match submenu_idx {
+                                0 => SelectedApp::InventoryStock,
+                                1 => SelectedApp::InventoryStocktake,
+                                _ => SelectedApp::Hospital,
+                            },
+
+                            13 => match submenu_idx {
+                                1 => SelectedApp::WebhookLog,
+                                _ => SelectedApp::Settings,
+                            },
+                            14 => SelectedApp::Plugin(submenu_idx),
                             _ => SelectedApp::Hospital,
                         }));
                     } else {
@@ -225,7 +477,7 @@ impl Component for Home {
 
     fn render(&self, frame: &mut Frame) {
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             frame.area(),
         );
 
@@ -235,6 +487,7 @@ impl Component for Home {
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(5),
+                Constraint::Length(6),
                 Constraint::Length(1),
                 Constraint::Min(10),
                 Constraint::Length(3),
@@ -242,18 +495,18 @@ impl Component for Home {
             ])
             .split(area);
 
-        let username = self.username.as_deref().unwrap_or("User");
+        let username = self.username.as_deref().unwrap_or(t("home.default_user"));
         let welcome_text = Line::from(vec![
             Span::styled(
-                "Welcome to Rustoria, ",
+                format!("{} ", t("home.welcome")),
                 Style::default()
-                    .fg(Color::White)
+                    .fg(theme().text)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
                 username,
                 Style::default()
-                    .fg(Color::Rgb(129, 199, 245))
+                    .fg(theme().accent)
                     .add_modifier(Modifier::BOLD),
             ),
         ]);
@@ -261,8 +514,8 @@ impl Component for Home {
         let welcome_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(24, 24, 40)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
 
         let welcome_inner = welcome_block.inner(main_layout[0]);
         frame.render_widget(welcome_block, main_layout[0]);
@@ -273,36 +526,38 @@ impl Component for Home {
 
         frame.render_widget(welcome_paragraph, welcome_inner);
 
-        let instruction = Paragraph::new("Please select a task:")
-            .style(Style::default().fg(Color::Rgb(180, 190, 254)))
+        self.render_dashboard(frame, main_layout[1]);
+
+        let instruction = Paragraph::new(t("home.select_task"))
+            .style(Style::default().fg(theme().title))
             .alignment(Alignment::Center);
 
-        frame.render_widget(instruction, main_layout[1]);
+        frame.render_widget(instruction, main_layout[2]);
 
         let content_layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
             .spacing(2)
             .margin(1)
-            .split(main_layout[2]);
+            .split(main_layout[3]);
 
         let left_panel_style = if self.active_panel == 0 && self.selection_mode == 0 {
-            Style::default().fg(Color::Rgb(250, 250, 110))
+            Style::default().fg(theme().focus)
         } else {
-            Style::default().fg(Color::Rgb(140, 140, 200))
+            Style::default().fg(theme().unfocused)
         };
 
         let left_panel_block = Block::default()
-            .title(" 🏥 Hospital Management ")
+            .title(format!(" 🏥 {} ", t("home.menu")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .border_style(left_panel_style)
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .style(Style::default().bg(theme().surface));
 
         frame.render_widget(left_panel_block.clone(), content_layout[0]);
         let left_inner = left_panel_block.inner(content_layout[0]);
@@ -320,15 +575,15 @@ impl Component for Home {
                 let style = if idx == self.selected_feature_index {
                     if self.active_panel == 0 && self.selection_mode == 0 {
                         Style::default()
-                            .fg(Color::Rgb(250, 250, 110))
+                            .fg(theme().focus)
                             .add_modifier(Modifier::BOLD)
                     } else {
                         Style::default()
-                            .fg(Color::Rgb(140, 219, 140))
+                            .fg(theme().success)
                             .add_modifier(Modifier::BOLD)
                     }
                 } else {
-                    Style::default().fg(Color::Rgb(200, 200, 220))
+                    Style::default().fg(theme().text)
                 };
 
                 let prefix = if idx == self.selected_feature_index {
@@ -342,10 +597,21 @@ impl Component for Home {
                     1 => "📋",
                     2 => "👤",
                     3 => "👥",
+                    4 => "⏳",
+                    5 => "📜",
+                    6 => "📞",
+                    7 => "🎫",
+                    8 => "🔁",
+                    9 => "🩺",
+                    10 => "🤰",
+                    11 => "🗓",
+                    12 => "📦",
+                    13 => "⚙️",
+                    14 => "🧩",
                     _ => "•",
                 };
 
-                ListItem::new(format!("{}{} {}", prefix, icon, feature)).style(style)
+                ListItem::new(format!("{}{} {}", prefix, icon, t(feature))).style(style)
             })
             .collect();
 
@@ -353,29 +619,34 @@ impl Component for Home {
             .block(Block::default())
             .highlight_style(
                 Style::default()
-                    .bg(Color::Rgb(40, 40, 65))
+                    .bg(theme().highlight)
                     .add_modifier(Modifier::BOLD),
             );
 
-        frame.render_widget(features_list, left_padded[1]);
+        // Stateful so the selected feature scrolls into view on short terminals.
+        frame.render_stateful_widget(
+            features_list,
+            left_padded[1],
+            &mut ListState::default().with_selected(Some(self.selected_feature_index)),
+        );
 
         let right_panel_style = if self.active_panel == 1 && self.selection_mode == 0 {
-            Style::default().fg(Color::Rgb(250, 250, 110))
+            Style::default().fg(theme().focus)
         } else {
-            Style::default().fg(Color::Rgb(140, 140, 200))
+            Style::default().fg(theme().unfocused)
         };
 
         let right_panel_block = Block::default()
-            .title(" Sub menu ")
+            .title(format!(" {} ", t("home.submenu")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .border_style(right_panel_style)
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .style(Style::default().bg(theme().surface));
 
         frame.render_widget(right_panel_block.clone(), content_layout[1]);
         let right_inner = right_panel_block.inner(content_layout[1]);
@@ -390,15 +661,15 @@ impl Component for Home {
                 let style = if current_submenu_state.selected() == Some(idx) {
                     if self.active_panel == 1 && self.selection_mode == 0 {
                         Style::default()
-                            .fg(Color::Rgb(250, 250, 110))
+                            .fg(theme().focus)
                             .add_modifier(Modifier::BOLD)
                     } else {
                         Style::default()
-                            .fg(Color::Rgb(129, 199, 245))
+                            .fg(theme().accent)
                             .add_modifier(Modifier::BOLD)
                     }
                 } else {
-                    Style::default().fg(Color::Rgb(200, 200, 220))
+                    Style::default().fg(theme().text)
                 };
 
                 let prefix = if current_submenu_state.selected() == Some(idx) {
@@ -407,7 +678,7 @@ impl Component for Home {
                     "   "
                 };
 
-                ListItem::new(format!("{}{}", prefix, option)).style(style)
+                ListItem::new(format!("{}{}", prefix, t(option))).style(style)
             })
             .collect();
 
@@ -415,47 +686,71 @@ impl Component for Home {
             .block(Block::default().padding(Padding::new(2, 0, 2, 0)))
             .highlight_style(
                 Style::default()
-                    .bg(Color::Rgb(40, 40, 65))
+                    .bg(theme().highlight)
                     .add_modifier(Modifier::BOLD),
             );
 
         frame.render_widget(submenu_list, right_inner);
 
-        let help_text =
-            "←→: Switch panels | ↑↓: Navigate | Enter: Select | Tab: Logout | Esc: Back";
+        let mut help_text = vec![Line::from(t("home.help"))];
+        let actions = quick_actions::actions();
+        if !actions.is_empty() {
+            let actions: Vec<String> = actions
+                .iter()
+                .map(|(key, title, _)| format!("{}: {}", key, t(title)))
+                .collect();
+            help_text.push(Line::from(format!(
+                "{} {}",
+                t("home.quick_actions"),
+                actions.join(" | ")
+            )));
+        }
+        let recent = ui_state::recent();
+        if !recent.is_empty() {
+            let recent: Vec<String> = recent
+                .iter()
+                .enumerate()
+                .map(|(index, item)| format!("{}: {}", index + 1, item.label))
+                .collect();
+            help_text.push(Line::from(format!(
+                "{} {}",
+                t("home.recent"),
+                recent.join(" | ")
+            )));
+        }
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(140, 140, 170)))
+            .style(Style::default().fg(theme().help))
             .alignment(Alignment::Center);
 
-        frame.render_widget(help_paragraph, main_layout[3]);
+        frame.render_widget(help_paragraph, main_layout[4]);
 
-        let back_text = if self.selection_mode == 1 {
-            "[ Logout ]"
+        let back_text = if self.selection_mode == 2 {
+            format!("[ {} ]", t("home.logout"))
         } else {
-            "  Logout  "
+            format!("  {}  ", t("home.logout"))
         };
 
-        let back_style = if self.selection_mode == 1 {
+        let back_style = if self.selection_mode == 2 {
             Style::default()
-                .fg(Color::Rgb(255, 100, 100))
+                .fg(theme().error)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let back_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(if self.selection_mode == 1 {
-                Style::default().fg(Color::Rgb(255, 100, 100))
+            .border_style(if self.selection_mode == 2 {
+                Style::default().fg(theme().error)
             } else {
-                Style::default().fg(Color::Rgb(100, 100, 140))
+                Style::default().fg(theme().help)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
-        frame.render_widget(back_block.clone(), main_layout[4]);
+        frame.render_widget(back_block.clone(), main_layout[5]);
 
-        let inner_logout = back_block.inner(main_layout[4]);
+        let inner_logout = back_block.inner(main_layout[5]);
         let back_paragraph = Paragraph::new(back_text)
             .style(back_style)
             .alignment(Alignment::Center);
@@ -469,6 +764,135 @@ impl Component for Home {
 }
 
 impl Home {
+    fn render_dashboard(&self, frame: &mut Frame, area: Rect) {
+        let m = &self.metrics;
+        let on_shift = match m.staff_on_shift.as_slice() {
+            [] => t("home.nobody_rostered").to_string(),
+            [one] => one.clone(),
+            [first, rest @ ..] => tf("home.more", &[("name", first), ("count", &rest.len())]),
+        };
+        let mut notifications = Vec::new();
+        if m.pending_handovers > 0 {
+            notifications.push(tf(
+                "home.pending_handovers",
+                &[("count", &m.pending_handovers)],
+            ));
+        }
+        if m.urgent_waitlist > 0 {
+            notifications.push(tf("home.urgent_waitlist", &[("count", &m.urgent_waitlist)]));
+        }
+        if m.queue_waiting > 0 {
+            notifications.push(tf("home.queue_waiting", &[("count", &m.queue_waiting)]));
+        }
+        if m.feedback_to_follow_up > 0 {
+            notifications.push(tf(
+                "home.feedback_to_follow_up",
+                &[("count", &m.feedback_to_follow_up)],
+            ));
+        }
+        if notifications.is_empty() {
+            notifications.push(t("home.all_clear").to_string());
+        }
+
+        let tiles: [(String, String, String); TILE_COUNT] = [
+            (
+                format!(" 📅 {} ", t("home.tile.visits")),
+                (m.tokens_today + m.appointments_today + m.teleconsults_today).to_string(),
+                tf(
+                    "home.visits_detail",
+                    &[
+                        ("tokens", &m.tokens_today),
+                        ("booked", &m.appointments_today),
+                        ("remote", &m.teleconsults_today),
+                    ],
+                ),
+            ),
+            (
+                format!(" 🧑 {} ", t("home.tile.patients")),
+                m.admitted_count.to_string(),
+                tf("home.admitted", &[("count", &m.patient_count)]),
+            ),
+            (
+                format!(" 👥 {} ", t("home.tile.on_shift")),
+                m.staff_on_shift.len().to_string(),
+                on_shift,
+            ),
+            (
+                format!(" 💰 {} ", t("home.tile.outstanding")),
+                money(m.invoiced_total - m.paid_total),
+                tf(
+                    "home.outstanding_detail",
+                    &[
+                        ("billed", &money(m.invoiced_total)),
+                        ("paid", &money(m.paid_total)),
+                    ],
+                ),
+            ),
+            (
+                format!(" 🔔 {} ", t("home.tile.notifications")),
+                notifications.len().to_string(),
+                notifications.join(" · "),
+            ),
+            (
+                format!(" 📌 {} ", t("home.tile.pinned")),
+                m.pinned_patients.len().to_string(),
+                if m.pinned_patients.is_empty() {
+                    t("home.nothing_pinned").to_string()
+                } else {
+                    m.pinned_patients.join(" · ")
+                },
+            ),
+        ];
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, TILE_COUNT as u32); TILE_COUNT])
+            .horizontal_margin(1)
+            .split(area);
+        for (i, (title, value, detail)) in tiles.into_iter().enumerate() {
+            let focused = self.selection_mode == 1 && self.selected_tile == i;
+            let block = Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(if focused {
+                    Style::default().fg(theme().focus)
+                } else {
+                    Style::default().fg(theme().border)
+                })
+                .style(Style::default().bg(theme().surface));
+            let lines = vec![
+                Line::from(Span::styled(
+                    value,
+                    Style::default()
+                        .fg(if focused {
+                            theme().focus
+                        } else {
+                            theme().accent
+                        })
+                        .add_modifier(Modifier::BOLD),
+                )),
+                Line::from(Span::styled(detail, Style::default().fg(theme().help))),
+            ];
+            frame.render_widget(
+                Paragraph::new(lines)
+                    .alignment(Alignment::Center)
+                    .wrap(Wrap { trim: true })
+                    .block(block),
+                columns[i],
+            );
+        }
+
+        if let Some(error) = &self.metrics_error {
+            frame.render_widget(
+                Paragraph::new(error.as_str())
+                    .style(Style::default().fg(theme().error))
+                    .alignment(Alignment::Center),
+                Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1),
+            );
+        }
+    }
+
     fn render_logout_dialog(&self, frame: &mut Frame, area: Rect) {
         let dialog_width = 40;
         let dialog_height = 8;
@@ -483,16 +907,16 @@ impl Home {
         frame.render_widget(Clear, dialog_area);
 
         let dialog_block = Block::default()
-            .title(" Confirm Logout ")
+            .title(format!(" {} ", t("home.confirm_logout")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-            .style(Style::default().bg(Color::Rgb(30, 30, 46)));
+            .border_style(Style::default().fg(theme().unfocused))
+            .style(Style::default().bg(theme().dialog));
 
         frame.render_widget(dialog_block.clone(), dialog_area);
 
@@ -504,8 +928,8 @@ impl Home {
             .constraints([Constraint::Length(2), Constraint::Length(2)])
             .split(inner_area);
 
-        let message = Paragraph::new("Are you sure you want to logout?")
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+        let message = Paragraph::new(t("home.confirm_logout_message"))
+            .style(Style::default().fg(theme().text))
             .add_modifier(Modifier::BOLD)
             .alignment(Alignment::Center);
 
@@ -518,30 +942,30 @@ impl Home {
 
         let yes_style = if self.logout_dialog_selected == 0 {
             Style::default()
-                .fg(Color::Rgb(140, 219, 140))
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let no_style = if self.logout_dialog_selected == 1 {
             Style::default()
-                .fg(Color::Rgb(255, 100, 100))
+                .fg(theme().error)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let yes_text = if self.logout_dialog_selected == 0 {
-            "► Yes ◄"
+            format!("► {} ◄", t("common.yes"))
         } else {
-            "  Yes  "
+            format!("  {}  ", t("common.yes"))
         };
 
         let no_text = if self.logout_dialog_selected == 1 {
-            "► No ◄"
+            format!("► {} ◄", t("common.no"))
         } else {
-            "  No  "
+            format!("  {}  ", t("common.no"))
         };
 
         let yes_button = Paragraph::new(yes_text)
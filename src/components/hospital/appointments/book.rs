@@ -0,0 +1,684 @@
+use super::{find_conflict, span};
+use crate::app::SelectedApp;
+use crate::components::form::{self, integer_range, Field, Form};
+use crate::components::hospital::staff::shift_label;
+use crate::components::quick_actions;
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::date_picker::DatePicker;
+use crate::components::widgets::new_patient::{NewPatient, NewPatientOutcome};
+use crate::components::widgets::text_input::TextInput;
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{
+    Appointment, AppointmentStatus, Patient, StaffMember, StaffRole, TeleconsultStatus,
+};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state;
+use crate::utils::{config, datetime};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+
+const PATIENT_INPUT: usize = 0;
+const DOCTOR_INPUT: usize = 1;
+const TIME_INPUT: usize = 2;
+const DURATION_INPUT: usize = 3;
+const REASON_INPUT: usize = 4;
+const SUBMIT_BUTTON: usize = 5;
+const BACK_BUTTON: usize = 6;
+const INPUT_FIELDS: usize = 5;
+
+fn appointment_form() -> Form {
+    let mut form = Form::new(vec![
+        Field::new(t("common.patient_id"))
+            .required()
+            .validate_with(integer_range(1, i64::MAX)),
+        Field::new(t("common.doctor_id"))
+            .required()
+            .validate_with(integer_range(1, i64::MAX)),
+        Field::new(t("appointments.book.date_time"))
+            .required()
+            .validate_with(Box::new(form::date_time)),
+        Field::new(t("appointments.book.duration"))
+            .required()
+            .validate_with(integer_range(5, 12 * 60)),
+        Field::new(t("appointments.book.reason")),
+    ]);
+    form.fields[DURATION_INPUT].set(config::config().slot_minutes.max(5).to_string());
+    form.mark_clean();
+    form
+}
+
+fn shift_hours(shift: &str) -> &'static str {
+    match shift {
+        "Morning" => "6am-2pm",
+        "Afternoon" => "2pm-10pm",
+        _ => "10pm-6am",
+    }
+}
+
+/// Books a new appointment, or changes one on the update screen.
+pub struct AppointmentForm {
+    db: Db,
+    /// The appointment being changed; booking a new one when `None`.
+    appointment: Option<Appointment>,
+    /// On the update screen, the id being typed until an appointment is
+    /// loaded.
+    id_input: Option<TextInput>,
+    form: Form,
+    focus_index: usize,
+    patients: HashMap<i64, Patient>,
+    doctors: HashMap<i64, StaffMember>,
+    /// The chosen doctor's shifts and bookings on the chosen day.
+    doctor_day: Vec<Line<'static>>,
+    date_picker: Option<DatePicker>,
+    new_patient: Option<NewPatient>,
+}
+
+impl AppointmentForm {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            appointment: None,
+            id_input: None,
+            form: appointment_form(),
+            focus_index: PATIENT_INPUT,
+            patients: HashMap::new(),
+            doctors: HashMap::new(),
+            doctor_day: Vec::new(),
+            date_picker: None,
+            new_patient: None,
+        }
+    }
+
+    /// The update screen, which starts by asking for the appointment.
+    pub fn for_update(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            id_input: Some(TextInput::new()),
+            ..Self::new(db)
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        self.doctors = db::get_all_staff(&self.db)?
+            .into_iter()
+            .filter(|s| s.role == StaffRole::Doctor)
+            .map(|s| (s.id, s))
+            .collect();
+        self.refresh_doctor_day();
+        Ok(())
+    }
+
+    pub fn load_appointment(&mut self, appointment: Appointment) {
+        let form = &mut self.form;
+        form.fields[PATIENT_INPUT].set(appointment.patient_id.to_string());
+        form.fields[DOCTOR_INPUT].set(appointment.doctor_id.to_string());
+        form.fields[TIME_INPUT].set(datetime::facility_stamp(&appointment.scheduled_at));
+        form.fields[DURATION_INPUT].set(appointment.duration_minutes.to_string());
+        form.fields[REASON_INPUT].set(appointment.reason.clone().unwrap_or_default());
+        form.mark_clean();
+        self.id_input = None;
+        self.appointment = Some(appointment);
+        self.focus_index = TIME_INPUT;
+        self.refresh_doctor_day();
+    }
+
+    fn load_typed_id(&mut self) {
+        let typed = self
+            .id_input
+            .as_ref()
+            .map(|input| input.value().trim().to_string())
+            .unwrap_or_default();
+        let Ok(id) = typed.parse::<i64>() else {
+            toast::push(Toast::error(t("appointments.book.enter_number")));
+            return;
+        };
+        match db::get_appointment(&self.db, id) {
+            Ok(appointment) if appointment.status != AppointmentStatus::Scheduled => {
+                toast::push(Toast::error(tf(
+                    "appointments.not_changeable",
+                    &[
+                        ("id", &id),
+                        (
+                            "status",
+                            &super::status_text(appointment.status).to_lowercase(),
+                        ),
+                    ],
+                )));
+            }
+            Ok(appointment) => self.load_appointment(appointment),
+            Err(e) => toast::push(Toast::error(format!("{}", e))),
+        }
+    }
+
+    fn resolved_patient(&self) -> Option<&Patient> {
+        self.form
+            .value(PATIENT_INPUT)
+            .parse::<i64>()
+            .ok()
+            .and_then(|id| self.patients.get(&id))
+    }
+
+    fn resolved_doctor(&self) -> Option<&StaffMember> {
+        self.form
+            .value(DOCTOR_INPUT)
+            .parse::<i64>()
+            .ok()
+            .and_then(|id| self.doctors.get(&id))
+    }
+
+    fn patient_name(&self, patient_id: i64) -> String {
+        self.patients
+            .get(&patient_id)
+            .map(|p| format!("{} {}", p.first_name, p.last_name))
+            .unwrap_or_else(|| format!("Patient #{}", patient_id))
+    }
+
+    /// Works out the summary of the doctor's day shown under the form, after
+    /// the doctor or the date changed.
+    fn refresh_doctor_day(&mut self) {
+        self.doctor_day = match self.doctor_day_lines() {
+            Ok(lines) => lines,
+            Err(e) => vec![Line::styled(
+                tf("appointments.book.day_failed", &[("error", &e)]),
+                Style::default().fg(theme().error),
+            )],
+        };
+    }
+
+    fn doctor_day_lines(&self) -> Result<Vec<Line<'static>>> {
+        let Some(doctor) = self.resolved_doctor() else {
+            return Ok(Vec::new());
+        };
+        let Some(date) = datetime::parse_date(datetime::date_part(&self.form.value(TIME_INPUT)))
+        else {
+            return Ok(Vec::new());
+        };
+        let day = datetime::format_date(date);
+        let label =
+            |text: &str| Span::styled(text.to_string(), Style::default().fg(theme().inactive));
+
+        let shifts: Vec<String> = db::get_assigned_shifts_for_staff(&self.db, doctor.id)?
+            .into_iter()
+            .filter(|(d, _)| *d == date)
+            .map(|(_, shift)| format!("{} ({})", shift_label(&shift), shift_hours(&shift)))
+            .collect();
+        let shift_line = Line::from(vec![
+            label(&format!(
+                "{} ",
+                tf(
+                    "appointments.book.doctor_day",
+                    &[("name", &doctor.name), ("day", &day)]
+                )
+            )),
+            if shifts.is_empty() {
+                Span::styled(
+                    t("appointments.book.no_shift"),
+                    Style::default().fg(theme().warning),
+                )
+            } else {
+                Span::styled(shifts.join(", "), Style::default().fg(theme().success))
+            },
+        ]);
+
+        let editing = self.appointment.as_ref().map(|a| a.id);
+        let mut booked: Vec<(time::PrimitiveDateTime, String)> = Vec::new();
+        for a in db::get_appointments_between(&self.db, &day, &day)? {
+            if a.doctor_id != doctor.id
+                || a.status != AppointmentStatus::Scheduled
+                || Some(a.id) == editing
+            {
+                continue;
+            }
+            if let Some((start, end)) = span(&a.scheduled_at, a.duration_minutes.into()) {
+                booked.push((
+                    start,
+                    format!(
+                        "{:02}:{:02}-{:02}:{:02}",
+                        start.hour(),
+                        start.minute(),
+                        end.hour(),
+                        end.minute()
+                    ),
+                ));
+            }
+        }
+        for c in db::get_teleconsultations_for_day(&self.db, &day)? {
+            if c.doctor_id != doctor.id || c.status != TeleconsultStatus::Scheduled {
+                continue;
+            }
+            let minutes = c
+                .duration_minutes
+                .map_or(config::config().slot_minutes as i64, i64::from);
+            if let Some((start, end)) = span(&c.scheduled_at, minutes) {
+                booked.push((
+                    start,
+                    format!(
+                        "{:02}:{:02}-{:02}:{:02} (tele)",
+                        start.hour(),
+                        start.minute(),
+                        end.hour(),
+                        end.minute()
+                    ),
+                ));
+            }
+        }
+        booked.sort();
+        let booked_line = Line::from(vec![
+            label(&format!("{} ", t("appointments.book.already_booked"))),
+            Span::styled(
+                if booked.is_empty() {
+                    t("appointments.book.nothing").to_string()
+                } else {
+                    booked
+                        .into_iter()
+                        .map(|(_, text)| text)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                },
+                Style::default().fg(theme().text),
+            ),
+        ]);
+        Ok(vec![shift_line, booked_line])
+    }
+
+    fn reset_form(&mut self) {
+        self.form = appointment_form();
+        self.focus_index = PATIENT_INPUT;
+        self.doctor_day.clear();
+    }
+
+    /// Books or changes the appointment; returns whether it was saved.
+    fn submit(&mut self) -> Result<bool> {
+        if let Some(invalid) = self.form.validate() {
+            self.focus_index = invalid;
+            return Ok(false);
+        }
+        let Some(patient_id) = self.resolved_patient().map(|p| p.id) else {
+            toast::push(Toast::error(t("common.unknown_patient")));
+            self.focus_index = PATIENT_INPUT;
+            return Ok(false);
+        };
+        let Some(doctor) = self.resolved_doctor().cloned() else {
+            toast::push(Toast::error(t("common.unknown_doctor")));
+            self.focus_index = DOCTOR_INPUT;
+            return Ok(false);
+        };
+        let typed = self.form.value(TIME_INPUT);
+        let Some(start) = datetime::parse_date_time(&typed) else {
+            self.focus_index = TIME_INPUT;
+            return Ok(false);
+        };
+
+        let appointment = Appointment {
+            patient_id,
+            doctor_id: doctor.id,
+            scheduled_at: datetime::to_stored(start),
+            duration_minutes: self.form.value(DURATION_INPUT).parse().unwrap_or(15),
+            reason: self.form.optional_value(REASON_INPUT),
+            ..self.appointment.clone().unwrap_or(Appointment {
+                id: 0,
+                patient_id,
+                doctor_id: doctor.id,
+                scheduled_at: String::new(),
+                duration_minutes: 0,
+                reason: None,
+                status: AppointmentStatus::Scheduled,
+                cancel_reason: None,
+                booked_by: ui_state::current_user(),
+                checked_in_at: None,
+            })
+        };
+        if let Some(conflict) =
+            find_conflict(&self.db, &appointment, &doctor, |id| self.patient_name(id))?
+        {
+            toast::push(Toast::error(conflict));
+            self.focus_index = TIME_INPUT;
+            return Ok(false);
+        }
+
+        let who = tf(
+            "appointments.book.with_doctor",
+            &[
+                ("patient", &self.patient_name(patient_id)),
+                ("doctor", &doctor.name),
+            ],
+        );
+        if self.appointment.is_some() {
+            match db::update_appointment(&self.db, &appointment) {
+                Ok(()) => {
+                    toast::push(Toast::success(tf(
+                        "appointments.book.moved",
+                        &[("patient", &who), ("time", &typed)],
+                    )));
+                    self.form.mark_clean();
+                    Ok(true)
+                }
+                Err(e) => {
+                    toast::push(Toast::error(tf(
+                        "appointments.update_failed",
+                        &[("error", &e)],
+                    )));
+                    Ok(false)
+                }
+            }
+        } else {
+            match db::create_appointment(&self.db, &appointment) {
+                Ok(id) => {
+                    toast::push(Toast::success(tf(
+                        "appointments.book.booked",
+                        &[("id", &id), ("patient", &who), ("time", &typed)],
+                    )));
+                    self.reset_form();
+                    quick_actions::saved(SelectedApp::AppointmentBook);
+                    Ok(true)
+                }
+                Err(e) => {
+                    toast::push(Toast::error(tf(
+                        "appointments.book.book_failed",
+                        &[("error", &e)],
+                    )));
+                    Ok(false)
+                }
+            }
+        }
+    }
+
+    fn open_date_picker(&mut self) {
+        let today = datetime::today();
+        let initial = datetime::parse_date(datetime::date_part(&self.form.value(TIME_INPUT)))
+            .unwrap_or(today);
+        self.date_picker = Some(DatePicker::new(initial).with_min(today));
+    }
+
+    fn handle_date_picker(&mut self, key: KeyEvent) {
+        let Some(picker) = self.date_picker.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let typed = self.form.value(TIME_INPUT);
+                let time_part = typed
+                    .get(10..)
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .unwrap_or("09:00")
+                    .to_string();
+                self.form.fields[TIME_INPUT].set(format!(
+                    "{} {}",
+                    datetime::format_date(picker.selected()),
+                    time_part
+                ));
+                self.date_picker = None;
+                self.refresh_doctor_day();
+            }
+            KeyCode::Esc => self.date_picker = None,
+            _ => {
+                picker.handle_key(key);
+            }
+        }
+    }
+
+    fn handle_id_input(&mut self, key: KeyEvent) -> Option<SelectedApp> {
+        match key.code {
+            KeyCode::Esc => return Some(SelectedApp::None),
+            KeyCode::Enter => self.load_typed_id(),
+            _ => {
+                if let Some(input) = self.id_input.as_mut() {
+                    input.handle_key(key);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if self.id_input.is_some() {
+            return Ok(self.handle_id_input(key));
+        }
+        if self.date_picker.is_some() {
+            self.handle_date_picker(key);
+            return Ok(None);
+        }
+        if let Some(popup) = &mut self.new_patient {
+            match popup.handle_key(key) {
+                Some(NewPatientOutcome::Created(patient)) => {
+                    self.new_patient = None;
+                    self.form.fields[PATIENT_INPUT].set(patient.id.to_string());
+                    self.patients.insert(patient.id, *patient);
+                    self.focus_index = DOCTOR_INPUT;
+                }
+                Some(NewPatientOutcome::Cancelled) => self.new_patient = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('d') if ctrl && self.focus_index == TIME_INPUT => {
+                self.open_date_picker();
+            }
+            KeyCode::Char('n')
+                if ctrl && self.focus_index == PATIENT_INPUT && self.appointment.is_none() =>
+            {
+                self.new_patient = Some(NewPatient::new(&self.db));
+            }
+            KeyCode::Tab | KeyCode::Down => {
+                self.focus_index = (self.focus_index + 1) % (BACK_BUTTON + 1);
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                self.focus_index = (self.focus_index + BACK_BUTTON) % (BACK_BUTTON + 1);
+            }
+            KeyCode::Enter => match self.focus_index {
+                SUBMIT_BUTTON => {
+                    if self.submit()? && self.appointment.is_some() {
+                        return Ok(Some(SelectedApp::None));
+                    }
+                }
+                BACK_BUTTON => return Ok(Some(SelectedApp::None)),
+                _ => self.focus_index += 1,
+            },
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {
+                if self.form.handle_key(self.focus_index, key)
+                    && matches!(self.focus_index, DOCTOR_INPUT | TIME_INPUT)
+                {
+                    self.refresh_doctor_day();
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn render_id_prompt(&self, frame: &mut Frame, area: Rect, input: &TextInput) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(3),
+                Constraint::Min(0),
+            ])
+            .horizontal_margin(2)
+            .split(area);
+        frame.render_widget(
+            Paragraph::new(t("appointments.book.change_hint"))
+                .style(Style::default().fg(theme().inactive))
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(input.line(true))
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(Span::styled(
+                            format!(" {} ", t("appointments.book.appointment_id")),
+                            Style::default().fg(theme().title),
+                        ))
+                        .border_style(Style::default().fg(theme().focus))
+                        .style(Style::default().bg(theme().input)),
+                ),
+            layout[1],
+        );
+    }
+
+    fn render_button(&self, frame: &mut Frame, area: Rect, index: usize, text: &str, color: Color) {
+        let focused = self.focus_index == index;
+        frame.render_widget(
+            Paragraph::new(if focused {
+                format!("► {} ◄", text)
+            } else {
+                format!("  {}  ", text)
+            })
+            .style(if focused {
+                Style::default().fg(color).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme().inactive)
+            })
+            .alignment(Alignment::Center),
+            area,
+        );
+    }
+}
+
+impl Component for AppointmentForm {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(15),
+                Constraint::Length(2),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let title = match (&self.appointment, &self.id_input) {
+            (Some(a), _) => tf("appointments.book.change_title_id", &[("id", &a.id)]),
+            (None, Some(_)) => t("appointments.book.change_title").to_string(),
+            (None, None) => t("appointments.book.book_title").to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(title)
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+
+        if let Some(input) = &self.id_input {
+            self.render_id_prompt(frame, layout[1], input);
+            frame.render_widget(
+                Paragraph::new(t("appointments.book.load_help"))
+                    .style(Style::default().fg(theme().help))
+                    .alignment(Alignment::Center),
+                layout[6],
+            );
+            return;
+        }
+
+        let form_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3); INPUT_FIELDS])
+            .horizontal_margin(2)
+            .split(layout[1]);
+        for index in 0..INPUT_FIELDS {
+            self.form
+                .render_field(frame, index, form_layout[index], self.focus_index);
+        }
+
+        let mut chosen = Vec::new();
+        if let Some(p) = self.resolved_patient() {
+            chosen.push(tf(
+                "appointments.book.patient",
+                &[("first", &p.first_name), ("last", &p.last_name)],
+            ));
+        }
+        if let Some(d) = self.resolved_doctor() {
+            chosen.push(tf("appointments.book.doctor", &[("name", &d.name)]));
+        }
+        let mut lines = vec![Line::styled(
+            chosen.join("   ·   "),
+            Style::default().fg(theme().accent),
+        )];
+        lines.extend(self.doctor_day.iter().cloned());
+        let info_area = Rect {
+            y: layout[1].y + 3 * INPUT_FIELDS as u16,
+            height: layout[1].height.saturating_sub(3 * INPUT_FIELDS as u16),
+            ..layout[1]
+        };
+        frame.render_widget(
+            Paragraph::new(lines)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            info_area,
+        );
+
+        let action = if self.appointment.is_some() {
+            t("common.save")
+        } else {
+            t("appointments.book.book")
+        };
+        self.render_button(frame, layout[3], SUBMIT_BUTTON, action, theme().success);
+        self.render_button(
+            frame,
+            layout[4],
+            BACK_BUTTON,
+            t("common.back"),
+            theme().accent,
+        );
+
+        let help = if self.appointment.is_some() {
+            t("appointments.book.change_help")
+        } else {
+            t("appointments.book.help")
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[6],
+        );
+
+        if let Some(picker) = &self.date_picker {
+            picker.render_popup(frame, t("appointments.book.date_picker"));
+        }
+        if let Some(popup) = &self.new_patient {
+            popup.render_popup(frame);
+        }
+    }
+}
@@ -0,0 +1,588 @@
+use super::{span, status_text, AppointmentAction};
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::export_view::{self, ExportView, ShownRows};
+use crate::components::widgets::no_shows::{self, NoShowReport};
+use crate::components::widgets::text_input::TextInput;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{Appointment, AppointmentStatus};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+use std::time::Instant;
+use time::{Date, Duration};
+
+const HEADERS: [&str; 7] = [
+    "appointments.column.id",
+    "appointments.column.time",
+    "appointments.column.until",
+    "appointments.column.patient",
+    "appointments.column.doctor",
+    "appointments.column.reason",
+    "appointments.column.status",
+];
+
+/// How often appointments past the grace period are looked for while the
+/// list is open.
+const NO_SHOW_SWEEP: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// A day's appointments, with check-in, cancelling and marking them
+/// attended. Those nobody checked in to in time become no-shows.
+pub struct ListAppointments {
+    db: Db,
+    day: Date,
+    appointments: Vec<Appointment>,
+    patient_names: HashMap<i64, String>,
+    doctor_names: HashMap<i64, String>,
+    show_cancelled: bool,
+    state: TableState,
+    /// The reason being typed while cancelling the selected appointment.
+    cancel_reason: Option<TextInput>,
+    export_view: Option<ExportView>,
+    report: Option<NoShowReport>,
+    last_sweep: Instant,
+}
+
+impl ListAppointments {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            day: datetime::today(),
+            appointments: Vec::new(),
+            patient_names: HashMap::new(),
+            doctor_names: HashMap::new(),
+            show_cancelled: false,
+            state: TableState::default(),
+            cancel_reason: None,
+            export_view: None,
+            report: None,
+            last_sweep: Instant::now(),
+        }
+    }
+
+    pub fn fetch_appointments(&mut self) -> Result<()> {
+        self.last_sweep = Instant::now();
+        no_shows::mark_overdue(&self.db);
+        let day = datetime::format_date(self.day);
+        self.appointments = db::get_appointments_between(&self.db, &day, &day)?
+            .into_iter()
+            .filter(|a| self.show_cancelled || a.status != AppointmentStatus::Cancelled)
+            .collect();
+        self.patient_names = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, format!("{} {}", p.first_name, p.last_name)))
+            .collect();
+        self.doctor_names = db::get_all_staff(&self.db)?
+            .into_iter()
+            .map(|s| (s.id, s.name))
+            .collect();
+        let len = self.appointments.len();
+        match self.state.selected() {
+            _ if len == 0 => self.state.select(None),
+            Some(i) if i < len => {}
+            _ => self.state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    pub fn tick(&mut self) {
+        if self.last_sweep.elapsed() >= NO_SHOW_SWEEP {
+            self.last_sweep = Instant::now();
+            if no_shows::mark_overdue(&self.db) > 0 {
+                self.reload();
+            }
+        }
+    }
+
+    fn reload(&mut self) {
+        if let Err(e) = self.fetch_appointments() {
+            toast::push(Toast::error(tf(
+                "appointments.load_failed",
+                &[("error", &e)],
+            )));
+        }
+    }
+
+    fn selected_appointment(&self) -> Option<&Appointment> {
+        self.appointments.get(self.state.selected()?)
+    }
+
+    fn patient_name(&self, patient_id: i64) -> String {
+        self.patient_names
+            .get(&patient_id)
+            .cloned()
+            .unwrap_or_else(|| tf("common.patient_number", &[("id", &patient_id)]))
+    }
+
+    fn doctor_name(&self, doctor_id: i64) -> String {
+        self.doctor_names
+            .get(&doctor_id)
+            .map(|name| tf("common.doctor", &[("name", &name)]))
+            .unwrap_or_else(|| tf("common.staff_number", &[("id", &doctor_id)]))
+    }
+
+    fn row_values(&self, a: &Appointment) -> Vec<String> {
+        let until = span(&a.scheduled_at, a.duration_minutes.into())
+            .map(|(_, end)| format!("{:02}:{:02}", end.hour(), end.minute()))
+            .unwrap_or_default();
+        let status = match (&a.status, &a.cancel_reason, &a.checked_in_at) {
+            (AppointmentStatus::Cancelled, Some(reason), _) => {
+                tf("appointments.cancelled_because", &[("reason", &reason)])
+            }
+            (AppointmentStatus::Scheduled, _, Some(arrived)) => tf(
+                "appointments.checked_in_at",
+                &[("time", &datetime::time_part(arrived))],
+            ),
+            (status, _, _) => status_text(*status).to_string(),
+        };
+        vec![
+            a.id.to_string(),
+            datetime::time_part(&a.scheduled_at),
+            until,
+            self.patient_name(a.patient_id),
+            self.doctor_name(a.doctor_id),
+            a.reason.clone().unwrap_or_default(),
+            status,
+        ]
+    }
+
+    fn shown_rows(&self) -> ShownRows {
+        let mut shown = ShownRows::new(&HEADERS);
+        shown.rows = self
+            .appointments
+            .iter()
+            .map(|a| self.row_values(a))
+            .collect();
+        shown
+    }
+
+    fn move_day(&mut self, day: Date) {
+        self.day = day;
+        self.state.select(None);
+        self.reload();
+    }
+
+    fn cancel_selected(&mut self) -> Result<()> {
+        let Some(input) = self.cancel_reason.take() else {
+            return Ok(());
+        };
+        let Some(appointment) = self.selected_appointment().cloned() else {
+            return Ok(());
+        };
+        let reason = Some(input.value().trim()).filter(|r| !r.is_empty());
+        match db::cancel_appointment(&self.db, appointment.id, reason) {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "appointments.cancelled",
+                    &[
+                        ("patient", &self.patient_name(appointment.patient_id)),
+                        ("time", &datetime::time_part(&appointment.scheduled_at)),
+                    ],
+                )));
+                self.fetch_appointments()?;
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "appointments.cancel_failed",
+                &[("error", &e)],
+            ))),
+        }
+        Ok(())
+    }
+
+    fn toggle_check_in(&mut self) -> Result<()> {
+        let Some(appointment) = self.selected_appointment().cloned() else {
+            return Ok(());
+        };
+        if appointment.status != AppointmentStatus::Scheduled {
+            toast::push(Toast::error(t("appointments.only_scheduled_check_in")));
+            return Ok(());
+        }
+        let arrived = appointment.checked_in_at.is_none();
+        match db::check_in_appointment(&self.db, appointment.id, arrived) {
+            Ok(()) => {
+                let name = self.patient_name(appointment.patient_id);
+                toast::push(Toast::success(if arrived {
+                    tf("appointments.checked_in", &[("patient", &name)])
+                } else {
+                    tf("appointments.check_in_undone", &[("patient", &name)])
+                }));
+                self.fetch_appointments()?;
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "appointments.check_in_failed",
+                &[("error", &e)],
+            ))),
+        }
+        Ok(())
+    }
+
+    /// Marks the selected appointment attended, or back to scheduled if it
+    /// already was. A no-show who turns up late after all is attended.
+    fn toggle_attended(&mut self) -> Result<()> {
+        let Some(appointment) = self.selected_appointment().cloned() else {
+            return Ok(());
+        };
+        let status = match appointment.status {
+            AppointmentStatus::Scheduled | AppointmentStatus::NoShow => {
+                AppointmentStatus::Completed
+            }
+            AppointmentStatus::Completed => AppointmentStatus::Scheduled,
+            AppointmentStatus::Cancelled => {
+                toast::push(Toast::error(t("appointments.cancelled_not_attended")));
+                return Ok(());
+            }
+        };
+        match db::set_appointment_status(&self.db, appointment.id, status) {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "appointments.marked",
+                    &[
+                        ("patient", &self.patient_name(appointment.patient_id)),
+                        ("status", &status_text(status).to_lowercase()),
+                    ],
+                )));
+                self.fetch_appointments()?;
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "appointments.update_failed",
+                &[("error", &e)],
+            ))),
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<AppointmentAction>> {
+        if let Some(export) = &mut self.export_view {
+            if export.handle_key(key) {
+                self.export_view = None;
+            }
+            return Ok(None);
+        }
+        if let Some(report) = &mut self.report {
+            if report.handle_key(key) {
+                self.report = None;
+            }
+            return Ok(None);
+        }
+        if let Some(input) = &mut self.cancel_reason {
+            match key.code {
+                KeyCode::Enter => self.cancel_selected()?,
+                KeyCode::Esc => self.cancel_reason = None,
+                _ => {
+                    input.handle_key(key);
+                }
+            }
+            return Ok(None);
+        }
+        if export_view::is_open_key(key) {
+            self.export_view = Some(ExportView::new("appointments", self.shown_rows()));
+            return Ok(None);
+        }
+
+        let len = self.appointments.len();
+        match key.code {
+            KeyCode::Left => self.move_day(self.day - Duration::days(1)),
+            KeyCode::Right => self.move_day(self.day + Duration::days(1)),
+            KeyCode::Char('t') | KeyCode::Char('T') => self.move_day(datetime::today()),
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => return Ok(Some(AppointmentAction::Book)),
+            KeyCode::Char('e') | KeyCode::Char('E') | KeyCode::Enter => {
+                match self.selected_appointment() {
+                    Some(a) if a.status == AppointmentStatus::Scheduled => {
+                        return Ok(Some(AppointmentAction::Change(Box::new(a.clone()))));
+                    }
+                    Some(a) => toast::push(Toast::error(tf(
+                        "appointments.not_changeable",
+                        &[
+                            ("id", &a.id),
+                            ("status", &status_text(a.status).to_lowercase()),
+                        ],
+                    ))),
+                    None => {}
+                }
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') => match self.selected_appointment() {
+                Some(a) if a.status == AppointmentStatus::Scheduled => {
+                    self.cancel_reason = Some(TextInput::new());
+                }
+                Some(_) => toast::push(Toast::error(t("appointments.only_scheduled_cancel"))),
+                None => {}
+            },
+            KeyCode::Char('i') | KeyCode::Char('I') => self.toggle_check_in()?,
+            KeyCode::Char('d') | KeyCode::Char('D') => self.toggle_attended()?,
+            KeyCode::Char('n') | KeyCode::Char('N') => match NoShowReport::new(&self.db) {
+                Ok(report) => self.report = Some(report),
+                Err(e) => toast::push(Toast::error(tf(
+                    "appointments.no_shows_failed",
+                    &[("error", &e)],
+                ))),
+            },
+            KeyCode::Char('h') | KeyCode::Char('H') => {
+                self.show_cancelled = !self.show_cancelled;
+                self.reload();
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => self.reload(),
+            KeyCode::Esc => return Ok(Some(AppointmentAction::BackToHome)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_cancel_prompt(&self, frame: &mut Frame, input: &TextInput) {
+        let area = frame.area();
+        let width = 64.min(area.width);
+        let height = 5.min(area.height);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let title = self
+            .selected_appointment()
+            .map(|a| {
+                format!(
+                    " {} ",
+                    tf(
+                        "appointments.cancel_title",
+                        &[
+                            ("patient", &self.patient_name(a.patient_id)),
+                            ("time", &datetime::time_part(&a.scheduled_at))
+                        ]
+                    )
+                )
+            })
+            .unwrap_or_default();
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().warning))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+        frame.render_widget(
+            Paragraph::new(t("appointments.reason_optional"))
+                .style(Style::default().fg(theme().inactive)),
+            chunks[0],
+        );
+        frame.render_widget(
+            Paragraph::new(input.line(true)).style(Style::default().fg(theme().text)),
+            chunks[1],
+        );
+        frame.render_widget(
+            Paragraph::new(t("appointments.cancel_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[2],
+        );
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("appointments.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+
+        let today = if self.day == datetime::today() {
+            format!(" ({})", t("common.today"))
+        } else {
+            String::new()
+        };
+        frame.render_widget(
+            Paragraph::new(format!(
+                "◄ {} {}{} ►",
+                self.day.weekday(),
+                datetime::format_date(self.day),
+                today
+            ))
+            .style(Style::default().fg(theme().accent))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("common.day")))
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            ),
+            layout[1],
+        );
+
+        let count = |status| {
+            self.appointments
+                .iter()
+                .filter(|a| a.status == status)
+                .count()
+        };
+        let mut summary = vec![
+            Span::styled(
+                tf(
+                    "appointments.count.scheduled",
+                    &[("count", &count(AppointmentStatus::Scheduled))],
+                ),
+                Style::default().fg(theme().text),
+            ),
+            Span::raw("  ·  "),
+            Span::styled(
+                tf(
+                    "appointments.count.attended",
+                    &[("count", &count(AppointmentStatus::Completed))],
+                ),
+                Style::default().fg(theme().success),
+            ),
+            Span::raw("  ·  "),
+            Span::styled(
+                tf(
+                    "appointments.count.no_show",
+                    &[("count", &count(AppointmentStatus::NoShow))],
+                ),
+                Style::default().fg(theme().error),
+            ),
+        ];
+        if self.show_cancelled {
+            summary.push(Span::raw("  ·  "));
+            summary.push(Span::styled(
+                tf(
+                    "appointments.count.cancelled",
+                    &[("count", &count(AppointmentStatus::Cancelled))],
+                ),
+                Style::default().fg(theme().inactive),
+            ));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(summary)).alignment(Alignment::Center),
+            layout[2],
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("appointments.list_title")))
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.appointments.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("appointments.empty"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[3],
+            );
+        } else {
+            let widths = [
+                Constraint::Length(6),
+                Constraint::Length(6),
+                Constraint::Length(6),
+                Constraint::Percentage(22),
+                Constraint::Percentage(20),
+                Constraint::Min(12),
+                Constraint::Length(18),
+            ];
+            let columns = column_widths(block.inner(layout[3]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .appointments
+                .iter()
+                .map(|a| {
+                    let color = match a.status {
+                        AppointmentStatus::Scheduled => theme().text,
+                        AppointmentStatus::Completed => theme().success,
+                        AppointmentStatus::Cancelled => theme().inactive,
+                        AppointmentStatus::NoShow => theme().error,
+                    };
+                    Row::new(
+                        self.row_values(a)
+                            .iter()
+                            .enumerate()
+                            .map(|(column, text)| cell(text, &columns, column))
+                            .collect::<Vec<_>>(),
+                    )
+                    .style(Style::default().fg(color))
+                })
+                .collect();
+            let header = Row::new(HEADERS.map(t).to_vec())
+                .style(Style::default().bg(theme().header).fg(theme().title));
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(block)
+                .row_highlight_style(
+                    Style::default()
+                        .fg(theme().focus)
+                        .bg(theme().highlight)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[3], &mut self.state.clone());
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("appointments.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+
+        if let Some(input) = &self.cancel_reason {
+            self.render_cancel_prompt(frame, input);
+        }
+        if let Some(export) = &self.export_view {
+            export.render_popup(frame);
+        }
+        if let Some(report) = &self.report {
+            report.render_popup(frame);
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! In-person appointments with doctors: booking, the day's list, changing
+//! and cancelling them. A booking has to fall inside the doctor's assigned
+//! shifts and clear their other appointments and tele-consultations.
+
+use crate::app::SelectedApp;
+use crate::components::hospital::staff::shift_at;
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{
+    Appointment, AppointmentStatus, StaffMember, TeleconsultStatus, Teleconsultation,
+};
+use crate::tui::Frame;
+use crate::utils::{calendar, config, datetime};
+use anyhow::Result;
+use crossterm::event::KeyEvent;
+use time::{Duration, PrimitiveDateTime};
+
+pub mod book;
+pub mod list;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppointmentsState {
+    Book,
+    List,
+    Update,
+}
+
+/// What the appointment list asks of the module.
+pub enum AppointmentAction {
+    BackToHome,
+    Book,
+    Change(Box<Appointment>),
+}
+
+pub struct Appointments {
+    db: Db,
+    pub book: book::AppointmentForm,
+    pub list: list::ListAppointments,
+    pub update: book::AppointmentForm,
+    pub state: AppointmentsState,
+    /// Whether the form showing was opened from the list, which it goes
+    /// back to rather than home.
+    from_list: bool,
+}
+
+impl Appointments {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            book: book::AppointmentForm::new(db),
+            list: list::ListAppointments::new(db),
+            update: book::AppointmentForm::for_update(db),
+            state: AppointmentsState::List,
+            from_list: false,
+        }
+    }
+
+    pub fn initialize_list(&mut self) -> Result<()> {
+        match self.state {
+            AppointmentsState::Book => self.book.load_data()?,
+            AppointmentsState::List => self.list.fetch_appointments()?,
+            AppointmentsState::Update => self.update.load_data()?,
+        }
+        Ok(())
+    }
+
+    /// Leaves the booking or update form for the list or home screen.
+    fn close_form(&mut self) -> Result<Option<SelectedApp>> {
+        if !self.from_list {
+            return Ok(Some(SelectedApp::None));
+        }
+        self.from_list = false;
+        self.state = AppointmentsState::List;
+        self.list.fetch_appointments()?;
+        Ok(None)
+    }
+}
+
+pub fn status_text(status: AppointmentStatus) -> &'static str {
+    match status {
+        AppointmentStatus::Scheduled => t("appointments.status.scheduled"),
+        AppointmentStatus::Completed => t("appointments.status.attended"),
+        AppointmentStatus::Cancelled => t("appointments.status.cancelled"),
+        AppointmentStatus::NoShow => t("appointments.status.no_show"),
+    }
+}
+
+/// When `stamp` (stored) starts and ends `minutes` later, at the facility.
+pub fn span(stamp: &str, minutes: i64) -> Option<(PrimitiveDateTime, PrimitiveDateTime)> {
+    let start = datetime::parse_date_time(&datetime::facility_stamp(stamp))?;
+    Some((start, start + Duration::minutes(minutes.max(1))))
+}
+
+fn consultation_span(c: &Teleconsultation) -> Option<(PrimitiveDateTime, PrimitiveDateTime)> {
+    let minutes = c
+        .duration_minutes
+        .map_or(config::config().slot_minutes as i64, i64::from);
+    span(&c.scheduled_at, minutes)
+}
+
+/// Why `appointment` cannot go ahead as it stands: the facility is closed
+/// that day, the doctor is on leave or not rostered for all of it, or the
+/// doctor or the patient is already booked at that time. `None` when it
+/// can be booked.
+pub fn find_conflict(
+    db: &Db,
+    appointment: &Appointment,
+    doctor: &StaffMember,
+    patient_name: impl Fn(i64) -> String,
+) -> Result<Option<String>> {
+    let Some((start, end)) = span(
+        &appointment.scheduled_at,
+        appointment.duration_minutes.into(),
+    ) else {
+        return Ok(Some(t("appointments.bad_time").to_string()));
+    };
+    if let Some(reason) = calendar::closed_reason(start.date()) {
+        return Ok(Some(tf("appointments.closed_day", &[("reason", &reason)])));
+    }
+
+    let day = datetime::format_date(start.date());
+    if db::get_leave_between(db, &day, &day)?
+        .iter()
+        .any(|leave| leave.staff_id == doctor.id)
+    {
+        return Ok(Some(tf(
+            "appointments.doctor_on_leave",
+            &[("doctor", &doctor.name), ("day", &day)],
+        )));
+    }
+
+    let shifts = db::get_assigned_shifts_for_staff(db, doctor.id)?;
+    for at in [start, end - Duration::minutes(1)] {
+        let (date, shift) = shift_at(at);
+        if !shifts.iter().any(|(d, s)| *d == date && s == shift) {
+            return Ok(Some(tf(
+                "appointments.not_rostered",
+                &[
+                    ("doctor", &doctor.name),
+                    ("shift", &shift),
+                    ("day", &datetime::format_date(date)),
+                ],
+            )));
+        }
+    }
+
+    let overlaps = |(from, to): (PrimitiveDateTime, PrimitiveDateTime)| from < end && start < to;
+    let first = datetime::format_date(start.date() - Duration::days(1));
+    let last = datetime::format_date(end.date());
+    for other in db::get_appointments_between(db, &first, &last)? {
+        if other.id == appointment.id || other.status != AppointmentStatus::Scheduled {
+            continue;
+        }
+        let Some(other_span) = span(&other.scheduled_at, other.duration_minutes.into()) else {
+            continue;
+        };
+        if !overlaps(other_span) {
+            continue;
+        }
+        let time = other_span.0.time();
+        if other.doctor_id == doctor.id {
+            return Ok(Some(tf(
+                "appointments.doctor_busy",
+                &[
+                    ("doctor", &doctor.name),
+                    ("patient", &patient_name(other.patient_id)),
+                    ("hour", &format!("{:02}", time.hour())),
+                    ("minute", &format!("{:02}", time.minute())),
+                ],
+            )));
+        }
+        if other.patient_id == appointment.patient_id {
+            return Ok(Some(tf(
+                "appointments.patient_busy",
+                &[
+                    ("patient", &patient_name(other.patient_id)),
+                    ("hour", &format!("{:02}", time.hour())),
+                    ("minute", &format!("{:02}", time.minute())),
+                ],
+            )));
+        }
+    }
+
+    let mut date = start.date() - Duration::days(1);
+    while date <= end.date() {
+        for consultation in db::get_teleconsultations_for_day(db, &datetime::format_date(date))? {
+            if consultation.status != TeleconsultStatus::Scheduled
+                || (consultation.doctor_id != doctor.id
+                    && consultation.patient_id != appointment.patient_id)
+            {
+                continue;
+            }
+            let Some(other_span) = consultation_span(&consultation) else {
+                continue;
+            };
+            if overlaps(other_span) {
+                let time = other_span.0.time();
+                return Ok(Some(tf(
+                    "appointments.teleconsult_clash",
+                    &[
+                        (
+                            "who",
+                            &if consultation.doctor_id == doctor.id {
+                                tf("common.doctor", &[("name", &doctor.name)])
+                            } else {
+                                patient_name(consultation.patient_id)
+                            },
+                        ),
+                        ("hour", &format!("{:02}", time.hour())),
+                        ("minute", &format!("{:02}", time.minute())),
+                    ],
+                )));
+            }
+        }
+        date += Duration::days(1);
+    }
+    Ok(None)
+}
+
+impl Component for Appointments {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        match self.state {
+            AppointmentsState::Book => {
+                if let Some(SelectedApp::None) = self.book.handle_input(event)? {
+                    return self.close_form();
+                }
+            }
+            AppointmentsState::Update => {
+                if let Some(SelectedApp::None) = self.update.handle_input(event)? {
+                    return self.close_form();
+                }
+            }
+            AppointmentsState::List => match self.list.handle_input(event)? {
+                Some(AppointmentAction::BackToHome) => return Ok(Some(SelectedApp::None)),
+                Some(AppointmentAction::Book) => {
+                    self.book = book::AppointmentForm::new(&self.db);
+                    self.book.load_data()?;
+                    self.from_list = true;
+                    self.state = AppointmentsState::Book;
+                }
+                Some(AppointmentAction::Change(appointment)) => {
+                    self.update = book::AppointmentForm::for_update(&self.db);
+                    self.update.load_data()?;
+                    self.update.load_appointment(*appointment);
+                    self.from_list = true;
+                    self.state = AppointmentsState::Update;
+                }
+                None => {}
+            },
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        match self.state {
+            AppointmentsState::Book => self.book.render(frame),
+            AppointmentsState::List => self.list.render(frame),
+            AppointmentsState::Update => self.update.render(frame),
+        }
+    }
+
+    fn tick(&mut self) {
+        if self.state == AppointmentsState::List {
+            self.list.tick();
+        }
+    }
+}
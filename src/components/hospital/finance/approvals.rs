@@ -0,0 +1,378 @@
+//! Discounts beyond the limit under `[invoice]`, waiting for an
+//! administrator or accountant to approve or reject them, and the ones
+//! decided lately. Their invoices are held, and not billed, until then.
+
+use crate::app::SelectedApp;
+use crate::auth;
+use crate::components::table::{cell, column_widths, numeric_cell};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::export_view::{self, ExportView, ShownRows};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{ApprovalStatus, PriceOverride};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state;
+use crate::utils::{datetime, money};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+const HEADERS: [&str; 10] = [
+    "approvals.column.number",
+    "approvals.column.requested",
+    "approvals.column.by",
+    "approvals.column.patient",
+    "approvals.column.item",
+    "approvals.column.catalog",
+    "approvals.column.price",
+    "approvals.column.off",
+    "approvals.column.reason",
+    "approvals.column.decision",
+];
+/// Columns "Catalog" to "Off" hold amounts.
+const AMOUNT_COLUMNS: std::ops::RangeInclusive<usize> = 5..=7;
+/// How many decided requests the history shows.
+const HISTORY_LIMIT: i64 = 200;
+
+fn may_decide(db: &Db) -> bool {
+    ui_state::current_user().is_some_and(|user| auth::may_approve_discounts(db, &user))
+}
+
+fn decision(request: &PriceOverride) -> String {
+    let by = request.decided_by.as_deref().unwrap_or("?");
+    match request.status {
+        ApprovalStatus::Pending => t("approvals.pending").to_string(),
+        ApprovalStatus::Approved => tf("approvals.approved_by", &[("name", &by)]),
+        ApprovalStatus::Rejected => tf("approvals.rejected_by", &[("name", &by)]),
+    }
+}
+
+fn status_color(status: ApprovalStatus) -> Color {
+    match status {
+        ApprovalStatus::Pending => theme().warning,
+        ApprovalStatus::Approved => theme().success,
+        ApprovalStatus::Rejected => theme().error,
+    }
+}
+
+pub struct PriceApprovals {
+    db: Db,
+    requests: Vec<PriceOverride>,
+    /// Whether decided requests are shown instead of the waiting ones.
+    history: bool,
+    state: TableState,
+    confirm_reject: bool,
+    export_view: Option<ExportView>,
+}
+
+impl PriceApprovals {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            requests: Vec::new(),
+            history: false,
+            state: TableState::default(),
+            confirm_reject: false,
+            export_view: None,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.requests = if self.history {
+            db::get_decided_price_overrides(&self.db, HISTORY_LIMIT)?
+        } else {
+            db::get_pending_price_overrides(&self.db)?
+        };
+        let len = self.requests.len();
+        match self.state.selected() {
+            _ if len == 0 => self.state.select(None),
+            Some(i) if i < len => {}
+            _ => self.state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) {
+        if let Err(e) = self.load_data() {
+            toast::push(Toast::error(tf("approvals.load_failed", &[("error", &e)])));
+        }
+    }
+
+    fn selected(&self) -> Option<&PriceOverride> {
+        self.requests.get(self.state.selected()?)
+    }
+
+    fn row_values(request: &PriceOverride) -> Vec<String> {
+        vec![
+            request.invoice_id.to_string(),
+            datetime::facility_stamp(&request.requested_at),
+            request.requested_by.clone(),
+            request.patient_name.clone(),
+            request.item.clone(),
+            money(request.list_price),
+            money(request.requested_price),
+            format!("{:.1}%", request.discount_percent()),
+            request.reason.clone(),
+            decision(request),
+        ]
+    }
+
+    fn shown_rows(&self) -> ShownRows {
+        let mut shown = ShownRows::new(&HEADERS);
+        shown.rows = self.requests.iter().map(Self::row_values).collect();
+        shown
+    }
+
+    fn decide(&mut self, approve: bool) -> Result<()> {
+        self.confirm_reject = false;
+        let Some(request) = self.selected().cloned() else {
+            return Ok(());
+        };
+        let user = ui_state::current_user().unwrap_or_default();
+        if request.requested_by == user {
+            toast::push(Toast::error(t("approvals.own_request")));
+            return Ok(());
+        }
+        match db::decide_price_override(&self.db, request.id, approve, &user) {
+            Ok(()) if approve => toast::push(Toast::success(tf(
+                "approvals.approved",
+                &[
+                    ("invoice", &request.invoice_id),
+                    ("price", &money(request.requested_price)),
+                ],
+            ))),
+            Ok(()) => toast::push(Toast::success(tf(
+                "approvals.rejected",
+                &[
+                    ("invoice", &request.invoice_id),
+                    ("price", &money(request.list_price)),
+                ],
+            ))),
+            Err(e) => toast::push(Toast::error(tf(
+                "approvals.decide_failed",
+                &[("error", &e)],
+            ))),
+        }
+        self.load_data()
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(export) = &mut self.export_view {
+            if export.handle_key(key) {
+                self.export_view = None;
+            }
+            return Ok(None);
+        }
+        if export_view::is_open_key(key) {
+            self.export_view = Some(ExportView::new("price_approvals", self.shown_rows()));
+            return Ok(None);
+        }
+        if self.confirm_reject {
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => self.decide(false)?,
+                _ => self.confirm_reject = false,
+            }
+            return Ok(None);
+        }
+        let len = self.requests.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('a' | 'A' | 'x' | 'X') if self.history => {}
+            KeyCode::Char('a' | 'A' | 'x' | 'X') if !may_decide(&self.db) => {
+                toast::push(Toast::error(t("approvals.not_allowed")));
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') if self.selected().is_some() => {
+                self.decide(true)?
+            }
+            KeyCode::Char('x') | KeyCode::Char('X') if self.selected().is_some() => {
+                self.confirm_reject = true;
+            }
+            KeyCode::Char('h') | KeyCode::Char('H') => {
+                self.history = !self.history;
+                self.state.select(None);
+                self.reload();
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => self.reload(),
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_summary(&self, frame: &mut Frame, area: Rect) {
+        let discount: f64 = self
+            .requests
+            .iter()
+            .fold(0.0, |sum, r| sum + r.list_price - r.requested_price);
+        let text = if self.history {
+            let approved = self
+                .requests
+                .iter()
+                .filter(|r| r.status == ApprovalStatus::Approved)
+                .count();
+            tf(
+                "approvals.decided_summary",
+                &[
+                    ("count", &self.requests.len()),
+                    ("approved", &approved),
+                    ("rejected", &(self.requests.len() - approved)),
+                ],
+            )
+        } else {
+            tf(
+                "approvals.waiting_summary",
+                &[
+                    ("count", &self.requests.len()),
+                    ("amount", &money(discount)),
+                ],
+            )
+        };
+        frame.render_widget(
+            Paragraph::new(text)
+                .style(Style::default().fg(theme().text))
+                .alignment(Alignment::Center),
+            area,
+        );
+    }
+}
+
+impl Component for PriceApprovals {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("approvals.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        self.render_summary(frame, layout[1]);
+
+        let title = if self.history {
+            format!(" {} ", t("approvals.decided_title"))
+        } else {
+            format!(" {} ", t("approvals.waiting_title"))
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.requests.is_empty() {
+            let text = if self.history {
+                t("approvals.none_decided")
+            } else {
+                t("approvals.none_waiting")
+            };
+            frame.render_widget(
+                Paragraph::new(text)
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[2],
+            );
+        } else {
+            let widths = [
+                Constraint::Length(6),
+                Constraint::Length(17),
+                Constraint::Length(10),
+                Constraint::Length(16),
+                Constraint::Min(12),
+                Constraint::Length(11),
+                Constraint::Length(11),
+                Constraint::Length(7),
+                Constraint::Min(16),
+                Constraint::Length(20),
+            ];
+            let columns = column_widths(block.inner(layout[2]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .requests
+                .iter()
+                .map(|request| {
+                    let cells: Vec<Cell> = Self::row_values(request)
+                        .into_iter()
+                        .enumerate()
+                        .map(|(column, text)| match column {
+                            c if AMOUNT_COLUMNS.contains(&c) => numeric_cell(text, &columns, c),
+                            c if c == HEADERS.len() - 1 => cell(text, &columns, c)
+                                .style(Style::default().fg(status_color(request.status))),
+                            c => cell(text, &columns, c),
+                        })
+                        .collect();
+                    Row::new(cells).style(Style::default().fg(theme().text))
+                })
+                .collect();
+            let header = Row::new(HEADERS.map(t).to_vec())
+                .style(Style::default().bg(theme().header).fg(theme().title));
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(block)
+                .row_highlight_style(
+                    Style::default()
+                        .fg(theme().focus)
+                        .bg(theme().highlight)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
+        }
+
+        let (help, color) = if self.confirm_reject {
+            (t("approvals.reject_confirm"), theme().warning)
+        } else if self.history {
+            (t("approvals.history_help"), theme().help)
+        } else {
+            (t("approvals.help"), theme().help)
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(color))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+
+        if let Some(export) = &self.export_view {
+            export.render_popup(frame);
+        }
+    }
+}
@@ -0,0 +1,766 @@
+//! Monthly budgets per department, and optionally per category, against
+//! what was spent and invoiced. Anyone can read the comparison; only
+//! administrators set the budgets.
+
+use super::expenses::{month_range, step_month};
+use crate::app::SelectedApp;
+use crate::auth;
+use crate::components::form::{self, number_range, Field, Form};
+use crate::components::table::{cell, column_widths, numeric_cell};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::export_view::{self, ExportView, ShownRows};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{Budget, Expense, EXPENSE_CATEGORIES};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state;
+use crate::utils::{datetime, money, signed_money};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::BTreeMap;
+use time::Date;
+
+const HEADERS: [&str; 7] = [
+    "budget.column.department",
+    "budget.column.category",
+    "budget.column.budget",
+    "budget.column.spent",
+    "budget.column.variance",
+    "budget.column.used",
+    "budget.column.status",
+];
+
+/// Spending at or above this share of its budget is flagged before it
+/// goes over.
+const NEAR_LIMIT: f64 = 0.9;
+
+const DEPARTMENT: usize = 0;
+const CATEGORY: usize = 1;
+const AMOUNT: usize = 2;
+
+fn budget_form() -> Form {
+    Form::new(vec![
+        Field::new(t("budget.department")).required(),
+        Field::new(t("budget.category_field")).validate_with(form::one_of(&EXPENSE_CATEGORIES)),
+        Field::new(t("budget.amount"))
+            .required()
+            .validate_with(number_range(0.01, 1_000_000_000.0)),
+    ])
+}
+
+fn may_set_budgets(db: &Db) -> bool {
+    ui_state::current_user().is_some_and(|user| auth::is_admin(db, &user))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Under,
+    Near,
+    Over,
+    Unbudgeted,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Under => t("budget.within"),
+            Status::Near => t("budget.near"),
+            Status::Over => t("budget.over"),
+            Status::Unbudgeted => t("budget.unbudgeted"),
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Status::Under => theme().success,
+            Status::Near | Status::Unbudgeted => theme().warning,
+            Status::Over => theme().error,
+        }
+    }
+}
+
+/// One row of the comparison: a budget and what was spent against it, or
+/// spending no budget covers.
+struct BudgetLine {
+    budget: Option<Budget>,
+    department: String,
+    category: Option<String>,
+    spent: f64,
+}
+
+impl BudgetLine {
+    fn budgeted(&self) -> f64 {
+        self.budget.as_ref().map_or(0.0, |b| b.amount)
+    }
+
+    fn variance(&self) -> f64 {
+        self.budgeted() - self.spent
+    }
+
+    fn status(&self) -> Status {
+        match &self.budget {
+            None => Status::Unbudgeted,
+            Some(b) if self.spent > b.amount => Status::Over,
+            Some(b) if self.spent >= b.amount * NEAR_LIMIT => Status::Near,
+            Some(_) => Status::Under,
+        }
+    }
+
+    fn used(&self) -> String {
+        match &self.budget {
+            Some(b) if b.amount > 0.0 => format!("{:.0}%", self.spent / b.amount * 100.0),
+            _ => "-".to_string(),
+        }
+    }
+}
+
+fn covers(budget: &Budget, expense: &Expense) -> bool {
+    budget.department.eq_ignore_ascii_case(&expense.department)
+        && budget
+            .category
+            .as_ref()
+            .is_none_or(|category| category == &expense.category)
+}
+
+/// The budgets with their spending, followed by spending no budget covers
+/// grouped by department and category.
+fn budget_lines(budgets: &[Budget], expenses: &[Expense]) -> Vec<BudgetLine> {
+    let mut lines: Vec<BudgetLine> = budgets
+        .iter()
+        .map(|b| BudgetLine {
+            budget: Some(b.clone()),
+            department: b.department.clone(),
+            category: b.category.clone(),
+            spent: expenses
+                .iter()
+                .filter(|e| covers(b, e))
+                .map(|e| e.amount)
+                .sum(),
+        })
+        .collect();
+    let mut unbudgeted: BTreeMap<(&str, &str), f64> = BTreeMap::new();
+    for expense in expenses {
+        if !budgets.iter().any(|b| covers(b, expense)) {
+            *unbudgeted
+                .entry((expense.department.as_str(), expense.category.as_str()))
+                .or_default() += expense.amount;
+        }
+    }
+    lines.extend(
+        unbudgeted
+            .into_iter()
+            .map(|((department, category), spent)| BudgetLine {
+                budget: None,
+                department: department.to_string(),
+                category: Some(category.to_string()),
+                spent,
+            }),
+    );
+    lines
+}
+
+/// The month's budget in total: a department's whole-department budget
+/// where it has one, otherwise the sum of its category budgets.
+fn total_budgeted(budgets: &[Budget]) -> f64 {
+    let mut departments: BTreeMap<String, (Option<f64>, f64)> = BTreeMap::new();
+    for b in budgets {
+        let entry = departments.entry(b.department.to_lowercase()).or_default();
+        match b.category {
+            None => entry.0 = Some(b.amount),
+            Some(_) => entry.1 += b.amount,
+        }
+    }
+    departments
+        .values()
+        .map(|(whole, categories)| whole.unwrap_or(*categories))
+        .sum()
+}
+
+struct Popup {
+    /// The budget being changed; a new one when `None`.
+    budget_id: Option<i64>,
+    form: Form,
+    focus_index: usize,
+}
+
+pub struct BudgetReport {
+    db: Db,
+    /// First day of the month shown.
+    month: Date,
+    budgets: Vec<Budget>,
+    expenses: Vec<Expense>,
+    invoiced: f64,
+    /// Departments named on budgets or expenses, for matching their spelling.
+    departments: Vec<String>,
+    state: TableState,
+    popup: Option<Popup>,
+    confirm_delete: bool,
+    export_view: Option<ExportView>,
+}
+
+impl BudgetReport {
+    pub fn new(db: &Db) -> Self {
+        let today = datetime::today();
+        Self {
+            db: db.clone(),
+            month: today.replace_day(1).unwrap_or(today),
+            budgets: Vec::new(),
+            expenses: Vec::new(),
+            invoiced: 0.0,
+            departments: Vec::new(),
+            state: TableState::default(),
+            popup: None,
+            confirm_delete: false,
+            export_view: None,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        let (from, to) = month_range(self.month);
+        self.budgets = db::get_budgets(&self.db, &datetime::format_month(self.month))?;
+        self.expenses = db::get_expenses_between(&self.db, &from, &to)?;
+        self.invoiced = db::get_invoiced_between(&self.db, &from, &to)?;
+        self.departments = db::get_expense_departments(&self.db)?;
+        for budget in &self.budgets {
+            if !self
+                .departments
+                .iter()
+                .any(|d| d.eq_ignore_ascii_case(&budget.department))
+            {
+                self.departments.push(budget.department.clone());
+            }
+        }
+        let len = self.lines().len();
+        match self.state.selected() {
+            _ if len == 0 => self.state.select(None),
+            Some(i) if i < len => {}
+            _ => self.state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) {
+        if let Err(e) = self.load_data() {
+            toast::push(Toast::error(tf("budget.load_failed", &[("error", &e)])));
+        }
+    }
+
+    fn lines(&self) -> Vec<BudgetLine> {
+        budget_lines(&self.budgets, &self.expenses)
+    }
+
+    fn selected_line(&self) -> Option<BudgetLine> {
+        self.lines().into_iter().nth(self.state.selected()?)
+    }
+
+    fn row_values(line: &BudgetLine) -> Vec<String> {
+        vec![
+            line.department.clone(),
+            line.category
+                .clone()
+                .unwrap_or_else(|| t("budget.all").to_string()),
+            line.budget
+                .as_ref()
+                .map_or_else(|| "-".to_string(), |b| money(b.amount)),
+            money(line.spent),
+            signed_money(line.variance()),
+            line.used(),
+            line.status().label().to_string(),
+        ]
+    }
+
+    fn shown_rows(&self) -> ShownRows {
+        let mut shown = ShownRows::new(&HEADERS);
+        shown.rows = self.lines().iter().map(Self::row_values).collect();
+        shown
+    }
+
+    /// Opens the form for `line`'s budget, or for a new budget prefilled
+    /// with `line`'s department and category when it has none.
+    fn open_popup(&mut self, line: Option<BudgetLine>) {
+        let mut form = budget_form();
+        if let Some(line) = &line {
+            form.fields[DEPARTMENT].set(line.department.clone());
+            form.fields[CATEGORY].set(line.category.clone().unwrap_or_default());
+            match &line.budget {
+                Some(b) => form.fields[AMOUNT].set(format!("{:.2}", b.amount)),
+                None => form.fields[AMOUNT].set(format!("{:.2}", line.spent)),
+            }
+        }
+        let budget_id = line.and_then(|l| l.budget).map(|b| b.id);
+        self.popup = Some(Popup {
+            budget_id,
+            form,
+            focus_index: if budget_id.is_some() {
+                AMOUNT
+            } else {
+                DEPARTMENT
+            },
+        });
+    }
+
+    fn save_popup(&mut self) -> Result<()> {
+        let Some(mut popup) = self.popup.take() else {
+            return Ok(());
+        };
+        let typed = popup.form.value(CATEGORY);
+        if let Some(category) = EXPENSE_CATEGORIES
+            .iter()
+            .find(|c| c.eq_ignore_ascii_case(&typed))
+        {
+            popup.form.fields[CATEGORY].set(*category);
+        }
+        if let Some(invalid) = popup.form.validate() {
+            popup.focus_index = invalid;
+            self.popup = Some(popup);
+            return Ok(());
+        }
+
+        let form = &popup.form;
+        let typed = form.value(DEPARTMENT);
+        let department = self
+            .departments
+            .iter()
+            .find(|d| d.eq_ignore_ascii_case(&typed))
+            .cloned()
+            .unwrap_or(typed);
+        let budget = Budget {
+            id: popup.budget_id.unwrap_or_default(),
+            month: datetime::format_month(self.month),
+            department,
+            category: form.optional_value(CATEGORY),
+            amount: form.value(AMOUNT).parse().unwrap_or_default(),
+            set_by: ui_state::current_user(),
+        };
+        match db::save_budget(&self.db, &budget) {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "budget.set",
+                    &[
+                        ("amount", &money(budget.amount)),
+                        ("department", &budget.department),
+                        (
+                            "category",
+                            &budget
+                                .category
+                                .as_deref()
+                                .unwrap_or(t("budget.all_categories")),
+                        ),
+                    ],
+                )));
+                self.load_data()?;
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("budget.save_failed", &[("error", &e)])));
+                self.popup = Some(popup);
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_selected(&mut self) -> Result<()> {
+        self.confirm_delete = false;
+        let Some(budget) = self.selected_line().and_then(|l| l.budget) else {
+            return Ok(());
+        };
+        match db::delete_budget(&self.db, budget.id) {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "budget.removed",
+                    &[("department", &budget.department)],
+                )));
+                self.load_data()?;
+            }
+            Err(e) => toast::push(Toast::error(tf("budget.delete_failed", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    /// Starts this month from last month's budgets.
+    fn copy_previous(&mut self) -> Result<()> {
+        let previous = datetime::format_month(step_month(self.month, false));
+        let current = datetime::format_month(self.month);
+        let user = ui_state::current_user();
+        match db::copy_budgets(&self.db, &previous, &current, user.as_deref()) {
+            Ok(0) => toast::push(Toast::error(tf(
+                "budget.nothing_to_copy",
+                &[("month", &previous)],
+            ))),
+            Ok(copied) => {
+                toast::push(Toast::success(tf(
+                    "budget.copied",
+                    &[("count", &copied), ("month", &previous)],
+                )));
+                self.load_data()?;
+            }
+            Err(e) => toast::push(Toast::error(tf("budget.copy_failed", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    fn handle_popup_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(popup) = self.popup.as_mut() else {
+            return Ok(());
+        };
+        let len = popup.form.len();
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Tab | KeyCode::Down => popup.focus_index = (popup.focus_index + 1) % len,
+            KeyCode::BackTab | KeyCode::Up => {
+                popup.focus_index = (popup.focus_index + len - 1) % len
+            }
+            KeyCode::Enter if popup.focus_index + 1 < len => popup.focus_index += 1,
+            KeyCode::Enter => self.save_popup()?,
+            _ => {
+                popup.form.handle_key(popup.focus_index, key);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if self.popup.is_some() {
+            self.handle_popup_input(key)?;
+            return Ok(None);
+        }
+        if let Some(export) = &mut self.export_view {
+            if export.handle_key(key) {
+                self.export_view = None;
+            }
+            return Ok(None);
+        }
+        if self.confirm_delete {
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.delete_selected()?
+                }
+                _ => self.confirm_delete = false,
+            }
+            return Ok(None);
+        }
+        if export_view::is_open_key(key) {
+            self.export_view = Some(ExportView::new("budget", self.shown_rows()));
+            return Ok(None);
+        }
+        let changes = matches!(
+            key.code,
+            KeyCode::Char('a' | 'A' | 'e' | 'E' | 'x' | 'X' | 'c' | 'C') | KeyCode::Enter
+        );
+        if changes && !may_set_budgets(&self.db) {
+            toast::push(Toast::error(t("budget.admins_only")));
+            return Ok(None);
+        }
+        let len = self.lines().len();
+        match key.code {
+            KeyCode::Left => {
+                self.month = step_month(self.month, false);
+                self.reload();
+            }
+            KeyCode::Right => {
+                self.month = step_month(self.month, true);
+                self.reload();
+            }
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => self.open_popup(None),
+            KeyCode::Char('e') | KeyCode::Char('E') | KeyCode::Enter => {
+                if let Some(line) = self.selected_line() {
+                    self.open_popup(Some(line));
+                }
+            }
+            KeyCode::Char('x') | KeyCode::Char('X')
+                if self.selected_line().is_some_and(|l| l.budget.is_some()) =>
+            {
+                self.confirm_delete = true;
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') => self.copy_previous()?,
+            KeyCode::Char('r') | KeyCode::Char('R') => self.reload(),
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_summary(&self, frame: &mut Frame, area: Rect) {
+        let budgeted = total_budgeted(&self.budgets);
+        let spent: f64 = self.expenses.iter().map(|e| e.amount).sum();
+        let variance = budgeted - spent;
+        let net = self.invoiced - spent;
+        let figure = |label: &str, amount: f64, color: Color| {
+            vec![
+                Span::styled(format!("{} ", label), Style::default().fg(theme().inactive)),
+                Span::styled(
+                    signed_money(amount),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+            ]
+        };
+        let sign_color = |amount: f64| {
+            if amount < 0.0 {
+                theme().error
+            } else {
+                theme().success
+            }
+        };
+        let mut spans = figure(t("budget.revenue"), self.invoiced, theme().success);
+        spans.push(Span::raw("   ·   "));
+        spans.extend(figure(t("budget.budget"), budgeted, theme().text));
+        spans.push(Span::raw("   ·   "));
+        spans.extend(figure(t("budget.spent"), spent, theme().warning));
+        spans.push(Span::raw("   ·   "));
+        spans.extend(figure(t("budget.variance"), variance, sign_color(variance)));
+        spans.push(Span::raw("   ·   "));
+        spans.extend(figure(t("budget.net"), net, sign_color(net)));
+        frame.render_widget(
+            Paragraph::new(Line::from(spans))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("budget.this_month")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            area,
+        );
+    }
+
+    fn render_lines(&self, frame: &mut Frame, area: Rect) {
+        let lines = self.lines();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("budget.vs_actual")))
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if lines.is_empty() {
+            let message = if may_set_budgets(&self.db) {
+                t("budget.empty_admin")
+            } else {
+                t("budget.empty")
+            };
+            frame.render_widget(
+                Paragraph::new(message)
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        }
+
+        let widths = [
+            Constraint::Percentage(22),
+            Constraint::Length(12),
+            Constraint::Length(14),
+            Constraint::Length(14),
+            Constraint::Length(14),
+            Constraint::Length(6),
+            Constraint::Min(14),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+        let rows: Vec<Row> = lines
+            .iter()
+            .map(|line| {
+                let status = line.status();
+                let values = Self::row_values(line);
+                Row::new(
+                    values
+                        .iter()
+                        .enumerate()
+                        .map(|(column, text)| match column {
+                            2 | 3 | 5 => numeric_cell(text, &columns, column),
+                            4 => numeric_cell(text, &columns, column)
+                                .style(Style::default().fg(status.color())),
+                            6 => cell(text, &columns, column)
+                                .style(Style::default().fg(status.color())),
+                            _ => cell(text, &columns, column),
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .style(Style::default().fg(if line.budget.is_some() {
+                    theme().text
+                } else {
+                    theme().inactive
+                }))
+            })
+            .collect();
+        let header = Row::new(HEADERS.map(t).to_vec())
+            .style(Style::default().bg(theme().header).fg(theme().title));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.state.clone());
+    }
+
+    fn render_popup(&self, frame: &mut Frame, popup: &Popup) {
+        let area = frame.area();
+        let height = (popup.form.len() as u16 * 3 + 4).min(area.height);
+        let width = 70.min(area.width);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let month = datetime::format_month(self.month);
+        let title = format!(
+            " {} ",
+            if popup.budget_id.is_some() {
+                tf("budget.change_title", &[("month", &month)])
+            } else {
+                tf("budget.set_title", &[("month", &month)])
+            }
+        );
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let mut constraints = vec![Constraint::Length(3); popup.form.len()];
+        constraints.push(Constraint::Length(1));
+        constraints.push(Constraint::Min(1));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .horizontal_margin(1)
+            .split(inner);
+        for index in 0..popup.form.len() {
+            popup
+                .form
+                .render_field(frame, index, chunks[index], popup.focus_index);
+        }
+        let hint = if popup.focus_index == CATEGORY {
+            tf(
+                "budget.categories",
+                &[("list", &(EXPENSE_CATEGORIES.join(", ")))],
+            )
+        } else if self.departments.is_empty() {
+            String::new()
+        } else {
+            tf(
+                "budget.departments",
+                &[("list", &(self.departments.join(", ")))],
+            )
+        };
+        frame.render_widget(
+            Paragraph::new(hint).style(Style::default().fg(theme().inactive)),
+            chunks[popup.form.len()],
+        );
+        frame.render_widget(
+            Paragraph::new(t("common.form_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[popup.form.len() + 1],
+        );
+    }
+}
+
+impl Component for BudgetReport {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("budget.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+
+        frame.render_widget(
+            Paragraph::new(format!("◄ {} ►", datetime::format_month(self.month)))
+                .style(Style::default().fg(theme().accent))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("common.month")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[1],
+        );
+
+        self.render_summary(frame, layout[2]);
+        self.render_lines(frame, layout[3]);
+
+        let help = if self.confirm_delete {
+            t("budget.remove_confirm")
+        } else if may_set_budgets(&self.db) {
+            t("budget.admin_help")
+        } else {
+            t("budget.help")
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(if self.confirm_delete {
+                    theme().warning
+                } else {
+                    theme().help
+                }))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+
+        if let Some(popup) = &self.popup {
+            self.render_popup(frame, popup);
+        }
+        if let Some(export) = &self.export_view {
+            export.render_popup(frame);
+        }
+    }
+}
@@ -0,0 +1,590 @@
+//! The cash drawer: a cashier opens a session with a float, takes payments
+//! and closes it by counting the cash, which is checked against the float
+//! plus the cash taken.
+
+use crate::app::SelectedApp;
+use crate::components::form::{self, integer_range, number_range, Field, Form};
+use crate::components::table::{cell, column_widths, numeric_cell};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{CashierSession, Payment, PaymentMethod};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state;
+use crate::utils::{datetime, money, signed_money};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+
+const HEADERS: [&str; 5] = [
+    "cashier.column.time",
+    "cashier.column.patient",
+    "cashier.column.method",
+    "cashier.column.amount",
+    "cashier.column.reference",
+];
+const AMOUNT_COLUMN: usize = 3;
+
+const PATIENT: usize = 0;
+const AMOUNT: usize = 1;
+const METHOD: usize = 2;
+const REFERENCE: usize = 3;
+
+const COUNTED: usize = 0;
+const CLOSE_NOTE: usize = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PopupKind {
+    Open,
+    Payment,
+    Close,
+}
+
+struct Popup {
+    kind: PopupKind,
+    form: Form,
+    focus_index: usize,
+    /// The balance of the patient typed in the payment form.
+    balance: String,
+}
+
+impl Popup {
+    fn new(kind: PopupKind) -> Self {
+        let mut form = match kind {
+            PopupKind::Open => Form::new(vec![Field::new(t("cashier.opening_float"))
+                .required()
+                .validate_with(number_range(0.0, 1_000_000.0))]),
+            PopupKind::Payment => Form::new(vec![
+                Field::new(t("common.patient_id"))
+                    .required()
+                    .validate_with(integer_range(1, i64::MAX)),
+                Field::new(t("cashier.amount"))
+                    .required()
+                    .validate_with(number_range(0.01, 1_000_000_000.0)),
+                Field::new(t("cashier.method"))
+                    .required()
+                    .validate_with(form::one_of(&PaymentMethod::LABELS)),
+                Field::new(t("cashier.reference")),
+            ]),
+            PopupKind::Close => Form::new(vec![
+                Field::new(t("cashier.counted"))
+                    .required()
+                    .validate_with(number_range(0.0, 1_000_000_000.0)),
+                Field::new(t("cashier.note")),
+            ]),
+        };
+        if kind == PopupKind::Payment {
+            form.fields[METHOD].set(PaymentMethod::Cash.label());
+        }
+        Self {
+            kind,
+            form,
+            focus_index: 0,
+            balance: String::new(),
+        }
+    }
+}
+
+pub struct CashDrawer {
+    db: Db,
+    /// The logged-in user's open session.
+    session: Option<CashierSession>,
+    payments: Vec<Payment>,
+    patient_names: HashMap<i64, String>,
+    state: TableState,
+    popup: Option<Popup>,
+}
+
+impl CashDrawer {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            session: None,
+            payments: Vec::new(),
+            patient_names: HashMap::new(),
+            state: TableState::default(),
+            popup: None,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.session = match ui_state::current_user() {
+            Some(user) => db::get_open_cashier_session(&self.db, &user)?,
+            None => None,
+        };
+        self.payments = match &self.session {
+            Some(session) => db::get_session_payments(&self.db, session.id)?,
+            None => Vec::new(),
+        };
+        self.patient_names = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, format!("{} {}", p.first_name, p.last_name)))
+            .collect();
+        let len = self.payments.len();
+        self.state.select(len.checked_sub(1));
+        Ok(())
+    }
+
+    fn reload(&mut self) {
+        if let Err(e) = self.load_data() {
+            toast::push(Toast::error(tf("cashier.load_failed", &[("error", &e)])));
+        }
+    }
+
+    fn patient_name(&self, patient_id: i64) -> String {
+        self.patient_names
+            .get(&patient_id)
+            .cloned()
+            .unwrap_or_else(|| tf("common.patient_number", &[("id", &patient_id)]))
+    }
+
+    /// What the patient `typed` in the payment form owes, for the hint
+    /// under the form.
+    fn balance_hint(&self, typed: &str) -> String {
+        let Ok(patient_id) = typed.parse::<i64>() else {
+            return String::new();
+        };
+        if !self.patient_names.contains_key(&patient_id) {
+            return t("cashier.no_patient").to_string();
+        }
+        match db::get_patient_balance(&self.db, patient_id) {
+            Ok((invoiced, paid)) => tf(
+                "cashier.balance",
+                &[
+                    ("patient", &self.patient_name(patient_id)),
+                    ("invoiced", &money(invoiced)),
+                    ("paid", &money(paid)),
+                    ("balance", &(money(invoiced - paid))),
+                ],
+            ),
+            Err(e) => tf("cashier.balance_failed", &[("error", &e)]),
+        }
+    }
+
+    fn save_popup(&mut self) -> Result<()> {
+        let Some(mut popup) = self.popup.take() else {
+            return Ok(());
+        };
+        if popup.kind == PopupKind::Payment {
+            let typed = popup.form.value(METHOD);
+            if let Some(method) = PaymentMethod::parse(&typed) {
+                popup.form.fields[METHOD].set(method.label());
+            }
+        }
+        if let Some(invalid) = popup.form.validate() {
+            popup.focus_index = invalid;
+            self.popup = Some(popup);
+            return Ok(());
+        }
+        let Some(user) = ui_state::current_user() else {
+            toast::push(Toast::error(t("cashier.log_in")));
+            return Ok(());
+        };
+
+        let form = &popup.form;
+        let amount = |index| form.value(index).parse::<f64>().unwrap_or_default();
+        let result = match popup.kind {
+            PopupKind::Open => db::open_cashier_session(&self.db, &user, amount(0)).map(|id| {
+                tf(
+                    "cashier.opened",
+                    &[("id", &id), ("amount", &money(amount(0)))],
+                )
+            }),
+            PopupKind::Payment => self.take_payment(form, &user),
+            PopupKind::Close => match &self.session {
+                Some(session) => {
+                    let counted = amount(COUNTED);
+                    let note = form.optional_value(CLOSE_NOTE);
+                    db::close_cashier_session(&self.db, session.id, counted, note.as_deref()).map(
+                        |()| {
+                            let variance = counted - session.expected_cash();
+                            tf(
+                                "cashier.closed",
+                                &[
+                                    ("id", &session.id),
+                                    ("expected", &money(session.expected_cash())),
+                                    ("counted", &money(counted)),
+                                    ("variance", &signed_money(variance)),
+                                ],
+                            )
+                        },
+                    )
+                }
+                None => Err(anyhow::anyhow!(t("cashier.no_session"))),
+            },
+        };
+        match result {
+            Ok(message) => {
+                toast::push(Toast::success(message));
+                self.load_data()?;
+            }
+            Err(e) => {
+                toast::push(Toast::error(format!("{}", e)));
+                self.popup = Some(popup);
+            }
+        }
+        Ok(())
+    }
+
+    fn take_payment(&self, form: &Form, user: &str) -> Result<String> {
+        let patient_id: i64 = form.value(PATIENT).parse().unwrap_or_default();
+        if !self.patient_names.contains_key(&patient_id) {
+            return Err(anyhow::anyhow!(t("common.unknown_patient")));
+        }
+        let method = PaymentMethod::parse(&form.value(METHOD)).unwrap_or(PaymentMethod::Cash);
+        if method == PaymentMethod::Cash && self.session.is_none() {
+            return Err(anyhow::anyhow!(t("cashier.open_first")));
+        }
+        let payment = Payment {
+            id: 0,
+            patient_id,
+            amount: form.value(AMOUNT).parse().unwrap_or_default(),
+            method,
+            paid_at: String::new(),
+            session_id: self.session.as_ref().map(|s| s.id),
+            received_by: Some(user.to_string()),
+            reference: form.optional_value(REFERENCE),
+        };
+        let id = db::create_payment(&self.db, &payment)?;
+        Ok(tf(
+            "cashier.received",
+            &[
+                ("id", &id),
+                ("amount", &money(payment.amount)),
+                ("method", &payment.method.label().to_lowercase()),
+                ("patient", &self.patient_name(patient_id)),
+            ],
+        ))
+    }
+
+    fn handle_popup_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(popup) = self.popup.as_mut() else {
+            return Ok(());
+        };
+        let len = popup.form.len();
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Tab | KeyCode::Down => popup.focus_index = (popup.focus_index + 1) % len,
+            KeyCode::BackTab | KeyCode::Up => {
+                popup.focus_index = (popup.focus_index + len - 1) % len
+            }
+            KeyCode::Enter if popup.focus_index + 1 < len => popup.focus_index += 1,
+            KeyCode::Enter => self.save_popup()?,
+            _ => {
+                if popup.form.handle_key(popup.focus_index, key)
+                    && popup.kind == PopupKind::Payment
+                    && popup.focus_index == PATIENT
+                {
+                    let typed = popup.form.value(PATIENT);
+                    let balance = self.balance_hint(&typed);
+                    if let Some(popup) = self.popup.as_mut() {
+                        popup.balance = balance;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if self.popup.is_some() {
+            self.handle_popup_input(key)?;
+            return Ok(None);
+        }
+        let len = self.payments.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('o') | KeyCode::Char('O') => match &self.session {
+                Some(session) => toast::push(Toast::error(tf(
+                    "cashier.already_open",
+                    &[("id", &session.id)],
+                ))),
+                None => self.popup = Some(Popup::new(PopupKind::Open)),
+            },
+            KeyCode::Char('p') | KeyCode::Char('P') => {
+                let mut popup = Popup::new(PopupKind::Payment);
+                if self.session.is_none() {
+                    popup.form.fields[METHOD].set("Card");
+                }
+                self.popup = Some(popup);
+            }
+            KeyCode::Char('x') | KeyCode::Char('X') => {
+                if self.session.is_some() {
+                    self.popup = Some(Popup::new(PopupKind::Close));
+                } else {
+                    toast::push(Toast::error(t("cashier.no_session")));
+                }
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => self.reload(),
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_session(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("cashier.session_title")))
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        let Some(session) = &self.session else {
+            frame.render_widget(
+                Paragraph::new(vec![
+                    Line::styled(
+                        t("cashier.none_open"),
+                        Style::default()
+                            .fg(theme().warning)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Line::styled(
+                        t("cashier.open_hint"),
+                        Style::default().fg(theme().inactive),
+                    ),
+                ])
+                .alignment(Alignment::Center)
+                .block(block),
+                area,
+            );
+            return;
+        };
+        let label = |text: String| Span::styled(text, Style::default().fg(theme().inactive));
+        let value = |text: String| Span::styled(text, Style::default().fg(theme().text));
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(vec![
+                    Span::styled(
+                        tf("cashier.session", &[("id", &session.id)]),
+                        Style::default()
+                            .fg(theme().success)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    label(format!("  {} ", t("cashier.opened_label"))),
+                    value(datetime::facility_stamp(&session.opened_at)),
+                    label(format!(" {} ", t("cashier.by"))),
+                    value(session.cashier.clone()),
+                ]),
+                Line::from(vec![
+                    label(format!("{} ", t("cashier.float"))),
+                    value(money(session.opening_float)),
+                    label(format!("  {} ", t("cashier.cash_taken"))),
+                    value(money(session.cash_taken)),
+                    label(format!("  {} ", t("cashier.expected"))),
+                    Span::styled(
+                        money(session.expected_cash()),
+                        Style::default()
+                            .fg(theme().accent)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    label(format!("  {} ", t("cashier.other"))),
+                    value(money(session.other_taken)),
+                ]),
+            ])
+            .alignment(Alignment::Center)
+            .block(block),
+            area,
+        );
+    }
+
+    fn render_popup(&self, frame: &mut Frame, popup: &Popup) {
+        let area = frame.area();
+        let height = (popup.form.len() as u16 * 3 + 4).min(area.height);
+        let width = 70.min(area.width);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let title = match (popup.kind, &self.session) {
+            (PopupKind::Open, _) => format!(" {} ", t("cashier.open_title")),
+            (PopupKind::Payment, _) => format!(" {} ", t("cashier.payment_title")),
+            (PopupKind::Close, Some(session)) => format!(
+                " {} ",
+                tf(
+                    "cashier.close_title_expected",
+                    &[
+                        ("id", &session.id),
+                        ("amount", &money(session.expected_cash()))
+                    ]
+                )
+            ),
+            (PopupKind::Close, None) => format!(" {} ", t("cashier.close_title")),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let mut constraints = vec![Constraint::Length(3); popup.form.len()];
+        constraints.push(Constraint::Length(1));
+        constraints.push(Constraint::Min(1));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .horizontal_margin(1)
+            .split(inner);
+        for index in 0..popup.form.len() {
+            popup
+                .form
+                .render_field(frame, index, chunks[index], popup.focus_index);
+        }
+        let hint = match popup.kind {
+            PopupKind::Payment if popup.focus_index == METHOD => tf(
+                "cashier.methods",
+                &[("list", &(PaymentMethod::LABELS.join(", ")))],
+            ),
+            PopupKind::Payment => popup.balance.clone(),
+            PopupKind::Open => t("cashier.open_help").to_string(),
+            PopupKind::Close => t("cashier.close_help").to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(hint).style(Style::default().fg(theme().inactive)),
+            chunks[popup.form.len()],
+        );
+        frame.render_widget(
+            Paragraph::new(t("common.form_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[popup.form.len() + 1],
+        );
+    }
+}
+
+impl Component for CashDrawer {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(4),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("cashier.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        self.render_session(frame, layout[1]);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("cashier.payments")))
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.payments.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("cashier.no_payments"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[2],
+            );
+        } else {
+            let widths = [
+                Constraint::Length(7),
+                Constraint::Percentage(30),
+                Constraint::Length(10),
+                Constraint::Length(14),
+                Constraint::Min(12),
+            ];
+            let columns = column_widths(block.inner(layout[2]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .payments
+                .iter()
+                .map(|p| {
+                    let values = [
+                        datetime::time_part(&p.paid_at),
+                        self.patient_name(p.patient_id),
+                        p.method.label().to_string(),
+                        money(p.amount),
+                        p.reference.clone().unwrap_or_default(),
+                    ];
+                    Row::new(
+                        values
+                            .iter()
+                            .enumerate()
+                            .map(|(column, text)| match column {
+                                AMOUNT_COLUMN => numeric_cell(text, &columns, column),
+                                _ => cell(text, &columns, column),
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                    .style(Style::default().fg(theme().text))
+                })
+                .collect();
+            let header = Row::new(HEADERS.map(t).to_vec())
+                .style(Style::default().bg(theme().header).fg(theme().title));
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(block)
+                .row_highlight_style(
+                    Style::default()
+                        .fg(theme().focus)
+                        .bg(theme().highlight)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
+        }
+
+        let help = if self.session.is_some() {
+            t("cashier.help")
+        } else {
+            t("cashier.closed_help")
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+
+        if let Some(popup) = &self.popup {
+            self.render_popup(frame, popup);
+        }
+    }
+}
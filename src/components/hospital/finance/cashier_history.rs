@@ -0,0 +1,359 @@
+//! Cashier sessions opened in a month with their float, takings, count and
+//! variance. Administrators see every cashier's, others only their own.
+
+use super::expenses::{month_range, step_month};
+use crate::app::SelectedApp;
+use crate::auth;
+use crate::components::table::{cell, column_widths, numeric_cell};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::export_view::{self, ExportView, ShownRows};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::CashierSession;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state;
+use crate::utils::{datetime, money, signed_money};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use time::Date;
+
+const HEADERS: [&str; 10] = [
+    "cashier_history.column.number",
+    "cashier_history.column.cashier",
+    "cashier_history.column.opened",
+    "cashier_history.column.closed",
+    "cashier_history.column.float",
+    "cashier_history.column.cash_taken",
+    "cashier_history.column.other",
+    "cashier_history.column.expected",
+    "cashier_history.column.counted",
+    "cashier_history.column.variance",
+];
+/// Columns from "Float" on hold amounts.
+const FIRST_AMOUNT_COLUMN: usize = 4;
+const VARIANCE_COLUMN: usize = 9;
+
+fn variance_color(variance: Option<f64>) -> Color {
+    match variance {
+        None => theme().inactive,
+        Some(v) if v.abs() < 0.005 => theme().success,
+        Some(v) if v < 0.0 => theme().error,
+        Some(_) => theme().warning,
+    }
+}
+
+pub struct CashierHistory {
+    db: Db,
+    /// First day of the month shown.
+    month: Date,
+    sessions: Vec<CashierSession>,
+    /// Whether every cashier's sessions are shown, for administrators.
+    all_cashiers: bool,
+    state: TableState,
+    export_view: Option<ExportView>,
+}
+
+impl CashierHistory {
+    pub fn new(db: &Db) -> Self {
+        let today = datetime::today();
+        Self {
+            db: db.clone(),
+            month: today.replace_day(1).unwrap_or(today),
+            sessions: Vec::new(),
+            all_cashiers: false,
+            state: TableState::default(),
+            export_view: None,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        let user = ui_state::current_user();
+        self.all_cashiers = user
+            .as_ref()
+            .is_some_and(|user| auth::is_admin(&self.db, user));
+        let cashier = if self.all_cashiers {
+            None
+        } else {
+            Some(user.unwrap_or_default())
+        };
+        let (from, to) = month_range(self.month);
+        self.sessions = db::get_cashier_sessions_between(&self.db, &from, &to, cashier.as_deref())?;
+        let len = self.sessions.len();
+        match self.state.selected() {
+            _ if len == 0 => self.state.select(None),
+            Some(i) if i < len => {}
+            _ => self.state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) {
+        if let Err(e) = self.load_data() {
+            toast::push(Toast::error(tf(
+                "cashier_history.load_failed",
+                &[("error", &e)],
+            )));
+        }
+    }
+
+    fn row_values(session: &CashierSession) -> Vec<String> {
+        let closed = session
+            .closed_at
+            .as_deref()
+            .map(datetime::facility_stamp)
+            .unwrap_or_else(|| t("cashier_history.open").to_string());
+        vec![
+            session.id.to_string(),
+            session.cashier.clone(),
+            datetime::facility_stamp(&session.opened_at),
+            closed,
+            money(session.opening_float),
+            money(session.cash_taken),
+            money(session.other_taken),
+            money(session.expected_cash()),
+            session.counted_cash.map(money).unwrap_or_default(),
+            session.variance().map(signed_money).unwrap_or_default(),
+        ]
+    }
+
+    fn shown_rows(&self) -> ShownRows {
+        let mut shown = ShownRows::new(&HEADERS);
+        shown.rows = self.sessions.iter().map(Self::row_values).collect();
+        shown
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(export) = &mut self.export_view {
+            if export.handle_key(key) {
+                self.export_view = None;
+            }
+            return Ok(None);
+        }
+        if export_view::is_open_key(key) {
+            self.export_view = Some(ExportView::new("cashier_sessions", self.shown_rows()));
+            return Ok(None);
+        }
+        let len = self.sessions.len();
+        match key.code {
+            KeyCode::Left => {
+                self.month = step_month(self.month, false);
+                self.reload();
+            }
+            KeyCode::Right => {
+                self.month = step_month(self.month, true);
+                self.reload();
+            }
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => self.reload(),
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_summary(&self, frame: &mut Frame, area: Rect) {
+        let closed: Vec<&CashierSession> = self
+            .sessions
+            .iter()
+            .filter(|s| s.closed_at.is_some())
+            .collect();
+        let open = self.sessions.len() - closed.len();
+        let taken: f64 = self.sessions.iter().map(|s| s.cash_taken).sum();
+        let variance: f64 = closed.iter().filter_map(|s| s.variance()).sum();
+        let short = closed
+            .iter()
+            .filter(|s| s.variance().is_some_and(|v| v < -0.005))
+            .count();
+        let mut spans = vec![
+            Span::styled(
+                tf(
+                    "cashier_history.sessions",
+                    &[("count", &self.sessions.len()), ("open", &open)],
+                ),
+                Style::default().fg(theme().text),
+            ),
+            Span::raw("  ·  "),
+            Span::styled(
+                tf("cashier_history.cash_taken", &[("amount", &money(taken))]),
+                Style::default().fg(theme().text),
+            ),
+            Span::raw("  ·  "),
+            Span::styled(
+                tf(
+                    "cashier_history.net_variance",
+                    &[("amount", &signed_money(variance))],
+                ),
+                Style::default()
+                    .fg(variance_color(Some(variance)))
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ];
+        if short > 0 {
+            spans.push(Span::raw("  ·  "));
+            spans.push(Span::styled(
+                tf("cashier_history.short", &[("count", &short)]),
+                Style::default().fg(theme().error),
+            ));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(spans)).alignment(Alignment::Center),
+            area,
+        );
+    }
+}
+
+impl Component for CashierHistory {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("cashier_history.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+
+        let whose = if self.all_cashiers {
+            t("cashier_history.all")
+        } else {
+            t("cashier_history.yours")
+        };
+        frame.render_widget(
+            Paragraph::new(format!(
+                "◄ {} ►   {}",
+                datetime::format_month(self.month),
+                whose
+            ))
+            .style(Style::default().fg(theme().accent))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("common.month")))
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            ),
+            layout[1],
+        );
+        self.render_summary(frame, layout[2]);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("cashier_history.sessions_title")))
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.sessions.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("cashier_history.empty"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[3],
+            );
+        } else {
+            let widths = [
+                Constraint::Length(5),
+                Constraint::Min(10),
+                Constraint::Length(17),
+                Constraint::Length(17),
+                Constraint::Length(11),
+                Constraint::Length(11),
+                Constraint::Length(13),
+                Constraint::Length(11),
+                Constraint::Length(11),
+                Constraint::Length(11),
+            ];
+            let columns = column_widths(block.inner(layout[3]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .sessions
+                .iter()
+                .map(|session| {
+                    let values = Self::row_values(session);
+                    let cells: Vec<Cell> = values
+                        .iter()
+                        .enumerate()
+                        .map(|(column, text)| match column {
+                            VARIANCE_COLUMN => numeric_cell(text, &columns, column)
+                                .style(Style::default().fg(variance_color(session.variance()))),
+                            c if c >= FIRST_AMOUNT_COLUMN => numeric_cell(text, &columns, column),
+                            _ => cell(text, &columns, column),
+                        })
+                        .collect();
+                    let color = if session.closed_at.is_some() {
+                        theme().text
+                    } else {
+                        theme().accent
+                    };
+                    Row::new(cells).style(Style::default().fg(color))
+                })
+                .collect();
+            let header = Row::new(HEADERS.map(t).to_vec())
+                .style(Style::default().bg(theme().header).fg(theme().title));
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(block)
+                .row_highlight_style(
+                    Style::default()
+                        .fg(theme().focus)
+                        .bg(theme().highlight)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[3], &mut self.state.clone());
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("cashier_history.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+
+        if let Some(export) = &self.export_view {
+            export.render_popup(frame);
+        }
+    }
+}
@@ -0,0 +1,424 @@
+//! A month's expenses totalled by department and by category, next to what
+//! was invoiced in the same month.
+
+use super::expenses::{month_range, step_month};
+use crate::app::SelectedApp;
+use crate::components::table::{cell, column_widths, numeric_cell};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::Expense;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{csv_line, datetime, money, signed_money, write_export};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::BTreeMap;
+use time::Date;
+
+const EXPORT_HEADERS: [&str; 7] = [
+    "Date",
+    "Department",
+    "Category",
+    "Amount",
+    "Payee",
+    "Note",
+    "Entered By",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Departments,
+    Categories,
+}
+
+impl View {
+    fn title(self) -> String {
+        match self {
+            View::Departments => format!(" {} ", t("expense_report.by_department")),
+            View::Categories => format!(" {} ", t("expense_report.by_category")),
+        }
+    }
+
+    fn column(self) -> &'static str {
+        match self {
+            View::Departments => t("expense_report.department"),
+            View::Categories => t("expense_report.category"),
+        }
+    }
+}
+
+struct Group {
+    name: String,
+    entries: usize,
+    total: f64,
+    /// The category, or department, this group spent the most on.
+    largest: String,
+}
+
+fn group_by(expenses: &[Expense], view: View) -> Vec<Group> {
+    let mut groups: BTreeMap<&str, Vec<&Expense>> = BTreeMap::new();
+    for expense in expenses {
+        let key = match view {
+            View::Departments => expense.department.as_str(),
+            View::Categories => expense.category.as_str(),
+        };
+        groups.entry(key).or_default().push(expense);
+    }
+    let mut groups: Vec<Group> = groups
+        .into_iter()
+        .map(|(name, items)| {
+            let mut by_other: BTreeMap<&str, f64> = BTreeMap::new();
+            for e in &items {
+                let other = match view {
+                    View::Departments => e.category.as_str(),
+                    View::Categories => e.department.as_str(),
+                };
+                *by_other.entry(other).or_default() += e.amount;
+            }
+            let largest = by_other
+                .into_iter()
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(other, _)| other.to_string())
+                .unwrap_or_default();
+            Group {
+                name: name.to_string(),
+                entries: items.len(),
+                total: items.iter().map(|e| e.amount).sum(),
+                largest,
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| b.total.total_cmp(&a.total));
+    groups
+}
+
+pub struct ExpenseReport {
+    db: Db,
+    /// First day of the month shown.
+    month: Date,
+    view: View,
+    expenses: Vec<Expense>,
+    invoiced: f64,
+    state: TableState,
+}
+
+impl ExpenseReport {
+    pub fn new(db: &Db) -> Self {
+        let today = datetime::today();
+        Self {
+            db: db.clone(),
+            month: today.replace_day(1).unwrap_or(today),
+            view: View::Departments,
+            expenses: Vec::new(),
+            invoiced: 0.0,
+            state: TableState::default(),
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        let (from, to) = month_range(self.month);
+        self.expenses = db::get_expenses_between(&self.db, &from, &to)?;
+        self.invoiced = db::get_invoiced_between(&self.db, &from, &to)?;
+        let len = group_by(&self.expenses, self.view).len();
+        match self.state.selected() {
+            _ if len == 0 => self.state.select(None),
+            Some(i) if i < len => {}
+            _ => self.state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) {
+        if let Err(e) = self.load_data() {
+            toast::push(Toast::error(tf(
+                "expense_report.load_failed",
+                &[("error", &e)],
+            )));
+        }
+    }
+
+    fn export(&self) {
+        if self.expenses.is_empty() {
+            toast::push(Toast::error(t("expense_report.none")));
+            return;
+        }
+        let mut contents = csv_line(&EXPORT_HEADERS);
+        contents.push('\n');
+        for e in &self.expenses {
+            let values = [
+                e.spent_on.clone(),
+                e.department.clone(),
+                e.category.clone(),
+                format!("{:.2}", e.amount),
+                e.payee.clone().unwrap_or_default(),
+                e.note.clone().unwrap_or_default(),
+                e.recorded_by.clone().unwrap_or_default(),
+            ];
+            let fields: Vec<&str> = values.iter().map(String::as_str).collect();
+            contents.push_str(&csv_line(&fields));
+            contents.push('\n');
+        }
+        let file_name = format!("expenses_{}.csv", datetime::format_month(self.month));
+        match write_export(&file_name, &contents) {
+            Ok(path) => toast::push(Toast::success(tf(
+                "expense_report.exported",
+                &[("count", &self.expenses.len()), ("path", &path.display())],
+            ))),
+            Err(e) => toast::push(Toast::error(format!("{}", e))),
+        }
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        let len = group_by(&self.expenses, self.view).len();
+        match key.code {
+            KeyCode::Left => {
+                self.month = step_month(self.month, false);
+                self.reload();
+            }
+            KeyCode::Right => {
+                self.month = step_month(self.month, true);
+                self.reload();
+            }
+            KeyCode::Tab => {
+                self.view = match self.view {
+                    View::Departments => View::Categories,
+                    View::Categories => View::Departments,
+                };
+                let len = group_by(&self.expenses, self.view).len();
+                self.state.select((len > 0).then_some(0));
+            }
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('e') | KeyCode::Char('E') => self.export(),
+            KeyCode::Char('r') | KeyCode::Char('R') => self.reload(),
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_summary(&self, frame: &mut Frame, area: Rect) {
+        let spent: f64 = self.expenses.iter().map(|e| e.amount).sum();
+        let net = self.invoiced - spent;
+        let figure = |label: &str, amount: f64, color: Color| {
+            vec![
+                Span::styled(format!("{} ", label), Style::default().fg(theme().inactive)),
+                Span::styled(
+                    signed_money(amount),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+            ]
+        };
+        let mut spans = figure(t("expense_report.invoiced"), self.invoiced, theme().success);
+        spans.push(Span::raw("   ·   "));
+        spans.extend(figure(t("expense_report.spent"), spent, theme().warning));
+        spans.push(Span::raw("   ·   "));
+        spans.extend(figure(
+            t("expense_report.net"),
+            net,
+            if net < 0.0 {
+                theme().error
+            } else {
+                theme().text
+            },
+        ));
+        frame.render_widget(
+            Paragraph::new(Line::from(spans))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("expense_report.this_month")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            area,
+        );
+    }
+
+    fn render_groups(&self, frame: &mut Frame, area: Rect) {
+        let groups = group_by(&self.expenses, self.view);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(self.view.title())
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if groups.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("expense_report.empty"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        }
+
+        let spent: f64 = groups.iter().map(|g| g.total).sum();
+        let largest_header = match self.view {
+            View::Departments => t("expense_report.mostly_on"),
+            View::Categories => t("expense_report.mostly_by"),
+        };
+        let widths = [
+            Constraint::Percentage(30),
+            Constraint::Length(8),
+            Constraint::Length(14),
+            Constraint::Length(8),
+            Constraint::Min(14),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+        let rows: Vec<Row> = groups
+            .iter()
+            .map(|g| {
+                let share = if spent > 0.0 {
+                    g.total / spent * 100.0
+                } else {
+                    0.0
+                };
+                Row::new(vec![
+                    cell(&g.name, &columns, 0),
+                    numeric_cell(g.entries.to_string(), &columns, 1),
+                    numeric_cell(money(g.total), &columns, 2),
+                    numeric_cell(format!("{:.0}%", share), &columns, 3),
+                    cell(&g.largest, &columns, 4),
+                ])
+                .style(Style::default().fg(theme().text))
+            })
+            .collect();
+        let header = Row::new(vec![
+            self.view.column(),
+            t("expense_report.entries"),
+            t("expense_report.total"),
+            t("expense_report.share"),
+            largest_header,
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.state.clone());
+    }
+
+    fn render_chart(&self, frame: &mut Frame, area: Rect) {
+        let groups = group_by(&self.expenses, self.view);
+        let bars: Vec<Bar> = groups
+            .iter()
+            .map(|g| {
+                Bar::default()
+                    .label(Line::from(g.name.clone()))
+                    .value(g.total.round().max(0.0) as u64)
+                    .text_value(money(g.total))
+                    .style(Style::default().fg(theme().accent))
+            })
+            .collect();
+        frame.render_widget(
+            BarChart::default()
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("expense_report.spent_title")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                )
+                .direction(Direction::Horizontal)
+                .bar_width(1)
+                .bar_gap(0)
+                .data(BarGroup::default().bars(&bars)),
+            area,
+        );
+    }
+}
+
+impl Component for ExpenseReport {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("expense_report.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+
+        frame.render_widget(
+            Paragraph::new(format!("◄ {} ►", datetime::format_month(self.month)))
+                .style(Style::default().fg(theme().accent))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("common.month")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[1],
+        );
+
+        self.render_summary(frame, layout[2]);
+
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(layout[3]);
+        self.render_groups(frame, body[0]);
+        self.render_chart(frame, body[1]);
+
+        frame.render_widget(
+            Paragraph::new(t("expense_report.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+    }
+}
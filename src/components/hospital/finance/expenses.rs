@@ -0,0 +1,533 @@
+//! Money going out: expenses entered by department and category, a month at
+//! a time.
+
+use crate::app::SelectedApp;
+use crate::components::form::{self, date, number_range, Field, Form};
+use crate::components::table::{cell, column_widths, numeric_cell};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{Expense, EXPENSE_CATEGORIES};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state;
+use crate::utils::{datetime, money};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use time::{Date, Duration};
+
+const HEADERS: [&str; 7] = [
+    "expenses.column.date",
+    "expenses.column.department",
+    "expenses.column.category",
+    "expenses.column.payee",
+    "expenses.column.amount",
+    "expenses.column.note",
+    "expenses.column.entered_by",
+];
+const AMOUNT_COLUMN: usize = 4;
+
+const DEPARTMENT: usize = 0;
+const CATEGORY: usize = 1;
+const AMOUNT: usize = 2;
+const SPENT_ON: usize = 3;
+const PAYEE: usize = 4;
+const NOTE: usize = 5;
+
+fn expense_form() -> Form {
+    Form::new(vec![
+        Field::new(t("expenses.department")).required(),
+        Field::new(t("expenses.category_field"))
+            .required()
+            .validate_with(form::one_of(&EXPENSE_CATEGORIES)),
+        Field::new(t("expenses.amount"))
+            .required()
+            .validate_with(number_range(0.01, 1_000_000_000.0)),
+        Field::new(t("expenses.date"))
+            .required()
+            .validate_with(Box::new(date)),
+        Field::new(t("expenses.payee")),
+        Field::new(t("expenses.note")),
+    ])
+}
+
+/// The first and last day of the month starting on `month`.
+pub fn month_range(month: Date) -> (String, String) {
+    let next = (month + Duration::days(31)).replace_day(1).unwrap_or(month);
+    (
+        datetime::format_date(month),
+        datetime::format_date(next - Duration::days(1)),
+    )
+}
+
+/// The first day of the month before or after `month`.
+pub fn step_month(month: Date, forward: bool) -> Date {
+    let moved = if forward {
+        month + Duration::days(31)
+    } else {
+        month - Duration::days(1)
+    };
+    moved.replace_day(1).unwrap_or(moved)
+}
+
+struct Popup {
+    /// The expense being edited; a new expense when `None`.
+    expense_id: Option<i64>,
+    form: Form,
+    focus_index: usize,
+}
+
+pub struct Expenses {
+    db: Db,
+    /// First day of the month shown.
+    month: Date,
+    expenses: Vec<Expense>,
+    /// Departments already used, so a new entry can match their spelling.
+    departments: Vec<String>,
+    state: TableState,
+    popup: Option<Popup>,
+    confirm_delete: bool,
+}
+
+impl Expenses {
+    pub fn new(db: &Db) -> Self {
+        let today = datetime::today();
+        Self {
+            db: db.clone(),
+            month: today.replace_day(1).unwrap_or(today),
+            expenses: Vec::new(),
+            departments: Vec::new(),
+            state: TableState::default(),
+            popup: None,
+            confirm_delete: false,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        let (from, to) = month_range(self.month);
+        self.expenses = db::get_expenses_between(&self.db, &from, &to)?;
+        self.departments = db::get_expense_departments(&self.db)?;
+        let len = self.expenses.len();
+        match self.state.selected() {
+            _ if len == 0 => self.state.select(None),
+            Some(i) if i < len => {}
+            _ => self.state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) {
+        if let Err(e) = self.load_data() {
+            toast::push(Toast::error(tf(
+                "expense_report.load_failed",
+                &[("error", &e)],
+            )));
+        }
+    }
+
+    fn selected_expense(&self) -> Option<&Expense> {
+        self.expenses.get(self.state.selected()?)
+    }
+
+    fn open_popup(&mut self, expense: Option<Expense>) {
+        let mut form = expense_form();
+        match &expense {
+            Some(e) => {
+                form.fields[DEPARTMENT].set(e.department.clone());
+                form.fields[CATEGORY].set(e.category.clone());
+                form.fields[AMOUNT].set(format!("{:.2}", e.amount));
+                form.fields[SPENT_ON].set(e.spent_on.clone());
+                form.fields[PAYEE].set(e.payee.clone().unwrap_or_default());
+                form.fields[NOTE].set(e.note.clone().unwrap_or_default());
+            }
+            None => form.fields[SPENT_ON].set(datetime::today_text()),
+        }
+        self.popup = Some(Popup {
+            expense_id: expense.map(|e| e.id),
+            form,
+            focus_index: DEPARTMENT,
+        });
+    }
+
+    /// `typed` spelled like a department already in use, if one matches
+    /// apart from case, so the report does not split it in two.
+    fn known_department(&self, typed: &str) -> String {
+        self.departments
+            .iter()
+            .find(|d| d.eq_ignore_ascii_case(typed))
+            .cloned()
+            .unwrap_or_else(|| typed.to_string())
+    }
+
+    fn save_popup(&mut self) -> Result<()> {
+        let Some(mut popup) = self.popup.take() else {
+            return Ok(());
+        };
+        let typed = popup.form.value(CATEGORY);
+        if let Some(category) = EXPENSE_CATEGORIES
+            .iter()
+            .find(|c| c.eq_ignore_ascii_case(&typed))
+        {
+            popup.form.fields[CATEGORY].set(*category);
+        }
+        if let Some(invalid) = popup.form.validate() {
+            popup.focus_index = invalid;
+            self.popup = Some(popup);
+            return Ok(());
+        }
+
+        let form = &popup.form;
+        let expense = Expense {
+            id: popup.expense_id.unwrap_or_default(),
+            department: self.known_department(&form.value(DEPARTMENT)),
+            category: form.value(CATEGORY),
+            amount: form.value(AMOUNT).parse().unwrap_or_default(),
+            spent_on: form.value(SPENT_ON),
+            payee: form.optional_value(PAYEE),
+            note: form.optional_value(NOTE),
+            recorded_by: ui_state::current_user(),
+        };
+        let result = match popup.expense_id {
+            Some(_) => db::update_expense(&self.db, &expense),
+            None => db::create_expense(&self.db, &expense).map(|_| ()),
+        };
+        match result {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "expenses.saved",
+                    &[
+                        ("category", &expense.category),
+                        ("amount", &money(expense.amount)),
+                        ("department", &expense.department),
+                    ],
+                )));
+                if let Some(spent_on) = datetime::parse_date(&expense.spent_on) {
+                    self.month = spent_on.replace_day(1).unwrap_or(spent_on);
+                }
+                self.load_data()?;
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("expenses.save_failed", &[("error", &e)])));
+                self.popup = Some(popup);
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_selected(&mut self) -> Result<()> {
+        self.confirm_delete = false;
+        let Some(expense) = self.selected_expense().cloned() else {
+            return Ok(());
+        };
+        match db::delete_expense(&self.db, expense.id) {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "expenses.deleted",
+                    &[
+                        ("category", &expense.category),
+                        ("amount", &money(expense.amount)),
+                    ],
+                )));
+                self.load_data()?;
+            }
+            Err(e) => toast::push(Toast::error(tf("expenses.delete_failed", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    fn handle_popup_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(popup) = self.popup.as_mut() else {
+            return Ok(());
+        };
+        let len = popup.form.len();
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Tab | KeyCode::Down => popup.focus_index = (popup.focus_index + 1) % len,
+            KeyCode::BackTab | KeyCode::Up => {
+                popup.focus_index = (popup.focus_index + len - 1) % len
+            }
+            KeyCode::Enter if popup.focus_index + 1 < len => popup.focus_index += 1,
+            KeyCode::Enter => self.save_popup()?,
+            _ => {
+                popup.form.handle_key(popup.focus_index, key);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if self.popup.is_some() {
+            self.handle_popup_input(key)?;
+            return Ok(None);
+        }
+        if self.confirm_delete {
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.delete_selected()?
+                }
+                _ => self.confirm_delete = false,
+            }
+            return Ok(None);
+        }
+        let len = self.expenses.len();
+        match key.code {
+            KeyCode::Left => {
+                self.month = step_month(self.month, false);
+                self.reload();
+            }
+            KeyCode::Right => {
+                self.month = step_month(self.month, true);
+                self.reload();
+            }
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => self.open_popup(None),
+            KeyCode::Char('e') | KeyCode::Char('E') | KeyCode::Enter => {
+                if let Some(expense) = self.selected_expense().cloned() {
+                    self.open_popup(Some(expense));
+                }
+            }
+            KeyCode::Char('x') | KeyCode::Char('X') if self.selected_expense().is_some() => {
+                self.confirm_delete = true;
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => self.reload(),
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_popup(&self, frame: &mut Frame, popup: &Popup) {
+        let area = frame.area();
+        let height = (popup.form.len() as u16 * 3 + 4).min(area.height);
+        let width = 70.min(area.width);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let title = match popup.expense_id {
+            Some(id) => format!(" {} ", tf("expenses.edit_title", &[("id", &id)])),
+            None => format!(" {} ", t("expenses.new_title")),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let mut constraints = vec![Constraint::Length(3); popup.form.len()];
+        constraints.push(Constraint::Length(1));
+        constraints.push(Constraint::Min(1));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .horizontal_margin(1)
+            .split(inner);
+        for index in 0..popup.form.len() {
+            popup
+                .form
+                .render_field(frame, index, chunks[index], popup.focus_index);
+        }
+        let hint = if popup.focus_index == CATEGORY {
+            tf(
+                "expenses.categories",
+                &[("list", &(EXPENSE_CATEGORIES.join(", ")))],
+            )
+        } else if self.departments.is_empty() {
+            String::new()
+        } else {
+            tf(
+                "expenses.departments",
+                &[("list", &(self.departments.join(", ")))],
+            )
+        };
+        frame.render_widget(
+            Paragraph::new(hint).style(Style::default().fg(theme().inactive)),
+            chunks[popup.form.len()],
+        );
+        frame.render_widget(
+            Paragraph::new(t("common.form_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[popup.form.len() + 1],
+        );
+    }
+}
+
+impl Component for Expenses {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("expenses.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+
+        frame.render_widget(
+            Paragraph::new(format!("◄ {} ►", datetime::format_month(self.month)))
+                .style(Style::default().fg(theme().accent))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("common.month")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[1],
+        );
+
+        let total: f64 = self.expenses.iter().map(|e| e.amount).sum();
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(
+                    tf("expenses.count", &[("count", &self.expenses.len())]),
+                    Style::default().fg(theme().text),
+                ),
+                Span::raw("  ·  "),
+                Span::styled(
+                    tf("expenses.spent", &[("amount", &money(total))]),
+                    Style::default()
+                        .fg(theme().warning)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]))
+            .alignment(Alignment::Center),
+            layout[2],
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("expenses.list_title")))
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.expenses.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("expenses.empty"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[3],
+            );
+        } else {
+            let widths = [
+                Constraint::Length(11),
+                Constraint::Percentage(18),
+                Constraint::Length(12),
+                Constraint::Percentage(18),
+                Constraint::Length(14),
+                Constraint::Min(12),
+                Constraint::Length(12),
+            ];
+            let columns = column_widths(block.inner(layout[3]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .expenses
+                .iter()
+                .map(|e| {
+                    let values = [
+                        e.spent_on.clone(),
+                        e.department.clone(),
+                        e.category.clone(),
+                        e.payee.clone().unwrap_or_default(),
+                        money(e.amount),
+                        e.note.clone().unwrap_or_default(),
+                        e.recorded_by.clone().unwrap_or_default(),
+                    ];
+                    Row::new(
+                        values
+                            .iter()
+                            .enumerate()
+                            .map(|(column, text)| match column {
+                                AMOUNT_COLUMN => numeric_cell(text, &columns, column),
+                                _ => cell(text, &columns, column),
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                    .style(Style::default().fg(theme().text))
+                })
+                .collect();
+            let header = Row::new(HEADERS.map(t).to_vec())
+                .style(Style::default().bg(theme().header).fg(theme().title));
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(block)
+                .row_highlight_style(
+                    Style::default()
+                        .fg(theme().focus)
+                        .bg(theme().highlight)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[3], &mut self.state.clone());
+        }
+
+        let help = if self.confirm_delete {
+            t("expenses.delete_confirm")
+        } else {
+            t("expenses.help")
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(if self.confirm_delete {
+                    theme().warning
+                } else {
+                    theme().help
+                }))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+
+        if let Some(popup) = &self.popup {
+            self.render_popup(frame, popup);
+        }
+    }
+}
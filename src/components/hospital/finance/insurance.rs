@@ -0,0 +1,746 @@
+//! Insurance plans, what each pays for every service category, and the
+//! patients enrolled in them. Invoices for an enrolled patient are split
+//! between the insurer and the patient's co-pay as they are saved.
+//! Everyone can look; only administrators make changes.
+
+use crate::app::SelectedApp;
+use crate::auth;
+use crate::components::form::{integer_range, number_range, Field, Form};
+use crate::components::table::{cell, column_widths, numeric_cell};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{InsurancePlan, PatientInsurance, PlanCoverage, SERVICE_CATEGORIES};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state;
+use crate::utils::money;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+const NAME: usize = 0;
+const INSURER: usize = 1;
+
+const PERCENT: usize = 0;
+const ANNUAL_CAP: usize = 1;
+
+const PATIENT_ID: usize = 0;
+const MEMBER_NUMBER: usize = 1;
+
+fn may_edit_plans(db: &Db) -> bool {
+    ui_state::current_user().is_some_and(|user| auth::is_admin(db, &user))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Plans,
+    Coverage,
+    Members,
+}
+
+impl Pane {
+    fn next(self) -> Self {
+        match self {
+            Pane::Plans => Pane::Coverage,
+            Pane::Coverage => Pane::Members,
+            Pane::Members => Pane::Plans,
+        }
+    }
+}
+
+enum PopupKind {
+    /// A new plan, or the plan with this id renamed.
+    Plan(Option<i64>),
+    /// What the selected plan pays for this category.
+    Coverage(&'static str),
+    /// A patient enrolled in the selected plan, or their member number
+    /// changed.
+    Enrol,
+}
+
+struct Popup {
+    kind: PopupKind,
+    form: Form,
+    focus_index: usize,
+}
+
+pub struct InsurancePlans {
+    db: Db,
+    plans: Vec<InsurancePlan>,
+    /// What the selected plan pays, for the categories it covers.
+    coverage: Vec<PlanCoverage>,
+    /// The patients enrolled in the selected plan.
+    members: Vec<PatientInsurance>,
+    pane: Pane,
+    plan_state: TableState,
+    /// Row in [`SERVICE_CATEGORIES`].
+    coverage_state: TableState,
+    member_state: TableState,
+    popup: Option<Popup>,
+    confirm_delete: bool,
+}
+
+impl InsurancePlans {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            plans: Vec::new(),
+            coverage: Vec::new(),
+            members: Vec::new(),
+            pane: Pane::Plans,
+            plan_state: TableState::default(),
+            coverage_state: TableState::default().with_selected(Some(0)),
+            member_state: TableState::default(),
+            popup: None,
+            confirm_delete: false,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.plans = db::get_insurance_plans(&self.db)?;
+        let len = self.plans.len();
+        match self.plan_state.selected() {
+            _ if len == 0 => self.plan_state.select(None),
+            Some(i) if i < len => {}
+            _ => self.plan_state.select(Some(0)),
+        }
+        self.load_plan()
+    }
+
+    /// Loads the coverage and members of the selected plan.
+    fn load_plan(&mut self) -> Result<()> {
+        match self.selected_plan().map(|plan| plan.id) {
+            Some(plan_id) => {
+                self.coverage = db::get_plan_coverage(&self.db, plan_id)?;
+                self.members = db::get_plan_members(&self.db, plan_id)?;
+            }
+            None => {
+                self.coverage.clear();
+                self.members.clear();
+            }
+        }
+        let len = self.members.len();
+        match self.member_state.selected() {
+            _ if len == 0 => self.member_state.select(None),
+            Some(i) if i < len => {}
+            _ => self.member_state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) {
+        if let Err(e) = self.load_data() {
+            toast::push(Toast::error(tf("insurance.load_failed", &[("error", &e)])));
+        }
+    }
+
+    fn selected_plan(&self) -> Option<&InsurancePlan> {
+        self.plans.get(self.plan_state.selected()?)
+    }
+
+    fn selected_category(&self) -> &'static str {
+        SERVICE_CATEGORIES[self.coverage_state.selected().unwrap_or(0)]
+    }
+
+    fn coverage_for(&self, category: &str) -> Option<&PlanCoverage> {
+        self.coverage.iter().find(|c| c.category == category)
+    }
+
+    fn selected_member(&self) -> Option<&PatientInsurance> {
+        self.members.get(self.member_state.selected()?)
+    }
+
+    fn open_plan_popup(&mut self, plan: Option<InsurancePlan>) {
+        let mut form = Form::new(vec![
+            Field::new(t("insurance.plan_name")).required(),
+            Field::new(t("insurance.insurer")),
+        ]);
+        if let Some(plan) = &plan {
+            form.fields[NAME].set(plan.name.clone());
+            form.fields[INSURER].set(plan.insurer.clone().unwrap_or_default());
+        }
+        self.popup = Some(Popup {
+            kind: PopupKind::Plan(plan.map(|p| p.id)),
+            form,
+            focus_index: NAME,
+        });
+    }
+
+    fn open_coverage_popup(&mut self) {
+        if self.selected_plan().is_none() {
+            return;
+        }
+        let category = self.selected_category();
+        let mut form = Form::new(vec![
+            Field::new(t("insurance.percent"))
+                .required()
+                .validate_with(number_range(0.0, 100.0)),
+            Field::new(t("insurance.cap_field")).validate_with(number_range(0.0, 1_000_000_000.0)),
+        ]);
+        if let Some(coverage) = self.coverage_for(category) {
+            form.fields[PERCENT].set(coverage.percent.to_string());
+            if let Some(cap) = coverage.annual_cap {
+                form.fields[ANNUAL_CAP].set(format!("{:.2}", cap));
+            }
+        }
+        self.popup = Some(Popup {
+            kind: PopupKind::Coverage(category),
+            form,
+            focus_index: PERCENT,
+        });
+    }
+
+    fn open_enrol_popup(&mut self, member: Option<PatientInsurance>) {
+        if self.selected_plan().is_none() {
+            return;
+        }
+        let mut form = Form::new(vec![
+            Field::new(t("common.patient_id"))
+                .required()
+                .validate_with(integer_range(1, i64::MAX)),
+            Field::new(t("insurance.member_number")),
+        ]);
+        let mut focus_index = PATIENT_ID;
+        if let Some(member) = member {
+            form.fields[PATIENT_ID].set(member.patient_id.to_string());
+            form.fields[MEMBER_NUMBER].set(member.member_number.unwrap_or_default());
+            focus_index = MEMBER_NUMBER;
+        }
+        self.popup = Some(Popup {
+            kind: PopupKind::Enrol,
+            form,
+            focus_index,
+        });
+    }
+
+    fn save_popup(&mut self) -> Result<()> {
+        let Some(mut popup) = self.popup.take() else {
+            return Ok(());
+        };
+        if let Some(invalid) = popup.form.validate() {
+            popup.focus_index = invalid;
+            self.popup = Some(popup);
+            return Ok(());
+        }
+        let plan_id = self.selected_plan().map(|plan| plan.id).unwrap_or_default();
+        let form = &popup.form;
+        let result = match &popup.kind {
+            PopupKind::Plan(id) => {
+                let name = form.value(NAME).trim().to_string();
+                let insurer = form.optional_value(INSURER);
+                let saved = match id {
+                    Some(id) => db::update_insurance_plan(&self.db, *id, &name, insurer.as_deref()),
+                    None => {
+                        db::create_insurance_plan(&self.db, &name, insurer.as_deref()).map(|_| ())
+                    }
+                };
+                saved.map(|()| tf("insurance.saved_plan", &[("name", &name)]))
+            }
+            PopupKind::Coverage(category) => {
+                let coverage = PlanCoverage {
+                    plan_id,
+                    category: category.to_string(),
+                    percent: form.value(PERCENT).parse().unwrap_or_default(),
+                    annual_cap: form
+                        .optional_value(ANNUAL_CAP)
+                        .and_then(|cap| cap.parse().ok()),
+                };
+                db::set_plan_coverage(&self.db, &coverage).map(|()| {
+                    tf(
+                        "insurance.covered_at",
+                        &[("category", &category), ("percent", &coverage.percent)],
+                    )
+                })
+            }
+            PopupKind::Enrol => {
+                let patient_id: i64 = form.value(PATIENT_ID).parse().unwrap_or_default();
+                let member_number = form.optional_value(MEMBER_NUMBER);
+                db::enrol_patient(&self.db, patient_id, plan_id, member_number.as_deref())
+                    .map(|()| tf("insurance.enrolled", &[("id", &patient_id)]))
+            }
+        };
+        match result {
+            Ok(message) => {
+                toast::push(Toast::success(message));
+                if let PopupKind::Plan(None) = popup.kind {
+                    let name = popup.form.value(NAME);
+                    self.load_data()?;
+                    let row = self.plans.iter().position(|p| p.name == name.trim());
+                    self.plan_state.select(row.or(self.plan_state.selected()));
+                    self.load_plan()?;
+                } else {
+                    self.load_data()?;
+                }
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("insurance.save_failed", &[("error", &e)])));
+                self.popup = Some(popup);
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_selected(&mut self) -> Result<()> {
+        self.confirm_delete = false;
+        let result = match self.pane {
+            Pane::Plans => {
+                let Some(plan) = self.selected_plan().cloned() else {
+                    return Ok(());
+                };
+                db::delete_insurance_plan(&self.db, plan.id)
+                    .map(|()| tf("insurance.deleted_plan", &[("name", &plan.name)]))
+            }
+            Pane::Coverage => {
+                let Some(plan_id) = self.selected_plan().map(|plan| plan.id) else {
+                    return Ok(());
+                };
+                let category = self.selected_category();
+                db::delete_plan_coverage(&self.db, plan_id, category)
+                    .map(|()| tf("insurance.not_covered_now", &[("category", &category)]))
+            }
+            Pane::Members => {
+                let Some(member) = self.selected_member().cloned() else {
+                    return Ok(());
+                };
+                db::remove_patient_insurance(&self.db, member.patient_id)
+                    .map(|()| tf("insurance.removed", &[("patient", &member.patient_name)]))
+            }
+        };
+        match result {
+            Ok(message) => {
+                toast::push(Toast::success(message));
+                self.load_data()?;
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "insurance.delete_failed",
+                &[("error", &e)],
+            ))),
+        }
+        Ok(())
+    }
+
+    fn handle_popup_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(popup) = self.popup.as_mut() else {
+            return Ok(());
+        };
+        let len = popup.form.len();
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Tab | KeyCode::Down => popup.focus_index = (popup.focus_index + 1) % len,
+            KeyCode::BackTab | KeyCode::Up => {
+                popup.focus_index = (popup.focus_index + len - 1) % len
+            }
+            KeyCode::Enter if popup.focus_index + 1 < len => popup.focus_index += 1,
+            KeyCode::Enter => self.save_popup()?,
+            _ => {
+                popup.form.handle_key(popup.focus_index, key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the selection of the focused pane by `step` rows, wrapping.
+    fn step(&mut self, forward: bool) -> Result<()> {
+        let (state, len) = match self.pane {
+            Pane::Plans => (&mut self.plan_state, self.plans.len()),
+            Pane::Coverage => (&mut self.coverage_state, SERVICE_CATEGORIES.len()),
+            Pane::Members => (&mut self.member_state, self.members.len()),
+        };
+        if len == 0 {
+            return Ok(());
+        }
+        let i = match (state.selected(), forward) {
+            (None, _) => 0,
+            (Some(i), true) => (i + 1) % len,
+            (Some(i), false) => (i + len - 1) % len,
+        };
+        state.select(Some(i));
+        if self.pane == Pane::Plans {
+            self.member_state.select(None);
+            self.load_plan()?;
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if self.popup.is_some() {
+            self.handle_popup_input(key)?;
+            return Ok(None);
+        }
+        if self.confirm_delete {
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.delete_selected()?
+                }
+                _ => self.confirm_delete = false,
+            }
+            return Ok(None);
+        }
+        let changes = matches!(
+            key.code,
+            KeyCode::Char('a' | 'A' | 'e' | 'E' | 'x' | 'X') | KeyCode::Enter
+        );
+        if changes && !may_edit_plans(&self.db) {
+            toast::push(Toast::error(t("insurance.admins_only")));
+            return Ok(None);
+        }
+        match (key.code, self.pane) {
+            (KeyCode::Tab, _) => self.pane = self.pane.next(),
+            (KeyCode::Down, _) => self.step(true)?,
+            (KeyCode::Up, _) => self.step(false)?,
+            (KeyCode::Char('a') | KeyCode::Char('A'), Pane::Plans) => self.open_plan_popup(None),
+            (KeyCode::Char('a') | KeyCode::Char('A'), Pane::Members) => self.open_enrol_popup(None),
+            (KeyCode::Char('e') | KeyCode::Char('E') | KeyCode::Enter, Pane::Plans) => {
+                if let Some(plan) = self.selected_plan().cloned() {
+                    self.open_plan_popup(Some(plan));
+                }
+            }
+            (KeyCode::Char('e') | KeyCode::Char('E') | KeyCode::Enter, Pane::Coverage) => {
+                self.open_coverage_popup()
+            }
+            (KeyCode::Char('e') | KeyCode::Char('E') | KeyCode::Enter, Pane::Members) => {
+                if let Some(member) = self.selected_member().cloned() {
+                    self.open_enrol_popup(Some(member));
+                }
+            }
+            (KeyCode::Char('x') | KeyCode::Char('X'), Pane::Plans)
+                if self.selected_plan().is_some() =>
+            {
+                self.confirm_delete = true;
+            }
+            (KeyCode::Char('x') | KeyCode::Char('X'), Pane::Coverage)
+                if self.coverage_for(self.selected_category()).is_some() =>
+            {
+                self.confirm_delete = true;
+            }
+            (KeyCode::Char('x') | KeyCode::Char('X'), Pane::Members)
+                if self.selected_member().is_some() =>
+            {
+                self.confirm_delete = true;
+            }
+            (KeyCode::Char('r') | KeyCode::Char('R'), _) => self.reload(),
+            (KeyCode::Esc, _) => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn pane_block(&self, title: &str, pane: Pane) -> Block<'static> {
+        let border = if self.pane == pane {
+            theme().focus
+        } else {
+            theme().border
+        };
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", title))
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(border))
+            .style(Style::default().bg(theme().surface))
+    }
+
+    fn highlighted(&self, table: Table<'static>, pane: Pane) -> Table<'static> {
+        if self.pane != pane {
+            return table;
+        }
+        table
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ")
+    }
+
+    fn render_plans(&self, frame: &mut Frame, area: Rect) {
+        let block = self.pane_block(t("insurance.plans"), Pane::Plans);
+        if self.plans.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("insurance.no_plans"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .wrap(Wrap { trim: true })
+                    .block(block),
+                area,
+            );
+            return;
+        }
+        let widths = [
+            Constraint::Min(12),
+            Constraint::Percentage(35),
+            Constraint::Length(8),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+        let rows: Vec<Row> = self
+            .plans
+            .iter()
+            .map(|plan| {
+                Row::new(vec![
+                    cell(&plan.name, &columns, 0),
+                    cell(plan.insurer.as_deref().unwrap_or("-"), &columns, 1),
+                    numeric_cell(plan.members.to_string(), &columns, 2),
+                ])
+                .style(Style::default().fg(theme().text))
+            })
+            .collect();
+        let header = Row::new(vec![
+            t("insurance.plan"),
+            t("insurance.insurer"),
+            t("insurance.members"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let table = Table::new(rows, widths).header(header).block(block);
+        frame.render_stateful_widget(
+            self.highlighted(table, Pane::Plans),
+            area,
+            &mut self.plan_state.clone(),
+        );
+    }
+
+    fn render_coverage(&self, frame: &mut Frame, area: Rect) {
+        let title = match self.selected_plan() {
+            Some(plan) => tf("insurance.coverage_of", &[("plan", &plan.name)]),
+            None => t("insurance.coverage").to_string(),
+        };
+        let block = self.pane_block(&title, Pane::Coverage);
+        let widths = [
+            Constraint::Min(14),
+            Constraint::Length(10),
+            Constraint::Length(16),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+        let rows: Vec<Row> = SERVICE_CATEGORIES
+            .iter()
+            .map(|category| match self.coverage_for(category) {
+                Some(coverage) => Row::new(vec![
+                    cell(category, &columns, 0),
+                    numeric_cell(format!("{}%", coverage.percent), &columns, 1),
+                    numeric_cell(
+                        coverage
+                            .annual_cap
+                            .map(money)
+                            .unwrap_or_else(|| t("insurance.no_cap").to_string()),
+                        &columns,
+                        2,
+                    ),
+                ])
+                .style(Style::default().fg(theme().text)),
+                None => Row::new(vec![
+                    cell(category, &columns, 0),
+                    numeric_cell("-", &columns, 1),
+                    cell(t("insurance.not_covered"), &columns, 2),
+                ])
+                .style(Style::default().fg(theme().inactive)),
+            })
+            .collect();
+        let header = Row::new(vec![
+            t("insurance.category"),
+            t("insurance.covered"),
+            t("insurance.annual_cap"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let table = Table::new(rows, widths).header(header).block(block);
+        frame.render_stateful_widget(
+            self.highlighted(table, Pane::Coverage),
+            area,
+            &mut self.coverage_state.clone(),
+        );
+    }
+
+    fn render_members(&self, frame: &mut Frame, area: Rect) {
+        let block = self.pane_block(t("insurance.members"), Pane::Members);
+        if self.members.is_empty() {
+            let text = if self.selected_plan().is_some() {
+                t("insurance.no_members")
+            } else {
+                ""
+            };
+            frame.render_widget(
+                Paragraph::new(text)
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        }
+        let widths = [
+            Constraint::Length(8),
+            Constraint::Min(16),
+            Constraint::Percentage(35),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+        let rows: Vec<Row> = self
+            .members
+            .iter()
+            .map(|member| {
+                Row::new(vec![
+                    cell(member.patient_id.to_string(), &columns, 0),
+                    cell(&member.patient_name, &columns, 1),
+                    cell(member.member_number.as_deref().unwrap_or("-"), &columns, 2),
+                ])
+                .style(Style::default().fg(theme().text))
+            })
+            .collect();
+        let header = Row::new(vec![
+            t("insurance.id"),
+            t("insurance.patient"),
+            t("insurance.member_no"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let table = Table::new(rows, widths).header(header).block(block);
+        frame.render_stateful_widget(
+            self.highlighted(table, Pane::Members),
+            area,
+            &mut self.member_state.clone(),
+        );
+    }
+
+    fn render_popup(&self, frame: &mut Frame, popup: &Popup) {
+        let area = frame.area();
+        let height = (popup.form.len() as u16 * 3 + 4).min(area.height);
+        let width = 60.min(area.width);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let plan_name = self
+            .selected_plan()
+            .map(|plan| plan.name.clone())
+            .unwrap_or_default();
+        let title = match &popup.kind {
+            PopupKind::Plan(Some(_)) => format!(" {} ", t("insurance.edit_plan")),
+            PopupKind::Plan(None) => format!(" {} ", t("insurance.new_plan")),
+            PopupKind::Coverage(category) => format!(" {} - {} ", plan_name, category),
+            PopupKind::Enrol => format!(" {} ", tf("insurance.enrol_in", &[("plan", &plan_name)])),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let mut constraints = vec![Constraint::Length(3); popup.form.len()];
+        constraints.push(Constraint::Length(1));
+        constraints.push(Constraint::Min(1));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .horizontal_margin(1)
+            .split(inner);
+        for index in 0..popup.form.len() {
+            popup
+                .form
+                .render_field(frame, index, chunks[index], popup.focus_index);
+        }
+        let hint = match popup.kind {
+            PopupKind::Enrol => t("insurance.enrol_hint"),
+            PopupKind::Coverage(_) => t("insurance.cap_hint"),
+            PopupKind::Plan(_) => "",
+        };
+        frame.render_widget(
+            Paragraph::new(hint).style(Style::default().fg(theme().inactive)),
+            chunks[popup.form.len()],
+        );
+        frame.render_widget(
+            Paragraph::new(t("common.form_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[popup.form.len() + 1],
+        );
+    }
+}
+
+impl Component for InsurancePlans {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(12),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("insurance.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(layout[1]);
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(SERVICE_CATEGORIES.len() as u16 + 3),
+                Constraint::Min(5),
+            ])
+            .split(columns[1]);
+        self.render_plans(frame, columns[0]);
+        self.render_coverage(frame, right[0]);
+        self.render_members(frame, right[1]);
+
+        let help = if self.confirm_delete {
+            match self.pane {
+                Pane::Plans => t("insurance.delete_plan_confirm"),
+                Pane::Coverage => t("insurance.uncover_confirm"),
+                Pane::Members => t("insurance.unenrol_confirm"),
+            }
+        } else {
+            match self.pane {
+                Pane::Plans => t("insurance.plans_help"),
+                Pane::Coverage => t("insurance.coverage_help"),
+                Pane::Members => t("insurance.members_help"),
+            }
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(if self.confirm_delete {
+                    theme().warning
+                } else {
+                    theme().help
+                }))
+                .alignment(Alignment::Center),
+            layout[2],
+        );
+
+        if let Some(popup) = &self.popup {
+            self.render_popup(frame, popup);
+        }
+    }
+}
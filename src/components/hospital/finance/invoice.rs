@@ -1,13 +1,24 @@
 use crate::app::SelectedApp;
+use crate::components::form::{self, Field, Form};
+use crate::components::quick_actions;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::new_patient::{NewPatient, NewPatientOutcome};
+use crate::components::widgets::procedure_picker::{PickerOutcome, ProcedurePicker};
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
-use crate::db;
-use crate::models::{Invoice, Patient};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{
+    discount_percent, Invoice, Patient, PatientInsurance, Procedure, SERVICE_CATEGORIES,
+};
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::ui_state::{self, Recent};
+use crate::utils::{config, datetime, money};
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{prelude::*, widgets::*};
-use std::time::{Duration, Instant};
-use time::OffsetDateTime;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InvoiceState {
@@ -16,58 +27,99 @@ pub enum InvoiceState {
 }
 
 const PATIENT_SELECTION: usize = 0;
-const INVOICE_DETAILS_FIELDS: usize = 3;
-const SUBMIT_BUTTON: usize = 4;
-const BACK_BUTTON: usize = 5;
+const INVOICE_DETAILS_FIELDS: usize = 5;
+const SUBMIT_BUTTON: usize = 5;
+const BACK_BUTTON: usize = 6;
+
+const PROCEDURE: usize = 0;
+const ITEM: usize = 1;
+const CATEGORY: usize = 2;
+const QUANTITY: usize = 3;
+const COST: usize = 4;
+
+fn invoice_form(db: &Db) -> Form {
+    let db = db.clone();
+    Form::new(vec![
+        Field::new(t("invoice.procedure_code"))
+            .validate_with(Box::new(move |value| form::procedure_code(&db, value))),
+        Field::new(t("invoice.description")).required(),
+        Field::new(t("invoice.category_field")).validate_with(form::one_of(&SERVICE_CATEGORIES)),
+        Field::new(t("invoice.quantity"))
+            .required()
+            .validate_with(form::integer_range(1, 10_000)),
+        Field::new(t("invoice.cost"))
+            .required()
+            .validate_with(form::number_range(0.0, 1_000_000.0)),
+    ])
+}
+
+/// Shown instead of saving when the patient was already billed for the same
+/// item today; the invoice is only raised with a note saying why.
+struct DuplicateWarning {
+    invoice: Invoice,
+    matches: Vec<Invoice>,
+    note: TextInput,
+}
+
+/// Shown instead of saving when the price is further below the catalog's
+/// than the user may go; the invoice is then raised held, with the reason,
+/// until an approver decides.
+struct OverrideRequest {
+    invoice: Invoice,
+    list_price: f64,
+    reason: TextInput,
+}
 
 pub struct InvoiceComponent {
+    db: Db,
     all_patients: Vec<Patient>,
     filtered_patients: Vec<Patient>,
     selected_patient: Option<Patient>,
     search_input: String,
     is_searching: bool,
     table_state: TableState,
-    invoice_item: String,
-    invoice_quantity: String,
-    invoice_cost: String,
+    form: Form,
     focus_index: usize,
     state: InvoiceState,
-    error_message: Option<String>,
-    error_timer: Option<Instant>,
-    success_message: Option<String>,
-    success_timer: Option<Instant>,
+    duplicate: Option<DuplicateWarning>,
+    price_override: Option<OverrideRequest>,
+    new_patient: Option<NewPatient>,
+    procedure_picker: Option<ProcedurePicker>,
+    /// The selected patient's insurance plan, if they have one.
+    insurance: Option<PatientInsurance>,
+    /// What the plan would pay of the invoice as entered so far.
+    insurer_share: Option<f64>,
+    /// The unit price of the catalog procedure picked and the cost it last
+    /// filled in, so the cost follows the quantity until it is typed over.
+    catalog_cost: Option<(f64, String)>,
 }
 
-impl Default for InvoiceComponent {
-    fn default() -> Self {
+impl InvoiceComponent {
+    pub fn new(db: &Db) -> Self {
         let mut table_state = TableState::default();
         table_state.select(Some(0));
         InvoiceComponent {
+            db: db.clone(),
             all_patients: Vec::new(),
             filtered_patients: Vec::new(),
             selected_patient: None,
             search_input: String::new(),
             is_searching: false,
             table_state,
-            invoice_item: String::new(),
-            invoice_quantity: String::new(),
-            invoice_cost: String::new(),
+            form: invoice_form(db),
             focus_index: PATIENT_SELECTION,
             state: InvoiceState::SelectingPatient,
-            error_message: None,
-            error_timer: None,
-            success_message: None,
-            success_timer: None,
+            duplicate: None,
+            price_override: None,
+            new_patient: None,
+            procedure_picker: None,
+            insurance: None,
+            insurer_share: None,
+            catalog_cost: None,
         }
     }
-}
-
-impl InvoiceComponent {
-    pub fn new() -> Self {
-        Self::default()
-    }
     pub fn load_patients(&mut self) -> Result<()> {
-        self.all_patients = db::get_all_patients()?;
+        self.all_patients = db::get_all_patients(&self.db)?;
         self.filter_patients();
         Ok(())
     }
@@ -130,51 +182,293 @@ impl InvoiceComponent {
         self.table_state.select(Some(i));
     }
 
-    fn clear_error(&mut self) {
-        self.error_message = None;
-        self.error_timer = None;
+    fn save_invoice(&mut self, invoice: &Invoice) {
+        match self.needs_approval(invoice) {
+            Ok(Some(list_price)) => {
+                self.price_override = Some(OverrideRequest {
+                    invoice: invoice.clone(),
+                    list_price,
+                    reason: TextInput::new(),
+                });
+                return;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                toast::push(Toast::error(tf("common.database_error", &[("error", &e)])));
+                return;
+            }
+        }
+        let user = ui_state::current_user().unwrap_or_default();
+        match db::create_invoice(&self.db, invoice, &user) {
+            Ok(id) => {
+                let split = db::get_invoice(&self.db, id)
+                    .ok()
+                    .filter(|saved| saved.insurer_share.is_some());
+                match split {
+                    Some(saved) => toast::push(Toast::success(tf(
+                        "invoice.created_insured",
+                        &[
+                            ("insurer", &money(saved.insurer_share.unwrap_or_default())),
+                            ("patient", &money(saved.co_pay())),
+                        ],
+                    ))),
+                    None => toast::push(Toast::success(t("invoice.created"))),
+                }
+                quick_actions::saved(SelectedApp::BillingInvoice);
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("common.database_error", &[("error", &e)])));
+                return;
+            }
+        }
+        self.finish_invoice();
     }
 
-    fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_timer = Some(Instant::now());
+    /// Starts over with the next patient once an invoice is raised.
+    fn finish_invoice(&mut self) {
+        self.form.clear();
+        self.catalog_cost = None;
+        self.state = InvoiceState::SelectingPatient;
+        self.focus_index = PATIENT_SELECTION;
+        self.selected_patient = None;
     }
 
-    fn clear_success(&mut self) {
-        self.success_message = None;
-        self.success_timer = None;
+    /// The catalog price of the line `invoice` undercuts by more than the
+    /// current user may discount, if it does.
+    fn needs_approval(&self, invoice: &Invoice) -> Result<Option<f64>> {
+        let user = ui_state::current_user().unwrap_or_default();
+        Ok(db::discount_needing_approval(&self.db, &user, invoice)?
+            .map(|(list_price, _)| list_price))
     }
-    pub fn check_error_timeout(&mut self) {
-        if let Some(timer) = self.error_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_error();
+
+    fn handle_override_input(&mut self, key: KeyEvent) {
+        let Some(request) = &mut self.price_override else {
+            return;
+        };
+        match key.code {
+            _ if request.reason.handle_key(key) => {}
+            KeyCode::Enter => {
+                let reason = request.reason.value().trim().to_string();
+                if reason.is_empty() {
+                    toast::push(Toast::error(t("invoice.discount_reason")));
+                    return;
+                }
+                let user = ui_state::current_user().unwrap_or_default();
+                match db::request_price_override(&self.db, &request.invoice, &reason, &user) {
+                    Ok(_) => {
+                        toast::push(Toast::success(tf(
+                            "invoice.held",
+                            &[(
+                                "percent",
+                                &format!(
+                                    "{:.1}",
+                                    discount_percent(request.list_price, request.invoice.cost)
+                                ),
+                            )],
+                        )));
+                        self.price_override = None;
+                        quick_actions::saved(SelectedApp::BillingInvoice);
+                        self.finish_invoice();
+                    }
+                    Err(e) => {
+                        toast::push(Toast::error(tf("common.database_error", &[("error", &e)])))
+                    }
+                }
             }
+            KeyCode::Esc => self.price_override = None,
+            _ => {}
         }
     }
 
-    pub fn check_success_timeout(&mut self) {
-        if let Some(timer) = self.success_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_success();
+    fn handle_duplicate_input(&mut self, key: KeyEvent) {
+        let Some(duplicate) = &mut self.duplicate else {
+            return;
+        };
+        match key.code {
+            _ if duplicate.note.handle_key(key) => {}
+            KeyCode::Enter => {
+                let note = duplicate.note.value().trim().to_string();
+                if note.is_empty() {
+                    toast::push(Toast::error(t("invoice.rebill_reason")));
+                    return;
+                }
+                let mut invoice = duplicate.invoice.clone();
+                invoice.duplicate_note = Some(note);
+                self.duplicate = None;
+                self.save_invoice(&invoice);
             }
+            KeyCode::Esc => self.duplicate = None,
+            _ => {}
+        }
+    }
+
+    /// Selects `patient`, just added or picked from the recent ones, clearing
+    /// any search that would hide them.
+    fn select_patient(&mut self, patient: Patient) {
+        self.search_input.clear();
+        self.is_searching = false;
+        if let Err(e) = self.load_patients() {
+            toast::push(Toast::error(tf(
+                "common.reload_patients_failed",
+                &[("error", &e)],
+            )));
+        }
+        let row = self
+            .filtered_patients
+            .iter()
+            .position(|p| p.id == patient.id);
+        self.table_state.select(row);
+        self.selected_patient = Some(patient);
+        self.focus_index = PATIENT_SELECTION;
+    }
+
+    /// Patients opened recently, most recent first.
+    fn recent_patients(&self) -> Vec<&Patient> {
+        ui_state::recent_patients()
+            .into_iter()
+            .filter_map(|id| self.all_patients.iter().find(|p| p.id == id))
+            .collect()
+    }
+
+    fn open_procedure_picker(&mut self) {
+        match db::get_procedures(&self.db) {
+            Ok(catalog) => self.procedure_picker = Some(ProcedurePicker::new(catalog)),
+            Err(e) => toast::push(Toast::error(tf(
+                "invoice.procedures_failed",
+                &[("error", &e)],
+            ))),
         }
     }
-    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
-        self.check_error_timeout();
-        self.check_success_timeout();
 
+    /// The invoice as entered so far, for `patient`.
+    fn entered_invoice(&self, patient_id: i64) -> Invoice {
+        let typed = self.form.value(CATEGORY);
+        let category = SERVICE_CATEGORIES
+            .iter()
+            .find(|c| c.eq_ignore_ascii_case(typed.trim()))
+            .map(|c| c.to_string());
+        Invoice {
+            id: 0,
+            patient_id,
+            item: self.form.value(ITEM),
+            quantity: self.form.value(QUANTITY).parse::<i32>().unwrap_or(1),
+            cost: self.form.value(COST).parse::<f64>().unwrap_or(0.0),
+            created_at: None,
+            duplicate_note: None,
+            procedure_code: self.form.optional_value(PROCEDURE),
+            category,
+            insurer_share: None,
+            held: false,
+        }
+    }
+
+    /// Looks up the selected patient's plan as details entry starts.
+    fn load_insurance(&mut self) {
+        self.insurance = None;
+        self.insurer_share = None;
+        let Some(patient) = &self.selected_patient else {
+            return;
+        };
+        match db::get_patient_insurance(&self.db, patient.id) {
+            Ok(insurance) => self.insurance = insurance,
+            Err(e) => toast::push(Toast::error(tf(
+                "invoice.insurance_failed",
+                &[("error", &e)],
+            ))),
+        }
+    }
+
+    /// Works out the insurer's share again after the form changed.
+    fn refresh_insurer_share(&mut self) {
+        self.insurer_share = None;
+        let (Some(_), Some(patient)) = (&self.insurance, &self.selected_patient) else {
+            return;
+        };
+        let invoice = self.entered_invoice(patient.id);
+        self.insurer_share = db::preview_insurer_share(&self.db, &invoice).ok().flatten();
+    }
+
+    /// Bills `procedure` at its catalog name and price, taxed as configured.
+    fn fill_procedure(&mut self, procedure: &Procedure) {
+        self.form.fields[PROCEDURE].set(procedure.code.clone());
+        self.form.fields[ITEM].set(procedure.name.clone());
+        if self.form.value(QUANTITY).is_empty() {
+            self.form.fields[QUANTITY].set("1");
+        }
+        let price = config::config().invoice.billed(procedure.price);
+        self.catalog_cost = Some((price, String::new()));
+        self.fill_catalog_cost();
+        self.refresh_insurer_share();
+    }
+
+    /// Sets the cost to the catalog price of the quantity entered, unless
+    /// the cost was typed over since it was last filled in.
+    fn fill_catalog_cost(&mut self) {
+        let Some((price, filled)) = &self.catalog_cost else {
+            return;
+        };
+        if !filled.is_empty() && self.form.value(COST) != *filled {
+            return;
+        }
+        let Ok(quantity) = self.form.value(QUANTITY).parse::<i32>() else {
+            return;
+        };
+        let cost = format!("{:.2}", price * f64::from(quantity.max(1)));
+        self.form.fields[COST].set(cost.clone());
+        self.catalog_cost = Some((*price, cost));
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(picker) = &mut self.procedure_picker {
+            match picker.handle_key(key) {
+                Some(PickerOutcome::Picked(procedure)) => {
+                    self.procedure_picker = None;
+                    self.fill_procedure(&procedure);
+                }
+                Some(PickerOutcome::Cancelled) => self.procedure_picker = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+        if let Some(popup) = &mut self.new_patient {
+            match popup.handle_key(key) {
+                Some(NewPatientOutcome::Created(patient)) => {
+                    self.new_patient = None;
+                    self.select_patient(*patient);
+                }
+                Some(NewPatientOutcome::Cancelled) => self.new_patient = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+        if self.duplicate.is_some() {
+            self.handle_duplicate_input(key);
+            return Ok(None);
+        }
+        if self.price_override.is_some() {
+            self.handle_override_input(key);
+            return Ok(None);
+        }
         match self.state {
             InvoiceState::SelectingPatient => {
                 match key.code {
+                    KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.new_patient = Some(NewPatient::new(&self.db));
+                    }
+                    KeyCode::Char(digit @ '1'..='9') if !self.is_searching => {
+                        let index = digit as usize - '1' as usize;
+                        if let Some(patient) = self.recent_patients().get(index) {
+                            let patient = (*patient).clone();
+                            self.select_patient(patient);
+                        }
+                    }
                     KeyCode::Char(c) if self.is_searching => {
                         self.search_input.push(c);
                         self.filter_patients();
-                        self.clear_error();
                     }
                     KeyCode::Backspace if self.is_searching => {
                         self.search_input.pop();
                         self.filter_patients();
-                        self.clear_error();
                     }
                     KeyCode::Down if self.is_searching && !self.filtered_patients.is_empty() => {
                         self.is_searching = false;
@@ -230,19 +524,21 @@ impl InvoiceComponent {
                                 if selected < self.filtered_patients.len() {
                                     if let Some(patient) = &self.selected_patient {
                                         if patient.id == self.filtered_patients[selected].id {
+                                            ui_state::add_recent(Recent::patient(patient));
                                             self.state = InvoiceState::EnteringDetails;
                                             self.focus_index = 0;
+                                            self.load_insurance();
                                             return Ok(None);
                                         } else {
-                                            self.set_error(
-                                                "Please Select Patient with Spacebar".to_string(),
-                                            );
+                                            toast::push(Toast::error(
+                                                t("common.select_with_spacebar").to_string(),
+                                            ));
                                             return Ok(None);
                                         }
                                     } else {
-                                        self.set_error(
-                                            "Please Select Patient with Spacebar".to_string(),
-                                        );
+                                        toast::push(Toast::error(
+                                            t("common.select_with_spacebar").to_string(),
+                                        ));
                                         return Ok(None);
                                     }
                                 }
@@ -255,24 +551,20 @@ impl InvoiceComponent {
                 return Ok(None);
             }
             InvoiceState::EnteringDetails => match key.code {
-                KeyCode::Char(c) => match self.focus_index {
-                    0 => self.invoice_item.push(c),
-                    1 => self.invoice_quantity.push(c),
-                    2 => self.invoice_cost.push(c),
-                    _ => {}
-                },
-                KeyCode::Backspace => match self.focus_index {
-                    0 => {
-                        self.invoice_item.pop();
-                    }
-                    1 => {
-                        self.invoice_quantity.pop();
+                _ if self.form.handle_key(self.focus_index, key) => {
+                    match self.focus_index {
+                        QUANTITY => self.fill_catalog_cost(),
+                        PROCEDURE => self.catalog_cost = None,
+                        _ => {}
                     }
-                    2 => {
-                        self.invoice_cost.pop();
-                    }
-                    _ => {}
-                },
+                    self.refresh_insurer_share();
+                }
+                KeyCode::Char('f')
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && self.focus_index == PROCEDURE =>
+                {
+                    self.open_procedure_picker();
+                }
                 KeyCode::Tab => {
                     self.focus_index = (self.focus_index + 1) % (INVOICE_DETAILS_FIELDS + 2);
                 }
@@ -289,57 +581,35 @@ impl InvoiceComponent {
                     return Ok(None);
                 }
                 KeyCode::Enter if self.focus_index == SUBMIT_BUTTON => {
-                    if self.invoice_item.is_empty() {
-                        self.set_error("Invoice Item cannot be empty".to_string());
-                        return Ok(None);
-                    }
-                    if self.invoice_quantity.is_empty() {
-                        self.set_error("Invoice Quantity cannot be empty".to_string());
-                        return Ok(None);
+                    if let Some(category) = self.entered_invoice(0).category {
+                        self.form.fields[CATEGORY].set(category);
                     }
-                    if self.invoice_cost.is_empty() {
-                        self.set_error("Invoice Cost cannot be empty".to_string());
-                        return Ok(None);
-                    }
-                    if self.invoice_quantity.parse::<i32>().is_err() {
-                        self.set_error(
-                            "Invalid quantity. Please enter a valid number.".to_string(),
-                        );
-                        return Ok(None);
-                    }
-                    if self.invoice_cost.parse::<f64>().is_err() {
-                        self.set_error("Invalid cost. Please enter a valid number.".to_string());
+                    if let Some(invalid) = self.form.validate() {
+                        self.focus_index = invalid;
+                        toast::push(Toast::error(t("common.fix_fields")));
                         return Ok(None);
                     }
                     if let Some(patient) = &self.selected_patient {
-                        let new_invoice = Invoice {
-                            id: 0,
-                            patient_id: patient.id,
-                            item: self.invoice_item.clone(),
-                            quantity: self.invoice_quantity.parse::<i32>().unwrap(),
-                            cost: self.invoice_cost.parse::<f64>().unwrap(),
-                        };
-                        match db::create_invoice(&new_invoice) {
-                            Ok(_) => {
-                                self.success_message =
-                                    Some("Invoice created successfully!".to_string());
-                                self.success_timer = Some(Instant::now());
+                        let new_invoice = self.entered_invoice(patient.id);
+                        match db::find_duplicate_invoices(&self.db, &new_invoice) {
+                            Ok(matches) if !matches.is_empty() => {
+                                self.duplicate = Some(DuplicateWarning {
+                                    invoice: new_invoice,
+                                    matches,
+                                    note: TextInput::new(),
+                                });
                             }
+                            Ok(_) => self.save_invoice(&new_invoice),
                             Err(e) => {
-                                self.set_error(format!("Database error: {}", e));
-                                return Ok(None);
+                                toast::push(Toast::error(tf(
+                                    "common.database_error",
+                                    &[("error", &e)],
+                                )));
                             }
                         }
-                        self.invoice_item.clear();
-                        self.invoice_quantity.clear();
-                        self.invoice_cost.clear();
-                        self.state = InvoiceState::SelectingPatient;
-                        self.focus_index = PATIENT_SELECTION;
-                        self.selected_patient = None;
-                        self.clear_error();
                         return Ok(None);
                     } else {
-                        self.set_error("Please select a patient first.".to_string());
+                        toast::push(Toast::error(t("invoice.select_first")));
                         return Ok(None);
                     }
                 }
@@ -362,7 +632,7 @@ impl Component for InvoiceComponent {
     }
     fn render(&self, frame: &mut Frame) {
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             frame.area(),
         );
         match self.state {
@@ -373,6 +643,18 @@ impl Component for InvoiceComponent {
                 self.render_invoice_details_page(frame);
             }
         }
+        if let Some(duplicate) = &self.duplicate {
+            self.render_duplicate_warning(frame, duplicate);
+        }
+        if let Some(request) = &self.price_override {
+            self.render_override_request(frame, request);
+        }
+        if let Some(popup) = &self.new_patient {
+            popup.render_popup(frame);
+        }
+        if let Some(picker) = &self.procedure_picker {
+            picker.render_popup(frame);
+        }
     }
 }
 impl InvoiceComponent {
@@ -393,31 +675,30 @@ impl InvoiceComponent {
             .split(area);
         let header = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header, layout[0]);
-        let title = Paragraph::new("🧾 SELECT PATIENT FOR INVOICE")
+        let title = Paragraph::new(t("invoice.select_title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
         self.render_patient_selection_content(frame, layout[1]);
-        self.render_status_message(frame, layout[2]);
         let back_text = if self.focus_index == BACK_BUTTON {
-            "► Back ◄"
+            format!("► {} ◄", t("common.back"))
         } else {
-            "  Back  "
+            format!("  {}  ", t("common.back"))
         };
         let back_style = if self.focus_index == BACK_BUTTON {
             Style::default()
-                .fg(Color::Rgb(129, 199, 245))
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
         frame.render_widget(
             Paragraph::new(back_text)
@@ -426,13 +707,26 @@ impl InvoiceComponent {
             layout[3],
         );
         frame.render_widget(
-            Paragraph::new(
-                "/ or s: Search, ↑/↓: Navigate | Spacebar: Select | Enter: Confirm | Tab: Back | Esc: Exit"
-            )
-            .style(Style::default().fg(Color::Rgb(180, 180, 200)))
-            .alignment(Alignment::Center),
+            Paragraph::new(t("invoice.select_help"))
+                .style(Style::default().fg(theme().inactive))
+                .alignment(Alignment::Center),
             layout[6],
         );
+
+        let recent: Vec<String> = self
+            .recent_patients()
+            .iter()
+            .enumerate()
+            .map(|(index, p)| format!("{}: {} {}", index + 1, p.first_name, p.last_name))
+            .collect();
+        if !recent.is_empty() {
+            frame.render_widget(
+                Paragraph::new(tf("common.recent", &[("list", &(recent.join(" | ")))]))
+                    .style(Style::default().fg(theme().help))
+                    .alignment(Alignment::Center),
+                layout[5],
+            );
+        }
     }
 
     fn render_patient_selection_content(&self, frame: &mut Frame, area: Rect) {
@@ -444,21 +738,21 @@ impl InvoiceComponent {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Patients ",
+                format!(" {} ", t("common.search_patients")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(
                 if self.is_searching && self.focus_index == PATIENT_SELECTION {
-                    Style::default().fg(Color::Rgb(250, 250, 110))
+                    Style::default().fg(theme().focus)
                 } else {
-                    Style::default().fg(Color::Rgb(75, 75, 120))
+                    Style::default().fg(theme().border)
                 },
             )
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .style(Style::default().bg(theme().surface));
         let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .block(search_block);
         frame.render_widget(search_paragraph, content_layout[0]);
         let table_block = Block::default()
@@ -466,33 +760,50 @@ impl InvoiceComponent {
             .border_type(BorderType::Rounded)
             .title(if !self.search_input.is_empty() {
                 format!(
-                    " Select Patient ({} of {} matches) ",
-                    self.filtered_patients.len(),
-                    self.all_patients.len()
+                    " {} ",
+                    tf(
+                        "common.select_patient_matches",
+                        &[
+                            ("shown", &self.filtered_patients.len()),
+                            ("total", &self.all_patients.len())
+                        ]
+                    )
                 )
             } else {
-                format!(" Select Patient ({}) ", self.all_patients.len())
+                format!(
+                    " {} ",
+                    tf(
+                        "common.select_patient_count",
+                        &[("total", &self.all_patients.len())]
+                    )
+                )
             })
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .border_style(
                 if self.focus_index == PATIENT_SELECTION && !self.is_searching {
-                    Style::default().fg(Color::Rgb(250, 250, 110))
+                    Style::default().fg(theme().focus)
                 } else {
-                    Style::default().fg(Color::Rgb(140, 140, 200))
+                    Style::default().fg(theme().unfocused)
                 },
             )
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
         let selected_style = Style::default()
-            .bg(Color::Rgb(45, 45, 60))
-            .fg(Color::Rgb(250, 250, 110))
+            .bg(theme().highlight)
+            .fg(theme().focus)
             .add_modifier(Modifier::BOLD);
-        let normal_style = Style::default()
-            .bg(Color::Rgb(26, 26, 36))
-            .fg(Color::Rgb(220, 220, 240));
+        let normal_style = Style::default().bg(theme().input).fg(theme().text);
+        let widths = [
+            Constraint::Length(3),
+            Constraint::Length(8),
+            Constraint::Length(15),
+            Constraint::Length(15),
+            Constraint::Min(15),
+        ];
+        let columns = column_widths(content_layout[1].inner(Margin::new(1, 1)), &widths, 1, "► ");
         let mut rows = Vec::new();
         for patient in &self.filtered_patients {
             let selected_indicator = if let Some(selected) = &self.selected_patient {
@@ -506,54 +817,44 @@ impl InvoiceComponent {
             };
             rows.push(Row::new(vec![
                 Cell::from(selected_indicator.to_string()).style(normal_style),
-                Cell::from(patient.id.to_string()).style(normal_style),
-                Cell::from(patient.first_name.clone()).style(normal_style),
-                Cell::from(patient.last_name.clone()).style(normal_style),
-                Cell::from(patient.phone_number.clone()).style(normal_style),
+                cell(patient.id.to_string(), &columns, 1).style(normal_style),
+                cell(&patient.first_name, &columns, 2).style(normal_style),
+                cell(&patient.last_name, &columns, 3).style(normal_style),
+                cell(&patient.phone_number, &columns, 4).style(normal_style),
             ]));
         }
         if self.filtered_patients.is_empty() {
             let message = if self.search_input.is_empty() {
-                "No patients found in database"
+                t("common.no_patients")
             } else {
-                "No patients match your search criteria"
+                t("common.no_patient_matches")
             };
             rows.push(Row::new(vec![
                 Cell::from(""),
                 Cell::from(""),
-                Cell::from(message).style(Style::default().fg(Color::Rgb(180, 180, 200))),
+                Cell::from(message).style(Style::default().fg(theme().inactive)),
                 Cell::from(""),
                 Cell::from(""),
             ]));
         }
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(3),
-                Constraint::Length(8),
-                Constraint::Length(15),
-                Constraint::Length(15),
-                Constraint::Min(15),
-            ],
-        )
-        .header(
-            Row::new(vec![
-                Cell::from(""),
-                Cell::from("ID").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("First Name").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Last Name").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Phone").style(Style::default().add_modifier(Modifier::BOLD)),
-            ])
-            .style(
-                Style::default()
-                    .bg(Color::Rgb(80, 60, 130))
-                    .fg(Color::Rgb(180, 180, 250)),
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec![
+                    Cell::from(""),
+                    Cell::from(t("common.id")).style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.first_name"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.last_name"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.phone"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                ])
+                .style(Style::default().bg(theme().header).fg(theme().title))
+                .height(1),
             )
-            .height(1),
-        )
-        .block(table_block)
-        .row_highlight_style(selected_style)
-        .highlight_symbol("► ");
+            .block(table_block)
+            .row_highlight_style(selected_style)
+            .highlight_symbol("► ");
         frame.render_stateful_widget(table, content_layout[1], &mut self.table_state.clone());
     }
 
@@ -563,7 +864,7 @@ impl InvoiceComponent {
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),
-                Constraint::Length(12),
+                Constraint::Length(18),
                 Constraint::Length(1),
                 Constraint::Length(1),
                 Constraint::Length(1),
@@ -577,31 +878,33 @@ impl InvoiceComponent {
             .split(area);
         let header = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header, layout[0]);
-        let title = Paragraph::new("🧾 ADD INVOICE DETAILS")
+        let title = Paragraph::new(t("invoice.details_title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
         self.render_invoice_form_fields(frame, layout[1]);
-        self.render_status_message(frame, layout[3]);
+        if let Some(line) = self.insurance_line() {
+            frame.render_widget(line.alignment(Alignment::Center), layout[2]);
+        }
         let submit_text = if self.focus_index == SUBMIT_BUTTON {
-            "► Add Invoice ◄"
+            format!("► {} ◄", t("invoice.add"))
         } else {
-            "  Add Invoice  "
+            format!("  {}  ", t("invoice.add"))
         };
         let submit_style = if self.focus_index == SUBMIT_BUTTON {
             Style::default()
-                .fg(Color::Rgb(140, 219, 140))
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
         frame.render_widget(
             Paragraph::new(submit_text)
@@ -610,16 +913,16 @@ impl InvoiceComponent {
             layout[4],
         );
         let back_text = if self.focus_index == BACK_BUTTON {
-            "► Back ◄"
+            format!("► {} ◄", t("common.back"))
         } else {
-            "  Back  "
+            format!("  {}  ", t("common.back"))
         };
         let back_style = if self.focus_index == BACK_BUTTON {
             Style::default()
-                .fg(Color::Rgb(129, 199, 245))
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
         frame.render_widget(
             Paragraph::new(back_text)
@@ -628,8 +931,8 @@ impl InvoiceComponent {
             layout[6],
         );
         frame.render_widget(
-            Paragraph::new("Tab: Switch Focus, ↑/↓: Navigate | Enter: Submit | Esc: Back")
-                .style(Style::default().fg(Color::Rgb(180, 180, 200)))
+            Paragraph::new(t("invoice.help"))
+                .style(Style::default().fg(theme().inactive))
                 .alignment(Alignment::Center),
             layout[9],
         );
@@ -643,110 +946,214 @@ impl InvoiceComponent {
                 Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
             ])
             .horizontal_margin(3)
             .split(area);
-        let required_style = Style::default().fg(Color::Rgb(230, 230, 250));
-        let invoice_item_input = Paragraph::new(self.invoice_item.clone())
-            .style(if self.focus_index == 0 {
-                Style::default().fg(Color::Yellow)
-            } else {
-                Style::default().fg(Color::Rgb(220, 220, 240))
-            })
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Item Description* ", required_style))
-                    .border_style(if self.focus_index == 0 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(invoice_item_input, form_layout[0]);
-        let invoice_quantity_input = Paragraph::new(self.invoice_quantity.clone())
-            .style(if self.focus_index == 1 {
-                Style::default().fg(Color::Yellow)
-            } else {
-                Style::default().fg(Color::Rgb(220, 220, 240))
-            })
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Quantity* ", required_style))
-                    .border_style(if self.focus_index == 1 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(invoice_quantity_input, form_layout[1]);
-        let invoice_cost_input = Paragraph::new(self.invoice_cost.clone())
-            .style(if self.focus_index == 2 {
-                Style::default().fg(Color::Yellow)
-            } else {
-                Style::default().fg(Color::Rgb(220, 220, 240))
-            })
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Cost* ", required_style))
-                    .border_style(if self.focus_index == 2 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(invoice_cost_input, form_layout[2]);
-        let now = OffsetDateTime::now_utc();
-        let formatted_date = format!(
-            "  {}",
-            now.format(&time::format_description::parse("[year]-[month]-[day]").unwrap())
-                .unwrap()
-        );
+        for index in 0..self.form.len() {
+            self.form
+                .render_field(frame, index, form_layout[index], self.focus_index);
+        }
+        let formatted_date = format!("  {}", datetime::display_date(datetime::today()));
         let time_date_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Time & Date ",
-                Style::default().fg(Color::Rgb(230, 230, 250)),
+                format!(" {} ", t("common.time_date")),
+                Style::default().fg(theme().title),
             ))
-            .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .border_style(Style::default().fg(theme().unfocused))
+            .style(Style::default().bg(theme().input));
         let time_date_paragraph = Paragraph::new(formatted_date)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .block(time_date_block);
-        frame.render_widget(time_date_paragraph, form_layout[3]);
+        frame.render_widget(time_date_paragraph, form_layout[5]);
     }
 
-    fn render_status_message(&self, frame: &mut Frame, area: Rect) {
-        let status_message = if let Some(success) = &self.success_message {
-            Paragraph::new(format!("✓ {}", success))
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD)
-                        .bg(Color::Rgb(16, 16, 28)),
-                )
-                .alignment(Alignment::Center)
-        } else if let Some(error) = &self.error_message {
-            Paragraph::new(format!("⚠️ {}", error))
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(255, 100, 100))
-                        .add_modifier(Modifier::BOLD)
-                        .bg(Color::Rgb(16, 16, 28)),
-                )
-                .alignment(Alignment::Center)
-        } else {
-            Paragraph::new("").style(Style::default().bg(Color::Rgb(16, 16, 28)))
-        };
-        frame.render_widget(status_message, area);
+    /// The patient's plan and how the invoice would be split with it.
+    fn insurance_line(&self) -> Option<Paragraph<'static>> {
+        let insurance = self.insurance.as_ref()?;
+        let mut spans = vec![Span::styled(
+            format!("🛡 {}", insurance.plan_name),
+            Style::default().fg(theme().accent),
+        )];
+        if let Some(share) = self.insurer_share {
+            let cost = self.form.value(COST).parse::<f64>().unwrap_or(0.0);
+            spans.push(Span::raw("  ·  "));
+            spans.push(Span::styled(
+                tf(
+                    "invoice.insurer_pays",
+                    &[
+                        ("insurer", &money(share)),
+                        ("patient", &(money(cost - share))),
+                    ],
+                ),
+                Style::default()
+                    .fg(theme().text)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        Some(Paragraph::new(Line::from(spans)))
+    }
+
+    fn render_duplicate_warning(&self, frame: &mut Frame, duplicate: &DuplicateWarning) {
+        let area = frame.area();
+        let shown = duplicate.matches.len().min(5) as u16;
+        let width = 70.min(area.width);
+        let height = (shown + 8).min(area.height);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" {} ", t("invoice.duplicate_title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().warning))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(shown + 1),
+                Constraint::Length(3),
+                Constraint::Min(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        let patient = self
+            .selected_patient
+            .as_ref()
+            .map(|p| format!("{} {}", p.first_name, p.last_name))
+            .unwrap_or_default();
+        frame.render_widget(
+            Paragraph::new(tf(
+                "invoice.already_billed",
+                &[("patient", &patient), ("item", &duplicate.invoice.item)],
+            ))
+            .style(Style::default().fg(theme().warning)),
+            layout[0],
+        );
+        let lines: Vec<Line> = duplicate
+            .matches
+            .iter()
+            .take(5)
+            .map(|invoice| {
+                let time = invoice
+                    .created_at
+                    .as_deref()
+                    .map_or("--:--".to_string(), datetime::time_part);
+                Line::from(format!(
+                    "  #{}  {}  x{}  {}",
+                    invoice.id,
+                    time,
+                    invoice.quantity,
+                    money(invoice.cost)
+                ))
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).style(Style::default().fg(theme().text)),
+            layout[1],
+        );
+        frame.render_widget(
+            Paragraph::new(duplicate.note.line(true))
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("invoice.rebill_title")))
+                        .border_style(Style::default().fg(theme().focus))
+                        .style(Style::default().bg(theme().input)),
+                ),
+            layout[2],
+        );
+        frame.render_widget(
+            Paragraph::new(t("invoice.rebill_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+    }
+
+    fn render_override_request(&self, frame: &mut Frame, request: &OverrideRequest) {
+        let area = frame.area();
+        let width = 70.min(area.width);
+        let height = 9.min(area.height);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" {} ", t("invoice.discount_title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().warning))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        let invoice = &request.invoice;
+        frame.render_widget(
+            Paragraph::new(tf(
+                "invoice.discount_explain",
+                &[
+                    ("item", &invoice.item),
+                    ("price", &money(invoice.cost)),
+                    (
+                        "percent",
+                        &format!("{:.1}", discount_percent(request.list_price, invoice.cost)),
+                    ),
+                    ("list_price", &money(request.list_price)),
+                    ("limit", &config::config().invoice.discount_limit),
+                ],
+            ))
+            .style(Style::default().fg(theme().warning))
+            .wrap(Wrap { trim: true }),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(request.reason.line(true))
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("invoice.discount_reason_title")))
+                        .border_style(Style::default().fg(theme().focus))
+                        .style(Style::default().bg(theme().input)),
+                ),
+            layout[1],
+        );
+        frame.render_widget(
+            Paragraph::new(t("invoice.discount_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[2],
+        );
     }
 }
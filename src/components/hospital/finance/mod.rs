@@ -1,9 +1,17 @@
 use crate::app::SelectedApp;
 use crate::components::Component;
+use crate::db::Db;
 use crate::tui::Frame;
 use anyhow::Result;
 use crossterm::event::KeyEvent;
 
+pub mod approvals;
+pub mod budget;
+pub mod cashier;
+pub mod cashier_history;
+pub mod expense_report;
+pub mod expenses;
+pub mod insurance;
 pub mod invoice;
 pub mod update;
 pub mod view;
@@ -13,6 +21,13 @@ pub enum FinanceState {
     Invoice,
     View,
     Update,
+    Expenses,
+    ExpenseReport,
+    Budget,
+    Cashier,
+    CashierHistory,
+    Insurance,
+    Approvals,
 }
 
 pub struct Finance {
@@ -20,26 +35,40 @@ pub struct Finance {
     pub invoice: invoice::InvoiceComponent,
     pub view_invoices: view::ViewInvoices,
     pub update_invoice: update::UpdateInvoice,
+    pub expenses: expenses::Expenses,
+    pub expense_report: expense_report::ExpenseReport,
+    pub budget: budget::BudgetReport,
+    pub cashier: cashier::CashDrawer,
+    pub cashier_history: cashier_history::CashierHistory,
+    pub insurance: insurance::InsurancePlans,
+    pub approvals: approvals::PriceApprovals,
 }
 
 impl Finance {
-    pub fn new() -> Self {
-        let mut invoice = invoice::InvoiceComponent::new();
-        invoice.load_patients().expect("Failed to load patients");
-        let mut view_invoices = view::ViewInvoices::new();
-        view_invoices
-            .fetch_invoices()
-            .expect("Failed to fetch invoices");
-        let mut update_invoice = update::UpdateInvoice::new();
-        update_invoice
-            .fetch_invoices()
-            .expect("Failed to fetch invoices");
-        Self {
+    pub fn new(db: &Db) -> Result<Self> {
+        let mut invoice = invoice::InvoiceComponent::new(db);
+        invoice.load_patients()?;
+        let mut view_invoices = view::ViewInvoices::new(db);
+        view_invoices.fetch_invoices()?;
+        let mut update_invoice = update::UpdateInvoice::new(db);
+        update_invoice.fetch_invoices()?;
+        Ok(Self {
             state: FinanceState::Invoice,
             invoice,
             view_invoices,
             update_invoice,
-        }
+            expenses: expenses::Expenses::new(db),
+            expense_report: expense_report::ExpenseReport::new(db),
+            budget: budget::BudgetReport::new(db),
+            cashier: cashier::CashDrawer::new(db),
+            cashier_history: cashier_history::CashierHistory::new(db),
+            insurance: insurance::InsurancePlans::new(db),
+            approvals: approvals::PriceApprovals::new(db),
+        })
+    }
+
+    pub fn save_view(&self) {
+        self.view_invoices.save_view();
     }
 
     pub fn set_finance_state(&mut self, state: FinanceState) {
@@ -60,6 +89,41 @@ impl Finance {
                     eprintln!("Error initializing invoice list on update: {}", e);
                 }
             }
+            FinanceState::Expenses => {
+                if let Err(e) = self.expenses.load_data() {
+                    eprintln!("Error initializing expenses: {}", e);
+                }
+            }
+            FinanceState::ExpenseReport => {
+                if let Err(e) = self.expense_report.load_data() {
+                    eprintln!("Error initializing expense report: {}", e);
+                }
+            }
+            FinanceState::Budget => {
+                if let Err(e) = self.budget.load_data() {
+                    eprintln!("Error initializing budget report: {}", e);
+                }
+            }
+            FinanceState::Cashier => {
+                if let Err(e) = self.cashier.load_data() {
+                    eprintln!("Error initializing cash drawer: {}", e);
+                }
+            }
+            FinanceState::CashierHistory => {
+                if let Err(e) = self.cashier_history.load_data() {
+                    eprintln!("Error initializing cashier sessions: {}", e);
+                }
+            }
+            FinanceState::Insurance => {
+                if let Err(e) = self.insurance.load_data() {
+                    eprintln!("Error initializing insurance plans: {}", e);
+                }
+            }
+            FinanceState::Approvals => {
+                if let Err(e) = self.approvals.load_data() {
+                    eprintln!("Error initializing price approvals: {}", e);
+                }
+            }
         }
     }
 }
@@ -82,6 +146,41 @@ impl Component for Finance {
                     return Ok(Some(action));
                 }
             }
+            FinanceState::Expenses => {
+                if let Some(action) = self.expenses.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            FinanceState::ExpenseReport => {
+                if let Some(action) = self.expense_report.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            FinanceState::Budget => {
+                if let Some(action) = self.budget.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            FinanceState::Cashier => {
+                if let Some(action) = self.cashier.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            FinanceState::CashierHistory => {
+                if let Some(action) = self.cashier_history.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            FinanceState::Insurance => {
+                if let Some(action) = self.insurance.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            FinanceState::Approvals => {
+                if let Some(action) = self.approvals.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
         }
         Ok(None)
     }
@@ -91,6 +190,13 @@ impl Component for Finance {
             FinanceState::Invoice => self.invoice.render(frame),
             FinanceState::View => self.view_invoices.render(frame),
             FinanceState::Update => self.update_invoice.render(frame),
+            FinanceState::Expenses => self.expenses.render(frame),
+            FinanceState::ExpenseReport => self.expense_report.render(frame),
+            FinanceState::Budget => self.budget.render(frame),
+            FinanceState::Cashier => self.cashier.render(frame),
+            FinanceState::CashierHistory => self.cashier_history.render(frame),
+            FinanceState::Insurance => self.insurance.render(frame),
+            FinanceState::Approvals => self.approvals.render(frame),
         }
     }
 }
@@ -1,16 +1,29 @@
 use crate::app::SelectedApp;
+use crate::components::form;
+use crate::components::table::{cell, column_widths, numeric_cell};
+use crate::components::toast::{self, Toast};
+use crate::components::undo::EditHistory;
+use crate::components::widgets::discard_prompt::DiscardPrompt;
+use crate::components::widgets::edit_warning;
+use crate::components::widgets::procedure_picker::{PickerOutcome, ProcedurePicker};
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
-use crate::db;
-use crate::models::{Invoice, Patient};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::locks::EditLock;
+use crate::models::{Invoice, Patient, Procedure, SERVICE_CATEGORIES};
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::ui_state;
+use crate::utils::config;
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{prelude::*, widgets::*};
 use std::collections::HashMap;
-use std::time::{Duration, Instant};
 
 enum ConfirmAction {
     UpdateInvoice,
+    DiscardChanges,
 }
 
 enum UpdateState {
@@ -19,28 +32,29 @@ enum UpdateState {
 }
 
 pub struct UpdateInvoice {
+    db: Db,
     all_invoices: Vec<Invoice>,
     filtered_invoices: Vec<Invoice>,
     patients: HashMap<i64, Patient>,
-    search_input: String,
+    search_input: TextInput,
     is_searching: bool,
     table_state: TableState,
     update_state: UpdateState,
     invoice_id_input: String,
     invoice: Invoice,
+    history: EditHistory<Invoice>,
     loaded: bool,
+    edit_lock: Option<EditLock>,
     selected_field: Option<usize>,
     edit_table_state: TableState,
     input_value: String,
     editing: bool,
-    error_message: Option<String>,
-    error_timer: Option<Instant>,
-    success_message: Option<String>,
-    success_timer: Option<Instant>,
     show_confirmation: bool,
     confirmation_message: String,
     confirmed_action: Option<ConfirmAction>,
     confirmation_selected: usize,
+    discard_prompt: Option<DiscardPrompt>,
+    procedure_picker: Option<ProcedurePicker>,
 }
 
 const ID_INPUT: usize = 0;
@@ -48,10 +62,12 @@ const PATIENT_ID_INPUT: usize = 1;
 const ITEM_INPUT: usize = 2;
 const QUANTITY_INPUT: usize = 3;
 const COST_INPUT: usize = 4;
-const INPUT_FIELDS: usize = 4;
+const PROCEDURE_INPUT: usize = 5;
+const CATEGORY_INPUT: usize = 6;
+const INPUT_FIELDS: usize = 6;
 
 impl UpdateInvoice {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
         let mut selection_state = TableState::default();
         selection_state.select(Some(0));
 
@@ -59,10 +75,11 @@ impl UpdateInvoice {
         edit_table_state.select(Some(0));
 
         Self {
+            db: db.clone(),
             all_invoices: Vec::new(),
             filtered_invoices: Vec::new(),
             patients: HashMap::new(),
-            search_input: String::new(),
+            search_input: TextInput::new(),
             is_searching: false,
             table_state: selection_state,
             update_state: UpdateState::SelectingInvoice,
@@ -73,25 +90,31 @@ impl UpdateInvoice {
                 item: String::new(),
                 quantity: 0,
                 cost: 0.0,
+                created_at: None,
+                duplicate_note: None,
+                procedure_code: None,
+                category: None,
+                insurer_share: None,
+                held: false,
             },
+            history: EditHistory::new(),
             loaded: false,
+            edit_lock: None,
             selected_field: Some(0),
             edit_table_state,
             input_value: String::new(),
             editing: false,
-            error_message: None,
-            error_timer: None,
-            success_message: None,
-            success_timer: None,
             show_confirmation: false,
             confirmation_message: String::new(),
             confirmed_action: None,
             confirmation_selected: 0,
+            discard_prompt: None,
+            procedure_picker: None,
         }
     }
 
     pub fn fetch_invoices(&mut self) -> Result<()> {
-        self.all_invoices = db::get_all_invoices()?;
+        self.all_invoices = db::get_all_invoices(&self.db)?;
         self.fetch_patients_data()?;
         self.filter_invoices();
         Ok(())
@@ -99,7 +122,7 @@ impl UpdateInvoice {
 
     fn fetch_patients_data(&mut self) -> Result<()> {
         self.patients.clear();
-        let all_patients = db::get_all_patients()?;
+        let all_patients = db::get_all_patients(&self.db)?;
         for patient in all_patients {
             self.patients.insert(patient.id, patient);
         }
@@ -114,7 +137,7 @@ impl UpdateInvoice {
         if self.search_input.is_empty() {
             self.filtered_invoices = self.all_invoices.clone();
         } else {
-            let search_term = self.search_input.to_lowercase();
+            let search_term = self.search_input.value().to_lowercase();
             self.filtered_invoices = self
                 .all_invoices
                 .iter()
@@ -141,17 +164,29 @@ impl UpdateInvoice {
         }
     }
 
-    fn load_invoice_by_id(&mut self, invoice_id: i64) -> Result<()> {
-        match db::get_invoice(invoice_id) {
+    pub fn load_invoice_by_id(&mut self, invoice_id: i64) -> Result<()> {
+        match db::get_invoice(&self.db, invoice_id) {
+            Ok(invoice) if invoice.held => {
+                toast::push(Toast::error(tf(
+                    "invoice_update.held",
+                    &[("id", &invoice_id)],
+                )));
+                Err(anyhow::anyhow!("Invoice is held"))
+            }
             Ok(invoice) => {
                 self.invoice = invoice;
+                self.edit_lock = Some(EditLock::claim(&self.db, "invoice", invoice_id));
+                self.history.clear();
                 self.loaded = true;
                 self.update_state = UpdateState::EditingInvoice;
                 self.update_input_value();
                 Ok(())
             }
             Err(_) => {
-                self.set_error(format!("Invoice with ID {} doesn't exist", invoice_id));
+                toast::push(Toast::error(tf(
+                    "invoice_update.not_found",
+                    &[("id", &invoice_id)],
+                )));
                 Err(anyhow::anyhow!("Invoice not found"))
             }
         }
@@ -162,7 +197,7 @@ impl UpdateInvoice {
             if let Ok(invoice_id) = self.invoice_id_input.parse::<i64>() {
                 self.load_invoice_by_id(invoice_id)
             } else {
-                self.set_error("Invalid Invoice ID format.".to_string());
+                toast::push(Toast::error(t("invoice_update.bad_id")));
                 Err(anyhow::anyhow!("Invalid Invoice ID format"))
             }
         } else {
@@ -178,8 +213,8 @@ impl UpdateInvoice {
                 return self.load_invoice_by_id(invoice_id);
             }
         }
-        self.set_error("No invoice selected".to_string());
-        Err(anyhow::anyhow!("No invoice selected"))
+        toast::push(Toast::error(t("invoice_update.none_selected")));
+        Err(anyhow::anyhow!(t("invoice_update.none_selected")))
     }
 
     fn update_input_value(&mut self) {
@@ -195,6 +230,8 @@ impl UpdateInvoice {
                 ITEM_INPUT => self.invoice.item.clone(),
                 QUANTITY_INPUT => self.invoice.quantity.to_string(),
                 COST_INPUT => self.invoice.cost.to_string(),
+                PROCEDURE_INPUT => self.invoice.procedure_code.clone().unwrap_or_default(),
+                CATEGORY_INPUT => self.invoice.category.clone().unwrap_or_default(),
                 _ => String::new(),
             };
         }
@@ -205,13 +242,14 @@ impl UpdateInvoice {
             return;
         }
 
+        let before = self.invoice.clone();
         if let Some(field_index) = self.selected_field {
             match field_index {
                 PATIENT_ID_INPUT => {
                     if let Ok(patient_id) = self.input_value.parse::<i64>() {
                         self.invoice.patient_id = patient_id;
                     } else {
-                        self.set_error("Invalid Patient ID format.".to_string());
+                        toast::push(Toast::error(t("invoice_update.bad_patient_id")));
                         return;
                     }
                 }
@@ -220,7 +258,7 @@ impl UpdateInvoice {
                     if let Ok(quantity) = self.input_value.parse::<i32>() {
                         self.invoice.quantity = quantity;
                     } else {
-                        self.set_error("Invalid Quantity format.".to_string());
+                        toast::push(Toast::error(t("invoice_update.bad_quantity")));
                         return;
                     }
                 }
@@ -228,14 +266,50 @@ impl UpdateInvoice {
                     if let Ok(cost) = self.input_value.parse::<f64>() {
                         self.invoice.cost = cost;
                     } else {
-                        self.set_error("Invalid Cost format.".to_string());
+                        toast::push(Toast::error(t("invoice_update.bad_cost")));
+                        return;
+                    }
+                }
+                PROCEDURE_INPUT => {
+                    let code = self.input_value.trim();
+                    if let Err(e) = form::procedure_code(&self.db, code) {
+                        toast::push(Toast::error(e));
                         return;
                     }
+                    self.invoice.procedure_code = (!code.is_empty()).then(|| code.to_string());
+                }
+                CATEGORY_INPUT => {
+                    let typed = self.input_value.trim();
+                    let category = SERVICE_CATEGORIES
+                        .iter()
+                        .find(|c| c.eq_ignore_ascii_case(typed));
+                    if category.is_none() && !typed.is_empty() {
+                        toast::push(Toast::error(tf(
+                            "invoice_update.bad_category",
+                            &[("list", &(SERVICE_CATEGORIES.join(", ")))],
+                        )));
+                        return;
+                    }
+                    self.invoice.category = category.map(|c| c.to_string());
                 }
                 _ => {}
             }
         }
+        self.history.record(before);
+        self.editing = false;
+    }
+
+    /// Bills the quantity of `procedure` at its catalog name and price,
+    /// taxed as configured, as one edit.
+    fn apply_procedure(&mut self, procedure: &Procedure) {
+        let before = self.invoice.clone();
+        self.invoice.procedure_code = Some(procedure.code.clone());
+        self.invoice.item = procedure.name.clone();
+        self.invoice.cost = config::config().invoice.billed(procedure.price)
+            * f64::from(self.invoice.quantity.max(1));
+        self.history.record(before);
         self.editing = false;
+        self.update_input_value();
     }
 
     fn show_confirmation(&mut self, message: String, action: ConfirmAction) {
@@ -245,13 +319,56 @@ impl UpdateInvoice {
         self.confirmation_selected = 0;
     }
 
+    fn undo_edit(&mut self) {
+        match self.history.undo(self.invoice.clone()) {
+            Some(previous) => {
+                self.invoice = previous;
+                self.update_input_value();
+            }
+            None => toast::push(Toast::error(t("common.nothing_to_undo"))),
+        }
+    }
+
+    fn redo_edit(&mut self) {
+        match self.history.redo(self.invoice.clone()) {
+            Some(next) => {
+                self.invoice = next;
+                self.update_input_value();
+            }
+            None => toast::push(Toast::error(t("common.nothing_to_redo"))),
+        }
+    }
+
+    fn discard_changes(&mut self) {
+        if let Some(original) = self.history.discard() {
+            self.invoice = original;
+            self.update_input_value();
+            toast::push(Toast::success(t("common.discarded")));
+        }
+    }
+
     fn update_invoice(&mut self) -> Result<()> {
-        match db::update_invoice(&self.invoice) {
+        let user = ui_state::current_user().unwrap_or_default();
+        match db::discount_needing_approval(&self.db, &user, &self.invoice) {
+            Ok(Some((_, percent))) => {
+                toast::push(Toast::error(tf(
+                    "invoice_update.needs_approval",
+                    &[("percent", &format!("{:.1}", percent))],
+                )));
+                return Ok(());
+            }
+            Ok(None) => {}
+            Err(e) => {
+                toast::push(Toast::error(tf("common.database_error", &[("error", &e)])));
+                return Err(e);
+            }
+        }
+        match db::update_invoice(&self.db, &self.invoice, &user) {
             Ok(_) => {
-                self.success_message = Some("Invoice updated successfully!".to_string());
-                self.success_timer = Some(Instant::now());
+                toast::push(Toast::success(t("invoice_update.updated")));
+                self.history.clear();
 
-                if let Ok(invoices) = db::get_all_invoices() {
+                if let Ok(invoices) = db::get_all_invoices(&self.db) {
                     self.all_invoices = invoices.clone();
                     self.filtered_invoices = invoices;
                     self.filter_invoices();
@@ -260,7 +377,7 @@ impl UpdateInvoice {
                 Ok(())
             }
             Err(e) => {
-                self.set_error(format!("Database error: {}", e));
+                toast::push(Toast::error(tf("common.database_error", &[("error", &e)])));
                 Err(e)
             }
         }
@@ -269,15 +386,35 @@ impl UpdateInvoice {
     fn back_to_selection(&mut self) {
         self.update_state = UpdateState::SelectingInvoice;
         self.loaded = false;
+        self.edit_lock = None;
         self.invoice_id_input = String::new();
         self.editing = false;
-        self.clear_error();
-        self.clear_success();
+        self.history.clear();
     }
 
     fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
-        self.check_timeouts();
-
+        if let Some(picker) = &mut self.procedure_picker {
+            match picker.handle_key(key) {
+                Some(PickerOutcome::Picked(procedure)) => {
+                    self.procedure_picker = None;
+                    self.apply_procedure(&procedure);
+                }
+                Some(PickerOutcome::Cancelled) => self.procedure_picker = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+        if let Some(prompt) = &mut self.discard_prompt {
+            match prompt.handle_key(key) {
+                Some(true) => {
+                    self.discard_prompt = None;
+                    self.back_to_selection();
+                }
+                Some(false) => self.discard_prompt = None,
+                None => {}
+            }
+            return Ok(None);
+        }
         if self.show_confirmation {
             match key.code {
                 KeyCode::Left | KeyCode::Right => {
@@ -285,8 +422,12 @@ impl UpdateInvoice {
                 }
                 KeyCode::Enter => {
                     if self.confirmation_selected == 0 {
-                        if let Some(ConfirmAction::UpdateInvoice) = self.confirmed_action.take() {
-                            let _ = self.update_invoice();
+                        match self.confirmed_action.take() {
+                            Some(ConfirmAction::UpdateInvoice) => {
+                                let _ = self.update_invoice();
+                            }
+                            Some(ConfirmAction::DiscardChanges) => self.discard_changes(),
+                            None => {}
                         }
                     }
                     self.show_confirmation = false;
@@ -302,6 +443,18 @@ impl UpdateInvoice {
 
         if self.editing {
             match key.code {
+                KeyCode::Char('f')
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && self.selected_field == Some(PROCEDURE_INPUT) =>
+                {
+                    match db::get_procedures(&self.db) {
+                        Ok(catalog) => self.procedure_picker = Some(ProcedurePicker::new(catalog)),
+                        Err(e) => toast::push(Toast::error(tf(
+                            "invoice.procedures_failed",
+                            &[("error", &e)],
+                        ))),
+                    }
+                }
                 KeyCode::Char(c) => {
                     self.input_value.push(c);
                 }
@@ -322,15 +475,8 @@ impl UpdateInvoice {
 
         if matches!(self.update_state, UpdateState::SelectingInvoice) {
             match key.code {
-                KeyCode::Char(c) if self.is_searching => {
-                    self.search_input.push(c);
-                    self.filter_invoices();
-                    self.clear_error();
-                }
-                KeyCode::Backspace if self.is_searching => {
-                    self.search_input.pop();
+                _ if self.is_searching && self.search_input.handle_key(key) => {
                     self.filter_invoices();
-                    self.clear_error();
                 }
                 KeyCode::Down if self.is_searching && !self.filtered_invoices.is_empty() => {
                     self.is_searching = false;
@@ -350,12 +496,10 @@ impl UpdateInvoice {
                 KeyCode::Char(c) if !self.is_searching => {
                     self.invoice_id_input.push(c);
                     self.input_value = self.invoice_id_input.clone();
-                    self.clear_error();
                 }
                 KeyCode::Backspace if !self.is_searching => {
                     self.invoice_id_input.pop();
                     self.input_value = self.invoice_id_input.clone();
-                    self.clear_error();
                 }
 
                 KeyCode::Up if !self.is_searching => {
@@ -417,13 +561,35 @@ impl UpdateInvoice {
                 if key.modifiers.contains(KeyModifiers::CONTROL) =>
             {
                 self.show_confirmation(
-                    "Are you sure you want to update this invoice?".to_string(),
+                    t("invoice_update.confirm").to_string(),
                     ConfirmAction::UpdateInvoice,
                 );
             }
+            KeyCode::Char('z') | KeyCode::Char('Z')
+                if key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.undo_edit();
+            }
+            KeyCode::Char('y') | KeyCode::Char('Y')
+                if key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.redo_edit();
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') if self.history.has_changes() => {
+                self.show_confirmation(
+                    t("invoice_update.discard_confirm").to_string(),
+                    ConfirmAction::DiscardChanges,
+                );
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                toast::push(Toast::error(t("common.nothing_to_discard")));
+            }
             KeyCode::Char('e') | KeyCode::Char('E') => {
                 self.editing = true;
             }
+            KeyCode::Esc if self.history.has_changes() => {
+                self.discard_prompt = Some(DiscardPrompt::new());
+            }
             KeyCode::Esc => {
                 self.back_to_selection();
                 return Ok(None);
@@ -433,48 +599,6 @@ impl UpdateInvoice {
 
         Ok(None)
     }
-
-    fn clear_error(&mut self) {
-        self.error_message = None;
-        self.error_timer = None;
-    }
-
-    fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_timer = Some(Instant::now());
-    }
-
-    fn clear_success(&mut self) {
-        self.success_message = None;
-        self.success_timer = None;
-    }
-
-    fn check_success_timeout(&mut self) {
-        if let Some(timer) = self.success_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_success();
-            }
-        }
-    }
-
-    fn check_error_timeout(&mut self) {
-        if let Some(timer) = self.error_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_error();
-            }
-        }
-    }
-
-    fn check_timeouts(&mut self) {
-        self.check_error_timeout();
-        self.check_success_timeout();
-    }
-}
-
-impl Default for UpdateInvoice {
-    fn default() -> Self {
-        Self::new()
-    }
 }
 
 impl Component for UpdateInvoice {
@@ -488,7 +612,7 @@ impl Component for UpdateInvoice {
     fn render(&self, frame: &mut Frame) {
         let area = frame.area();
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             area,
         );
 
@@ -500,6 +624,12 @@ impl Component for UpdateInvoice {
         if self.show_confirmation {
             self.render_confirmation_dialog(frame, area);
         }
+        if let Some(prompt) = &self.discard_prompt {
+            prompt.render_popup(frame);
+        }
+        if let Some(picker) = &self.procedure_picker {
+            picker.render_popup(frame);
+        }
     }
 }
 
@@ -520,16 +650,16 @@ impl UpdateInvoice {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, main_layout[0]);
 
-        let title = Paragraph::new("✍️  SELECT INVOICE TO UPDATE")
+        let title = Paragraph::new(t("invoice_update.select_title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, main_layout[0]);
@@ -538,24 +668,20 @@ impl UpdateInvoice {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Invoices ",
+                format!(" {} ", t("invoice_update.search")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
-        let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
+        let search_paragraph = Paragraph::new(self.search_input.line(self.is_searching))
+            .style(Style::default().fg(theme().text).bg(theme().input))
             .block(search_block);
         frame.render_widget(search_paragraph, main_layout[1]);
 
@@ -563,80 +689,82 @@ impl UpdateInvoice {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Invoice ID ",
+                format!(" {} ", t("invoice_update.invoice_id")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if !self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
         let id_input_paragraph = Paragraph::new(self.invoice_id_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
+            .style(Style::default().fg(theme().text).bg(theme().input))
             .block(id_input_block);
         frame.render_widget(id_input_paragraph, main_layout[2]);
 
         if self.filtered_invoices.is_empty() {
             let no_invoices = Paragraph::new(if self.search_input.is_empty() {
-                "No invoices found in database"
+                t("invoice_update.no_invoices")
             } else {
-                "No invoices match your search criteria"
+                t("invoice_update.no_matches")
             })
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Center);
             frame.render_widget(no_invoices, main_layout[3]);
         } else {
+            let widths = [
+                Constraint::Percentage(10),
+                Constraint::Percentage(30),
+                Constraint::Percentage(30),
+                Constraint::Percentage(10),
+                Constraint::Percentage(20),
+            ];
+            let columns = column_widths(main_layout[3].inner(Margin::new(1, 1)), &widths, 2, "► ");
             let invoices_rows: Vec<Row> = self
                 .filtered_invoices
                 .iter()
                 .map(|invoice| {
                     let patient_name = match self.get_patient(invoice.patient_id) {
                         Some(patient) => format!("{} {}", patient.first_name, patient.last_name),
-                        None => "Unknown Patient".to_string(),
+                        None => t("common.unknown_patient_name").to_string(),
                     };
 
                     Row::new(vec![
-                        invoice.id.to_string(),
-                        patient_name,
-                        invoice.item.clone(),
-                        invoice.quantity.to_string(),
-                        format!("{:.2}", invoice.cost),
+                        cell(invoice.id.to_string(), &columns, 0),
+                        cell(patient_name, &columns, 1),
+                        cell(&invoice.item, &columns, 2),
+                        numeric_cell(invoice.quantity.to_string(), &columns, 3),
+                        numeric_cell(format!("{:.2}", invoice.cost), &columns, 4),
                     ])
-                    .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                    .style(Style::default().fg(theme().text))
                     .height(1)
                     .bottom_margin(0)
                 })
                 .collect();
 
             let selected_style = Style::default()
-                .fg(Color::Rgb(250, 250, 110))
-                .bg(Color::Rgb(40, 40, 60))
+                .fg(theme().focus)
+                .bg(theme().highlight)
                 .add_modifier(Modifier::BOLD);
 
-            let header = Row::new(vec!["ID", "Patient", "Item", "Quantity", "Cost"])
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(220, 220, 240))
-                        .bg(Color::Rgb(80, 60, 130))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .height(1);
-
-            let widths = [
-                Constraint::Percentage(10),
-                Constraint::Percentage(30),
-                Constraint::Percentage(30),
-                Constraint::Percentage(10),
-                Constraint::Percentage(20),
-            ];
+            let header = Row::new(vec![
+                t("common.id"),
+                t("invoice_update.patient"),
+                t("invoice_update.item"),
+                t("invoice_update.quantity"),
+                t("invoice_update.cost"),
+            ])
+            .style(
+                Style::default()
+                    .fg(theme().text)
+                    .bg(theme().header)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1);
 
             let invoices_table = Table::new(invoices_rows, widths)
                 .header(header)
@@ -644,9 +772,15 @@ impl UpdateInvoice {
                     Block::default()
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .title(format!(" Invoices ({}) ", self.filtered_invoices.len()))
-                        .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-                        .style(Style::default().bg(Color::Rgb(26, 26, 36))),
+                        .title(format!(
+                            " {} ",
+                            tf(
+                                "invoice_update.invoices_count",
+                                &[("count", &self.filtered_invoices.len())]
+                            )
+                        ))
+                        .border_style(Style::default().fg(theme().unfocused))
+                        .style(Style::default().bg(theme().input)),
                 )
                 .column_spacing(2)
                 .row_highlight_style(selected_style)
@@ -659,34 +793,14 @@ impl UpdateInvoice {
             );
         }
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(255, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, main_layout[4]);
-        } else if let Some(success) = &self.success_message {
-            let success_paragraph = Paragraph::new(success.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, main_layout[4]);
-        }
-
         let help_text = if self.is_searching {
-            "Type to search | ↓: To results | Esc: Cancel search"
+            t("common.search_help")
         } else {
-            "/ or s: Search | ↑/↓: Navigate invoices | Enter: Select invoice | Esc: Back"
+            t("invoice_update.select_help")
         };
 
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, main_layout[5]);
     }
@@ -706,79 +820,88 @@ impl UpdateInvoice {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, main_layout[0]);
 
-        let title_text = if self.editing {
-            "✍️  EDITING INVOICE"
-        } else {
-            "✍️  UPDATE INVOICE"
+        let title_text = match (self.editing, self.history.has_changes()) {
+            (true, _) => t("invoice_update.editing_title"),
+            (false, true) => t("invoice_update.unsaved_title"),
+            (false, false) => t("invoice_update.title"),
         };
 
         let title = Paragraph::new(title_text)
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, main_layout[0]);
+        edit_warning::render(
+            frame,
+            Rect {
+                y: main_layout[0].y + 1,
+                height: 1,
+                ..main_layout[0]
+            },
+            self.edit_lock.as_ref(),
+        );
 
         let id_str = self.invoice.id.to_string();
         let patient_id_str = self.invoice.patient_id.to_string();
         let quantity_str = self.invoice.quantity.to_string();
         let cost_str = format!("{:.2}", self.invoice.cost);
 
-        let table_items = vec![
-            Row::new(vec!["ID", &id_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Patient ID", &patient_id_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Item", &self.invoice.item])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Quantity", &quantity_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Cost", &cost_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+        let widths = [Constraint::Percentage(30), Constraint::Percentage(70)];
+        let columns = column_widths(main_layout[1].inner(Margin::new(1, 1)), &widths, 2, "► ");
+        let field = |label: &str, value: &str| {
+            Row::new(vec![cell(label, &columns, 0), cell(value, &columns, 1)])
+                .style(Style::default().fg(theme().text))
                 .height(1)
-                .bottom_margin(0),
+                .bottom_margin(0)
+        };
+
+        let table_items = vec![
+            field(t("common.id"), &id_str),
+            field(t("common.patient_id"), &patient_id_str),
+            field(t("invoice_update.item"), &self.invoice.item),
+            field(t("invoice_update.quantity"), &quantity_str),
+            field(t("invoice_update.cost"), &cost_str),
+            field(
+                t("invoice_update.procedure_code"),
+                self.invoice.procedure_code.as_deref().unwrap_or_default(),
+            ),
+            field(
+                t("invoice_update.service_category"),
+                self.invoice.category.as_deref().unwrap_or_default(),
+            ),
         ];
 
         let selected_style = Style::default()
-            .fg(Color::Rgb(250, 250, 110))
-            .bg(Color::Rgb(40, 40, 60))
+            .fg(theme().focus)
+            .bg(theme().highlight)
             .add_modifier(Modifier::BOLD);
 
-        let header = Row::new(vec!["Field", "Value"])
+        let header = Row::new(vec![t("common.field"), t("common.value")])
             .style(
                 Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(80, 60, 130))
+                    .fg(theme().text)
+                    .bg(theme().header)
                     .add_modifier(Modifier::BOLD),
             )
             .height(1);
 
-        let widths = [Constraint::Percentage(30), Constraint::Percentage(70)];
-
         let table = Table::new(table_items, widths)
             .header(header)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
-                    .title(" Invoice Data ")
-                    .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
+                    .title(format!(" {} ", t("invoice_update.data")))
+                    .border_style(Style::default().fg(theme().unfocused))
+                    .style(Style::default().bg(theme().input)),
             )
             .column_spacing(2)
             .row_highlight_style(selected_style)
@@ -787,66 +910,49 @@ impl UpdateInvoice {
         frame.render_stateful_widget(table, main_layout[1], &mut self.edit_table_state.clone());
 
         let input_label = match self.selected_field {
-            Some(ID_INPUT) => "ID",
-            Some(PATIENT_ID_INPUT) => "Patient ID",
-            Some(ITEM_INPUT) => "Item",
-            Some(QUANTITY_INPUT) => "Quantity",
-            Some(COST_INPUT) => "Cost",
-            _ => "Field",
+            Some(ID_INPUT) => t("common.id"),
+            Some(PATIENT_ID_INPUT) => t("common.patient_id"),
+            Some(ITEM_INPUT) => t("invoice_update.item"),
+            Some(QUANTITY_INPUT) => t("invoice_update.quantity"),
+            Some(COST_INPUT) => t("invoice_update.cost"),
+            Some(PROCEDURE_INPUT) => t("invoice_update.procedure_code"),
+            Some(CATEGORY_INPUT) => t("invoice_update.service_category"),
+            _ => t("common.field"),
         };
 
         let input_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(format!(
-                " {} {} ",
-                if self.editing { "Editing" } else { "Selected" },
-                input_label
+                " {} ",
+                if self.editing {
+                    tf("common.editing_field", &[("field", &input_label)])
+                } else {
+                    tf("common.selected_field", &[("field", &input_label)])
+                }
             ))
             .border_style(if self.editing {
-                Style::default().fg(Color::Rgb(140, 219, 140))
+                Style::default().fg(theme().success)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
         let input_paragraph = Paragraph::new(self.input_value.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
+            .style(Style::default().fg(theme().text).bg(theme().input))
             .block(input_block);
         frame.render_widget(input_paragraph, main_layout[2]);
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(255, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, main_layout[3]);
-        } else if let Some(success) = &self.success_message {
-            let success_paragraph = Paragraph::new(success.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, main_layout[3]);
-        }
-
-        let help_text = if self.editing {
-            "Enter: Save Changes | Esc: Cancel Editing"
+        let help_text = if self.editing && self.selected_field == Some(PROCEDURE_INPUT) {
+            t("invoice_update.edit_procedure_help")
+        } else if self.editing {
+            t("common.edit_help")
         } else {
-            "↑/↓: Navigate | E: Edit Field | Ctrl+S: Save Invoice | Esc: Back"
+            t("invoice_update.help")
         };
 
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, main_layout[4]);
     }
@@ -864,16 +970,16 @@ impl UpdateInvoice {
         frame.render_widget(Clear, dialog_area);
 
         let dialog_block = Block::default()
-            .title(" Update Invoice ")
+            .title(format!(" {} ", t("invoice_update.update_title")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-            .style(Style::default().bg(Color::Rgb(30, 30, 46)));
+            .border_style(Style::default().fg(theme().unfocused))
+            .style(Style::default().bg(theme().dialog));
 
         frame.render_widget(dialog_block.clone(), dialog_area);
 
@@ -885,7 +991,7 @@ impl UpdateInvoice {
             .split(inner_area);
 
         let message = Paragraph::new(self.confirmation_message.as_str())
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .add_modifier(Modifier::BOLD)
             .alignment(Alignment::Center);
         frame.render_widget(message, content_layout[0]);
@@ -897,28 +1003,28 @@ impl UpdateInvoice {
 
         let yes_style = if self.confirmation_selected == 0 {
             Style::default()
-                .fg(Color::Rgb(140, 219, 140))
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
         let no_style = if self.confirmation_selected == 1 {
             Style::default()
-                .fg(Color::Rgb(255, 100, 100))
+                .fg(theme().error)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let yes_text = if self.confirmation_selected == 0 {
-            "► Yes ◄"
+            format!("► {} ◄", t("common.yes"))
         } else {
-            "  Yes  "
+            format!("  {}  ", t("common.yes"))
         };
         let no_text = if self.confirmation_selected == 1 {
-            "► No ◄"
+            format!("► {} ◄", t("common.no"))
         } else {
-            "  No  "
+            format!("  {}  ", t("common.no"))
         };
 
         let yes_button = Paragraph::new(yes_text)
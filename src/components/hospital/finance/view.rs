@@ -1,62 +1,160 @@
 use crate::app::SelectedApp;
+use crate::components::responsive::is_narrow;
+use crate::components::table::{cell, column_widths, numeric_cell, Columns};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::column_picker::{self, ColumnPicker};
+use crate::components::widgets::export_view::{self, ExportView, ShownRows};
+use crate::components::widgets::print_dialog::PrintDialog;
+use crate::components::widgets::saved_searches::{self, SavedSearchOutcome, SavedSearchesPopup};
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
-use crate::db;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
 use crate::models::{Invoice, Patient};
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::ui_state::{self, Recent};
+use crate::utils::{config, datetime, invoice_layout, money};
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{prelude::*, widgets::*};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 const SEARCH_FIELD: usize = 0;
 const PATIENT_LIST: usize = 1;
 const BACK_BUTTON: usize = 2;
+const VIEW_KEY: &str = "finance.view";
+const HEADERS: [&str; 3] = [
+    "invoices.column.patient_id",
+    "invoices.column.patient_name",
+    "invoices.column.total_cost",
+];
+const WIDTHS: [Constraint; 3] = [
+    Constraint::Percentage(20),
+    Constraint::Percentage(50),
+    Constraint::Percentage(30),
+];
+const TOTAL_COLUMN: usize = 2;
+/// The columns kept on narrow terminals.
+const MINIMAL_COLUMNS: [usize; 2] = [1, 2];
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ViewState {
     ViewingList,
     ViewingDetails,
 }
 pub struct ViewInvoices {
+    db: Db,
     invoices: Vec<Invoice>,
     filtered_invoices: Vec<Invoice>,
     patients: HashMap<i64, Patient>,
-    search_input: String,
+    search_input: TextInput,
     is_searching: bool,
     state: TableState,
-    error_message: Option<String>,
     focus_index: usize,
     view_state: ViewState,
     selected_patient_id: Option<i64>,
+    print_dialog: Option<PrintDialog>,
+    saved_searches: Option<SavedSearchesPopup>,
+    export_view: Option<ExportView>,
+    columns: Columns,
+    column_picker: Option<ColumnPicker>,
 }
 impl ViewInvoices {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
+        let view = ui_state::view(VIEW_KEY);
+        let mut search_input = TextInput::new();
+        search_input.set_value(view.search.as_str());
         Self {
+            db: db.clone(),
             invoices: Vec::new(),
             filtered_invoices: Vec::new(),
             patients: HashMap::new(),
-            search_input: String::new(),
+            search_input,
             is_searching: false,
-            state: TableState::default(),
-            error_message: None,
+            state: view.table(),
             focus_index: PATIENT_LIST,
             view_state: ViewState::ViewingList,
             selected_patient_id: None,
+            print_dialog: None,
+            saved_searches: None,
+            export_view: None,
+            columns: Columns::new(VIEW_KEY, &HEADERS, &[], &MINIMAL_COLUMNS),
+            column_picker: None,
         }
     }
     pub fn fetch_invoices(&mut self) -> Result<()> {
-        self.invoices = db::get_all_invoices()?;
+        self.invoices = db::get_all_invoices(&self.db)?;
         self.fetch_patients_data()?;
         self.filter_invoices();
-        if self.invoices.is_empty() {
+        let patients_with_invoices = self
+            .filtered_invoices
+            .iter()
+            .map(|invoice| invoice.patient_id)
+            .collect::<HashSet<_>>()
+            .len();
+        if patients_with_invoices == 0 {
             self.state.select(None);
         } else {
-            self.state.select(Some(0));
+            let selection = self
+                .state
+                .selected()
+                .unwrap_or(0)
+                .min(patients_with_invoices - 1);
+            self.state.select(Some(selection));
         }
-        self.error_message = None;
         Ok(())
     }
+    pub fn save_view(&self) {
+        ui_state::set_view(VIEW_KEY, self.current_view().with_table(&self.state));
+    }
+    fn current_view(&self) -> ui_state::ViewState {
+        ui_state::ViewState {
+            search: self.search_input.value().to_string(),
+            ..ui_state::ViewState::default()
+        }
+    }
+    /// Each patient with filtered invoices, their name and the invoices'
+    /// total, by patient id as listed. Held invoices are not in the total.
+    fn patient_totals(&self) -> Vec<(i64, (String, f64))> {
+        let mut aggregated_invoices: HashMap<i64, (String, f64)> = HashMap::new();
+        for invoice in &self.filtered_invoices {
+            if let Some(patient) = self.get_patient(invoice.patient_id) {
+                let full_name = format!("{} {}", patient.first_name, patient.last_name);
+                let entry = aggregated_invoices
+                    .entry(patient.id)
+                    .or_insert((full_name, 0.0));
+                if !invoice.held {
+                    entry.1 += invoice.cost;
+                }
+            }
+        }
+        let mut sorted_invoices: Vec<_> = aggregated_invoices.into_iter().collect();
+        sorted_invoices.sort_by_key(|&(patient_id, _)| patient_id);
+        sorted_invoices
+    }
+    fn shown_rows(&self) -> ShownRows {
+        let visible = self.columns.visible(false);
+        let mut shown = ShownRows::new(&Columns::pick(&HEADERS, &visible));
+        shown.rows = self
+            .patient_totals()
+            .into_iter()
+            .map(|(patient_id, (name, total))| {
+                Columns::pick(&[patient_id.to_string(), name, money(total)], &visible)
+            })
+            .collect();
+        shown
+    }
+    /// Shows the invoices matching a saved search, from the top.
+    fn apply_view(&mut self, view: ui_state::ViewState) {
+        self.search_input.set_value(view.search.as_str());
+        self.is_searching = false;
+        self.focus_index = PATIENT_LIST;
+        self.filter_invoices();
+        let empty = self.filtered_invoices.is_empty();
+        self.state.select((!empty).then_some(0));
+    }
     fn fetch_patients_data(&mut self) -> Result<()> {
         self.patients.clear();
-        let all_patients = db::get_all_patients()?;
+        let all_patients = db::get_all_patients(&self.db)?;
         for patient in all_patients {
             self.patients.insert(patient.id, patient);
         }
@@ -66,7 +164,7 @@ impl ViewInvoices {
         if self.search_input.is_empty() {
             self.filtered_invoices = self.invoices.clone();
         } else {
-            let search_term = self.search_input.to_lowercase();
+            let search_term = self.search_input.value().to_lowercase();
             self.filtered_invoices = self
                 .invoices
                 .iter()
@@ -130,19 +228,100 @@ impl ViewInvoices {
         };
         self.state.select(Some(i));
     }
+    /// The patients with filtered invoices, in table order.
+    fn listed_patient_ids(&self) -> Vec<i64> {
+        let mut patient_ids: Vec<i64> = self
+            .filtered_invoices
+            .iter()
+            .map(|invoice| invoice.patient_id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        patient_ids.sort();
+        patient_ids
+    }
+    fn highlighted_patient_id(&self) -> Option<i64> {
+        let selected_index = self.state.selected()?;
+        self.listed_patient_ids().get(selected_index).copied()
+    }
     fn view_invoice_details(&mut self) {
-        if let Some(selected_index) = self.state.selected() {
-            let mut aggregated_invoices: HashMap<i64, f64> = HashMap::new();
-            for invoice in &self.filtered_invoices {
-                *aggregated_invoices.entry(invoice.patient_id).or_insert(0.0) += invoice.cost;
+        if let Some(patient_id) = self.highlighted_patient_id() {
+            self.selected_patient_id = Some(patient_id);
+            self.view_state = ViewState::ViewingDetails;
+            ui_state::add_recent(Recent::invoices(patient_id, self.get_patient(patient_id)));
+        }
+    }
+    /// Clears the search and opens the invoices of `patient_id`, for recently
+    /// opened invoices.
+    pub fn show_patient(&mut self, patient_id: i64) {
+        self.search_input.clear();
+        self.filter_invoices();
+        let Some(index) = self
+            .listed_patient_ids()
+            .iter()
+            .position(|id| *id == patient_id)
+        else {
+            return;
+        };
+        self.state.select(Some(index));
+        self.focus_index = PATIENT_LIST;
+        self.is_searching = false;
+        self.view_invoice_details();
+    }
+    /// Prints a new invoice, or receipt, for the patient's billed lines that
+    /// are on none yet, or the latest one again when every line is.
+    fn print_invoice(&mut self, patient_id: Option<i64>, receipt: bool) {
+        let Some(patient_id) = patient_id else {
+            return;
+        };
+        let issued = db::issue_invoice_document(
+            &self.db,
+            patient_id,
+            receipt,
+            ui_state::current_user().as_deref(),
+        )
+        .and_then(|issued| match issued {
+            Some(document) => Ok(Some(document)),
+            None => Ok(db::get_invoice_documents(&self.db, patient_id)?
+                .into_iter()
+                .find(|document| document.receipt == receipt)),
+        });
+        let document = match issued {
+            Ok(Some(document)) => document,
+            // Held invoices are not billed until their price is approved.
+            Ok(None) => {
+                toast::push(Toast::error(t("invoices.nothing_to_print")));
+                return;
             }
-            let mut patient_ids: Vec<_> = aggregated_invoices.keys().cloned().collect();
-            patient_ids.sort();
-            if let Some(patient_id) = patient_ids.get(selected_index) {
-                self.selected_patient_id = Some(*patient_id);
-                self.view_state = ViewState::ViewingDetails;
+            Err(e) => {
+                toast::push(Toast::error(tf("invoices.number_failed", &[("error", &e)])));
+                return;
             }
+        };
+        if let Err(e) = self.fetch_invoices() {
+            toast::push(Toast::error(e.to_string()));
         }
+        let invoices: Vec<&Invoice> = self
+            .invoices
+            .iter()
+            .filter(|invoice| document.invoice_ids.contains(&invoice.id))
+            .collect();
+        let text = invoice_layout::document(
+            &config::config().invoice,
+            &invoices,
+            self.get_patient(patient_id),
+            &document.document_number,
+            datetime::parse_date(&document.issued_on).unwrap_or_else(datetime::today),
+            receipt,
+        );
+        self.print_dialog = Some(PrintDialog::new(
+            &self.db,
+            tf(
+                "invoices.document_for",
+                &[("number", &document.document_number), ("id", &patient_id)],
+            ),
+            text,
+        ));
     }
     fn return_to_list(&mut self) {
         self.view_state = ViewState::ViewingList;
@@ -160,16 +339,53 @@ impl ViewInvoices {
         self.patients.get(&patient_id)
     }
     pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(dialog) = self.print_dialog.as_mut() {
+            if dialog.handle_key(key) {
+                self.print_dialog = None;
+            }
+            return Ok(None);
+        }
+        if let Some(export) = &mut self.export_view {
+            if export.handle_key(key) {
+                self.export_view = None;
+            }
+            return Ok(None);
+        }
+        if let Some(picker) = &mut self.column_picker {
+            if picker.handle_key(key, &mut self.columns) {
+                self.column_picker = None;
+            }
+            return Ok(None);
+        }
+        if let Some(popup) = &mut self.saved_searches {
+            match popup.handle_key(key) {
+                Some(SavedSearchOutcome::Apply(view)) => {
+                    self.saved_searches = None;
+                    self.apply_view(view);
+                }
+                Some(SavedSearchOutcome::Closed) => self.saved_searches = None,
+                None => {}
+            }
+            return Ok(None);
+        }
         match self.view_state {
             ViewState::ViewingList => {
+                if saved_searches::is_open_key(key) {
+                    self.saved_searches =
+                        Some(SavedSearchesPopup::new(VIEW_KEY, self.current_view()));
+                    return Ok(None);
+                }
+                if export_view::is_open_key(key) {
+                    self.export_view = Some(ExportView::new("invoices", self.shown_rows()));
+                    return Ok(None);
+                }
+                if column_picker::is_open_key(key) {
+                    self.column_picker = Some(ColumnPicker::new());
+                    return Ok(None);
+                }
                 if self.is_searching {
                     match key.code {
-                        KeyCode::Char(c) => {
-                            self.search_input.push(c);
-                            self.filter_invoices();
-                        }
-                        KeyCode::Backspace => {
-                            self.search_input.pop();
+                        _ if self.search_input.handle_key(key) => {
                             self.filter_invoices();
                         }
                         KeyCode::Enter | KeyCode::Down | KeyCode::Tab => {
@@ -222,8 +438,17 @@ impl ViewInvoices {
                         return Ok(Some(SelectedApp::None));
                     }
                     KeyCode::Char('r') | KeyCode::Char('R') => {
+                        db::cache::invalidate_all(&self.db);
                         self.fetch_invoices()?;
                     }
+                    KeyCode::Char('p') if self.focus_index == PATIENT_LIST => {
+                        self.print_invoice(self.highlighted_patient_id(), false);
+                    }
+                    KeyCode::Char('1'..='9') if self.focus_index == PATIENT_LIST => {
+                        if let Some(view) = saved_searches::shortcut(VIEW_KEY, key) {
+                            self.apply_view(view);
+                        }
+                    }
                     KeyCode::Esc => {
                         return Ok(Some(SelectedApp::None));
                     }
@@ -237,6 +462,8 @@ impl ViewInvoices {
                 KeyCode::Char('b') | KeyCode::Char('B') => {
                     self.return_to_list();
                 }
+                KeyCode::Char('p') => self.print_invoice(self.selected_patient_id, false),
+                KeyCode::Char('P') => self.print_invoice(self.selected_patient_id, true),
                 _ => {}
             },
         }
@@ -249,13 +476,25 @@ impl Component for ViewInvoices {
     }
     fn render(&self, frame: &mut Frame) {
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             frame.area(),
         );
         match self.view_state {
             ViewState::ViewingList => self.render_list_view(frame),
             ViewState::ViewingDetails => self.render_details_view(frame),
         }
+        if let Some(popup) = &self.saved_searches {
+            popup.render_popup(frame);
+        }
+        if let Some(export) = &self.export_view {
+            export.render_popup(frame);
+        }
+        if let Some(picker) = &self.column_picker {
+            picker.render_popup(frame, &self.columns);
+        }
+        if let Some(dialog) = &self.print_dialog {
+            dialog.render_popup(frame);
+        }
     }
 }
 impl ViewInvoices {
@@ -276,15 +515,15 @@ impl ViewInvoices {
             .split(area);
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
-        let title = Paragraph::new("🧾 Invoices")
+        let title = Paragraph::new(t("invoices.title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
@@ -292,119 +531,118 @@ impl ViewInvoices {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Patients ",
+                format!(" {} ", t("common.search_patients")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(75, 75, 120))
+                Style::default().fg(theme().border)
             })
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
-        let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().bg(theme().surface));
+        let search_paragraph = Paragraph::new(self.search_input.line(self.is_searching))
+            .style(Style::default().fg(theme().text))
             .block(search_block);
         frame.render_widget(search_paragraph, layout[1]);
-        let mut aggregated_invoices: HashMap<i64, (String, f64)> = HashMap::new();
-        for invoice in &self.filtered_invoices {
-            if let Some(patient) = self.get_patient(invoice.patient_id) {
-                let full_name = format!("{} {}", patient.first_name, patient.last_name);
-                let entry = aggregated_invoices
-                    .entry(patient.id)
-                    .or_insert((full_name, 0.0));
-                entry.1 += invoice.cost;
-            }
-        }
-        let mut sorted_invoices: Vec<_> = aggregated_invoices.into_iter().collect();
-        sorted_invoices.sort_by_key(|&(patient_id, _)| patient_id);
+        let sorted_invoices = self.patient_totals();
+        let visible = self.columns.visible(is_narrow(layout[2]));
+        let widths = Columns::widths(&WIDTHS, &visible);
+        let columns = column_widths(layout[2].inner(Margin::new(1, 1)), &widths, 1, "► ");
+
         let rows = sorted_invoices
             .iter()
             .map(|(patient_id, (patient_name, total_cost))| {
-                let cells = vec![
-                    Cell::from(patient_id.to_string()),
-                    Cell::from(patient_name.clone()),
-                    Cell::from(format!("${:.2}", total_cost)),
+                let values = [
+                    patient_id.to_string(),
+                    patient_name.clone(),
+                    money(*total_cost),
                 ];
+                let cells: Vec<Cell> = visible
+                    .iter()
+                    .enumerate()
+                    .map(|(column, &i)| {
+                        if i == TOTAL_COLUMN {
+                            numeric_cell(&values[i], &columns, column)
+                        } else {
+                            cell(&values[i], &columns, column)
+                        }
+                    })
+                    .collect();
                 Row::new(cells)
                     .height(1)
                     .bottom_margin(0)
-                    .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                    .style(Style::default().fg(theme().text))
             });
-        let header_cells = ["Patient ID", "Patient Name", "Total Cost"]
-            .iter()
-            .map(|h| Cell::from(*h).style(Style::default().fg(Color::Rgb(230, 230, 250))));
+        let header_cells = Columns::pick(&HEADERS, &visible)
+            .into_iter()
+            .map(|h| Cell::from(t(h)).style(Style::default().fg(theme().title)));
         let header = Row::new(header_cells)
-            .style(Style::default().bg(Color::Rgb(80, 60, 130)))
+            .style(Style::default().bg(theme().header))
             .height(1);
         let selected_style = Style::default()
-            .fg(Color::Rgb(250, 250, 110))
-            .bg(Color::Rgb(40, 40, 60))
+            .fg(theme().focus)
+            .bg(theme().highlight)
             .add_modifier(Modifier::BOLD);
         let table_title = if !self.search_input.is_empty() {
             format!(
-                " Patients with Invoices ({} of {} patients with invoices) ",
-                sorted_invoices.len(),
-                self.patients.len()
+                " {} ",
+                tf(
+                    "invoices.with_invoices_matches",
+                    &[
+                        ("shown", &sorted_invoices.len()),
+                        ("total", &self.patients.len())
+                    ]
+                )
             )
         } else {
-            format!(" Patients with Invoices ({}) ", self.patients.len())
+            format!(
+                " {} ",
+                tf("invoices.with_invoices", &[("total", &self.patients.len())])
+            )
         };
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Percentage(20),
-                Constraint::Percentage(50),
-                Constraint::Percentage(30),
-            ],
-        )
-        .header(header)
-        .block(
-            Block::default()
-                .title(table_title)
-                .title_alignment(Alignment::Center)
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-                .style(Style::default().bg(Color::Rgb(22, 22, 35))),
-        )
-        .row_highlight_style(selected_style)
-        .highlight_symbol(if self.focus_index == PATIENT_LIST {
-            "► "
-        } else {
-            "  "
-        });
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .title(table_title)
+                    .title_alignment(Alignment::Center)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .row_highlight_style(selected_style)
+            .highlight_symbol(if self.focus_index == PATIENT_LIST {
+                "► "
+            } else {
+                "  "
+            });
         frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, layout[3]);
-        }
         let back_text = if self.focus_index == BACK_BUTTON {
-            "► Back ◄"
+            format!("► {} ◄", t("common.back"))
         } else {
-            "  Back  "
+            format!("  {}  ", t("common.back"))
         };
         let back_style = if self.focus_index == BACK_BUTTON {
             Style::default()
-                .fg(Color::Rgb(129, 199, 245))
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
         let back_button = Paragraph::new(back_text)
             .style(back_style)
             .alignment(Alignment::Center);
         frame.render_widget(back_button, layout[4]);
         let help_text = if self.is_searching {
-            "Type to search | ↓/Enter: To results | Esc: Cancel search"
+            t("common.search_enter_help")
         } else {
-            "/ or s: Search | ↑↓: Navigate | Enter: View Details | r: Refresh | Tab: Focus"
+            t("invoices.help")
         };
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(140, 140, 170)))
+            .style(Style::default().fg(theme().help))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, layout[6]);
     }
@@ -422,15 +660,15 @@ impl ViewInvoices {
             .split(area);
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
-        let title = Paragraph::new("🧾 INVOICE DETAILS")
+        let title = Paragraph::new(t("invoices.details_title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
@@ -438,96 +676,156 @@ impl ViewInvoices {
             let patient_name = self
                 .get_patient(patient_id)
                 .map(|p| format!("{} {}", p.first_name, p.last_name))
-                .unwrap_or_else(|| "Unknown Patient".to_string());
+                .unwrap_or_else(|| t("common.unknown_patient_name").to_string());
             let invoices_for_patient: Vec<&Invoice> = self
                 .invoices
                 .iter()
                 .filter(|inv| inv.patient_id == patient_id)
                 .collect();
-            let header_cells = ["Item", "Quantity", "Cost"].iter().map(|h| {
-                Cell::from(format!("  {}", h)).style(Style::default().fg(Color::Rgb(230, 230, 250)))
-            });
+            // Insured patients also see how each line is split with the insurer.
+            let insured = invoices_for_patient
+                .iter()
+                .any(|invoice| invoice.insurer_share.is_some());
+            let mut headers = vec![
+                t("invoices.item"),
+                t("invoices.quantity"),
+                t("invoices.cost"),
+            ];
+            let mut widths = vec![
+                Constraint::Percentage(50),
+                Constraint::Percentage(20),
+                Constraint::Percentage(30),
+            ];
+            if insured {
+                headers.extend([t("invoices.insurer"), t("invoices.co_pay")]);
+                widths = vec![
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(12),
+                    Constraint::Percentage(16),
+                    Constraint::Percentage(16),
+                    Constraint::Percentage(16),
+                ];
+            }
+            let header_cells = headers
+                .iter()
+                .map(|h| Cell::from(format!("  {}", h)).style(Style::default().fg(theme().title)));
             let header = Row::new(header_cells)
-                .style(Style::default().bg(Color::Rgb(80, 60, 130)))
+                .style(Style::default().bg(theme().header))
                 .height(1);
+            let columns = column_widths(layout[1].inner(Margin::new(1, 1)), &widths, 1, "");
+
             let rows = invoices_for_patient.iter().map(|invoice| {
-                let cells = vec![
-                    Cell::from(format!("  {}", invoice.item))
-                        .style(Style::default().fg(Color::Rgb(220, 220, 240))),
-                    Cell::from(format!("  {}", invoice.quantity))
-                        .style(Style::default().fg(Color::Rgb(220, 220, 240))),
-                    Cell::from(format!("  ${:.2}", invoice.cost))
-                        .style(Style::default().fg(Color::Rgb(220, 220, 240))),
+                let label = match &invoice.procedure_code {
+                    Some(code) => format!("{} [{}]", invoice.item, code),
+                    None => invoice.item.clone(),
+                };
+                // Billed twice on purpose: keep the reason next to the item.
+                let item = match &invoice.duplicate_note {
+                    _ if invoice.held => cell(
+                        format!("  {}", tf("invoices.awaiting", &[("item", &label)])),
+                        &columns,
+                        0,
+                    )
+                    .style(Style::default().fg(theme().warning)),
+                    Some(note) => cell(format!("  {} ⚠ {}", label, note), &columns, 0)
+                        .style(Style::default().fg(theme().warning)),
+                    None => cell(format!("  {}", label), &columns, 0)
+                        .style(Style::default().fg(theme().text)),
+                };
+                let mut cells = vec![
+                    item,
+                    cell(format!("  {}", invoice.quantity), &columns, 1)
+                        .style(Style::default().fg(theme().text)),
+                    cell(format!("  {}", money(invoice.cost)), &columns, 2)
+                        .style(Style::default().fg(theme().text)),
                 ];
+                if insured {
+                    let share = invoice.insurer_share.map(money).unwrap_or_default();
+                    cells.push(
+                        cell(format!("  {}", share), &columns, 3)
+                            .style(Style::default().fg(theme().accent)),
+                    );
+                    cells.push(
+                        cell(format!("  {}", money(invoice.co_pay())), &columns, 4)
+                            .style(Style::default().fg(theme().text)),
+                    );
+                }
                 Row::new(cells).height(1).bottom_margin(0)
             });
             let title_text = vec![
                 Span::styled(
-                    "Invoices for Patient: ",
-                    Style::default().fg(Color::Rgb(230, 230, 250)),
+                    format!("{} ", t("invoices.for_patient")),
+                    Style::default().fg(theme().title),
                 ),
                 Span::styled(
                     patient_name,
                     Style::default()
-                        .fg(Color::Rgb(250, 250, 110))
+                        .fg(theme().focus)
                         .add_modifier(Modifier::BOLD),
                 ),
             ];
             let title = Line::from(title_text);
-            let table = Table::new(
-                rows,
-                [
-                    Constraint::Percentage(50),
-                    Constraint::Percentage(20),
-                    Constraint::Percentage(30),
-                ],
-            )
-            .header(header)
-            .block(
-                Block::default()
-                    .title(title)
-                    .title_alignment(Alignment::Center)
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-                    .style(Style::default().bg(Color::Rgb(22, 22, 35))),
-            )
-            .row_highlight_style(
-                Style::default()
-                    .fg(Color::Rgb(250, 250, 110))
-                    .bg(Color::Rgb(40, 40, 60))
-                    .add_modifier(Modifier::BOLD),
-            );
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(
+                    Block::default()
+                        .title(title)
+                        .title_alignment(Alignment::Center)
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                )
+                .row_highlight_style(
+                    Style::default()
+                        .fg(theme().focus)
+                        .bg(theme().highlight)
+                        .add_modifier(Modifier::BOLD),
+                );
             frame.render_widget(table, layout[1]);
-            let total_cost: f64 = invoices_for_patient
+            let billed: Vec<&&Invoice> = invoices_for_patient
                 .iter()
-                .map(|invoice| invoice.cost)
-                .sum();
-            let total_cost_paragraph = Paragraph::new(format!("Total Cost: ${:.2}", total_cost))
+                .filter(|invoice| !invoice.held)
+                .collect();
+            let total_cost: f64 = billed.iter().map(|invoice| invoice.cost).sum();
+            let mut total_text = tf("invoices.total", &[("amount", &money(total_cost))]);
+            let held = invoices_for_patient.len() - billed.len();
+            if held > 0 {
+                total_text.push_str(&format!("  {}", tf("invoices.held", &[("count", &held)])));
+            }
+            if insured {
+                let co_pay: f64 = billed.iter().map(|i| i.co_pay()).sum();
+                total_text.push_str(&format!(
+                    "  {}",
+                    tf(
+                        "invoices.shares",
+                        &[
+                            ("insurer", &(money(total_cost - co_pay))),
+                            ("co_pay", &money(co_pay))
+                        ]
+                    )
+                ));
+            }
+            let total_cost_paragraph = Paragraph::new(total_text)
                 .style(
                     Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
+                        .fg(theme().success)
                         .add_modifier(Modifier::BOLD),
                 )
                 .alignment(Alignment::Center);
             frame.render_widget(total_cost_paragraph, layout[2]);
         } else {
             frame.render_widget(
-                Paragraph::new("No patient selected.")
-                    .style(Style::default().fg(Color::Red))
+                Paragraph::new(t("invoices.none_selected"))
+                    .style(Style::default().fg(theme().error))
                     .alignment(Alignment::Center),
                 layout[1],
             );
         }
-        let help_text = "Enter/Esc/Backspace: Return to list";
+        let help_text = t("invoices.details_help");
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(140, 140, 170)))
+            .style(Style::default().fg(theme().help))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, layout[3]);
     }
 }
-impl Default for ViewInvoices {
-    fn default() -> Self {
-        Self::new()
-    }
-}
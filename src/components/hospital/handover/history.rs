@@ -0,0 +1,474 @@
+use crate::app::SelectedApp;
+use crate::components::hospital::staff::shift_label;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{HandoverNote, StaffMember};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state::{self, ViewState};
+use crate::utils::datetime::{self, now_text};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+use time::Date;
+
+const VIEW_KEY: &str = "handover.history";
+
+pub struct HandoverHistory {
+    db: Db,
+    day: Date,
+    notes: Vec<HandoverNote>,
+    filtered_notes: Vec<HandoverNote>,
+    staff: HashMap<i64, StaffMember>,
+    wards: Vec<String>,
+    ward_filter: Option<usize>,
+    /// Ward filter remembered from the last session, applied once the notes
+    /// are loaded.
+    restore_ward: Option<String>,
+    state: TableState,
+    acknowledge_input: Option<String>,
+}
+
+impl HandoverHistory {
+    pub fn new(db: &Db) -> Self {
+        let view = ui_state::view(VIEW_KEY);
+        Self {
+            db: db.clone(),
+            day: datetime::today(),
+            notes: Vec::new(),
+            filtered_notes: Vec::new(),
+            staff: HashMap::new(),
+            wards: Vec::new(),
+            ward_filter: None,
+            restore_ward: view.filter("ward").map(str::to_string),
+            state: view.table(),
+            acknowledge_input: None,
+        }
+    }
+
+    fn day_text(&self) -> String {
+        datetime::format_date(self.day)
+    }
+
+    pub fn fetch_notes(&mut self) -> Result<()> {
+        self.notes = db::get_all_handover_notes(&self.db)?;
+        self.staff = db::get_all_staff(&self.db)?
+            .into_iter()
+            .map(|s| (s.id, s))
+            .collect();
+
+        let selected_ward = self
+            .ward_filter
+            .map(|i| self.wards[i].clone())
+            .or(self.restore_ward.take());
+        self.wards = self.notes.iter().map(|n| n.ward.clone()).collect();
+        self.wards.sort();
+        self.wards.dedup();
+        self.ward_filter = selected_ward.and_then(|w| self.wards.iter().position(|x| *x == w));
+
+        self.filter_notes();
+        Ok(())
+    }
+
+    pub fn save_view(&self) {
+        let ward = self
+            .ward_filter
+            .map(|i| self.wards[i].as_str())
+            .or(self.restore_ward.as_deref());
+        let view = ViewState::default().with_filter("ward", ward);
+        ui_state::set_view(VIEW_KEY, view.with_table(&self.state));
+    }
+
+    fn filter_notes(&mut self) {
+        let day = self.day_text();
+        let ward = self.ward_filter.map(|i| self.wards[i].as_str());
+        self.filtered_notes = self
+            .notes
+            .iter()
+            .filter(|n| n.handover_date == day)
+            .filter(|n| ward.is_none_or(|w| n.ward == w))
+            .cloned()
+            .collect();
+
+        match self.state.selected() {
+            _ if self.filtered_notes.is_empty() => self.state.select(None),
+            Some(i) if i < self.filtered_notes.len() => {}
+            _ => self.state.select(Some(0)),
+        }
+    }
+
+    fn staff_name(&self, staff_id: i64) -> String {
+        self.staff
+            .get(&staff_id)
+            .map(|s| s.name.clone())
+            .unwrap_or_else(|| tf("common.unknown_number", &[("id", &staff_id)]))
+    }
+
+    fn selected_note(&self) -> Option<&HandoverNote> {
+        self.state
+            .selected()
+            .and_then(|i| self.filtered_notes.get(i))
+    }
+
+    fn change_day(&mut self, forward: bool) {
+        let next = if forward {
+            self.day.next_day()
+        } else {
+            self.day.previous_day()
+        };
+        if let Some(day) = next {
+            self.day = day;
+            self.state.select(None);
+            self.filter_notes();
+        }
+    }
+
+    fn cycle_ward_filter(&mut self) {
+        self.ward_filter = match self.ward_filter {
+            None if !self.wards.is_empty() => Some(0),
+            Some(i) if i + 1 < self.wards.len() => Some(i + 1),
+            _ => None,
+        };
+        self.state.select(None);
+        self.filter_notes();
+    }
+
+    fn open_acknowledge(&mut self) {
+        match self.selected_note() {
+            Some(note) if note.acknowledged_by.is_some() => {
+                toast::push(Toast::error(t("handover.already_acknowledged")))
+            }
+            Some(_) => self.acknowledge_input = Some(String::new()),
+            None => {}
+        }
+    }
+
+    fn acknowledge(&mut self) -> Result<()> {
+        let (Some(input), Some(note)) = (&self.acknowledge_input, self.selected_note()) else {
+            return Ok(());
+        };
+        let Some(staff_id) = input
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .filter(|id| self.staff.contains_key(id))
+        else {
+            toast::push(Toast::error(t("common.unknown_staff")));
+            return Ok(());
+        };
+        if staff_id == note.author_id {
+            toast::push(Toast::error(t("handover.incoming_only")));
+            return Ok(());
+        }
+
+        let note_id = note.id;
+        let ward = note.ward.clone();
+        match db::acknowledge_handover_note(&self.db, note_id, staff_id, &now_text()) {
+            Ok(()) => {
+                self.acknowledge_input = None;
+                toast::push(Toast::success(tf(
+                    "handover.acknowledged",
+                    &[("ward", &ward), ("name", &self.staff_name(staff_id))],
+                )));
+                self.fetch_notes()?;
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "handover.acknowledge_failed",
+                &[("error", &e)],
+            ))),
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(input) = &mut self.acknowledge_input {
+            match key.code {
+                KeyCode::Char(c) if c.is_ascii_digit() => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => self.acknowledge()?,
+                KeyCode::Esc => self.acknowledge_input = None,
+                _ => {}
+            }
+            return Ok(None);
+        }
+
+        match key.code {
+            KeyCode::Down if !self.filtered_notes.is_empty() => {
+                let len = self.filtered_notes.len();
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if !self.filtered_notes.is_empty() => {
+                let len = self.filtered_notes.len();
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Left => self.change_day(false),
+            KeyCode::Right => self.change_day(true),
+            KeyCode::Char('t') | KeyCode::Char('T') => {
+                self.day = datetime::today();
+                self.filter_notes();
+            }
+            KeyCode::Char('w') | KeyCode::Char('W') => self.cycle_ward_filter(),
+            KeyCode::Char('a') | KeyCode::Char('A') | KeyCode::Enter => self.open_acknowledge(),
+            KeyCode::Char('r') | KeyCode::Char('R') => self.fetch_notes()?,
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_acknowledge_dialog(&self, frame: &mut Frame, input: &str) {
+        let area = frame.area();
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(50) / 2,
+            area.height.saturating_sub(7) / 2,
+            50.min(area.width),
+            7.min(area.height),
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" {} ", t("handover.acknowledge_title")))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(1)])
+            .horizontal_margin(1)
+            .split(inner);
+
+        let title = match input.parse::<i64>().ok().and_then(|id| self.staff.get(&id)) {
+            Some(s) => format!(" {} ", tf("handover.incoming_named", &[("name", &s.name)])),
+            None => format!(" {} ", t("handover.incoming")),
+        };
+        frame.render_widget(
+            Paragraph::new(input)
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(title)
+                        .border_style(Style::default().fg(theme().focus)),
+                ),
+            chunks[0],
+        );
+        frame.render_widget(
+            Paragraph::new(t("handover.acknowledge_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[1],
+        );
+    }
+}
+
+impl Component for HandoverHistory {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(6),
+                Constraint::Length(9),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(t("handover.history_title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let ward_label = match self.ward_filter {
+            Some(i) => self.wards[i].clone(),
+            None => t("handover.all_wards").to_string(),
+        };
+        let pending = self
+            .filtered_notes
+            .iter()
+            .filter(|n| n.acknowledged_by.is_none())
+            .count();
+        frame.render_widget(
+            Paragraph::new(tf(
+                "handover.day_summary",
+                &[
+                    ("day", &self.day_text()),
+                    ("ward", &ward_label),
+                    ("count", &pending),
+                ],
+            ))
+            .style(Style::default().fg(theme().accent))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("handover.day_ward")))
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            ),
+            layout[1],
+        );
+
+        let widths = [
+            Constraint::Percentage(25),
+            Constraint::Length(10),
+            Constraint::Percentage(25),
+            Constraint::Length(10),
+            Constraint::Min(12),
+        ];
+        let columns = column_widths(layout[2].inner(Margin::new(1, 1)), &widths, 1, "► ");
+
+        let rows = self.filtered_notes.iter().map(|n| {
+            let (acknowledged, style) = match n.acknowledged_by {
+                Some(id) => (self.staff_name(id), Style::default().fg(theme().success)),
+                None => (
+                    t("handover.pending").to_string(),
+                    Style::default()
+                        .fg(theme().focus)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            };
+            Row::new(vec![
+                cell(&n.ward, &columns, 0),
+                cell(shift_label(&n.shift), &columns, 1),
+                cell(self.staff_name(n.author_id), &columns, 2),
+                cell(datetime::time_part(&n.created_at), &columns, 3),
+                cell(acknowledged, &columns, 4),
+            ])
+            .style(style)
+        });
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec![
+                    t("handover.ward"),
+                    t("handover.shift"),
+                    t("handover.handed_over_by"),
+                    t("handover.time"),
+                    t("handover.acknowledged_by"),
+                ])
+                .style(Style::default().bg(theme().header).fg(theme().title)),
+            )
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(
+                        " {} ",
+                        tf(
+                            "handover.handovers",
+                            &[("count", &self.filtered_notes.len())]
+                        )
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .row_highlight_style(
+                Style::default()
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
+
+        let details = match self.selected_note() {
+            Some(n) => {
+                let mut lines = vec![
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{} ", t("handover.critical")),
+                            Style::default().fg(theme().error),
+                        ),
+                        Span::raw(n.critical_patients.clone()),
+                    ]),
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{} ", t("handover.tasks")),
+                            Style::default().fg(theme().focus),
+                        ),
+                        Span::raw(n.pending_tasks.clone()),
+                    ]),
+                ];
+                if let Some(notes) = &n.notes {
+                    lines.push(Line::from(vec![
+                        Span::styled(
+                            format!("{} ", t("handover.notes")),
+                            Style::default().fg(theme().accent),
+                        ),
+                        Span::raw(notes.clone()),
+                    ]));
+                }
+                if let Some(at) = &n.acknowledged_at {
+                    lines.push(Line::from(Span::styled(
+                        tf("handover.acknowledged_at", &[("time", &at)]),
+                        Style::default().fg(theme().success),
+                    )));
+                }
+                lines
+            }
+            None => vec![Line::from(t("handover.none"))],
+        };
+        frame.render_widget(
+            Paragraph::new(details)
+                .style(Style::default().fg(theme().text))
+                .wrap(Wrap { trim: true })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("handover.details")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[3],
+        );
+
+        frame.render_widget(
+            Paragraph::new(t("handover.history_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[5],
+        );
+
+        if let Some(input) = &self.acknowledge_input {
+            self.render_acknowledge_dialog(frame, input);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::app::SelectedApp;
+use crate::components::Component;
+use crate::db::Db;
+use crate::tui::Frame;
+use anyhow::Result;
+use crossterm::event::KeyEvent;
+
+pub mod history;
+pub mod record;
+
+pub const SHIFTS: [&str; 3] = ["Morning", "Afternoon", "Night"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandoverState {
+    Record,
+    History,
+}
+
+pub struct Handover {
+    pub record: record::RecordHandover,
+    pub history: history::HandoverHistory,
+    pub state: HandoverState,
+}
+
+impl Handover {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            record: record::RecordHandover::new(db),
+            history: history::HandoverHistory::new(db),
+            state: HandoverState::Record,
+        }
+    }
+
+    pub fn save_view(&self) {
+        self.history.save_view();
+    }
+
+    pub fn initialize_list(&mut self) -> Result<()> {
+        match self.state {
+            HandoverState::Record => self.record.load_data()?,
+            HandoverState::History => self.history.fetch_notes()?,
+        }
+        Ok(())
+    }
+}
+
+impl Component for Handover {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        let action = match self.state {
+            HandoverState::Record => self.record.handle_input(event)?,
+            HandoverState::History => self.history.handle_input(event)?,
+        };
+        if let Some(SelectedApp::None) = action {
+            return Ok(Some(SelectedApp::None));
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        match self.state {
+            HandoverState::Record => self.record.render(frame),
+            HandoverState::History => self.history.render(frame),
+        }
+    }
+}
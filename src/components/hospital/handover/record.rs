@@ -0,0 +1,313 @@
+use crate::app::SelectedApp;
+use crate::components::hospital::handover::SHIFTS;
+use crate::components::hospital::staff::shift_label;
+use crate::components::quick_actions;
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{HandoverNote, StaffMember};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime::{self, now_text, today_text};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+
+const WARD_INPUT: usize = 0;
+const SHIFT_SELECT: usize = 1;
+const AUTHOR_INPUT: usize = 2;
+const CRITICAL_INPUT: usize = 3;
+const TASKS_INPUT: usize = 4;
+const NOTES_INPUT: usize = 5;
+const SUBMIT_BUTTON: usize = 6;
+const BACK_BUTTON: usize = 7;
+
+pub struct RecordHandover {
+    db: Db,
+    staff: HashMap<i64, StaffMember>,
+    ward_input: String,
+    shift_index: usize,
+    author_input: String,
+    critical_input: String,
+    tasks_input: String,
+    notes_input: String,
+    focus_index: usize,
+}
+
+impl RecordHandover {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            staff: HashMap::new(),
+            ward_input: String::new(),
+            shift_index: 0,
+            author_input: String::new(),
+            critical_input: String::new(),
+            tasks_input: String::new(),
+            notes_input: String::new(),
+            focus_index: WARD_INPUT,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.staff = db::get_all_staff(&self.db)?
+            .into_iter()
+            .map(|s| (s.id, s))
+            .collect();
+        Ok(())
+    }
+
+    fn resolved_author(&self) -> Option<&StaffMember> {
+        self.author_input
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|id| self.staff.get(&id))
+    }
+
+    fn active_input(&mut self) -> Option<&mut String> {
+        match self.focus_index {
+            WARD_INPUT => Some(&mut self.ward_input),
+            AUTHOR_INPUT => Some(&mut self.author_input),
+            CRITICAL_INPUT => Some(&mut self.critical_input),
+            TASKS_INPUT => Some(&mut self.tasks_input),
+            NOTES_INPUT => Some(&mut self.notes_input),
+            _ => None,
+        }
+    }
+
+    fn submit(&mut self) -> Result<()> {
+        let ward = self.ward_input.trim().to_string();
+        if ward.is_empty() {
+            toast::push(Toast::error(t("handover.ward_required")));
+            return Ok(());
+        }
+        let Some(author_id) = self.resolved_author().map(|s| s.id) else {
+            toast::push(Toast::error(t("handover.author_unknown")));
+            return Ok(());
+        };
+        if self.critical_input.trim().is_empty() && self.tasks_input.trim().is_empty() {
+            toast::push(Toast::error(t("handover.nothing")));
+            return Ok(());
+        }
+
+        let note = HandoverNote {
+            id: 0,
+            ward: ward.clone(),
+            handover_date: today_text(),
+            shift: SHIFTS[self.shift_index].to_string(),
+            author_id,
+            critical_patients: self.critical_input.trim().to_string(),
+            pending_tasks: self.tasks_input.trim().to_string(),
+            notes: Some(self.notes_input.trim().to_string()).filter(|n| !n.is_empty()),
+            created_at: now_text(),
+            acknowledged_by: None,
+            acknowledged_at: None,
+        };
+        match db::create_handover_note(&self.db, &note) {
+            Ok(_) => {
+                self.critical_input.clear();
+                self.tasks_input.clear();
+                self.notes_input.clear();
+                self.focus_index = WARD_INPUT;
+                quick_actions::saved(SelectedApp::HandoverRecord);
+                toast::push(Toast::success(tf(
+                    "handover.recorded",
+                    &[("shift", &shift_label(&note.shift)), ("ward", &ward)],
+                )));
+            }
+            Err(e) => toast::push(Toast::error(tf("handover.record_failed", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        match key.code {
+            KeyCode::Left | KeyCode::Right if self.focus_index == SHIFT_SELECT => {
+                let len = SHIFTS.len();
+                self.shift_index = if key.code == KeyCode::Right {
+                    (self.shift_index + 1) % len
+                } else {
+                    (self.shift_index + len - 1) % len
+                };
+            }
+            KeyCode::Char(c) if self.focus_index != SHIFT_SELECT => {
+                if let Some(input) = self.active_input() {
+                    input.push(c);
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(input) = self.active_input() {
+                    input.pop();
+                }
+            }
+            KeyCode::Tab | KeyCode::Down => {
+                self.focus_index = (self.focus_index + 1) % (BACK_BUTTON + 1);
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                self.focus_index = (self.focus_index + BACK_BUTTON) % (BACK_BUTTON + 1);
+            }
+            KeyCode::Enter => match self.focus_index {
+                SUBMIT_BUTTON => self.submit()?,
+                BACK_BUTTON => return Ok(Some(SelectedApp::None)),
+                _ => self.focus_index += 1,
+            },
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+impl Component for RecordHandover {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(tf(
+                "handover.title",
+                &[("date", &datetime::display_date(datetime::today()))],
+            ))
+            .style(
+                Style::default()
+                    .fg(theme().title)
+                    .add_modifier(Modifier::BOLD)
+                    .bg(theme().background),
+            )
+            .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let author_label = match self.resolved_author() {
+            Some(s) => format!(" {} ", tf("handover.outgoing_named", &[("name", &s.name)])),
+            None => format!(" {} ", t("handover.outgoing")),
+        };
+        let shift_text = format!("◄ {} ►", shift_label(SHIFTS[self.shift_index]));
+        let fields = [
+            (
+                format!(" {} ", t("handover.ward_field")),
+                self.ward_input.as_str(),
+                WARD_INPUT,
+            ),
+            (
+                format!(" {} ", t("handover.outgoing_shift")),
+                shift_text.as_str(),
+                SHIFT_SELECT,
+            ),
+            (author_label, self.author_input.as_str(), AUTHOR_INPUT),
+            (
+                format!(" {} ", t("handover.critical_field")),
+                self.critical_input.as_str(),
+                CRITICAL_INPUT,
+            ),
+            (
+                format!(" {} ", t("handover.tasks_field")),
+                self.tasks_input.as_str(),
+                TASKS_INPUT,
+            ),
+            (
+                format!(" {} ", t("handover.notes_field")),
+                self.notes_input.as_str(),
+                NOTES_INPUT,
+            ),
+        ];
+        for (i, (title, value, index)) in fields.into_iter().enumerate() {
+            let border_style = if self.focus_index == index {
+                Style::default().fg(theme().focus)
+            } else {
+                Style::default().fg(theme().unfocused)
+            };
+            frame.render_widget(
+                Paragraph::new(value)
+                    .style(Style::default().fg(theme().text))
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .border_type(BorderType::Rounded)
+                            .title(title)
+                            .border_style(border_style)
+                            .style(Style::default().bg(theme().input)),
+                    ),
+                layout[i + 1],
+            );
+        }
+
+        let submit_style = if self.focus_index == SUBMIT_BUTTON {
+            Style::default()
+                .fg(theme().success)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        frame.render_widget(
+            Paragraph::new(if self.focus_index == SUBMIT_BUTTON {
+                format!("► {} ◄", t("handover.record"))
+            } else {
+                format!("  {}  ", t("handover.record"))
+            })
+            .style(submit_style)
+            .alignment(Alignment::Center),
+            layout[8],
+        );
+
+        let back_style = if self.focus_index == BACK_BUTTON {
+            Style::default()
+                .fg(theme().accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        frame.render_widget(
+            Paragraph::new(if self.focus_index == BACK_BUTTON {
+                format!("► {} ◄", t("common.back"))
+            } else {
+                format!("  {}  ", t("common.back"))
+            })
+            .style(back_style)
+            .alignment(Alignment::Center),
+            layout[9],
+        );
+
+        frame.render_widget(
+            Paragraph::new(t("handover.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[12],
+        );
+    }
+}
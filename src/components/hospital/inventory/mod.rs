@@ -0,0 +1,73 @@
+//! Stock kept at the hospital. Every receipt, issue and correction is a
+//! movement in a ledger that records who made it and when, and what is
+//! on hand is their sum. A stocktake freezes what the ledger expects,
+//! takes the counts (typed or scanned), reports the variance and posts
+//! the differences as adjustments.
+
+use crate::app::SelectedApp;
+use crate::auth;
+use crate::components::Component;
+use crate::db::Db;
+use crate::tui::Frame;
+use crate::ui_state;
+use anyhow::Result;
+use crossterm::event::KeyEvent;
+
+pub mod stock;
+pub mod stocktake;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryState {
+    Stock,
+    Stocktake,
+}
+
+/// Whether the signed-in user may post or abandon a stocktake. Anyone can
+/// count.
+fn may_close_stocktakes(db: &Db) -> bool {
+    ui_state::current_user().is_some_and(|user| auth::is_admin(db, &user))
+}
+
+/// Who a movement is posted by.
+fn posted_by() -> String {
+    ui_state::current_user().unwrap_or_else(|| "unknown".to_string())
+}
+
+pub struct Inventory {
+    pub stock: stock::StockList,
+    pub stocktake: stocktake::StocktakeScreen,
+    pub state: InventoryState,
+}
+
+impl Inventory {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            stock: stock::StockList::new(db),
+            stocktake: stocktake::StocktakeScreen::new(db),
+            state: InventoryState::Stock,
+        }
+    }
+
+    pub fn initialize_list(&mut self) -> Result<()> {
+        match self.state {
+            InventoryState::Stock => self.stock.fetch_items(),
+            InventoryState::Stocktake => self.stocktake.fetch_stocktake(),
+        }
+    }
+}
+
+impl Component for Inventory {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        match self.state {
+            InventoryState::Stock => self.stock.handle_input(event),
+            InventoryState::Stocktake => self.stocktake.handle_input(event),
+        }
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        match self.state {
+            InventoryState::Stock => self.stock.render(frame),
+            InventoryState::Stocktake => self.stocktake.render(frame),
+        }
+    }
+}
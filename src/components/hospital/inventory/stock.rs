@@ -0,0 +1,564 @@
+use super::posted_by;
+use crate::app::SelectedApp;
+use crate::components::form::{integer_range, Field, Form};
+use crate::components::table::{cell, column_widths, numeric_cell};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::export_view::{self, ExportView, ShownRows};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{InventoryKind, InventoryTransaction, StockItem};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+const HEADERS: [&str; 4] = [
+    "stock.column.code",
+    "stock.column.item",
+    "stock.column.unit",
+    "stock.column.on_hand",
+];
+
+const CODE: usize = 0;
+const NAME: usize = 1;
+const UNIT: usize = 2;
+
+const QUANTITY: usize = 0;
+const REFERENCE: usize = 1;
+
+enum PopupKind {
+    Item,
+    /// A receipt or an issue of the selected item.
+    Movement(InventoryKind),
+}
+
+struct Popup {
+    kind: PopupKind,
+    form: Form,
+    focus_index: usize,
+}
+
+/// The movements of one item, newest first.
+struct History {
+    item: StockItem,
+    transactions: Vec<InventoryTransaction>,
+}
+
+fn kind_text(kind: InventoryKind) -> &'static str {
+    match kind {
+        InventoryKind::Receipt => t("stock.received"),
+        InventoryKind::Issue => t("stock.issued"),
+        InventoryKind::Adjustment => t("stock.adjusted"),
+    }
+}
+
+/// The stock items with what is on hand, where stock is received and
+/// issued.
+pub struct StockList {
+    db: Db,
+    items: Vec<StockItem>,
+    state: TableState,
+    /// Whether a stocktake is being counted, which the header mentions.
+    open_stocktake: Option<i64>,
+    popup: Option<Popup>,
+    history: Option<History>,
+    export_view: Option<ExportView>,
+}
+
+impl StockList {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            items: Vec::new(),
+            state: TableState::default(),
+            open_stocktake: None,
+            popup: None,
+            history: None,
+            export_view: None,
+        }
+    }
+
+    pub fn fetch_items(&mut self) -> Result<()> {
+        self.items = db::get_stock_items(&self.db)?;
+        self.open_stocktake = db::get_open_stocktake(&self.db)?.map(|stocktake| stocktake.id);
+        let len = self.items.len();
+        match self.state.selected() {
+            _ if len == 0 => self.state.select(None),
+            Some(i) if i < len => {}
+            _ => self.state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) {
+        if let Err(e) = self.fetch_items() {
+            toast::push(Toast::error(tf("stock.load_failed", &[("error", &e)])));
+        }
+    }
+
+    fn selected_item(&self) -> Option<&StockItem> {
+        self.items.get(self.state.selected()?)
+    }
+
+    fn row_values(item: &StockItem) -> Vec<String> {
+        vec![
+            item.code.clone(),
+            item.name.clone(),
+            item.unit.clone(),
+            item.on_hand.to_string(),
+        ]
+    }
+
+    fn shown_rows(&self) -> ShownRows {
+        let mut shown = ShownRows::new(&HEADERS);
+        shown.rows = self.items.iter().map(Self::row_values).collect();
+        shown
+    }
+
+    fn open_item_popup(&mut self) {
+        let form = Form::new(vec![
+            Field::new(t("stock.code")).required(),
+            Field::new(t("stock.name")).required(),
+            Field::new(t("stock.unit")).required(),
+        ]);
+        self.popup = Some(Popup {
+            kind: PopupKind::Item,
+            form,
+            focus_index: CODE,
+        });
+    }
+
+    fn open_movement_popup(&mut self, kind: InventoryKind) {
+        if self.selected_item().is_none() {
+            return;
+        }
+        let form = Form::new(vec![
+            Field::new(t("stock.quantity"))
+                .required()
+                .validate_with(integer_range(1, 1_000_000)),
+            Field::new(match kind {
+                InventoryKind::Receipt => t("stock.delivery_note"),
+                _ => t("stock.issued_to"),
+            }),
+        ]);
+        self.popup = Some(Popup {
+            kind: PopupKind::Movement(kind),
+            form,
+            focus_index: QUANTITY,
+        });
+    }
+
+    fn save_popup(&mut self) -> Result<()> {
+        let Some(mut popup) = self.popup.take() else {
+            return Ok(());
+        };
+        if let Some(invalid) = popup.form.validate() {
+            popup.focus_index = invalid;
+            self.popup = Some(popup);
+            return Ok(());
+        }
+        let form = &popup.form;
+        let result = match popup.kind {
+            PopupKind::Item => {
+                let name = form.value(NAME).trim().to_string();
+                db::create_stock_item(&self.db, &form.value(CODE), &name, &form.value(UNIT))
+                    .map(|_| tf("stock.added", &[("name", &name)]))
+            }
+            PopupKind::Movement(kind) => {
+                let Some(item) = self.selected_item().cloned() else {
+                    return Ok(());
+                };
+                let quantity: i64 = form.value(QUANTITY).parse().unwrap_or_default();
+                let signed = if kind == InventoryKind::Issue {
+                    -quantity
+                } else {
+                    quantity
+                };
+                db::record_stock_movement(
+                    &self.db,
+                    item.id,
+                    kind,
+                    signed,
+                    form.optional_value(REFERENCE).as_deref(),
+                    &posted_by(),
+                )
+                .map(|()| {
+                    tf(
+                        "stock.moved",
+                        &[
+                            ("movement", &kind_text(kind)),
+                            ("quantity", &quantity),
+                            ("unit", &item.unit),
+                            ("item", &item.name),
+                        ],
+                    )
+                })
+            }
+        };
+        match result {
+            Ok(message) => {
+                toast::push(Toast::success(message));
+                self.fetch_items()?;
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("insurance.save_failed", &[("error", &e)])));
+                self.popup = Some(popup);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_popup_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(popup) = self.popup.as_mut() else {
+            return Ok(());
+        };
+        let len = popup.form.len();
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Tab | KeyCode::Down => popup.focus_index = (popup.focus_index + 1) % len,
+            KeyCode::BackTab | KeyCode::Up => {
+                popup.focus_index = (popup.focus_index + len - 1) % len
+            }
+            KeyCode::Enter if popup.focus_index + 1 < len => popup.focus_index += 1,
+            KeyCode::Enter => self.save_popup()?,
+            _ => {
+                popup.form.handle_key(popup.focus_index, key);
+            }
+        }
+        Ok(())
+    }
+
+    fn open_history(&mut self) {
+        let Some(item) = self.selected_item().cloned() else {
+            return;
+        };
+        match db::get_item_transactions(&self.db, item.id) {
+            Ok(transactions) => self.history = Some(History { item, transactions }),
+            Err(e) => toast::push(Toast::error(tf("stock.movements_failed", &[("error", &e)]))),
+        }
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(export) = &mut self.export_view {
+            if export.handle_key(key) {
+                self.export_view = None;
+            }
+            return Ok(None);
+        }
+        if self.popup.is_some() {
+            self.handle_popup_input(key)?;
+            return Ok(None);
+        }
+        if self.history.is_some() {
+            if matches!(
+                key.code,
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('h') | KeyCode::Char('H')
+            ) {
+                self.history = None;
+            }
+            return Ok(None);
+        }
+        if export_view::is_open_key(key) {
+            self.export_view = Some(ExportView::new("stock", self.shown_rows()));
+            return Ok(None);
+        }
+
+        let len = self.items.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => self.open_item_popup(),
+            KeyCode::Char('+') => self.open_movement_popup(InventoryKind::Receipt),
+            KeyCode::Char('-') => self.open_movement_popup(InventoryKind::Issue),
+            KeyCode::Char('h') | KeyCode::Char('H') | KeyCode::Enter => self.open_history(),
+            KeyCode::Char('s') | KeyCode::Char('S') => {
+                return Ok(Some(SelectedApp::InventoryStocktake));
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => self.reload(),
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("stock.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+
+        let out_of_stock = self.items.iter().filter(|i| i.on_hand <= 0).count();
+        let mut summary = vec![
+            Span::styled(
+                tf("stock.items", &[("count", &self.items.len())]),
+                Style::default().fg(theme().text),
+            ),
+            Span::raw("  ·  "),
+            Span::styled(
+                tf("stock.out_of_stock", &[("count", &out_of_stock)]),
+                Style::default().fg(if out_of_stock > 0 {
+                    theme().error
+                } else {
+                    theme().inactive
+                }),
+            ),
+        ];
+        if let Some(id) = self.open_stocktake {
+            summary.push(Span::raw("  ·  "));
+            summary.push(Span::styled(
+                tf("stock.being_counted", &[("id", &id)]),
+                Style::default().fg(theme().warning),
+            ));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(summary)).alignment(Alignment::Center),
+            layout[1],
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("stock.items_title")))
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.items.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("stock.empty"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[2],
+            );
+        } else {
+            let widths = [
+                Constraint::Length(16),
+                Constraint::Min(20),
+                Constraint::Length(10),
+                Constraint::Length(9),
+            ];
+            let columns = column_widths(block.inner(layout[2]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .items
+                .iter()
+                .map(|item| {
+                    let values = Self::row_values(item);
+                    Row::new(vec![
+                        cell(&values[0], &columns, 0),
+                        cell(&values[1], &columns, 1),
+                        cell(&values[2], &columns, 2),
+                        numeric_cell(&values[3], &columns, 3),
+                    ])
+                    .style(Style::default().fg(if item.on_hand <= 0 {
+                        theme().error
+                    } else {
+                        theme().text
+                    }))
+                })
+                .collect();
+            let header = Row::new(HEADERS.map(t).to_vec())
+                .style(Style::default().bg(theme().header).fg(theme().title));
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(block)
+                .row_highlight_style(
+                    Style::default()
+                        .fg(theme().focus)
+                        .bg(theme().highlight)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("stock.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+
+        if let Some(popup) = &self.popup {
+            self.render_popup(frame, popup);
+        }
+        if let Some(history) = &self.history {
+            render_history(frame, history);
+        }
+        if let Some(export) = &self.export_view {
+            export.render_popup(frame);
+        }
+    }
+
+    fn render_popup(&self, frame: &mut Frame, popup: &Popup) {
+        let area = frame.area();
+        let height = (popup.form.len() as u16 * 3 + 3).min(area.height);
+        let width = 60.min(area.width);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let item_name = self
+            .selected_item()
+            .map(|item| format!("{} ({})", item.name, item.unit))
+            .unwrap_or_default();
+        let title = match popup.kind {
+            PopupKind::Item => format!(" {} ", t("stock.new_item")),
+            PopupKind::Movement(InventoryKind::Receipt) => {
+                format!(" {} ", tf("stock.receive", &[("item", &item_name)]))
+            }
+            PopupKind::Movement(_) => format!(" {} ", tf("stock.issue", &[("item", &item_name)])),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let mut constraints = vec![Constraint::Length(3); popup.form.len()];
+        constraints.push(Constraint::Min(1));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .horizontal_margin(1)
+            .split(inner);
+        for index in 0..popup.form.len() {
+            popup
+                .form
+                .render_field(frame, index, chunks[index], popup.focus_index);
+        }
+        frame.render_widget(
+            Paragraph::new(t("common.form_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[popup.form.len()],
+        );
+    }
+}
+
+fn render_history(frame: &mut Frame, history: &History) {
+    let area = frame.area();
+    let width = 90.min(area.width);
+    let height = 20.min(area.height);
+    let dialog_area = Rect::new(
+        area.width.saturating_sub(width) / 2,
+        area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    );
+    frame.render_widget(Clear, dialog_area);
+    let block = Block::default()
+        .title(format!(
+            " {} ",
+            tf(
+                "stock.on_hand",
+                &[
+                    ("item", &history.item.name),
+                    ("quantity", &history.item.on_hand),
+                    ("unit", &history.item.unit)
+                ]
+            )
+        ))
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme().focus))
+        .style(Style::default().bg(theme().dialog));
+    let inner = block.inner(dialog_area);
+    frame.render_widget(block, dialog_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .horizontal_margin(1)
+        .split(inner);
+
+    if history.transactions.is_empty() {
+        frame.render_widget(
+            Paragraph::new(t("stock.no_movements"))
+                .style(Style::default().fg(theme().inactive))
+                .alignment(Alignment::Center),
+            chunks[0],
+        );
+    } else {
+        let widths = [
+            Constraint::Length(17),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Min(14),
+            Constraint::Length(12),
+        ];
+        let columns = column_widths(chunks[0], &widths, 1, "");
+        let rows = history.transactions.iter().map(|t| {
+            Row::new(vec![
+                cell(datetime::display_stamp(&t.posted_at), &columns, 0),
+                cell(kind_text(t.kind), &columns, 1),
+                numeric_cell(format!("{:+}", t.quantity), &columns, 2),
+                cell(t.reference.as_deref().unwrap_or("-"), &columns, 3),
+                cell(&t.posted_by, &columns, 4),
+            ])
+            .style(Style::default().fg(if t.quantity < 0 {
+                theme().warning
+            } else {
+                theme().text
+            }))
+        });
+        let header = Row::new(vec![
+            t("stock.when"),
+            t("stock.movement"),
+            t("stock.qty"),
+            t("stock.reference"),
+            t("stock.by"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        frame.render_widget(Table::new(rows, widths).header(header), chunks[0]);
+    }
+    frame.render_widget(
+        Paragraph::new(t("common.close_help"))
+            .style(Style::default().fg(theme().help))
+            .alignment(Alignment::Center),
+        chunks[1],
+    );
+}
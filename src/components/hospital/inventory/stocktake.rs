@@ -0,0 +1,545 @@
+use super::{may_close_stocktakes, posted_by};
+use crate::app::SelectedApp;
+use crate::components::table::{cell, column_widths, numeric_cell};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::export_view::{self, ExportView, ShownRows};
+use crate::components::widgets::text_input::TextInput;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{Stocktake, StocktakeLine};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+
+const COUNT_HEADERS: [&str; 4] = [
+    "stocktake.column.code",
+    "stocktake.column.item",
+    "stocktake.column.unit",
+    "stocktake.column.counted",
+];
+const VARIANCE_HEADERS: [&str; 6] = [
+    "stocktake.column.code",
+    "stocktake.column.item",
+    "stocktake.column.unit",
+    "stocktake.column.expected",
+    "stocktake.column.counted",
+    "stocktake.column.variance",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Closing {
+    Post,
+    Abandon,
+}
+
+/// Splits what was typed or scanned into a quantity and an item code:
+/// `CODE` counts one, `N*CODE` counts N, and a negative N takes back a
+/// miscount.
+fn parse_count(text: &str) -> Option<(i64, &str)> {
+    let text = text.trim();
+    let (quantity, code) = match text.split_once('*') {
+        Some((quantity, code)) => (quantity.trim().parse().ok()?, code.trim()),
+        None => (1, text),
+    };
+    (!code.is_empty() && quantity != 0).then_some((quantity, code))
+}
+
+fn variance_text(line: &StocktakeLine) -> String {
+    match line.variance() {
+        Some(variance) => format!("{:+}", variance),
+        None => t("stocktake.not_counted").to_string(),
+    }
+}
+
+/// The open stocktake. The count is blind: what the ledger expects only
+/// shows in the variance report, so counters report what they find.
+pub struct StocktakeScreen {
+    db: Db,
+    stocktake: Option<Stocktake>,
+    lines: Vec<StocktakeLine>,
+    state: TableState,
+    /// The code being typed or scanned, always focused while counting.
+    input: TextInput,
+    /// What the last count did, or why it failed.
+    last_count: Option<Result<String, String>>,
+    show_variance: bool,
+    closing: Option<Closing>,
+    export_view: Option<ExportView>,
+}
+
+impl StocktakeScreen {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            stocktake: None,
+            lines: Vec::new(),
+            state: TableState::default(),
+            input: TextInput::new(),
+            last_count: None,
+            show_variance: false,
+            closing: None,
+            export_view: None,
+        }
+    }
+
+    pub fn fetch_stocktake(&mut self) -> Result<()> {
+        self.stocktake = db::get_open_stocktake(&self.db)?;
+        self.lines = match &self.stocktake {
+            Some(stocktake) => db::get_stocktake_lines(&self.db, stocktake.id)?,
+            None => Vec::new(),
+        };
+        let len = self.lines.len();
+        match self.state.selected() {
+            _ if len == 0 => self.state.select(None),
+            Some(i) if i < len => {}
+            _ => self.state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) {
+        if let Err(e) = self.fetch_stocktake() {
+            toast::push(Toast::error(tf("stocktake.load_failed", &[("error", &e)])));
+        }
+    }
+
+    fn start(&mut self) {
+        match db::start_stocktake(&self.db, &posted_by()) {
+            Ok(id) => {
+                toast::push(Toast::success(tf("stocktake.started", &[("id", &id)])));
+                self.last_count = None;
+                self.reload();
+            }
+            Err(e) => toast::push(Toast::error(tf("stocktake.start_failed", &[("error", &e)]))),
+        }
+    }
+
+    fn count(&mut self) {
+        let Some(stocktake_id) = self.stocktake.as_ref().map(|s| s.id) else {
+            return;
+        };
+        let text = self.input.value().to_string();
+        if text.trim().is_empty() {
+            return;
+        }
+        self.input.clear();
+        let Some((quantity, code)) = parse_count(&text) else {
+            self.last_count = Some(Err(tf("stocktake.bad_input", &[("input", &text.trim())])));
+            return;
+        };
+        self.last_count = Some(
+            match db::count_stock(&self.db, stocktake_id, code, quantity) {
+                Ok(line) => {
+                    self.reload();
+                    let row = self.lines.iter().position(|l| l.item_id == line.item_id);
+                    self.state.select(row.or(self.state.selected()));
+                    Ok(tf(
+                        "stocktake.counted",
+                        &[
+                            ("change", &format!("{:+}", quantity)),
+                            ("code", &line.code),
+                            ("item", &line.name),
+                            ("count", &line.counted.unwrap_or_default()),
+                            ("unit", &line.unit),
+                        ],
+                    ))
+                }
+                Err(e) => Err(e.to_string()),
+            },
+        );
+    }
+
+    fn close(&mut self, closing: Closing) -> Result<Option<SelectedApp>> {
+        let Some(stocktake) = self.stocktake.clone() else {
+            return Ok(None);
+        };
+        let result = match closing {
+            Closing::Post => {
+                db::post_stocktake(&self.db, stocktake.id, &posted_by()).map(|adjusted| {
+                    tf(
+                        "stocktake.posted",
+                        &[("id", &stocktake.id), ("count", &adjusted)],
+                    )
+                })
+            }
+            Closing::Abandon => db::abandon_stocktake(&self.db, stocktake.id)
+                .map(|()| tf("stocktake.abandoned", &[("id", &stocktake.id)])),
+        };
+        match result {
+            Ok(message) => {
+                toast::push(Toast::success(message));
+                Ok(Some(SelectedApp::InventoryStock))
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("stocktake.close_failed", &[("error", &e)])));
+                Ok(None)
+            }
+        }
+    }
+
+    fn shown_rows(&self) -> ShownRows {
+        let headers: &[&str] = if self.show_variance {
+            &VARIANCE_HEADERS
+        } else {
+            &COUNT_HEADERS
+        };
+        let mut shown = ShownRows::new(headers);
+        shown.rows = self.lines.iter().map(|l| self.row_values(l)).collect();
+        shown
+    }
+
+    fn row_values(&self, line: &StocktakeLine) -> Vec<String> {
+        let counted = line.counted.map_or("-".to_string(), |c| c.to_string());
+        let mut values = vec![line.code.clone(), line.name.clone(), line.unit.clone()];
+        if self.show_variance {
+            values.push(line.expected.to_string());
+            values.push(counted);
+            values.push(variance_text(line));
+        } else {
+            values.push(counted);
+        }
+        values
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(export) = &mut self.export_view {
+            if export.handle_key(key) {
+                self.export_view = None;
+            }
+            return Ok(None);
+        }
+        if let Some(closing) = self.closing.take() {
+            if matches!(
+                key.code,
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y')
+            ) {
+                return self.close(closing);
+            }
+            return Ok(None);
+        }
+        if self.stocktake.is_none() {
+            match key.code {
+                KeyCode::Char('s') | KeyCode::Char('S') => self.start(),
+                KeyCode::Esc => return Ok(Some(SelectedApp::InventoryStock)),
+                _ => {}
+            }
+            return Ok(None);
+        }
+        if export_view::is_open_key(key) {
+            let name = if self.show_variance {
+                "stocktake_variance"
+            } else {
+                "stocktake_count"
+            };
+            self.export_view = Some(ExportView::new(name, self.shown_rows()));
+            return Ok(None);
+        }
+
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let len = self.lines.len();
+        match key.code {
+            KeyCode::Char('s') | KeyCode::Char('x') if control => {
+                if !may_close_stocktakes(&self.db) {
+                    toast::push(Toast::error(t("stocktake.admins_only")));
+                } else if key.code == KeyCode::Char('s') {
+                    self.show_variance = true;
+                    self.closing = Some(Closing::Post);
+                } else {
+                    self.closing = Some(Closing::Abandon);
+                }
+            }
+            KeyCode::Enter => self.count(),
+            KeyCode::Tab | KeyCode::BackTab => self.show_variance = !self.show_variance,
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::InventoryStock)),
+            _ => {
+                self.input.handle_key(key);
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(6),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("stocktake.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+
+        let Some(stocktake) = &self.stocktake else {
+            frame.render_widget(
+                Paragraph::new(t("stocktake.none"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .wrap(Wrap { trim: true }),
+                layout[4],
+            );
+            frame.render_widget(
+                Paragraph::new(t("stocktake.none_help"))
+                    .style(Style::default().fg(theme().help))
+                    .alignment(Alignment::Center),
+                layout[5],
+            );
+            return;
+        };
+
+        let counted = self.lines.iter().filter(|l| l.counted.is_some()).count();
+        let differ = self
+            .lines
+            .iter()
+            .filter(|l| l.variance().is_some_and(|v| v != 0))
+            .count();
+        let mut summary = vec![
+            Span::styled(
+                tf(
+                    "stocktake.started_by",
+                    &[
+                        ("id", &stocktake.id),
+                        ("name", &stocktake.started_by),
+                        ("time", &datetime::display_stamp(&stocktake.started_at)),
+                    ],
+                ),
+                Style::default().fg(theme().accent),
+            ),
+            Span::raw("  ·  "),
+            Span::styled(
+                tf(
+                    "stocktake.progress",
+                    &[("counted", &counted), ("total", &self.lines.len())],
+                ),
+                Style::default().fg(theme().text),
+            ),
+        ];
+        if self.show_variance {
+            summary.push(Span::raw("  ·  "));
+            summary.push(Span::styled(
+                tf("stocktake.differ", &[("count", &differ)]),
+                Style::default().fg(if differ > 0 {
+                    theme().warning
+                } else {
+                    theme().success
+                }),
+            ));
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(summary)).alignment(Alignment::Center),
+            layout[1],
+        );
+
+        frame.render_widget(
+            Paragraph::new(self.input.line(true))
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("stocktake.input_title")))
+                        .border_style(Style::default().fg(theme().focus))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[2],
+        );
+
+        let (message, color) = match &self.last_count {
+            Some(Ok(message)) => (message.as_str(), theme().success),
+            Some(Err(message)) => (message.as_str(), theme().error),
+            None => ("", theme().inactive),
+        };
+        frame.render_widget(
+            Paragraph::new(message)
+                .style(Style::default().fg(color))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(if self.show_variance {
+                format!(" {} ", t("stocktake.variance_title"))
+            } else {
+                format!(" {} ", t("stocktake.count_title"))
+            })
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.lines.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("stocktake.no_items"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[4],
+            );
+        } else {
+            let mut widths = vec![
+                Constraint::Length(16),
+                Constraint::Min(20),
+                Constraint::Length(10),
+            ];
+            if self.show_variance {
+                widths.push(Constraint::Length(9));
+            }
+            widths.push(Constraint::Length(8));
+            if self.show_variance {
+                widths.push(Constraint::Length(12));
+            }
+            let columns = column_widths(block.inner(layout[4]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .lines
+                .iter()
+                .map(|line| {
+                    let color = match line.variance() {
+                        _ if !self.show_variance => theme().text,
+                        None => theme().inactive,
+                        Some(0) => theme().success,
+                        Some(_) => theme().warning,
+                    };
+                    Row::new(
+                        self.row_values(line)
+                            .iter()
+                            .enumerate()
+                            .map(|(column, text)| {
+                                if column < 3 {
+                                    cell(text, &columns, column)
+                                } else {
+                                    numeric_cell(text, &columns, column)
+                                }
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                    .style(Style::default().fg(color))
+                })
+                .collect();
+            let headers: &[&str] = if self.show_variance {
+                &VARIANCE_HEADERS
+            } else {
+                &COUNT_HEADERS
+            };
+            let header = Row::new(headers.iter().copied().map(t).collect::<Vec<_>>())
+                .style(Style::default().bg(theme().header).fg(theme().title));
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(block)
+                .row_highlight_style(
+                    Style::default()
+                        .fg(theme().focus)
+                        .bg(theme().highlight)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[4], &mut self.state.clone());
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("stocktake.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[5],
+        );
+
+        if let Some(closing) = self.closing {
+            self.render_confirm(frame, closing);
+        }
+        if let Some(export) = &self.export_view {
+            export.render_popup(frame);
+        }
+    }
+
+    fn render_confirm(&self, frame: &mut Frame, closing: Closing) {
+        let area = frame.area();
+        let width = 64.min(area.width);
+        let height = 6.min(area.height);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let uncounted = self.lines.iter().filter(|l| l.counted.is_none()).count();
+        let differ = self
+            .lines
+            .iter()
+            .filter(|l| l.variance().is_some_and(|v| v != 0))
+            .count();
+        let (title, text) = match closing {
+            Closing::Post => (
+                format!(" {} ", t("stocktake.post_title")),
+                tf(
+                    "stocktake.post_confirm",
+                    &[("count", &differ), ("uncounted", &uncounted)],
+                ),
+            ),
+            Closing::Abandon => (
+                format!(" {} ", t("stocktake.abandon_title")),
+                t("stocktake.abandon_confirm").to_string(),
+            ),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().warning))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(2), Constraint::Length(1)])
+            .horizontal_margin(1)
+            .split(inner);
+        frame.render_widget(
+            Paragraph::new(text)
+                .style(Style::default().fg(theme().text))
+                .wrap(Wrap { trim: true }),
+            chunks[0],
+        );
+        frame.render_widget(
+            Paragraph::new(t("stocktake.confirm_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[1],
+        );
+    }
+}
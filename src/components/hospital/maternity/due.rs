@@ -0,0 +1,317 @@
+//! Pregnancies whose EDD falls in a month, for planning the labour ward.
+//! Delivered and closed pregnancies stay in the list, greyed out, so the
+//! month can be compared with what actually happened.
+
+use crate::app::SelectedApp;
+use crate::components::hospital::maternity::{gestation_text, status_text};
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{Patient, Pregnancy};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+use time::{Date, Duration};
+
+fn first_of_month(date: Date) -> Date {
+    date.replace_day(1).unwrap_or(date)
+}
+
+fn days_text(days_left: i64) -> String {
+    match days_left {
+        d if d < 0 => tf("maternity.due.past", &[("days", &(-d))]),
+        0 => t("common.today").to_string(),
+        d => d.to_string(),
+    }
+}
+
+pub struct DueReport {
+    db: Db,
+    month: Date,
+    pregnancies: Vec<Pregnancy>,
+    patients: HashMap<i64, Patient>,
+    last_visits: HashMap<i64, String>,
+    state: TableState,
+}
+
+impl DueReport {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            month: first_of_month(datetime::today()),
+            pregnancies: Vec::new(),
+            patients: HashMap::new(),
+            last_visits: HashMap::new(),
+            state: TableState::default(),
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        let month = datetime::format_month(self.month);
+        self.pregnancies = db::get_all_pregnancies(&self.db)?
+            .into_iter()
+            .filter(|p| p.edd.starts_with(&month))
+            .collect();
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        // Visits come latest first, so the first one seen is the last visit.
+        self.last_visits = HashMap::new();
+        for visit in db::get_all_antenatal_visits(&self.db)? {
+            self.last_visits
+                .entry(visit.pregnancy_id)
+                .or_insert(visit.visit_date);
+        }
+        self.state
+            .select((!self.pregnancies.is_empty()).then_some(0));
+        Ok(())
+    }
+
+    fn change_month(&mut self, forward: bool) {
+        self.month = if forward {
+            first_of_month(self.month + Duration::days(31))
+        } else {
+            first_of_month(self.month - Duration::days(1))
+        };
+        if let Err(e) = self.load_data() {
+            toast::push(Toast::error(tf(
+                "maternity.due.load_failed",
+                &[("error", &e)],
+            )));
+        }
+    }
+
+    fn summary(&self) -> Line<'static> {
+        let open: Vec<&Pregnancy> = self.pregnancies.iter().filter(|p| p.is_open()).collect();
+        let at_risk = open.iter().filter(|p| p.risk_factors.is_some()).count();
+        let delivered = self
+            .pregnancies
+            .iter()
+            .filter(|p| p.birth_id.is_some())
+            .count();
+        Line::from(vec![
+            Span::styled(
+                tf("maternity.due.due", &[("count", &open.len())]),
+                Style::default()
+                    .fg(theme().text)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("  |  ", Style::default().fg(theme().inactive)),
+            Span::styled(
+                tf("maternity.due.at_risk", &[("count", &at_risk)]),
+                Style::default().fg(theme().warning),
+            ),
+            Span::styled("  |  ", Style::default().fg(theme().inactive)),
+            Span::styled(
+                tf("maternity.due.delivered", &[("count", &delivered)]),
+                Style::default().fg(theme().inactive),
+            ),
+        ])
+    }
+}
+
+impl Component for DueReport {
+    fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        let len = self.pregnancies.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Left => self.change_month(false),
+            KeyCode::Right => self.change_month(true),
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                if let Err(e) = self.load_data() {
+                    toast::push(Toast::error(tf(
+                        "maternity.due.load_failed",
+                        &[("error", &e)],
+                    )));
+                }
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("maternity.due.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(format!("◄ {} ►", datetime::format_month(self.month)))
+                .style(Style::default().fg(theme().accent))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("common.month")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[1],
+        );
+        frame.render_widget(
+            Paragraph::new(self.summary()).alignment(Alignment::Center),
+            layout[2],
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.pregnancies.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("maternity.due.empty"))
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[3],
+            );
+        } else {
+            let today = datetime::today();
+            let widths = [
+                Constraint::Length(11),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Percentage(20),
+                Constraint::Length(15),
+                Constraint::Length(7),
+                Constraint::Min(14),
+                Constraint::Length(11),
+                Constraint::Length(20),
+            ];
+            let columns = column_widths(block.inner(layout[3]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .pregnancies
+                .iter()
+                .map(|p| {
+                    let patient = self.patients.get(&p.patient_id);
+                    let days_left =
+                        datetime::parse_date(&p.edd).map(|edd| (edd - today).whole_days());
+                    let style = if !p.is_open() {
+                        Style::default().fg(theme().inactive)
+                    } else if days_left.is_some_and(|d| d < 0) {
+                        Style::default().fg(theme().error)
+                    } else if p.risk_factors.is_some() {
+                        Style::default().fg(theme().warning)
+                    } else {
+                        Style::default().fg(theme().text)
+                    };
+                    Row::new(vec![
+                        cell(p.edd.as_str(), &columns, 0),
+                        cell(
+                            match days_left {
+                                Some(d) if p.is_open() => days_text(d),
+                                _ => "-".to_string(),
+                            },
+                            &columns,
+                            1,
+                        ),
+                        cell(
+                            if p.is_open() {
+                                gestation_text(&p.edd, today)
+                            } else {
+                                "-".to_string()
+                            },
+                            &columns,
+                            2,
+                        ),
+                        cell(
+                            patient.map_or(
+                                tf("common.unknown_number", &[("id", &p.patient_id)]),
+                                |pt| format!("{} {}", pt.first_name, pt.last_name),
+                            ),
+                            &columns,
+                            3,
+                        ),
+                        cell(
+                            patient.map_or("-", |pt| pt.phone_number.as_str()),
+                            &columns,
+                            4,
+                        ),
+                        cell(format!("G{} P{}", p.gravida, p.para), &columns, 5),
+                        cell(p.risk_factors.as_deref().unwrap_or("-"), &columns, 6),
+                        cell(
+                            self.last_visits.get(&p.id).map_or("-", String::as_str),
+                            &columns,
+                            7,
+                        ),
+                        cell(status_text(p), &columns, 8),
+                    ])
+                    .style(style)
+                })
+                .collect();
+            let header = Row::new(vec![
+                t("maternity.due.edd"),
+                t("maternity.due.days"),
+                t("maternity.due.gest"),
+                t("maternity.due.patient"),
+                t("common.phone"),
+                t("maternity.due.gp"),
+                t("maternity.due.risk"),
+                t("maternity.due.last_visit"),
+                t("maternity.due.status"),
+            ])
+            .style(Style::default().bg(theme().header).fg(theme().title));
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(block)
+                .row_highlight_style(Style::default().bg(theme().highlight))
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[3], &mut self.state.clone());
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("maternity.due.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use crate::app::SelectedApp;
+use crate::components::Component;
+use crate::db::Db;
+use crate::i18n::{t, tf};
+use crate::models::Pregnancy;
+use crate::tui::Frame;
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::KeyEvent;
+use time::Date;
+
+pub mod due;
+pub mod pregnancies;
+
+/// A pregnancy lasts 280 days from the last menstrual period to the EDD.
+pub const TERM_DAYS: i64 = 280;
+
+/// Gestational age on `on` as whole weeks and days, counted back from the
+/// EDD.
+pub fn gestation(edd: &str, on: Date) -> Option<(i64, i64)> {
+    let edd = datetime::parse_date(edd)?;
+    let days = TERM_DAYS - (edd - on).whole_days();
+    (days >= 0).then_some((days / 7, days % 7))
+}
+
+/// Gestational age like "32w 4d", or "-" before the pregnancy began.
+pub fn gestation_text(edd: &str, on: Date) -> String {
+    gestation(edd, on)
+        .map(|(weeks, days)| tf("maternity.gestation", &[("weeks", &weeks), ("days", &days)]))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// t("maternity.active"), "Delivered (B-000012)" or "Closed 2026-03-02".
+pub fn status_text(pregnancy: &Pregnancy) -> String {
+    match (pregnancy.birth_id, &pregnancy.closed_on) {
+        (Some(birth_id), _) => tf(
+            "maternity.delivered",
+            &[("birth", &format!("{:06}", birth_id))],
+        ),
+        (None, Some(closed_on)) => tf("maternity.closed", &[("date", &closed_on)]),
+        (None, None) => t("maternity.active").to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaternityState {
+    Pregnancies,
+    Due,
+}
+
+pub struct Maternity {
+    pub pregnancies: pregnancies::PregnancyList,
+    pub due: due::DueReport,
+    pub state: MaternityState,
+}
+
+impl Maternity {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            pregnancies: pregnancies::PregnancyList::new(db),
+            due: due::DueReport::new(db),
+            state: MaternityState::Pregnancies,
+        }
+    }
+
+    pub fn save_view(&self) {
+        self.pregnancies.save_view();
+    }
+
+    pub fn initialize_list(&mut self) -> Result<()> {
+        match self.state {
+            MaternityState::Pregnancies => self.pregnancies.load_data(),
+            MaternityState::Due => self.due.load_data(),
+        }
+    }
+}
+
+impl Component for Maternity {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        match self.state {
+            MaternityState::Pregnancies => self.pregnancies.handle_input(event),
+            MaternityState::Due => self.due.handle_input(event),
+        }
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        match self.state {
+            MaternityState::Pregnancies => self.pregnancies.render(frame),
+            MaternityState::Due => self.due.render(frame),
+        }
+    }
+}
@@ -0,0 +1,756 @@
+use crate::app::SelectedApp;
+use crate::components::form::{
+    blood_pressure, date, integer_range, number_range, past_date, Field, Form,
+};
+use crate::components::hospital::maternity::{gestation_text, status_text, TERM_DAYS};
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{AntenatalVisit, Patient, Pregnancy, StaffMember};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state::{self, ViewState};
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+use time::Duration;
+
+pub const VIEW_KEY: &str = "maternity.pregnancies";
+
+const PATIENT_ID: usize = 0;
+const LMP: usize = 1;
+const EDD: usize = 2;
+const GRAVIDA: usize = 3;
+const PARA: usize = 4;
+const RISK_FACTORS: usize = 5;
+
+fn pregnancy_form() -> Form {
+    Form::new(vec![
+        Field::new(t("common.patient_id"))
+            .required()
+            .validate_with(integer_range(1, i64::MAX)),
+        Field::new(t("maternity.lmp")).validate_with(Box::new(past_date)),
+        Field::new(t("maternity.edd_field")).validate_with(Box::new(date)),
+        Field::new(t("maternity.gravida"))
+            .required()
+            .validate_with(integer_range(1, 30)),
+        Field::new(t("maternity.para"))
+            .required()
+            .validate_with(integer_range(0, 29)),
+        Field::new(t("maternity.risk_factors")),
+    ])
+}
+
+const VISIT_DATE: usize = 0;
+const BLOOD_PRESSURE: usize = 1;
+const WEIGHT: usize = 2;
+const FUNDAL_HEIGHT: usize = 3;
+const FETAL_HEART_RATE: usize = 4;
+const STAFF_ID: usize = 5;
+const NOTES: usize = 6;
+
+fn visit_form() -> Form {
+    let mut form = Form::new(vec![
+        Field::new(t("maternity.visit_date"))
+            .required()
+            .validate_with(Box::new(past_date)),
+        Field::new(t("maternity.blood_pressure")).validate_with(Box::new(blood_pressure)),
+        Field::new(t("maternity.weight_kg")).validate_with(number_range(30.0, 250.0)),
+        Field::new(t("maternity.fundal_height")).validate_with(integer_range(5, 50)),
+        Field::new(t("maternity.fetal_heart_rate")).validate_with(integer_range(60, 220)),
+        Field::new(t("maternity.seen_by_field")).validate_with(integer_range(1, i64::MAX)),
+        Field::new(t("maternity.notes")),
+    ]);
+    form.fields[VISIT_DATE].set(datetime::today_text());
+    form
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PopupKind {
+    /// Adds a pregnancy, or edits the one with this ID.
+    Pregnancy(Option<i64>),
+    /// Records an antenatal visit for the pregnancy with this ID.
+    Visit(i64),
+}
+
+struct Popup {
+    kind: PopupKind,
+    form: Form,
+    focus_index: usize,
+}
+
+/// Pregnancies followed by the maternity clinic, the one due soonest first,
+/// with the antenatal visits of the selected one underneath.
+pub struct PregnancyList {
+    db: Db,
+    pregnancies: Vec<Pregnancy>,
+    filtered: Vec<Pregnancy>,
+    visits: HashMap<i64, Vec<AntenatalVisit>>,
+    patients: HashMap<i64, Patient>,
+    staff: HashMap<i64, StaffMember>,
+    show_closed: bool,
+    state: TableState,
+    popup: Option<Popup>,
+    confirm_close: bool,
+}
+
+impl PregnancyList {
+    pub fn new(db: &Db) -> Self {
+        let view = ui_state::view(VIEW_KEY);
+        Self {
+            db: db.clone(),
+            pregnancies: Vec::new(),
+            filtered: Vec::new(),
+            visits: HashMap::new(),
+            patients: HashMap::new(),
+            staff: HashMap::new(),
+            show_closed: view.filter("closed").is_some(),
+            state: view.table(),
+            popup: None,
+            confirm_close: false,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.pregnancies = db::get_all_pregnancies(&self.db)?;
+        self.visits = HashMap::new();
+        for visit in db::get_all_antenatal_visits(&self.db)? {
+            self.visits
+                .entry(visit.pregnancy_id)
+                .or_default()
+                .push(visit);
+        }
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        self.staff = db::get_all_staff(&self.db)?
+            .into_iter()
+            .map(|s| (s.id, s))
+            .collect();
+        self.filter();
+        Ok(())
+    }
+
+    pub fn save_view(&self) {
+        let view = ViewState::default().with_filter("closed", self.show_closed.then_some("shown"));
+        ui_state::set_view(VIEW_KEY, view.with_table(&self.state));
+    }
+
+    fn filter(&mut self) {
+        self.filtered = self
+            .pregnancies
+            .iter()
+            .filter(|p| self.show_closed || p.is_open())
+            .cloned()
+            .collect();
+        match self.state.selected() {
+            _ if self.filtered.is_empty() => self.state.select(None),
+            Some(i) if i < self.filtered.len() => {}
+            _ => self.state.select(Some(0)),
+        }
+    }
+
+    fn selected(&self) -> Option<&Pregnancy> {
+        self.state.selected().and_then(|i| self.filtered.get(i))
+    }
+
+    fn patient_name(&self, patient_id: i64) -> String {
+        self.patients
+            .get(&patient_id)
+            .map(|p| format!("{} {}", p.first_name, p.last_name))
+            .unwrap_or_else(|| tf("common.unknown_number", &[("id", &patient_id)]))
+    }
+
+    fn open_pregnancy_popup(&mut self, pregnancy: Option<Pregnancy>) {
+        let mut form = pregnancy_form();
+        if let Some(pregnancy) = &pregnancy {
+            form.fields[PATIENT_ID].set(pregnancy.patient_id.to_string());
+            form.fields[EDD].set(pregnancy.edd.as_str());
+            form.fields[GRAVIDA].set(pregnancy.gravida.to_string());
+            form.fields[PARA].set(pregnancy.para.to_string());
+            if let Some(risk_factors) = &pregnancy.risk_factors {
+                form.fields[RISK_FACTORS].set(risk_factors.as_str());
+            }
+        }
+        self.popup = Some(Popup {
+            kind: PopupKind::Pregnancy(pregnancy.map(|p| p.id)),
+            form,
+            focus_index: 0,
+        });
+    }
+
+    fn open_visit_popup(&mut self) {
+        let Some(pregnancy) = self.selected() else {
+            return;
+        };
+        if !pregnancy.is_open() {
+            toast::push(Toast::error(t("maternity.closed_pregnancy")));
+            return;
+        }
+        self.popup = Some(Popup {
+            kind: PopupKind::Visit(pregnancy.id),
+            form: visit_form(),
+            focus_index: 0,
+        });
+    }
+
+    /// The pregnancy in the form, or the index of the field to fix.
+    fn pregnancy_from_form(
+        &self,
+        form: &mut Form,
+        pregnancy_id: Option<i64>,
+    ) -> Result<Pregnancy, usize> {
+        let patient_id = form.value(PATIENT_ID).parse().unwrap_or_default();
+        if !self.patients.contains_key(&patient_id) {
+            form.fields[PATIENT_ID].error = Some(t("maternity.no_patient").to_string());
+            return Err(PATIENT_ID);
+        }
+        let already_open = self
+            .pregnancies
+            .iter()
+            .any(|p| p.patient_id == patient_id && p.is_open() && Some(p.id) != pregnancy_id);
+        if already_open {
+            form.fields[PATIENT_ID].error = Some(t("maternity.already_open").to_string());
+            return Err(PATIENT_ID);
+        }
+        let edd = match (form.optional_value(EDD), form.optional_value(LMP)) {
+            (Some(edd), _) => edd,
+            (None, Some(lmp)) => datetime::parse_date(&lmp)
+                .map(|lmp| datetime::format_date(lmp + Duration::days(TERM_DAYS)))
+                .unwrap_or_default(),
+            (None, None) => {
+                form.fields[EDD].error = Some(t("maternity.edd_or_lmp").to_string());
+                return Err(EDD);
+            }
+        };
+        let gravida: i64 = form.value(GRAVIDA).parse().unwrap_or(1);
+        let para: i64 = form.value(PARA).parse().unwrap_or_default();
+        if para >= gravida {
+            form.fields[PARA].error = Some(t("maternity.para_less").to_string());
+            return Err(PARA);
+        }
+        let existing = pregnancy_id.and_then(|id| self.pregnancies.iter().find(|p| p.id == id));
+        Ok(Pregnancy {
+            id: pregnancy_id.unwrap_or_default(),
+            patient_id,
+            edd,
+            gravida,
+            para,
+            risk_factors: form.optional_value(RISK_FACTORS),
+            birth_id: existing.and_then(|p| p.birth_id),
+            closed_on: existing.and_then(|p| p.closed_on.clone()),
+        })
+    }
+
+    /// The visit in the form, or the index of the field to fix.
+    fn visit_from_form(&self, form: &mut Form, pregnancy_id: i64) -> Result<AntenatalVisit, usize> {
+        let staff_id = form.optional_value(STAFF_ID).and_then(|v| v.parse().ok());
+        if staff_id.is_some_and(|id| !self.staff.contains_key(&id)) {
+            form.fields[STAFF_ID].error = Some(t("maternity.no_staff").to_string());
+            return Err(STAFF_ID);
+        }
+        Ok(AntenatalVisit {
+            id: 0,
+            pregnancy_id,
+            visit_date: form.value(VISIT_DATE),
+            blood_pressure: form.optional_value(BLOOD_PRESSURE),
+            weight_kg: form.optional_value(WEIGHT).and_then(|v| v.parse().ok()),
+            fundal_height_cm: form
+                .optional_value(FUNDAL_HEIGHT)
+                .and_then(|v| v.parse().ok()),
+            fetal_heart_rate: form
+                .optional_value(FETAL_HEART_RATE)
+                .and_then(|v| v.parse().ok()),
+            staff_id,
+            notes: form.optional_value(NOTES),
+        })
+    }
+
+    fn save_popup(&mut self) -> Result<()> {
+        let Some(mut popup) = self.popup.take() else {
+            return Ok(());
+        };
+        if let Some(invalid) = popup.form.validate() {
+            popup.focus_index = invalid;
+            self.popup = Some(popup);
+            return Ok(());
+        }
+        let result = match popup.kind {
+            PopupKind::Pregnancy(pregnancy_id) => {
+                let pregnancy = match self.pregnancy_from_form(&mut popup.form, pregnancy_id) {
+                    Ok(pregnancy) => pregnancy,
+                    Err(invalid) => {
+                        popup.focus_index = invalid;
+                        self.popup = Some(popup);
+                        return Ok(());
+                    }
+                };
+                let saved = match pregnancy_id {
+                    Some(_) => db::update_pregnancy(&self.db, &pregnancy),
+                    None => db::create_pregnancy(&self.db, &pregnancy).map(|_| ()),
+                };
+                saved.map(|()| {
+                    tf(
+                        "maternity.saved",
+                        &[
+                            ("patient", &self.patient_name(pregnancy.patient_id)),
+                            ("edd", &pregnancy.edd),
+                        ],
+                    )
+                })
+            }
+            PopupKind::Visit(pregnancy_id) => {
+                let visit = match self.visit_from_form(&mut popup.form, pregnancy_id) {
+                    Ok(visit) => visit,
+                    Err(invalid) => {
+                        popup.focus_index = invalid;
+                        self.popup = Some(popup);
+                        return Ok(());
+                    }
+                };
+                db::create_antenatal_visit(&self.db, &visit)
+                    .map(|_| tf("maternity.visit_recorded", &[("date", &visit.visit_date)]))
+            }
+        };
+        match result {
+            Ok(message) => {
+                toast::push(Toast::success(message));
+                self.load_data()?;
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("insurance.save_failed", &[("error", &e)])));
+                self.popup = Some(popup);
+            }
+        }
+        Ok(())
+    }
+
+    /// Closes the selected pregnancy without a birth, after a loss or when
+    /// care moves elsewhere.
+    fn close_selected(&mut self) -> Result<()> {
+        self.confirm_close = false;
+        let Some(mut pregnancy) = self.selected().cloned() else {
+            return Ok(());
+        };
+        pregnancy.closed_on = Some(datetime::today_text());
+        match db::update_pregnancy(&self.db, &pregnancy) {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "maternity.closed_toast",
+                    &[("patient", &self.patient_name(pregnancy.patient_id))],
+                )));
+                self.load_data()?;
+            }
+            Err(e) => toast::push(Toast::error(tf("maternity.close_failed", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    fn handle_popup_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(popup) = self.popup.as_mut() else {
+            return Ok(());
+        };
+        let len = popup.form.len();
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Tab | KeyCode::Down => popup.focus_index = (popup.focus_index + 1) % len,
+            KeyCode::BackTab | KeyCode::Up => {
+                popup.focus_index = (popup.focus_index + len - 1) % len
+            }
+            KeyCode::Enter if popup.focus_index + 1 < len => popup.focus_index += 1,
+            KeyCode::Enter => self.save_popup()?,
+            _ => {
+                popup.form.handle_key(popup.focus_index, key);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if self.popup.is_some() {
+            self.handle_popup_input(key)?;
+            return Ok(None);
+        }
+        if self.confirm_close {
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.close_selected()?
+                }
+                _ => self.confirm_close = false,
+            }
+            return Ok(None);
+        }
+        let len = self.filtered.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => self.open_pregnancy_popup(None),
+            KeyCode::Char('e') | KeyCode::Char('E') | KeyCode::Enter => {
+                if let Some(pregnancy) = self.selected().cloned() {
+                    self.open_pregnancy_popup(Some(pregnancy));
+                }
+            }
+            KeyCode::Char('v') | KeyCode::Char('V') => self.open_visit_popup(),
+            KeyCode::Char('x') | KeyCode::Char('X')
+                if self.selected().is_some_and(Pregnancy::is_open) =>
+            {
+                self.confirm_close = true;
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') => {
+                self.show_closed = !self.show_closed;
+                self.filter();
+            }
+            KeyCode::Char('b') | KeyCode::Char('B') => {
+                return Ok(Some(SelectedApp::RegistryBirths));
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                self.load_data()?;
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_table(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("maternity.pregnancies")))
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.filtered.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("maternity.empty"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        }
+
+        let today = datetime::today();
+        let widths = [
+            Constraint::Length(11),
+            Constraint::Length(8),
+            Constraint::Percentage(20),
+            Constraint::Length(7),
+            Constraint::Length(6),
+            Constraint::Length(11),
+            Constraint::Min(16),
+            Constraint::Length(20),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+        let rows: Vec<Row> = self
+            .filtered
+            .iter()
+            .map(|p| {
+                let visits = self.visits.get(&p.id);
+                let style = if !p.is_open() {
+                    Style::default().fg(theme().inactive)
+                } else if p.risk_factors.is_some() {
+                    Style::default().fg(theme().warning)
+                } else {
+                    Style::default().fg(theme().text)
+                };
+                Row::new(vec![
+                    cell(p.edd.as_str(), &columns, 0),
+                    cell(
+                        if p.is_open() {
+                            gestation_text(&p.edd, today)
+                        } else {
+                            "-".to_string()
+                        },
+                        &columns,
+                        1,
+                    ),
+                    cell(self.patient_name(p.patient_id), &columns, 2),
+                    cell(format!("G{} P{}", p.gravida, p.para), &columns, 3),
+                    cell(visits.map_or(0, Vec::len).to_string(), &columns, 4),
+                    cell(
+                        visits
+                            .and_then(|v| v.first())
+                            .map_or("-", |v| v.visit_date.as_str()),
+                        &columns,
+                        5,
+                    ),
+                    cell(p.risk_factors.as_deref().unwrap_or("-"), &columns, 6),
+                    cell(status_text(p), &columns, 7),
+                ])
+                .style(style)
+            })
+            .collect();
+        let header = Row::new(vec![
+            t("maternity.due.edd"),
+            t("maternity.due.gest"),
+            t("maternity.due.patient"),
+            t("maternity.due.gp"),
+            t("maternity.visits"),
+            t("maternity.due.last_visit"),
+            t("maternity.risk_factors"),
+            t("maternity.due.status"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.state.clone());
+    }
+
+    fn render_visits(&self, frame: &mut Frame, area: Rect) {
+        let pregnancy = self.selected();
+        let title = match pregnancy {
+            Some(p) => format!(
+                " {} ",
+                tf(
+                    "maternity.visits_of",
+                    &[("patient", &self.patient_name(p.patient_id))]
+                )
+            ),
+            None => format!(" {} ", t("maternity.visits_title")),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(title)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        let visits = pregnancy.and_then(|p| self.visits.get(&p.id).map(|v| (p, v)));
+        let Some((pregnancy, visits)) = visits else {
+            frame.render_widget(
+                Paragraph::new(t("maternity.no_visits"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        };
+
+        let widths = [
+            Constraint::Length(11),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(7),
+            Constraint::Length(6),
+            Constraint::Length(16),
+            Constraint::Min(10),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "");
+        let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        let rows: Vec<Row> = visits
+            .iter()
+            .map(|v| {
+                let gestation = datetime::parse_date(&v.visit_date)
+                    .map(|on| gestation_text(&pregnancy.edd, on))
+                    .unwrap_or_else(|| "-".to_string());
+                Row::new(vec![
+                    cell(v.visit_date.as_str(), &columns, 0),
+                    cell(gestation, &columns, 1),
+                    cell(or_dash(v.blood_pressure.clone()), &columns, 2),
+                    cell(
+                        or_dash(v.weight_kg.map(|w| format!("{:.1}", w))),
+                        &columns,
+                        3,
+                    ),
+                    cell(
+                        or_dash(v.fundal_height_cm.map(|h| h.to_string())),
+                        &columns,
+                        4,
+                    ),
+                    cell(
+                        or_dash(v.fetal_heart_rate.map(|r| r.to_string())),
+                        &columns,
+                        5,
+                    ),
+                    cell(
+                        or_dash(v.staff_id.map(|id| {
+                            self.staff
+                                .get(&id)
+                                .map_or(format!("#{}", id), |s| s.name.clone())
+                        })),
+                        &columns,
+                        6,
+                    ),
+                    cell(v.notes.as_deref().unwrap_or("-"), &columns, 7),
+                ])
+                .style(Style::default().fg(theme().text))
+            })
+            .collect();
+        let header = Row::new(vec![
+            t("maternity.date"),
+            t("maternity.due.gest"),
+            t("maternity.bp"),
+            t("maternity.weight"),
+            t("maternity.fundus"),
+            t("maternity.fhr"),
+            t("maternity.seen_by"),
+            t("maternity.notes"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        frame.render_widget(Table::new(rows, widths).header(header).block(block), area);
+    }
+
+    fn render_popup(&self, frame: &mut Frame, popup: &Popup) {
+        let area = frame.area();
+        let height = (popup.form.len() as u16 * 3 + 3).min(area.height);
+        let width = 60.min(area.width);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let title = match popup.kind {
+            PopupKind::Pregnancy(Some(id)) => {
+                format!(" {} ", tf("maternity.edit_title", &[("id", &id)]))
+            }
+            PopupKind::Pregnancy(None) => format!(" {} ", t("maternity.new_title")),
+            PopupKind::Visit(_) => format!(" {} ", t("maternity.new_visit")),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let mut constraints = vec![Constraint::Length(3); popup.form.len()];
+        constraints.push(Constraint::Min(1));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .horizontal_margin(1)
+            .split(inner);
+        for index in 0..popup.form.len() {
+            popup
+                .form
+                .render_field(frame, index, chunks[index], popup.focus_index);
+        }
+        frame.render_widget(
+            Paragraph::new(t("common.form_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[popup.form.len()],
+        );
+    }
+}
+
+impl Component for PregnancyList {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(9),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("maternity.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+
+        let open: Vec<&Pregnancy> = self.pregnancies.iter().filter(|p| p.is_open()).collect();
+        let at_risk = open.iter().filter(|p| p.risk_factors.is_some()).count();
+        let summary = Line::from(vec![
+            Span::styled(
+                tf("maternity.open", &[("count", &open.len())]),
+                Style::default()
+                    .fg(theme().text)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("  |  ", Style::default().fg(theme().inactive)),
+            Span::styled(
+                tf("maternity.due.at_risk", &[("count", &at_risk)]),
+                Style::default().fg(theme().warning),
+            ),
+            Span::styled(
+                if self.show_closed {
+                    format!("  {}", t("maternity.showing_closed"))
+                } else {
+                    String::new()
+                },
+                Style::default().fg(theme().inactive),
+            ),
+        ]);
+        frame.render_widget(
+            Paragraph::new(summary).alignment(Alignment::Center),
+            layout[1],
+        );
+
+        self.render_table(frame, layout[2]);
+        self.render_visits(frame, layout[3]);
+
+        let help = if self.confirm_close {
+            t("maternity.close_confirm")
+        } else {
+            t("maternity.help")
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(if self.confirm_close {
+                    theme().warning
+                } else {
+                    theme().help
+                }))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+
+        if let Some(popup) = &self.popup {
+            self.render_popup(frame, popup);
+        }
+    }
+}
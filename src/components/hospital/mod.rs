@@ -1,19 +1,47 @@
+use self::appointments::Appointments;
+use self::appointments::AppointmentsState;
 use self::finance::Finance;
 use self::finance::FinanceState;
+use self::handover::Handover;
+use self::handover::HandoverState;
+use self::inventory::Inventory;
+use self::inventory::InventoryState;
+use self::maternity::Maternity;
+use self::maternity::MaternityState;
 use self::patients::PatientsState;
+use self::queue::Queue;
+use self::queue::QueueState;
 use self::records::Records;
 use self::records::RecordsState;
+use self::registry::Registry;
+use self::registry::RegistryState;
 use self::staff::Staff;
 use self::staff::StaffState;
+use self::telemedicine::Telemedicine;
+use self::telemedicine::TelemedicineState;
+use self::waitlist::Waitlist;
+use self::waitlist::WaitlistState;
+use self::worklist::Worklist;
+use self::worklist::WorklistState;
 use crate::components::Component;
+use crate::db::Db;
 use crate::tui::Frame;
 use anyhow::Result;
 use crossterm::event::KeyEvent;
 
+pub mod appointments;
 pub mod finance;
+pub mod handover;
+pub mod inventory;
+pub mod maternity;
 pub mod patients;
+pub mod queue;
 pub mod records;
+pub mod registry;
 pub mod staff;
+pub mod telemedicine;
+pub mod waitlist;
+pub mod worklist;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HospitalState {
@@ -21,6 +49,15 @@ pub enum HospitalState {
     Patients,
     Staff,
     Records,
+    Waitlist,
+    Registry,
+    Telemedicine,
+    Queue,
+    Handover,
+    Worklist,
+    Maternity,
+    Appointments,
+    Inventory,
 }
 
 pub struct HospitalApp {
@@ -29,35 +66,46 @@ pub struct HospitalApp {
     pub patients: patients::Patients,
     pub records: Records,
     pub staff: Staff,
+    pub waitlist: Waitlist,
+    pub registry: Registry,
+    pub telemedicine: Telemedicine,
+    pub queue: Queue,
+    pub handover: Handover,
+    pub worklist: Worklist,
+    pub maternity: Maternity,
+    pub appointments: Appointments,
+    pub inventory: Inventory,
 }
 
 impl HospitalApp {
-    pub fn new() -> Self {
-        let finance = Finance::new();
+    pub fn new(db: &Db) -> Result<Self> {
+        let finance = Finance::new(db)?;
 
-        let mut patients = patients::Patients::new();
+        let mut patients = patients::Patients::new(db);
+        patients.initialize_list()?;
 
-        patients
-            .initialize_list()
-            .expect("Failed to initialize patient list");
+        let mut staff = Staff::new(db)?;
+        staff.initialize_list()?;
 
-        let mut staff = Staff::new();
-        staff
-            .initialize_list()
-            .expect("Failed to initialize staff list");
+        let mut records = Records::new(db);
+        records.initialize_list()?;
 
-        let mut records = Records::new();
-        records
-            .initialize_list()
-            .expect("Failed to initialize records list");
-
-        Self {
+        Ok(Self {
             state: HospitalState::Patients,
             finance,
             patients,
             staff,
             records,
-        }
+            waitlist: Waitlist::new(db),
+            registry: Registry::new(db),
+            telemedicine: Telemedicine::new(db),
+            queue: Queue::new(db),
+            handover: Handover::new(db),
+            worklist: Worklist::new(db),
+            maternity: Maternity::new(db),
+            appointments: Appointments::new(db),
+            inventory: Inventory::new(db),
+        })
     }
 
     pub fn set_patients_state(&mut self, state: PatientsState) {
@@ -94,6 +142,89 @@ impl HospitalApp {
     pub fn set_finance_state(&mut self, state: FinanceState) {
         self.finance.set_finance_state(state);
     }
+
+    pub fn set_waitlist_state(&mut self, state: WaitlistState) {
+        self.waitlist.state = state;
+        if let Err(e) = self.waitlist.initialize_list() {
+            eprintln!("Error initializing waitlist: {}", e);
+        }
+    }
+
+    pub fn set_registry_state(&mut self, state: RegistryState) {
+        self.registry.state = state;
+        if let Err(e) = self.registry.initialize_list() {
+            eprintln!("Error initializing registry: {}", e);
+        }
+    }
+
+    pub fn set_telemedicine_state(&mut self, state: TelemedicineState) {
+        self.telemedicine.state = state;
+        if let Err(e) = self.telemedicine.initialize_list() {
+            eprintln!("Error initializing telemedicine list: {}", e);
+        }
+    }
+
+    pub fn set_queue_state(&mut self, state: QueueState) {
+        self.queue.state = state;
+        if let Err(e) = self.queue.initialize_list() {
+            eprintln!("Error initializing outpatient queue: {}", e);
+        }
+    }
+
+    /// Remembers the active module's list screen (search, filters and
+    /// position) in the user's UI state.
+    pub fn save_view(&self) {
+        match self.state {
+            HospitalState::Finance => self.finance.save_view(),
+            HospitalState::Patients => self.patients.save_view(),
+            HospitalState::Staff => self.staff.save_view(),
+            HospitalState::Records => self.records.save_view(),
+            HospitalState::Waitlist => self.waitlist.save_view(),
+            HospitalState::Telemedicine => self.telemedicine.save_view(),
+            HospitalState::Handover => self.handover.save_view(),
+            HospitalState::Worklist => self.worklist.save_view(),
+            HospitalState::Maternity => self.maternity.save_view(),
+            HospitalState::Registry
+            | HospitalState::Queue
+            | HospitalState::Appointments
+            | HospitalState::Inventory => {}
+        }
+    }
+
+    pub fn set_handover_state(&mut self, state: HandoverState) {
+        self.handover.state = state;
+        if let Err(e) = self.handover.initialize_list() {
+            eprintln!("Error initializing handover: {}", e);
+        }
+    }
+
+    pub fn set_worklist_state(&mut self, state: WorklistState) {
+        self.worklist.state = state;
+        if let Err(e) = self.worklist.initialize_list() {
+            eprintln!("Error initializing worklist: {}", e);
+        }
+    }
+
+    pub fn set_maternity_state(&mut self, state: MaternityState) {
+        self.maternity.state = state;
+        if let Err(e) = self.maternity.initialize_list() {
+            eprintln!("Error initializing maternity: {}", e);
+        }
+    }
+
+    pub fn set_appointments_state(&mut self, state: AppointmentsState) {
+        self.appointments.state = state;
+        if let Err(e) = self.appointments.initialize_list() {
+            eprintln!("Error initializing appointments: {}", e);
+        }
+    }
+
+    pub fn set_inventory_state(&mut self, state: InventoryState) {
+        self.inventory.state = state;
+        if let Err(e) = self.inventory.initialize_list() {
+            eprintln!("Error initializing inventory: {}", e);
+        }
+    }
 }
 
 impl Component for HospitalApp {
@@ -119,6 +250,51 @@ impl Component for HospitalApp {
                     return Ok(Some(action));
                 }
             }
+            HospitalState::Waitlist => {
+                if let Some(action) = self.waitlist.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            HospitalState::Registry => {
+                if let Some(action) = self.registry.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            HospitalState::Telemedicine => {
+                if let Some(action) = self.telemedicine.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            HospitalState::Queue => {
+                if let Some(action) = self.queue.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            HospitalState::Handover => {
+                if let Some(action) = self.handover.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            HospitalState::Worklist => {
+                if let Some(action) = self.worklist.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            HospitalState::Maternity => {
+                if let Some(action) = self.maternity.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            HospitalState::Appointments => {
+                if let Some(action) = self.appointments.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
+            HospitalState::Inventory => {
+                if let Some(action) = self.inventory.handle_input(event)? {
+                    return Ok(Some(action));
+                }
+            }
         }
         Ok(None)
     }
@@ -129,6 +305,33 @@ impl Component for HospitalApp {
             HospitalState::Patients => self.patients.render(frame),
             HospitalState::Staff => self.staff.render(frame),
             HospitalState::Records => self.records.render(frame),
+            HospitalState::Waitlist => self.waitlist.render(frame),
+            HospitalState::Registry => self.registry.render(frame),
+            HospitalState::Telemedicine => self.telemedicine.render(frame),
+            HospitalState::Queue => self.queue.render(frame),
+            HospitalState::Handover => self.handover.render(frame),
+            HospitalState::Worklist => self.worklist.render(frame),
+            HospitalState::Maternity => self.maternity.render(frame),
+            HospitalState::Appointments => self.appointments.render(frame),
+            HospitalState::Inventory => self.inventory.render(frame),
+        }
+    }
+
+    fn tick(&mut self) {
+        match self.state {
+            HospitalState::Finance => self.finance.tick(),
+            HospitalState::Patients => self.patients.tick(),
+            HospitalState::Staff => self.staff.tick(),
+            HospitalState::Records => self.records.tick(),
+            HospitalState::Waitlist => self.waitlist.tick(),
+            HospitalState::Registry => self.registry.tick(),
+            HospitalState::Telemedicine => self.telemedicine.tick(),
+            HospitalState::Queue => self.queue.tick(),
+            HospitalState::Handover => self.handover.tick(),
+            HospitalState::Worklist => self.worklist.tick(),
+            HospitalState::Maternity => self.maternity.tick(),
+            HospitalState::Appointments => self.appointments.tick(),
+            HospitalState::Inventory => self.inventory.tick(),
         }
     }
 }
@@ -1,185 +1,192 @@
+use crate::app::SelectedApp;
+use crate::components::form::{self, Field, Form};
 use crate::components::hospital::patients::PatientAction;
+use crate::components::quick_actions;
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::date_picker::DatePicker;
 use crate::components::Component;
-use crate::db;
-use crate::models::{Gender, Patient};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{ContactPreference, Gender, Patient};
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::utils::{contact, datetime};
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{prelude::*, widgets::*};
-use std::time::{Duration, Instant};
+
+const FIRST_NAME: usize = 0;
+const LAST_NAME: usize = 1;
+const DOB: usize = 2;
+const GENDER: usize = 3;
+const ADDRESS: usize = 4;
+const POSTCODE: usize = 5;
+const CITY: usize = 6;
+const REGION: usize = 7;
+const PHONE: usize = 8;
+const EMAIL: usize = 9;
+const CONTACT: usize = 10;
+const MEDICAL_HISTORY: usize = 11;
+const ALLERGIES: usize = 12;
+const MEDICATIONS: usize = 13;
+const CONTACT_PREFERENCES: [&str; 4] = ["SMS", "Email", "Both", "None"];
 
 pub struct AddPatient {
-    first_name: String,
-    last_name: String,
-    dob: String,
-    gender: Gender,
-    address: String,
-    phone: String,
-    email: Option<String>,
-    medical_history: Option<String>,
-    allergies: Option<String>,
-    medications: Option<String>,
+    db: Db,
+    form: Form,
     focus_index: usize,
-    error_message: Option<String>,
-    error_timer: Option<Instant>,
-    success_message: Option<String>,
-    success_timer: Option<Instant>,
+    dob_picker: Option<DatePicker>,
 }
 
-const INPUT_FIELDS: usize = 10;
+const INPUT_FIELDS: usize = 14;
+
+fn patient_form() -> Form {
+    let mut form = Form::new(vec![
+        Field::new(t("common.first_name")).required(),
+        Field::new(t("common.last_name")).required(),
+        Field::new(t("patients.dob_field"))
+            .required()
+            .validate_with(Box::new(form::past_date)),
+        Field::new(t("patients.gender_field"))
+            .required()
+            .validate_with(Box::new(form::gender)),
+        Field::new(t("patients.street")).required(),
+        Field::new(t("patients.postcode")),
+        Field::new(t("patients.city")),
+        Field::new(t("patients.region")),
+        Field::new(t("common.phone"))
+            .required()
+            .validate_with(Box::new(form::phone)),
+        Field::new(t("patients.email")).validate_with(Box::new(form::email)),
+        Field::new(t("patients.contact_by_field"))
+            .required()
+            .validate_with(form::one_of(&CONTACT_PREFERENCES)),
+        Field::new(t("patients.medical_history")),
+        Field::new(t("patients.allergies")),
+        Field::new(t("patients.medications")),
+    ]);
+    form.fields[GENDER].set(Gender::Male.label());
+    form.fields[CONTACT].set(CONTACT_PREFERENCES[0]);
+    form
+}
 
-impl Default for AddPatient {
-    fn default() -> Self {
+impl AddPatient {
+    pub fn new(db: &Db) -> Self {
         AddPatient {
-            first_name: String::new(),
-            last_name: String::new(),
-            dob: String::new(),
-            gender: Gender::Male,
-            address: String::new(),
-            phone: String::new(),
-            email: None,
-            medical_history: None,
-            allergies: None,
-            medications: None,
+            db: db.clone(),
+            form: patient_form(),
             focus_index: 0,
-            error_message: None,
-            error_timer: None,
-            success_message: None,
-            success_timer: None,
+            dob_picker: None,
         }
     }
-}
-
-impl AddPatient {
-    pub fn new() -> Self {
-        Self::default()
-    }
 
-    fn clear_error(&mut self) {
-        self.error_message = None;
-        self.error_timer = None;
-    }
+    fn submit(&mut self) {
+        if let Some(invalid) = self.form.validate() {
+            self.focus_index = invalid;
+            toast::push(Toast::error(t("common.fix_fields")));
+            return;
+        }
 
-    fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_timer = Some(Instant::now());
-    }
+        let new_patient = Patient {
+            id: 0,
+            first_name: self.form.value(FIRST_NAME),
+            last_name: self.form.value(LAST_NAME),
+            date_of_birth: self.form.value(DOB),
+            gender: Gender::from(self.form.value(GENDER)),
+            address: self.form.value(ADDRESS),
+            city: self.form.value(CITY),
+            region: self.form.value(REGION),
+            postcode: self.form.value(POSTCODE),
+            phone_number: contact::normalize_phone(&self.form.value(PHONE)),
+            email: self
+                .form
+                .optional_value(EMAIL)
+                .map(|email| contact::normalize_email(&email)),
+            medical_history: self.form.optional_value(MEDICAL_HISTORY),
+            allergies: self.form.optional_value(ALLERGIES),
+            current_medications: self.form.optional_value(MEDICATIONS),
+            contact_preference: ContactPreference::parse(&self.form.value(CONTACT))
+                .unwrap_or_default(),
+            photo_path: None,
+            mrn: String::new(),
+        };
 
-    pub fn check_error_timeout(&mut self) {
-        if let Some(timer) = self.error_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_error();
+        match db::create_patient(&self.db, &new_patient) {
+            Ok(_) => {
+                self.form = patient_form();
+                self.focus_index = 0;
+                toast::push(Toast::success(t("patients.added")));
+                quick_actions::saved(SelectedApp::PatientAdd);
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("common.database_error", &[("error", &e)])));
             }
         }
     }
 
-    fn clear_success(&mut self) {
-        self.success_message = None;
-        self.success_timer = None;
+    fn open_dob_picker(&mut self) {
+        let today = datetime::today();
+        let initial = datetime::parse_date(self.form.fields[DOB].value()).unwrap_or(today);
+        self.dob_picker = Some(DatePicker::new(initial).with_max(today));
     }
 
-    #[allow(dead_code)]
-    fn set_success(&mut self, message: String) {
-        self.success_message = Some(message);
-        self.success_timer = Some(Instant::now());
-    }
-
-    pub fn check_success_timeout(&mut self) {
-        if let Some(timer) = self.success_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_success();
+    fn handle_dob_picker(&mut self, key: KeyEvent) {
+        let Some(picker) = self.dob_picker.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let date = datetime::format_date(picker.selected());
+                self.form.fields[DOB].set(date);
+                self.dob_picker = None;
+            }
+            KeyCode::Esc => self.dob_picker = None,
+            _ => {
+                picker.handle_key(key);
             }
         }
     }
 
     pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<PatientAction>> {
-        self.check_error_timeout();
-        self.check_success_timeout();
+        if self.dob_picker.is_some() {
+            self.handle_dob_picker(key);
+            return Ok(None);
+        }
+        let focused = self.focus_index;
+        let action = self.process_key(key);
+        if focused == POSTCODE && self.focus_index != POSTCODE {
+            self.form.fill_from_postcode(POSTCODE, CITY, REGION);
+        }
+        Ok(action)
+    }
+
+    fn process_key(&mut self, key: KeyEvent) -> Option<PatientAction> {
         match key.code {
-            KeyCode::Char(c) => {
-                match self.focus_index {
-                    0 => self.first_name.push(c),
-                    1 => self.last_name.push(c),
-                    2 => self.dob.push(c),
-                    3 => {
-                        if c.to_ascii_lowercase() == 'f' {
-                            self.gender = Gender::Female;
-                        } else if c.to_ascii_lowercase() == 'm' {
-                            self.gender = Gender::Male;
-                        } else if c.to_ascii_lowercase() == 'o' {
-                            self.gender = Gender::Other;
-                        }
-                    }
-                    4 => self.address.push(c),
-                    5 => self.phone.push(c),
-                    6 => {
-                        if let Some(ref mut email) = self.email {
-                            email.push(c);
-                        } else {
-                            self.email = Some(c.to_string());
-                        }
-                    }
-                    7 => {
-                        if let Some(ref mut history) = self.medical_history {
-                            history.push(c);
-                        } else {
-                            self.medical_history = Some(c.to_string());
-                        }
-                    }
-                    8 => {
-                        if let Some(ref mut allergies) = self.allergies {
-                            allergies.push(c);
-                        } else {
-                            self.allergies = Some(c.to_string());
-                        }
-                    }
-                    9 => {
-                        if let Some(ref mut medications) = self.medications {
-                            medications.push(c);
-                        } else {
-                            self.medications = Some(c.to_string());
-                        }
-                    }
-                    _ => {}
+            KeyCode::Char('d')
+                if key.modifiers.contains(KeyModifiers::CONTROL) && self.focus_index == DOB =>
+            {
+                self.open_dob_picker();
+            }
+            KeyCode::Char(' ') if self.focus_index == GENDER => {
+                let gender = Gender::parse(self.form.fields[GENDER].value())
+                    .map_or(Gender::Male, |gender| gender.next());
+                self.form.fields[GENDER].set(gender.label());
+            }
+            KeyCode::Char(c) if self.focus_index == GENDER => {
+                if let Some(gender) = Gender::parse(&c.to_string()) {
+                    self.form.fields[GENDER].set(gender.label());
                 }
-                self.clear_error();
             }
-            KeyCode::Backspace => {
-                match self.focus_index {
-                    0 => self.first_name.pop(),
-                    1 => self.last_name.pop(),
-                    2 => self.dob.pop(),
-                    3 => None,
-                    4 => self.address.pop(),
-                    5 => self.phone.pop(),
-                    6 => self.email.as_mut().and_then(|email| email.pop()),
-                    7 => {
-                        if let Some(ref mut history) = self.medical_history {
-                            history.pop()
-                        } else {
-                            None
-                        }
-                    }
-                    8 => {
-                        if let Some(ref mut allergies) = self.allergies {
-                            allergies.pop()
-                        } else {
-                            None
-                        }
-                    }
-                    9 => {
-                        if let Some(ref mut medications) = self.medications {
-                            medications.pop()
-                        } else {
-                            None
-                        }
-                    }
-                    _ => None,
-                };
-                self.clear_error();
+            KeyCode::Char(c) if self.focus_index == CONTACT => {
+                if let Some(preference) = ContactPreference::parse(&c.to_string()) {
+                    self.form.fields[CONTACT].set(preference.label());
+                }
             }
+            _ if self.focus_index != GENDER
+                && self.focus_index != CONTACT
+                && self.form.handle_key(self.focus_index, key) => {}
             KeyCode::Tab => {
-                if self.focus_index <= 9 {
+                if self.focus_index < INPUT_FIELDS {
                     self.focus_index = INPUT_FIELDS;
                 } else if self.focus_index == INPUT_FIELDS {
                     self.focus_index = INPUT_FIELDS + 1;
@@ -194,84 +201,28 @@ impl AddPatient {
                 self.focus_index = (self.focus_index + INPUT_FIELDS + 1) % (INPUT_FIELDS + 2);
             }
             KeyCode::Left => {
-                if self.focus_index >= 6 && self.focus_index <= 9 {
-                    self.focus_index -= 6;
+                if (EMAIL..=MEDICATIONS).contains(&self.focus_index) {
+                    self.focus_index -= EMAIL;
                 }
             }
             KeyCode::Right => {
-                if self.focus_index <= 5 {
-                    self.focus_index = std::cmp::min(self.focus_index + 6, 9);
+                if self.focus_index < EMAIL {
+                    self.focus_index = std::cmp::min(self.focus_index + EMAIL, MEDICATIONS);
                 }
             }
             KeyCode::Esc => {
-                return Ok(Some(PatientAction::BackToHome));
+                return Some(PatientAction::BackToHome);
             }
             KeyCode::Enter => {
                 if self.focus_index == INPUT_FIELDS + 1 {
-                    return Ok(Some(PatientAction::BackToHome));
+                    return Some(PatientAction::BackToHome);
                 } else if self.focus_index == INPUT_FIELDS {
-                    if self.first_name.is_empty() {
-                        self.set_error("First Name cannot be empty".to_string());
-                        return Ok(None);
-                    }
-                    if self.last_name.is_empty() {
-                        self.set_error("Last Name cannot be empty".to_string());
-                        return Ok(None);
-                    }
-                    if self.dob.is_empty() {
-                        self.set_error("Date of Birth cannot be empty".to_string());
-                        return Ok(None);
-                    }
-                    if self.address.is_empty() {
-                        self.set_error("Address cannot be empty".to_string());
-                        return Ok(None);
-                    }
-                    if self.phone.is_empty() {
-                        self.set_error("Phone Number cannot be empty".to_string());
-                        return Ok(None);
-                    }
-
-                    let new_patient = Patient {
-                        id: 0,
-                        first_name: self.first_name.clone(),
-                        last_name: self.last_name.clone(),
-                        date_of_birth: self.dob.clone(),
-                        gender: self.gender.clone(),
-                        address: self.address.clone(),
-                        phone_number: self.phone.clone(),
-                        email: self.email.clone(),
-                        medical_history: self.medical_history.clone(),
-                        allergies: self.allergies.clone(),
-                        current_medications: self.medications.clone(),
-                    };
-
-                    match db::create_patient(&new_patient) {
-                        Ok(_) => {
-                            self.first_name.clear();
-                            self.last_name.clear();
-                            self.dob.clear();
-                            self.gender = Gender::Male;
-                            self.address.clear();
-                            self.phone.clear();
-                            self.email = None;
-                            self.medical_history = None;
-                            self.allergies = None;
-                            self.medications = None;
-                            self.focus_index = 0;
-
-                            self.success_message = Some("Patient added successfully!".to_string());
-
-                            self.clear_error();
-                        }
-                        Err(e) => {
-                            self.set_error(format!("Database error: {}", e));
-                        }
-                    }
+                    self.submit();
                 }
             }
             _ => {}
         }
-        Ok(None)
+        None
     }
 }
 
@@ -288,7 +239,7 @@ impl Component for AddPatient {
     fn render(&self, frame: &mut Frame) {
         let area = frame.area();
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             area,
         );
 
@@ -296,7 +247,7 @@ impl Component for AddPatient {
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),
-                Constraint::Min(22),
+                Constraint::Min(25),
                 Constraint::Length(0),
                 Constraint::Length(1),
                 Constraint::Length(1),
@@ -307,16 +258,16 @@ impl Component for AddPatient {
 
         let header = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header, main_layout[0]);
 
-        let title = Paragraph::new("🏥 PATIENT REGISTRATION")
+        let title = Paragraph::new(t("patients.registration_title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, main_layout[0]);
@@ -324,8 +275,8 @@ impl Component for AddPatient {
         let body_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
 
         frame.render_widget(body_block.clone(), main_layout[1]);
         let body_inner = body_block.inner(main_layout[1]);
@@ -345,6 +296,7 @@ impl Component for AddPatient {
                 Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Length(3),
+                Constraint::Length(3),
             ])
             .margin(1)
             .split(body_layout[0]);
@@ -354,265 +306,55 @@ impl Component for AddPatient {
             .constraints([
                 Constraint::Length(1),
                 Constraint::Length(3),
-                Constraint::Length(5),
-                Constraint::Length(5),
-                Constraint::Length(5),
+                Constraint::Length(3),
+                Constraint::Length(4),
+                Constraint::Length(4),
+                Constraint::Length(4),
             ])
             .margin(1)
             .split(body_layout[1]);
 
-        let primary_title = Paragraph::new("● REQUIRED INFORMATION").style(
+        let primary_title = Paragraph::new(t("common.required_info")).style(
             Style::default()
-                .fg(Color::Rgb(250, 250, 110))
+                .fg(theme().focus)
                 .add_modifier(Modifier::BOLD)
-                .bg(Color::Rgb(22, 22, 35)),
+                .bg(theme().surface),
         );
         frame.render_widget(primary_title, left_layout[0]);
 
-        let secondary_title = Paragraph::new("○ OPTIONAL INFORMATION").style(
+        let secondary_title = Paragraph::new(t("common.optional_info")).style(
             Style::default()
-                .fg(Color::Rgb(250, 250, 110))
+                .fg(theme().focus)
                 .add_modifier(Modifier::BOLD)
-                .bg(Color::Rgb(22, 22, 35)),
+                .bg(theme().surface),
         );
         frame.render_widget(secondary_title, right_layout[0]);
 
-        let required_style = Style::default().fg(Color::Rgb(230, 230, 250));
-
-        let first_name_input = Paragraph::new(self.first_name.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" First Name* ", required_style))
-                    .border_style(if self.focus_index == 0 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(first_name_input, left_layout[1]);
-
-        let last_name_input = Paragraph::new(self.last_name.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Last Name* ", required_style))
-                    .border_style(if self.focus_index == 1 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(last_name_input, left_layout[2]);
-
-        let dob_input = Paragraph::new(self.dob.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Date of Birth* ", required_style))
-                    .title_alignment(Alignment::Left)
-                    .border_style(if self.focus_index == 2 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(dob_input, left_layout[3]);
-
-        let gender_text = match self.gender {
-            Gender::Male => "Male",
-            Gender::Female => "Female",
-            Gender::Other => "Other",
-        };
-
-        let gender_input = Paragraph::new(gender_text)
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Gender* ", required_style))
-                    .border_style(if self.focus_index == 3 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(gender_input, left_layout[4]);
-
-        let address_input = Paragraph::new(self.address.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Address* ", required_style))
-                    .border_style(if self.focus_index == 4 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(address_input, left_layout[5]);
-
-        let phone_input = Paragraph::new(self.phone.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Phone* ", required_style))
-                    .border_style(if self.focus_index == 5 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(phone_input, left_layout[6]);
-
-        let optional_style = Style::default().fg(Color::Rgb(180, 180, 200));
-
-        let email_input = Paragraph::new(self.email.clone().unwrap_or_default())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Email (optional) ", optional_style))
-                    .border_style(if self.focus_index == 6 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(email_input, right_layout[1]);
-
-        let history_input = Paragraph::new(self.medical_history.clone().unwrap_or_default())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Medical History (optional) ", optional_style))
-                    .border_style(if self.focus_index == 7 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            )
-            .wrap(Wrap { trim: true });
-        frame.render_widget(history_input, right_layout[2]);
-
-        let allergies_input = Paragraph::new(self.allergies.clone().unwrap_or_default())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Allergies (optional) ", optional_style))
-                    .border_style(if self.focus_index == 8 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            )
-            .wrap(Wrap { trim: true });
-        frame.render_widget(allergies_input, right_layout[3]);
-
-        let medications_input = Paragraph::new(self.medications.clone().unwrap_or_default())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Medications (optional) ", optional_style))
-                    .border_style(if self.focus_index == 9 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            )
-            .wrap(Wrap { trim: true });
-        frame.render_widget(medications_input, right_layout[4]);
-
-        let status_message = if let Some(success) = &self.success_message {
-            Paragraph::new(format!("✓ {}", success))
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD)
-                        .bg(Color::Rgb(16, 16, 28)),
-                )
-                .alignment(Alignment::Center)
-        } else if let Some(error) = &self.error_message {
-            Paragraph::new(format!("⚠️ {}", error))
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(255, 100, 100))
-                        .add_modifier(Modifier::BOLD)
-                        .bg(Color::Rgb(16, 16, 28)),
-                )
-                .alignment(Alignment::Center)
-        } else {
-            Paragraph::new("").style(Style::default().bg(Color::Rgb(16, 16, 28)))
-        };
-        frame.render_widget(status_message, main_layout[3]);
+        for (index, area) in [FIRST_NAME, LAST_NAME, DOB, GENDER, ADDRESS]
+            .into_iter()
+            .zip(&left_layout[1..])
+        {
+            self.form
+                .render_field(frame, index, *area, self.focus_index);
+        }
+        let locality = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Ratio(1, 3),
+                Constraint::Ratio(1, 3),
+                Constraint::Ratio(1, 3),
+            ])
+            .split(left_layout[6]);
+        for (index, area) in [POSTCODE, CITY, REGION].into_iter().zip(locality.iter()) {
+            self.form
+                .render_field(frame, index, *area, self.focus_index);
+        }
+        self.form
+            .render_field(frame, PHONE, left_layout[7], self.focus_index);
+        for (index, area) in right_layout[1..].iter().enumerate() {
+            self.form
+                .render_field(frame, EMAIL + index, *area, self.focus_index);
+        }
 
         let footer_layout = Layout::default()
             .direction(Direction::Vertical)
@@ -624,17 +366,17 @@ impl Component for AddPatient {
             .split(main_layout[5]);
 
         let submit_text = if self.focus_index == INPUT_FIELDS {
-            "► Submit ◄"
+            format!("► {} ◄", t("common.submit"))
         } else {
-            "  Submit  "
+            format!("  {}  ", t("common.submit"))
         };
 
         let submit_style = if self.focus_index == INPUT_FIELDS {
             Style::default()
-                .fg(Color::Rgb(140, 219, 140))
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let submit_button = Paragraph::new(submit_text)
@@ -643,17 +385,17 @@ impl Component for AddPatient {
         frame.render_widget(submit_button, footer_layout[0]);
 
         let back_text = if self.focus_index == INPUT_FIELDS + 1 {
-            "► Back ◄"
+            format!("► {} ◄", t("common.back"))
         } else {
-            "  Back  "
+            format!("  {}  ", t("common.back"))
         };
 
         let back_style = if self.focus_index == INPUT_FIELDS + 1 {
             Style::default()
-                .fg(Color::Rgb(129, 199, 245))
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let back_button = Paragraph::new(back_text)
@@ -661,9 +403,13 @@ impl Component for AddPatient {
             .alignment(Alignment::Center);
         frame.render_widget(back_button, footer_layout[1]);
 
-        let help_text = Paragraph::new("Tab: Switch Focus | Arrow Keys: Switch Fields | Enter: Submit | Esc: Back\nFor Gender: Type 'M' for Male, 'F' for Female, 'O' for Others")
-.style(Style::default().fg(Color::Rgb(140, 140, 170)).bg(Color::Rgb(16, 16, 28)))
-.alignment(Alignment::Center);
+        let help_text = Paragraph::new(t("patients.add_help"))
+            .style(Style::default().fg(theme().help).bg(theme().background))
+            .alignment(Alignment::Center);
         frame.render_widget(help_text, footer_layout[2]);
+
+        if let Some(picker) = &self.dob_picker {
+            picker.render_popup(frame, t("patients.dob_picker"));
+        }
     }
 }
@@ -0,0 +1,639 @@
+//! Data protection requests for a patient: a subject access export of
+//! everything stored about them, and an erasure that anonymizes them in
+//! place. Erasure is limited to administrators, needs a reason and the
+//! patient's MRN typed back, and both kinds of request are logged.
+
+use crate::app::SelectedApp;
+use crate::auth;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::text_input::TextInput;
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{DataRequest, Patient};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state;
+use crate::utils::{contact, datetime, write_export};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use serde_json::{json, Value};
+
+const REASON: usize = 0;
+const CONFIRM: usize = 1;
+
+struct ErasePopup {
+    patient: Patient,
+    reason: TextInput,
+    confirm: TextInput,
+    focus_index: usize,
+    error: Option<String>,
+}
+
+/// A request's kind in the user's language; `kind` is as stored.
+fn kind_label(kind: &str) -> &str {
+    match kind {
+        "Access" => t("compliance.kind.access"),
+        "Erasure" => t("compliance.kind.erasure"),
+        other => other,
+    }
+}
+
+/// "medical_records" as "Medical records".
+fn table_label(table: &str) -> String {
+    let text = table.replace('_', " ");
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => text,
+    }
+}
+
+pub struct Compliance {
+    db: Db,
+    patients: Vec<Patient>,
+    filtered: Vec<Patient>,
+    search_input: TextInput,
+    is_searching: bool,
+    state: TableState,
+    /// Everything stored about the selected patient, table by table.
+    subject: Vec<(String, Vec<Value>)>,
+    requests: Vec<DataRequest>,
+    popup: Option<ErasePopup>,
+}
+
+impl Compliance {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            patients: Vec::new(),
+            filtered: Vec::new(),
+            search_input: TextInput::new(),
+            is_searching: false,
+            state: TableState::default(),
+            subject: Vec::new(),
+            requests: Vec::new(),
+            popup: None,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.patients = db::get_all_patients(&self.db)?;
+        self.filter();
+        Ok(())
+    }
+
+    fn filter(&mut self) {
+        let term = self.search_input.value().trim().to_lowercase();
+        self.filtered = self
+            .patients
+            .iter()
+            .filter(|p| {
+                term.is_empty()
+                    || format!("{} {}", p.first_name, p.last_name)
+                        .to_lowercase()
+                        .contains(&term)
+                    || p.id.to_string() == term
+                    || p.mrn.to_lowercase() == term
+                    || contact::phone_matches(&p.phone_number, &term)
+            })
+            .cloned()
+            .collect();
+        match self.state.selected() {
+            _ if self.filtered.is_empty() => self.state.select(None),
+            Some(i) if i < self.filtered.len() => {}
+            _ => self.state.select(Some(0)),
+        }
+        self.load_selected();
+    }
+
+    fn selected(&self) -> Option<&Patient> {
+        self.state.selected().and_then(|i| self.filtered.get(i))
+    }
+
+    fn load_selected(&mut self) {
+        let Some(patient_id) = self.selected().map(|p| p.id) else {
+            self.subject.clear();
+            self.requests.clear();
+            return;
+        };
+        let loaded = db::get_subject_data(&self.db, patient_id)
+            .and_then(|subject| Ok((subject, db::get_data_requests(&self.db, patient_id)?)));
+        match loaded {
+            Ok((subject, requests)) => {
+                self.subject = subject;
+                self.requests = requests;
+            }
+            Err(e) => toast::push(Toast::error(tf("compliance.load_failed", &[("error", &e)]))),
+        }
+    }
+
+    fn rows_in(&self, table: &str) -> usize {
+        self.subject
+            .iter()
+            .find(|(name, _)| name == table)
+            .map_or(0, |(_, rows)| rows.len())
+    }
+
+    fn is_erased(&self) -> bool {
+        self.requests.iter().any(|r| r.kind == "Erasure")
+    }
+
+    /// Writes the subject access export of the selected patient and logs
+    /// the request.
+    fn export_access(&mut self) -> Result<()> {
+        let Some(patient) = self.selected().cloned() else {
+            return Ok(());
+        };
+        let mrn = patient.mrn.clone();
+        let handled_by = ui_state::current_user();
+        let created_at = datetime::now_text();
+        let data: serde_json::Map<String, Value> = self
+            .subject
+            .iter()
+            .map(|(table, rows)| (table.clone(), Value::Array(rows.clone())))
+            .collect();
+        let document = json!({
+            "request": "Subject access",
+            "mrn": mrn,
+            "generated_at": created_at,
+            "generated_by": handled_by,
+            "data": data,
+        });
+        let file_name = format!("subject_access_{}_{}.json", mrn, datetime::today_text());
+        let exported = serde_json::to_string_pretty(&document)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| write_export(&file_name, &contents));
+        match exported {
+            Ok(path) => {
+                db::create_data_request(
+                    &self.db,
+                    &DataRequest {
+                        id: 0,
+                        patient_id: patient.id,
+                        kind: "Access".to_string(),
+                        reason: None,
+                        handled_by,
+                        details: Some(path.display().to_string()),
+                        created_at,
+                    },
+                )?;
+                toast::push(Toast::success(tf(
+                    "compliance.exported",
+                    &[("mrn", &mrn), ("path", &path.display())],
+                )));
+                self.load_selected();
+            }
+            Err(e) => toast::push(Toast::error(format!("{}", e))),
+        }
+        Ok(())
+    }
+
+    fn open_erase_popup(&mut self) {
+        let Some(patient) = self.selected().cloned() else {
+            return;
+        };
+        let is_admin = ui_state::current_user().is_some_and(|user| auth::is_admin(&self.db, &user));
+        if !is_admin {
+            toast::push(Toast::error(t("compliance.admins_only")));
+            return;
+        }
+        if self.is_erased() {
+            toast::push(Toast::error(t("compliance.already_erased")));
+            return;
+        }
+        self.popup = Some(ErasePopup {
+            patient,
+            reason: TextInput::new(),
+            confirm: TextInput::new(),
+            focus_index: REASON,
+            error: None,
+        });
+    }
+
+    fn erase(&mut self) -> Result<()> {
+        let details = format!(
+            "Name, contact details and birth date anonymized; {} document references and {} newborn names removed",
+            self.rows_in("patient_documents"),
+            self.rows_in("births")
+        );
+        let Some(popup) = self.popup.as_mut() else {
+            return Ok(());
+        };
+        let mrn = popup.patient.mrn.clone();
+        let reason = popup.reason.value().trim().to_string();
+        if reason.is_empty() {
+            popup.error = Some(t("compliance.reason_required").to_string());
+            popup.focus_index = REASON;
+            return Ok(());
+        }
+        if !popup.confirm.value().trim().eq_ignore_ascii_case(&mrn) {
+            popup.error = Some(tf("compliance.type_to_confirm", &[("mrn", &mrn)]));
+            popup.focus_index = CONFIRM;
+            return Ok(());
+        }
+        let request = DataRequest {
+            id: 0,
+            patient_id: popup.patient.id,
+            kind: "Erasure".to_string(),
+            reason: Some(reason),
+            handled_by: ui_state::current_user(),
+            details: Some(details),
+            created_at: datetime::now_text(),
+        };
+        match db::erase_patient(&self.db, &request) {
+            Ok(()) => {
+                self.popup = None;
+                toast::push(Toast::success(tf("compliance.erased", &[("mrn", &mrn)])));
+                self.load_data()?;
+            }
+            Err(e) => popup.error = Some(tf("compliance.erase_failed", &[("error", &e)])),
+        }
+        Ok(())
+    }
+
+    fn handle_popup_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(popup) = self.popup.as_mut() else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+                popup.focus_index = 1 - popup.focus_index;
+            }
+            KeyCode::Enter if popup.focus_index == REASON => popup.focus_index = CONFIRM,
+            KeyCode::Enter => self.erase()?,
+            _ => {
+                let input = match popup.focus_index {
+                    REASON => &mut popup.reason,
+                    _ => &mut popup.confirm,
+                };
+                if input.handle_key(key) {
+                    popup.error = None;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if self.popup.is_some() {
+            self.handle_popup_input(key)?;
+            return Ok(None);
+        }
+        if self.is_searching {
+            match key.code {
+                _ if self.search_input.handle_key(key) => self.filter(),
+                KeyCode::Enter | KeyCode::Down | KeyCode::Esc => self.is_searching = false,
+                _ => {}
+            }
+            return Ok(None);
+        }
+        let len = self.filtered.len();
+        match key.code {
+            KeyCode::Char('/') | KeyCode::Char('s') | KeyCode::Char('S') => {
+                self.is_searching = true;
+            }
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+                self.load_selected();
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+                self.load_selected();
+            }
+            KeyCode::Char('e') | KeyCode::Char('E') => self.export_access()?,
+            KeyCode::Char('x') | KeyCode::Char('X') => self.open_erase_popup(),
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                self.load_data()?;
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_patients(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("compliance.patients")))
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.filtered.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("compliance.no_patients"))
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        }
+        let widths = [
+            Constraint::Length(10),
+            Constraint::Min(16),
+            Constraint::Length(11),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+        let rows: Vec<Row> = self
+            .filtered
+            .iter()
+            .map(|p| {
+                Row::new(vec![
+                    cell(p.mrn.clone(), &columns, 0),
+                    cell(format!("{} {}", p.first_name, p.last_name), &columns, 1),
+                    cell(p.date_of_birth.as_str(), &columns, 2),
+                ])
+                .style(Style::default().fg(theme().text))
+            })
+            .collect();
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec![
+                    t("compliance.mrn"),
+                    t("compliance.name"),
+                    t("compliance.born"),
+                ])
+                .style(Style::default().bg(theme().header).fg(theme().title)),
+            )
+            .block(block)
+            .row_highlight_style(Style::default().bg(theme().highlight))
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.state.clone());
+    }
+
+    fn render_stored(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = self
+            .subject
+            .iter()
+            .filter(|(_, rows)| !rows.is_empty())
+            .map(|(table, rows)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {:<24}", table_label(table)),
+                        Style::default().fg(theme().text),
+                    ),
+                    Span::styled(
+                        format!("{:>4}", rows.len()),
+                        Style::default().fg(theme().accent),
+                    ),
+                ])
+            })
+            .collect();
+        let total: usize = self.subject.iter().map(|(_, rows)| rows.len()).sum();
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(
+                        " {} ",
+                        tf("compliance.stored", &[("count", &total)])
+                    ))
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            ),
+            area,
+        );
+    }
+
+    fn render_requests(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = if self.requests.is_empty() {
+            vec![Line::from(Span::styled(
+                format!(" {}", t("compliance.none")),
+                Style::default().fg(theme().inactive),
+            ))]
+        } else {
+            self.requests
+                .iter()
+                .flat_map(|r| {
+                    let color = if r.kind == "Erasure" {
+                        theme().error
+                    } else {
+                        theme().accent
+                    };
+                    let mut lines = vec![Line::from(vec![
+                        Span::styled(
+                            format!(" {} ", datetime::display_stamp(&r.created_at)),
+                            Style::default().fg(theme().inactive),
+                        ),
+                        Span::styled(
+                            format!("{:<8}", kind_label(&r.kind)),
+                            Style::default().fg(color).add_modifier(Modifier::BOLD),
+                        ),
+                        Span::styled(
+                            format!(
+                                " {}",
+                                tf(
+                                    "compliance.by",
+                                    &[(
+                                        "name",
+                                        &r.handled_by.as_deref().unwrap_or(t("common.unknown"))
+                                    )]
+                                )
+                            ),
+                            Style::default().fg(theme().text),
+                        ),
+                    ])];
+                    if let Some(reason) = &r.reason {
+                        lines.push(Line::from(Span::styled(
+                            format!("   {}", tf("compliance.reason", &[("reason", &reason)])),
+                            Style::default().fg(theme().text),
+                        )));
+                    }
+                    if let Some(details) = &r.details {
+                        lines.push(Line::from(Span::styled(
+                            format!("   {}", details),
+                            Style::default().fg(theme().inactive),
+                        )));
+                    }
+                    lines
+                })
+                .collect()
+        };
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("compliance.requests")))
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            ),
+            area,
+        );
+    }
+
+    fn render_popup(&self, frame: &mut Frame, popup: &ErasePopup) {
+        let area = frame.area();
+        let width = 70.min(area.width);
+        let height = 14.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" {} ", t("compliance.erase_title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().error))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(4),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        let mrn = popup.patient.mrn.clone();
+        frame.render_widget(
+            Paragraph::new(tf(
+                "compliance.erase_warning",
+                &[
+                    ("first", &popup.patient.first_name),
+                    ("last", &popup.patient.last_name),
+                    ("mrn", &mrn),
+                ],
+            ))
+            .style(Style::default().fg(theme().text))
+            .wrap(Wrap { trim: true }),
+            layout[0],
+        );
+        let input_block = |title: String, focused: bool| {
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(title)
+                .border_style(Style::default().fg(if focused {
+                    theme().focus
+                } else {
+                    theme().unfocused
+                }))
+                .style(Style::default().bg(theme().input))
+        };
+        frame.render_widget(
+            Paragraph::new(popup.reason.line(popup.focus_index == REASON)).block(input_block(
+                format!(" {} ", t("compliance.reason_field")),
+                popup.focus_index == REASON,
+            )),
+            layout[1],
+        );
+        frame.render_widget(
+            Paragraph::new(popup.confirm.line(popup.focus_index == CONFIRM)).block(input_block(
+                format!(" {} ", tf("compliance.type_to_confirm", &[("mrn", &mrn)])),
+                popup.focus_index == CONFIRM,
+            )),
+            layout[2],
+        );
+        if let Some(error) = &popup.error {
+            frame.render_widget(
+                Paragraph::new(error.as_str()).style(Style::default().fg(theme().error)),
+                layout[3],
+            );
+        }
+        frame.render_widget(
+            Paragraph::new(t("compliance.erase_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+    }
+}
+
+impl Component for Compliance {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(10),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("compliance.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(self.search_input.line(self.is_searching)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("compliance.search")))
+                    .border_style(Style::default().fg(if self.is_searching {
+                        theme().focus
+                    } else {
+                        theme().unfocused
+                    }))
+                    .style(Style::default().bg(theme().input)),
+            ),
+            layout[1],
+        );
+
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(layout[2]);
+        self.render_patients(frame, body[0]);
+        let details = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(body[1]);
+        self.render_stored(frame, details[0]);
+        self.render_requests(frame, details[1]);
+
+        frame.render_widget(
+            Paragraph::new(t("compliance.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+
+        if let Some(popup) = &self.popup {
+            self.render_popup(frame, popup);
+        }
+    }
+}
@@ -1,34 +1,36 @@
 use crate::app::SelectedApp;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
-use crate::db;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
 use crate::models::Patient;
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::utils::contact;
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     prelude::*,
     widgets::{Block, BorderType, Borders, Cell, Clear, Paragraph, Row, Table, TableState},
 };
-use std::time::{Duration, Instant};
 
 pub struct DeletePatient {
+    db: Db,
     patients: Vec<Patient>,
     filtered_patients: Vec<Patient>,
     selected_patients: Vec<bool>,
-    search_input: String,
+    search_input: TextInput,
     is_searching: bool,
     table_state: TableState,
     show_confirmation: bool,
     confirmation_selected: usize,
-    error_message: Option<String>,
-    success_message: Option<String>,
-    error_timer: Option<Instant>,
-    success_timer: Option<Instant>,
 }
 
 impl DeletePatient {
-    pub fn new() -> Self {
-        let patients = match db::get_all_patients() {
+    pub fn new(db: &Db) -> Self {
+        let patients = match db::get_all_patients(db) {
             Ok(p) => p,
             Err(_) => Vec::new(),
         };
@@ -39,18 +41,15 @@ impl DeletePatient {
         }
 
         Self {
+            db: db.clone(),
             selected_patients: vec![false; patients.len()],
             filtered_patients: patients.clone(),
             patients,
-            search_input: String::new(),
+            search_input: TextInput::new(),
             is_searching: false,
             table_state,
             show_confirmation: false,
             confirmation_selected: 1,
-            error_message: None,
-            success_message: None,
-            error_timer: None,
-            success_timer: None,
         }
     }
 
@@ -59,7 +58,7 @@ impl DeletePatient {
             self.filtered_patients = self.patients.clone();
             self.selected_patients = vec![false; self.patients.len()];
         } else {
-            let search_term = self.search_input.to_lowercase();
+            let search_term = self.search_input.value().to_lowercase();
             self.filtered_patients = self
                 .patients
                 .iter()
@@ -67,8 +66,8 @@ impl DeletePatient {
                     p.first_name.to_lowercase().contains(&search_term)
                         || p.last_name.to_lowercase().contains(&search_term)
                         || p.id.to_string().contains(&search_term)
-                        || p.phone_number.to_lowercase().contains(&search_term)
-                        || p.address.to_lowercase().contains(&search_term)
+                        || contact::phone_matches(&p.phone_number, &search_term)
+                        || p.full_address().to_lowercase().contains(&search_term)
                 })
                 .cloned()
                 .collect();
@@ -86,8 +85,6 @@ impl DeletePatient {
     }
 
     fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
-        self.check_timeouts();
-
         if self.show_confirmation {
             match key.code {
                 KeyCode::Left | KeyCode::Right => {
@@ -107,7 +104,7 @@ impl DeletePatient {
                         }
 
                         for patient_id in patients_to_delete {
-                            match db::delete_patient(patient_id) {
+                            match db::delete_patient(&self.db, patient_id) {
                                 Ok(_) => deleted_count += 1,
                                 Err(_) => {
                                     error_occurred = true;
@@ -117,22 +114,20 @@ impl DeletePatient {
                         }
 
                         if error_occurred {
-                            self.set_error(format!(
-                                "Error during deletion. {} patients deleted successfully.",
-                                deleted_count
-                            ));
+                            toast::push(Toast::error(tf(
+                                "patients.delete.partial",
+                                &[("count", &deleted_count)],
+                            )));
                         } else if deleted_count > 0 {
-                            self.success_message = Some(format!(
-                                "{} patient{} deleted successfully!",
-                                deleted_count,
-                                if deleted_count == 1 { "" } else { "s" }
-                            ));
-                            self.success_timer = Some(Instant::now());
+                            toast::push(Toast::success(tf(
+                                "patients.delete.deleted",
+                                &[("count", &deleted_count)],
+                            )));
                         } else {
-                            self.set_error("No patients were selected for deletion.".to_string());
+                            toast::push(Toast::error(t("patients.delete.none_selected")));
                         }
 
-                        if let Ok(patients) = db::get_all_patients() {
+                        if let Ok(patients) = db::get_all_patients(&self.db) {
                             self.patients = patients;
                             self.filter_patients();
 
@@ -158,12 +153,7 @@ impl DeletePatient {
             }
         } else if self.is_searching {
             match key.code {
-                KeyCode::Char(c) => {
-                    self.search_input.push(c);
-                    self.filter_patients();
-                }
-                KeyCode::Backspace => {
-                    self.search_input.pop();
+                _ if self.search_input.handle_key(key) => {
                     self.filter_patients();
                 }
                 KeyCode::Enter | KeyCode::Down => {
@@ -225,7 +215,7 @@ impl DeletePatient {
                         self.show_confirmation = true;
                         self.confirmation_selected = 1;
                     } else {
-                        self.set_error("No patients selected for deletion.".to_string());
+                        toast::push(Toast::error(t("patients.delete.none_selected_2")));
                     }
                 }
                 KeyCode::Enter => {
@@ -244,7 +234,8 @@ impl DeletePatient {
                     }
                 }
                 KeyCode::Char('r') | KeyCode::Char('R') => {
-                    if let Ok(patients) = db::get_all_patients() {
+                    db::cache::invalidate_all(&self.db);
+                    if let Ok(patients) = db::get_all_patients(&self.db) {
                         self.patients = patients;
                         self.filter_patients();
                     }
@@ -257,48 +248,6 @@ impl DeletePatient {
         }
         Ok(None)
     }
-
-    fn clear_error(&mut self) {
-        self.error_message = None;
-        self.error_timer = None;
-    }
-
-    fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_timer = Some(Instant::now());
-    }
-
-    fn clear_success(&mut self) {
-        self.success_message = None;
-        self.success_timer = None;
-    }
-
-    fn check_success_timeout(&mut self) {
-        if let Some(timer) = self.success_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_success();
-            }
-        }
-    }
-
-    fn check_error_timeout(&mut self) {
-        if let Some(timer) = self.error_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_error();
-            }
-        }
-    }
-
-    fn check_timeouts(&mut self) {
-        self.check_error_timeout();
-        self.check_success_timeout();
-    }
-}
-
-impl Default for DeletePatient {
-    fn default() -> Self {
-        Self::new()
-    }
 }
 
 impl Component for DeletePatient {
@@ -309,7 +258,7 @@ impl Component for DeletePatient {
     fn render(&self, frame: &mut Frame) {
         let area = frame.area();
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             area,
         );
 
@@ -327,16 +276,16 @@ impl Component for DeletePatient {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
 
-        let title = Paragraph::new("🗑️ PATIENT DELETION MANAGER")
+        let title = Paragraph::new(t("patients.delete.title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
@@ -345,24 +294,20 @@ impl Component for DeletePatient {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Patients ",
+                format!(" {} ", t("common.search_patients")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(75, 75, 120))
+                Style::default().fg(theme().border)
             })
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .style(Style::default().bg(theme().surface));
 
-        let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(22, 22, 35)),
-            )
+        let search_paragraph = Paragraph::new(self.search_input.line(self.is_searching))
+            .style(Style::default().fg(theme().text).bg(theme().surface))
             .block(search_block);
         frame.render_widget(search_paragraph, layout[1]);
 
@@ -371,29 +316,47 @@ impl Component for DeletePatient {
             .border_type(BorderType::Rounded)
             .title(if !self.search_input.is_empty() {
                 format!(
-                    " Patients ({} of {} matches) ",
-                    self.filtered_patients.len(),
-                    self.patients.len()
+                    " {} ",
+                    tf(
+                        "common.patients_matches",
+                        &[
+                            ("shown", &self.filtered_patients.len()),
+                            ("total", &self.patients.len())
+                        ]
+                    )
                 )
             } else {
-                format!(" Patients ({}) ", self.patients.len())
+                format!(
+                    " {} ",
+                    tf("common.patients_count", &[("total", &self.patients.len())])
+                )
             })
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
-            .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .border_style(Style::default().fg(theme().unfocused))
+            .style(Style::default().bg(theme().input));
 
         let selected_style = Style::default()
-            .bg(Color::Rgb(45, 45, 60))
-            .fg(Color::Rgb(250, 250, 110))
+            .bg(theme().highlight)
+            .fg(theme().focus)
             .add_modifier(Modifier::BOLD);
 
-        let normal_style = Style::default()
-            .bg(Color::Rgb(26, 26, 36))
-            .fg(Color::Rgb(220, 220, 240));
+        let normal_style = Style::default().bg(theme().input).fg(theme().text);
+
+        let widths = [
+            Constraint::Length(5),
+            Constraint::Length(8),
+            Constraint::Length(15),
+            Constraint::Length(15),
+            Constraint::Length(12),
+            Constraint::Length(8),
+            Constraint::Length(15),
+            Constraint::Min(20),
+        ];
+        let columns = column_widths(layout[2].inner(Margin::new(1, 1)), &widths, 1, "► ");
 
         let mut rows = Vec::new();
         for (i, patient) in self.filtered_patients.iter().enumerate() {
@@ -405,32 +368,27 @@ impl Component for DeletePatient {
 
             rows.push(Row::new(vec![
                 Cell::from(checkbox).style(normal_style),
-                Cell::from(patient.id.to_string()).style(normal_style),
-                Cell::from(patient.first_name.clone()).style(normal_style),
-                Cell::from(patient.last_name.clone()).style(normal_style),
-                Cell::from(patient.date_of_birth.clone()).style(normal_style),
-                Cell::from(match patient.gender {
-                    crate::models::Gender::Male => "Male",
-                    crate::models::Gender::Female => "Female",
-                    crate::models::Gender::Other => "Other",
-                })
-                .style(normal_style),
-                Cell::from(patient.phone_number.clone()).style(normal_style),
-                Cell::from(patient.address.clone()).style(normal_style),
+                cell(patient.id.to_string(), &columns, 1).style(normal_style),
+                cell(&patient.first_name, &columns, 2).style(normal_style),
+                cell(&patient.last_name, &columns, 3).style(normal_style),
+                cell(&patient.date_of_birth, &columns, 4).style(normal_style),
+                Cell::from(patient.gender.label()).style(normal_style),
+                cell(&patient.phone_number, &columns, 6).style(normal_style),
+                cell(patient.full_address(), &columns, 7).style(normal_style),
             ]));
         }
 
         if self.filtered_patients.is_empty() {
             let message = if self.search_input.is_empty() {
-                "No patients found in database"
+                t("common.no_patients")
             } else {
-                "No patients match your search criteria"
+                t("common.no_patient_matches")
             };
 
             rows.push(Row::new(vec![
                 Cell::from(""),
                 Cell::from(""),
-                Cell::from(message).style(Style::default().fg(Color::Rgb(180, 180, 200))),
+                Cell::from(message).style(Style::default().fg(theme().inactive)),
                 Cell::from(""),
                 Cell::from(""),
                 Cell::from(""),
@@ -439,86 +397,55 @@ impl Component for DeletePatient {
             ]));
         }
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(5),
-                Constraint::Length(8),
-                Constraint::Length(15),
-                Constraint::Length(15),
-                Constraint::Length(12),
-                Constraint::Length(8),
-                Constraint::Length(15),
-                Constraint::Min(20),
-            ],
-        )
-        .header(
-            Row::new(vec![
-                Cell::from(""),
-                Cell::from("ID").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("First Name").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Last Name").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("DOB").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Gender").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Phone").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Address").style(Style::default().add_modifier(Modifier::BOLD)),
-            ])
-            .style(
-                Style::default()
-                    .bg(Color::Rgb(80, 60, 130))
-                    .fg(Color::Rgb(180, 180, 250)),
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec![
+                    Cell::from(""),
+                    Cell::from(t("common.id")).style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.first_name"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.last_name"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.dob"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.gender"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.phone"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.address"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                ])
+                .style(Style::default().bg(theme().header).fg(theme().title))
+                .height(1),
             )
-            .height(1),
-        )
-        .block(table_block)
-        .row_highlight_style(selected_style)
-        .highlight_symbol("► ");
+            .block(table_block)
+            .row_highlight_style(selected_style)
+            .highlight_symbol("► ");
 
         let mut table_state_copy = self.table_state.clone();
         frame.render_stateful_widget(table, layout[2], &mut table_state_copy);
 
-        if let Some(success) = &self.success_message {
-            let success_paragraph = Paragraph::new(success.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, layout[3]);
-        } else if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(240, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, layout[3]);
-        }
-
         if self.is_searching {
-            let help_text =
-                Paragraph::new("Type to search | ↓/Enter: To results | Esc: Cancel search")
-                    .style(Style::default().fg(Color::Rgb(180, 180, 200)))
-                    .alignment(Alignment::Center);
+            let help_text = Paragraph::new(t("common.search_enter_help"))
+                .style(Style::default().fg(theme().inactive))
+                .alignment(Alignment::Center);
             frame.render_widget(help_text, layout[4]);
         } else {
             let help_block = Block::default()
-                .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-                .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+                .border_style(Style::default().fg(theme().border))
+                .style(Style::default().bg(theme().background));
 
             let help_layout = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
                 .split(layout[4]);
 
-            let help_text1 = Paragraph::new("/ or s: Search | ↑/↓: Navigate | Space: Toggle | A: Select/deselect all | R: Refresh")
-                .style(Style::default().fg(Color::Rgb(180, 180, 200)))
+            let help_text1 = Paragraph::new(t("patients.delete.help"))
+                .style(Style::default().fg(theme().inactive))
                 .alignment(Alignment::Center);
 
-            let help_text2 = Paragraph::new("Enter: Delete selected | B: Bulk delete | Esc: Back")
-                .style(Style::default().fg(Color::Rgb(180, 180, 200)))
+            let help_text2 = Paragraph::new(t("patients.delete.help_2"))
+                .style(Style::default().fg(theme().inactive))
                 .alignment(Alignment::Center);
 
             frame.render_widget(help_block, layout[4]);
@@ -540,22 +467,24 @@ impl Component for DeletePatient {
 
             let selected_count = self.selected_patients.iter().filter(|&&x| x).count();
             let title = format!(
-                " Delete {} Patient{} ",
-                selected_count,
-                if selected_count == 1 { "" } else { "s" }
+                " {} ",
+                tf(
+                    "patients.delete.confirm_title",
+                    &[("count", &selected_count)]
+                )
             );
 
             let dialog_block = Block::default()
                 .title(title)
                 .title_style(
                     Style::default()
-                        .fg(Color::Rgb(230, 230, 250))
+                        .fg(theme().title)
                         .add_modifier(Modifier::BOLD),
                 )
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-                .style(Style::default().bg(Color::Rgb(30, 30, 46)));
+                .border_style(Style::default().fg(theme().unfocused))
+                .style(Style::default().bg(theme().dialog));
 
             frame.render_widget(dialog_block.clone(), dialog_area);
 
@@ -566,11 +495,10 @@ impl Component for DeletePatient {
                 .constraints([Constraint::Length(2), Constraint::Length(2)])
                 .split(inner_area);
 
-            let message =
-                Paragraph::new("Are you sure you want to delete the selected patient(s)?")
-                    .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                    .add_modifier(Modifier::BOLD)
-                    .alignment(Alignment::Center);
+            let message = Paragraph::new(t("patients.delete.confirm"))
+                .style(Style::default().fg(theme().text))
+                .add_modifier(Modifier::BOLD)
+                .alignment(Alignment::Center);
             frame.render_widget(message, content_layout[0]);
 
             let buttons_layout = Layout::default()
@@ -580,28 +508,28 @@ impl Component for DeletePatient {
 
             let yes_style = if self.confirmation_selected == 0 {
                 Style::default()
-                    .fg(Color::Rgb(140, 219, 140))
+                    .fg(theme().success)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::Rgb(180, 180, 200))
+                Style::default().fg(theme().inactive)
             };
             let no_style = if self.confirmation_selected == 1 {
                 Style::default()
-                    .fg(Color::Rgb(255, 100, 100))
+                    .fg(theme().error)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::Rgb(180, 180, 200))
+                Style::default().fg(theme().inactive)
             };
 
             let yes_text = if self.confirmation_selected == 0 {
-                "► Yes ◄"
+                format!("► {} ◄", t("common.yes"))
             } else {
-                "  Yes  "
+                format!("  {}  ", t("common.yes"))
             };
             let no_text = if self.confirmation_selected == 1 {
-                "► No ◄"
+                format!("► {} ◄", t("common.no"))
             } else {
-                "  No  "
+                format!("  {}  ", t("common.no"))
             };
 
             let yes_button = Paragraph::new(yes_text)
@@ -0,0 +1,543 @@
+use crate::components::hospital::patients::PatientAction;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{DocumentType, Patient, PatientDocument};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{datetime, open_in_external_viewer};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::path::Path;
+
+const DOCUMENT_TYPES: [DocumentType; 5] = [
+    DocumentType::IdCopy,
+    DocumentType::ReferralLetter,
+    DocumentType::PastRecord,
+    DocumentType::LabReport,
+    DocumentType::Other,
+];
+
+const TYPE_INPUT: usize = 0;
+const DATE_INPUT: usize = 1;
+const EXPIRES_INPUT: usize = 2;
+const PATH_INPUT: usize = 3;
+const NOTES_INPUT: usize = 4;
+const SAVE_BUTTON: usize = 5;
+const CANCEL_BUTTON: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DocumentsMode {
+    Browsing,
+    Adding,
+    ConfirmDelete,
+}
+
+pub fn document_type_text(doc_type: DocumentType) -> &'static str {
+    match doc_type {
+        DocumentType::IdCopy => t("documents.type.id_copy"),
+        DocumentType::ReferralLetter => t("documents.type.referral"),
+        DocumentType::PastRecord => t("documents.type.past_record"),
+        DocumentType::LabReport => t("documents.type.lab_report"),
+        DocumentType::Other => t("documents.type.other"),
+    }
+}
+
+pub struct PatientDocuments {
+    db: Db,
+    patient: Patient,
+    documents: Vec<PatientDocument>,
+    state: TableState,
+    mode: DocumentsMode,
+    type_index: usize,
+    date_input: String,
+    expires_input: String,
+    path_input: String,
+    notes_input: String,
+    focus_index: usize,
+}
+
+impl PatientDocuments {
+    pub fn new(db: &Db, patient: Patient) -> Result<Self> {
+        let mut documents = Self {
+            db: db.clone(),
+            patient,
+            documents: Vec::new(),
+            state: TableState::default(),
+            mode: DocumentsMode::Browsing,
+            type_index: 0,
+            date_input: String::new(),
+            expires_input: String::new(),
+            path_input: String::new(),
+            notes_input: String::new(),
+            focus_index: TYPE_INPUT,
+        };
+        documents.fetch_documents()?;
+        Ok(documents)
+    }
+
+    fn fetch_documents(&mut self) -> Result<()> {
+        self.documents = db::get_patient_documents(&self.db, self.patient.id)?;
+        if self.documents.is_empty() {
+            self.state.select(None);
+        } else {
+            let selection = self
+                .state
+                .selected()
+                .unwrap_or(0)
+                .min(self.documents.len() - 1);
+            self.state.select(Some(selection));
+        }
+        Ok(())
+    }
+
+    fn selected_document(&self) -> Option<&PatientDocument> {
+        self.state.selected().and_then(|i| self.documents.get(i))
+    }
+
+    fn start_adding(&mut self) {
+        self.type_index = 0;
+        self.date_input = datetime::today_text();
+        self.expires_input.clear();
+        self.path_input.clear();
+        self.notes_input.clear();
+        self.focus_index = TYPE_INPUT;
+        self.mode = DocumentsMode::Adding;
+    }
+
+    fn save_document(&mut self) -> Result<()> {
+        let document_date = self.date_input.trim().to_string();
+        if datetime::parse_date(&document_date).is_none() {
+            toast::push(Toast::error(t("documents.bad_date")));
+            return Ok(());
+        }
+        let expires_on = self.expires_input.trim().to_string();
+        if !expires_on.is_empty() && datetime::parse_date(&expires_on).is_none() {
+            toast::push(Toast::error(t("documents.bad_expiry")));
+            return Ok(());
+        }
+        let file_path = self.path_input.trim().to_string();
+        if file_path.is_empty() {
+            toast::push(Toast::error(t("documents.no_path")));
+            return Ok(());
+        }
+        if !Path::new(&file_path).is_file() {
+            toast::push(Toast::error(tf(
+                "documents.no_file",
+                &[("path", &file_path)],
+            )));
+            return Ok(());
+        }
+
+        let document = PatientDocument {
+            id: 0,
+            patient_id: self.patient.id,
+            doc_type: DOCUMENT_TYPES[self.type_index],
+            document_date,
+            file_path,
+            notes: if self.notes_input.trim().is_empty() {
+                None
+            } else {
+                Some(self.notes_input.trim().to_string())
+            },
+            expires_on: (!expires_on.is_empty()).then_some(expires_on),
+        };
+
+        match db::create_patient_document(&self.db, &document) {
+            Ok(id) => {
+                self.mode = DocumentsMode::Browsing;
+                self.fetch_documents()?;
+                if let Some(pos) = self.documents.iter().position(|d| d.id == id) {
+                    self.state.select(Some(pos));
+                }
+                toast::push(Toast::success(t("documents.registered")));
+            }
+            Err(e) => toast::push(Toast::error(tf("documents.save_failed", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    fn open_selected(&mut self) {
+        let Some(document) = self.selected_document() else {
+            return;
+        };
+        let path = document.file_path.clone();
+        match open_in_external_viewer(Path::new(&path)) {
+            Ok(()) => toast::push(Toast::success(tf("common.opened", &[("path", &path)]))),
+            Err(e) => toast::push(Toast::error(e.to_string())),
+        }
+    }
+
+    fn delete_selected(&mut self) -> Result<()> {
+        let Some(document_id) = self.selected_document().map(|d| d.id) else {
+            return Ok(());
+        };
+        match db::delete_patient_document(&self.db, document_id) {
+            Ok(()) => {
+                self.fetch_documents()?;
+                toast::push(Toast::success(t("documents.removed")));
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "documents.remove_failed",
+                &[("error", &e)],
+            ))),
+        }
+        Ok(())
+    }
+
+    fn handle_add_input(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Left if self.focus_index == TYPE_INPUT => {
+                self.type_index =
+                    (self.type_index + DOCUMENT_TYPES.len() - 1) % DOCUMENT_TYPES.len();
+            }
+            KeyCode::Right | KeyCode::Char(' ') if self.focus_index == TYPE_INPUT => {
+                self.type_index = (self.type_index + 1) % DOCUMENT_TYPES.len();
+            }
+            KeyCode::Char(c) => match self.focus_index {
+                DATE_INPUT => self.date_input.push(c),
+                EXPIRES_INPUT => self.expires_input.push(c),
+                PATH_INPUT => self.path_input.push(c),
+                NOTES_INPUT => self.notes_input.push(c),
+                _ => {}
+            },
+            KeyCode::Backspace => match self.focus_index {
+                DATE_INPUT => {
+                    self.date_input.pop();
+                }
+                EXPIRES_INPUT => {
+                    self.expires_input.pop();
+                }
+                PATH_INPUT => {
+                    self.path_input.pop();
+                }
+                NOTES_INPUT => {
+                    self.notes_input.pop();
+                }
+                _ => {}
+            },
+            KeyCode::Tab | KeyCode::Down => {
+                self.focus_index = (self.focus_index + 1) % (CANCEL_BUTTON + 1);
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                self.focus_index = (self.focus_index + CANCEL_BUTTON) % (CANCEL_BUTTON + 1);
+            }
+            KeyCode::Enter => match self.focus_index {
+                SAVE_BUTTON => self.save_document()?,
+                CANCEL_BUTTON => self.mode = DocumentsMode::Browsing,
+                _ => self.focus_index += 1,
+            },
+            KeyCode::Esc => self.mode = DocumentsMode::Browsing,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<PatientAction>> {
+        match self.mode {
+            DocumentsMode::Adding => {
+                self.handle_add_input(key)?;
+                return Ok(None);
+            }
+            DocumentsMode::ConfirmDelete => {
+                if let KeyCode::Char('y') | KeyCode::Char('Y') = key.code {
+                    self.delete_selected()?;
+                }
+                self.mode = DocumentsMode::Browsing;
+                return Ok(None);
+            }
+            DocumentsMode::Browsing => {}
+        }
+
+        match key.code {
+            KeyCode::Down if !self.documents.is_empty() => {
+                let i = self
+                    .state
+                    .selected()
+                    .map_or(0, |i| (i + 1) % self.documents.len());
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if !self.documents.is_empty() => {
+                let len = self.documents.len();
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => self.start_adding(),
+            KeyCode::Enter | KeyCode::Char('o') | KeyCode::Char('O') => self.open_selected(),
+            KeyCode::Char('x') | KeyCode::Char('X') | KeyCode::Delete
+                if self.selected_document().is_some() =>
+            {
+                self.mode = DocumentsMode::ConfirmDelete;
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => self.fetch_documents()?,
+            KeyCode::Esc | KeyCode::Char('b') | KeyCode::Char('B') => {
+                return Ok(Some(PatientAction::BackToList));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_table(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(format!(
+                " {} ",
+                tf("documents.count", &[("count", &self.documents.len())])
+            ))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+
+        if self.documents.is_empty() {
+            let empty = Paragraph::new(t("documents.empty"))
+                .style(Style::default().fg(theme().text))
+                .alignment(Alignment::Center)
+                .block(block);
+            frame.render_widget(empty, area);
+            return;
+        }
+
+        let header = Row::new(
+            [
+                t("common.id"),
+                t("documents.type"),
+                t("documents.date"),
+                t("documents.expires"),
+                t("documents.file"),
+                t("documents.notes"),
+            ]
+            .iter()
+            .map(|h| Cell::from(*h).style(Style::default().fg(theme().title))),
+        )
+        .style(Style::default().bg(theme().header))
+        .height(1);
+
+        let widths = [
+            Constraint::Percentage(5),
+            Constraint::Percentage(15),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+            Constraint::Percentage(40),
+            Constraint::Percentage(20),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+
+        let today = datetime::today_text();
+        let rows = self.documents.iter().map(|document| {
+            let missing = !Path::new(&document.file_path).is_file();
+            let expired = document
+                .expires_on
+                .as_deref()
+                .is_some_and(|expires_on| expires_on < today.as_str());
+            Row::new(vec![
+                cell(document.id.to_string(), &columns, 0),
+                cell(document_type_text(document.doc_type), &columns, 1),
+                cell(&document.document_date, &columns, 2),
+                cell(document.expires_on.as_deref().unwrap_or("-"), &columns, 3),
+                cell(
+                    if missing {
+                        tf("documents.missing", &[("path", &document.file_path)])
+                    } else {
+                        document.file_path.clone()
+                    },
+                    &columns,
+                    4,
+                ),
+                cell(document.notes.as_deref().unwrap_or_default(), &columns, 5),
+            ])
+            .style(if missing {
+                Style::default().fg(theme().error)
+            } else if expired {
+                Style::default().fg(theme().warning)
+            } else {
+                Style::default().fg(theme().text)
+            })
+        });
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.state.clone());
+    }
+
+    fn render_form(&self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .horizontal_margin(2)
+            .split(area);
+
+        let fields = [
+            (
+                format!(" {} ", t("documents.type_field")),
+                format!(
+                    "◄ {} ►",
+                    document_type_text(DOCUMENT_TYPES[self.type_index])
+                ),
+            ),
+            (
+                format!(" {} ", t("documents.date_field")),
+                self.date_input.clone(),
+            ),
+            (
+                format!(" {} ", t("documents.expires_field")),
+                self.expires_input.clone(),
+            ),
+            (
+                format!(" {} ", t("documents.path_field")),
+                self.path_input.clone(),
+            ),
+            (
+                format!(" {} ", t("documents.notes")),
+                self.notes_input.clone(),
+            ),
+        ];
+
+        for (i, (label, value)) in fields.iter().enumerate() {
+            let input = Paragraph::new(value.as_str())
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(Span::styled(
+                            label.as_str(),
+                            Style::default().fg(theme().title),
+                        ))
+                        .border_style(if self.focus_index == i {
+                            Style::default().fg(theme().focus)
+                        } else {
+                            Style::default().fg(theme().unfocused)
+                        })
+                        .style(Style::default().bg(theme().input)),
+                );
+            frame.render_widget(input, chunks[i]);
+        }
+
+        let buttons = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[6]);
+
+        let save_button = Paragraph::new(if self.focus_index == SAVE_BUTTON {
+            format!("► {} ◄", t("common.save"))
+        } else {
+            format!("  {}  ", t("common.save"))
+        })
+        .style(if self.focus_index == SAVE_BUTTON {
+            Style::default()
+                .fg(theme().success)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        })
+        .alignment(Alignment::Center);
+        frame.render_widget(save_button, buttons[0]);
+
+        let cancel_button = Paragraph::new(if self.focus_index == CANCEL_BUTTON {
+            format!("► {} ◄", t("common.cancel"))
+        } else {
+            format!("  {}  ", t("common.cancel"))
+        })
+        .style(if self.focus_index == CANCEL_BUTTON {
+            Style::default()
+                .fg(theme().accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        })
+        .alignment(Alignment::Center);
+        frame.render_widget(cancel_button, buttons[1]);
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(10),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header_block = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header_block, layout[0]);
+
+        let title = Paragraph::new(tf(
+            "documents.title",
+            &[
+                ("first", &self.patient.first_name),
+                ("last", &self.patient.last_name),
+                ("id", &self.patient.id),
+            ],
+        ))
+        .style(
+            Style::default()
+                .fg(theme().title)
+                .add_modifier(Modifier::BOLD)
+                .bg(theme().background),
+        )
+        .alignment(Alignment::Center);
+        frame.render_widget(title, layout[0]);
+
+        if self.mode == DocumentsMode::Adding {
+            self.render_form(frame, layout[1]);
+        } else {
+            self.render_table(frame, layout[1]);
+        }
+
+        let status = if self.mode == DocumentsMode::ConfirmDelete {
+            Paragraph::new(t("documents.remove_confirm")).style(
+                Style::default()
+                    .fg(theme().focus)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Paragraph::new("")
+        };
+        frame.render_widget(status.alignment(Alignment::Center), layout[2]);
+
+        let help_text = if self.mode == DocumentsMode::Adding {
+            t("documents.form_help")
+        } else {
+            t("documents.help")
+        };
+        frame.render_widget(
+            Paragraph::new(help_text)
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+    }
+}
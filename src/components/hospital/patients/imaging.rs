@@ -0,0 +1,552 @@
+use crate::components::form::{one_of, past_date, Field, Form};
+use crate::components::hospital::patients::PatientAction;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::label::LabelPopup;
+use crate::components::widgets::text_input::TextInput;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{ImagingStudy, Patient, ReportStatus};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{copy_to_clipboard, datetime, labels, open_in_external_viewer};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::path::Path;
+
+/// DICOM modality codes offered for new studies.
+const MODALITIES: &[&str] = &[
+    "CR", "CT", "DX", "MG", "MR", "NM", "OT", "PT", "RF", "US", "XA",
+];
+
+const ACCESSION: usize = 0;
+const MODALITY: usize = 1;
+const STUDY_DATE: usize = 2;
+const DESCRIPTION: usize = 3;
+const ORDER_NUMBER: usize = 4;
+const REFERENCE: usize = 5;
+
+pub fn report_status_text(status: ReportStatus) -> &'static str {
+    match status {
+        ReportStatus::Pending => t("imaging.status.pending"),
+        ReportStatus::Preliminary => t("imaging.status.preliminary"),
+        ReportStatus::Final => t("imaging.status.final"),
+        ReportStatus::Amended => t("imaging.status.amended"),
+    }
+}
+
+fn next_report_status(status: ReportStatus) -> ReportStatus {
+    match status {
+        ReportStatus::Pending => ReportStatus::Preliminary,
+        ReportStatus::Preliminary => ReportStatus::Final,
+        ReportStatus::Final => ReportStatus::Amended,
+        ReportStatus::Amended => ReportStatus::Pending,
+    }
+}
+
+fn study_form() -> Form {
+    let mut form = Form::new(vec![
+        Field::new(t("imaging.accession_field")).required(),
+        Field::new(t("imaging.modality_field"))
+            .required()
+            .validate_with(one_of(MODALITIES)),
+        Field::new(t("imaging.date_field"))
+            .required()
+            .validate_with(Box::new(past_date)),
+        Field::new(t("imaging.description")),
+        Field::new(t("imaging.order_field")),
+        Field::new(t("imaging.reference_field")).required(),
+    ]);
+    form.fields[STUDY_DATE].set(datetime::today_text());
+    form
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImagingMode {
+    Browsing,
+    Searching,
+    Adding,
+    ConfirmDelete,
+}
+
+/// The imaging studies registered for one patient. Only metadata is kept
+/// here; the images stay in the PACS or on disk the reference points to.
+pub struct PatientImaging {
+    db: Db,
+    patient: Patient,
+    studies: Vec<ImagingStudy>,
+    filtered: Vec<ImagingStudy>,
+    search_input: TextInput,
+    state: TableState,
+    mode: ImagingMode,
+    form: Form,
+    focus_index: usize,
+    label: Option<LabelPopup>,
+}
+
+impl PatientImaging {
+    pub fn new(db: &Db, patient: Patient, search: &str) -> Result<Self> {
+        let mut search_input = TextInput::new();
+        search_input.set_value(search);
+        let mut imaging = Self {
+            db: db.clone(),
+            patient,
+            studies: Vec::new(),
+            filtered: Vec::new(),
+            search_input,
+            state: TableState::default(),
+            mode: ImagingMode::Browsing,
+            form: study_form(),
+            focus_index: 0,
+            label: None,
+        };
+        imaging.fetch_studies()?;
+        Ok(imaging)
+    }
+
+    fn fetch_studies(&mut self) -> Result<()> {
+        self.studies = db::get_patient_imaging_studies(&self.db, self.patient.id)?;
+        self.filter_studies();
+        Ok(())
+    }
+
+    fn filter_studies(&mut self) {
+        let term = self.search_input.value().trim().to_lowercase();
+        self.filtered = self
+            .studies
+            .iter()
+            .filter(|s| {
+                term.is_empty()
+                    || s.accession_number.to_lowercase().contains(&term)
+                    || s.modality.to_lowercase().contains(&term)
+                    || s.study_date.contains(&term)
+                    || s.description
+                        .as_deref()
+                        .is_some_and(|d| d.to_lowercase().contains(&term))
+                    || s.order_number
+                        .as_deref()
+                        .is_some_and(|o| o.to_lowercase().contains(&term))
+                    || report_status_text(s.report_status)
+                        .to_lowercase()
+                        .contains(&term)
+            })
+            .cloned()
+            .collect();
+        if self.filtered.is_empty() {
+            self.state.select(None);
+        } else {
+            let selection = self
+                .state
+                .selected()
+                .unwrap_or(0)
+                .min(self.filtered.len() - 1);
+            self.state.select(Some(selection));
+        }
+    }
+
+    fn selected_study(&self) -> Option<&ImagingStudy> {
+        self.state.selected().and_then(|i| self.filtered.get(i))
+    }
+
+    fn start_adding(&mut self) {
+        self.form = study_form();
+        self.focus_index = ACCESSION;
+        self.mode = ImagingMode::Adding;
+    }
+
+    fn save_study(&mut self) -> Result<()> {
+        if let Some(invalid) = self.form.validate() {
+            self.focus_index = invalid;
+            return Ok(());
+        }
+        let study = ImagingStudy {
+            id: 0,
+            patient_id: self.patient.id,
+            accession_number: self.form.value(ACCESSION),
+            modality: self.form.value(MODALITY),
+            study_date: self.form.value(STUDY_DATE),
+            description: self.form.optional_value(DESCRIPTION),
+            order_number: self.form.optional_value(ORDER_NUMBER),
+            report_status: ReportStatus::Pending,
+            reference: self.form.value(REFERENCE),
+        };
+        match db::create_imaging_study(&self.db, &study) {
+            Ok(id) => {
+                self.mode = ImagingMode::Browsing;
+                self.search_input.clear();
+                self.fetch_studies()?;
+                if let Some(pos) = self.filtered.iter().position(|s| s.id == id) {
+                    self.state.select(Some(pos));
+                }
+                toast::push(Toast::success(t("imaging.registered")));
+                // The sample or order needs its label straight away.
+                self.open_label();
+            }
+            Err(e) => toast::push(Toast::error(tf("imaging.save_failed", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    fn advance_report_status(&mut self) -> Result<()> {
+        let Some(study) = self.selected_study() else {
+            return Ok(());
+        };
+        let status = next_report_status(study.report_status);
+        match db::update_imaging_report_status(&self.db, study, status) {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "imaging.status_set",
+                    &[("status", &report_status_text(status))],
+                )));
+                self.fetch_studies()?;
+            }
+            Err(e) => toast::push(Toast::error(tf("imaging.report_failed", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    fn open_selected(&self) {
+        let Some(study) = self.selected_study() else {
+            return;
+        };
+        let path = Path::new(&study.reference);
+        if !path.exists() {
+            toast::push(Toast::error(t("imaging.not_local")));
+            return;
+        }
+        match open_in_external_viewer(path) {
+            Ok(()) => toast::push(Toast::success(tf(
+                "common.opened",
+                &[("path", &study.reference)],
+            ))),
+            Err(e) => toast::push(Toast::error(e.to_string())),
+        }
+    }
+
+    fn copy_reference(&self) {
+        let Some(study) = self.selected_study() else {
+            return;
+        };
+        match copy_to_clipboard(&study.reference) {
+            Ok(()) => toast::push(Toast::success(t("imaging.copied"))),
+            Err(e) => toast::push(Toast::error(tf("imaging.copy_failed", &[("error", &e)]))),
+        }
+    }
+
+    fn delete_selected(&mut self) -> Result<()> {
+        let Some(study_id) = self.selected_study().map(|s| s.id) else {
+            return Ok(());
+        };
+        match db::delete_imaging_study(&self.db, study_id) {
+            Ok(()) => {
+                self.fetch_studies()?;
+                toast::push(Toast::success(t("imaging.removed")));
+            }
+            Err(e) => toast::push(Toast::error(tf("imaging.remove_failed", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    fn handle_add_input(&mut self, key: KeyEvent) -> Result<()> {
+        let len = self.form.len();
+        match key.code {
+            KeyCode::Esc => self.mode = ImagingMode::Browsing,
+            KeyCode::Tab | KeyCode::Down => self.focus_index = (self.focus_index + 1) % len,
+            KeyCode::BackTab | KeyCode::Up => self.focus_index = (self.focus_index + len - 1) % len,
+            KeyCode::Enter if self.focus_index + 1 < len => self.focus_index += 1,
+            KeyCode::Enter => self.save_study()?,
+            _ => {
+                self.form.handle_key(self.focus_index, key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Shows the sample label of the selected study, coded with its
+    /// accession number.
+    fn open_label(&mut self) {
+        let Some(study) = self.selected_study() else {
+            return;
+        };
+        self.label = LabelPopup::open(&self.db, labels::sample_tube(&self.patient, study));
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<PatientAction>> {
+        if let Some(label) = &mut self.label {
+            if label.handle_key(key) {
+                self.label = None;
+            }
+            return Ok(None);
+        }
+        match self.mode {
+            ImagingMode::Adding => {
+                self.handle_add_input(key)?;
+                return Ok(None);
+            }
+            ImagingMode::Searching => {
+                match key.code {
+                    _ if self.search_input.handle_key(key) => self.filter_studies(),
+                    KeyCode::Enter | KeyCode::Down | KeyCode::Tab | KeyCode::Esc => {
+                        self.mode = ImagingMode::Browsing;
+                    }
+                    _ => {}
+                }
+                return Ok(None);
+            }
+            ImagingMode::ConfirmDelete => {
+                if let KeyCode::Char('y') | KeyCode::Char('Y') = key.code {
+                    self.delete_selected()?;
+                }
+                self.mode = ImagingMode::Browsing;
+                return Ok(None);
+            }
+            ImagingMode::Browsing => {}
+        }
+
+        match key.code {
+            KeyCode::Down if !self.filtered.is_empty() => {
+                let i = self
+                    .state
+                    .selected()
+                    .map_or(0, |i| (i + 1) % self.filtered.len());
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if !self.filtered.is_empty() => {
+                let len = self.filtered.len();
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('/') => self.mode = ImagingMode::Searching,
+            KeyCode::Char('a') | KeyCode::Char('A') => self.start_adding(),
+            KeyCode::Char('s') | KeyCode::Char('S') => self.advance_report_status()?,
+            KeyCode::Enter | KeyCode::Char('o') | KeyCode::Char('O') => self.open_selected(),
+            KeyCode::Char('y') | KeyCode::Char('Y') => self.copy_reference(),
+            KeyCode::Char('l') | KeyCode::Char('L') => self.open_label(),
+            KeyCode::Char('x') | KeyCode::Char('X') | KeyCode::Delete
+                if self.selected_study().is_some() =>
+            {
+                self.mode = ImagingMode::ConfirmDelete;
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => self.fetch_studies()?,
+            KeyCode::Esc if !self.search_input.is_empty() => {
+                self.search_input.clear();
+                self.filter_studies();
+            }
+            KeyCode::Esc | KeyCode::Char('b') | KeyCode::Char('B') => {
+                return Ok(Some(PatientAction::BackToList));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_table(&self, frame: &mut Frame, area: Rect) {
+        let title = if self.filtered.len() == self.studies.len() {
+            format!(
+                " {} ",
+                tf("imaging.count", &[("count", &self.studies.len())])
+            )
+        } else {
+            format!(
+                " {} ",
+                tf(
+                    "imaging.count_of",
+                    &[
+                        ("shown", &self.filtered.len()),
+                        ("total", &self.studies.len())
+                    ]
+                )
+            )
+        };
+        let block = Block::default()
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+
+        if self.filtered.is_empty() {
+            let text = if self.studies.is_empty() {
+                t("imaging.empty")
+            } else {
+                t("imaging.no_matches")
+            };
+            let empty = Paragraph::new(text)
+                .style(Style::default().fg(theme().text))
+                .alignment(Alignment::Center)
+                .block(block);
+            frame.render_widget(empty, area);
+            return;
+        }
+
+        let header = Row::new(
+            [
+                t("imaging.accession"),
+                t("imaging.modality"),
+                t("imaging.date"),
+                t("imaging.description"),
+                t("imaging.order"),
+                t("imaging.report"),
+                t("imaging.reference"),
+            ]
+            .iter()
+            .map(|h| Cell::from(*h).style(Style::default().fg(theme().title))),
+        )
+        .style(Style::default().bg(theme().header))
+        .height(1);
+
+        let widths = [
+            Constraint::Percentage(14),
+            Constraint::Percentage(8),
+            Constraint::Percentage(10),
+            Constraint::Percentage(22),
+            Constraint::Percentage(10),
+            Constraint::Percentage(11),
+            Constraint::Percentage(25),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+
+        let rows = self.filtered.iter().map(|study| {
+            let report_style = match study.report_status {
+                ReportStatus::Pending => Style::default().fg(theme().warning),
+                ReportStatus::Preliminary => Style::default().fg(theme().accent),
+                ReportStatus::Final | ReportStatus::Amended => Style::default().fg(theme().success),
+            };
+            Row::new(vec![
+                cell(&study.accession_number, &columns, 0),
+                cell(&study.modality, &columns, 1),
+                cell(&study.study_date, &columns, 2),
+                cell(
+                    study.description.as_deref().unwrap_or_default(),
+                    &columns,
+                    3,
+                ),
+                cell(study.order_number.as_deref().unwrap_or("-"), &columns, 4),
+                cell(report_status_text(study.report_status), &columns, 5).style(report_style),
+                cell(&study.reference, &columns, 6),
+            ])
+            .style(Style::default().fg(theme().text))
+        });
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.state.clone());
+    }
+
+    fn render_form(&self, frame: &mut Frame, area: Rect) {
+        let mut constraints = vec![Constraint::Length(3); self.form.len()];
+        constraints.push(Constraint::Min(0));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .horizontal_margin(2)
+            .split(area);
+        for index in 0..self.form.len() {
+            self.form
+                .render_field(frame, index, chunks[index], self.focus_index);
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(10),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header_block = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header_block, layout[0]);
+
+        let title = Paragraph::new(tf(
+            "imaging.title",
+            &[
+                ("first", &self.patient.first_name),
+                ("last", &self.patient.last_name),
+                ("id", &self.patient.id),
+            ],
+        ))
+        .style(
+            Style::default()
+                .fg(theme().title)
+                .add_modifier(Modifier::BOLD)
+                .bg(theme().background),
+        )
+        .alignment(Alignment::Center);
+        frame.render_widget(title, layout[0]);
+
+        if self.mode == ImagingMode::Adding {
+            let body = layout[1].union(layout[2]);
+            self.render_form(frame, body);
+        } else {
+            let searching = self.mode == ImagingMode::Searching;
+            let search = Paragraph::new(self.search_input.line(searching))
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", t("imaging.search")))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(if searching {
+                            theme().focus
+                        } else {
+                            theme().unfocused
+                        }))
+                        .style(Style::default().bg(theme().input)),
+                );
+            frame.render_widget(search, layout[1]);
+            self.render_table(frame, layout[2]);
+        }
+
+        let status = if self.mode == ImagingMode::ConfirmDelete {
+            Paragraph::new(t("imaging.remove_confirm")).style(
+                Style::default()
+                    .fg(theme().focus)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Paragraph::new("")
+        };
+        frame.render_widget(status.alignment(Alignment::Center), layout[3]);
+
+        let help_text = match self.mode {
+            ImagingMode::Adding => t("common.form_help"),
+            ImagingMode::Searching => t("imaging.search_help"),
+            _ => t("imaging.help"),
+        };
+        frame.render_widget(
+            Paragraph::new(help_text)
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+
+        if let Some(label) = &self.label {
+            label.render_popup(frame);
+        }
+    }
+}
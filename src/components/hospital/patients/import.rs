@@ -0,0 +1,692 @@
+//! Registering many patients at once from a CSV file. Every row is checked
+//! the way the add form checks it, and against the patients already
+//! registered and the rows above it, and the file is shown as a preview
+//! with each row's problems. Only the rows without any are imported, all in
+//! one transaction.
+//!
+//! The first line may name the columns (`first_name,last_name,dob,...`, in
+//! any order); without one the columns are taken in the order of the add
+//! form.
+
+use crate::app::SelectedApp;
+use crate::components::form;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::export_view::{self, ExportView, ShownRows};
+use crate::components::widgets::text_input::TextInput;
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{ContactPreference, Gender, Patient};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{contact, csv_records, parse_csv_line};
+use anyhow::{anyhow, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::fs;
+use std::path::Path;
+
+const FIRST_NAME: usize = 0;
+const LAST_NAME: usize = 1;
+const DOB: usize = 2;
+const GENDER: usize = 3;
+const ADDRESS: usize = 4;
+const POSTCODE: usize = 5;
+const CITY: usize = 6;
+const REGION: usize = 7;
+const PHONE: usize = 8;
+const EMAIL: usize = 9;
+const CONTACT: usize = 10;
+const MEDICAL_HISTORY: usize = 11;
+const ALLERGIES: usize = 12;
+const MEDICATIONS: usize = 13;
+const COLUMN_COUNT: usize = 14;
+
+/// Columns a header has to name, as they are called in the messages.
+const REQUIRED: [(usize, &str); 6] = [
+    (FIRST_NAME, "first_name"),
+    (LAST_NAME, "last_name"),
+    (DOB, "date_of_birth"),
+    (GENDER, "gender"),
+    (ADDRESS, "street"),
+    (PHONE, "phone"),
+];
+
+const HEADERS: [&str; 7] = [
+    "import.column.line",
+    "import.column.name",
+    "import.column.born",
+    "import.column.gender",
+    "import.column.phone",
+    "import.column.email",
+    "import.column.status",
+];
+
+/// The field a header names, allowing for the usual spellings. The byte
+/// order mark spreadsheets put before the first one is ignored.
+fn column(header: &str) -> Option<usize> {
+    let name = header
+        .trim_start_matches('\u{feff}')
+        .trim()
+        .to_lowercase()
+        .replace([' ', '-'], "_");
+    let index = match name.as_str() {
+        "first_name" | "first" | "given_name" => FIRST_NAME,
+        "last_name" | "last" | "surname" | "family_name" => LAST_NAME,
+        "date_of_birth" | "dob" | "birth_date" | "born" => DOB,
+        "gender" | "sex" => GENDER,
+        "street" | "address" => ADDRESS,
+        "postcode" | "postal_code" | "zip" => POSTCODE,
+        "city" | "town" => CITY,
+        "region" | "state" | "county" => REGION,
+        "phone" | "phone_number" | "mobile" => PHONE,
+        "email" => EMAIL,
+        "contact_by" | "contact_preference" => CONTACT,
+        "medical_history" | "history" => MEDICAL_HISTORY,
+        "allergies" => ALLERGIES,
+        "medications" | "current_medications" => MEDICATIONS,
+        _ => return None,
+    };
+    Some(index)
+}
+
+/// One line of the file, as the patient it would register and what stops
+/// it from being registered.
+struct ImportRow {
+    line: usize,
+    patient: Patient,
+    errors: Vec<String>,
+}
+
+impl ImportRow {
+    fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    fn status(&self) -> String {
+        if self.is_valid() {
+            t("import.ready").to_string()
+        } else {
+            self.errors.join("; ")
+        }
+    }
+}
+
+fn optional(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn check_row(line: usize, values: &[String; COLUMN_COUNT]) -> ImportRow {
+    let mut errors = Vec::new();
+    let mut check = |label: &str, value: &str, validate: fn(&str) -> Result<(), String>| {
+        if let Err(e) = validate(value) {
+            errors.push(format!("{}: {}", label, e));
+        }
+    };
+    if !values[DOB].is_empty() {
+        check(t("import.date_of_birth"), &values[DOB], form::past_date);
+    }
+    if !values[GENDER].is_empty() {
+        check(t("common.gender"), &values[GENDER], form::gender);
+    }
+    if !values[PHONE].is_empty() {
+        check(t("common.phone"), &values[PHONE], form::phone);
+    }
+    if !values[EMAIL].is_empty() {
+        check(t("patients.email"), &values[EMAIL], form::email);
+    }
+    let contact_preference = match values[CONTACT].as_str() {
+        "" => ContactPreference::default(),
+        value => ContactPreference::parse(value).unwrap_or_else(|| {
+            errors.push(t("import.contact_by").to_string());
+            ContactPreference::default()
+        }),
+    };
+    for (index, label) in [
+        (FIRST_NAME, t("import.first_name")),
+        (LAST_NAME, t("import.last_name")),
+        (DOB, t("import.date_of_birth")),
+        (GENDER, t("common.gender")),
+        (ADDRESS, t("patients.street")),
+        (PHONE, t("common.phone")),
+    ] {
+        if values[index].is_empty() {
+            errors.push(tf("import.missing", &[("field", &label)]));
+        }
+    }
+
+    let patient = Patient {
+        id: 0,
+        first_name: values[FIRST_NAME].clone(),
+        last_name: values[LAST_NAME].clone(),
+        date_of_birth: values[DOB].clone(),
+        gender: Gender::from(values[GENDER].clone()),
+        address: values[ADDRESS].clone(),
+        city: values[CITY].clone(),
+        region: values[REGION].clone(),
+        postcode: values[POSTCODE].clone(),
+        phone_number: contact::normalize_phone(&values[PHONE]),
+        email: optional(&values[EMAIL]).map(|email| contact::normalize_email(&email)),
+        medical_history: optional(&values[MEDICAL_HISTORY]),
+        allergies: optional(&values[ALLERGIES]),
+        current_medications: optional(&values[MEDICATIONS]),
+        contact_preference,
+        photo_path: None,
+        mrn: String::new(),
+    };
+    ImportRow {
+        line,
+        patient,
+        errors,
+    }
+}
+
+fn same_person(a: &Patient, b: &Patient) -> bool {
+    a.first_name.eq_ignore_ascii_case(&b.first_name)
+        && a.last_name.eq_ignore_ascii_case(&b.last_name)
+        && a.date_of_birth == b.date_of_birth
+}
+
+/// Reads and checks every row of the CSV file at `path`.
+fn read_rows(path: &Path, existing: &[Patient]) -> Result<Vec<ImportRow>> {
+    let text = fs::read_to_string(path)
+        .with_context(|| tf("import.read_failed", &[("path", &path.display())]))?;
+    check_rows(&text, existing)
+}
+
+/// Checks every record of the CSV `text`.
+fn check_rows(text: &str, existing: &[Patient]) -> Result<Vec<ImportRow>> {
+    let mut lines = csv_records(text.trim_start_matches('\u{feff}'))
+        .into_iter()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .peekable();
+
+    let mut columns: Vec<Option<usize>> = (0..COLUMN_COUNT).map(Some).collect();
+    if let Some((_, first)) = lines.peek() {
+        let header: Vec<Option<usize>> = parse_csv_line(first).iter().map(|h| column(h)).collect();
+        if header.iter().any(Option::is_some) {
+            if let Some((_, name)) = REQUIRED
+                .iter()
+                .find(|(index, _)| !header.contains(&Some(*index)))
+            {
+                return Err(anyhow!(tf("import.no_column", &[("name", &name)])));
+            }
+            columns = header;
+            lines.next();
+        }
+    }
+
+    let mut rows: Vec<ImportRow> = Vec::new();
+    for (line_number, line) in lines {
+        let mut values: [String; COLUMN_COUNT] = Default::default();
+        for (field, value) in columns.iter().zip(parse_csv_line(&line)) {
+            if let Some(field) = field {
+                values[*field] = value.trim().to_string();
+            }
+        }
+        let mut row = check_row(line_number, &values);
+        if let Some(registered) = existing.iter().find(|p| same_person(p, &row.patient)) {
+            row.errors
+                .push(tf("import.already_registered", &[("mrn", &registered.mrn)]));
+        } else if let Some(earlier) = rows.iter().find(|r| same_person(&r.patient, &row.patient)) {
+            row.errors
+                .push(tf("import.same_as", &[("line", &earlier.line)]));
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+pub struct ImportPatients {
+    db: Db,
+    path: TextInput,
+    editing_path: bool,
+    rows: Vec<ImportRow>,
+    state: TableState,
+    confirm_import: bool,
+    export_view: Option<ExportView>,
+}
+
+impl ImportPatients {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            path: TextInput::new(),
+            editing_path: true,
+            rows: Vec::new(),
+            state: TableState::default(),
+            confirm_import: false,
+            export_view: None,
+        }
+    }
+
+    fn valid_count(&self) -> usize {
+        self.rows.iter().filter(|r| r.is_valid()).count()
+    }
+
+    fn selected(&self) -> Option<&ImportRow> {
+        self.rows.get(self.state.selected()?)
+    }
+
+    fn read_file(&mut self) {
+        // Paths dropped into a terminal often arrive quoted.
+        let path = self
+            .path
+            .value()
+            .trim()
+            .trim_matches(['\'', '"'])
+            .to_string();
+        if path.is_empty() {
+            toast::push(Toast::error(t("import.enter_path")));
+            return;
+        }
+        let read = db::get_all_patients(&self.db)
+            .and_then(|existing| read_rows(Path::new(&path), &existing));
+        match read {
+            Ok(rows) if rows.is_empty() => {
+                toast::push(Toast::error(tf("import.no_rows", &[("path", &path)])));
+            }
+            Ok(rows) => {
+                self.rows = rows;
+                self.state.select(Some(0));
+                self.editing_path = false;
+            }
+            Err(e) => toast::push(Toast::error(format!("{}", e))),
+        }
+    }
+
+    fn import(&mut self) {
+        self.confirm_import = false;
+        let patients: Vec<Patient> = self
+            .rows
+            .iter()
+            .filter(|r| r.is_valid())
+            .map(|r| r.patient.clone())
+            .collect();
+        let left_out = self.rows.len() - patients.len();
+        match db::create_patients_batch(&self.db, &patients) {
+            Ok(ids) => {
+                let mut message = tf("import.imported", &[("count", &ids.len())]);
+                if left_out > 0 {
+                    message.push_str(&tf("import.left_out", &[("count", &left_out)]));
+                }
+                toast::push(Toast::success(message));
+                self.rows.clear();
+                self.state.select(None);
+                self.editing_path = true;
+            }
+            Err(e) => toast::push(Toast::error(tf("import.failed", &[("error", &e)]))),
+        }
+    }
+
+    fn row_values(row: &ImportRow) -> Vec<String> {
+        let patient = &row.patient;
+        vec![
+            row.line.to_string(),
+            format!("{} {}", patient.first_name, patient.last_name),
+            patient.date_of_birth.clone(),
+            patient.gender.label().to_string(),
+            patient.phone_number.clone(),
+            patient.email.clone().unwrap_or_default(),
+            row.status(),
+        ]
+    }
+
+    fn shown_rows(&self) -> ShownRows {
+        let mut shown = ShownRows::new(&HEADERS);
+        shown.rows = self.rows.iter().map(Self::row_values).collect();
+        shown
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(export) = &mut self.export_view {
+            if export.handle_key(key) {
+                self.export_view = None;
+            }
+            return Ok(None);
+        }
+        if self.editing_path {
+            match key.code {
+                KeyCode::Enter => self.read_file(),
+                KeyCode::Esc if self.rows.is_empty() => return Ok(Some(SelectedApp::None)),
+                KeyCode::Esc => self.editing_path = false,
+                _ => {
+                    self.path.handle_key(key);
+                }
+            }
+            return Ok(None);
+        }
+        if self.confirm_import {
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => self.import(),
+                _ => self.confirm_import = false,
+            }
+            return Ok(None);
+        }
+        if export_view::is_open_key(key) {
+            self.export_view = Some(ExportView::new("patient_import", self.shown_rows()));
+            return Ok(None);
+        }
+        let len = self.rows.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') if self.valid_count() == 0 => {
+                toast::push(Toast::error(t("import.none_ready")));
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') => self.confirm_import = true,
+            KeyCode::Char('f') | KeyCode::Char('F') => self.editing_path = true,
+            KeyCode::Char('r') | KeyCode::Char('R') => self.read_file(),
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_rows(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("import.preview")))
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.rows.is_empty() {
+            let text = vec![
+                Line::from(""),
+                Line::from(t("import.intro")),
+                Line::from(""),
+                Line::from(t("import.intro_columns")),
+                Line::from(Span::styled(
+                    "first_name, last_name, date_of_birth, gender, street, postcode, city, region,",
+                    Style::default().fg(theme().accent),
+                )),
+                Line::from(Span::styled(
+                    "phone, email, contact_by, medical_history, allergies, medications",
+                    Style::default().fg(theme().accent),
+                )),
+            ];
+            frame.render_widget(
+                Paragraph::new(text)
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        }
+        let widths = [
+            Constraint::Length(6),
+            Constraint::Length(22),
+            Constraint::Length(11),
+            Constraint::Length(10),
+            Constraint::Length(15),
+            Constraint::Length(22),
+            Constraint::Min(24),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+        let rows: Vec<Row> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let color = if row.is_valid() {
+                    theme().success
+                } else {
+                    theme().error
+                };
+                let cells: Vec<Cell> = Self::row_values(row)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(column, text)| match column {
+                        c if c == HEADERS.len() - 1 => {
+                            cell(text, &columns, c).style(Style::default().fg(color))
+                        }
+                        c => cell(text, &columns, c),
+                    })
+                    .collect();
+                Row::new(cells).style(Style::default().fg(theme().text))
+            })
+            .collect();
+        let header = Row::new(HEADERS.map(t).to_vec())
+            .style(Style::default().bg(theme().header).fg(theme().title));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.state.clone());
+    }
+
+    /// Everything wrong with the selected row, which the status column may
+    /// have cut short.
+    fn render_errors(&self, frame: &mut Frame, area: Rect) {
+        let Some(row) = self.selected().filter(|r| !r.is_valid()) else {
+            return;
+        };
+        frame.render_widget(
+            Paragraph::new(tf(
+                "import.line_errors",
+                &[("line", &row.line), ("errors", &(row.errors.join(" · ")))],
+            ))
+            .style(Style::default().fg(theme().error))
+            .wrap(Wrap { trim: true }),
+            area,
+        );
+    }
+}
+
+impl Component for ImportPatients {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(2),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("import.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(self.path.line(self.editing_path)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("import.file")))
+                    .border_style(Style::default().fg(if self.editing_path {
+                        theme().focus
+                    } else {
+                        theme().unfocused
+                    }))
+                    .style(Style::default().bg(theme().input)),
+            ),
+            layout[1],
+        );
+        if !self.rows.is_empty() {
+            let valid = self.valid_count();
+            frame.render_widget(
+                Paragraph::new(tf(
+                    "import.summary",
+                    &[
+                        ("rows", &self.rows.len()),
+                        ("ready", &valid),
+                        ("errors", &(self.rows.len() - valid)),
+                    ],
+                ))
+                .style(Style::default().fg(theme().text))
+                .alignment(Alignment::Center),
+                layout[2],
+            );
+        }
+        self.render_rows(frame, layout[3]);
+        self.render_errors(frame, layout[4]);
+
+        let (help, color) = if self.confirm_import {
+            (
+                tf("import.confirm", &[("count", &self.valid_count())]),
+                theme().warning,
+            )
+        } else if self.editing_path {
+            (t("import.path_help").to_string(), theme().help)
+        } else {
+            (t("import.help").to_string(), theme().help)
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(color))
+                .alignment(Alignment::Center),
+            layout[5],
+        );
+
+        if let Some(export) = &self.export_view {
+            export.render_popup(frame);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "first_name,last_name,date_of_birth,gender,street,phone";
+
+    fn registered(id: i64, first_name: &str, last_name: &str, born: &str) -> Patient {
+        Patient {
+            id,
+            first_name: first_name.to_string(),
+            last_name: last_name.to_string(),
+            date_of_birth: born.to_string(),
+            gender: Gender::Female,
+            address: String::new(),
+            city: String::new(),
+            region: String::new(),
+            postcode: String::new(),
+            phone_number: String::new(),
+            email: None,
+            medical_history: None,
+            allergies: None,
+            current_medications: None,
+            contact_preference: ContactPreference::default(),
+            photo_path: None,
+            mrn: String::new(),
+        }
+    }
+
+    #[test]
+    fn byte_order_mark_before_the_header_is_ignored() {
+        let text = format!(
+            "\u{feff}{}\nAna,Lopez,1980-04-02,F,1 Main St,07700900123\n",
+            HEADER
+        );
+        let rows = check_rows(&text, &[]).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0].is_valid(), "{:?}", rows[0].errors);
+        assert_eq!(rows[0].patient.first_name, "Ana");
+    }
+
+    #[test]
+    fn quoted_commas_stay_in_their_field() {
+        let text = format!(
+            "{}\n\"Eve, Jr\",Stone,2001-12-12,O,\"5 Quay, Flat 2\",07700900127\n",
+            HEADER
+        );
+        let rows = check_rows(&text, &[]).unwrap();
+        assert!(rows[0].is_valid(), "{:?}", rows[0].errors);
+        assert_eq!(rows[0].patient.first_name, "Eve, Jr");
+        assert_eq!(rows[0].patient.address, "5 Quay, Flat 2");
+    }
+
+    #[test]
+    fn quoted_line_breaks_stay_in_their_record() {
+        let text = format!(
+            "{},medical_history\n\
+             Ana,Lopez,1980-04-02,F,1 Main St,07700900123,\"Asthma\nHay fever\"\n\
+             Ben,Ng,1975-03-03,M,2 High St,07700900124,\n",
+            HEADER
+        );
+        let rows = check_rows(&text, &[]).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0].patient.medical_history.as_deref(),
+            Some("Asthma\nHay fever")
+        );
+        assert_eq!(rows[1].line, 4);
+        assert_eq!(rows[1].patient.first_name, "Ben");
+        assert!(rows.iter().all(ImportRow::is_valid));
+    }
+
+    #[test]
+    fn header_without_a_required_column_is_refused() {
+        let text = "first_name,last_name,gender,street,phone\nAna,Lopez,F,1 Main St,07700900123\n";
+        let error = check_rows(text, &[]).err().unwrap();
+        assert_eq!(error.to_string(), "The header has no date_of_birth column");
+    }
+
+    #[test]
+    fn missing_and_invalid_values_are_reported_per_row() {
+        let text = format!("{}\nAna,,1980/04/02,X,1 Main St,\n", HEADER);
+        let errors = &check_rows(&text, &[]).unwrap()[0].errors;
+        assert!(errors.contains(&"Last name is missing".to_string()));
+        assert!(errors.contains(&"Phone is missing".to_string()));
+        assert!(errors.iter().any(|e| e.starts_with("Date of birth:")));
+        assert!(errors.iter().any(|e| e.starts_with("Gender:")));
+    }
+
+    #[test]
+    fn duplicates_in_the_file_and_the_database_are_flagged() {
+        let text = format!(
+            "{}\n\
+             Ana,Lopez,1980-04-02,F,1 Main St,07700900123\n\
+             ANA,lopez,1980-04-02,F,9 Other St,07700900125\n\
+             Ben,Ng,1975-03-03,M,2 High St,07700900124\n",
+            HEADER
+        );
+        let existing = [registered(7, "Ben", "Ng", "1975-03-03")];
+        let rows = check_rows(&text, &existing).unwrap();
+        assert!(rows[0].is_valid());
+        assert_eq!(rows[1].errors, vec!["Same patient as line 2".to_string()]);
+        assert_eq!(rows[2].errors.len(), 1);
+        assert!(rows[2].errors[0].starts_with("Already registered as"));
+    }
+}
@@ -1,45 +1,244 @@
+use crate::components::hospital::patients::documents::PatientDocuments;
+use crate::components::hospital::patients::imaging::PatientImaging;
 use crate::components::hospital::patients::PatientAction;
+use crate::components::master_detail;
+use crate::components::responsive::is_narrow;
+use crate::components::table::{cell, column_widths, Columns};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::column_picker::{self, ColumnPicker};
+use crate::components::widgets::compare::{CompareAction, ComparePane, Side};
+use crate::components::widgets::export_view::{self, ExportView, ShownRows};
+use crate::components::widgets::label::LabelPopup;
+use crate::components::widgets::photo::render_photo;
+use crate::components::widgets::saved_searches::{self, SavedSearchOutcome, SavedSearchesPopup};
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
-use crate::db;
+use crate::db::worker::{self, Task};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::interop::fhir;
 use crate::models::Patient;
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::ui_state::{self, Recent, ViewState};
+use crate::utils::config;
+use crate::utils::photo::{self, Image};
+use crate::utils::{contact, copy_to_clipboard, labels, tsv_line, write_export};
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 
 const SEARCH_FIELD: usize = 0;
 const PATIENT_LIST: usize = 1;
 const BACK_BUTTON: usize = 2;
 
+/// Rows the photo takes above the details, borders included.
+const PHOTO_HEIGHT: u16 = 12;
+const VIEW_KEY: &str = "patients.list";
+const HEADERS: [&str; 8] = [
+    "common.id",
+    "common.first_name",
+    "common.last_name",
+    "patients.date_of_birth",
+    "common.gender",
+    "common.phone",
+    "patients.email",
+    "common.address",
+];
+const WIDTHS: [Constraint; 8] = [
+    Constraint::Percentage(5),
+    Constraint::Percentage(15),
+    Constraint::Percentage(15),
+    Constraint::Percentage(10),
+    Constraint::Percentage(10),
+    Constraint::Percentage(15),
+    Constraint::Percentage(20),
+    Constraint::Percentage(30),
+];
+/// Email is shown once asked for.
+const HIDDEN_COLUMNS: [usize; 1] = [6];
+/// The columns kept on narrow terminals.
+const MINIMAL_COLUMNS: [usize; 5] = [0, 1, 2, 3, 5];
+
 pub struct ListPatients {
+    db: Db,
     patients: Vec<Patient>,
     filtered_patients: Vec<Patient>,
-    search_input: String,
+    search_input: TextInput,
     is_searching: bool,
     state: TableState,
-    error_message: Option<String>,
     show_details: bool,
     focus_index: usize,
+    documents: Option<PatientDocuments>,
+    imaging: Option<PatientImaging>,
+    label: Option<LabelPopup>,
+    /// Accession and order numbers of imaging studies by patient, so a study
+    /// number typed in the search finds its patient.
+    imaging_numbers: Vec<(i64, String)>,
+    /// What admitted patients are isolated for, by patient, shown in red so
+    /// staff take precautions before seeing them.
+    isolations: HashMap<i64, String>,
+    /// Fields set by hook scripts on the highlighted patient.
+    custom_fields: Option<(i64, Vec<(String, String)>)>,
+    /// The patient to keep, marked with `m` before picking its duplicate.
+    merge_mark: Option<i64>,
+    merge: Option<PatientMerge>,
+    saved_searches: Option<SavedSearchesPopup>,
+    export_view: Option<ExportView>,
+    columns: Columns,
+    column_picker: Option<ColumnPicker>,
+    /// Patients the signed-in user pinned, listed first in pinning order.
+    pinned: Vec<i64>,
+    /// Decoded previews by photo path, or why one could not be shown.
+    photos: HashMap<String, Result<Image, String>>,
+    /// The camera command running for a patient, yielding the saved path.
+    capture: Option<Task<String>>,
+}
+
+/// Two records of the same person being merged: `keep` survives with the
+/// values picked in `pane`, `duplicate` is deleted.
+struct PatientMerge {
+    pane: ComparePane,
+    keep: Patient,
+    duplicate: Patient,
+}
+
+/// The fields compared when merging, in the order `merged_patient` reads the
+/// picks back.
+fn merge_fields(keep: &Patient, duplicate: &Patient) -> Vec<(String, String, String)> {
+    let gender = |p: &Patient| p.gender.label().to_string();
+    let optional = |text: &Option<String>| text.clone().unwrap_or_else(|| "-".to_string());
+    let row = |label: &str, left: String, right: String| (label.to_string(), left, right);
+    vec![
+        row(
+            t("common.first_name"),
+            keep.first_name.clone(),
+            duplicate.first_name.clone(),
+        ),
+        row(
+            t("common.last_name"),
+            keep.last_name.clone(),
+            duplicate.last_name.clone(),
+        ),
+        row(
+            t("patients.date_of_birth"),
+            keep.date_of_birth.clone(),
+            duplicate.date_of_birth.clone(),
+        ),
+        row(t("common.gender"), gender(keep), gender(duplicate)),
+        row(
+            t("common.address"),
+            keep.full_address(),
+            duplicate.full_address(),
+        ),
+        row(
+            t("common.phone"),
+            keep.phone_number.clone(),
+            duplicate.phone_number.clone(),
+        ),
+        row(
+            t("patients.email"),
+            optional(&keep.email),
+            optional(&duplicate.email),
+        ),
+        row(
+            t("patients.medical_history"),
+            optional(&keep.medical_history),
+            optional(&duplicate.medical_history),
+        ),
+        row(
+            t("patients.allergies"),
+            optional(&keep.allergies),
+            optional(&duplicate.allergies),
+        ),
+        row(
+            t("patients.current_medications"),
+            optional(&keep.current_medications),
+            optional(&duplicate.current_medications),
+        ),
+        row(
+            t("patients.contact_by"),
+            keep.contact_preference.label().to_string(),
+            duplicate.contact_preference.label().to_string(),
+        ),
+    ]
+}
+
+/// `keep` with each field taken from the side picked for it.
+fn merged_patient(keep: &Patient, duplicate: &Patient, picks: &[Side]) -> Patient {
+    let from = |i: usize| match picks.get(i) {
+        Some(Side::Right) => duplicate,
+        _ => keep,
+    };
+    Patient {
+        id: keep.id,
+        first_name: from(0).first_name.clone(),
+        last_name: from(1).last_name.clone(),
+        date_of_birth: from(2).date_of_birth.clone(),
+        gender: from(3).gender.clone(),
+        address: from(4).address.clone(),
+        city: from(4).city.clone(),
+        region: from(4).region.clone(),
+        postcode: from(4).postcode.clone(),
+        phone_number: from(5).phone_number.clone(),
+        email: from(6).email.clone(),
+        medical_history: from(7).medical_history.clone(),
+        allergies: from(8).allergies.clone(),
+        current_medications: from(9).current_medications.clone(),
+        contact_preference: from(10).contact_preference,
+        photo_path: keep.photo_path.clone(),
+        mrn: keep.mrn.clone(),
+    }
 }
 
 impl ListPatients {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
+        let view = ui_state::view(VIEW_KEY);
+        let mut search_input = TextInput::new();
+        search_input.set_value(view.search.as_str());
         Self {
+            db: db.clone(),
             patients: Vec::new(),
             filtered_patients: Vec::new(),
-            search_input: String::new(),
+            search_input,
             is_searching: false,
-            state: TableState::default(),
-            error_message: None,
+            state: view.table(),
             show_details: false,
             focus_index: PATIENT_LIST,
+            documents: None,
+            imaging: None,
+            label: None,
+            imaging_numbers: Vec::new(),
+            isolations: HashMap::new(),
+            custom_fields: None,
+            merge_mark: None,
+            merge: None,
+            saved_searches: None,
+            export_view: None,
+            columns: Columns::new(VIEW_KEY, &HEADERS, &HIDDEN_COLUMNS, &MINIMAL_COLUMNS),
+            column_picker: None,
+            pinned: Vec::new(),
+            photos: HashMap::new(),
+            capture: None,
         }
     }
 
     pub fn fetch_patients(&mut self) -> Result<()> {
-        match db::get_all_patients() {
+        match db::get_all_patients(&self.db) {
             Ok(patients) => {
                 self.patients = patients;
+                self.imaging_numbers = db::get_imaging_numbers(&self.db).unwrap_or_default();
+                self.pinned = ui_state::current_user()
+                    .and_then(|user| db::get_pinned_patients(&self.db, &user).ok())
+                    .unwrap_or_default();
+                self.isolations = db::get_active_isolations(&self.db)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|a| Some((a.patient_id, a.isolation_text()?)))
+                    .collect();
                 self.filter_patients();
 
                 if self.filtered_patients.is_empty() {
@@ -52,21 +251,73 @@ impl ListPatients {
                         .min(self.filtered_patients.len() - 1);
                     self.state.select(Some(selection));
                 }
-                self.error_message = None;
+                self.load_selected_details();
                 Ok(())
             }
             Err(e) => {
-                self.error_message = Some(format!("Failed to fetch patients: {}", e));
+                toast::push(Toast::error(tf("patients.fetch_failed", &[("error", &e)])));
                 Ok(())
             }
         }
     }
 
+    pub fn save_view(&self) {
+        ui_state::set_view(VIEW_KEY, self.current_view().with_table(&self.state));
+    }
+
+    fn current_view(&self) -> ViewState {
+        ViewState {
+            search: self.search_input.value().to_string(),
+            ..ViewState::default()
+        }
+    }
+
+    /// The cells of `patient`'s row, pinned ones marked with `*`.
+    fn row_values(&self, patient: &Patient) -> Vec<String> {
+        let id = if self.pinned.contains(&patient.id) {
+            format!("*{}", patient.id)
+        } else {
+            patient.id.to_string()
+        };
+        vec![
+            id,
+            patient.first_name.clone(),
+            patient.last_name.clone(),
+            patient.date_of_birth.clone(),
+            patient.gender.label().to_string(),
+            patient.phone_number.clone(),
+            patient.email.clone().unwrap_or_default(),
+            patient.full_address(),
+        ]
+    }
+
+    fn shown_rows(&self) -> ShownRows {
+        let visible = self.columns.visible(false);
+        let mut shown = ShownRows::new(&Columns::pick(&HEADERS, &visible));
+        shown.rows = self
+            .filtered_patients
+            .iter()
+            .map(|p| Columns::pick(&self.row_values(p), &visible))
+            .collect();
+        shown
+    }
+
+    /// Shows the patients matching a saved search, from the top.
+    fn apply_view(&mut self, view: ViewState) {
+        self.search_input.set_value(view.search.as_str());
+        self.is_searching = false;
+        self.focus_index = PATIENT_LIST;
+        self.show_details = false;
+        self.state.select(Some(0));
+        self.filter_patients();
+        self.load_selected_details();
+    }
+
     fn filter_patients(&mut self) {
         if self.search_input.is_empty() {
             self.filtered_patients = self.patients.clone();
         } else {
-            let search_term = self.search_input.to_lowercase();
+            let search_term = self.search_input.value().to_lowercase();
             self.filtered_patients = self
                 .patients
                 .iter()
@@ -74,12 +325,22 @@ impl ListPatients {
                     p.first_name.to_lowercase().contains(&search_term)
                         || p.last_name.to_lowercase().contains(&search_term)
                         || p.id.to_string().contains(&search_term)
-                        || p.phone_number.to_lowercase().contains(&search_term)
-                        || p.address.to_lowercase().contains(&search_term)
+                        || p.mrn.to_lowercase() == search_term
+                        || contact::phone_matches(&p.phone_number, &search_term)
+                        || p.full_address().to_lowercase().contains(&search_term)
+                        || self.imaging_numbers.iter().any(|(patient_id, number)| {
+                            *patient_id == p.id && number.to_lowercase().contains(&search_term)
+                        })
                 })
                 .cloned()
                 .collect();
         }
+        self.filtered_patients.sort_by_key(|p| {
+            self.pinned
+                .iter()
+                .position(|id| *id == p.id)
+                .unwrap_or(usize::MAX)
+        });
 
         if let Some(selected) = self.state.selected() {
             if selected >= self.filtered_patients.len() && !self.filtered_patients.is_empty() {
@@ -105,6 +366,7 @@ impl ListPatients {
             None => 0,
         };
         self.state.select(Some(i));
+        self.load_selected_details();
     }
 
     fn select_previous(&mut self) {
@@ -122,11 +384,109 @@ impl ListPatients {
             None => 0,
         };
         self.state.select(Some(i));
+        self.load_selected_details();
     }
 
     fn toggle_details(&mut self) {
         if !self.filtered_patients.is_empty() && self.state.selected().is_some() {
             self.show_details = !self.show_details;
+            if self.show_details {
+                self.note_recent();
+            }
+        }
+    }
+
+    /// Pins or unpins the highlighted patient for the signed-in user and
+    /// keeps them highlighted as they move.
+    fn toggle_pin(&mut self) {
+        let (Some(patient_id), Some(user)) = (
+            self.selected_patient().map(|p| p.id),
+            ui_state::current_user(),
+        ) else {
+            return;
+        };
+        let pin = !self.pinned.contains(&patient_id);
+        if let Err(e) = db::set_patient_pinned(&self.db, &user, patient_id, pin) {
+            toast::push(Toast::error(tf("patients.pin_failed", &[("error", &e)])));
+            return;
+        }
+        if pin {
+            self.pinned.push(patient_id);
+        } else {
+            self.pinned.retain(|id| *id != patient_id);
+        }
+        self.filter_patients();
+        let row = self
+            .filtered_patients
+            .iter()
+            .position(|p| p.id == patient_id);
+        self.state.select(row);
+    }
+
+    fn note_recent(&self) {
+        if let Some(patient) = self.selected_patient() {
+            ui_state::add_recent(Recent::patient(patient));
+        }
+    }
+
+    /// Loads what the details show beyond the patient row: fields set by
+    /// hook scripts and the photo preview.
+    fn load_selected_details(&mut self) {
+        self.custom_fields = self.selected_patient().map(|patient| {
+            let fields = db::get_custom_fields(&self.db, "patient", patient.id).unwrap_or_default();
+            (patient.id, fields)
+        });
+        let Some(path) = self
+            .selected_patient()
+            .and_then(|patient| patient.photo_path.clone())
+        else {
+            return;
+        };
+        self.photos.entry(path).or_insert_with_key(|path| {
+            photo::load_preview(Path::new(path)).map_err(|e| format!("{:#}", e))
+        });
+    }
+
+    /// Runs the camera command for the highlighted patient in the
+    /// background; the previous photo is replaced once the new one is saved.
+    fn take_photo(&mut self) {
+        if self.capture.is_some() {
+            return;
+        }
+        let Some(patient) = self.selected_patient() else {
+            return;
+        };
+        if config::config().photo.command.is_empty() {
+            toast::push(Toast::error(t("patients.no_camera")));
+            return;
+        }
+        let patient_id = patient.id;
+        let previous = patient.photo_path.clone();
+        let db = self.db.clone();
+        self.capture = Some(worker::spawn(move || {
+            let path = photo::capture(patient_id)?.to_string_lossy().into_owned();
+            db::set_patient_photo(&db, patient_id, &path)?;
+            if let Some(previous) = previous.filter(|previous| *previous != path) {
+                let _ = fs::remove_file(previous);
+            }
+            Ok(path)
+        }));
+    }
+
+    pub fn tick(&mut self) {
+        let Some(capture) = self.capture.take() else {
+            return;
+        };
+        match capture.poll() {
+            None => self.capture = Some(capture),
+            Some(Ok(_)) => {
+                toast::push(Toast::success(t("patients.photo_saved")));
+                let _ = self.fetch_patients();
+            }
+            Some(Err(e)) => toast::push(Toast::error(tf(
+                "patients.photo_failed",
+                &[("error", &format!("{:#}", e))],
+            ))),
         }
     }
 
@@ -148,15 +508,238 @@ impl ListPatients {
         }
     }
 
+    fn open_documents(&mut self) {
+        if let Some(patient) = self.selected_patient().cloned() {
+            match PatientDocuments::new(&self.db, patient) {
+                Ok(documents) => self.documents = Some(documents),
+                Err(e) => toast::push(Toast::error(tf(
+                    "patients.documents_failed",
+                    &[("error", &e)],
+                ))),
+            }
+        }
+    }
+
+    /// Opens the selected patient's imaging studies, narrowed to the search
+    /// when it was a study's accession or order number.
+    fn open_imaging(&mut self) {
+        let Some(patient) = self.selected_patient().cloned() else {
+            return;
+        };
+        let term = self.search_input.value().trim().to_lowercase();
+        let matches_study = !term.is_empty()
+            && self.imaging_numbers.iter().any(|(patient_id, number)| {
+                *patient_id == patient.id && number.to_lowercase().contains(&term)
+            });
+        let search = if matches_study {
+            self.search_input.value().trim()
+        } else {
+            ""
+        };
+        match PatientImaging::new(&self.db, patient, search) {
+            Ok(imaging) => self.imaging = Some(imaging),
+            Err(e) => toast::push(Toast::error(tf(
+                "patients.imaging_failed",
+                &[("error", &e)],
+            ))),
+        }
+    }
+
+    fn open_label(&mut self) {
+        let Some(patient) = self.selected_patient() else {
+            return;
+        };
+        let admission = db::get_current_admissions(&self.db)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|a| a.patient_id == patient.id);
+        self.label = LabelPopup::open(&self.db, labels::wristband(patient, admission.as_ref()));
+    }
+
+    /// The first `m` marks the patient to keep; the second, on another
+    /// patient, compares the two so the surviving values can be picked.
+    fn mark_for_merge(&mut self) {
+        let Some(selected) = self.selected_patient().cloned() else {
+            return;
+        };
+        let keep = match self.merge_mark {
+            Some(id) if id != selected.id => self.patients.iter().find(|p| p.id == id).cloned(),
+            _ => None,
+        };
+        let Some(keep) = keep else {
+            self.merge_mark = Some(selected.id);
+            toast::push(Toast::success(tf(
+                "patients.marked",
+                &[
+                    ("first", &selected.first_name),
+                    ("last", &selected.last_name),
+                ],
+            )));
+            return;
+        };
+        let pane = ComparePane::new(
+            t("patients.merge_title"),
+            tf(
+                "patients.keep",
+                &[
+                    ("first", &keep.first_name),
+                    ("last", &keep.last_name),
+                    ("id", &keep.id),
+                ],
+            ),
+            tf(
+                "patients.duplicate",
+                &[
+                    ("first", &selected.first_name),
+                    ("last", &selected.last_name),
+                    ("id", &selected.id),
+                ],
+            ),
+            merge_fields(&keep, &selected),
+            Side::Left,
+        )
+        .picking()
+        .confirm_with(tf(
+            "patients.merge_confirm",
+            &[("from", &selected.id), ("to", &keep.id)],
+        ));
+        self.merge = Some(PatientMerge {
+            pane,
+            keep,
+            duplicate: selected,
+        });
+    }
+
+    fn finish_merge(&mut self, merge: PatientMerge) -> Result<()> {
+        let merged = merged_patient(&merge.keep, &merge.duplicate, &merge.pane.picks());
+        match db::merge_patients(&self.db, &merged, merge.duplicate.id) {
+            Ok(()) => {
+                self.merge_mark = None;
+                toast::push(Toast::success(tf(
+                    "patients.merged",
+                    &[
+                        ("from", &merge.duplicate.id),
+                        ("first", &merged.first_name),
+                        ("last", &merged.last_name),
+                        ("id", &merged.id),
+                    ],
+                )));
+                self.fetch_patients()?;
+            }
+            Err(e) => toast::push(Toast::error(tf("patients.merge_failed", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    /// Clears the search and selects `patient_id` with its details open, for
+    /// a scanned wristband or a recently opened patient.
+    pub fn show_patient(&mut self, patient_id: i64) -> Result<()> {
+        self.search_input.clear();
+        self.fetch_patients()?;
+        let Some(index) = self
+            .filtered_patients
+            .iter()
+            .position(|p| p.id == patient_id)
+        else {
+            return Ok(());
+        };
+        self.state.select(Some(index));
+        self.focus_index = PATIENT_LIST;
+        self.is_searching = false;
+        self.show_details = true;
+        self.note_recent();
+        self.load_selected_details();
+        Ok(())
+    }
+
+    /// Opens the imaging study `accession_number` of `patient_id`, for a
+    /// scanned sample label.
+    pub fn show_study(&mut self, patient_id: i64, accession_number: &str) -> Result<()> {
+        self.show_patient(patient_id)?;
+        if let Some(patient) = self.selected_patient().cloned() {
+            match PatientImaging::new(&self.db, patient, accession_number) {
+                Ok(imaging) => self.imaging = Some(imaging),
+                Err(e) => toast::push(Toast::error(tf(
+                    "patients.imaging_failed",
+                    &[("error", &e)],
+                ))),
+            }
+        }
+        Ok(())
+    }
+
     pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<PatientAction>> {
+        if let Some(merge) = &mut self.merge {
+            match merge.pane.handle_key(key) {
+                Some(CompareAction::Confirm) => {
+                    if let Some(merge) = self.merge.take() {
+                        self.finish_merge(merge)?;
+                    }
+                }
+                Some(CompareAction::Cancel) => self.merge = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+        if let Some(label) = &mut self.label {
+            if label.handle_key(key) {
+                self.label = None;
+            }
+            return Ok(None);
+        }
+        if let Some(imaging) = &mut self.imaging {
+            if let Some(PatientAction::BackToList) = imaging.handle_input(key)? {
+                self.imaging = None;
+                self.imaging_numbers = db::get_imaging_numbers(&self.db).unwrap_or_default();
+            }
+            return Ok(None);
+        }
+        if let Some(documents) = &mut self.documents {
+            if let Some(PatientAction::BackToList) = documents.handle_input(key)? {
+                self.documents = None;
+            }
+            return Ok(None);
+        }
+
+        if let Some(popup) = &mut self.saved_searches {
+            match popup.handle_key(key) {
+                Some(SavedSearchOutcome::Apply(view)) => {
+                    self.saved_searches = None;
+                    self.apply_view(view);
+                }
+                Some(SavedSearchOutcome::Closed) => self.saved_searches = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+        if saved_searches::is_open_key(key) {
+            self.saved_searches = Some(SavedSearchesPopup::new(VIEW_KEY, self.current_view()));
+            return Ok(None);
+        }
+        if let Some(export) = &mut self.export_view {
+            if export.handle_key(key) {
+                self.export_view = None;
+            }
+            return Ok(None);
+        }
+        if export_view::is_open_key(key) {
+            self.export_view = Some(ExportView::new("patients", self.shown_rows()));
+            return Ok(None);
+        }
+        if let Some(picker) = &mut self.column_picker {
+            if picker.handle_key(key, &mut self.columns) {
+                self.column_picker = None;
+            }
+            return Ok(None);
+        }
+        if column_picker::is_open_key(key) {
+            self.column_picker = Some(ColumnPicker::new());
+            return Ok(None);
+        }
+
         if self.is_searching {
             match key.code {
-                KeyCode::Char(c) => {
-                    self.search_input.push(c);
-                    self.filter_patients();
-                }
-                KeyCode::Backspace => {
-                    self.search_input.pop();
+                _ if self.search_input.handle_key(key) => {
                     self.filter_patients();
                 }
                 KeyCode::Enter | KeyCode::Down | KeyCode::Tab => {
@@ -164,6 +747,7 @@ impl ListPatients {
                         self.is_searching = false;
                         self.focus_index = PATIENT_LIST;
                         self.state.select(Some(0));
+                        self.load_selected_details();
                     }
                 }
                 KeyCode::Esc => {
@@ -174,7 +758,6 @@ impl ListPatients {
             }
             return Ok(None);
         }
-
         match key.code {
             KeyCode::Char('/') | KeyCode::Char('s') | KeyCode::Char('S') => {
                 self.is_searching = true;
@@ -210,8 +793,37 @@ impl ListPatients {
                 return Ok(Some(PatientAction::BackToHome));
             }
             KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
                 self.fetch_patients()?;
             }
+            KeyCode::Char('d') | KeyCode::Char('D') if self.focus_index == PATIENT_LIST => {
+                self.open_documents();
+            }
+            KeyCode::Char('i') | KeyCode::Char('I') if self.focus_index == PATIENT_LIST => {
+                self.open_imaging();
+            }
+            KeyCode::Char('l') | KeyCode::Char('L') if self.focus_index == PATIENT_LIST => {
+                self.open_label();
+            }
+            KeyCode::Char('m') | KeyCode::Char('M') if self.focus_index == PATIENT_LIST => {
+                self.mark_for_merge();
+            }
+            KeyCode::Char('p') | KeyCode::Char('P') if self.focus_index == PATIENT_LIST => {
+                self.toggle_pin();
+            }
+            KeyCode::Char('y') if self.focus_index == PATIENT_LIST => self.copy_selected(false),
+            KeyCode::Char('Y') if self.focus_index == PATIENT_LIST => self.copy_selected(true),
+            KeyCode::Char('f') | KeyCode::Char('F') if self.focus_index == PATIENT_LIST => {
+                self.export_fhir();
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') if self.focus_index == PATIENT_LIST => {
+                self.take_photo();
+            }
+            KeyCode::Char('1'..='9') if self.focus_index == PATIENT_LIST => {
+                if let Some(view) = saved_searches::shortcut(VIEW_KEY, key) {
+                    self.apply_view(view);
+                }
+            }
             KeyCode::Esc => {
                 if self.show_details {
                     self.show_details = false;
@@ -224,11 +836,135 @@ impl ListPatients {
         Ok(None)
     }
 
+    fn copy_selected(&mut self, full_row: bool) {
+        let Some(patient) = self.selected_patient() else {
+            return;
+        };
+        let (text, what) = if full_row {
+            let id = patient.id.to_string();
+            let gender = patient.gender.label();
+            let row = tsv_line(&[
+                &id,
+                &patient.first_name,
+                &patient.last_name,
+                &patient.date_of_birth,
+                gender,
+                &patient.phone_number,
+                &patient.full_address(),
+                patient.email.as_deref().unwrap_or_default(),
+            ]);
+            (row, t("patients.row"))
+        } else {
+            (patient.phone_number.clone(), t("patients.phone_number"))
+        };
+        match copy_to_clipboard(&text) {
+            Ok(()) => {
+                toast::push(Toast::success(tf("patients.copied", &[("what", &what)])));
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("imaging.copy_failed", &[("error", &e)])));
+            }
+        }
+    }
+
+    fn export_fhir(&self) {
+        let Some(patient) = self.selected_patient() else {
+            return;
+        };
+        let exported = fhir::patient_bundle(&self.db, patient.id).and_then(|bundle| {
+            write_export(
+                &format!("patient_{}_fhir.json", patient.id),
+                &serde_json::to_string_pretty(&bundle)?,
+            )
+        });
+        match exported {
+            Ok(path) => toast::push(Toast::success(tf(
+                "patients.exported",
+                &[("path", &path.display())],
+            ))),
+            Err(e) => toast::push(Toast::error(tf("patients.fhir_failed", &[("error", &e)]))),
+        }
+    }
+
     fn selected_patient(&self) -> Option<&Patient> {
         self.state
             .selected()
             .and_then(|i| self.filtered_patients.get(i))
     }
+
+    fn render_photo_pane(&self, frame: &mut Frame, area: Rect, photo: &Result<Image, String>) {
+        let block = Block::default()
+            .title(format!(" {} ", t("patients.photo")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        match photo {
+            Ok(image) => render_photo(frame, inner, image),
+            Err(error) => frame.render_widget(
+                Paragraph::new(error.as_str())
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .wrap(Wrap { trim: true }),
+                inner,
+            ),
+        }
+    }
+
+    fn detail_fields(&self, patient: &Patient) -> Vec<(&str, String)> {
+        let optional = |text: &Option<String>| text.clone().unwrap_or_default();
+        let mut fields = Vec::new();
+        if let Some(isolation) = self.isolations.get(&patient.id) {
+            fields.push((t("patients.isolation"), isolation.clone()));
+        }
+        fields.extend([
+            (
+                t("patients.name"),
+                tf(
+                    "patients.name_id",
+                    &[
+                        ("first", &patient.first_name),
+                        ("last", &patient.last_name),
+                        ("id", &patient.id),
+                    ],
+                ),
+            ),
+            (t("patients.mrn"), patient.mrn.clone()),
+            (t("patients.date_of_birth"), patient.date_of_birth.clone()),
+            (t("common.gender"), patient.gender.label().to_string()),
+            (t("common.phone"), patient.phone_number.clone()),
+            (t("patients.email"), optional(&patient.email)),
+            (
+                t("patients.contact_by"),
+                patient.contact_preference.label().to_string(),
+            ),
+            (t("common.address"), patient.full_address()),
+            (
+                t("patients.medical_history"),
+                optional(&patient.medical_history),
+            ),
+            (t("patients.allergies"), optional(&patient.allergies)),
+            (
+                t("patients.current_medications"),
+                optional(&patient.current_medications),
+            ),
+        ]);
+        if let Some((_, custom)) = self
+            .custom_fields
+            .as_ref()
+            .filter(|(id, _)| *id == patient.id)
+        {
+            fields.extend(
+                custom
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.clone())),
+            );
+        }
+        fields
+    }
 }
 
 impl Component for ListPatients {
@@ -241,9 +977,18 @@ impl Component for ListPatients {
     }
 
     fn render(&self, frame: &mut Frame) {
+        if let Some(imaging) = &self.imaging {
+            imaging.render(frame);
+            return;
+        }
+        if let Some(documents) = &self.documents {
+            documents.render(frame);
+            return;
+        }
+
         let area = frame.area();
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             area,
         );
 
@@ -251,27 +996,29 @@ impl Component for ListPatients {
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Min(10),
+                Constraint::Min(13),
                 Constraint::Length(3),
                 Constraint::Length(2),
                 Constraint::Length(1),
             ])
             .margin(1)
             .split(area);
+        let (list_area, details_area) = master_detail::split(layout[1]);
+        let [search_area, table_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(10)]).areas(list_area);
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
 
-        let title = Paragraph::new("🏥 PATIENT LIST")
+        let title = Paragraph::new(t("patients.list_title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
@@ -280,115 +1027,100 @@ impl Component for ListPatients {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Patients ",
+                format!(" {} ", t("common.search_patients")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(75, 75, 120))
+                Style::default().fg(theme().border)
             })
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .style(Style::default().bg(theme().surface));
 
-        let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(22, 22, 35)),
-            )
+        let search_paragraph = Paragraph::new(self.search_input.line(self.is_searching))
+            .style(Style::default().fg(theme().text).bg(theme().surface))
             .block(search_block);
-        frame.render_widget(search_paragraph, layout[1]);
-
-        let header_cells = [
-            "ID",
-            "First Name",
-            "Last Name",
-            "Date of Birth",
-            "Gender",
-            "Phone",
-            "Address",
-        ]
-        .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::Rgb(230, 230, 250))));
+        frame.render_widget(search_paragraph, search_area);
+
+        let visible = self.columns.visible(is_narrow(list_area));
+        let header_cells = Columns::pick(&HEADERS, &visible)
+            .into_iter()
+            .map(|h| Cell::from(t(h)).style(Style::default().fg(theme().title)));
         let header = Row::new(header_cells)
-            .style(Style::default().bg(Color::Rgb(80, 60, 130)))
+            .style(Style::default().bg(theme().header))
             .height(1);
 
+        let widths = Columns::widths(&WIDTHS, &visible);
+
+        let columns = column_widths(table_area.inner(Margin::new(1, 1)), &widths, 1, "► ");
+
         let rows = self.filtered_patients.iter().map(|patient| {
-            let cells = vec![
-                Cell::from(patient.id.to_string()),
-                Cell::from(patient.first_name.clone()),
-                Cell::from(patient.last_name.clone()),
-                Cell::from(patient.date_of_birth.clone()),
-                Cell::from(match patient.gender {
-                    crate::models::Gender::Male => "Male",
-                    crate::models::Gender::Female => "Female",
-                    crate::models::Gender::Other => "Other",
-                }),
-                Cell::from(patient.phone_number.clone()),
-                Cell::from(patient.address.clone()),
-            ];
-            Row::new(cells)
-                .height(1)
-                .bottom_margin(0)
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            let cells = Columns::pick(&self.row_values(patient), &visible)
+                .into_iter()
+                .enumerate()
+                .map(|(column, text)| cell(text, &columns, column))
+                .collect::<Vec<_>>();
+            Row::new(cells).height(1).bottom_margin(0).style(
+                if self.isolations.contains_key(&patient.id) {
+                    Style::default().fg(theme().error)
+                } else {
+                    Style::default().fg(theme().text)
+                },
+            )
         });
 
         let selected_style = Style::default()
-            .fg(Color::Rgb(250, 250, 110))
-            .bg(Color::Rgb(40, 40, 60))
+            .fg(theme().focus)
+            .bg(theme().highlight)
             .add_modifier(Modifier::BOLD);
 
         let table_title = if !self.search_input.is_empty() {
             format!(
-                " Patients ({} of {} matches) ",
-                self.filtered_patients.len(),
-                self.patients.len()
+                " {} ",
+                tf(
+                    "common.patients_matches",
+                    &[
+                        ("shown", &self.filtered_patients.len()),
+                        ("total", &self.patients.len())
+                    ]
+                )
             )
         } else {
-            format!(" Patients ({}) ", self.patients.len())
+            format!(
+                " {} ",
+                tf("common.patients_count", &[("total", &self.patients.len())])
+            )
         };
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Percentage(5),
-                Constraint::Percentage(15),
-                Constraint::Percentage(15),
-                Constraint::Percentage(10),
-                Constraint::Percentage(10),
-                Constraint::Percentage(15),
-                Constraint::Percentage(30),
-            ],
-        )
-        .header(header)
-        .block(
-            Block::default()
-                .title(table_title.clone())
-                .title_alignment(Alignment::Center)
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-                .style(Style::default().bg(Color::Rgb(22, 22, 35))),
-        )
-        .row_highlight_style(selected_style)
-        .highlight_symbol(if self.focus_index == PATIENT_LIST {
-            "► "
-        } else {
-            "  "
-        });
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .title(table_title.clone())
+                    .title_alignment(Alignment::Center)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .row_highlight_style(selected_style)
+            .highlight_symbol(if self.focus_index == PATIENT_LIST {
+                "► "
+            } else {
+                "  "
+            });
 
         if self.filtered_patients.is_empty() {
             let message = if self.search_input.is_empty() {
-                "No patients found in database"
+                t("common.no_patients")
             } else {
-                "No patients match your search criteria"
+                t("common.no_patient_matches")
             };
 
             let no_patients = Paragraph::new(message)
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                .style(Style::default().fg(theme().text))
                 .alignment(Alignment::Center)
                 .block(
                     Block::default()
@@ -396,86 +1128,142 @@ impl Component for ListPatients {
                         .title_alignment(Alignment::Center)
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-                        .style(Style::default().bg(Color::Rgb(22, 22, 35))),
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
                 );
-            frame.render_widget(no_patients, layout[2]);
+            frame.render_widget(no_patients, table_area);
         } else {
-            frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
+            frame.render_stateful_widget(table, table_area, &mut self.state.clone());
         }
 
-        if self.show_details && self.state.selected().is_some() {
+        if let Some(mut details_area) = details_area {
+            let photo = self
+                .selected_patient()
+                .and_then(|patient| patient.photo_path.as_ref())
+                .and_then(|path| self.photos.get(path));
+            if let Some(photo) = photo.filter(|_| details_area.height >= PHOTO_HEIGHT * 2) {
+                let [photo_area, rest] =
+                    Layout::vertical([Constraint::Length(PHOTO_HEIGHT), Constraint::Min(0)])
+                        .areas(details_area);
+                self.render_photo_pane(frame, photo_area, photo);
+                details_area = rest;
+            }
+            let fields = self
+                .selected_patient()
+                .map(|patient| self.detail_fields(patient));
+            master_detail::render_details(
+                frame,
+                details_area,
+                t("patients.details_title"),
+                fields.as_deref(),
+                t("patients.none_selected"),
+            );
+        }
+
+        if details_area.is_none() && self.show_details && self.state.selected().is_some() {
             if let Some(patient) = self.selected_patient() {
-                let details = format!(
-                    "Details for {} {}: Born on {}, Gender: {}, Phone: {}, Address: {}",
-                    patient.first_name,
-                    patient.last_name,
-                    patient.date_of_birth,
-                    match patient.gender {
-                        crate::models::Gender::Male => "Male",
-                        crate::models::Gender::Female => "Female",
-                        crate::models::Gender::Other => "Other",
-                    },
-                    patient.phone_number,
-                    patient.address
+                let mut details = self
+                    .isolations
+                    .get(&patient.id)
+                    .map(|isolation| {
+                        format!(
+                            "{} ",
+                            tf("patients.isolation_line", &[("isolation", &isolation)])
+                        )
+                    })
+                    .unwrap_or_default();
+                details += &tf(
+                    "patients.details",
+                    &[
+                        ("first", &patient.first_name),
+                        ("last", &patient.last_name),
+                        ("born", &patient.date_of_birth),
+                        ("gender", &patient.gender.label()),
+                        ("phone", &patient.phone_number),
+                        ("address", &patient.full_address()),
+                    ],
                 );
+                if let Some((_, fields)) = self
+                    .custom_fields
+                    .as_ref()
+                    .filter(|(id, _)| *id == patient.id)
+                {
+                    for (name, value) in fields {
+                        details.push_str(&format!(" | {}: {}", name, value));
+                    }
+                }
+                if patient.photo_path.is_some() {
+                    details.push_str(&format!(" {}", t("patients.photo_on_file")));
+                }
+                details.push_str(&format!(" {}", t("patients.details_keys")));
 
                 let details_widget = Paragraph::new(details)
-                    .style(Style::default().fg(Color::Rgb(200, 200, 220)))
+                    .style(Style::default().fg(theme().text))
                     .block(
                         Block::default()
-                            .title(" Patient Details ")
+                            .title(format!(" {} ", t("patients.details_title")))
                             .borders(Borders::ALL)
                             .border_type(BorderType::Rounded)
-                            .border_style(Style::default().fg(Color::Rgb(75, 75, 120))),
+                            .border_style(Style::default().fg(theme().border)),
                     )
                     .wrap(Wrap { trim: true });
 
-                frame.render_widget(details_widget, layout[3]);
+                frame.render_widget(details_widget, layout[2]);
             }
         } else {
-            let help_text = if self.is_searching {
-                "Type to search | ↓/Enter: To results | Esc: Cancel search"
+            let capturing = self
+                .capture
+                .as_ref()
+                .map(|capture| tf("patients.taking_photo", &[("spinner", &capture.spinner())]));
+            let help_text = if let Some(capturing) = capturing.as_deref() {
+                capturing
+            } else if self.is_searching {
+                t("common.search_enter_help")
+            } else if is_narrow(area) {
+                t("patients.narrow_help")
             } else {
-                "/ or s: Search | ↑↓: Navigate | Enter: View Details | R: Refresh | Tab: Focus"
+                t("patients.help")
             };
 
             let help_paragraph = Paragraph::new(help_text)
-                .style(Style::default().fg(Color::Rgb(140, 140, 170)))
+                .style(Style::default().fg(theme().help))
                 .alignment(Alignment::Center);
-            frame.render_widget(help_paragraph, layout[3]);
+            frame.render_widget(help_paragraph, layout[2]);
         }
 
         let back_text = if self.focus_index == BACK_BUTTON {
-            "► Back ◄"
+            format!("► {} ◄", t("common.back"))
         } else {
-            "  Back  "
+            format!("  {}  ", t("common.back"))
         };
 
         let back_style = if self.focus_index == BACK_BUTTON {
             Style::default()
-                .fg(Color::Rgb(129, 199, 245))
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let back_button = Paragraph::new(back_text)
             .style(back_style)
             .alignment(Alignment::Center);
-        frame.render_widget(back_button, layout[4]);
+        frame.render_widget(back_button, layout[3]);
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, layout[5]);
+        if let Some(label) = &self.label {
+            label.render_popup(frame);
+        }
+        if let Some(merge) = &self.merge {
+            merge.pane.render(frame);
+        }
+        if let Some(popup) = &self.saved_searches {
+            popup.render_popup(frame);
+        }
+        if let Some(export) = &self.export_view {
+            export.render_popup(frame);
+        }
+        if let Some(picker) = &self.column_picker {
+            picker.render_popup(frame, &self.columns);
         }
-    }
-}
-
-impl Default for ListPatients {
-    fn default() -> Self {
-        Self::new()
     }
 }
@@ -1,22 +1,27 @@
 use crate::app::SelectedApp;
 use crate::components::hospital::patients::add::AddPatient;
 use crate::components::hospital::patients::delete::DeletePatient;
+use crate::components::hospital::patients::import::ImportPatients;
 use crate::components::hospital::patients::list::ListPatients;
 use crate::components::hospital::patients::update::UpdatePatient;
 use crate::components::Component;
+use crate::db::Db;
 use crate::tui::Frame;
 use anyhow::Result;
 use crossterm::event::KeyEvent;
 
 pub mod add;
+pub mod compliance;
 pub mod delete;
+pub mod documents;
+pub mod imaging;
+pub mod import;
 pub mod list;
 pub mod update;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PatientAction {
     BackToHome,
-    #[allow(dead_code)]
     BackToList,
 }
 
@@ -26,6 +31,8 @@ pub enum PatientsState {
     ListPatients,
     DeletePatient,
     UpdatePatient,
+    Compliance,
+    Import,
 }
 
 pub struct Patients {
@@ -33,24 +40,35 @@ pub struct Patients {
     pub list_patients: ListPatients,
     pub delete_patient: Option<DeletePatient>,
     pub update_patient: Option<UpdatePatient>,
+    pub compliance: compliance::Compliance,
+    pub import: ImportPatients,
     pub state: PatientsState,
 }
 
 impl Patients {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
         Self {
-            add_patient: AddPatient::new(),
-            list_patients: ListPatients::new(),
+            add_patient: AddPatient::new(db),
+            list_patients: ListPatients::new(db),
             delete_patient: None,
             update_patient: None,
+            compliance: compliance::Compliance::new(db),
+            import: ImportPatients::new(db),
             state: PatientsState::ListPatients,
         }
     }
 
+    pub fn save_view(&self) {
+        self.list_patients.save_view();
+    }
+
     pub fn initialize_list(&mut self) -> Result<()> {
         if self.state == PatientsState::ListPatients {
             self.list_patients.fetch_patients()?;
         }
+        if self.state == PatientsState::Compliance {
+            self.compliance.load_data()?;
+        }
         Ok(())
     }
 }
@@ -108,6 +126,16 @@ impl Component for Patients {
                     }
                 }
             }
+            PatientsState::Compliance => {
+                if let Some(SelectedApp::None) = self.compliance.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
+            PatientsState::Import => {
+                if let Some(SelectedApp::None) = self.import.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
         }
         Ok(None)
     }
@@ -126,6 +154,14 @@ impl Component for Patients {
                     update_patient.render(frame);
                 }
             }
+            PatientsState::Compliance => self.compliance.render(frame),
+            PatientsState::Import => self.import.render(frame),
+        }
+    }
+
+    fn tick(&mut self) {
+        if self.state == PatientsState::ListPatients {
+            self.list_patients.tick();
         }
     }
 }
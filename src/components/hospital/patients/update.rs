@@ -1,16 +1,28 @@
 use crate::app::SelectedApp;
+use crate::components::form;
 use crate::components::hospital::patients::PatientAction;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::undo::EditHistory;
+use crate::components::widgets::discard_prompt::DiscardPrompt;
+use crate::components::widgets::edit_warning;
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
-use crate::db;
-use crate::models::{Gender, Patient};
+use crate::db::{self, Db};
+use crate::hooks;
+use crate::i18n::{t, tf};
+use crate::locks::EditLock;
+use crate::models::{ContactPreference, Gender, Patient};
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::utils::contact;
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{prelude::*, widgets::*};
-use std::time::{Duration, Instant};
 
 enum ConfirmAction {
     UpdatePatient,
+    DiscardChanges,
 }
 
 enum UpdateState {
@@ -19,27 +31,29 @@ enum UpdateState {
 }
 
 pub struct UpdatePatient {
+    db: Db,
     all_patients: Vec<Patient>,
     filtered_patients: Vec<Patient>,
-    search_input: String,
+    search_input: TextInput,
     is_searching: bool,
     table_state: TableState,
     update_state: UpdateState,
     patient_id_input: String,
     patient: Patient,
+    history: EditHistory<Patient>,
     loaded: bool,
+    edit_lock: Option<EditLock>,
     selected_field: Option<usize>,
     edit_table_state: TableState,
     input_value: String,
+    /// Why the value being edited was refused, shown under it.
+    input_error: Option<String>,
     editing: bool,
-    error_message: Option<String>,
-    error_timer: Option<Instant>,
-    success_message: Option<String>,
-    success_timer: Option<Instant>,
     show_confirmation: bool,
     confirmation_message: String,
     confirmed_action: Option<ConfirmAction>,
     confirmation_selected: usize,
+    discard_prompt: Option<DiscardPrompt>,
 }
 
 const ID_INPUT: usize = 0;
@@ -48,30 +62,35 @@ const LAST_NAME_INPUT: usize = 2;
 const DOB_INPUT: usize = 3;
 const GENDER_INPUT: usize = 4;
 const ADDRESS_INPUT: usize = 5;
-const PHONE_INPUT: usize = 6;
-const EMAIL_INPUT: usize = 7;
-const MEDICAL_HISTORY_INPUT: usize = 8;
-const ALLERGIES_INPUT: usize = 9;
-const MEDICATIONS_INPUT: usize = 10;
-const INPUT_FIELDS: usize = 10;
+const POSTCODE_INPUT: usize = 6;
+const CITY_INPUT: usize = 7;
+const REGION_INPUT: usize = 8;
+const PHONE_INPUT: usize = 9;
+const EMAIL_INPUT: usize = 10;
+const MEDICAL_HISTORY_INPUT: usize = 11;
+const ALLERGIES_INPUT: usize = 12;
+const MEDICATIONS_INPUT: usize = 13;
+const CONTACT_INPUT: usize = 14;
+const INPUT_FIELDS: usize = 14;
 
 impl UpdatePatient {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
         let mut selection_state = TableState::default();
         selection_state.select(Some(0));
 
         let mut edit_table_state = TableState::default();
         edit_table_state.select(Some(0));
 
-        let all_patients = match db::get_all_patients() {
+        let all_patients = match db::get_all_patients(db) {
             Ok(patients) => patients,
             Err(_) => Vec::new(),
         };
 
         Self {
+            db: db.clone(),
             all_patients: all_patients.clone(),
             filtered_patients: all_patients,
-            search_input: String::new(),
+            search_input: TextInput::new(),
             is_searching: false,
             table_state: selection_state,
             update_state: UpdateState::SelectingPatient,
@@ -83,25 +102,31 @@ impl UpdatePatient {
                 date_of_birth: String::new(),
                 gender: Gender::Male,
                 address: String::new(),
+                city: String::new(),
+                region: String::new(),
+                postcode: String::new(),
                 phone_number: String::new(),
                 email: None,
                 medical_history: None,
                 allergies: None,
                 current_medications: None,
+                contact_preference: ContactPreference::default(),
+                photo_path: None,
+                mrn: String::new(),
             },
+            history: EditHistory::new(),
             loaded: false,
+            edit_lock: None,
             selected_field: Some(0),
             edit_table_state,
             input_value: String::new(),
+            input_error: None,
             editing: false,
-            error_message: None,
-            error_timer: None,
-            success_message: None,
-            success_timer: None,
             show_confirmation: false,
             confirmation_message: String::new(),
             confirmed_action: None,
             confirmation_selected: 0,
+            discard_prompt: None,
         }
     }
 
@@ -109,7 +134,7 @@ impl UpdatePatient {
         if self.search_input.is_empty() {
             self.filtered_patients = self.all_patients.clone();
         } else {
-            let search_term = self.search_input.to_lowercase();
+            let search_term = self.search_input.value().to_lowercase();
             self.filtered_patients = self
                 .all_patients
                 .iter()
@@ -117,7 +142,7 @@ impl UpdatePatient {
                     p.first_name.to_lowercase().contains(&search_term)
                         || p.last_name.to_lowercase().contains(&search_term)
                         || p.id.to_string().contains(&search_term)
-                        || p.phone_number.to_lowercase().contains(&search_term)
+                        || contact::phone_matches(&p.phone_number, &search_term)
                 })
                 .cloned()
                 .collect();
@@ -130,17 +155,22 @@ impl UpdatePatient {
         }
     }
 
-    fn load_patient_by_id(&mut self, patient_id: i64) -> Result<()> {
-        match db::get_patient(patient_id) {
+    pub fn load_patient_by_id(&mut self, patient_id: i64) -> Result<()> {
+        match db::get_patient(&self.db, patient_id) {
             Ok(patient) => {
                 self.patient = patient;
+                self.edit_lock = Some(EditLock::claim(&self.db, "patient", patient_id));
+                self.history.clear();
                 self.loaded = true;
                 self.update_state = UpdateState::EditingPatient;
                 self.update_input_value();
                 Ok(())
             }
             Err(_) => {
-                self.set_error(format!("Patient with ID {} doesn't exist", patient_id));
+                toast::push(Toast::error(tf(
+                    "patients.update.not_found",
+                    &[("id", &patient_id)],
+                )));
                 Err(anyhow::anyhow!("Patient not found"))
             }
         }
@@ -154,7 +184,7 @@ impl UpdatePatient {
                     Err(e) => Err(e),
                 }
             } else {
-                self.set_error("Invalid Patient ID format.".to_string());
+                toast::push(Toast::error(t("invoice_update.bad_patient_id")));
                 Err(anyhow::anyhow!("Invalid Patient ID format"))
             }
         } else {
@@ -170,8 +200,8 @@ impl UpdatePatient {
                 return self.load_patient_by_id(patient_id);
             }
         }
-        self.set_error("No patient selected".to_string());
-        Err(anyhow::anyhow!("No patient selected"))
+        toast::push(Toast::error(t("patients.none_selected")));
+        Err(anyhow::anyhow!(t("patients.none_selected")))
     }
 
     fn update_input_value(&mut self) {
@@ -186,17 +216,17 @@ impl UpdatePatient {
                 FIRST_NAME_INPUT => self.patient.first_name.clone(),
                 LAST_NAME_INPUT => self.patient.last_name.clone(),
                 DOB_INPUT => self.patient.date_of_birth.clone(),
-                GENDER_INPUT => match self.patient.gender {
-                    Gender::Male => "Male".to_string(),
-                    Gender::Female => "Female".to_string(),
-                    Gender::Other => "Other".to_string(),
-                },
+                GENDER_INPUT => self.patient.gender.label().to_string(),
                 ADDRESS_INPUT => self.patient.address.clone(),
+                POSTCODE_INPUT => self.patient.postcode.clone(),
+                CITY_INPUT => self.patient.city.clone(),
+                REGION_INPUT => self.patient.region.clone(),
                 PHONE_INPUT => self.patient.phone_number.clone(),
                 EMAIL_INPUT => self.patient.email.clone().unwrap_or_default(),
                 MEDICAL_HISTORY_INPUT => self.patient.medical_history.clone().unwrap_or_default(),
                 ALLERGIES_INPUT => self.patient.allergies.clone().unwrap_or_default(),
                 MEDICATIONS_INPUT => self.patient.current_medications.clone().unwrap_or_default(),
+                CONTACT_INPUT => self.patient.contact_preference.label().to_string(),
                 _ => String::new(),
             };
         }
@@ -207,21 +237,52 @@ impl UpdatePatient {
             return;
         }
 
+        let before = self.patient.clone();
         if let Some(field_index) = self.selected_field {
             match field_index {
                 FIRST_NAME_INPUT => self.patient.first_name = self.input_value.clone(),
                 LAST_NAME_INPUT => self.patient.last_name = self.input_value.clone(),
                 DOB_INPUT => self.patient.date_of_birth = self.input_value.clone(),
-                GENDER_INPUT => {
-                    self.patient.gender = match self.input_value.to_lowercase().as_str() {
-                        "f" | "female" => Gender::Female,
-                        "m" | "male" => Gender::Male,
-                        _ => Gender::Other,
+                GENDER_INPUT => match Gender::parse(&self.input_value) {
+                    Some(gender) => self.patient.gender = gender,
+                    None => {
+                        self.input_error = form::gender(&self.input_value).err();
+                        return;
                     }
-                }
+                },
                 ADDRESS_INPUT => self.patient.address = self.input_value.clone(),
-                PHONE_INPUT => self.patient.phone_number = self.input_value.clone(),
-                EMAIL_INPUT => self.patient.email = Some(self.input_value.clone()),
+                POSTCODE_INPUT => {
+                    self.patient.postcode = self.input_value.trim().to_string();
+                    if let Some((city, region)) = hooks::lookup_postcode(&self.patient.postcode) {
+                        if self.patient.city.trim().is_empty() {
+                            self.patient.city = city;
+                        }
+                        if self.patient.region.trim().is_empty() {
+                            self.patient.region = region;
+                        }
+                    }
+                }
+                CITY_INPUT => self.patient.city = self.input_value.clone(),
+                REGION_INPUT => self.patient.region = self.input_value.clone(),
+                PHONE_INPUT => match form::phone(self.input_value.trim()) {
+                    Ok(()) => {
+                        self.patient.phone_number = contact::normalize_phone(&self.input_value)
+                    }
+                    Err(error) => {
+                        self.input_error = Some(error);
+                        return;
+                    }
+                },
+                EMAIL_INPUT if self.input_value.trim().is_empty() => self.patient.email = None,
+                EMAIL_INPUT => match form::email(self.input_value.trim()) {
+                    Ok(()) => {
+                        self.patient.email = Some(contact::normalize_email(&self.input_value))
+                    }
+                    Err(error) => {
+                        self.input_error = Some(error);
+                        return;
+                    }
+                },
                 MEDICAL_HISTORY_INPUT => {
                     self.patient.medical_history = Some(self.input_value.clone())
                 }
@@ -229,9 +290,17 @@ impl UpdatePatient {
                 MEDICATIONS_INPUT => {
                     self.patient.current_medications = Some(self.input_value.clone())
                 }
+                CONTACT_INPUT => match ContactPreference::parse(&self.input_value) {
+                    Some(preference) => self.patient.contact_preference = preference,
+                    None => {
+                        toast::push(Toast::error(t("patients.update.contact_by")));
+                        return;
+                    }
+                },
                 _ => {}
             }
         }
+        self.history.record(before);
         self.editing = false;
     }
 
@@ -242,13 +311,41 @@ impl UpdatePatient {
         self.confirmation_selected = 0;
     }
 
+    fn undo_edit(&mut self) {
+        match self.history.undo(self.patient.clone()) {
+            Some(previous) => {
+                self.patient = previous;
+                self.update_input_value();
+            }
+            None => toast::push(Toast::error(t("common.nothing_to_undo"))),
+        }
+    }
+
+    fn redo_edit(&mut self) {
+        match self.history.redo(self.patient.clone()) {
+            Some(next) => {
+                self.patient = next;
+                self.update_input_value();
+            }
+            None => toast::push(Toast::error(t("common.nothing_to_redo"))),
+        }
+    }
+
+    fn discard_changes(&mut self) {
+        if let Some(original) = self.history.discard() {
+            self.patient = original;
+            self.update_input_value();
+            toast::push(Toast::success(t("common.discarded")));
+        }
+    }
+
     fn update_patient(&mut self) -> Result<()> {
-        match db::update_patient(&self.patient) {
+        match db::update_patient(&self.db, &self.patient) {
             Ok(_) => {
-                self.success_message = Some("Patient updated successfully!".to_string());
-                self.success_timer = Some(Instant::now());
+                toast::push(Toast::success(t("patients.update.updated")));
+                self.history.clear();
 
-                if let Ok(patients) = db::get_all_patients() {
+                if let Ok(patients) = db::get_all_patients(&self.db) {
                     self.all_patients = patients.clone();
                     self.filtered_patients = patients;
                     self.filter_patients();
@@ -257,7 +354,7 @@ impl UpdatePatient {
                 Ok(())
             }
             Err(e) => {
-                self.set_error(format!("Database error: {}", e));
+                toast::push(Toast::error(tf("common.database_error", &[("error", &e)])));
                 Err(e)
             }
         }
@@ -266,15 +363,24 @@ impl UpdatePatient {
     fn back_to_selection(&mut self) {
         self.update_state = UpdateState::SelectingPatient;
         self.loaded = false;
+        self.edit_lock = None;
         self.patient_id_input = String::new();
         self.editing = false;
-        self.clear_error();
-        self.clear_success();
+        self.history.clear();
     }
 
     fn handle_input(&mut self, key: KeyEvent) -> Result<Option<PatientAction>> {
-        self.check_timeouts();
-
+        if let Some(prompt) = &mut self.discard_prompt {
+            match prompt.handle_key(key) {
+                Some(true) => {
+                    self.discard_prompt = None;
+                    self.back_to_selection();
+                }
+                Some(false) => self.discard_prompt = None,
+                None => {}
+            }
+            return Ok(None);
+        }
         if self.show_confirmation {
             match key.code {
                 KeyCode::Left | KeyCode::Right => {
@@ -282,8 +388,12 @@ impl UpdatePatient {
                 }
                 KeyCode::Enter => {
                     if self.confirmation_selected == 0 {
-                        if let Some(ConfirmAction::UpdatePatient) = self.confirmed_action.take() {
-                            let _ = self.update_patient();
+                        match self.confirmed_action.take() {
+                            Some(ConfirmAction::UpdatePatient) => {
+                                let _ = self.update_patient();
+                            }
+                            Some(ConfirmAction::DiscardChanges) => self.discard_changes(),
+                            None => {}
                         }
                     }
                     self.show_confirmation = false;
@@ -302,15 +412,18 @@ impl UpdatePatient {
             match key.code {
                 KeyCode::Char(c) => {
                     self.input_value.push(c);
+                    self.input_error = None;
                 }
                 KeyCode::Backspace => {
                     self.input_value.pop();
+                    self.input_error = None;
                 }
                 KeyCode::Enter => {
                     self.apply_edited_value();
                 }
                 KeyCode::Esc => {
                     self.editing = false;
+                    self.input_error = None;
                     self.update_input_value();
                 }
                 _ => {}
@@ -320,15 +433,8 @@ impl UpdatePatient {
 
         if matches!(self.update_state, UpdateState::SelectingPatient) {
             match key.code {
-                KeyCode::Char(c) if self.is_searching => {
-                    self.search_input.push(c);
-                    self.filter_patients();
-                    self.clear_error();
-                }
-                KeyCode::Backspace if self.is_searching => {
-                    self.search_input.pop();
+                _ if self.is_searching && self.search_input.handle_key(key) => {
                     self.filter_patients();
-                    self.clear_error();
                 }
                 KeyCode::Down if self.is_searching && !self.filtered_patients.is_empty() => {
                     self.is_searching = false;
@@ -349,12 +455,10 @@ impl UpdatePatient {
                 KeyCode::Char(c) if !self.is_searching => {
                     self.patient_id_input.push(c);
                     self.input_value = self.patient_id_input.clone();
-                    self.clear_error();
                 }
                 KeyCode::Backspace if !self.is_searching => {
                     self.patient_id_input.pop();
                     self.input_value = self.patient_id_input.clone();
-                    self.clear_error();
                 }
 
                 KeyCode::Up if !self.is_searching => {
@@ -419,13 +523,35 @@ impl UpdatePatient {
                 if key.modifiers.contains(KeyModifiers::CONTROL) =>
             {
                 self.show_confirmation(
-                    "Are you sure you want to update this patient?".to_string(),
+                    t("patients.update.confirm").to_string(),
                     ConfirmAction::UpdatePatient,
                 );
             }
+            KeyCode::Char('z') | KeyCode::Char('Z')
+                if key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.undo_edit();
+            }
+            KeyCode::Char('y') | KeyCode::Char('Y')
+                if key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.redo_edit();
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') if self.history.has_changes() => {
+                self.show_confirmation(
+                    t("patients.update.discard_confirm").to_string(),
+                    ConfirmAction::DiscardChanges,
+                );
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                toast::push(Toast::error(t("common.nothing_to_discard")));
+            }
             KeyCode::Char('e') | KeyCode::Char('E') => {
                 self.editing = true;
             }
+            KeyCode::Esc if self.history.has_changes() => {
+                self.discard_prompt = Some(DiscardPrompt::new());
+            }
             KeyCode::Esc => {
                 self.back_to_selection();
                 return Ok(None);
@@ -435,48 +561,6 @@ impl UpdatePatient {
 
         Ok(None)
     }
-
-    fn clear_error(&mut self) {
-        self.error_message = None;
-        self.error_timer = None;
-    }
-
-    fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_timer = Some(Instant::now());
-    }
-
-    fn clear_success(&mut self) {
-        self.success_message = None;
-        self.success_timer = None;
-    }
-
-    fn check_success_timeout(&mut self) {
-        if let Some(timer) = self.success_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_success();
-            }
-        }
-    }
-
-    fn check_error_timeout(&mut self) {
-        if let Some(timer) = self.error_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_error();
-            }
-        }
-    }
-
-    fn check_timeouts(&mut self) {
-        self.check_error_timeout();
-        self.check_success_timeout();
-    }
-}
-
-impl Default for UpdatePatient {
-    fn default() -> Self {
-        Self::new()
-    }
 }
 
 impl Component for UpdatePatient {
@@ -491,7 +575,7 @@ impl Component for UpdatePatient {
     fn render(&self, frame: &mut Frame) {
         let area = frame.area();
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             area,
         );
 
@@ -503,6 +587,9 @@ impl Component for UpdatePatient {
         if self.show_confirmation {
             self.render_confirmation_dialog(frame, area);
         }
+        if let Some(prompt) = &self.discard_prompt {
+            prompt.render_popup(frame);
+        }
     }
 }
 
@@ -523,16 +610,16 @@ impl UpdatePatient {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, main_layout[0]);
 
-        let title = Paragraph::new("✍️  SELECT PATIENT TO UPDATE")
+        let title = Paragraph::new(t("patients.update.select_title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, main_layout[0]);
@@ -541,24 +628,20 @@ impl UpdatePatient {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Patients ",
+                format!(" {} ", t("common.search_patients")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
-        let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
+        let search_paragraph = Paragraph::new(self.search_input.line(self.is_searching))
+            .style(Style::default().fg(theme().text).bg(theme().input))
             .block(search_block);
         frame.render_widget(search_paragraph, main_layout[1]);
 
@@ -566,73 +649,74 @@ impl UpdatePatient {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Patient ID ",
+                format!(" {} ", t("common.patient_id")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if !self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
         let id_input_paragraph = Paragraph::new(self.patient_id_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
+            .style(Style::default().fg(theme().text).bg(theme().input))
             .block(id_input_block);
         frame.render_widget(id_input_paragraph, main_layout[2]);
 
         if self.filtered_patients.is_empty() {
             let no_patients = Paragraph::new(if self.search_input.is_empty() {
-                "No patients found in database"
+                t("common.no_patients")
             } else {
-                "No patients match your search criteria"
+                t("common.no_patient_matches")
             })
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Center);
             frame.render_widget(no_patients, main_layout[3]);
         } else {
+            let widths = [
+                Constraint::Percentage(15),
+                Constraint::Percentage(30),
+                Constraint::Percentage(30),
+                Constraint::Percentage(25),
+            ];
+            let columns = column_widths(main_layout[3].inner(Margin::new(1, 1)), &widths, 2, "► ");
             let patients_rows: Vec<Row> = self
                 .filtered_patients
                 .iter()
                 .map(|p| {
                     Row::new(vec![
-                        p.id.to_string(),
-                        p.first_name.clone(),
-                        p.last_name.clone(),
-                        p.phone_number.clone(),
+                        cell(p.id.to_string(), &columns, 0),
+                        cell(&p.first_name, &columns, 1),
+                        cell(&p.last_name, &columns, 2),
+                        cell(&p.phone_number, &columns, 3),
                     ])
-                    .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                    .style(Style::default().fg(theme().text))
                     .height(1)
                     .bottom_margin(0)
                 })
                 .collect();
 
             let selected_style = Style::default()
-                .fg(Color::Rgb(250, 250, 110))
-                .bg(Color::Rgb(40, 40, 60))
+                .fg(theme().focus)
+                .bg(theme().highlight)
                 .add_modifier(Modifier::BOLD);
 
-            let header = Row::new(vec!["ID", "First Name", "Last Name", "Phone"])
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(220, 220, 240))
-                        .bg(Color::Rgb(80, 60, 130))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .height(1);
-
-            let widths = [
-                Constraint::Percentage(15),
-                Constraint::Percentage(30),
-                Constraint::Percentage(30),
-                Constraint::Percentage(25),
-            ];
+            let header = Row::new(vec![
+                t("common.id"),
+                t("common.first_name"),
+                t("common.last_name"),
+                t("common.phone"),
+            ])
+            .style(
+                Style::default()
+                    .fg(theme().text)
+                    .bg(theme().header)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1);
 
             let patients_table = Table::new(patients_rows, widths)
                 .header(header)
@@ -640,9 +724,15 @@ impl UpdatePatient {
                     Block::default()
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .title(format!(" Patients ({}) ", self.filtered_patients.len()))
-                        .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-                        .style(Style::default().bg(Color::Rgb(26, 26, 36))),
+                        .title(format!(
+                            " {} ",
+                            tf(
+                                "common.patients_count",
+                                &[("total", &self.filtered_patients.len())]
+                            )
+                        ))
+                        .border_style(Style::default().fg(theme().unfocused))
+                        .style(Style::default().bg(theme().input)),
                 )
                 .column_spacing(2)
                 .row_highlight_style(selected_style)
@@ -655,34 +745,14 @@ impl UpdatePatient {
             );
         }
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(255, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, main_layout[4]);
-        } else if let Some(success) = &self.success_message {
-            let success_paragraph = Paragraph::new(success.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, main_layout[4]);
-        }
-
         let help_text = if self.is_searching {
-            "Type to search | ↓: To results | Esc: Cancel search"
+            t("common.search_help")
         } else {
-            "/ or s: Search | ↑/↓: Navigate patients | Enter: Select patient | Esc: Back"
+            t("patients.update.select_help")
         };
 
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, main_layout[5]);
     }
@@ -692,7 +762,7 @@ impl UpdatePatient {
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),
-                Constraint::Min(14),
+                Constraint::Min(18),
                 Constraint::Length(3),
                 Constraint::Length(1),
                 Constraint::Length(2),
@@ -702,109 +772,95 @@ impl UpdatePatient {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, main_layout[0]);
 
-        let title_text = if self.editing {
-            "✍️  EDITING PATIENT"
-        } else {
-            "✍️  UPDATE PATIENT"
+        let title_text = match (self.editing, self.history.has_changes()) {
+            (true, _) => t("patients.update.editing_title"),
+            (false, true) => t("patients.update.unsaved_title"),
+            (false, false) => t("patients.update.title"),
         };
 
         let title = Paragraph::new(title_text)
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, main_layout[0]);
+        edit_warning::render(
+            frame,
+            Rect {
+                y: main_layout[0].y + 1,
+                height: 1,
+                ..main_layout[0]
+            },
+            self.edit_lock.as_ref(),
+        );
 
         let id_str = self.patient.id.to_string();
-        let gender_str = match self.patient.gender {
-            Gender::Male => "Male",
-            Gender::Female => "Female",
-            Gender::Other => "Other",
-        };
+        let gender_str = self.patient.gender.label();
         let email_str = self.patient.email.clone().unwrap_or_default();
         let medical_history_str = self.patient.medical_history.clone().unwrap_or_default();
         let allergies_str = self.patient.allergies.clone().unwrap_or_default();
         let medications_str = self.patient.current_medications.clone().unwrap_or_default();
 
-        let table_items = vec![
-            Row::new(vec!["ID", &id_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["First Name", &self.patient.first_name])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Last Name", &self.patient.last_name])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Date of Birth", &self.patient.date_of_birth])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Gender", &gender_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Phone", &self.patient.phone_number])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Address", &self.patient.address])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Email", &email_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Medical History", &medical_history_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Allergies", &allergies_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Medications", &medications_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+        let widths = [Constraint::Percentage(30), Constraint::Percentage(70)];
+        let columns = column_widths(main_layout[1].inner(Margin::new(1, 1)), &widths, 2, "► ");
+        let field = |label: &str, value: &str| {
+            Row::new(vec![cell(label, &columns, 0), cell(value, &columns, 1)])
+                .style(Style::default().fg(theme().text))
                 .height(1)
-                .bottom_margin(0),
+                .bottom_margin(0)
+        };
+
+        let table_items = vec![
+            field(t("common.id"), &id_str),
+            field(t("common.first_name"), &self.patient.first_name),
+            field(t("common.last_name"), &self.patient.last_name),
+            field(t("patients.date_of_birth"), &self.patient.date_of_birth),
+            field(t("common.gender"), gender_str),
+            field(t("patients.street"), &self.patient.address),
+            field(t("patients.postcode"), &self.patient.postcode),
+            field(t("patients.city"), &self.patient.city),
+            field(t("patients.region"), &self.patient.region),
+            field(t("common.phone"), &self.patient.phone_number),
+            field(t("patients.email"), &email_str),
+            field(t("patients.medical_history"), &medical_history_str),
+            field(t("patients.allergies"), &allergies_str),
+            field(t("patients.medications"), &medications_str),
+            field(
+                t("patients.contact_by"),
+                self.patient.contact_preference.label(),
+            ),
         ];
 
         let selected_style = Style::default()
-            .fg(Color::Rgb(250, 250, 110))
-            .bg(Color::Rgb(40, 40, 60))
+            .fg(theme().focus)
+            .bg(theme().highlight)
             .add_modifier(Modifier::BOLD);
 
-        let header = Row::new(vec!["Field", "Value"])
+        let header = Row::new(vec![t("common.field"), t("common.value")])
             .style(
                 Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(80, 60, 130))
+                    .fg(theme().text)
+                    .bg(theme().header)
                     .add_modifier(Modifier::BOLD),
             )
             .height(1);
 
-        let widths = [Constraint::Percentage(30), Constraint::Percentage(70)];
-
         let table = Table::new(table_items, widths)
             .header(header)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
-                    .title(" Patient Data ")
-                    .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
+                    .title(format!(" {} ", t("patients.update.data")))
+                    .border_style(Style::default().fg(theme().unfocused))
+                    .style(Style::default().bg(theme().input)),
             )
             .column_spacing(2)
             .row_highlight_style(selected_style)
@@ -813,72 +869,63 @@ impl UpdatePatient {
         frame.render_stateful_widget(table, main_layout[1], &mut self.edit_table_state.clone());
 
         let input_label = match self.selected_field {
-            Some(ID_INPUT) => "ID",
-            Some(FIRST_NAME_INPUT) => "First Name",
-            Some(LAST_NAME_INPUT) => "Last Name",
-            Some(DOB_INPUT) => "Date of Birth",
-            Some(GENDER_INPUT) => "Gender",
-            Some(ADDRESS_INPUT) => "Address",
-            Some(PHONE_INPUT) => "Phone",
-            Some(EMAIL_INPUT) => "Email",
-            Some(MEDICAL_HISTORY_INPUT) => "Medical History",
-            Some(ALLERGIES_INPUT) => "Allergies",
-            Some(MEDICATIONS_INPUT) => "Medications",
-            _ => "Field",
+            Some(ID_INPUT) => t("common.id"),
+            Some(FIRST_NAME_INPUT) => t("common.first_name"),
+            Some(LAST_NAME_INPUT) => t("common.last_name"),
+            Some(DOB_INPUT) => t("patients.date_of_birth"),
+            Some(GENDER_INPUT) => t("common.gender"),
+            Some(ADDRESS_INPUT) => t("patients.street"),
+            Some(POSTCODE_INPUT) => t("patients.postcode"),
+            Some(CITY_INPUT) => t("patients.city"),
+            Some(REGION_INPUT) => t("patients.region"),
+            Some(PHONE_INPUT) => t("common.phone"),
+            Some(EMAIL_INPUT) => t("patients.email"),
+            Some(MEDICAL_HISTORY_INPUT) => t("patients.medical_history"),
+            Some(ALLERGIES_INPUT) => t("patients.allergies"),
+            Some(MEDICATIONS_INPUT) => t("patients.medications"),
+            Some(CONTACT_INPUT) => t("patients.contact_by_field"),
+            _ => t("common.field"),
         };
 
-        let input_block = Block::default()
+        let mut input_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(format!(
-                " {} {} ",
-                if self.editing { "Editing" } else { "Selected" },
-                input_label
+                " {} ",
+                if self.editing {
+                    tf("common.editing_field", &[("field", &input_label)])
+                } else {
+                    tf("common.selected_field", &[("field", &input_label)])
+                }
             ))
-            .border_style(if self.editing {
-                Style::default().fg(Color::Rgb(140, 219, 140))
+            .border_style(if self.input_error.is_some() {
+                Style::default().fg(theme().error)
+            } else if self.editing {
+                Style::default().fg(theme().success)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
+        if let Some(error) = &self.input_error {
+            input_block = input_block.title_bottom(Line::styled(
+                format!(" ⚠ {} ", error),
+                Style::default().fg(theme().error),
+            ));
+        }
 
         let input_paragraph = Paragraph::new(self.input_value.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
+            .style(Style::default().fg(theme().text).bg(theme().input))
             .block(input_block);
         frame.render_widget(input_paragraph, main_layout[2]);
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(255, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, main_layout[3]);
-        } else if let Some(success) = &self.success_message {
-            let success_paragraph = Paragraph::new(success.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, main_layout[3]);
-        }
-
         let help_text = if self.editing {
-            "Enter: Save Changes | Esc: Cancel Editing"
+            t("common.edit_help")
         } else {
-            "↑/↓: Navigate | E: Edit Field | Ctrl+S: Save Patient | Esc: Back"
+            t("patients.update.help")
         };
 
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, main_layout[4]);
     }
@@ -896,16 +943,16 @@ impl UpdatePatient {
         frame.render_widget(Clear, dialog_area);
 
         let dialog_block = Block::default()
-            .title(" Update Patient ")
+            .title(format!(" {} ", t("patients.update.update_title")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-            .style(Style::default().bg(Color::Rgb(30, 30, 46)));
+            .border_style(Style::default().fg(theme().unfocused))
+            .style(Style::default().bg(theme().dialog));
 
         frame.render_widget(dialog_block.clone(), dialog_area);
 
@@ -917,7 +964,7 @@ impl UpdatePatient {
             .split(inner_area);
 
         let message = Paragraph::new(self.confirmation_message.as_str())
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .add_modifier(Modifier::BOLD)
             .alignment(Alignment::Center);
         frame.render_widget(message, content_layout[0]);
@@ -929,28 +976,28 @@ impl UpdatePatient {
 
         let yes_style = if self.confirmation_selected == 0 {
             Style::default()
-                .fg(Color::Rgb(140, 219, 140))
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
         let no_style = if self.confirmation_selected == 1 {
             Style::default()
-                .fg(Color::Rgb(255, 100, 100))
+                .fg(theme().error)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let yes_text = if self.confirmation_selected == 0 {
-            "► Yes ◄"
+            format!("► {} ◄", t("common.yes"))
         } else {
-            "  Yes  "
+            format!("  {}  ", t("common.yes"))
         };
         let no_text = if self.confirmation_selected == 1 {
-            "► No ◄"
+            format!("► {} ◄", t("common.no"))
         } else {
-            "  No  "
+            format!("  {}  ", t("common.no"))
         };
 
         let yes_button = Paragraph::new(yes_text)
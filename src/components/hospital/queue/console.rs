@@ -0,0 +1,341 @@
+use crate::app::SelectedApp;
+use crate::components::hospital::queue::token_status_text;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::feedback::FeedbackPopup;
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{Patient, QueueToken, StaffMember, StaffRole, TokenStatus};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime::{self, now_text, today_text};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+
+pub struct DoctorConsole {
+    db: Db,
+    doctors: Vec<StaffMember>,
+    patients: HashMap<i64, Patient>,
+    doctor_index: usize,
+    tokens: Vec<QueueToken>,
+    feedback: Option<FeedbackPopup>,
+}
+
+impl DoctorConsole {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            doctors: Vec::new(),
+            patients: HashMap::new(),
+            doctor_index: 0,
+            tokens: Vec::new(),
+            feedback: None,
+        }
+    }
+
+    pub fn fetch_tokens(&mut self) -> Result<()> {
+        self.doctors = db::get_all_staff(&self.db)?
+            .into_iter()
+            .filter(|s| s.role == StaffRole::Doctor)
+            .collect();
+        if self.doctor_index >= self.doctors.len() {
+            self.doctor_index = 0;
+        }
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        self.tokens = match self.doctors.get(self.doctor_index) {
+            Some(doctor) => db::get_queue_tokens_for_day(&self.db, &today_text())?
+                .into_iter()
+                .filter(|t| t.doctor_id == doctor.id)
+                .collect(),
+            None => Vec::new(),
+        };
+        Ok(())
+    }
+
+    fn patient_name(&self, patient_id: i64) -> String {
+        self.patients
+            .get(&patient_id)
+            .map(|p| format!("{} {}", p.first_name, p.last_name))
+            .unwrap_or_else(|| tf("common.unknown_number", &[("id", &patient_id)]))
+    }
+
+    fn serving_token(&self) -> Option<&QueueToken> {
+        self.tokens
+            .iter()
+            .find(|t| t.status == TokenStatus::Serving)
+    }
+
+    fn call_next(&mut self) -> Result<()> {
+        let Some(doctor_id) = self.doctors.get(self.doctor_index).map(|d| d.id) else {
+            return Ok(());
+        };
+        match db::call_next_queue_token(&self.db, doctor_id, &today_text(), &now_text())? {
+            Some(token) => {
+                let name = self.patient_name(token.patient_id);
+                toast::push(Toast::success(tf(
+                    "queue.calling",
+                    &[
+                        ("token", &format!("{:03}", token.token_number)),
+                        ("patient", &name),
+                    ],
+                )));
+            }
+            None => toast::push(Toast::error(t("queue.no_more"))),
+        }
+        self.fetch_tokens()
+    }
+
+    fn close_serving(&mut self, status: TokenStatus) -> Result<()> {
+        let Some(token) = self.serving_token().cloned() else {
+            toast::push(Toast::error(t("queue.none_serving")));
+            return Ok(());
+        };
+        db::update_queue_token_status(&self.db, token.id, status)?;
+        toast::push(Toast::success(tf(
+            "queue.marked",
+            &[
+                ("token", &format!("{:03}", token.token_number)),
+                ("status", &token_status_text(status)),
+            ],
+        )));
+        self.fetch_tokens()
+    }
+
+    /// Takes the rating of the patient seen last, the latest token
+    /// completed today.
+    fn open_feedback(&mut self) -> Result<()> {
+        let Some(token) = self
+            .tokens
+            .iter()
+            .filter(|t| t.status == TokenStatus::Done)
+            .max_by(|a, b| a.called_at.cmp(&b.called_at))
+        else {
+            toast::push(Toast::error(t("queue.none_completed")));
+            return Ok(());
+        };
+        let subject = tf(
+            "queue.feedback_subject",
+            &[
+                ("token", &format!("{:03}", token.token_number)),
+                ("patient", &self.patient_name(token.patient_id)),
+            ],
+        );
+        self.feedback = Some(FeedbackPopup::new(
+            &self.db,
+            token.patient_id,
+            token.doctor_id,
+            None,
+            Some(token.id),
+            subject,
+        )?);
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(popup) = self.feedback.as_mut() {
+            if popup.handle_key(key).is_some() {
+                self.feedback = None;
+            }
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Left | KeyCode::Right if !self.doctors.is_empty() => {
+                let len = self.doctors.len();
+                self.doctor_index = if key.code == KeyCode::Right {
+                    (self.doctor_index + 1) % len
+                } else {
+                    (self.doctor_index + len - 1) % len
+                };
+                self.fetch_tokens()?;
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Enter => self.call_next()?,
+            KeyCode::Char('c') | KeyCode::Char('C') => self.close_serving(TokenStatus::Done)?,
+            KeyCode::Char('s') | KeyCode::Char('S') => self.close_serving(TokenStatus::Skipped)?,
+            KeyCode::Char('f') | KeyCode::Char('F') => self.open_feedback()?,
+            KeyCode::Char('r') | KeyCode::Char('R') => self.fetch_tokens()?,
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+impl Component for DoctorConsole {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(5),
+                Constraint::Min(8),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(t("queue.console_title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let doctor_text = match self.doctors.get(self.doctor_index) {
+            Some(d) => tf(
+                "queue.doctor_day",
+                &[
+                    ("name", &d.name),
+                    ("date", &datetime::display_date(datetime::today())),
+                ],
+            ),
+            None => t("queue.no_doctors").to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(doctor_text)
+                .style(Style::default().fg(theme().accent))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("queue.console_for")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[1],
+        );
+
+        let serving_lines = match self.serving_token() {
+            Some(token) => vec![
+                Line::from(Span::styled(
+                    tf(
+                        "queue.token",
+                        &[("token", &format!("{:03}", token.token_number))],
+                    ),
+                    Style::default()
+                        .fg(theme().focus)
+                        .add_modifier(Modifier::BOLD),
+                )),
+                Line::from(self.patient_name(token.patient_id)),
+            ],
+            None => vec![Line::from(t("queue.nobody"))],
+        };
+        frame.render_widget(
+            Paragraph::new(serving_lines)
+                .style(Style::default().fg(theme().text))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("queue.now_serving")))
+                        .title_alignment(Alignment::Center)
+                        .border_style(Style::default().fg(theme().success))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[2],
+        );
+
+        let waiting = self
+            .tokens
+            .iter()
+            .filter(|t| t.status == TokenStatus::Waiting)
+            .count();
+        let widths = [
+            Constraint::Length(7),
+            Constraint::Percentage(40),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Min(10),
+        ];
+        let columns = column_widths(layout[3].inner(Margin::new(1, 1)), &widths, 1, "");
+
+        let rows = self.tokens.iter().map(|t| {
+            let style = match t.status {
+                TokenStatus::Waiting => Style::default().fg(theme().text),
+                TokenStatus::Serving => Style::default()
+                    .fg(theme().focus)
+                    .add_modifier(Modifier::BOLD),
+                TokenStatus::Done => Style::default().fg(theme().success),
+                TokenStatus::Skipped => Style::default().fg(theme().inactive),
+            };
+            Row::new(vec![
+                cell(format!("{:03}", t.token_number), &columns, 0),
+                cell(self.patient_name(t.patient_id), &columns, 1),
+                cell(datetime::time_part(&t.issued_at), &columns, 2),
+                cell(
+                    t.called_at
+                        .as_deref()
+                        .map_or("-".to_string(), datetime::time_part),
+                    &columns,
+                    3,
+                ),
+                cell(token_status_text(t.status), &columns, 4),
+            ])
+            .style(style)
+        });
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec![
+                    t("queue.column.token"),
+                    t("queue.column.patient"),
+                    t("queue.column.issued"),
+                    t("queue.column.called"),
+                    t("queue.column.status"),
+                ])
+                .style(Style::default().bg(theme().header).fg(theme().title)),
+            )
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(
+                        " {} ",
+                        tf("queue.todays_queue", &[("count", &waiting)])
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            );
+        frame.render_widget(table, layout[3]);
+
+        frame.render_widget(
+            Paragraph::new(t("queue.console_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[5],
+        );
+
+        if let Some(popup) = &self.feedback {
+            popup.render_popup(frame);
+        }
+    }
+}
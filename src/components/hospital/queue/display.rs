@@ -0,0 +1,229 @@
+use crate::app::SelectedApp;
+use crate::components::Component;
+use crate::db::worker::{self, Task};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{QueueToken, StaffMember, StaffRole, TokenStatus};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime::{self, today_text};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::time::{Duration, Instant};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(3);
+const CARDS_PER_ROW: usize = 3;
+
+/// Today's tokens plus the doctors who have at least one of them.
+fn load_board(db: &Db, day: &str) -> Result<(Vec<QueueToken>, Vec<StaffMember>)> {
+    let tokens = db::get_queue_tokens_for_day(db, day)?;
+    let doctors = db::get_all_staff(db)?
+        .into_iter()
+        .filter(|s| s.role == StaffRole::Doctor)
+        .filter(|s| tokens.iter().any(|t| t.doctor_id == s.id))
+        .collect();
+    Ok((tokens, doctors))
+}
+
+pub struct DisplayBoard {
+    db: Db,
+    doctors: Vec<StaffMember>,
+    tokens: Vec<QueueToken>,
+    last_refresh: Option<Instant>,
+    error_message: Option<String>,
+    pending: Option<Task<(Vec<QueueToken>, Vec<StaffMember>)>>,
+}
+
+impl DisplayBoard {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            doctors: Vec::new(),
+            tokens: Vec::new(),
+            last_refresh: None,
+            error_message: None,
+            pending: None,
+        }
+    }
+
+    pub fn fetch_tokens(&mut self) -> Result<()> {
+        self.last_refresh = Some(Instant::now());
+        (self.tokens, self.doctors) = load_board(&self.db, &today_text())?;
+        Ok(())
+    }
+
+    /// Polls the queue on the database worker so a slow query never stalls the board.
+    pub fn tick(&mut self) {
+        if let Some(pending) = &self.pending {
+            let Some(result) = pending.poll() else {
+                return;
+            };
+            self.pending = None;
+            match result {
+                Ok((tokens, doctors)) => {
+                    self.tokens = tokens;
+                    self.doctors = doctors;
+                    self.error_message = None;
+                }
+                Err(e) => self.error_message = Some(tf("queue.refresh_failed", &[("error", &e)])),
+            }
+        }
+        if self
+            .last_refresh
+            .is_some_and(|t| t.elapsed() < REFRESH_INTERVAL)
+        {
+            return;
+        }
+        self.last_refresh = Some(Instant::now());
+        let day = today_text();
+        let db = self.db.clone();
+        self.pending = Some(worker::spawn(move || load_board(&db, &day)));
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        match key.code {
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            KeyCode::Char('r') | KeyCode::Char('R') => self.fetch_tokens()?,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_card(&self, frame: &mut Frame, area: Rect, doctor: &StaffMember) {
+        let doctor_tokens = self.tokens.iter().filter(|t| t.doctor_id == doctor.id);
+        let serving = doctor_tokens
+            .clone()
+            .find(|t| t.status == TokenStatus::Serving)
+            .map(|t| format!("{:03}", t.token_number))
+            .unwrap_or_else(|| "---".to_string());
+        let upcoming: Vec<String> = doctor_tokens
+            .filter(|t| t.status == TokenStatus::Waiting)
+            .take(5)
+            .map(|t| format!("{:03}", t.token_number))
+            .collect();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .title(format!(
+                " {} ",
+                tf("common.doctor", &[("name", &doctor.name)])
+            ))
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().accent))
+            .style(Style::default().bg(theme().surface));
+
+        let lines = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                t("queue.now_serving_big"),
+                Style::default().fg(theme().inactive),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                serving,
+                Style::default()
+                    .fg(theme().focus)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                if upcoming.is_empty() {
+                    t("queue.no_one_waiting").to_string()
+                } else {
+                    tf("queue.next", &[("list", &(upcoming.join("  ")))])
+                },
+                Style::default().fg(theme().text),
+            )),
+        ];
+        frame.render_widget(
+            Paragraph::new(lines)
+                .alignment(Alignment::Center)
+                .block(block),
+            area,
+        );
+    }
+}
+
+impl Component for DisplayBoard {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn tick(&mut self) {
+        self.tick();
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(10),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(tf(
+                "queue.clinic_title",
+                &[("date", &datetime::display_date(datetime::today()))],
+            ))
+            .style(
+                Style::default()
+                    .fg(theme().title)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::BOTTOM)
+                    .border_style(Style::default().fg(theme().border)),
+            ),
+            layout[0],
+        );
+
+        if self.doctors.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("queue.no_tokens"))
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center),
+                layout[1],
+            );
+        } else {
+            let row_count = self.doctors.len().div_ceil(CARDS_PER_ROW);
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![Constraint::Ratio(1, row_count as u32); row_count])
+                .split(layout[1]);
+            for (row_area, doctors) in rows.iter().zip(self.doctors.chunks(CARDS_PER_ROW)) {
+                let cards = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(vec![
+                        Constraint::Ratio(1, CARDS_PER_ROW as u32);
+                        CARDS_PER_ROW
+                    ])
+                    .split(*row_area);
+                for (card_area, doctor) in cards.iter().zip(doctors) {
+                    self.render_card(frame, *card_area, doctor);
+                }
+            }
+        }
+
+        let footer = match &self.error_message {
+            Some(error) => {
+                Paragraph::new(format!("⚠️ {}", error)).style(Style::default().fg(theme().error))
+            }
+            None => Paragraph::new(t("queue.please_wait")).style(Style::default().fg(theme().help)),
+        };
+        frame.render_widget(footer.alignment(Alignment::Center), layout[2]);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::app::SelectedApp;
+use crate::components::Component;
+use crate::db::Db;
+use crate::i18n::t;
+use crate::models::TokenStatus;
+use crate::tui::Frame;
+use anyhow::Result;
+use crossterm::event::KeyEvent;
+
+pub mod console;
+pub mod display;
+pub mod reception;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueState {
+    Reception,
+    Console,
+    Display,
+}
+
+pub struct Queue {
+    pub reception: reception::IssueToken,
+    pub console: console::DoctorConsole,
+    pub display: display::DisplayBoard,
+    pub state: QueueState,
+}
+
+impl Queue {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            reception: reception::IssueToken::new(db),
+            console: console::DoctorConsole::new(db),
+            display: display::DisplayBoard::new(db),
+            state: QueueState::Reception,
+        }
+    }
+
+    pub fn initialize_list(&mut self) -> Result<()> {
+        match self.state {
+            QueueState::Reception => self.reception.load_data()?,
+            QueueState::Console => self.console.fetch_tokens()?,
+            QueueState::Display => self.display.fetch_tokens()?,
+        }
+        Ok(())
+    }
+}
+
+pub fn token_status_text(status: TokenStatus) -> &'static str {
+    match status {
+        TokenStatus::Waiting => t("queue.status.waiting"),
+        TokenStatus::Serving => t("queue.status.serving"),
+        TokenStatus::Done => t("queue.status.done"),
+        TokenStatus::Skipped => t("queue.status.skipped"),
+    }
+}
+
+impl Component for Queue {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        let action = match self.state {
+            QueueState::Reception => self.reception.handle_input(event)?,
+            QueueState::Console => self.console.handle_input(event)?,
+            QueueState::Display => self.display.handle_input(event)?,
+        };
+        if let Some(SelectedApp::None) = action {
+            return Ok(Some(SelectedApp::None));
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        match self.state {
+            QueueState::Reception => self.reception.render(frame),
+            QueueState::Console => self.console.render(frame),
+            QueueState::Display => self.display.render(frame),
+        }
+    }
+
+    fn tick(&mut self) {
+        match self.state {
+            QueueState::Reception => self.reception.tick(),
+            QueueState::Console => self.console.tick(),
+            QueueState::Display => self.display.tick(),
+        }
+    }
+}
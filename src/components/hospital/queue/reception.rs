@@ -0,0 +1,326 @@
+use crate::app::SelectedApp;
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{Patient, QueueToken, StaffMember, StaffRole, TokenStatus};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime::{display_stamp, now_text, today_text};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+
+const PATIENT_INPUT: usize = 0;
+const DOCTOR_SELECT: usize = 1;
+const ISSUE_BUTTON: usize = 2;
+const BACK_BUTTON: usize = 3;
+
+pub struct IssueToken {
+    db: Db,
+    patients: HashMap<i64, Patient>,
+    doctors: Vec<StaffMember>,
+    waiting_counts: HashMap<i64, usize>,
+    patient_id_input: String,
+    doctor_index: usize,
+    focus_index: usize,
+    last_token: Option<QueueToken>,
+}
+
+impl IssueToken {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            patients: HashMap::new(),
+            doctors: Vec::new(),
+            waiting_counts: HashMap::new(),
+            patient_id_input: String::new(),
+            doctor_index: 0,
+            focus_index: PATIENT_INPUT,
+            last_token: None,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        self.doctors = db::get_all_staff(&self.db)?
+            .into_iter()
+            .filter(|s| s.role == StaffRole::Doctor)
+            .collect();
+        if self.doctor_index >= self.doctors.len() {
+            self.doctor_index = 0;
+        }
+        self.waiting_counts.clear();
+        for token in db::get_queue_tokens_for_day(&self.db, &today_text())? {
+            if token.status == TokenStatus::Waiting {
+                *self.waiting_counts.entry(token.doctor_id).or_insert(0) += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn resolved_patient(&self) -> Option<&Patient> {
+        self.patient_id_input
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|id| self.patients.get(&id))
+    }
+
+    fn issue(&mut self) -> Result<()> {
+        let Some(patient_id) = self.resolved_patient().map(|p| p.id) else {
+            toast::push(Toast::error(t("common.unknown_patient")));
+            return Ok(());
+        };
+        let Some(doctor) = self.doctors.get(self.doctor_index) else {
+            toast::push(Toast::error(t("queue.no_doctors_available")));
+            return Ok(());
+        };
+
+        match db::issue_queue_token(&self.db, patient_id, doctor.id, &today_text(), &now_text()) {
+            Ok(token) => {
+                self.last_token = Some(token);
+                self.patient_id_input.clear();
+                self.focus_index = PATIENT_INPUT;
+                self.load_data()?;
+            }
+            Err(e) => toast::push(Toast::error(tf("queue.issue_failed", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        match key.code {
+            KeyCode::Left | KeyCode::Right
+                if self.focus_index == DOCTOR_SELECT && !self.doctors.is_empty() =>
+            {
+                let len = self.doctors.len();
+                self.doctor_index = if key.code == KeyCode::Right {
+                    (self.doctor_index + 1) % len
+                } else {
+                    (self.doctor_index + len - 1) % len
+                };
+            }
+            KeyCode::Char(c) if self.focus_index == PATIENT_INPUT => {
+                self.patient_id_input.push(c);
+            }
+            KeyCode::Backspace if self.focus_index == PATIENT_INPUT => {
+                self.patient_id_input.pop();
+            }
+            KeyCode::Tab | KeyCode::Down => {
+                self.focus_index = (self.focus_index + 1) % (BACK_BUTTON + 1);
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                self.focus_index = (self.focus_index + BACK_BUTTON) % (BACK_BUTTON + 1);
+            }
+            KeyCode::Enter => match self.focus_index {
+                ISSUE_BUTTON => self.issue()?,
+                BACK_BUTTON => return Ok(Some(SelectedApp::None)),
+                _ => self.focus_index += 1,
+            },
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+impl Component for IssueToken {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(7),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(t("queue.reception_title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let field_style = |focused: bool| {
+            if focused {
+                Style::default().fg(theme().focus)
+            } else {
+                Style::default().fg(theme().unfocused)
+            }
+        };
+
+        let patient_label = match self.resolved_patient() {
+            Some(p) => format!(
+                " {} ",
+                tf(
+                    "queue.patient_named",
+                    &[("first", &p.first_name), ("last", &p.last_name)]
+                )
+            ),
+            None => format!(" {} ", t("common.patient_id")),
+        };
+        frame.render_widget(
+            Paragraph::new(self.patient_id_input.as_str())
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(patient_label)
+                        .border_style(field_style(self.focus_index == PATIENT_INPUT))
+                        .style(Style::default().bg(theme().input)),
+                ),
+            layout[1],
+        );
+
+        let doctor_text = match self.doctors.get(self.doctor_index) {
+            Some(d) => tf(
+                "queue.doctor_waiting",
+                &[
+                    ("name", &d.name),
+                    (
+                        "count",
+                        &self.waiting_counts.get(&d.id).copied().unwrap_or(0),
+                    ),
+                ],
+            ),
+            None => t("queue.no_doctors").to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(doctor_text)
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("queue.clinic_doctor")))
+                        .border_style(field_style(self.focus_index == DOCTOR_SELECT))
+                        .style(Style::default().bg(theme().input)),
+                ),
+            layout[2],
+        );
+
+        let issue_style = if self.focus_index == ISSUE_BUTTON {
+            Style::default()
+                .fg(theme().success)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        frame.render_widget(
+            Paragraph::new(if self.focus_index == ISSUE_BUTTON {
+                format!("► {} ◄", t("queue.issue"))
+            } else {
+                format!("  {}  ", t("home.queue.issue"))
+            })
+            .style(issue_style)
+            .alignment(Alignment::Center),
+            layout[4],
+        );
+
+        let back_style = if self.focus_index == BACK_BUTTON {
+            Style::default()
+                .fg(theme().accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        frame.render_widget(
+            Paragraph::new(if self.focus_index == BACK_BUTTON {
+                format!("► {} ◄", t("common.back"))
+            } else {
+                format!("  {}  ", t("common.back"))
+            })
+            .style(back_style)
+            .alignment(Alignment::Center),
+            layout[5],
+        );
+
+        if let Some(token) = &self.last_token {
+            let doctor = self
+                .doctors
+                .iter()
+                .find(|d| d.id == token.doctor_id)
+                .map(|d| d.name.clone())
+                .unwrap_or_default();
+            let patient = self
+                .patients
+                .get(&token.patient_id)
+                .map(|p| format!("{} {}", p.first_name, p.last_name))
+                .unwrap_or_default();
+            let ticket = Paragraph::new(vec![
+                Line::from(""),
+                Line::from(Span::styled(
+                    tf(
+                        "queue.token",
+                        &[("token", &format!("{:03}", token.token_number))],
+                    ),
+                    Style::default()
+                        .fg(theme().focus)
+                        .add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+                Line::from(tf(
+                    "queue.to_doctor",
+                    &[("patient", &patient), ("doctor", &doctor)],
+                )),
+                Line::from(tf(
+                    "queue.issued_at",
+                    &[("time", &display_stamp(&token.issued_at))],
+                )),
+            ])
+            .style(Style::default().fg(theme().text))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("queue.last_issued")))
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            );
+            frame.render_widget(ticket, layout[6]);
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("queue.reception_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[8],
+        );
+    }
+}
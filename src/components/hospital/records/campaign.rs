@@ -0,0 +1,564 @@
+//! Campaign mode for flu-shot drives and screening days: one vaccine and
+//! record template, given to a set of patients picked from a list, saved for
+//! all of them in one pass.
+
+use crate::app::SelectedApp;
+use crate::components::form::{date, Field, Form};
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::text_input::TextInput;
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{Immunization, MedicalRecord, Patient};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state;
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::{BTreeSet, HashMap};
+
+const CAMPAIGN: usize = 0;
+const VACCINE: usize = 1;
+const DOSE: usize = 2;
+const LOT_NUMBER: usize = 3;
+const GIVEN_ON: usize = 4;
+const DIAGNOSIS: usize = 5;
+const NOTES: usize = 6;
+
+fn template_form() -> Form {
+    Form::new(vec![
+        Field::new(t("campaign.name")),
+        Field::new(t("campaign.vaccine")).required(),
+        Field::new(t("campaign.dose_field")),
+        Field::new(t("campaign.lot")),
+        Field::new(t("campaign.given_on"))
+            .required()
+            .validate_with(Box::new(date)),
+        Field::new(t("campaign.diagnosis")).required(),
+        Field::new(t("campaign.notes")),
+    ])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Template,
+    Patients,
+    Confirm,
+}
+
+pub struct VaccinationCampaign {
+    db: Db,
+    step: Step,
+    form: Form,
+    focus_index: usize,
+    patients: Vec<Patient>,
+    filtered_patients: Vec<Patient>,
+    search_input: TextInput,
+    is_searching: bool,
+    state: TableState,
+    /// The IDs of the patients ticked for the campaign.
+    picked: BTreeSet<i64>,
+    /// When each patient last had the template's vaccine, by patient ID.
+    last_given: HashMap<i64, String>,
+}
+
+impl VaccinationCampaign {
+    pub fn new(db: &Db) -> Self {
+        let mut campaign = Self {
+            db: db.clone(),
+            step: Step::Template,
+            form: template_form(),
+            focus_index: CAMPAIGN,
+            patients: Vec::new(),
+            filtered_patients: Vec::new(),
+            search_input: TextInput::new(),
+            is_searching: false,
+            state: TableState::default(),
+            picked: BTreeSet::new(),
+            last_given: HashMap::new(),
+        };
+        campaign.reset_template();
+        campaign
+    }
+
+    fn reset_template(&mut self) {
+        self.form.clear();
+        self.form.fields[GIVEN_ON].set(datetime::today_text());
+        self.form.fields[DIAGNOSIS].set("Immunization");
+        self.form.mark_clean();
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.patients = db::get_all_patients(&self.db)?;
+        self.patients.sort_by_key(|p| p.id);
+        self.load_last_given()?;
+        self.filter_patients();
+        Ok(())
+    }
+
+    fn load_last_given(&mut self) -> Result<()> {
+        let vaccine = self.form.value(VACCINE);
+        self.last_given = if vaccine.trim().is_empty() {
+            HashMap::new()
+        } else {
+            db::get_last_immunizations(&self.db, &vaccine)?
+                .into_iter()
+                .collect()
+        };
+        Ok(())
+    }
+
+    fn filter_patients(&mut self) {
+        let term = self.search_input.value().to_lowercase();
+        self.filtered_patients = self
+            .patients
+            .iter()
+            .filter(|p| {
+                term.is_empty()
+                    || format!("{} {}", p.first_name, p.last_name)
+                        .to_lowercase()
+                        .contains(&term)
+                    || p.id.to_string().contains(&term)
+            })
+            .cloned()
+            .collect();
+        self.state
+            .select((!self.filtered_patients.is_empty()).then_some(0));
+    }
+
+    fn selected_patient(&self) -> Option<&Patient> {
+        self.filtered_patients.get(self.state.selected()?)
+    }
+
+    fn toggle_selected(&mut self) {
+        let Some(id) = self.selected_patient().map(|p| p.id) else {
+            return;
+        };
+        if !self.picked.remove(&id) {
+            self.picked.insert(id);
+        }
+        let len = self.filtered_patients.len();
+        let next = self.state.selected().map_or(0, |i| (i + 1).min(len - 1));
+        self.state.select(Some(next));
+    }
+
+    /// Ticks every patient shown, or unticks them all when they already are.
+    fn toggle_all_shown(&mut self) {
+        let shown: Vec<i64> = self.filtered_patients.iter().map(|p| p.id).collect();
+        if shown.iter().all(|id| self.picked.contains(id)) {
+            for id in &shown {
+                self.picked.remove(id);
+            }
+        } else {
+            self.picked.extend(shown);
+        }
+    }
+
+    /// Unticks the patients who already had the vaccine on the campaign day.
+    fn untick_already_given(&mut self) {
+        let given_on = self.form.value(GIVEN_ON);
+        let before = self.picked.len();
+        self.picked
+            .retain(|id| self.last_given.get(id) != Some(&given_on));
+        let removed = before - self.picked.len();
+        if removed > 0 {
+            toast::push(Toast::success(tf(
+                "campaign.unticked",
+                &[("count", &removed), ("vaccine", &self.form.value(VACCINE))],
+            )));
+        }
+    }
+
+    fn submit_template(&mut self) -> Result<()> {
+        if let Some(invalid) = self.form.validate() {
+            self.focus_index = invalid;
+            return Ok(());
+        }
+        self.load_last_given()?;
+        self.step = Step::Patients;
+        Ok(())
+    }
+
+    fn save(&mut self) -> Result<()> {
+        let author_id = ui_state::current_user()
+            .and_then(|username| db::get_user_staff_id(&self.db, &username).ok().flatten());
+        let vaccine = self.form.value(VACCINE).trim().to_string();
+        let dose = self.form.optional_value(DOSE);
+        let campaign = self.form.optional_value(CAMPAIGN);
+        let given_on = self.form.value(GIVEN_ON);
+
+        let mut doctor_notes = format!("{} given", vaccine);
+        if let Some(dose) = &dose {
+            doctor_notes.push_str(&format!(" (dose {})", dose));
+        }
+        if let Some(campaign) = &campaign {
+            doctor_notes.push_str(&format!(" during {}", campaign));
+        }
+        doctor_notes.push_str(&format!(" on {}.", given_on));
+        if let Some(notes) = self.form.optional_value(NOTES) {
+            doctor_notes.push(' ');
+            doctor_notes.push_str(&notes);
+        }
+        let record = MedicalRecord {
+            id: 0,
+            patient_id: 0,
+            doctor_notes,
+            nurse_notes: None,
+            diagnosis: self.form.value(DIAGNOSIS),
+            prescription: Some(vaccine.clone()),
+            procedures: None,
+            author_id,
+        };
+        let immunization = Immunization {
+            id: 0,
+            patient_id: 0,
+            medical_record_id: None,
+            vaccine: vaccine.clone(),
+            dose,
+            lot_number: self.form.optional_value(LOT_NUMBER),
+            given_on,
+            given_by: author_id,
+            campaign,
+        };
+
+        let patient_ids: Vec<i64> = self.picked.iter().copied().collect();
+        match db::record_immunizations(&self.db, &patient_ids, &record, &immunization) {
+            Ok(count) => {
+                toast::push(Toast::success(tf(
+                    "campaign.recorded",
+                    &[("what", &vaccine), ("count", &count)],
+                )));
+                self.picked.clear();
+                self.load_last_given()?;
+                self.step = Step::Patients;
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("campaign.failed", &[("error", &e)])));
+                self.step = Step::Patients;
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_template_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        let len = self.form.len();
+        match key.code {
+            KeyCode::Esc => {
+                self.picked.clear();
+                self.reset_template();
+                self.focus_index = CAMPAIGN;
+                return Ok(Some(SelectedApp::None));
+            }
+            KeyCode::Tab | KeyCode::Down => self.focus_index = (self.focus_index + 1) % len,
+            KeyCode::BackTab | KeyCode::Up => self.focus_index = (self.focus_index + len - 1) % len,
+            KeyCode::Enter if self.focus_index + 1 < len => self.focus_index += 1,
+            KeyCode::Enter => self.submit_template()?,
+            _ => {
+                self.form.handle_key(self.focus_index, key);
+            }
+        }
+        Ok(None)
+    }
+
+    fn handle_patients_input(&mut self, key: KeyEvent) -> Result<()> {
+        if self.is_searching {
+            match key.code {
+                _ if self.search_input.handle_key(key) => self.filter_patients(),
+                KeyCode::Enter | KeyCode::Down | KeyCode::Esc => self.is_searching = false,
+                _ => {}
+            }
+            return Ok(());
+        }
+        let len = self.filtered_patients.len();
+        match key.code {
+            KeyCode::Esc => self.step = Step::Template,
+            KeyCode::Char('/') | KeyCode::Char('s') => self.is_searching = true,
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char(' ') => self.toggle_selected(),
+            KeyCode::Char('a') | KeyCode::Char('A') => self.toggle_all_shown(),
+            KeyCode::Char('u') | KeyCode::Char('U') => self.untick_already_given(),
+            KeyCode::Enter if self.picked.is_empty() => {
+                toast::push(Toast::error(t("campaign.tick_one")));
+            }
+            KeyCode::Enter => self.step = Step::Confirm,
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                self.load_data()?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        match self.step {
+            Step::Template => return self.handle_template_input(key),
+            Step::Patients => self.handle_patients_input(key)?,
+            Step::Confirm => match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => self.save()?,
+                _ => self.step = Step::Patients,
+            },
+        }
+        Ok(None)
+    }
+
+    fn render_template(&self, frame: &mut Frame, area: Rect) {
+        let mut constraints = vec![Constraint::Length(3); self.form.len()];
+        constraints.push(Constraint::Min(0));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .horizontal_margin(3)
+            .split(area);
+        for index in 0..self.form.len() {
+            self.form
+                .render_field(frame, index, chunks[index], self.focus_index);
+        }
+    }
+
+    fn render_patients(&self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(5)])
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(self.search_input.line(self.is_searching))
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("common.search_patients")))
+                        .border_style(Style::default().fg(if self.is_searching {
+                            theme().focus
+                        } else {
+                            theme().border
+                        }))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[0],
+        );
+
+        let vaccine = self.form.value(VACCINE);
+        let last_header = tf("campaign.last", &[("vaccine", &vaccine.trim())]);
+        let widths = [
+            Constraint::Length(3),
+            Constraint::Length(6),
+            Constraint::Percentage(30),
+            Constraint::Length(13),
+            Constraint::Length(15),
+            Constraint::Min(14),
+        ];
+        let columns = column_widths(layout[1].inner(Margin::new(1, 1)), &widths, 1, "► ");
+        let given_on = self.form.value(GIVEN_ON);
+        let rows = self.filtered_patients.iter().map(|p| {
+            let picked = self.picked.contains(&p.id);
+            let last = self.last_given.get(&p.id);
+            let values = [
+                if picked { "[x]" } else { "[ ]" }.to_string(),
+                p.id.to_string(),
+                format!("{} {}", p.first_name, p.last_name),
+                p.date_of_birth.clone(),
+                p.phone_number.clone(),
+                last.cloned().unwrap_or_else(|| "-".to_string()),
+            ];
+            let style = if last == Some(&given_on) {
+                Style::default().fg(theme().inactive)
+            } else if picked {
+                Style::default().fg(theme().success)
+            } else {
+                Style::default().fg(theme().text)
+            };
+            Row::new(
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(column, text)| cell(text, &columns, column))
+                    .collect::<Vec<_>>(),
+            )
+            .style(style)
+        });
+        let header = Row::new(vec![
+            "",
+            t("common.id"),
+            t("campaign.patient"),
+            t("patients.date_of_birth"),
+            t("common.phone"),
+            last_header.as_str(),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(
+                        " {} ",
+                        tf(
+                            "campaign.ticked",
+                            &[
+                                ("ticked", &self.picked.len()),
+                                ("total", &self.patients.len())
+                            ]
+                        )
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .row_highlight_style(
+                Style::default()
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, layout[1], &mut self.state.clone());
+    }
+
+    fn render_confirm(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let width = 80.min(area.width);
+        let height = 8.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" {} ", t("campaign.record_title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let mut what = self.form.value(VACCINE).trim().to_string();
+        if let Some(dose) = self.form.optional_value(DOSE) {
+            what.push_str(&format!(" ({})", tf("campaign.dose", &[("dose", &dose)])));
+        }
+        let lines = vec![
+            Line::from(tf(
+                "campaign.confirm",
+                &[
+                    ("what", &what),
+                    ("date", &self.form.value(GIVEN_ON)),
+                    ("count", &self.picked.len()),
+                ],
+            )),
+            Line::from(t("campaign.confirm_note")).style(Style::default().fg(theme().inactive)),
+            Line::from(""),
+            Line::from(t("campaign.confirm_help")).style(Style::default().fg(theme().help)),
+        ];
+        frame.render_widget(
+            Paragraph::new(lines)
+                .style(Style::default().fg(theme().text))
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true })
+                .block(block),
+            dialog_area,
+        );
+    }
+}
+
+impl Component for VaccinationCampaign {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("campaign.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+
+        let step = |label: &'static str, active: bool| {
+            Span::styled(
+                label,
+                if active {
+                    Style::default()
+                        .fg(theme().accent)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme().inactive)
+                },
+            )
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                step(t("campaign.step.template"), self.step == Step::Template),
+                Span::styled("  →  ", Style::default().fg(theme().inactive)),
+                step(t("campaign.step.patients"), self.step != Step::Template),
+                Span::styled("  →  ", Style::default().fg(theme().inactive)),
+                step(t("campaign.step.confirm"), self.step == Step::Confirm),
+            ]))
+            .alignment(Alignment::Center),
+            layout[1],
+        );
+
+        let help = match self.step {
+            Step::Template => {
+                self.render_template(frame, layout[2]);
+                t("campaign.template_help")
+            }
+            Step::Patients | Step::Confirm => {
+                self.render_patients(frame, layout[2]);
+                if self.is_searching {
+                    t("campaign.search_help")
+                } else {
+                    t("campaign.help")
+                }
+            }
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+
+        if self.step == Step::Confirm {
+            self.render_confirm(frame);
+        }
+    }
+}
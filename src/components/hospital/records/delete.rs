@@ -1,7 +1,12 @@
 use crate::app::SelectedApp;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
-use crate::db;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
 use crate::models::{MedicalRecord, Patient};
+use crate::theme::theme;
 use crate::tui::Frame;
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
@@ -10,45 +15,38 @@ use ratatui::{
     widgets::{Block, BorderType, Borders, Cell, Clear, Paragraph, Row, Table, TableState},
 };
 use std::collections::HashMap;
-use std::time::{Duration, Instant};
 
 pub struct DeleteRecord {
+    db: Db,
     records: Vec<MedicalRecord>,
     filtered_records: Vec<MedicalRecord>,
     patients: HashMap<i64, Patient>,
     selected_record_ids: Vec<i64>,
-    search_input: String,
+    search_input: TextInput,
     is_searching: bool,
     table_state: TableState,
     show_confirmation: bool,
     confirmation_selected: usize,
-    error_message: Option<String>,
-    error_timer: Option<Instant>,
-    success_message: Option<String>,
-    success_timer: Option<Instant>,
 }
 
 impl DeleteRecord {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
         Self {
+            db: db.clone(),
             records: Vec::new(),
             filtered_records: Vec::new(),
             patients: HashMap::new(),
             selected_record_ids: Vec::new(),
-            search_input: String::new(),
+            search_input: TextInput::new(),
             is_searching: false,
             table_state: TableState::default(),
             show_confirmation: false,
             confirmation_selected: 1,
-            error_message: None,
-            error_timer: None,
-            success_message: None,
-            success_timer: None,
         }
     }
 
     pub fn fetch_records(&mut self) -> Result<()> {
-        self.records = db::get_all_medical_records()?;
+        self.records = db::get_all_medical_records(&self.db)?;
         self.fetch_patients_data()?;
         self.filter_records();
 
@@ -65,7 +63,7 @@ impl DeleteRecord {
         if self.search_input.is_empty() {
             self.filtered_records = self.records.clone();
         } else {
-            let search_term = self.search_input.to_lowercase();
+            let search_term = self.search_input.value().to_lowercase();
             self.filtered_records = self
                 .records
                 .iter()
@@ -102,7 +100,7 @@ impl DeleteRecord {
     fn fetch_patients_data(&mut self) -> Result<()> {
         self.patients.clear();
 
-        match db::get_all_patients() {
+        match db::get_all_patients(&self.db) {
             Ok(all_patients) => {
                 for patient in all_patients {
                     self.patients.insert(patient.id, patient);
@@ -110,7 +108,10 @@ impl DeleteRecord {
                 Ok(())
             }
             Err(e) => {
-                self.set_error(format!("Failed to fetch patient data: {}", e));
+                toast::push(Toast::error(tf(
+                    "records.delete.fetch_failed",
+                    &[("error", &e)],
+                )));
                 Ok(())
             }
         }
@@ -121,8 +122,6 @@ impl DeleteRecord {
     }
 
     fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
-        self.check_timeouts();
-
         if self.show_confirmation {
             match key.code {
                 KeyCode::Left | KeyCode::Right => {
@@ -131,13 +130,13 @@ impl DeleteRecord {
                 KeyCode::Enter => {
                     if self.confirmation_selected == 0 {
                         if self.selected_record_ids.is_empty() {
-                            self.set_error("No records were selected for deletion.".to_string());
+                            toast::push(Toast::error(t("records.delete.none_selected")));
                         } else {
                             let mut deleted_count = 0;
                             let mut error_occurred = false;
 
                             for record_id in &self.selected_record_ids {
-                                match db::delete_medical_record(*record_id) {
+                                match db::delete_medical_record(&self.db, *record_id) {
                                     Ok(_) => deleted_count += 1,
                                     Err(_) => {
                                         error_occurred = true;
@@ -148,17 +147,15 @@ impl DeleteRecord {
                             self.selected_record_ids.clear();
 
                             if error_occurred {
-                                self.set_error(format!(
-                                    "Error during deletion. {} records deleted successfully.",
-                                    deleted_count
-                                ));
+                                toast::push(Toast::error(tf(
+                                    "records.delete.partial",
+                                    &[("count", &deleted_count)],
+                                )));
                             } else if deleted_count > 0 {
-                                self.success_message = Some(format!(
-                                    "{} record{} deleted successfully!",
-                                    deleted_count,
-                                    if deleted_count == 1 { "" } else { "s" }
-                                ));
-                                self.success_timer = Some(Instant::now());
+                                toast::push(Toast::success(tf(
+                                    "records.delete.deleted",
+                                    &[("count", &deleted_count)],
+                                )));
                             }
 
                             self.fetch_records()?;
@@ -175,12 +172,7 @@ impl DeleteRecord {
             }
         } else if self.is_searching {
             match key.code {
-                KeyCode::Char(c) => {
-                    self.search_input.push(c);
-                    self.filter_records();
-                }
-                KeyCode::Backspace => {
-                    self.search_input.pop();
+                _ if self.search_input.handle_key(key) => {
                     self.filter_records();
                 }
                 KeyCode::Enter | KeyCode::Down => {
@@ -251,9 +243,9 @@ impl DeleteRecord {
                         self.show_confirmation = true;
                         self.confirmation_selected = 1;
                     } else {
-                        self.set_error(
-                            "Please select record(s) to delete using the spacebar.".to_string(),
-                        );
+                        toast::push(Toast::error(
+                            t("records.delete.select_with_space").to_string(),
+                        ));
                     }
                 }
                 KeyCode::Char('b') => {
@@ -261,9 +253,9 @@ impl DeleteRecord {
                         self.show_confirmation = true;
                         self.confirmation_selected = 1;
                     } else {
-                        self.set_error(
-                            "Please select record(s) to delete using the spacebar.".to_string(),
-                        );
+                        toast::push(Toast::error(
+                            t("records.delete.select_with_space").to_string(),
+                        ));
                     }
                 }
                 KeyCode::Char('a') => {
@@ -275,6 +267,7 @@ impl DeleteRecord {
                     }
                 }
                 KeyCode::Char('r') | KeyCode::Char('R') => {
+                    db::cache::invalidate_all(&self.db);
                     self.fetch_records()?;
                 }
                 KeyCode::Esc => {
@@ -285,48 +278,6 @@ impl DeleteRecord {
         }
         Ok(None)
     }
-
-    fn clear_error(&mut self) {
-        self.error_message = None;
-        self.error_timer = None;
-    }
-
-    fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_timer = Some(Instant::now());
-    }
-
-    fn clear_success(&mut self) {
-        self.success_message = None;
-        self.success_timer = None;
-    }
-
-    fn check_success_timeout(&mut self) {
-        if let Some(timer) = self.success_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_success();
-            }
-        }
-    }
-
-    fn check_error_timeout(&mut self) {
-        if let Some(timer) = self.error_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_error();
-            }
-        }
-    }
-
-    fn check_timeouts(&mut self) {
-        self.check_error_timeout();
-        self.check_success_timeout();
-    }
-}
-
-impl Default for DeleteRecord {
-    fn default() -> Self {
-        Self::new()
-    }
 }
 
 impl Component for DeleteRecord {
@@ -337,7 +288,7 @@ impl Component for DeleteRecord {
     fn render(&self, frame: &mut Frame) {
         let area = frame.area();
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             area,
         );
 
@@ -355,16 +306,16 @@ impl Component for DeleteRecord {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
 
-        let title = Paragraph::new("🗑️ RECORD DELETION MANAGER")
+        let title = Paragraph::new(t("records.delete.title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
@@ -373,24 +324,20 @@ impl Component for DeleteRecord {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Records ",
+                format!(" {} ", t("common.search_records")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(75, 75, 120))
+                Style::default().fg(theme().border)
             })
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .style(Style::default().bg(theme().surface));
 
-        let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(22, 22, 35)),
-            )
+        let search_paragraph = Paragraph::new(self.search_input.line(self.is_searching))
+            .style(Style::default().fg(theme().text).bg(theme().surface))
             .block(search_block);
         frame.render_widget(search_paragraph, layout[1]);
 
@@ -399,28 +346,43 @@ impl Component for DeleteRecord {
             .border_type(BorderType::Rounded)
             .title(if !self.search_input.is_empty() {
                 format!(
-                    " Records ({} of {} matches) ",
-                    self.filtered_records.len(),
-                    self.records.len()
+                    " {} ",
+                    tf(
+                        "common.records_matches",
+                        &[
+                            ("shown", &self.filtered_records.len()),
+                            ("total", &self.records.len())
+                        ]
+                    )
                 )
             } else {
-                format!(" Records ({}) ", self.records.len())
+                format!(
+                    " {} ",
+                    tf("common.records_count", &[("total", &self.records.len())])
+                )
             })
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
-            .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .border_style(Style::default().fg(theme().unfocused))
+            .style(Style::default().bg(theme().input));
 
         let selected_style = Style::default()
-            .bg(Color::Rgb(45, 45, 60))
-            .fg(Color::Rgb(250, 250, 110))
+            .bg(theme().highlight)
+            .fg(theme().focus)
             .add_modifier(Modifier::BOLD);
-        let normal_style = Style::default()
-            .bg(Color::Rgb(26, 26, 36))
-            .fg(Color::Rgb(220, 220, 240));
+        let normal_style = Style::default().bg(theme().input).fg(theme().text);
+
+        let widths = [
+            Constraint::Length(5),
+            Constraint::Length(8),
+            Constraint::Length(12),
+            Constraint::Length(12),
+            Constraint::Min(20),
+        ];
+        let columns = column_widths(layout[2].inner(Margin::new(1, 1)), &widths, 1, "► ");
 
         let mut rows = Vec::new();
         for record in &self.filtered_records {
@@ -432,107 +394,76 @@ impl Component for DeleteRecord {
 
             let (first_name, last_name) = match self.get_patient(record.patient_id) {
                 Some(patient) => (patient.first_name.clone(), patient.last_name.clone()),
-                None => ("Unknown".to_string(), "Patient".to_string()),
+                None => (t("common.unknown_patient_name").to_string(), String::new()),
             };
 
             rows.push(Row::new(vec![
                 Cell::from(checkbox).style(normal_style),
-                Cell::from(record.id.to_string()).style(normal_style),
-                Cell::from(first_name).style(normal_style),
-                Cell::from(last_name).style(normal_style),
-                Cell::from(record.diagnosis.clone()).style(normal_style),
+                cell(record.id.to_string(), &columns, 1).style(normal_style),
+                cell(first_name, &columns, 2).style(normal_style),
+                cell(last_name, &columns, 3).style(normal_style),
+                cell(&record.diagnosis, &columns, 4).style(normal_style),
             ]));
         }
 
         if self.filtered_records.is_empty() {
             let message = if self.search_input.is_empty() {
-                "No records found in database"
+                t("common.no_records")
             } else {
-                "No records match your search criteria"
+                t("common.no_record_matches")
             };
 
             rows.push(Row::new(vec![
                 Cell::from(""),
                 Cell::from(""),
                 Cell::from(""),
-                Cell::from(message).style(Style::default().fg(Color::Rgb(180, 180, 200))),
+                Cell::from(message).style(Style::default().fg(theme().inactive)),
                 Cell::from(""),
             ]));
         }
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(5),
-                Constraint::Length(8),
-                Constraint::Length(12),
-                Constraint::Length(12),
-                Constraint::Min(20),
-            ],
-        )
-        .header(
-            Row::new(vec![
-                Cell::from(""),
-                Cell::from("ID").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("First Name").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Last Name").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Diagnosis").style(Style::default().add_modifier(Modifier::BOLD)),
-            ])
-            .style(
-                Style::default()
-                    .bg(Color::Rgb(80, 60, 130))
-                    .fg(Color::Rgb(180, 180, 250)),
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec![
+                    Cell::from(""),
+                    Cell::from(t("common.id")).style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.first_name"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.last_name"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("campaign.diagnosis"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                ])
+                .style(Style::default().bg(theme().header).fg(theme().title))
+                .height(1),
             )
-            .height(1),
-        )
-        .block(table_block)
-        .row_highlight_style(selected_style)
-        .highlight_symbol("► ");
+            .block(table_block)
+            .row_highlight_style(selected_style)
+            .highlight_symbol("► ");
 
         frame.render_stateful_widget(table, layout[2], &mut self.table_state.clone());
 
-        if let Some(success) = &self.success_message {
-            let success_paragraph = Paragraph::new(success.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, layout[3]);
-        } else if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(240, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, layout[3]);
-        }
-
         if self.is_searching {
-            let help_text =
-                Paragraph::new("Type to search | ↓/Enter: To results | Esc: Cancel search")
-                    .style(Style::default().fg(Color::Rgb(180, 180, 200)))
-                    .alignment(Alignment::Center);
+            let help_text = Paragraph::new(t("common.search_enter_help"))
+                .style(Style::default().fg(theme().inactive))
+                .alignment(Alignment::Center);
             frame.render_widget(help_text, layout[4]);
         } else {
             let help_block = Block::default()
-                .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-                .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+                .border_style(Style::default().fg(theme().border))
+                .style(Style::default().bg(theme().background));
 
             let help_layout = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
                 .split(layout[4]);
 
-            let help_text1 = Paragraph::new(" / or s: Search | ↑/↓: Navigate | Space: Toggle | A: Select/deselect all | R: Refresh")
-                .style(Style::default().fg(Color::Rgb(180, 180, 200)))
+            let help_text1 = Paragraph::new(format!(" {}", t("patients.delete.help")))
+                .style(Style::default().fg(theme().inactive))
                 .alignment(Alignment::Center);
 
-            let help_text2 = Paragraph::new("Enter: Delete selected | B: Bulk delete | Esc: Back")
-                .style(Style::default().fg(Color::Rgb(180, 180, 200)))
+            let help_text2 = Paragraph::new(t("patients.delete.help_2"))
+                .style(Style::default().fg(theme().inactive))
                 .alignment(Alignment::Center);
 
             frame.render_widget(help_block, layout[4]);
@@ -554,22 +485,24 @@ impl Component for DeleteRecord {
 
             let selected_count = self.selected_record_ids.len();
             let title = format!(
-                " Delete {} Record{} ",
-                selected_count,
-                if selected_count == 1 { "" } else { "s" }
+                " {} ",
+                tf(
+                    "records.delete.confirm_title",
+                    &[("count", &selected_count)]
+                )
             );
 
             let dialog_block = Block::default()
                 .title(title)
                 .title_style(
                     Style::default()
-                        .fg(Color::Rgb(230, 230, 250))
+                        .fg(theme().title)
                         .add_modifier(Modifier::BOLD),
                 )
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-                .style(Style::default().bg(Color::Rgb(30, 30, 46)));
+                .border_style(Style::default().fg(theme().unfocused))
+                .style(Style::default().bg(theme().dialog));
 
             frame.render_widget(dialog_block.clone(), dialog_area);
 
@@ -580,8 +513,8 @@ impl Component for DeleteRecord {
                 .constraints([Constraint::Length(2), Constraint::Length(2)])
                 .split(inner_area);
 
-            let message = Paragraph::new("Are you sure you want to delete the selected record(s)?")
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            let message = Paragraph::new(t("records.delete.confirm"))
+                .style(Style::default().fg(theme().text))
                 .add_modifier(Modifier::BOLD)
                 .alignment(Alignment::Center);
             frame.render_widget(message, content_layout[0]);
@@ -593,28 +526,28 @@ impl Component for DeleteRecord {
 
             let yes_style = if self.confirmation_selected == 0 {
                 Style::default()
-                    .fg(Color::Rgb(140, 219, 140))
+                    .fg(theme().success)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::Rgb(180, 180, 200))
+                Style::default().fg(theme().inactive)
             };
             let no_style = if self.confirmation_selected == 1 {
                 Style::default()
-                    .fg(Color::Rgb(255, 100, 100))
+                    .fg(theme().error)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::Rgb(180, 180, 200))
+                Style::default().fg(theme().inactive)
             };
 
             let yes_text = if self.confirmation_selected == 0 {
-                "► Yes ◄"
+                format!("► {} ◄", t("common.yes"))
             } else {
-                "  Yes  "
+                format!("  {}  ", t("common.yes"))
             };
             let no_text = if self.confirmation_selected == 1 {
-                "► No ◄"
+                format!("► {} ◄", t("common.no"))
             } else {
-                "  No  "
+                format!("  {}  ", t("common.no"))
             };
 
             let yes_button = Paragraph::new(yes_text)
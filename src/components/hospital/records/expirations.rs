@@ -0,0 +1,328 @@
+//! Everything that stops being valid on a given date, in one list sorted by
+//! the days left and coloured by how close the date is. Patient documents
+//! (ID copies, referrals) are the only records with an expiry date so far;
+//! further sources add their rows in [`load_expirations`].
+
+use crate::app::SelectedApp;
+use crate::components::hospital::patients::documents::document_type_text;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+use time::Date;
+
+/// Items expiring within this many days are shown as due soon.
+const DUE_SOON_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortBy {
+    DaysLeft,
+    Kind,
+    Holder,
+}
+
+impl SortBy {
+    fn label(self) -> &'static str {
+        match self {
+            SortBy::DaysLeft => t("expirations.days_left"),
+            SortBy::Kind => t("expirations.type"),
+            SortBy::Holder => t("expirations.holder"),
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            SortBy::DaysLeft => SortBy::Kind,
+            SortBy::Kind => SortBy::Holder,
+            SortBy::Holder => SortBy::DaysLeft,
+        }
+    }
+}
+
+struct Expiration {
+    kind: String,
+    item: String,
+    holder: String,
+    expires_on: Date,
+    days_left: i64,
+}
+
+fn load_expirations(db: &Db) -> Result<Vec<Expiration>> {
+    let today = datetime::today();
+    let patients: HashMap<i64, String> = db::get_all_patients(db)?
+        .into_iter()
+        .map(|p| (p.id, format!("{} {}", p.first_name, p.last_name)))
+        .collect();
+    let mut expirations = Vec::new();
+    for document in db::get_expiring_documents(db)? {
+        let Some(expires_on) = document
+            .expires_on
+            .as_deref()
+            .and_then(datetime::parse_date)
+        else {
+            continue;
+        };
+        expirations.push(Expiration {
+            kind: document_type_text(document.doc_type).to_string(),
+            item: document
+                .notes
+                .clone()
+                .unwrap_or_else(|| document.file_path.clone()),
+            holder: patients
+                .get(&document.patient_id)
+                .cloned()
+                .unwrap_or_else(|| tf("common.patient_number", &[("id", &document.patient_id)])),
+            expires_on,
+            days_left: (expires_on - today).whole_days(),
+        });
+    }
+    Ok(expirations)
+}
+
+fn days_text(days_left: i64) -> String {
+    match days_left {
+        d if d < 0 => tf("expirations.overdue", &[("days", &(-d))]),
+        0 => t("common.today").to_string(),
+        d => d.to_string(),
+    }
+}
+
+fn urgency_style(days_left: i64) -> Style {
+    if days_left < 0 {
+        Style::default().fg(theme().error)
+    } else if days_left <= DUE_SOON_DAYS {
+        Style::default().fg(theme().warning)
+    } else {
+        Style::default().fg(theme().text)
+    }
+}
+
+pub struct ExpirationReport {
+    db: Db,
+    items: Vec<Expiration>,
+    state: TableState,
+    sort_by: SortBy,
+    descending: bool,
+}
+
+impl ExpirationReport {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            items: Vec::new(),
+            state: TableState::default(),
+            sort_by: SortBy::DaysLeft,
+            descending: false,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.items = load_expirations(&self.db)?;
+        self.sort();
+        Ok(())
+    }
+
+    fn sort(&mut self) {
+        match self.sort_by {
+            SortBy::DaysLeft => self.items.sort_by_key(|e| e.days_left),
+            SortBy::Kind => self
+                .items
+                .sort_by(|a, b| a.kind.cmp(&b.kind).then(a.days_left.cmp(&b.days_left))),
+            SortBy::Holder => self.items.sort_by(|a, b| {
+                a.holder
+                    .to_lowercase()
+                    .cmp(&b.holder.to_lowercase())
+                    .then(a.days_left.cmp(&b.days_left))
+            }),
+        }
+        if self.descending {
+            self.items.reverse();
+        }
+        self.state.select((!self.items.is_empty()).then_some(0));
+    }
+
+    fn summary(&self) -> Line<'static> {
+        let expired = self.items.iter().filter(|e| e.days_left < 0).count();
+        let due_soon = self
+            .items
+            .iter()
+            .filter(|e| (0..=DUE_SOON_DAYS).contains(&e.days_left))
+            .count();
+        let later = self.items.len() - expired - due_soon;
+        Line::from(vec![
+            Span::styled(
+                tf("expirations.expired", &[("count", &expired)]),
+                urgency_style(-1),
+            ),
+            Span::styled("  |  ", Style::default().fg(theme().inactive)),
+            Span::styled(
+                tf(
+                    "expirations.due_soon",
+                    &[("count", &due_soon), ("days", &DUE_SOON_DAYS)],
+                ),
+                urgency_style(0),
+            ),
+            Span::styled("  |  ", Style::default().fg(theme().inactive)),
+            Span::styled(
+                tf("expirations.later", &[("count", &later)]),
+                urgency_style(DUE_SOON_DAYS + 1),
+            ),
+            Span::styled(
+                format!(
+                    "  {}",
+                    tf(
+                        "expirations.sorted",
+                        &[
+                            ("field", &self.sort_by.label()),
+                            ("arrow", &(if self.descending { "▼" } else { "▲" }))
+                        ]
+                    )
+                ),
+                Style::default().fg(theme().inactive),
+            ),
+        ])
+    }
+}
+
+impl Component for ExpirationReport {
+    fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        let len = self.items.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('s') | KeyCode::Char('S') => {
+                self.sort_by = self.sort_by.next();
+                self.descending = false;
+                self.sort();
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                self.descending = !self.descending;
+                self.sort();
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                if let Err(e) = self.load_data() {
+                    toast::push(Toast::error(tf(
+                        "expirations.load_failed",
+                        &[("error", &e)],
+                    )));
+                }
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("expirations.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(self.summary()).alignment(Alignment::Center),
+            layout[1],
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.items.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("expirations.empty"))
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[2],
+            );
+        } else {
+            let widths = [
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Length(16),
+                Constraint::Percentage(35),
+                Constraint::Min(16),
+            ];
+            let columns = column_widths(block.inner(layout[2]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .items
+                .iter()
+                .map(|e| {
+                    Row::new(vec![
+                        cell(days_text(e.days_left), &columns, 0),
+                        cell(datetime::display_date(e.expires_on), &columns, 1),
+                        cell(e.kind.as_str(), &columns, 2),
+                        cell(e.holder.as_str(), &columns, 3),
+                        cell(e.item.as_str(), &columns, 4),
+                    ])
+                    .style(urgency_style(e.days_left))
+                })
+                .collect();
+            let header = Row::new(vec![
+                t("expirations.days_left"),
+                t("documents.expires"),
+                t("expirations.type"),
+                t("expirations.holder"),
+                t("expirations.item"),
+            ])
+            .style(Style::default().bg(theme().header).fg(theme().title));
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(block)
+                .row_highlight_style(Style::default().bg(theme().highlight))
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("expirations.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+    }
+}
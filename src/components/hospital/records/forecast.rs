@@ -0,0 +1,690 @@
+//! Bed occupancy projected for the coming week, ward by ward, against the
+//! beds configured under `[wards]`.
+//!
+//! Patients on a ward leave on their expected discharge date, or after the
+//! ward's average stay over the last 90 days when none is set. Planned
+//! admissions arrive on their date and stay the average too, and unplanned
+//! ones keep arriving at the ward's daily rate over the last 28 days.
+//! Occupancy is counted at the end of each day.
+
+use crate::app::SelectedApp;
+use crate::components::form::{self, integer_range, Field, Form};
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::PlannedAdmission;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{config, datetime};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use time::{Date, Duration};
+
+const DAYS: usize = 7;
+/// Days of discharges the average stay is taken over.
+const STAY_HISTORY_DAYS: i64 = 90;
+/// Days of admissions the unplanned arrival rate is taken over.
+const ARRIVAL_HISTORY_DAYS: i64 = 28;
+/// Share of the beds from which a day is flagged as nearly full.
+const NEARLY_FULL: f64 = 0.9;
+
+const PATIENT: usize = 0;
+const WARD: usize = 1;
+const DATE: usize = 2;
+
+fn plan_form(ward: &str) -> Form {
+    let mut form = Form::new(vec![
+        Field::new(t("common.patient_id"))
+            .required()
+            .validate_with(integer_range(1, i64::MAX)),
+        Field::new(t("kiosk.ward")).required(),
+        Field::new(t("forecast.date_field"))
+            .required()
+            .validate_with(Box::new(form::date)),
+    ]);
+    form.fields[WARD].set(ward);
+    form.fields[DATE].set(datetime::format_date(datetime::today() + Duration::days(1)));
+    form
+}
+
+#[derive(Debug, Clone, Default)]
+struct WardForecast {
+    ward: String,
+    beds: Option<u32>,
+    now: usize,
+    /// Expected patients at the end of each of the next days.
+    days: [f64; DAYS],
+    average_stay: Option<f64>,
+    arrivals_per_day: f64,
+    expected_home: usize,
+    planned: usize,
+}
+
+impl WardForecast {
+    fn style(&self, patients: f64) -> Style {
+        let Some(beds) = self.beds.filter(|beds| *beds > 0) else {
+            return Style::default().fg(theme().text);
+        };
+        let patients = patients.round();
+        if patients >= beds as f64 {
+            Style::default()
+                .fg(theme().error)
+                .add_modifier(Modifier::BOLD)
+        } else if patients >= beds as f64 * NEARLY_FULL {
+            Style::default().fg(theme().warning)
+        } else {
+            Style::default().fg(theme().text)
+        }
+    }
+
+    /// The first day the ward is expected to run out of beds.
+    fn full_on(&self) -> Option<usize> {
+        let beds = self.beds.filter(|beds| *beds > 0)? as f64;
+        self.days.iter().position(|p| p.round() >= beds)
+    }
+}
+
+pub struct BedForecast {
+    db: Db,
+    wards: Vec<WardForecast>,
+    planned: Vec<PlannedAdmission>,
+    patients: HashMap<i64, String>,
+    state: TableState,
+    planned_state: TableState,
+    /// Whether ↑↓ and `x` work on the planned admissions.
+    planned_focus: bool,
+    popup: Option<(Form, usize)>,
+    confirm_cancel: bool,
+}
+
+impl BedForecast {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            wards: Vec::new(),
+            planned: Vec::new(),
+            patients: HashMap::new(),
+            state: TableState::default(),
+            planned_state: TableState::default(),
+            planned_focus: false,
+            popup: None,
+            confirm_cancel: false,
+        }
+    }
+
+    fn ward(&self) -> Option<&WardForecast> {
+        self.wards.get(self.state.selected()?)
+    }
+
+    /// The selected ward's planned admissions.
+    fn ward_plans(&self) -> Vec<&PlannedAdmission> {
+        let ward = self.ward().map(|w| w.ward.as_str());
+        self.planned
+            .iter()
+            .filter(|p| Some(p.ward.as_str()) == ward)
+            .collect()
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        let today = datetime::today();
+        let admissions = db::get_all_admissions(&self.db)?;
+        let planned_ids: HashSet<i64> = db::get_planned_admission_ids(&self.db)?
+            .into_iter()
+            .collect();
+        self.planned = db::get_open_planned_admissions(&self.db, &datetime::format_date(today))?;
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, format!("{} {}", p.first_name, p.last_name)))
+            .collect();
+
+        let mut wards: BTreeMap<String, WardForecast> = BTreeMap::new();
+        let mut ward = |name: &str| {
+            wards
+                .entry(name.to_string())
+                .or_insert_with(|| WardForecast {
+                    ward: name.to_string(),
+                    ..WardForecast::default()
+                });
+        };
+        for name in config::config().wards.keys() {
+            ward(name);
+        }
+
+        // Average stay and unplanned arrivals per ward.
+        let mut stays: HashMap<&str, Vec<f64>> = HashMap::new();
+        let mut arrivals: HashMap<&str, usize> = HashMap::new();
+        for admission in &admissions {
+            ward(&admission.ward);
+            let admitted_on = datetime::local_date(&admission.admitted_at);
+            if admitted_on
+                .is_some_and(|on| on < today && on >= today - Duration::days(ARRIVAL_HISTORY_DAYS))
+                && !planned_ids.contains(&admission.id)
+            {
+                *arrivals.entry(&admission.ward).or_default() += 1;
+            }
+            let Some(discharged_at) = admission.discharged_at.as_deref() else {
+                continue;
+            };
+            if datetime::local_date(discharged_at)
+                .is_none_or(|on| on < today - Duration::days(STAY_HISTORY_DAYS))
+            {
+                continue;
+            }
+            if let (Some(admitted), Some(discharged)) = (
+                datetime::parse_stamp(&admission.admitted_at),
+                datetime::parse_stamp(discharged_at),
+            ) {
+                let days = (discharged - admitted).whole_minutes().max(0) as f64 / 1440.0;
+                stays.entry(&admission.ward).or_default().push(days);
+            }
+        }
+        for plan in &self.planned {
+            ward(&plan.ward);
+        }
+        let average =
+            |days: &[f64]| (!days.is_empty()).then(|| days.iter().sum::<f64>() / days.len() as f64);
+        let all_stays: Vec<f64> = stays.values().flatten().copied().collect();
+        let overall_stay = average(&all_stays);
+        let config = config::config();
+        let beds = &config.wards;
+        for forecast in wards.values_mut() {
+            forecast.beds = beds.get(&forecast.ward).copied();
+            forecast.average_stay = stays
+                .get(forecast.ward.as_str())
+                .and_then(|days| average(days))
+                .or(overall_stay);
+            forecast.arrivals_per_day = arrivals.get(forecast.ward.as_str()).copied().unwrap_or(0)
+                as f64
+                / ARRIVAL_HISTORY_DAYS as f64;
+        }
+
+        let day = |k: usize| today + Duration::days(k as i64 + 1);
+        let last_day = day(DAYS - 1);
+        for admission in admissions.iter().filter(|a| a.discharged_at.is_none()) {
+            let forecast = wards.get_mut(&admission.ward).expect("ward listed above");
+            forecast.now += 1;
+            let stay = forecast
+                .average_stay
+                .map(|s| Duration::days(s.round().max(1.0) as i64));
+            let leaves: Option<Date> = match admission
+                .expected_discharge
+                .as_deref()
+                .and_then(datetime::parse_date)
+            {
+                Some(date) => Some(date),
+                None => datetime::local_date(&admission.admitted_at)
+                    .zip(stay)
+                    .map(|(on, stay)| on + stay),
+            };
+            if leaves.is_some_and(|date| date <= last_day) {
+                forecast.expected_home += 1;
+            }
+            for (k, patients) in forecast.days.iter_mut().enumerate() {
+                if leaves.is_none_or(|date| date > day(k)) {
+                    *patients += 1.0;
+                }
+            }
+        }
+        for plan in &self.planned {
+            let Some(arrives) = datetime::parse_date(&plan.planned_date) else {
+                continue;
+            };
+            let forecast = wards.get_mut(&plan.ward).expect("ward listed above");
+            if arrives <= last_day {
+                forecast.planned += 1;
+            }
+            let stay = forecast
+                .average_stay
+                .map(|s| Duration::days(s.round().max(1.0) as i64));
+            for (k, patients) in forecast.days.iter_mut().enumerate() {
+                if arrives <= day(k) && stay.is_none_or(|stay| arrives + stay > day(k)) {
+                    *patients += 1.0;
+                }
+            }
+        }
+        for forecast in wards.values_mut() {
+            // Someone arriving on day j is still in on day k while j is
+            // within the average stay of k.
+            let stay = forecast.average_stay.map(|s| s.round().max(1.0) as usize);
+            for (k, patients) in forecast.days.iter_mut().enumerate() {
+                let arrived = stay.map_or(k + 1, |stay| (k + 1).min(stay));
+                *patients += forecast.arrivals_per_day * arrived as f64;
+            }
+        }
+
+        let selected = self.ward().map(|w| w.ward.clone());
+        self.wards = wards.into_values().collect();
+        let index = selected
+            .and_then(|name| self.wards.iter().position(|w| w.ward == name))
+            .unwrap_or(0);
+        self.state.select((!self.wards.is_empty()).then_some(index));
+        self.clamp_planned();
+        Ok(())
+    }
+
+    fn clamp_planned(&mut self) {
+        let len = self.ward_plans().len();
+        match self.planned_state.selected() {
+            _ if len == 0 => self.planned_state.select(None),
+            Some(i) if i >= len => self.planned_state.select(Some(len - 1)),
+            None => self.planned_state.select(Some(0)),
+            _ => {}
+        }
+    }
+
+    fn save_plan(&mut self) -> Result<()> {
+        let Some((mut form, _)) = self.popup.take() else {
+            return Ok(());
+        };
+        if let Some(invalid) = form.validate() {
+            self.popup = Some((form, invalid));
+            return Ok(());
+        }
+        let patient_id: i64 = form.value(PATIENT).parse().unwrap_or_default();
+        if !self.patients.contains_key(&patient_id) {
+            form.fields[PATIENT].error = Some(t("common.no_patient_id").to_string());
+            self.popup = Some((form, PATIENT));
+            return Ok(());
+        }
+        if datetime::parse_date(&form.value(DATE)).is_some_and(|date| date < datetime::today()) {
+            form.fields[DATE].error = Some(t("forecast.past_date").to_string());
+            self.popup = Some((form, DATE));
+            return Ok(());
+        }
+        let plan = PlannedAdmission {
+            id: 0,
+            patient_id,
+            ward: form.value(WARD),
+            planned_date: form.value(DATE),
+            admission_id: None,
+        };
+        match db::add_planned_admission(&self.db, &plan) {
+            Ok(_) => {
+                toast::push(Toast::success(tf(
+                    "forecast.planned",
+                    &[
+                        ("patient", &self.patients[&patient_id]),
+                        ("ward", &plan.ward),
+                        ("date", &plan.planned_date),
+                    ],
+                )));
+                self.load_data()?;
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("insurance.save_failed", &[("error", &e)])));
+                self.popup = Some((form, PATIENT));
+            }
+        }
+        Ok(())
+    }
+
+    fn cancel_selected_plan(&mut self) -> Result<()> {
+        self.confirm_cancel = false;
+        let Some(plan) = self
+            .planned_state
+            .selected()
+            .and_then(|i| self.ward_plans().get(i).map(|p| (*p).clone()))
+        else {
+            return Ok(());
+        };
+        match db::cancel_planned_admission(&self.db, plan.id) {
+            Ok(()) => {
+                toast::push(Toast::success(t("forecast.cancelled")));
+                self.load_data()?;
+            }
+            Err(e) => toast::push(Toast::error(tf("forecast.cancel_failed", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    fn handle_popup_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some((form, focus)) = &mut self.popup else {
+            return Ok(());
+        };
+        let len = form.len();
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Tab | KeyCode::Down => *focus = (*focus + 1) % len,
+            KeyCode::BackTab | KeyCode::Up => *focus = (*focus + len - 1) % len,
+            KeyCode::Enter if *focus + 1 < len => *focus += 1,
+            KeyCode::Enter => self.save_plan()?,
+            _ => {
+                form.handle_key(*focus, key);
+            }
+        }
+        Ok(())
+    }
+
+    fn render_table(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("forecast.expected_title")))
+            .border_style(Style::default().fg(if self.planned_focus {
+                theme().border
+            } else {
+                theme().focus
+            }))
+            .style(Style::default().bg(theme().surface));
+        if self.wards.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("forecast.no_wards"))
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        }
+        let today = datetime::today();
+        let mut widths = vec![
+            Constraint::Min(12),
+            Constraint::Length(5),
+            Constraint::Length(5),
+        ];
+        widths.extend([Constraint::Length(7); DAYS]);
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+        let mut header = vec![
+            t("kiosk.ward").to_string(),
+            t("forecast.beds").to_string(),
+            t("forecast.now").to_string(),
+        ];
+        header.extend((1..=DAYS).map(|k| {
+            let date = today + Duration::days(k as i64);
+            format!("{} {}", &date.weekday().to_string()[..3], date.day())
+        }));
+        let rows = self.wards.iter().map(|w| {
+            let mut cells = vec![
+                cell(w.ward.as_str(), &columns, 0),
+                cell(
+                    w.beds.map_or("-".to_string(), |b| b.to_string()),
+                    &columns,
+                    1,
+                ),
+                cell(w.now.to_string(), &columns, 2).style(w.style(w.now as f64)),
+            ];
+            cells.extend(w.days.iter().enumerate().map(|(k, patients)| {
+                cell(format!("{:.0}", patients), &columns, k + 3).style(w.style(*patients))
+            }));
+            Row::new(cells).style(Style::default().fg(theme().text))
+        });
+        let table = Table::new(rows, widths)
+            .header(Row::new(header).style(Style::default().bg(theme().header).fg(theme().title)))
+            .block(block)
+            .row_highlight_style(Style::default().bg(theme().highlight))
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.state.clone());
+    }
+
+    fn render_details(&self, frame: &mut Frame, area: Rect) {
+        let Some(ward) = self.ward() else {
+            return;
+        };
+        let [notes, plans] =
+            Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .areas(area);
+
+        let mut lines = vec![
+            Line::from(match ward.average_stay {
+                Some(days) => tf(
+                    "forecast.average_stay",
+                    &[("days", &format!("{:.1}", days))],
+                ),
+                None => t("forecast.no_discharges").to_string(),
+            }),
+            Line::from(tf(
+                "forecast.arrivals",
+                &[("count", &format!("{:.1}", ward.arrivals_per_day))],
+            )),
+            Line::from(tf(
+                "forecast.week",
+                &[("home", &ward.expected_home), ("planned", &ward.planned)],
+            )),
+        ];
+        let today = datetime::today();
+        lines.push(match (ward.beds, ward.full_on()) {
+            (None, _) => Line::styled(t("forecast.no_beds"), Style::default().fg(theme().inactive)),
+            (Some(_), Some(k)) => Line::styled(
+                tf(
+                    "forecast.full",
+                    &[(
+                        "date",
+                        &(datetime::display_date(today + Duration::days(k as i64 + 1))),
+                    )],
+                ),
+                Style::default()
+                    .fg(theme().error)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            (Some(beds), None) => Line::styled(
+                tf("forecast.within", &[("beds", &beds)]),
+                Style::default().fg(theme().success),
+            ),
+        });
+        frame.render_widget(
+            Paragraph::new(lines)
+                .style(Style::default().fg(theme().text))
+                .wrap(Wrap { trim: true })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", ward.ward))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            notes,
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("forecast.planned_title")))
+            .border_style(Style::default().fg(if self.planned_focus {
+                theme().focus
+            } else {
+                theme().border
+            }))
+            .style(Style::default().bg(theme().surface));
+        let plans_list = self.ward_plans();
+        if plans_list.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("forecast.none_planned"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                plans,
+            );
+            return;
+        }
+        let widths = [Constraint::Length(12), Constraint::Min(12)];
+        let columns = column_widths(block.inner(plans), &widths, 1, "► ");
+        let rows = plans_list.iter().map(|plan| {
+            let date = datetime::parse_date(&plan.planned_date)
+                .map_or(plan.planned_date.clone(), datetime::display_date);
+            Row::new(vec![
+                cell(date, &columns, 0),
+                cell(
+                    self.patients
+                        .get(&plan.patient_id)
+                        .cloned()
+                        .unwrap_or_else(|| {
+                            tf("common.unknown_number", &[("id", &plan.patient_id)])
+                        }),
+                    &columns,
+                    1,
+                ),
+            ])
+            .style(Style::default().fg(theme().text))
+        });
+        let table = Table::new(rows, widths)
+            .block(block)
+            .row_highlight_style(if self.planned_focus {
+                Style::default().bg(theme().highlight)
+            } else {
+                Style::default()
+            })
+            .highlight_symbol(if self.planned_focus { "► " } else { "  " });
+        frame.render_stateful_widget(table, plans, &mut self.planned_state.clone());
+    }
+
+    fn render_popup(&self, frame: &mut Frame, form: &Form, focus: usize) {
+        let area = frame.area();
+        let height = (form.len() as u16 * 3 + 3).min(area.height);
+        let width = 60.min(area.width);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" {} ", t("forecast.plan_title")))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let mut constraints = vec![Constraint::Length(3); form.len()];
+        constraints.push(Constraint::Min(1));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .horizontal_margin(1)
+            .split(inner);
+        for index in 0..form.len() {
+            form.render_field(frame, index, chunks[index], focus);
+        }
+        frame.render_widget(
+            Paragraph::new(t("common.form_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[form.len()],
+        );
+    }
+}
+
+impl Component for BedForecast {
+    fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if self.popup.is_some() {
+            self.handle_popup_input(key)?;
+            return Ok(None);
+        }
+        if self.confirm_cancel {
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.cancel_selected_plan()?
+                }
+                _ => self.confirm_cancel = false,
+            }
+            return Ok(None);
+        }
+        let plans = self.ward_plans().len();
+        let (state, len) = if self.planned_focus {
+            (&mut self.planned_state, plans)
+        } else {
+            (&mut self.state, self.wards.len())
+        };
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = state.selected().map_or(0, |i| (i + 1) % len);
+                state.select(Some(i));
+                if !self.planned_focus {
+                    self.planned_state.select(None);
+                    self.clamp_planned();
+                }
+            }
+            KeyCode::Up if len > 0 => {
+                let i = state.selected().map_or(0, |i| (i + len - 1) % len);
+                state.select(Some(i));
+                if !self.planned_focus {
+                    self.planned_state.select(None);
+                    self.clamp_planned();
+                }
+            }
+            KeyCode::Tab | KeyCode::BackTab => self.planned_focus = !self.planned_focus,
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                let ward = self.ward().map(|w| w.ward.clone()).unwrap_or_default();
+                self.popup = Some((plan_form(&ward), PATIENT));
+            }
+            KeyCode::Char('x') | KeyCode::Char('X')
+                if self.planned_focus && self.planned_state.selected().is_some() =>
+            {
+                self.confirm_cancel = true;
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                if let Err(e) = self.load_data() {
+                    toast::push(Toast::error(tf("forecast.load_failed", &[("error", &e)])));
+                }
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(6),
+                Constraint::Length(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("forecast.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        self.render_table(frame, layout[1]);
+        self.render_details(frame, layout[2]);
+
+        let (help, style) = if self.confirm_cancel {
+            (
+                t("forecast.confirm_cancel"),
+                Style::default().fg(theme().warning),
+            )
+        } else {
+            (t("forecast.help"), Style::default().fg(theme().help))
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(style)
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+
+        if let Some((form, focus)) = &self.popup {
+            self.render_popup(frame, form, *focus);
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! The recorded versions of a medical record, read back from the sync change
+//! log. Any version can be compared with the current record and chosen
+//! fields restored from it.
+
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::compare::{CompareAction, ComparePane, Side};
+use crate::db::sync::RowVersion;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::MedicalRecord;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use serde_json::{Map, Value};
+
+/// The compared fields, by label key and change log column.
+const FIELDS: [(&str, &str); 4] = [
+    ("history.diagnosis", "diagnosis"),
+    ("history.doctor_notes", "doctor_notes"),
+    ("history.nurse_notes", "nurse_notes"),
+    ("history.prescription", "prescription"),
+];
+
+fn current_value(record: &MedicalRecord, column: &str) -> Option<String> {
+    match column {
+        "diagnosis" => Some(record.diagnosis.clone()),
+        "doctor_notes" => Some(record.doctor_notes.clone()),
+        "nurse_notes" => record.nurse_notes.clone(),
+        _ => record.prescription.clone(),
+    }
+}
+
+fn version_value(row: &Map<String, Value>, column: &str) -> Option<String> {
+    match row.get(column) {
+        Some(Value::String(text)) => Some(text.clone()),
+        Some(Value::Null) | None => None,
+        Some(other) => Some(other.to_string()),
+    }
+}
+
+fn shown(value: Option<String>) -> String {
+    value.unwrap_or_else(|| "-".to_string())
+}
+
+/// A change log timestamp, shown to the second since edits come in bursts.
+fn when(changed_at: &str) -> String {
+    let seconds = changed_at.get(16..19).unwrap_or_default();
+    format!("{}{}", datetime::display_stamp(changed_at), seconds)
+}
+
+pub struct RecordHistory {
+    db: Db,
+    record: MedicalRecord,
+    versions: Vec<RowVersion>,
+    state: TableState,
+    /// The version being compared, by index, and its pane.
+    compare: Option<(usize, ComparePane)>,
+}
+
+impl RecordHistory {
+    pub fn new(db: &Db, record: MedicalRecord) -> Self {
+        let mut history = Self {
+            db: db.clone(),
+            record,
+            versions: Vec::new(),
+            state: TableState::default(),
+            compare: None,
+        };
+        history.load_versions();
+        history
+    }
+
+    fn load_versions(&mut self) {
+        match db::sync::history(&self.db, "medical_records", self.record.id) {
+            Ok(versions) => self.versions = versions,
+            Err(e) => toast::push(Toast::error(tf("history.load_failed", &[("error", &e)]))),
+        }
+        self.state.select((!self.versions.is_empty()).then_some(0));
+    }
+
+    /// How many compared fields of `row` differ from the current record.
+    fn differences(&self, row: &Map<String, Value>) -> usize {
+        FIELDS
+            .iter()
+            .filter(|(_, column)| version_value(row, column) != current_value(&self.record, column))
+            .count()
+    }
+
+    fn open_compare(&mut self) {
+        let Some(index) = self.state.selected() else {
+            return;
+        };
+        let Some(row) = self.versions.get(index).and_then(|v| v.row.as_ref()) else {
+            return;
+        };
+        let fields = FIELDS
+            .iter()
+            .map(|(label, column)| {
+                (
+                    t(label).to_string(),
+                    shown(version_value(row, column)),
+                    shown(current_value(&self.record, column)),
+                )
+            })
+            .collect();
+        let pane = ComparePane::new(
+            tf("history.pane_title", &[("id", &self.record.id)]),
+            tf(
+                "history.version_of",
+                &[("when", &when(&self.versions[index].changed_at))],
+            ),
+            t("history.current"),
+            fields,
+            Side::Right,
+        )
+        .picking()
+        .confirm_with(t("history.confirm"));
+        self.compare = Some((index, pane));
+    }
+
+    /// Writes the values picked from version `index` over the record.
+    fn restore(&mut self, index: usize, picks: &[Side]) {
+        let Some(row) = self.versions.get(index).and_then(|v| v.row.as_ref()) else {
+            return;
+        };
+        let mut record = self.record.clone();
+        let mut changed = false;
+        for ((_, column), side) in FIELDS.iter().zip(picks) {
+            let value = version_value(row, column);
+            if *side == Side::Right || value == current_value(&record, column) {
+                continue;
+            }
+            changed = true;
+            match *column {
+                "diagnosis" => record.diagnosis = value.unwrap_or_default(),
+                "doctor_notes" => record.doctor_notes = value.unwrap_or_default(),
+                "nurse_notes" => record.nurse_notes = value,
+                _ => record.prescription = value,
+            }
+        }
+        if !changed {
+            toast::push(Toast::success(t("history.nothing_differs")));
+            return;
+        }
+        match db::update_medical_record(&self.db, &record) {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "history.restored",
+                    &[
+                        ("id", &record.id),
+                        ("when", &when(&self.versions[index].changed_at)),
+                    ],
+                )));
+                self.record = record;
+                self.load_versions();
+            }
+            Err(e) => toast::push(Toast::error(tf("history.restore_failed", &[("error", &e)]))),
+        }
+    }
+
+    /// Returns true once the history is closed.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if let Some((index, pane)) = &mut self.compare {
+            match pane.handle_key(key) {
+                Some(CompareAction::Confirm) => {
+                    let (index, picks) = (*index, pane.picks());
+                    self.compare = None;
+                    self.restore(index, &picks);
+                }
+                Some(CompareAction::Cancel) => self.compare = None,
+                None => {}
+            }
+            return false;
+        }
+        let len = self.versions.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Enter => self.open_compare(),
+            KeyCode::Esc | KeyCode::Char('q') => return true,
+            _ => {}
+        }
+        false
+    }
+
+    pub fn render_popup(&self, frame: &mut Frame) {
+        if let Some((_, pane)) = &self.compare {
+            pane.render(frame);
+            return;
+        }
+        let area = frame.area();
+        let width = 90.min(area.width);
+        let height = (self.versions.len() as u16 + 7)
+            .clamp(9, 24)
+            .min(area.height);
+        let popup = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, popup);
+        let block = Block::default()
+            .title(format!(" Record #{} History ", self.record.id))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(popup);
+        frame.render_widget(block, popup);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .horizontal_margin(1)
+            .split(inner);
+
+        if self.versions.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("history.no_versions"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center),
+                layout[0],
+            );
+        } else {
+            let widths = [
+                Constraint::Length(19),
+                Constraint::Length(16),
+                Constraint::Min(20),
+                Constraint::Length(12),
+            ];
+            let columns = column_widths(layout[0], &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .versions
+                .iter()
+                .map(|version| {
+                    let (diagnosis, differences) = match &version.row {
+                        Some(row) => (
+                            shown(version_value(row, "diagnosis")),
+                            match self.differences(row) {
+                                0 => t("history.current_lower").to_string(),
+                                n => tf("history.fields", &[("count", &n)]),
+                            },
+                        ),
+                        None => (t("history.deleted").to_string(), "-".to_string()),
+                    };
+                    Row::new(vec![
+                        cell(when(&version.changed_at), &columns, 0),
+                        cell(version.origin.as_str(), &columns, 1),
+                        cell(diagnosis, &columns, 2),
+                        cell(differences, &columns, 3),
+                    ])
+                    .style(Style::default().fg(theme().text))
+                })
+                .collect();
+            let header = Row::new(vec![
+                t("history.changed"),
+                t("history.instance"),
+                t("campaign.diagnosis"),
+                t("history.differs"),
+            ])
+            .style(Style::default().bg(theme().header).fg(theme().title));
+            let table = Table::new(rows, widths)
+                .header(header)
+                .row_highlight_style(Style::default().bg(theme().highlight))
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[0], &mut self.state.clone());
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("history.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[1],
+        );
+    }
+}
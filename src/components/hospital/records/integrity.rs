@@ -0,0 +1,524 @@
+//! Data-quality checks across patients, staff, records, invoices and
+//! shifts: rows pointing at someone who no longer exists, dates that do not
+//! read, negative amounts, required fields left blank, and procedure codes
+//! missing from the catalog or recorded without being billed (and the other
+//! way round). Each finding opens the screen that fixes it.
+
+use crate::app::SelectedApp;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{datetime, procedures};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entity {
+    Patient,
+    Staff,
+    Record,
+    Invoice,
+    Shift,
+}
+
+impl Entity {
+    const ALL: [Entity; 5] = [
+        Entity::Patient,
+        Entity::Staff,
+        Entity::Record,
+        Entity::Invoice,
+        Entity::Shift,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Entity::Patient => t("integrity.patient"),
+            Entity::Staff => t("integrity.staff"),
+            Entity::Record => t("integrity.record"),
+            Entity::Invoice => t("integrity.invoice"),
+            Entity::Shift => t("integrity.shift"),
+        }
+    }
+
+    /// The screen that edits this kind of row. Shifts have none; they are
+    /// removed from the report and assigned again.
+    pub fn fix_app(self) -> Option<SelectedApp> {
+        match self {
+            Entity::Patient => Some(SelectedApp::PatientUpdate),
+            Entity::Staff => Some(SelectedApp::StaffUpdate),
+            Entity::Record => Some(SelectedApp::RecordUpdate),
+            Entity::Invoice => Some(SelectedApp::BillingUpdate),
+            Entity::Shift => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub entity: Entity,
+    pub id: i64,
+    problem: String,
+}
+
+fn finding(entity: Entity, id: i64, problem: impl Into<String>) -> Finding {
+    Finding {
+        entity,
+        id,
+        problem: problem.into(),
+    }
+}
+
+/// One finding per blank field among `fields`.
+fn blank_fields(findings: &mut Vec<Finding>, entity: Entity, id: i64, fields: &[(&str, &str)]) {
+    for (name, value) in fields {
+        if value.trim().is_empty() {
+            findings.push(finding(
+                entity,
+                id,
+                tf("integrity.blank", &[("field", &name)]),
+            ));
+        }
+    }
+}
+
+fn scan(db: &Db) -> Result<Vec<Finding>> {
+    let patients = db::get_all_patients(db)?;
+    let staff = db::get_all_staff(db)?;
+    let patient_ids: HashSet<i64> = patients.iter().map(|p| p.id).collect();
+    let staff_ids: HashSet<i64> = staff.iter().map(|s| s.id).collect();
+    let today = datetime::today();
+    let mut findings = Vec::new();
+
+    for p in &patients {
+        match datetime::parse_date(&p.date_of_birth) {
+            None => findings.push(finding(
+                Entity::Patient,
+                p.id,
+                tf("integrity.bad_dob", &[("value", &p.date_of_birth)]),
+            )),
+            Some(dob) if dob > today => findings.push(finding(
+                Entity::Patient,
+                p.id,
+                tf("integrity.future_dob", &[("value", &p.date_of_birth)]),
+            )),
+            Some(_) => {}
+        }
+        blank_fields(
+            &mut findings,
+            Entity::Patient,
+            p.id,
+            &[
+                (t("integrity.first_name"), &p.first_name),
+                (t("integrity.last_name"), &p.last_name),
+                (t("integrity.street"), &p.address),
+                (t("common.phone"), &p.phone_number),
+            ],
+        );
+    }
+
+    for s in &staff {
+        blank_fields(
+            &mut findings,
+            Entity::Staff,
+            s.id,
+            &[
+                (t("stock.name"), &s.name),
+                (t("common.phone"), &s.phone_number),
+                (t("integrity.street"), &s.address),
+            ],
+        );
+    }
+
+    let catalog = db::get_procedures(db)?;
+    let records = db::get_all_medical_records(db)?;
+    let invoices = db::get_all_invoices(db)?;
+    // Procedures by patient on each side, to check one against the other.
+    let recorded: HashSet<(i64, String)> = records
+        .iter()
+        .flat_map(|r| {
+            procedures::codes(r.procedures.as_deref().unwrap_or_default())
+                .into_iter()
+                .map(move |code| (r.patient_id, code.to_uppercase()))
+        })
+        .collect();
+    let billed: HashSet<(i64, String)> = invoices
+        .iter()
+        .filter_map(|i| Some((i.patient_id, i.procedure_code.as_ref()?.to_uppercase())))
+        .collect();
+
+    for r in records {
+        if !patient_ids.contains(&r.patient_id) {
+            findings.push(finding(
+                Entity::Record,
+                r.id,
+                tf("integrity.no_patient", &[("id", &r.patient_id)]),
+            ));
+        }
+        blank_fields(
+            &mut findings,
+            Entity::Record,
+            r.id,
+            &[
+                (t("integrity.doctor_notes"), &r.doctor_notes),
+                (t("history.diagnosis"), &r.diagnosis),
+            ],
+        );
+        for code in procedures::codes(r.procedures.as_deref().unwrap_or_default()) {
+            if procedures::find(&catalog, &code).is_none() {
+                findings.push(finding(
+                    Entity::Record,
+                    r.id,
+                    tf("integrity.not_in_catalog", &[("code", &code)]),
+                ));
+            } else if !billed.contains(&(r.patient_id, code.to_uppercase())) {
+                findings.push(finding(
+                    Entity::Record,
+                    r.id,
+                    tf("integrity.not_billed", &[("code", &code)]),
+                ));
+            }
+        }
+    }
+
+    for i in invoices {
+        if !patient_ids.contains(&i.patient_id) {
+            findings.push(finding(
+                Entity::Invoice,
+                i.id,
+                tf("integrity.no_patient", &[("id", &i.patient_id)]),
+            ));
+        }
+        if i.cost < 0.0 {
+            findings.push(finding(
+                Entity::Invoice,
+                i.id,
+                tf(
+                    "integrity.negative_cost",
+                    &[("cost", &format!("{:.2}", i.cost))],
+                ),
+            ));
+        }
+        if i.quantity <= 0 {
+            findings.push(finding(
+                Entity::Invoice,
+                i.id,
+                tf("integrity.quantity", &[("quantity", &i.quantity)]),
+            ));
+        }
+        if let Some(created_at) = &i.created_at {
+            if datetime::parse_date(datetime::date_part(created_at)).is_none() {
+                findings.push(finding(
+                    Entity::Invoice,
+                    i.id,
+                    tf("integrity.bad_created", &[("value", &created_at)]),
+                ));
+            }
+        }
+        blank_fields(
+            &mut findings,
+            Entity::Invoice,
+            i.id,
+            &[(t("invoices.item"), &i.item)],
+        );
+        if let Some(code) = &i.procedure_code {
+            if procedures::find(&catalog, code).is_none() {
+                findings.push(finding(
+                    Entity::Invoice,
+                    i.id,
+                    tf("integrity.not_in_catalog", &[("code", &code)]),
+                ));
+            } else if !recorded.contains(&(i.patient_id, code.to_uppercase())) {
+                findings.push(finding(
+                    Entity::Invoice,
+                    i.id,
+                    tf("integrity.not_on_records", &[("code", &code)]),
+                ));
+            }
+        }
+    }
+
+    for (id, staff_id, date, shift) in db::get_all_shifts(db)? {
+        if !staff_ids.contains(&staff_id) {
+            findings.push(finding(
+                Entity::Shift,
+                id,
+                tf("integrity.no_staff", &[("id", &staff_id)]),
+            ));
+        }
+        if datetime::parse_date(&date).is_none() {
+            findings.push(finding(
+                Entity::Shift,
+                id,
+                tf("integrity.bad_date", &[("value", &date)]),
+            ));
+        }
+        blank_fields(
+            &mut findings,
+            Entity::Shift,
+            id,
+            &[(t("integrity.shift"), &shift)],
+        );
+    }
+
+    Ok(findings)
+}
+
+pub struct IntegrityReport {
+    db: Db,
+    findings: Vec<Finding>,
+    state: TableState,
+    confirm_remove: bool,
+    /// The finding picked with Enter, for the app to open once it has
+    /// switched to the fixing screen.
+    fix: Option<Finding>,
+}
+
+impl IntegrityReport {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            findings: Vec::new(),
+            state: TableState::default(),
+            confirm_remove: false,
+            fix: None,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.findings = scan(&self.db)?;
+        match self.state.selected() {
+            _ if self.findings.is_empty() => self.state.select(None),
+            Some(i) if i < self.findings.len() => {}
+            _ => self.state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    pub fn take_fix(&mut self) -> Option<Finding> {
+        self.fix.take()
+    }
+
+    fn selected(&self) -> Option<&Finding> {
+        self.state.selected().and_then(|i| self.findings.get(i))
+    }
+
+    fn remove_shift(&mut self) {
+        let Some(shift_id) = self
+            .selected()
+            .filter(|f| f.entity == Entity::Shift)
+            .map(|f| f.id)
+        else {
+            return;
+        };
+        match db::delete_shift(&self.db, shift_id) {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "integrity.shift_removed",
+                    &[("id", &shift_id)],
+                )));
+                if let Err(e) = self.load_data() {
+                    toast::push(Toast::error(tf("integrity.scan_failed", &[("error", &e)])));
+                }
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "integrity.remove_failed",
+                &[("error", &e)],
+            ))),
+        }
+    }
+
+    fn summary(&self) -> Line<'static> {
+        if self.findings.is_empty() {
+            return Line::from(Span::styled(
+                t("integrity.no_problems"),
+                Style::default().fg(theme().success),
+            ));
+        }
+        let mut spans = vec![Span::styled(
+            tf("integrity.findings", &[("count", &self.findings.len())]),
+            Style::default()
+                .fg(theme().error)
+                .add_modifier(Modifier::BOLD),
+        )];
+        for entity in Entity::ALL {
+            let count = self.findings.iter().filter(|f| f.entity == entity).count();
+            spans.push(Span::styled("  |  ", Style::default().fg(theme().inactive)));
+            spans.push(Span::styled(
+                format!("{} {}", count, entity.label()),
+                Style::default().fg(theme().text),
+            ));
+        }
+        Line::from(spans)
+    }
+}
+
+impl Component for IntegrityReport {
+    fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if self.confirm_remove {
+            if let KeyCode::Char('y') | KeyCode::Char('Y') = key.code {
+                self.remove_shift();
+            }
+            self.confirm_remove = false;
+            return Ok(None);
+        }
+        let len = self.findings.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Enter => {
+                if let Some(finding) = self.selected().cloned() {
+                    match finding.entity.fix_app() {
+                        Some(app) => {
+                            self.fix = Some(finding);
+                            return Ok(Some(app));
+                        }
+                        None => {
+                            toast::push(Toast::error(t("integrity.no_edit_shift")));
+                        }
+                    }
+                }
+            }
+            KeyCode::Char('x') | KeyCode::Char('X')
+                if self.selected().is_some_and(|f| f.entity == Entity::Shift) =>
+            {
+                self.confirm_remove = true;
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                if let Err(e) = self.load_data() {
+                    toast::push(Toast::error(tf("integrity.scan_failed", &[("error", &e)])));
+                }
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("integrity.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(self.summary()).alignment(Alignment::Center),
+            layout[1],
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.findings.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("integrity.all_ok"))
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[2],
+            );
+        } else {
+            let widths = [
+                Constraint::Length(8),
+                Constraint::Length(7),
+                Constraint::Min(30),
+                Constraint::Length(16),
+            ];
+            let columns = column_widths(block.inner(layout[2]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .findings
+                .iter()
+                .map(|f| {
+                    let fix = match f.entity {
+                        Entity::Patient => t("integrity.update_patient"),
+                        Entity::Staff => t("integrity.update_staff"),
+                        Entity::Record => t("integrity.update_record"),
+                        Entity::Invoice => t("integrity.update_invoice"),
+                        Entity::Shift => t("integrity.remove_x"),
+                    };
+                    Row::new(vec![
+                        cell(f.entity.label(), &columns, 0),
+                        cell(f.id.to_string(), &columns, 1),
+                        cell(f.problem.as_str(), &columns, 2),
+                        cell(fix, &columns, 3),
+                    ])
+                    .style(Style::default().fg(theme().text))
+                })
+                .collect();
+            let header = Row::new(vec![
+                t("integrity.entity"),
+                t("common.id"),
+                t("integrity.problem"),
+                t("integrity.fix"),
+            ])
+            .style(Style::default().bg(theme().header).fg(theme().title));
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(block)
+                .row_highlight_style(Style::default().bg(theme().highlight))
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
+        }
+
+        let status = if self.confirm_remove {
+            Paragraph::new(t("integrity.confirm_remove")).style(
+                Style::default()
+                    .fg(theme().focus)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Paragraph::new("")
+        };
+        frame.render_widget(status.alignment(Alignment::Center), layout[3]);
+
+        frame.render_widget(
+            Paragraph::new(t("integrity.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Every admitted patient in isolation or flagged with an infection, by ward
+//! and bed, for infection control rounds.
+
+use crate::app::SelectedApp;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{Admission, ISOLATION_PRECAUTIONS};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+
+/// Whole days since `isolated_since`, if it reads as a date.
+fn days_isolated(admission: &Admission) -> Option<i64> {
+    let since = datetime::parse_date(datetime::date_part(admission.isolated_since.as_deref()?))?;
+    Some((datetime::today() - since).whole_days())
+}
+
+pub struct IsolationReport {
+    db: Db,
+    isolations: Vec<Admission>,
+    patients: HashMap<i64, String>,
+    state: TableState,
+}
+
+impl IsolationReport {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            isolations: Vec::new(),
+            patients: HashMap::new(),
+            state: TableState::default(),
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.isolations = db::get_active_isolations(&self.db)?;
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, format!("{} {}", p.first_name, p.last_name)))
+            .collect();
+        match self.state.selected() {
+            _ if self.isolations.is_empty() => self.state.select(None),
+            Some(i) if i < self.isolations.len() => {}
+            _ => self.state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    fn summary(&self) -> Line<'static> {
+        let mut spans = vec![Span::styled(
+            tf("isolations.isolated", &[("count", &self.isolations.len())]),
+            Style::default()
+                .fg(theme().error)
+                .add_modifier(Modifier::BOLD),
+        )];
+        for precaution in ISOLATION_PRECAUTIONS {
+            let count = self
+                .isolations
+                .iter()
+                .filter(|a| a.isolation.as_deref() == Some(precaution))
+                .count();
+            spans.push(Span::styled("  |  ", Style::default().fg(theme().inactive)));
+            spans.push(Span::styled(
+                format!("{} {}", count, precaution),
+                Style::default().fg(theme().text),
+            ));
+        }
+        let flagged_only = self
+            .isolations
+            .iter()
+            .filter(|a| a.isolation.is_none())
+            .count();
+        if flagged_only > 0 {
+            spans.push(Span::styled(
+                format!(
+                    "  {}",
+                    tf("isolations.flagged", &[("count", &flagged_only)])
+                ),
+                Style::default().fg(theme().warning),
+            ));
+        }
+        Line::from(spans)
+    }
+}
+
+impl Component for IsolationReport {
+    fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        let len = self.isolations.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                if let Err(e) = self.load_data() {
+                    toast::push(Toast::error(tf("isolations.load_failed", &[("error", &e)])));
+                }
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("isolations.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(self.summary()).alignment(Alignment::Center),
+            layout[1],
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.isolations.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("isolations.empty"))
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[2],
+            );
+        } else {
+            let widths = [
+                Constraint::Percentage(15),
+                Constraint::Length(6),
+                Constraint::Percentage(22),
+                Constraint::Length(12),
+                Constraint::Min(16),
+                Constraint::Length(11),
+                Constraint::Length(6),
+            ];
+            let columns = column_widths(block.inner(layout[2]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .isolations
+                .iter()
+                .map(|a| {
+                    Row::new(vec![
+                        cell(a.ward.as_str(), &columns, 0),
+                        cell(a.bed.as_deref().unwrap_or("-"), &columns, 1),
+                        cell(
+                            self.patients
+                                .get(&a.patient_id)
+                                .cloned()
+                                .unwrap_or_else(|| {
+                                    tf("common.patient_number", &[("id", &a.patient_id)])
+                                }),
+                            &columns,
+                            2,
+                        ),
+                        cell(a.isolation.as_deref().unwrap_or("-"), &columns, 3),
+                        cell(a.infection_flags.as_deref().unwrap_or("-"), &columns, 4),
+                        cell(
+                            a.isolated_since
+                                .as_deref()
+                                .map(datetime::date_part)
+                                .unwrap_or("-"),
+                            &columns,
+                            5,
+                        ),
+                        cell(
+                            days_isolated(a).map_or("-".to_string(), |d| d.to_string()),
+                            &columns,
+                            6,
+                        ),
+                    ])
+                    .style(Style::default().fg(theme().text))
+                })
+                .collect();
+            let header = Row::new(vec![
+                t("kiosk.ward"),
+                t("kiosk.bed"),
+                t("kiosk.patient"),
+                t("isolations.precautions"),
+                t("isolations.infections"),
+                t("isolations.since"),
+                t("maternity.due.days"),
+            ])
+            .style(Style::default().bg(theme().header).fg(theme().title));
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(block)
+                .row_highlight_style(Style::default().bg(theme().highlight))
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("isolations.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+    }
+}
@@ -1,13 +1,24 @@
 use crate::app::SelectedApp;
 use crate::components::Component;
+use crate::db::Db;
 use crate::tui::Frame;
 use anyhow::Result;
 use crossterm::event::KeyEvent;
 
+pub mod campaign;
 pub mod delete;
+pub mod expirations;
+pub mod forecast;
+pub mod history;
+pub mod integrity;
+pub mod isolations;
 pub mod retrieve;
+pub mod satisfaction;
+pub mod statistics;
+pub mod stays;
 pub mod store;
 pub mod update;
+pub mod waiting;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RecordsState {
@@ -15,6 +26,15 @@ pub enum RecordsState {
     RetrieveRecords,
     DeleteRecord,
     UpdateRecord,
+    Statistics,
+    Expirations,
+    Isolations,
+    Stays,
+    Integrity,
+    Waiting,
+    Forecast,
+    Satisfaction,
+    Campaign,
 }
 
 pub struct Records {
@@ -22,24 +42,73 @@ pub struct Records {
     pub retrieve_records: retrieve::RetrieveRecords,
     pub delete_record: Option<delete::DeleteRecord>,
     pub update_record: Option<update::UpdateRecord>,
+    pub statistics: statistics::PublicHealthStatistics,
+    pub expirations: expirations::ExpirationReport,
+    pub isolations: isolations::IsolationReport,
+    pub stays: stays::StayReport,
+    pub integrity: integrity::IntegrityReport,
+    pub waiting: waiting::WaitingReport,
+    pub forecast: forecast::BedForecast,
+    pub satisfaction: satisfaction::SatisfactionReport,
+    pub campaign: campaign::VaccinationCampaign,
     pub state: RecordsState,
 }
 
 impl Records {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
         Self {
-            store_record: store::StoreRecord::new(),
-            retrieve_records: retrieve::RetrieveRecords::new(),
+            store_record: store::StoreRecord::new(db),
+            retrieve_records: retrieve::RetrieveRecords::new(db),
             delete_record: None,
             update_record: None,
+            statistics: statistics::PublicHealthStatistics::new(db),
+            expirations: expirations::ExpirationReport::new(db),
+            isolations: isolations::IsolationReport::new(db),
+            stays: stays::StayReport::new(db),
+            integrity: integrity::IntegrityReport::new(db),
+            waiting: waiting::WaitingReport::new(db),
+            forecast: forecast::BedForecast::new(db),
+            satisfaction: satisfaction::SatisfactionReport::new(db),
+            campaign: campaign::VaccinationCampaign::new(db),
             state: RecordsState::RetrieveRecords,
         }
     }
 
+    pub fn save_view(&self) {
+        self.retrieve_records.save_view();
+    }
+
     pub fn initialize_list(&mut self) -> Result<()> {
         if self.state == RecordsState::RetrieveRecords {
             self.retrieve_records.fetch_records()?;
         }
+        if self.state == RecordsState::Statistics {
+            self.statistics.fetch_cases()?;
+        }
+        if self.state == RecordsState::Expirations {
+            self.expirations.load_data()?;
+        }
+        if self.state == RecordsState::Isolations {
+            self.isolations.load_data()?;
+        }
+        if self.state == RecordsState::Stays {
+            self.stays.load_data()?;
+        }
+        if self.state == RecordsState::Integrity {
+            self.integrity.load_data()?;
+        }
+        if self.state == RecordsState::Waiting {
+            self.waiting.load_data()?;
+        }
+        if self.state == RecordsState::Forecast {
+            self.forecast.load_data()?;
+        }
+        if self.state == RecordsState::Satisfaction {
+            self.satisfaction.load_data()?;
+        }
+        if self.state == RecordsState::Campaign {
+            self.campaign.load_data()?;
+        }
         self.store_record.load_patients()?;
         Ok(())
     }
@@ -98,6 +167,51 @@ impl Component for Records {
                     }
                 }
             }
+            RecordsState::Statistics => {
+                if let Some(SelectedApp::None) = self.statistics.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
+            RecordsState::Expirations => {
+                if let Some(SelectedApp::None) = self.expirations.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
+            RecordsState::Isolations => {
+                if let Some(SelectedApp::None) = self.isolations.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
+            RecordsState::Stays => {
+                if let Some(SelectedApp::None) = self.stays.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
+            RecordsState::Integrity => {
+                if let Some(selected_app) = self.integrity.handle_input(event)? {
+                    return Ok(Some(selected_app));
+                }
+            }
+            RecordsState::Waiting => {
+                if let Some(SelectedApp::None) = self.waiting.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
+            RecordsState::Forecast => {
+                if let Some(SelectedApp::None) = self.forecast.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
+            RecordsState::Satisfaction => {
+                if let Some(SelectedApp::None) = self.satisfaction.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
+            RecordsState::Campaign => {
+                if let Some(SelectedApp::None) = self.campaign.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
         }
         Ok(None)
     }
@@ -116,12 +230,21 @@ impl Component for Records {
                     update_record.render(frame);
                 }
             }
+            RecordsState::Statistics => self.statistics.render(frame),
+            RecordsState::Expirations => self.expirations.render(frame),
+            RecordsState::Isolations => self.isolations.render(frame),
+            RecordsState::Stays => self.stays.render(frame),
+            RecordsState::Integrity => self.integrity.render(frame),
+            RecordsState::Waiting => self.waiting.render(frame),
+            RecordsState::Forecast => self.forecast.render(frame),
+            RecordsState::Satisfaction => self.satisfaction.render(frame),
+            RecordsState::Campaign => self.campaign.render(frame),
         }
     }
-}
 
-impl Default for Records {
-    fn default() -> Self {
-        Self::new()
+    fn tick(&mut self) {
+        if self.state == RecordsState::Statistics {
+            self.statistics.tick();
+        }
     }
 }
@@ -1,8 +1,26 @@
 use crate::app::SelectedApp;
+use crate::components::hospital::records::history::RecordHistory;
+use crate::components::master_detail;
+use crate::components::responsive::is_narrow;
+use crate::components::scroll::render_scrollable_text;
+use crate::components::table::{cell, column_widths, Columns};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::aftercare::AftercarePopup;
+use crate::components::widgets::column_picker::{self, ColumnPicker};
+use crate::components::widgets::export_view::{self, ExportView, ShownRows};
+use crate::components::widgets::label::LabelPopup;
+use crate::components::widgets::print_dialog::PrintDialog;
+use crate::components::widgets::saved_searches::{self, SavedSearchOutcome, SavedSearchesPopup};
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
-use crate::db;
-use crate::models::{MedicalRecord, Patient};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{MedicalRecord, Patient, PrescribingOverride};
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::ui_state::{self, Recent, ViewState};
+use crate::utils::aftercare::Visit;
+use crate::utils::{barcode, copy_to_clipboard, datetime, labels, tsv_line};
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{prelude::*, widgets::*};
@@ -11,6 +29,23 @@ use std::collections::HashMap;
 const SEARCH_FIELD: usize = 0;
 const RECORD_LIST: usize = 1;
 const BACK_BUTTON: usize = 2;
+const VIEW_KEY: &str = "records.retrieve";
+const HEADERS: [&str; 4] = [
+    "common.id",
+    "common.first_name",
+    "common.last_name",
+    "retrieve.campaign.diagnosis",
+];
+const WIDTHS: [Constraint; 4] = [
+    Constraint::Percentage(10),
+    Constraint::Percentage(20),
+    Constraint::Percentage(20),
+    Constraint::Percentage(50),
+];
+/// The columns kept on narrow terminals.
+const MINIMAL_COLUMNS: [usize; 3] = [0, 2, 3];
+
+const DETAIL_SECTIONS: usize = 4;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RetrieveState {
@@ -18,34 +53,110 @@ pub enum RetrieveState {
     ViewingDetails,
 }
 pub struct RetrieveRecords {
+    db: Db,
     records: Vec<MedicalRecord>,
     filtered_records: Vec<MedicalRecord>,
-    search_input: String,
+    search_input: TextInput,
     is_searching: bool,
     state: TableState,
-    error_message: Option<String>,
     focus_index: usize,
     view_state: RetrieveState,
     patients: HashMap<i64, Patient>,
+    /// What admitted patients are isolated for, by patient.
+    isolations: HashMap<i64, String>,
+    /// Prescribing alerts saved through on the record being viewed.
+    overrides: Vec<PrescribingOverride>,
+    detail_focus: usize,
+    detail_scroll: [u16; DETAIL_SECTIONS],
+    detail_max_scroll: std::cell::Cell<[u16; DETAIL_SECTIONS]>,
+    print_dialog: Option<PrintDialog>,
+    label: Option<LabelPopup>,
+    history: Option<RecordHistory>,
+    aftercare: Option<AftercarePopup>,
+    saved_searches: Option<SavedSearchesPopup>,
+    export_view: Option<ExportView>,
+    columns: Columns,
+    column_picker: Option<ColumnPicker>,
 }
 
 impl RetrieveRecords {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
+        let view = ui_state::view(VIEW_KEY);
+        let mut search_input = TextInput::new();
+        search_input.set_value(view.search.as_str());
         Self {
+            db: db.clone(),
             records: Vec::new(),
             filtered_records: Vec::new(),
-            search_input: String::new(),
+            search_input,
             is_searching: false,
-            state: TableState::default(),
-            error_message: None,
+            state: view.table(),
             focus_index: RECORD_LIST,
             view_state: RetrieveState::ViewingList,
             patients: HashMap::new(),
+            isolations: HashMap::new(),
+            overrides: Vec::new(),
+            detail_focus: 2,
+            detail_scroll: [0; DETAIL_SECTIONS],
+            detail_max_scroll: std::cell::Cell::new([0; DETAIL_SECTIONS]),
+            print_dialog: None,
+            label: None,
+            history: None,
+            aftercare: None,
+            saved_searches: None,
+            export_view: None,
+            columns: Columns::new(VIEW_KEY, &HEADERS, &[], &MINIMAL_COLUMNS),
+            column_picker: None,
         }
     }
 
+    pub fn save_view(&self) {
+        ui_state::set_view(VIEW_KEY, self.current_view().with_table(&self.state));
+    }
+
+    fn current_view(&self) -> ViewState {
+        ViewState {
+            search: self.search_input.value().to_string(),
+            ..ViewState::default()
+        }
+    }
+
+    fn row_values(&self, record: &MedicalRecord) -> Vec<String> {
+        let (first_name, last_name) = match self.get_patient(record.patient_id) {
+            Some(patient) => (patient.first_name.clone(), patient.last_name.clone()),
+            None => (t("common.unknown_patient_name").to_string(), String::new()),
+        };
+        vec![
+            record.id.to_string(),
+            first_name,
+            last_name,
+            record.diagnosis.clone(),
+        ]
+    }
+
+    fn shown_rows(&self) -> ShownRows {
+        let visible = self.columns.visible(false);
+        let mut shown = ShownRows::new(&Columns::pick(&HEADERS, &visible));
+        shown.rows = self
+            .filtered_records
+            .iter()
+            .map(|r| Columns::pick(&self.row_values(r), &visible))
+            .collect();
+        shown
+    }
+
+    /// Shows the records matching a saved search, from the top.
+    fn apply_view(&mut self, view: ViewState) {
+        self.search_input.set_value(view.search.as_str());
+        self.is_searching = false;
+        self.focus_index = RECORD_LIST;
+        self.filter_records();
+        let empty = self.filtered_records.is_empty();
+        self.state.select((!empty).then_some(0));
+    }
+
     pub fn fetch_records(&mut self) -> Result<()> {
-        match db::get_all_medical_records() {
+        match db::get_all_medical_records(&self.db) {
             Ok(records) => {
                 self.records = records;
                 self.fetch_patients_data()?;
@@ -61,11 +172,10 @@ impl RetrieveRecords {
                         .min(self.filtered_records.len() - 1);
                     self.state.select(Some(selection));
                 }
-                self.error_message = None;
                 Ok(())
             }
             Err(e) => {
-                self.error_message = Some(format!("Failed to fetch records: {}", e));
+                toast::push(Toast::error(tf("retrieve.fetch_failed", &[("error", &e)])));
                 Ok(())
             }
         }
@@ -73,8 +183,13 @@ impl RetrieveRecords {
 
     fn fetch_patients_data(&mut self) -> Result<()> {
         self.patients.clear();
+        self.isolations = db::get_active_isolations(&self.db)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|a| Some((a.patient_id, a.isolation_text()?)))
+            .collect();
 
-        match db::get_all_patients() {
+        match db::get_all_patients(&self.db) {
             Ok(all_patients) => {
                 for patient in all_patients {
                     self.patients.insert(patient.id, patient);
@@ -82,7 +197,10 @@ impl RetrieveRecords {
                 Ok(())
             }
             Err(e) => {
-                self.error_message = Some(format!("Failed to fetch patient data: {}", e));
+                toast::push(Toast::error(tf(
+                    "records.delete.fetch_failed",
+                    &[("error", &e)],
+                )));
                 Ok(())
             }
         }
@@ -92,7 +210,7 @@ impl RetrieveRecords {
         if self.search_input.is_empty() {
             self.filtered_records = self.records.clone();
         } else {
-            let search_term = self.search_input.to_lowercase();
+            let search_term = self.search_input.value().to_lowercase();
             self.filtered_records = self
                 .records
                 .iter()
@@ -160,9 +278,60 @@ impl RetrieveRecords {
     fn view_record_details(&mut self) {
         if !self.filtered_records.is_empty() && self.state.selected().is_some() {
             self.view_state = RetrieveState::ViewingDetails;
+            self.detail_focus = 2;
+            self.detail_scroll = [0; DETAIL_SECTIONS];
+            self.overrides = self
+                .selected_record()
+                .and_then(|r| db::get_prescribing_overrides(&self.db, r.id).ok())
+                .unwrap_or_default();
+            self.warn_isolation();
+            if let Some(record) = self.selected_record() {
+                ui_state::add_recent(Recent::record(
+                    record.id,
+                    record.patient_id,
+                    self.get_patient(record.patient_id),
+                ));
+            }
+        }
+    }
+
+    /// Clears the search and opens record `record_id`, for a recently opened
+    /// record.
+    pub fn show_record(&mut self, record_id: i64) {
+        self.search_input.clear();
+        self.filter_records();
+        let Some(index) = self.filtered_records.iter().position(|r| r.id == record_id) else {
+            return;
+        };
+        self.state.select(Some(index));
+        self.focus_index = RECORD_LIST;
+        self.is_searching = false;
+        self.view_record_details();
+    }
+
+    /// Warns whoever opens the record of a patient in isolation.
+    fn warn_isolation(&self) {
+        let Some(record) = self.selected_record() else {
+            return;
+        };
+        if let Some(isolation) = self.isolations.get(&record.patient_id) {
+            let name = self
+                .get_patient(record.patient_id)
+                .map(|p| format!("{} {}", p.first_name, p.last_name))
+                .unwrap_or_else(|| tf("common.patient_number", &[("id", &record.patient_id)]));
+            toast::push(Toast::error(tf(
+                "retrieve.in_isolation",
+                &[("name", &name), ("precautions", &isolation)],
+            )));
         }
     }
 
+    fn scroll_details(&mut self, delta: i32) {
+        let max = self.detail_max_scroll.get()[self.detail_focus];
+        let offset = &mut self.detail_scroll[self.detail_focus];
+        *offset = (i32::from((*offset).min(max)) + delta).clamp(0, i32::from(max)) as u16;
+    }
+
     fn return_to_list(&mut self) {
         self.view_state = RetrieveState::ViewingList;
     }
@@ -178,16 +347,73 @@ impl RetrieveRecords {
     }
 
     pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(dialog) = self.print_dialog.as_mut() {
+            if dialog.handle_key(key) {
+                self.print_dialog = None;
+            }
+            return Ok(None);
+        }
+        if let Some(label) = &mut self.label {
+            if label.handle_key(key) {
+                self.label = None;
+            }
+            return Ok(None);
+        }
+        if let Some(history) = &mut self.history {
+            if history.handle_key(key) {
+                self.history = None;
+                // A restore changed the record.
+                self.fetch_records()?;
+            }
+            return Ok(None);
+        }
+        if let Some(aftercare) = &mut self.aftercare {
+            if aftercare.handle_key(key) {
+                self.aftercare = None;
+            }
+            return Ok(None);
+        }
+        if let Some(export) = &mut self.export_view {
+            if export.handle_key(key) {
+                self.export_view = None;
+            }
+            return Ok(None);
+        }
+        if let Some(picker) = &mut self.column_picker {
+            if picker.handle_key(key, &mut self.columns) {
+                self.column_picker = None;
+            }
+            return Ok(None);
+        }
+        if let Some(popup) = &mut self.saved_searches {
+            match popup.handle_key(key) {
+                Some(SavedSearchOutcome::Apply(view)) => {
+                    self.saved_searches = None;
+                    self.apply_view(view);
+                }
+                Some(SavedSearchOutcome::Closed) => self.saved_searches = None,
+                None => {}
+            }
+            return Ok(None);
+        }
         match self.view_state {
             RetrieveState::ViewingList => {
+                if saved_searches::is_open_key(key) {
+                    self.saved_searches =
+                        Some(SavedSearchesPopup::new(VIEW_KEY, self.current_view()));
+                    return Ok(None);
+                }
+                if export_view::is_open_key(key) {
+                    self.export_view = Some(ExportView::new("records", self.shown_rows()));
+                    return Ok(None);
+                }
+                if column_picker::is_open_key(key) {
+                    self.column_picker = Some(ColumnPicker::new());
+                    return Ok(None);
+                }
                 if self.is_searching {
                     match key.code {
-                        KeyCode::Char(c) => {
-                            self.search_input.push(c);
-                            self.filter_records();
-                        }
-                        KeyCode::Backspace => {
-                            self.search_input.pop();
+                        _ if self.search_input.handle_key(key) => {
                             self.filter_records();
                         }
                         KeyCode::Enter | KeyCode::Down | KeyCode::Tab => {
@@ -241,8 +467,32 @@ impl RetrieveRecords {
                         return Ok(Some(SelectedApp::None));
                     }
                     KeyCode::Char('r') | KeyCode::Char('R') => {
+                        db::cache::invalidate_all(&self.db);
                         self.fetch_records()?;
                     }
+                    KeyCode::Char('y') if self.focus_index == RECORD_LIST => {
+                        self.copy_selected(false)
+                    }
+                    KeyCode::Char('Y') if self.focus_index == RECORD_LIST => {
+                        self.copy_selected(true)
+                    }
+                    KeyCode::Char('p') | KeyCode::Char('P') if self.focus_index == RECORD_LIST => {
+                        self.print_selected()
+                    }
+                    KeyCode::Char('l') | KeyCode::Char('L') if self.focus_index == RECORD_LIST => {
+                        self.open_pharmacy_label()
+                    }
+                    KeyCode::Char('h') | KeyCode::Char('H') if self.focus_index == RECORD_LIST => {
+                        self.open_history()
+                    }
+                    KeyCode::Char('a') | KeyCode::Char('A') if self.focus_index == RECORD_LIST => {
+                        self.open_aftercare()
+                    }
+                    KeyCode::Char('1'..='9') if self.focus_index == RECORD_LIST => {
+                        if let Some(view) = saved_searches::shortcut(VIEW_KEY, key) {
+                            self.apply_view(view);
+                        }
+                    }
                     KeyCode::Esc => {
                         return Ok(Some(SelectedApp::None));
                     }
@@ -256,12 +506,120 @@ impl RetrieveRecords {
                 KeyCode::Char('b') | KeyCode::Char('B') => {
                     self.return_to_list();
                 }
+                KeyCode::Tab => self.detail_focus = (self.detail_focus + 1) % DETAIL_SECTIONS,
+                KeyCode::BackTab => {
+                    self.detail_focus = (self.detail_focus + DETAIL_SECTIONS - 1) % DETAIL_SECTIONS
+                }
+                KeyCode::Down => self.scroll_details(1),
+                KeyCode::Up => self.scroll_details(-1),
+                KeyCode::PageDown => self.scroll_details(10),
+                KeyCode::PageUp => self.scroll_details(-10),
+                KeyCode::Home => self.detail_scroll[self.detail_focus] = 0,
+                KeyCode::Char('y') => self.copy_selected(false),
+                KeyCode::Char('Y') => self.copy_selected(true),
+                KeyCode::Char('p') | KeyCode::Char('P') => self.print_selected(),
+                KeyCode::Char('l') | KeyCode::Char('L') => self.open_pharmacy_label(),
+                KeyCode::Char('h') | KeyCode::Char('H') => self.open_history(),
+                KeyCode::Char('a') | KeyCode::Char('A') => self.open_aftercare(),
                 _ => {}
             },
         }
         Ok(None)
     }
 
+    fn copy_selected(&mut self, full_row: bool) {
+        let Some(record) = self.selected_record() else {
+            return;
+        };
+        let (text, what) = if full_row {
+            let id = record.id.to_string();
+            let patient_id = record.patient_id.to_string();
+            let patient_name = self
+                .get_patient(record.patient_id)
+                .map(|p| format!("{} {}", p.first_name, p.last_name))
+                .unwrap_or_default();
+            let row = tsv_line(&[
+                &id,
+                &patient_id,
+                &patient_name,
+                &record.diagnosis,
+                &record.doctor_notes,
+                record.nurse_notes.as_deref().unwrap_or_default(),
+                record.prescription.as_deref().unwrap_or_default(),
+            ]);
+            (row, t("retrieve.record_row"))
+        } else {
+            let notes = match &record.nurse_notes {
+                Some(nurse_notes) if !nurse_notes.is_empty() => format!(
+                    "Doctor's notes:\n{}\n\nNurse's notes:\n{}",
+                    record.doctor_notes, nurse_notes
+                ),
+                _ => record.doctor_notes.clone(),
+            };
+            (notes, t("campaign.notes"))
+        };
+        match copy_to_clipboard(&text) {
+            Ok(()) => {
+                toast::push(Toast::success(tf("retrieve.copied", &[("what", &what)])));
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("retrieve.copy_failed", &[("error", &e)])));
+            }
+        }
+    }
+
+    fn print_selected(&mut self) {
+        let Some(record) = self.selected_record() else {
+            return;
+        };
+        let document = record_document(record, self.get_patient(record.patient_id));
+        self.print_dialog = Some(PrintDialog::new(
+            &self.db,
+            tf("retrieve.print_title", &[("id", &record.id)]),
+            document,
+        ));
+    }
+
+    /// The label for the medication dispensed on the selected record's
+    /// prescription.
+    fn open_pharmacy_label(&mut self) {
+        let Some(record) = self.selected_record() else {
+            return;
+        };
+        if record.prescription.as_deref().is_none_or(str::is_empty) {
+            toast::push(Toast::error(t("retrieve.no_prescription")));
+            return;
+        }
+        let Some(patient) = self.get_patient(record.patient_id) else {
+            return;
+        };
+        let today = datetime::today_text();
+        self.label = LabelPopup::open(&self.db, labels::pharmacy(patient, record, &today));
+    }
+
+    fn open_history(&mut self) {
+        if let Some(record) = self.selected_record() {
+            self.history = Some(RecordHistory::new(&self.db, record.clone()));
+        }
+    }
+
+    /// Aftercare instructions for the selected record's patient, filled in
+    /// with its diagnosis and prescription.
+    fn open_aftercare(&mut self) {
+        let Some(record) = self.selected_record() else {
+            return;
+        };
+        let Some(patient) = self.get_patient(record.patient_id) else {
+            return;
+        };
+        let visit = Visit {
+            diagnosis: Some(record.diagnosis.clone()),
+            prescription: record.prescription.clone(),
+            ward: None,
+        };
+        self.aftercare = Some(AftercarePopup::new(&self.db, patient.clone(), visit));
+    }
+
     fn selected_record(&self) -> Option<&MedicalRecord> {
         self.state
             .selected()
@@ -273,6 +631,50 @@ impl RetrieveRecords {
     }
 }
 
+fn record_document(record: &MedicalRecord, patient: Option<&Patient>) -> String {
+    let optional = |text: &Option<String>| match text.as_deref() {
+        Some(text) if !text.is_empty() => text.to_string(),
+        _ => "-".to_string(),
+    };
+    let mrn = patient.map_or_else(|| "-".to_string(), |p| p.mrn.clone());
+    let mut lines = vec![
+        "RUSTORIA HOSPITAL - MEDICAL RECORD".to_string(),
+        "=".repeat(34),
+        format!("Record No.:     {}", record.id),
+        format!(
+            "Patient:        {} (ID {})",
+            patient
+                .map(|p| format!("{} {}", p.first_name, p.last_name))
+                .unwrap_or_else(|| "Unknown patient".to_string()),
+            record.patient_id
+        ),
+        format!("MRN:            {}", mrn),
+        format!(
+            "Date of birth:  {}",
+            patient.map(|p| p.date_of_birth.as_str()).unwrap_or("-")
+        ),
+        String::new(),
+        format!("Diagnosis:      {}", record.diagnosis),
+        format!("Procedures:     {}", optional(&record.procedures)),
+        String::new(),
+        "Doctor's notes:".to_string(),
+        record.doctor_notes.clone(),
+        String::new(),
+        "Nurse's notes:".to_string(),
+        optional(&record.nurse_notes),
+        String::new(),
+        "Prescription:".to_string(),
+        optional(&record.prescription),
+        String::new(),
+    ];
+    // Lets the printed record be scanned back to the patient.
+    if let Some(qr) = patient.and_then(|p| barcode::qr_lines(&p.mrn).ok()) {
+        lines.extend(qr);
+        lines.push(String::new());
+    }
+    lines.join("\n")
+}
+
 impl Component for RetrieveRecords {
     fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
         self.handle_input(event)
@@ -280,7 +682,7 @@ impl Component for RetrieveRecords {
 
     fn render(&self, frame: &mut Frame) {
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             frame.area(),
         );
 
@@ -288,6 +690,27 @@ impl Component for RetrieveRecords {
             RetrieveState::ViewingList => self.render_list_view(frame),
             RetrieveState::ViewingDetails => self.render_details_view(frame),
         }
+        if let Some(dialog) = &self.print_dialog {
+            dialog.render_popup(frame);
+        }
+        if let Some(label) = &self.label {
+            label.render_popup(frame);
+        }
+        if let Some(history) = &self.history {
+            history.render_popup(frame);
+        }
+        if let Some(aftercare) = &self.aftercare {
+            aftercare.render_popup(frame);
+        }
+        if let Some(popup) = &self.saved_searches {
+            popup.render_popup(frame);
+        }
+        if let Some(export) = &self.export_view {
+            export.render_popup(frame);
+        }
+        if let Some(picker) = &self.column_picker {
+            picker.render_popup(frame, &self.columns);
+        }
     }
 }
 
@@ -299,8 +722,7 @@ impl RetrieveRecords {
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Min(10),
+                Constraint::Min(13),
                 Constraint::Length(1),
                 Constraint::Length(1),
                 Constraint::Length(1),
@@ -308,19 +730,22 @@ impl RetrieveRecords {
             ])
             .margin(1)
             .split(area);
+        let (list_area, details_area) = master_detail::split(layout[1]);
+        let [search_area, table_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(10)]).areas(list_area);
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
 
-        let title = Paragraph::new("🏥 MEDICAL RECORDS")
+        let title = Paragraph::new(t("retrieve.title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
@@ -329,102 +754,96 @@ impl RetrieveRecords {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Records ",
+                format!(" {} ", t("common.search_records")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(75, 75, 120))
+                Style::default().fg(theme().border)
             })
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .style(Style::default().bg(theme().surface));
 
-        let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(22, 22, 35)),
-            )
+        let search_paragraph = Paragraph::new(self.search_input.line(self.is_searching))
+            .style(Style::default().fg(theme().text).bg(theme().surface))
             .block(search_block);
-        frame.render_widget(search_paragraph, layout[1]);
+        frame.render_widget(search_paragraph, search_area);
 
-        let header_cells = ["ID", "First Name", "Last Name", "Diagnosis"]
-            .iter()
-            .map(|h| Cell::from(*h).style(Style::default().fg(Color::Rgb(230, 230, 250))));
+        let visible = self.columns.visible(is_narrow(table_area));
+        let header_cells = Columns::pick(&HEADERS, &visible)
+            .into_iter()
+            .map(|h| Cell::from(t(h)).style(Style::default().fg(theme().title)));
         let header = Row::new(header_cells)
-            .style(Style::default().bg(Color::Rgb(80, 60, 130)))
+            .style(Style::default().bg(theme().header))
             .height(1);
 
-        let rows = self.filtered_records.iter().map(|record| {
-            let (first_name, last_name) = match self.get_patient(record.patient_id) {
-                Some(patient) => (patient.first_name.clone(), patient.last_name.clone()),
-                None => ("Unknown".to_string(), "Patient".to_string()),
-            };
+        let widths = Columns::widths(&WIDTHS, &visible);
+        let columns = column_widths(table_area.inner(Margin::new(1, 1)), &widths, 1, "► ");
 
-            let cells = vec![
-                Cell::from(record.id.to_string()),
-                Cell::from(first_name),
-                Cell::from(last_name),
-                Cell::from(record.diagnosis.clone()),
-            ];
+        let rows = self.filtered_records.iter().map(|record| {
+            let cells = Columns::pick(&self.row_values(record), &visible)
+                .into_iter()
+                .enumerate()
+                .map(|(column, text)| cell(text, &columns, column))
+                .collect::<Vec<_>>();
             Row::new(cells)
                 .height(1)
                 .bottom_margin(0)
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                .style(Style::default().fg(theme().text))
         });
 
         let selected_style = Style::default()
-            .fg(Color::Rgb(250, 250, 110))
-            .bg(Color::Rgb(40, 40, 60))
+            .fg(theme().focus)
+            .bg(theme().highlight)
             .add_modifier(Modifier::BOLD);
 
         let table_title = if !self.search_input.is_empty() {
             format!(
-                " Records ({} of {} matches) ",
-                self.filtered_records.len(),
-                self.records.len()
+                " {} ",
+                tf(
+                    "common.records_matches",
+                    &[
+                        ("shown", &self.filtered_records.len()),
+                        ("total", &self.records.len())
+                    ]
+                )
             )
         } else {
-            format!(" Records ({}) ", self.records.len())
+            format!(
+                " {} ",
+                tf("common.records_count", &[("total", &self.records.len())])
+            )
         };
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Percentage(10),
-                Constraint::Percentage(20),
-                Constraint::Percentage(20),
-                Constraint::Percentage(50),
-            ],
-        )
-        .header(header)
-        .block(
-            Block::default()
-                .title(table_title.clone())
-                .title_alignment(Alignment::Center)
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-                .style(Style::default().bg(Color::Rgb(22, 22, 35))),
-        )
-        .row_highlight_style(selected_style)
-        .highlight_symbol(if self.focus_index == RECORD_LIST {
-            "► "
-        } else {
-            "  "
-        });
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .title(table_title.clone())
+                    .title_alignment(Alignment::Center)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .row_highlight_style(selected_style)
+            .highlight_symbol(if self.focus_index == RECORD_LIST {
+                "► "
+            } else {
+                "  "
+            });
 
         if self.filtered_records.is_empty() {
             let message = if self.search_input.is_empty() {
-                "No records found in database"
+                t("common.no_records")
             } else {
-                "No records match your search criteria"
+                t("common.no_record_matches")
             };
 
             let no_records = Paragraph::new(message)
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                .style(Style::default().fg(theme().text))
                 .alignment(Alignment::Center)
                 .block(
                     Block::default()
@@ -432,50 +851,87 @@ impl RetrieveRecords {
                         .title_alignment(Alignment::Center)
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-                        .style(Style::default().bg(Color::Rgb(22, 22, 35))),
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
                 );
-            frame.render_widget(no_records, layout[2]);
+            frame.render_widget(no_records, table_area);
         } else {
-            frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
+            frame.render_stateful_widget(table, table_area, &mut self.state.clone());
         }
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, layout[3]);
+        if let Some(details_area) = details_area {
+            let fields = self.selected_record().map(|record| {
+                let patient = self
+                    .get_patient(record.patient_id)
+                    .map(|p| format!("{} {}", p.first_name, p.last_name))
+                    .unwrap_or_else(|| t("common.unknown_patient_name").to_string());
+                let mut fields = vec![
+                    (t("retrieve.record_number"), record.id.to_string()),
+                    (
+                        t("kiosk.patient"),
+                        tf(
+                            "common.name_with_id",
+                            &[("name", &patient), ("id", &record.patient_id)],
+                        ),
+                    ),
+                    (t("history.diagnosis"), record.diagnosis.clone()),
+                    (
+                        t("retrieve.procedures"),
+                        record.procedures.clone().unwrap_or_default(),
+                    ),
+                    (
+                        t("history.prescription"),
+                        record.prescription.clone().unwrap_or_default(),
+                    ),
+                    (t("history.doctor_notes"), record.doctor_notes.clone()),
+                    (
+                        t("history.nurse_notes"),
+                        record.nurse_notes.clone().unwrap_or_default(),
+                    ),
+                ];
+                if let Some(isolation) = self.isolations.get(&record.patient_id) {
+                    fields.insert(2, (t("retrieve.isolation"), isolation.clone()));
+                }
+                fields
+            });
+            master_detail::render_details(
+                frame,
+                details_area,
+                t("retrieve.details"),
+                fields.as_deref(),
+                t("retrieve.none_selected"),
+            );
         }
 
         let back_text = if self.focus_index == BACK_BUTTON {
-            "► Back ◄"
+            format!("► {} ◄", t("common.back"))
         } else {
-            "  Back  "
+            format!("  {}  ", t("common.back"))
         };
 
         let back_style = if self.focus_index == BACK_BUTTON {
             Style::default()
-                .fg(Color::Rgb(129, 199, 245))
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let back_button = Paragraph::new(back_text)
             .style(back_style)
             .alignment(Alignment::Center);
-        frame.render_widget(back_button, layout[4]);
+        frame.render_widget(back_button, layout[3]);
 
         let help_text = if self.is_searching {
-            "Type to search | ↓/Enter: To results | Esc: Cancel search"
+            t("common.search_enter_help")
         } else {
-            "/ or s: Search | ↑↓: Navigate | Enter: View Details | R: Refresh | Tab: Focus"
+            t("retrieve.help")
         };
 
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(140, 140, 170)))
+            .style(Style::default().fg(theme().help))
             .alignment(Alignment::Center);
-        frame.render_widget(help_paragraph, layout[6]);
+        frame.render_widget(help_paragraph, layout[5]);
     }
 
     fn render_details_view(&self, frame: &mut Frame) {
@@ -493,16 +949,16 @@ impl RetrieveRecords {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
 
-        let title = Paragraph::new("🏥 RECORD DETAILS")
+        let title = Paragraph::new(t("retrieve.details_title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
@@ -511,122 +967,139 @@ impl RetrieveRecords {
             let patient = self.get_patient(record.patient_id);
             let _patient_name = match patient {
                 Some(p) => format!("{} {}", p.first_name, p.last_name),
-                None => "Unknown Patient".to_string(),
+                None => t("common.unknown_patient_name").to_string(),
             };
 
             let blocks_layout = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
                     Constraint::Length(3),
-                    Constraint::Length(4),
-                    Constraint::Length(4),
-                    Constraint::Length(6),
-                    Constraint::Length(6),
+                    Constraint::Length(5),
+                    Constraint::Length(5),
+                    Constraint::Min(6),
+                    Constraint::Min(6),
                 ])
                 .split(layout[1]);
 
-            let record_info_text = format!("   Record Number: {}", record.id);
-            let record_info_block = Block::default()
-                .title(Span::styled(
-                    " Patient Information ",
+            let isolation = self.isolations.get(&record.patient_id);
+            let mut record_info_text = vec![Span::styled(
+                format!(
+                    "   {}",
+                    tf("retrieve.record_number_line", &[("id", &record.id)])
+                ),
+                Style::default().fg(theme().text),
+            )];
+            if let Some(isolation) = isolation {
+                record_info_text.push(Span::styled(
+                    format!(
+                        "   {}",
+                        tf("retrieve.isolation_line", &[("precautions", &isolation)])
+                    ),
                     Style::default()
-                        .fg(Color::Cyan)
+                        .fg(theme().error)
                         .add_modifier(Modifier::BOLD),
-                ))
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::White))
-                .style(Style::default().bg(Color::Rgb(22, 22, 35)));
-
-            let record_info_widget = Paragraph::new(record_info_text)
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .block(record_info_block);
-
-            frame.render_widget(record_info_widget, blocks_layout[0]);
-
-            let diagnosis_block = Block::default()
+                ));
+            }
+            let record_info_block = Block::default()
                 .title(Span::styled(
-                    " Diagnosis ",
+                    format!(" {} ", t("retrieve.patient_info")),
                     Style::default()
-                        .fg(Color::Cyan)
+                        .fg(theme().accent)
                         .add_modifier(Modifier::BOLD),
                 ))
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::White))
-                .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+                .border_style(Style::default().fg(if isolation.is_some() {
+                    theme().error
+                } else {
+                    theme().border
+                }))
+                .style(Style::default().bg(theme().surface));
 
-            let diagnosis_widget = Paragraph::new(format!("   {}", record.diagnosis))
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .block(diagnosis_block)
-                .wrap(Wrap { trim: true });
+            let record_info_widget =
+                Paragraph::new(Line::from(record_info_text)).block(record_info_block);
 
-            frame.render_widget(diagnosis_widget, blocks_layout[1]);
+            frame.render_widget(record_info_widget, blocks_layout[0]);
 
-            let prescription_text = record
+            let mut prescription_text = record
                 .prescription
-                .clone()
-                .unwrap_or_else(|| "None".to_string());
-            let prescription_block = Block::default()
-                .title(Span::styled(
-                    " Prescription ",
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                ))
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::White))
-                .style(Style::default().bg(Color::Rgb(22, 22, 35)));
-
-            let prescription_widget = Paragraph::new(format!("   {}", prescription_text))
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .block(prescription_block)
-                .wrap(Wrap { trim: true });
-
-            frame.render_widget(prescription_widget, blocks_layout[2]);
-
-            let doctor_notes_block = Block::default()
-                .title(Span::styled(
-                    " Doctor's Notes ",
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                ))
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::White))
-                .style(Style::default().bg(Color::Rgb(22, 22, 35)));
-
-            let doctor_notes_widget = Paragraph::new(format!("   {}", record.doctor_notes))
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .block(doctor_notes_block)
-                .wrap(Wrap { trim: true });
-
-            frame.render_widget(doctor_notes_widget, blocks_layout[3]);
-
-            let nurse_notes_text = record
-                .nurse_notes
-                .clone()
-                .unwrap_or_else(|| "None".to_string());
-            let nurse_notes_block = Block::default()
-                .title(Span::styled(
-                    " Nurse's Notes ",
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                ))
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::White))
-                .style(Style::default().bg(Color::Rgb(22, 22, 35)));
-
-            let nurse_notes_widget = Paragraph::new(format!("   {}", nurse_notes_text))
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .block(nurse_notes_block)
-                .wrap(Wrap { trim: true });
+                .as_deref()
+                .unwrap_or(t("common.none"))
+                .to_string();
+            for entry in &self.overrides {
+                prescription_text.push_str(&tf(
+                    "retrieve.override",
+                    &[
+                        ("severity", &entry.severity),
+                        (
+                            "user",
+                            &entry
+                                .overridden_by
+                                .as_deref()
+                                .unwrap_or(t("common.unknown")),
+                        ),
+                        (
+                            "date",
+                            &(datetime::local_date(&entry.created_at)
+                                .map(datetime::format_date)
+                                .unwrap_or_default()),
+                        ),
+                        ("warning", &entry.warning),
+                        (
+                            "reason",
+                            &entry.reason.as_deref().unwrap_or(t("retrieve.none_given")),
+                        ),
+                    ],
+                ));
+            }
+            let nurse_notes_text = record.nurse_notes.as_deref().unwrap_or(t("common.none"));
+            let diagnosis_text = match &record.procedures {
+                Some(procedures) => tf(
+                    "retrieve.procedures_line",
+                    &[
+                        ("diagnosis", &record.diagnosis),
+                        ("procedures", &procedures),
+                    ],
+                ),
+                None => record.diagnosis.clone(),
+            };
+            let sections = [
+                ("history.diagnosis", diagnosis_text.as_str()),
+                ("history.prescription", prescription_text.as_str()),
+                ("history.doctor_notes", record.doctor_notes.as_str()),
+                ("history.nurse_notes", nurse_notes_text),
+            ];
 
-            frame.render_widget(nurse_notes_widget, blocks_layout[4]);
+            let mut max_scroll = [0; DETAIL_SECTIONS];
+            for (index, (title, text)) in sections.iter().enumerate() {
+                let border_color = if index == self.detail_focus {
+                    theme().focus
+                } else {
+                    theme().border
+                };
+                let block = Block::default()
+                    .title(Span::styled(
+                        format!(" {} ", t(title)),
+                        Style::default()
+                            .fg(theme().accent)
+                            .add_modifier(Modifier::BOLD),
+                    ))
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(border_color))
+                    .style(Style::default().bg(theme().surface))
+                    .padding(Padding::horizontal(2));
+
+                max_scroll[index] = render_scrollable_text(
+                    frame,
+                    blocks_layout[index + 1],
+                    text,
+                    Style::default().fg(theme().text),
+                    block,
+                    self.detail_scroll[index],
+                );
+            }
+            self.detail_max_scroll.set(max_scroll);
         }
 
         let footer_layout = Layout::default()
@@ -634,25 +1107,18 @@ impl RetrieveRecords {
             .constraints([Constraint::Length(1), Constraint::Length(1)])
             .split(layout[2]);
 
-        let back_button = Paragraph::new("► Back ◄")
+        let back_button = Paragraph::new(format!("► {} ◄", t("common.back")))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(129, 199, 245))
+                    .fg(theme().accent)
                     .add_modifier(Modifier::BOLD),
             )
             .alignment(Alignment::Center);
         frame.render_widget(back_button, footer_layout[0]);
 
-        let help_text = "Enter/Esc/Backspace: Return to list";
-        let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(140, 140, 170)))
+        let help_paragraph = Paragraph::new(t("retrieve.detail_help"))
+            .style(Style::default().fg(theme().help))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, footer_layout[1]);
     }
 }
-
-impl Default for RetrieveRecords {
-    fn default() -> Self {
-        Self::new()
-    }
-}
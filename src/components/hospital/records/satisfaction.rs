@@ -0,0 +1,651 @@
+//! Patient satisfaction over a month: ratings per doctor and per service,
+//! and the low ratings still waiting for someone to get back to the
+//! patient.
+//!
+//! Rustoria has no departments, so feedback is grouped by the service the
+//! visit came through: remote consultations or the outpatient queue.
+//! Ratings at or below `follow_up_rating` are flagged for follow-up, from
+//! any month, until a note is saved on them.
+
+use crate::app::SelectedApp;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::feedback::{needs_follow_up, stars};
+use crate::components::widgets::text_input::TextInput;
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{self, t, tf};
+use crate::models::{PatientFeedback, StaffMember};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{config, csv_line, datetime, truncate, write_export};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::{BTreeMap, HashMap};
+use time::{Date, Duration};
+
+const EXPORT_HEADERS: [&str; 9] = [
+    "Date",
+    "Patient",
+    "Doctor",
+    "Service",
+    "Rating",
+    "Comment",
+    "Follow-up Needed",
+    "Followed Up",
+    "Follow-up Note",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Doctors,
+    Services,
+    FollowUps,
+}
+
+impl View {
+    fn next(self) -> Self {
+        match self {
+            View::Doctors => View::Services,
+            View::Services => View::FollowUps,
+            View::FollowUps => View::Doctors,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            View::Doctors => "satisfaction.by_doctor",
+            View::Services => "satisfaction.by_service",
+            View::FollowUps => "satisfaction.follow_ups",
+        }
+    }
+}
+
+/// The catalog key of the service a visit came through.
+fn service(feedback: &PatientFeedback) -> &'static str {
+    if feedback.teleconsultation_id.is_some() {
+        "satisfaction.remote"
+    } else if feedback.queue_token_id.is_some() {
+        "satisfaction.queue"
+    } else {
+        "satisfaction.removed"
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct RatingGroup {
+    name: String,
+    ratings: Vec<i64>,
+    low: usize,
+    followed_up: usize,
+}
+
+impl RatingGroup {
+    fn average(&self) -> Option<f64> {
+        (!self.ratings.is_empty())
+            .then(|| self.ratings.iter().sum::<i64>() as f64 / self.ratings.len() as f64)
+    }
+}
+
+fn group_by(
+    feedback: &[PatientFeedback],
+    key: impl Fn(&PatientFeedback) -> String,
+) -> Vec<RatingGroup> {
+    let mut groups: BTreeMap<String, RatingGroup> = BTreeMap::new();
+    for item in feedback {
+        let name = key(item);
+        let group = groups.entry(name.clone()).or_insert_with(|| RatingGroup {
+            name,
+            ..RatingGroup::default()
+        });
+        group.ratings.push(item.rating);
+        if needs_follow_up(item.rating) {
+            group.low += 1;
+            if item.followed_up_at.is_some() {
+                group.followed_up += 1;
+            }
+        }
+    }
+    groups.into_values().collect()
+}
+
+pub struct SatisfactionReport {
+    db: Db,
+    /// First day of the month shown.
+    month: Date,
+    view: View,
+    feedback: Vec<PatientFeedback>,
+    follow_ups: Vec<PatientFeedback>,
+    doctors: HashMap<i64, StaffMember>,
+    patients: HashMap<i64, String>,
+    state: TableState,
+    /// The follow-up being closed, with the note typed so far.
+    note: Option<(PatientFeedback, TextInput)>,
+}
+
+impl SatisfactionReport {
+    pub fn new(db: &Db) -> Self {
+        let today = datetime::today();
+        Self {
+            db: db.clone(),
+            month: today.replace_day(1).unwrap_or(today),
+            view: View::Doctors,
+            feedback: Vec::new(),
+            follow_ups: Vec::new(),
+            doctors: HashMap::new(),
+            patients: HashMap::new(),
+            state: TableState::default(),
+            note: None,
+        }
+    }
+
+    fn month_end(&self) -> Date {
+        let next = (self.month + Duration::days(31))
+            .replace_day(1)
+            .unwrap_or(self.month);
+        next - Duration::days(1)
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.feedback = db::get_feedback_between(
+            &self.db,
+            &datetime::format_date(self.month),
+            &datetime::format_date(self.month_end()),
+        )?;
+        self.follow_ups =
+            db::get_feedback_to_follow_up(&self.db, config::config().follow_up_rating as i64)?;
+        self.doctors = db::get_all_staff(&self.db)?
+            .into_iter()
+            .map(|s| (s.id, s))
+            .collect();
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, format!("{} {}", p.first_name, p.last_name)))
+            .collect();
+        let len = self.row_count();
+        match self.state.selected() {
+            _ if len == 0 => self.state.select(None),
+            Some(i) if i < len => {}
+            _ => self.state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) {
+        if let Err(e) = self.load_data() {
+            toast::push(Toast::error(tf(
+                "satisfaction.load_failed",
+                &[("error", &e)],
+            )));
+        }
+    }
+
+    fn doctor_name(&self, doctor_id: i64) -> String {
+        self.doctors.get(&doctor_id).map_or_else(
+            || tf("common.unknown_number", &[("id", &doctor_id)]),
+            |d| d.name.clone(),
+        )
+    }
+
+    fn patient_name(&self, patient_id: i64) -> String {
+        self.patients
+            .get(&patient_id)
+            .cloned()
+            .unwrap_or_else(|| tf("common.unknown_number", &[("id", &patient_id)]))
+    }
+
+    fn groups(&self) -> Vec<RatingGroup> {
+        match self.view {
+            View::Doctors => group_by(&self.feedback, |f| self.doctor_name(f.doctor_id)),
+            View::Services => group_by(&self.feedback, |f| t(service(f)).to_string()),
+            View::FollowUps => Vec::new(),
+        }
+    }
+
+    fn row_count(&self) -> usize {
+        match self.view {
+            View::FollowUps => self.follow_ups.len(),
+            _ => self.groups().len(),
+        }
+    }
+
+    fn change_month(&mut self, forward: bool) {
+        let moved = if forward {
+            self.month + Duration::days(31)
+        } else {
+            self.month - Duration::days(1)
+        };
+        self.month = moved.replace_day(1).unwrap_or(moved);
+        self.reload();
+    }
+
+    fn switch_view(&mut self) {
+        self.view = self.view.next();
+        let selected = (self.row_count() > 0).then_some(0);
+        self.state.select(selected);
+    }
+
+    fn export(&self) {
+        if self.feedback.is_empty() {
+            toast::push(Toast::error(t("satisfaction.no_feedback")));
+            return;
+        }
+        let mut contents = csv_line(&EXPORT_HEADERS);
+        contents.push('\n');
+        for item in &self.feedback {
+            let values = [
+                datetime::local_date(&item.created_at)
+                    .map_or_else(|| item.created_at.clone(), datetime::format_date),
+                self.patient_name(item.patient_id),
+                self.doctor_name(item.doctor_id),
+                i18n::english(service(item)).to_string(),
+                item.rating.to_string(),
+                item.comment.clone().unwrap_or_default(),
+                if needs_follow_up(item.rating) {
+                    "Yes"
+                } else {
+                    "No"
+                }
+                .to_string(),
+                item.followed_up_at
+                    .as_deref()
+                    .map_or(String::new(), datetime::facility_stamp),
+                item.follow_up_note.clone().unwrap_or_default(),
+            ];
+            let fields: Vec<&str> = values.iter().map(String::as_str).collect();
+            contents.push_str(&csv_line(&fields));
+            contents.push('\n');
+        }
+        let file_name = format!(
+            "patient_feedback_{}.csv",
+            datetime::format_month(self.month)
+        );
+        match write_export(&file_name, &contents) {
+            Ok(path) => toast::push(Toast::success(tf(
+                "satisfaction.exported",
+                &[("count", &self.feedback.len()), ("path", &path.display())],
+            ))),
+            Err(e) => toast::push(Toast::error(format!("{}", e))),
+        }
+    }
+
+    fn open_note(&mut self) {
+        if self.view != View::FollowUps {
+            return;
+        }
+        if let Some(item) = self.state.selected().and_then(|i| self.follow_ups.get(i)) {
+            self.note = Some((item.clone(), TextInput::new()));
+        }
+    }
+
+    fn handle_note(&mut self, key: KeyEvent) {
+        let Some((item, input)) = self.note.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.note = None,
+            KeyCode::Enter => {
+                let note = input.value().trim().to_string();
+                if note.is_empty() {
+                    toast::push(Toast::error(t("satisfaction.note_required")));
+                    return;
+                }
+                let (id, patient_id) = (item.id, item.patient_id);
+                match db::follow_up_feedback(&self.db, id, &note, &datetime::now_text()) {
+                    Ok(()) => {
+                        toast::push(Toast::success(tf(
+                            "satisfaction.saved",
+                            &[("name", &self.patient_name(patient_id))],
+                        )));
+                        self.note = None;
+                        self.reload();
+                    }
+                    Err(e) => toast::push(Toast::error(format!("{}", e))),
+                }
+            }
+            _ => {
+                input.handle_key(key);
+            }
+        }
+    }
+
+    fn summary(&self) -> Line<'static> {
+        let count = self.feedback.len();
+        let average = (count > 0)
+            .then(|| self.feedback.iter().map(|f| f.rating).sum::<i64>() as f64 / count as f64);
+        let low = self
+            .feedback
+            .iter()
+            .filter(|f| needs_follow_up(f.rating))
+            .count();
+        let separator = || Span::styled("  |  ", Style::default().fg(theme().inactive));
+        Line::from(vec![
+            Span::styled(
+                tf("satisfaction.ratings_count", &[("count", &count)]),
+                Style::default()
+                    .fg(theme().text)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            separator(),
+            Span::styled(
+                average.map_or(t("satisfaction.no_average").to_string(), |a| {
+                    tf(
+                        "satisfaction.average_value",
+                        &[("average", &format!("{:.1}", a))],
+                    )
+                }),
+                Style::default().fg(theme().warning),
+            ),
+            separator(),
+            Span::styled(
+                tf("satisfaction.low", &[("count", &low)]),
+                Style::default().fg(theme().error),
+            ),
+            separator(),
+            Span::styled(
+                tf("satisfaction.waiting", &[("count", &self.follow_ups.len())]),
+                Style::default().fg(if self.follow_ups.is_empty() {
+                    theme().success
+                } else {
+                    theme().error
+                }),
+            ),
+        ])
+    }
+
+    fn render_groups(&self, frame: &mut Frame, area: Rect, block: Block) {
+        let groups = self.groups();
+        if groups.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("satisfaction.no_feedback"))
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        }
+        let widths = [
+            Constraint::Min(20),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(7),
+            Constraint::Length(12),
+            Constraint::Length(12),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+        let rows: Vec<Row> = groups
+            .iter()
+            .map(|group| {
+                let average = group.average().unwrap_or_default();
+                let style = if needs_follow_up(average.round() as i64) {
+                    Style::default().fg(theme().error)
+                } else {
+                    Style::default().fg(theme().text)
+                };
+                Row::new(vec![
+                    cell(group.name.clone(), &columns, 0),
+                    cell(group.ratings.len().to_string(), &columns, 1),
+                    cell(format!("{:.1}", average), &columns, 2),
+                    cell(stars(average.round() as i64), &columns, 3),
+                    cell(group.low.to_string(), &columns, 4),
+                    cell(group.followed_up.to_string(), &columns, 5),
+                ])
+                .style(style)
+            })
+            .collect();
+        let name = if self.view == View::Doctors {
+            t("satisfaction.doctor")
+        } else {
+            t("satisfaction.service")
+        };
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec![
+                    name,
+                    t("satisfaction.ratings"),
+                    t("satisfaction.average"),
+                    "",
+                    t("satisfaction.low_ratings"),
+                    t("satisfaction.followed_up"),
+                ])
+                .style(Style::default().bg(theme().header).fg(theme().title)),
+            )
+            .block(block)
+            .row_highlight_style(Style::default().bg(theme().highlight))
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.state.clone());
+    }
+
+    fn render_follow_ups(&self, frame: &mut Frame, area: Rect, block: Block) {
+        if self.follow_ups.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("satisfaction.all_followed"))
+                    .style(Style::default().fg(theme().success))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        }
+        let widths = [
+            Constraint::Length(12),
+            Constraint::Length(22),
+            Constraint::Length(18),
+            Constraint::Length(7),
+            Constraint::Min(20),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+        let rows: Vec<Row> = self
+            .follow_ups
+            .iter()
+            .map(|item| {
+                Row::new(vec![
+                    cell(datetime::display_stamp(&item.created_at), &columns, 0),
+                    cell(self.patient_name(item.patient_id), &columns, 1),
+                    cell(self.doctor_name(item.doctor_id), &columns, 2),
+                    cell(stars(item.rating), &columns, 3),
+                    cell(item.comment.clone().unwrap_or_default(), &columns, 4),
+                ])
+                .style(Style::default().fg(theme().text))
+            })
+            .collect();
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec![
+                    t("satisfaction.given"),
+                    t("kiosk.patient"),
+                    t("satisfaction.doctor"),
+                    t("satisfaction.rating"),
+                    t("satisfaction.comment"),
+                ])
+                .style(Style::default().bg(theme().header).fg(theme().title)),
+            )
+            .block(block)
+            .row_highlight_style(Style::default().bg(theme().highlight))
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.state.clone());
+    }
+
+    fn render_note(&self, frame: &mut Frame, item: &PatientFeedback, input: &TextInput) {
+        let area = frame.area();
+        let width = 70.min(area.width);
+        let height = 9.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(
+                " {} ",
+                tf(
+                    "satisfaction.follow_up_title",
+                    &[("name", &(truncate(&self.patient_name(item.patient_id), 40)))]
+                )
+            ))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(3),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+        frame.render_widget(
+            Paragraph::new(format!(
+                "{}  {}",
+                stars(item.rating),
+                item.comment
+                    .as_deref()
+                    .unwrap_or(t("satisfaction.no_comment"))
+            ))
+            .style(Style::default().fg(theme().text)),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(input.line(true))
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("satisfaction.what_done")))
+                        .border_style(Style::default().fg(theme().focus))
+                        .style(Style::default().bg(theme().input)),
+                ),
+            layout[1],
+        );
+        frame.render_widget(
+            Paragraph::new(t("satisfaction.save_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[2],
+        );
+    }
+}
+
+impl Component for SatisfactionReport {
+    fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if self.note.is_some() {
+            self.handle_note(key);
+            return Ok(None);
+        }
+        let len = self.row_count();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Left => self.change_month(false),
+            KeyCode::Right => self.change_month(true),
+            KeyCode::Tab => self.switch_view(),
+            KeyCode::Enter => self.open_note(),
+            KeyCode::Char('e') | KeyCode::Char('E') => self.export(),
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                self.reload();
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("satisfaction.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(format!("◄ {} ►", datetime::format_month(self.month)))
+                .style(Style::default().fg(theme().accent))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("common.month")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[1],
+        );
+        frame.render_widget(
+            Paragraph::new(self.summary()).alignment(Alignment::Center),
+            layout[2],
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t(self.view.title())))
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        match self.view {
+            View::FollowUps => self.render_follow_ups(frame, layout[3], block),
+            _ => self.render_groups(frame, layout[3], block),
+        }
+
+        let help = if self.view == View::FollowUps {
+            t("satisfaction.help_follow_ups")
+        } else {
+            t("satisfaction.help")
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+
+        if let Some((item, input)) = &self.note {
+            self.render_note(frame, item, input);
+        }
+    }
+}
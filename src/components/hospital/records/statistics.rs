@@ -0,0 +1,827 @@
+use crate::app::SelectedApp;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::date_picker::DatePicker;
+use crate::components::widgets::progress::render_progress;
+use crate::components::Component;
+use crate::db::worker::{self, Task};
+use crate::db::{self, Db};
+use crate::i18n::{self, t, tf};
+use crate::models::{DiagnosisCase, ResearchEncounter};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{csv_line, datetime, write_export};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use time::Date;
+
+const GROUPINGS: [&str; 4] = [
+    "statistics.diagnosis_code",
+    "statistics.age_band",
+    "statistics.month",
+    "statistics.sex",
+];
+const AGE_BANDS: [(i32, &str); 6] = [
+    (5, "0-4"),
+    (15, "5-14"),
+    (25, "15-24"),
+    (45, "25-44"),
+    (65, "45-64"),
+    (i32::MAX, "65+"),
+];
+const MIN_CELL_COUNT: usize = 5;
+/// Ages from here up are reported together, as "90+".
+const OLDEST_AGE: i32 = 90;
+
+const EXPORT_MODES: [&str; 3] = [
+    "statistics.aggregate_csv",
+    "statistics.research_csv",
+    "statistics.research_json",
+];
+const RESEARCH_HEADERS: [&str; 8] = [
+    "statistics.patient",
+    "statistics.encounter",
+    "statistics.month",
+    "statistics.age",
+    "statistics.sex",
+    "statistics.diagnosis",
+    "statistics.ward",
+    "statistics.stay_days",
+];
+
+const PERIOD_INPUT: usize = 0;
+const SUPPRESS_TOGGLE: usize = GROUPINGS.len() + 1;
+const EXPORT_MODE: usize = GROUPINGS.len() + 2;
+const EXPORT_BUTTON: usize = GROUPINGS.len() + 3;
+const BACK_BUTTON: usize = GROUPINGS.len() + 4;
+
+/// One encounter with the direct identifiers stripped: the patient is only
+/// a pseudonym, dates are cut to the month, ages from 90 are pooled and no
+/// free-text notes are included.
+#[derive(Debug, Clone, Serialize)]
+struct ResearchRow {
+    patient: String,
+    encounter: String,
+    month: String,
+    age: String,
+    sex: String,
+    diagnosis: Option<String>,
+    ward: Option<String>,
+    stay_days: Option<i64>,
+}
+
+impl ResearchRow {
+    fn from_encounter(encounter: &ResearchEncounter) -> Self {
+        let started = encounter.started_at.as_deref().unwrap_or_default();
+        let age = parse_date(started).and_then(|on| age_years(&encounter.date_of_birth, on));
+        let stay_days = parse_date(started)
+            .zip(encounter.ended_at.as_deref().and_then(parse_date))
+            .map(|(from, to)| (to - from).whole_days());
+        Self {
+            patient: encounter.pseudonym.clone(),
+            encounter: encounter.kind.clone(),
+            month: datetime::month_key(started).unwrap_or_else(|| "Unknown".to_string()),
+            age: match age {
+                Some(age) if age >= OLDEST_AGE => format!("{}+", OLDEST_AGE),
+                Some(age) => age.to_string(),
+                None => "Unknown".to_string(),
+            },
+            sex: encounter.gender.clone(),
+            diagnosis: encounter.diagnosis.as_deref().map(diagnosis_code),
+            ward: encounter.ward.clone(),
+            stay_days,
+        }
+    }
+
+    fn values(&self) -> Vec<String> {
+        vec![
+            self.patient.clone(),
+            self.encounter.clone(),
+            self.month.clone(),
+            self.age.clone(),
+            self.sex.clone(),
+            self.diagnosis.clone().unwrap_or_default(),
+            self.ward.clone().unwrap_or_default(),
+            self.stay_days.map(|d| d.to_string()).unwrap_or_default(),
+        ]
+    }
+}
+
+pub struct PublicHealthStatistics {
+    db: Db,
+    cases: Vec<DiagnosisCase>,
+    encounters: Vec<ResearchEncounter>,
+    rows: Vec<(Vec<String>, usize)>,
+    research_rows: Vec<ResearchRow>,
+    period_input: String,
+    range_picker: Option<(DatePicker, Option<Date>)>,
+    group_by: [bool; GROUPINGS.len()],
+    suppress_small: bool,
+    export_mode: usize,
+    focus_index: usize,
+    loading: Option<Task<(Vec<DiagnosisCase>, Vec<ResearchEncounter>)>>,
+    exporting: Option<(Task<PathBuf>, usize)>,
+}
+
+/// The ICD-style code a diagnosis starts with, like "J18.9", or the whole
+/// diagnosis in capitals when it does not start with one.
+pub fn diagnosis_code(diagnosis: &str) -> String {
+    let token = diagnosis
+        .split(|c: char| c.is_whitespace() || c == ':' || c == '-')
+        .next()
+        .unwrap_or_default()
+        .to_uppercase();
+    let looks_like_code = token
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic())
+        && token.len() >= 3
+        && token
+            .chars()
+            .skip(1)
+            .all(|c| c.is_ascii_alphanumeric() || c == '.')
+        && token.chars().skip(1).take(2).all(|c| c.is_ascii_digit());
+    if looks_like_code {
+        token
+    } else {
+        diagnosis.trim().to_uppercase()
+    }
+}
+
+fn parse_date(value: &str) -> Option<Date> {
+    datetime::parse_date(datetime::date_part(value))
+}
+
+fn in_period(period: &str, recorded: &str) -> bool {
+    match period.split_once("..") {
+        Some((from, to)) => parse_date(recorded).is_some_and(|on| {
+            parse_date(from).is_none_or(|from| on >= from)
+                && parse_date(to).is_none_or(|to| on <= to)
+        }),
+        None => recorded.starts_with(period),
+    }
+}
+
+fn age_years(date_of_birth: &str, on: Date) -> Option<i32> {
+    let dob = parse_date(date_of_birth)?;
+    let mut age = on.year() - dob.year();
+    if (on.month() as u8, on.day()) < (dob.month() as u8, dob.day()) {
+        age -= 1;
+    }
+    (age >= 0).then_some(age)
+}
+
+fn age_band(date_of_birth: &str, on: Date) -> &'static str {
+    let Some(age) = age_years(date_of_birth, on) else {
+        return "Unknown";
+    };
+    AGE_BANDS
+        .iter()
+        .find(|(upper, _)| age < *upper)
+        .map(|(_, label)| *label)
+        .unwrap_or("Unknown")
+}
+
+impl PublicHealthStatistics {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            cases: Vec::new(),
+            encounters: Vec::new(),
+            rows: Vec::new(),
+            research_rows: Vec::new(),
+            period_input: String::new(),
+            range_picker: None,
+            group_by: [true, true, true, false],
+            suppress_small: true,
+            export_mode: 0,
+            focus_index: PERIOD_INPUT,
+            loading: None,
+            exporting: None,
+        }
+    }
+
+    pub fn fetch_cases(&mut self) -> Result<()> {
+        let db = self.db.clone();
+        self.loading = Some(worker::spawn(move || {
+            Ok((
+                db::get_diagnosis_cases(&db)?,
+                db::get_research_encounters(&db)?,
+            ))
+        }));
+        Ok(())
+    }
+
+    pub fn tick(&mut self) {
+        if let Some(result) = self.loading.as_ref().and_then(Task::poll) {
+            self.loading = None;
+            match result {
+                Ok((cases, encounters)) => {
+                    self.cases = cases;
+                    self.encounters = encounters;
+                    self.aggregate();
+                }
+                Err(e) => toast::push(Toast::error(tf("statistics.load_failed", &[("error", &e)]))),
+            }
+        }
+        if let Some((exporting, count)) = self.exporting.take() {
+            match exporting.poll() {
+                None => self.exporting = Some((exporting, count)),
+                Some(Ok(path)) => toast::push(Toast::success(tf(
+                    "statistics.exported",
+                    &[("count", &count), ("path", &path.display())],
+                ))),
+                Some(Err(_)) if exporting.is_cancelled() => {
+                    toast::push(Toast::error(t("statistics.export_cancelled")));
+                }
+                Some(Err(e)) => toast::push(Toast::error(format!("{}", e))),
+            }
+        }
+    }
+
+    fn aggregate(&mut self) {
+        let period = self.period_input.trim();
+        let today = datetime::today();
+        let mut counts: BTreeMap<Vec<String>, usize> = BTreeMap::new();
+        for case in &self.cases {
+            let recorded = case.recorded_at.as_deref().unwrap_or_default();
+            if !in_period(period, recorded) {
+                continue;
+            }
+            let on = parse_date(recorded).unwrap_or(today);
+            let values = [
+                diagnosis_code(&case.diagnosis),
+                age_band(&case.date_of_birth, on).to_string(),
+                datetime::month_key(recorded).unwrap_or_else(|| "Unknown".to_string()),
+                case.gender.label().to_string(),
+            ];
+            let key = values
+                .into_iter()
+                .zip(self.group_by)
+                .filter(|(_, enabled)| *enabled)
+                .map(|(value, _)| value)
+                .collect();
+            *counts.entry(key).or_insert(0) += 1;
+        }
+        self.rows = counts.into_iter().collect();
+        self.research_rows = self
+            .encounters
+            .iter()
+            .filter(|e| in_period(period, e.started_at.as_deref().unwrap_or_default()))
+            .map(ResearchRow::from_encounter)
+            .collect();
+    }
+
+    fn research_mode(&self) -> bool {
+        self.export_mode > 0
+    }
+
+    fn count_text(&self, count: usize) -> String {
+        if self.suppress_small && count < MIN_CELL_COUNT {
+            format!("<{}", MIN_CELL_COUNT)
+        } else {
+            count.to_string()
+        }
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        GROUPINGS
+            .iter()
+            .zip(self.group_by)
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .chain(["statistics.cases"])
+            .collect()
+    }
+
+    fn export(&mut self) {
+        if self.loading.is_some() || self.exporting.is_some() {
+            return;
+        }
+        if self.research_mode() {
+            self.export_research();
+            return;
+        }
+        if self.rows.is_empty() {
+            toast::push(Toast::error(t("statistics.no_cases")));
+            return;
+        }
+        let headers = csv_line(
+            &self
+                .headers()
+                .into_iter()
+                .map(i18n::english)
+                .collect::<Vec<_>>(),
+        );
+        let rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|(key, count)| {
+                key.iter()
+                    .cloned()
+                    .chain([self.count_text(*count)])
+                    .collect()
+            })
+            .collect();
+        let period = self.period_input.trim();
+        let suffix = if period.is_empty() { "all" } else { period };
+        let file_name = format!("public_health_stats_{}.csv", suffix);
+
+        let count = rows.len();
+        self.exporting = Some((
+            worker::spawn_with_progress(move |progress| {
+                let total = rows.len() as u64;
+                let mut contents = headers;
+                contents.push('\n');
+                for (index, row) in rows.iter().enumerate() {
+                    if progress.is_cancelled() {
+                        anyhow::bail!(t("statistics.export_cancelled"));
+                    }
+                    let fields: Vec<&str> = row.iter().map(String::as_str).collect();
+                    contents.push_str(&csv_line(&fields));
+                    contents.push('\n');
+                    progress.set(index as u64 + 1, total);
+                }
+                write_export(&file_name, &contents)
+            }),
+            count,
+        ));
+    }
+
+    /// Writes one row per encounter, without names, contact details or
+    /// notes, as CSV or JSON depending on the export mode.
+    fn export_research(&mut self) {
+        if self.research_rows.is_empty() {
+            toast::push(Toast::error(t("statistics.no_encounters")));
+            return;
+        }
+        let rows = self.research_rows.clone();
+        let json = self.export_mode == 2;
+        let period = self.period_input.trim();
+        let suffix = if period.is_empty() { "all" } else { period };
+        let file_name = format!(
+            "research_dataset_{}.{}",
+            suffix,
+            if json { "json" } else { "csv" }
+        );
+
+        let count = rows.len();
+        self.exporting = Some((
+            worker::spawn_with_progress(move |progress| {
+                if json {
+                    return write_export(&file_name, &serde_json::to_string_pretty(&rows)?);
+                }
+                let total = rows.len() as u64;
+                let mut contents = csv_line(&RESEARCH_HEADERS.map(i18n::english));
+                contents.push('\n');
+                for (index, row) in rows.iter().enumerate() {
+                    if progress.is_cancelled() {
+                        anyhow::bail!(t("statistics.export_cancelled"));
+                    }
+                    let values = row.values();
+                    let fields: Vec<&str> = values.iter().map(String::as_str).collect();
+                    contents.push_str(&csv_line(&fields));
+                    contents.push('\n');
+                    progress.set(index as u64 + 1, total);
+                }
+                write_export(&file_name, &contents)
+            }),
+            count,
+        ));
+    }
+
+    fn toggle_focused(&mut self) {
+        match self.focus_index {
+            i if (1..=GROUPINGS.len()).contains(&i) => {
+                self.group_by[i - 1] = !self.group_by[i - 1];
+                self.aggregate();
+            }
+            SUPPRESS_TOGGLE => self.suppress_small = !self.suppress_small,
+            EXPORT_MODE => self.export_mode = (self.export_mode + 1) % EXPORT_MODES.len(),
+            _ => {}
+        }
+    }
+
+    fn open_range_picker(&mut self) {
+        let today = datetime::today();
+        let from = self
+            .period_input
+            .split_once("..")
+            .and_then(|(from, _)| parse_date(from))
+            .unwrap_or(today);
+        self.range_picker = Some((DatePicker::new(from).with_max(today), None));
+    }
+
+    fn handle_range_picker(&mut self, key: KeyEvent) {
+        let Some((picker, start)) = self.range_picker.as_mut() else {
+            return;
+        };
+        match (key.code, *start) {
+            (KeyCode::Enter, None) => {
+                let from = picker.selected();
+                *start = Some(from);
+                *picker = picker.clone().with_min(from);
+            }
+            (KeyCode::Enter, Some(from)) => {
+                self.period_input = format!(
+                    "{}..{}",
+                    datetime::format_date(from),
+                    datetime::format_date(picker.selected())
+                );
+                self.range_picker = None;
+                self.aggregate();
+            }
+            (KeyCode::Esc, _) => self.range_picker = None,
+            _ => {
+                picker.handle_key(key);
+            }
+        }
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some((exporting, _)) = &self.exporting {
+            if key.code == KeyCode::Esc {
+                exporting.cancel();
+            }
+            return Ok(None);
+        }
+        if let Some(loading) = &self.loading {
+            if key.code == KeyCode::Esc {
+                loading.cancel();
+                self.loading = None;
+                toast::push(Toast::error(t("statistics.loading_cancelled")));
+            }
+            return Ok(None);
+        }
+        if self.range_picker.is_some() {
+            self.handle_range_picker(key);
+            return Ok(None);
+        }
+
+        match key.code {
+            KeyCode::Char('d')
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && self.focus_index == PERIOD_INPUT =>
+            {
+                self.open_range_picker();
+            }
+            KeyCode::Char(c)
+                if self.focus_index == PERIOD_INPUT
+                    && (c.is_ascii_digit() || c == '-' || c == '.') =>
+            {
+                self.period_input.push(c);
+                self.aggregate();
+            }
+            KeyCode::Backspace if self.focus_index == PERIOD_INPUT => {
+                self.period_input.pop();
+                self.aggregate();
+            }
+            KeyCode::Char(' ') => self.toggle_focused(),
+            KeyCode::Tab | KeyCode::Down => {
+                self.focus_index = (self.focus_index + 1) % (BACK_BUTTON + 1);
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                self.focus_index = (self.focus_index + BACK_BUTTON) % (BACK_BUTTON + 1);
+            }
+            KeyCode::Enter => match self.focus_index {
+                PERIOD_INPUT => self.focus_index += 1,
+                EXPORT_BUTTON => self.export(),
+                BACK_BUTTON => return Ok(Some(SelectedApp::None)),
+                _ => self.toggle_focused(),
+            },
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+impl Component for PublicHealthStatistics {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn tick(&mut self) {
+        self.tick();
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(12),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(t("statistics.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(36), Constraint::Min(30)])
+            .split(layout[1]);
+        let options = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(GROUPINGS.len() as u16 + 2),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(body[0]);
+
+        let focus_style = |focused: bool| {
+            if focused {
+                Style::default().fg(theme().focus)
+            } else {
+                Style::default().fg(theme().unfocused)
+            }
+        };
+
+        frame.render_widget(
+            Paragraph::new(self.period_input.as_str())
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("statistics.period")))
+                        .border_style(focus_style(self.focus_index == PERIOD_INPUT))
+                        .style(Style::default().bg(theme().input)),
+                ),
+            options[0],
+        );
+
+        let grouping_lines: Vec<Line> = GROUPINGS
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let mark = if self.group_by[i] { "[x]" } else { "[ ]" };
+                Line::from(Span::styled(
+                    format!(" {} {}", mark, t(name)),
+                    if self.focus_index == i + 1 {
+                        Style::default()
+                            .fg(theme().focus)
+                            .add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(theme().text)
+                    },
+                ))
+            })
+            .collect();
+        let grouping_focused = (1..=GROUPINGS.len()).contains(&self.focus_index);
+        frame.render_widget(
+            Paragraph::new(grouping_lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("statistics.group_by")))
+                    .border_style(focus_style(grouping_focused))
+                    .style(Style::default().bg(theme().input)),
+            ),
+            options[1],
+        );
+
+        frame.render_widget(
+            Paragraph::new(format!(
+                " {}",
+                tf(
+                    "statistics.suppress",
+                    &[
+                        ("mark", &(if self.suppress_small { "[x]" } else { "[ ]" })),
+                        ("min", &MIN_CELL_COUNT)
+                    ]
+                )
+            ))
+            .style(Style::default().fg(theme().text))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("statistics.privacy")))
+                    .border_style(focus_style(self.focus_index == SUPPRESS_TOGGLE))
+                    .style(Style::default().bg(theme().input)),
+            ),
+            options[2],
+        );
+
+        frame.render_widget(
+            Paragraph::new(format!(" ◄ {} ►", t(EXPORT_MODES[self.export_mode])))
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("statistics.export")))
+                        .border_style(focus_style(self.focus_index == EXPORT_MODE))
+                        .style(Style::default().bg(theme().input)),
+                ),
+            options[3],
+        );
+
+        let export_style = if self.focus_index == EXPORT_BUTTON {
+            Style::default()
+                .fg(theme().success)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        let export_label = if self.export_mode == 2 {
+            t("statistics.export_json")
+        } else {
+            t("statistics.export_csv")
+        };
+        frame.render_widget(
+            Paragraph::new(if self.focus_index == EXPORT_BUTTON {
+                format!("► {} ◄", export_label)
+            } else {
+                format!("  {}  ", export_label)
+            })
+            .style(export_style)
+            .alignment(Alignment::Center),
+            options[5],
+        );
+
+        let back_style = if self.focus_index == BACK_BUTTON {
+            Style::default()
+                .fg(theme().accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        frame.render_widget(
+            Paragraph::new(if self.focus_index == BACK_BUTTON {
+                format!("► {} ◄", t("common.back"))
+            } else {
+                format!("  {}  ", t("common.back"))
+            })
+            .style(back_style)
+            .alignment(Alignment::Center),
+            options[6],
+        );
+
+        if self.research_mode() {
+            self.render_research_preview(frame, body[1]);
+        } else {
+            self.render_preview(frame, body[1]);
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("statistics.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+
+        if let Some((picker, start)) = &self.range_picker {
+            let title = if start.is_some() {
+                t("statistics.period_end")
+            } else {
+                t("statistics.period_start")
+            };
+            picker.render_popup(frame, title);
+        }
+
+        if let Some(loading) = &self.loading {
+            render_progress(frame, loading, t("statistics.loading"), true);
+        }
+        if let Some((exporting, _)) = &self.exporting {
+            render_progress(
+                frame,
+                exporting,
+                if self.export_mode == 2 {
+                    t("statistics.exporting_json")
+                } else {
+                    t("statistics.exporting_csv")
+                },
+                !exporting.is_cancelled(),
+            );
+        }
+    }
+}
+
+impl PublicHealthStatistics {
+    fn render_preview(&self, frame: &mut Frame, area: Rect) {
+        let headers = self.headers();
+        let widths = vec![Constraint::Ratio(1, headers.len() as u32); headers.len()];
+        let columns = column_widths(area.inner(Margin::new(1, 1)), &widths, 1, "");
+        let total: usize = self.rows.iter().map(|(_, count)| count).sum();
+        let rows = self.rows.iter().map(|(key, count)| {
+            Row::new(
+                key.iter()
+                    .cloned()
+                    .chain([self.count_text(*count)])
+                    .enumerate()
+                    .map(|(column, text)| cell(text, &columns, column))
+                    .collect::<Vec<_>>(),
+            )
+            .style(Style::default().fg(theme().text))
+        });
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(headers.into_iter().map(t))
+                    .style(Style::default().bg(theme().header).fg(theme().title)),
+            )
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(
+                        " {} ",
+                        tf(
+                            "statistics.preview",
+                            &[("rows", &self.rows.len()), ("cases", &total)]
+                        )
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            );
+        frame.render_widget(table, area);
+    }
+
+    fn render_research_preview(&self, frame: &mut Frame, area: Rect) {
+        let widths = [
+            Constraint::Length(14),
+            Constraint::Length(9),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Length(6),
+            Constraint::Min(9),
+            Constraint::Length(10),
+            Constraint::Length(9),
+        ];
+        let columns = column_widths(area.inner(Margin::new(1, 1)), &widths, 1, "");
+        let patients: BTreeSet<&str> = self
+            .research_rows
+            .iter()
+            .map(|row| row.patient.as_str())
+            .collect();
+        let rows = self.research_rows.iter().map(|row| {
+            Row::new(
+                row.values()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(column, text)| cell(text, &columns, column))
+                    .collect::<Vec<_>>(),
+            )
+            .style(Style::default().fg(theme().text))
+        });
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(RESEARCH_HEADERS.map(t).to_vec())
+                    .style(Style::default().bg(theme().header).fg(theme().title)),
+            )
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(
+                        " {} ",
+                        tf(
+                            "statistics.research_preview",
+                            &[
+                                ("encounters", &self.research_rows.len()),
+                                ("patients", &patients.len())
+                            ]
+                        )
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            );
+        frame.render_widget(table, area);
+    }
+}
@@ -0,0 +1,426 @@
+//! Average length of stay and 30-day readmission rates, by ward or by
+//! diagnosis, over the stays that ended in a month or over all time.
+//!
+//! A stay counts as readmitted when the same patient is admitted again
+//! within 30 days of its discharge; the readmission is put down to the
+//! earlier stay's ward and diagnosis.
+
+use crate::app::SelectedApp;
+use crate::components::hospital::records::statistics::diagnosis_code;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::Admission;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::{BTreeMap, HashMap};
+use time::{Date, Duration};
+
+const READMISSION_DAYS: i64 = 30;
+const GROUPINGS: [&str; 2] = ["statistics.ward", "statistics.diagnosis"];
+
+#[derive(Debug, Clone, Default)]
+struct StayGroup {
+    name: String,
+    discharges: usize,
+    total_days: f64,
+    readmitted: usize,
+}
+
+impl StayGroup {
+    fn average_days(&self) -> f64 {
+        if self.discharges == 0 {
+            0.0
+        } else {
+            self.total_days / self.discharges as f64
+        }
+    }
+
+    fn readmission_rate(&self) -> f64 {
+        if self.discharges == 0 {
+            0.0
+        } else {
+            self.readmitted as f64 * 100.0 / self.discharges as f64
+        }
+    }
+}
+
+pub struct StayReport {
+    db: Db,
+    admissions: Vec<Admission>,
+    diagnoses: HashMap<i64, String>,
+    /// First day of the month shown, or `None` for all time.
+    month: Option<Date>,
+    grouping: usize,
+    groups: Vec<StayGroup>,
+    state: TableState,
+}
+
+impl StayReport {
+    pub fn new(db: &Db) -> Self {
+        let today = datetime::today();
+        Self {
+            db: db.clone(),
+            admissions: Vec::new(),
+            diagnoses: HashMap::new(),
+            month: Some(today.replace_day(1).unwrap_or(today)),
+            grouping: 0,
+            groups: Vec::new(),
+            state: TableState::default(),
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.admissions = db::get_all_admissions(&self.db)?;
+        self.diagnoses = db::get_admission_diagnoses(&self.db)?.into_iter().collect();
+        self.aggregate();
+        Ok(())
+    }
+
+    fn group_name(&self, admission: &Admission) -> String {
+        match self.grouping {
+            0 => admission.ward.clone(),
+            _ => self
+                .diagnoses
+                .get(&admission.id)
+                .map_or(t("common.unknown_value").to_string(), |d| diagnosis_code(d)),
+        }
+    }
+
+    fn aggregate(&mut self) {
+        let month = self.month.map(datetime::format_month);
+        let mut groups: BTreeMap<String, StayGroup> = BTreeMap::new();
+        for admission in &self.admissions {
+            let Some(discharged_at) = admission.discharged_at.as_deref() else {
+                continue;
+            };
+            if month
+                .as_ref()
+                .is_some_and(|m| !discharged_at.starts_with(m))
+            {
+                continue;
+            }
+            let (Some(admitted), Some(discharged)) = (
+                datetime::parse_stamp(&admission.admitted_at),
+                datetime::parse_stamp(discharged_at),
+            ) else {
+                continue;
+            };
+            let window_end = discharged + Duration::days(READMISSION_DAYS);
+            let readmitted = self.admissions.iter().any(|other| {
+                other.patient_id == admission.patient_id
+                    && other.id != admission.id
+                    && datetime::parse_stamp(&other.admitted_at)
+                        .is_some_and(|at| at >= discharged && at <= window_end)
+            });
+
+            let name = self.group_name(admission);
+            let group = groups.entry(name.clone()).or_insert_with(|| StayGroup {
+                name,
+                ..StayGroup::default()
+            });
+            group.discharges += 1;
+            group.total_days += (discharged - admitted).whole_minutes().max(0) as f64 / 1440.0;
+            if readmitted {
+                group.readmitted += 1;
+            }
+        }
+        self.groups = groups.into_values().collect();
+        self.state.select((!self.groups.is_empty()).then_some(0));
+    }
+
+    fn change_month(&mut self, forward: bool) {
+        let month = self.month.unwrap_or_else(|| {
+            let today = datetime::today();
+            today.replace_day(1).unwrap_or(today)
+        });
+        let moved = if forward {
+            month + Duration::days(31)
+        } else {
+            month - Duration::days(1)
+        };
+        self.month = Some(moved.replace_day(1).unwrap_or(moved));
+        self.aggregate();
+    }
+
+    fn period_text(&self) -> String {
+        match self.month {
+            Some(month) => tf("stays.month", &[("month", &datetime::format_month(month))]),
+            None => t("stays.all").to_string(),
+        }
+    }
+
+    fn summary(&self) -> Line<'static> {
+        let discharges: usize = self.groups.iter().map(|g| g.discharges).sum();
+        let days: f64 = self.groups.iter().map(|g| g.total_days).sum();
+        let readmitted: usize = self.groups.iter().map(|g| g.readmitted).sum();
+        let overall = StayGroup {
+            name: String::new(),
+            discharges,
+            total_days: days,
+            readmitted,
+        };
+        Line::from(vec![
+            Span::styled(
+                tf("stays.discharges_count", &[("count", &discharges)]),
+                Style::default()
+                    .fg(theme().text)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("  |  ", Style::default().fg(theme().inactive)),
+            Span::styled(
+                tf(
+                    "stays.average",
+                    &[("days", &format!("{:.1}", overall.average_days()))],
+                ),
+                Style::default().fg(theme().accent),
+            ),
+            Span::styled("  |  ", Style::default().fg(theme().inactive)),
+            Span::styled(
+                tf(
+                    "stays.readmitted_summary",
+                    &[
+                        ("count", &readmitted),
+                        ("days", &READMISSION_DAYS),
+                        ("rate", &format!("{:.0}", overall.readmission_rate())),
+                    ],
+                ),
+                Style::default().fg(theme().warning),
+            ),
+        ])
+    }
+
+    fn render_table(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(
+                " {} ",
+                tf("stays.by", &[("grouping", &t(GROUPINGS[self.grouping]))])
+            ))
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.groups.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("stays.empty"))
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        }
+        let widths = [
+            Constraint::Min(14),
+            Constraint::Length(10),
+            Constraint::Length(9),
+            Constraint::Length(11),
+            Constraint::Length(9),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+        let rows: Vec<Row> = self
+            .groups
+            .iter()
+            .map(|g| {
+                Row::new(vec![
+                    cell(g.name.as_str(), &columns, 0),
+                    cell(g.discharges.to_string(), &columns, 1),
+                    cell(format!("{:.1}", g.average_days()), &columns, 2),
+                    cell(g.readmitted.to_string(), &columns, 3),
+                    cell(format!("{:.0}%", g.readmission_rate()), &columns, 4),
+                ])
+                .style(Style::default().fg(theme().text))
+            })
+            .collect();
+        let header = Row::new(vec![
+            t(GROUPINGS[self.grouping]),
+            t("stays.discharges"),
+            t("stays.avg_days"),
+            t("stays.readmitted"),
+            t("stays.rate"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .row_highlight_style(Style::default().bg(theme().highlight))
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.state.clone());
+    }
+
+    /// Horizontal bars, one per group, labelled with `text` of the value.
+    fn render_chart(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        color: Color,
+        value: impl Fn(&StayGroup) -> f64,
+        text: impl Fn(f64) -> String,
+    ) {
+        // Bars take whole numbers, so values are scaled to keep a decimal.
+        let bars: Vec<Bar> = self
+            .groups
+            .iter()
+            .map(|g| {
+                let v = value(g);
+                Bar::default()
+                    .label(Line::from(g.name.clone()))
+                    .value((v * 10.0).round() as u64)
+                    .text_value(text(v))
+                    .style(Style::default().fg(color))
+                    .value_style(Style::default().fg(theme().background).bg(color))
+            })
+            .collect();
+        let chart = BarChart::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(title.to_string())
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0)
+            .label_style(Style::default().fg(theme().text))
+            .data(BarGroup::default().bars(&bars));
+        frame.render_widget(chart, area);
+    }
+}
+
+impl Component for StayReport {
+    fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        let len = self.groups.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Left => self.change_month(false),
+            KeyCode::Right => self.change_month(true),
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                self.month = match self.month {
+                    Some(_) => None,
+                    None => {
+                        let today = datetime::today();
+                        Some(today.replace_day(1).unwrap_or(today))
+                    }
+                };
+                self.aggregate();
+            }
+            KeyCode::Tab => {
+                self.grouping = (self.grouping + 1) % GROUPINGS.len();
+                self.aggregate();
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                if let Err(e) = self.load_data() {
+                    toast::push(Toast::error(tf("forecast.load_failed", &[("error", &e)])));
+                }
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(10),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("stays.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(self.period_text())
+                .style(Style::default().fg(theme().accent))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("stays.period")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[1],
+        );
+        frame.render_widget(
+            Paragraph::new(self.summary()).alignment(Alignment::Center),
+            layout[2],
+        );
+
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(layout[3]);
+        self.render_table(frame, body[0]);
+        let charts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(body[1]);
+        self.render_chart(
+            frame,
+            charts[0],
+            &format!(" {} ", t("stays.average_chart")),
+            theme().accent,
+            StayGroup::average_days,
+            |v| format!("{:.1}", v),
+        );
+        self.render_chart(
+            frame,
+            charts[1],
+            &format!(" {} ", t("stays.readmission_chart")),
+            theme().warning,
+            StayGroup::readmission_rate,
+            |v| format!("{:.0}%", v),
+        );
+
+        frame.render_widget(
+            Paragraph::new(t("stays.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+    }
+}
@@ -1,12 +1,23 @@
 use crate::app::SelectedApp;
+use crate::components::form::{self, Field, Form};
+use crate::components::quick_actions;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::discard_prompt::DiscardPrompt;
+use crate::components::widgets::new_patient::{NewPatient, NewPatientOutcome};
+use crate::components::widgets::prescribing_alert::{AlertOutcome, PrescribingAlert};
+use crate::components::widgets::procedure_picker::{PickerOutcome, ProcedurePicker};
 use crate::components::Component;
-use crate::db;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
 use crate::models::{MedicalRecord, Patient};
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::ui_state::{self, Recent};
+use crate::utils::{prescribing, procedures};
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{prelude::*, widgets::*};
-use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StoreRecordState {
@@ -15,61 +26,69 @@ pub enum StoreRecordState {
 }
 
 const PATIENT_SELECTION: usize = 0;
-const INPUT_FIELDS: usize = 4;
-const SUBMIT_BUTTON: usize = 4;
-const BACK_BUTTON: usize = 5;
+const INPUT_FIELDS: usize = 5;
+const SUBMIT_BUTTON: usize = 5;
+const BACK_BUTTON: usize = 6;
+
+const DOCTOR_NOTES: usize = 0;
+const NURSE_NOTES: usize = 1;
+const DIAGNOSIS: usize = 2;
+const PRESCRIPTION: usize = 3;
+const PROCEDURES: usize = 4;
+
+fn record_form(db: &Db) -> Form {
+    let db = db.clone();
+    Form::new(vec![
+        Field::new(t("history.doctor_notes")).required(),
+        Field::new(t("history.nurse_notes")),
+        Field::new(t("history.diagnosis")).required(),
+        Field::new(t("history.prescription")),
+        Field::new(t("store.procedure_codes"))
+            .validate_with(Box::new(move |value| form::procedure_codes(&db, value))),
+    ])
+}
 
 pub struct StoreRecord {
+    db: Db,
     all_patients: Vec<Patient>,
     filtered_patients: Vec<Patient>,
     selected_patient: Option<Patient>,
     search_input: String,
     is_searching: bool,
     table_state: TableState,
-    doctor_notes: String,
-    nurse_notes: Option<String>,
-    diagnosis: String,
-    prescription: Option<String>,
+    form: Form,
     focus_index: usize,
     state: StoreRecordState,
-    error_message: Option<String>,
-    error_timer: Option<Instant>,
-    success_message: Option<String>,
-    success_timer: Option<Instant>,
+    prescribing_alert: Option<PrescribingAlert>,
+    new_patient: Option<NewPatient>,
+    procedure_picker: Option<ProcedurePicker>,
+    discard_prompt: Option<DiscardPrompt>,
 }
 
-impl Default for StoreRecord {
-    fn default() -> Self {
+impl StoreRecord {
+    pub fn new(db: &Db) -> Self {
         let mut table_state = TableState::default();
         table_state.select(Some(0));
         StoreRecord {
+            db: db.clone(),
             all_patients: Vec::new(),
             filtered_patients: Vec::new(),
             selected_patient: None,
             search_input: String::new(),
             is_searching: false,
             table_state,
-            doctor_notes: String::new(),
-            nurse_notes: None,
-            diagnosis: String::new(),
-            prescription: None,
+            form: record_form(db),
             focus_index: PATIENT_SELECTION,
             state: StoreRecordState::SelectingPatient,
-            error_message: None,
-            error_timer: None,
-            success_message: None,
-            success_timer: None,
+            prescribing_alert: None,
+            new_patient: None,
+            procedure_picker: None,
+            discard_prompt: None,
         }
     }
-}
-
-impl StoreRecord {
-    pub fn new() -> Self {
-        Self::default()
-    }
 
     pub fn load_patients(&mut self) -> Result<()> {
-        self.all_patients = db::get_all_patients()?;
+        self.all_patients = db::get_all_patients(&self.db)?;
         self.filter_patients();
         Ok(())
     }
@@ -132,53 +151,163 @@ impl StoreRecord {
         self.table_state.select(Some(i));
     }
 
-    fn clear_error(&mut self) {
-        self.error_message = None;
-        self.error_timer = None;
+    /// Saves the record, with the prescribing alerts it was saved through.
+    fn save_record(&mut self, alert: Option<PrescribingAlert>) {
+        let Some(patient) = &self.selected_patient else {
+            toast::push(Toast::error(t("invoice.select_first")));
+            return;
+        };
+        let new_record = MedicalRecord {
+            id: 0,
+            patient_id: patient.id,
+            doctor_notes: self.form.value(DOCTOR_NOTES),
+            nurse_notes: self.form.optional_value(NURSE_NOTES),
+            diagnosis: self.form.value(DIAGNOSIS),
+            prescription: self.form.optional_value(PRESCRIPTION),
+            procedures: self
+                .form
+                .optional_value(PROCEDURES)
+                .map(|text| procedures::codes(&text).join(", ")),
+            author_id: ui_state::current_user()
+                .and_then(|username| db::get_user_staff_id(&self.db, &username).ok().flatten()),
+        };
+
+        match db::create_medical_record(&self.db, &new_record) {
+            Ok(record_id) => {
+                match alert.map(|a| a.save_overrides(record_id)) {
+                    Some(Err(e)) => {
+                        toast::push(Toast::error(tf("store.overrides_failed", &[("error", &e)])))
+                    }
+                    _ => toast::push(Toast::success(t("store.added"))),
+                }
+
+                quick_actions::saved(SelectedApp::RecordStore);
+                self.form.clear();
+                self.state = StoreRecordState::SelectingPatient;
+                self.focus_index = PATIENT_SELECTION;
+                self.selected_patient = None;
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("common.database_error", &[("error", &e)])));
+            }
+        }
     }
 
-    fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_timer = Some(Instant::now());
+    /// Selects `patient`, just added or picked from the recent ones, clearing
+    /// any search that would hide them.
+    fn select_patient(&mut self, patient: Patient) {
+        self.search_input.clear();
+        self.is_searching = false;
+        if let Err(e) = self.load_patients() {
+            toast::push(Toast::error(tf(
+                "common.reload_patients_failed",
+                &[("error", &e)],
+            )));
+        }
+        let row = self
+            .filtered_patients
+            .iter()
+            .position(|p| p.id == patient.id);
+        self.table_state.select(row);
+        self.selected_patient = Some(patient);
+        self.focus_index = PATIENT_SELECTION;
     }
 
-    fn clear_success(&mut self) {
-        self.success_message = None;
-        self.success_timer = None;
+    /// Patients opened recently, most recent first.
+    fn recent_patients(&self) -> Vec<&Patient> {
+        ui_state::recent_patients()
+            .into_iter()
+            .filter_map(|id| self.all_patients.iter().find(|p| p.id == id))
+            .collect()
     }
 
-    pub fn check_error_timeout(&mut self) {
-        if let Some(timer) = self.error_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_error();
-            }
+    fn open_procedure_picker(&mut self) {
+        match db::get_procedures(&self.db) {
+            Ok(catalog) => self.procedure_picker = Some(ProcedurePicker::new(catalog)),
+            Err(e) => toast::push(Toast::error(tf("form.procedures_failed", &[("error", &e)]))),
         }
     }
 
-    pub fn check_success_timeout(&mut self) {
-        if let Some(timer) = self.success_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_success();
-            }
+    /// Back to the patient list, asking first when the details were changed.
+    fn leave_details(&mut self) {
+        if self.form.is_dirty() {
+            self.discard_prompt = Some(DiscardPrompt::new());
+        } else {
+            self.state = StoreRecordState::SelectingPatient;
+            self.focus_index = PATIENT_SELECTION;
         }
     }
 
     pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
-        self.check_error_timeout();
-        self.check_success_timeout();
-
+        if let Some(prompt) = &mut self.discard_prompt {
+            match prompt.handle_key(key) {
+                Some(true) => {
+                    self.discard_prompt = None;
+                    self.form.clear();
+                    self.leave_details();
+                }
+                Some(false) => self.discard_prompt = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+        if let Some(picker) = &mut self.procedure_picker {
+            match picker.handle_key(key) {
+                Some(PickerOutcome::Picked(procedure)) => {
+                    let codes = procedures::add_code(&self.form.value(PROCEDURES), &procedure.code);
+                    self.form.fields[PROCEDURES].set(codes);
+                    self.procedure_picker = None;
+                }
+                Some(PickerOutcome::Cancelled) => self.procedure_picker = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+        if let Some(popup) = &mut self.new_patient {
+            match popup.handle_key(key) {
+                Some(NewPatientOutcome::Created(patient)) => {
+                    self.new_patient = None;
+                    self.select_patient(*patient);
+                }
+                Some(NewPatientOutcome::Cancelled) => self.new_patient = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+        if let Some(alert) = &mut self.prescribing_alert {
+            match alert.handle_key(key) {
+                Some(AlertOutcome::Proceed) => {
+                    let alert = self.prescribing_alert.take();
+                    self.save_record(alert);
+                }
+                Some(AlertOutcome::Cancel) => {
+                    self.prescribing_alert = None;
+                    self.focus_index = PRESCRIPTION;
+                }
+                None => {}
+            }
+            return Ok(None);
+        }
         match self.state {
             StoreRecordState::SelectingPatient => {
                 match key.code {
+                    KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.new_patient = Some(NewPatient::new(&self.db));
+                    }
+                    KeyCode::Char(digit @ '1'..='9') if !self.is_searching => {
+                        let index = digit as usize - '1' as usize;
+                        if let Some(patient) = self.recent_patients().get(index) {
+                            let patient = (*patient).clone();
+                            self.select_patient(patient);
+                        }
+                    }
                     KeyCode::Char(c) if self.is_searching => {
                         self.search_input.push(c);
                         self.filter_patients();
-                        self.clear_error();
                     }
                     KeyCode::Backspace if self.is_searching => {
                         self.search_input.pop();
                         self.filter_patients();
-                        self.clear_error();
                     }
                     KeyCode::Down if self.is_searching && !self.filtered_patients.is_empty() => {
                         self.is_searching = false;
@@ -236,19 +365,20 @@ impl StoreRecord {
                                 if selected < self.filtered_patients.len() {
                                     if let Some(patient) = &self.selected_patient {
                                         if patient.id == self.filtered_patients[selected].id {
+                                            ui_state::add_recent(Recent::patient(patient));
                                             self.state = StoreRecordState::EnteringDetails;
                                             self.focus_index = 0;
                                             return Ok(None);
                                         } else {
-                                            self.set_error(
-                                                "Please Select Patient with Spacebar".to_string(),
-                                            );
+                                            toast::push(Toast::error(
+                                                t("common.select_with_spacebar").to_string(),
+                                            ));
                                             return Ok(None);
                                         }
                                     } else {
-                                        self.set_error(
-                                            "Please Select Patient with Spacebar".to_string(),
-                                        );
+                                        toast::push(Toast::error(
+                                            t("common.select_with_spacebar").to_string(),
+                                        ));
                                         return Ok(None);
                                     }
                                 }
@@ -262,44 +392,13 @@ impl StoreRecord {
             }
 
             StoreRecordState::EnteringDetails => match key.code {
-                KeyCode::Char(c) => match self.focus_index {
-                    0 => self.doctor_notes.push(c),
-                    1 => {
-                        if let Some(ref mut notes) = self.nurse_notes {
-                            notes.push(c);
-                        } else {
-                            self.nurse_notes = Some(c.to_string());
-                        }
-                    }
-                    2 => self.diagnosis.push(c),
-                    3 => {
-                        if let Some(ref mut prescription) = self.prescription {
-                            prescription.push(c);
-                        } else {
-                            self.prescription = Some(c.to_string());
-                        }
-                    }
-                    _ => {}
-                },
-                KeyCode::Backspace => match self.focus_index {
-                    0 => {
-                        self.doctor_notes.pop();
-                    }
-                    1 => {
-                        if let Some(notes) = self.nurse_notes.as_mut() {
-                            notes.pop();
-                        }
-                    }
-                    2 => {
-                        self.diagnosis.pop();
-                    }
-                    3 => {
-                        if let Some(prescription) = self.prescription.as_mut() {
-                            prescription.pop();
-                        }
-                    }
-                    _ => {}
-                },
+                _ if self.form.handle_key(self.focus_index, key) => {}
+                KeyCode::Char('f')
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && self.focus_index == PROCEDURES =>
+                {
+                    self.open_procedure_picker();
+                }
                 KeyCode::Tab => {
                     self.focus_index = (self.focus_index + 1) % (INPUT_FIELDS + 2);
                 }
@@ -309,61 +408,31 @@ impl StoreRecord {
                 KeyCode::Up => {
                     self.focus_index = (self.focus_index + (INPUT_FIELDS + 1)) % (INPUT_FIELDS + 2);
                 }
-                KeyCode::Enter if self.focus_index == BACK_BUTTON => {
-                    self.state = StoreRecordState::SelectingPatient;
-                    self.focus_index = PATIENT_SELECTION;
-                    return Ok(None);
-                }
+                KeyCode::Enter if self.focus_index == BACK_BUTTON => self.leave_details(),
                 KeyCode::Enter if self.focus_index == SUBMIT_BUTTON => {
-                    if self.doctor_notes.is_empty() {
-                        self.set_error("Doctor's Notes cannot be empty".to_string());
-                        return Ok(None);
-                    }
-                    if self.diagnosis.is_empty() {
-                        self.set_error("Diagnosis cannot be empty".to_string());
+                    if let Some(invalid) = self.form.validate() {
+                        self.focus_index = invalid;
+                        toast::push(Toast::error(t("common.fix_fields")));
                         return Ok(None);
                     }
-                    if let Some(patient) = &self.selected_patient {
-                        let new_record = MedicalRecord {
-                            id: 0,
-                            patient_id: patient.id,
-                            doctor_notes: self.doctor_notes.clone(),
-                            nurse_notes: self.nurse_notes.clone(),
-                            diagnosis: self.diagnosis.clone(),
-                            prescription: self.prescription.clone(),
-                        };
-
-                        match db::create_medical_record(&new_record) {
-                            Ok(_) => {
-                                self.success_message =
-                                    Some("Medical record added successfully!".to_string());
-                                self.success_timer = Some(Instant::now());
-
-                                self.doctor_notes.clear();
-                                self.nurse_notes = None;
-                                self.diagnosis.clear();
-                                self.prescription = None;
-                                self.state = StoreRecordState::SelectingPatient;
-                                self.focus_index = PATIENT_SELECTION;
-                                self.selected_patient = None;
-                                self.clear_error();
-                            }
-                            Err(e) => {
-                                self.set_error(format!("Database error: {}", e));
-                            }
+                    let alerts = match (
+                        &self.selected_patient,
+                        self.form.optional_value(PRESCRIPTION),
+                    ) {
+                        (Some(patient), Some(prescription)) => {
+                            prescribing::check(&prescription, patient)
                         }
+                        _ => Vec::new(),
+                    };
+                    if alerts.is_empty() {
+                        self.save_record(None);
                     } else {
-                        self.set_error("Please select a patient first.".to_string());
-                        return Ok(None);
+                        self.prescribing_alert = Some(PrescribingAlert::new(&self.db, alerts));
                     }
                 }
                 KeyCode::Enter => {}
 
-                KeyCode::Esc => {
-                    self.state = StoreRecordState::SelectingPatient;
-                    self.focus_index = PATIENT_SELECTION;
-                    return Ok(None);
-                }
+                KeyCode::Esc => self.leave_details(),
                 _ => {}
             },
         }
@@ -378,7 +447,7 @@ impl Component for StoreRecord {
 
     fn render(&self, frame: &mut Frame) {
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             frame.area(),
         );
 
@@ -390,6 +459,19 @@ impl Component for StoreRecord {
                 self.render_record_details_page(frame);
             }
         }
+
+        if let Some(alert) = &self.prescribing_alert {
+            alert.render_popup(frame);
+        }
+        if let Some(popup) = &self.new_patient {
+            popup.render_popup(frame);
+        }
+        if let Some(picker) = &self.procedure_picker {
+            picker.render_popup(frame);
+        }
+        if let Some(prompt) = &self.discard_prompt {
+            prompt.render_popup(frame);
+        }
     }
 }
 
@@ -413,35 +495,33 @@ impl StoreRecord {
 
         let header = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header, layout[0]);
 
-        let title = Paragraph::new("📝 SELECT PATIENT TO ADD/STORE RECORDS")
+        let title = Paragraph::new(t("store.title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
 
         self.render_patient_selection_content(frame, layout[1]);
 
-        self.render_status_message(frame, layout[2]);
-
         let back_text = if self.focus_index == BACK_BUTTON {
-            "► Back ◄"
+            format!("► {} ◄", t("common.back"))
         } else {
-            "  Back  "
+            format!("  {}  ", t("common.back"))
         };
         let back_style = if self.focus_index == BACK_BUTTON {
             Style::default()
-                .fg(Color::Rgb(129, 199, 245))
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
         frame.render_widget(
             Paragraph::new(back_text)
@@ -451,13 +531,26 @@ impl StoreRecord {
         );
 
         frame.render_widget(
-            Paragraph::new(
-                "/ or s: Search, ↑/↓: Navigate | Spacebar: Select | Enter: Confirm | Tab: Back | Esc: Exit"
-            )
-            .style(Style::default().fg(Color::Rgb(180, 180, 200)))
-            .alignment(Alignment::Center),
+            Paragraph::new(t("invoice.select_help"))
+                .style(Style::default().fg(theme().inactive))
+                .alignment(Alignment::Center),
             layout[6],
         );
+
+        let recent: Vec<String> = self
+            .recent_patients()
+            .iter()
+            .enumerate()
+            .map(|(index, p)| format!("{}: {} {}", index + 1, p.first_name, p.last_name))
+            .collect();
+        if !recent.is_empty() {
+            frame.render_widget(
+                Paragraph::new(tf("common.recent", &[("list", &(recent.join(" | ")))]))
+                    .style(Style::default().fg(theme().help))
+                    .alignment(Alignment::Center),
+                layout[5],
+            );
+        }
     }
 
     fn render_patient_selection_content(&self, frame: &mut Frame, area: Rect) {
@@ -470,22 +563,22 @@ impl StoreRecord {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Patients ",
+                format!(" {} ", t("common.search_patients")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(
                 if self.is_searching && self.focus_index == PATIENT_SELECTION {
-                    Style::default().fg(Color::Rgb(250, 250, 110))
+                    Style::default().fg(theme().focus)
                 } else {
-                    Style::default().fg(Color::Rgb(75, 75, 120))
+                    Style::default().fg(theme().border)
                 },
             )
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .style(Style::default().bg(theme().surface));
 
         let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .block(search_block);
         frame.render_widget(search_paragraph, content_layout[0]);
 
@@ -494,34 +587,52 @@ impl StoreRecord {
             .border_type(BorderType::Rounded)
             .title(if !self.search_input.is_empty() {
                 format!(
-                    " Select Patient ({} of {} matches) ",
-                    self.filtered_patients.len(),
-                    self.all_patients.len()
+                    " {} ",
+                    tf(
+                        "common.select_patient_matches",
+                        &[
+                            ("shown", &self.filtered_patients.len()),
+                            ("total", &self.all_patients.len())
+                        ]
+                    )
                 )
             } else {
-                format!(" Select Patient ({}) ", self.all_patients.len())
+                format!(
+                    " {} ",
+                    tf(
+                        "common.select_patient_count",
+                        &[("total", &self.all_patients.len())]
+                    )
+                )
             })
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .border_style(
                 if self.focus_index == PATIENT_SELECTION && !self.is_searching {
-                    Style::default().fg(Color::Rgb(250, 250, 110))
+                    Style::default().fg(theme().focus)
                 } else {
-                    Style::default().fg(Color::Rgb(140, 140, 200))
+                    Style::default().fg(theme().unfocused)
                 },
             )
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
         let selected_style = Style::default()
-            .bg(Color::Rgb(45, 45, 60))
-            .fg(Color::Rgb(250, 250, 110))
+            .bg(theme().highlight)
+            .fg(theme().focus)
             .add_modifier(Modifier::BOLD);
-        let normal_style = Style::default()
-            .bg(Color::Rgb(26, 26, 36))
-            .fg(Color::Rgb(220, 220, 240));
+        let normal_style = Style::default().bg(theme().input).fg(theme().text);
+
+        let widths = [
+            Constraint::Length(3),
+            Constraint::Length(8),
+            Constraint::Length(15),
+            Constraint::Length(15),
+            Constraint::Min(15),
+        ];
+        let columns = column_widths(content_layout[1].inner(Margin::new(1, 1)), &widths, 1, "► ");
 
         let mut rows = Vec::new();
         for patient in &self.filtered_patients {
@@ -537,57 +648,47 @@ impl StoreRecord {
 
             rows.push(Row::new(vec![
                 Cell::from(selected_indicator.to_string()).style(normal_style),
-                Cell::from(patient.id.to_string()).style(normal_style),
-                Cell::from(patient.first_name.clone()).style(normal_style),
-                Cell::from(patient.last_name.clone()).style(normal_style),
-                Cell::from(patient.phone_number.clone()).style(normal_style),
+                cell(patient.id.to_string(), &columns, 1).style(normal_style),
+                cell(&patient.first_name, &columns, 2).style(normal_style),
+                cell(&patient.last_name, &columns, 3).style(normal_style),
+                cell(&patient.phone_number, &columns, 4).style(normal_style),
             ]));
         }
 
         if self.filtered_patients.is_empty() {
             let message = if self.search_input.is_empty() {
-                "No patients found in database"
+                t("common.no_patients")
             } else {
-                "No patients match your search criteria"
+                t("common.no_patient_matches")
             };
 
             rows.push(Row::new(vec![
                 Cell::from(""),
                 Cell::from(""),
-                Cell::from(message).style(Style::default().fg(Color::Rgb(180, 180, 200))),
+                Cell::from(message).style(Style::default().fg(theme().inactive)),
                 Cell::from(""),
                 Cell::from(""),
             ]));
         }
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(3),
-                Constraint::Length(8),
-                Constraint::Length(15),
-                Constraint::Length(15),
-                Constraint::Min(15),
-            ],
-        )
-        .header(
-            Row::new(vec![
-                Cell::from(""),
-                Cell::from("ID").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("First Name").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Last Name").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Phone").style(Style::default().add_modifier(Modifier::BOLD)),
-            ])
-            .style(
-                Style::default()
-                    .bg(Color::Rgb(80, 60, 130))
-                    .fg(Color::Rgb(180, 180, 250)),
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec![
+                    Cell::from(""),
+                    Cell::from(t("common.id")).style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.first_name"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.last_name"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.phone"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                ])
+                .style(Style::default().bg(theme().header).fg(theme().title))
+                .height(1),
             )
-            .height(1),
-        )
-        .block(table_block)
-        .row_highlight_style(selected_style)
-        .highlight_symbol("► ");
+            .block(table_block)
+            .row_highlight_style(selected_style)
+            .highlight_symbol("► ");
 
         frame.render_stateful_widget(table, content_layout[1], &mut self.table_state.clone());
     }
@@ -599,7 +700,7 @@ impl StoreRecord {
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),
-                Constraint::Length(12),
+                Constraint::Length(15),
                 Constraint::Length(1),
                 Constraint::Length(1),
                 Constraint::Length(1),
@@ -614,35 +715,33 @@ impl StoreRecord {
 
         let header = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header, layout[0]);
 
-        let title = Paragraph::new("📝 ADD RECORD DETAILS")
+        let title = Paragraph::new(t("store.details_title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
 
         self.render_record_form_fields(frame, layout[1]);
 
-        self.render_status_message(frame, layout[3]);
-
         let submit_text = if self.focus_index == SUBMIT_BUTTON {
-            "► Add Details ◄"
+            format!("► {} ◄", t("store.add_details"))
         } else {
-            "  Add Details  "
+            format!("  {}  ", t("store.add_details"))
         };
         let submit_style = if self.focus_index == SUBMIT_BUTTON {
             Style::default()
-                .fg(Color::Rgb(140, 219, 140))
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
         frame.render_widget(
             Paragraph::new(submit_text)
@@ -652,16 +751,16 @@ impl StoreRecord {
         );
 
         let back_text = if self.focus_index == BACK_BUTTON {
-            "► Back ◄"
+            format!("► {} ◄", t("common.back"))
         } else {
-            "  Back  "
+            format!("  {}  ", t("common.back"))
         };
         let back_style = if self.focus_index == BACK_BUTTON {
             Style::default()
-                .fg(Color::Rgb(129, 199, 245))
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
         frame.render_widget(
             Paragraph::new(back_text)
@@ -671,8 +770,8 @@ impl StoreRecord {
         );
 
         frame.render_widget(
-            Paragraph::new("Tab: Switch Focus, ↑/↓: Navigate | Enter: Submit | Esc: Back")
-                .style(Style::default().fg(Color::Rgb(180, 180, 200)))
+            Paragraph::new(t("store.details_help"))
+                .style(Style::default().fg(theme().inactive))
                 .alignment(Alignment::Center),
             layout[9],
         );
@@ -686,115 +785,14 @@ impl StoreRecord {
                 Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Length(3),
+                Constraint::Length(3),
             ])
             .horizontal_margin(3)
             .split(area);
 
-        let required_style = Style::default().fg(Color::Rgb(230, 230, 250));
-
-        let doctor_notes_input = Paragraph::new(self.doctor_notes.clone())
-            .style(if self.focus_index == 0 {
-                Style::default().fg(Color::Yellow)
-            } else {
-                Style::default().fg(Color::Rgb(220, 220, 240))
-            })
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Doctor's Notes* ", required_style))
-                    .border_style(if self.focus_index == 0 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(doctor_notes_input, form_layout[0]);
-
-        let nurse_notes_input = Paragraph::new(self.nurse_notes.clone().unwrap_or_default())
-            .style(if self.focus_index == 1 {
-                Style::default().fg(Color::Yellow)
-            } else {
-                Style::default().fg(Color::Rgb(220, 220, 240))
-            })
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Nurse's Notes ", required_style))
-                    .border_style(if self.focus_index == 1 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(nurse_notes_input, form_layout[1]);
-
-        let diagnosis_input = Paragraph::new(self.diagnosis.clone())
-            .style(if self.focus_index == 2 {
-                Style::default().fg(Color::Yellow)
-            } else {
-                Style::default().fg(Color::Rgb(220, 220, 240))
-            })
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Diagnosis* ", required_style))
-                    .border_style(if self.focus_index == 2 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(diagnosis_input, form_layout[2]);
-
-        let prescription_input = Paragraph::new(self.prescription.clone().unwrap_or_default())
-            .style(if self.focus_index == 3 {
-                Style::default().fg(Color::Yellow)
-            } else {
-                Style::default().fg(Color::Rgb(220, 220, 240))
-            })
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(" Prescription ", required_style))
-                    .border_style(if self.focus_index == 3 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(prescription_input, form_layout[3]);
-    }
-
-    fn render_status_message(&self, frame: &mut Frame, area: Rect) {
-        let status_message = if let Some(success) = &self.success_message {
-            Paragraph::new(format!("✓ {}", success))
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD)
-                        .bg(Color::Rgb(16, 16, 28)),
-                )
-                .alignment(Alignment::Center)
-        } else if let Some(error) = &self.error_message {
-            Paragraph::new(format!("⚠️ {}", error))
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(255, 100, 100))
-                        .add_modifier(Modifier::BOLD)
-                        .bg(Color::Rgb(16, 16, 28)),
-                )
-                .alignment(Alignment::Center)
-        } else {
-            Paragraph::new("").style(Style::default().bg(Color::Rgb(16, 16, 28)))
-        };
-        frame.render_widget(status_message, area);
+        for (index, area) in form_layout.iter().enumerate() {
+            self.form
+                .render_field(frame, index, *area, self.focus_index);
+        }
     }
 }
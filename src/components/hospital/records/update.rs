@@ -1,16 +1,30 @@
 use crate::app::SelectedApp;
+use crate::components::form;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::undo::EditHistory;
+use crate::components::widgets::discard_prompt::DiscardPrompt;
+use crate::components::widgets::edit_warning;
+use crate::components::widgets::prescribing_alert::{AlertOutcome, PrescribingAlert};
+use crate::components::widgets::procedure_picker::{PickerOutcome, ProcedurePicker};
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
-use crate::db;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::locks::EditLock;
 use crate::models::{MedicalRecord, Patient};
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::utils::prescribing::{self, Alert};
+use crate::utils::procedures;
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{prelude::*, widgets::*};
 use std::collections::HashMap;
-use std::time::{Duration, Instant};
 
 enum ConfirmAction {
     UpdateRecord,
+    DiscardChanges,
 }
 
 enum UpdateState {
@@ -19,28 +33,30 @@ enum UpdateState {
 }
 
 pub struct UpdateRecord {
+    db: Db,
     all_records: Vec<MedicalRecord>,
     filtered_records: Vec<MedicalRecord>,
     patients: HashMap<i64, Patient>,
-    search_input: String,
+    search_input: TextInput,
     is_searching: bool,
     table_state: TableState,
     update_state: UpdateState,
     record_id_input: String,
     record: MedicalRecord,
+    history: EditHistory<MedicalRecord>,
     loaded: bool,
+    edit_lock: Option<EditLock>,
     selected_field: Option<usize>,
     edit_table_state: TableState,
     input_value: String,
     editing: bool,
-    error_message: Option<String>,
-    error_timer: Option<Instant>,
-    success_message: Option<String>,
-    success_timer: Option<Instant>,
     show_confirmation: bool,
     confirmation_message: String,
     confirmed_action: Option<ConfirmAction>,
     confirmation_selected: usize,
+    discard_prompt: Option<DiscardPrompt>,
+    prescribing_alert: Option<PrescribingAlert>,
+    procedure_picker: Option<ProcedurePicker>,
 }
 
 const ID_INPUT: usize = 0;
@@ -49,10 +65,11 @@ const DOCTOR_NOTES_INPUT: usize = 2;
 const NURSE_NOTES_INPUT: usize = 3;
 const DIAGNOSIS_INPUT: usize = 4;
 const PRESCRIPTION_INPUT: usize = 5;
-const INPUT_FIELDS: usize = 5;
+const PROCEDURES_INPUT: usize = 6;
+const INPUT_FIELDS: usize = 6;
 
 impl UpdateRecord {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
         let mut selection_state = TableState::default();
         selection_state.select(Some(0));
 
@@ -60,10 +77,11 @@ impl UpdateRecord {
         edit_table_state.select(Some(0));
 
         Self {
+            db: db.clone(),
             all_records: Vec::new(),
             filtered_records: Vec::new(),
             patients: HashMap::new(),
-            search_input: String::new(),
+            search_input: TextInput::new(),
             is_searching: false,
             table_state: selection_state,
             update_state: UpdateState::SelectingRecord,
@@ -75,25 +93,28 @@ impl UpdateRecord {
                 nurse_notes: None,
                 diagnosis: String::new(),
                 prescription: None,
+                procedures: None,
+                author_id: None,
             },
+            history: EditHistory::new(),
             loaded: false,
+            edit_lock: None,
             selected_field: Some(0),
             edit_table_state,
             input_value: String::new(),
             editing: false,
-            error_message: None,
-            error_timer: None,
-            success_message: None,
-            success_timer: None,
             show_confirmation: false,
             confirmation_message: String::new(),
             confirmed_action: None,
             confirmation_selected: 0,
+            discard_prompt: None,
+            prescribing_alert: None,
+            procedure_picker: None,
         }
     }
 
     pub fn fetch_records(&mut self) -> Result<()> {
-        self.all_records = db::get_all_medical_records()?;
+        self.all_records = db::get_all_medical_records(&self.db)?;
         self.fetch_patients_data()?;
         self.filter_records();
         Ok(())
@@ -102,7 +123,7 @@ impl UpdateRecord {
     fn fetch_patients_data(&mut self) -> Result<()> {
         self.patients.clear();
 
-        match db::get_all_patients() {
+        match db::get_all_patients(&self.db) {
             Ok(all_patients) => {
                 for patient in all_patients {
                     self.patients.insert(patient.id, patient);
@@ -110,7 +131,10 @@ impl UpdateRecord {
                 Ok(())
             }
             Err(e) => {
-                self.set_error(format!("Failed to fetch patient data: {}", e));
+                toast::push(Toast::error(tf(
+                    "records.delete.fetch_failed",
+                    &[("error", &e)],
+                )));
                 Ok(())
             }
         }
@@ -124,7 +148,7 @@ impl UpdateRecord {
         if self.search_input.is_empty() {
             self.filtered_records = self.all_records.clone();
         } else {
-            let search_term = self.search_input.to_lowercase();
+            let search_term = self.search_input.value().to_lowercase();
             self.filtered_records = self
                 .all_records
                 .iter()
@@ -153,17 +177,22 @@ impl UpdateRecord {
         }
     }
 
-    fn load_record_by_id(&mut self, record_id: i64) -> Result<()> {
-        match db::get_medical_record(record_id) {
+    pub fn load_record_by_id(&mut self, record_id: i64) -> Result<()> {
+        match db::get_medical_record(&self.db, record_id) {
             Ok(record) => {
                 self.record = record;
+                self.edit_lock = Some(EditLock::claim(&self.db, "medical_record", record_id));
+                self.history.clear();
                 self.loaded = true;
                 self.update_state = UpdateState::EditingRecord;
                 self.update_input_value();
                 Ok(())
             }
             Err(_) => {
-                self.set_error(format!("Record with ID {} doesn't exist", record_id));
+                toast::push(Toast::error(tf(
+                    "records.update.not_found",
+                    &[("id", &record_id)],
+                )));
                 Err(anyhow::anyhow!("Record not found"))
             }
         }
@@ -177,7 +206,7 @@ impl UpdateRecord {
                     Err(e) => Err(e),
                 }
             } else {
-                self.set_error("Invalid Record ID format.".to_string());
+                toast::push(Toast::error(t("records.update.invalid_id")));
                 Err(anyhow::anyhow!("Invalid Record ID format"))
             }
         } else {
@@ -193,7 +222,7 @@ impl UpdateRecord {
                 return self.load_record_by_id(record_id);
             }
         }
-        self.set_error("No record selected".to_string());
+        toast::push(Toast::error(t("retrieve.none_selected")));
         Err(anyhow::anyhow!("No record selected"))
     }
 
@@ -211,6 +240,7 @@ impl UpdateRecord {
                 NURSE_NOTES_INPUT => self.record.nurse_notes.clone().unwrap_or_default(),
                 DIAGNOSIS_INPUT => self.record.diagnosis.clone(),
                 PRESCRIPTION_INPUT => self.record.prescription.clone().unwrap_or_default(),
+                PROCEDURES_INPUT => self.record.procedures.clone().unwrap_or_default(),
                 _ => String::new(),
             };
         }
@@ -221,13 +251,14 @@ impl UpdateRecord {
             return;
         }
 
+        let before = self.record.clone();
         if let Some(field_index) = self.selected_field {
             match field_index {
                 PATIENT_ID_INPUT => {
                     if let Ok(patient_id) = self.input_value.parse::<i64>() {
                         self.record.patient_id = patient_id;
                     } else {
-                        self.set_error("Invalid Patient ID format.".to_string());
+                        toast::push(Toast::error(t("records.update.invalid_patient_id")));
                         return;
                     }
                 }
@@ -235,9 +266,18 @@ impl UpdateRecord {
                 NURSE_NOTES_INPUT => self.record.nurse_notes = Some(self.input_value.clone()),
                 DIAGNOSIS_INPUT => self.record.diagnosis = self.input_value.clone(),
                 PRESCRIPTION_INPUT => self.record.prescription = Some(self.input_value.clone()),
+                PROCEDURES_INPUT => {
+                    if let Err(e) = form::procedure_codes(&self.db, &self.input_value) {
+                        toast::push(Toast::error(e));
+                        return;
+                    }
+                    let codes = procedures::codes(&self.input_value);
+                    self.record.procedures = (!codes.is_empty()).then(|| codes.join(", "));
+                }
                 _ => {}
             }
         }
+        self.history.record(before);
         self.editing = false;
     }
 
@@ -248,13 +288,41 @@ impl UpdateRecord {
         self.confirmation_selected = 0;
     }
 
+    fn undo_edit(&mut self) {
+        match self.history.undo(self.record.clone()) {
+            Some(previous) => {
+                self.record = previous;
+                self.update_input_value();
+            }
+            None => toast::push(Toast::error(t("common.nothing_to_undo"))),
+        }
+    }
+
+    fn redo_edit(&mut self) {
+        match self.history.redo(self.record.clone()) {
+            Some(next) => {
+                self.record = next;
+                self.update_input_value();
+            }
+            None => toast::push(Toast::error(t("common.nothing_to_redo"))),
+        }
+    }
+
+    fn discard_changes(&mut self) {
+        if let Some(original) = self.history.discard() {
+            self.record = original;
+            self.update_input_value();
+            toast::push(Toast::success(t("common.discarded")));
+        }
+    }
+
     fn update_record(&mut self) -> Result<()> {
-        match db::update_medical_record(&self.record) {
+        match db::update_medical_record(&self.db, &self.record) {
             Ok(_) => {
-                self.success_message = Some("Record updated successfully!".to_string());
-                self.success_timer = Some(Instant::now());
+                toast::push(Toast::success(t("records.update.updated")));
+                self.history.clear();
 
-                if let Ok(records) = db::get_all_medical_records() {
+                if let Ok(records) = db::get_all_medical_records(&self.db) {
                     self.all_records = records.clone();
                     self.filtered_records = records;
                     self.filter_records();
@@ -263,24 +331,94 @@ impl UpdateRecord {
                 Ok(())
             }
             Err(e) => {
-                self.set_error(format!("Database error: {}", e));
+                toast::push(Toast::error(tf("common.database_error", &[("error", &e)])));
                 Err(e)
             }
         }
     }
 
+    /// Prescribing alerts raised by the edited prescription that the saved
+    /// one did not already raise, so a record is not re-flagged for
+    /// warnings overridden when it was written.
+    fn new_prescribing_alerts(&self) -> Vec<Alert> {
+        let Some(patient) = self.patients.get(&self.record.patient_id) else {
+            return Vec::new();
+        };
+        let check = |prescription: Option<&str>| {
+            prescription.map_or(Vec::new(), |p| prescribing::check(p, patient))
+        };
+        let saved = self
+            .all_records
+            .iter()
+            .find(|r| r.id == self.record.id)
+            .filter(|r| r.patient_id == self.record.patient_id)
+            .map_or(Vec::new(), |r| check(r.prescription.as_deref()));
+        check(self.record.prescription.as_deref())
+            .into_iter()
+            .filter(|alert| !saved.contains(alert))
+            .collect()
+    }
+
+    fn save_with_alerts(&mut self) {
+        let alerts = self.new_prescribing_alerts();
+        if alerts.is_empty() {
+            let _ = self.update_record();
+        } else {
+            self.prescribing_alert = Some(PrescribingAlert::new(&self.db, alerts));
+        }
+    }
+
     fn back_to_selection(&mut self) {
         self.update_state = UpdateState::SelectingRecord;
         self.loaded = false;
+        self.edit_lock = None;
         self.record_id_input = String::new();
         self.editing = false;
-        self.clear_error();
-        self.clear_success();
+        self.history.clear();
     }
 
     fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
-        self.check_timeouts();
+        if let Some(picker) = &mut self.procedure_picker {
+            match picker.handle_key(key) {
+                Some(PickerOutcome::Picked(procedure)) => {
+                    self.input_value = procedures::add_code(&self.input_value, &procedure.code);
+                    self.procedure_picker = None;
+                }
+                Some(PickerOutcome::Cancelled) => self.procedure_picker = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+        if let Some(alert) = &mut self.prescribing_alert {
+            match alert.handle_key(key) {
+                Some(AlertOutcome::Proceed) => {
+                    let alert = self.prescribing_alert.take();
+                    if self.update_record().is_ok() {
+                        if let Some(Err(e)) = alert.map(|a| a.save_overrides(self.record.id)) {
+                            toast::push(Toast::error(tf(
+                                "records.update.overrides_failed",
+                                &[("error", &e)],
+                            )));
+                        }
+                    }
+                }
+                Some(AlertOutcome::Cancel) => self.prescribing_alert = None,
+                None => {}
+            }
+            return Ok(None);
+        }
 
+        if let Some(prompt) = &mut self.discard_prompt {
+            match prompt.handle_key(key) {
+                Some(true) => {
+                    self.discard_prompt = None;
+                    self.back_to_selection();
+                }
+                Some(false) => self.discard_prompt = None,
+                None => {}
+            }
+            return Ok(None);
+        }
         if self.show_confirmation {
             match key.code {
                 KeyCode::Left | KeyCode::Right => {
@@ -288,8 +426,10 @@ impl UpdateRecord {
                 }
                 KeyCode::Enter => {
                     if self.confirmation_selected == 0 {
-                        if let Some(ConfirmAction::UpdateRecord) = self.confirmed_action.take() {
-                            let _ = self.update_record();
+                        match self.confirmed_action.take() {
+                            Some(ConfirmAction::UpdateRecord) => self.save_with_alerts(),
+                            Some(ConfirmAction::DiscardChanges) => self.discard_changes(),
+                            None => {}
                         }
                     }
                     self.show_confirmation = false;
@@ -305,6 +445,18 @@ impl UpdateRecord {
 
         if self.editing {
             match key.code {
+                KeyCode::Char('f')
+                    if key.modifiers.contains(KeyModifiers::CONTROL)
+                        && self.selected_field == Some(PROCEDURES_INPUT) =>
+                {
+                    match db::get_procedures(&self.db) {
+                        Ok(catalog) => self.procedure_picker = Some(ProcedurePicker::new(catalog)),
+                        Err(e) => toast::push(Toast::error(tf(
+                            "form.procedures_failed",
+                            &[("error", &e)],
+                        ))),
+                    }
+                }
                 KeyCode::Char(c) => {
                     self.input_value.push(c);
                 }
@@ -325,15 +477,8 @@ impl UpdateRecord {
 
         if matches!(self.update_state, UpdateState::SelectingRecord) {
             match key.code {
-                KeyCode::Char(c) if self.is_searching => {
-                    self.search_input.push(c);
+                _ if self.is_searching && self.search_input.handle_key(key) => {
                     self.filter_records();
-                    self.clear_error();
-                }
-                KeyCode::Backspace if self.is_searching => {
-                    self.search_input.pop();
-                    self.filter_records();
-                    self.clear_error();
                 }
                 KeyCode::Down if self.is_searching && !self.filtered_records.is_empty() => {
                     self.is_searching = false;
@@ -353,12 +498,10 @@ impl UpdateRecord {
                 KeyCode::Char(c) if !self.is_searching => {
                     self.record_id_input.push(c);
                     self.input_value = self.record_id_input.clone();
-                    self.clear_error();
                 }
                 KeyCode::Backspace if !self.is_searching => {
                     self.record_id_input.pop();
                     self.input_value = self.record_id_input.clone();
-                    self.clear_error();
                 }
 
                 KeyCode::Up if !self.is_searching => {
@@ -420,13 +563,35 @@ impl UpdateRecord {
                 if key.modifiers.contains(KeyModifiers::CONTROL) =>
             {
                 self.show_confirmation(
-                    "Are you sure you want to update this record?".to_string(),
+                    t("records.update.confirm").to_string(),
                     ConfirmAction::UpdateRecord,
                 );
             }
+            KeyCode::Char('z') | KeyCode::Char('Z')
+                if key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.undo_edit();
+            }
+            KeyCode::Char('y') | KeyCode::Char('Y')
+                if key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                self.redo_edit();
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') if self.history.has_changes() => {
+                self.show_confirmation(
+                    t("records.update.confirm_discard").to_string(),
+                    ConfirmAction::DiscardChanges,
+                );
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                toast::push(Toast::error(t("common.nothing_to_discard")));
+            }
             KeyCode::Char('e') | KeyCode::Char('E') => {
                 self.editing = true;
             }
+            KeyCode::Esc if self.history.has_changes() => {
+                self.discard_prompt = Some(DiscardPrompt::new());
+            }
             KeyCode::Esc => {
                 self.back_to_selection();
                 return Ok(None);
@@ -436,48 +601,6 @@ impl UpdateRecord {
 
         Ok(None)
     }
-
-    fn clear_error(&mut self) {
-        self.error_message = None;
-        self.error_timer = None;
-    }
-
-    fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_timer = Some(Instant::now());
-    }
-
-    fn clear_success(&mut self) {
-        self.success_message = None;
-        self.success_timer = None;
-    }
-
-    fn check_success_timeout(&mut self) {
-        if let Some(timer) = self.success_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_success();
-            }
-        }
-    }
-
-    fn check_error_timeout(&mut self) {
-        if let Some(timer) = self.error_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_error();
-            }
-        }
-    }
-
-    fn check_timeouts(&mut self) {
-        self.check_error_timeout();
-        self.check_success_timeout();
-    }
-}
-
-impl Default for UpdateRecord {
-    fn default() -> Self {
-        Self::new()
-    }
 }
 
 impl Component for UpdateRecord {
@@ -491,7 +614,7 @@ impl Component for UpdateRecord {
     fn render(&self, frame: &mut Frame) {
         let area = frame.area();
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             area,
         );
 
@@ -503,6 +626,15 @@ impl Component for UpdateRecord {
         if self.show_confirmation {
             self.render_confirmation_dialog(frame, area);
         }
+        if let Some(prompt) = &self.discard_prompt {
+            prompt.render_popup(frame);
+        }
+        if let Some(alert) = &self.prescribing_alert {
+            alert.render_popup(frame);
+        }
+        if let Some(picker) = &self.procedure_picker {
+            picker.render_popup(frame);
+        }
     }
 }
 
@@ -523,16 +655,16 @@ impl UpdateRecord {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, main_layout[0]);
 
-        let title = Paragraph::new("✍️  SELECT RECORD TO UPDATE")
+        let title = Paragraph::new(t("records.update.title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, main_layout[0]);
@@ -541,24 +673,20 @@ impl UpdateRecord {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Records ",
+                format!(" {} ", t("common.search_records")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
-        let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
+        let search_paragraph = Paragraph::new(self.search_input.line(self.is_searching))
+            .style(Style::default().fg(theme().text).bg(theme().input))
             .block(search_block);
         frame.render_widget(search_paragraph, main_layout[1]);
 
@@ -566,78 +694,79 @@ impl UpdateRecord {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Record ID ",
+                format!(" {} ", t("records.update.record_id")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if !self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
         let id_input_paragraph = Paragraph::new(self.record_id_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
+            .style(Style::default().fg(theme().text).bg(theme().input))
             .block(id_input_block);
         frame.render_widget(id_input_paragraph, main_layout[2]);
 
         if self.filtered_records.is_empty() {
             let no_records = Paragraph::new(if self.search_input.is_empty() {
-                "No records found in database"
+                t("common.no_records")
             } else {
-                "No records match your search criteria"
+                t("common.no_record_matches")
             })
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Center);
             frame.render_widget(no_records, main_layout[3]);
         } else {
+            let widths = [
+                Constraint::Percentage(10),
+                Constraint::Percentage(20),
+                Constraint::Percentage(20),
+                Constraint::Percentage(50),
+            ];
+            let columns = column_widths(main_layout[3].inner(Margin::new(1, 1)), &widths, 2, "► ");
             let records_rows: Vec<Row> = self
                 .filtered_records
                 .iter()
                 .map(|r| {
                     let (first_name, last_name) = match self.get_patient(r.patient_id) {
                         Some(patient) => (patient.first_name.clone(), patient.last_name.clone()),
-                        None => ("Unknown".to_string(), "Patient".to_string()),
+                        None => (t("common.unknown_patient_name").to_string(), String::new()),
                     };
 
                     Row::new(vec![
-                        r.id.to_string(),
-                        first_name,
-                        last_name,
-                        r.diagnosis.clone(),
+                        cell(r.id.to_string(), &columns, 0),
+                        cell(first_name, &columns, 1),
+                        cell(last_name, &columns, 2),
+                        cell(&r.diagnosis, &columns, 3),
                     ])
-                    .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                    .style(Style::default().fg(theme().text))
                     .height(1)
                     .bottom_margin(0)
                 })
                 .collect();
 
             let selected_style = Style::default()
-                .fg(Color::Rgb(250, 250, 110))
-                .bg(Color::Rgb(40, 40, 60))
+                .fg(theme().focus)
+                .bg(theme().highlight)
                 .add_modifier(Modifier::BOLD);
 
-            let header = Row::new(vec!["ID", "First Name", "Last Name", "Diagnosis"])
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(220, 220, 240))
-                        .bg(Color::Rgb(80, 60, 130))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .height(1);
-
-            let widths = [
-                Constraint::Percentage(10),
-                Constraint::Percentage(20),
-                Constraint::Percentage(20),
-                Constraint::Percentage(50),
-            ];
+            let header = Row::new(vec![
+                t("common.id"),
+                t("common.first_name"),
+                t("common.last_name"),
+                t("history.diagnosis"),
+            ])
+            .style(
+                Style::default()
+                    .fg(theme().text)
+                    .bg(theme().header)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1);
 
             let records_table = Table::new(records_rows, widths)
                 .header(header)
@@ -645,9 +774,15 @@ impl UpdateRecord {
                     Block::default()
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .title(format!(" Records ({}) ", self.filtered_records.len()))
-                        .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-                        .style(Style::default().bg(Color::Rgb(26, 26, 36))),
+                        .title(format!(
+                            " {} ",
+                            tf(
+                                "common.records_count",
+                                &[("total", &self.filtered_records.len())]
+                            )
+                        ))
+                        .border_style(Style::default().fg(theme().unfocused))
+                        .style(Style::default().bg(theme().input)),
                 )
                 .column_spacing(2)
                 .row_highlight_style(selected_style)
@@ -660,34 +795,14 @@ impl UpdateRecord {
             );
         }
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(255, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, main_layout[4]);
-        } else if let Some(success) = &self.success_message {
-            let success_paragraph = Paragraph::new(success.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, main_layout[4]);
-        }
-
         let help_text = if self.is_searching {
-            "Type to search | ↓: To results | Esc: Cancel search"
+            t("common.search_help")
         } else {
-            "/ or s: Search | ↑/↓: Navigate records | Enter: Select record | Esc: Back"
+            t("records.update.help")
         };
 
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, main_layout[5]);
     }
@@ -707,83 +822,83 @@ impl UpdateRecord {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, main_layout[0]);
 
-        let title_text = if self.editing {
-            "✍️  EDITING RECORD"
-        } else {
-            "✍️  UPDATE RECORD"
+        let title_text = match (self.editing, self.history.has_changes()) {
+            (true, _) => t("records.update.editing_title"),
+            (false, true) => t("records.update.unsaved_title"),
+            (false, false) => t("records.update.update_title"),
         };
 
         let title = Paragraph::new(title_text)
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, main_layout[0]);
+        edit_warning::render(
+            frame,
+            Rect {
+                y: main_layout[0].y + 1,
+                height: 1,
+                ..main_layout[0]
+            },
+            self.edit_lock.as_ref(),
+        );
 
         let id_str = self.record.id.to_string();
         let patient_id_str = self.record.patient_id.to_string();
         let nurse_notes_str = self.record.nurse_notes.clone().unwrap_or_default();
         let prescription_str = self.record.prescription.clone().unwrap_or_default();
+        let procedures_str = self.record.procedures.clone().unwrap_or_default();
 
-        let table_items = vec![
-            Row::new(vec!["ID", &id_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Patient ID", &patient_id_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Doctor's Notes", &self.record.doctor_notes])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Nurse's Notes", &nurse_notes_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Diagnosis", &self.record.diagnosis])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Prescription", &prescription_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+        let widths = [Constraint::Percentage(30), Constraint::Percentage(70)];
+        let columns = column_widths(main_layout[1].inner(Margin::new(1, 1)), &widths, 2, "► ");
+        let field = |label: &str, value: &str| {
+            Row::new(vec![cell(label, &columns, 0), cell(value, &columns, 1)])
+                .style(Style::default().fg(theme().text))
                 .height(1)
-                .bottom_margin(0),
+                .bottom_margin(0)
+        };
+
+        let table_items = vec![
+            field(t("common.id"), &id_str),
+            field(t("common.patient_id"), &patient_id_str),
+            field(t("history.doctor_notes"), &self.record.doctor_notes),
+            field(t("history.nurse_notes"), &nurse_notes_str),
+            field(t("history.diagnosis"), &self.record.diagnosis),
+            field(t("history.prescription"), &prescription_str),
+            field(t("store.procedure_codes"), &procedures_str),
         ];
 
         let selected_style = Style::default()
-            .fg(Color::Rgb(250, 250, 110))
-            .bg(Color::Rgb(40, 40, 60))
+            .fg(theme().focus)
+            .bg(theme().highlight)
             .add_modifier(Modifier::BOLD);
 
-        let header = Row::new(vec!["Field", "Value"])
+        let header = Row::new(vec![t("common.field"), t("common.value")])
             .style(
                 Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(80, 60, 130))
+                    .fg(theme().text)
+                    .bg(theme().header)
                     .add_modifier(Modifier::BOLD),
             )
             .height(1);
 
-        let widths = [Constraint::Percentage(30), Constraint::Percentage(70)];
-
         let table = Table::new(table_items, widths)
             .header(header)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
-                    .title(" Record Data ")
-                    .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
+                    .title(format!(" {} ", t("records.update.record_data")))
+                    .border_style(Style::default().fg(theme().unfocused))
+                    .style(Style::default().bg(theme().input)),
             )
             .column_spacing(2)
             .row_highlight_style(selected_style)
@@ -792,67 +907,49 @@ impl UpdateRecord {
         frame.render_stateful_widget(table, main_layout[1], &mut self.edit_table_state.clone());
 
         let input_label = match self.selected_field {
-            Some(ID_INPUT) => "ID",
-            Some(PATIENT_ID_INPUT) => "Patient ID",
-            Some(DOCTOR_NOTES_INPUT) => "Doctor's Notes",
-            Some(NURSE_NOTES_INPUT) => "Nurse's Notes",
-            Some(DIAGNOSIS_INPUT) => "Diagnosis",
-            Some(PRESCRIPTION_INPUT) => "Prescription",
-            _ => "Field",
+            Some(ID_INPUT) => t("common.id"),
+            Some(PATIENT_ID_INPUT) => t("common.patient_id"),
+            Some(DOCTOR_NOTES_INPUT) => t("history.doctor_notes"),
+            Some(NURSE_NOTES_INPUT) => t("history.nurse_notes"),
+            Some(DIAGNOSIS_INPUT) => t("history.diagnosis"),
+            Some(PRESCRIPTION_INPUT) => t("history.prescription"),
+            Some(PROCEDURES_INPUT) => t("store.procedure_codes"),
+            _ => t("common.field"),
         };
 
         let input_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(format!(
-                " {} {} ",
-                if self.editing { "Editing" } else { "Selected" },
-                input_label
+                " {} ",
+                if self.editing {
+                    tf("common.editing_field", &[("field", &input_label)])
+                } else {
+                    tf("common.selected_field", &[("field", &input_label)])
+                }
             ))
             .border_style(if self.editing {
-                Style::default().fg(Color::Rgb(140, 219, 140))
+                Style::default().fg(theme().success)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
         let input_paragraph = Paragraph::new(self.input_value.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
+            .style(Style::default().fg(theme().text).bg(theme().input))
             .block(input_block);
         frame.render_widget(input_paragraph, main_layout[2]);
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(255, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, main_layout[3]);
-        } else if let Some(success) = &self.success_message {
-            let success_paragraph = Paragraph::new(success.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, main_layout[3]);
-        }
-
-        let help_text = if self.editing {
-            "Enter: Save Changes | Esc: Cancel Editing"
+        let help_text = if self.editing && self.selected_field == Some(PROCEDURES_INPUT) {
+            t("records.update.edit_help_procedures")
+        } else if self.editing {
+            t("common.edit_help")
         } else {
-            "↑/↓: Navigate | E: Edit Field | Ctrl+S: Save Record | Esc: Back"
+            t("records.update.view_help")
         };
 
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, main_layout[4]);
     }
@@ -870,16 +967,16 @@ impl UpdateRecord {
         frame.render_widget(Clear, dialog_area);
 
         let dialog_block = Block::default()
-            .title(" Update Record ")
+            .title(format!(" {} ", t("records.update.dialog_title")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-            .style(Style::default().bg(Color::Rgb(30, 30, 46)));
+            .border_style(Style::default().fg(theme().unfocused))
+            .style(Style::default().bg(theme().dialog));
 
         frame.render_widget(dialog_block.clone(), dialog_area);
 
@@ -891,7 +988,7 @@ impl UpdateRecord {
             .split(inner_area);
 
         let message = Paragraph::new(self.confirmation_message.as_str())
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .add_modifier(Modifier::BOLD)
             .alignment(Alignment::Center);
         frame.render_widget(message, content_layout[0]);
@@ -903,28 +1000,28 @@ impl UpdateRecord {
 
         let yes_style = if self.confirmation_selected == 0 {
             Style::default()
-                .fg(Color::Rgb(140, 219, 140))
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
         let no_style = if self.confirmation_selected == 1 {
             Style::default()
-                .fg(Color::Rgb(255, 100, 100))
+                .fg(theme().error)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let yes_text = if self.confirmation_selected == 0 {
-            "► Yes ◄"
+            format!("► {} ◄", t("common.yes"))
         } else {
-            "  Yes  "
+            format!("  {}  ", t("common.yes"))
         };
         let no_text = if self.confirmation_selected == 1 {
-            "► No ◄"
+            format!("► {} ◄", t("common.no"))
         } else {
-            "  No  "
+            format!("  {}  ", t("common.no"))
         };
 
         let yes_button = Paragraph::new(yes_text)
@@ -0,0 +1,526 @@
+//! Door-to-doctor times and queue lengths for the outpatient queue over a
+//! month, from when each token was issued and called.
+//!
+//! A token's wait runs from issue to call; skipped tokens count, as they
+//! were called. Tokens never called are left out of the waits and count as
+//! waiting for the rest of their day. Days over `wait_target` or
+//! `queue_target` are highlighted in the charts.
+
+use crate::app::SelectedApp;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::QueueToken;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{config, datetime};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::{BTreeMap, HashMap};
+use time::{Date, Duration};
+
+/// The nearest-rank `percent`th percentile of `sorted`.
+fn percentile(sorted: &[i64], percent: usize) -> Option<i64> {
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+fn minutes(value: Option<i64>) -> String {
+    value.map_or("-".to_string(), |m| m.to_string())
+}
+
+#[derive(Debug, Clone, Default)]
+struct WaitGroup {
+    name: String,
+    /// Minutes from issue to call, shortest first.
+    waits: Vec<i64>,
+    never_called: usize,
+}
+
+impl WaitGroup {
+    fn over(&self, target: u64) -> usize {
+        if target == 0 {
+            return 0;
+        }
+        self.waits.iter().filter(|w| **w > target as i64).count()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Day {
+    date: Date,
+    median: Option<i64>,
+    peak: usize,
+}
+
+pub struct WaitingReport {
+    db: Db,
+    tokens: Vec<QueueToken>,
+    doctors: HashMap<i64, String>,
+    /// First day of the month shown.
+    month: Date,
+    groups: Vec<WaitGroup>,
+    overall: WaitGroup,
+    days: Vec<Day>,
+    state: TableState,
+}
+
+impl WaitingReport {
+    pub fn new(db: &Db) -> Self {
+        let today = datetime::today();
+        Self {
+            db: db.clone(),
+            tokens: Vec::new(),
+            doctors: HashMap::new(),
+            month: today.replace_day(1).unwrap_or(today),
+            groups: Vec::new(),
+            overall: WaitGroup::default(),
+            days: Vec::new(),
+            state: TableState::default(),
+        }
+    }
+
+    /// The last day shown: the end of the month, or today in this one.
+    fn last_day(&self) -> Date {
+        let next = (self.month + Duration::days(31))
+            .replace_day(1)
+            .unwrap_or(self.month);
+        (next - Duration::days(1)).min(datetime::today().max(self.month))
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.tokens = db::get_queue_tokens_between(
+            &self.db,
+            &datetime::format_date(self.month),
+            &datetime::format_date(self.last_day()),
+        )?;
+        self.doctors = db::get_all_staff(&self.db)?
+            .into_iter()
+            .map(|s| (s.id, s.name))
+            .collect();
+        self.aggregate();
+        Ok(())
+    }
+
+    fn aggregate(&mut self) {
+        let mut groups: BTreeMap<String, WaitGroup> = BTreeMap::new();
+        let mut overall = WaitGroup {
+            name: t("waiting.all_doctors").to_string(),
+            ..WaitGroup::default()
+        };
+        // Waits, and the times patients joined (true) and left the queue,
+        // by queue date.
+        let mut day_waits: BTreeMap<&str, Vec<i64>> = BTreeMap::new();
+        let mut day_events: BTreeMap<&str, Vec<(&str, bool)>> = BTreeMap::new();
+        for token in &self.tokens {
+            let name = self
+                .doctors
+                .get(&token.doctor_id)
+                .cloned()
+                .unwrap_or_else(|| tf("waiting.doctor_fallback", &[("id", &token.doctor_id)]));
+            let group = groups.entry(name.clone()).or_insert_with(|| WaitGroup {
+                name,
+                ..WaitGroup::default()
+            });
+            let events = day_events.entry(&token.queue_date).or_default();
+            events.push((token.issued_at.as_str(), true));
+            let wait = token.called_at.as_deref().and_then(|called_at| {
+                events.push((called_at, false));
+                let called = datetime::parse_stamp(called_at)?;
+                let issued = datetime::parse_stamp(&token.issued_at)?;
+                Some((called - issued).whole_minutes().max(0))
+            });
+            match wait {
+                Some(wait) => {
+                    group.waits.push(wait);
+                    overall.waits.push(wait);
+                    day_waits.entry(&token.queue_date).or_default().push(wait);
+                }
+                None if token.called_at.is_none() => {
+                    group.never_called += 1;
+                    overall.never_called += 1;
+                }
+                None => {}
+            }
+        }
+        for group in groups.values_mut() {
+            group.waits.sort_unstable();
+        }
+        overall.waits.sort_unstable();
+
+        let mut days = Vec::new();
+        let mut date = self.month;
+        while date <= self.last_day() {
+            let key = datetime::format_date(date);
+            let median = day_waits.get_mut(key.as_str()).and_then(|waits| {
+                waits.sort_unstable();
+                percentile(waits, 50)
+            });
+            let peak = match day_events.get_mut(key.as_str()) {
+                Some(events) => {
+                    // Calls go before issues at the same moment, so a
+                    // patient called straight away never adds to the queue.
+                    events.sort_by(|a, b| a.0.cmp(b.0).then(a.1.cmp(&b.1)));
+                    let mut waiting = 0usize;
+                    let mut peak = 0;
+                    for (_, issued) in events.iter() {
+                        if *issued {
+                            waiting += 1;
+                            peak = peak.max(waiting);
+                        } else {
+                            waiting = waiting.saturating_sub(1);
+                        }
+                    }
+                    peak
+                }
+                None => 0,
+            };
+            days.push(Day { date, median, peak });
+            date += Duration::days(1);
+        }
+
+        self.groups = groups.into_values().collect();
+        self.overall = overall;
+        self.days = days;
+        self.state.select((!self.groups.is_empty()).then_some(0));
+    }
+
+    fn change_month(&mut self, forward: bool) {
+        let moved = if forward {
+            self.month + Duration::days(31)
+        } else {
+            self.month - Duration::days(1)
+        };
+        self.month = moved.replace_day(1).unwrap_or(moved);
+        if let Err(e) = self.load_data() {
+            toast::push(Toast::error(tf("waiting.load_failed", &[("error", &e)])));
+        }
+    }
+
+    fn summary(&self) -> Line<'static> {
+        let config = config::config();
+        let waits = &self.overall.waits;
+        let mut spans = vec![
+            Span::styled(
+                tf("waiting.called_count", &[("count", &waits.len())]),
+                Style::default()
+                    .fg(theme().text)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("  |  ", Style::default().fg(theme().inactive)),
+            Span::styled(
+                tf(
+                    "waiting.percentiles",
+                    &[
+                        ("median", &(minutes(percentile(waits, 50)))),
+                        ("p90", &(minutes(percentile(waits, 90)))),
+                        ("p95", &(minutes(percentile(waits, 95)))),
+                    ],
+                ),
+                Style::default().fg(theme().accent),
+            ),
+        ];
+        if config.wait_target > 0 {
+            let breaches = self
+                .days
+                .iter()
+                .filter(|d| d.median.is_some_and(|m| m > config.wait_target as i64))
+                .count();
+            spans.push(Span::styled("  |  ", Style::default().fg(theme().inactive)));
+            spans.push(Span::styled(
+                tf(
+                    "waiting.over_target",
+                    &[("days", &breaches), ("target", &config.wait_target)],
+                ),
+                Style::default().fg(if breaches > 0 {
+                    theme().error
+                } else {
+                    theme().success
+                }),
+            ));
+        }
+        if self.overall.never_called > 0 {
+            spans.push(Span::styled("  |  ", Style::default().fg(theme().inactive)));
+            spans.push(Span::styled(
+                tf(
+                    "waiting.never_called",
+                    &[("count", &self.overall.never_called)],
+                ),
+                Style::default().fg(theme().warning),
+            ));
+        }
+        Line::from(spans)
+    }
+
+    fn render_table(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("waiting.door_to_doctor")))
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.groups.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("waiting.empty"))
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        }
+        let target = config::config().wait_target;
+        let widths = [
+            Constraint::Min(14),
+            Constraint::Length(6),
+            Constraint::Length(7),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(8),
+            Constraint::Length(7),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+        let row = |g: &WaitGroup| {
+            Row::new(vec![
+                cell(g.name.as_str(), &columns, 0),
+                cell(g.waits.len().to_string(), &columns, 1),
+                cell(minutes(percentile(&g.waits, 50)), &columns, 2),
+                cell(minutes(percentile(&g.waits, 90)), &columns, 3),
+                cell(minutes(percentile(&g.waits, 95)), &columns, 4),
+                cell(minutes(g.waits.last().copied()), &columns, 5),
+                cell(
+                    if target > 0 {
+                        g.over(target).to_string()
+                    } else {
+                        "-".to_string()
+                    },
+                    &columns,
+                    6,
+                ),
+            ])
+        };
+        let mut rows: Vec<Row> = self
+            .groups
+            .iter()
+            .map(|g| {
+                let over =
+                    percentile(&g.waits, 50).is_some_and(|m| target > 0 && m > target as i64);
+                row(g).style(Style::default().fg(if over { theme().error } else { theme().text }))
+            })
+            .collect();
+        rows.push(
+            row(&self.overall).style(
+                Style::default()
+                    .fg(theme().accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        );
+        let header = Row::new(vec![
+            t("kiosk.doctor"),
+            t("waiting.called"),
+            t("waiting.median"),
+            "90th",
+            "95th",
+            t("waiting.longest"),
+            t("waiting.over"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .row_highlight_style(Style::default().bg(theme().highlight))
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.state.clone());
+    }
+
+    /// One bar per day, in red on days over `target` when it is set.
+    fn render_chart(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: String,
+        target: u64,
+        value: impl Fn(&Day) -> Option<u64>,
+    ) {
+        let count = self.days.len().max(1) as u16;
+        let bar_width = ((area.width.saturating_sub(2) + 1) / count)
+            .saturating_sub(1)
+            .max(1);
+        let bars: Vec<Bar> = self
+            .days
+            .iter()
+            .map(|day| {
+                let v = value(day);
+                let color = if target > 0 && v.is_some_and(|v| v > target) {
+                    theme().error
+                } else {
+                    theme().accent
+                };
+                let label = if bar_width >= 2 {
+                    day.date.day().to_string()
+                } else {
+                    String::new()
+                };
+                Bar::default()
+                    .label(Line::from(label))
+                    .value(v.unwrap_or(0))
+                    .text_value(v.map_or(String::new(), |v| v.to_string()))
+                    .style(Style::default().fg(color))
+                    .value_style(Style::default().fg(theme().background).bg(color))
+            })
+            .collect();
+        let chart = BarChart::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(title)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .label_style(Style::default().fg(theme().text))
+            .data(BarGroup::default().bars(&bars));
+        frame.render_widget(chart, area);
+    }
+}
+
+impl Component for WaitingReport {
+    fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        let len = self.groups.len() + 1;
+        match key.code {
+            KeyCode::Down => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Left => self.change_month(false),
+            KeyCode::Right => self.change_month(true),
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                if let Err(e) = self.load_data() {
+                    toast::push(Toast::error(tf("waiting.load_failed", &[("error", &e)])));
+                }
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(10),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("waiting.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(tf(
+                "waiting.month",
+                &[("month", &datetime::format_month(self.month))],
+            ))
+            .style(Style::default().fg(theme().accent))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("stays.period")))
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            ),
+            layout[1],
+        );
+        frame.render_widget(
+            Paragraph::new(self.summary()).alignment(Alignment::Center),
+            layout[2],
+        );
+
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(layout[3]);
+        self.render_table(frame, body[0]);
+        let charts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(body[1]);
+        let config = config::config();
+        let target_text = |target: u64, unit: &str| {
+            if target > 0 {
+                tf("waiting.target", &[("target", &target), ("unit", &unit)])
+            } else {
+                String::new()
+            }
+        };
+        self.render_chart(
+            frame,
+            charts[0],
+            format!(
+                " {} ",
+                tf(
+                    "waiting.median_chart",
+                    &[("target", &(target_text(config.wait_target, " min")))]
+                )
+            ),
+            config.wait_target,
+            |day| day.median.map(|m| m as u64),
+        );
+        self.render_chart(
+            frame,
+            charts[1],
+            format!(
+                " {} ",
+                tf(
+                    "waiting.longest_chart",
+                    &[("target", &(target_text(config.queue_target, "")))]
+                )
+            ),
+            config.queue_target,
+            |day| (day.peak > 0).then_some(day.peak as u64),
+        );
+
+        frame.render_widget(
+            Paragraph::new(t("waiting.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+    }
+}
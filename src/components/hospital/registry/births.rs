@@ -0,0 +1,582 @@
+use crate::app::SelectedApp;
+use crate::components::hospital::registry::certificate::{birth_certificate, births_report_csv};
+use crate::components::hospital::registry::{render_form_fields, render_prompt};
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::print_dialog::PrintDialog;
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{BirthRecord, Gender, Patient, Pregnancy, StaffMember, StaffRole};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime;
+use crate::utils::write_export;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BirthMode {
+    Viewing,
+    Adding,
+    ExportPrompt,
+}
+
+const FORM_FIELDS: usize = 7;
+
+pub struct BirthRegistry {
+    db: Db,
+    records: Vec<BirthRecord>,
+    patients: HashMap<i64, Patient>,
+    staff: HashMap<i64, StaffMember>,
+    /// Open pregnancies by mother; registering the birth closes them.
+    pregnancies: HashMap<i64, Pregnancy>,
+    table_state: TableState,
+    mode: BirthMode,
+    form: [String; FORM_FIELDS],
+    sex: Gender,
+    focus_index: usize,
+    export_period: String,
+    print_dialog: Option<PrintDialog>,
+}
+
+impl BirthRegistry {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            records: Vec::new(),
+            patients: HashMap::new(),
+            staff: HashMap::new(),
+            pregnancies: HashMap::new(),
+            table_state: TableState::default(),
+            mode: BirthMode::Viewing,
+            form: Default::default(),
+            sex: Gender::Female,
+            focus_index: 0,
+            export_period: String::new(),
+            print_dialog: None,
+        }
+    }
+
+    pub fn fetch_records(&mut self) -> Result<()> {
+        self.records = db::get_all_birth_records(&self.db)?;
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        self.staff = db::get_all_staff(&self.db)?
+            .into_iter()
+            .map(|s| (s.id, s))
+            .collect();
+        self.pregnancies = HashMap::new();
+        for pregnancy in db::get_all_pregnancies(&self.db)? {
+            if pregnancy.is_open() {
+                self.pregnancies
+                    .entry(pregnancy.patient_id)
+                    .or_insert(pregnancy);
+            }
+        }
+        if self.records.is_empty() {
+            self.table_state.select(None);
+        } else {
+            let selected = self.table_state.selected().unwrap_or(0);
+            self.table_state
+                .select(Some(selected.min(self.records.len() - 1)));
+        }
+        Ok(())
+    }
+
+    fn selected_record(&self) -> Option<&BirthRecord> {
+        self.table_state
+            .selected()
+            .and_then(|i| self.records.get(i))
+    }
+
+    fn resolve_patient(&self, input: &str) -> Option<&Patient> {
+        input
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|id| self.patients.get(&id))
+    }
+
+    fn resolve_doctor(&self, input: &str) -> Option<&StaffMember> {
+        input
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|id| self.staff.get(&id))
+            .filter(|s| s.role == StaffRole::Doctor)
+    }
+
+    fn start_adding(&mut self) {
+        self.form = Default::default();
+        self.form[3] = datetime::format_date_time(datetime::local_now());
+        self.sex = Gender::Female;
+        self.focus_index = 0;
+        self.mode = BirthMode::Adding;
+    }
+
+    fn save(&mut self) -> Result<()> {
+        let Some(mother) = self.resolve_patient(&self.form[0]).cloned() else {
+            toast::push(Toast::error(t("births.no_mother")));
+            return Ok(());
+        };
+        if self.form[1].trim().is_empty() {
+            toast::push(Toast::error(t("births.no_name")));
+            return Ok(());
+        }
+        if datetime::parse_date_time(&self.form[3]).is_none() {
+            toast::push(Toast::error(t("births.bad_time")));
+            return Ok(());
+        }
+        let weight_grams = if self.form[4].trim().is_empty() {
+            None
+        } else {
+            match self.form[4].trim().parse::<i32>() {
+                Ok(w) if w > 0 => Some(w),
+                _ => {
+                    toast::push(Toast::error(t("births.bad_weight")));
+                    return Ok(());
+                }
+            }
+        };
+        let Some(doctor) = self.resolve_doctor(&self.form[5]).cloned() else {
+            toast::push(Toast::error(t("births.no_doctor")));
+            return Ok(());
+        };
+
+        let record = BirthRecord {
+            id: 0,
+            mother_id: mother.id,
+            newborn_name: self.form[1].trim().to_string(),
+            sex: self.sex.clone(),
+            birth_time: self.form[3].trim().to_string(),
+            weight_grams,
+            delivering_doctor_id: doctor.id,
+            notes: if self.form[6].trim().is_empty() {
+                None
+            } else {
+                Some(self.form[6].trim().to_string())
+            },
+        };
+
+        let closed = match self.pregnancies.get(&mother.id) {
+            Some(pregnancy) => format!(
+                " {}",
+                tf("births.pregnancy_closed", &[("edd", &pregnancy.edd)])
+            ),
+            None => String::new(),
+        };
+        match db::create_birth_record(&self.db, &record) {
+            Ok(id) => {
+                self.mode = BirthMode::Viewing;
+                self.fetch_records()?;
+                if let Some(pos) = self.records.iter().position(|r| r.id == id) {
+                    self.table_state.select(Some(pos));
+                }
+                toast::push(Toast::success(tf(
+                    "births.registered",
+                    &[("id", &format!("{:06}", id)), ("closed", &closed)],
+                )));
+            }
+            Err(e) => toast::push(Toast::error(tf("common.database_error", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    fn export_certificate(&mut self) {
+        let Some(record) = self.selected_record() else {
+            toast::push(Toast::error(t("births.none_selected")));
+            return;
+        };
+        let contents = birth_certificate(record, &self.patients, &self.staff);
+        let file_name = format!("birth_certificate_B-{:06}.txt", record.id);
+        match write_export(&file_name, &contents) {
+            Ok(path) => toast::push(Toast::success(tf(
+                "births.certificate_written",
+                &[("path", &path.display())],
+            ))),
+            Err(e) => toast::push(Toast::error(format!("{}", e))),
+        }
+    }
+
+    fn print_certificate(&mut self) {
+        let Some(record) = self.selected_record() else {
+            toast::push(Toast::error(t("births.none_selected")));
+            return;
+        };
+        let contents = birth_certificate(record, &self.patients, &self.staff);
+        self.print_dialog = Some(PrintDialog::new(
+            &self.db,
+            tf(
+                "births.print_title",
+                &[("id", &format!("{:06}", record.id))],
+            ),
+            contents,
+        ));
+    }
+
+    fn export_report(&mut self, period: &str) {
+        let records: Vec<&BirthRecord> = self
+            .records
+            .iter()
+            .filter(|r| r.birth_time.starts_with(period))
+            .collect();
+        if records.is_empty() {
+            toast::push(Toast::error(tf(
+                "births.none_in_period",
+                &[("period", &period)],
+            )));
+            return;
+        }
+        let contents = births_report_csv(&records, &self.patients, &self.staff);
+        let suffix = if period.is_empty() { "all" } else { period };
+        match write_export(&format!("births_report_{}.csv", suffix), &contents) {
+            Ok(path) => toast::push(Toast::success(tf(
+                "births.exported",
+                &[("count", &records.len()), ("path", &path.display())],
+            ))),
+            Err(e) => toast::push(Toast::error(format!("{}", e))),
+        }
+    }
+
+    fn handle_form_input(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Char(c) if self.focus_index == 2 => match c.to_ascii_lowercase() {
+                'f' => self.sex = Gender::Female,
+                'm' => self.sex = Gender::Male,
+                'o' => self.sex = Gender::Other,
+                _ => {}
+            },
+            KeyCode::Char(c) if self.focus_index < FORM_FIELDS => {
+                self.form[self.focus_index].push(c);
+            }
+            KeyCode::Backspace if self.focus_index < FORM_FIELDS => {
+                self.form[self.focus_index].pop();
+            }
+            KeyCode::Tab | KeyCode::Down => {
+                self.focus_index = (self.focus_index + 1) % (FORM_FIELDS + 2);
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                self.focus_index = (self.focus_index + FORM_FIELDS + 1) % (FORM_FIELDS + 2);
+            }
+            KeyCode::Enter => match self.focus_index {
+                i if i < FORM_FIELDS => self.focus_index += 1,
+                i if i == FORM_FIELDS => self.save()?,
+                _ => self.mode = BirthMode::Viewing,
+            },
+            KeyCode::Esc => self.mode = BirthMode::Viewing,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(dialog) = self.print_dialog.as_mut() {
+            if dialog.handle_key(key) {
+                self.print_dialog = None;
+            }
+            return Ok(None);
+        }
+        match self.mode {
+            BirthMode::Adding => {
+                self.handle_form_input(key)?;
+                return Ok(None);
+            }
+            BirthMode::ExportPrompt => {
+                match key.code {
+                    KeyCode::Char(c) => self.export_period.push(c),
+                    KeyCode::Backspace => {
+                        self.export_period.pop();
+                    }
+                    KeyCode::Enter => {
+                        let period = self.export_period.trim().to_string();
+                        self.mode = BirthMode::Viewing;
+                        self.export_report(&period);
+                    }
+                    KeyCode::Esc => self.mode = BirthMode::Viewing,
+                    _ => {}
+                }
+                return Ok(None);
+            }
+            BirthMode::Viewing => {}
+        }
+
+        match key.code {
+            KeyCode::Down if !self.records.is_empty() => {
+                let i = self
+                    .table_state
+                    .selected()
+                    .map_or(0, |i| (i + 1) % self.records.len());
+                self.table_state.select(Some(i));
+            }
+            KeyCode::Up if !self.records.is_empty() => {
+                let len = self.records.len();
+                let i = self
+                    .table_state
+                    .selected()
+                    .map_or(0, |i| (i + len - 1) % len);
+                self.table_state.select(Some(i));
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => self.start_adding(),
+            KeyCode::Char('c') | KeyCode::Char('C') => self.export_certificate(),
+            KeyCode::Char('p') | KeyCode::Char('P') => self.print_certificate(),
+            KeyCode::Char('e') | KeyCode::Char('E') => {
+                self.export_period = datetime::format_month(datetime::today());
+                self.mode = BirthMode::ExportPrompt;
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => self.fetch_records()?,
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_list(&self, frame: &mut Frame, area: Rect) {
+        let header = Row::new(vec![
+            t("births.reg_no"),
+            t("births.newborn"),
+            t("statistics.sex"),
+            t("births.born"),
+            t("births.weight"),
+            t("births.mother"),
+            t("births.delivered_by"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let widths = [
+            Constraint::Length(10),
+            Constraint::Percentage(20),
+            Constraint::Length(7),
+            Constraint::Length(17),
+            Constraint::Length(8),
+            Constraint::Percentage(20),
+            Constraint::Min(15),
+        ];
+        let columns = column_widths(area.inner(Margin::new(1, 1)), &widths, 1, "► ");
+        let rows = self.records.iter().map(|r| {
+            Row::new(vec![
+                cell(format!("B-{:06}", r.id), &columns, 0),
+                cell(&r.newborn_name, &columns, 1),
+                cell(r.sex.label(), &columns, 2),
+                cell(&r.birth_time, &columns, 3),
+                cell(
+                    r.weight_grams
+                        .map(|w| format!("{} g", w))
+                        .unwrap_or_else(|| "-".to_string()),
+                    &columns,
+                    4,
+                ),
+                cell(
+                    self.patients
+                        .get(&r.mother_id)
+                        .map(|p| format!("{} {}", p.first_name, p.last_name))
+                        .unwrap_or_else(|| format!("#{}", r.mother_id)),
+                    &columns,
+                    5,
+                ),
+                cell(
+                    self.staff
+                        .get(&r.delivering_doctor_id)
+                        .map(|s| s.name.clone())
+                        .unwrap_or_else(|| format!("#{}", r.delivering_doctor_id)),
+                    &columns,
+                    6,
+                ),
+            ])
+            .style(Style::default().fg(theme().text))
+        });
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(
+                        " {} ",
+                        tf("births.registered_title", &[("count", &self.records.len())])
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.table_state.clone());
+    }
+
+    fn render_form(&self, frame: &mut Frame, area: Rect) {
+        let mother = self
+            .resolve_patient(&self.form[0])
+            .map(|p| match self.pregnancies.get(&p.id) {
+                Some(pregnancy) => format!(
+                    " {}",
+                    tf(
+                        "births.mother_due",
+                        &[
+                            ("first", &p.first_name),
+                            ("last", &p.last_name),
+                            ("edd", &pregnancy.edd)
+                        ]
+                    )
+                ),
+                None => format!(" → {} {}", p.first_name, p.last_name),
+            })
+            .unwrap_or_default();
+        let doctor = self
+            .resolve_doctor(&self.form[5])
+            .map(|d| format!(" {}", tf("births.doctor_name", &[("name", &d.name)])))
+            .unwrap_or_default();
+        let fields = vec![
+            (
+                tf("births.mother_field", &[("mother", &mother)]),
+                self.form[0].clone(),
+            ),
+            (t("births.name_field").to_string(), self.form[1].clone()),
+            (
+                t("births.sex_field").to_string(),
+                self.sex.label().to_string(),
+            ),
+            (t("births.time_field").to_string(), self.form[3].clone()),
+            (t("births.weight_field").to_string(), self.form[4].clone()),
+            (
+                tf("births.doctor_field", &[("doctor", &doctor)]),
+                self.form[5].clone(),
+            ),
+            (t("campaign.notes").to_string(), self.form[6].clone()),
+        ];
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3 * FORM_FIELDS as u16),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(area);
+        render_form_fields(frame, chunks[0], &fields, self.focus_index);
+
+        let buttons = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[2]);
+        let save_style = if self.focus_index == FORM_FIELDS {
+            Style::default()
+                .fg(theme().success)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        let cancel_style = if self.focus_index == FORM_FIELDS + 1 {
+            Style::default()
+                .fg(theme().accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        frame.render_widget(
+            Paragraph::new(if self.focus_index == FORM_FIELDS {
+                format!("► {} ◄", t("births.register"))
+            } else {
+                format!("  {}  ", t("births.register"))
+            })
+            .style(save_style)
+            .alignment(Alignment::Center),
+            buttons[0],
+        );
+        frame.render_widget(
+            Paragraph::new(if self.focus_index == FORM_FIELDS + 1 {
+                format!("► {} ◄", t("common.cancel"))
+            } else {
+                format!("  {}  ", t("common.cancel"))
+            })
+            .style(cancel_style)
+            .alignment(Alignment::Center),
+            buttons[1],
+        );
+    }
+}
+
+impl Component for BirthRegistry {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(10),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(if self.mode == BirthMode::Adding {
+                t("births.register_title")
+            } else {
+                t("births.title")
+            })
+            .style(
+                Style::default()
+                    .fg(theme().title)
+                    .add_modifier(Modifier::BOLD)
+                    .bg(theme().background),
+            )
+            .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        if self.mode == BirthMode::Adding {
+            self.render_form(frame, layout[1]);
+        } else {
+            self.render_list(frame, layout[1]);
+        }
+
+        let help_text = if self.mode == BirthMode::Adding {
+            t("births.form_help")
+        } else {
+            t("births.help")
+        };
+        frame.render_widget(
+            Paragraph::new(help_text)
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+
+        if self.mode == BirthMode::ExportPrompt {
+            render_prompt(
+                frame,
+                t("births.statutory"),
+                t("births.period_prompt"),
+                &self.export_period,
+            );
+        }
+        if let Some(dialog) = &self.print_dialog {
+            dialog.render_popup(frame);
+        }
+    }
+}
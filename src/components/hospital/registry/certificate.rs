@@ -0,0 +1,182 @@
+use crate::models::{BirthRecord, DeathRecord, Patient, StaffMember};
+use crate::utils::csv_line;
+use std::collections::HashMap;
+
+fn patient_name(patients: &HashMap<i64, Patient>, id: i64) -> String {
+    patients
+        .get(&id)
+        .map(|p| format!("{} {}", p.first_name, p.last_name))
+        .unwrap_or_else(|| format!("Unknown patient #{}", id))
+}
+
+fn staff_name(staff: &HashMap<i64, StaffMember>, id: i64) -> String {
+    staff
+        .get(&id)
+        .map(|s| s.name.clone())
+        .unwrap_or_else(|| format!("Unknown staff #{}", id))
+}
+
+pub fn birth_certificate(
+    record: &BirthRecord,
+    patients: &HashMap<i64, Patient>,
+    staff: &HashMap<i64, StaffMember>,
+) -> String {
+    let mother = patients.get(&record.mother_id);
+    [
+        "RUSTORIA HOSPITAL - NOTIFICATION OF BIRTH".to_string(),
+        "=".repeat(42),
+        format!("Registration No.:      B-{:06}", record.id),
+        String::new(),
+        format!("Name of child:         {}", record.newborn_name),
+        format!("Sex:                   {}", record.sex.label()),
+        format!("Date & time of birth:  {}", record.birth_time),
+        format!(
+            "Birth weight:          {}",
+            record
+                .weight_grams
+                .map(|w| format!("{} g", w))
+                .unwrap_or_else(|| "Not recorded".to_string())
+        ),
+        String::new(),
+        format!(
+            "Mother:                {}",
+            patient_name(patients, record.mother_id)
+        ),
+        format!(
+            "Mother's date of birth: {}",
+            mother.map(|m| m.date_of_birth.as_str()).unwrap_or("-")
+        ),
+        format!(
+            "Mother's address:      {}",
+            mother.map_or("-".to_string(), |m| m.full_address())
+        ),
+        String::new(),
+        format!(
+            "Delivered by:          Dr. {}",
+            staff_name(staff, record.delivering_doctor_id)
+        ),
+        format!(
+            "Remarks:               {}",
+            record.notes.as_deref().unwrap_or("-")
+        ),
+        String::new(),
+        "Signature of attending physician: ______________________".to_string(),
+        String::new(),
+    ]
+    .join("\n")
+}
+
+pub fn death_certificate(
+    record: &DeathRecord,
+    patients: &HashMap<i64, Patient>,
+    staff: &HashMap<i64, StaffMember>,
+) -> String {
+    let deceased = patients.get(&record.patient_id);
+    [
+        "RUSTORIA HOSPITAL - MEDICAL CERTIFICATE OF CAUSE OF DEATH".to_string(),
+        "=".repeat(57),
+        format!("Registration No.:      D-{:06}", record.id),
+        String::new(),
+        format!(
+            "Name of deceased:      {}",
+            patient_name(patients, record.patient_id)
+        ),
+        format!(
+            "Sex:                   {}",
+            deceased.map(|p| p.gender.label()).unwrap_or("-")
+        ),
+        format!(
+            "Date of birth:         {}",
+            deceased.map(|p| p.date_of_birth.as_str()).unwrap_or("-")
+        ),
+        format!(
+            "Usual address:         {}",
+            deceased.map_or("-".to_string(), |p| p.full_address())
+        ),
+        String::new(),
+        format!("Date & time of death:  {}", record.time_of_death),
+        format!(
+            "Place of death:        {}",
+            record.place.as_deref().unwrap_or("Rustoria Hospital")
+        ),
+        format!("Cause of death:        {}", record.cause),
+        String::new(),
+        format!(
+            "Certified by:          Dr. {}",
+            staff_name(staff, record.certifying_doctor_id)
+        ),
+        format!(
+            "Remarks:               {}",
+            record.notes.as_deref().unwrap_or("-")
+        ),
+        String::new(),
+        "Signature of certifying physician: ______________________".to_string(),
+        String::new(),
+    ]
+    .join("\n")
+}
+
+pub fn births_report_csv(
+    records: &[&BirthRecord],
+    patients: &HashMap<i64, Patient>,
+    staff: &HashMap<i64, StaffMember>,
+) -> String {
+    let mut lines = vec![csv_line(&[
+        "registration_no",
+        "birth_time",
+        "child_name",
+        "sex",
+        "weight_grams",
+        "mother_name",
+        "mother_date_of_birth",
+        "delivering_doctor",
+    ])];
+    for record in records {
+        let mother = patients.get(&record.mother_id);
+        lines.push(csv_line(&[
+            &format!("B-{:06}", record.id),
+            &record.birth_time,
+            &record.newborn_name,
+            record.sex.label(),
+            &record
+                .weight_grams
+                .map(|w| w.to_string())
+                .unwrap_or_default(),
+            &patient_name(patients, record.mother_id),
+            mother.map(|m| m.date_of_birth.as_str()).unwrap_or(""),
+            &staff_name(staff, record.delivering_doctor_id),
+        ]));
+    }
+    lines.join("\n") + "\n"
+}
+
+pub fn deaths_report_csv(
+    records: &[&DeathRecord],
+    patients: &HashMap<i64, Patient>,
+    staff: &HashMap<i64, StaffMember>,
+) -> String {
+    let mut lines = vec![csv_line(&[
+        "registration_no",
+        "time_of_death",
+        "deceased_name",
+        "sex",
+        "date_of_birth",
+        "cause_of_death",
+        "place_of_death",
+        "certifying_doctor",
+    ])];
+    for record in records {
+        let deceased = patients.get(&record.patient_id);
+        lines.push(csv_line(&[
+            &format!("D-{:06}", record.id),
+            &record.time_of_death,
+            &patient_name(patients, record.patient_id),
+            deceased.map(|p| p.gender.label()).unwrap_or(""),
+            deceased.map(|p| p.date_of_birth.as_str()).unwrap_or(""),
+            &record.cause,
+            record.place.as_deref().unwrap_or(""),
+            &staff_name(staff, record.certifying_doctor_id),
+        ]));
+    }
+    lines.join("\n") + "\n"
+}
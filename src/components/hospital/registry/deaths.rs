@@ -0,0 +1,528 @@
+use crate::app::SelectedApp;
+use crate::components::hospital::registry::certificate::{death_certificate, deaths_report_csv};
+use crate::components::hospital::registry::{render_form_fields, render_prompt};
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::print_dialog::PrintDialog;
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{DeathRecord, Patient, StaffMember, StaffRole};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime;
+use crate::utils::write_export;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeathMode {
+    Viewing,
+    Adding,
+    ExportPrompt,
+}
+
+const FORM_FIELDS: usize = 6;
+
+pub struct DeathRegistry {
+    db: Db,
+    records: Vec<DeathRecord>,
+    patients: HashMap<i64, Patient>,
+    staff: HashMap<i64, StaffMember>,
+    table_state: TableState,
+    mode: DeathMode,
+    form: [String; FORM_FIELDS],
+    focus_index: usize,
+    export_period: String,
+    print_dialog: Option<PrintDialog>,
+}
+
+impl DeathRegistry {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            records: Vec::new(),
+            patients: HashMap::new(),
+            staff: HashMap::new(),
+            table_state: TableState::default(),
+            mode: DeathMode::Viewing,
+            form: Default::default(),
+            focus_index: 0,
+            export_period: String::new(),
+            print_dialog: None,
+        }
+    }
+
+    pub fn fetch_records(&mut self) -> Result<()> {
+        self.records = db::get_all_death_records(&self.db)?;
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        self.staff = db::get_all_staff(&self.db)?
+            .into_iter()
+            .map(|s| (s.id, s))
+            .collect();
+        if self.records.is_empty() {
+            self.table_state.select(None);
+        } else {
+            let selected = self.table_state.selected().unwrap_or(0);
+            self.table_state
+                .select(Some(selected.min(self.records.len() - 1)));
+        }
+        Ok(())
+    }
+
+    fn selected_record(&self) -> Option<&DeathRecord> {
+        self.table_state
+            .selected()
+            .and_then(|i| self.records.get(i))
+    }
+
+    fn resolve_patient(&self, input: &str) -> Option<&Patient> {
+        input
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|id| self.patients.get(&id))
+    }
+
+    fn resolve_doctor(&self, input: &str) -> Option<&StaffMember> {
+        input
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|id| self.staff.get(&id))
+            .filter(|s| s.role == StaffRole::Doctor)
+    }
+
+    fn start_adding(&mut self) {
+        self.form = Default::default();
+        self.form[1] = datetime::format_date_time(datetime::local_now());
+        self.focus_index = 0;
+        self.mode = DeathMode::Adding;
+    }
+
+    fn save(&mut self) -> Result<()> {
+        let Some(patient) = self.resolve_patient(&self.form[0]).cloned() else {
+            toast::push(Toast::error(t("deaths.no_patient")));
+            return Ok(());
+        };
+        if datetime::parse_date_time(&self.form[1]).is_none() {
+            toast::push(Toast::error(t("deaths.bad_time")));
+            return Ok(());
+        }
+        if self.form[2].trim().is_empty() {
+            toast::push(Toast::error(t("deaths.no_cause")));
+            return Ok(());
+        }
+        let Some(doctor) = self.resolve_doctor(&self.form[3]).cloned() else {
+            toast::push(Toast::error(t("deaths.no_doctor")));
+            return Ok(());
+        };
+
+        let optional = |value: &String| {
+            if value.trim().is_empty() {
+                None
+            } else {
+                Some(value.trim().to_string())
+            }
+        };
+        let record = DeathRecord {
+            id: 0,
+            patient_id: patient.id,
+            time_of_death: self.form[1].trim().to_string(),
+            cause: self.form[2].trim().to_string(),
+            certifying_doctor_id: doctor.id,
+            place: optional(&self.form[4]),
+            notes: optional(&self.form[5]),
+        };
+
+        match db::create_death_record(&self.db, &record) {
+            Ok(id) => {
+                self.mode = DeathMode::Viewing;
+                self.fetch_records()?;
+                if let Some(pos) = self.records.iter().position(|r| r.id == id) {
+                    self.table_state.select(Some(pos));
+                }
+                toast::push(Toast::success(tf(
+                    "deaths.registered",
+                    &[("id", &format!("{:06}", id))],
+                )));
+            }
+            Err(e) => toast::push(Toast::error(format!("{}", e))),
+        }
+        Ok(())
+    }
+
+    fn export_certificate(&mut self) {
+        let Some(record) = self.selected_record() else {
+            toast::push(Toast::error(t("deaths.none_selected")));
+            return;
+        };
+        let contents = death_certificate(record, &self.patients, &self.staff);
+        let file_name = format!("death_certificate_D-{:06}.txt", record.id);
+        match write_export(&file_name, &contents) {
+            Ok(path) => toast::push(Toast::success(tf(
+                "births.certificate_written",
+                &[("path", &path.display())],
+            ))),
+            Err(e) => toast::push(Toast::error(format!("{}", e))),
+        }
+    }
+
+    fn print_certificate(&mut self) {
+        let Some(record) = self.selected_record() else {
+            toast::push(Toast::error(t("deaths.none_selected")));
+            return;
+        };
+        let contents = death_certificate(record, &self.patients, &self.staff);
+        self.print_dialog = Some(PrintDialog::new(
+            &self.db,
+            tf(
+                "deaths.print_title",
+                &[("id", &format!("{:06}", record.id))],
+            ),
+            contents,
+        ));
+    }
+
+    fn export_report(&mut self, period: &str) {
+        let records: Vec<&DeathRecord> = self
+            .records
+            .iter()
+            .filter(|r| r.time_of_death.starts_with(period))
+            .collect();
+        if records.is_empty() {
+            toast::push(Toast::error(tf(
+                "deaths.none_in_period",
+                &[("period", &period)],
+            )));
+            return;
+        }
+        let contents = deaths_report_csv(&records, &self.patients, &self.staff);
+        let suffix = if period.is_empty() { "all" } else { period };
+        match write_export(&format!("deaths_report_{}.csv", suffix), &contents) {
+            Ok(path) => toast::push(Toast::success(tf(
+                "deaths.exported",
+                &[("count", &records.len()), ("path", &path.display())],
+            ))),
+            Err(e) => toast::push(Toast::error(format!("{}", e))),
+        }
+    }
+
+    fn handle_form_input(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Char(c) if self.focus_index < FORM_FIELDS => {
+                self.form[self.focus_index].push(c);
+            }
+            KeyCode::Backspace if self.focus_index < FORM_FIELDS => {
+                self.form[self.focus_index].pop();
+            }
+            KeyCode::Tab | KeyCode::Down => {
+                self.focus_index = (self.focus_index + 1) % (FORM_FIELDS + 2);
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                self.focus_index = (self.focus_index + FORM_FIELDS + 1) % (FORM_FIELDS + 2);
+            }
+            KeyCode::Enter => match self.focus_index {
+                i if i < FORM_FIELDS => self.focus_index += 1,
+                i if i == FORM_FIELDS => self.save()?,
+                _ => self.mode = DeathMode::Viewing,
+            },
+            KeyCode::Esc => self.mode = DeathMode::Viewing,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(dialog) = self.print_dialog.as_mut() {
+            if dialog.handle_key(key) {
+                self.print_dialog = None;
+            }
+            return Ok(None);
+        }
+        match self.mode {
+            DeathMode::Adding => {
+                self.handle_form_input(key)?;
+                return Ok(None);
+            }
+            DeathMode::ExportPrompt => {
+                match key.code {
+                    KeyCode::Char(c) => self.export_period.push(c),
+                    KeyCode::Backspace => {
+                        self.export_period.pop();
+                    }
+                    KeyCode::Enter => {
+                        let period = self.export_period.trim().to_string();
+                        self.mode = DeathMode::Viewing;
+                        self.export_report(&period);
+                    }
+                    KeyCode::Esc => self.mode = DeathMode::Viewing,
+                    _ => {}
+                }
+                return Ok(None);
+            }
+            DeathMode::Viewing => {}
+        }
+
+        match key.code {
+            KeyCode::Down if !self.records.is_empty() => {
+                let i = self
+                    .table_state
+                    .selected()
+                    .map_or(0, |i| (i + 1) % self.records.len());
+                self.table_state.select(Some(i));
+            }
+            KeyCode::Up if !self.records.is_empty() => {
+                let len = self.records.len();
+                let i = self
+                    .table_state
+                    .selected()
+                    .map_or(0, |i| (i + len - 1) % len);
+                self.table_state.select(Some(i));
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => self.start_adding(),
+            KeyCode::Char('c') | KeyCode::Char('C') => self.export_certificate(),
+            KeyCode::Char('p') | KeyCode::Char('P') => self.print_certificate(),
+            KeyCode::Char('e') | KeyCode::Char('E') => {
+                self.export_period = datetime::format_month(datetime::today());
+                self.mode = DeathMode::ExportPrompt;
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => self.fetch_records()?,
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_list(&self, frame: &mut Frame, area: Rect) {
+        let header = Row::new(vec![
+            t("births.reg_no"),
+            t("deaths.deceased"),
+            t("deaths.time"),
+            t("deaths.cause"),
+            t("deaths.place"),
+            t("deaths.certified_by"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let widths = [
+            Constraint::Length(10),
+            Constraint::Percentage(20),
+            Constraint::Length(17),
+            Constraint::Percentage(25),
+            Constraint::Percentage(15),
+            Constraint::Min(15),
+        ];
+        let columns = column_widths(area.inner(Margin::new(1, 1)), &widths, 1, "► ");
+        let rows = self.records.iter().map(|r| {
+            Row::new(vec![
+                cell(format!("D-{:06}", r.id), &columns, 0),
+                cell(
+                    self.patients
+                        .get(&r.patient_id)
+                        .map(|p| format!("{} {}", p.first_name, p.last_name))
+                        .unwrap_or_else(|| format!("#{}", r.patient_id)),
+                    &columns,
+                    1,
+                ),
+                cell(&r.time_of_death, &columns, 2),
+                cell(&r.cause, &columns, 3),
+                cell(r.place.as_deref().unwrap_or("-"), &columns, 4),
+                cell(
+                    self.staff
+                        .get(&r.certifying_doctor_id)
+                        .map(|s| s.name.clone())
+                        .unwrap_or_else(|| format!("#{}", r.certifying_doctor_id)),
+                    &columns,
+                    5,
+                ),
+            ])
+            .style(Style::default().fg(theme().text))
+        });
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(
+                        " {} ",
+                        tf("deaths.registered_title", &[("count", &self.records.len())])
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.table_state.clone());
+    }
+
+    fn render_form(&self, frame: &mut Frame, area: Rect) {
+        let patient = self
+            .resolve_patient(&self.form[0])
+            .map(|p| {
+                format!(
+                    " {}",
+                    tf(
+                        "deaths.name",
+                        &[("first", &p.first_name), ("last", &p.last_name)]
+                    )
+                )
+            })
+            .unwrap_or_default();
+        let doctor = self
+            .resolve_doctor(&self.form[3])
+            .map(|d| format!(" {}", tf("births.doctor_name", &[("name", &d.name)])))
+            .unwrap_or_default();
+        let fields = vec![
+            (
+                tf("deaths.patient_field", &[("patient", &patient)]),
+                self.form[0].clone(),
+            ),
+            (t("deaths.time_field").to_string(), self.form[1].clone()),
+            (t("deaths.cause_field").to_string(), self.form[2].clone()),
+            (
+                tf("deaths.doctor_field", &[("doctor", &doctor)]),
+                self.form[3].clone(),
+            ),
+            (t("deaths.place_field").to_string(), self.form[4].clone()),
+            (t("campaign.notes").to_string(), self.form[5].clone()),
+        ];
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3 * FORM_FIELDS as u16),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(area);
+        render_form_fields(frame, chunks[0], &fields, self.focus_index);
+
+        let buttons = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[2]);
+        let save_style = if self.focus_index == FORM_FIELDS {
+            Style::default()
+                .fg(theme().success)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        let cancel_style = if self.focus_index == FORM_FIELDS + 1 {
+            Style::default()
+                .fg(theme().accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        frame.render_widget(
+            Paragraph::new(if self.focus_index == FORM_FIELDS {
+                format!("► {} ◄", t("deaths.register"))
+            } else {
+                format!("  {}  ", t("deaths.register"))
+            })
+            .style(save_style)
+            .alignment(Alignment::Center),
+            buttons[0],
+        );
+        frame.render_widget(
+            Paragraph::new(if self.focus_index == FORM_FIELDS + 1 {
+                format!("► {} ◄", t("common.cancel"))
+            } else {
+                format!("  {}  ", t("common.cancel"))
+            })
+            .style(cancel_style)
+            .alignment(Alignment::Center),
+            buttons[1],
+        );
+    }
+}
+
+impl Component for DeathRegistry {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(10),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(if self.mode == DeathMode::Adding {
+                t("deaths.register_title")
+            } else {
+                t("deaths.title")
+            })
+            .style(
+                Style::default()
+                    .fg(theme().title)
+                    .add_modifier(Modifier::BOLD)
+                    .bg(theme().background),
+            )
+            .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        if self.mode == DeathMode::Adding {
+            self.render_form(frame, layout[1]);
+        } else {
+            self.render_list(frame, layout[1]);
+        }
+
+        let help_text = if self.mode == DeathMode::Adding {
+            t("births.form_help")
+        } else {
+            t("deaths.help")
+        };
+        frame.render_widget(
+            Paragraph::new(help_text)
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+
+        if self.mode == DeathMode::ExportPrompt {
+            render_prompt(
+                frame,
+                t("births.statutory"),
+                t("births.period_prompt"),
+                &self.export_period,
+            );
+        }
+        if let Some(dialog) = &self.print_dialog {
+            dialog.render_popup(frame);
+        }
+    }
+}
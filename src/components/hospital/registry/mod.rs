@@ -0,0 +1,130 @@
+use crate::app::SelectedApp;
+use crate::components::Component;
+use crate::db::Db;
+use crate::i18n::t;
+use crate::theme::theme;
+use crate::tui::Frame;
+use anyhow::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{prelude::*, widgets::*};
+
+pub mod births;
+pub mod certificate;
+pub mod deaths;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryState {
+    Births,
+    Deaths,
+}
+
+pub struct Registry {
+    pub births: births::BirthRegistry,
+    pub deaths: deaths::DeathRegistry,
+    pub state: RegistryState,
+}
+
+impl Registry {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            births: births::BirthRegistry::new(db),
+            deaths: deaths::DeathRegistry::new(db),
+            state: RegistryState::Births,
+        }
+    }
+
+    pub fn initialize_list(&mut self) -> Result<()> {
+        match self.state {
+            RegistryState::Births => self.births.fetch_records(),
+            RegistryState::Deaths => self.deaths.fetch_records(),
+        }
+    }
+}
+
+pub fn render_form_fields(
+    frame: &mut Frame,
+    area: Rect,
+    fields: &[(String, String)],
+    focus_index: usize,
+) {
+    let mut constraints = vec![Constraint::Length(3); fields.len()];
+    constraints.push(Constraint::Min(0));
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .horizontal_margin(2)
+        .split(area);
+
+    for (i, (label, value)) in fields.iter().enumerate() {
+        let input = Paragraph::new(value.clone())
+            .style(Style::default().fg(theme().text))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(Span::styled(
+                        format!(" {} ", label),
+                        Style::default().fg(theme().title),
+                    ))
+                    .border_style(if focus_index == i {
+                        Style::default().fg(theme().focus)
+                    } else {
+                        Style::default().fg(theme().unfocused)
+                    })
+                    .style(Style::default().bg(theme().input)),
+            );
+        frame.render_widget(input, chunks[i]);
+    }
+}
+
+pub fn render_prompt(frame: &mut Frame, title: &str, label: &str, value: &str) {
+    let area = frame.area();
+    let dialog_area = Rect::new(
+        area.width.saturating_sub(50) / 2,
+        area.height.saturating_sub(7) / 2,
+        50.min(area.width),
+        7.min(area.height),
+    );
+    frame.render_widget(Clear, dialog_area);
+    let block = Block::default()
+        .title(format!(" {} ", title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme().focus))
+        .style(Style::default().bg(theme().dialog));
+    let inner = block.inner(dialog_area);
+    frame.render_widget(block, dialog_area);
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(label.to_string()),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("{}_", value),
+                Style::default()
+                    .fg(theme().focus)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(t("registry.prompt_help")),
+        ])
+        .style(Style::default().fg(theme().text))
+        .alignment(Alignment::Center),
+        inner,
+    );
+}
+
+impl Component for Registry {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        match self.state {
+            RegistryState::Births => self.births.handle_input(event),
+            RegistryState::Deaths => self.deaths.handle_input(event),
+        }
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        match self.state {
+            RegistryState::Births => self.births.render(frame),
+            RegistryState::Deaths => self.deaths.render(frame),
+        }
+    }
+}
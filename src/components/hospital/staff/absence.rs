@@ -0,0 +1,600 @@
+//! Registering a staff member's unexpected leave, then moving the
+//! consultations booked with them in it. Each one is offered another doctor
+//! at the same time, those on shift first, or the same doctor at the same
+//! time once they are back; the moves accepted are saved together and the
+//! patients told.
+
+use super::shift_at;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::date_picker::DatePicker;
+use crate::components::widgets::text_input::TextInput;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{StaffLeave, StaffMember, StaffRole, Teleconsultation};
+use crate::notify;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{calendar, config, datetime};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+use time::{Date, Duration, PrimitiveDateTime};
+
+/// Days after the leave searched for a free slot with the absent doctor.
+const SEARCH_DAYS: i64 = 28;
+/// Other doctors offered for each consultation.
+const MAX_COVER: usize = 3;
+
+/// One consultation in the leave and where it could go.
+struct Move {
+    consultation: Teleconsultation,
+    patient: String,
+    /// Doctor id and time in the facility's time zone; the other doctors
+    /// come first, then the absent one after the leave.
+    options: Vec<(i64, PrimitiveDateTime)>,
+    choice: usize,
+    accepted: bool,
+}
+
+impl Move {
+    fn chosen(&self) -> Option<(i64, PrimitiveDateTime)> {
+        self.options.get(self.choice).copied()
+    }
+}
+
+enum Step {
+    /// Choosing the first day, then the last one.
+    Picking(DatePicker, Option<Date>),
+    Reason {
+        from: Date,
+        to: Date,
+        reason: TextInput,
+    },
+    Rescheduling {
+        from: Date,
+        to: Date,
+        moves: Vec<Move>,
+        state: TableState,
+    },
+}
+
+/// Dialog shown over the shift assignment screen for one staff member.
+pub struct StaffAbsence {
+    db: Db,
+    staff: StaffMember,
+    step: Step,
+    /// Doctor names by id, and which of them are on shift at each
+    /// suggested time, for the suggestions.
+    doctors: HashMap<i64, String>,
+    on_shift: Vec<(i64, PrimitiveDateTime)>,
+}
+
+fn slot() -> Duration {
+    Duration::minutes(config::config().slot_minutes.max(1) as i64)
+}
+
+impl StaffAbsence {
+    pub fn new(db: &Db, staff: StaffMember) -> Self {
+        Self {
+            db: db.clone(),
+            staff,
+            step: Step::Picking(DatePicker::today().with_min(datetime::today()), None),
+            doctors: HashMap::new(),
+            on_shift: Vec::new(),
+        }
+    }
+
+    /// Saves the leave and works out the moves for the consultations in it.
+    fn register(&mut self, from: Date, to: Date, reason: &str) -> Result<Vec<Move>> {
+        let leave = StaffLeave {
+            id: 0,
+            staff_id: self.staff.id,
+            start_date: datetime::format_date(from),
+            end_date: datetime::format_date(to),
+            reason: (!reason.trim().is_empty()).then(|| reason.trim().to_string()),
+        };
+        db::register_leave(&self.db, &leave)?;
+        self.plan_moves(from, to)
+    }
+
+    fn plan_moves(&mut self, from: Date, to: Date) -> Result<Vec<Move>> {
+        let last = to + Duration::days(SEARCH_DAYS);
+        let (from_text, last_text) = (datetime::format_date(from), datetime::format_date(last));
+        let bookings = db::get_scheduled_teleconsultations(&self.db, &from_text, &last_text)?;
+        let leave = db::get_leave_between(&self.db, &from_text, &last_text)?;
+        let shifts = db::get_all_shifts(&self.db)?;
+        let patients: HashMap<i64, String> = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, format!("{} {}", p.first_name, p.last_name)))
+            .collect();
+        let mut doctors: Vec<StaffMember> = db::get_all_staff(&self.db)?
+            .into_iter()
+            .filter(|s| s.role == StaffRole::Doctor || s.id == self.staff.id)
+            .collect();
+        doctors.sort_by(|a, b| a.name.cmp(&b.name));
+        self.doctors = doctors.iter().map(|d| (d.id, d.name.clone())).collect();
+
+        let local = |c: &Teleconsultation| {
+            datetime::parse_date_time(&datetime::facility_stamp(&c.scheduled_at))
+        };
+        let mut booked: Vec<(i64, PrimitiveDateTime)> = bookings
+            .iter()
+            .filter_map(|c| Some((c.doctor_id, local(c)?)))
+            .collect();
+        let away = |doctor: i64, date: Date| {
+            let date = datetime::format_date(date);
+            leave
+                .iter()
+                .any(|l| l.staff_id == doctor && l.covers(&date))
+        };
+        let rostered = |doctor: i64, at: PrimitiveDateTime| {
+            let (date, shift) = shift_at(at);
+            let date = datetime::format_date(date);
+            shifts
+                .iter()
+                .any(|(_, staff_id, d, s)| *staff_id == doctor && *d == date && s == shift)
+        };
+        let slot = slot();
+        let free = |booked: &[(i64, PrimitiveDateTime)], doctor: i64, at: PrimitiveDateTime| {
+            !booked
+                .iter()
+                .any(|(d, t)| *d == doctor && (*t - at).abs() < slot)
+        };
+
+        let mut moves = Vec::new();
+        for consultation in bookings.iter().filter(|c| {
+            c.doctor_id == self.staff.id
+                && datetime::local_date(&c.scheduled_at).is_some_and(|date| date <= to)
+        }) {
+            let Some(at) = local(consultation) else {
+                continue;
+            };
+            let mut cover: Vec<&StaffMember> = doctors
+                .iter()
+                .filter(|d| d.id != self.staff.id)
+                .filter(|d| !away(d.id, at.date()) && free(&booked, d.id, at))
+                .collect();
+            cover.sort_by_key(|d| !rostered(d.id, at));
+            let mut options: Vec<(i64, PrimitiveDateTime)> = cover
+                .into_iter()
+                .take(MAX_COVER)
+                .map(|d| (d.id, at))
+                .collect();
+            for (doctor, at) in &options {
+                if rostered(*doctor, *at) {
+                    self.on_shift.push((*doctor, *at));
+                }
+            }
+            let later = (1..=SEARCH_DAYS)
+                .map(|n| to + Duration::days(n))
+                .filter(|day| calendar::closed_reason(*day).is_none() && !away(self.staff.id, *day))
+                .map(|day| PrimitiveDateTime::new(day, at.time()))
+                .find(|t| free(&booked, self.staff.id, *t));
+            options.extend(later.map(|t| (self.staff.id, t)));
+            if let Some(first) = options.first() {
+                booked.push(*first);
+            }
+            moves.push(Move {
+                patient: patients
+                    .get(&consultation.patient_id)
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        tf(
+                            "absence.patient_fallback",
+                            &[("id", &consultation.patient_id)],
+                        )
+                    }),
+                consultation: consultation.clone(),
+                accepted: !options.is_empty(),
+                options,
+                choice: 0,
+            });
+        }
+        Ok(moves)
+    }
+
+    /// Saves the accepted moves in one go and queues the patients' notices.
+    /// False, with the reason shown, when nothing was saved.
+    fn apply(db: &Db, moves: &[Move]) -> bool {
+        let chosen: Vec<(&Move, (i64, PrimitiveDateTime))> = moves
+            .iter()
+            .filter(|m| m.accepted)
+            .filter_map(|m| Some((m, m.chosen()?)))
+            .collect();
+        if chosen.is_empty() {
+            toast::push(Toast::error(t("absence.none_accepted")));
+            return false;
+        }
+        let slot = slot();
+        for (i, (first, (doctor, at))) in chosen.iter().enumerate() {
+            if let Some((second, _)) = chosen[i + 1..]
+                .iter()
+                .find(|(_, (d, t))| d == doctor && (*t - *at).abs() < slot)
+            {
+                toast::push(Toast::error(tf(
+                    "absence.clash",
+                    &[
+                        ("first", &first.patient),
+                        ("second", &second.patient),
+                        (
+                            "time",
+                            &(datetime::display_stamp(&datetime::to_stored(*at))),
+                        ),
+                    ],
+                )));
+                return false;
+            }
+        }
+
+        let changes: Vec<(i64, i64, String)> = chosen
+            .iter()
+            .map(|(m, (doctor, at))| (m.consultation.id, *doctor, datetime::to_stored(*at)))
+            .collect();
+        match db::reschedule_teleconsultations(db, &changes) {
+            Ok(()) => {
+                let notices = chosen
+                    .iter()
+                    .zip(&changes)
+                    .map(|((m, _), (_, doctor_id, scheduled_at))| {
+                        let after = Teleconsultation {
+                            doctor_id: *doctor_id,
+                            scheduled_at: scheduled_at.clone(),
+                            ..m.consultation.clone()
+                        };
+                        (m.consultation.clone(), after)
+                    })
+                    .collect();
+                notify::rescheduled(db, notices);
+                toast::push(Toast::success(tf(
+                    "absence.moved",
+                    &[("count", &changes.len())],
+                )));
+                true
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("absence.move_failed", &[("error", &e)])));
+                false
+            }
+        }
+    }
+
+    /// Returns true once the dialog is finished with.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match &mut self.step {
+            Step::Picking(picker, start) => match (key.code, *start) {
+                (KeyCode::Enter, None) => {
+                    let from = picker.selected();
+                    *start = Some(from);
+                    *picker = picker.clone().with_min(from);
+                }
+                (KeyCode::Enter, Some(from)) => {
+                    self.step = Step::Reason {
+                        from,
+                        to: picker.selected(),
+                        reason: TextInput::new(),
+                    };
+                }
+                (KeyCode::Esc, _) => return true,
+                _ => {
+                    picker.handle_key(key);
+                }
+            },
+            Step::Reason { from, to, reason } => match key.code {
+                _ if reason.handle_key(key) => {}
+                KeyCode::Enter => {
+                    let (from, to, reason) = (*from, *to, reason.value().to_string());
+                    match self.register(from, to, &reason) {
+                        Ok(moves) if moves.is_empty() => {
+                            toast::push(Toast::success(tf(
+                                "absence.registered_none",
+                                &[("name", &self.staff.name)],
+                            )));
+                            return true;
+                        }
+                        Ok(moves) => {
+                            let mut state = TableState::default();
+                            state.select(Some(0));
+                            self.step = Step::Rescheduling {
+                                from,
+                                to,
+                                moves,
+                                state,
+                            };
+                        }
+                        Err(e) => {
+                            toast::push(Toast::error(tf(
+                                "absence.register_failed",
+                                &[("error", &e)],
+                            )));
+                            return true;
+                        }
+                    }
+                }
+                KeyCode::Esc => return true,
+                _ => {}
+            },
+            Step::Rescheduling { moves, state, .. } => {
+                let selected = state.selected().unwrap_or(0);
+                match key.code {
+                    KeyCode::Up => state.select(Some(selected.saturating_sub(1))),
+                    KeyCode::Down => state.select(Some((selected + 1).min(moves.len() - 1))),
+                    KeyCode::Left | KeyCode::Right => {
+                        if let Some(m) = moves.get_mut(selected).filter(|m| !m.options.is_empty()) {
+                            let count = m.options.len();
+                            m.choice = if key.code == KeyCode::Right {
+                                (m.choice + 1) % count
+                            } else {
+                                (m.choice + count - 1) % count
+                            };
+                        }
+                    }
+                    KeyCode::Char(' ') => {
+                        if let Some(m) = moves.get_mut(selected).filter(|m| !m.options.is_empty()) {
+                            m.accepted = !m.accepted;
+                        }
+                    }
+                    KeyCode::Enter => return Self::apply(&self.db, moves),
+                    KeyCode::Esc => {
+                        toast::push(Toast::success(tf(
+                            "absence.registered_kept",
+                            &[("name", &self.staff.name)],
+                        )));
+                        return true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        false
+    }
+
+    fn doctor_name(&self, id: i64) -> String {
+        self.doctors
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| tf("waiting.doctor_fallback", &[("id", &id)]))
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        match &self.step {
+            Step::Picking(picker, start) => {
+                let title = if start.is_some() {
+                    tf("absence.to_title", &[("name", &self.staff.name)])
+                } else {
+                    tf("absence.from_title", &[("name", &self.staff.name)])
+                };
+                picker.render_popup(frame, &title);
+            }
+            Step::Reason { from, to, reason } => self.render_reason(frame, *from, *to, reason),
+            Step::Rescheduling {
+                from,
+                to,
+                moves,
+                state,
+            } => self.render_moves(frame, *from, *to, moves, state),
+        }
+    }
+
+    fn render_reason(&self, frame: &mut Frame, from: Date, to: Date, reason: &TextInput) {
+        let area = frame.area();
+        let width = 60.min(area.width);
+        let height = 9.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(
+                " {} ",
+                tf("absence.leave_of", &[("name", &self.staff.name)])
+            ))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let [dates, input, _, help] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .horizontal_margin(1)
+        .areas(inner);
+        frame.render_widget(
+            Paragraph::new(tf(
+                "common.date_range",
+                &[
+                    ("from", &datetime::format_date(from)),
+                    ("to", &datetime::format_date(to)),
+                ],
+            ))
+            .style(
+                Style::default()
+                    .fg(theme().accent)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .alignment(Alignment::Center),
+            dates,
+        );
+        frame.render_widget(
+            Paragraph::new(reason.line(true))
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", t("absence.reason")))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(theme().focus)),
+                ),
+            input,
+        );
+        frame.render_widget(
+            Paragraph::new(t("absence.register_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            help,
+        );
+    }
+
+    fn render_moves(
+        &self,
+        frame: &mut Frame,
+        from: Date,
+        to: Date,
+        moves: &[Move],
+        state: &TableState,
+    ) {
+        let area = frame.area();
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(5),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(tf(
+                "absence.reschedule",
+                &[
+                    ("name", &self.staff.name.to_uppercase()),
+                    ("from", &datetime::format_date(from)),
+                    ("to", &datetime::format_date(to)),
+                ],
+            ))
+            .style(
+                Style::default()
+                    .fg(theme().title)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::BOTTOM)
+                    .border_style(Style::default().fg(theme().border)),
+            ),
+            layout[0],
+        );
+        let accepted = moves.iter().filter(|m| m.accepted).count();
+        let stranded = moves.iter().filter(|m| m.options.is_empty()).count();
+        let mut summary = tf(
+            "absence.summary",
+            &[("count", &moves.len()), ("accepted", &accepted)],
+        );
+        if stranded > 0 {
+            summary.push_str(&tf("absence.stranded", &[("count", &stranded)]));
+        }
+        frame.render_widget(
+            Paragraph::new(summary)
+                .style(Style::default().fg(theme().text))
+                .alignment(Alignment::Center),
+            layout[1],
+        );
+
+        let widths = [
+            Constraint::Length(3),
+            Constraint::Percentage(25),
+            Constraint::Percentage(20),
+            Constraint::Percentage(22),
+            Constraint::Percentage(33),
+        ];
+        let columns = column_widths(layout[2].inner(Margin::new(1, 1)), &widths, 1, "► ");
+        let header = Row::new(
+            [
+                "",
+                t("kiosk.patient"),
+                t("absence.booked"),
+                t("absence.move_to"),
+                t("kiosk.doctor"),
+            ]
+            .map(|h| {
+                Cell::from(h).style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD),
+                )
+            }),
+        )
+        .style(Style::default().bg(theme().header));
+        let rows = moves.iter().map(|m| {
+            let mark = if m.accepted { "[x]" } else { "[ ]" };
+            let (time, doctor) = match m.chosen() {
+                Some((doctor, at)) => {
+                    let mut name = self.doctor_name(doctor);
+                    if doctor == self.staff.id {
+                        name.push_str(&format!(" ({})", t("absence.after_leave")));
+                    } else if self.on_shift.contains(&(doctor, at)) {
+                        name.push_str(&format!(" ({})", t("absence.on_shift")));
+                    }
+                    if m.options.len() > 1 {
+                        name.push_str(&format!(" {}/{}", m.choice + 1, m.options.len()));
+                    }
+                    (datetime::display_stamp(&datetime::to_stored(at)), name)
+                }
+                None => ("-".to_string(), t("absence.no_slot").to_string()),
+            };
+            let style = if m.options.is_empty() {
+                Style::default().fg(theme().error)
+            } else if m.accepted {
+                Style::default().fg(theme().text)
+            } else {
+                Style::default().fg(theme().inactive)
+            };
+            Row::new(vec![
+                cell(mark, &columns, 0),
+                cell(&m.patient, &columns, 1),
+                cell(
+                    datetime::display_stamp(&m.consultation.scheduled_at),
+                    &columns,
+                    2,
+                ),
+                cell(time, &columns, 3),
+                cell(doctor, &columns, 4),
+            ])
+            .style(style)
+        });
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .title(format!(" {} ", t("absence.moves_title")))
+                    .title_alignment(Alignment::Center)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, layout[2], &mut state.clone());
+
+        frame.render_widget(
+            Paragraph::new(t("absence.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+    }
+}
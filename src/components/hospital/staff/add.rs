@@ -1,112 +1,124 @@
 use crate::app::SelectedApp;
+use crate::components::form::{self, Field, Form};
+use crate::components::quick_actions;
+use crate::components::toast::{self, Toast};
 use crate::components::Component;
-use crate::db;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
 use crate::models::{StaffMember, StaffRole};
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::utils::contact;
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{prelude::*, widgets::*};
-use std::time::{Duration, Instant};
+
+const NAME: usize = 0;
+const ROLE: usize = 1;
+const PHONE: usize = 2;
+const EMAIL: usize = 3;
+const ADDRESS: usize = 4;
+const POSTCODE: usize = 5;
+const CITY: usize = 6;
+const REGION: usize = 7;
+
+const ROLES: [&str; 5] = ["Doctor", "Nurse", "Admin", "Technician", "Accountant"];
 
 pub struct AddStaff {
-    name: String,
-    role: StaffRole,
-    phone: String,
-    email: Option<String>,
-    address: String,
+    db: Db,
+    form: Form,
     focus_index: usize,
-    error_message: Option<String>,
-    error_timer: Option<Instant>,
-    success_message: Option<String>,
-    success_timer: Option<Instant>,
 }
 
-const INPUT_FIELDS: usize = 5;
-const SUBMIT_BUTTON: usize = 5;
-const BACK_BUTTON: usize = 6;
+const INPUT_FIELDS: usize = 8;
+const SUBMIT_BUTTON: usize = 8;
+const BACK_BUTTON: usize = 9;
+
+fn staff_form() -> Form {
+    let mut form = Form::new(vec![
+        Field::new(t("staff.add.full_name")).required(),
+        Field::new(t("staff.add.role_field"))
+            .required()
+            .validate_with(form::one_of(&ROLES)),
+        Field::new(t("common.phone"))
+            .required()
+            .validate_with(Box::new(form::phone)),
+        Field::new(t("patients.email")).validate_with(Box::new(form::email)),
+        Field::new(t("patients.street")).required(),
+        Field::new(t("patients.postcode")),
+        Field::new(t("patients.city")),
+        Field::new(t("patients.region")),
+    ]);
+    form.fields[ROLE].set(ROLES[0]);
+    form
+}
 
 impl AddStaff {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
         Self {
-            name: String::new(),
-            role: StaffRole::Doctor,
-            phone: String::new(),
-            email: None,
-            address: String::new(),
+            db: db.clone(),
+            form: staff_form(),
             focus_index: 0,
-            error_message: None,
-            error_timer: None,
-            success_message: None,
-            success_timer: None,
         }
     }
 
-    fn clear_error(&mut self) {
-        self.error_message = None;
-        self.error_timer = None;
-    }
+    fn submit(&mut self) {
+        if let Some(invalid) = self.form.validate() {
+            self.focus_index = invalid;
+            toast::push(Toast::error(t("common.fix_fields")));
+            return;
+        }
 
-    fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_timer = Some(Instant::now());
-    }
+        let new_staff_member = StaffMember {
+            id: 0,
+            name: self.form.value(NAME),
+            role: match self.form.value(ROLE).as_str() {
+                "Nurse" => StaffRole::Nurse,
+                "Admin" => StaffRole::Admin,
+                "Technician" => StaffRole::Technician,
+                "Accountant" => StaffRole::Accountant,
+                _ => StaffRole::Doctor,
+            },
+            phone_number: contact::normalize_phone(&self.form.value(PHONE)),
+            email: self
+                .form
+                .optional_value(EMAIL)
+                .map(|email| contact::normalize_email(&email)),
+            address: self.form.value(ADDRESS),
+            city: self.form.value(CITY),
+            region: self.form.value(REGION),
+            postcode: self.form.value(POSTCODE),
+        };
 
-    fn clear_success(&mut self) {
-        self.success_message = None;
-        self.success_timer = None;
+        match db::create_staff_member(&self.db, &new_staff_member) {
+            Ok(_) => {
+                toast::push(Toast::success(t("staff.add.added")));
+                quick_actions::saved(SelectedApp::StaffAdd);
+                self.form = staff_form();
+                self.focus_index = 0;
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("common.database_error", &[("error", &e)])));
+            }
+        }
     }
 
     fn process_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
-        self.check_timeouts();
         match key.code {
-            KeyCode::Char(c) => {
-                match self.focus_index {
-                    0 => self.name.push(c),
-                    1 => {
-                        if c.to_ascii_lowercase() == 'd' {
-                            self.role = StaffRole::Doctor;
-                        } else if c.to_ascii_lowercase() == 'n' {
-                            self.role = StaffRole::Nurse;
-                        } else if c.to_ascii_lowercase() == 'a' {
-                            self.role = StaffRole::Admin;
-                        } else if c.to_ascii_lowercase() == 't' {
-                            self.role = StaffRole::Technician;
-                        }
-                    }
-                    2 => self.phone.push(c),
-                    3 => {
-                        if let Some(ref mut email) = self.email {
-                            email.push(c);
-                        } else {
-                            self.email = Some(c.to_string());
-                        }
-                    }
-                    4 => self.address.push(c),
-                    _ => {}
-                }
-                self.clear_error();
-            }
-            KeyCode::Backspace => {
-                match self.focus_index {
-                    0 => {
-                        self.name.pop();
-                    }
-                    1 => { /* Role handled separately */ }
-                    2 => {
-                        self.phone.pop();
-                    }
-                    3 => {
-                        if let Some(email) = self.email.as_mut() {
-                            email.pop();
-                        }
-                    }
-                    4 => {
-                        self.address.pop();
-                    }
-                    _ => {}
+            KeyCode::Char(c) if self.focus_index == ROLE => {
+                let role = match c.to_ascii_lowercase() {
+                    'd' => Some(ROLES[0]),
+                    'n' => Some(ROLES[1]),
+                    'a' => Some(ROLES[2]),
+                    't' => Some(ROLES[3]),
+                    'c' => Some(ROLES[4]),
+                    _ => None,
+                };
+                if let Some(role) = role {
+                    self.form.fields[ROLE].set(role);
                 }
-                self.clear_error();
             }
+            _ if self.focus_index != ROLE && self.form.handle_key(self.focus_index, key) => {}
             KeyCode::Tab => {
                 if self.focus_index <= INPUT_FIELDS - 1 {
                     self.focus_index = SUBMIT_BUTTON;
@@ -129,46 +141,7 @@ impl AddStaff {
                     return Ok(Some(SelectedApp::None));
                 }
                 if self.focus_index == SUBMIT_BUTTON {
-                    if self.name.is_empty() {
-                        self.set_error("Name cannot be empty".to_string());
-                        return Ok(None);
-                    }
-                    if self.phone.is_empty() {
-                        self.set_error("Phone number cannot be empty".to_string());
-                        return Ok(None);
-                    }
-                    if self.address.is_empty() {
-                        self.set_error("Address cannot be empty".to_string());
-                        return Ok(None);
-                    }
-
-                    let new_staff_member = StaffMember {
-                        id: 0,
-                        name: self.name.clone(),
-                        role: self.role.clone(),
-                        phone_number: self.phone.clone(),
-                        email: self.email.clone(),
-                        address: self.address.clone(),
-                    };
-
-                    match db::create_staff_member(&new_staff_member) {
-                        Ok(_) => {
-                            self.success_message =
-                                Some("Staff member added successfully!".to_string());
-                            self.success_timer = Some(Instant::now());
-
-                            self.name.clear();
-                            self.role = StaffRole::Doctor;
-                            self.phone.clear();
-                            self.email = None;
-                            self.address.clear();
-                            self.focus_index = 0;
-                            self.clear_error();
-                        }
-                        Err(e) => {
-                            self.set_error(format!("Database error: {}", e));
-                        }
-                    }
+                    self.submit();
                 }
             }
             KeyCode::Esc => {
@@ -179,49 +152,22 @@ impl AddStaff {
 
         Ok(None)
     }
-
-    fn check_success_timeout(&mut self) {
-        if let Some(timer) = self.success_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_success();
-            }
-        }
-    }
-
-    fn check_error_timeout(&mut self) {
-        if let Some(timer) = self.error_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_error();
-            }
-        }
-    }
-
-    fn check_timeouts(&mut self) {
-        self.check_error_timeout();
-        self.check_success_timeout();
-    }
-}
-
-impl Default for AddStaff {
-    fn default() -> Self {
-        Self::new()
-    }
 }
 
 impl Component for AddStaff {
     fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
-        self.check_timeouts();
-
-        if let Some(selected_app) = self.process_input(event)? {
-            return Ok(Some(selected_app));
+        let focused = self.focus_index;
+        let selected_app = self.process_input(event)?;
+        if focused == POSTCODE && self.focus_index != POSTCODE {
+            self.form.fill_from_postcode(POSTCODE, CITY, REGION);
         }
-        Ok(None)
+        Ok(selected_app)
     }
 
     fn render(&self, frame: &mut Frame) {
         let area = frame.area();
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             area,
         );
 
@@ -240,16 +186,16 @@ impl Component for AddStaff {
 
         let header = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header, main_layout[0]);
 
-        let title = Paragraph::new("👥 ADD STAFF")
+        let title = Paragraph::new(t("staff.add.title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, main_layout[0]);
@@ -257,8 +203,8 @@ impl Component for AddStaff {
         let body_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
 
         frame.render_widget(body_block.clone(), main_layout[1]);
         let body_inner = body_block.inner(main_layout[1]);
@@ -271,174 +217,27 @@ impl Component for AddStaff {
                 Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Length(3),
+                Constraint::Length(3),
             ])
             .margin(1)
             .split(body_inner);
 
-        let name_input = Paragraph::new(self.name.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(
-                        " Full Name ",
-                        Style::default().fg(if self.focus_index == 0 {
-                            Color::Rgb(250, 250, 110)
-                        } else {
-                            Color::Rgb(140, 140, 200)
-                        }),
-                    ))
-                    .border_style(if self.focus_index == 0 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(name_input, body_layout[0]);
-
-        let role_text = match self.role {
-            StaffRole::Doctor => "Doctor",
-            StaffRole::Nurse => "Nurse",
-            StaffRole::Admin => "Admin",
-            StaffRole::Technician => "Technician",
-        };
-
-        let role_input = Paragraph::new(role_text)
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(
-                        " Role (D/N/A/T) ",
-                        Style::default().fg(if self.focus_index == 1 {
-                            Color::Rgb(250, 250, 110)
-                        } else {
-                            Color::Rgb(140, 140, 200)
-                        }),
-                    ))
-                    .border_style(if self.focus_index == 1 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(role_input, body_layout[1]);
-
-        let phone_input = Paragraph::new(self.phone.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(
-                        " Phone ",
-                        Style::default().fg(if self.focus_index == 2 {
-                            Color::Rgb(250, 250, 110)
-                        } else {
-                            Color::Rgb(140, 140, 200)
-                        }),
-                    ))
-                    .border_style(if self.focus_index == 2 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(phone_input, body_layout[2]);
-
-        let email_input = Paragraph::new(self.email.clone().unwrap_or_default())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(
-                        " Email ",
-                        Style::default().fg(if self.focus_index == 3 {
-                            Color::Rgb(250, 250, 110)
-                        } else {
-                            Color::Rgb(140, 140, 200)
-                        }),
-                    ))
-                    .border_style(if self.focus_index == 3 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(email_input, body_layout[3]);
-
-        let address_input = Paragraph::new(self.address.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(Span::styled(
-                        " Address ",
-                        Style::default().fg(if self.focus_index == 4 {
-                            Color::Rgb(250, 250, 110)
-                        } else {
-                            Color::Rgb(140, 140, 200)
-                        }),
-                    ))
-                    .border_style(if self.focus_index == 4 {
-                        Style::default().fg(Color::Rgb(250, 250, 110))
-                    } else {
-                        Style::default().fg(Color::Rgb(140, 140, 200))
-                    })
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-            );
-        frame.render_widget(address_input, body_layout[4]);
-
-        let status_message = if let Some(success) = &self.success_message {
-            Paragraph::new(format!("✓ {}", success))
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD)
-                        .bg(Color::Rgb(16, 16, 28)),
-                )
-                .alignment(Alignment::Center)
-        } else if let Some(error) = &self.error_message {
-            Paragraph::new(format!("⚠️ {}", error))
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(255, 100, 100))
-                        .add_modifier(Modifier::BOLD)
-                        .bg(Color::Rgb(16, 16, 28)),
-                )
-                .alignment(Alignment::Center)
-        } else {
-            Paragraph::new("").style(Style::default().bg(Color::Rgb(16, 16, 28)))
-        };
-        frame.render_widget(status_message, main_layout[3]);
+        for (index, area) in body_layout[..=ADDRESS].iter().enumerate() {
+            self.form
+                .render_field(frame, index, *area, self.focus_index);
+        }
+        let locality = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Ratio(1, 3),
+                Constraint::Ratio(1, 3),
+                Constraint::Ratio(1, 3),
+            ])
+            .split(body_layout[ADDRESS + 1]);
+        for (index, area) in [POSTCODE, CITY, REGION].into_iter().zip(locality.iter()) {
+            self.form
+                .render_field(frame, index, *area, self.focus_index);
+        }
 
         let footer_layout = Layout::default()
             .direction(Direction::Vertical)
@@ -450,17 +249,17 @@ impl Component for AddStaff {
             .split(main_layout[5]);
 
         let submit_text = if self.focus_index == SUBMIT_BUTTON {
-            "► Submit ◄"
+            format!("► {} ◄", t("common.submit"))
         } else {
-            "  Submit  "
+            format!("  {}  ", t("common.submit"))
         };
 
         let submit_style = if self.focus_index == SUBMIT_BUTTON {
             Style::default()
-                .fg(Color::Rgb(140, 219, 140))
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let submit_button = Paragraph::new(submit_text)
@@ -469,17 +268,17 @@ impl Component for AddStaff {
         frame.render_widget(submit_button, footer_layout[0]);
 
         let back_text = if self.focus_index == BACK_BUTTON {
-            "► Back ◄"
+            format!("► {} ◄", t("common.back"))
         } else {
-            "  Back  "
+            format!("  {}  ", t("common.back"))
         };
 
         let back_style = if self.focus_index == BACK_BUTTON {
             Style::default()
-                .fg(Color::Rgb(129, 199, 245))
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let back_button = Paragraph::new(back_text)
@@ -487,13 +286,9 @@ impl Component for AddStaff {
             .alignment(Alignment::Center);
         frame.render_widget(back_button, footer_layout[1]);
 
-        let help_text = "Tab: Switch Focus | Arrow Keys: Switch Fields | Enter: Submit | Esc: Back\nFor Role: Type 'D' for Doctor, 'N' for Nurse, 'A' for Admin, 'T' for Technician";
+        let help_text = t("staff.add.help");
         let help_paragraph = Paragraph::new(help_text)
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(140, 140, 170))
-                    .bg(Color::Rgb(16, 16, 28)),
-            )
+            .style(Style::default().fg(theme().help).bg(theme().background))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, footer_layout[2]);
     }
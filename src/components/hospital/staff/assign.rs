@@ -1,14 +1,25 @@
 use crate::app::SelectedApp;
+use crate::auth;
+use crate::components::hospital::staff::absence::StaffAbsence;
+use crate::components::hospital::staff::roster_reset::RosterReset;
+use crate::components::hospital::staff::shift_label;
+use crate::components::responsive::{is_narrow, is_short};
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::date_picker::DatePicker;
+use crate::components::widgets::print_dialog::PrintDialog;
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
-use crate::db;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
 use crate::models::StaffMember;
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::ui_state;
+use crate::utils::{calendar, contact, datetime, pad};
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
-use ratatui::widgets::calendar::{CalendarEventStore, Monthly};
 use ratatui::{prelude::*, widgets::*};
-use std::time::{Duration, Instant};
-use time::macros::format_description;
 use time::Date;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,9 +40,10 @@ pub enum AssignState {
 }
 
 pub struct AssignStaff {
+    db: Db,
     staff: Vec<StaffMember>,
     filtered_staff: Vec<StaffMember>,
-    search_input: String,
+    search_input: TextInput,
     is_searching: bool,
     table_state: TableState,
     shift_list_state: ListState,
@@ -41,23 +53,29 @@ pub struct AssignStaff {
     selected_shift: Option<Shift>,
     show_confirmation: bool,
     confirmation_selected: usize,
-    error_message: Option<String>,
-    error_timer: Option<Instant>,
-    success_message: Option<String>,
-    success_timer: Option<Instant>,
     staff_assignments: Vec<(Date, String)>,
-    focused_month: usize,
+    date_picker: DatePicker,
+    print_dialog: Option<PrintDialog>,
+    roster_reset: Option<RosterReset>,
+    absence: Option<StaffAbsence>,
+}
+
+fn shift_date_picker() -> DatePicker {
+    DatePicker::today()
+        .with_months(6)
+        .with_min(datetime::today())
 }
 
 impl AssignStaff {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
         let mut shift_list_state = ListState::default();
         shift_list_state.select(Some(0));
 
         Self {
+            db: db.clone(),
             staff: Vec::new(),
             filtered_staff: Vec::new(),
-            search_input: String::new(),
+            search_input: TextInput::new(),
             is_searching: false,
             table_state: TableState::default(),
             shift_list_state,
@@ -66,18 +84,17 @@ impl AssignStaff {
             selected_date: None,
             selected_shift: None,
             show_confirmation: false,
-            error_message: None,
-            error_timer: None,
-            success_message: None,
-            success_timer: None,
             staff_assignments: Vec::new(),
-            focused_month: 0,
+            date_picker: shift_date_picker(),
             confirmation_selected: 0,
+            print_dialog: None,
+            roster_reset: None,
+            absence: None,
         }
     }
 
     pub fn fetch_staff(&mut self) -> Result<()> {
-        self.staff = db::get_all_staff()?;
+        self.staff = db::get_all_staff(&self.db)?;
         self.filter_staff();
 
         if !self.filtered_staff.is_empty() {
@@ -90,7 +107,7 @@ impl AssignStaff {
     }
 
     fn fetch_staff_assignments(&mut self, staff_id: i64) -> Result<()> {
-        match db::get_assigned_shifts_for_staff(staff_id) {
+        match db::get_assigned_shifts_for_staff(&self.db, staff_id) {
             Ok(assignments) => {
                 self.staff_assignments = assignments;
                 Ok(())
@@ -103,14 +120,14 @@ impl AssignStaff {
         if self.search_input.is_empty() {
             self.filtered_staff = self.staff.clone();
         } else {
-            let search_term = self.search_input.to_lowercase();
+            let search_term = self.search_input.value().to_lowercase();
             self.filtered_staff = self
                 .staff
                 .iter()
                 .filter(|s| {
                     s.name.to_lowercase().contains(&search_term)
                         || s.id.to_string().contains(&search_term)
-                        || s.phone_number.to_lowercase().contains(&search_term)
+                        || contact::phone_matches(&s.phone_number, &search_term)
                 })
                 .cloned()
                 .collect();
@@ -163,138 +180,19 @@ impl AssignStaff {
                 self.selected_staff = Some(self.filtered_staff[selected].clone());
                 self.assign_state = AssignState::SelectingDate;
 
-                if self.selected_date.is_none() {
-                    self.selected_date = Some(time::OffsetDateTime::now_utc().date());
+                self.date_picker = shift_date_picker();
+                if let Some(date) = self.selected_date {
+                    self.date_picker.set_selected(date);
                 }
-
-                self.focused_month = 0;
+                self.selected_date = Some(self.date_picker.selected());
 
                 self.fetch_staff_assignments(self.filtered_staff[selected].id)?;
 
                 return Ok(());
             }
         }
-        self.set_error("No staff selected".to_string());
-        Err(anyhow::anyhow!("No staff selected"))
-    }
-
-    fn cycle_month_focus(&mut self) {
-        self.focused_month = (self.focused_month + 1) % 6;
-
-        let today = time::OffsetDateTime::now_utc().date();
-        let mut current_date = today;
-
-        for _ in 0..self.focused_month {
-            if let Some(next_month) = current_date.checked_add(time::Duration::days(32)) {
-                current_date =
-                    time::Date::from_calendar_date(next_month.year(), next_month.month(), 1)
-                        .unwrap_or(next_month);
-            }
-        }
-
-        self.selected_date = Some(
-            time::Date::from_calendar_date(current_date.year(), current_date.month(), 1)
-                .unwrap_or(current_date),
-        );
-    }
-
-    fn navigate_date(&mut self, direction: &str) {
-        if let Some(date) = self.selected_date {
-            let new_date = match direction {
-                "left" => {
-                    if date.day() > 1 {
-                        if let Some(prev) = date.previous_day() {
-                            prev
-                        } else {
-                            date
-                        }
-                    } else {
-                        let days_in_month = self.get_days_in_month(date.year(), date.month());
-                        time::Date::from_calendar_date(date.year(), date.month(), days_in_month)
-                            .unwrap_or(date)
-                    }
-                }
-                "right" => {
-                    let days_in_month = self.get_days_in_month(date.year(), date.month());
-                    if date.day() < days_in_month {
-                        if let Some(next) = date.next_day() {
-                            next
-                        } else {
-                            date
-                        }
-                    } else {
-                        time::Date::from_calendar_date(date.year(), date.month(), 1).unwrap_or(date)
-                    }
-                }
-                "up" => {
-                    let new_day = if date.day() > 7 {
-                        date.day() - 7
-                    } else {
-                        let days_in_month = self.get_days_in_month(date.year(), date.month());
-                        let offset = 7 - date.day();
-                        if days_in_month >= date.day() + (7 - offset) {
-                            days_in_month - (7 - offset)
-                        } else {
-                            days_in_month
-                        }
-                    };
-
-                    time::Date::from_calendar_date(date.year(), date.month(), new_day)
-                        .unwrap_or(date)
-                }
-                "down" => {
-                    let days_in_month = self.get_days_in_month(date.year(), date.month());
-                    let new_day = if date.day() + 7 <= days_in_month {
-                        date.day() + 7
-                    } else {
-                        date.day() + 7 - days_in_month
-                    };
-
-                    time::Date::from_calendar_date(date.year(), date.month(), new_day)
-                        .unwrap_or(date)
-                }
-                _ => date,
-            };
-
-            self.selected_date = Some(new_date);
-
-            let today = time::OffsetDateTime::now_utc().date();
-            let mut current_date = today;
-            let mut month_index = 0;
-
-            for i in 0..6 {
-                if new_date.year() == current_date.year()
-                    && new_date.month() == current_date.month()
-                {
-                    month_index = i;
-                    break;
-                }
-
-                if let Some(next_month) = current_date.checked_add(time::Duration::days(32)) {
-                    current_date =
-                        time::Date::from_calendar_date(next_month.year(), next_month.month(), 1)
-                            .unwrap_or(next_month);
-                }
-            }
-
-            self.focused_month = month_index;
-        } else {
-            self.selected_date = Some(time::OffsetDateTime::now_utc().date());
-        }
-    }
-
-    fn get_days_in_month(&self, year: i32, month: time::Month) -> u8 {
-        let current = time::Date::from_calendar_date(year, month, 1).unwrap();
-        let next_month = if month == time::Month::December {
-            time::Date::from_calendar_date(year + 1, time::Month::January, 1).unwrap()
-        } else {
-            let next_month_num = month as u8 + 1;
-            let next_month = time::Month::try_from(next_month_num).unwrap();
-            time::Date::from_calendar_date(year, next_month, 1).unwrap()
-        };
-
-        let days_diff = next_month.to_julian_day() - current.to_julian_day();
-        days_diff as u8
+        toast::push(Toast::error(t("assign.no_staff")));
+        Err(anyhow::anyhow!(t("assign.no_staff")))
     }
 
     fn assign_shift(&mut self) -> Result<()> {
@@ -309,21 +207,25 @@ impl AssignStaff {
                 Shift::Night => "Night",
             };
 
-            match db::assign_staff_shift(staff.id, date, shift_str) {
+            match db::assign_staff_shift(&self.db, staff.id, date, shift_str) {
                 Ok(_) => {
-                    self.success_message =
-                        Some(format!("Shift assigned to {} successfully!", staff.name));
-                    self.success_timer = Some(Instant::now());
+                    toast::push(Toast::success(match calendar::holiday(*date) {
+                        Some(holiday) => tf(
+                            "assign.assigned_holiday",
+                            &[("name", &staff.name), ("holiday", &holiday)],
+                        ),
+                        None => tf("assign.assigned", &[("name", &staff.name)]),
+                    }));
                     self.reset();
                     Ok(())
                 }
                 Err(e) => {
-                    self.set_error(format!("Database error: {}", e));
+                    toast::push(Toast::error(tf("common.database_error", &[("error", &e)])));
                     Err(e)
                 }
             }
         } else {
-            self.set_error("Please select staff, date, and shift.".to_string());
+            toast::push(Toast::error(t("assign.select_all")));
             Err(anyhow::anyhow!(
                 "Staff, date, or shift not selected for assignment."
             ))
@@ -336,56 +238,114 @@ impl AssignStaff {
         self.selected_shift = None;
         self.assign_state = AssignState::SelectingStaff;
         self.show_confirmation = false;
-        self.clear_error();
-        self.clear_success();
         self.staff_assignments.clear();
-        self.focused_month = 0;
+        self.date_picker = shift_date_picker();
 
-        if let Ok(staff) = db::get_all_staff() {
+        if let Ok(staff) = db::get_all_staff(&self.db) {
             self.staff = staff;
             self.filter_staff();
         }
     }
 
-    fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_timer = Some(Instant::now());
-    }
-
-    fn clear_error(&mut self) {
-        self.error_message = None;
-        self.error_timer = None;
-    }
-
-    fn clear_success(&mut self) {
-        self.success_message = None;
-        self.success_timer = None;
-    }
-
-    fn check_error_timeout(&mut self) {
-        if let Some(timer) = self.error_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_error();
+    /// Prints the coming week's roster for all staff.
+    fn print_week_roster(&mut self) {
+        let today = datetime::today();
+        let end = today + time::Duration::days(6);
+        let (from, to) = (datetime::format_date(today), datetime::format_date(end));
+        match db::get_roster(&self.db, &from, &to) {
+            Ok(roster) => {
+                let mut lines = vec![
+                    "RUSTORIA HOSPITAL - STAFF ROSTER".to_string(),
+                    "=".repeat(48),
+                    format!("Week:  {} to {}", from, to),
+                ];
+                let mut day = None;
+                for (date, shift, name) in &roster {
+                    if day != Some(date) {
+                        lines.push(String::new());
+                        lines.push(date.clone());
+                        day = Some(date);
+                    }
+                    let premium = datetime::parse_date(date)
+                        .and_then(calendar::holiday)
+                        .map(|holiday| format!("  * holiday premium ({})", holiday))
+                        .unwrap_or_default();
+                    lines.push(format!("  {:<12}{}{}", shift, name, premium));
+                }
+                if roster.is_empty() {
+                    lines.push(String::new());
+                    lines.push("No shifts assigned".to_string());
+                }
+                lines.push(String::new());
+                self.print_dialog = Some(PrintDialog::new(
+                    &self.db,
+                    tf("assign.roster_title", &[("from", &from)]),
+                    lines.join("\n"),
+                ));
             }
+            Err(e) => toast::push(Toast::error(tf("assign.roster_failed", &[("error", &e)]))),
         }
     }
 
-    fn check_success_timeout(&mut self) {
-        if let Some(timer) = self.success_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_success();
-            }
+    /// Prints the shifts listed for the selected staff member.
+    fn print_staff_shifts(&mut self) {
+        let Some(staff) = &self.selected_staff else {
+            return;
+        };
+        let mut lines = vec![
+            "RUSTORIA HOSPITAL - SHIFT SCHEDULE".to_string(),
+            "=".repeat(48),
+            format!("Staff:  {}", staff.name),
+            String::new(),
+        ];
+        for (date, shift) in &self.staff_assignments {
+            let hours = match shift.as_str() {
+                "Morning" => "6am - 2pm",
+                "Afternoon" => "2pm - 10pm",
+                "Night" => "10pm - 6am",
+                _ => "",
+            };
+            let premium = calendar::holiday(*date)
+                .map(|holiday| format!("  * holiday premium ({})", holiday))
+                .unwrap_or_default();
+            lines.push(format!(
+                "  {}  {:<12}{:<12}{}",
+                datetime::format_date(*date),
+                shift,
+                hours,
+                premium
+            ));
         }
-    }
-
-    fn check_timeouts(&mut self) {
-        self.check_error_timeout();
-        self.check_success_timeout();
+        if self.staff_assignments.is_empty() {
+            lines.push("No shifts assigned".to_string());
+        }
+        lines.push(String::new());
+        self.print_dialog = Some(PrintDialog::new(
+            &self.db,
+            tf("assign.shifts_for", &[("name", &staff.name)]),
+            lines.join("\n"),
+        ));
     }
 
     fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
-        self.check_timeouts();
-
+        if let Some(dialog) = self.print_dialog.as_mut() {
+            if dialog.handle_key(key) {
+                self.print_dialog = None;
+            }
+            return Ok(None);
+        }
+        if let Some(reset) = self.roster_reset.as_mut() {
+            if reset.handle_key(key) {
+                self.roster_reset = None;
+            }
+            return Ok(None);
+        }
+        if let Some(absence) = self.absence.as_mut() {
+            if absence.handle_key(key) {
+                self.absence = None;
+            }
+            return Ok(None);
+        }
         if self.show_confirmation {
             match key.code {
                 KeyCode::Left | KeyCode::Right => {
@@ -407,15 +367,8 @@ impl AssignStaff {
 
         match self.assign_state {
             AssignState::SelectingStaff => match key.code {
-                KeyCode::Char(c) if self.is_searching => {
-                    self.search_input.push(c);
-                    self.filter_staff();
-                    self.clear_error();
-                }
-                KeyCode::Backspace if self.is_searching => {
-                    self.search_input.pop();
+                _ if self.is_searching && self.search_input.handle_key(key) => {
                     self.filter_staff();
-                    self.clear_error();
                 }
                 KeyCode::Down if self.is_searching && !self.filtered_staff.is_empty() => {
                     self.is_searching = false;
@@ -447,15 +400,45 @@ impl AssignStaff {
                             if let Err(e) =
                                 self.fetch_staff_assignments(self.filtered_staff[selected].id)
                             {
-                                self.set_error(format!("Failed to load assignments: {}", e));
+                                toast::push(Toast::error(tf(
+                                    "assign.load_failed",
+                                    &[("error", &e)],
+                                )));
                             } else {
                                 self.assign_state = AssignState::ViewingAssignments;
                             }
                         } else {
-                            self.set_error("No staff selected".to_string());
+                            toast::push(Toast::error(t("assign.no_staff")));
                         }
                     } else {
-                        self.set_error("No staff selected".to_string());
+                        toast::push(Toast::error(t("assign.no_staff")));
+                    }
+                }
+
+                KeyCode::Char('p') | KeyCode::Char('P') if !self.is_searching => {
+                    self.print_week_roster();
+                }
+
+                KeyCode::Char('l') | KeyCode::Char('L') if !self.is_searching => {
+                    match self
+                        .table_state
+                        .selected()
+                        .and_then(|i| self.filtered_staff.get(i))
+                    {
+                        Some(staff) => {
+                            self.absence = Some(StaffAbsence::new(&self.db, staff.clone()))
+                        }
+                        None => toast::push(Toast::error(t("assign.no_staff"))),
+                    }
+                }
+
+                KeyCode::Char('x') | KeyCode::Char('X') if !self.is_searching => {
+                    let is_admin = ui_state::current_user()
+                        .is_some_and(|user| auth::is_admin(&self.db, &user));
+                    if is_admin {
+                        self.roster_reset = Some(RosterReset::new(&self.db));
+                    } else {
+                        toast::push(Toast::error(t("assign.admins_only")));
                     }
                 }
 
@@ -465,20 +448,8 @@ impl AssignStaff {
                 _ => {}
             },
             AssignState::SelectingDate => match key.code {
-                KeyCode::Left => {
-                    self.navigate_date("left");
-                }
-                KeyCode::Right => {
-                    self.navigate_date("right");
-                }
-                KeyCode::Up => {
-                    self.navigate_date("up");
-                }
-                KeyCode::Down => {
-                    self.navigate_date("down");
-                }
-                KeyCode::Tab => {
-                    self.cycle_month_focus();
+                _ if self.date_picker.handle_key(key) => {
+                    self.selected_date = Some(self.date_picker.selected());
                 }
                 KeyCode::Enter => {
                     if self.selected_date.is_some() {
@@ -487,7 +458,7 @@ impl AssignStaff {
                         self.selected_shift = Some(Shift::Morning);
                         self.shift_list_state.select(Some(0));
                     } else {
-                        self.selected_date = Some(time::OffsetDateTime::now_utc().date());
+                        self.selected_date = Some(datetime::today());
                     }
                 }
                 KeyCode::Esc => {
@@ -538,6 +509,7 @@ impl AssignStaff {
                 KeyCode::Esc => {
                     self.assign_state = AssignState::SelectingStaff;
                 }
+                KeyCode::Char('p') | KeyCode::Char('P') => self.print_staff_shifts(),
                 _ => {}
             },
             AssignState::Confirming => {}
@@ -547,12 +519,6 @@ impl AssignStaff {
     }
 }
 
-impl Default for AssignStaff {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl Component for AssignStaff {
     fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
         self.handle_input(event)
@@ -570,6 +536,15 @@ impl Component for AssignStaff {
         if self.show_confirmation {
             self.render_confirmation_dialog(frame);
         }
+        if let Some(reset) = &self.roster_reset {
+            reset.render(frame);
+        }
+        if let Some(absence) = &self.absence {
+            absence.render(frame);
+        }
+        if let Some(dialog) = &self.print_dialog {
+            dialog.render_popup(frame);
+        }
     }
 }
 
@@ -577,7 +552,7 @@ impl AssignStaff {
     fn render_staff_selection(&self, frame: &mut Frame) {
         let area = frame.area();
 
-        let background = Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28)));
+        let background = Block::default().style(Style::default().bg(theme().background));
         frame.render_widget(background, area);
 
         for y in (0..area.height).step_by(4) {
@@ -585,7 +560,7 @@ impl AssignStaff {
                 if (x + y) % 8 == 0 {
                     let pos = (area.x + x, area.y + y);
                     if pos.0 < frame.area().width && pos.1 < frame.area().height {
-                        frame.buffer_mut()[pos].set_bg(Color::Rgb(20, 20, 32));
+                        frame.buffer_mut()[pos].set_bg(theme().background);
                     }
                 }
             }
@@ -605,16 +580,16 @@ impl AssignStaff {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
 
-        let title = Paragraph::new(" 📅 SELECT STAFF TO ASSIGN SHIFT")
+        let title = Paragraph::new(format!(" {}", t("assign.title")))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
@@ -623,24 +598,20 @@ impl AssignStaff {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Staff ",
+                format!(" {} ", t("common.search_staff")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(75, 75, 120))
+                Style::default().fg(theme().border)
             })
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .style(Style::default().bg(theme().surface));
 
-        let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(22, 22, 35)),
-            )
+        let search_paragraph = Paragraph::new(self.search_input.line(self.is_searching))
+            .style(Style::default().fg(theme().text).bg(theme().surface))
             .block(search_block);
         frame.render_widget(search_paragraph, layout[1]);
 
@@ -649,57 +620,73 @@ impl AssignStaff {
             .border_type(BorderType::Rounded)
             .title(if !self.search_input.is_empty() {
                 format!(
-                    " Staff ({} of {} matches) ",
-                    self.filtered_staff.len(),
-                    self.staff.len()
+                    " {} ",
+                    tf(
+                        "common.staff_matches",
+                        &[
+                            ("shown", &self.filtered_staff.len()),
+                            ("total", &self.staff.len())
+                        ]
+                    )
                 )
             } else {
-                format!(" Staff ({}) ", self.staff.len())
+                format!(
+                    " {} ",
+                    tf("common.staff_count", &[("total", &self.staff.len())])
+                )
             })
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
-            .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .border_style(Style::default().fg(theme().unfocused))
+            .style(Style::default().bg(theme().input));
 
         let selected_style = Style::default()
-            .fg(Color::Rgb(250, 250, 110))
-            .bg(Color::Rgb(40, 40, 60))
+            .fg(theme().focus)
+            .bg(theme().highlight)
             .add_modifier(Modifier::BOLD);
-        let normal_style = Style::default()
-            .bg(Color::Rgb(26, 26, 36))
-            .fg(Color::Rgb(220, 220, 240));
+        let normal_style = Style::default().bg(theme().input).fg(theme().text);
+
+        let widths = [
+            Constraint::Length(6),
+            Constraint::Percentage(35),
+            Constraint::Percentage(25),
+            Constraint::Percentage(40),
+        ];
+        let columns = column_widths(layout[2].inner(Margin::new(1, 1)), &widths, 1, "► ");
 
         let mut rows = Vec::new();
         for staff_member in &self.filtered_staff {
             rows.push(Row::new(vec![
-                Cell::from(staff_member.id.to_string()).style(normal_style),
-                Cell::from(staff_member.name.clone()).style(normal_style),
+                cell(staff_member.id.to_string(), &columns, 0).style(normal_style),
+                cell(&staff_member.name, &columns, 1).style(normal_style),
                 Cell::from(match staff_member.role {
-                    crate::models::StaffRole::Doctor => "Doctor",
-                    crate::models::StaffRole::Nurse => "Nurse",
-                    crate::models::StaffRole::Admin => "Admin",
-                    crate::models::StaffRole::Technician => "Technician",
+                    crate::models::StaffRole::Doctor => t("staff.role.doctor"),
+                    crate::models::StaffRole::Nurse => t("staff.role.nurse"),
+                    crate::models::StaffRole::Admin => t("staff.role.admin"),
+                    crate::models::StaffRole::Technician => t("staff.role.technician"),
+                    crate::models::StaffRole::Accountant => t("staff.role.accountant"),
                 })
                 .style(normal_style),
-                Cell::from(staff_member.phone_number.clone()).style(normal_style),
+                cell(&staff_member.phone_number, &columns, 3).style(normal_style),
             ]));
         }
 
         if self.filtered_staff.is_empty() {
             let message = if self.search_input.is_empty() {
-                "No staff found in database"
+                t("common.no_staff")
             } else {
-                "No staff match your search criteria"
+                t("common.no_staff_matches")
             };
 
             let table_width = layout[2].width as usize - 4;
             let padded_message = format!("{:^width$}", message, width = table_width);
             rows.push(
-                Row::new(vec![Cell::from(padded_message)
-                    .style(Style::default().fg(Color::Rgb(180, 180, 200)))])
+                Row::new(vec![
+                    Cell::from(padded_message).style(Style::default().fg(theme().inactive))
+                ])
                 .height(2),
             );
         }
@@ -708,17 +695,15 @@ impl AssignStaff {
             (vec![Constraint::Percentage(100)], vec![Cell::from("")])
         } else {
             (
+                widths.to_vec(),
                 vec![
-                    Constraint::Length(6),
-                    Constraint::Percentage(35),
-                    Constraint::Percentage(25),
-                    Constraint::Percentage(40),
-                ],
-                vec![
-                    Cell::from("ID").style(Style::default().add_modifier(Modifier::BOLD)),
-                    Cell::from("Name").style(Style::default().add_modifier(Modifier::BOLD)),
-                    Cell::from("Role").style(Style::default().add_modifier(Modifier::BOLD)),
-                    Cell::from("Phone").style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.id")).style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.name"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.role"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.phone"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
                 ],
             )
         };
@@ -726,11 +711,7 @@ impl AssignStaff {
         let table = Table::new(rows, constraints.clone())
             .header(
                 Row::new(header_cells)
-                    .style(
-                        Style::default()
-                            .bg(Color::Rgb(80, 60, 130))
-                            .fg(Color::Rgb(180, 180, 250)),
-                    )
+                    .style(Style::default().bg(theme().header).fg(theme().title))
                     .height(1),
             )
             .block(table_block)
@@ -740,34 +721,14 @@ impl AssignStaff {
         let mut table_state_copy = self.table_state.clone();
         frame.render_stateful_widget(table, layout[2], &mut table_state_copy);
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(240, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, layout[3]);
-        } else if let Some(success) = &self.success_message {
-            let success_paragraph = Paragraph::new(success.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, layout[3]);
-        }
-
         let help_text = if self.is_searching {
-            "Type to search | ↓: To results | Esc: Cancel search"
+            t("common.search_help")
         } else {
-            "/ or s: Search | ↑/↓: Navigate | Enter: Select staff | v: View assignments | Esc: Back"
+            t("assign.help")
         };
 
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(180, 180, 200)))
+            .style(Style::default().fg(theme().inactive))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, layout[4]);
     }
@@ -775,7 +736,7 @@ impl AssignStaff {
     fn render_date_selection(&self, frame: &mut Frame) {
         let area = frame.area();
 
-        let background = Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28)));
+        let background = Block::default().style(Style::default().bg(theme().background));
         frame.render_widget(background, area);
 
         for y in (0..area.height).step_by(4) {
@@ -783,7 +744,7 @@ impl AssignStaff {
                 if (x + y) % 8 == 0 {
                     let pos = (area.x + x, area.y + y);
                     if pos.0 < frame.area().width && pos.1 < frame.area().height {
-                        frame.buffer_mut()[pos].set_bg(Color::Rgb(20, 20, 32));
+                        frame.buffer_mut()[pos].set_bg(theme().background);
                     }
                 }
             }
@@ -794,25 +755,25 @@ impl AssignStaff {
             .constraints([
                 Constraint::Length(3),
                 Constraint::Min(18),
-                Constraint::Length(2),
+                Constraint::Length(if is_short(area) { 1 } else { 2 }),
                 Constraint::Length(1),
                 Constraint::Length(2),
             ])
-            .margin(2)
+            .margin(if is_short(area) { 1 } else { 2 })
             .split(area);
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
 
-        let title = Paragraph::new("📅 SELECT DATE")
+        let title = Paragraph::new(t("assign.date_title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
@@ -820,261 +781,99 @@ impl AssignStaff {
         let calendar_area = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .title(" Calendar ")
+            .title(format!(" {} ", t("assign.calendar")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
-            .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .border_style(Style::default().fg(theme().unfocused))
+            .style(Style::default().bg(theme().surface));
 
         let calendar_inner = calendar_area.inner(layout[1]);
         frame.render_widget(calendar_area, layout[1]);
 
-        let calendar_rows = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(calendar_inner);
-
-        let top_row = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(33),
-                Constraint::Percentage(33),
-                Constraint::Percentage(34),
-            ])
-            .split(calendar_rows[0]);
-
-        let bottom_row = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(33),
-                Constraint::Percentage(33),
-                Constraint::Percentage(34),
-            ])
-            .split(calendar_rows[1]);
-
-        let today = time::OffsetDateTime::now_utc().date();
-        let mut selected_date = self.selected_date.unwrap_or(today);
-
-        let mut first_available_date = today;
-        for _ in 0..365 {
-            if first_available_date >= today {
-                break;
-            }
-            if let Some(next_day) = first_available_date.next_day() {
-                first_available_date = next_day;
-            } else {
-                break;
-            }
-        }
-
-        if selected_date < today {
-            selected_date = first_available_date;
-        }
-
-        let mut events = CalendarEventStore::default();
-
-        events.add(
-            selected_date,
-            Style::default()
-                .fg(Color::Rgb(250, 250, 250))
-                .bg(Color::Rgb(150, 80, 180))
-                .add_modifier(Modifier::BOLD),
-        );
-
-        events.add(
-            today,
-            Style::default()
-                .fg(Color::Rgb(250, 250, 250))
-                .bg(Color::Rgb(40, 120, 50))
-                .add_modifier(Modifier::BOLD),
-        );
-
-        for (date, shift) in &self.staff_assignments {
-            let shift_style = match shift.as_str() {
-                "Morning" => Style::default().fg(Color::Rgb(20, 20, 50)).bg(Color::Cyan),
-                "Afternoon" => Style::default().fg(Color::Rgb(20, 20, 50)).bg(Color::Cyan),
-                "Night" => Style::default().fg(Color::Rgb(20, 20, 50)).bg(Color::Cyan),
-
-                _ => Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(40, 40, 40)),
-            };
-            events.add(*date, shift_style);
-        }
-
-        let default_style = Style::default()
-            .fg(Color::Rgb(220, 220, 240))
-            .bg(Color::Rgb(26, 26, 36));
-
-        let past_date_style = Style::default()
-            .fg(Color::DarkGray)
-            .bg(Color::Rgb(26, 26, 36));
-
-        let mut month_dates = Vec::new();
-        let mut current_date = today;
-
-        month_dates.push(current_date);
-
-        for _ in 0..5 {
-            if let Some(next_month) = current_date.checked_add(time::Duration::days(32)) {
-                current_date =
-                    time::Date::from_calendar_date(next_month.year(), next_month.month(), 1)
-                        .unwrap_or(next_month);
-                month_dates.push(current_date);
-            }
-        }
-        let apply_date_styles = |date: Date, events: &mut CalendarEventStore| {
-            if date < today {
-                events.add(date, past_date_style);
-            }
-        };
-
-        let start_date = today.checked_sub(time::Duration::days(60)).unwrap_or(today);
-        let end_date = today
-            .checked_add(time::Duration::days(180))
-            .unwrap_or(today);
-
-        let mut style_date = start_date;
-        while style_date <= end_date {
-            apply_date_styles(style_date, &mut events);
-
-            if let Some(next_day) = style_date.checked_add(time::Duration::days(1)) {
-                style_date = next_day;
-            } else {
-                break;
-            }
-        }
-
-        for (i, month_date) in month_dates.iter().take(3).enumerate() {
-            let month_name = format!(" {} {} ", month_date.month(), month_date.year());
-
-            let border_style = if i == self.focused_month {
-                Style::default().fg(Color::Rgb(250, 250, 110))
-            } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
-            };
-
-            let month = Monthly::new(*month_date, events.clone())
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .border_type(BorderType::Rounded)
-                        .title(month_name)
-                        .title_style(
-                            Style::default()
-                                .fg(Color::Rgb(230, 230, 250))
-                                .add_modifier(Modifier::BOLD),
-                        )
-                        .border_style(border_style)
-                        .style(Style::default().bg(Color::Rgb(26, 26, 36))),
-                )
-                .show_month_header(
-                    Style::default()
-                        .fg(Color::Rgb(230, 230, 250))
-                        .bg(Color::Rgb(60, 60, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .show_weekdays_header(
-                    Style::default()
-                        .fg(Color::Black)
-                        .bg(Color::Rgb(250, 250, 110))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .default_style(default_style);
-
-            frame.render_widget(month, top_row[i]);
-        }
-
-        for (i, month_date) in month_dates.iter().skip(3).take(3).enumerate() {
-            let month_name = format!(" {} {} ", month_date.month(), month_date.year());
-
-            let border_style = if i + 3 == self.focused_month {
-                Style::default().fg(Color::Rgb(250, 250, 110))
-            } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
-            };
-
-            let month = Monthly::new(*month_date, events.clone())
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .border_type(BorderType::Rounded)
-                        .title(month_name)
-                        .title_style(
-                            Style::default()
-                                .fg(Color::Rgb(230, 230, 250))
-                                .add_modifier(Modifier::BOLD),
-                        )
-                        .border_style(border_style)
-                        .style(Style::default().bg(Color::Rgb(26, 26, 36))),
+        let assigned: Vec<(Date, Style)> = self
+            .staff_assignments
+            .iter()
+            .map(|(date, _)| {
+                (
+                    *date,
+                    Style::default().fg(theme().background).bg(theme().accent),
                 )
-                .show_month_header(
-                    Style::default()
-                        .fg(Color::Rgb(230, 230, 250))
-                        .bg(Color::Rgb(60, 60, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .show_weekdays_header(
-                    Style::default()
-                        .fg(Color::Black)
-                        .bg(Color::Rgb(250, 250, 110))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .default_style(default_style);
-
-            frame.render_widget(month, bottom_row[i]);
-        }
-
-        let legend_layout = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(20),
-                Constraint::Percentage(20),
-                Constraint::Percentage(20),
-                Constraint::Percentage(20),
-                Constraint::Percentage(20),
-            ])
-            .split(layout[2]);
-
-        let today_legend = Paragraph::new(" ● Today ")
-            .style(Style::default().fg(Color::Rgb(40, 120, 50)))
-            .alignment(Alignment::Center);
+            })
+            .collect();
+        self.date_picker.render(frame, calendar_inner, &assigned);
+
+        if is_narrow(area) || is_short(area) {
+            let legend = Line::from(vec![
+                Span::styled(
+                    format!("{}  ", t("assign.legend_today")),
+                    Style::default().fg(theme().success),
+                ),
+                Span::styled(
+                    format!("{}  ", t("assign.legend_selected")),
+                    Style::default().fg(theme().header),
+                ),
+                Span::styled(
+                    format!("{}  ", t("assign.legend_weekend")),
+                    Style::default().fg(theme().error),
+                ),
+                Span::styled(
+                    format!("{}  ", t("assign.legend_assigned")),
+                    Style::default().fg(theme().accent),
+                ),
+                Span::styled(
+                    t("assign.legend_holiday"),
+                    Style::default().fg(theme().warning),
+                ),
+            ]);
+            frame.render_widget(
+                Paragraph::new(legend).alignment(Alignment::Center),
+                layout[2],
+            );
+        } else {
+            let legend_layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Percentage(20),
+                    Constraint::Percentage(20),
+                    Constraint::Percentage(20),
+                    Constraint::Percentage(20),
+                    Constraint::Percentage(20),
+                ])
+                .split(layout[2]);
 
-        let selected_legend = Paragraph::new(" ● Selected ")
-            .style(Style::default().fg(Color::Rgb(150, 80, 180)))
-            .alignment(Alignment::Center);
+            let today_legend = Paragraph::new(format!(" {} ", t("assign.legend_today")))
+                .style(Style::default().fg(theme().success))
+                .alignment(Alignment::Center);
 
-        let weekend_legend = Paragraph::new(" ● Weekend ")
-            .style(Style::default().fg(Color::Rgb(255, 100, 100)))
-            .alignment(Alignment::Center);
+            let selected_legend = Paragraph::new(format!(" {} ", t("assign.legend_selected")))
+                .style(Style::default().fg(theme().header))
+                .alignment(Alignment::Center);
 
-        let assigned_legend = Paragraph::new(" ● Assigned ")
-            .style(Style::default().fg(Color::Cyan))
-            .alignment(Alignment::Center);
+            let weekend_legend = Paragraph::new(format!(" {} ", t("assign.legend_weekend")))
+                .style(Style::default().fg(theme().error))
+                .alignment(Alignment::Center);
 
-        frame.render_widget(today_legend, legend_layout[0]);
-        frame.render_widget(selected_legend, legend_layout[1]);
-        frame.render_widget(weekend_legend, legend_layout[2]);
-        frame.render_widget(assigned_legend, legend_layout[3]);
+            let assigned_legend = Paragraph::new(format!(" {} ", t("assign.legend_assigned")))
+                .style(Style::default().fg(theme().accent))
+                .alignment(Alignment::Center);
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(240, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
+            let holiday_legend = Paragraph::new(format!(" {} ", t("assign.legend_holiday")))
+                .style(Style::default().fg(theme().warning))
                 .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, layout[3]);
+
+            frame.render_widget(today_legend, legend_layout[0]);
+            frame.render_widget(selected_legend, legend_layout[1]);
+            frame.render_widget(weekend_legend, legend_layout[2]);
+            frame.render_widget(assigned_legend, legend_layout[3]);
+            frame.render_widget(holiday_legend, legend_layout[4]);
         }
 
-        let help_text =
-            "↑↓←→: Navigate within month | Tab: Switch month | Enter: Select date | Esc: Back";
+        let help_text = t("assign.date_help");
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(180, 180, 200)))
+            .style(Style::default().fg(theme().inactive))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, layout[4]);
     }
@@ -1082,7 +881,7 @@ impl AssignStaff {
     fn render_shift_selection(&self, frame: &mut Frame) {
         let area = frame.area();
 
-        let background = Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28)));
+        let background = Block::default().style(Style::default().bg(theme().background));
         frame.render_widget(background, area);
 
         for y in (0..area.height).step_by(4) {
@@ -1090,7 +889,7 @@ impl AssignStaff {
                 if (x + y) % 8 == 0 {
                     let pos = (area.x + x, area.y + y);
                     if pos.0 < frame.area().width && pos.1 < frame.area().height {
-                        frame.buffer_mut()[pos].set_bg(Color::Rgb(20, 20, 32));
+                        frame.buffer_mut()[pos].set_bg(theme().background);
                     }
                 }
             }
@@ -1109,29 +908,30 @@ impl AssignStaff {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
 
-        let title = Paragraph::new(" 🕒 SELECT SHIFT")
+        let title = Paragraph::new(format!(" {}", t("assign.shift_title")))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
 
         let info_text =
             if let (Some(staff), Some(date)) = (&self.selected_staff, &self.selected_date) {
-                let date_str = date
-                    .format(&format_description!("[year]-[month]-[day]"))
-                    .unwrap_or_else(|_| "Unknown date".to_string());
+                let date_str = datetime::format_date(*date);
 
-                format!("Assigning shift to: {} on {}", staff.name, date_str)
+                tf(
+                    "assign.assigning",
+                    &[("name", &staff.name), ("date", &date_str)],
+                )
             } else {
-                "Select a shift".to_string()
+                t("assign.select_shift").to_string()
             };
 
         let list_block = Block::default()
@@ -1140,11 +940,11 @@ impl AssignStaff {
             .title(Span::styled(
                 format!(" {} ", info_text),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
-            .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .border_style(Style::default().fg(theme().unfocused))
+            .style(Style::default().bg(theme().input));
 
         let inner_area = list_block.inner(layout[1]);
         frame.render_widget(list_block, layout[1]);
@@ -1156,16 +956,16 @@ impl AssignStaff {
             .split(inner_area);
 
         let header = Row::new(vec![
-            Cell::from("Shift").style(
+            Cell::from(t("handover.shift")).style(
                 Style::default()
-                    .fg(Color::Rgb(180, 180, 250))
-                    .bg(Color::Rgb(40, 40, 60))
+                    .fg(theme().title)
+                    .bg(theme().highlight)
                     .add_modifier(Modifier::BOLD),
             ),
-            Cell::from("Time").style(
+            Cell::from(t("kiosk.time")).style(
                 Style::default()
-                    .fg(Color::Rgb(180, 180, 250))
-                    .bg(Color::Rgb(40, 40, 60))
+                    .fg(theme().title)
+                    .bg(theme().highlight)
                     .add_modifier(Modifier::BOLD),
             ),
         ]);
@@ -1179,25 +979,28 @@ impl AssignStaff {
         frame.render_widget(header_table, header_layout[0]);
 
         let shift_items = [
-            ("🌅 Morning", "6am - 2pm"),
-            ("🌇 Afternoon", "2pm - 10pm"),
-            ("🌃 Night", "10pm - 6am"),
+            ("🌅", "Morning", "6am - 2pm"),
+            ("🌇", "Afternoon", "2pm - 10pm"),
+            ("🌃", "Night", "10pm - 6am"),
         ]
         .iter()
-        .map(|(shift, time)| {
+        .map(|(icon, shift, time)| {
             let shift_color = match *shift {
-                "🌅 Morning" => Color::Rgb(230, 180, 80),
-                "🌇 Afternoon" => Color::Rgb(230, 120, 80),
-                "🌃 Night" => Color::Rgb(100, 130, 200),
-                _ => Color::Rgb(220, 220, 240),
+                "Morning" => theme().warning,
+                "Afternoon" => theme().warning,
+                "Night" => theme().accent,
+                _ => theme().text,
             };
 
             ListItem::new(Line::from(vec![
-                Span::styled(format!("{:<18}", shift), Style::default().fg(shift_color)),
+                Span::styled(
+                    pad(&format!("{} {}", icon, shift_label(shift)), 18),
+                    Style::default().fg(shift_color),
+                ),
                 Span::raw("│ "),
                 Span::raw(time.to_string()),
             ]))
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
         })
         .collect::<Vec<ListItem>>();
 
@@ -1206,37 +1009,17 @@ impl AssignStaff {
         let list = List::new(shift_items)
             .highlight_style(
                 Style::default()
-                    .bg(Color::Rgb(60, 60, 100))
-                    .fg(Color::Rgb(250, 250, 110))
+                    .bg(theme().highlight)
+                    .fg(theme().focus)
                     .add_modifier(Modifier::BOLD),
             )
             .highlight_symbol("► ");
 
         frame.render_stateful_widget(list, header_layout[1], &mut list_state_copy);
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(240, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, layout[2]);
-        } else if let Some(success) = &self.success_message {
-            let success_paragraph = Paragraph::new(success.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, layout[2]);
-        }
-
-        let help_text = "↑/↓: Navigate | Enter: Select shift | Esc: Back";
+        let help_text = t("assign.shift_help");
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(180, 180, 200)))
+            .style(Style::default().fg(theme().inactive))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, layout[3]);
     }
@@ -1244,7 +1027,7 @@ impl AssignStaff {
     fn render_assigned_shifts(&self, frame: &mut Frame) {
         let area = frame.area();
 
-        let background = Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28)));
+        let background = Block::default().style(Style::default().bg(theme().background));
         frame.render_widget(background, area);
 
         for y in (0..area.height).step_by(4) {
@@ -1252,7 +1035,7 @@ impl AssignStaff {
                 if (x + y) % 8 == 0 {
                     let pos = (area.x + x, area.y + y);
                     if pos.0 < frame.area().width && pos.1 < frame.area().height {
-                        frame.buffer_mut()[pos].set_bg(Color::Rgb(20, 20, 32));
+                        frame.buffer_mut()[pos].set_bg(theme().background);
                     }
                 }
             }
@@ -1271,29 +1054,32 @@ impl AssignStaff {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
 
         let title = if let Some(staff) = &self.selected_staff {
             Paragraph::new(format!(
-                " 📋 SHIFTS ASSIGNED TO {} ",
-                staff.name.to_uppercase()
+                " {} ",
+                tf(
+                    "assign.assigned_to",
+                    &[("name", &staff.name.to_uppercase())]
+                )
             ))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center)
         } else {
-            Paragraph::new(" 📋 ASSIGNED SHIFTS ")
+            Paragraph::new(format!(" {} ", t("assign.assigned_title")))
                 .style(
                     Style::default()
-                        .fg(Color::Rgb(230, 230, 250))
+                        .fg(theme().title)
                         .add_modifier(Modifier::BOLD)
-                        .bg(Color::Rgb(16, 16, 28)),
+                        .bg(theme().background),
                 )
                 .alignment(Alignment::Center)
         };
@@ -1303,119 +1089,106 @@ impl AssignStaff {
             let table_block = Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .title(" Assigned Shifts ")
+                .title(format!(" {} ", t("assign.assigned_shifts")))
                 .title_style(
                     Style::default()
-                        .fg(Color::Rgb(230, 230, 250))
+                        .fg(theme().title)
                         .add_modifier(Modifier::BOLD),
                 )
-                .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-                .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+                .border_style(Style::default().fg(theme().unfocused))
+                .style(Style::default().bg(theme().input));
+
+            let normal_style = Style::default().bg(theme().input).fg(theme().text);
 
-            let normal_style = Style::default()
-                .bg(Color::Rgb(26, 26, 36))
-                .fg(Color::Rgb(220, 220, 240));
+            let widths = [
+                Constraint::Length(16),
+                Constraint::Length(14),
+                Constraint::Min(10),
+            ];
+            let columns = column_widths(layout[1].inner(Margin::new(1, 1)), &widths, 1, "");
 
             let rows = if self.staff_assignments.is_empty() {
                 vec![Row::new(vec![
                     Cell::from(""),
-                    Cell::from("No shifts assigned")
-                        .style(Style::default().fg(Color::Rgb(180, 180, 200))),
+                    Cell::from(t("assign.no_shifts")).style(Style::default().fg(theme().inactive)),
                     Cell::from(""),
                 ])]
             } else {
                 self.staff_assignments
                     .iter()
                     .map(|(date, shift)| {
-                        let date_str = date
-                            .format(&format_description!("[month repr:short] [day], [year]"))
-                            .unwrap_or_else(|_| "Unknown".to_string());
+                        let date_str = datetime::display_date(*date);
 
                         let (shift_style, shift_icon, time_range) = match shift.as_str() {
                             "Morning" => (
-                                Style::default()
-                                    .fg(Color::Rgb(230, 180, 80))
-                                    .bg(Color::Rgb(30, 30, 30)),
-                                "🌅 Morning",
+                                Style::default().fg(theme().warning).bg(theme().input),
+                                format!("🌅 {}", t("shift.morning")),
                                 "6am - 2pm",
                             ),
                             "Afternoon" => (
-                                Style::default()
-                                    .fg(Color::Rgb(230, 120, 80))
-                                    .bg(Color::Rgb(30, 30, 30)),
-                                "🌇 Afternoon",
+                                Style::default().fg(theme().warning).bg(theme().input),
+                                format!("🌇 {}", t("shift.afternoon")),
                                 "2pm - 10pm",
                             ),
                             "Night" => (
-                                Style::default()
-                                    .fg(Color::Rgb(100, 130, 200))
-                                    .bg(Color::Rgb(30, 30, 30)),
-                                "🌃 Night",
+                                Style::default().fg(theme().accent).bg(theme().input),
+                                format!("🌃 {}", t("shift.night")),
                                 "10pm - 6am",
                             ),
-                            _ => (normal_style, shift.as_ref(), ""),
+                            _ => (normal_style, shift.clone(), ""),
                         };
 
+                        let (time_range, time_style) = match calendar::holiday(*date) {
+                            Some(holiday) => (
+                                tf(
+                                    "assign.holiday_premium",
+                                    &[("time", &time_range), ("holiday", &holiday)],
+                                ),
+                                normal_style.fg(theme().warning),
+                            ),
+                            None => (time_range.to_string(), normal_style),
+                        };
                         Row::new(vec![
-                            Cell::from(date_str).style(normal_style),
-                            Cell::from(shift_icon).style(shift_style),
-                            Cell::from(time_range).style(normal_style),
+                            cell(date_str, &columns, 0).style(normal_style),
+                            cell(shift_icon, &columns, 1).style(shift_style),
+                            cell(time_range, &columns, 2).style(time_style),
                         ])
                     })
                     .collect()
             };
 
-            let table = Table::new(
-                rows,
-                [
-                    Constraint::Length(16),
-                    Constraint::Length(14),
-                    Constraint::Min(10),
-                ],
-            )
-            .header(
-                Row::new(vec![
-                    Cell::from("Date").style(Style::default().add_modifier(Modifier::BOLD)),
-                    Cell::from("Shift").style(Style::default().add_modifier(Modifier::BOLD)),
-                    Cell::from("Time").style(Style::default().add_modifier(Modifier::BOLD)),
-                ])
-                .style(
-                    Style::default()
-                        .bg(Color::Rgb(80, 60, 130))
-                        .fg(Color::Rgb(180, 180, 250)),
+            let table = Table::new(rows, widths)
+                .header(
+                    Row::new(vec![
+                        Cell::from(t("imaging.date"))
+                            .style(Style::default().add_modifier(Modifier::BOLD)),
+                        Cell::from(t("handover.shift"))
+                            .style(Style::default().add_modifier(Modifier::BOLD)),
+                        Cell::from(t("kiosk.time"))
+                            .style(Style::default().add_modifier(Modifier::BOLD)),
+                    ])
+                    .style(Style::default().bg(theme().header).fg(theme().title))
+                    .height(1),
                 )
-                .height(1),
-            )
-            .block(table_block);
+                .block(table_block);
 
             frame.render_widget(table, layout[1]);
         } else {
-            let message = Paragraph::new("No staff member selected")
-                .style(Style::default().fg(Color::Rgb(180, 180, 200)))
+            let message = Paragraph::new(t("assign.no_member"))
+                .style(Style::default().fg(theme().inactive))
                 .alignment(Alignment::Center)
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .style(Style::default().bg(Color::Rgb(26, 26, 36))),
+                        .style(Style::default().bg(theme().input)),
                 );
             frame.render_widget(message, layout[1]);
         }
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(240, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, layout[2]);
-        }
-
-        let help_text = "Esc: Back to staff selection";
+        let help_text = t("assign.assigned_help");
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(180, 180, 200)))
+            .style(Style::default().fg(theme().inactive))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, layout[3]);
     }
@@ -1435,16 +1208,16 @@ impl AssignStaff {
         frame.render_widget(Clear, dialog_area);
 
         let dialog_block = Block::default()
-            .title(" Confirm Assignment ")
+            .title(format!(" {} ", t("assign.confirm_title")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-            .style(Style::default().bg(Color::Rgb(30, 30, 46)));
+            .border_style(Style::default().fg(theme().unfocused))
+            .style(Style::default().bg(theme().dialog));
 
         frame.render_widget(dialog_block.clone(), dialog_area);
 
@@ -1460,30 +1233,32 @@ impl AssignStaff {
             .selected_staff
             .as_ref()
             .map(|s| s.name.clone())
-            .unwrap_or_else(|| "Unknown".to_string());
+            .unwrap_or_else(|| t("common.unknown_value").to_string());
 
         let date_str = self
             .selected_date
-            .map(|d| {
-                d.format(&format_description!("[year]-[month]-[day]"))
-                    .unwrap_or_else(|_| "Unknown".to_string())
-            })
-            .unwrap_or_else(|| "Unknown".to_string());
+            .map(datetime::format_date)
+            .unwrap_or_else(|| t("common.unknown_value").to_string());
 
         let (shift_str, shift_time) = match self.selected_shift {
-            Some(Shift::Morning) => ("🌅 Morning", "6am - 2pm"),
-            Some(Shift::Afternoon) => ("🌇 Afternoon", "2pm - 10pm"),
-            Some(Shift::Night) => ("🌃 Night", "10pm - 6am"),
-            None => ("Unknown", ""),
+            Some(Shift::Morning) => (format!("🌅 {}", t("shift.morning")), "6am - 2pm"),
+            Some(Shift::Afternoon) => (format!("🌇 {}", t("shift.afternoon")), "2pm - 10pm"),
+            Some(Shift::Night) => (format!("🌃 {}", t("shift.night")), "10pm - 6am"),
+            None => (t("common.unknown_value").to_string(), ""),
         };
 
-        let message_text = format!(
-            "Assign {} ({}) shift to {} on {}?",
-            shift_str, shift_time, staff_name, date_str
+        let message_text = tf(
+            "assign.confirm",
+            &[
+                ("shift", &shift_str),
+                ("time", &shift_time),
+                ("name", &staff_name),
+                ("date", &date_str),
+            ],
         );
 
         let message = Paragraph::new(message_text)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Center)
             .wrap(Wrap { trim: true });
         frame.render_widget(message, content_layout[0]);
@@ -1495,30 +1270,30 @@ impl AssignStaff {
 
         let yes_style = if self.confirmation_selected == 0 {
             Style::default()
-                .fg(Color::Rgb(140, 219, 140))
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let no_style = if self.confirmation_selected == 1 {
             Style::default()
-                .fg(Color::Rgb(255, 100, 100))
+                .fg(theme().error)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let yes_text = if self.confirmation_selected == 0 {
-            "► Yes ◄"
+            format!("► {} ◄", t("common.yes"))
         } else {
-            "  Yes  "
+            format!("  {}  ", t("common.yes"))
         };
 
         let no_text = if self.confirmation_selected == 1 {
-            "► No ◄"
+            format!("► {} ◄", t("common.no"))
         } else {
-            "  No  "
+            format!("  {}  ", t("common.no"))
         };
 
         let yes_button = Paragraph::new(yes_text)
@@ -1,51 +1,50 @@
 use crate::app::SelectedApp;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
-use crate::db;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
 use crate::models::StaffMember;
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::utils::contact;
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     prelude::*,
     widgets::{Block, BorderType, Borders, Cell, Clear, Paragraph, Row, Table, TableState},
 };
-use std::time::{Duration, Instant};
 
 pub struct DeleteStaff {
+    db: Db,
     staff: Vec<StaffMember>,
     filtered_staff: Vec<StaffMember>,
     selected_staff_ids: Vec<i64>,
-    search_input: String,
+    search_input: TextInput,
     is_searching: bool,
     table_state: TableState,
     show_confirmation: bool,
     confirmation_selected: usize,
-    error_message: Option<String>,
-    error_timer: Option<Instant>,
-    success_message: Option<String>,
-    success_timer: Option<Instant>,
 }
 
 impl DeleteStaff {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
         Self {
+            db: db.clone(),
             staff: Vec::new(),
             filtered_staff: Vec::new(),
             selected_staff_ids: Vec::new(),
-            search_input: String::new(),
+            search_input: TextInput::new(),
             is_searching: false,
             table_state: TableState::default(),
             show_confirmation: false,
             confirmation_selected: 1,
-            error_message: None,
-            error_timer: None,
-            success_message: None,
-            success_timer: None,
         }
     }
 
     pub fn fetch_staff(&mut self) -> Result<()> {
-        self.staff = db::get_all_staff()?;
+        self.staff = db::get_all_staff(&self.db)?;
         self.filter_staff();
 
         if !self.filtered_staff.is_empty() {
@@ -61,15 +60,15 @@ impl DeleteStaff {
         if self.search_input.is_empty() {
             self.filtered_staff = self.staff.clone();
         } else {
-            let search_term = self.search_input.to_lowercase();
+            let search_term = self.search_input.value().to_lowercase();
             self.filtered_staff = self
                 .staff
                 .iter()
                 .filter(|s| {
                     s.name.to_lowercase().contains(&search_term)
                         || s.id.to_string().contains(&search_term)
-                        || s.phone_number.to_lowercase().contains(&search_term)
-                        || s.address.to_lowercase().contains(&search_term)
+                        || contact::phone_matches(&s.phone_number, &search_term)
+                        || s.full_address().to_lowercase().contains(&search_term)
                 })
                 .cloned()
                 .collect();
@@ -86,8 +85,6 @@ impl DeleteStaff {
     }
 
     fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
-        self.check_timeouts();
-
         if self.show_confirmation {
             match key.code {
                 KeyCode::Left | KeyCode::Right => {
@@ -99,7 +96,7 @@ impl DeleteStaff {
                         let mut error_occurred = false;
 
                         for staff_id in &self.selected_staff_ids {
-                            match db::delete_staff_member(*staff_id) {
+                            match db::delete_staff_member(&self.db, *staff_id) {
                                 Ok(_) => deleted_count += 1,
                                 Err(_) => {
                                     error_occurred = true;
@@ -110,22 +107,20 @@ impl DeleteStaff {
                         self.selected_staff_ids.clear();
 
                         if error_occurred {
-                            self.set_error(format!(
-                                "Error during deletion. {} staff deleted successfully.",
-                                deleted_count
-                            ));
+                            toast::push(Toast::error(tf(
+                                "staff.delete.partial",
+                                &[("count", &deleted_count)],
+                            )));
                         } else if deleted_count > 0 {
-                            self.success_message = Some(format!(
-                                "{} staff member{} deleted successfully!",
-                                deleted_count,
-                                if deleted_count == 1 { "" } else { "s" }
-                            ));
-                            self.success_timer = Some(Instant::now());
+                            toast::push(Toast::success(tf(
+                                "staff.delete.deleted",
+                                &[("count", &deleted_count)],
+                            )));
                         } else {
-                            self.set_error("No staff were selected for deletion.".to_string());
+                            toast::push(Toast::error(t("staff.delete.none_selected")));
                         }
 
-                        if let Ok(staff) = db::get_all_staff() {
+                        if let Ok(staff) = db::get_all_staff(&self.db) {
                             self.staff = staff;
                             self.filter_staff();
 
@@ -151,12 +146,7 @@ impl DeleteStaff {
             }
         } else if self.is_searching {
             match key.code {
-                KeyCode::Char(c) => {
-                    self.search_input.push(c);
-                    self.filter_staff();
-                }
-                KeyCode::Backspace => {
-                    self.search_input.pop();
+                _ if self.search_input.handle_key(key) => {
                     self.filter_staff();
                 }
                 KeyCode::Enter | KeyCode::Down => {
@@ -227,7 +217,7 @@ impl DeleteStaff {
                         self.show_confirmation = true;
                         self.confirmation_selected = 1;
                     } else {
-                        self.set_error("No staff selected for deletion.".to_string());
+                        toast::push(Toast::error(t("staff.delete.select_first")));
                     }
                 }
                 KeyCode::Enter => {
@@ -249,7 +239,8 @@ impl DeleteStaff {
                     }
                 }
                 KeyCode::Char('r') | KeyCode::Char('R') => {
-                    if let Ok(staff) = db::get_all_staff() {
+                    db::cache::invalidate_all(&self.db);
+                    if let Ok(staff) = db::get_all_staff(&self.db) {
                         self.staff = staff;
                         self.filter_staff();
                     }
@@ -262,48 +253,6 @@ impl DeleteStaff {
         }
         Ok(None)
     }
-
-    fn clear_error(&mut self) {
-        self.error_message = None;
-        self.error_timer = None;
-    }
-
-    fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_timer = Some(Instant::now());
-    }
-
-    fn clear_success(&mut self) {
-        self.success_message = None;
-        self.success_timer = None;
-    }
-
-    fn check_success_timeout(&mut self) {
-        if let Some(timer) = self.success_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_success();
-            }
-        }
-    }
-
-    fn check_error_timeout(&mut self) {
-        if let Some(timer) = self.error_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_error();
-            }
-        }
-    }
-
-    fn check_timeouts(&mut self) {
-        self.check_error_timeout();
-        self.check_success_timeout();
-    }
-}
-
-impl Default for DeleteStaff {
-    fn default() -> Self {
-        Self::new()
-    }
 }
 
 impl Component for DeleteStaff {
@@ -314,7 +263,7 @@ impl Component for DeleteStaff {
     fn render(&self, frame: &mut Frame) {
         let area = frame.area();
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             area,
         );
 
@@ -332,16 +281,16 @@ impl Component for DeleteStaff {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
 
-        let title = Paragraph::new("🗑️ STAFF DELETION MANAGER")
+        let title = Paragraph::new(t("staff.delete.title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
@@ -350,24 +299,20 @@ impl Component for DeleteStaff {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Staff ",
+                format!(" {} ", t("common.search_staff")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110)) // Yellow when active
+                Style::default().fg(theme().focus) // Yellow when active
             } else {
-                Style::default().fg(Color::Rgb(75, 75, 120))
+                Style::default().fg(theme().border)
             })
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .style(Style::default().bg(theme().surface));
 
-        let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(22, 22, 35)),
-            )
+        let search_paragraph = Paragraph::new(self.search_input.line(self.is_searching))
+            .style(Style::default().fg(theme().text).bg(theme().surface))
             .block(search_block);
         frame.render_widget(search_paragraph, layout[1]);
 
@@ -376,28 +321,44 @@ impl Component for DeleteStaff {
             .border_type(BorderType::Rounded)
             .title(if !self.search_input.is_empty() {
                 format!(
-                    " Staff ({} of {} matches) ",
-                    self.filtered_staff.len(),
-                    self.staff.len()
+                    " {} ",
+                    tf(
+                        "common.staff_matches",
+                        &[
+                            ("shown", &self.filtered_staff.len()),
+                            ("total", &self.staff.len())
+                        ]
+                    )
                 )
             } else {
-                format!(" Staff ({}) ", self.staff.len())
+                format!(
+                    " {} ",
+                    tf("common.staff_count", &[("total", &self.staff.len())])
+                )
             })
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
-            .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .border_style(Style::default().fg(theme().unfocused))
+            .style(Style::default().bg(theme().input));
 
         let selected_style = Style::default()
-            .bg(Color::Rgb(45, 45, 60))
-            .fg(Color::Rgb(250, 250, 110))
+            .bg(theme().highlight)
+            .fg(theme().focus)
             .add_modifier(Modifier::BOLD);
-        let normal_style = Style::default()
-            .bg(Color::Rgb(26, 26, 36))
-            .fg(Color::Rgb(220, 220, 240));
+        let normal_style = Style::default().bg(theme().input).fg(theme().text);
+
+        let widths = [
+            Constraint::Length(5),
+            Constraint::Length(8),
+            Constraint::Length(20),
+            Constraint::Length(12),
+            Constraint::Length(15),
+            Constraint::Min(20),
+        ];
+        let columns = column_widths(layout[2].inner(Margin::new(1, 1)), &widths, 1, "► ");
 
         let mut rows = Vec::new();
         for staff_member in &self.filtered_staff {
@@ -409,113 +370,83 @@ impl Component for DeleteStaff {
 
             rows.push(Row::new(vec![
                 Cell::from(checkbox).style(normal_style),
-                Cell::from(staff_member.id.to_string()).style(normal_style),
-                Cell::from(staff_member.name.clone()).style(normal_style),
+                cell(staff_member.id.to_string(), &columns, 1).style(normal_style),
+                cell(&staff_member.name, &columns, 2).style(normal_style),
                 Cell::from(match staff_member.role {
-                    crate::models::StaffRole::Doctor => "Doctor",
-                    crate::models::StaffRole::Nurse => "Nurse",
-                    crate::models::StaffRole::Admin => "Admin",
-                    crate::models::StaffRole::Technician => "Technician",
+                    crate::models::StaffRole::Doctor => t("staff.role.doctor"),
+                    crate::models::StaffRole::Nurse => t("staff.role.nurse"),
+                    crate::models::StaffRole::Admin => t("staff.role.admin"),
+                    crate::models::StaffRole::Technician => t("staff.role.technician"),
+                    crate::models::StaffRole::Accountant => t("staff.role.accountant"),
                 })
                 .style(normal_style),
-                Cell::from(staff_member.phone_number.clone()).style(normal_style),
-                Cell::from(staff_member.address.clone()).style(normal_style),
+                cell(&staff_member.phone_number, &columns, 4).style(normal_style),
+                cell(staff_member.full_address(), &columns, 5).style(normal_style),
             ]));
         }
 
         if self.filtered_staff.is_empty() {
             let message = if self.search_input.is_empty() {
-                "No staff found in database"
+                t("common.no_staff")
             } else {
-                "No staff match your search criteria"
+                t("common.no_staff_matches")
             };
 
             rows.push(Row::new(vec![
                 Cell::from(""),
                 Cell::from(""),
-                Cell::from(message).style(Style::default().fg(Color::Rgb(180, 180, 200))),
+                Cell::from(message).style(Style::default().fg(theme().inactive)),
                 Cell::from(""),
                 Cell::from(""),
                 Cell::from(""),
             ]));
         }
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(5),
-                Constraint::Length(8),
-                Constraint::Length(20),
-                Constraint::Length(12),
-                Constraint::Length(15),
-                Constraint::Min(20),
-            ],
-        )
-        .header(
-            Row::new(vec![
-                Cell::from(""),
-                Cell::from("ID").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Name").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Role").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Phone").style(Style::default().add_modifier(Modifier::BOLD)),
-                Cell::from("Address").style(Style::default().add_modifier(Modifier::BOLD)),
-            ])
-            .style(
-                Style::default()
-                    .bg(Color::Rgb(80, 60, 130))
-                    .fg(Color::Rgb(180, 180, 250)),
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(vec![
+                    Cell::from(""),
+                    Cell::from(t("common.id")).style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.name"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.role"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.phone"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(t("common.address"))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                ])
+                .style(Style::default().bg(theme().header).fg(theme().title))
+                .height(1),
             )
-            .height(1),
-        )
-        .block(table_block)
-        .row_highlight_style(selected_style)
-        .highlight_symbol("► ");
+            .block(table_block)
+            .row_highlight_style(selected_style)
+            .highlight_symbol("► ");
 
         let mut table_state = self.table_state.clone();
         frame.render_stateful_widget(table, layout[2], &mut table_state);
 
-        if let Some(success) = &self.success_message {
-            let success_paragraph = Paragraph::new(success.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, layout[3]);
-        } else if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(240, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, layout[3]);
-        }
-
         if self.is_searching {
-            let help_text =
-                Paragraph::new("Type to search | ↓/Enter: To results | Esc: Cancel search")
-                    .style(Style::default().fg(Color::Rgb(180, 180, 200)))
-                    .alignment(Alignment::Center);
+            let help_text = Paragraph::new(t("common.search_enter_help"))
+                .style(Style::default().fg(theme().inactive))
+                .alignment(Alignment::Center);
             frame.render_widget(help_text, layout[4]);
         } else {
             let help_block = Block::default()
-                .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-                .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+                .border_style(Style::default().fg(theme().border))
+                .style(Style::default().bg(theme().background));
 
             let help_layout = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
                 .split(layout[4]);
 
-            let help_text1 = Paragraph::new("/ or s: Search | ↑/↓: Navigate | Space: Toggle | A: Select/deselect all | R: Refresh")
-                .style(Style::default().fg(Color::Rgb(180, 180, 200)))
+            let help_text1 = Paragraph::new(t("patients.delete.help"))
+                .style(Style::default().fg(theme().inactive))
                 .alignment(Alignment::Center);
 
-            let help_text2 = Paragraph::new("Enter: Delete selected | B: Bulk delete | Esc: Back")
-                .style(Style::default().fg(Color::Rgb(180, 180, 200)))
+            let help_text2 = Paragraph::new(t("patients.delete.help_2"))
+                .style(Style::default().fg(theme().inactive))
                 .alignment(Alignment::Center);
 
             frame.render_widget(help_block, layout[4]);
@@ -537,22 +468,21 @@ impl Component for DeleteStaff {
 
             let selected_count = self.selected_staff_ids.len();
             let title = format!(
-                " Delete {} Staff Member{} ",
-                selected_count,
-                if selected_count == 1 { "" } else { "s" }
+                " {} ",
+                tf("staff.delete.confirm_title", &[("count", &selected_count)])
             );
 
             let dialog_block = Block::default()
                 .title(title)
                 .title_style(
                     Style::default()
-                        .fg(Color::Rgb(230, 230, 250))
+                        .fg(theme().title)
                         .add_modifier(Modifier::BOLD),
                 )
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-                .style(Style::default().bg(Color::Rgb(30, 30, 46)));
+                .border_style(Style::default().fg(theme().unfocused))
+                .style(Style::default().bg(theme().dialog));
 
             frame.render_widget(dialog_block.clone(), dialog_area);
 
@@ -563,11 +493,10 @@ impl Component for DeleteStaff {
                 .constraints([Constraint::Length(2), Constraint::Length(2)])
                 .split(inner_area);
 
-            let message =
-                Paragraph::new("Are you sure you want to delete the selected staff member(s)?")
-                    .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                    .add_modifier(Modifier::BOLD)
-                    .alignment(Alignment::Center);
+            let message = Paragraph::new(t("staff.delete.confirm"))
+                .style(Style::default().fg(theme().text))
+                .add_modifier(Modifier::BOLD)
+                .alignment(Alignment::Center);
             frame.render_widget(message, content_layout[0]);
 
             let buttons_layout = Layout::default()
@@ -577,28 +506,28 @@ impl Component for DeleteStaff {
 
             let yes_style = if self.confirmation_selected == 0 {
                 Style::default()
-                    .fg(Color::Rgb(140, 219, 140))
+                    .fg(theme().success)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::Rgb(180, 180, 200))
+                Style::default().fg(theme().inactive)
             };
             let no_style = if self.confirmation_selected == 1 {
                 Style::default()
-                    .fg(Color::Rgb(255, 100, 100))
+                    .fg(theme().error)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::Rgb(180, 180, 200))
+                Style::default().fg(theme().inactive)
             };
 
             let yes_text = if self.confirmation_selected == 0 {
-                "► Yes ◄"
+                format!("► {} ◄", t("common.yes"))
             } else {
-                "  Yes  "
+                format!("  {}  ", t("common.yes"))
             };
             let no_text = if self.confirmation_selected == 1 {
-                "► No ◄"
+                format!("► {} ◄", t("common.no"))
             } else {
-                "  No  "
+                format!("  {}  ", t("common.no"))
             };
 
             let yes_button = Paragraph::new(yes_text)
@@ -1,8 +1,17 @@
 use crate::components::hospital::staff::StaffAction;
+use crate::components::master_detail;
+use crate::components::responsive::is_narrow;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
-use crate::db;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
 use crate::models::StaffMember;
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::ui_state::{self, ViewState};
+use crate::utils::contact;
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{prelude::*, widgets::*};
@@ -10,6 +19,7 @@ use ratatui::{prelude::*, widgets::*};
 const SEARCH_FIELD: usize = 0;
 const STAFF_LIST: usize = 1;
 const BACK_BUTTON: usize = 2;
+const VIEW_KEY: &str = "staff.list";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StaffViewState {
@@ -18,32 +28,43 @@ pub enum StaffViewState {
 }
 
 pub struct ListStaff {
+    db: Db,
     staff: Vec<StaffMember>,
     filtered_staff: Vec<StaffMember>,
-    search_input: String,
+    search_input: TextInput,
     is_searching: bool,
     state: TableState,
-    error_message: Option<String>,
     view_state: StaffViewState,
     focus_index: usize,
 }
 
 impl ListStaff {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
+        let view = ui_state::view(VIEW_KEY);
+        let mut search_input = TextInput::new();
+        search_input.set_value(view.search.as_str());
         Self {
+            db: db.clone(),
             staff: Vec::new(),
             filtered_staff: Vec::new(),
-            search_input: String::new(),
+            search_input,
             is_searching: false,
-            state: TableState::default(),
-            error_message: None,
+            state: view.table(),
             view_state: StaffViewState::ViewingList,
             focus_index: STAFF_LIST,
         }
     }
 
+    pub fn save_view(&self) {
+        let view = ViewState {
+            search: self.search_input.value().to_string(),
+            ..ViewState::default()
+        };
+        ui_state::set_view(VIEW_KEY, view.with_table(&self.state));
+    }
+
     pub fn fetch_staff(&mut self) -> Result<()> {
-        match db::get_all_staff() {
+        match db::get_all_staff(&self.db) {
             Ok(staff) => {
                 self.staff = staff;
                 self.filter_staff();
@@ -58,11 +79,13 @@ impl ListStaff {
                         .min(self.filtered_staff.len() - 1);
                     self.state.select(Some(selection));
                 }
-                self.error_message = None;
                 Ok(())
             }
             Err(e) => {
-                self.error_message = Some(format!("Failed to fetch staff: {}", e));
+                toast::push(Toast::error(tf(
+                    "staff.list.fetch_failed",
+                    &[("error", &e)],
+                )));
                 Ok(())
             }
         }
@@ -72,15 +95,15 @@ impl ListStaff {
         if self.search_input.is_empty() {
             self.filtered_staff = self.staff.clone();
         } else {
-            let search_term = self.search_input.to_lowercase();
+            let search_term = self.search_input.value().to_lowercase();
             self.filtered_staff = self
                 .staff
                 .iter()
                 .filter(|s| {
                     s.name.to_lowercase().contains(&search_term)
                         || s.id.to_string().contains(&search_term)
-                        || s.phone_number.to_lowercase().contains(&search_term)
-                        || s.address.to_lowercase().contains(&search_term)
+                        || contact::phone_matches(&s.phone_number, &search_term)
+                        || s.full_address().to_lowercase().contains(&search_term)
                 })
                 .cloned()
                 .collect();
@@ -159,12 +182,7 @@ impl ListStaff {
     pub fn process_input(&mut self, key: KeyEvent) -> Result<Option<StaffAction>> {
         if self.is_searching {
             match key.code {
-                KeyCode::Char(c) => {
-                    self.search_input.push(c);
-                    self.filter_staff();
-                }
-                KeyCode::Backspace => {
-                    self.search_input.pop();
+                _ if self.search_input.handle_key(key) => {
                     self.filter_staff();
                 }
                 KeyCode::Enter | KeyCode::Down | KeyCode::Tab => {
@@ -240,6 +258,7 @@ impl ListStaff {
                 return Ok(Some(StaffAction::BackToHome));
             }
             KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
                 self.fetch_staff()?;
             }
             KeyCode::Esc => {
@@ -269,7 +288,7 @@ impl Component for ListStaff {
     fn render(&self, frame: &mut Frame) {
         let area = frame.area();
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             area,
         );
 
@@ -288,27 +307,29 @@ impl ListStaff {
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Min(10),
+                Constraint::Min(13),
                 Constraint::Length(1),
                 Constraint::Length(1),
                 Constraint::Length(1),
             ])
             .margin(1)
             .split(area);
+        let (list_area, details_area) = master_detail::split(layout[1]);
+        let [search_area, table_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(10)]).areas(list_area);
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
 
-        let title = Paragraph::new("👥 STAFF LIST")
+        let title = Paragraph::new(t("staff.list.title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
@@ -317,104 +338,126 @@ impl ListStaff {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Staff ",
+                format!(" {} ", t("common.search_staff")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(75, 75, 120))
+                Style::default().fg(theme().border)
             })
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .style(Style::default().bg(theme().surface));
 
-        let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(22, 22, 35)),
-            )
+        let search_paragraph = Paragraph::new(self.search_input.line(self.is_searching))
+            .style(Style::default().fg(theme().text).bg(theme().surface))
             .block(search_block);
-        frame.render_widget(search_paragraph, layout[1]);
-
-        let header_cells = ["ID", "Name", "Role", "Phone", "Address"]
-            .iter()
-            .map(|h| Cell::from(*h).style(Style::default().fg(Color::Rgb(230, 230, 250))));
+        frame.render_widget(search_paragraph, search_area);
+
+        let narrow = is_narrow(list_area);
+        let header_cells = [
+            "common.id",
+            "common.name",
+            "common.role",
+            "common.phone",
+            "common.address",
+        ]
+        .iter()
+        .take(if narrow { 4 } else { 5 })
+        .map(|h| Cell::from(t(h)).style(Style::default().fg(theme().title)));
         let header = Row::new(header_cells)
-            .style(Style::default().bg(Color::Rgb(80, 60, 130)))
+            .style(Style::default().bg(theme().header))
             .height(1);
 
+        let widths = if narrow {
+            vec![
+                Constraint::Percentage(10),
+                Constraint::Percentage(40),
+                Constraint::Percentage(20),
+                Constraint::Percentage(30),
+            ]
+        } else {
+            vec![
+                Constraint::Percentage(5),
+                Constraint::Percentage(20),
+                Constraint::Percentage(15),
+                Constraint::Percentage(20),
+                Constraint::Percentage(40),
+            ]
+        };
+        let columns = column_widths(table_area.inner(Margin::new(1, 1)), &widths, 1, "► ");
+
         let rows = self.filtered_staff.iter().map(|staff_member| {
             let cells = vec![
-                Cell::from(staff_member.id.to_string()),
-                Cell::from(staff_member.name.clone()),
+                cell(staff_member.id.to_string(), &columns, 0),
+                cell(&staff_member.name, &columns, 1),
                 Cell::from(match staff_member.role {
-                    crate::models::StaffRole::Doctor => "Doctor",
-                    crate::models::StaffRole::Nurse => "Nurse",
-                    crate::models::StaffRole::Admin => "Admin",
-                    crate::models::StaffRole::Technician => "Technician",
+                    crate::models::StaffRole::Doctor => t("staff.role.doctor"),
+                    crate::models::StaffRole::Nurse => t("staff.role.nurse"),
+                    crate::models::StaffRole::Admin => t("staff.role.admin"),
+                    crate::models::StaffRole::Technician => t("staff.role.technician"),
+                    crate::models::StaffRole::Accountant => t("staff.role.accountant"),
                 }),
-                Cell::from(staff_member.phone_number.clone()),
-                Cell::from(staff_member.address.clone()),
+                cell(&staff_member.phone_number, &columns, 3),
+                cell(staff_member.full_address(), &columns, 4),
             ];
-            Row::new(cells)
+            Row::new(cells.into_iter().take(if narrow { 4 } else { 5 }))
                 .height(1)
                 .bottom_margin(0)
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                .style(Style::default().fg(theme().text))
         });
 
         let selected_style = Style::default()
-            .fg(Color::Rgb(250, 250, 110))
-            .bg(Color::Rgb(40, 40, 60))
+            .fg(theme().focus)
+            .bg(theme().highlight)
             .add_modifier(Modifier::BOLD);
 
         let table_title = if !self.search_input.is_empty() {
             format!(
-                " Staff ({} of {} matches) ",
-                self.filtered_staff.len(),
-                self.staff.len()
+                " {} ",
+                tf(
+                    "common.staff_matches",
+                    &[
+                        ("shown", &self.filtered_staff.len()),
+                        ("total", &self.staff.len())
+                    ]
+                )
             )
         } else {
-            format!(" Staff ({}) ", self.staff.len())
+            format!(
+                " {} ",
+                tf("common.staff_count", &[("total", &self.staff.len())])
+            )
         };
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Percentage(5),
-                Constraint::Percentage(20),
-                Constraint::Percentage(15),
-                Constraint::Percentage(20),
-                Constraint::Percentage(40),
-            ],
-        )
-        .header(header)
-        .block(
-            Block::default()
-                .title(table_title.clone())
-                .title_alignment(Alignment::Center)
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-                .style(Style::default().bg(Color::Rgb(22, 22, 35))),
-        )
-        .row_highlight_style(selected_style)
-        .highlight_symbol(if self.focus_index == STAFF_LIST {
-            "► "
-        } else {
-            "  "
-        });
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .title(table_title.clone())
+                    .title_alignment(Alignment::Center)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .row_highlight_style(selected_style)
+            .highlight_symbol(if self.focus_index == STAFF_LIST {
+                "► "
+            } else {
+                "  "
+            });
 
         if self.filtered_staff.is_empty() {
             let message = if self.search_input.is_empty() {
-                "No staff found in database"
+                t("common.no_staff")
             } else {
-                "No staff match your search criteria"
+                t("common.no_staff_matches")
             };
 
             let no_staff = Paragraph::new(message)
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                .style(Style::default().fg(theme().text))
                 .alignment(Alignment::Center)
                 .block(
                     Block::default()
@@ -422,50 +465,81 @@ impl ListStaff {
                         .title_alignment(Alignment::Center)
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-                        .style(Style::default().bg(Color::Rgb(22, 22, 35))),
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
                 );
-            frame.render_widget(no_staff, layout[2]);
+            frame.render_widget(no_staff, table_area);
         } else {
-            frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
+            frame.render_stateful_widget(table, table_area, &mut self.state.clone());
+        }
+
+        if let Some(details_area) = details_area {
+            let fields = self.selected_staff().map(|staff_member| {
+                vec![
+                    (
+                        t("common.name"),
+                        tf(
+                            "common.name_with_id",
+                            &[("name", &staff_member.name), ("id", &staff_member.id)],
+                        ),
+                    ),
+                    (
+                        t("common.role"),
+                        match staff_member.role {
+                            crate::models::StaffRole::Doctor => t("staff.role.doctor"),
+                            crate::models::StaffRole::Nurse => t("staff.role.nurse"),
+                            crate::models::StaffRole::Admin => t("staff.list.administrator"),
+                            crate::models::StaffRole::Technician => t("staff.role.technician"),
+                            crate::models::StaffRole::Accountant => t("staff.role.accountant"),
+                        }
+                        .to_string(),
+                    ),
+                    (t("common.phone_number"), staff_member.phone_number.clone()),
+                    (
+                        t("patients.email"),
+                        staff_member.email.clone().unwrap_or_default(),
+                    ),
+                    (t("common.address"), staff_member.full_address()),
+                ]
+            });
+            master_detail::render_details(
+                frame,
+                details_area,
+                t("staff.list.details"),
+                fields.as_deref(),
+                t("assign.no_member"),
+            );
         }
 
         let help_text = if self.is_searching {
-            "Type to search | ↓/Enter: To results | Esc: Cancel search"
+            t("common.search_enter_help")
         } else {
-            "/ or s: Search | ↑↓: Navigate | Enter: View Details | R: Refresh | Tab: Focus"
+            t("staff.list.help")
         };
 
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(140, 140, 170)))
+            .style(Style::default().fg(theme().help))
             .alignment(Alignment::Center);
-        frame.render_widget(help_paragraph, layout[3]);
+        frame.render_widget(help_paragraph, layout[2]);
 
         let back_text = if self.focus_index == BACK_BUTTON {
-            "► Back ◄"
+            format!("► {} ◄", t("common.back"))
         } else {
-            "  Back  "
+            format!("  {}  ", t("common.back"))
         };
 
         let back_style = if self.focus_index == BACK_BUTTON {
             Style::default()
-                .fg(Color::Rgb(129, 199, 245))
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let back_button = Paragraph::new(back_text)
             .style(back_style)
             .alignment(Alignment::Center);
-        frame.render_widget(back_button, layout[4]);
-
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, layout[5]);
-        }
+        frame.render_widget(back_button, layout[3]);
     }
 
     fn render_details_view(&self, frame: &mut Frame) {
@@ -484,16 +558,16 @@ impl ListStaff {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, layout[0]);
 
-        let title = Paragraph::new("👤 STAFF DETAILS")
+        let title = Paragraph::new(t("staff.list.details_title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, layout[0]);
@@ -511,19 +585,28 @@ impl ListStaff {
                 .margin(1)
                 .split(layout[1]);
 
-            let title_style = Style::default().fg(Color::Cyan);
+            let title_style = Style::default().fg(theme().accent);
 
             let id_name_block = Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .title(Span::styled(" Basic Information ", title_style))
-                .border_style(Style::default().fg(Color::White))
-                .style(Style::default().bg(Color::Rgb(22, 22, 35)));
-
-            let id_name_text = format!("  ID: {}, Name: {}", staff_member.id, staff_member.name);
+                .title(Span::styled(
+                    format!(" {} ", t("staff.list.basic")),
+                    title_style,
+                ))
+                .border_style(Style::default().fg(theme().border))
+                .style(Style::default().bg(theme().surface));
+
+            let id_name_text = format!(
+                "  {}",
+                tf(
+                    "staff.list.id_name",
+                    &[("id", &staff_member.id), ("name", &staff_member.name)]
+                )
+            );
 
             let id_name_widget = Paragraph::new(id_name_text)
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                .style(Style::default().fg(theme().text))
                 .block(id_name_block);
 
             frame.render_widget(id_name_widget, content_layout[0]);
@@ -531,22 +614,23 @@ impl ListStaff {
             let role_block = Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .title(Span::styled(" Role ", title_style))
-                .border_style(Style::default().fg(Color::White))
-                .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+                .title(Span::styled(format!(" {} ", t("common.role")), title_style))
+                .border_style(Style::default().fg(theme().border))
+                .style(Style::default().bg(theme().surface));
 
             let role_text = format!(
                 "  {}",
                 match staff_member.role {
-                    crate::models::StaffRole::Doctor => "Doctor",
-                    crate::models::StaffRole::Nurse => "Nurse",
-                    crate::models::StaffRole::Admin => "Administrator",
-                    crate::models::StaffRole::Technician => "Technician",
+                    crate::models::StaffRole::Doctor => t("staff.role.doctor"),
+                    crate::models::StaffRole::Nurse => t("staff.role.nurse"),
+                    crate::models::StaffRole::Admin => t("staff.list.administrator"),
+                    crate::models::StaffRole::Technician => t("staff.role.technician"),
+                    crate::models::StaffRole::Accountant => t("staff.role.accountant"),
                 }
             );
 
             let role_widget = Paragraph::new(role_text)
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                .style(Style::default().fg(theme().text))
                 .block(role_block);
 
             frame.render_widget(role_widget, content_layout[1]);
@@ -554,14 +638,17 @@ impl ListStaff {
             let phone_block = Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .title(Span::styled(" Phone Number ", title_style))
-                .border_style(Style::default().fg(Color::White))
-                .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+                .title(Span::styled(
+                    format!(" {} ", t("common.phone_number")),
+                    title_style,
+                ))
+                .border_style(Style::default().fg(theme().border))
+                .style(Style::default().bg(theme().surface));
 
             let phone_text = format!("  {}", staff_member.phone_number);
 
             let phone_widget = Paragraph::new(phone_text)
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                .style(Style::default().fg(theme().text))
                 .block(phone_block);
 
             frame.render_widget(phone_widget, content_layout[2]);
@@ -569,39 +656,36 @@ impl ListStaff {
             let address_block = Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .title(Span::styled(" Address ", title_style))
-                .border_style(Style::default().fg(Color::White))
-                .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+                .title(Span::styled(
+                    format!(" {} ", t("common.address")),
+                    title_style,
+                ))
+                .border_style(Style::default().fg(theme().border))
+                .style(Style::default().bg(theme().surface));
 
-            let address_text = format!("    {}", staff_member.address);
+            let address_text = format!("    {}", staff_member.full_address());
 
             let address_widget = Paragraph::new(address_text)
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                .style(Style::default().fg(theme().text))
                 .block(address_block)
                 .wrap(Wrap { trim: true });
 
             frame.render_widget(address_widget, content_layout[3]);
         }
 
-        let back_button = Paragraph::new("► Back ◄")
+        let back_button = Paragraph::new(format!("► {} ◄", t("common.back")))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(129, 199, 245))
+                    .fg(theme().accent)
                     .add_modifier(Modifier::BOLD),
             )
             .alignment(Alignment::Center);
         frame.render_widget(back_button, layout[2]);
 
-        let help_text = "Enter/Esc/Backspace: Return to list";
+        let help_text = t("staff.list.detail_help");
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(140, 140, 170)))
+            .style(Style::default().fg(theme().help))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, layout[3]);
     }
 }
-
-impl Default for ListStaff {
-    fn default() -> Self {
-        Self::new()
-    }
-}
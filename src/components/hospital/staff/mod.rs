@@ -2,18 +2,46 @@ use self::add::AddStaff;
 use self::assign::AssignStaff;
 use self::delete::DeleteStaff;
 use self::list::ListStaff;
+use self::performance::PerformanceReport;
 use self::update::UpdateStaff;
 use crate::app::SelectedApp;
 use crate::components::Component;
+use crate::db::Db;
+use crate::i18n::t;
 use crate::tui::Frame;
 use anyhow::Result;
 use crossterm::event::KeyEvent;
+use time::{Date, Duration, PrimitiveDateTime};
+pub mod absence;
 pub mod add;
 pub mod assign;
 pub mod delete;
 pub mod list;
+pub mod performance;
+pub mod roster_reset;
 pub mod update;
 
+/// The shift (as stored) and its date that cover `at`: Morning 6am-2pm,
+/// Afternoon 2pm-10pm, and Night 10pm-6am dated by the evening it starts.
+pub fn shift_at(at: PrimitiveDateTime) -> (Date, &'static str) {
+    match at.hour() {
+        0..=5 => (at.date() - Duration::days(1), "Night"),
+        6..=13 => (at.date(), "Morning"),
+        14..=21 => (at.date(), "Afternoon"),
+        _ => (at.date(), "Night"),
+    }
+}
+
+/// A shift's name in the user's language; `shift` is as stored.
+pub fn shift_label(shift: &str) -> &str {
+    match shift {
+        "Morning" => t("shift.morning"),
+        "Afternoon" => t("shift.afternoon"),
+        "Night" => t("shift.night"),
+        other => other,
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StaffAction {
     BackToHome,
@@ -28,6 +56,7 @@ pub enum StaffState {
     DeleteStaff,
     UpdateStaff,
     AssignStaff,
+    Performance,
 }
 
 pub struct Staff {
@@ -36,22 +65,28 @@ pub struct Staff {
     pub delete_staff: Option<DeleteStaff>,
     pub update_staff: Option<UpdateStaff>,
     pub assign_staff: Option<AssignStaff>,
+    pub performance: PerformanceReport,
     pub state: StaffState,
 }
 
 impl Staff {
-    pub fn new() -> Self {
-        let mut list_staff = ListStaff::new();
-        list_staff.fetch_staff().expect("Failed to fetch staff");
+    pub fn new(db: &Db) -> Result<Self> {
+        let mut list_staff = ListStaff::new(db);
+        list_staff.fetch_staff()?;
 
-        Self {
-            add_staff: AddStaff::new(),
+        Ok(Self {
+            add_staff: AddStaff::new(db),
             list_staff,
             delete_staff: None,
             update_staff: None,
             assign_staff: None,
+            performance: PerformanceReport::new(db),
             state: StaffState::ListStaff,
-        }
+        })
+    }
+
+    pub fn save_view(&self) {
+        self.list_staff.save_view();
     }
 
     pub fn initialize_list(&mut self) -> Result<()> {
@@ -59,6 +94,9 @@ impl Staff {
         if self.state == StaffState::ListStaff {
             self.list_staff.fetch_staff()?;
         }
+        if self.state == StaffState::Performance {
+            self.performance.load_data()?;
+        }
         Ok(())
     }
 }
@@ -133,6 +171,11 @@ impl Component for Staff {
                     }
                 }
             }
+            StaffState::Performance => {
+                if let Some(SelectedApp::None) = self.performance.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
         }
         Ok(None)
     }
@@ -156,12 +199,7 @@ impl Component for Staff {
                     assign_staff.render(frame);
                 }
             }
+            StaffState::Performance => self.performance.render(frame),
         }
     }
 }
-
-impl Default for Staff {
-    fn default() -> Self {
-        Self::new()
-    }
-}
@@ -0,0 +1,334 @@
+//! What each staff member did in a month, for appraisals: shifts worked,
+//! medical records written, consultations and walk-ins seen to the end, and
+//! the patients' average rating of their visits.
+//!
+//! Records count towards the staff member linked to the account that wrote
+//! them, so records from unlinked accounts, and those written before
+//! authors were kept, are left out.
+
+use crate::app::SelectedApp;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{self, t, tf};
+use crate::models::StaffPerformance;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{csv_line, datetime, write_export};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use time::{Date, Duration};
+
+const HEADERS: [&str; 9] = [
+    "performance.staff",
+    "common.role",
+    "performance.shifts",
+    "performance.leave_days",
+    "performance.records",
+    "performance.consultations",
+    "performance.walk_ins",
+    "satisfaction.ratings",
+    "performance.avg_rating",
+];
+
+fn month_range(date: Date) -> (Date, Date) {
+    let first = date.replace_day(1).unwrap_or(date);
+    let last = first
+        .replace_day(first.month().length(first.year()))
+        .unwrap_or(first);
+    (first, last)
+}
+
+fn values(row: &StaffPerformance) -> Vec<String> {
+    vec![
+        row.name.clone(),
+        row.role.clone(),
+        row.shifts.to_string(),
+        row.leave_days.to_string(),
+        row.records.to_string(),
+        row.consultations.to_string(),
+        row.walk_ins.to_string(),
+        row.ratings.to_string(),
+        row.average_rating
+            .map_or("-".to_string(), |r| format!("{:.1}", r)),
+    ]
+}
+
+fn is_idle(row: &StaffPerformance) -> bool {
+    row.shifts == 0 && row.records == 0 && row.consultations == 0 && row.walk_ins == 0
+}
+
+pub struct PerformanceReport {
+    db: Db,
+    from: Date,
+    to: Date,
+    rows: Vec<StaffPerformance>,
+    state: TableState,
+}
+
+impl PerformanceReport {
+    pub fn new(db: &Db) -> Self {
+        let (from, to) = month_range(datetime::today());
+        Self {
+            db: db.clone(),
+            from,
+            to,
+            rows: Vec::new(),
+            state: TableState::default(),
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.rows = db::get_staff_performance(
+            &self.db,
+            &datetime::format_date(self.from),
+            &datetime::format_date(self.to),
+        )?;
+        match self.state.selected() {
+            _ if self.rows.is_empty() => self.state.select(None),
+            Some(i) if i < self.rows.len() => {}
+            _ => self.state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) {
+        if let Err(e) = self.load_data() {
+            toast::push(Toast::error(tf(
+                "performance.load_failed",
+                &[("error", &e)],
+            )));
+        }
+    }
+
+    fn change_month(&mut self, forward: bool) {
+        let (from, to) = if forward {
+            month_range(self.to + Duration::days(1))
+        } else {
+            month_range(self.from - Duration::days(1))
+        };
+        self.from = from;
+        self.to = to;
+        self.reload();
+    }
+
+    fn export(&self) {
+        if self.rows.is_empty() {
+            toast::push(Toast::error(t("performance.no_staff")));
+            return;
+        }
+        let mut contents = csv_line(&HEADERS.map(i18n::english));
+        contents.push('\n');
+        for row in &self.rows {
+            let values = values(row);
+            let fields: Vec<&str> = values.iter().map(String::as_str).collect();
+            contents.push_str(&csv_line(&fields));
+            contents.push('\n');
+        }
+        let file_name = format!(
+            "staff_performance_{}_{}.csv",
+            datetime::format_date(self.from),
+            datetime::format_date(self.to)
+        );
+        match write_export(&file_name, &contents) {
+            Ok(path) => toast::push(Toast::success(tf(
+                "performance.exported",
+                &[("count", &self.rows.len()), ("path", &path.display())],
+            ))),
+            Err(e) => toast::push(Toast::error(format!("{}", e))),
+        }
+    }
+
+    fn summary(&self) -> Line<'static> {
+        let total = |f: fn(&StaffPerformance) -> i64| self.rows.iter().map(f).sum::<i64>();
+        let separator = || Span::styled("  |  ", Style::default().fg(theme().inactive));
+        Line::from(vec![
+            Span::styled(
+                tf(
+                    "performance.shifts_worked",
+                    &[("count", &(total(|r| r.shifts)))],
+                ),
+                Style::default()
+                    .fg(theme().text)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            separator(),
+            Span::styled(
+                tf(
+                    "performance.leave_days_count",
+                    &[("count", &(total(|r| r.leave_days)))],
+                ),
+                Style::default().fg(theme().warning),
+            ),
+            separator(),
+            Span::styled(
+                tf(
+                    "performance.records_count",
+                    &[("count", &(total(|r| r.records)))],
+                ),
+                Style::default().fg(theme().text),
+            ),
+            separator(),
+            Span::styled(
+                tf(
+                    "performance.consultations_count",
+                    &[("count", &(total(|r| r.consultations + r.walk_ins)))],
+                ),
+                Style::default().fg(theme().success),
+            ),
+        ])
+    }
+}
+
+impl Component for PerformanceReport {
+    fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        let len = self.rows.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Left => self.change_month(false),
+            KeyCode::Right => self.change_month(true),
+            KeyCode::Char('e') | KeyCode::Char('E') => self.export(),
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                self.reload();
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("performance.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(tf(
+                "performance.range",
+                &[
+                    ("from", &datetime::format_date(self.from)),
+                    ("to", &datetime::format_date(self.to)),
+                ],
+            ))
+            .style(Style::default().fg(theme().accent))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("common.month")))
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            ),
+            layout[1],
+        );
+
+        frame.render_widget(
+            Paragraph::new(self.summary()).alignment(Alignment::Center),
+            layout[2],
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.rows.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("performance.empty"))
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[3],
+            );
+        } else {
+            let widths = [
+                Constraint::Min(16),
+                Constraint::Length(14),
+                Constraint::Length(6),
+                Constraint::Length(10),
+                Constraint::Length(7),
+                Constraint::Length(13),
+                Constraint::Length(8),
+                Constraint::Length(7),
+                Constraint::Length(10),
+            ];
+            let columns = column_widths(block.inner(layout[3]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .rows
+                .iter()
+                .map(|row| {
+                    let style = if is_idle(row) {
+                        Style::default().fg(theme().inactive)
+                    } else {
+                        Style::default().fg(theme().text)
+                    };
+                    Row::new(
+                        values(row)
+                            .into_iter()
+                            .enumerate()
+                            .map(|(column, text)| cell(text, &columns, column))
+                            .collect::<Vec<_>>(),
+                    )
+                    .style(style)
+                })
+                .collect();
+            let table = Table::new(rows, widths)
+                .header(
+                    Row::new(HEADERS.map(t).to_vec())
+                        .style(Style::default().bg(theme().header).fg(theme().title)),
+                )
+                .block(block)
+                .row_highlight_style(Style::default().bg(theme().highlight))
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[3], &mut self.state.clone());
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("performance.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+    }
+}
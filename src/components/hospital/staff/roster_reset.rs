@@ -0,0 +1,263 @@
+//! Clearing or regenerating every shift in a date range at once, for when a
+//! stretch of the roster was planned wrongly.
+
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::date_picker::DatePicker;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use time::{Date, Duration};
+
+enum Step {
+    /// Choosing the first day, then the last one.
+    Picking(DatePicker, Option<Date>),
+    Confirming {
+        from: Date,
+        to: Date,
+    },
+}
+
+/// Admin dialog shown over the shift assignment screen.
+pub struct RosterReset {
+    db: Db,
+    step: Step,
+    regenerate: bool,
+    /// Shifts removed and created by applying, or why that is unknown.
+    preview: Result<(i64, usize), String>,
+    confirmation_selected: usize,
+}
+
+impl RosterReset {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            step: Step::Picking(DatePicker::today().with_months(6), None),
+            regenerate: false,
+            preview: Ok((0, 0)),
+            confirmation_selected: 1,
+        }
+    }
+
+    fn refresh_preview(&mut self) {
+        if let Step::Confirming { from, to } = self.step {
+            self.preview = db::preview_roster_reset(&self.db, from, to, self.regenerate)
+                .map_err(|e| tf("roster_reset.count_failed", &[("error", &e)]));
+        }
+    }
+
+    fn apply(&self, from: Date, to: Date) -> bool {
+        match db::reset_roster(&self.db, from, to, self.regenerate) {
+            Ok((removed, created)) => {
+                toast::push(Toast::success(if self.regenerate {
+                    tf(
+                        "roster_reset.regenerated",
+                        &[("removed", &removed), ("created", &created)],
+                    )
+                } else {
+                    tf("roster_reset.cleared", &[("removed", &removed)])
+                }));
+                true
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("roster_reset.failed", &[("error", &e)])));
+                false
+            }
+        }
+    }
+
+    /// Returns true once the reset has been applied or cancelled.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match &mut self.step {
+            Step::Picking(picker, start) => match (key.code, *start) {
+                (KeyCode::Enter, None) => {
+                    let from = picker.selected();
+                    *start = Some(from);
+                    *picker = picker.clone().with_min(from);
+                }
+                (KeyCode::Enter, Some(from)) => {
+                    self.step = Step::Confirming {
+                        from,
+                        to: picker.selected(),
+                    };
+                    self.refresh_preview();
+                }
+                (KeyCode::Esc, _) => return true,
+                _ => {
+                    picker.handle_key(key);
+                }
+            },
+            Step::Confirming { from, to } => {
+                let (from, to) = (*from, *to);
+                match key.code {
+                    KeyCode::Tab | KeyCode::BackTab => {
+                        self.regenerate = !self.regenerate;
+                        self.refresh_preview();
+                    }
+                    KeyCode::Left | KeyCode::Right => {
+                        self.confirmation_selected = 1 - self.confirmation_selected;
+                    }
+                    KeyCode::Enter if self.confirmation_selected == 0 => {
+                        return self.preview.is_ok() && self.apply(from, to);
+                    }
+                    KeyCode::Enter | KeyCode::Esc => return true,
+                    _ => {}
+                }
+            }
+        }
+        false
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let (from, to) = match &self.step {
+            Step::Picking(picker, start) => {
+                let title = if start.is_some() {
+                    t("roster_reset.to_title")
+                } else {
+                    t("roster_reset.from_title")
+                };
+                picker.render_popup(frame, title);
+                return;
+            }
+            Step::Confirming { from, to } => (*from, *to),
+        };
+
+        let area = frame.area();
+        let width = 64.min(area.width);
+        let height = 11.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" {} ", t("roster_reset.title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().warning))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        frame.render_widget(
+            Paragraph::new(tf(
+                "common.date_range",
+                &[
+                    ("from", &datetime::format_date(from)),
+                    ("to", &datetime::format_date(to)),
+                ],
+            ))
+            .style(
+                Style::default()
+                    .fg(theme().accent)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .alignment(Alignment::Center),
+            layout[0],
+        );
+        let mode = |label: &'static str, active: bool| {
+            if active {
+                Span::styled(
+                    format!("► {} ◄", label),
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                Span::styled(
+                    format!("  {}  ", label),
+                    Style::default().fg(theme().inactive),
+                )
+            }
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                mode(t("roster_reset.clear"), !self.regenerate),
+                Span::raw("   "),
+                mode(t("roster_reset.regenerate"), self.regenerate),
+            ]))
+            .alignment(Alignment::Center),
+            layout[2],
+        );
+
+        let week_before = tf(
+            "common.date_range",
+            &[
+                ("from", &(datetime::format_date(from - Duration::days(7)))),
+                ("to", &(datetime::format_date(from - Duration::days(1)))),
+            ],
+        );
+        let (preview, style) = match &self.preview {
+            Ok((removed, _)) if !self.regenerate => (
+                tf("roster_reset.will_remove", &[("removed", &removed)]),
+                Style::default().fg(theme().text),
+            ),
+            Ok((removed, created)) => (
+                tf(
+                    "roster_reset.will_regenerate",
+                    &[
+                        ("removed", &removed),
+                        ("created", &created),
+                        ("week", &week_before),
+                    ],
+                ),
+                Style::default().fg(theme().text),
+            ),
+            Err(e) => (e.clone(), Style::default().fg(theme().error)),
+        };
+        frame.render_widget(
+            Paragraph::new(preview)
+                .style(style)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            layout[3],
+        );
+
+        let button = |label: &str, index: usize, color: Color| {
+            if self.confirmation_selected == index {
+                Paragraph::new(format!("► {} ◄", label))
+                    .style(Style::default().fg(color).add_modifier(Modifier::BOLD))
+            } else {
+                Paragraph::new(format!("  {}  ", label))
+                    .style(Style::default().fg(theme().inactive))
+            }
+            .alignment(Alignment::Center)
+        };
+        let buttons = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(layout[4]);
+        frame.render_widget(
+            button(t("roster_reset.apply"), 0, theme().error),
+            buttons[0],
+        );
+        frame.render_widget(button(t("common.cancel"), 1, theme().success), buttons[1]);
+
+        frame.render_widget(
+            Paragraph::new(t("roster_reset.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[6],
+        );
+    }
+}
@@ -1,12 +1,23 @@
 use crate::app::SelectedApp;
+use crate::auth;
+use crate::components::form;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::edit_warning;
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
-use crate::db;
+use crate::db::{self, Db};
+use crate::hooks;
+use crate::i18n::{t, tf};
+use crate::locks::EditLock;
 use crate::models::{StaffMember, StaffRole};
+use crate::theme::theme;
 use crate::tui::Frame;
+use crate::ui_state;
+use crate::utils::contact;
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{prelude::*, widgets::*};
-use std::time::{Duration, Instant};
 
 enum ConfirmAction {
     UpdateStaff,
@@ -17,23 +28,27 @@ enum UpdateState {
     EditingStaff,
 }
 pub struct UpdateStaff {
+    db: Db,
     all_staff: Vec<StaffMember>,
     filtered_staff: Vec<StaffMember>,
-    search_input: String,
+    search_input: TextInput,
     is_searching: bool,
     table_state: TableState,
     update_state: UpdateState,
     staff_id_input: String,
     staff: StaffMember,
+    /// The login account linked to the staff member, as loaded and as
+    /// edited; empty for none.
+    linked_account: String,
+    account: String,
     loaded: bool,
+    edit_lock: Option<EditLock>,
     selected_field: Option<usize>,
     edit_table_state: TableState,
     input_value: String,
+    /// Why the value being edited was refused, shown under it.
+    input_error: Option<String>,
     editing: bool,
-    error_message: Option<String>,
-    error_timer: Option<Instant>,
-    success_message: Option<String>,
-    success_timer: Option<Instant>,
     show_confirmation: bool,
     confirmation_message: String,
     confirmed_action: Option<ConfirmAction>,
@@ -46,10 +61,14 @@ const ROLE_INPUT: usize = 2;
 const PHONE_INPUT: usize = 3;
 const EMAIL_INPUT: usize = 4;
 const ADDRESS_INPUT: usize = 5;
-const INPUT_FIELDS: usize = 5;
+const POSTCODE_INPUT: usize = 6;
+const CITY_INPUT: usize = 7;
+const REGION_INPUT: usize = 8;
+const ACCOUNT_INPUT: usize = 9;
+const INPUT_FIELDS: usize = 9;
 
 impl UpdateStaff {
-    pub fn new() -> Self {
+    pub fn new(db: &Db) -> Self {
         let mut selection_state = TableState::default();
         selection_state.select(Some(0));
 
@@ -57,9 +76,10 @@ impl UpdateStaff {
         edit_table_state.select(Some(0));
 
         Self {
+            db: db.clone(),
             all_staff: Vec::new(),
             filtered_staff: Vec::new(),
-            search_input: String::new(),
+            search_input: TextInput::new(),
             is_searching: false,
             table_state: selection_state,
             update_state: UpdateState::SelectingStaff,
@@ -71,16 +91,19 @@ impl UpdateStaff {
                 phone_number: String::new(),
                 email: None,
                 address: String::new(),
+                city: String::new(),
+                region: String::new(),
+                postcode: String::new(),
             },
+            linked_account: String::new(),
+            account: String::new(),
             loaded: false,
+            edit_lock: None,
             selected_field: Some(0),
             edit_table_state,
             input_value: String::new(),
+            input_error: None,
             editing: false,
-            error_message: None,
-            error_timer: None,
-            success_message: None,
-            success_timer: None,
             show_confirmation: false,
             confirmation_message: String::new(),
             confirmed_action: None,
@@ -89,7 +112,7 @@ impl UpdateStaff {
     }
 
     pub fn fetch_staff(&mut self) -> Result<()> {
-        self.all_staff = db::get_all_staff()?;
+        self.all_staff = db::get_all_staff(&self.db)?;
         self.filter_staff();
         Ok(())
     }
@@ -98,14 +121,14 @@ impl UpdateStaff {
         if self.search_input.is_empty() {
             self.filtered_staff = self.all_staff.clone();
         } else {
-            let search_term = self.search_input.to_lowercase();
+            let search_term = self.search_input.value().to_lowercase();
             self.filtered_staff = self
                 .all_staff
                 .iter()
                 .filter(|s| {
                     s.name.to_lowercase().contains(&search_term)
                         || s.id.to_string().contains(&search_term)
-                        || s.phone_number.to_lowercase().contains(&search_term)
+                        || contact::phone_matches(&s.phone_number, &search_term)
                 })
                 .cloned()
                 .collect();
@@ -118,17 +141,24 @@ impl UpdateStaff {
         }
     }
 
-    fn load_staff_by_id(&mut self, staff_id: i64) -> Result<()> {
-        match db::get_staff(staff_id) {
+    pub fn load_staff_by_id(&mut self, staff_id: i64) -> Result<()> {
+        match db::get_staff(&self.db, staff_id) {
             Ok(staff) => {
                 self.staff = staff;
+                self.linked_account =
+                    db::get_staff_account(&self.db, staff_id)?.unwrap_or_default();
+                self.account = self.linked_account.clone();
+                self.edit_lock = Some(EditLock::claim(&self.db, "staff", staff_id));
                 self.loaded = true;
                 self.update_state = UpdateState::EditingStaff;
                 self.update_input_value();
                 Ok(())
             }
             Err(_) => {
-                self.set_error(format!("Staff with ID {} doesn't exist", staff_id));
+                toast::push(Toast::error(tf(
+                    "staff.update.not_found",
+                    &[("id", &staff_id)],
+                )));
                 Err(anyhow::anyhow!("Staff not found"))
             }
         }
@@ -142,7 +172,7 @@ impl UpdateStaff {
                     Err(e) => Err(e),
                 }
             } else {
-                self.set_error("Invalid Staff ID format.".to_string());
+                toast::push(Toast::error(t("staff.update.invalid_id")));
                 Err(anyhow::anyhow!("Invalid Staff ID format"))
             }
         } else {
@@ -158,8 +188,8 @@ impl UpdateStaff {
                 return self.load_staff_by_id(staff_id);
             }
         }
-        self.set_error("No staff selected".to_string());
-        Err(anyhow::anyhow!("No staff selected"))
+        toast::push(Toast::error(t("assign.no_staff")));
+        Err(anyhow::anyhow!(t("assign.no_staff")))
     }
 
     fn update_input_value(&mut self) {
@@ -177,10 +207,15 @@ impl UpdateStaff {
                     StaffRole::Nurse => "Nurse".to_string(),
                     StaffRole::Admin => "Admin".to_string(),
                     StaffRole::Technician => "Technician".to_string(),
+                    StaffRole::Accountant => "Accountant".to_string(),
                 },
                 PHONE_INPUT => self.staff.phone_number.clone(),
                 EMAIL_INPUT => self.staff.email.clone().unwrap_or_default(),
                 ADDRESS_INPUT => self.staff.address.clone(),
+                POSTCODE_INPUT => self.staff.postcode.clone(),
+                CITY_INPUT => self.staff.city.clone(),
+                REGION_INPUT => self.staff.region.clone(),
+                ACCOUNT_INPUT => self.account.clone(),
                 _ => String::new(),
             };
         }
@@ -193,6 +228,12 @@ impl UpdateStaff {
 
         if let Some(field_index) = self.selected_field {
             match field_index {
+                // The role and the linked account decide what an account may
+                // do, so only administrators change them.
+                ROLE_INPUT | ACCOUNT_INPUT if !self.is_admin() => {
+                    self.input_error = Some(t("staff.update.admins_only").to_string());
+                    return;
+                }
                 NAME_INPUT => self.staff.name = self.input_value.clone(),
                 ROLE_INPUT => {
                     self.staff.role = match self.input_value.to_lowercase().as_str() {
@@ -200,18 +241,60 @@ impl UpdateStaff {
                         "nurse" | "n" => StaffRole::Nurse,
                         "admin" | "a" => StaffRole::Admin,
                         "technician" | "t" => StaffRole::Technician,
+                        "accountant" | "c" => StaffRole::Accountant,
                         _ => StaffRole::Doctor,
                     }
                 }
-                PHONE_INPUT => self.staff.phone_number = self.input_value.clone(),
-                EMAIL_INPUT => self.staff.email = Some(self.input_value.clone()),
+                PHONE_INPUT => match form::phone(self.input_value.trim()) {
+                    Ok(()) => self.staff.phone_number = contact::normalize_phone(&self.input_value),
+                    Err(error) => {
+                        self.input_error = Some(error);
+                        return;
+                    }
+                },
+                EMAIL_INPUT if self.input_value.trim().is_empty() => self.staff.email = None,
+                EMAIL_INPUT => match form::email(self.input_value.trim()) {
+                    Ok(()) => self.staff.email = Some(contact::normalize_email(&self.input_value)),
+                    Err(error) => {
+                        self.input_error = Some(error);
+                        return;
+                    }
+                },
                 ADDRESS_INPUT => self.staff.address = self.input_value.clone(),
+                POSTCODE_INPUT => {
+                    self.staff.postcode = self.input_value.trim().to_string();
+                    if let Some((city, region)) = hooks::lookup_postcode(&self.staff.postcode) {
+                        if self.staff.city.trim().is_empty() {
+                            self.staff.city = city;
+                        }
+                        if self.staff.region.trim().is_empty() {
+                            self.staff.region = region;
+                        }
+                    }
+                }
+                CITY_INPUT => self.staff.city = self.input_value.clone(),
+                REGION_INPUT => self.staff.region = self.input_value.clone(),
+                ACCOUNT_INPUT => {
+                    let username = self.input_value.trim();
+                    if !username.is_empty()
+                        && !db::username_exists(&self.db, username).unwrap_or(false)
+                    {
+                        self.input_error =
+                            Some(tf("staff.update.no_account", &[("name", &username)]));
+                        return;
+                    }
+                    self.account = username.to_string();
+                }
                 _ => {}
             }
         }
         self.editing = false;
     }
 
+    fn is_admin(&self) -> bool {
+        ui_state::current_user().is_some_and(|user| auth::is_admin(&self.db, &user))
+    }
+
     fn show_confirmation(&mut self, message: String, action: ConfirmAction) {
         self.show_confirmation = true;
         self.confirmation_message = message;
@@ -219,13 +302,30 @@ impl UpdateStaff {
         self.confirmation_selected = 0;
     }
 
+    /// Moves the account link to the account entered, if it was changed.
+    fn save_account_link(&mut self, user: &str) -> Result<()> {
+        if self.account == self.linked_account {
+            return Ok(());
+        }
+        if !self.linked_account.is_empty() {
+            db::link_user_to_staff(&self.db, user, &self.linked_account, None)?;
+        }
+        if !self.account.is_empty() {
+            db::link_user_to_staff(&self.db, user, &self.account, Some(self.staff.id))?;
+        }
+        self.linked_account = self.account.clone();
+        Ok(())
+    }
+
     fn update_staff(&mut self) -> Result<()> {
-        match db::update_staff_member(&self.staff) {
+        let user = ui_state::current_user().unwrap_or_default();
+        match db::update_staff_member(&self.db, &user, &self.staff)
+            .and_then(|()| self.save_account_link(&user))
+        {
             Ok(_) => {
-                self.success_message = Some("Staff updated successfully!".to_string());
-                self.success_timer = Some(Instant::now());
+                toast::push(Toast::success(t("staff.update.updated")));
 
-                if let Ok(staff) = db::get_all_staff() {
+                if let Ok(staff) = db::get_all_staff(&self.db) {
                     self.all_staff = staff.clone();
                     self.filtered_staff = staff;
                     self.filter_staff();
@@ -234,7 +334,7 @@ impl UpdateStaff {
                 Ok(())
             }
             Err(e) => {
-                self.set_error(format!("Database error: {}", e));
+                toast::push(Toast::error(tf("common.database_error", &[("error", &e)])));
                 Err(e)
             }
         }
@@ -243,15 +343,12 @@ impl UpdateStaff {
     fn back_to_selection(&mut self) {
         self.update_state = UpdateState::SelectingStaff;
         self.loaded = false;
+        self.edit_lock = None;
         self.staff_id_input = String::new();
         self.editing = false;
-        self.clear_error();
-        self.clear_success();
     }
 
     fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
-        self.check_timeouts();
-
         if self.show_confirmation {
             match key.code {
                 KeyCode::Left | KeyCode::Right => {
@@ -279,15 +376,18 @@ impl UpdateStaff {
             match key.code {
                 KeyCode::Char(c) => {
                     self.input_value.push(c);
+                    self.input_error = None;
                 }
                 KeyCode::Backspace => {
                     self.input_value.pop();
+                    self.input_error = None;
                 }
                 KeyCode::Enter => {
                     self.apply_edited_value();
                 }
                 KeyCode::Esc => {
                     self.editing = false;
+                    self.input_error = None;
                     self.update_input_value();
                 }
                 _ => {}
@@ -297,15 +397,8 @@ impl UpdateStaff {
 
         if matches!(self.update_state, UpdateState::SelectingStaff) {
             match key.code {
-                KeyCode::Char(c) if self.is_searching => {
-                    self.search_input.push(c);
-                    self.filter_staff();
-                    self.clear_error();
-                }
-                KeyCode::Backspace if self.is_searching => {
-                    self.search_input.pop();
+                _ if self.is_searching && self.search_input.handle_key(key) => {
                     self.filter_staff();
-                    self.clear_error();
                 }
                 KeyCode::Down if self.is_searching && !self.filtered_staff.is_empty() => {
                     self.is_searching = false;
@@ -325,12 +418,10 @@ impl UpdateStaff {
                 KeyCode::Char(c) if !self.is_searching => {
                     self.staff_id_input.push(c);
                     self.input_value = self.staff_id_input.clone();
-                    self.clear_error();
                 }
                 KeyCode::Backspace if !self.is_searching => {
                     self.staff_id_input.pop();
                     self.input_value = self.staff_id_input.clone();
-                    self.clear_error();
                 }
                 KeyCode::Up if !self.is_searching => {
                     let selected = self.table_state.selected().unwrap_or(0);
@@ -392,7 +483,7 @@ impl UpdateStaff {
                 if key.modifiers.contains(KeyModifiers::CONTROL) =>
             {
                 self.show_confirmation(
-                    "Are you sure you want to update this staff member? (y/n)".to_string(),
+                    t("staff.update.confirm").to_string(),
                     ConfirmAction::UpdateStaff,
                 );
             }
@@ -408,48 +499,6 @@ impl UpdateStaff {
 
         Ok(None)
     }
-
-    fn clear_error(&mut self) {
-        self.error_message = None;
-        self.error_timer = None;
-    }
-
-    fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_timer = Some(Instant::now());
-    }
-
-    fn clear_success(&mut self) {
-        self.success_message = None;
-        self.success_timer = None;
-    }
-
-    fn check_success_timeout(&mut self) {
-        if let Some(timer) = self.success_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_success();
-            }
-        }
-    }
-
-    fn check_error_timeout(&mut self) {
-        if let Some(timer) = self.error_timer {
-            if timer.elapsed() > Duration::from_secs(5) {
-                self.clear_error();
-            }
-        }
-    }
-
-    fn check_timeouts(&mut self) {
-        self.check_error_timeout();
-        self.check_success_timeout();
-    }
-}
-
-impl Default for UpdateStaff {
-    fn default() -> Self {
-        Self::new()
-    }
 }
 
 impl Component for UpdateStaff {
@@ -463,7 +512,7 @@ impl Component for UpdateStaff {
     fn render(&self, frame: &mut Frame) {
         let area = frame.area();
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             area,
         );
 
@@ -495,16 +544,16 @@ impl UpdateStaff {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, main_layout[0]);
 
-        let title = Paragraph::new("✍️  SELECT STAFF TO UPDATE")
+        let title = Paragraph::new(t("staff.update.title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, main_layout[0]);
@@ -513,24 +562,20 @@ impl UpdateStaff {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Search Staff ",
+                format!(" {} ", t("common.search_staff")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
-        let search_paragraph = Paragraph::new(self.search_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
+        let search_paragraph = Paragraph::new(self.search_input.line(self.is_searching))
+            .style(Style::default().fg(theme().text).bg(theme().input))
             .block(search_block);
         frame.render_widget(search_paragraph, main_layout[1]);
 
@@ -538,81 +583,83 @@ impl UpdateStaff {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(Span::styled(
-                " Staff ID ",
+                format!(" {} ", t("staff.update.staff_id")),
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             ))
             .border_style(if !self.is_searching {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
         let id_input_paragraph = Paragraph::new(self.staff_id_input.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
+            .style(Style::default().fg(theme().text).bg(theme().input))
             .block(id_input_block);
         frame.render_widget(id_input_paragraph, main_layout[2]);
 
         if self.filtered_staff.is_empty() {
             let no_staff = Paragraph::new(if self.search_input.is_empty() {
-                "No staff found in database"
+                t("common.no_staff")
             } else {
-                "No staff match your search criteria"
+                t("common.no_staff_matches")
             })
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Center);
             frame.render_widget(no_staff, main_layout[3]);
         } else {
+            let widths = [
+                Constraint::Percentage(15),
+                Constraint::Percentage(40),
+                Constraint::Percentage(45),
+            ];
+            let columns = column_widths(main_layout[3].inner(Margin::new(1, 1)), &widths, 2, "► ");
             let staff_rows: Vec<Row> = self
                 .filtered_staff
                 .iter()
                 .map(|s| {
                     Row::new(vec![
-                        s.id.to_string(),
-                        s.name.clone(),
-                        s.phone_number.clone(),
+                        cell(s.id.to_string(), &columns, 0),
+                        cell(&s.name, &columns, 1),
+                        cell(&s.phone_number, &columns, 2),
                     ])
-                    .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+                    .style(Style::default().fg(theme().text))
                     .height(1)
                     .bottom_margin(0)
                 })
                 .collect();
 
             let selected_style = Style::default()
-                .fg(Color::Rgb(250, 250, 110))
-                .bg(Color::Rgb(40, 40, 60))
+                .fg(theme().focus)
+                .bg(theme().highlight)
                 .add_modifier(Modifier::BOLD);
 
-            let header = Row::new(vec!["ID", "Name", "Phone"])
+            let header = Row::new(vec![t("common.id"), t("common.name"), t("common.phone")])
                 .style(
                     Style::default()
-                        .fg(Color::Rgb(220, 220, 240))
-                        .bg(Color::Rgb(80, 60, 130))
+                        .fg(theme().text)
+                        .bg(theme().header)
                         .add_modifier(Modifier::BOLD),
                 )
                 .height(1);
 
-            let widths = [
-                Constraint::Percentage(15),
-                Constraint::Percentage(40),
-                Constraint::Percentage(45),
-            ];
-
             let staff_table = Table::new(staff_rows, widths)
                 .header(header)
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .title(format!(" Staff ({}) ", self.filtered_staff.len()))
-                        .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-                        .style(Style::default().bg(Color::Rgb(26, 26, 36))),
+                        .title(format!(
+                            " {} ",
+                            tf(
+                                "common.staff_count",
+                                &[("total", &self.filtered_staff.len())]
+                            )
+                        ))
+                        .border_style(Style::default().fg(theme().unfocused))
+                        .style(Style::default().bg(theme().input)),
                 )
                 .column_spacing(2)
                 .row_highlight_style(selected_style)
@@ -622,34 +669,14 @@ impl UpdateStaff {
             frame.render_stateful_widget(staff_table, main_layout[3], &mut table_state_copy);
         }
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(255, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, main_layout[4]);
-        } else if let Some(success) = &self.success_message {
-            let success_paragraph = Paragraph::new(success.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, main_layout[4]);
-        }
-
         let help_text = if self.is_searching {
-            "Type to search | ↓: To results | Esc: Cancel search"
+            t("common.search_help")
         } else {
-            "/ or s: Search | ↑/↓: Navigate staff | Enter: Select staff | Esc: Back"
+            t("staff.update.help")
         };
 
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, main_layout[5]);
     }
@@ -659,7 +686,7 @@ impl UpdateStaff {
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),
-                Constraint::Min(10),
+                Constraint::Min(13),
                 Constraint::Length(3),
                 Constraint::Length(1),
                 Constraint::Length(2),
@@ -669,87 +696,90 @@ impl UpdateStaff {
 
         let header_block = Block::default()
             .borders(Borders::BOTTOM)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(16, 16, 28)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
         frame.render_widget(header_block, main_layout[0]);
 
         let title_text = if self.editing {
-            "✍️  EDITING STAFF"
+            t("staff.update.editing_title")
         } else {
-            "✍️  UPDATE STAFF"
+            t("staff.update.update_title")
         };
 
         let title = Paragraph::new(title_text)
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD)
-                    .bg(Color::Rgb(16, 16, 28)),
+                    .bg(theme().background),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, main_layout[0]);
+        edit_warning::render(
+            frame,
+            Rect {
+                y: main_layout[0].y + 1,
+                height: 1,
+                ..main_layout[0]
+            },
+            self.edit_lock.as_ref(),
+        );
 
         let id_str = self.staff.id.to_string();
         let role_str = match self.staff.role {
-            StaffRole::Doctor => "Doctor",
-            StaffRole::Nurse => "Nurse",
-            StaffRole::Admin => "Admin",
-            StaffRole::Technician => "Technician",
+            StaffRole::Doctor => t("staff.role.doctor"),
+            StaffRole::Nurse => t("staff.role.nurse"),
+            StaffRole::Admin => t("staff.role.admin"),
+            StaffRole::Technician => t("staff.role.technician"),
+            StaffRole::Accountant => t("staff.role.accountant"),
         };
         let email_str = self.staff.email.clone().unwrap_or_default();
 
-        let table_items = vec![
-            Row::new(vec!["ID", &id_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Name", &self.staff.name])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Role", &role_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Phone", &self.staff.phone_number])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Email", &email_str])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-                .height(1)
-                .bottom_margin(0),
-            Row::new(vec!["Address", &self.staff.address])
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+        let widths = [Constraint::Percentage(30), Constraint::Percentage(70)];
+        let columns = column_widths(main_layout[1].inner(Margin::new(1, 1)), &widths, 2, "► ");
+        let field = |label: &str, value: &str| {
+            Row::new(vec![cell(label, &columns, 0), cell(value, &columns, 1)])
+                .style(Style::default().fg(theme().text))
                 .height(1)
-                .bottom_margin(0),
+                .bottom_margin(0)
+        };
+
+        let table_items = vec![
+            field(t("common.id"), &id_str),
+            field(t("common.name"), &self.staff.name),
+            field(t("common.role"), role_str),
+            field(t("common.phone"), &self.staff.phone_number),
+            field(t("patients.email"), &email_str),
+            field(t("patients.street"), &self.staff.address),
+            field(t("patients.postcode"), &self.staff.postcode),
+            field(t("patients.city"), &self.staff.city),
+            field(t("patients.region"), &self.staff.region),
+            field(t("staff.update.account"), &self.account),
         ];
 
         let selected_style = Style::default()
-            .fg(Color::Rgb(250, 250, 110))
-            .bg(Color::Rgb(40, 40, 60))
+            .fg(theme().focus)
+            .bg(theme().highlight)
             .add_modifier(Modifier::BOLD);
 
-        let header = Row::new(vec!["Field", "Value"])
+        let header = Row::new(vec![t("common.field"), t("common.value")])
             .style(
                 Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(80, 60, 130))
+                    .fg(theme().text)
+                    .bg(theme().header)
                     .add_modifier(Modifier::BOLD),
             )
             .height(1);
 
-        let widths = [Constraint::Percentage(30), Constraint::Percentage(70)];
-
         let table = Table::new(table_items, widths)
             .header(header)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
-                    .title(" Staff Data ")
-                    .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-                    .style(Style::default().bg(Color::Rgb(26, 26, 36))),
+                    .title(format!(" {} ", t("staff.update.data")))
+                    .border_style(Style::default().fg(theme().unfocused))
+                    .style(Style::default().bg(theme().input)),
             )
             .column_spacing(2)
             .row_highlight_style(selected_style)
@@ -759,67 +789,58 @@ impl UpdateStaff {
         frame.render_stateful_widget(table, main_layout[1], &mut edit_table_state_copy);
 
         let input_label = match self.selected_field {
-            Some(ID_INPUT) => "ID",
-            Some(NAME_INPUT) => "Name",
-            Some(ROLE_INPUT) => "Role",
-            Some(PHONE_INPUT) => "Phone",
-            Some(EMAIL_INPUT) => "Email",
-            Some(ADDRESS_INPUT) => "Address",
-            _ => "Field",
+            Some(ID_INPUT) => t("common.id"),
+            Some(NAME_INPUT) => t("common.name"),
+            Some(ROLE_INPUT) => t("common.role"),
+            Some(PHONE_INPUT) => t("common.phone"),
+            Some(EMAIL_INPUT) => t("patients.email"),
+            Some(ADDRESS_INPUT) => t("patients.street"),
+            Some(POSTCODE_INPUT) => t("patients.postcode"),
+            Some(CITY_INPUT) => t("patients.city"),
+            Some(REGION_INPUT) => t("patients.region"),
+            Some(ACCOUNT_INPUT) => t("staff.update.account"),
+            _ => t("common.field"),
         };
 
-        let input_block = Block::default()
+        let mut input_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(format!(
-                " {} {} ",
-                if self.editing { "Editing" } else { "Selected" },
-                input_label
+                " {} ",
+                if self.editing {
+                    tf("common.editing_field", &[("field", &input_label)])
+                } else {
+                    tf("common.selected_field", &[("field", &input_label)])
+                }
             ))
-            .border_style(if self.editing {
-                Style::default().fg(Color::Rgb(140, 219, 140))
+            .border_style(if self.input_error.is_some() {
+                Style::default().fg(theme().error)
+            } else if self.editing {
+                Style::default().fg(theme().success)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
+        if let Some(error) = &self.input_error {
+            input_block = input_block.title_bottom(Line::styled(
+                format!(" ⚠ {} ", error),
+                Style::default().fg(theme().error),
+            ));
+        }
 
         let input_paragraph = Paragraph::new(self.input_value.clone())
-            .style(
-                Style::default()
-                    .fg(Color::Rgb(220, 220, 240))
-                    .bg(Color::Rgb(26, 26, 36)),
-            )
+            .style(Style::default().fg(theme().text).bg(theme().input))
             .block(input_block);
         frame.render_widget(input_paragraph, main_layout[2]);
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(255, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, main_layout[3]);
-        } else if let Some(success) = &self.success_message {
-            let success_paragraph = Paragraph::new(success.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(140, 219, 140))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, main_layout[3]);
-        }
-
         let help_text = if self.editing {
-            "Enter: Save Changes | Esc: Cancel Editing"
+            t("common.edit_help")
         } else {
-            "↑/↓: Navigate | E: Edit Field | Ctrl+S: Save Staff | Esc: Back"
+            t("staff.update.view_help")
         };
 
         let help_paragraph = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Center);
         frame.render_widget(help_paragraph, main_layout[4]);
     }
@@ -837,16 +858,16 @@ impl UpdateStaff {
         frame.render_widget(Clear, dialog_area);
 
         let dialog_block = Block::default()
-            .title(" Update Patient ")
+            .title(format!(" {} ", t("integrity.update_staff")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-            .style(Style::default().bg(Color::Rgb(30, 30, 46)));
+            .border_style(Style::default().fg(theme().unfocused))
+            .style(Style::default().bg(theme().dialog));
 
         frame.render_widget(dialog_block.clone(), dialog_area);
 
@@ -858,7 +879,7 @@ impl UpdateStaff {
             .split(inner_area);
 
         let message = Paragraph::new(self.confirmation_message.as_str())
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .add_modifier(Modifier::BOLD)
             .alignment(Alignment::Center);
         frame.render_widget(message, content_layout[0]);
@@ -870,28 +891,28 @@ impl UpdateStaff {
 
         let yes_style = if self.confirmation_selected == 0 {
             Style::default()
-                .fg(Color::Rgb(140, 219, 140))
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
         let no_style = if self.confirmation_selected == 1 {
             Style::default()
-                .fg(Color::Rgb(255, 100, 100))
+                .fg(theme().error)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let yes_text = if self.confirmation_selected == 0 {
-            "► Yes ◄"
+            format!("► {} ◄", t("common.yes"))
         } else {
-            "  Yes  "
+            format!("  {}  ", t("common.yes"))
         };
         let no_text = if self.confirmation_selected == 1 {
-            "► No ◄"
+            format!("► {} ◄", t("common.no"))
         } else {
-            "  No  "
+            format!("  {}  ", t("common.no"))
         };
 
         let yes_button = Paragraph::new(yes_text)
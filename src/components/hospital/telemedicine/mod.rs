@@ -0,0 +1,96 @@
+use crate::app::SelectedApp;
+use crate::components::Component;
+use crate::db::Db;
+use crate::i18n::t;
+use crate::models::TeleconsultStatus;
+use crate::tui::Frame;
+use anyhow::Result;
+use crossterm::event::KeyEvent;
+
+pub mod schedule;
+pub mod upcoming;
+pub mod utilization;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelemedicineState {
+    Schedule,
+    Upcoming,
+    Utilization,
+}
+
+pub struct Telemedicine {
+    pub schedule: schedule::ScheduleTeleconsultation,
+    pub upcoming: upcoming::UpcomingTeleconsultations,
+    pub utilization: utilization::UtilizationReport,
+    pub state: TelemedicineState,
+}
+
+impl Telemedicine {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            schedule: schedule::ScheduleTeleconsultation::new(db),
+            upcoming: upcoming::UpcomingTeleconsultations::new(db),
+            utilization: utilization::UtilizationReport::new(db),
+            state: TelemedicineState::Upcoming,
+        }
+    }
+
+    pub fn save_view(&self) {
+        self.upcoming.save_view();
+    }
+
+    pub fn initialize_list(&mut self) -> Result<()> {
+        match self.state {
+            TelemedicineState::Schedule => self.schedule.load_data()?,
+            TelemedicineState::Upcoming => self.upcoming.fetch_consultations()?,
+            TelemedicineState::Utilization => self.utilization.load_data()?,
+        }
+        Ok(())
+    }
+}
+
+pub fn status_text(status: TeleconsultStatus) -> &'static str {
+    match status {
+        TeleconsultStatus::Scheduled => t("appointments.status.scheduled"),
+        TeleconsultStatus::Completed => t("telemedicine.status.completed"),
+        TeleconsultStatus::NoShow => t("appointments.status.no_show"),
+        TeleconsultStatus::Cancelled => t("appointments.status.cancelled"),
+    }
+}
+
+impl Component for Telemedicine {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        match self.state {
+            TelemedicineState::Schedule => {
+                if let Some(SelectedApp::None) = self.schedule.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
+            TelemedicineState::Upcoming => {
+                if let Some(SelectedApp::None) = self.upcoming.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
+            TelemedicineState::Utilization => {
+                if let Some(SelectedApp::None) = self.utilization.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        match self.state {
+            TelemedicineState::Schedule => self.schedule.render(frame),
+            TelemedicineState::Upcoming => self.upcoming.render(frame),
+            TelemedicineState::Utilization => self.utilization.render(frame),
+        }
+    }
+
+    fn tick(&mut self) {
+        if self.state == TelemedicineState::Upcoming {
+            self.upcoming.tick();
+        }
+    }
+}
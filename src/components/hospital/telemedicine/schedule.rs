@@ -0,0 +1,444 @@
+use crate::app::SelectedApp;
+use crate::components::quick_actions;
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::date_picker::DatePicker;
+use crate::components::widgets::new_patient::{NewPatient, NewPatientOutcome};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{Patient, StaffMember, StaffRole, TeleconsultStatus, Teleconsultation};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{calendar, datetime};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+
+const PATIENT_INPUT: usize = 0;
+const DOCTOR_INPUT: usize = 1;
+const TIME_INPUT: usize = 2;
+const CONTACT_INPUT: usize = 3;
+const DURATION_INPUT: usize = 4;
+const RECORD_INPUT: usize = 5;
+const SUBMIT_BUTTON: usize = 6;
+const BACK_BUTTON: usize = 7;
+const INPUT_FIELDS: usize = 6;
+
+pub struct ScheduleTeleconsultation {
+    db: Db,
+    patients: HashMap<i64, Patient>,
+    doctors: HashMap<i64, StaffMember>,
+    patient_id_input: String,
+    doctor_id_input: String,
+    scheduled_at_input: String,
+    contact_input: String,
+    duration_input: String,
+    record_id_input: String,
+    focus_index: usize,
+    date_picker: Option<DatePicker>,
+    new_patient: Option<NewPatient>,
+}
+
+impl ScheduleTeleconsultation {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            patients: HashMap::new(),
+            doctors: HashMap::new(),
+            patient_id_input: String::new(),
+            doctor_id_input: String::new(),
+            scheduled_at_input: String::new(),
+            contact_input: String::new(),
+            duration_input: String::new(),
+            record_id_input: String::new(),
+            focus_index: PATIENT_INPUT,
+            date_picker: None,
+            new_patient: None,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        self.doctors = db::get_all_staff(&self.db)?
+            .into_iter()
+            .filter(|s| s.role == StaffRole::Doctor)
+            .map(|s| (s.id, s))
+            .collect();
+        Ok(())
+    }
+
+    fn resolved_patient(&self) -> Option<&Patient> {
+        self.patient_id_input
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|id| self.patients.get(&id))
+    }
+
+    fn resolved_doctor(&self) -> Option<&StaffMember> {
+        self.doctor_id_input
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|id| self.doctors.get(&id))
+    }
+
+    fn input_mut(&mut self) -> Option<&mut String> {
+        match self.focus_index {
+            PATIENT_INPUT => Some(&mut self.patient_id_input),
+            DOCTOR_INPUT => Some(&mut self.doctor_id_input),
+            TIME_INPUT => Some(&mut self.scheduled_at_input),
+            CONTACT_INPUT => Some(&mut self.contact_input),
+            DURATION_INPUT => Some(&mut self.duration_input),
+            RECORD_INPUT => Some(&mut self.record_id_input),
+            _ => None,
+        }
+    }
+
+    fn reset_form(&mut self) {
+        self.patient_id_input.clear();
+        self.doctor_id_input.clear();
+        self.scheduled_at_input.clear();
+        self.contact_input.clear();
+        self.duration_input.clear();
+        self.record_id_input.clear();
+        self.focus_index = PATIENT_INPUT;
+    }
+
+    fn submit(&mut self) -> Result<()> {
+        let Some(patient_id) = self.resolved_patient().map(|p| p.id) else {
+            toast::push(Toast::error(t("common.unknown_patient")));
+            return Ok(());
+        };
+        let Some(doctor_id) = self.resolved_doctor().map(|d| d.id) else {
+            toast::push(Toast::error(t("common.unknown_doctor")));
+            return Ok(());
+        };
+        let typed = self.scheduled_at_input.trim().to_string();
+        let Some(start) = datetime::parse_date_time(&typed) else {
+            toast::push(Toast::error(t("telemedicine.schedule.bad_time")));
+            return Ok(());
+        };
+        if let Some(reason) = calendar::closed_reason(start.date()) {
+            toast::push(Toast::error(tf(
+                "telemedicine.schedule.other_day",
+                &[("reason", &reason)],
+            )));
+            return Ok(());
+        }
+        let contact = self.contact_input.trim().to_string();
+        if contact.is_empty() {
+            toast::push(Toast::error(t("telemedicine.schedule.no_contact")));
+            return Ok(());
+        }
+        let duration_minutes = match self.duration_input.trim() {
+            "" => None,
+            value => match value.parse::<i32>() {
+                Ok(minutes) if minutes > 0 => Some(minutes),
+                _ => {
+                    toast::push(Toast::error(t("telemedicine.schedule.bad_duration")));
+                    return Ok(());
+                }
+            },
+        };
+        let medical_record_id = match self.record_id_input.trim() {
+            "" => None,
+            value => match value
+                .parse::<i64>()
+                .ok()
+                .map(|id| db::get_medical_record(&self.db, id))
+            {
+                Some(Ok(record)) if record.patient_id == patient_id => Some(record.id),
+                Some(Ok(_)) => {
+                    toast::push(Toast::error(t("telemedicine.schedule.wrong_record")));
+                    return Ok(());
+                }
+                _ => {
+                    toast::push(Toast::error(t("telemedicine.schedule.no_record")));
+                    return Ok(());
+                }
+            },
+        };
+
+        let consultation = Teleconsultation {
+            id: 0,
+            patient_id,
+            doctor_id,
+            scheduled_at: datetime::to_stored(start),
+            contact,
+            duration_minutes,
+            status: TeleconsultStatus::Scheduled,
+            outcome: None,
+            medical_record_id,
+            checked_in_at: None,
+        };
+
+        match db::create_teleconsultation(&self.db, &consultation) {
+            Ok(_) => {
+                let message = tf("telemedicine.schedule.scheduled", &[("time", &typed)]);
+                self.reset_form();
+                toast::push(Toast::success(message));
+                quick_actions::saved(SelectedApp::TeleconsultSchedule);
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "telemedicine.schedule.failed",
+                &[("error", &e)],
+            ))),
+        }
+        Ok(())
+    }
+
+    fn open_date_picker(&mut self) {
+        let today = datetime::today();
+        let initial =
+            datetime::parse_date(datetime::date_part(&self.scheduled_at_input)).unwrap_or(today);
+        self.date_picker = Some(DatePicker::new(initial).with_min(today));
+    }
+
+    fn handle_date_picker(&mut self, key: KeyEvent) {
+        let Some(picker) = self.date_picker.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let time_part = self
+                    .scheduled_at_input
+                    .trim()
+                    .get(10..)
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .unwrap_or("09:00")
+                    .to_string();
+                self.scheduled_at_input =
+                    format!("{} {}", datetime::format_date(picker.selected()), time_part);
+                self.date_picker = None;
+            }
+            KeyCode::Esc => self.date_picker = None,
+            _ => {
+                picker.handle_key(key);
+            }
+        }
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if self.date_picker.is_some() {
+            self.handle_date_picker(key);
+            return Ok(None);
+        }
+        if let Some(popup) = &mut self.new_patient {
+            match popup.handle_key(key) {
+                Some(NewPatientOutcome::Created(patient)) => {
+                    self.new_patient = None;
+                    self.patient_id_input = patient.id.to_string();
+                    self.patients.insert(patient.id, *patient);
+                    self.focus_index = DOCTOR_INPUT;
+                }
+                Some(NewPatientOutcome::Cancelled) => self.new_patient = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+
+        match key.code {
+            KeyCode::Char('d')
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && self.focus_index == TIME_INPUT =>
+            {
+                self.open_date_picker();
+            }
+            KeyCode::Char('n')
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && self.focus_index == PATIENT_INPUT =>
+            {
+                self.new_patient = Some(NewPatient::new(&self.db));
+            }
+            KeyCode::Char(c) => {
+                if let Some(input) = self.input_mut() {
+                    input.push(c);
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(input) = self.input_mut() {
+                    input.pop();
+                }
+            }
+            KeyCode::Tab | KeyCode::Down => {
+                self.focus_index = (self.focus_index + 1) % (BACK_BUTTON + 1);
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                self.focus_index = (self.focus_index + BACK_BUTTON) % (BACK_BUTTON + 1);
+            }
+            KeyCode::Enter => match self.focus_index {
+                SUBMIT_BUTTON => self.submit()?,
+                BACK_BUTTON => return Ok(Some(SelectedApp::None)),
+                _ => self.focus_index += 1,
+            },
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+impl Component for ScheduleTeleconsultation {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(18),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(t("telemedicine.schedule.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let patient_label = match self.resolved_patient() {
+            Some(p) => format!(
+                " {} ",
+                tf(
+                    "telemedicine.schedule.patient_named",
+                    &[("first", &p.first_name), ("last", &p.last_name)]
+                )
+            ),
+            None => format!(" {} ", t("telemedicine.schedule.patient")),
+        };
+        let doctor_label = match self.resolved_doctor() {
+            Some(d) => format!(
+                " {} ",
+                tf("telemedicine.schedule.doctor_named", &[("name", &d.name)])
+            ),
+            None => format!(" {} ", t("telemedicine.schedule.doctor")),
+        };
+        let fields = [
+            (patient_label, &self.patient_id_input),
+            (doctor_label, &self.doctor_id_input),
+            (
+                format!(" {} ", t("telemedicine.schedule.time")),
+                &self.scheduled_at_input,
+            ),
+            (
+                format!(" {} ", t("telemedicine.schedule.contact")),
+                &self.contact_input,
+            ),
+            (
+                format!(" {} ", t("telemedicine.schedule.duration")),
+                &self.duration_input,
+            ),
+            (
+                format!(" {} ", t("telemedicine.schedule.record")),
+                &self.record_id_input,
+            ),
+        ];
+
+        let form_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3); INPUT_FIELDS])
+            .horizontal_margin(2)
+            .split(layout[1]);
+
+        for (i, (label, value)) in fields.iter().enumerate() {
+            let input = Paragraph::new(value.as_str())
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(Span::styled(
+                            label.clone(),
+                            Style::default().fg(theme().title),
+                        ))
+                        .border_style(if self.focus_index == i {
+                            Style::default().fg(theme().focus)
+                        } else {
+                            Style::default().fg(theme().unfocused)
+                        })
+                        .style(Style::default().bg(theme().input)),
+                );
+            frame.render_widget(input, form_layout[i]);
+        }
+
+        let submit_style = if self.focus_index == SUBMIT_BUTTON {
+            Style::default()
+                .fg(theme().success)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        frame.render_widget(
+            Paragraph::new(if self.focus_index == SUBMIT_BUTTON {
+                format!("► {} ◄", t("telemedicine.schedule.schedule"))
+            } else {
+                format!("  {}  ", t("telemedicine.schedule.schedule"))
+            })
+            .style(submit_style)
+            .alignment(Alignment::Center),
+            layout[2],
+        );
+
+        let back_style = if self.focus_index == BACK_BUTTON {
+            Style::default()
+                .fg(theme().accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        frame.render_widget(
+            Paragraph::new(if self.focus_index == BACK_BUTTON {
+                format!("► {} ◄", t("common.back"))
+            } else {
+                format!("  {}  ", t("common.back"))
+            })
+            .style(back_style)
+            .alignment(Alignment::Center),
+            layout[3],
+        );
+
+        frame.render_widget(
+            Paragraph::new(t("telemedicine.schedule.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[5],
+        );
+
+        if let Some(picker) = &self.date_picker {
+            picker.render_popup(frame, t("telemedicine.schedule.date_title"));
+        }
+        if let Some(popup) = &self.new_patient {
+            popup.render_popup(frame);
+        }
+    }
+}
@@ -0,0 +1,692 @@
+use crate::app::SelectedApp;
+use crate::components::hospital::telemedicine::status_text;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::feedback::FeedbackPopup;
+use crate::components::widgets::no_shows::{self, NoShowReport};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{Patient, StaffMember, StaffRole, TeleconsultStatus, Teleconsultation};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state::{self, ViewState};
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use time::Date;
+
+const OUTCOME_STATUSES: [TeleconsultStatus; 4] = [
+    TeleconsultStatus::Completed,
+    TeleconsultStatus::NoShow,
+    TeleconsultStatus::Cancelled,
+    TeleconsultStatus::Scheduled,
+];
+
+const STATUS_FIELD: usize = 0;
+const DURATION_FIELD: usize = 1;
+const OUTCOME_FIELD: usize = 2;
+const RECORD_FIELD: usize = 3;
+const OUTCOME_FIELDS: usize = 4;
+pub const VIEW_KEY: &str = "telemedicine.upcoming";
+/// How often consultations past the grace period are looked for while the
+/// list is open.
+const NO_SHOW_SWEEP: Duration = Duration::from_secs(60);
+
+struct OutcomeForm {
+    consultation: Teleconsultation,
+    status_index: usize,
+    duration_input: String,
+    outcome_input: String,
+    record_id_input: String,
+    focus_index: usize,
+}
+
+pub struct UpcomingTeleconsultations {
+    db: Db,
+    day: Date,
+    consultations: Vec<Teleconsultation>,
+    filtered_consultations: Vec<Teleconsultation>,
+    patients: HashMap<i64, Patient>,
+    doctors: Vec<StaffMember>,
+    doctor_filter: Option<usize>,
+    /// Doctor filter remembered from the last session, applied once the
+    /// doctors are loaded.
+    restore_doctor: Option<i64>,
+    state: TableState,
+    outcome_form: Option<OutcomeForm>,
+    report: Option<NoShowReport>,
+    feedback: Option<FeedbackPopup>,
+    last_sweep: Instant,
+}
+
+impl UpcomingTeleconsultations {
+    pub fn new(db: &Db) -> Self {
+        let view = ui_state::view(VIEW_KEY);
+        Self {
+            db: db.clone(),
+            day: datetime::today(),
+            consultations: Vec::new(),
+            filtered_consultations: Vec::new(),
+            patients: HashMap::new(),
+            doctors: Vec::new(),
+            doctor_filter: None,
+            restore_doctor: view.filter("doctor").and_then(|id| id.parse().ok()),
+            state: view.table(),
+            outcome_form: None,
+            report: None,
+            feedback: None,
+            last_sweep: Instant::now(),
+        }
+    }
+
+    fn day_text(&self) -> String {
+        datetime::format_date(self.day)
+    }
+
+    pub fn fetch_consultations(&mut self) -> Result<()> {
+        self.mark_no_shows();
+        self.consultations = db::get_teleconsultations_for_day(&self.db, &self.day_text())?;
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        self.doctors = db::get_all_staff(&self.db)?
+            .into_iter()
+            .filter(|s| s.role == StaffRole::Doctor)
+            .collect();
+        if let Some(id) = self.restore_doctor.take() {
+            self.doctor_filter = self.doctors.iter().position(|d| d.id == id);
+        }
+        if self.doctor_filter.is_some_and(|i| i >= self.doctors.len()) {
+            self.doctor_filter = None;
+        }
+        self.filter_consultations();
+        Ok(())
+    }
+
+    fn mark_no_shows(&mut self) -> usize {
+        self.last_sweep = Instant::now();
+        no_shows::mark_overdue(&self.db)
+    }
+
+    pub fn tick(&mut self) {
+        if self.last_sweep.elapsed() >= NO_SHOW_SWEEP && self.mark_no_shows() > 0 {
+            if let Err(e) = self.fetch_consultations() {
+                toast::push(Toast::error(tf(
+                    "telemedicine.refresh_failed",
+                    &[("error", &e)],
+                )));
+            }
+        }
+    }
+
+    pub fn save_view(&self) {
+        let doctor = self
+            .doctor_filter
+            .map(|i| self.doctors[i].id)
+            .or(self.restore_doctor);
+        let view = ViewState::default().with_filter("doctor", doctor);
+        ui_state::set_view(VIEW_KEY, view.with_table(&self.state));
+    }
+
+    fn filter_consultations(&mut self) {
+        let doctor_id = self.doctor_filter.map(|i| self.doctors[i].id);
+        self.filtered_consultations = self
+            .consultations
+            .iter()
+            .filter(|c| doctor_id.is_none_or(|id| c.doctor_id == id))
+            .cloned()
+            .collect();
+
+        match self.state.selected() {
+            _ if self.filtered_consultations.is_empty() => self.state.select(None),
+            Some(i) if i < self.filtered_consultations.len() => {}
+            _ => self.state.select(Some(0)),
+        }
+    }
+
+    fn patient_name(&self, patient_id: i64) -> String {
+        self.patients
+            .get(&patient_id)
+            .map(|p| format!("{} {}", p.first_name, p.last_name))
+            .unwrap_or_else(|| tf("common.unknown_number", &[("id", &patient_id)]))
+    }
+
+    fn doctor_name(&self, doctor_id: i64) -> String {
+        self.doctors
+            .iter()
+            .find(|d| d.id == doctor_id)
+            .map(|d| d.name.clone())
+            .unwrap_or_else(|| tf("common.unknown_number", &[("id", &doctor_id)]))
+    }
+
+    fn selected_consultation(&self) -> Option<&Teleconsultation> {
+        self.state
+            .selected()
+            .and_then(|i| self.filtered_consultations.get(i))
+    }
+
+    fn change_day(&mut self, forward: bool) -> Result<()> {
+        let next = if forward {
+            self.day.next_day()
+        } else {
+            self.day.previous_day()
+        };
+        if let Some(day) = next {
+            self.day = day;
+            self.state.select(None);
+            self.fetch_consultations()?;
+        }
+        Ok(())
+    }
+
+    fn cycle_doctor_filter(&mut self) {
+        self.doctor_filter = match self.doctor_filter {
+            None if !self.doctors.is_empty() => Some(0),
+            Some(i) if i + 1 < self.doctors.len() => Some(i + 1),
+            _ => None,
+        };
+        self.filter_consultations();
+    }
+
+    fn toggle_check_in(&mut self) -> Result<()> {
+        let Some(consultation) = self.selected_consultation().cloned() else {
+            return Ok(());
+        };
+        if consultation.status != TeleconsultStatus::Scheduled {
+            toast::push(Toast::error(t("telemedicine.scheduled_only")));
+            return Ok(());
+        }
+        let arrived = consultation.checked_in_at.is_none();
+        match db::check_in_teleconsultation(&self.db, consultation.id, arrived) {
+            Ok(()) => {
+                let name = self.patient_name(consultation.patient_id);
+                toast::push(Toast::success(if arrived {
+                    tf("appointments.checked_in", &[("patient", &name)])
+                } else {
+                    tf("telemedicine.check_in_undone", &[("patient", &name)])
+                }));
+                self.fetch_consultations()?;
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "telemedicine.check_in_failed",
+                &[("error", &e)],
+            ))),
+        }
+        Ok(())
+    }
+
+    fn open_report(&mut self) {
+        match NoShowReport::new(&self.db) {
+            Ok(report) => self.report = Some(report),
+            Err(e) => toast::push(Toast::error(tf(
+                "telemedicine.no_show_failed",
+                &[("error", &e)],
+            ))),
+        }
+    }
+
+    fn open_outcome_form(&mut self) {
+        if let Some(consultation) = self.selected_consultation().cloned() {
+            let status_index = OUTCOME_STATUSES
+                .iter()
+                .position(|s| *s == consultation.status)
+                .filter(|_| consultation.status != TeleconsultStatus::Scheduled)
+                .unwrap_or(0);
+            self.outcome_form = Some(OutcomeForm {
+                status_index,
+                duration_input: consultation
+                    .duration_minutes
+                    .map(|d| d.to_string())
+                    .unwrap_or_default(),
+                outcome_input: consultation.outcome.clone().unwrap_or_default(),
+                record_id_input: consultation
+                    .medical_record_id
+                    .map(|id| id.to_string())
+                    .unwrap_or_default(),
+                focus_index: STATUS_FIELD,
+                consultation,
+            });
+        }
+    }
+
+    /// Takes the patient's rating of the selected consultation, once it
+    /// has been completed.
+    fn open_feedback(&mut self) -> Result<()> {
+        let Some(consultation) = self.selected_consultation() else {
+            return Ok(());
+        };
+        if consultation.status != TeleconsultStatus::Completed {
+            toast::push(Toast::error(t("telemedicine.completed_only")));
+            return Ok(());
+        }
+        let subject = tf(
+            "telemedicine.with_doctor",
+            &[
+                ("patient", &self.patient_name(consultation.patient_id)),
+                ("doctor", &self.doctor_name(consultation.doctor_id)),
+            ],
+        );
+        self.feedback = Some(FeedbackPopup::new(
+            &self.db,
+            consultation.patient_id,
+            consultation.doctor_id,
+            Some(consultation.id),
+            None,
+            subject,
+        )?);
+        Ok(())
+    }
+
+    fn save_outcome(&mut self) -> Result<()> {
+        let Some(form) = &self.outcome_form else {
+            return Ok(());
+        };
+        let duration_minutes = match form.duration_input.trim() {
+            "" => None,
+            value => match value.parse::<i32>() {
+                Ok(minutes) if minutes > 0 => Some(minutes),
+                _ => {
+                    toast::push(Toast::error(t("telemedicine.schedule.bad_duration")));
+                    return Ok(());
+                }
+            },
+        };
+        let medical_record_id = match form.record_id_input.trim() {
+            "" => None,
+            value => match value
+                .parse::<i64>()
+                .ok()
+                .map(|id| db::get_medical_record(&self.db, id))
+            {
+                Some(Ok(record)) if record.patient_id == form.consultation.patient_id => {
+                    Some(record.id)
+                }
+                Some(Ok(_)) => {
+                    toast::push(Toast::error(t("telemedicine.schedule.wrong_record")));
+                    return Ok(());
+                }
+                _ => {
+                    toast::push(Toast::error(t("telemedicine.schedule.no_record")));
+                    return Ok(());
+                }
+            },
+        };
+
+        let consultation = Teleconsultation {
+            duration_minutes,
+            status: OUTCOME_STATUSES[form.status_index],
+            outcome: if form.outcome_input.trim().is_empty() {
+                None
+            } else {
+                Some(form.outcome_input.trim().to_string())
+            },
+            medical_record_id,
+            ..form.consultation.clone()
+        };
+
+        match db::update_teleconsultation_outcome(&self.db, &consultation) {
+            Ok(()) => {
+                self.outcome_form = None;
+                toast::push(Toast::success(tf(
+                    "telemedicine.marked",
+                    &[
+                        ("patient", &self.patient_name(consultation.patient_id)),
+                        ("status", &status_text(consultation.status)),
+                    ],
+                )));
+                self.fetch_consultations()?;
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "telemedicine.outcome_failed",
+                &[("error", &e)],
+            ))),
+        }
+        Ok(())
+    }
+
+    fn handle_outcome_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(form) = &mut self.outcome_form else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Left if form.focus_index == STATUS_FIELD => {
+                form.status_index =
+                    (form.status_index + OUTCOME_STATUSES.len() - 1) % OUTCOME_STATUSES.len();
+            }
+            KeyCode::Right | KeyCode::Char(' ') if form.focus_index == STATUS_FIELD => {
+                form.status_index = (form.status_index + 1) % OUTCOME_STATUSES.len();
+            }
+            KeyCode::Char(c) => match form.focus_index {
+                DURATION_FIELD => form.duration_input.push(c),
+                OUTCOME_FIELD => form.outcome_input.push(c),
+                RECORD_FIELD => form.record_id_input.push(c),
+                _ => {}
+            },
+            KeyCode::Backspace => match form.focus_index {
+                DURATION_FIELD => {
+                    form.duration_input.pop();
+                }
+                OUTCOME_FIELD => {
+                    form.outcome_input.pop();
+                }
+                RECORD_FIELD => {
+                    form.record_id_input.pop();
+                }
+                _ => {}
+            },
+            KeyCode::Tab | KeyCode::Down => {
+                form.focus_index = (form.focus_index + 1) % OUTCOME_FIELDS;
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                form.focus_index = (form.focus_index + OUTCOME_FIELDS - 1) % OUTCOME_FIELDS;
+            }
+            KeyCode::Enter => self.save_outcome()?,
+            KeyCode::Esc => self.outcome_form = None,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(popup) = self.feedback.as_mut() {
+            if popup.handle_key(key).is_some() {
+                self.feedback = None;
+            }
+            return Ok(None);
+        }
+        if self.outcome_form.is_some() {
+            self.handle_outcome_input(key)?;
+            return Ok(None);
+        }
+        if let Some(report) = &mut self.report {
+            if report.handle_key(key) {
+                self.report = None;
+            }
+            return Ok(None);
+        }
+
+        match key.code {
+            KeyCode::Down if !self.filtered_consultations.is_empty() => {
+                let len = self.filtered_consultations.len();
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if !self.filtered_consultations.is_empty() => {
+                let len = self.filtered_consultations.len();
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Left => self.change_day(false)?,
+            KeyCode::Right => self.change_day(true)?,
+            KeyCode::Char('t') | KeyCode::Char('T') => {
+                self.day = datetime::today();
+                self.fetch_consultations()?;
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => self.cycle_doctor_filter(),
+            KeyCode::Enter | KeyCode::Char('o') | KeyCode::Char('O') => self.open_outcome_form(),
+            KeyCode::Char('c') | KeyCode::Char('C') => self.toggle_check_in()?,
+            KeyCode::Char('f') | KeyCode::Char('F') => self.open_feedback()?,
+            KeyCode::Char('n') | KeyCode::Char('N') => self.open_report(),
+            KeyCode::Char('r') | KeyCode::Char('R') => self.fetch_consultations()?,
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_outcome_form(&self, frame: &mut Frame, form: &OutcomeForm) {
+        let area = frame.area();
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(60) / 2,
+            area.height.saturating_sub(17) / 2,
+            60.min(area.width),
+            17.min(area.height),
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(
+                " {} ",
+                tf(
+                    "telemedicine.outcome_title",
+                    &[("patient", &self.patient_name(form.consultation.patient_id))]
+                )
+            ))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        let fields = [
+            (
+                "telemedicine.outcome.status",
+                format!("◄ {} ►", status_text(OUTCOME_STATUSES[form.status_index])),
+            ),
+            ("telemedicine.outcome.duration", form.duration_input.clone()),
+            ("telemedicine.outcome.summary", form.outcome_input.clone()),
+            ("telemedicine.schedule.record", form.record_id_input.clone()),
+        ];
+        for (i, (label, value)) in fields.iter().enumerate() {
+            frame.render_widget(
+                Paragraph::new(value.as_str())
+                    .style(Style::default().fg(theme().text))
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .border_type(BorderType::Rounded)
+                            .title(format!(" {} ", t(label)))
+                            .border_style(if form.focus_index == i {
+                                Style::default().fg(theme().focus)
+                            } else {
+                                Style::default().fg(theme().unfocused)
+                            }),
+                    ),
+                chunks[i],
+            );
+        }
+        frame.render_widget(
+            Paragraph::new(t("common.form_save_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[4],
+        );
+    }
+}
+
+impl Component for UpcomingTeleconsultations {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(10),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(t("telemedicine.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let doctor_label = match self.doctor_filter {
+            Some(i) => tf("common.doctor", &[("name", &self.doctors[i].name)]),
+            None => t("waiting.all_doctors").to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(format!("◄ {} ► | {}", self.day_text(), doctor_label))
+                .style(Style::default().fg(theme().accent))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("telemedicine.day_doctor")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[1],
+        );
+
+        let header_row = Row::new(vec![
+            t("kiosk.time"),
+            t("telemedicine.in"),
+            t("kiosk.patient"),
+            t("kiosk.doctor"),
+            t("telemedicine.contact_column"),
+            t("telemedicine.min"),
+            t("kiosk.status"),
+            t("integrity.record"),
+            t("telemedicine.outcome"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+
+        let widths = [
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Percentage(16),
+            Constraint::Percentage(14),
+            Constraint::Percentage(22),
+            Constraint::Length(4),
+            Constraint::Length(10),
+            Constraint::Length(7),
+            Constraint::Min(10),
+        ];
+        let columns = column_widths(layout[2].inner(Margin::new(1, 1)), &widths, 1, "► ");
+
+        let rows = self.filtered_consultations.iter().map(|c| {
+            let status_style = match c.status {
+                TeleconsultStatus::Scheduled => Style::default().fg(theme().accent),
+                TeleconsultStatus::Completed => Style::default().fg(theme().success),
+                TeleconsultStatus::NoShow => Style::default().fg(theme().error),
+                TeleconsultStatus::Cancelled => Style::default().fg(theme().inactive),
+            };
+            let (status, status_style) = match (c.status, &c.checked_in_at) {
+                (TeleconsultStatus::Scheduled, Some(_)) => {
+                    (t("kiosk.checked_in"), Style::default().fg(theme().success))
+                }
+                (status, _) => (status_text(status), status_style),
+            };
+            Row::new(vec![
+                cell(datetime::time_part(&c.scheduled_at), &columns, 0),
+                cell(
+                    c.checked_in_at
+                        .as_deref()
+                        .map_or("-".to_string(), datetime::time_part),
+                    &columns,
+                    1,
+                ),
+                cell(self.patient_name(c.patient_id), &columns, 2),
+                cell(self.doctor_name(c.doctor_id), &columns, 3),
+                cell(&c.contact, &columns, 4),
+                cell(
+                    c.duration_minutes
+                        .map(|d| d.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    &columns,
+                    5,
+                ),
+                cell(status, &columns, 6).style(status_style),
+                cell(
+                    c.medical_record_id
+                        .map(|id| format!("#{}", id))
+                        .unwrap_or_else(|| "-".to_string()),
+                    &columns,
+                    7,
+                ),
+                cell(c.outcome.as_deref().unwrap_or_default(), &columns, 8),
+            ])
+            .style(Style::default().fg(theme().text))
+        });
+
+        let table = Table::new(rows, widths)
+            .header(header_row)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(
+                        " {} ",
+                        tf(
+                            "telemedicine.count",
+                            &[("count", &self.filtered_consultations.len())]
+                        )
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
+
+        frame.render_widget(
+            Paragraph::new(t("telemedicine.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+
+        if let Some(form) = &self.outcome_form {
+            self.render_outcome_form(frame, form);
+        }
+        if let Some(report) = &self.report {
+            report.render_popup(frame);
+        }
+        if let Some(popup) = &self.feedback {
+            popup.render_popup(frame);
+        }
+    }
+
+    fn tick(&mut self) {
+        self.tick();
+    }
+}
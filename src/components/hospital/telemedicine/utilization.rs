@@ -0,0 +1,402 @@
+//! Per-doctor clinic throughput over a period: consultations booked and
+//! attended, walk-ins seen from the queue, how long consultations take from
+//! check-in to check-out, and the appointment slots left idle on shift.
+
+use crate::app::SelectedApp;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::date_picker::DatePicker;
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{self, t, tf};
+use crate::models::DoctorUtilization;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{config, csv_line, datetime, write_export};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use time::{Date, Duration};
+
+/// Every shift (Morning, Afternoon, Night) is eight hours long.
+const SHIFT_MINUTES: i64 = 8 * 60;
+
+const HEADERS: [&str; 10] = [
+    "satisfaction.doctor",
+    "performance.shifts",
+    "utilization.booked",
+    "appointments.status.attended",
+    "utilization.no_shows",
+    "appointments.status.cancelled",
+    "utilization.attended_percent",
+    "performance.walk_ins",
+    "utilization.avg_minutes",
+    "utilization.idle_slots",
+];
+
+fn month_range(date: Date) -> (Date, Date) {
+    let first = date.replace_day(1).unwrap_or(date);
+    let last = first
+        .replace_day(first.month().length(first.year()))
+        .unwrap_or(first);
+    (first, last)
+}
+
+/// Share of the consultations that went ahead which were attended.
+fn attended_percent(row: &DoctorUtilization) -> Option<f64> {
+    let due = row.booked - row.cancelled;
+    (due > 0).then(|| row.attended as f64 * 100.0 / due as f64)
+}
+
+/// Slots on shift that no consultation was booked into and no walk-in
+/// filled.
+fn idle_slots(row: &DoctorUtilization, slot_minutes: i64) -> i64 {
+    let slots = row.shifts * (SHIFT_MINUTES / slot_minutes.max(1));
+    (slots - (row.booked - row.cancelled) - row.walk_ins).max(0)
+}
+
+fn values(row: &DoctorUtilization, slot_minutes: i64) -> Vec<String> {
+    vec![
+        row.name.clone(),
+        row.shifts.to_string(),
+        row.booked.to_string(),
+        row.attended.to_string(),
+        row.no_shows.to_string(),
+        row.cancelled.to_string(),
+        attended_percent(row).map_or("-".to_string(), |p| format!("{:.0}%", p)),
+        row.walk_ins.to_string(),
+        row.average_minutes
+            .map_or("-".to_string(), |m| format!("{:.0}", m)),
+        idle_slots(row, slot_minutes).to_string(),
+    ]
+}
+
+pub struct UtilizationReport {
+    db: Db,
+    from: Date,
+    to: Date,
+    rows: Vec<DoctorUtilization>,
+    range_picker: Option<(DatePicker, Option<Date>)>,
+    state: TableState,
+}
+
+impl UtilizationReport {
+    pub fn new(db: &Db) -> Self {
+        let (from, to) = month_range(datetime::today());
+        Self {
+            db: db.clone(),
+            from,
+            to,
+            rows: Vec::new(),
+            range_picker: None,
+            state: TableState::default(),
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.rows = db::get_doctor_utilization(
+            &self.db,
+            &datetime::format_date(self.from),
+            &datetime::format_date(self.to),
+        )?;
+        match self.state.selected() {
+            _ if self.rows.is_empty() => self.state.select(None),
+            Some(i) if i < self.rows.len() => {}
+            _ => self.state.select(Some(0)),
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) {
+        if let Err(e) = self.load_data() {
+            toast::push(Toast::error(tf(
+                "utilization.load_failed",
+                &[("error", &e)],
+            )));
+        }
+    }
+
+    fn change_month(&mut self, forward: bool) {
+        let (from, to) = if forward {
+            month_range(self.to + Duration::days(1))
+        } else {
+            month_range(self.from - Duration::days(1))
+        };
+        self.from = from;
+        self.to = to;
+        self.reload();
+    }
+
+    fn handle_range_picker(&mut self, key: KeyEvent) {
+        let Some((picker, start)) = self.range_picker.as_mut() else {
+            return;
+        };
+        match (key.code, *start) {
+            (KeyCode::Enter, None) => {
+                let from = picker.selected();
+                *start = Some(from);
+                *picker = picker.clone().with_min(from);
+            }
+            (KeyCode::Enter, Some(from)) => {
+                self.from = from;
+                self.to = picker.selected();
+                self.range_picker = None;
+                self.reload();
+            }
+            (KeyCode::Esc, _) => self.range_picker = None,
+            _ => {
+                picker.handle_key(key);
+            }
+        }
+    }
+
+    fn export(&self) {
+        if self.rows.is_empty() {
+            toast::push(Toast::error(t("utilization.no_doctors")));
+            return;
+        }
+        let slot_minutes = config::config().slot_minutes as i64;
+        let mut contents = csv_line(&HEADERS.map(i18n::english));
+        contents.push('\n');
+        for row in &self.rows {
+            let values = values(row, slot_minutes);
+            let fields: Vec<&str> = values.iter().map(String::as_str).collect();
+            contents.push_str(&csv_line(&fields));
+            contents.push('\n');
+        }
+        let file_name = format!(
+            "doctor_utilization_{}_{}.csv",
+            datetime::format_date(self.from),
+            datetime::format_date(self.to)
+        );
+        match write_export(&file_name, &contents) {
+            Ok(path) => toast::push(Toast::success(tf(
+                "utilization.exported",
+                &[("count", &self.rows.len()), ("path", &path.display())],
+            ))),
+            Err(e) => toast::push(Toast::error(format!("{}", e))),
+        }
+    }
+
+    fn summary(&self, slot_minutes: i64) -> Line<'static> {
+        let total = |f: fn(&DoctorUtilization) -> i64| self.rows.iter().map(f).sum::<i64>();
+        let idle: i64 = self
+            .rows
+            .iter()
+            .map(|row| idle_slots(row, slot_minutes))
+            .sum();
+        let separator = || Span::styled("  |  ", Style::default().fg(theme().inactive));
+        Line::from(vec![
+            Span::styled(
+                tf(
+                    "utilization.booked_count",
+                    &[("count", &(total(|r| r.booked)))],
+                ),
+                Style::default()
+                    .fg(theme().text)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            separator(),
+            Span::styled(
+                tf(
+                    "utilization.attended_count",
+                    &[("count", &(total(|r| r.attended)))],
+                ),
+                Style::default().fg(theme().success),
+            ),
+            separator(),
+            Span::styled(
+                tf(
+                    "utilization.no_shows_count",
+                    &[("count", &(total(|r| r.no_shows)))],
+                ),
+                Style::default().fg(theme().error),
+            ),
+            separator(),
+            Span::styled(
+                tf(
+                    "utilization.walk_ins_count",
+                    &[("count", &(total(|r| r.walk_ins)))],
+                ),
+                Style::default().fg(theme().text),
+            ),
+            separator(),
+            Span::styled(
+                tf(
+                    "utilization.idle",
+                    &[("count", &idle), ("minutes", &slot_minutes)],
+                ),
+                Style::default().fg(theme().warning),
+            ),
+        ])
+    }
+}
+
+impl Component for UtilizationReport {
+    fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if self.range_picker.is_some() {
+            self.handle_range_picker(key);
+            return Ok(None);
+        }
+        let len = self.rows.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Left => self.change_month(false),
+            KeyCode::Right => self.change_month(true),
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.range_picker = Some((DatePicker::new(self.from), None));
+            }
+            KeyCode::Char('e') | KeyCode::Char('E') => self.export(),
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                self.reload();
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("utilization.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(tf(
+                "common.date_range",
+                &[
+                    ("from", &datetime::format_date(self.from)),
+                    ("to", &datetime::format_date(self.to)),
+                ],
+            ))
+            .style(Style::default().fg(theme().accent))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("stays.period")))
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            ),
+            layout[1],
+        );
+
+        let slot_minutes = config::config().slot_minutes as i64;
+        frame.render_widget(
+            Paragraph::new(self.summary(slot_minutes)).alignment(Alignment::Center),
+            layout[2],
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.rows.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("utilization.empty"))
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[3],
+            );
+        } else {
+            let widths = [
+                Constraint::Min(16),
+                Constraint::Length(6),
+                Constraint::Length(6),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(9),
+                Constraint::Length(10),
+                Constraint::Length(8),
+                Constraint::Length(11),
+                Constraint::Length(10),
+            ];
+            let columns = column_widths(block.inner(layout[3]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .rows
+                .iter()
+                .map(|row| {
+                    let style = if row.booked == 0 && row.walk_ins == 0 {
+                        Style::default().fg(theme().inactive)
+                    } else {
+                        Style::default().fg(theme().text)
+                    };
+                    Row::new(
+                        values(row, slot_minutes)
+                            .into_iter()
+                            .enumerate()
+                            .map(|(column, text)| cell(text, &columns, column))
+                            .collect::<Vec<_>>(),
+                    )
+                    .style(style)
+                })
+                .collect();
+            let table = Table::new(rows, widths)
+                .header(
+                    Row::new(HEADERS.map(t).to_vec())
+                        .style(Style::default().bg(theme().header).fg(theme().title)),
+                )
+                .block(block)
+                .row_highlight_style(Style::default().bg(theme().highlight))
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[3], &mut self.state.clone());
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("utilization.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+
+        if let Some((picker, start)) = &self.range_picker {
+            let title = if start.is_some() {
+                t("statistics.period_end")
+            } else {
+                t("statistics.period_start")
+            };
+            picker.render_popup(frame, title);
+        }
+    }
+}
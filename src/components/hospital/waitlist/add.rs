@@ -0,0 +1,619 @@
+use crate::app::SelectedApp;
+use crate::components::hospital::waitlist::priority_text;
+use crate::components::quick_actions;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{
+    Patient, StaffMember, StaffRole, WaitlistEntry, WaitlistPriority, WaitlistStatus,
+};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddWaitlistState {
+    SelectingPatient,
+    SelectingDoctor,
+    EnteringDetails,
+}
+
+const DETAIL_FIELDS: usize = 4;
+const SUBMIT_BUTTON: usize = 4;
+const BACK_BUTTON: usize = 5;
+
+pub struct AddWaitlistEntry {
+    db: Db,
+    all_patients: Vec<Patient>,
+    filtered_patients: Vec<Patient>,
+    doctors: Vec<StaffMember>,
+    search_input: String,
+    is_searching: bool,
+    patient_table_state: TableState,
+    doctor_list_state: ListState,
+    selected_patient: Option<Patient>,
+    selected_doctor: Option<StaffMember>,
+    procedure: String,
+    priority: WaitlistPriority,
+    preferred_date: String,
+    notes: String,
+    focus_index: usize,
+    state: AddWaitlistState,
+}
+
+impl AddWaitlistEntry {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            all_patients: Vec::new(),
+            filtered_patients: Vec::new(),
+            doctors: Vec::new(),
+            search_input: String::new(),
+            is_searching: false,
+            patient_table_state: TableState::default(),
+            doctor_list_state: ListState::default(),
+            selected_patient: None,
+            selected_doctor: None,
+            procedure: String::new(),
+            priority: WaitlistPriority::Normal,
+            preferred_date: String::new(),
+            notes: String::new(),
+            focus_index: 0,
+            state: AddWaitlistState::SelectingPatient,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.all_patients = db::get_all_patients(&self.db)?;
+        self.doctors = db::get_all_staff(&self.db)?
+            .into_iter()
+            .filter(|s| s.role == StaffRole::Doctor)
+            .collect();
+        self.filter_patients();
+        self.doctor_list_state.select(if self.doctors.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+        Ok(())
+    }
+
+    fn filter_patients(&mut self) {
+        let search_term = self.search_input.to_lowercase();
+        self.filtered_patients = self
+            .all_patients
+            .iter()
+            .filter(|p| {
+                search_term.is_empty()
+                    || p.first_name.to_lowercase().contains(&search_term)
+                    || p.last_name.to_lowercase().contains(&search_term)
+                    || p.id.to_string().contains(&search_term)
+            })
+            .cloned()
+            .collect();
+
+        if self.filtered_patients.is_empty() {
+            self.patient_table_state.select(None);
+        } else {
+            self.patient_table_state.select(Some(0));
+        }
+    }
+
+    fn move_patient_selection(&mut self, forward: bool) {
+        let len = self.filtered_patients.len();
+        if len == 0 {
+            return;
+        }
+        let i = self.patient_table_state.selected().unwrap_or(0);
+        let next = if forward {
+            (i + 1) % len
+        } else {
+            (i + len - 1) % len
+        };
+        self.patient_table_state.select(Some(next));
+    }
+
+    fn move_doctor_selection(&mut self, forward: bool) {
+        let len = self.doctors.len();
+        if len == 0 {
+            return;
+        }
+        let i = self.doctor_list_state.selected().unwrap_or(0);
+        let next = if forward {
+            (i + 1) % len
+        } else {
+            (i + len - 1) % len
+        };
+        self.doctor_list_state.select(Some(next));
+    }
+
+    fn cycle_priority(&mut self, forward: bool) {
+        let order = [
+            WaitlistPriority::Urgent,
+            WaitlistPriority::High,
+            WaitlistPriority::Normal,
+            WaitlistPriority::Low,
+        ];
+        let i = order.iter().position(|p| *p == self.priority).unwrap_or(2);
+        let next = if forward { (i + 1) % 4 } else { (i + 3) % 4 };
+        self.priority = order[next];
+    }
+
+    fn reset_form(&mut self) {
+        self.selected_patient = None;
+        self.selected_doctor = None;
+        self.procedure.clear();
+        self.priority = WaitlistPriority::Normal;
+        self.preferred_date.clear();
+        self.notes.clear();
+        self.focus_index = 0;
+        self.state = AddWaitlistState::SelectingPatient;
+    }
+
+    fn submit(&mut self) -> Result<()> {
+        if self.procedure.trim().is_empty() {
+            toast::push(Toast::error(t("waitlist.add.no_reason")));
+            return Ok(());
+        }
+        let preferred_date = if self.preferred_date.trim().is_empty() {
+            None
+        } else {
+            match datetime::parse_date(&self.preferred_date) {
+                Some(_) => Some(self.preferred_date.trim().to_string()),
+                None => {
+                    toast::push(Toast::error(t("waitlist.add.bad_date")));
+                    return Ok(());
+                }
+            }
+        };
+        let (Some(patient), Some(doctor)) = (&self.selected_patient, &self.selected_doctor) else {
+            toast::push(Toast::error(t("waitlist.add.select_first")));
+            return Ok(());
+        };
+
+        let created_at = datetime::format_date_time(datetime::now());
+
+        let entry = WaitlistEntry {
+            id: 0,
+            patient_id: patient.id,
+            doctor_id: doctor.id,
+            procedure: self.procedure.trim().to_string(),
+            priority: self.priority,
+            preferred_date,
+            notes: if self.notes.trim().is_empty() {
+                None
+            } else {
+                Some(self.notes.trim().to_string())
+            },
+            status: WaitlistStatus::Waiting,
+            created_at,
+        };
+
+        match db::create_waitlist_entry(&self.db, &entry) {
+            Ok(_) => {
+                let message = tf(
+                    "waitlist.add.added",
+                    &[
+                        ("first", &patient.first_name),
+                        ("last", &patient.last_name),
+                        ("doctor", &doctor.name),
+                    ],
+                );
+                self.reset_form();
+                toast::push(Toast::success(message));
+                quick_actions::saved(SelectedApp::WaitlistAdd);
+            }
+            Err(e) => toast::push(Toast::error(tf("common.database_error", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        match self.state {
+            AddWaitlistState::SelectingPatient => match key.code {
+                KeyCode::Char(c) if self.is_searching => {
+                    self.search_input.push(c);
+                    self.filter_patients();
+                }
+                KeyCode::Backspace if self.is_searching => {
+                    self.search_input.pop();
+                    self.filter_patients();
+                }
+                KeyCode::Esc if self.is_searching => {
+                    self.is_searching = false;
+                    self.search_input.clear();
+                    self.filter_patients();
+                }
+                KeyCode::Down | KeyCode::Enter if self.is_searching => {
+                    self.is_searching = false;
+                }
+                KeyCode::Char('/') | KeyCode::Char('s') | KeyCode::Char('S') => {
+                    self.is_searching = true;
+                }
+                KeyCode::Up => self.move_patient_selection(false),
+                KeyCode::Down => self.move_patient_selection(true),
+                KeyCode::Enter => {
+                    if let Some(patient) = self
+                        .patient_table_state
+                        .selected()
+                        .and_then(|i| self.filtered_patients.get(i))
+                    {
+                        self.selected_patient = Some(patient.clone());
+                        self.state = AddWaitlistState::SelectingDoctor;
+                    } else {
+                        toast::push(Toast::error(t("waitlist.add.no_patient")));
+                    }
+                }
+                KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+                _ => {}
+            },
+            AddWaitlistState::SelectingDoctor => match key.code {
+                KeyCode::Up => self.move_doctor_selection(false),
+                KeyCode::Down => self.move_doctor_selection(true),
+                KeyCode::Enter => {
+                    if let Some(doctor) = self
+                        .doctor_list_state
+                        .selected()
+                        .and_then(|i| self.doctors.get(i))
+                    {
+                        self.selected_doctor = Some(doctor.clone());
+                        self.state = AddWaitlistState::EnteringDetails;
+                        self.focus_index = 0;
+                    } else {
+                        toast::push(Toast::error(t("waitlist.add.no_doctors")));
+                    }
+                }
+                KeyCode::Esc => {
+                    self.state = AddWaitlistState::SelectingPatient;
+                }
+                _ => {}
+            },
+            AddWaitlistState::EnteringDetails => match key.code {
+                KeyCode::Char(c) => match self.focus_index {
+                    0 => self.procedure.push(c),
+                    1 => match c.to_ascii_lowercase() {
+                        'u' => self.priority = WaitlistPriority::Urgent,
+                        'h' => self.priority = WaitlistPriority::High,
+                        'n' => self.priority = WaitlistPriority::Normal,
+                        'l' => self.priority = WaitlistPriority::Low,
+                        _ => {}
+                    },
+                    2 => self.preferred_date.push(c),
+                    3 => self.notes.push(c),
+                    _ => {}
+                },
+                KeyCode::Backspace => {
+                    match self.focus_index {
+                        0 => self.procedure.pop(),
+                        2 => self.preferred_date.pop(),
+                        3 => self.notes.pop(),
+                        _ => None,
+                    };
+                }
+                KeyCode::Left if self.focus_index == 1 => self.cycle_priority(false),
+                KeyCode::Right if self.focus_index == 1 => self.cycle_priority(true),
+                KeyCode::Tab | KeyCode::Down => {
+                    self.focus_index = (self.focus_index + 1) % (DETAIL_FIELDS + 2);
+                }
+                KeyCode::Up => {
+                    self.focus_index = (self.focus_index + DETAIL_FIELDS + 1) % (DETAIL_FIELDS + 2);
+                }
+                KeyCode::Enter => match self.focus_index {
+                    SUBMIT_BUTTON => self.submit()?,
+                    BACK_BUTTON => self.state = AddWaitlistState::SelectingDoctor,
+                    _ => self.focus_index += 1,
+                },
+                KeyCode::Esc => {
+                    self.state = AddWaitlistState::SelectingDoctor;
+                }
+                _ => {}
+            },
+        }
+        Ok(None)
+    }
+}
+
+impl Component for AddWaitlistEntry {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(10),
+                Constraint::Length(1),
+                Constraint::Length(2),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+
+        let title = match self.state {
+            AddWaitlistState::SelectingPatient => t("waitlist.add.select_patient_title"),
+            AddWaitlistState::SelectingDoctor => t("waitlist.add.select_doctor_title"),
+            AddWaitlistState::EnteringDetails => t("waitlist.add.details_title"),
+        };
+        frame.render_widget(
+            Paragraph::new(title)
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let summary = format!(
+            " {} ",
+            tf(
+                "waitlist.add.summary",
+                &[
+                    (
+                        "patient",
+                        &(self
+                            .selected_patient
+                            .as_ref()
+                            .map(|p| format!("{} {}", p.first_name, p.last_name))
+                            .unwrap_or_else(|| "-".to_string()))
+                    ),
+                    (
+                        "doctor",
+                        &(self
+                            .selected_doctor
+                            .as_ref()
+                            .map(|d| d.name.clone())
+                            .unwrap_or_else(|| "-".to_string()))
+                    )
+                ]
+            )
+        );
+        frame.render_widget(
+            Paragraph::new(summary)
+                .style(Style::default().fg(theme().text))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[1],
+        );
+
+        match self.state {
+            AddWaitlistState::SelectingPatient => self.render_patient_picker(frame, layout[2]),
+            AddWaitlistState::SelectingDoctor => self.render_doctor_picker(frame, layout[2]),
+            AddWaitlistState::EnteringDetails => self.render_details_form(frame, layout[2]),
+        }
+
+        let help_text = match self.state {
+            AddWaitlistState::SelectingPatient => t("waitlist.add.patient_help"),
+            AddWaitlistState::SelectingDoctor => t("waitlist.add.doctor_help"),
+            AddWaitlistState::EnteringDetails => t("waitlist.add.details_help"),
+        };
+        frame.render_widget(
+            Paragraph::new(help_text)
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+    }
+}
+
+impl AddWaitlistEntry {
+    fn render_patient_picker(&self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(4)])
+            .split(area);
+
+        let search = Paragraph::new(self.search_input.clone())
+            .style(Style::default().fg(theme().text))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("common.search_patients")))
+                    .border_style(if self.is_searching {
+                        Style::default().fg(theme().focus)
+                    } else {
+                        Style::default().fg(theme().border)
+                    })
+                    .style(Style::default().bg(theme().surface)),
+            );
+        frame.render_widget(search, chunks[0]);
+
+        let widths = [
+            Constraint::Length(8),
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+            Constraint::Min(15),
+        ];
+        let columns = column_widths(chunks[1].inner(Margin::new(1, 1)), &widths, 1, "► ");
+
+        let rows = self.filtered_patients.iter().map(|p| {
+            Row::new(vec![
+                cell(p.id.to_string(), &columns, 0),
+                cell(&p.first_name, &columns, 1),
+                cell(&p.last_name, &columns, 2),
+                cell(&p.phone_number, &columns, 3),
+            ])
+            .style(Style::default().fg(theme().text))
+        });
+        let header = Row::new(vec![
+            t("common.id"),
+            t("common.first_name"),
+            t("common.last_name"),
+            t("common.phone"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(
+                        " {} ",
+                        tf(
+                            "common.select_patient_count",
+                            &[("total", &self.filtered_patients.len())]
+                        )
+                    ))
+                    .border_style(if self.is_searching {
+                        Style::default().fg(theme().unfocused)
+                    } else {
+                        Style::default().fg(theme().focus)
+                    })
+                    .style(Style::default().bg(theme().input)),
+            )
+            .row_highlight_style(
+                Style::default()
+                    .bg(theme().highlight)
+                    .fg(theme().focus)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, chunks[1], &mut self.patient_table_state.clone());
+    }
+
+    fn render_doctor_picker(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = if self.doctors.is_empty() {
+            vec![ListItem::new(t("waitlist.add.no_doctors_registered"))]
+        } else {
+            self.doctors
+                .iter()
+                .map(|d| {
+                    ListItem::new(tf(
+                        "common.name_with_id",
+                        &[("name", &d.name), ("id", &d.id)],
+                    ))
+                })
+                .collect()
+        };
+        let list = List::new(items)
+            .style(Style::default().fg(theme().text))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("waitlist.add.select_doctor")))
+                    .border_style(Style::default().fg(theme().focus))
+                    .style(Style::default().bg(theme().input)),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(theme().highlight)
+                    .fg(theme().focus)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(list, area, &mut self.doctor_list_state.clone());
+    }
+
+    fn render_details_form(&self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(3)
+            .split(area);
+
+        let priority = format!("◄ {} ►", priority_text(self.priority));
+        let fields = [
+            ("waitlist.add.reason", self.procedure.as_str()),
+            ("waitlist.add.priority", priority.as_str()),
+            ("waitlist.add.date", self.preferred_date.as_str()),
+            ("waitlist.add.notes", self.notes.as_str()),
+        ];
+
+        for (i, (label, value)) in fields.iter().enumerate() {
+            let input = Paragraph::new(value.to_string())
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(Span::styled(
+                            format!(" {} ", t(label)),
+                            Style::default().fg(theme().title),
+                        ))
+                        .border_style(if self.focus_index == i {
+                            Style::default().fg(theme().focus)
+                        } else {
+                            Style::default().fg(theme().unfocused)
+                        })
+                        .style(Style::default().bg(theme().input)),
+                );
+            frame.render_widget(input, chunks[i]);
+        }
+
+        let submit_text = if self.focus_index == SUBMIT_BUTTON {
+            format!("► {} ◄", t("waitlist.add.submit"))
+        } else {
+            format!("  {}  ", t("waitlist.add.submit"))
+        };
+        let submit_style = if self.focus_index == SUBMIT_BUTTON {
+            Style::default()
+                .fg(theme().success)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        frame.render_widget(
+            Paragraph::new(submit_text)
+                .style(submit_style)
+                .alignment(Alignment::Center),
+            chunks[5],
+        );
+
+        let back_text = if self.focus_index == BACK_BUTTON {
+            format!("► {} ◄", t("common.back"))
+        } else {
+            format!("  {}  ", t("common.back"))
+        };
+        let back_style = if self.focus_index == BACK_BUTTON {
+            Style::default()
+                .fg(theme().accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme().inactive)
+        };
+        frame.render_widget(
+            Paragraph::new(back_text)
+                .style(back_style)
+                .alignment(Alignment::Center),
+            chunks[6],
+        );
+    }
+}
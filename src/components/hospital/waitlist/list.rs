@@ -0,0 +1,616 @@
+use crate::app::SelectedApp;
+use crate::components::hospital::waitlist::{priority_text, status_text};
+use crate::components::responsive::is_narrow;
+use crate::components::table::{cell, column_widths, Columns};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::column_picker::{self, ColumnPicker};
+use crate::components::widgets::export_view::{self, ExportView, ShownRows};
+use crate::components::widgets::saved_searches::{self, SavedSearchOutcome, SavedSearchesPopup};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{
+    Patient, StaffMember, StaffRole, WaitlistEntry, WaitlistPriority, WaitlistStatus,
+};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state::{self, ViewState};
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+
+pub const VIEW_KEY: &str = "waitlist.list";
+const HEADERS: [&str; 8] = [
+    "#",
+    "kiosk.patient",
+    "satisfaction.doctor",
+    "waitlist.procedure",
+    "waitlist.priority",
+    "waitlist.preferred",
+    "kiosk.status",
+    "waitlist.added_column",
+];
+const WIDTHS: [Constraint; 8] = [
+    Constraint::Length(4),
+    Constraint::Percentage(18),
+    Constraint::Percentage(15),
+    Constraint::Percentage(20),
+    Constraint::Length(9),
+    Constraint::Length(11),
+    Constraint::Length(8),
+    Constraint::Min(16),
+];
+const PRIORITY_COLUMN: usize = 4;
+/// The columns kept on narrow terminals.
+const MINIMAL_COLUMNS: [usize; 5] = [0, 1, 3, 4, 6];
+
+pub struct ListWaitlist {
+    db: Db,
+    entries: Vec<WaitlistEntry>,
+    filtered_entries: Vec<WaitlistEntry>,
+    patients: HashMap<i64, Patient>,
+    doctors: Vec<StaffMember>,
+    doctor_filter: Option<usize>,
+    /// Doctor filter remembered from the last session, applied once the
+    /// doctors are loaded.
+    restore_doctor: Option<i64>,
+    show_closed: bool,
+    search_input: String,
+    is_searching: bool,
+    state: TableState,
+    slot_prompt: Option<String>,
+    suggested_entry: Option<i64>,
+    saved_searches: Option<SavedSearchesPopup>,
+    export_view: Option<ExportView>,
+    columns: Columns,
+    column_picker: Option<ColumnPicker>,
+}
+
+impl ListWaitlist {
+    pub fn new(db: &Db) -> Self {
+        let view = ui_state::view(VIEW_KEY);
+        Self {
+            db: db.clone(),
+            entries: Vec::new(),
+            filtered_entries: Vec::new(),
+            patients: HashMap::new(),
+            doctors: Vec::new(),
+            doctor_filter: None,
+            restore_doctor: view.filter("doctor").and_then(|id| id.parse().ok()),
+            show_closed: view.filter("closed").is_some(),
+            search_input: view.search.clone(),
+            is_searching: false,
+            state: view.table(),
+            slot_prompt: None,
+            suggested_entry: None,
+            saved_searches: None,
+            export_view: None,
+            columns: Columns::new(VIEW_KEY, &HEADERS, &[], &MINIMAL_COLUMNS),
+            column_picker: None,
+        }
+    }
+
+    pub fn fetch_entries(&mut self) -> Result<()> {
+        self.entries = db::get_all_waitlist_entries(&self.db)?;
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        self.doctors = db::get_all_staff(&self.db)?
+            .into_iter()
+            .filter(|s| s.role == StaffRole::Doctor)
+            .collect();
+        if let Some(id) = self.restore_doctor.take() {
+            self.doctor_filter = self.doctors.iter().position(|d| d.id == id);
+        }
+        if self.doctor_filter.is_some_and(|i| i >= self.doctors.len()) {
+            self.doctor_filter = None;
+        }
+        self.filter_entries();
+        Ok(())
+    }
+
+    pub fn save_view(&self) {
+        ui_state::set_view(VIEW_KEY, self.current_view().with_table(&self.state));
+    }
+
+    fn current_view(&self) -> ViewState {
+        let doctor = self
+            .doctor_filter
+            .map(|i| self.doctors[i].id)
+            .or(self.restore_doctor);
+        ViewState {
+            search: self.search_input.clone(),
+            ..ViewState::default()
+        }
+        .with_filter("doctor", doctor)
+        .with_filter("closed", self.show_closed.then_some("shown"))
+    }
+
+    /// The cells of the `position`th row, showing `entry`.
+    fn row_values(&self, position: usize, entry: &WaitlistEntry) -> Vec<String> {
+        vec![
+            (position + 1).to_string(),
+            self.patient_name(entry.patient_id),
+            self.doctor_name(entry.doctor_id),
+            entry.procedure.clone(),
+            priority_text(entry.priority).to_string(),
+            entry
+                .preferred_date
+                .clone()
+                .unwrap_or_else(|| t("waitlist.any").to_string()),
+            status_text(entry.status).to_string(),
+            datetime::display_stamp(&entry.created_at),
+        ]
+    }
+
+    fn shown_rows(&self) -> ShownRows {
+        let visible = self.columns.visible(false);
+        let mut shown = ShownRows::new(&Columns::pick(&HEADERS, &visible));
+        shown.rows = self
+            .filtered_entries
+            .iter()
+            .enumerate()
+            .map(|(i, e)| Columns::pick(&self.row_values(i, e), &visible))
+            .collect();
+        shown
+    }
+
+    /// Shows the entries matching a saved search, from the top.
+    fn apply_view(&mut self, view: ViewState) {
+        let doctor: Option<i64> = view.filter("doctor").and_then(|id| id.parse().ok());
+        self.doctor_filter = doctor.and_then(|id| self.doctors.iter().position(|d| d.id == id));
+        self.show_closed = view.filter("closed").is_some();
+        self.search_input = view.search;
+        self.is_searching = false;
+        self.suggested_entry = None;
+        self.state.select(Some(0));
+        self.filter_entries();
+    }
+
+    fn patient_name(&self, patient_id: i64) -> String {
+        self.patients
+            .get(&patient_id)
+            .map(|p| format!("{} {}", p.first_name, p.last_name))
+            .unwrap_or_else(|| tf("common.unknown_number", &[("id", &patient_id)]))
+    }
+
+    fn doctor_name(&self, doctor_id: i64) -> String {
+        self.doctors
+            .iter()
+            .find(|d| d.id == doctor_id)
+            .map(|d| d.name.clone())
+            .unwrap_or_else(|| tf("common.unknown_number", &[("id", &doctor_id)]))
+    }
+
+    fn filter_entries(&mut self) {
+        let search_term = self.search_input.to_lowercase();
+        let doctor_id = self.doctor_filter.map(|i| self.doctors[i].id);
+        self.filtered_entries = self
+            .entries
+            .iter()
+            .filter(|e| self.show_closed || e.status == WaitlistStatus::Waiting)
+            .filter(|e| doctor_id.is_none_or(|id| e.doctor_id == id))
+            .filter(|e| {
+                search_term.is_empty()
+                    || e.procedure.to_lowercase().contains(&search_term)
+                    || self
+                        .patient_name(e.patient_id)
+                        .to_lowercase()
+                        .contains(&search_term)
+            })
+            .cloned()
+            .collect();
+
+        match self.state.selected() {
+            _ if self.filtered_entries.is_empty() => self.state.select(None),
+            Some(i) if i < self.filtered_entries.len() => {}
+            _ => self.state.select(Some(0)),
+        }
+    }
+
+    fn select_next(&mut self) {
+        let len = self.filtered_entries.len();
+        if len > 0 {
+            let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+            self.state.select(Some(i));
+        }
+    }
+
+    fn select_previous(&mut self) {
+        let len = self.filtered_entries.len();
+        if len > 0 {
+            let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+            self.state.select(Some(i));
+        }
+    }
+
+    fn selected_entry(&self) -> Option<&WaitlistEntry> {
+        self.state
+            .selected()
+            .and_then(|i| self.filtered_entries.get(i))
+    }
+
+    fn cycle_doctor_filter(&mut self) {
+        self.doctor_filter = match self.doctor_filter {
+            None if !self.doctors.is_empty() => Some(0),
+            Some(i) if i + 1 < self.doctors.len() => Some(i + 1),
+            _ => None,
+        };
+        self.suggested_entry = None;
+        self.filter_entries();
+    }
+
+    fn set_entry_status(&mut self, status: WaitlistStatus) -> Result<()> {
+        if let Some(entry) = self.selected_entry().cloned() {
+            db::update_waitlist_status(&self.db, entry.id, status)?;
+            let name = self.patient_name(entry.patient_id);
+            toast::push(Toast::success(tf(
+                "waitlist.marked",
+                &[("patient", &name), ("status", &status_text(status))],
+            )));
+            self.suggested_entry = None;
+            self.fetch_entries()?;
+        }
+        Ok(())
+    }
+
+    fn suggest_for_slot(&mut self, slot_date: &str) -> Result<()> {
+        if datetime::parse_date(slot_date).is_none() {
+            toast::push(Toast::error(t("waitlist.bad_slot")));
+            return Ok(());
+        }
+        let doctor_id = match (self.doctor_filter, self.selected_entry()) {
+            (Some(i), _) => self.doctors[i].id,
+            (None, Some(entry)) => entry.doctor_id,
+            (None, None) => {
+                toast::push(Toast::error(t("waitlist.doctor_first")));
+                return Ok(());
+            }
+        };
+
+        match db::next_waitlisted_patient(&self.db, doctor_id, None, slot_date)? {
+            Some(entry) => {
+                let message = tf(
+                    "waitlist.next",
+                    &[
+                        ("doctor", &self.doctor_name(doctor_id)),
+                        ("date", &slot_date),
+                        ("patient", &self.patient_name(entry.patient_id)),
+                        ("priority", &priority_text(entry.priority)),
+                        ("procedure", &entry.procedure),
+                    ],
+                );
+                self.suggested_entry = Some(entry.id);
+                if let Some(pos) = self.filtered_entries.iter().position(|e| e.id == entry.id) {
+                    self.state.select(Some(pos));
+                }
+                toast::push(Toast::success(message));
+            }
+            None => {
+                self.suggested_entry = None;
+                toast::push(Toast::error(tf(
+                    "waitlist.none_eligible",
+                    &[
+                        ("doctor", &self.doctor_name(doctor_id)),
+                        ("date", &slot_date),
+                    ],
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(prompt) = &mut self.slot_prompt {
+            match key.code {
+                KeyCode::Char(c) => prompt.push(c),
+                KeyCode::Backspace => {
+                    prompt.pop();
+                }
+                KeyCode::Enter => {
+                    let slot_date = prompt.trim().to_string();
+                    self.slot_prompt = None;
+                    self.suggest_for_slot(&slot_date)?;
+                }
+                KeyCode::Esc => self.slot_prompt = None,
+                _ => {}
+            }
+            return Ok(None);
+        }
+        if let Some(export) = &mut self.export_view {
+            if export.handle_key(key) {
+                self.export_view = None;
+            }
+            return Ok(None);
+        }
+        if export_view::is_open_key(key) {
+            self.export_view = Some(ExportView::new("waitlist", self.shown_rows()));
+            return Ok(None);
+        }
+        if let Some(picker) = &mut self.column_picker {
+            if picker.handle_key(key, &mut self.columns) {
+                self.column_picker = None;
+            }
+            return Ok(None);
+        }
+        if column_picker::is_open_key(key) {
+            self.column_picker = Some(ColumnPicker::new());
+            return Ok(None);
+        }
+        if let Some(popup) = &mut self.saved_searches {
+            match popup.handle_key(key) {
+                Some(SavedSearchOutcome::Apply(view)) => {
+                    self.saved_searches = None;
+                    self.apply_view(view);
+                }
+                Some(SavedSearchOutcome::Closed) => self.saved_searches = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+        if saved_searches::is_open_key(key) {
+            self.saved_searches = Some(SavedSearchesPopup::new(VIEW_KEY, self.current_view()));
+            return Ok(None);
+        }
+
+        if self.is_searching {
+            match key.code {
+                KeyCode::Char(c) => {
+                    self.search_input.push(c);
+                    self.filter_entries();
+                }
+                KeyCode::Backspace => {
+                    self.search_input.pop();
+                    self.filter_entries();
+                }
+                KeyCode::Enter | KeyCode::Down | KeyCode::Esc => self.is_searching = false,
+                _ => {}
+            }
+            return Ok(None);
+        }
+
+        match key.code {
+            KeyCode::Char('/') | KeyCode::Char('s') | KeyCode::Char('S') => {
+                self.is_searching = true;
+            }
+            KeyCode::Down => self.select_next(),
+            KeyCode::Up => self.select_previous(),
+            KeyCode::Char('d') | KeyCode::Char('D') => self.cycle_doctor_filter(),
+            KeyCode::Char('c') | KeyCode::Char('C') => {
+                self.show_closed = !self.show_closed;
+                self.filter_entries();
+            }
+            KeyCode::Char('b') | KeyCode::Char('B') => {
+                self.set_entry_status(WaitlistStatus::Booked)?;
+            }
+            KeyCode::Char('x') | KeyCode::Char('X') | KeyCode::Delete => {
+                self.set_entry_status(WaitlistStatus::Removed)?;
+            }
+            KeyCode::Char('w') | KeyCode::Char('W') => {
+                self.set_entry_status(WaitlistStatus::Waiting)?;
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') => {
+                self.slot_prompt = Some(datetime::today_text());
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => self.fetch_entries()?,
+            KeyCode::Char('1'..='9') => {
+                if let Some(view) = saved_searches::shortcut(VIEW_KEY, key) {
+                    self.apply_view(view);
+                }
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+impl Component for ListWaitlist {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(10),
+                Constraint::Length(1),
+                Constraint::Length(2),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(t("waitlist.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let filter_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(layout[1]);
+
+        let search = Paragraph::new(self.search_input.clone())
+            .style(Style::default().fg(theme().text))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("waitlist.search")))
+                    .border_style(if self.is_searching {
+                        Style::default().fg(theme().focus)
+                    } else {
+                        Style::default().fg(theme().border)
+                    })
+                    .style(Style::default().bg(theme().surface)),
+            );
+        frame.render_widget(search, filter_layout[0]);
+
+        let doctor_label = match self.doctor_filter {
+            Some(i) => tf("common.doctor", &[("name", &self.doctors[i].name)]),
+            None => t("waiting.all_doctors").to_string(),
+        };
+        let filter_text = tf(
+            "waitlist.filter_text",
+            &[
+                ("doctor", &doctor_label),
+                (
+                    "closed",
+                    &(if self.show_closed {
+                        t("waitlist.incl_closed")
+                    } else {
+                        t("waitlist.waiting_only")
+                    }),
+                ),
+            ],
+        );
+        frame.render_widget(
+            Paragraph::new(filter_text)
+                .style(Style::default().fg(theme().accent))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("waitlist.filter")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            filter_layout[1],
+        );
+
+        let visible = self.columns.visible(is_narrow(layout[2]));
+        let header_row = Row::new(Columns::pick(&HEADERS, &visible).into_iter().map(t))
+            .style(Style::default().bg(theme().header).fg(theme().title));
+
+        let widths = Columns::widths(&WIDTHS, &visible);
+        let columns = column_widths(layout[2].inner(Margin::new(1, 1)), &widths, 1, "► ");
+
+        let rows = self.filtered_entries.iter().enumerate().map(|(i, e)| {
+            let priority_style = match e.priority {
+                WaitlistPriority::Urgent => Style::default().fg(theme().error),
+                WaitlistPriority::High => Style::default().fg(theme().warning),
+                WaitlistPriority::Normal => Style::default().fg(theme().text),
+                WaitlistPriority::Low => Style::default().fg(theme().inactive),
+            };
+            let row_style = if self.suggested_entry == Some(e.id) {
+                Style::default()
+                    .fg(theme().success)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme().text)
+            };
+            let values = self.row_values(i, e);
+            Row::new(
+                visible
+                    .iter()
+                    .enumerate()
+                    .map(|(column, &index)| match index {
+                        PRIORITY_COLUMN => {
+                            cell(&values[index], &columns, column).style(priority_style)
+                        }
+                        _ => cell(&values[index], &columns, column),
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .style(row_style)
+        });
+
+        let table = Table::new(rows, widths)
+            .header(header_row)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(
+                        " {} ",
+                        tf("waitlist.count", &[("count", &self.filtered_entries.len())])
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
+
+        frame.render_widget(
+            Paragraph::new(t("waitlist.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+
+        if let Some(prompt) = &self.slot_prompt {
+            let dialog_area = Rect::new(
+                area.width.saturating_sub(50) / 2,
+                area.height.saturating_sub(7) / 2,
+                50.min(area.width),
+                7.min(area.height),
+            );
+            frame.render_widget(Clear, dialog_area);
+            let block = Block::default()
+                .title(format!(" {} ", t("waitlist.slot_title")))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme().focus))
+                .style(Style::default().bg(theme().dialog));
+            let inner = block.inner(dialog_area);
+            frame.render_widget(block, dialog_area);
+            frame.render_widget(
+                Paragraph::new(vec![
+                    Line::from(t("waitlist.slot_prompt")),
+                    Line::from(""),
+                    Line::from(Span::styled(
+                        format!("{}_", prompt),
+                        Style::default()
+                            .fg(theme().focus)
+                            .add_modifier(Modifier::BOLD),
+                    )),
+                    Line::from(""),
+                    Line::from(t("waitlist.slot_help")),
+                ])
+                .style(Style::default().fg(theme().text))
+                .alignment(Alignment::Center),
+                inner,
+            );
+        }
+        if let Some(popup) = &self.saved_searches {
+            popup.render_popup(frame);
+        }
+        if let Some(export) = &self.export_view {
+            export.render_popup(frame);
+        }
+        if let Some(picker) = &self.column_picker {
+            picker.render_popup(frame, &self.columns);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::app::SelectedApp;
+use crate::components::Component;
+use crate::db::Db;
+use crate::i18n::t;
+use crate::models::{WaitlistPriority, WaitlistStatus};
+use crate::tui::Frame;
+use anyhow::Result;
+use crossterm::event::KeyEvent;
+
+pub mod add;
+pub mod list;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitlistState {
+    AddEntry,
+    ListEntries,
+}
+
+pub struct Waitlist {
+    pub add_entry: add::AddWaitlistEntry,
+    pub list_entries: list::ListWaitlist,
+    pub state: WaitlistState,
+}
+
+impl Waitlist {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            add_entry: add::AddWaitlistEntry::new(db),
+            list_entries: list::ListWaitlist::new(db),
+            state: WaitlistState::ListEntries,
+        }
+    }
+
+    pub fn save_view(&self) {
+        self.list_entries.save_view();
+    }
+
+    pub fn initialize_list(&mut self) -> Result<()> {
+        match self.state {
+            WaitlistState::AddEntry => self.add_entry.load_data()?,
+            WaitlistState::ListEntries => self.list_entries.fetch_entries()?,
+        }
+        Ok(())
+    }
+}
+
+pub fn priority_text(priority: WaitlistPriority) -> &'static str {
+    match priority {
+        WaitlistPriority::Urgent => t("waitlist.priority.urgent"),
+        WaitlistPriority::High => t("waitlist.priority.high"),
+        WaitlistPriority::Normal => t("waitlist.priority.normal"),
+        WaitlistPriority::Low => t("waitlist.priority.low"),
+    }
+}
+
+pub fn status_text(status: WaitlistStatus) -> &'static str {
+    match status {
+        WaitlistStatus::Waiting => t("queue.status.waiting"),
+        WaitlistStatus::Booked => t("waitlist.status.booked"),
+        WaitlistStatus::Removed => t("waitlist.status.removed"),
+    }
+}
+
+impl Component for Waitlist {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        match self.state {
+            WaitlistState::AddEntry => {
+                if let Some(SelectedApp::None) = self.add_entry.handle_input(event)? {
+                    self.state = WaitlistState::ListEntries;
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
+            WaitlistState::ListEntries => {
+                if let Some(SelectedApp::None) = self.list_entries.handle_input(event)? {
+                    return Ok(Some(SelectedApp::None));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        match self.state {
+            WaitlistState::AddEntry => self.add_entry.render(frame),
+            WaitlistState::ListEntries => self.list_entries.render(frame),
+        }
+    }
+}
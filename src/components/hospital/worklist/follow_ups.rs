@@ -0,0 +1,798 @@
+use crate::app::SelectedApp;
+use crate::components::form::{date, integer_range, Field, Form};
+use crate::components::responsive::is_narrow;
+use crate::components::table::{cell, column_widths, Columns};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::column_picker::{self, ColumnPicker};
+use crate::components::widgets::export_view::{self, ExportView, ShownRows};
+use crate::components::widgets::saved_searches::{self, SavedSearchOutcome, SavedSearchesPopup};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{CarePlan, Patient, StaffMember, StaffRole};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state::{self, ViewState};
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+use time::Duration;
+
+pub const VIEW_KEY: &str = "worklist.follow_ups";
+
+/// Plans due within this many days are shown as due soon.
+const DUE_SOON_DAYS: i64 = 7;
+
+const HEADERS: [&str; 8] = [
+    "follow_ups.next_due",
+    "kiosk.status",
+    "kiosk.patient",
+    "follow_ups.condition",
+    "follow_ups.every_column",
+    "satisfaction.doctor",
+    "staff.role.nurse",
+    "follow_ups.reviewed_column",
+];
+const WIDTHS: [Constraint; 8] = [
+    Constraint::Length(11),
+    Constraint::Length(17),
+    Constraint::Percentage(20),
+    Constraint::Min(14),
+    Constraint::Length(8),
+    Constraint::Length(14),
+    Constraint::Length(14),
+    Constraint::Length(11),
+];
+const STATUS_COLUMN: usize = 1;
+/// The columns kept on narrow terminals.
+const MINIMAL_COLUMNS: [usize; 4] = [0, 1, 2, 3];
+
+const PATIENT_ID: usize = 0;
+const CONDITION: usize = 1;
+const INTERVAL: usize = 2;
+const DOCTOR_ID: usize = 3;
+const NURSE_ID: usize = 4;
+const NEXT_DUE: usize = 5;
+
+fn plan_form() -> Form {
+    Form::new(vec![
+        Field::new(t("common.patient_id"))
+            .required()
+            .validate_with(integer_range(1, i64::MAX)),
+        Field::new(t("follow_ups.condition")).required(),
+        Field::new(t("follow_ups.interval_field"))
+            .required()
+            .validate_with(integer_range(1, 3650)),
+        Field::new(t("common.doctor_id")).validate_with(integer_range(1, i64::MAX)),
+        Field::new(t("follow_ups.nurse_id_field")).validate_with(integer_range(1, i64::MAX)),
+        Field::new(t("follow_ups.due_field"))
+            .required()
+            .validate_with(Box::new(date)),
+    ])
+}
+
+struct Popup {
+    /// The plan being edited; a new plan when `None`.
+    plan_id: Option<i64>,
+    form: Form,
+    focus_index: usize,
+}
+
+fn days_left(plan: &CarePlan, today: time::Date) -> Option<i64> {
+    datetime::parse_date(&plan.next_due).map(|due| (due - today).whole_days())
+}
+
+fn status_text(days_left: Option<i64>) -> String {
+    match days_left {
+        None => "-".to_string(),
+        Some(d) if d < 0 => tf("follow_ups.overdue_days", &[("days", &(-d))]),
+        Some(0) => t("follow_ups.due_today").to_string(),
+        Some(d) => tf("follow_ups.in_days", &[("days", &d)]),
+    }
+}
+
+fn status_color(days_left: Option<i64>) -> Color {
+    match days_left {
+        Some(d) if d < 0 => theme().error,
+        Some(d) if d <= DUE_SOON_DAYS => theme().warning,
+        _ => theme().text,
+    }
+}
+
+/// Care plans for chronic conditions, soonest due first, so patients whose
+/// review is overdue stand out. Reviewing a patient moves their plan on by
+/// its interval.
+pub struct FollowUps {
+    db: Db,
+    plans: Vec<CarePlan>,
+    filtered_plans: Vec<CarePlan>,
+    patients: HashMap<i64, Patient>,
+    /// Doctors and nurses, who can be assigned to a plan.
+    clinicians: Vec<StaffMember>,
+    clinician_filter: Option<usize>,
+    restore_clinician: Option<i64>,
+    overdue_only: bool,
+    state: TableState,
+    popup: Option<Popup>,
+    confirm_delete: bool,
+    saved_searches: Option<SavedSearchesPopup>,
+    export_view: Option<ExportView>,
+    columns: Columns,
+    column_picker: Option<ColumnPicker>,
+}
+
+impl FollowUps {
+    pub fn new(db: &Db) -> Self {
+        let view = ui_state::view(VIEW_KEY);
+        Self {
+            db: db.clone(),
+            plans: Vec::new(),
+            filtered_plans: Vec::new(),
+            patients: HashMap::new(),
+            clinicians: Vec::new(),
+            clinician_filter: None,
+            restore_clinician: view.filter("clinician").and_then(|id| id.parse().ok()),
+            overdue_only: view.filter("overdue").is_some(),
+            state: view.table(),
+            popup: None,
+            confirm_delete: false,
+            saved_searches: None,
+            export_view: None,
+            columns: Columns::new(VIEW_KEY, &HEADERS, &[], &MINIMAL_COLUMNS),
+            column_picker: None,
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.plans = db::get_all_care_plans(&self.db)?;
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        self.clinicians = db::get_all_staff(&self.db)?
+            .into_iter()
+            .filter(|s| matches!(s.role, StaffRole::Doctor | StaffRole::Nurse))
+            .collect();
+        if let Some(id) = self.restore_clinician.take() {
+            self.clinician_filter = self.clinicians.iter().position(|c| c.id == id);
+        }
+        if self
+            .clinician_filter
+            .is_some_and(|i| i >= self.clinicians.len())
+        {
+            self.clinician_filter = None;
+        }
+        self.filter_plans();
+        Ok(())
+    }
+
+    pub fn save_view(&self) {
+        ui_state::set_view(VIEW_KEY, self.current_view().with_table(&self.state));
+    }
+
+    fn current_view(&self) -> ViewState {
+        let clinician = self
+            .clinician_filter
+            .map(|i| self.clinicians[i].id)
+            .or(self.restore_clinician);
+        ViewState::default()
+            .with_filter("clinician", clinician)
+            .with_filter("overdue", self.overdue_only.then_some("only"))
+    }
+
+    fn row_values(&self, plan: &CarePlan, days: Option<i64>) -> Vec<String> {
+        vec![
+            plan.next_due.clone(),
+            status_text(days),
+            self.patient_name(plan.patient_id),
+            plan.condition.clone(),
+            tf("follow_ups.every", &[("days", &plan.review_interval_days)]),
+            self.clinician_name(plan.doctor_id),
+            self.clinician_name(plan.nurse_id),
+            plan.last_reviewed
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+        ]
+    }
+
+    fn shown_rows(&self) -> ShownRows {
+        let today = datetime::today();
+        let visible = self.columns.visible(false);
+        let mut shown = ShownRows::new(&Columns::pick(&HEADERS, &visible));
+        shown.rows = self
+            .filtered_plans
+            .iter()
+            .map(|p| Columns::pick(&self.row_values(p, days_left(p, today)), &visible))
+            .collect();
+        shown
+    }
+
+    /// Shows the plans matching a saved search, from the top.
+    fn apply_view(&mut self, view: ViewState) {
+        let clinician: Option<i64> = view.filter("clinician").and_then(|id| id.parse().ok());
+        self.clinician_filter =
+            clinician.and_then(|id| self.clinicians.iter().position(|c| c.id == id));
+        self.overdue_only = view.filter("overdue").is_some();
+        self.state.select(Some(0));
+        self.filter_plans();
+    }
+
+    fn filter_plans(&mut self) {
+        let today = datetime::today();
+        let clinician = self.clinician_filter.map(|i| self.clinicians[i].id);
+        self.filtered_plans = self
+            .plans
+            .iter()
+            .filter(|p| {
+                clinician.is_none_or(|id| p.doctor_id == Some(id) || p.nurse_id == Some(id))
+            })
+            .filter(|p| !self.overdue_only || days_left(p, today).is_some_and(|d| d < 0))
+            .cloned()
+            .collect();
+
+        match self.state.selected() {
+            _ if self.filtered_plans.is_empty() => self.state.select(None),
+            Some(i) if i < self.filtered_plans.len() => {}
+            _ => self.state.select(Some(0)),
+        }
+    }
+
+    fn patient_name(&self, patient_id: i64) -> String {
+        self.patients
+            .get(&patient_id)
+            .map(|p| format!("{} {}", p.first_name, p.last_name))
+            .unwrap_or_else(|| tf("common.unknown_number", &[("id", &patient_id)]))
+    }
+
+    fn clinician_name(&self, staff_id: Option<i64>) -> String {
+        match staff_id {
+            Some(id) => self
+                .clinicians
+                .iter()
+                .find(|c| c.id == id)
+                .map(|c| c.name.clone())
+                .unwrap_or_else(|| format!("#{}", id)),
+            None => "-".to_string(),
+        }
+    }
+
+    fn selected_plan(&self) -> Option<&CarePlan> {
+        self.state
+            .selected()
+            .and_then(|i| self.filtered_plans.get(i))
+    }
+
+    fn open_popup(&mut self, plan: Option<CarePlan>) {
+        let mut form = plan_form();
+        match &plan {
+            Some(plan) => {
+                form.fields[PATIENT_ID].set(plan.patient_id.to_string());
+                form.fields[CONDITION].set(plan.condition.as_str());
+                form.fields[INTERVAL].set(plan.review_interval_days.to_string());
+                if let Some(id) = plan.doctor_id {
+                    form.fields[DOCTOR_ID].set(id.to_string());
+                }
+                if let Some(id) = plan.nurse_id {
+                    form.fields[NURSE_ID].set(id.to_string());
+                }
+                form.fields[NEXT_DUE].set(plan.next_due.as_str());
+            }
+            None => {
+                // A new plan starts with the clinician being filtered on.
+                if let Some(clinician) = self.clinician_filter.map(|i| &self.clinicians[i]) {
+                    let field = match clinician.role {
+                        StaffRole::Nurse => NURSE_ID,
+                        _ => DOCTOR_ID,
+                    };
+                    form.fields[field].set(clinician.id.to_string());
+                }
+                form.fields[NEXT_DUE].set(datetime::today_text());
+            }
+        }
+        self.popup = Some(Popup {
+            plan_id: plan.map(|p| p.id),
+            form,
+            focus_index: 0,
+        });
+    }
+
+    /// The staff ID entered in `field`, checked to be a clinician of `role`.
+    fn staff_field(&self, form: &mut Form, field: usize, role: StaffRole) -> Option<Option<i64>> {
+        let Some(id) = form.optional_value(field).and_then(|v| v.parse().ok()) else {
+            return Some(None);
+        };
+        if self.clinicians.iter().any(|c| c.id == id && c.role == role) {
+            return Some(Some(id));
+        }
+        form.fields[field].error = Some(match role {
+            StaffRole::Nurse => t("follow_ups.no_nurse").to_string(),
+            _ => t("follow_ups.no_doctor").to_string(),
+        });
+        None
+    }
+
+    fn save_popup(&mut self) -> Result<()> {
+        let Some(mut popup) = self.popup.take() else {
+            return Ok(());
+        };
+        if let Some(invalid) = popup.form.validate() {
+            popup.focus_index = invalid;
+            self.popup = Some(popup);
+            return Ok(());
+        }
+        let patient_id = popup.form.value(PATIENT_ID).parse().unwrap_or_default();
+        if !self.patients.contains_key(&patient_id) {
+            popup.form.fields[PATIENT_ID].error = Some(t("common.no_patient_id").to_string());
+            popup.focus_index = PATIENT_ID;
+            self.popup = Some(popup);
+            return Ok(());
+        }
+        let doctor_id = self.staff_field(&mut popup.form, DOCTOR_ID, StaffRole::Doctor);
+        let nurse_id = self.staff_field(&mut popup.form, NURSE_ID, StaffRole::Nurse);
+        let (Some(doctor_id), Some(nurse_id)) = (doctor_id, nurse_id) else {
+            popup.focus_index = if doctor_id.is_none() {
+                DOCTOR_ID
+            } else {
+                NURSE_ID
+            };
+            self.popup = Some(popup);
+            return Ok(());
+        };
+
+        let form = &popup.form;
+        let existing = popup
+            .plan_id
+            .and_then(|id| self.plans.iter().find(|p| p.id == id));
+        let plan = CarePlan {
+            id: popup.plan_id.unwrap_or_default(),
+            patient_id,
+            condition: form.value(CONDITION),
+            review_interval_days: form.value(INTERVAL).parse().unwrap_or(1),
+            doctor_id,
+            nurse_id,
+            next_due: form.value(NEXT_DUE),
+            last_reviewed: existing.and_then(|p| p.last_reviewed.clone()),
+        };
+        let result = match popup.plan_id {
+            Some(_) => db::update_care_plan(&self.db, &plan),
+            None => db::create_care_plan(&self.db, &plan).map(|_| ()),
+        };
+        match result {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "follow_ups.saved",
+                    &[
+                        ("condition", &plan.condition),
+                        ("patient", &self.patient_name(patient_id)),
+                    ],
+                )));
+                self.load_data()?;
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("follow_ups.save_failed", &[("error", &e)])));
+                self.popup = Some(popup);
+            }
+        }
+        Ok(())
+    }
+
+    /// Records that the selected patient was reviewed today and moves their
+    /// next review on by the plan's interval.
+    fn mark_reviewed(&mut self) -> Result<()> {
+        let Some(mut plan) = self.selected_plan().cloned() else {
+            return Ok(());
+        };
+        let today = datetime::today();
+        let next_due = today + Duration::days(plan.review_interval_days);
+        plan.last_reviewed = Some(datetime::format_date(today));
+        plan.next_due = datetime::format_date(next_due);
+        match db::update_care_plan(&self.db, &plan) {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "follow_ups.reviewed",
+                    &[
+                        ("patient", &self.patient_name(plan.patient_id)),
+                        ("date", &datetime::display_date(next_due)),
+                    ],
+                )));
+                self.load_data()?;
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "follow_ups.review_failed",
+                &[("error", &e)],
+            ))),
+        }
+        Ok(())
+    }
+
+    fn delete_selected(&mut self) -> Result<()> {
+        self.confirm_delete = false;
+        let Some(plan) = self.selected_plan().cloned() else {
+            return Ok(());
+        };
+        match db::delete_care_plan(&self.db, plan.id) {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "follow_ups.closed",
+                    &[
+                        ("condition", &plan.condition),
+                        ("patient", &self.patient_name(plan.patient_id)),
+                    ],
+                )));
+                self.load_data()?;
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "follow_ups.close_failed",
+                &[("error", &e)],
+            ))),
+        }
+        Ok(())
+    }
+
+    fn cycle_clinician_filter(&mut self, forward: bool) {
+        let count = self.clinicians.len();
+        self.clinician_filter = match (self.clinician_filter, forward) {
+            (None, true) if count > 0 => Some(0),
+            (None, false) if count > 0 => Some(count - 1),
+            (Some(i), true) if i + 1 < count => Some(i + 1),
+            (Some(i), false) if i > 0 => Some(i - 1),
+            _ => None,
+        };
+        self.filter_plans();
+    }
+
+    fn handle_popup_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(popup) = self.popup.as_mut() else {
+            return Ok(());
+        };
+        let len = popup.form.len();
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Tab | KeyCode::Down => popup.focus_index = (popup.focus_index + 1) % len,
+            KeyCode::BackTab | KeyCode::Up => {
+                popup.focus_index = (popup.focus_index + len - 1) % len
+            }
+            KeyCode::Enter if popup.focus_index + 1 < len => popup.focus_index += 1,
+            KeyCode::Enter => self.save_popup()?,
+            _ => {
+                popup.form.handle_key(popup.focus_index, key);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if self.popup.is_some() {
+            self.handle_popup_input(key)?;
+            return Ok(None);
+        }
+        if self.confirm_delete {
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.delete_selected()?
+                }
+                _ => self.confirm_delete = false,
+            }
+            return Ok(None);
+        }
+        if let Some(export) = &mut self.export_view {
+            if export.handle_key(key) {
+                self.export_view = None;
+            }
+            return Ok(None);
+        }
+        if export_view::is_open_key(key) {
+            self.export_view = Some(ExportView::new("follow_ups", self.shown_rows()));
+            return Ok(None);
+        }
+        if let Some(picker) = &mut self.column_picker {
+            if picker.handle_key(key, &mut self.columns) {
+                self.column_picker = None;
+            }
+            return Ok(None);
+        }
+        if column_picker::is_open_key(key) {
+            self.column_picker = Some(ColumnPicker::new());
+            return Ok(None);
+        }
+        if let Some(popup) = &mut self.saved_searches {
+            match popup.handle_key(key) {
+                Some(SavedSearchOutcome::Apply(view)) => {
+                    self.saved_searches = None;
+                    self.apply_view(view);
+                }
+                Some(SavedSearchOutcome::Closed) => self.saved_searches = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+        if saved_searches::is_open_key(key) {
+            self.saved_searches = Some(SavedSearchesPopup::new(VIEW_KEY, self.current_view()));
+            return Ok(None);
+        }
+        let len = self.filtered_plans.len();
+        match key.code {
+            KeyCode::Left => self.cycle_clinician_filter(false),
+            KeyCode::Right => self.cycle_clinician_filter(true),
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => self.open_popup(None),
+            KeyCode::Char('e') | KeyCode::Char('E') | KeyCode::Enter => {
+                if let Some(plan) = self.selected_plan().cloned() {
+                    self.open_popup(Some(plan));
+                }
+            }
+            KeyCode::Char('v') | KeyCode::Char('V') => self.mark_reviewed()?,
+            KeyCode::Char('x') | KeyCode::Char('X') if self.selected_plan().is_some() => {
+                self.confirm_delete = true;
+            }
+            KeyCode::Char('o') | KeyCode::Char('O') => {
+                self.overdue_only = !self.overdue_only;
+                self.filter_plans();
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                self.load_data()?;
+            }
+            KeyCode::Char('1'..='9') => {
+                if let Some(view) = saved_searches::shortcut(VIEW_KEY, key) {
+                    self.apply_view(view);
+                }
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_popup(&self, frame: &mut Frame, popup: &Popup) {
+        let area = frame.area();
+        let height = (popup.form.len() as u16 * 3 + 3).min(area.height);
+        let width = 60.min(area.width);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let title = match popup.plan_id {
+            Some(id) => format!(" {} ", tf("follow_ups.edit_title", &[("id", &id)])),
+            None => format!(" {} ", t("follow_ups.new_title")),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let mut constraints = vec![Constraint::Length(3); popup.form.len()];
+        constraints.push(Constraint::Min(1));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .horizontal_margin(1)
+            .split(inner);
+        for index in 0..popup.form.len() {
+            popup
+                .form
+                .render_field(frame, index, chunks[index], popup.focus_index);
+        }
+        frame.render_widget(
+            Paragraph::new(t("common.form_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[popup.form.len()],
+        );
+    }
+}
+
+impl Component for FollowUps {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(t("follow_ups.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::BOTTOM)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            layout[0],
+        );
+
+        let clinician_text = match self.clinician_filter.map(|i| &self.clinicians[i]) {
+            Some(c) if c.role == StaffRole::Doctor => {
+                tf("follow_ups.dr_filter", &[("name", &c.name)])
+            }
+            Some(c) => tf("follow_ups.nurse_filter", &[("name", &c.name)]),
+            None => t("follow_ups.all_clinicians").to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(clinician_text)
+                .style(Style::default().fg(theme().accent))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("follow_ups.assigned")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[1],
+        );
+
+        let today = datetime::today();
+        let days: Vec<Option<i64>> = self
+            .filtered_plans
+            .iter()
+            .map(|p| days_left(p, today))
+            .collect();
+        let overdue = days.iter().filter(|d| d.is_some_and(|d| d < 0)).count();
+        let due_soon = days
+            .iter()
+            .filter(|d| d.is_some_and(|d| (0..=DUE_SOON_DAYS).contains(&d)))
+            .count();
+        let summary = Line::from(vec![
+            Span::styled(
+                tf("expirations.overdue", &[("days", &overdue)]),
+                Style::default()
+                    .fg(status_color(Some(-1)))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("  |  ", Style::default().fg(theme().inactive)),
+            Span::styled(
+                tf(
+                    "follow_ups.due_soon",
+                    &[("count", &due_soon), ("days", &DUE_SOON_DAYS)],
+                ),
+                Style::default().fg(status_color(Some(0))),
+            ),
+            Span::styled(
+                if self.overdue_only {
+                    format!("  {}", t("follow_ups.overdue_only"))
+                } else {
+                    format!(
+                        "  {}",
+                        tf(
+                            "follow_ups.plans_count",
+                            &[("count", &self.filtered_plans.len())]
+                        )
+                    )
+                },
+                Style::default().fg(theme().inactive),
+            ),
+        ]);
+        frame.render_widget(
+            Paragraph::new(summary).alignment(Alignment::Center),
+            layout[2],
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", t("follow_ups.plans")))
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.filtered_plans.is_empty() {
+            let text = if self.overdue_only {
+                t("follow_ups.none_overdue")
+            } else {
+                t("follow_ups.none")
+            };
+            frame.render_widget(
+                Paragraph::new(text)
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[3],
+            );
+        } else {
+            let visible = self.columns.visible(is_narrow(layout[3]));
+            let widths = Columns::widths(&WIDTHS, &visible);
+            let columns = column_widths(block.inner(layout[3]), &widths, 1, "► ");
+            let rows: Vec<Row> = self
+                .filtered_plans
+                .iter()
+                .zip(&days)
+                .map(|(plan, &days)| {
+                    let values = self.row_values(plan, days);
+                    Row::new(
+                        visible
+                            .iter()
+                            .enumerate()
+                            .map(|(column, &index)| match index {
+                                STATUS_COLUMN => cell(&values[index], &columns, column)
+                                    .style(Style::default().fg(status_color(days))),
+                                _ => cell(&values[index], &columns, column),
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                    .style(Style::default().fg(theme().text))
+                })
+                .collect();
+            let header = Row::new(Columns::pick(&HEADERS, &visible).into_iter().map(t))
+                .style(Style::default().bg(theme().header).fg(theme().title));
+            let table = Table::new(rows, widths)
+                .header(header)
+                .block(block)
+                .row_highlight_style(
+                    Style::default()
+                        .fg(theme().focus)
+                        .bg(theme().highlight)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[3], &mut self.state.clone());
+        }
+
+        let help = if self.confirm_delete {
+            t("follow_ups.confirm_close")
+        } else {
+            t("follow_ups.help")
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(if self.confirm_delete {
+                    theme().warning
+                } else {
+                    theme().help
+                }))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+
+        if let Some(popup) = &self.popup {
+            self.render_popup(frame, popup);
+        }
+        if let Some(popup) = &self.saved_searches {
+            popup.render_popup(frame);
+        }
+        if let Some(export) = &self.export_view {
+            export.render_popup(frame);
+        }
+        if let Some(picker) = &self.column_picker {
+            picker.render_popup(frame, &self.columns);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::app::SelectedApp;
+use crate::components::Component;
+use crate::db::Db;
+use crate::tui::Frame;
+use anyhow::Result;
+use crossterm::event::KeyEvent;
+
+pub mod follow_ups;
+pub mod my_day;
+pub mod ward_round;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorklistState {
+    MyDay,
+    WardRound,
+    FollowUps,
+}
+
+pub struct Worklist {
+    pub my_day: my_day::MyDay,
+    pub ward_round: ward_round::WardRound,
+    pub follow_ups: follow_ups::FollowUps,
+    pub state: WorklistState,
+}
+
+impl Worklist {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            my_day: my_day::MyDay::new(db),
+            ward_round: ward_round::WardRound::new(db),
+            follow_ups: follow_ups::FollowUps::new(db),
+            state: WorklistState::MyDay,
+        }
+    }
+
+    pub fn initialize_list(&mut self) -> Result<()> {
+        match self.state {
+            WorklistState::MyDay => self.my_day.load_data()?,
+            WorklistState::WardRound => self.ward_round.load_data()?,
+            WorklistState::FollowUps => self.follow_ups.load_data()?,
+        }
+        Ok(())
+    }
+
+    pub fn save_view(&self) {
+        if self.state == WorklistState::FollowUps {
+            self.follow_ups.save_view();
+        }
+    }
+}
+
+impl Component for Worklist {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        match self.state {
+            WorklistState::MyDay => self.my_day.handle_input(event),
+            WorklistState::WardRound => self.ward_round.handle_input(event),
+            WorklistState::FollowUps => self.follow_ups.handle_input(event),
+        }
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        match self.state {
+            WorklistState::MyDay => self.my_day.render(frame),
+            WorklistState::WardRound => self.ward_round.render(frame),
+            WorklistState::FollowUps => self.follow_ups.render(frame),
+        }
+    }
+}
@@ -0,0 +1,487 @@
+use crate::app::SelectedApp;
+use crate::auth;
+use crate::components::hospital::queue::token_status_text;
+use crate::components::hospital::worklist::follow_ups;
+use crate::components::hospital::{telemedicine, waitlist};
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{
+    Patient, StaffMember, StaffRole, TeleconsultStatus, TokenStatus, WaitlistStatus,
+};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state::{self, ViewState};
+use crate::utils::datetime::{self, today_text};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Appointment,
+    Queue,
+    Waitlist,
+    FollowUp,
+    Unrecorded,
+}
+
+impl Section {
+    fn label(self) -> &'static str {
+        match self {
+            Section::Appointment => t("my_day.section.appointment"),
+            Section::Queue => t("my_day.section.queue"),
+            Section::Waitlist => t("my_day.section.waitlist"),
+            Section::FollowUp => t("my_day.section.follow_up"),
+            Section::Unrecorded => t("my_day.section.no_record"),
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Section::Appointment => theme().accent,
+            Section::Queue => theme().focus,
+            Section::Waitlist => theme().warning,
+            Section::FollowUp => theme().warning,
+            Section::Unrecorded => theme().error,
+        }
+    }
+}
+
+struct WorkItem {
+    section: Section,
+    when: String,
+    patient_id: i64,
+    detail: String,
+}
+
+/// Everything waiting on one doctor today: remote consultations still to
+/// happen, patients in their outpatient queue, their waitlist, care plan
+/// reviews that are due and finished consultations nobody wrote a record for. Opens on the doctor the logged-in
+/// account is linked to.
+pub struct MyDay {
+    db: Db,
+    doctors: Vec<StaffMember>,
+    patients: HashMap<i64, Patient>,
+    doctor_index: usize,
+    /// The doctor linked to the logged-in account, if any.
+    linked_doctor: Option<i64>,
+    loaded: bool,
+    items: Vec<WorkItem>,
+    state: TableState,
+}
+
+impl MyDay {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            doctors: Vec::new(),
+            patients: HashMap::new(),
+            doctor_index: 0,
+            linked_doctor: None,
+            loaded: false,
+            items: Vec::new(),
+            state: TableState::default(),
+        }
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        self.doctors = db::get_all_staff(&self.db)?
+            .into_iter()
+            .filter(|s| s.role == StaffRole::Doctor)
+            .collect();
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        self.linked_doctor = match ui_state::current_user() {
+            Some(username) => db::get_user_staff_id(&self.db, &username)?,
+            None => None,
+        };
+        if !self.loaded {
+            self.loaded = true;
+            self.doctor_index = self
+                .linked_doctor
+                .and_then(|id| self.doctors.iter().position(|d| d.id == id))
+                .unwrap_or(0);
+        }
+        if self.doctor_index >= self.doctors.len() {
+            self.doctor_index = 0;
+        }
+        self.fetch_items()
+    }
+
+    fn fetch_items(&mut self) -> Result<()> {
+        self.items.clear();
+        let Some(doctor_id) = self.doctors.get(self.doctor_index).map(|d| d.id) else {
+            self.state.select(None);
+            return Ok(());
+        };
+        let today = today_text();
+
+        for c in db::get_teleconsultations_for_day(&self.db, &today)? {
+            if c.doctor_id != doctor_id || c.status != TeleconsultStatus::Scheduled {
+                continue;
+            }
+            let detail = match c.checked_in_at.as_deref().map(datetime::time_part) {
+                Some(at) => tf("my_day.remote_checked_in", &[("time", &at)]),
+                None => tf("my_day.remote_via", &[("contact", &c.contact)]),
+            };
+            self.items.push(WorkItem {
+                section: Section::Appointment,
+                when: datetime::time_part(&c.scheduled_at),
+                patient_id: c.patient_id,
+                detail,
+            });
+        }
+
+        for token in db::get_queue_tokens_for_day(&self.db, &today)? {
+            if token.doctor_id != doctor_id
+                || !matches!(token.status, TokenStatus::Waiting | TokenStatus::Serving)
+            {
+                continue;
+            }
+            self.items.push(WorkItem {
+                section: Section::Queue,
+                when: datetime::time_part(&token.issued_at),
+                patient_id: token.patient_id,
+                detail: tf(
+                    "my_day.token",
+                    &[
+                        ("number", &format!("{:03}", token.token_number)),
+                        ("status", &token_status_text(token.status)),
+                    ],
+                ),
+            });
+        }
+
+        for entry in db::get_all_waitlist_entries(&self.db)? {
+            if entry.doctor_id != doctor_id || entry.status != WaitlistStatus::Waiting {
+                continue;
+            }
+            self.items.push(WorkItem {
+                section: Section::Waitlist,
+                when: entry.preferred_date.unwrap_or_else(|| "-".to_string()),
+                patient_id: entry.patient_id,
+                detail: format!(
+                    "{} ({})",
+                    entry.procedure,
+                    waitlist::priority_text(entry.priority)
+                ),
+            });
+        }
+
+        for plan in db::get_all_care_plans(&self.db)? {
+            if plan.doctor_id != Some(doctor_id) || plan.next_due > today {
+                continue;
+            }
+            self.items.push(WorkItem {
+                section: Section::FollowUp,
+                when: plan.next_due,
+                patient_id: plan.patient_id,
+                detail: tf(
+                    "my_day.review",
+                    &[
+                        ("condition", &plan.condition),
+                        ("days", &plan.review_interval_days),
+                    ],
+                ),
+            });
+        }
+
+        for c in db::get_unrecorded_teleconsultations(&self.db, doctor_id)? {
+            self.items.push(WorkItem {
+                section: Section::Unrecorded,
+                when: datetime::local_date(&c.scheduled_at)
+                    .map(datetime::format_date)
+                    .unwrap_or_default(),
+                patient_id: c.patient_id,
+                detail: c
+                    .outcome
+                    .unwrap_or_else(|| t("my_day.no_outcome").to_string()),
+            });
+        }
+
+        match self.state.selected() {
+            _ if self.items.is_empty() => self.state.select(None),
+            Some(i) if i >= self.items.len() => self.state.select(Some(self.items.len() - 1)),
+            None => self.state.select(Some(0)),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn patient_name(&self, patient_id: i64) -> String {
+        self.patients
+            .get(&patient_id)
+            .map(|p| format!("{} {}", p.first_name, p.last_name))
+            .unwrap_or_else(|| tf("common.unknown_number", &[("id", &patient_id)]))
+    }
+
+    fn count(&self, section: Section) -> usize {
+        self.items.iter().filter(|i| i.section == section).count()
+    }
+
+    /// Links the logged-in account to the doctor on screen, or unlinks it
+    /// when it already is. Only administrators may link; other accounts are
+    /// linked in Update Staff.
+    fn toggle_link(&mut self) -> Result<()> {
+        let Some(doctor) = self.doctors.get(self.doctor_index).cloned() else {
+            return Ok(());
+        };
+        let Some(username) = ui_state::current_user() else {
+            return Ok(());
+        };
+        let link = (self.linked_doctor != Some(doctor.id)).then_some(doctor.id);
+        if link.is_some() && !auth::is_admin(&self.db, &username) {
+            toast::push(Toast::error(t("my_day.admins_only")));
+            return Ok(());
+        }
+        match db::link_user_to_staff(&self.db, &username, &username, link) {
+            Ok(()) => {
+                self.linked_doctor = link;
+                toast::push(Toast::success(match link {
+                    Some(_) => tf(
+                        "my_day.linked",
+                        &[("user", &username), ("name", &doctor.name)],
+                    ),
+                    None => tf(
+                        "my_day.unlinked",
+                        &[("user", &username), ("name", &doctor.name)],
+                    ),
+                }));
+            }
+            Err(e) => toast::push(Toast::error(tf("my_day.link_failed", &[("error", &e)]))),
+        }
+        Ok(())
+    }
+
+    /// The screen that deals with the selected item, filtered to this doctor
+    /// where the screen supports it.
+    fn open_selected(&self) -> Option<SelectedApp> {
+        let item = self.items.get(self.state.selected()?)?;
+        let doctor_id = self.doctors.get(self.doctor_index)?.id;
+        let doctor_view = ViewState::default().with_filter("doctor", Some(doctor_id));
+        Some(match item.section {
+            Section::Appointment => {
+                ui_state::set_view(telemedicine::upcoming::VIEW_KEY, doctor_view);
+                SelectedApp::TeleconsultUpcoming
+            }
+            Section::Queue => SelectedApp::QueueConsole,
+            Section::Waitlist => {
+                ui_state::set_view(waitlist::list::VIEW_KEY, doctor_view);
+                SelectedApp::WaitlistList
+            }
+            Section::FollowUp => {
+                let clinician_view = ViewState::default().with_filter("clinician", Some(doctor_id));
+                ui_state::set_view(follow_ups::VIEW_KEY, clinician_view);
+                SelectedApp::WorklistFollowUps
+            }
+            Section::Unrecorded => SelectedApp::RecordStore,
+        })
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        let len = self.items.len();
+        match key.code {
+            KeyCode::Left | KeyCode::Right if !self.doctors.is_empty() => {
+                let count = self.doctors.len();
+                self.doctor_index = if key.code == KeyCode::Right {
+                    (self.doctor_index + 1) % count
+                } else {
+                    (self.doctor_index + count - 1) % count
+                };
+                self.state.select(None);
+                self.fetch_items()?;
+            }
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Enter => return Ok(self.open_selected()),
+            KeyCode::Char('l') | KeyCode::Char('L') => self.toggle_link()?,
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                self.load_data()?;
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+impl Component for MyDay {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(t("my_day.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let doctor_text = match self.doctors.get(self.doctor_index) {
+            Some(d) if self.linked_doctor == Some(d.id) => tf(
+                "my_day.doctor_yours",
+                &[
+                    ("name", &d.name),
+                    ("date", &datetime::display_date(datetime::today())),
+                ],
+            ),
+            Some(d) => tf(
+                "my_day.doctor",
+                &[
+                    ("name", &d.name),
+                    ("date", &datetime::display_date(datetime::today())),
+                ],
+            ),
+            None => t("my_day.no_doctors").to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(doctor_text)
+                .style(Style::default().fg(theme().accent))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("my_day.for")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[1],
+        );
+
+        let summary = Line::from(
+            [
+                Section::Appointment,
+                Section::Queue,
+                Section::Waitlist,
+                Section::FollowUp,
+                Section::Unrecorded,
+            ]
+            .into_iter()
+            .flat_map(|section| {
+                [
+                    Span::styled(
+                        format!(" {} ", self.count(section)),
+                        Style::default()
+                            .fg(section.color())
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!("{}  ", section.label()),
+                        Style::default().fg(theme().text),
+                    ),
+                ]
+            })
+            .collect::<Vec<_>>(),
+        );
+        frame.render_widget(
+            Paragraph::new(summary).alignment(Alignment::Center),
+            layout[2],
+        );
+
+        let header_row = Row::new(vec![
+            t("my_day.item"),
+            t("stock.when"),
+            t("kiosk.patient"),
+            t("my_day.details"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let widths = [
+            Constraint::Length(12),
+            Constraint::Length(11),
+            Constraint::Percentage(25),
+            Constraint::Min(20),
+        ];
+        let columns = column_widths(layout[3].inner(Margin::new(1, 1)), &widths, 1, "► ");
+        let rows = self.items.iter().map(|item| {
+            Row::new(vec![
+                cell(item.section.label(), &columns, 0)
+                    .style(Style::default().fg(item.section.color())),
+                cell(&item.when, &columns, 1),
+                cell(self.patient_name(item.patient_id), &columns, 2),
+                cell(&item.detail, &columns, 3),
+            ])
+            .style(Style::default().fg(theme().text))
+        });
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(
+                " {} ",
+                tf("my_day.worklist", &[("count", &self.items.len())])
+            ))
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if self.items.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("my_day.empty"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                layout[3],
+            );
+        } else {
+            let table = Table::new(rows, widths)
+                .header(header_row)
+                .block(block)
+                .row_highlight_style(
+                    Style::default()
+                        .fg(theme().focus)
+                        .bg(theme().highlight)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol("► ");
+            frame.render_stateful_widget(table, layout[3], &mut self.state.clone());
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("my_day.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+    }
+}
@@ -0,0 +1,896 @@
+use crate::app::SelectedApp;
+use crate::components::form::{self, blood_pressure, integer_range, number_range, Field, Form};
+use crate::components::hospital::staff::shift_label;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::aftercare::AftercarePopup;
+use crate::components::widgets::label::LabelPopup;
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{Admission, HandoverNote, Observation, Patient, ISOLATION_PRECAUTIONS};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::aftercare::Visit;
+use crate::utils::datetime::{self, now_text};
+use crate::utils::labels;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+use time::Duration;
+
+/// Vital signs are due again this long after they were last taken.
+const VITALS_DUE_AFTER: Duration = Duration::hours(4);
+
+const TEMPERATURE: usize = 0;
+const PULSE: usize = 1;
+const BLOOD_PRESSURE: usize = 2;
+const RESPIRATORY_RATE: usize = 3;
+const SPO2: usize = 4;
+
+fn admit_form(ward: &str) -> Form {
+    let mut form = Form::new(vec![
+        Field::new(t("common.patient_id"))
+            .required()
+            .validate_with(integer_range(1, i64::MAX)),
+        Field::new(t("kiosk.ward")).required(),
+        Field::new(t("kiosk.bed")),
+    ]);
+    form.fields[1].set(ward);
+    form
+}
+
+/// The vital signs form, also used by the quick entry opened from any screen.
+pub(crate) fn vitals_form() -> Form {
+    Form::new(vec![
+        Field::new(t("ward_round.temperature")).validate_with(number_range(30.0, 45.0)),
+        Field::new(t("ward_round.pulse")).validate_with(integer_range(20, 250)),
+        Field::new(t("ward_round.blood_pressure")).validate_with(Box::new(blood_pressure)),
+        Field::new(t("ward_round.respiratory_rate")).validate_with(integer_range(4, 60)),
+        Field::new(t("ward_round.spo2")).validate_with(integer_range(50, 100)),
+    ])
+}
+
+/// The readings in a validated [`vitals_form`], taken now.
+pub(crate) fn vitals_observation(form: &Form, patient_id: i64) -> Observation {
+    Observation {
+        id: 0,
+        patient_id,
+        recorded_at: now_text(),
+        temperature: form
+            .optional_value(TEMPERATURE)
+            .and_then(|v| v.parse().ok()),
+        pulse: form.optional_value(PULSE).and_then(|v| v.parse().ok()),
+        blood_pressure: form
+            .optional_value(BLOOD_PRESSURE)
+            .map(|v| v.replace(' ', "")),
+        respiratory_rate: form
+            .optional_value(RESPIRATORY_RATE)
+            .and_then(|v| v.parse().ok()),
+        spo2: form.optional_value(SPO2).and_then(|v| v.parse().ok()),
+        note: None,
+    }
+}
+
+fn note_form() -> Form {
+    Form::new(vec![Field::new(t("cashier.note")).required()])
+}
+
+const PRECAUTIONS: usize = 0;
+const INFECTION_FLAGS: usize = 1;
+
+fn precautions(value: &str) -> Result<(), String> {
+    if ISOLATION_PRECAUTIONS
+        .iter()
+        .any(|p| p.eq_ignore_ascii_case(value))
+    {
+        Ok(())
+    } else {
+        Err(tf(
+            "form.one_of",
+            &[("options", &ISOLATION_PRECAUTIONS.join(", "))],
+        ))
+    }
+}
+
+/// Left empty when the day is not known.
+fn discharge_form(admission: &Admission) -> Form {
+    let mut form = Form::new(vec![
+        Field::new(t("ward_round.expected_discharge")).validate_with(Box::new(form::date))
+    ]);
+    if let Some(date) = &admission.expected_discharge {
+        form.fields[0].set(date.as_str());
+    }
+    form
+}
+
+/// Both fields left empty lifts the isolation.
+fn isolation_form(admission: &Admission) -> Form {
+    let mut form = Form::new(vec![
+        Field::new(t("ward_round.precautions")).validate_with(Box::new(precautions)),
+        Field::new(t("ward_round.infection_flags")),
+    ]);
+    if let Some(isolation) = &admission.isolation {
+        form.fields[PRECAUTIONS].set(isolation.as_str());
+    }
+    if let Some(flags) = &admission.infection_flags {
+        form.fields[INFECTION_FLAGS].set(flags.as_str());
+    }
+    form
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PopupKind {
+    Admit,
+    Vitals,
+    Note,
+    Isolation,
+    Discharge,
+}
+
+struct Popup {
+    kind: PopupKind,
+    form: Form,
+    focus_index: usize,
+}
+
+/// One admitted patient as the round sees them.
+struct Bedside {
+    admission: Admission,
+    last_vitals: Option<Observation>,
+    last_note: Option<Observation>,
+}
+
+impl Bedside {
+    fn vitals_due(&self, due_before: &str) -> bool {
+        self.last_vitals
+            .as_ref()
+            .is_none_or(|o| o.recorded_at.as_str() < due_before)
+    }
+}
+
+/// The admitted patients of one ward with their medication list, whether
+/// vital signs are due and the latest nursing note, plus the pending tasks of
+/// the ward's latest handover. Vitals and notes are entered from the list.
+pub struct WardRound {
+    db: Db,
+    wards: Vec<String>,
+    ward_index: usize,
+    patients: HashMap<i64, Patient>,
+    beds: Vec<Bedside>,
+    handover: Option<HandoverNote>,
+    state: TableState,
+    popup: Option<Popup>,
+    confirm_discharge: bool,
+    label: Option<LabelPopup>,
+    /// Offered right after a discharge.
+    aftercare: Option<AftercarePopup>,
+}
+
+impl WardRound {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            wards: Vec::new(),
+            ward_index: 0,
+            patients: HashMap::new(),
+            beds: Vec::new(),
+            handover: None,
+            state: TableState::default(),
+            popup: None,
+            confirm_discharge: false,
+            label: None,
+            aftercare: None,
+        }
+    }
+
+    fn ward(&self) -> Option<&str> {
+        self.wards.get(self.ward_index).map(String::as_str)
+    }
+
+    pub fn load_data(&mut self) -> Result<()> {
+        let current = self.ward().map(str::to_string);
+        self.patients = db::get_all_patients(&self.db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+        let admissions = db::get_current_admissions(&self.db)?;
+        self.wards = admissions.iter().map(|a| a.ward.clone()).collect();
+        self.wards.dedup();
+        self.ward_index = current
+            .and_then(|ward| self.wards.iter().position(|w| *w == ward))
+            .unwrap_or(0);
+
+        let ward = self.ward().map(str::to_string);
+        self.beds.clear();
+        for admission in admissions {
+            if Some(&admission.ward) != ward.as_ref() {
+                continue;
+            }
+            let observations = db::get_patient_observations(&self.db, admission.patient_id)?;
+            self.beds.push(Bedside {
+                last_vitals: observations.iter().find(|o| o.has_vitals()).cloned(),
+                last_note: observations.into_iter().find(|o| o.note.is_some()),
+                admission,
+            });
+        }
+        self.handover = match &ward {
+            Some(ward) => db::get_all_handover_notes(&self.db)?
+                .into_iter()
+                .find(|note| note.ward.eq_ignore_ascii_case(ward)),
+            None => None,
+        };
+
+        match self.state.selected() {
+            _ if self.beds.is_empty() => self.state.select(None),
+            Some(i) if i >= self.beds.len() => self.state.select(Some(self.beds.len() - 1)),
+            None => self.state.select(Some(0)),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn patient_name(&self, patient_id: i64) -> String {
+        self.patients
+            .get(&patient_id)
+            .map(|p| format!("{} {}", p.first_name, p.last_name))
+            .unwrap_or_else(|| tf("common.unknown_number", &[("id", &patient_id)]))
+    }
+
+    fn selected_bed(&self) -> Option<&Bedside> {
+        self.beds.get(self.state.selected()?)
+    }
+
+    fn open_popup(&mut self, kind: PopupKind) {
+        let form = match kind {
+            PopupKind::Admit => admit_form(self.ward().unwrap_or_default()),
+            PopupKind::Vitals | PopupKind::Note if self.selected_bed().is_none() => return,
+            PopupKind::Vitals => vitals_form(),
+            PopupKind::Note => note_form(),
+            PopupKind::Isolation => match self.selected_bed() {
+                Some(bed) => isolation_form(&bed.admission),
+                None => return,
+            },
+            PopupKind::Discharge => match self.selected_bed() {
+                Some(bed) => discharge_form(&bed.admission),
+                None => return,
+            },
+        };
+        self.popup = Some(Popup {
+            kind,
+            form,
+            focus_index: 0,
+        });
+    }
+
+    fn save_popup(&mut self) -> Result<()> {
+        let Some(mut popup) = self.popup.take() else {
+            return Ok(());
+        };
+        if let Some(invalid) = popup.form.validate() {
+            popup.focus_index = invalid;
+            self.popup = Some(popup);
+            return Ok(());
+        }
+        let form = &popup.form;
+        let patient_id = self
+            .selected_bed()
+            .map(|b| b.admission.patient_id)
+            .unwrap_or_default();
+        let result = match popup.kind {
+            PopupKind::Admit => {
+                let patient_id = form.value(0).parse().unwrap_or_default();
+                if !self.patients.contains_key(&patient_id) {
+                    popup.form.fields[0].error = Some(t("common.no_patient_id").to_string());
+                    popup.focus_index = 0;
+                    self.popup = Some(popup);
+                    return Ok(());
+                }
+                let ward = form.value(1);
+                let admission = Admission {
+                    id: 0,
+                    patient_id,
+                    ward: ward.clone(),
+                    bed: form.optional_value(2),
+                    admitted_at: now_text(),
+                    discharged_at: None,
+                    isolation: None,
+                    infection_flags: None,
+                    isolated_since: None,
+                    expected_discharge: None,
+                };
+                db::admit_patient(&self.db, &admission).map(|_| {
+                    // Open the ward the patient went to.
+                    self.wards = vec![ward.clone()];
+                    self.ward_index = 0;
+                    // A new admission needs its wristband.
+                    self.label = self.patients.get(&patient_id).and_then(|p| {
+                        LabelPopup::open(&self.db, labels::wristband(p, Some(&admission)))
+                    });
+                    tf(
+                        "ward_round.admitted",
+                        &[("patient", &self.patient_name(patient_id)), ("ward", &ward)],
+                    )
+                })
+            }
+            PopupKind::Vitals => {
+                let observation = vitals_observation(form, patient_id);
+                if !observation.has_vitals() {
+                    popup.form.fields[TEMPERATURE].error = Some(t("vitals.enter_one").to_string());
+                    popup.focus_index = TEMPERATURE;
+                    self.popup = Some(popup);
+                    return Ok(());
+                }
+                db::create_observation(&self.db, &observation).map(|_| {
+                    tf(
+                        "vitals.saved",
+                        &[("patient", &self.patient_name(patient_id))],
+                    )
+                })
+            }
+            PopupKind::Note => db::create_observation(
+                &self.db,
+                &Observation {
+                    id: 0,
+                    patient_id,
+                    recorded_at: now_text(),
+                    temperature: None,
+                    pulse: None,
+                    blood_pressure: None,
+                    respiratory_rate: None,
+                    spo2: None,
+                    note: Some(form.value(0)),
+                },
+            )
+            .map(|_| {
+                tf(
+                    "ward_round.note_added",
+                    &[("patient", &self.patient_name(patient_id))],
+                )
+            }),
+            PopupKind::Isolation => self.save_isolation(form),
+            PopupKind::Discharge => {
+                let expected = form.optional_value(0);
+                let admission_id = self
+                    .selected_bed()
+                    .map(|b| b.admission.id)
+                    .unwrap_or_default();
+                db::set_expected_discharge(&self.db, admission_id, expected.as_deref()).map(|_| {
+                    match expected {
+                        Some(date) => tf(
+                            "ward_round.expected_home",
+                            &[("patient", &self.patient_name(patient_id)), ("date", &date)],
+                        ),
+                        None => tf(
+                            "ward_round.expected_cleared",
+                            &[("patient", &self.patient_name(patient_id))],
+                        ),
+                    }
+                })
+            }
+        };
+        match result {
+            Ok(message) => {
+                toast::push(Toast::success(message));
+                self.load_data()?;
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("insurance.save_failed", &[("error", &e)])));
+                self.popup = Some(popup);
+            }
+        }
+        Ok(())
+    }
+
+    fn save_isolation(&self, form: &Form) -> Result<String> {
+        let Some(bed) = self.selected_bed() else {
+            return Ok(String::new());
+        };
+        let admission = &bed.admission;
+        let isolation = form.optional_value(PRECAUTIONS).and_then(|value| {
+            ISOLATION_PRECAUTIONS
+                .iter()
+                .find(|p| p.eq_ignore_ascii_case(&value))
+                .map(|p| p.to_string())
+        });
+        let flags = form.optional_value(INFECTION_FLAGS);
+        let name = self.patient_name(admission.patient_id);
+        if isolation.is_none() && flags.is_none() {
+            db::set_isolation(&self.db, admission.id, None, None, None)?;
+            return Ok(tf("ward_round.isolation_lifted", &[("patient", &name)]));
+        }
+        let since = admission.isolated_since.clone().unwrap_or_else(now_text);
+        db::set_isolation(
+            &self.db,
+            admission.id,
+            isolation.as_deref(),
+            flags.as_deref(),
+            Some(&since),
+        )?;
+        Ok(tf("ward_round.isolation_flagged", &[("patient", &name)]))
+    }
+
+    fn handle_popup_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(popup) = &mut self.popup else {
+            return Ok(());
+        };
+        let len = popup.form.len();
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Tab | KeyCode::Down => popup.focus_index = (popup.focus_index + 1) % len,
+            KeyCode::BackTab | KeyCode::Up => {
+                popup.focus_index = (popup.focus_index + len - 1) % len
+            }
+            KeyCode::Enter if popup.focus_index + 1 < len => popup.focus_index += 1,
+            KeyCode::Enter => self.save_popup()?,
+            _ => {
+                popup.form.handle_key(popup.focus_index, key);
+            }
+        }
+        Ok(())
+    }
+
+    fn discharge_selected(&mut self) -> Result<()> {
+        self.confirm_discharge = false;
+        let Some(bed) = self.selected_bed() else {
+            return Ok(());
+        };
+        let (id, patient_id) = (bed.admission.id, bed.admission.patient_id);
+        let ward = bed.admission.ward.clone();
+        match db::discharge_patient(&self.db, id, &now_text()) {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "ward_round.discharged",
+                    &[("patient", &self.patient_name(patient_id))],
+                )));
+                self.open_aftercare(patient_id, ward);
+                self.load_data()?;
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "ward_round.discharge_failed",
+                &[("error", &e)],
+            ))),
+        }
+        Ok(())
+    }
+
+    /// Aftercare instructions for a patient just discharged from `ward`,
+    /// filled in from their latest medical record.
+    fn open_aftercare(&mut self, patient_id: i64, ward: String) {
+        let Some(patient) = self.patients.get(&patient_id) else {
+            return;
+        };
+        let latest = db::get_all_medical_records(&self.db)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|r| r.patient_id == patient_id)
+            .max_by_key(|r| r.id);
+        let visit = Visit {
+            diagnosis: latest.as_ref().map(|r| r.diagnosis.clone()),
+            prescription: latest.and_then(|r| r.prescription),
+            ward: Some(ward),
+        };
+        self.aftercare = Some(AftercarePopup::new(&self.db, patient.clone(), visit));
+    }
+
+    fn open_wristband(&mut self) {
+        let Some(bed) = self.selected_bed() else {
+            return;
+        };
+        let Some(patient) = self.patients.get(&bed.admission.patient_id) else {
+            return;
+        };
+        self.label = LabelPopup::open(&self.db, labels::wristband(patient, Some(&bed.admission)));
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(aftercare) = &mut self.aftercare {
+            if aftercare.handle_key(key) {
+                self.aftercare = None;
+            }
+            return Ok(None);
+        }
+        if let Some(label) = &mut self.label {
+            if label.handle_key(key) {
+                self.label = None;
+            }
+            return Ok(None);
+        }
+        if self.popup.is_some() {
+            self.handle_popup_input(key)?;
+            return Ok(None);
+        }
+        if self.confirm_discharge {
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.discharge_selected()?
+                }
+                _ => self.confirm_discharge = false,
+            }
+            return Ok(None);
+        }
+        let len = self.beds.len();
+        match key.code {
+            KeyCode::Left | KeyCode::Right if !self.wards.is_empty() => {
+                let count = self.wards.len();
+                self.ward_index = if key.code == KeyCode::Right {
+                    (self.ward_index + 1) % count
+                } else {
+                    (self.ward_index + count - 1) % count
+                };
+                self.state.select(None);
+                self.load_data()?;
+            }
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('v') | KeyCode::Char('V') => self.open_popup(PopupKind::Vitals),
+            KeyCode::Char('n') | KeyCode::Char('N') => self.open_popup(PopupKind::Note),
+            KeyCode::Char('a') | KeyCode::Char('A') => self.open_popup(PopupKind::Admit),
+            KeyCode::Char('w') | KeyCode::Char('W') => self.open_wristband(),
+            KeyCode::Char('i') | KeyCode::Char('I') => self.open_popup(PopupKind::Isolation),
+            KeyCode::Char('e') | KeyCode::Char('E') => self.open_popup(PopupKind::Discharge),
+            KeyCode::Char('x') | KeyCode::Char('X') if self.selected_bed().is_some() => {
+                self.confirm_discharge = true;
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                db::cache::invalidate_all(&self.db);
+                self.load_data()?;
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn render_popup(&self, frame: &mut Frame, popup: &Popup) {
+        let area = frame.area();
+        let height = (popup.form.len() as u16 * 3 + 3).min(area.height);
+        let width = 60.min(area.width);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let title = match popup.kind {
+            PopupKind::Admit => format!(
+                " {} ",
+                tf(
+                    "ward_round.admit",
+                    &[("ward", &self.ward().unwrap_or(t("ward_round.a_ward")))]
+                )
+            ),
+            PopupKind::Vitals | PopupKind::Note | PopupKind::Isolation | PopupKind::Discharge => {
+                let patient = self
+                    .selected_bed()
+                    .map(|b| self.patient_name(b.admission.patient_id))
+                    .unwrap_or_default();
+                match popup.kind {
+                    PopupKind::Vitals => {
+                        format!(" {} ", tf("ward_round.vitals", &[("patient", &patient)]))
+                    }
+                    PopupKind::Note => {
+                        format!(" {} ", tf("ward_round.note", &[("patient", &patient)]))
+                    }
+                    PopupKind::Discharge => format!(
+                        " {} ",
+                        tf("ward_round.discharge_title", &[("patient", &patient)])
+                    ),
+                    _ => format!(" {} ", tf("ward_round.isolation", &[("patient", &patient)])),
+                }
+            }
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let mut constraints = vec![Constraint::Length(3); popup.form.len()];
+        constraints.push(Constraint::Min(1));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .horizontal_margin(1)
+            .split(inner);
+        for index in 0..popup.form.len() {
+            popup
+                .form
+                .render_field(frame, index, chunks[index], popup.focus_index);
+        }
+        frame.render_widget(
+            Paragraph::new(t("common.form_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[popup.form.len()],
+        );
+    }
+}
+
+fn vitals_text(observation: &Observation) -> String {
+    let mut readings = Vec::new();
+    if let Some(t) = observation.temperature {
+        readings.push(format!("{:.1}°", t));
+    }
+    if let Some(p) = observation.pulse {
+        readings.push(format!("{}bpm", p));
+    }
+    if let Some(bp) = &observation.blood_pressure {
+        readings.push(bp.clone());
+    }
+    if let Some(r) = observation.respiratory_rate {
+        readings.push(format!("RR{}", r));
+    }
+    if let Some(s) = observation.spo2 {
+        readings.push(format!("{}%", s));
+    }
+    format!(
+        "{} {}",
+        datetime::time_part(&observation.recorded_at),
+        readings.join(" ")
+    )
+}
+
+impl Component for WardRound {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(8),
+                Constraint::Length(6),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(t("ward_round.title"))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let ward_text = match self.ward() {
+            Some(ward) => tf(
+                "ward_round.ward_count",
+                &[("ward", &ward), ("count", &self.beds.len())],
+            ),
+            None => t("ward_round.no_patients").to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(ward_text)
+                .style(Style::default().fg(theme().accent))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("ward_round.ward")))
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[1],
+        );
+
+        let due_before = datetime::format_timestamp(datetime::now() - VITALS_DUE_AFTER);
+        let header_row = Row::new(vec![
+            t("kiosk.bed"),
+            t("kiosk.patient"),
+            t("ward_round.medications"),
+            t("ward_round.vitals_column"),
+            t("ward_round.last_note"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let widths = [
+            Constraint::Length(6),
+            Constraint::Percentage(20),
+            Constraint::Percentage(25),
+            Constraint::Length(30),
+            Constraint::Min(12),
+        ];
+        let columns = column_widths(layout[2].inner(Margin::new(1, 1)), &widths, 1, "► ");
+        let rows = self.beds.iter().map(|bed| {
+            let patient = self.patients.get(&bed.admission.patient_id);
+            let (vitals, vitals_style) = match &bed.last_vitals {
+                None => (t("ward_round.due_none").to_string(), theme().error),
+                Some(o) if bed.vitals_due(&due_before) => (
+                    tf("ward_round.due", &[("vitals", &vitals_text(o))]),
+                    theme().warning,
+                ),
+                Some(o) => (vitals_text(o), theme().success),
+            };
+            let isolation_style = Style::default()
+                .fg(theme().error)
+                .add_modifier(Modifier::BOLD);
+            // The infection, or the precautions when none is named; the
+            // isolations report has both.
+            let flag = bed
+                .admission
+                .infection_flags
+                .as_deref()
+                .or(bed.admission.isolation.as_deref());
+            let mut name = self.patient_name(bed.admission.patient_id);
+            if let Some(date) = bed
+                .admission
+                .expected_discharge
+                .as_deref()
+                .and_then(datetime::parse_date)
+            {
+                name.push_str(&format!(" → {}", datetime::display_date(date)));
+            }
+            let (bed_cell, patient_cell) = match flag {
+                Some(flag) => (
+                    cell(
+                        format!("☣ {}", bed.admission.bed.as_deref().unwrap_or("-")),
+                        &columns,
+                        0,
+                    )
+                    .style(isolation_style),
+                    cell(format!("{} ({})", name, flag), &columns, 1).style(isolation_style),
+                ),
+                None => (
+                    cell(bed.admission.bed.as_deref().unwrap_or("-"), &columns, 0),
+                    cell(name, &columns, 1),
+                ),
+            };
+            Row::new(vec![
+                bed_cell,
+                patient_cell,
+                cell(
+                    patient
+                        .and_then(|p| p.current_medications.as_deref())
+                        .unwrap_or("-"),
+                    &columns,
+                    2,
+                ),
+                cell(vitals, &columns, 3).style(Style::default().fg(vitals_style)),
+                cell(
+                    bed.last_note
+                        .as_ref()
+                        .and_then(|o| o.note.as_deref())
+                        .unwrap_or("-"),
+                    &columns,
+                    4,
+                ),
+            ])
+            .style(Style::default().fg(theme().text))
+        });
+        let due = self
+            .beds
+            .iter()
+            .filter(|bed| bed.vitals_due(&due_before))
+            .count();
+        let isolated = self
+            .beds
+            .iter()
+            .filter(|bed| bed.admission.is_isolated())
+            .count();
+        let table = Table::new(rows, widths)
+            .header(header_row)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(match isolated {
+                        0 => format!(
+                            " {} ",
+                            tf(
+                                "ward_round.patients",
+                                &[("count", &self.beds.len()), ("due", &due)]
+                            )
+                        ),
+                        n => format!(
+                            " {} ",
+                            tf(
+                                "ward_round.patients_isolated",
+                                &[("count", &self.beds.len()), ("due", &due), ("isolated", &n)]
+                            )
+                        ),
+                    })
+                    .title_alignment(Alignment::Center)
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            )
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, layout[2], &mut self.state.clone());
+
+        let (tasks_title, tasks) = match &self.handover {
+            Some(note) => (
+                format!(
+                    " {} ",
+                    tf(
+                        "ward_round.tasks_handover",
+                        &[
+                            ("date", &note.handover_date),
+                            ("shift", &shift_label(&note.shift))
+                        ]
+                    )
+                ),
+                note.pending_tasks.as_str(),
+            ),
+            None => (
+                format!(" {} ", t("ward_round.tasks")),
+                t("ward_round.no_handover"),
+            ),
+        };
+        frame.render_widget(
+            Paragraph::new(tasks)
+                .style(Style::default().fg(theme().text))
+                .wrap(Wrap { trim: true })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(tasks_title)
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            layout[3],
+        );
+
+        let help = if self.confirm_discharge {
+            t("ward_round.confirm_discharge")
+        } else {
+            t("ward_round.help")
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(if self.confirm_discharge {
+                    theme().warning
+                } else {
+                    theme().help
+                }))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+
+        if let Some(popup) = &self.popup {
+            self.render_popup(frame, popup);
+        }
+        if let Some(label) = &self.label {
+            label.render_popup(frame);
+        }
+        if let Some(aftercare) = &self.aftercare {
+            aftercare.render_popup(frame);
+        }
+    }
+}
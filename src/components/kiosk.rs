@@ -0,0 +1,429 @@
+//! The read-only screens started with `--kiosk`, for waiting-room and
+//! nurse-station displays: a bed board, the outpatient queue and today's
+//! appointments. Nobody logs in, the database is opened read-only and the
+//! screens rotate on their own:
+//!
+//! ```toml
+//! [kiosk]
+//! screens = ["queue", "appointments"]
+//! rotate = 20
+//! ```
+
+use crate::components::hospital::queue::display::DisplayBoard;
+use crate::components::hospital::telemedicine::status_text;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::worker::{self, Task};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{Admission, Patient, TeleconsultStatus, Teleconsultation};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::config;
+use crate::utils::datetime::{self, today_text};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KioskScreen {
+    Beds,
+    Queue,
+    Appointments,
+}
+
+impl KioskScreen {
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "beds" => Some(KioskScreen::Beds),
+            "queue" => Some(KioskScreen::Queue),
+            "appointments" => Some(KioskScreen::Appointments),
+            _ => None,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            KioskScreen::Beds => t("kiosk.beds"),
+            KioskScreen::Queue => t("kiosk.queue"),
+            KioskScreen::Appointments => t("kiosk.appointments"),
+        }
+    }
+}
+
+/// Everything the bed board and appointment list show.
+#[derive(Default)]
+struct KioskData {
+    admissions: Vec<Admission>,
+    consultations: Vec<Teleconsultation>,
+    patients: HashMap<i64, Patient>,
+    doctors: HashMap<i64, String>,
+}
+
+fn load_data(db: &Db, day: &str) -> Result<KioskData> {
+    Ok(KioskData {
+        admissions: db::get_current_admissions(db)?,
+        consultations: db::get_teleconsultations_for_day(db, day)?
+            .into_iter()
+            .filter(|c| c.status != TeleconsultStatus::Cancelled)
+            .collect(),
+        patients: db::get_all_patients(db)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect(),
+        doctors: db::get_all_staff(db)?
+            .into_iter()
+            .map(|s| (s.id, s.name))
+            .collect(),
+    })
+}
+
+pub struct Kiosk {
+    db: Db,
+    screens: Vec<KioskScreen>,
+    current: usize,
+    rotate: Option<Duration>,
+    shown_at: Instant,
+    board: DisplayBoard,
+    data: KioskData,
+    last_refresh: Option<Instant>,
+    pending: Option<Task<KioskData>>,
+    error_message: Option<String>,
+}
+
+impl Kiosk {
+    pub fn new(db: &Db) -> Self {
+        let config = config::config();
+        let settings = &config.kiosk;
+        let mut screens = Vec::new();
+        for name in &settings.screens {
+            match KioskScreen::parse(name) {
+                Some(screen) if !screens.contains(&screen) => screens.push(screen),
+                Some(_) => {}
+                None => toast::push(Toast::error(tf(
+                    "kiosk.unknown_screen",
+                    &[("name", name), ("path", &config::path().display())],
+                ))),
+            }
+        }
+        if screens.is_empty() {
+            screens = vec![
+                KioskScreen::Beds,
+                KioskScreen::Queue,
+                KioskScreen::Appointments,
+            ];
+        }
+        Self {
+            db: db.clone(),
+            screens,
+            current: 0,
+            rotate: (settings.rotate > 0).then(|| Duration::from_secs(settings.rotate)),
+            shown_at: Instant::now(),
+            board: DisplayBoard::new(db),
+            data: KioskData::default(),
+            last_refresh: None,
+            pending: None,
+            error_message: None,
+        }
+    }
+
+    fn screen(&self) -> KioskScreen {
+        self.screens[self.current]
+    }
+
+    fn show(&mut self, index: usize) {
+        self.current = index % self.screens.len();
+        self.shown_at = Instant::now();
+    }
+
+    /// Name and initial only, since the screen faces the public.
+    fn patient_name(&self, patient_id: i64) -> String {
+        match self.data.patients.get(&patient_id) {
+            Some(p) => match p.last_name.chars().next() {
+                Some(initial) => format!("{} {}.", p.first_name, initial),
+                None => p.first_name.clone(),
+            },
+            None => "-".to_string(),
+        }
+    }
+
+    fn render_beds(&self, frame: &mut Frame, area: Rect) {
+        let widths = [
+            Constraint::Percentage(25),
+            Constraint::Length(10),
+            Constraint::Percentage(30),
+            Constraint::Length(14),
+            Constraint::Min(12),
+        ];
+        let columns = column_widths(area.inner(Margin::new(1, 1)), &widths, 1, "");
+        let rows = self.data.admissions.iter().map(|admission| {
+            // The board is public, so it shows the precautions to take but
+            // not the infection.
+            let style = if admission.is_isolated() {
+                Style::default()
+                    .fg(theme().error)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme().text)
+            };
+            let precautions = match (&admission.isolation, &admission.infection_flags) {
+                (Some(isolation), _) => format!("☣ {}", isolation),
+                (None, Some(_)) => "☣".to_string(),
+                (None, None) => "-".to_string(),
+            };
+            Row::new(vec![
+                cell(admission.ward.as_str(), &columns, 0),
+                cell(admission.bed.as_deref().unwrap_or("-"), &columns, 1),
+                cell(self.patient_name(admission.patient_id), &columns, 2),
+                cell(precautions, &columns, 3),
+                cell(
+                    datetime::local_date(&admission.admitted_at)
+                        .map(datetime::display_date)
+                        .unwrap_or_default(),
+                    &columns,
+                    4,
+                ),
+            ])
+            .style(style)
+        });
+        let header = Row::new(vec![
+            t("kiosk.ward"),
+            t("kiosk.bed"),
+            t("kiosk.patient"),
+            t("kiosk.precautions"),
+            t("kiosk.admitted"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        self.render_table(
+            frame,
+            area,
+            rows.collect(),
+            header,
+            &widths,
+            t("kiosk.no_admissions"),
+        );
+    }
+
+    fn render_appointments(&self, frame: &mut Frame, area: Rect) {
+        let widths = [
+            Constraint::Length(8),
+            Constraint::Percentage(35),
+            Constraint::Percentage(30),
+            Constraint::Min(12),
+        ];
+        let columns = column_widths(area.inner(Margin::new(1, 1)), &widths, 1, "");
+        let rows = self.data.consultations.iter().map(|consultation| {
+            let status = match consultation.status {
+                TeleconsultStatus::Scheduled if consultation.checked_in_at.is_some() => {
+                    t("kiosk.checked_in").to_string()
+                }
+                status => status_text(status).to_string(),
+            };
+            let doctor = self
+                .data
+                .doctors
+                .get(&consultation.doctor_id)
+                .map(|name| format!("Dr. {}", name))
+                .unwrap_or_else(|| "-".to_string());
+            Row::new(vec![
+                cell(datetime::time_part(&consultation.scheduled_at), &columns, 0),
+                cell(doctor, &columns, 1),
+                cell(self.patient_name(consultation.patient_id), &columns, 2),
+                cell(status, &columns, 3),
+            ])
+            .style(Style::default().fg(theme().text))
+        });
+        let header = Row::new(vec![
+            t("kiosk.time"),
+            t("kiosk.doctor"),
+            t("kiosk.patient"),
+            t("kiosk.status"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        self.render_table(
+            frame,
+            area,
+            rows.collect(),
+            header,
+            &widths,
+            t("kiosk.no_appointments"),
+        );
+    }
+
+    fn render_table(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        rows: Vec<Row>,
+        header: Row,
+        widths: &[Constraint],
+        empty: &str,
+    ) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+        if rows.is_empty() {
+            frame.render_widget(
+                Paragraph::new(format!("\n\n{}", empty))
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        }
+        frame.render_widget(
+            Table::new(rows, widths.to_vec())
+                .header(header)
+                .block(block),
+            area,
+        );
+    }
+
+    /// The screens along the bottom row, the current one highlighted.
+    fn render_screen_bar(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let bar = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
+        let mut spans = Vec::new();
+        for (i, screen) in self.screens.iter().enumerate() {
+            let style = if i == self.current {
+                Style::default()
+                    .fg(theme().focus)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme().inactive)
+            };
+            spans.push(Span::styled(
+                format!(" {} {} ", i + 1, screen.title()),
+                style,
+            ));
+        }
+        spans.push(Span::styled(
+            format!("  {}", t("kiosk.read_only")),
+            Style::default().fg(theme().help),
+        ));
+        frame.render_widget(
+            Paragraph::new(Line::from(spans))
+                .alignment(Alignment::Center)
+                .style(Style::default().bg(theme().background)),
+            bar,
+        );
+    }
+}
+
+impl Component for Kiosk {
+    /// Only switches screens; nothing can be changed from a kiosk.
+    fn handle_input(&mut self, key: KeyEvent) -> Result<Option<crate::app::SelectedApp>> {
+        let count = self.screens.len();
+        match key.code {
+            KeyCode::Right | KeyCode::Tab => self.show(self.current + 1),
+            KeyCode::Left | KeyCode::BackTab => self.show(self.current + count - 1),
+            KeyCode::Char(c) => {
+                if let Some(index) = c.to_digit(10).filter(|d| (1..=count as u32).contains(d)) {
+                    self.show(index as usize - 1);
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn tick(&mut self) {
+        if self
+            .rotate
+            .is_some_and(|every| self.shown_at.elapsed() >= every)
+        {
+            self.show(self.current + 1);
+        }
+        if self.screen() == KioskScreen::Queue {
+            self.board.tick();
+        }
+        if let Some(pending) = &self.pending {
+            let Some(result) = pending.poll() else {
+                return;
+            };
+            self.pending = None;
+            match result {
+                Ok(data) => {
+                    self.data = data;
+                    self.error_message = None;
+                }
+                Err(e) => self.error_message = Some(tf("kiosk.refresh_failed", &[("error", &e)])),
+            }
+        }
+        if self
+            .last_refresh
+            .is_some_and(|at| at.elapsed() < REFRESH_INTERVAL)
+        {
+            return;
+        }
+        self.last_refresh = Some(Instant::now());
+        let day = today_text();
+        let db = self.db.clone();
+        self.pending = Some(worker::spawn(move || load_data(&db, &day)));
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let screen = self.screen();
+        if screen == KioskScreen::Queue {
+            self.board.render(frame);
+            self.render_screen_bar(frame);
+            return;
+        }
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(format!(
+                "{} — {}",
+                screen.title().to_uppercase(),
+                datetime::display_date(datetime::today())
+            ))
+            .style(
+                Style::default()
+                    .fg(theme().title)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::BOTTOM)
+                    .border_style(Style::default().fg(theme().border)),
+            ),
+            layout[0],
+        );
+        match screen {
+            KioskScreen::Beds => self.render_beds(frame, layout[1]),
+            KioskScreen::Appointments => self.render_appointments(frame, layout[1]),
+            KioskScreen::Queue => {}
+        }
+        if let Some(error) = &self.error_message {
+            frame.render_widget(
+                Paragraph::new(format!("⚠️ {}", error))
+                    .style(Style::default().fg(theme().error))
+                    .alignment(Alignment::Center),
+                layout[2],
+            );
+        }
+        self.render_screen_bar(frame);
+    }
+}
@@ -1,5 +1,9 @@
 use crate::app::SelectedApp;
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
+use crate::i18n::t;
+use crate::theme::theme;
 use crate::tui::Frame;
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
@@ -7,21 +11,16 @@ use ratatui::{
     prelude::*,
     widgets::{Block, BorderType, Borders, Clear, Paragraph},
 };
-use std::time::{Duration, Instant};
 
 #[derive(Debug, Default)]
 pub struct Login {
-    pub username: String,
-    pub password: String,
+    pub username: TextInput,
+    pub password: TextInput,
     #[allow(dead_code)]
     pub focus_username: bool,
-    pub error_message: Option<String>,
-    pub success_message: Option<String>,
     pub selected_index: usize,
     pub show_exit_dialog: bool,
     pub exit_dialog_selected: usize,
-    error_message_time: Option<std::time::Instant>,
-    success_message_time: Option<std::time::Instant>,
 }
 
 impl Login {
@@ -31,7 +30,6 @@ impl Login {
             selected_index: 0,
             show_exit_dialog: false,
             exit_dialog_selected: 0,
-            error_message_time: None,
             ..Default::default()
         }
     }
@@ -58,22 +56,8 @@ impl Login {
 
     fn handle_login_input(&mut self, key: KeyEvent) -> Result<bool> {
         match key.code {
-            KeyCode::Char(c) => {
-                if self.selected_index == 0 {
-                    self.username.push(c);
-                } else if self.selected_index == 1 {
-                    self.password.push(c);
-                }
-                self.clear_error_message();
-            }
-            KeyCode::Backspace => {
-                if self.selected_index == 0 {
-                    self.username.pop();
-                } else if self.selected_index == 1 {
-                    self.password.pop();
-                }
-                self.clear_error_message();
-            }
+            _ if self.selected_index == 0 && self.username.handle_key(key) => {}
+            _ if self.selected_index == 1 && self.password.handle_key(key) => {}
             KeyCode::Tab | KeyCode::Down => {
                 self.selected_index = (self.selected_index + 1) % 4;
             }
@@ -83,12 +67,12 @@ impl Login {
             KeyCode::Enter => match self.selected_index {
                 0 | 1 => {
                     if self.username.is_empty() {
-                        self.set_error_message("⚠️ Username cannot be empty.".to_string());
+                        toast::push(Toast::error(t("auth.username_empty")));
                         return Ok(false);
                     }
 
                     if self.password.is_empty() {
-                        self.set_error_message("⚠️ Password cannot be empty.".to_string());
+                        toast::push(Toast::error(t("auth.password_empty")));
                         return Ok(false);
                     }
 
@@ -110,48 +94,6 @@ impl Login {
         }
         Ok(false)
     }
-
-    fn clear_error_message(&mut self) {
-        self.error_message = None;
-        self.error_message_time = None;
-    }
-
-    fn clear_success_message(&mut self) {
-        self.success_message = None;
-        self.success_message_time = None;
-    }
-
-    fn set_error_message(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_message_time = Some(Instant::now());
-    }
-
-    pub fn set_success_message(&mut self, message: String) {
-        self.success_message = Some(message);
-        self.success_message_time = Some(Instant::now());
-    }
-
-    pub fn check_error_timeout(&mut self) {
-        if let Some(time) = self.error_message_time {
-            if time.elapsed() >= Duration::from_secs(5) {
-                self.clear_error_message();
-            }
-        }
-    }
-
-    pub fn check_message_timeouts(&mut self) {
-        if let Some(time) = self.error_message_time {
-            if time.elapsed() >= Duration::from_secs(5) {
-                self.clear_error_message();
-            }
-        }
-
-        if let Some(time) = self.success_message_time {
-            if time.elapsed() >= Duration::from_secs(5) {
-                self.clear_success_message();
-            }
-        }
-    }
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -176,8 +118,6 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 
 impl Component for Login {
     fn handle_input(&mut self, event: KeyEvent) -> Result<Option<crate::app::SelectedApp>> {
-        self.check_message_timeouts();
-
         if self.show_exit_dialog {
             if self.handle_exit_dialog_input(event)? {
                 return Ok(Some(SelectedApp::Quit));
@@ -196,7 +136,7 @@ impl Component for Login {
 
     fn render(&self, frame: &mut Frame) {
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             frame.area(),
         );
 
@@ -234,14 +174,14 @@ impl Component for Login {
             Line::from("╚═╝░░╚═╝░╚═════╝░╚═════╝░░░░╚═╝░░░░╚════╝░╚═╝░░╚═╝╚═╝╚═╝░░╚═╝".to_string()),
         ]))
         .alignment(Alignment::Center)
-        .style(Style::default().fg(Color::Rgb(129, 199, 245)));
+        .style(Style::default().fg(theme().accent));
         frame.render_widget(title, vertical_layout[0]);
 
         let title_block = Block::default().borders(Borders::NONE);
         let title = Paragraph::new(Text::from(vec![Line::from(Span::styled(
-            "Seamless Hospital & Pharmacy Operations",
+            t("login.tagline"),
             Style::default()
-                .fg(Color::Rgb(140, 219, 140))
+                .fg(theme().success)
                 .add_modifier(Modifier::BOLD)
                 .add_modifier(Modifier::ITALIC),
         ))]))
@@ -253,14 +193,14 @@ impl Component for Login {
         let login_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
         frame.render_widget(login_block.clone(), login_area);
 
         let subtitle = Paragraph::new(Span::styled(
-            "Login to Rustoria",
+            t("login.title"),
             Style::default()
-                .fg(Color::Rgb(230, 230, 250))
+                .fg(theme().title)
                 .add_modifier(Modifier::BOLD),
         ))
         .alignment(Alignment::Center);
@@ -269,81 +209,62 @@ impl Component for Login {
         let username_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .title(" Username ")
+            .title(format!(" {} ", t("auth.username")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .border_style(if self.selected_index == 0 {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
         let username_area = centered_rect(60, 100, vertical_layout[5]);
-        let username_input = Paragraph::new(self.username.clone())
+        let username_input = Paragraph::new(self.username.line(self.selected_index == 0))
             .block(username_block)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Left);
         frame.render_widget(username_input, username_area);
 
         let password_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .title(" Password ")
+            .title(format!(" {} ", t("auth.password")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .border_style(if self.selected_index == 1 {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
         let password_area = centered_rect(60, 100, vertical_layout[6]);
-        let password_input = Paragraph::new("•".repeat(self.password.len()))
-            .block(password_block)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-            .alignment(Alignment::Left);
+        let password_input =
+            Paragraph::new(self.password.masked_line(self.selected_index == 1, '•'))
+                .block(password_block)
+                .style(Style::default().fg(theme().text))
+                .alignment(Alignment::Left);
         frame.render_widget(password_input, password_area);
 
-        if let Some(error) = &self.error_message {
-            let error_message = Paragraph::new(Span::styled(
-                error,
-                Style::default()
-                    .fg(Color::Rgb(255, 100, 100))
-                    .add_modifier(Modifier::BOLD),
-            ))
-            .alignment(Alignment::Center);
-            frame.render_widget(error_message, vertical_layout[7]);
-        } else if let Some(success) = &self.success_message {
-            let success_message = Paragraph::new(Span::styled(
-                success,
-                Style::default()
-                    .fg(Color::Rgb(140, 219, 140))
-                    .add_modifier(Modifier::BOLD),
-            ))
-            .alignment(Alignment::Center);
-            frame.render_widget(success_message, vertical_layout[7]);
-        }
-
         let create_account_style = if self.selected_index == 2 {
             Style::default()
-                .fg(Color::Rgb(250, 250, 110))
+                .fg(theme().focus)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let create_account_text = Paragraph::new(if self.selected_index == 2 {
-            "► Create Account ◄"
+            format!("► {} ◄", t("login.create_account"))
         } else {
-            "  Create Account  "
+            format!("  {}  ", t("login.create_account"))
         })
         .style(create_account_style)
         .alignment(Alignment::Center);
@@ -351,26 +272,24 @@ impl Component for Login {
 
         let exit_style = if self.selected_index == 3 {
             Style::default()
-                .fg(Color::Rgb(255, 100, 100))
+                .fg(theme().error)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let exit_text = Paragraph::new(if self.selected_index == 3 {
-            "► Exit ◄"
+            format!("► {} ◄", t("login.exit"))
         } else {
-            "  Exit  "
+            format!("  {}  ", t("login.exit"))
         })
         .style(exit_style)
         .alignment(Alignment::Center);
         frame.render_widget(exit_text, vertical_layout[11]);
 
-        let help_text = Paragraph::new(
-            "TAB/Arrow Keys: Navigate | ENTER: Login/Select | ESC: Toggle Exit Dialog",
-        )
-        .style(Style::default().fg(Color::Rgb(140, 140, 170)))
-        .alignment(Alignment::Center);
+        let help_text = Paragraph::new(t("login.help"))
+            .style(Style::default().fg(theme().help))
+            .alignment(Alignment::Center);
         frame.render_widget(help_text, vertical_layout[13]);
 
         if self.show_exit_dialog {
@@ -387,16 +306,16 @@ impl Component for Login {
             frame.render_widget(Clear, dialog_area);
 
             let dialog_block = Block::default()
-                .title(" Confirm Exit ")
+                .title(format!(" {} ", t("login.confirm_exit")))
                 .title_style(
                     Style::default()
-                        .fg(Color::Rgb(230, 230, 250))
+                        .fg(theme().title)
                         .add_modifier(Modifier::BOLD),
                 )
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::Rgb(140, 140, 200)))
-                .style(Style::default().bg(Color::Rgb(30, 30, 46)));
+                .border_style(Style::default().fg(theme().unfocused))
+                .style(Style::default().bg(theme().dialog));
 
             frame.render_widget(dialog_block.clone(), dialog_area);
 
@@ -408,8 +327,8 @@ impl Component for Login {
                 .constraints([Constraint::Length(2), Constraint::Length(2)])
                 .split(inner_area);
 
-            let message = Paragraph::new("Are you sure you want to exit?")
-                .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            let message = Paragraph::new(t("login.confirm_exit_message"))
+                .style(Style::default().fg(theme().text))
                 .add_modifier(Modifier::BOLD)
                 .alignment(Alignment::Center);
 
@@ -422,30 +341,30 @@ impl Component for Login {
 
             let yes_style = if self.exit_dialog_selected == 0 {
                 Style::default()
-                    .fg(Color::Rgb(140, 219, 140))
+                    .fg(theme().success)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::Rgb(180, 180, 200))
+                Style::default().fg(theme().inactive)
             };
 
             let no_style = if self.exit_dialog_selected == 1 {
                 Style::default()
-                    .fg(Color::Rgb(255, 100, 100))
+                    .fg(theme().error)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::Rgb(180, 180, 200))
+                Style::default().fg(theme().inactive)
             };
 
             let yes_text = if self.exit_dialog_selected == 0 {
-                "► Yes ◄"
+                format!("► {} ◄", t("common.yes"))
             } else {
-                "  Yes  "
+                format!("  {}  ", t("common.yes"))
             };
 
             let no_text = if self.exit_dialog_selected == 1 {
-                "► No ◄"
+                format!("► {} ◄", t("common.no"))
             } else {
-                "  No  "
+                format!("  {}  ", t("common.no"))
             };
 
             let yes_button = Paragraph::new(yes_text)
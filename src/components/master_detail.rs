@@ -0,0 +1,88 @@
+//! List-with-details layout: the searchable list on the left and the fields
+//! of its highlighted row on the right, updated as the selection moves.
+
+use crate::components::responsive::is_narrow;
+use crate::theme::theme;
+use crate::tui::Frame;
+use ratatui::{prelude::*, widgets::*};
+
+/// Share of the width given to the list when the details pane is shown.
+const LIST_PERCENT: u16 = 60;
+
+/// Splits `area` into the list and the details pane. Terminals too narrow
+/// for both get the list alone, and screens keep their own details view.
+pub fn split(area: Rect) -> (Rect, Option<Rect>) {
+    if is_narrow(area) {
+        return (area, None);
+    }
+    let [list, details] = Layout::horizontal([
+        Constraint::Percentage(LIST_PERCENT),
+        Constraint::Percentage(100 - LIST_PERCENT),
+    ])
+    .spacing(1)
+    .areas(area);
+    (list, Some(details))
+}
+
+/// Renders `fields` as label/value pairs, or `empty` when no row is
+/// highlighted. Empty values are shown as "-".
+pub fn render_details(
+    frame: &mut Frame,
+    area: Rect,
+    title: &str,
+    fields: Option<&[(&str, String)]>,
+    empty: &str,
+) {
+    let block = Block::default()
+        .title(Span::styled(
+            format!(" {} ", title),
+            Style::default()
+                .fg(theme().title)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme().border))
+        .style(Style::default().bg(theme().surface))
+        .padding(Padding::horizontal(1));
+
+    let Some(fields) = fields else {
+        frame.render_widget(
+            Paragraph::new(empty)
+                .style(Style::default().fg(theme().inactive))
+                .alignment(Alignment::Center)
+                .block(block),
+            area,
+        );
+        return;
+    };
+
+    let mut lines = Vec::new();
+    for (label, value) in fields {
+        let value = if value.trim().is_empty() { "-" } else { value };
+        let mut value_lines = value.lines();
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{}: ", label),
+                Style::default()
+                    .fg(theme().accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                value_lines.next().unwrap_or_default().to_string(),
+                Style::default().fg(theme().text),
+            ),
+        ]));
+        lines.extend(
+            value_lines
+                .map(|line| Line::styled(line.to_string(), Style::default().fg(theme().text))),
+        );
+    }
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(block),
+        area,
+    );
+}
@@ -2,12 +2,34 @@ use crate::tui::Frame;
 use anyhow::Result;
 use crossterm::event::KeyEvent;
 
+pub mod database_error;
+pub mod form;
+pub mod help;
 pub mod home;
 pub mod hospital;
+pub mod kiosk;
 pub mod login;
+pub mod master_detail;
+pub mod palette;
+pub mod quick_actions;
+pub mod quick_vitals;
 pub mod register;
+pub mod responsive;
+pub mod scan;
+pub mod schema_prompt;
+pub mod scroll;
+pub mod settings;
+pub mod table;
+pub mod tabs;
+pub mod toast;
+pub mod undo;
+pub mod webhook_log;
+pub mod widgets;
 
 pub trait Component {
     fn handle_input(&mut self, event: KeyEvent) -> Result<Option<crate::app::SelectedApp>>; // Modified return
     fn render(&self, frame: &mut Frame);
+
+    /// Called on every event-loop tick, whether or not a key was pressed.
+    fn tick(&mut self) {}
 }
@@ -0,0 +1,267 @@
+use crate::app::SelectedApp;
+use crate::components::widgets::text_input::TextInput;
+use crate::components::Component;
+use crate::i18n::t;
+use crate::plugins;
+use crate::theme::theme;
+use crate::tui::Frame;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+const COMMANDS: &[(&str, SelectedApp)] = &[
+    ("palette.add_patient", SelectedApp::PatientAdd),
+    ("palette.list_patients", SelectedApp::PatientList),
+    ("palette.update_patient", SelectedApp::PatientUpdate),
+    ("palette.delete_patient", SelectedApp::PatientDelete),
+    ("palette.compliance", SelectedApp::PatientCompliance),
+    ("palette.import_patients", SelectedApp::PatientImport),
+    ("palette.add_staff", SelectedApp::StaffAdd),
+    ("palette.assign_shift", SelectedApp::StaffAssign),
+    ("palette.list_staff", SelectedApp::StaffList),
+    ("palette.update_staff", SelectedApp::StaffUpdate),
+    ("palette.delete_staff", SelectedApp::StaffDelete),
+    ("palette.staff_performance", SelectedApp::StaffPerformance),
+    ("palette.add_record", SelectedApp::RecordStore),
+    ("palette.view_records", SelectedApp::RecordRetrieve),
+    ("palette.update_record", SelectedApp::RecordUpdate),
+    ("palette.delete_record", SelectedApp::RecordDelete),
+    ("palette.campaign", SelectedApp::RecordCampaign),
+    ("palette.statistics", SelectedApp::RecordStatistics),
+    ("palette.expirations", SelectedApp::RecordExpirations),
+    ("palette.isolations", SelectedApp::RecordIsolations),
+    ("palette.stays", SelectedApp::RecordStays),
+    ("palette.integrity", SelectedApp::RecordIntegrity),
+    ("palette.waiting", SelectedApp::RecordWaiting),
+    ("palette.forecast", SelectedApp::RecordForecast),
+    ("palette.satisfaction", SelectedApp::RecordSatisfaction),
+    ("palette.create_invoice", SelectedApp::BillingInvoice),
+    ("palette.view_invoices", SelectedApp::BillingView),
+    ("palette.update_invoice", SelectedApp::BillingUpdate),
+    ("palette.expenses", SelectedApp::BillingExpenses),
+    ("palette.expense_report", SelectedApp::BillingExpenseReport),
+    ("palette.budget", SelectedApp::BillingBudget),
+    ("palette.cash_drawer", SelectedApp::BillingCashier),
+    (
+        "palette.cashier_sessions",
+        SelectedApp::BillingCashierHistory,
+    ),
+    ("palette.insurance_plans", SelectedApp::BillingInsurance),
+    ("palette.price_approvals", SelectedApp::BillingApprovals),
+    ("palette.add_waitlist", SelectedApp::WaitlistAdd),
+    ("palette.view_waitlist", SelectedApp::WaitlistList),
+    ("palette.births", SelectedApp::RegistryBirths),
+    ("palette.deaths", SelectedApp::RegistryDeaths),
+    (
+        "palette.schedule_teleconsult",
+        SelectedApp::TeleconsultSchedule,
+    ),
+    (
+        "palette.upcoming_teleconsults",
+        SelectedApp::TeleconsultUpcoming,
+    ),
+    (
+        "palette.doctor_utilization",
+        SelectedApp::TeleconsultUtilization,
+    ),
+    ("palette.issue_token", SelectedApp::QueueIssue),
+    ("palette.queue_console", SelectedApp::QueueConsole),
+    ("palette.queue_display", SelectedApp::QueueDisplay),
+    ("palette.record_handover", SelectedApp::HandoverRecord),
+    ("palette.handover_history", SelectedApp::HandoverHistory),
+    ("palette.my_day", SelectedApp::WorklistMyDay),
+    ("palette.ward_round", SelectedApp::WorklistWardRound),
+    ("palette.follow_ups", SelectedApp::WorklistFollowUps),
+    ("palette.pregnancies", SelectedApp::MaternityPregnancies),
+    ("palette.due_this_month", SelectedApp::MaternityDue),
+    ("palette.book_appointment", SelectedApp::AppointmentBook),
+    ("palette.appointments", SelectedApp::AppointmentList),
+    ("palette.update_appointment", SelectedApp::AppointmentUpdate),
+    ("palette.stock", SelectedApp::InventoryStock),
+    ("palette.stocktake", SelectedApp::InventoryStocktake),
+    ("palette.settings", SelectedApp::Settings),
+    ("palette.backup_database", SelectedApp::SettingsBackup),
+    ("palette.webhooks", SelectedApp::WebhookLog),
+];
+
+/// The palette command `name`, like `add_patient` for `palette.add_patient`,
+/// with the key of its title.
+pub fn command(name: &str) -> Option<(&'static str, SelectedApp)> {
+    COMMANDS
+        .iter()
+        .find(|(key, _)| key.strip_prefix("palette.") == Some(name))
+        .copied()
+}
+
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let offset = candidate[position..].iter().position(|c| *c == q)?;
+        let index = position + offset;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == index) {
+            score += 5;
+        }
+        if index == 0 || candidate[index - 1] == ' ' {
+            score += 3;
+        }
+        score -= offset as i32;
+        previous = Some(index);
+        position = index + 1;
+    }
+    Some(score)
+}
+
+pub struct CommandPalette {
+    query: TextInput,
+    matches: Vec<(&'static str, SelectedApp)>,
+    state: ListState,
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        let mut palette = Self {
+            query: TextInput::new(),
+            matches: Vec::new(),
+            state: ListState::default(),
+        };
+        palette.update_matches();
+        palette
+    }
+
+    fn update_matches(&mut self) {
+        let plugins = plugins::plugins();
+        let plugin_commands = plugins
+            .iter()
+            .enumerate()
+            .map(|(id, plugin)| (plugin.name, SelectedApp::Plugin(id)));
+        let mut scored: Vec<(i32, &'static str, SelectedApp)> = COMMANDS
+            .iter()
+            .copied()
+            .chain(plugin_commands)
+            .filter_map(|(key, app)| {
+                let label = t(key);
+                fuzzy_score(self.query.value(), label).map(|s| (s, label, app))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+        self.matches = scored.into_iter().map(|(_, l, a)| (l, a)).collect();
+        self.state.select(if self.matches.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        match key.code {
+            _ if self.query.handle_key(key) => self.update_matches(),
+            KeyCode::Down if !self.matches.is_empty() => {
+                let i = self
+                    .state
+                    .selected()
+                    .map_or(0, |i| (i + 1) % self.matches.len());
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if !self.matches.is_empty() => {
+                let len = self.matches.len();
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Enter => {
+                if let Some((_, app)) = self.state.selected().and_then(|i| self.matches.get(i)) {
+                    return Ok(Some(*app));
+                }
+            }
+            KeyCode::Esc => return Ok(Some(SelectedApp::None)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+impl Component for CommandPalette {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(60) / 2,
+            area.height / 6,
+            60.min(area.width),
+            16.min(area.height),
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" 🔎 {} ", t("palette.title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(3),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        let mut query = self.query.line(true);
+        query.spans.insert(0, Span::raw("> "));
+        frame.render_widget(
+            Paragraph::new(query)
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().input)),
+                ),
+            chunks[0],
+        );
+
+        let items: Vec<ListItem> = if self.matches.is_empty() {
+            vec![ListItem::new(t("palette.no_matches")).style(Style::default().fg(theme().inactive))]
+        } else {
+            self.matches
+                .iter()
+                .map(|(label, _)| ListItem::new(*label))
+                .collect()
+        };
+        let list = List::new(items)
+            .style(Style::default().fg(theme().text))
+            .highlight_style(
+                Style::default()
+                    .bg(theme().highlight)
+                    .fg(theme().focus)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(list, chunks[1], &mut self.state.clone());
+
+        frame.render_widget(
+            Paragraph::new(t("palette.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[2],
+        );
+    }
+}
+
+impl Default for CommandPalette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,111 @@
+//! Single keys on the home screen that jump straight into a screen, set in
+//! the configuration by palette command name:
+//!
+//! ```toml
+//! [quick_actions]
+//! n = "add_patient"
+//! i = "create_invoice"
+//! b = "schedule_teleconsult"
+//! ```
+//!
+//! An add form opened this way goes back home once it has saved.
+
+use crate::app::SelectedApp;
+use crate::components::palette;
+use crate::components::toast::{self, Toast};
+use crate::i18n::tf;
+use crate::utils::config;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::sync::Mutex;
+
+/// Keys that already mean something on the home screen: macros and help.
+/// Digits open recently viewed items.
+const RESERVED: [char; 3] = ['q', '@', '?'];
+
+/// The screen opened by a quick action, and whether it has saved, until the
+/// user is back home.
+static OPENED: Mutex<Option<(SelectedApp, bool)>> = Mutex::new(None);
+
+fn key_char(key: &str) -> Option<char> {
+    let mut chars = key.chars();
+    let c = chars.next()?;
+    (chars.next().is_none() && !c.is_whitespace() && !c.is_ascii_digit() && !RESERVED.contains(&c))
+        .then_some(c)
+}
+
+/// The configured quick actions as (key, title key, screen), in key order.
+pub fn actions() -> Vec<(char, &'static str, SelectedApp)> {
+    config::config()
+        .quick_actions
+        .iter()
+        .filter_map(|(key, command)| {
+            let (title, app) = palette::command(command)?;
+            Some((key_char(key)?, title, app))
+        })
+        .collect()
+}
+
+/// The screen a key pressed on the home screen opens, if it is a quick
+/// action.
+pub fn open(key: KeyEvent) -> Option<SelectedApp> {
+    let KeyCode::Char(c) = key.code else {
+        return None;
+    };
+    if key
+        .modifiers
+        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+    {
+        return None;
+    }
+    let (_, _, app) = actions().into_iter().find(|(key, _, _)| *key == c)?;
+    *OPENED.lock().unwrap_or_else(|e| e.into_inner()) = Some((app, false));
+    Some(app)
+}
+
+/// Called by the add form `app` after it saved; if a quick action opened it,
+/// [`finished`] then takes the user back home.
+pub fn saved(app: SelectedApp) {
+    if let Some((opened, saved)) = OPENED.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        if *opened == app {
+            *saved = true;
+        }
+    }
+}
+
+/// True once, after the screen a quick action opened has saved.
+pub fn finished() -> bool {
+    let mut opened = OPENED.lock().unwrap_or_else(|e| e.into_inner());
+    if opened.is_some_and(|(_, saved)| saved) {
+        *opened = None;
+        true
+    } else {
+        false
+    }
+}
+
+/// Forgets the quick action once the user is back home.
+pub fn clear() {
+    *OPENED.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Warns about quick actions that cannot work, so a typo in the
+/// configuration file does not go unnoticed.
+pub fn check_config() {
+    for (key, command) in &config::config().quick_actions {
+        let problem = if key_char(key).is_none() {
+            tf("quick_actions.not_free_key", &[("key", &key)])
+        } else if palette::command(command).is_none() {
+            tf("quick_actions.no_command", &[("command", &command)])
+        } else {
+            continue;
+        };
+        toast::push(Toast::error(tf(
+            "quick_actions.problem",
+            &[
+                ("key", &key),
+                ("path", &config::path().display()),
+                ("problem", &problem),
+            ],
+        )));
+    }
+}
@@ -0,0 +1,295 @@
+use crate::components::form::Form;
+use crate::components::hospital::worklist::ward_round::{vitals_form, vitals_observation};
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::text_input::TextInput;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::theme::theme;
+use crate::tui::Frame;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+/// A patient offered by the picker.
+struct Candidate {
+    patient_id: i64,
+    name: String,
+    mrn: String,
+    phone_number: String,
+    /// Ward and bed while admitted.
+    place: Option<String>,
+}
+
+/// Vital signs entered over any screen: pick the patient, by name, MRN or
+/// phone, then fill in the readings. Admitted patients are offered first.
+pub struct QuickVitals {
+    db: Db,
+    candidates: Vec<Candidate>,
+    query: TextInput,
+    list_state: ListState,
+    /// The patient picked and the readings, once past the picker.
+    entry: Option<(usize, Form)>,
+    focus_index: usize,
+}
+
+impl QuickVitals {
+    pub fn new(db: &Db) -> Result<Self> {
+        let admissions = db::get_current_admissions(db)?;
+        let mut candidates: Vec<Candidate> = db::get_all_patients(db)?
+            .into_iter()
+            .map(|p| Candidate {
+                patient_id: p.id,
+                name: format!("{} {}", p.first_name, p.last_name),
+                mrn: p.mrn.clone(),
+                phone_number: p.phone_number,
+                place: admissions
+                    .iter()
+                    .find(|a| a.patient_id == p.id)
+                    .map(|a| match &a.bed {
+                        Some(bed) => format!("{} / {}", a.ward, bed),
+                        None => a.ward.clone(),
+                    }),
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            a.place
+                .is_none()
+                .cmp(&b.place.is_none())
+                .then_with(|| a.place.cmp(&b.place))
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+        Ok(Self {
+            db: db.clone(),
+            candidates,
+            query: TextInput::new(),
+            list_state,
+            entry: None,
+            focus_index: 0,
+        })
+    }
+
+    /// Indexes into `candidates` of the patients matching the search.
+    fn matches(&self) -> Vec<usize> {
+        let query = self.query.value().trim().to_lowercase();
+        self.candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| {
+                query.is_empty()
+                    || c.name.to_lowercase().contains(&query)
+                    || c.mrn.to_lowercase().contains(&query)
+                    || c.phone_number.contains(&query)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Handles `key`; returns whether the quick entry is finished.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if self.entry.is_some() {
+            return self.handle_form_key(key);
+        }
+        let matches = self.matches();
+        let count = matches.len();
+        let selected = self.list_state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc => return true,
+            KeyCode::Enter => {
+                if let Some(&index) = matches.get(selected) {
+                    self.entry = Some((index, vitals_form()));
+                    self.focus_index = 0;
+                }
+            }
+            KeyCode::Down if count > 0 => self.list_state.select(Some((selected + 1) % count)),
+            KeyCode::Up if count > 0 => {
+                self.list_state.select(Some((selected + count - 1) % count))
+            }
+            _ => {
+                if self.query.handle_key(key) {
+                    self.list_state.select(Some(0));
+                }
+            }
+        }
+        false
+    }
+
+    fn handle_form_key(&mut self, key: KeyEvent) -> bool {
+        let Some((_, form)) = &mut self.entry else {
+            return false;
+        };
+        let len = form.len();
+        match key.code {
+            // Back to the picker, for the wrong patient.
+            KeyCode::Esc => self.entry = None,
+            KeyCode::Tab | KeyCode::Down => self.focus_index = (self.focus_index + 1) % len,
+            KeyCode::BackTab | KeyCode::Up => self.focus_index = (self.focus_index + len - 1) % len,
+            KeyCode::Enter if self.focus_index + 1 < len => self.focus_index += 1,
+            KeyCode::Enter => return self.save(),
+            _ => {
+                form.handle_key(self.focus_index, key);
+            }
+        }
+        false
+    }
+
+    /// Records the readings; returns whether they were saved.
+    fn save(&mut self) -> bool {
+        let Some((index, form)) = &mut self.entry else {
+            return false;
+        };
+        if let Some(invalid) = form.validate() {
+            self.focus_index = invalid;
+            return false;
+        }
+        let candidate = &self.candidates[*index];
+        let observation = vitals_observation(form, candidate.patient_id);
+        if !observation.has_vitals() {
+            form.fields[0].error = Some(t("vitals.enter_one").to_string());
+            self.focus_index = 0;
+            return false;
+        }
+        match db::create_observation(&self.db, &observation) {
+            Ok(_) => {
+                toast::push(Toast::success(tf(
+                    "vitals.saved",
+                    &[("patient", &candidate.name)],
+                )));
+                true
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("vitals.failed", &[("error", &e)])));
+                false
+            }
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let width = 70.min(area.width);
+        let height = 20.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let title = match &self.entry {
+            Some((index, _)) => {
+                let candidate = &self.candidates[*index];
+                format!(
+                    " 🩺 {} - {} ({}) ",
+                    t("vitals.title"),
+                    candidate.name,
+                    candidate.mrn
+                )
+            }
+            None => format!(" 🩺 {} ", t("vitals.title")),
+        };
+        let block = Block::default()
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        match &self.entry {
+            Some((_, form)) => self.render_form(frame, inner, form),
+            None => self.render_picker(frame, inner),
+        }
+    }
+
+    fn render_picker(&self, frame: &mut Frame, area: Rect) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(1)
+            .split(area);
+
+        frame.render_widget(
+            Paragraph::new(self.query.line(true))
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("vitals.search")))
+                        .border_style(Style::default().fg(theme().focus))
+                        .style(Style::default().bg(theme().input)),
+                ),
+            layout[0],
+        );
+
+        let matches = self.matches();
+        let items: Vec<ListItem> = if matches.is_empty() {
+            vec![ListItem::new(t("vitals.no_match")).style(Style::default().fg(theme().inactive))]
+        } else {
+            matches
+                .iter()
+                .map(|&index| {
+                    let candidate = &self.candidates[index];
+                    let place = candidate.place.as_deref().unwrap_or("");
+                    let color = if candidate.place.is_some() {
+                        theme().text
+                    } else {
+                        theme().inactive
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(
+                            format!("{:<17} ", candidate.mrn),
+                            Style::default().fg(theme().accent),
+                        ),
+                        Span::styled(
+                            format!("{:<28} ", candidate.name),
+                            Style::default().fg(theme().text),
+                        ),
+                        Span::styled(place.to_string(), Style::default().fg(color)),
+                    ]))
+                })
+                .collect()
+        };
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(theme().highlight))
+            .highlight_symbol("► ");
+        let mut state = self.list_state.clone();
+        if matches.is_empty() {
+            state.select(None);
+        }
+        frame.render_stateful_widget(list, layout[1], &mut state);
+
+        frame.render_widget(
+            Paragraph::new(t("vitals.pick_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[2],
+        );
+    }
+
+    fn render_form(&self, frame: &mut Frame, area: Rect, form: &Form) {
+        let mut constraints = vec![Constraint::Length(3); form.len()];
+        constraints.push(Constraint::Min(1));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .horizontal_margin(1)
+            .split(area);
+        for index in 0..form.len() {
+            form.render_field(frame, index, chunks[index], self.focus_index);
+        }
+        frame.render_widget(
+            Paragraph::new(t("vitals.form_help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[form.len()],
+        );
+    }
+}
@@ -1,5 +1,10 @@
 use crate::app::SelectedApp;
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::text_input::TextInput;
 use crate::components::Component;
+use crate::db::Db;
+use crate::i18n::t;
+use crate::theme::theme;
 use crate::tui::Frame;
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
@@ -7,45 +12,55 @@ use ratatui::{
     prelude::*,
     widgets::{Block, BorderType, Borders, Paragraph},
 };
-use std::time::{Duration, Instant};
 
-#[derive(Debug, Default)]
 pub struct Register {
-    pub username: String,
-    pub password: String,
-    pub confirm_password: String,
+    db: Db,
+    pub username: TextInput,
+    pub password: TextInput,
+    pub confirm_password: TextInput,
     focus_index: usize,
-    pub error_message: Option<String>,
-    error_message_time: Option<Instant>,
     pub registration_success: bool,
+    /// Whether the typed username already has an account, checked as it is typed.
+    username_taken: bool,
 }
 
 impl Register {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            username: TextInput::default(),
+            password: TextInput::default(),
+            confirm_password: TextInput::default(),
+            focus_index: 0,
+            registration_success: false,
+            username_taken: false,
+        }
+    }
+
+    fn focused_input(&mut self) -> Option<&mut TextInput> {
+        match self.focus_index {
+            0 => Some(&mut self.username),
+            1 => Some(&mut self.password),
+            2 => Some(&mut self.confirm_password),
+            _ => None,
+        }
+    }
+
+    fn check_username(&mut self) {
+        self.username_taken = !self.username.is_empty()
+            && crate::db::username_exists(&self.db, self.username.value()).unwrap_or(false);
     }
 
     fn handle_register_input(&mut self, key: KeyEvent) -> Result<bool> {
         match key.code {
-            KeyCode::Char(c) => {
-                match self.focus_index {
-                    0 => self.username.push(c),
-                    1 => self.password.push(c),
-                    2 => self.confirm_password.push(c),
-                    _ => {}
-                }
-                self.clear_error_message();
-                self.registration_success = false;
-            }
-            KeyCode::Backspace => {
-                match self.focus_index {
-                    0 => self.username.pop(),
-                    1 => self.password.pop(),
-                    2 => self.confirm_password.pop(),
-                    _ => None,
-                };
-                self.clear_error_message();
+            _ if self
+                .focused_input()
+                .is_some_and(|input| input.handle_key(key)) =>
+            {
                 self.registration_success = false;
+                if self.focus_index == 0 {
+                    self.check_username();
+                }
             }
             KeyCode::Tab | KeyCode::Down => {
                 self.focus_index = (self.focus_index + 1) % 4;
@@ -67,24 +82,6 @@ impl Register {
         }
         Ok(false)
     }
-
-    fn clear_error_message(&mut self) {
-        self.error_message = None;
-        self.error_message_time = None;
-    }
-
-    fn set_error_message(&mut self, message: String) {
-        self.error_message = Some(message);
-        self.error_message_time = Some(Instant::now());
-    }
-
-    pub fn check_error_timeout(&mut self) {
-        if let Some(time) = self.error_message_time {
-            if time.elapsed() >= Duration::from_secs(5) {
-                self.clear_error_message();
-            }
-        }
-    }
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -109,26 +106,31 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 
 impl Component for Register {
     fn handle_input(&mut self, event: KeyEvent) -> Result<Option<crate::app::SelectedApp>> {
-        self.check_error_timeout();
-
         if self.handle_register_input(event)? {
             if self.focus_index == 3 || event.code == KeyCode::Esc {
                 return Ok(Some(SelectedApp::None));
             } else {
                 if self.username.is_empty() {
-                    self.set_error_message("Username cannot be empty.".to_string());
+                    toast::push(Toast::error(t("auth.username_empty")));
+                    return Ok(None);
+                }
+                self.check_username();
+                if self.username_taken {
+                    self.focus_index = 0;
+                    toast::push(Toast::error(t("register.username_taken")));
                     return Ok(None);
                 }
                 if self.password.is_empty() {
-                    self.set_error_message("Password cannot be empty.".to_string());
+                    toast::push(Toast::error(t("auth.password_empty")));
                     return Ok(None);
                 }
-                if self.password != self.confirm_password {
-                    self.set_error_message("Passwords do not match.".to_string());
+                if self.password.value() != self.confirm_password.value() {
+                    toast::push(Toast::error(t("register.passwords_mismatch")));
                     return Ok(None);
                 }
 
-                match crate::db::create_user(&self.username, &self.password) {
+                match crate::db::create_user(&self.db, self.username.value(), self.password.value())
+                {
                     Ok(_) => {
                         self.registration_success = true;
 
@@ -138,7 +140,7 @@ impl Component for Register {
                         return Ok(Some(SelectedApp::None));
                     }
                     Err(err) => {
-                        self.set_error_message(format!("{}", err));
+                        toast::push(Toast::error(format!("{}", err)));
                         return Ok(None);
                     }
                 }
@@ -149,7 +151,7 @@ impl Component for Register {
 
     fn render(&self, frame: &mut Frame) {
         frame.render_widget(
-            Block::default().style(Style::default().bg(Color::Rgb(16, 16, 28))),
+            Block::default().style(Style::default().bg(theme().background)),
             frame.area(),
         );
 
@@ -157,8 +159,8 @@ impl Component for Register {
         let container_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::Rgb(75, 75, 120)))
-            .style(Style::default().bg(Color::Rgb(22, 22, 35)));
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
 
         frame.render_widget(container_block.clone(), form_container);
 
@@ -183,123 +185,117 @@ impl Component for Register {
             .margin(2)
             .split(container_block.inner(form_container));
 
-        let title = Paragraph::new("Create Account")
+        let title = Paragraph::new(t("register.title"))
             .style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .alignment(Alignment::Center);
         frame.render_widget(title, vertical_layout[0]);
 
-        let username_block = Block::default()
+        let mut username_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .title(" Username ")
+            .title(format!(" {} ", t("auth.username")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
-            .border_style(if self.focus_index == 0 {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+            .border_style(if self.username_taken {
+                Style::default().fg(theme().error)
+            } else if self.focus_index == 0 {
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
+        if self.username_taken {
+            username_block = username_block.title_bottom(Line::styled(
+                format!(" ⚠ {} ", t("register.username_taken")),
+                Style::default().fg(theme().error),
+            ));
+        } else if !self.username.is_empty() {
+            username_block = username_block.title_bottom(Line::styled(
+                format!(" ✓ {} ", t("register.username_available")),
+                Style::default().fg(theme().success),
+            ));
+        }
 
-        let username_input = Paragraph::new(self.username.clone())
+        let username_input = Paragraph::new(self.username.line(self.focus_index == 0))
             .block(username_block)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Left);
         frame.render_widget(username_input, vertical_layout[2]);
 
         let password_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .title(" Password ")
+            .title(format!(" {} ", t("auth.password")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .border_style(if self.focus_index == 1 {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
-        let password_input = Paragraph::new("•".repeat(self.password.len()))
+        let password_input = Paragraph::new(self.password.masked_line(self.focus_index == 1, '•'))
             .block(password_block)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
+            .style(Style::default().fg(theme().text))
             .alignment(Alignment::Left);
         frame.render_widget(password_input, vertical_layout[4]);
 
         let confirm_password_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .title(" Confirm Password ")
+            .title(format!(" {} ", t("register.confirm_password")))
             .title_style(
                 Style::default()
-                    .fg(Color::Rgb(230, 230, 250))
+                    .fg(theme().title)
                     .add_modifier(Modifier::BOLD),
             )
             .border_style(if self.focus_index == 2 {
-                Style::default().fg(Color::Rgb(250, 250, 110))
+                Style::default().fg(theme().focus)
             } else {
-                Style::default().fg(Color::Rgb(140, 140, 200))
+                Style::default().fg(theme().unfocused)
             })
-            .style(Style::default().bg(Color::Rgb(26, 26, 36)));
+            .style(Style::default().bg(theme().input));
 
-        let confirm_password_input = Paragraph::new("•".repeat(self.confirm_password.len()))
-            .block(confirm_password_block)
-            .style(Style::default().fg(Color::Rgb(220, 220, 240)))
-            .alignment(Alignment::Left);
+        let confirm_password_input = Paragraph::new(
+            self.confirm_password
+                .masked_line(self.focus_index == 2, '•'),
+        )
+        .block(confirm_password_block)
+        .style(Style::default().fg(theme().text))
+        .alignment(Alignment::Left);
         frame.render_widget(confirm_password_input, vertical_layout[6]);
 
-        if let Some(error) = &self.error_message {
-            let error_paragraph = Paragraph::new(error.as_str())
-                .style(
-                    Style::default()
-                        .fg(Color::Rgb(255, 100, 100))
-                        .add_modifier(Modifier::BOLD),
-                )
-                .alignment(Alignment::Center);
-            frame.render_widget(error_paragraph, vertical_layout[7]);
-        } else if self.registration_success {
-            let success_paragraph =
-                Paragraph::new("✓ Registration successful! You can now log in.")
-                    .style(
-                        Style::default()
-                            .fg(Color::Rgb(140, 219, 140))
-                            .add_modifier(Modifier::BOLD),
-                    )
-                    .alignment(Alignment::Center);
-            frame.render_widget(success_paragraph, vertical_layout[7]);
-        }
-
         let back_style = if self.focus_index == 3 {
             Style::default()
-                .fg(Color::Rgb(129, 199, 245))
+                .fg(theme().accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Rgb(180, 180, 200))
+            Style::default().fg(theme().inactive)
         };
 
         let back_to_login_text = Paragraph::new(if self.focus_index == 3 {
-            "► Back to Login ◄"
+            format!("► {} ◄", t("register.back_to_login"))
         } else {
-            "  Back to Login  "
+            format!("  {}  ", t("register.back_to_login"))
         })
         .style(back_style)
         .alignment(Alignment::Center);
         frame.render_widget(back_to_login_text, vertical_layout[8]);
 
-        let help_text =
-            Paragraph::new("TAB/Arrow Keys: Navigate | ENTER: Select | ESC: Back to Login")
-                .style(Style::default().fg(Color::Rgb(140, 140, 170)))
-                .alignment(Alignment::Center);
+        let help_text = Paragraph::new(t("register.help"))
+            .style(Style::default().fg(theme().help))
+            .alignment(Alignment::Center);
         frame.render_widget(help_text, vertical_layout[9]);
     }
 }
@@ -0,0 +1,82 @@
+use crate::i18n::{t, tf};
+use crate::theme::theme;
+use crate::tui::Frame;
+use ratatui::{prelude::*, widgets::*};
+
+pub const MIN_WIDTH: u16 = 80;
+pub const MIN_HEIGHT: u16 = 24;
+pub const NARROW_WIDTH: u16 = 110;
+pub const SHORT_HEIGHT: u16 = 30;
+
+pub fn is_too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+pub fn is_narrow(area: Rect) -> bool {
+    area.width < NARROW_WIDTH
+}
+
+pub fn is_short(area: Rect) -> bool {
+    area.height < SHORT_HEIGHT
+}
+
+pub fn render_too_small(frame: &mut Frame) {
+    let area = frame.area();
+    frame.render_widget(
+        Block::default().style(Style::default().bg(theme().background)),
+        area,
+    );
+
+    let size_style = |ok: bool| {
+        Style::default()
+            .fg(if ok { theme().success } else { theme().error })
+            .add_modifier(Modifier::BOLD)
+    };
+    let lines = vec![
+        Line::from(Span::styled(
+            t("responsive.too_small"),
+            Style::default()
+                .fg(theme().title)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!("{} ", t("responsive.current")),
+                Style::default().fg(theme().text),
+            ),
+            Span::styled(area.width.to_string(), size_style(area.width >= MIN_WIDTH)),
+            Span::styled(" x ", Style::default().fg(theme().text)),
+            Span::styled(
+                area.height.to_string(),
+                size_style(area.height >= MIN_HEIGHT),
+            ),
+        ]),
+        Line::from(Span::styled(
+            tf(
+                "responsive.needed",
+                &[("width", &MIN_WIDTH), ("height", &MIN_HEIGHT)],
+            ),
+            Style::default().fg(theme().text),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            t("responsive.resize"),
+            Style::default().fg(theme().help),
+        )),
+    ];
+
+    let height = (lines.len() as u16).min(area.height);
+    let notice_area = Rect::new(
+        area.x,
+        area.y + area.height.saturating_sub(height) / 2,
+        area.width,
+        height,
+    );
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        notice_area,
+    );
+}
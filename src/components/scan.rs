@@ -0,0 +1,139 @@
+use crate::components::widgets::text_input::TextInput;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::theme::theme;
+use crate::tui::Frame;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+/// The record a scanned code belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanTarget {
+    Patient(i64),
+    /// An imaging study, by its patient and accession number.
+    Study {
+        patient_id: i64,
+        accession_number: String,
+    },
+}
+
+pub enum ScanAction {
+    Open(ScanTarget),
+    Close,
+}
+
+/// Takes the text a keyboard-wedge scanner types, which ends with Enter, and
+/// finds the patient or sample it names.
+pub struct ScanPrompt {
+    db: Db,
+    input: TextInput,
+    error: Option<String>,
+}
+
+impl ScanPrompt {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            input: TextInput::new(),
+            error: None,
+        }
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<ScanAction>> {
+        match key.code {
+            _ if self.input.handle_key(key) => self.error = None,
+            KeyCode::Enter => {
+                let code = self.input.value().trim().to_string();
+                if code.is_empty() {
+                    return Ok(None);
+                }
+                match resolve(&self.db, &code) {
+                    Ok(Some(target)) => return Ok(Some(ScanAction::Open(target))),
+                    Ok(None) => {
+                        // Ready for the next scan straight away.
+                        self.error = Some(tf("scan.no_match", &[("code", &code)]));
+                        self.input.clear();
+                    }
+                    Err(e) => self.error = Some(e.to_string()),
+                }
+            }
+            KeyCode::Esc => return Ok(Some(ScanAction::Close)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(60) / 2,
+            area.height / 4,
+            60.min(area.width),
+            9.min(area.height),
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" ▦ {} ", t("scan.title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        frame.render_widget(
+            Paragraph::new(t("scan.prompt"))
+                .style(Style::default().fg(theme().text))
+                .wrap(Wrap { trim: true }),
+            chunks[0],
+        );
+        frame.render_widget(
+            Paragraph::new(self.input.line(true))
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().input)),
+                ),
+            chunks[1],
+        );
+        let (status, color) = match &self.error {
+            Some(error) => (error.as_str(), theme().error),
+            None => (t("scan.help"), theme().help),
+        };
+        frame.render_widget(
+            Paragraph::new(status)
+                .style(Style::default().fg(color))
+                .alignment(Alignment::Center),
+            chunks[2],
+        );
+    }
+}
+
+/// A patient's record number, or else a study's accession number.
+fn resolve(db: &Db, code: &str) -> Result<Option<ScanTarget>> {
+    if let Some(patient_id) = db::find_patient_by_mrn(db, code)? {
+        return Ok(Some(ScanTarget::Patient(patient_id)));
+    }
+    Ok(
+        db::find_imaging_study(db, code)?.map(|study| ScanTarget::Study {
+            patient_id: study.patient_id,
+            accession_number: study.accession_number,
+        }),
+    )
+}
@@ -0,0 +1,167 @@
+use crate::db::{self, Db, SchemaStatus, SCHEMA_VERSION};
+use crate::i18n::{t, tf};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaChoice {
+    Upgrade,
+    ReadOnly,
+    Quit,
+}
+
+/// Asks what to do with a database written by another version of Rustoria
+/// before anything reads from it. Upgrading is only offered for older files.
+pub struct SchemaPrompt {
+    db: Db,
+    status: SchemaStatus,
+    choices: Vec<SchemaChoice>,
+    selected: usize,
+}
+
+impl SchemaPrompt {
+    pub fn new(db: &Db, status: SchemaStatus) -> Self {
+        let choices = match status {
+            SchemaStatus::Older(_) => vec![
+                SchemaChoice::Upgrade,
+                SchemaChoice::ReadOnly,
+                SchemaChoice::Quit,
+            ],
+            _ => vec![SchemaChoice::ReadOnly, SchemaChoice::Quit],
+        };
+        Self {
+            db: db.clone(),
+            status,
+            choices,
+            selected: 0,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<SchemaChoice> {
+        let len = self.choices.len();
+        match key.code {
+            KeyCode::Right | KeyCode::Tab => self.selected = (self.selected + 1) % len,
+            KeyCode::Left | KeyCode::BackTab => self.selected = (self.selected + len - 1) % len,
+            KeyCode::Enter => return Some(self.choices[self.selected]),
+            KeyCode::Esc => return Some(SchemaChoice::Quit),
+            _ => {}
+        }
+        None
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let width = 76.min(area.width);
+        let height = 16.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        let block = Block::default()
+            .title(format!(" {} ", t("schema.title")))
+            .title_style(
+                Style::default()
+                    .fg(theme().warning)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().warning))
+            .style(Style::default().bg(theme().dialog))
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let [message, buttons, help] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(2),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .areas(inner);
+
+        let path = self.db.path().display().to_string();
+        let text = match self.status {
+            SchemaStatus::Older(version) => tf(
+                "schema.older",
+                &[
+                    ("path", &path),
+                    ("found", &version),
+                    ("expected", &SCHEMA_VERSION),
+                    (
+                        "backup",
+                        &db::upgrade_backup_path(&self.db, version)
+                            .display()
+                            .to_string(),
+                    ),
+                ],
+            ),
+            SchemaStatus::Newer(version) => tf(
+                "schema.newer",
+                &[
+                    ("path", &path),
+                    ("found", &version),
+                    ("expected", &SCHEMA_VERSION),
+                ],
+            ),
+            SchemaStatus::Current { .. } => String::new(),
+        };
+        frame.render_widget(
+            Paragraph::new(text)
+                .style(Style::default().fg(theme().text))
+                .wrap(Wrap { trim: true }),
+            message,
+        );
+
+        let button_areas = Layout::horizontal(vec![
+            Constraint::Ratio(1, self.choices.len() as u32);
+            self.choices.len()
+        ])
+        .split(buttons);
+        for (index, (choice, button_area)) in
+            self.choices.iter().zip(button_areas.iter()).enumerate()
+        {
+            let label = match choice {
+                SchemaChoice::Upgrade => t("schema.upgrade"),
+                SchemaChoice::ReadOnly => t("schema.read_only"),
+                SchemaChoice::Quit => t("schema.quit"),
+            };
+            let (text, style) = if self.selected == index {
+                (
+                    format!("► {} ◄", label),
+                    Style::default()
+                        .fg(theme().focus)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                (
+                    format!("  {}  ", label),
+                    Style::default().fg(theme().inactive),
+                )
+            };
+            frame.render_widget(
+                Paragraph::new(text)
+                    .style(style)
+                    .alignment(Alignment::Center),
+                *button_area,
+            );
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("schema.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            help,
+        );
+    }
+}
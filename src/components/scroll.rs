@@ -0,0 +1,65 @@
+use crate::tui::Frame;
+use ratatui::{prelude::*, widgets::*};
+
+pub fn wrapped_line_count(text: &str, width: u16) -> u16 {
+    let width = usize::from(width.max(1));
+    let mut lines = 0usize;
+    for line in text.lines() {
+        lines += 1;
+        let mut current = 0;
+        for word in line.split_whitespace() {
+            let word_width = Span::raw(word).width();
+            if current == 0 {
+                current = word_width;
+            } else if current + 1 + word_width <= width {
+                current += 1 + word_width;
+            } else {
+                lines += 1;
+                current = word_width;
+            }
+            while current > width {
+                lines += 1;
+                current -= width;
+            }
+        }
+    }
+    u16::try_from(lines.max(1)).unwrap_or(u16::MAX)
+}
+
+pub fn render_scrollable_text(
+    frame: &mut Frame,
+    area: Rect,
+    text: &str,
+    style: Style,
+    block: Block,
+    offset: u16,
+) -> u16 {
+    let inner = block.inner(area);
+    let total_lines = wrapped_line_count(text, inner.width);
+    let max_offset = total_lines.saturating_sub(inner.height);
+    let offset = offset.min(max_offset);
+
+    let paragraph = Paragraph::new(text)
+        .style(style)
+        .block(block)
+        .wrap(Wrap { trim: true })
+        .scroll((offset, 0));
+    frame.render_widget(paragraph, area);
+
+    if max_offset > 0 {
+        let mut scrollbar_state = ScrollbarState::new(usize::from(max_offset) + 1)
+            .viewport_content_length(usize::from(inner.height))
+            .position(usize::from(offset));
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state,
+        );
+    }
+    max_offset
+}
@@ -0,0 +1,698 @@
+use crate::accessibility;
+use crate::app::SelectedApp;
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::progress::render_progress;
+use crate::components::widgets::text_input::TextInput;
+use crate::components::Component;
+use crate::db::worker::{self, Task};
+use crate::db::{self, Db};
+use crate::i18n::{self, t, tf};
+use crate::theme::{self, theme, Theme, THEMES};
+use crate::tui::Frame;
+use crate::utils::config::{self, parse_key, Config};
+use crate::utils::datetime;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::path::PathBuf;
+
+const BACKUP_DIR: &str = "backups";
+
+const THEME_LIST: usize = 0;
+const LANGUAGE_LIST: usize = 1;
+const CONFIG_LIST: usize = 2;
+const PANES: usize = 3;
+
+/// The lines of the configuration file that can be edited here.
+#[derive(Clone, Copy, PartialEq)]
+enum ConfigField {
+    DbPath,
+    Theme,
+    Locale,
+    Currency,
+    SessionTimeout,
+    NoShowGrace,
+    SlotMinutes,
+    WaitTarget,
+    QueueTarget,
+    FollowUpRating,
+    Weekend,
+    Genders,
+    Timezone,
+    QuitKey,
+    PaletteKey,
+    HelpKey,
+    MacroRecordKey,
+    MacroPlayKey,
+    ScanKey,
+    VitalsKey,
+}
+
+const CONFIG_FIELDS: [ConfigField; 20] = [
+    ConfigField::DbPath,
+    ConfigField::Theme,
+    ConfigField::Locale,
+    ConfigField::Currency,
+    ConfigField::SessionTimeout,
+    ConfigField::NoShowGrace,
+    ConfigField::SlotMinutes,
+    ConfigField::WaitTarget,
+    ConfigField::QueueTarget,
+    ConfigField::FollowUpRating,
+    ConfigField::Weekend,
+    ConfigField::Genders,
+    ConfigField::Timezone,
+    ConfigField::QuitKey,
+    ConfigField::PaletteKey,
+    ConfigField::HelpKey,
+    ConfigField::MacroRecordKey,
+    ConfigField::MacroPlayKey,
+    ConfigField::ScanKey,
+    ConfigField::VitalsKey,
+];
+
+impl ConfigField {
+    fn label(self) -> &'static str {
+        match self {
+            ConfigField::DbPath => t("settings.config.db_path"),
+            ConfigField::Theme => t("settings.config.theme"),
+            ConfigField::Locale => t("settings.config.locale"),
+            ConfigField::Currency => t("settings.config.currency"),
+            ConfigField::SessionTimeout => t("settings.config.session_timeout"),
+            ConfigField::NoShowGrace => t("settings.config.no_show_grace"),
+            ConfigField::SlotMinutes => t("settings.config.slot_minutes"),
+            ConfigField::WaitTarget => t("settings.config.wait_target"),
+            ConfigField::QueueTarget => t("settings.config.queue_target"),
+            ConfigField::FollowUpRating => t("settings.config.follow_up_rating"),
+            ConfigField::Weekend => t("settings.config.weekend"),
+            ConfigField::Genders => t("settings.config.genders"),
+            ConfigField::Timezone => t("settings.config.timezone"),
+            ConfigField::QuitKey => t("settings.config.quit_key"),
+            ConfigField::PaletteKey => t("settings.config.palette_key"),
+            ConfigField::HelpKey => t("settings.config.help_key"),
+            ConfigField::MacroRecordKey => t("settings.config.macro_record_key"),
+            ConfigField::MacroPlayKey => t("settings.config.macro_play_key"),
+            ConfigField::ScanKey => t("settings.config.scan_key"),
+            ConfigField::VitalsKey => t("settings.config.vitals_key"),
+        }
+    }
+
+    fn value(self, config: &Config) -> String {
+        match self {
+            ConfigField::DbPath => config.db_path.display().to_string(),
+            ConfigField::Theme => config.theme.clone().unwrap_or_default(),
+            ConfigField::Locale => config.locale.clone().unwrap_or_default(),
+            ConfigField::Currency => config.currency.clone(),
+            ConfigField::SessionTimeout => config.session_timeout.to_string(),
+            ConfigField::NoShowGrace => config.no_show_grace.to_string(),
+            ConfigField::SlotMinutes => config.slot_minutes.to_string(),
+            ConfigField::WaitTarget => config.wait_target.to_string(),
+            ConfigField::QueueTarget => config.queue_target.to_string(),
+            ConfigField::FollowUpRating => config.follow_up_rating.to_string(),
+            ConfigField::Weekend => config.weekend.join(", "),
+            ConfigField::Genders => config.genders.join(", "),
+            ConfigField::Timezone => config.timezone.clone().unwrap_or_default(),
+            ConfigField::QuitKey => config.keymap.quit.clone(),
+            ConfigField::PaletteKey => config.keymap.palette.clone(),
+            ConfigField::HelpKey => config.keymap.help.clone(),
+            ConfigField::MacroRecordKey => config.keymap.macro_record.clone(),
+            ConfigField::MacroPlayKey => config.keymap.macro_play.clone(),
+            ConfigField::ScanKey => config.keymap.scan.clone(),
+            ConfigField::VitalsKey => config.keymap.vitals.clone(),
+        }
+    }
+
+    /// Stores `value` in `config`, or explains why it is not acceptable.
+    fn apply(self, config: &mut Config, value: &str) -> Result<(), String> {
+        let optional = (!value.is_empty()).then(|| value.to_string());
+        match self {
+            ConfigField::DbPath if value.is_empty() => {
+                return Err(t("settings.config.db_path_required").to_string());
+            }
+            ConfigField::DbPath => config.db_path = value.into(),
+            ConfigField::Theme => {
+                if optional.is_some() && theme::find_theme(value).is_none() {
+                    return Err(tf("settings.config.unknown_theme", &[("theme", &value)]));
+                }
+                config.theme = optional;
+            }
+            ConfigField::Locale => {
+                if optional.is_some() && !i18n::locales().contains(&value) {
+                    return Err(tf("settings.config.unknown_locale", &[("locale", &value)]));
+                }
+                config.locale = optional;
+            }
+            ConfigField::Currency => config.currency = value.to_string(),
+            ConfigField::SessionTimeout => {
+                config.session_timeout = value
+                    .parse()
+                    .map_err(|_| t("settings.config.invalid_minutes").to_string())?;
+            }
+            ConfigField::NoShowGrace => {
+                config.no_show_grace = value
+                    .parse()
+                    .map_err(|_| t("settings.config.invalid_minutes").to_string())?;
+            }
+            ConfigField::SlotMinutes => {
+                config.slot_minutes = value
+                    .parse()
+                    .ok()
+                    .filter(|minutes| *minutes > 0)
+                    .ok_or_else(|| t("settings.config.invalid_minutes").to_string())?;
+            }
+            ConfigField::WaitTarget => {
+                config.wait_target = value
+                    .parse()
+                    .map_err(|_| t("settings.config.invalid_minutes").to_string())?;
+            }
+            ConfigField::QueueTarget => {
+                config.queue_target = value
+                    .parse()
+                    .map_err(|_| t("settings.config.invalid_number").to_string())?;
+            }
+            ConfigField::FollowUpRating => {
+                config.follow_up_rating = value
+                    .parse()
+                    .ok()
+                    .filter(|rating| *rating <= 5)
+                    .ok_or_else(|| t("settings.config.invalid_rating").to_string())?;
+            }
+            ConfigField::Timezone => {
+                if datetime::parse_timezone(value).is_none() {
+                    return Err(tf(
+                        "settings.config.invalid_timezone",
+                        &[("timezone", &value)],
+                    ));
+                }
+                config.timezone = optional;
+            }
+            ConfigField::Weekend => {
+                config.weekend = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|day| !day.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            ConfigField::Genders => {
+                config.genders = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|gender| !gender.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            ConfigField::QuitKey
+            | ConfigField::PaletteKey
+            | ConfigField::HelpKey
+            | ConfigField::MacroRecordKey
+            | ConfigField::MacroPlayKey
+            | ConfigField::ScanKey
+            | ConfigField::VitalsKey => {
+                if parse_key(value).is_none() {
+                    return Err(tf("settings.config.invalid_key", &[("key", &value)]));
+                }
+                let key = value.to_string();
+                match self {
+                    ConfigField::QuitKey => config.keymap.quit = key,
+                    ConfigField::PaletteKey => config.keymap.palette = key,
+                    ConfigField::MacroRecordKey => config.keymap.macro_record = key,
+                    ConfigField::MacroPlayKey => config.keymap.macro_play = key,
+                    ConfigField::ScanKey => config.keymap.scan = key,
+                    ConfigField::VitalsKey => config.keymap.vitals = key,
+                    _ => config.keymap.help = key,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct Settings {
+    db: Db,
+    theme_state: ListState,
+    saved_theme: Theme,
+    language_state: ListState,
+    saved_locale: &'static str,
+    config: Config,
+    config_state: ListState,
+    editing: Option<TextInput>,
+    edit_error: Option<String>,
+    focus: usize,
+    backup: Option<Task<PathBuf>>,
+}
+
+impl Settings {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            theme_state: ListState::default(),
+            saved_theme: theme::selected_theme(),
+            language_state: ListState::default(),
+            saved_locale: i18n::locale(),
+            config: Config::clone(&config::config()),
+            config_state: ListState::default(),
+            editing: None,
+            edit_error: None,
+            focus: THEME_LIST,
+            backup: None,
+        }
+    }
+
+    pub fn load(&mut self) {
+        self.saved_theme = theme::selected_theme();
+        let index = THEMES.iter().position(|t| *t == self.saved_theme);
+        self.theme_state.select(index.or(Some(0)));
+        self.saved_locale = i18n::locale();
+        let index = i18n::locales()
+            .iter()
+            .position(|code| *code == self.saved_locale);
+        self.language_state.select(index.or(Some(0)));
+        self.config = Config::clone(&config::config());
+        self.config_state.select(Some(0));
+        self.editing = None;
+        self.edit_error = None;
+        self.focus = THEME_LIST;
+    }
+
+    fn selected_field(&self) -> ConfigField {
+        CONFIG_FIELDS[self.config_state.selected().unwrap_or(0)]
+    }
+
+    fn start_editing(&mut self) {
+        let mut input = TextInput::new();
+        input.set_value(self.selected_field().value(&self.config));
+        self.editing = Some(input);
+        self.edit_error = None;
+    }
+
+    /// Validates the edited value and writes the whole configuration file.
+    fn save_config_field(&mut self) {
+        let Some(input) = &self.editing else {
+            return;
+        };
+        let field = self.selected_field();
+        let mut updated = self.config.clone();
+        if let Err(e) = field.apply(&mut updated, input.value().trim()) {
+            self.edit_error = Some(e);
+            return;
+        }
+        match config::save(updated.clone()) {
+            Ok(()) => {
+                self.config = updated;
+                self.editing = None;
+                self.edit_error = None;
+                toast::push(Toast::success(if field == ConfigField::DbPath {
+                    t("settings.config.saved_restart").to_string()
+                } else {
+                    tf(
+                        "settings.config.saved",
+                        &[("path", &config::path().display())],
+                    )
+                }));
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "settings.config.save_failed",
+                &[("error", &format!("{:#}", e))],
+            ))),
+        }
+    }
+
+    fn preview(&mut self, forward: bool) {
+        if self.focus == LANGUAGE_LIST {
+            let locales = i18n::locales();
+            let i = step(self.language_state.selected(), locales.len(), forward);
+            self.language_state.select(Some(i));
+            i18n::set_locale(locales[i]);
+        } else {
+            let i = step(self.theme_state.selected(), THEMES.len(), forward);
+            self.theme_state.select(Some(i));
+            theme::set_theme(THEMES[i]);
+        }
+    }
+
+    fn save(&mut self) {
+        if self.focus == LANGUAGE_LIST {
+            let selected = i18n::locale();
+            match db::set_setting(&self.db, "locale", selected) {
+                Ok(()) => {
+                    self.saved_locale = selected;
+                    toast::push(Toast::success(tf(
+                        "settings.language_saved",
+                        &[("language", &i18n::locale_name(selected))],
+                    )));
+                }
+                Err(e) => toast::push(Toast::error(tf(
+                    "settings.language_save_failed",
+                    &[("error", &e)],
+                ))),
+            }
+            return;
+        }
+        let selected = theme::selected_theme();
+        match db::set_setting(&self.db, "theme", selected.name) {
+            Ok(()) => {
+                self.saved_theme = selected;
+                toast::push(Toast::success(tf(
+                    "settings.theme_saved",
+                    &[("theme", &selected.name)],
+                )));
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "settings.theme_save_failed",
+                &[("error", &e)],
+            ))),
+        }
+    }
+
+    fn toggle_accessibility(&mut self) {
+        let enabled = !accessibility::enabled();
+        accessibility::set_enabled(enabled);
+        let value = if enabled { "on" } else { "off" };
+        match db::set_setting(&self.db, "accessibility", value) {
+            Ok(()) => toast::push(Toast::success(if enabled {
+                t("settings.accessibility_on")
+            } else {
+                t("settings.accessibility_off")
+            })),
+            Err(e) => toast::push(Toast::error(tf(
+                "settings.accessibility_save_failed",
+                &[("error", &e)],
+            ))),
+        }
+    }
+
+    pub fn start_backup(&mut self) {
+        if self.backup.is_some() {
+            return;
+        }
+        let stamp = datetime::file_stamp(datetime::now());
+        let path = PathBuf::from(BACKUP_DIR).join(format!("rustoria_{}.db", stamp));
+        let db = self.db.clone();
+        self.backup = Some(worker::spawn_with_progress(move |progress| {
+            db::backup_database(&db, &path, progress)?;
+            Ok(path)
+        }));
+    }
+
+    pub fn tick(&mut self) {
+        let Some(backup) = self.backup.take() else {
+            return;
+        };
+        match backup.poll() {
+            None => self.backup = Some(backup),
+            Some(Ok(path)) => toast::push(Toast::success(tf(
+                "settings.backup_done",
+                &[("path", &path.display())],
+            ))),
+            Some(Err(_)) if backup.is_cancelled() => {
+                toast::push(Toast::error(t("settings.backup_cancelled")));
+            }
+            Some(Err(e)) => toast::push(Toast::error(format!("{:#}", e))),
+        }
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        if let Some(backup) = &self.backup {
+            if key.code == KeyCode::Esc {
+                backup.cancel();
+            }
+            return Ok(None);
+        }
+        if let Some(input) = &mut self.editing {
+            match key.code {
+                KeyCode::Enter => self.save_config_field(),
+                KeyCode::Esc => {
+                    self.editing = None;
+                    self.edit_error = None;
+                }
+                _ => {
+                    if input.handle_key(key) {
+                        self.edit_error = None;
+                    }
+                }
+            }
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Down | KeyCode::Up if self.focus == CONFIG_LIST => {
+                let forward = key.code == KeyCode::Down;
+                let i = step(self.config_state.selected(), CONFIG_FIELDS.len(), forward);
+                self.config_state.select(Some(i));
+            }
+            KeyCode::Down => self.preview(true),
+            KeyCode::Up => self.preview(false),
+            KeyCode::Tab | KeyCode::Right => self.focus = (self.focus + 1) % PANES,
+            KeyCode::BackTab | KeyCode::Left => self.focus = (self.focus + PANES - 1) % PANES,
+            KeyCode::Enter if self.focus == CONFIG_LIST => self.start_editing(),
+            KeyCode::Enter => self.save(),
+            KeyCode::Char('a') | KeyCode::Char('A') => self.toggle_accessibility(),
+            KeyCode::Char('b') | KeyCode::Char('B') => self.start_backup(),
+            KeyCode::Esc => {
+                theme::set_theme(self.saved_theme);
+                i18n::set_locale(self.saved_locale);
+                return Ok(Some(SelectedApp::None));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+impl Settings {
+    fn render_config(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = CONFIG_FIELDS
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let label = Span::styled(
+                    format!("{}: ", field.label()),
+                    Style::default().fg(theme().accent),
+                );
+                match &self.editing {
+                    Some(input) if self.config_state.selected() == Some(i) => {
+                        let mut line = input.line(true);
+                        line.spans.insert(0, label);
+                        ListItem::new(line)
+                    }
+                    _ => ListItem::new(Line::from(vec![
+                        label,
+                        Span::raw(field.value(&self.config)),
+                    ])),
+                }
+            })
+            .collect();
+
+        let focused = self.focus == CONFIG_LIST;
+        let footer = match &self.edit_error {
+            Some(error) => {
+                Line::styled(format!(" ⚠ {} ", error), Style::default().fg(theme().error))
+            }
+            None => Line::styled(
+                format!(" {} ", config::path().display()),
+                Style::default().fg(theme().inactive),
+            ),
+        };
+        let list = selection_list(items, t("settings.config"), focused)
+            .block(pane_block(t("settings.config"), focused).title_bottom(footer));
+        frame.render_stateful_widget(list, area, &mut self.config_state.clone());
+    }
+}
+
+fn pane_block<'a>(title: &str, focused: bool) -> Block<'a> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(format!(" {} ", title))
+        .border_style(Style::default().fg(if focused {
+            theme().focus
+        } else {
+            theme().unfocused
+        }))
+        .style(Style::default().bg(theme().surface))
+}
+
+fn selection_list<'a>(items: Vec<ListItem<'a>>, title: &str, focused: bool) -> List<'a> {
+    List::new(items)
+        .style(Style::default().fg(theme().text))
+        .block(pane_block(title, focused))
+        .highlight_style(
+            Style::default()
+                .bg(theme().highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("► ")
+}
+
+fn step(selected: Option<usize>, len: usize, forward: bool) -> usize {
+    match selected {
+        Some(i) if forward => (i + 1) % len,
+        Some(i) => (i + len - 1) % len,
+        None => 0,
+    }
+}
+
+impl Component for Settings {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        self.handle_input(event)
+    }
+
+    fn tick(&mut self) {
+        self.tick();
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(8),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(format!("⚙️ {}", t("settings.title")))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(30), Constraint::Min(30)])
+            .split(layout[1]);
+
+        let lists = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(THEMES.len() as u16 + 2),
+                Constraint::Min(3),
+            ])
+            .split(body[0]);
+
+        let themes: Vec<ListItem> = THEMES
+            .iter()
+            .map(|option| {
+                let marker = if *option == self.saved_theme {
+                    " ✓"
+                } else {
+                    ""
+                };
+                ListItem::new(format!("{}{}", option.name, marker))
+            })
+            .collect();
+        frame.render_stateful_widget(
+            selection_list(themes, t("settings.theme"), self.focus == THEME_LIST),
+            lists[0],
+            &mut self.theme_state.clone(),
+        );
+
+        let languages: Vec<ListItem> = i18n::locales()
+            .into_iter()
+            .map(|code| {
+                let marker = if code == self.saved_locale {
+                    " ✓"
+                } else {
+                    ""
+                };
+                ListItem::new(format!("{}{}", i18n::locale_name(code), marker))
+            })
+            .collect();
+        frame.render_stateful_widget(
+            selection_list(
+                languages,
+                t("settings.language"),
+                self.focus == LANGUAGE_LIST,
+            ),
+            lists[1],
+            &mut self.language_state.clone(),
+        );
+
+        let current = theme();
+        let swatches = [
+            (t("settings.swatch.text"), current.text),
+            (t("settings.swatch.title"), current.title),
+            (t("settings.swatch.accent"), current.accent),
+            (t("settings.swatch.focus"), current.focus),
+            (t("settings.swatch.success"), current.success),
+            (t("settings.swatch.warning"), current.warning),
+            (t("settings.swatch.error"), current.error),
+            (t("settings.swatch.help"), current.help),
+        ];
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(swatches.len() as u16 + 2),
+                Constraint::Min(CONFIG_FIELDS.len() as u16 + 2),
+            ])
+            .split(body[1]);
+        let lines: Vec<Line> = swatches
+            .iter()
+            .map(|(label, color)| {
+                Line::from(vec![
+                    Span::styled("  ████  ", Style::default().fg(*color)),
+                    Span::styled(*label, Style::default().fg(*color)),
+                ])
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} ", t("settings.preview")))
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface)),
+            ),
+            right[0],
+        );
+        self.render_config(frame, right[1]);
+
+        let status = if accessibility::enabled() {
+            t("settings.accessibility_status_on")
+        } else {
+            t("settings.accessibility_status_off")
+        };
+        frame.render_widget(
+            Paragraph::new(status)
+                .style(Style::default().fg(theme().text))
+                .alignment(Alignment::Center),
+            layout[2],
+        );
+
+        frame.render_widget(
+            Paragraph::new(t("settings.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+
+        if let Some(backup) = &self.backup {
+            let label = if backup.is_cancelled() {
+                t("settings.backup_cancelling")
+            } else {
+                t("settings.backup_running")
+            };
+            render_progress(frame, backup, label, !backup.is_cancelled());
+        }
+    }
+}
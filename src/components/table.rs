@@ -0,0 +1,144 @@
+use crate::i18n;
+use crate::ui_state;
+use crate::utils::{pad_left, truncate};
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::text::Span;
+use ratatui::widgets::Cell;
+
+/// Column widths a [`ratatui::widgets::Table`] will use when rendered into
+/// `area` (the area inside any border) with the given column `spacing` and
+/// the space reserved for `highlight_symbol`.
+pub fn column_widths(
+    area: Rect,
+    widths: &[Constraint],
+    spacing: u16,
+    highlight_symbol: &str,
+) -> Vec<usize> {
+    let selection = Span::raw(highlight_symbol).width() as u16;
+    let [_, columns] = Layout::horizontal([Constraint::Length(selection), Constraint::Fill(0)])
+        .areas(Rect::new(0, 0, area.width, 1));
+    Layout::horizontal(widths.iter().copied())
+        .flex(Flex::Start)
+        .spacing(spacing)
+        .split(columns)
+        .iter()
+        .map(|column| column.width as usize)
+        .collect()
+}
+
+/// A cell holding `text` cut to the width of column `index`, with an ellipsis
+/// when it does not fit.
+pub fn cell<'a>(text: impl AsRef<str>, columns: &[usize], index: usize) -> Cell<'a> {
+    let width = columns.get(index).copied().unwrap_or(usize::MAX);
+    Cell::from(truncate(text.as_ref(), width))
+}
+
+/// Like [`cell`], but right-aligned for amounts and counts.
+pub fn numeric_cell<'a>(text: impl AsRef<str>, columns: &[usize], index: usize) -> Cell<'a> {
+    match columns.get(index) {
+        Some(&width) => Cell::from(pad_left(text.as_ref(), width)),
+        None => Cell::from(text.as_ref().to_string()),
+    }
+}
+
+/// The columns of a list and which of them the user shows, remembered per
+/// user. On narrow terminals only the shown columns among `minimal` are kept.
+pub struct Columns {
+    list: &'static str,
+    headers: &'static [&'static str],
+    shown: Vec<bool>,
+    minimal: &'static [usize],
+}
+
+impl Columns {
+    /// The columns of `list` as the user left them, or all but `hidden` when
+    /// they never chose.
+    pub fn new(
+        list: &'static str,
+        headers: &'static [&'static str],
+        hidden: &[usize],
+        minimal: &'static [usize],
+    ) -> Self {
+        let shown = match ui_state::shown_columns(list) {
+            Some(names) => headers
+                .iter()
+                .map(|h| names.iter().any(|n| n == h || n == i18n::english(h)))
+                .collect(),
+            None => (0..headers.len()).map(|i| !hidden.contains(&i)).collect(),
+        };
+        Self {
+            list,
+            headers,
+            shown,
+            minimal,
+        }
+    }
+
+    pub fn headers(&self) -> &'static [&'static str] {
+        self.headers
+    }
+
+    pub fn is_shown(&self, index: usize) -> bool {
+        self.shown.get(index).copied().unwrap_or(false)
+    }
+
+    /// Shows or hides column `index`; the last shown column stays.
+    pub fn toggle(&mut self, index: usize) {
+        if self.is_shown(index) && self.shown.iter().filter(|s| **s).count() == 1 {
+            return;
+        }
+        if let Some(shown) = self.shown.get_mut(index) {
+            *shown = !*shown;
+        }
+        let names = self
+            .headers
+            .iter()
+            .zip(&self.shown)
+            .filter(|(_, shown)| **shown)
+            .map(|(h, _)| h.to_string())
+            .collect();
+        ui_state::set_shown_columns(self.list, names);
+    }
+
+    /// Indexes of the columns to draw, in order.
+    pub fn visible(&self, narrow: bool) -> Vec<usize> {
+        let shown: Vec<usize> = (0..self.headers.len())
+            .filter(|&i| self.is_shown(i))
+            .collect();
+        if !narrow {
+            return shown;
+        }
+        let minimal: Vec<usize> = shown
+            .iter()
+            .copied()
+            .filter(|i| self.minimal.contains(i))
+            .collect();
+        if minimal.is_empty() {
+            shown
+        } else {
+            minimal
+        }
+    }
+
+    /// The constraints of the `visible` columns out of `widths`, one per
+    /// header. Percentages become shares of the width left, so the visible
+    /// columns still fill the table.
+    pub fn widths(widths: &[Constraint], visible: &[usize]) -> Vec<Constraint> {
+        visible
+            .iter()
+            .filter_map(|&i| widths.get(i))
+            .map(|width| match *width {
+                Constraint::Percentage(p) => Constraint::Fill(p),
+                other => other,
+            })
+            .collect()
+    }
+
+    /// The cells of `values` in the `visible` columns.
+    pub fn pick<T: Clone>(values: &[T], visible: &[usize]) -> Vec<T> {
+        visible
+            .iter()
+            .filter_map(|&i| values.get(i).cloned())
+            .collect()
+    }
+}
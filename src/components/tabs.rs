@@ -0,0 +1,76 @@
+use crate::app::SelectedApp;
+use crate::i18n::t;
+use crate::theme::theme;
+use crate::tui::Frame;
+use ratatui::{prelude::*, widgets::*};
+
+pub const MODULE_TABS: [(&str, SelectedApp); 5] = [
+    ("tabs.patients", SelectedApp::PatientList),
+    ("tabs.staff", SelectedApp::StaffList),
+    ("tabs.records", SelectedApp::RecordRetrieve),
+    ("tabs.finance", SelectedApp::BillingView),
+    ("tabs.reports", SelectedApp::RecordStatistics),
+];
+
+pub fn module_of(selected_app: SelectedApp) -> Option<usize> {
+    match selected_app {
+        SelectedApp::PatientAdd
+        | SelectedApp::PatientList
+        | SelectedApp::PatientDelete
+        | SelectedApp::PatientCompliance
+        | SelectedApp::PatientImport
+        | SelectedApp::PatientUpdate => Some(0),
+        SelectedApp::StaffAdd
+        | SelectedApp::StaffAssign
+        | SelectedApp::StaffList
+        | SelectedApp::StaffDelete
+        | SelectedApp::StaffUpdate => Some(1),
+        SelectedApp::RecordStore
+        | SelectedApp::RecordRetrieve
+        | SelectedApp::RecordUpdate
+        | SelectedApp::RecordDelete
+        | SelectedApp::RecordCampaign => Some(2),
+        SelectedApp::BillingInvoice
+        | SelectedApp::BillingView
+        | SelectedApp::BillingUpdate
+        | SelectedApp::BillingExpenses
+        | SelectedApp::BillingBudget
+        | SelectedApp::BillingCashier
+        | SelectedApp::BillingInsurance
+        | SelectedApp::BillingApprovals => Some(3),
+        SelectedApp::RecordStatistics
+        | SelectedApp::RecordExpirations
+        | SelectedApp::RecordIsolations
+        | SelectedApp::RecordStays
+        | SelectedApp::RecordIntegrity
+        | SelectedApp::RecordWaiting
+        | SelectedApp::RecordForecast
+        | SelectedApp::RecordSatisfaction
+        | SelectedApp::BillingExpenseReport
+        | SelectedApp::BillingCashierHistory
+        | SelectedApp::StaffPerformance
+        | SelectedApp::TeleconsultUtilization => Some(4),
+        _ => None,
+    }
+}
+
+pub fn render_tab_bar(frame: &mut Frame, active: Option<usize>) {
+    let area = frame.area();
+    let bar = Rect::new(area.x, area.y, area.width, 1.min(area.height));
+    let titles: Vec<Line> = MODULE_TABS
+        .iter()
+        .enumerate()
+        .map(|(i, (name, _))| Line::from(format!("Alt+{} {}", i + 1, t(name))))
+        .collect();
+    let tabs = Tabs::new(titles)
+        .select(active)
+        .style(Style::default().fg(theme().inactive).bg(theme().background))
+        .highlight_style(
+            Style::default()
+                .fg(theme().focus)
+                .add_modifier(Modifier::BOLD),
+        )
+        .divider(Span::styled(" | ", Style::default().fg(theme().border)));
+    frame.render_widget(Clear, bar);
+    frame.render_widget(tabs, bar);
+}
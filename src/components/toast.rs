@@ -0,0 +1,115 @@
+use crate::theme::theme;
+use crate::tui::Frame;
+use ratatui::{prelude::*, widgets::*};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+const MAX_TOASTS: usize = 4;
+const TOAST_WIDTH: u16 = 56;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastKind {
+    Success,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub kind: ToastKind,
+    pub message: String,
+    created: Instant,
+}
+
+impl Toast {
+    pub fn success(message: impl Into<String>) -> Self {
+        Self::new(ToastKind::Success, message)
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(ToastKind::Error, message)
+    }
+
+    fn new(kind: ToastKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            created: Instant::now(),
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.created.elapsed() >= TOAST_DURATION
+    }
+}
+
+static QUEUE: Mutex<VecDeque<Toast>> = Mutex::new(VecDeque::new());
+
+fn queue() -> std::sync::MutexGuard<'static, VecDeque<Toast>> {
+    QUEUE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Queues a toast. Repeating the newest toast restarts its timer instead of stacking a copy.
+pub fn push(toast: Toast) {
+    let mut queue = queue();
+    if let Some(last) = queue.back_mut() {
+        if last.kind == toast.kind && last.message == toast.message {
+            last.created = toast.created;
+            return;
+        }
+    }
+    queue.push_back(toast);
+    while queue.len() > MAX_TOASTS {
+        queue.pop_front();
+    }
+}
+
+pub fn expire() {
+    queue().retain(|toast| !toast.is_expired());
+}
+
+pub fn clear() {
+    queue().clear();
+}
+
+/// Draws the live toasts stacked in the top-right corner, newest at the bottom.
+pub fn render_toasts(frame: &mut Frame) {
+    let toasts: Vec<Toast> = queue()
+        .iter()
+        .filter(|toast| !toast.is_expired())
+        .cloned()
+        .collect();
+    let area = frame.area();
+    let width = TOAST_WIDTH.min(area.width);
+    let mut y = area.y + 1;
+
+    for toast in toasts {
+        let (icon, color) = match toast.kind {
+            ToastKind::Success => ("✓", theme().success),
+            ToastKind::Error => ("⚠️", theme().error),
+        };
+        let text = format!("{} {}", icon, toast.message);
+        let lines = (text.chars().count() as u16).div_ceil(width.saturating_sub(4).max(1));
+        let height = lines.clamp(1, 3) + 2;
+        if y + height > area.bottom() {
+            break;
+        }
+        let toast_area = Rect::new(area.right().saturating_sub(width + 1), y, width, height);
+        frame.render_widget(Clear, toast_area);
+        frame.render_widget(
+            Paragraph::new(text)
+                .style(Style::default().fg(color).add_modifier(Modifier::BOLD))
+                .wrap(Wrap { trim: true })
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(color))
+                        .style(Style::default().bg(theme().dialog)),
+                ),
+            toast_area,
+        );
+        y += height;
+    }
+}
@@ -0,0 +1,55 @@
+pub struct EditHistory<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+}
+
+impl<T> EditHistory<T> {
+    pub fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, before: T) {
+        self.undo.push(before);
+        self.redo.clear();
+    }
+
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(next)
+    }
+
+    pub fn has_changes(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn discard(&mut self) -> Option<T> {
+        self.redo.clear();
+        let mut changes = std::mem::take(&mut self.undo);
+        if changes.is_empty() {
+            None
+        } else {
+            Some(changes.swap_remove(0))
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+impl<T> Default for EditHistory<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
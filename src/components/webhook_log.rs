@@ -0,0 +1,330 @@
+use crate::app::SelectedApp;
+use crate::components::table::{cell, column_widths};
+use crate::components::toast::{self, Toast};
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{DeliveryStatus, WebhookDelivery};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{config, datetime};
+use crate::webhooks::{self, MAX_ATTEMPTS};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::time::{Duration, Instant};
+
+/// Deliveries listed, newest first.
+const LOG_LIMIT: usize = 500;
+/// How often the list reloads while open, to show retries as they happen.
+const REFRESH_EVERY: Duration = Duration::from_secs(3);
+
+/// Every webhook delivery with its status, attempts and last error; failed
+/// deliveries can be queued again from here.
+pub struct WebhookLog {
+    db: Db,
+    deliveries: Vec<WebhookDelivery>,
+    state: TableState,
+    show_payload: bool,
+    loaded_at: Option<Instant>,
+}
+
+impl WebhookLog {
+    pub fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            deliveries: Vec::new(),
+            state: TableState::default(),
+            show_payload: false,
+            loaded_at: None,
+        }
+    }
+
+    pub fn load(&mut self) {
+        match db::get_webhook_deliveries(&self.db, LOG_LIMIT) {
+            Ok(deliveries) => self.deliveries = deliveries,
+            Err(e) => toast::push(Toast::error(format!("{:#}", e))),
+        }
+        self.loaded_at = Some(Instant::now());
+        if self.deliveries.is_empty() {
+            self.state.select(None);
+            self.show_payload = false;
+        } else {
+            let selection = self
+                .state
+                .selected()
+                .unwrap_or(0)
+                .min(self.deliveries.len() - 1);
+            self.state.select(Some(selection));
+        }
+    }
+
+    fn selected(&self) -> Option<&WebhookDelivery> {
+        self.state.selected().and_then(|i| self.deliveries.get(i))
+    }
+
+    fn retry_selected(&mut self) {
+        let Some(delivery) = self.selected() else {
+            return;
+        };
+        if delivery.status != DeliveryStatus::Failed {
+            toast::push(Toast::error(t("webhooks.retry_only_failed")));
+            return;
+        }
+        match db::requeue_webhook_delivery(&self.db, delivery.id, &datetime::now_text()) {
+            Ok(()) => {
+                toast::push(Toast::success(t("webhooks.requeued")));
+                webhooks::deliver_due(&self.db);
+                self.load();
+            }
+            Err(e) => toast::push(Toast::error(format!("{:#}", e))),
+        }
+    }
+
+    fn status_text(delivery: &WebhookDelivery) -> (String, Color) {
+        match delivery.status {
+            DeliveryStatus::Delivered => {
+                (t("webhooks.status.delivered").to_string(), theme().success)
+            }
+            DeliveryStatus::Failed => (t("webhooks.status.failed").to_string(), theme().error),
+            DeliveryStatus::Pending if delivery.attempts == 0 => {
+                (t("webhooks.status.queued").to_string(), theme().text)
+            }
+            DeliveryStatus::Pending => (
+                tf(
+                    "webhooks.status.retrying",
+                    &[("time", &delivery.next_attempt_at)],
+                ),
+                theme().warning,
+            ),
+        }
+    }
+
+    fn render_table(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(format!(
+                " {} ",
+                tf("webhooks.deliveries", &[("count", &self.deliveries.len())])
+            ))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().surface));
+
+        if self.deliveries.is_empty() {
+            let text = if config::config().webhooks.is_empty() {
+                t("webhooks.none_configured")
+            } else {
+                t("webhooks.empty")
+            };
+            frame.render_widget(
+                Paragraph::new(text)
+                    .style(Style::default().fg(theme().text))
+                    .alignment(Alignment::Center)
+                    .wrap(Wrap { trim: true })
+                    .block(block),
+                area,
+            );
+            return;
+        }
+
+        let header = Row::new(
+            [
+                t("webhooks.column.id"),
+                t("webhooks.column.created"),
+                t("webhooks.column.event"),
+                t("webhooks.column.url"),
+                t("webhooks.column.status"),
+                t("webhooks.column.attempts"),
+                t("webhooks.column.error"),
+            ]
+            .into_iter()
+            .map(|h| Cell::from(h).style(Style::default().fg(theme().title))),
+        )
+        .style(Style::default().bg(theme().header))
+        .height(1);
+
+        let widths = [
+            Constraint::Length(6),
+            Constraint::Length(19),
+            Constraint::Length(18),
+            Constraint::Percentage(30),
+            Constraint::Length(24),
+            Constraint::Length(8),
+            Constraint::Percentage(30),
+        ];
+        let columns = column_widths(block.inner(area), &widths, 1, "► ");
+
+        let rows = self.deliveries.iter().map(|delivery| {
+            let (status, color) = Self::status_text(delivery);
+            Row::new(vec![
+                cell(delivery.id.to_string(), &columns, 0),
+                cell(datetime::display_stamp(&delivery.created_at), &columns, 1),
+                cell(&delivery.event, &columns, 2),
+                cell(&delivery.url, &columns, 3),
+                cell(status, &columns, 4).style(Style::default().fg(color)),
+                cell(
+                    format!("{}/{}", delivery.attempts, MAX_ATTEMPTS),
+                    &columns,
+                    5,
+                ),
+                cell(
+                    delivery.last_error.as_deref().unwrap_or_default(),
+                    &columns,
+                    6,
+                ),
+            ])
+            .style(Style::default().fg(theme().text))
+        });
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, area, &mut self.state.clone());
+    }
+
+    fn render_payload(&self, frame: &mut Frame, area: Rect, delivery: &WebhookDelivery) {
+        let payload = serde_json::from_str::<serde_json::Value>(&delivery.payload)
+            .and_then(|value| serde_json::to_string_pretty(&value))
+            .unwrap_or_else(|_| delivery.payload.clone());
+        let mut lines = vec![Line::from(vec![
+            Span::styled(
+                format!("{}: ", t("webhooks.column.url")),
+                Style::default().fg(theme().title),
+            ),
+            Span::raw(delivery.url.clone()),
+        ])];
+        if let Some(delivered_at) = &delivery.delivered_at {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{}: ", t("webhooks.delivered_at")),
+                    Style::default().fg(theme().title),
+                ),
+                Span::raw(delivered_at.clone()),
+            ]));
+        }
+        if let Some(error) = &delivery.last_error {
+            lines.push(Line::styled(
+                error.clone(),
+                Style::default().fg(theme().error),
+            ));
+        }
+        lines.push(Line::raw(""));
+        lines.extend(payload.lines().map(|line| Line::raw(line.to_string())));
+        frame.render_widget(
+            Paragraph::new(lines)
+                .style(Style::default().fg(theme().text))
+                .wrap(Wrap { trim: false })
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", t("webhooks.payload")))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(theme().border))
+                        .style(Style::default().bg(theme().surface)),
+                ),
+            area,
+        );
+    }
+}
+
+impl Component for WebhookLog {
+    fn handle_input(&mut self, key: KeyEvent) -> Result<Option<SelectedApp>> {
+        match key.code {
+            KeyCode::Down if !self.deliveries.is_empty() => {
+                let i = self
+                    .state
+                    .selected()
+                    .map_or(0, |i| (i + 1) % self.deliveries.len());
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if !self.deliveries.is_empty() => {
+                let len = self.deliveries.len();
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Enter if self.selected().is_some() => {
+                self.show_payload = !self.show_payload;
+            }
+            KeyCode::Char('t') | KeyCode::Char('T') => self.retry_selected(),
+            KeyCode::Char('r') | KeyCode::Char('R') => self.load(),
+            KeyCode::Esc if self.show_payload => self.show_payload = false,
+            KeyCode::Esc | KeyCode::Char('b') | KeyCode::Char('B') => {
+                return Ok(Some(SelectedApp::None));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn tick(&mut self) {
+        if self
+            .loaded_at
+            .is_none_or(|at| at.elapsed() >= REFRESH_EVERY)
+        {
+            self.load();
+        }
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(8),
+                Constraint::Length(1),
+            ])
+            .margin(1)
+            .split(area);
+
+        let header = Block::default()
+            .borders(Borders::BOTTOM)
+            .border_style(Style::default().fg(theme().border))
+            .style(Style::default().bg(theme().background));
+        frame.render_widget(header, layout[0]);
+        frame.render_widget(
+            Paragraph::new(format!("📡 {}", t("webhooks.title")))
+                .style(
+                    Style::default()
+                        .fg(theme().title)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme().background),
+                )
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        match self.selected().filter(|_| self.show_payload) {
+            Some(delivery) => {
+                let body = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                    .split(layout[1]);
+                self.render_table(frame, body[0]);
+                self.render_payload(frame, body[1], delivery);
+            }
+            None => self.render_table(frame, layout[1]),
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("webhooks.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[2],
+        );
+    }
+}
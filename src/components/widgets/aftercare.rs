@@ -0,0 +1,174 @@
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::print_dialog::PrintDialog;
+use crate::db::Db;
+use crate::i18n::{t, tf};
+use crate::models::Patient;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::aftercare::{self, Template, Visit};
+use crate::utils::{datetime, write_export};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+/// Picks an aftercare template for a patient and shows it filled in; `p`
+/// prints the handout and `e` saves it to the exports directory.
+pub struct AftercarePopup {
+    db: Db,
+    patient: Patient,
+    visit: Visit,
+    templates: Vec<Template>,
+    state: ListState,
+    print_dialog: Option<PrintDialog>,
+}
+
+impl AftercarePopup {
+    pub fn new(db: &Db, patient: Patient, visit: Visit) -> Self {
+        let templates = aftercare::templates();
+        Self {
+            db: db.clone(),
+            patient,
+            visit,
+            state: ListState::default().with_selected((!templates.is_empty()).then_some(0)),
+            templates,
+            print_dialog: None,
+        }
+    }
+
+    fn handout(&self) -> Option<(&Template, String)> {
+        let template = self.templates.get(self.state.selected()?)?;
+        Some((
+            template,
+            aftercare::handout(template, &self.patient, &self.visit),
+        ))
+    }
+
+    fn export(&self) {
+        let Some((template, handout)) = self.handout() else {
+            return;
+        };
+        let file_name = format!(
+            "aftercare_{}_{}_{}.txt",
+            self.patient.id,
+            template.name.to_lowercase().replace(' ', "_"),
+            datetime::file_stamp(datetime::now())
+        );
+        match write_export(&file_name, &handout) {
+            Ok(path) => toast::push(Toast::success(tf(
+                "aftercare.exported",
+                &[("path", &path.display())],
+            ))),
+            Err(e) => toast::push(Toast::error(format!("{:#}", e))),
+        }
+    }
+
+    /// Returns true once the popup is closed.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if let Some(dialog) = self.print_dialog.as_mut() {
+            if dialog.handle_key(key) {
+                self.print_dialog = None;
+            }
+            return false;
+        }
+        let len = self.templates.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Char('p') | KeyCode::Char('P') => {
+                if let Some((template, handout)) = self.handout() {
+                    self.print_dialog =
+                        Some(PrintDialog::new(&self.db, template.name.clone(), handout));
+                }
+            }
+            KeyCode::Char('e') | KeyCode::Char('E') => self.export(),
+            KeyCode::Esc | KeyCode::Char('q') => return true,
+            _ => {}
+        }
+        false
+    }
+
+    pub fn render_popup(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let popup = Rect::new(
+            area.x + area.width / 20,
+            area.y + area.height / 10,
+            area.width - area.width / 10,
+            area.height - area.height / 5,
+        );
+        frame.render_widget(Clear, popup);
+        let block = Block::default()
+            .title(format!(
+                " {} - {} {} ",
+                t("aftercare.title"),
+                self.patient.first_name,
+                self.patient.last_name
+            ))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(popup);
+        frame.render_widget(block, popup);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(1)])
+            .horizontal_margin(1)
+            .split(inner);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(26), Constraint::Min(30)])
+            .split(layout[0]);
+
+        let items: Vec<ListItem> = self
+            .templates
+            .iter()
+            .map(|template| ListItem::new(template.name.as_str()))
+            .collect();
+        frame.render_stateful_widget(
+            List::new(items)
+                .style(Style::default().fg(theme().text))
+                .highlight_style(
+                    Style::default()
+                        .fg(theme().focus)
+                        .bg(theme().highlight)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol("► ")
+                .block(
+                    Block::default()
+                        .borders(Borders::RIGHT)
+                        .border_style(Style::default().fg(theme().border)),
+                ),
+            columns[0],
+            &mut self.state.clone(),
+        );
+
+        let preview = match self.handout() {
+            Some((_, handout)) => Paragraph::new(handout).style(Style::default().fg(theme().text)),
+            None => Paragraph::new(t("aftercare.no_templates"))
+                .style(Style::default().fg(theme().inactive)),
+        };
+        frame.render_widget(
+            preview.wrap(Wrap { trim: false }),
+            columns[1].inner(Margin::new(1, 0)),
+        );
+
+        frame.render_widget(
+            Paragraph::new(t("aftercare.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[1],
+        );
+
+        if let Some(dialog) = &self.print_dialog {
+            dialog.render_popup(frame);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::components::table::Columns;
+use crate::i18n::t;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+
+/// Whether `key` opens the column picker of a list screen.
+pub fn is_open_key(key: KeyEvent) -> bool {
+    key.code == KeyCode::Char('o') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// A popup ticking the columns a list shows. Changes apply as they are made.
+pub struct ColumnPicker {
+    list_state: ListState,
+}
+
+impl ColumnPicker {
+    pub fn new() -> Self {
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+        Self { list_state }
+    }
+
+    /// Handles `key` on `columns`; returns whether the picker is closed.
+    pub fn handle_key(&mut self, key: KeyEvent, columns: &mut Columns) -> bool {
+        let count = columns.headers().len();
+        let selected = self.list_state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter => return true,
+            KeyCode::Char(' ') => columns.toggle(selected),
+            KeyCode::Down => self.list_state.select(Some((selected + 1) % count)),
+            KeyCode::Up => self.list_state.select(Some((selected + count - 1) % count)),
+            _ => {}
+        }
+        false
+    }
+
+    pub fn render_popup(&self, frame: &mut Frame, columns: &Columns) {
+        let area = frame.area();
+        let width = 40.min(area.width);
+        let height = (columns.headers().len() as u16 + 4).min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" {} ", t("columns.title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .horizontal_margin(1)
+            .split(inner);
+        let items: Vec<ListItem> = columns
+            .headers()
+            .iter()
+            .enumerate()
+            .map(|(index, header)| {
+                let (mark, color) = if columns.is_shown(index) {
+                    ("[x]", theme().text)
+                } else {
+                    ("[ ]", theme().inactive)
+                };
+                ListItem::new(format!("{} {}", mark, t(header))).style(Style::default().fg(color))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(theme().highlight))
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(list, layout[0], &mut self.list_state.clone());
+        frame.render_widget(
+            Paragraph::new(t("columns.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[1],
+        );
+    }
+}
+
+impl Default for ColumnPicker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,283 @@
+use crate::components::table::{cell, column_widths};
+use crate::i18n::t;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+pub enum CompareAction {
+    /// The user accepted the picked values.
+    Confirm,
+    Cancel,
+}
+
+struct CompareRow {
+    label: String,
+    left: String,
+    right: String,
+    pick: Side,
+}
+
+impl CompareRow {
+    fn differs(&self) -> bool {
+        self.left != self.right
+    }
+}
+
+/// Two versions of a record side by side, one row per field, with the
+/// fields that differ highlighted. When picking, `←`/`→` choose which value
+/// of each field survives.
+pub struct ComparePane {
+    title: String,
+    left_title: String,
+    right_title: String,
+    rows: Vec<CompareRow>,
+    state: TableState,
+    picking: bool,
+    only_differences: bool,
+    /// Asked before confirming, for changes that cannot be undone.
+    question: Option<String>,
+    asking: bool,
+}
+
+impl ComparePane {
+    /// `fields` holds each field's label, left value and right value. Every
+    /// field starts out picking `default`.
+    pub fn new(
+        title: impl Into<String>,
+        left_title: impl Into<String>,
+        right_title: impl Into<String>,
+        fields: Vec<(String, String, String)>,
+        default: Side,
+    ) -> Self {
+        let rows: Vec<CompareRow> = fields
+            .into_iter()
+            .map(|(label, left, right)| CompareRow {
+                label,
+                left,
+                right,
+                pick: default,
+            })
+            .collect();
+        let selected = rows.iter().position(CompareRow::differs).unwrap_or(0);
+        Self {
+            title: title.into(),
+            left_title: left_title.into(),
+            right_title: right_title.into(),
+            state: TableState::default().with_selected((!rows.is_empty()).then_some(selected)),
+            rows,
+            picking: false,
+            only_differences: false,
+            question: None,
+            asking: false,
+        }
+    }
+
+    /// Lets the user pick a value per field and accept them with Enter.
+    pub fn picking(mut self) -> Self {
+        self.picking = true;
+        self
+    }
+
+    /// Asks `question` before the picked values are accepted.
+    pub fn confirm_with(mut self, question: impl Into<String>) -> Self {
+        self.question = Some(question.into());
+        self
+    }
+
+    /// The side picked for each field, in the order they were given.
+    pub fn picks(&self) -> Vec<Side> {
+        self.rows.iter().map(|row| row.pick).collect()
+    }
+
+    fn visible(&self) -> Vec<usize> {
+        (0..self.rows.len())
+            .filter(|&i| !self.only_differences || self.rows[i].differs())
+            .collect()
+    }
+
+    fn selected_row(&self) -> Option<usize> {
+        self.visible().get(self.state.selected()?).copied()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<CompareAction> {
+        if self.asking {
+            self.asking = false;
+            return match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => Some(CompareAction::Confirm),
+                _ => None,
+            };
+        }
+        let len = self.visible().len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Left | KeyCode::Right if self.picking => {
+                if let Some(i) = self.selected_row() {
+                    self.rows[i].pick = if key.code == KeyCode::Left {
+                        Side::Left
+                    } else {
+                        Side::Right
+                    };
+                }
+            }
+            KeyCode::Char('[') | KeyCode::Char(']') if self.picking => {
+                let side = if key.code == KeyCode::Char('[') {
+                    Side::Left
+                } else {
+                    Side::Right
+                };
+                for row in &mut self.rows {
+                    row.pick = side;
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                self.only_differences = !self.only_differences;
+                let len = self.visible().len();
+                self.state.select((len > 0).then_some(0));
+            }
+            KeyCode::Enter if self.picking => {
+                if self.question.is_some() {
+                    self.asking = true;
+                } else {
+                    return Some(CompareAction::Confirm);
+                }
+            }
+            KeyCode::Esc => return Some(CompareAction::Cancel),
+            _ => {}
+        }
+        None
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let popup = Rect::new(
+            area.x + area.width / 20,
+            area.y + area.height / 10,
+            area.width - area.width / 10,
+            area.height - area.height / 5,
+        );
+        frame.render_widget(Clear, popup);
+        let block = Block::default()
+            .title(format!(" {} ", self.title))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(popup);
+        frame.render_widget(block, popup);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(5),
+                Constraint::Length(6),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        let widths = [
+            Constraint::Percentage(20),
+            Constraint::Percentage(40),
+            Constraint::Percentage(40),
+        ];
+        let columns = column_widths(layout[0], &widths, 1, "► ");
+        let value_cell = |row: &CompareRow, side: Side, column: usize| {
+            let (value, marker) = match side {
+                Side::Left => (&row.left, "◀ "),
+                Side::Right => (&row.right, "▶ "),
+            };
+            let picked = self.picking && row.pick == side;
+            let text = if picked {
+                format!("{}{}", marker, value)
+            } else {
+                value.clone()
+            };
+            let style = if picked && row.differs() {
+                Style::default()
+                    .fg(theme().success)
+                    .add_modifier(Modifier::BOLD)
+            } else if row.differs() {
+                Style::default().fg(theme().warning)
+            } else {
+                Style::default().fg(theme().text)
+            };
+            cell(text, &columns, column).style(style)
+        };
+        let rows: Vec<Row> = self
+            .visible()
+            .into_iter()
+            .map(|i| {
+                let row = &self.rows[i];
+                Row::new(vec![
+                    cell(row.label.as_str(), &columns, 0).style(Style::default().fg(theme().title)),
+                    value_cell(row, Side::Left, 1),
+                    value_cell(row, Side::Right, 2),
+                ])
+            })
+            .collect();
+        let differences = self.rows.iter().filter(|row| row.differs()).count();
+        let header = Row::new(vec![
+            format!("{} ({})", t("compare.field"), differences),
+            self.left_title.clone(),
+            self.right_title.clone(),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .row_highlight_style(Style::default().bg(theme().highlight))
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(table, layout[0], &mut self.state.clone());
+
+        // The whole of both values, for fields too long for their cell.
+        if let Some(row) = self.selected_row().map(|i| &self.rows[i]) {
+            let halves = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(layout[1]);
+            for (value, title, area) in [
+                (&row.left, &self.left_title, halves[0]),
+                (&row.right, &self.right_title, halves[1]),
+            ] {
+                frame.render_widget(
+                    Paragraph::new(value.as_str())
+                        .style(Style::default().fg(theme().text))
+                        .wrap(Wrap { trim: false })
+                        .block(
+                            Block::default()
+                                .borders(Borders::TOP)
+                                .title(format!(" {} - {} ", row.label, title))
+                                .border_style(Style::default().fg(theme().border)),
+                        ),
+                    area,
+                );
+            }
+        }
+
+        let (help, color) = match &self.question {
+            Some(question) if self.asking => (format!("{} (y/n)", question), theme().warning),
+            _ if self.picking => (t("compare.help_pick").to_string(), theme().help),
+            _ => (t("compare.help").to_string(), theme().help),
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(color))
+                .alignment(Alignment::Center),
+            layout[2],
+        );
+    }
+}
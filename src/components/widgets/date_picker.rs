@@ -0,0 +1,299 @@
+use crate::i18n::t;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{calendar, datetime};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::widgets::calendar::{CalendarEventStore, Monthly};
+use ratatui::{prelude::*, widgets::*};
+use time::{Date, Duration, Month};
+
+const MONTH_WIDTH: u16 = 24;
+const MONTH_HEIGHT: u16 = 10;
+const GRID_COLUMNS: usize = 3;
+
+fn first_of_month(date: Date) -> Date {
+    date.replace_day(1).unwrap_or(date)
+}
+
+fn add_months(date: Date, delta: i32) -> Date {
+    let index = date.year() * 12 + date.month() as i32 - 1 + delta;
+    let year = index.div_euclid(12);
+    let Ok(month) = Month::try_from(index.rem_euclid(12) as u8 + 1) else {
+        return date;
+    };
+    let day = date.day().min(month.length(year));
+    Date::from_calendar_date(year, month, day).unwrap_or(date)
+}
+
+/// Month-grid date picker shared by the screens that need a calendar.
+///
+/// With a single month the view follows the selection; with several months
+/// the window stays fixed and the selection is kept inside it.
+#[derive(Debug, Clone)]
+pub struct DatePicker {
+    selected: Date,
+    first_month: Date,
+    months: usize,
+    min: Option<Date>,
+    max: Option<Date>,
+}
+
+impl DatePicker {
+    pub fn new(selected: Date) -> Self {
+        Self {
+            selected,
+            first_month: first_of_month(selected),
+            months: 1,
+            min: None,
+            max: None,
+        }
+    }
+
+    pub fn today() -> Self {
+        Self::new(datetime::today())
+    }
+
+    pub fn with_months(mut self, months: usize) -> Self {
+        self.months = months.max(1);
+        self
+    }
+
+    pub fn with_min(mut self, min: Date) -> Self {
+        self.min = Some(min);
+        self.set_selected(self.selected);
+        self
+    }
+
+    pub fn with_max(mut self, max: Date) -> Self {
+        self.max = Some(max);
+        self.set_selected(self.selected);
+        self
+    }
+
+    pub fn selected(&self) -> Date {
+        self.selected
+    }
+
+    pub fn set_selected(&mut self, date: Date) {
+        let mut date = date;
+        if let Some(min) = self.min {
+            date = date.max(min);
+        }
+        if let Some(max) = self.max {
+            date = date.min(max);
+        }
+        if self.months > 1 {
+            let last = add_months(self.first_month, self.months as i32) - Duration::days(1);
+            date = date.clamp(self.first_month, last);
+        } else {
+            self.first_month = first_of_month(date);
+        }
+        self.selected = date;
+    }
+
+    /// Index of the visible month holding the selection.
+    pub fn focused_month(&self) -> usize {
+        let months = |d: Date| d.year() * 12 + d.month() as i32;
+        (months(self.selected) - months(self.first_month)).max(0) as usize
+    }
+
+    pub fn visible_months(&self) -> Vec<Date> {
+        (0..self.months)
+            .map(|i| add_months(self.first_month, i as i32))
+            .collect()
+    }
+
+    fn in_range(&self, date: Date) -> bool {
+        self.min.is_none_or(|min| date >= min) && self.max.is_none_or(|max| date <= max)
+    }
+
+    /// Applies a navigation key, returning `false` for keys the picker does not use.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let date = self.selected;
+        let target = match key.code {
+            KeyCode::Left => date - Duration::days(1),
+            KeyCode::Right => date + Duration::days(1),
+            KeyCode::Up => date - Duration::days(7),
+            KeyCode::Down => date + Duration::days(7),
+            KeyCode::PageUp => add_months(date, -1),
+            KeyCode::PageDown => add_months(date, 1),
+            KeyCode::Char('[') => add_months(date, -12),
+            KeyCode::Char(']') => add_months(date, 12),
+            KeyCode::Char('t') => datetime::today(),
+            KeyCode::Tab if self.months > 1 => {
+                let next = (self.focused_month() + 1) % self.months;
+                add_months(self.first_month, next as i32)
+            }
+            _ => return false,
+        };
+        self.set_selected(target);
+        true
+    }
+
+    /// Draws the visible months in a grid of up to three columns. `marked`
+    /// adds screen-specific highlights such as existing assignments.
+    pub fn render(&self, frame: &mut Frame, area: Rect, marked: &[(Date, Style)]) {
+        let months = self.visible_months();
+        let rows = months.len().div_ceil(GRID_COLUMNS);
+        let columns = months.len().min(GRID_COLUMNS);
+
+        let row_areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Ratio(1, rows as u32); rows])
+            .split(area);
+
+        let events = self.events(marked);
+        for (index, month) in months.iter().enumerate() {
+            let cells = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![Constraint::Ratio(1, columns as u32); columns])
+                .split(row_areas[index / GRID_COLUMNS]);
+            self.render_month(
+                frame,
+                cells[index % GRID_COLUMNS],
+                *month,
+                &events,
+                index == self.focused_month(),
+            );
+        }
+    }
+
+    /// Draws a single month as a centred dialog on top of the current screen.
+    pub fn render_popup(&self, frame: &mut Frame, title: &str) {
+        let area = frame.area();
+        let width = (MONTH_WIDTH + 16).min(area.width);
+        let height = (MONTH_HEIGHT + 5).min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+
+        let block = Block::default()
+            .title(format!(" {} ", title))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(MONTH_HEIGHT), Constraint::Length(2)])
+            .split(inner);
+        let calendar_area = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(MONTH_WIDTH)])
+            .flex(layout::Flex::Center)
+            .split(layout[0])[0];
+
+        let events = self.events(&[]);
+        self.render_month(frame, calendar_area, self.first_month, &events, true);
+
+        frame.render_widget(
+            Paragraph::new(t("date_picker.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[1],
+        );
+    }
+
+    fn events(&self, marked: &[(Date, Style)]) -> CalendarEventStore {
+        let today = datetime::today();
+        let mut events = CalendarEventStore::default();
+
+        let out_of_range = Style::default().fg(theme().help).bg(theme().input);
+        let holiday = Style::default()
+            .fg(theme().background)
+            .bg(theme().warning)
+            .add_modifier(Modifier::BOLD);
+        let weekend = Style::default().fg(theme().error).bg(theme().input);
+        for month in self.visible_months() {
+            let days = month.month().length(month.year());
+            for day in 1..=days {
+                if let Ok(date) = month.replace_day(day) {
+                    if !self.in_range(date) {
+                        events.add(date, out_of_range);
+                    } else if calendar::holiday(date).is_some() {
+                        events.add(date, holiday);
+                    } else if calendar::is_weekend(date) {
+                        events.add(date, weekend);
+                    }
+                }
+            }
+        }
+        for (date, style) in marked {
+            events.add(*date, *style);
+        }
+        events.add(
+            today,
+            Style::default()
+                .fg(theme().title)
+                .bg(theme().success)
+                .add_modifier(Modifier::BOLD),
+        );
+        events.add(
+            self.selected,
+            Style::default()
+                .fg(theme().title)
+                .bg(theme().header)
+                .add_modifier(Modifier::BOLD),
+        );
+        events
+    }
+
+    fn render_month(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        month: Date,
+        events: &CalendarEventStore,
+        focused: bool,
+    ) {
+        let border_style = if focused {
+            Style::default().fg(theme().focus)
+        } else {
+            Style::default().fg(theme().unfocused)
+        };
+
+        let calendar = Monthly::new(month, events.clone())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" {} {} ", month.month(), month.year()))
+                    .title_style(
+                        Style::default()
+                            .fg(theme().title)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .border_style(border_style)
+                    .style(Style::default().bg(theme().input)),
+            )
+            .show_month_header(
+                Style::default()
+                    .fg(theme().title)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .show_weekdays_header(
+                Style::default()
+                    .fg(theme().background)
+                    .bg(theme().focus)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .default_style(Style::default().fg(theme().text).bg(theme().input));
+
+        frame.render_widget(calendar, area);
+    }
+}
+
+impl Default for DatePicker {
+    fn default() -> Self {
+        Self::today()
+    }
+}
@@ -0,0 +1,120 @@
+use crate::i18n::t;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+/// Asks before leaving a form whose changes have not been saved. Staying is
+/// selected first, so an extra Enter keeps the changes.
+pub struct DiscardPrompt {
+    discard_selected: bool,
+}
+
+impl DiscardPrompt {
+    pub fn new() -> Self {
+        Self {
+            discard_selected: false,
+        }
+    }
+
+    /// `Some(true)` to throw the changes away and leave, `Some(false)` to
+    /// keep editing.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<bool> {
+        match key.code {
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab => {
+                self.discard_selected = !self.discard_selected;
+                None
+            }
+            KeyCode::Enter => Some(self.discard_selected),
+            KeyCode::Char('y') | KeyCode::Char('Y') => Some(true),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => Some(false),
+            _ => None,
+        }
+    }
+
+    pub fn render_popup(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let width = 68.min(area.width);
+        let height = 7.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" {} ", t("discard_prompt.title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().warning))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(1),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+        frame.render_widget(
+            Paragraph::new(t("discard_prompt.question"))
+                .style(
+                    Style::default()
+                        .fg(theme().text)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let button = |label: &str, selected: bool, color: Color| {
+            if selected {
+                Span::styled(
+                    format!("► {} ◄", label),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                )
+            } else {
+                Span::styled(
+                    format!("  {}  ", label),
+                    Style::default().fg(theme().inactive),
+                )
+            }
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                button(
+                    t("discard_prompt.discard"),
+                    self.discard_selected,
+                    theme().error,
+                ),
+                Span::raw("      "),
+                button(
+                    t("discard_prompt.keep"),
+                    !self.discard_selected,
+                    theme().success,
+                ),
+            ]))
+            .alignment(Alignment::Center),
+            layout[1],
+        );
+        frame.render_widget(
+            Paragraph::new(t("discard_prompt.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+    }
+}
+
+impl Default for DiscardPrompt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
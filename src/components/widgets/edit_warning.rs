@@ -0,0 +1,23 @@
+use crate::i18n::tf;
+use crate::locks::EditLock;
+use crate::theme::theme;
+use crate::tui::Frame;
+use ratatui::{prelude::*, widgets::*};
+
+/// Draws "Also being edited by ..." on the line `area`, when anyone else has
+/// the record behind `lock` open.
+pub fn render(frame: &mut Frame, area: Rect, lock: Option<&EditLock>) {
+    let others = lock.map(EditLock::other_editors).unwrap_or_default();
+    if others.is_empty() {
+        return;
+    }
+    frame.render_widget(
+        Paragraph::new(tf(
+            "edit_warning.others",
+            &[("names", &(others.join(", ")))],
+        ))
+        .style(Style::default().fg(theme().warning).bg(theme().background))
+        .alignment(Alignment::Center),
+        area,
+    );
+}
@@ -0,0 +1,193 @@
+use crate::components::toast::{self, Toast};
+use crate::i18n::{t, tf};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{csv_line, datetime, markdown_line, write_export};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+
+/// Whether `key` exports the rows a list screen is showing.
+pub fn is_open_key(key: KeyEvent) -> bool {
+    key.code == KeyCode::Char('e') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// The rows a list is showing, in its order and with its columns.
+pub struct ShownRows {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl ShownRows {
+    /// `headers` are catalog keys, or the text itself where a list has no
+    /// translation.
+    pub fn new(headers: &[&'static str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| t(h).to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn lines(&self, line: fn(&[&str]) -> String) -> Vec<String> {
+        let headers: Vec<&str> = self.headers.iter().map(String::as_str).collect();
+        let mut lines = vec![line(&headers)];
+        for row in &self.rows {
+            let fields: Vec<&str> = row.iter().map(String::as_str).collect();
+            lines.push(line(&fields));
+        }
+        lines
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut contents = self.lines(csv_line).join("\n");
+        contents.push('\n');
+        contents
+    }
+
+    /// A Markdown table, for pasting into a report.
+    pub fn to_markdown(&self) -> String {
+        let mut lines = self.lines(markdown_line);
+        let rule = vec!["---"; self.headers.len()];
+        lines.insert(1, markdown_line(&rule));
+        let mut contents = lines.join("\n");
+        contents.push('\n');
+        contents
+    }
+}
+
+/// A popup writing the rows a list shows, exactly as filtered and sorted on
+/// screen, to a CSV file or a Markdown table in the exports directory.
+pub struct ExportView {
+    /// Starts the file name, as in `patients_20250101_093000.csv`.
+    name: &'static str,
+    shown: ShownRows,
+    markdown: bool,
+}
+
+impl ExportView {
+    pub fn new(name: &'static str, shown: ShownRows) -> Self {
+        Self {
+            name,
+            shown,
+            markdown: false,
+        }
+    }
+
+    fn export(&self, markdown: bool) {
+        let (contents, extension) = if markdown {
+            (self.shown.to_markdown(), "md")
+        } else {
+            (self.shown.to_csv(), "csv")
+        };
+        let file_name = format!(
+            "{}_{}.{}",
+            self.name,
+            datetime::file_stamp(datetime::now()),
+            extension
+        );
+        match write_export(&file_name, &contents) {
+            Ok(path) => toast::push(Toast::success(tf(
+                "export.exported",
+                &[("count", &self.shown.rows.len()), ("path", &path.display())],
+            ))),
+            Err(e) => toast::push(Toast::error(tf("export.failed", &[("error", &e)]))),
+        }
+    }
+
+    /// Handles `key`; returns whether the popup is done.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab => self.markdown = !self.markdown,
+            KeyCode::Enter => {
+                self.export(self.markdown);
+                return true;
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') => {
+                self.export(false);
+                return true;
+            }
+            KeyCode::Char('m') | KeyCode::Char('M') => {
+                self.export(true);
+                return true;
+            }
+            KeyCode::Esc => return true,
+            _ => {}
+        }
+        false
+    }
+
+    pub fn render_popup(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let width = 70.min(area.width);
+        let height = 7.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" {} ", t("export.title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(1),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+        frame.render_widget(
+            Paragraph::new(tf(
+                "export.prompt",
+                &[
+                    ("rows", &self.shown.rows.len()),
+                    ("columns", &self.shown.headers.len()),
+                ],
+            ))
+            .style(Style::default().fg(theme().text))
+            .alignment(Alignment::Center),
+            layout[0],
+        );
+
+        let option = |label: &str, selected: bool| {
+            if selected {
+                Span::styled(
+                    format!("► {} ◄", label),
+                    Style::default()
+                        .fg(theme().accent)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                Span::styled(
+                    format!("  {}  ", label),
+                    Style::default().fg(theme().inactive),
+                )
+            }
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                option("CSV", !self.markdown),
+                Span::raw("      "),
+                option("Markdown", self.markdown),
+            ]))
+            .alignment(Alignment::Center),
+            layout[1],
+        );
+        frame.render_widget(
+            Paragraph::new(t("export.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+    }
+}
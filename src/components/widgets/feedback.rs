@@ -0,0 +1,220 @@
+use super::text_input::TextInput;
+use crate::components::toast::{self, Toast};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::PatientFeedback;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{config, datetime};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+const RATING: usize = 0;
+const COMMENT: usize = 1;
+
+pub enum FeedbackOutcome {
+    Saved,
+    Cancelled,
+}
+
+/// `rating` as five stars, filled up to the rating.
+pub fn stars(rating: i64) -> String {
+    let filled = rating.clamp(0, 5) as usize;
+    format!("{}{}", "★".repeat(filled), "☆".repeat(5 - filled))
+}
+
+/// Whether `rating` is low enough to be followed up, per `follow_up_rating`.
+pub fn needs_follow_up(rating: i64) -> bool {
+    rating <= config::config().follow_up_rating as i64
+}
+
+/// A popup taking a patient's rating of a visit, 1 to 5, and an optional
+/// comment. Feedback already given on the visit is loaded to be changed.
+pub struct FeedbackPopup {
+    db: Db,
+    feedback: PatientFeedback,
+    subject: String,
+    comment: TextInput,
+    focus_index: usize,
+}
+
+impl FeedbackPopup {
+    /// Feedback on a consultation or queue token of `patient_id` with
+    /// `doctor_id`; `subject` names the patient and visit in the title.
+    pub fn new(
+        db: &Db,
+        patient_id: i64,
+        doctor_id: i64,
+        teleconsultation_id: Option<i64>,
+        queue_token_id: Option<i64>,
+        subject: String,
+    ) -> anyhow::Result<Self> {
+        let feedback = db::get_visit_feedback(db, teleconsultation_id, queue_token_id)?.unwrap_or(
+            PatientFeedback {
+                id: 0,
+                patient_id,
+                doctor_id,
+                teleconsultation_id,
+                queue_token_id,
+                rating: 0,
+                comment: None,
+                created_at: String::new(),
+                followed_up_at: None,
+                follow_up_note: None,
+            },
+        );
+        let mut comment = TextInput::new();
+        comment.set_value(feedback.comment.clone().unwrap_or_default());
+        Ok(Self {
+            db: db.clone(),
+            feedback,
+            subject,
+            comment,
+            focus_index: RATING,
+        })
+    }
+
+    fn submit(&mut self) -> Option<FeedbackOutcome> {
+        if !(1..=5).contains(&self.feedback.rating) {
+            self.focus_index = RATING;
+            toast::push(Toast::error(t("feedback.pick_rating")));
+            return None;
+        }
+        let comment = self.comment.value().trim();
+        let mut feedback = PatientFeedback {
+            comment: (!comment.is_empty()).then(|| comment.to_string()),
+            ..self.feedback.clone()
+        };
+        if feedback.id == 0 {
+            feedback.created_at = datetime::now_text();
+        }
+        match db::save_feedback(&self.db, &feedback) {
+            Ok(_) => {
+                if needs_follow_up(feedback.rating) && feedback.followed_up_at.is_none() {
+                    toast::push(Toast::success(t("feedback.saved_flagged")));
+                } else {
+                    toast::push(Toast::success(t("feedback.saved")));
+                }
+                Some(FeedbackOutcome::Saved)
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("feedback.save_failed", &[("error", &e)])));
+                None
+            }
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<FeedbackOutcome> {
+        match key.code {
+            KeyCode::Esc => return Some(FeedbackOutcome::Cancelled),
+            KeyCode::Enter => return self.submit(),
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+                self.focus_index = if self.focus_index == RATING {
+                    COMMENT
+                } else {
+                    RATING
+                };
+            }
+            KeyCode::Left if self.focus_index == RATING => {
+                self.feedback.rating = (self.feedback.rating - 1).max(1);
+            }
+            KeyCode::Right if self.focus_index == RATING => {
+                self.feedback.rating = (self.feedback.rating + 1).min(5);
+            }
+            KeyCode::Char(c @ '1'..='5') if self.focus_index == RATING => {
+                self.feedback.rating = c.to_digit(10).map_or(0, i64::from);
+            }
+            _ if self.focus_index == COMMENT => {
+                self.comment.handle_key(key);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    pub fn render_popup(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let width = 64.min(area.width);
+        let height = 12.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(
+                " {} ",
+                tf("feedback.title", &[("subject", &self.subject)])
+            ))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        let field = |title: String, index: usize| {
+            let border = if self.focus_index == index {
+                theme().focus
+            } else {
+                theme().border
+            };
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(title)
+                .border_style(Style::default().fg(border))
+                .style(Style::default().bg(theme().input))
+        };
+        let rating = if self.feedback.rating == 0 {
+            Line::from(Span::styled(
+                t("feedback.press"),
+                Style::default().fg(theme().inactive),
+            ))
+        } else {
+            let color = if needs_follow_up(self.feedback.rating) {
+                theme().error
+            } else {
+                theme().warning
+            };
+            Line::from(vec![
+                Span::styled(stars(self.feedback.rating), Style::default().fg(color)),
+                Span::styled(
+                    format!("  {}/5", self.feedback.rating),
+                    Style::default().fg(theme().text),
+                ),
+            ])
+        };
+        frame.render_widget(
+            Paragraph::new(rating).block(field(format!(" {} ", t("satisfaction.rating")), RATING)),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(self.comment.line(self.focus_index == COMMENT))
+                .style(Style::default().fg(theme().text))
+                .block(field(format!(" {} ", t("satisfaction.comment")), COMMENT)),
+            layout[1],
+        );
+
+        frame.render_widget(
+            Paragraph::new(t("feedback.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::components::toast::{self, Toast};
+use crate::components::widgets::print_dialog::PrintDialog;
+use crate::db::Db;
+use crate::i18n::{t, tf};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::barcode;
+use crate::utils::config::{self, LabelFormat};
+use crate::utils::labels::Label;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+const BARCODE_HEIGHT: usize = 3;
+
+/// A label with its QR code and barcode, shown over a screen; `p` prints it
+/// in the configured label format.
+pub struct LabelPopup {
+    db: Db,
+    label: Label,
+    qr: Vec<String>,
+    barcode: Vec<String>,
+    print_dialog: Option<PrintDialog>,
+}
+
+impl LabelPopup {
+    /// Fails when the label's code cannot be encoded, such as non-ASCII text
+    /// in a barcode.
+    pub fn new(db: &Db, label: Label) -> Result<Self> {
+        Ok(Self {
+            db: db.clone(),
+            qr: barcode::qr_lines(&label.code)?,
+            barcode: barcode::code128_lines(&label.code, BARCODE_HEIGHT)?,
+            label,
+            print_dialog: None,
+        })
+    }
+
+    /// The popup for `label`, or None after reporting why it cannot be made.
+    pub fn open(db: &Db, label: Label) -> Option<Self> {
+        match Self::new(db, label) {
+            Ok(popup) => Some(popup),
+            Err(e) => {
+                toast::push(Toast::error(tf("label.failed", &[("error", &e)])));
+                None
+            }
+        }
+    }
+
+    /// Returns true once the popup is closed.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if let Some(dialog) = self.print_dialog.as_mut() {
+            if dialog.handle_key(key) {
+                self.print_dialog = None;
+            }
+            return false;
+        }
+        match key.code {
+            KeyCode::Char('p') | KeyCode::Char('P') => {
+                let format = config::config().labels.format;
+                if let Ok(document) = self.label.render(format) {
+                    let dialog = PrintDialog::new(&self.db, self.label.kind.title(), document);
+                    self.print_dialog = Some(match format {
+                        LabelFormat::Text => dialog,
+                        LabelFormat::Zpl => dialog.raw(),
+                    });
+                }
+            }
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => return true,
+            _ => {}
+        }
+        false
+    }
+
+    pub fn render_popup(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let code_width = self
+            .qr
+            .iter()
+            .chain(&self.barcode)
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or_default() as u16;
+        let width = (code_width + 4).min(area.width);
+        let height = (self.label.lines.len() + self.qr.len() + BARCODE_HEIGHT + 6) as u16;
+        let height = height.min(area.height);
+        let popup = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, popup);
+
+        let block = Block::default()
+            .title(format!(" {} ", self.label.kind.title()))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(popup);
+        frame.render_widget(block, popup);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(self.label.lines.len() as u16),
+                Constraint::Length(1),
+                Constraint::Length(self.qr.len() as u16),
+                Constraint::Length(BARCODE_HEIGHT as u16),
+                Constraint::Length(1),
+                Constraint::Min(1),
+            ])
+            .split(inner);
+
+        frame.render_widget(
+            Paragraph::new(self.label.lines.join("\n"))
+                .style(Style::default().fg(theme().text))
+                .alignment(Alignment::Center),
+            layout[0],
+        );
+        // Scanners need dark bars on a light background, whatever the theme.
+        let code_style = Style::default().fg(Color::Black).bg(Color::White);
+        for (lines, area) in [(&self.qr, layout[2]), (&self.barcode, layout[3])] {
+            let width = lines.first().map_or(0, |line| line.chars().count()) as u16;
+            let centered = Rect {
+                x: area.x + area.width.saturating_sub(width) / 2,
+                width: width.min(area.width),
+                ..area
+            };
+            frame.render_widget(Paragraph::new(lines.join("\n")).style(code_style), centered);
+        }
+        frame.render_widget(
+            Paragraph::new(self.label.code.as_str())
+                .style(Style::default().fg(theme().title))
+                .alignment(Alignment::Center),
+            layout[4],
+        );
+        frame.render_widget(
+            Paragraph::new(t("label.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[5],
+        );
+
+        if let Some(dialog) = &self.print_dialog {
+            dialog.render_popup(frame);
+        }
+    }
+}
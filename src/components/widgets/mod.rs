@@ -0,0 +1,18 @@
+pub mod aftercare;
+pub mod column_picker;
+pub mod compare;
+pub mod date_picker;
+pub mod discard_prompt;
+pub mod edit_warning;
+pub mod export_view;
+pub mod feedback;
+pub mod label;
+pub mod new_patient;
+pub mod no_shows;
+pub mod photo;
+pub mod prescribing_alert;
+pub mod print_dialog;
+pub mod procedure_picker;
+pub mod progress;
+pub mod saved_searches;
+pub mod text_input;
@@ -0,0 +1,204 @@
+use crate::components::form::{self, Field, Form};
+use crate::components::toast::{self, Toast};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::{ContactPreference, Gender, Patient};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::contact;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+const FIRST_NAME: usize = 0;
+const LAST_NAME: usize = 1;
+const DOB: usize = 2;
+const GENDER: usize = 3;
+const PHONE: usize = 4;
+const ADDRESS: usize = 5;
+const POSTCODE: usize = 6;
+const CITY: usize = 7;
+const REGION: usize = 8;
+const FIELDS: usize = 9;
+
+pub enum NewPatientOutcome {
+    /// The patient was saved; carries the new row, id included.
+    Created(Box<Patient>),
+    Cancelled,
+}
+
+/// A popup registering a patient with only the required details, so a
+/// record, invoice or consultation for someone new does not mean leaving
+/// the screen. The rest can be filled in later from Update Patient.
+pub struct NewPatient {
+    db: Db,
+    form: Form,
+    focus_index: usize,
+}
+
+impl NewPatient {
+    pub fn new(db: &Db) -> Self {
+        let mut form = Form::new(vec![
+            Field::new(t("common.first_name")).required(),
+            Field::new(t("common.last_name")).required(),
+            Field::new(t("patients.dob_field"))
+                .required()
+                .validate_with(Box::new(form::past_date)),
+            Field::new(t("patients.gender_field"))
+                .required()
+                .validate_with(Box::new(form::gender)),
+            Field::new(t("common.phone"))
+                .required()
+                .validate_with(Box::new(form::phone)),
+            Field::new(t("patients.street")).required(),
+            Field::new(t("patients.postcode")),
+            Field::new(t("patients.city")),
+            Field::new(t("patients.region")),
+        ]);
+        form.fields[GENDER].set(Gender::Male.label());
+        Self {
+            db: db.clone(),
+            form,
+            focus_index: FIRST_NAME,
+        }
+    }
+
+    fn submit(&mut self) -> Option<NewPatientOutcome> {
+        if let Some(invalid) = self.form.validate() {
+            self.focus_index = invalid;
+            return None;
+        }
+        let mut patient = Patient {
+            id: 0,
+            first_name: self.form.value(FIRST_NAME),
+            last_name: self.form.value(LAST_NAME),
+            date_of_birth: self.form.value(DOB),
+            gender: Gender::from(self.form.value(GENDER)),
+            address: self.form.value(ADDRESS),
+            city: self.form.value(CITY),
+            region: self.form.value(REGION),
+            postcode: self.form.value(POSTCODE),
+            phone_number: contact::normalize_phone(&self.form.value(PHONE)),
+            email: None,
+            medical_history: None,
+            allergies: None,
+            current_medications: None,
+            contact_preference: ContactPreference::default(),
+            photo_path: None,
+            mrn: String::new(),
+        };
+        match db::create_patient(&self.db, &patient) {
+            Ok(id) => {
+                patient.id = id;
+                toast::push(Toast::success(tf(
+                    "new_patient.added",
+                    &[("first", &patient.first_name), ("last", &patient.last_name)],
+                )));
+                Some(NewPatientOutcome::Created(Box::new(patient)))
+            }
+            Err(e) => {
+                toast::push(Toast::error(tf("common.database_error", &[("error", &e)])));
+                None
+            }
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<NewPatientOutcome> {
+        let focused = self.focus_index;
+        let outcome = self.process_key(key);
+        if focused == POSTCODE && self.focus_index != POSTCODE {
+            self.form.fill_from_postcode(POSTCODE, CITY, REGION);
+        }
+        outcome
+    }
+
+    fn process_key(&mut self, key: KeyEvent) -> Option<NewPatientOutcome> {
+        match key.code {
+            KeyCode::Esc => return Some(NewPatientOutcome::Cancelled),
+            KeyCode::Enter => return self.submit(),
+            KeyCode::Char(' ') if self.focus_index == GENDER => {
+                let gender = Gender::parse(self.form.fields[GENDER].value())
+                    .map_or(Gender::Male, |gender| gender.next());
+                self.form.fields[GENDER].set(gender.label());
+            }
+            KeyCode::Char(c) if self.focus_index == GENDER => {
+                if let Some(gender) = Gender::parse(&c.to_string()) {
+                    self.form.fields[GENDER].set(gender.label());
+                }
+            }
+            _ if self.focus_index != GENDER && self.form.handle_key(self.focus_index, key) => {}
+            KeyCode::Tab | KeyCode::Down => {
+                self.focus_index = (self.focus_index + 1) % FIELDS;
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                self.focus_index = (self.focus_index + FIELDS - 1) % FIELDS;
+            }
+            _ => {}
+        }
+        None
+    }
+
+    pub fn render_popup(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let width = 76.min(area.width);
+        let height = 16.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" {} ", t("new_patient.title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        for row in 0..3 {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(layout[row]);
+            for (column, area) in columns.iter().enumerate() {
+                self.form
+                    .render_field(frame, row * 2 + column, *area, self.focus_index);
+            }
+        }
+        let locality = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Ratio(1, 3),
+                Constraint::Ratio(1, 3),
+                Constraint::Ratio(1, 3),
+            ])
+            .split(layout[3]);
+        for (index, area) in [POSTCODE, CITY, REGION].into_iter().zip(locality.iter()) {
+            self.form
+                .render_field(frame, index, *area, self.focus_index);
+        }
+
+        frame.render_widget(
+            Paragraph::new(t("new_patient.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[5],
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use crate::components::toast::{self, Toast};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::models::NoShowCount;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::config;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use std::collections::HashMap;
+
+/// Marks the appointments and consultations past the configured grace
+/// period without a check-in as no-shows. Returns how many were marked.
+pub fn mark_overdue(db: &Db) -> usize {
+    let grace = config::config().no_show_grace;
+    if grace == 0 || db.is_read_only() {
+        return 0;
+    }
+    match db::mark_no_shows(db, grace) {
+        Ok(0) => 0,
+        Ok(marked) => {
+            toast::push(Toast::success(tf(
+                "no_shows.marked",
+                &[("count", &marked), ("minutes", &grace)],
+            )));
+            marked
+        }
+        Err(e) => {
+            toast::push(Toast::error(tf("no_shows.mark_failed", &[("error", &e)])));
+            0
+        }
+    }
+}
+
+fn no_show_rate(count: &NoShowCount) -> f64 {
+    let total = count.attended + count.no_shows;
+    if total == 0 {
+        0.0
+    } else {
+        count.no_shows as f64 * 100.0 / total as f64
+    }
+}
+
+/// A popup with the no-show rates per doctor or per patient, worst first,
+/// counting in-person appointments and remote consultations alike.
+pub struct NoShowReport {
+    db: Db,
+    by_doctor: bool,
+    rows: Vec<NoShowCount>,
+    patient_names: HashMap<i64, String>,
+    doctor_names: HashMap<i64, String>,
+    state: TableState,
+}
+
+impl NoShowReport {
+    /// Opens on the rates per doctor.
+    pub fn new(db: &Db) -> Result<Self> {
+        let mut report = Self {
+            db: db.clone(),
+            by_doctor: true,
+            rows: Vec::new(),
+            patient_names: db::get_all_patients(db)?
+                .into_iter()
+                .map(|p| (p.id, format!("{} {}", p.first_name, p.last_name)))
+                .collect(),
+            doctor_names: db::get_all_staff(db)?
+                .into_iter()
+                .map(|s| (s.id, s.name))
+                .collect(),
+            state: TableState::default(),
+        };
+        report.load()?;
+        Ok(report)
+    }
+
+    fn load(&mut self) -> Result<()> {
+        let mut rows = if self.by_doctor {
+            db::get_no_show_counts_by_doctor(&self.db)?
+        } else {
+            db::get_no_show_counts_by_patient(&self.db)?
+        };
+        rows.sort_by(|a, b| {
+            no_show_rate(b)
+                .total_cmp(&no_show_rate(a))
+                .then(b.no_shows.cmp(&a.no_shows))
+        });
+        let selected = if rows.is_empty() { None } else { Some(0) };
+        self.rows = rows;
+        self.state = TableState::default().with_selected(selected);
+        Ok(())
+    }
+
+    fn name(&self, id: i64) -> String {
+        let (names, unknown) = if self.by_doctor {
+            (&self.doctor_names, "common.staff_number")
+        } else {
+            (&self.patient_names, "common.patient_number")
+        };
+        names
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| tf(unknown, &[("id", &id)]))
+    }
+
+    /// Handles a key; returns true once the popup should close.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let len = self.rows.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.by_doctor = !self.by_doctor;
+                if let Err(e) = self.load() {
+                    toast::push(Toast::error(tf("no_shows.load_failed", &[("error", &e)])));
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => return true,
+            _ => {}
+        }
+        false
+    }
+
+    pub fn render_popup(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let width = 70.min(area.width);
+        let height = 20.min(area.height);
+        let dialog_area = Rect::new(
+            area.width.saturating_sub(width) / 2,
+            area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(if self.by_doctor {
+                format!(" {} ", t("no_shows.by_doctor"))
+            } else {
+                format!(" {} ", t("no_shows.by_patient"))
+            })
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .horizontal_margin(1)
+            .split(inner);
+
+        let header_row = Row::new(vec![
+            t("common.name"),
+            t("no_shows.attended"),
+            t("no_shows.no_shows"),
+            t("no_shows.rate"),
+        ])
+        .style(Style::default().bg(theme().header).fg(theme().title));
+        let widths = [
+            Constraint::Min(16),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Length(7),
+        ];
+        let rows = self.rows.iter().map(|count| {
+            Row::new(vec![
+                self.name(count.id),
+                count.attended.to_string(),
+                count.no_shows.to_string(),
+                format!("{:.0}%", no_show_rate(count)),
+            ])
+            .style(Style::default().fg(theme().text))
+        });
+        let table = Table::new(rows, widths)
+            .header(header_row)
+            .row_highlight_style(
+                Style::default()
+                    .fg(theme().focus)
+                    .bg(theme().highlight)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        if self.rows.is_empty() {
+            frame.render_widget(
+                Paragraph::new(t("no_shows.empty"))
+                    .style(Style::default().fg(theme().inactive))
+                    .alignment(Alignment::Center),
+                chunks[0],
+            );
+        } else {
+            frame.render_stateful_widget(table, chunks[0], &mut self.state.clone());
+        }
+        frame.render_widget(
+            Paragraph::new(t("no_shows.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            chunks[1],
+        );
+    }
+}
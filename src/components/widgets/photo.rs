@@ -0,0 +1,39 @@
+use crate::tui::Frame;
+use crate::utils::photo::Image;
+use ratatui::{prelude::*, widgets::*};
+
+/// Draws `image` as large as fits in `area`, centred horizontally. Each
+/// cell is a `▀` showing two pixels: the upper in its foreground colour and
+/// the lower in its background.
+pub fn render_photo(frame: &mut Frame, area: Rect, image: &Image) {
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+    let rows = usize::from(area.height) * 2;
+    let scale = (f64::from(area.width) / image.width as f64).min(rows as f64 / image.height as f64);
+    let width = ((image.width as f64 * scale) as usize).clamp(1, usize::from(area.width));
+    let height = ((image.height as f64 * scale) as usize).clamp(1, rows);
+    let color = |x: usize, y: usize| {
+        let [r, g, b] = image.pixel(x * image.width / width, y * image.height / height);
+        Color::Rgb(r, g, b)
+    };
+
+    let lines: Vec<Line> = (0..height.div_ceil(2))
+        .map(|row| {
+            let (top, bottom) = (row * 2, row * 2 + 1);
+            (0..width)
+                .map(|x| {
+                    let style = Style::default().fg(color(x, top));
+                    if bottom < height {
+                        Span::styled("▀", style.bg(color(x, bottom)))
+                    } else {
+                        Span::styled("▀", style)
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    let x = area.x + (area.width - width as u16) / 2;
+    let area = Rect::new(x, area.y, width as u16, lines.len() as u16);
+    frame.render_widget(Paragraph::new(lines), area);
+}
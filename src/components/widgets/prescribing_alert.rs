@@ -0,0 +1,176 @@
+use super::text_input::TextInput;
+use crate::db::{self, Db};
+use crate::i18n::t;
+use crate::models::PrescribingOverride;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state;
+use crate::utils::datetime;
+use crate::utils::prescribing::{Alert, Severity};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertOutcome {
+    /// Save anyway; the alerts are recorded as overridden.
+    Proceed,
+    /// Back to the prescription to change it.
+    Cancel,
+}
+
+/// Prescribing warnings shown before a record is saved.
+///
+/// Moderate alerts only need acknowledging. A severe alert blocks saving
+/// until the prescriber gives a reason for overriding it.
+pub struct PrescribingAlert {
+    db: Db,
+    alerts: Vec<Alert>,
+    reason: TextInput,
+    error: Option<String>,
+}
+
+impl PrescribingAlert {
+    pub fn new(db: &Db, alerts: Vec<Alert>) -> Self {
+        Self {
+            db: db.clone(),
+            alerts,
+            reason: TextInput::new(),
+            error: None,
+        }
+    }
+
+    fn blocking(&self) -> bool {
+        self.alerts.iter().any(|a| a.severity == Severity::Severe)
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<AlertOutcome> {
+        match key.code {
+            KeyCode::Enter => {
+                if self.blocking() && self.reason.value().trim().is_empty() {
+                    self.error = Some(t("prescribing_alert.reason_required").into());
+                    return None;
+                }
+                return Some(AlertOutcome::Proceed);
+            }
+            KeyCode::Esc => return Some(AlertOutcome::Cancel),
+            _ => {
+                if self.reason.handle_key(key) {
+                    self.error = None;
+                }
+            }
+        }
+        None
+    }
+
+    /// Records every alert as overridden on `medical_record_id`, with the
+    /// reason given and the signed-in user.
+    pub fn save_overrides(&self, medical_record_id: i64) -> Result<()> {
+        let reason = self.reason.value().trim();
+        let overridden_by = ui_state::current_user();
+        let created_at = datetime::now_text();
+        for alert in &self.alerts {
+            db::create_prescribing_override(
+                &self.db,
+                &PrescribingOverride {
+                    id: 0,
+                    medical_record_id,
+                    severity: alert.severity.as_str().to_string(),
+                    warning: alert.message.clone(),
+                    reason: (!reason.is_empty()).then(|| reason.to_string()),
+                    overridden_by: overridden_by.clone(),
+                    created_at: created_at.clone(),
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn render_popup(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let width = 76.min(area.width);
+        let list_height = self.alerts.len() as u16 * 2;
+        let height = (list_height + 9).min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+
+        let border = if self.blocking() {
+            theme().error
+        } else {
+            theme().warning
+        };
+        let block = Block::default()
+            .title(format!(" {} ", t("prescribing_alert.title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(border))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(1),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        let lines: Vec<Line> = self
+            .alerts
+            .iter()
+            .map(|alert| {
+                let color = match alert.severity {
+                    Severity::Severe => theme().error,
+                    Severity::Moderate => theme().warning,
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:<9}", alert.severity.as_str()),
+                        Style::default().fg(color).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(alert.message.clone(), Style::default().fg(theme().text)),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), layout[0]);
+
+        let title = if self.blocking() {
+            format!(" {} ", t("prescribing_alert.reason_required_field"))
+        } else {
+            format!(" {} ", t("prescribing_alert.reason_optional"))
+        };
+        frame.render_widget(
+            Paragraph::new(self.reason.line(true)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(title)
+                    .border_style(Style::default().fg(theme().focus))
+                    .style(Style::default().bg(theme().input)),
+            ),
+            layout[1],
+        );
+        if let Some(error) = &self.error {
+            frame.render_widget(
+                Paragraph::new(error.as_str()).style(Style::default().fg(theme().error)),
+                layout[2],
+            );
+        }
+        frame.render_widget(
+            Paragraph::new(t("prescribing_alert.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use crate::components::toast::{self, Toast};
+use crate::db::{self, Db};
+use crate::i18n::{t, tf};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::print::{self, Printer};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+const LAST_PRINTER: &str = "printer";
+
+/// Printer chooser shown over a screen that has a document ready to print.
+///
+/// The printer used last is preselected, falling back to the system default.
+pub struct PrintDialog {
+    db: Db,
+    title: String,
+    document: String,
+    /// Sent to the printer untouched, like ZPL labels.
+    raw: bool,
+    printers: Vec<Printer>,
+    error: Option<String>,
+    state: ListState,
+}
+
+impl PrintDialog {
+    pub fn new(db: &Db, title: impl Into<String>, document: impl Into<String>) -> Self {
+        let (printers, error) = match print::printers() {
+            Ok(printers) => (printers, None),
+            Err(e) => (Vec::new(), Some(format!("{:#}", e))),
+        };
+        let last = db::get_setting(db, LAST_PRINTER).ok().flatten();
+        let selected = last
+            .and_then(|name| printers.iter().position(|p| p.name == name))
+            .or(if printers.is_empty() { None } else { Some(0) });
+        Self {
+            db: db.clone(),
+            title: title.into(),
+            document: document.into(),
+            raw: false,
+            printers,
+            error,
+            state: ListState::default().with_selected(selected),
+        }
+    }
+
+    /// Sends the document as printer commands rather than text.
+    pub fn raw(mut self) -> Self {
+        self.raw = true;
+        self
+    }
+
+    /// Returns true once the document has been sent or the dialog cancelled.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let len = self.printers.len();
+        match key.code {
+            KeyCode::Down if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Up if len > 0 => {
+                let i = self.state.selected().map_or(0, |i| (i + len - 1) % len);
+                self.state.select(Some(i));
+            }
+            KeyCode::Enter => {
+                let Some(printer) = self.state.selected().and_then(|i| self.printers.get(i)) else {
+                    return false;
+                };
+                let sent = if self.raw {
+                    print::print_raw(&printer.name, &self.title, &self.document)
+                } else {
+                    print::print(&printer.name, &self.title, &self.document)
+                };
+                match sent {
+                    Ok(()) => {
+                        let _ = db::set_setting(&self.db, LAST_PRINTER, &printer.name);
+                        toast::push(Toast::success(tf(
+                            "print.sent",
+                            &[("title", &self.title), ("printer", &printer.name)],
+                        )));
+                        return true;
+                    }
+                    Err(e) => self.error = Some(format!("{:#}", e)),
+                }
+            }
+            KeyCode::Esc => return true,
+            _ => {}
+        }
+        false
+    }
+
+    pub fn render_popup(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let width = 56.min(area.width);
+        let height = (self.printers.len().max(1) as u16 + 8)
+            .min(16)
+            .min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+
+        let block = Block::default()
+            .title(format!(
+                " 🖨️ {} ",
+                tf("print.title", &[("title", &self.title)])
+            ))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(1),
+                Constraint::Length(2),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        let items: Vec<ListItem> = if self.printers.is_empty() {
+            vec![ListItem::new(t("print.no_printers")).style(Style::default().fg(theme().inactive))]
+        } else {
+            self.printers
+                .iter()
+                .map(|printer| {
+                    let mut line = vec![Span::raw(printer.name.clone())];
+                    if printer.is_default {
+                        line.push(Span::styled(
+                            format!(" ({})", t("print.default")),
+                            Style::default().fg(theme().help),
+                        ));
+                    }
+                    ListItem::new(Line::from(line))
+                })
+                .collect()
+        };
+        let list = List::new(items)
+            .style(Style::default().fg(theme().text))
+            .highlight_style(
+                Style::default()
+                    .bg(theme().highlight)
+                    .fg(theme().focus)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(list, layout[0], &mut self.state.clone());
+
+        if let Some(error) = &self.error {
+            frame.render_widget(
+                Paragraph::new(error.as_str())
+                    .style(Style::default().fg(theme().error))
+                    .wrap(Wrap { trim: true }),
+                layout[1],
+            );
+        }
+        frame.render_widget(
+            Paragraph::new(t("print.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[2],
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use super::text_input::TextInput;
+use crate::i18n::t;
+use crate::models::Procedure;
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::{money, procedures, truncate};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+pub enum PickerOutcome {
+    Picked(Procedure),
+    Cancelled,
+}
+
+/// A popup searching the procedure catalog by code or name, opened with
+/// `Ctrl+F` from the fields that take a procedure code.
+pub struct ProcedurePicker {
+    catalog: Vec<Procedure>,
+    query: TextInput,
+    list_state: ListState,
+}
+
+impl ProcedurePicker {
+    pub fn new(catalog: Vec<Procedure>) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+        Self {
+            catalog,
+            query: TextInput::new(),
+            list_state,
+        }
+    }
+
+    fn matches(&self) -> Vec<&Procedure> {
+        procedures::search(&self.catalog, self.query.value())
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<PickerOutcome> {
+        let count = self.matches().len();
+        let selected = self.list_state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Enter => {
+                return self
+                    .matches()
+                    .get(selected)
+                    .map(|p| PickerOutcome::Picked((*p).clone()));
+            }
+            KeyCode::Esc => return Some(PickerOutcome::Cancelled),
+            KeyCode::Down if count > 0 => self.list_state.select(Some((selected + 1) % count)),
+            KeyCode::Up if count > 0 => {
+                self.list_state.select(Some((selected + count - 1) % count))
+            }
+            _ => {
+                if self.query.handle_key(key) {
+                    self.list_state.select(Some(0));
+                }
+            }
+        }
+        None
+    }
+
+    pub fn render_popup(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let width = 76.min(area.width);
+        let height = 20.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" {} ", t("procedure_picker.title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        frame.render_widget(
+            Paragraph::new(self.query.line(true))
+                .style(Style::default().fg(theme().text))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .title(format!(" {} ", t("procedure_picker.search")))
+                        .border_style(Style::default().fg(theme().focus))
+                        .style(Style::default().bg(theme().input)),
+                ),
+            layout[0],
+        );
+
+        let matches = self.matches();
+        let name_width = (layout[1].width as usize).saturating_sub(30);
+        let items: Vec<ListItem> = if self.catalog.is_empty() {
+            vec![ListItem::new(t("procedure_picker.empty"))
+                .style(Style::default().fg(theme().inactive))]
+        } else if matches.is_empty() {
+            vec![ListItem::new(t("procedure_picker.no_match"))
+                .style(Style::default().fg(theme().inactive))]
+        } else {
+            matches
+                .iter()
+                .map(|p| {
+                    ListItem::new(format!(
+                        "{:<12} {:<width$} {:>10}",
+                        truncate(&p.code, 12),
+                        truncate(&p.name, name_width),
+                        money(p.price),
+                        width = name_width
+                    ))
+                    .style(Style::default().fg(theme().text))
+                })
+                .collect()
+        };
+        let list = List::new(items)
+            .highlight_style(
+                Style::default()
+                    .bg(theme().highlight)
+                    .fg(theme().focus)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(list, layout[1], &mut self.list_state.clone());
+
+        frame.render_widget(
+            Paragraph::new(t("procedure_picker.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[2],
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use crate::db::worker::Task;
+use crate::i18n::t;
+use crate::theme::theme;
+use crate::tui::Frame;
+use ratatui::{prelude::*, widgets::*};
+
+const DIALOG_WIDTH: u16 = 50;
+const DIALOG_HEIGHT: u16 = 7;
+
+/// Draws a centred dialog for a running background task: a spinner with
+/// `label`, a gauge once the job reports its size, and a cancel hint.
+pub fn render_progress<T>(frame: &mut Frame, task: &Task<T>, label: &str, cancellable: bool) {
+    let area = frame.area();
+    let width = DIALOG_WIDTH.min(area.width);
+    let height = DIALOG_HEIGHT.min(area.height);
+    let dialog_area = Rect::new(
+        area.x + area.width.saturating_sub(width) / 2,
+        area.y + area.height.saturating_sub(height) / 2,
+        width,
+        height,
+    );
+    frame.render_widget(Clear, dialog_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme().focus))
+        .style(Style::default().bg(theme().dialog));
+    let inner = block.inner(dialog_area);
+    frame.render_widget(block, dialog_area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .horizontal_margin(1)
+        .split(inner);
+
+    frame.render_widget(
+        Paragraph::new(format!("{} {}", task.spinner(), label))
+            .style(
+                Style::default()
+                    .fg(theme().title)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .alignment(Alignment::Center),
+        layout[0],
+    );
+
+    if let Some(ratio) = task.ratio() {
+        frame.render_widget(
+            Gauge::default()
+                .gauge_style(Style::default().fg(theme().success).bg(theme().input))
+                .ratio(ratio),
+            layout[2],
+        );
+    }
+
+    if cancellable {
+        frame.render_widget(
+            Paragraph::new(t("progress.cancel_hint"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use super::text_input::TextInput;
+use crate::components::toast::{self, Toast};
+use crate::i18n::{t, tf};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::ui_state::{self, SavedSearch, ViewState};
+use crate::utils::truncate;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+
+pub enum SavedSearchOutcome {
+    Apply(ViewState),
+    Closed,
+}
+
+/// Whether `key` opens the saved searches of a list screen.
+pub fn is_open_key(key: KeyEvent) -> bool {
+    key.code == KeyCode::Char('f') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// The saved search on `screen` that the digit `key` applies, if any.
+pub fn shortcut(screen: &str, key: KeyEvent) -> Option<ViewState> {
+    let KeyCode::Char(c @ '1'..='9') = key.code else {
+        return None;
+    };
+    let index = c.to_digit(10)? as usize - 1;
+    let saved = ui_state::saved_searches(screen).into_iter().nth(index)?;
+    toast::push(Toast::success(tf(
+        "saved_searches.showing",
+        &[("name", &saved.name)],
+    )));
+    Some(saved.view())
+}
+
+/// A popup listing the searches the user saved on one list screen: apply one,
+/// save the screen's current search and filters under a name, or delete one.
+pub struct SavedSearchesPopup {
+    screen: &'static str,
+    current: ViewState,
+    searches: Vec<SavedSearch>,
+    list_state: ListState,
+    /// The name being typed for the current search.
+    naming: Option<TextInput>,
+}
+
+impl SavedSearchesPopup {
+    /// `current` is the search and filters shown on `screen` now.
+    pub fn new(screen: &'static str, current: ViewState) -> Self {
+        let searches = ui_state::saved_searches(screen);
+        let mut list_state = ListState::default();
+        list_state.select((!searches.is_empty()).then_some(0));
+        Self {
+            screen,
+            current,
+            searches,
+            list_state,
+            naming: None,
+        }
+    }
+
+    fn apply(&self, index: usize) -> Option<SavedSearchOutcome> {
+        let saved = self.searches.get(index)?;
+        toast::push(Toast::success(tf(
+            "saved_searches.showing",
+            &[("name", &saved.name)],
+        )));
+        Some(SavedSearchOutcome::Apply(saved.view()))
+    }
+
+    fn save_current(&mut self, name: &str) {
+        match ui_state::save_search(self.screen, name, &self.current) {
+            Ok(()) => {
+                toast::push(Toast::success(tf(
+                    "saved_searches.saved",
+                    &[("name", &name)],
+                )));
+                self.searches = ui_state::saved_searches(self.screen);
+                let index = self
+                    .searches
+                    .iter()
+                    .position(|s| s.name.eq_ignore_ascii_case(name));
+                self.list_state.select(index);
+            }
+            Err(e) => toast::push(Toast::error(tf(
+                "saved_searches.save_failed",
+                &[("error", &e)],
+            ))),
+        }
+    }
+
+    fn delete_selected(&mut self) {
+        let Some(index) = self.list_state.selected() else {
+            return;
+        };
+        let name = self.searches[index].name.clone();
+        if let Err(e) = ui_state::delete_saved_search(self.screen, index) {
+            toast::push(Toast::error(tf(
+                "saved_searches.delete_failed",
+                &[("error", &e)],
+            )));
+        }
+        self.searches = ui_state::saved_searches(self.screen);
+        toast::push(Toast::success(tf(
+            "saved_searches.deleted",
+            &[("name", &name)],
+        )));
+        let count = self.searches.len();
+        self.list_state
+            .select((count > 0).then(|| index.min(count - 1)));
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<SavedSearchOutcome> {
+        if let Some(name) = &mut self.naming {
+            match key.code {
+                KeyCode::Enter => {
+                    let name = name.value().trim().to_string();
+                    if name.is_empty() {
+                        toast::push(Toast::error(t("saved_searches.name_required")));
+                    } else {
+                        self.naming = None;
+                        self.save_current(&name);
+                    }
+                }
+                KeyCode::Esc => self.naming = None,
+                _ => {
+                    name.handle_key(key);
+                }
+            }
+            return None;
+        }
+
+        let count = self.searches.len();
+        let selected = self.list_state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc => return Some(SavedSearchOutcome::Closed),
+            KeyCode::Enter => return self.apply(selected),
+            KeyCode::Char(c @ '1'..='9') => {
+                return self.apply(c.to_digit(10).unwrap_or(1) as usize - 1);
+            }
+            KeyCode::Down if count > 0 => self.list_state.select(Some((selected + 1) % count)),
+            KeyCode::Up if count > 0 => {
+                self.list_state.select(Some((selected + count - 1) % count))
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') => self.naming = Some(TextInput::new()),
+            KeyCode::Char('x') | KeyCode::Char('X') | KeyCode::Delete => self.delete_selected(),
+            _ => {}
+        }
+        None
+    }
+
+    pub fn render_popup(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let width = 72.min(area.width);
+        let height = 18.min(area.height);
+        let dialog_area = Rect::new(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, dialog_area);
+        let block = Block::default()
+            .title(format!(" {} ", t("saved_searches.title")))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme().focus))
+            .style(Style::default().bg(theme().dialog));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(1),
+                Constraint::Length(3),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(1)
+            .split(inner);
+
+        let current = SavedSearch {
+            name: String::new(),
+            search: self.current.search.clone(),
+            filters: self.current.filters.clone(),
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", t("saved_searches.now_showing")),
+                    Style::default().fg(theme().inactive),
+                ),
+                Span::styled(current.summary(), Style::default().fg(theme().text)),
+            ])),
+            layout[0],
+        );
+
+        let items: Vec<ListItem> = if self.searches.is_empty() {
+            vec![ListItem::new(t("saved_searches.empty"))
+                .style(Style::default().fg(theme().inactive))]
+        } else {
+            let name_width = usize::from(layout[1].width / 3).max(12);
+            self.searches
+                .iter()
+                .enumerate()
+                .map(|(index, saved)| {
+                    ListItem::new(Line::from(vec![
+                        Span::styled(
+                            format!("{} ", index + 1),
+                            Style::default().fg(theme().accent),
+                        ),
+                        Span::styled(
+                            format!(
+                                "{:<width$} ",
+                                truncate(&saved.name, name_width),
+                                width = name_width
+                            ),
+                            Style::default()
+                                .fg(theme().text)
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                        Span::styled(saved.summary(), Style::default().fg(theme().inactive)),
+                    ]))
+                })
+                .collect()
+        };
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(theme().highlight))
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(list, layout[1], &mut self.list_state.clone());
+
+        if let Some(name) = &self.naming {
+            frame.render_widget(
+                Paragraph::new(name.line(true))
+                    .style(Style::default().fg(theme().text))
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .border_type(BorderType::Rounded)
+                            .title(format!(" {} ", t("saved_searches.save_as")))
+                            .border_style(Style::default().fg(theme().focus))
+                            .style(Style::default().bg(theme().input)),
+                    ),
+                layout[2],
+            );
+        }
+
+        let help = if self.naming.is_some() {
+            t("saved_searches.save_help")
+        } else {
+            t("saved_searches.help")
+        };
+        frame.render_widget(
+            Paragraph::new(help)
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[3],
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::prelude::*;
+
+/// Single-line editable text with an insertion caret.
+///
+/// The cursor is a char index, so multi-byte input never splits a character.
+#[derive(Debug, Clone, Default)]
+pub struct TextInput {
+    value: String,
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor = 0;
+    }
+
+    fn len(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(cursor)
+            .map_or(self.value.len(), |(i, _)| i)
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let index = self.byte_index(self.cursor);
+        self.value.insert(index, c);
+        self.cursor += 1;
+    }
+
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            let index = self.byte_index(self.cursor);
+            self.value.remove(index);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.cursor < self.len() {
+            let index = self.byte_index(self.cursor);
+            self.value.remove(index);
+        }
+    }
+
+    fn previous_word(&self) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut i = self.cursor;
+        while i > 0 && chars[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        while i > 0 && !chars[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        i
+    }
+
+    fn next_word(&self) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut i = self.cursor;
+        while i < chars.len() && !chars[i].is_whitespace() {
+            i += 1;
+        }
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        i
+    }
+
+    fn delete_previous_word(&mut self) {
+        let start = self.previous_word();
+        let (from, to) = (self.byte_index(start), self.byte_index(self.cursor));
+        self.value.replace_range(from..to, "");
+        self.cursor = start;
+    }
+
+    /// Applies an editing key. Returns `false` for keys the input does not use,
+    /// including Left/Right at either end so callers can move focus instead.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('w') if ctrl => self.delete_previous_word(),
+            KeyCode::Char(c) if !ctrl && !alt => self.insert(c),
+            KeyCode::Backspace if ctrl || alt => self.delete_previous_word(),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left if self.cursor > 0 => {
+                self.cursor = if ctrl || alt {
+                    self.previous_word()
+                } else {
+                    self.cursor - 1
+                };
+            }
+            KeyCode::Right if self.cursor < self.len() => {
+                self.cursor = if ctrl || alt {
+                    self.next_word()
+                } else {
+                    self.cursor + 1
+                };
+            }
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.len(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Text as a line with the caret drawn in reverse video when focused.
+    pub fn line(&self, focused: bool) -> Line<'static> {
+        self.render_line(self.value.chars().collect(), focused)
+    }
+
+    /// Like [`TextInput::line`] but with every character replaced by `mask`.
+    pub fn masked_line(&self, focused: bool, mask: char) -> Line<'static> {
+        self.render_line(vec![mask; self.len()], focused)
+    }
+
+    fn render_line(&self, chars: Vec<char>, focused: bool) -> Line<'static> {
+        if !focused {
+            return Line::from(chars.into_iter().collect::<String>());
+        }
+        let before: String = chars[..self.cursor].iter().collect();
+        let at = chars.get(self.cursor).copied().unwrap_or(' ');
+        let after: String = chars.iter().skip(self.cursor + 1).collect();
+        Line::from(vec![
+            Span::raw(before),
+            Span::styled(
+                at.to_string(),
+                Style::default().add_modifier(Modifier::REVERSED),
+            ),
+            Span::raw(after),
+        ])
+    }
+}
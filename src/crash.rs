@@ -0,0 +1,103 @@
+//! Panic handling: restores the terminal, writes a crash report under
+//! `crash_reports/` and tells the user where to find it, instead of leaving a
+//! raw-mode terminal and a backtrace nobody can read.
+
+use crate::components::toast::{self, Toast};
+use crate::utils::datetime;
+use std::backtrace::Backtrace;
+use std::fmt::Write as _;
+use std::fs;
+use std::panic::{self, PanicHookInfo};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use time::OffsetDateTime;
+
+const REPORT_DIR: &str = "crash_reports";
+
+static CONTEXT: Mutex<String> = Mutex::new(String::new());
+
+/// Records what the app is doing, included in any crash report. Called once
+/// per frame, so it only keeps the latest summary.
+pub fn set_context(summary: String) {
+    *CONTEXT.lock().unwrap_or_else(|e| e.into_inner()) = summary;
+}
+
+pub fn install() {
+    panic::set_hook(Box::new(|info| {
+        let backtrace = Backtrace::force_capture();
+        let report = write_report(info, &backtrace);
+
+        // A panic on a background thread only loses that job; the UI keeps
+        // running, so report it in place rather than tearing the screen down.
+        if thread::current().name() != Some("main") {
+            let message = match &report {
+                Ok(path) => format!(
+                    "A background task failed; crash report saved to {}",
+                    path.display()
+                ),
+                Err(_) => "A background task failed".to_string(),
+            };
+            toast::push(Toast::error(message));
+            return;
+        }
+
+        crate::tui::restore();
+        match report {
+            Ok(path) => eprintln!(
+                "Rustoria ran into an unexpected problem and had to close.\n\
+                 A crash report was saved to {}.\n\
+                 Please attach it when reporting the issue.",
+                path.display()
+            ),
+            Err(_) => eprintln!(
+                "Rustoria ran into an unexpected problem and had to close.\n\
+                 {}\n\n{}",
+                info, backtrace
+            ),
+        }
+    }));
+}
+
+fn write_report(info: &PanicHookInfo, backtrace: &Backtrace) -> std::io::Result<PathBuf> {
+    let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+    let stamp = datetime::file_stamp(now);
+    let time = datetime::format_timestamp(now);
+
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("(no message)");
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_else(|| "unknown".to_string());
+    let mut context = CONTEXT.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if context.is_empty() {
+        context = "(crashed before the first frame)".to_string();
+    }
+
+    let mut report = String::new();
+    let _ = writeln!(
+        report,
+        "Rustoria {} crash report",
+        env!("CARGO_PKG_VERSION")
+    );
+    let _ = writeln!(report, "Time: {}", time);
+    let _ = writeln!(
+        report,
+        "Thread: {}",
+        thread::current().name().unwrap_or("unnamed")
+    );
+    let _ = writeln!(report, "Message: {}", message);
+    let _ = writeln!(report, "Location: {}", location);
+    let _ = writeln!(report, "\n-- App state --\n{}", context);
+    let _ = writeln!(report, "\n-- Backtrace --\n{}", backtrace);
+
+    fs::create_dir_all(REPORT_DIR)?;
+    let path = PathBuf::from(REPORT_DIR).join(format!("crash_{}.txt", stamp));
+    fs::write(&path, report)?;
+    Ok(path)
+}
@@ -0,0 +1,72 @@
+//! In-memory copies of the entity lists screens load over and over, kept for
+//! the rest of the session. Every write to a table goes through this module's
+//! `invalidate` before returning, so the next read sees it. Writes made by
+//! another instance sharing the database file change its `data_version`,
+//! which makes every list load again.
+
+use super::pool::{DataVersion, Db};
+use crate::models::{Invoice, MedicalRecord, Patient, StaffMember};
+use anyhow::Result;
+use std::sync::Mutex;
+
+/// A list and the `data_version` of the file it was loaded at.
+pub(super) struct Cached<T>(Mutex<Option<(DataVersion, Vec<T>)>>);
+
+impl<T: Clone> Cached<T> {
+    const fn new() -> Self {
+        Self(Mutex::new(None))
+    }
+
+    /// The cached list, filled by `load` on first use, after invalidation
+    /// and once the file is at another `version`. A failed load leaves the
+    /// cache empty.
+    pub(super) fn get_or_load(
+        &self,
+        version: DataVersion,
+        load: impl FnOnce() -> Result<Vec<T>>,
+    ) -> Result<Vec<T>> {
+        let mut cached = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match &*cached {
+            Some((loaded_at, items)) if *loaded_at == version => return Ok(items.clone()),
+            _ => *cached = None,
+        }
+        let items = load()?;
+        *cached = Some((version, items.clone()));
+        Ok(items)
+    }
+
+    pub(super) fn invalidate(&self) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+/// The cached lists of one [`Db`](super::pool::Db).
+pub(super) struct Caches {
+    pub(super) patients: Cached<Patient>,
+    pub(super) staff: Cached<StaffMember>,
+    pub(super) medical_records: Cached<MedicalRecord>,
+    pub(super) invoices: Cached<Invoice>,
+}
+
+impl Caches {
+    pub(super) const fn new() -> Self {
+        Self {
+            patients: Cached::new(),
+            staff: Cached::new(),
+            medical_records: Cached::new(),
+            invoices: Cached::new(),
+        }
+    }
+
+    pub(super) fn invalidate_all(&self) {
+        self.patients.invalidate();
+        self.staff.invalidate();
+        self.medical_records.invalidate();
+        self.invoices.invalidate();
+    }
+}
+
+/// Drops every list cached for `db`, for changes that can touch any table.
+pub fn invalidate_all(db: &Db) {
+    db.cache().invalidate_all();
+}
@@ -1,25 +1,191 @@
-use crate::models::{Gender, Invoice, MedicalRecord, Patient, StaffMember, StaffRole};
+use crate::auth;
+use crate::hooks::{self, Event};
+use crate::interop::hl7::{self, AdtEvent};
+use crate::models::{
+    discount_percent, Admission, AntenatalVisit, Appointment, AppointmentStatus, ApprovalStatus,
+    BirthRecord, Budget, CarePlan, CashierSession, ContactPreference, DashboardMetrics,
+    DataRequest, DeathRecord, DeliveryStatus, DiagnosisCase, DoctorUtilization, DocumentType,
+    Expense, Gender, HandoverNote, ImagingStudy, Immunization, InsurancePlan, InventoryKind,
+    InventoryTransaction, Invoice, InvoiceDocument, MedicalRecord, NoShowCount, Observation,
+    Patient, PatientDocument, PatientFeedback, PatientInsurance, Payment, PaymentMethod,
+    PlanCoverage, PlannedAdmission, Pregnancy, PrescribingOverride, PriceOverride, Procedure,
+    QueueToken, ReportStatus, ResearchEncounter, StaffLeave, StaffMember, StaffPerformance,
+    StaffRole, StockItem, Stocktake, StocktakeLine, StocktakeStatus, TeleconsultStatus,
+    Teleconsultation, TokenStatus, WaitlistEntry, WaitlistPriority, WaitlistStatus,
+    WebhookDelivery,
+};
+use crate::notify;
+use crate::utils::{barcode, config, datetime, invoice_layout};
+use crate::webhooks::{self, WebhookEvent};
 use anyhow::{anyhow, Context, Result};
 use bcrypt::{hash, verify, DEFAULT_COST};
-use rusqlite::{params, Connection, OptionalExtension};
-use std::path::Path;
-use time::{format_description, Date};
+pub use pool::Db;
+use rusqlite::backup::{Backup, StepResult};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use time::Date;
 
-const DB_NAME: &str = "rustoria.db";
+pub mod cache;
+pub mod pool;
+pub mod sync;
+pub mod worker;
 
-fn get_connection() -> Result<Connection> {
-    let db_path = Path::new(DB_NAME);
-    Connection::open(db_path).context("Failed to open database connection")
+static DB_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// The database file, fixed at startup from the configuration so a path
+/// edited in Settings only takes effect on the next run.
+pub fn db_path() -> &'static Path {
+    DB_PATH.get_or_init(|| config::config().db_path.clone())
+}
+
+/// Version of the schema this build creates, stored in the database file as
+/// `PRAGMA user_version`. Bump it, and extend [`upgrade_db`], whenever the
+/// schema or the columns added in [`create_schema`] change.
+pub const SCHEMA_VERSION: i32 = 26;
+
+/// How the database file compares with the schema this build expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaStatus {
+    /// Ready to use; `created_root` says the default account was just added.
+    Current { created_root: bool },
+    /// Written by an older Rustoria (or before versions were recorded).
+    Older(i32),
+    /// Written by a newer Rustoria.
+    Newer(i32),
+}
+
+/// Counter in the `sequences` table numbering printed invoices.
+pub const INVOICE_SEQUENCE: &str = "invoice";
+/// Counter in the `sequences` table numbering printed receipts.
+pub const RECEIPT_SEQUENCE: &str = "receipt";
+/// Counter in the `sequences` table giving patients their ids, and so the
+/// number in their MRNs, so that the MRN of an erased patient is never handed
+/// out again.
+const MRN_SEQUENCE: &str = "mrn";
+
+/// Takes the next number of the counter `name`: one more than the larger of
+/// its last number and `floor`, the highest number already in use elsewhere.
+/// The increment is a single statement, so two writers never get the same
+/// number; inside a transaction it holds the write lock until commit.
+fn next_in_sequence(conn: &Connection, name: &str, floor: i64) -> Result<i64> {
+    let value = conn.query_row(
+        "INSERT INTO sequences (name, value) VALUES (?1, ?2 + 1)
+         ON CONFLICT (name) DO UPDATE SET value = MAX(value, ?2) + 1
+         RETURNING value",
+        params![name, floor],
+        |row| row.get(0),
+    )?;
+    Ok(value)
+}
+
+/// The id for a patient about to be inserted on `conn`, which should be in a
+/// transaction with the insert.
+pub(crate) fn next_patient_id(conn: &Connection) -> Result<i64> {
+    let highest: i64 = conn.query_row("SELECT COALESCE(MAX(id), 0) FROM patients", [], |row| {
+        row.get(0)
+    })?;
+    next_in_sequence(conn, MRN_SEQUENCE, highest)
+}
+
+/// Creates a new database, or checks an existing one against
+/// [`SCHEMA_VERSION`]. A database written by another version is left as it
+/// is so the user can decide what to do with it.
+pub fn init_db(db: &Db) -> Result<SchemaStatus> {
+    let conn = db.get()?;
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    let has_tables: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'users')",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_tables && version < SCHEMA_VERSION {
+        return Ok(SchemaStatus::Older(version));
+    }
+    if version > SCHEMA_VERSION {
+        return Ok(SchemaStatus::Newer(version));
+    }
+    let created_root = create_schema(&conn)?;
+    Ok(SchemaStatus::Current { created_root })
 }
 
-pub fn init_db() -> Result<()> {
-    let conn = get_connection()?;
+/// Brings a database from an older version up to [`SCHEMA_VERSION`], after
+/// copying the file next to itself as `<name>.v<version>.bak`. Returns whether
+/// the default account was created, like [`init_db`].
+pub fn upgrade_db(db: &Db) -> Result<bool> {
+    let conn = db.get()?;
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    let backup = upgrade_backup_path(db, version);
+    // A copy left by an earlier, failed attempt is the untouched original.
+    if !backup.exists() {
+        conn.execute("VACUUM INTO ?", params![backup.to_string_lossy()])
+            .with_context(|| format!("Failed to back up the database to {}", backup.display()))?;
+    }
+    create_schema(&conn)
+}
+
+/// Where [`upgrade_db`] keeps the copy made before upgrading from `version`.
+pub fn upgrade_backup_path(db: &Db, version: i32) -> PathBuf {
+    let mut name = db.path().as_os_str().to_owned();
+    name.push(format!(".v{}.bak", version));
+    PathBuf::from(name)
+}
 
+/// Creates any missing tables and columns and stamps the file with
+/// [`SCHEMA_VERSION`]. Returns whether the default `root` account had to be
+/// created, so the caller can tell the user about it.
+fn create_schema(conn: &Connection) -> Result<bool> {
     let schema = include_str!("schema.sql");
 
     conn.execute_batch(schema)
         .context("Failed to execute schema")?;
 
+    ensure_column(conn, "medical_records", "created_at", "TEXT")?;
+    ensure_column(conn, "teleconsultations", "checked_in_at", "TEXT")?;
+    ensure_column(conn, "users", "staff_id", "INTEGER")?;
+    ensure_column(conn, "patients", "contact_preference", "TEXT")?;
+    ensure_column(conn, "teleconsultations", "reminder_sent_at", "TEXT")?;
+    ensure_column(conn, "teleconsultations", "checked_out_at", "TEXT")?;
+    ensure_column(conn, "invoices", "created_at", "TEXT")?;
+    ensure_column(conn, "invoices", "duplicate_note", "TEXT")?;
+    ensure_column(conn, "patient_documents", "expires_on", "TEXT")?;
+    ensure_column(conn, "admissions", "isolation", "TEXT")?;
+    ensure_column(conn, "admissions", "infection_flags", "TEXT")?;
+    ensure_column(conn, "admissions", "isolated_since", "TEXT")?;
+    for table in ["patients", "staff"] {
+        ensure_column(conn, table, "city", "TEXT")?;
+        ensure_column(conn, table, "region", "TEXT")?;
+        ensure_column(conn, table, "postcode", "TEXT")?;
+    }
+    ensure_column(conn, "patients", "photo_path", "TEXT")?;
+    ensure_column(conn, "patients", "mrn", "TEXT")?;
+    ensure_column(conn, "appointments", "checked_in_at", "TEXT")?;
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS patients_mrn ON patients (mrn)",
+        [],
+    )?;
+    ensure_column(conn, "admissions", "expected_discharge", "TEXT")?;
+    ensure_column(conn, "medical_records", "procedures", "TEXT")?;
+    ensure_column(conn, "invoices", "procedure_code", "TEXT")?;
+    ensure_column(conn, "invoices", "category", "TEXT")?;
+    ensure_column(conn, "invoices", "insurer_share", "REAL")?;
+    ensure_column(conn, "invoices", "held", "INTEGER NOT NULL DEFAULT 0")?;
+    ensure_column(
+        conn,
+        "medical_records",
+        "author_id",
+        "INTEGER REFERENCES staff(id) ON DELETE SET NULL",
+    )?;
+    close_duplicate_sessions(conn)?;
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS cashier_sessions_open
+         ON cashier_sessions (cashier) WHERE closed_at IS NULL",
+        [],
+    )?;
+    sync::install(conn)?;
+    assign_missing_mrns(conn)?;
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+
     let mut stmt = conn.prepare("SELECT COUNT(*) FROM users WHERE username = ?")?;
     let count: i64 = stmt.query_row(params!["root"], |row| row.get(0))?;
 
@@ -30,15 +196,30 @@ pub fn init_db() -> Result<()> {
             "INSERT INTO users (username, password_hash) VALUES (?, ?)",
             params!["root", hashed_password],
         )?;
-        println!("Created 'root' user with default password.");
     }
 
+    Ok(count == 0)
+}
+
+fn ensure_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+    if !exists {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table, column, definition
+        ))
+        .with_context(|| format!("Failed to add {}.{}", table, column))?;
+    }
     Ok(())
 }
 
-pub fn authenticate_user(username: &str, password: &str) -> Result<i64> {
-    let db_path = Path::new(DB_NAME);
-    let conn = Connection::open(db_path)?;
+pub fn authenticate_user(db: &Db, username: &str, password: &str) -> Result<i64> {
+    let conn = db.get()?;
 
     let mut stmt = conn.prepare("SELECT id, password_hash FROM users WHERE username = ?")?;
     let (user_id, stored_hash): (i64, String) =
@@ -51,23 +232,39 @@ pub fn authenticate_user(username: &str, password: &str) -> Result<i64> {
     }
 }
 
-pub fn create_user(username: &str, password: &str) -> Result<()> {
-    let db_path = Path::new(DB_NAME);
-    let conn = Connection::open(db_path)?;
+pub fn create_user(db: &Db, username: &str, password: &str) -> Result<()> {
+    let conn = db.get()?;
 
     let hashed_password = hash(password, DEFAULT_COST).context("Failed to hash password")?;
 
     conn.execute(
         "INSERT INTO users (username, password_hash) VALUES (?, ?)",
         params![username, hashed_password],
-    )?;
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::SqliteFailure(err, _)
+            if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+        {
+            anyhow!("The username '{}' is already taken", username)
+        }
+        other => other.into(),
+    })?;
 
     Ok(())
 }
 
-pub fn get_username(user_id: i64) -> Result<String> {
-    let db_path = Path::new(DB_NAME);
-    let conn = Connection::open(db_path)?;
+pub fn username_exists(db: &Db, username: &str) -> Result<bool> {
+    let conn = db.get()?;
+    let exists = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM users WHERE username = ?)",
+        params![username],
+        |row| row.get(0),
+    )?;
+    Ok(exists)
+}
+
+pub fn get_username(db: &Db, user_id: i64) -> Result<String> {
+    let conn = db.get()?;
 
     let mut stmt = conn.prepare("SELECT username FROM users WHERE id = ?")?;
     let username: String = stmt.query_row(params![user_id], |row| row.get(0))?;
@@ -75,33 +272,187 @@ pub fn get_username(user_id: i64) -> Result<String> {
     Ok(username)
 }
 
-pub fn create_patient(patient: &Patient) -> Result<()> {
-    let conn = Connection::open(DB_NAME)?;
+/// The staff member `username` works as, if the account has been linked to one.
+pub fn get_user_staff_id(db: &Db, username: &str) -> Result<Option<i64>> {
+    let conn = db.get()?;
+    let staff_id = conn
+        .query_row(
+            "SELECT staff_id FROM users WHERE username = ?",
+            params![username],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(staff_id.flatten())
+}
+
+/// The account linked to staff member `staff_id`, if any.
+pub fn get_staff_account(db: &Db, staff_id: i64) -> Result<Option<String>> {
+    let conn = db.get()?;
+    let username = conn
+        .query_row(
+            "SELECT username FROM users WHERE staff_id = ? ORDER BY id LIMIT 1",
+            params![staff_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(username)
+}
+
+/// Links the account `username` to staff member `staff_id`, or unlinks it
+/// with `None`. The staff member's role becomes the account's, so only an
+/// administrator (`linked_by`) may link accounts; anyone may unlink their
+/// own.
+pub fn link_user_to_staff(
+    db: &Db,
+    linked_by: &str,
+    username: &str,
+    staff_id: Option<i64>,
+) -> Result<()> {
+    let unlinking_own = staff_id.is_none() && linked_by == username;
+    if !unlinking_own && !auth::is_admin(db, linked_by) {
+        return Err(anyhow!(
+            "Only administrators can link accounts to staff members"
+        ));
+    }
+    let conn = db.get()?;
+    let updated = conn.execute(
+        "UPDATE users SET staff_id = ? WHERE username = ?",
+        params![staff_id, username],
+    )?;
+    if updated == 0 {
+        return Err(anyhow!("There is no account named '{}'", username));
+    }
+    Ok(())
+}
+
+fn contact_preference_to_str(preference: ContactPreference) -> &'static str {
+    match preference {
+        ContactPreference::Sms => "Sms",
+        ContactPreference::Email => "Email",
+        ContactPreference::Both => "Both",
+        ContactPreference::None => "None",
+    }
+}
+
+/// Rows from before contact preferences existed hold NULL and default to SMS.
+fn contact_preference_from_str(value: Option<String>) -> ContactPreference {
+    match value.as_deref() {
+        Some("Email") => ContactPreference::Email,
+        Some("Both") => ContactPreference::Both,
+        Some("None") => ContactPreference::None,
+        _ => ContactPreference::Sms,
+    }
+}
+
+/// Inserts `patient` under the next free id, on a connection that should be
+/// in a transaction.
+fn insert_patient(conn: &Connection, patient: &Patient) -> Result<i64> {
+    let id = next_patient_id(conn)?;
     conn.execute(
-        "INSERT INTO patients (first_name, last_name, date_of_birth, gender, address, phone_number, email, medical_history, allergies, current_medications) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        "INSERT INTO patients (id, mrn, first_name, last_name, date_of_birth, gender, address, phone_number, email, medical_history, allergies, current_medications, contact_preference, city, region, postcode) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         params![
+            id,
+            barcode::mrn(&sync::instance_id(conn)?, id),
             patient.first_name,
             patient.last_name,
             patient.date_of_birth,
-            match patient.gender {
-                Gender::Male => "Male",
-                Gender::Female => "Female",
-                Gender::Other => "Other",
-            },
+            patient.gender.label(),
             patient.address,
             patient.phone_number,
             patient.email,
             patient.medical_history,
             patient.allergies,
             patient.current_medications,
+            contact_preference_to_str(patient.contact_preference),
+            patient.city,
+            patient.region,
+            patient.postcode,
         ],
     )?;
+    Ok(id)
+}
+
+/// Numbers the patients that have no medical record number yet: those
+/// registered before numbers included the instance, and any that arrived
+/// from a peer without one. The change is logged, so peers get it too.
+/// Closes all but the newest open session of each cashier, so databases from
+/// before the one-open-session index can take it.
+fn close_duplicate_sessions(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "UPDATE cashier_sessions
+         SET closed_at = datetime('now'),
+             close_note = 'Closed on upgrade: another session was open'
+         WHERE closed_at IS NULL
+           AND id < (SELECT MAX(s.id) FROM cashier_sessions s
+                     WHERE s.cashier = cashier_sessions.cashier AND s.closed_at IS NULL)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn assign_missing_mrns(conn: &Connection) -> Result<()> {
+    let instance = sync::instance_id(conn)?;
+    let ids = conn
+        .prepare("SELECT id FROM patients WHERE mrn IS NULL OR mrn = '' ORDER BY id")?
+        .query_map([], |row| row.get::<_, i64>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for id in ids {
+        conn.execute(
+            "UPDATE patients SET mrn = ? WHERE id = ?",
+            params![barcode::mrn(&instance, id), id],
+        )?;
+    }
     Ok(())
 }
 
-pub fn get_all_patients() -> Result<Vec<Patient>> {
-    let conn = Connection::open(DB_NAME)?;
-    let mut stmt = conn.prepare("SELECT id, first_name, last_name, date_of_birth, gender, address, phone_number, email, medical_history, allergies, current_medications FROM patients")?;
+/// The patient whose medical record number is `code`, as typed or scanned.
+pub fn find_patient_by_mrn(db: &Db, code: &str) -> Result<Option<i64>> {
+    let conn = db.get()?;
+    Ok(conn
+        .query_row(
+            "SELECT id FROM patients WHERE mrn = ?",
+            params![code.trim().to_ascii_uppercase()],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+pub fn create_patient(db: &Db, patient: &Patient) -> Result<i64> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let id = insert_patient(&tx, patient)?;
+    tx.commit()?;
+    db.cache().patients.invalidate();
+    hooks::fire(db, Event::PatientCreated, id, patient);
+    Ok(id)
+}
+
+/// Registers all of `patients` in one transaction, so either every one of
+/// them is added or none is. Returns their ids in order.
+pub fn create_patients_batch(db: &Db, patients: &[Patient]) -> Result<Vec<i64>> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let ids = patients
+        .iter()
+        .map(|patient| insert_patient(&tx, patient))
+        .collect::<Result<Vec<_>>>()?;
+    tx.commit()?;
+    db.cache().patients.invalidate();
+    for (id, patient) in ids.iter().zip(patients) {
+        hooks::fire(db, Event::PatientCreated, *id, patient);
+    }
+    Ok(ids)
+}
+
+pub fn get_all_patients(db: &Db) -> Result<Vec<Patient>> {
+    db.cache()
+        .patients
+        .get_or_load(db.data_version()?, || load_patients(db))
+}
+
+fn load_patients(db: &Db) -> Result<Vec<Patient>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare("SELECT id, first_name, last_name, date_of_birth, gender, address, phone_number, email, medical_history, allergies, current_medications, contact_preference, city, region, postcode, photo_path, mrn FROM patients")?;
 
     let patient_iter = stmt.query_map([], |row| {
         Ok(Patient {
@@ -109,24 +460,19 @@ pub fn get_all_patients() -> Result<Vec<Patient>> {
             first_name: row.get(1)?,
             last_name: row.get(2)?,
             date_of_birth: row.get(3)?,
-            gender: match &*row.get::<_, String>(4)? {
-                "Male" => Gender::Male,
-                "Female" => Gender::Female,
-                "Other" => Gender::Other,
-                _ => {
-                    return Err(rusqlite::Error::InvalidColumnType(
-                        4,
-                        String::from("Invalid gender value"),
-                        rusqlite::types::Type::Text,
-                    ))
-                }
-            },
+            gender: Gender::from(row.get::<_, String>(4)?),
             address: row.get(5)?,
             phone_number: row.get(6)?,
             email: row.get(7)?,
             medical_history: row.get(8)?,
             allergies: row.get(9)?,
             current_medications: row.get(10)?,
+            contact_preference: contact_preference_from_str(row.get(11)?),
+            city: row.get::<_, Option<String>>(12)?.unwrap_or_default(),
+            region: row.get::<_, Option<String>>(13)?.unwrap_or_default(),
+            postcode: row.get::<_, Option<String>>(14)?.unwrap_or_default(),
+            photo_path: row.get(15)?,
+            mrn: row.get::<_, Option<String>>(16)?.unwrap_or_default(),
         })
     })?;
     let mut patients = Vec::new();
@@ -137,9 +483,9 @@ pub fn get_all_patients() -> Result<Vec<Patient>> {
     Ok(patients)
 }
 
-pub fn get_patient(patient_id: i64) -> Result<Patient> {
-    let conn = Connection::open(DB_NAME)?;
-    let mut stmt = conn.prepare("SELECT id, first_name, last_name, date_of_birth, gender, address, phone_number, email, medical_history, allergies, current_medications FROM patients WHERE id = ?")?;
+pub fn get_patient(db: &Db, patient_id: i64) -> Result<Patient> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare("SELECT id, first_name, last_name, date_of_birth, gender, address, phone_number, email, medical_history, allergies, current_medications, contact_preference, city, region, postcode, photo_path, mrn FROM patients WHERE id = ?")?;
 
     let patient: Option<Patient> = stmt
         .query_row(params![patient_id], |row| {
@@ -148,24 +494,19 @@ pub fn get_patient(patient_id: i64) -> Result<Patient> {
                 first_name: row.get(1)?,
                 last_name: row.get(2)?,
                 date_of_birth: row.get(3)?,
-                gender: match &*row.get::<_, String>(4)? {
-                    "Male" => Gender::Male,
-                    "Female" => Gender::Female,
-                    "Other" => Gender::Other,
-                    _ => {
-                        return Err(rusqlite::Error::InvalidColumnType(
-                            4,
-                            String::from("Invalid gender value"),
-                            rusqlite::types::Type::Text,
-                        ))
-                    }
-                },
+                gender: Gender::from(row.get::<_, String>(4)?),
                 address: row.get(5)?,
                 phone_number: row.get(6)?,
                 email: row.get(7)?,
                 medical_history: row.get(8)?,
                 allergies: row.get(9)?,
                 current_medications: row.get(10)?,
+                contact_preference: contact_preference_from_str(row.get(11)?),
+                city: row.get::<_, Option<String>>(12)?.unwrap_or_default(),
+                region: row.get::<_, Option<String>>(13)?.unwrap_or_default(),
+                postcode: row.get::<_, Option<String>>(14)?.unwrap_or_default(),
+                photo_path: row.get(15)?,
+                mrn: row.get::<_, Option<String>>(16)?.unwrap_or_default(),
             })
         })
         .optional()?;
@@ -173,61 +514,194 @@ pub fn get_patient(patient_id: i64) -> Result<Patient> {
     patient.ok_or_else(|| anyhow!("Patient not found"))
 }
 
-pub fn update_patient(patient: &Patient) -> Result<()> {
-    let conn = Connection::open(DB_NAME)?;
+/// Stores where the patient's photo was saved. Kept apart from
+/// [`update_patient`] so an edit screen opened before the photo was taken
+/// does not clear it on save.
+pub fn set_patient_photo(db: &Db, patient_id: i64, path: &str) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "UPDATE patients SET photo_path = ? WHERE id = ?",
+        params![path, patient_id],
+    )?;
+    db.cache().patients.invalidate();
+    Ok(())
+}
+
+pub fn update_patient(db: &Db, patient: &Patient) -> Result<()> {
+    write_patient(&*db.get()?, patient)?;
+    db.cache().patients.invalidate();
+    Ok(())
+}
+
+fn write_patient(conn: &Connection, patient: &Patient) -> Result<()> {
     conn.execute(
-        "UPDATE patients SET first_name = ?, last_name = ?, date_of_birth = ?, gender = ?, address = ?, phone_number = ?, email = ?, medical_history = ?, allergies = ?, current_medications = ? WHERE id = ?",
+        "UPDATE patients SET first_name = ?, last_name = ?, date_of_birth = ?, gender = ?, address = ?, phone_number = ?, email = ?, medical_history = ?, allergies = ?, current_medications = ?, contact_preference = ?, city = ?, region = ?, postcode = ? WHERE id = ?",
         params![
             patient.first_name,
             patient.last_name,
             patient.date_of_birth,
-           match patient.gender {
-                Gender::Male => "Male",
-                Gender::Female => "Female",
-                Gender::Other => "Other",
-            },
+            patient.gender.label(),
             patient.address,
             patient.phone_number,
             patient.email,
             patient.medical_history,
             patient.allergies,
             patient.current_medications,
+            contact_preference_to_str(patient.contact_preference),
+            patient.city,
+            patient.region,
+            patient.postcode,
             patient.id,
         ],
     )?;
     Ok(())
 }
 
-pub fn delete_patient(patient_id: i64) -> Result<()> {
-    let conn = Connection::open(DB_NAME)?;
+pub fn delete_patient(db: &Db, patient_id: i64) -> Result<()> {
+    let conn = db.get()?;
     conn.execute("DELETE FROM patients WHERE id = ?", params![patient_id])?;
+    conn.execute(
+        "DELETE FROM custom_fields WHERE entity = 'patient' AND entity_id = ?",
+        params![patient_id],
+    )?;
+    conn.execute(
+        "DELETE FROM pinned_patients WHERE patient_id = ?",
+        params![patient_id],
+    )?;
+    conn.execute(
+        "DELETE FROM patient_insurance WHERE patient_id = ?",
+        params![patient_id],
+    )?;
+    db.cache().patients.invalidate();
+    Ok(())
+}
+
+/// Tables whose rows belong to a patient through `patient_id`.
+const PATIENT_TABLES: &[&str] = &[
+    "medical_records",
+    "invoices",
+    "payments",
+    "waitlist",
+    "deaths",
+    "patient_documents",
+    "imaging_studies",
+    "teleconsultations",
+    "appointments",
+    "queue_tokens",
+    "patient_feedback",
+    "admissions",
+    "planned_admissions",
+    "observations",
+    "care_plans",
+    "pregnancies",
+    "immunizations",
+    "data_requests",
+];
+
+/// Merges the duplicate `duplicate_id` into `merged`, the surviving patient
+/// with the field values picked from both: everything recorded for the
+/// duplicate moves over, then the duplicate is deleted.
+pub fn merge_patients(db: &Db, merged: &Patient, duplicate_id: i64) -> Result<()> {
+    if merged.id == duplicate_id {
+        return Err(anyhow!("A patient cannot be merged with itself"));
+    }
+    let mut conn = db.get()?;
+    let tx = conn.transaction()?;
+    let deaths: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM deaths WHERE patient_id IN (?, ?)",
+        params![merged.id, duplicate_id],
+        |row| row.get(0),
+    )?;
+    if deaths > 1 {
+        return Err(anyhow!(
+            "Both patients have a death record; delete one before merging"
+        ));
+    }
+    for table in PATIENT_TABLES {
+        tx.execute(
+            &format!("UPDATE {} SET patient_id = ? WHERE patient_id = ?", table),
+            params![merged.id, duplicate_id],
+        )?;
+    }
+    tx.execute(
+        "UPDATE births SET mother_id = ? WHERE mother_id = ?",
+        params![merged.id, duplicate_id],
+    )?;
+    // Custom fields the survivor already has keep the survivor's value.
+    tx.execute(
+        "INSERT OR IGNORE INTO custom_fields (entity, entity_id, name, value)
+         SELECT entity, ?, name, value FROM custom_fields WHERE entity = 'patient' AND entity_id = ?",
+        params![merged.id, duplicate_id],
+    )?;
+    tx.execute(
+        "DELETE FROM custom_fields WHERE entity = 'patient' AND entity_id = ?",
+        params![duplicate_id],
+    )?;
+    tx.execute(
+        "INSERT OR IGNORE INTO pinned_patients (username, patient_id, pinned_at)
+         SELECT username, ?, pinned_at FROM pinned_patients WHERE patient_id = ?",
+        params![merged.id, duplicate_id],
+    )?;
+    tx.execute(
+        "DELETE FROM pinned_patients WHERE patient_id = ?",
+        params![duplicate_id],
+    )?;
+    // A survivor already insured keeps their own plan.
+    tx.execute(
+        "UPDATE OR IGNORE patient_insurance SET patient_id = ? WHERE patient_id = ?",
+        params![merged.id, duplicate_id],
+    )?;
+    tx.execute(
+        "DELETE FROM patient_insurance WHERE patient_id = ?",
+        params![duplicate_id],
+    )?;
+    tx.execute("DELETE FROM patients WHERE id = ?", params![duplicate_id])?;
+    write_patient(&tx, merged)?;
+    tx.commit()?;
+    cache::invalidate_all(db);
     Ok(())
 }
 
-pub fn create_staff_member(staff_member: &StaffMember) -> Result<()> {
-    let conn = Connection::open(DB_NAME)?;
+fn staff_role_to_str(role: &StaffRole) -> &'static str {
+    match role {
+        StaffRole::Doctor => "Doctor",
+        StaffRole::Nurse => "Nurse",
+        StaffRole::Admin => "Admin",
+        StaffRole::Technician => "Technician",
+        StaffRole::Accountant => "Accountant",
+    }
+}
+
+pub fn create_staff_member(db: &Db, staff_member: &StaffMember) -> Result<()> {
+    let conn = db.get()?;
     conn.execute(
-        "INSERT INTO staff (name, role, phone_number, email, address) VALUES (?, ?, ?, ?, ?)",
+        "INSERT INTO staff (name, role, phone_number, email, address, city, region, postcode) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
         params![
             staff_member.name,
-            match staff_member.role {
-                StaffRole::Doctor => "Doctor",
-                StaffRole::Nurse => "Nurse",
-                StaffRole::Admin => "Admin",
-                StaffRole::Technician => "Technician",
-            },
+            staff_role_to_str(&staff_member.role),
             staff_member.phone_number,
             staff_member.email,
             staff_member.address,
+            staff_member.city,
+            staff_member.region,
+            staff_member.postcode,
         ],
     )?;
+    db.cache().staff.invalidate();
     Ok(())
 }
 
-pub fn get_all_staff() -> Result<Vec<StaffMember>> {
-    let conn = Connection::open(DB_NAME)?;
-    let mut stmt =
-        conn.prepare("SELECT id, name, role, phone_number, email, address FROM staff")?;
+pub fn get_all_staff(db: &Db) -> Result<Vec<StaffMember>> {
+    db.cache()
+        .staff
+        .get_or_load(db.data_version()?, || load_staff(db))
+}
+
+fn load_staff(db: &Db) -> Result<Vec<StaffMember>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, name, role, phone_number, email, address, city, region, postcode FROM staff",
+    )?;
     let staff_iter = stmt.query_map([], |row| {
         Ok(StaffMember {
             id: row.get(0)?,
@@ -237,6 +711,7 @@ pub fn get_all_staff() -> Result<Vec<StaffMember>> {
                 "Nurse" => StaffRole::Nurse,
                 "Admin" => StaffRole::Admin,
                 "Technician" => StaffRole::Technician,
+                "Accountant" => StaffRole::Accountant,
                 _ => {
                     return Err(rusqlite::Error::InvalidColumnType(
                         2,
@@ -248,6 +723,9 @@ pub fn get_all_staff() -> Result<Vec<StaffMember>> {
             phone_number: row.get(3)?,
             email: row.get(4)?,
             address: row.get(5)?,
+            city: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+            region: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+            postcode: row.get::<_, Option<String>>(8)?.unwrap_or_default(),
         })
     })?;
 
@@ -258,10 +736,11 @@ pub fn get_all_staff() -> Result<Vec<StaffMember>> {
     Ok(staff)
 }
 
-pub fn get_staff(staff_id: i64) -> Result<StaffMember> {
-    let conn = Connection::open(DB_NAME)?;
-    let mut stmt = conn
-        .prepare("SELECT id, name, role, phone_number, email, address FROM staff WHERE id = ?")?;
+pub fn get_staff(db: &Db, staff_id: i64) -> Result<StaffMember> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, name, role, phone_number, email, address, city, region, postcode FROM staff WHERE id = ?",
+    )?;
 
     let staff_member: Option<StaffMember> = stmt
         .query_row(params![staff_id], |row| {
@@ -273,6 +752,7 @@ pub fn get_staff(staff_id: i64) -> Result<StaffMember> {
                     "Nurse" => StaffRole::Nurse,
                     "Admin" => StaffRole::Admin,
                     "Technician" => StaffRole::Technician,
+                    "Accountant" => StaffRole::Accountant,
                     _ => {
                         return Err(rusqlite::Error::InvalidColumnType(
                             2,
@@ -284,6 +764,9 @@ pub fn get_staff(staff_id: i64) -> Result<StaffMember> {
                 phone_number: row.get(3)?,
                 email: row.get(4)?,
                 address: row.get(5)?,
+                city: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+                region: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+                postcode: row.get::<_, Option<String>>(8)?.unwrap_or_default(),
             })
         })
         .optional()?;
@@ -291,50 +774,67 @@ pub fn get_staff(staff_id: i64) -> Result<StaffMember> {
     staff_member.ok_or_else(|| anyhow!("Staff member not found"))
 }
 
-pub fn update_staff_member(staff_member: &StaffMember) -> Result<()> {
-    let conn = Connection::open(DB_NAME)?;
+/// Saves `staff_member`. The role decides what linked accounts may do, so
+/// only an administrator (`changed_by`) may change it.
+pub fn update_staff_member(db: &Db, changed_by: &str, staff_member: &StaffMember) -> Result<()> {
+    let conn = db.get()?;
+    let role: String = conn.query_row(
+        "SELECT role FROM staff WHERE id = ?",
+        params![staff_member.id],
+        |row| row.get(0),
+    )?;
+    let new_role = staff_role_to_str(&staff_member.role);
+    if role != new_role && !auth::is_admin(db, changed_by) {
+        return Err(anyhow!(
+            "Only administrators can change a staff member's role"
+        ));
+    }
     conn.execute(
-        "UPDATE staff SET name = ?, role = ?, phone_number = ?, email = ?, address = ? WHERE id = ?",
+        "UPDATE staff SET name = ?, role = ?, phone_number = ?, email = ?, address = ?, city = ?, region = ?, postcode = ? WHERE id = ?",
         params![
             staff_member.name,
-            match staff_member.role {
-                StaffRole::Doctor => "Doctor",
-                StaffRole::Nurse => "Nurse",
-                StaffRole::Admin => "Admin",
-                StaffRole::Technician => "Technician",
-            },
+            new_role,
             staff_member.phone_number,
             staff_member.email,
             staff_member.address,
+            staff_member.city,
+            staff_member.region,
+            staff_member.postcode,
             staff_member.id,
         ],
     )?;
+    db.cache().staff.invalidate();
     Ok(())
 }
 
-pub fn assign_staff_shift(staff_id: i64, date: &Date, shift: &str) -> Result<()> {
-    let conn = Connection::open("rustoria.db")?;
+pub fn assign_staff_shift(db: &Db, staff_id: i64, date: &Date, shift: &str) -> Result<()> {
+    let conn = db.get()?;
 
-    let date_str = date
-        .format(&format_description::parse("[year]-[month]-[day]").unwrap())
-        .unwrap();
+    let date_str = datetime::format_date(*date);
 
     conn.execute(
         "INSERT INTO shifts (staff_id, date, shift) VALUES (?, ?, ?)",
         params![staff_id, date_str, shift],
     )?;
+    hooks::fire(
+        db,
+        Event::ShiftAssigned,
+        conn.last_insert_rowid(),
+        serde_json::json!({ "staff_id": staff_id, "date": date_str, "shift": shift }),
+    );
 
     Ok(())
 }
 
-pub fn delete_staff_member(staff_id: i64) -> Result<()> {
-    let conn = Connection::open(DB_NAME)?;
+pub fn delete_staff_member(db: &Db, staff_id: i64) -> Result<()> {
+    let conn = db.get()?;
     conn.execute("DELETE FROM staff WHERE id = ?", params![staff_id])?;
+    db.cache().staff.invalidate();
     Ok(())
 }
 
-pub fn get_assigned_shifts_for_staff(staff_id: i64) -> Result<Vec<(Date, String)>> {
-    let conn = Connection::open(DB_NAME)?;
+pub fn get_assigned_shifts_for_staff(db: &Db, staff_id: i64) -> Result<Vec<(Date, String)>> {
+    let conn = db.get()?;
 
     let mut stmt =
         conn.prepare("SELECT date, shift FROM shifts WHERE staff_id = ? ORDER BY date")?;
@@ -342,56 +842,13 @@ pub fn get_assigned_shifts_for_staff(staff_id: i64) -> Result<Vec<(Date, String)
     let shifts_iter = stmt.query_map(params![staff_id], |row| {
         let date_str: String = row.get(0)?;
         let shift: String = row.get(1)?;
-
-        let parts: Vec<&str> = date_str.split('-').collect();
-        if parts.len() != 3 {
-            return Err(rusqlite::Error::InvalidColumnType(
-                0,
-                format!("Invalid date format: {}", date_str),
-                rusqlite::types::Type::Text,
-            ));
-        }
-
-        let year = parts[0].parse::<i32>().map_err(|_| {
-            rusqlite::Error::InvalidColumnType(
-                0,
-                format!("Invalid year: {}", parts[0]),
-                rusqlite::types::Type::Text,
-            )
-        })?;
-
-        let month = parts[1].parse::<u8>().map_err(|_| {
-            rusqlite::Error::InvalidColumnType(
-                0,
-                format!("Invalid month: {}", parts[1]),
-                rusqlite::types::Type::Text,
-            )
-        })?;
-
-        let day = parts[2].parse::<u8>().map_err(|_| {
-            rusqlite::Error::InvalidColumnType(
-                0,
-                format!("Invalid day: {}", parts[2]),
-                rusqlite::types::Type::Text,
-            )
-        })?;
-
-        let month = time::Month::try_from(month).map_err(|_| {
-            rusqlite::Error::InvalidColumnType(
-                0,
-                format!("Invalid month number: {}", month),
-                rusqlite::types::Type::Text,
-            )
-        })?;
-
-        let date = time::Date::from_calendar_date(year, month, day).map_err(|e| {
+        let date = datetime::parse_date(&date_str).ok_or_else(|| {
             rusqlite::Error::InvalidColumnType(
                 0,
-                format!("Invalid date components: {}", e),
+                format!("Invalid date format: {}", date_str),
                 rusqlite::types::Type::Text,
             )
         })?;
-
         Ok((date, shift))
     })?;
 
@@ -403,139 +860,4124 @@ pub fn get_assigned_shifts_for_staff(staff_id: i64) -> Result<Vec<(Date, String)
     Ok(shifts)
 }
 
-pub fn create_medical_record(record: &MedicalRecord) -> Result<()> {
-    let conn = Connection::open("rustoria.db")?;
+/// Every shift dated `from` to `to` inclusive (`YYYY-MM-DD`), as
+/// (date, shift, staff name) in the order the day runs.
+pub fn get_roster(db: &Db, from: &str, to: &str) -> Result<Vec<(String, String, String)>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT sh.date, sh.shift, s.name FROM shifts sh JOIN staff s ON s.id = sh.staff_id
+         WHERE sh.date BETWEEN ? AND ?
+         ORDER BY sh.date,
+                  CASE sh.shift WHEN 'Morning' THEN 0 WHEN 'Afternoon' THEN 1 ELSE 2 END,
+                  s.name",
+    )?;
+    let roster = stmt
+        .query_map(params![from, to], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(roster)
+}
+
+/// Every shift as (id, staff id, date, shift), the date as stored so that
+/// malformed ones come through for the integrity report.
+pub fn get_all_shifts(db: &Db) -> Result<Vec<(i64, i64, String, String)>> {
+    let conn = db.get()?;
+    let mut stmt =
+        conn.prepare("SELECT id, staff_id, date, shift FROM shifts ORDER BY date, id")?;
+    let shifts = stmt
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(shifts)
+}
+
+pub fn delete_shift(db: &Db, shift_id: i64) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute("DELETE FROM shifts WHERE id = ?", params![shift_id])?;
+    Ok(())
+}
+
+pub fn register_leave(db: &Db, leave: &StaffLeave) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO staff_leave (staff_id, start_date, end_date, reason) VALUES (?, ?, ?, ?)",
+        params![
+            leave.staff_id,
+            leave.start_date,
+            leave.end_date,
+            leave.reason
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Every leave overlapping `from` to `to` inclusive (`YYYY-MM-DD`).
+pub fn get_leave_between(db: &Db, from: &str, to: &str) -> Result<Vec<StaffLeave>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, staff_id, start_date, end_date, reason FROM staff_leave
+         WHERE start_date <= ? AND end_date >= ? ORDER BY start_date, id",
+    )?;
+    let leave = stmt
+        .query_map(params![to, from], |row| {
+            Ok(StaffLeave {
+                id: row.get(0)?,
+                staff_id: row.get(1)?,
+                start_date: row.get(2)?,
+                end_date: row.get(3)?,
+                reason: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(leave)
+}
+
+/// The shifts that regenerating `from` to `to` would create, as
+/// (staff id, date, shift): the week before `from`, repeated over the
+/// range. Staff members who have since left are skipped.
+fn regenerated_shifts(conn: &Connection, from: Date, to: Date) -> Result<Vec<(i64, Date, String)>> {
+    let week_start = from - time::Duration::days(7);
+    let mut stmt = conn.prepare(
+        "SELECT sh.staff_id, sh.date, sh.shift FROM shifts sh JOIN staff s ON s.id = sh.staff_id
+         WHERE sh.date BETWEEN ? AND ?",
+    )?;
+    let pattern = stmt
+        .query_map(
+            params![
+                datetime::format_date(week_start),
+                datetime::format_date(from - time::Duration::days(1))
+            ],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get(2)?)),
+        )?
+        .collect::<rusqlite::Result<Vec<(i64, String, String)>>>()?;
+
+    let mut shifts = Vec::new();
+    let mut date = from;
+    while date <= to {
+        let source = week_start + time::Duration::days((date - from).whole_days() % 7);
+        let source = datetime::format_date(source);
+        for (staff_id, _, shift) in pattern.iter().filter(|(_, d, _)| *d == source) {
+            shifts.push((*staff_id, date, shift.clone()));
+        }
+        date += time::Duration::days(1);
+    }
+    Ok(shifts)
+}
+
+/// What resetting the roster from `from` to `to` would do: the shifts
+/// removed, and the shifts created when `regenerate` is set.
+pub fn preview_roster_reset(
+    db: &Db,
+    from: Date,
+    to: Date,
+    regenerate: bool,
+) -> Result<(i64, usize)> {
+    let conn = db.get()?;
+    let removed = conn.query_row(
+        "SELECT COUNT(*) FROM shifts WHERE date BETWEEN ? AND ?",
+        params![datetime::format_date(from), datetime::format_date(to)],
+        |row| row.get(0),
+    )?;
+    let created = if regenerate {
+        regenerated_shifts(&conn, from, to)?.len()
+    } else {
+        0
+    };
+    Ok((removed, created))
+}
+
+/// Removes every shift from `from` to `to` inclusive and, when `regenerate`
+/// is set, fills the range again from the week before it. All or nothing.
+pub fn reset_roster(db: &Db, from: Date, to: Date, regenerate: bool) -> Result<(usize, usize)> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction()?;
+    let shifts = if regenerate {
+        regenerated_shifts(&tx, from, to)?
+    } else {
+        Vec::new()
+    };
+    let removed = tx.execute(
+        "DELETE FROM shifts WHERE date BETWEEN ? AND ?",
+        params![datetime::format_date(from), datetime::format_date(to)],
+    )?;
+    let mut created = Vec::new();
+    for (staff_id, date, shift) in shifts {
+        let date = datetime::format_date(date);
+        tx.execute(
+            "INSERT INTO shifts (staff_id, date, shift) VALUES (?, ?, ?)",
+            params![staff_id, date, shift],
+        )?;
+        created.push((tx.last_insert_rowid(), staff_id, date, shift));
+    }
+    tx.commit()?;
+    for (id, staff_id, date, shift) in &created {
+        hooks::fire(
+            db,
+            Event::ShiftAssigned,
+            *id,
+            serde_json::json!({ "staff_id": staff_id, "date": date, "shift": shift }),
+        );
+    }
+    Ok((removed, created.len()))
+}
+
+pub fn create_medical_record(db: &Db, record: &MedicalRecord) -> Result<i64> {
+    let conn = db.get()?;
     conn.execute(
-        "INSERT INTO medical_records (patient_id, doctor_notes, nurse_notes, diagnosis, prescription) VALUES (?, ?, ?, ?, ?)",
+        "INSERT INTO medical_records (patient_id, doctor_notes, nurse_notes, diagnosis, prescription, procedures, author_id, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, datetime('now'))",
         params![
             record.patient_id,
             record.doctor_notes,
             record.nurse_notes,
             record.diagnosis,
-            record.prescription
+            record.prescription,
+            record.procedures,
+            record.author_id
         ],
     )?;
-    Ok(())
+    db.cache().medical_records.invalidate();
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn get_all_medical_records(db: &Db) -> Result<Vec<MedicalRecord>> {
+    db.cache()
+        .medical_records
+        .get_or_load(db.data_version()?, || load_medical_records(db))
+}
+
+const MEDICAL_RECORD_COLUMNS: &str =
+    "id, patient_id, doctor_notes, nurse_notes, diagnosis, prescription, procedures, author_id";
+
+fn medical_record_from_row(row: &rusqlite::Row) -> rusqlite::Result<MedicalRecord> {
+    Ok(MedicalRecord {
+        id: row.get(0)?,
+        patient_id: row.get(1)?,
+        doctor_notes: row.get(2)?,
+        nurse_notes: row.get(3)?,
+        diagnosis: row.get(4)?,
+        prescription: row.get(5)?,
+        procedures: row.get(6)?,
+        author_id: row.get(7)?,
+    })
 }
 
-pub fn get_all_medical_records() -> Result<Vec<MedicalRecord>> {
-    let conn = Connection::open("rustoria.db")?;
-    let mut stmt = conn.prepare("SELECT * FROM medical_records")?;
+fn load_medical_records(db: &Db) -> Result<Vec<MedicalRecord>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM medical_records",
+        MEDICAL_RECORD_COLUMNS
+    ))?;
     let records = stmt
-        .query_map([], |row| {
-            Ok(MedicalRecord {
-                id: row.get(0)?,
-                patient_id: row.get(1)?,
-                doctor_notes: row.get(2)?,
-                nurse_notes: row.get(3)?,
-                diagnosis: row.get(4)?,
-                prescription: row.get(5)?,
-            })
-        })?
+        .query_map([], medical_record_from_row)?
         .collect::<Result<Vec<_>, _>>()?;
     Ok(records)
 }
 
-pub fn get_medical_record(record_id: i64) -> Result<MedicalRecord> {
-    let conn = Connection::open("rustoria.db")?;
-    let mut stmt = conn.prepare("SELECT * FROM medical_records WHERE id = ?")?;
-    let record = stmt.query_row(params![record_id], |row| {
-        Ok(MedicalRecord {
-            id: row.get(0)?,
-            patient_id: row.get(1)?,
-            doctor_notes: row.get(2)?,
-            nurse_notes: row.get(3)?,
-            diagnosis: row.get(4)?,
-            prescription: row.get(5)?,
-        })
-    })?;
+pub fn get_medical_record(db: &Db, record_id: i64) -> Result<MedicalRecord> {
+    let conn = db.get()?;
+    let record = conn.query_row(
+        &format!(
+            "SELECT {} FROM medical_records WHERE id = ?",
+            MEDICAL_RECORD_COLUMNS
+        ),
+        [record_id],
+        medical_record_from_row,
+    )?;
     Ok(record)
 }
 
-pub fn update_medical_record(record: &MedicalRecord) -> Result<()> {
-    let conn = Connection::open("rustoria.db")?;
+pub fn update_medical_record(db: &Db, record: &MedicalRecord) -> Result<()> {
+    let conn = db.get()?;
     conn.execute(
-        "UPDATE medical_records SET patient_id = ?, doctor_notes = ?, nurse_notes = ?, diagnosis = ?, prescription = ? WHERE id = ?",
+        "UPDATE medical_records SET patient_id = ?, doctor_notes = ?, nurse_notes = ?, diagnosis = ?, prescription = ?, procedures = ? WHERE id = ?",
         params![
             record.patient_id,
             record.doctor_notes,
             record.nurse_notes,
             record.diagnosis,
             record.prescription,
+            record.procedures,
             record.id
         ],
     )?;
+    db.cache().medical_records.invalidate();
     Ok(())
 }
 
-pub fn delete_medical_record(record_id: i64) -> Result<()> {
-    let conn = Connection::open("rustoria.db")?;
+pub fn delete_medical_record(db: &Db, record_id: i64) -> Result<()> {
+    let conn = db.get()?;
     conn.execute(
         "DELETE FROM medical_records WHERE id = ?",
         params![record_id],
     )?;
+    db.cache().medical_records.invalidate();
     Ok(())
 }
 
-pub fn create_invoice(invoice: &Invoice) -> Result<i64> {
-    let conn = Connection::open("rustoria.db")?;
+/// Writes `record` and `immunization` for every patient in `patient_ids`,
+/// linking each dose to its record. Either all of them are saved or, if any
+/// insert fails, none are. Returns how many patients were recorded.
+pub fn record_immunizations(
+    db: &Db,
+    patient_ids: &[i64],
+    record: &MedicalRecord,
+    immunization: &Immunization,
+) -> Result<usize> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    for &patient_id in patient_ids {
+        tx.execute(
+            "INSERT INTO medical_records (patient_id, doctor_notes, nurse_notes, diagnosis, prescription, procedures, author_id, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, datetime('now'))",
+            params![
+                patient_id,
+                record.doctor_notes,
+                record.nurse_notes,
+                record.diagnosis,
+                record.prescription,
+                record.procedures,
+                record.author_id
+            ],
+        )?;
+        let record_id = tx.last_insert_rowid();
+        tx.execute(
+            "INSERT INTO immunizations (patient_id, medical_record_id, vaccine, dose, lot_number, given_on, given_by, campaign) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                patient_id,
+                record_id,
+                immunization.vaccine,
+                immunization.dose,
+                immunization.lot_number,
+                immunization.given_on,
+                immunization.given_by,
+                immunization.campaign,
+            ],
+        )?;
+    }
+    tx.commit()?;
+    db.cache().medical_records.invalidate();
+    Ok(patient_ids.len())
+}
+
+/// The date each patient last had `vaccine`, matched without regard to case,
+/// by patient ID.
+pub fn get_last_immunizations(db: &Db, vaccine: &str) -> Result<Vec<(i64, String)>> {
+    let conn = db.get()?;
     let mut stmt = conn.prepare(
-        "INSERT INTO invoices (patient_id, item, quantity, cost)
-        VALUES (?, ?, ?, ?)",
+        "SELECT patient_id, MAX(given_on) FROM immunizations
+         WHERE vaccine = ? COLLATE NOCASE GROUP BY patient_id",
     )?;
-    stmt.execute((
-        &invoice.patient_id,
-        &invoice.item,
-        &invoice.quantity,
-        &invoice.cost,
-    ))?;
-    Ok(conn.last_insert_rowid())
+    let doses = stmt
+        .query_map(params![vaccine.trim()], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(doses)
 }
 
-pub fn get_invoice(id: i64) -> Result<Invoice> {
-    let conn = Connection::open("rustoria.db")?;
-    let mut stmt = conn.prepare("SELECT * FROM invoices WHERE id = ?")?;
-    let invoice = stmt.query_row([id], |row| {
-        Ok(Invoice {
-            id: row.get(0)?,
-            patient_id: row.get(1)?,
-            item: row.get(2)?,
-            quantity: row.get(3)?,
-            cost: row.get(4)?,
-        })
-    })?;
+/// Raises `invoice`, which `billed_by` may only do at a price they may bill
+/// without an approver.
+pub fn create_invoice(db: &Db, invoice: &Invoice, billed_by: &str) -> Result<i64> {
+    ensure_price_allowed(db, billed_by, invoice)?;
+    let mut conn = db.get()?;
+    // Immediate, so two invoices saved at once cannot both use the last of
+    // an insurance cap.
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let invoice = insert_invoice(&tx, invoice)?;
+    tx.commit()?;
+    db.cache().invoices.invalidate();
+    Ok(invoice.id)
+}
+
+/// Inserts `invoice` with its insurer share worked out, returning it as
+/// saved.
+fn insert_invoice(conn: &Connection, invoice: &Invoice) -> Result<Invoice> {
+    let mut invoice = Invoice {
+        insurer_share: insurer_share(conn, invoice)?,
+        ..invoice.clone()
+    };
+    conn.execute(
+        "INSERT INTO invoices (patient_id, item, quantity, cost, created_at, duplicate_note, procedure_code, category, insurer_share, held)
+        VALUES (?, ?, ?, ?, datetime('now'), ?, ?, ?, ?, ?)",
+        params![
+            invoice.patient_id,
+            invoice.item,
+            invoice.quantity,
+            invoice.cost,
+            invoice.duplicate_note,
+            invoice.procedure_code,
+            invoice.category,
+            invoice.insurer_share,
+            invoice.held,
+        ],
+    )?;
+    invoice.id = conn.last_insert_rowid();
     Ok(invoice)
 }
 
-pub fn get_all_invoices() -> Result<Vec<Invoice>> {
-    let conn = Connection::open("rustoria.db")?;
-    let mut stmt = conn.prepare("SELECT * FROM invoices")?;
-    let invoices = stmt
-        .query_map([], |row| {
-            Ok(Invoice {
+const INVOICE_COLUMNS: &str =
+    "id, patient_id, item, quantity, cost, created_at, duplicate_note, procedure_code, category, insurer_share, held";
+
+fn invoice_from_row(row: &rusqlite::Row) -> rusqlite::Result<Invoice> {
+    Ok(Invoice {
+        id: row.get(0)?,
+        patient_id: row.get(1)?,
+        item: row.get(2)?,
+        quantity: row.get(3)?,
+        cost: row.get(4)?,
+        created_at: row.get(5)?,
+        duplicate_note: row.get(6)?,
+        procedure_code: row.get(7)?,
+        category: row.get(8)?,
+        insurer_share: row.get(9)?,
+        held: row.get(10)?,
+    })
+}
+
+/// What the patient's insurance plan pays of `invoice`: its category's
+/// percentage of the cost, up to what is left of the category's cap for
+/// the year the invoice was raised in. Held invoices do not use up the cap
+/// until their price is approved. `None` when the patient has no plan.
+fn insurer_share(conn: &Connection, invoice: &Invoice) -> Result<Option<f64>> {
+    let plan_id: Option<i64> = conn
+        .query_row(
+            "SELECT plan_id FROM patient_insurance WHERE patient_id = ?",
+            params![invoice.patient_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if plan_id.is_none() {
+        return Ok(None);
+    }
+    let Some(category) = &invoice.category else {
+        return Ok(Some(0.0));
+    };
+    let coverage: Option<(f64, Option<f64>)> = conn
+        .query_row(
+            "SELECT percent, annual_cap FROM plan_coverage WHERE plan_id = ? AND category = ?",
+            params![plan_id, category],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    let Some((percent, annual_cap)) = coverage else {
+        return Ok(Some(0.0));
+    };
+    let mut share = invoice.cost * percent / 100.0;
+    if let Some(cap) = annual_cap {
+        let year = invoice
+            .created_at
+            .as_deref()
+            .and_then(datetime::year_key)
+            .unwrap_or_else(|| datetime::today().year().to_string());
+        let used: f64 = conn.query_row(
+            "SELECT COALESCE(SUM(insurer_share), 0.0) FROM invoices
+             WHERE patient_id = ? AND category = ? AND id != ? AND held = 0
+               AND strftime('%Y', created_at) = ?",
+            params![invoice.patient_id, category, invoice.id, year],
+            |row| row.get(0),
+        )?;
+        share = share.min(cap - used);
+    }
+    Ok(Some((share.max(0.0) * 100.0).round() / 100.0))
+}
+
+/// The insurer's share `invoice` would get if saved now, for showing the
+/// split before it is.
+pub fn preview_insurer_share(db: &Db, invoice: &Invoice) -> Result<Option<f64>> {
+    insurer_share(&*db.get()?, invoice)
+}
+
+pub fn get_invoice(db: &Db, id: i64) -> Result<Invoice> {
+    let conn = db.get()?;
+    let invoice = conn.query_row(
+        &format!("SELECT {} FROM invoices WHERE id = ?", INVOICE_COLUMNS),
+        [id],
+        invoice_from_row,
+    )?;
+    Ok(invoice)
+}
+
+pub fn get_all_invoices(db: &Db) -> Result<Vec<Invoice>> {
+    db.cache()
+        .invoices
+        .get_or_load(db.data_version()?, || load_invoices(db))
+}
+
+fn load_invoices(db: &Db) -> Result<Vec<Invoice>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM invoices", INVOICE_COLUMNS))?;
+    let invoices = stmt
+        .query_map([], invoice_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(invoices)
+}
+
+/// Invoices raised today for the same patient and item as `invoice`, which
+/// is most likely the same thing billed twice.
+pub fn find_duplicate_invoices(db: &Db, invoice: &Invoice) -> Result<Vec<Invoice>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM invoices
+         WHERE patient_id = ? AND id != ? AND lower(trim(item)) = lower(trim(?))
+           AND date(created_at, ?) = ?
+         ORDER BY created_at",
+        INVOICE_COLUMNS
+    ))?;
+    let invoices = stmt
+        .query_map(
+            params![
+                invoice.patient_id,
+                invoice.id,
+                invoice.item,
+                datetime::sql_offset(),
+                datetime::today_text()
+            ],
+            invoice_from_row,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(invoices)
+}
+
+/// Issues the patient's next invoice, or receipt when `receipt`, for their
+/// billed lines that are on none yet. The number is taken in the same
+/// transaction, so a document that cannot be numbered leaves no gap in the
+/// series. `None` when every billed line is on one already.
+pub fn issue_invoice_document(
+    db: &Db,
+    patient_id: i64,
+    receipt: bool,
+    issued_by: Option<&str>,
+) -> Result<Option<InvoiceDocument>> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let invoice_ids = tx
+        .prepare(
+            "SELECT id FROM invoices
+             WHERE patient_id = ?1 AND held = 0
+               AND id NOT IN (SELECT l.invoice_id FROM invoice_document_lines l
+                              JOIN invoice_documents d ON d.id = l.document_id
+                              WHERE d.receipt = ?2)
+             ORDER BY id",
+        )?
+        .query_map(params![patient_id, receipt], |row| row.get(0))?
+        .collect::<Result<Vec<i64>, _>>()?;
+    if invoice_ids.is_empty() {
+        return Ok(None);
+    }
+    let config = config::config();
+    let settings = &config.invoice;
+    let today = datetime::today();
+    let sequence = invoice_layout::sequence_name(settings, receipt, today);
+    let value = next_in_sequence(&tx, &sequence, 0)?;
+    let document = InvoiceDocument {
+        id: 0,
+        document_number: invoice_layout::document_number(
+            settings,
+            &sync::instance_id(&tx)?,
+            value,
+            receipt,
+            today,
+        )?,
+        patient_id,
+        receipt,
+        issued_on: datetime::format_date(today),
+        issued_by: issued_by.map(str::to_string),
+        invoice_ids,
+    };
+    tx.execute(
+        "INSERT INTO invoice_documents (document_number, patient_id, receipt, issued_on, issued_by)
+         VALUES (?, ?, ?, ?, ?)",
+        params![
+            document.document_number,
+            document.patient_id,
+            document.receipt,
+            document.issued_on,
+            document.issued_by,
+        ],
+    )?;
+    let id = tx.last_insert_rowid();
+    for invoice_id in &document.invoice_ids {
+        tx.execute(
+            "INSERT INTO invoice_document_lines (document_id, invoice_id) VALUES (?, ?)",
+            params![id, invoice_id],
+        )?;
+    }
+    tx.commit()?;
+    Ok(Some(InvoiceDocument { id, ..document }))
+}
+
+/// The invoices and receipts issued to `patient_id`, newest first.
+pub fn get_invoice_documents(db: &Db, patient_id: i64) -> Result<Vec<InvoiceDocument>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT d.id, d.document_number, d.patient_id, d.receipt, d.issued_on, d.issued_by,
+                GROUP_CONCAT(l.invoice_id)
+         FROM invoice_documents d
+         LEFT JOIN invoice_document_lines l ON l.document_id = d.id
+         WHERE d.patient_id = ?
+         GROUP BY d.id
+         ORDER BY d.id DESC",
+    )?;
+    let documents = stmt
+        .query_map(params![patient_id], |row| {
+            let lines: Option<String> = row.get(6)?;
+            Ok(InvoiceDocument {
+                id: row.get(0)?,
+                document_number: row.get(1)?,
+                patient_id: row.get(2)?,
+                receipt: row.get(3)?,
+                issued_on: row.get(4)?,
+                issued_by: row.get(5)?,
+                invoice_ids: lines
+                    .unwrap_or_default()
+                    .split(',')
+                    .filter_map(|id| id.parse().ok())
+                    .collect(),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(documents)
+}
+
+/// Fails when invoice line `invoice_id` is on an issued document, whose
+/// lines have to stay as they were printed.
+fn ensure_not_issued(conn: &Connection, invoice_id: i64) -> Result<()> {
+    let issued: Option<String> = conn
+        .query_row(
+            "SELECT d.document_number FROM invoice_document_lines l
+             JOIN invoice_documents d ON d.id = l.document_id
+             WHERE l.invoice_id = ?
+             ORDER BY d.id LIMIT 1",
+            params![invoice_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match issued {
+        Some(number) => Err(anyhow!(
+            "This line is on {}, which has been issued; it can no longer be changed",
+            number
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Saves `invoice`, working its insurer share out again for the new cost
+/// and category. Held invoices wait for their approver and cannot be
+/// changed, and `billed_by` may only set a price they may bill without one.
+pub fn update_invoice(db: &Db, invoice: &Invoice, billed_by: &str) -> Result<()> {
+    ensure_price_allowed(db, billed_by, invoice)?;
+    let mut conn = db.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let held: bool = tx.query_row(
+        "SELECT held FROM invoices WHERE id = ?",
+        params![invoice.id],
+        |row| row.get(0),
+    )?;
+    if held {
+        return Err(anyhow!(
+            "Invoice {} is waiting for a price approval and cannot be changed until it is decided",
+            invoice.id
+        ));
+    }
+    ensure_not_issued(&tx, invoice.id)?;
+    let share = insurer_share(&tx, invoice)?;
+    tx.execute(
+        "UPDATE invoices SET patient_id = ?, item = ?, quantity = ?, cost = ?, procedure_code = ?,
+                category = ?, insurer_share = ?
+         WHERE id = ?",
+        params![
+            invoice.patient_id,
+            invoice.item,
+            invoice.quantity,
+            invoice.cost,
+            invoice.procedure_code,
+            invoice.category,
+            share,
+            invoice.id,
+        ],
+    )?;
+    tx.commit()?;
+    db.cache().invoices.invalidate();
+    Ok(())
+}
+
+/// The catalog price of the quantity of the procedure `invoice` bills, with
+/// any tax on it. `None` for an item that is not a catalog procedure.
+fn catalog_price(conn: &Connection, invoice: &Invoice) -> Result<Option<f64>> {
+    let Some(code) = &invoice.procedure_code else {
+        return Ok(None);
+    };
+    let price: Option<f64> = conn
+        .query_row(
+            "SELECT price FROM procedures WHERE code = ? COLLATE NOCASE",
+            params![code],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(price
+        .map(|price| config::config().invoice.billed(price) * f64::from(invoice.quantity.max(1))))
+}
+
+/// The catalog price of `invoice` and how far below it the invoice is, in
+/// percent, when that is more than `billed_by` may discount without an
+/// approver. Administrators and accountants may bill any price; everyone
+/// else may bill only catalog procedures, so this fails for other items.
+pub fn discount_needing_approval(
+    db: &Db,
+    billed_by: &str,
+    invoice: &Invoice,
+) -> Result<Option<(f64, f64)>> {
+    if auth::may_approve_discounts(db, billed_by) {
+        return Ok(None);
+    }
+    let Some(list_price) = catalog_price(&*db.get()?, invoice)? else {
+        return Err(anyhow!(
+            "Only administrators and accountants can bill an item that is not a catalog procedure"
+        ));
+    };
+    let percent = discount_percent(list_price, invoice.cost);
+    let limit = config::config().invoice.discount_limit;
+    Ok(((percent * 100.0).round() / 100.0 > limit).then_some((list_price, percent)))
+}
+
+/// Fails when `billed_by` may not bill `invoice` at its cost without an
+/// approver.
+fn ensure_price_allowed(db: &Db, billed_by: &str, invoice: &Invoice) -> Result<()> {
+    match discount_needing_approval(db, billed_by, invoice)? {
+        Some((_, percent)) => Err(anyhow!(
+            "A {:.1}% discount on the catalog price needs approval",
+            percent
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Raises `invoice` at its requested price but held, with a request for an
+/// approver to allow the discount from its catalog price. The requested
+/// price is kept with the request, and that is the price approved.
+pub fn request_price_override(
+    db: &Db,
+    invoice: &Invoice,
+    reason: &str,
+    requested_by: &str,
+) -> Result<i64> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let list_price = catalog_price(&tx, invoice)?
+        .ok_or_else(|| anyhow!("Only catalog procedures can be billed at a discount"))?;
+    let invoice = insert_invoice(
+        &tx,
+        &Invoice {
+            held: true,
+            ..invoice.clone()
+        },
+    )?;
+    tx.execute(
+        "INSERT INTO price_overrides (invoice_id, list_price, requested_price, reason, requested_by, requested_at)
+         VALUES (?, ?, ?, ?, ?, datetime('now'))",
+        params![invoice.id, list_price, invoice.cost, reason, requested_by],
+    )?;
+    tx.commit()?;
+    db.cache().invoices.invalidate();
+    Ok(invoice.id)
+}
+
+fn approval_status_to_str(status: ApprovalStatus) -> &'static str {
+    match status {
+        ApprovalStatus::Pending => "Pending",
+        ApprovalStatus::Approved => "Approved",
+        ApprovalStatus::Rejected => "Rejected",
+    }
+}
+
+fn price_override_from_row(row: &rusqlite::Row) -> rusqlite::Result<PriceOverride> {
+    Ok(PriceOverride {
+        id: row.get(0)?,
+        invoice_id: row.get(1)?,
+        patient_id: row.get(2)?,
+        patient_name: row.get(3)?,
+        item: row.get(4)?,
+        list_price: row.get(5)?,
+        requested_price: row.get(6)?,
+        reason: row.get(7)?,
+        requested_by: row.get(8)?,
+        requested_at: row.get(9)?,
+        status: match row.get::<_, String>(10)?.as_str() {
+            "Pending" => ApprovalStatus::Pending,
+            "Approved" => ApprovalStatus::Approved,
+            "Rejected" => ApprovalStatus::Rejected,
+            _ => {
+                return Err(rusqlite::Error::InvalidColumnType(
+                    10,
+                    String::from("Invalid approval status value"),
+                    rusqlite::types::Type::Text,
+                ));
+            }
+        },
+        decided_by: row.get(11)?,
+        decided_at: row.get(12)?,
+    })
+}
+
+const PRICE_OVERRIDE_QUERY: &str = "SELECT o.id, o.invoice_id, i.patient_id,
+            COALESCE(p.first_name || ' ' || p.last_name, 'Unknown patient'), i.item,
+            o.list_price, o.requested_price, o.reason, o.requested_by, o.requested_at,
+            o.status, o.decided_by, o.decided_at
+     FROM price_overrides o
+     JOIN invoices i ON i.id = o.invoice_id
+     LEFT JOIN patients p ON p.id = i.patient_id";
+
+/// Price overrides waiting for a decision, oldest first.
+pub fn get_pending_price_overrides(db: &Db) -> Result<Vec<PriceOverride>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE o.status = 'Pending' ORDER BY o.requested_at, o.id",
+        PRICE_OVERRIDE_QUERY
+    ))?;
+    let overrides = stmt
+        .query_map([], price_override_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(overrides)
+}
+
+/// The last `limit` price overrides decided, most recent first.
+pub fn get_decided_price_overrides(db: &Db, limit: i64) -> Result<Vec<PriceOverride>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE o.status != 'Pending' ORDER BY o.decided_at DESC, o.id DESC LIMIT ?",
+        PRICE_OVERRIDE_QUERY
+    ))?;
+    let overrides = stmt
+        .query_map(params![limit], price_override_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(overrides)
+}
+
+/// Approves or rejects the pending price override `override_id` and bills
+/// its invoice: at the requested price when approved, at the catalog price
+/// when not.
+pub fn decide_price_override(
+    db: &Db,
+    override_id: i64,
+    approve: bool,
+    decided_by: &str,
+) -> Result<()> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let (invoice_id, list_price, requested_price, status, requested_by): (
+        i64,
+        f64,
+        f64,
+        String,
+        String,
+    ) = tx
+        .query_row(
+            "SELECT invoice_id, list_price, requested_price, status, requested_by
+             FROM price_overrides WHERE id = ?",
+            params![override_id],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )
+        .optional()?
+        .ok_or_else(|| anyhow!("Price override #{} no longer exists", override_id))?;
+    if status != approval_status_to_str(ApprovalStatus::Pending) {
+        return Err(anyhow!(
+            "Price override #{} was already {}",
+            override_id,
+            status.to_lowercase()
+        ));
+    }
+    if requested_by == decided_by {
+        return Err(anyhow!("Someone else has to decide on your own request"));
+    }
+    let mut invoice = tx.query_row(
+        &format!("SELECT {} FROM invoices WHERE id = ?", INVOICE_COLUMNS),
+        params![invoice_id],
+        invoice_from_row,
+    )?;
+    // The price asked for, whatever the held row says now.
+    invoice.cost = if approve { requested_price } else { list_price };
+    invoice.held = false;
+    invoice.insurer_share = insurer_share(&tx, &invoice)?;
+    tx.execute(
+        "UPDATE invoices SET cost = ?, insurer_share = ?, held = 0 WHERE id = ?",
+        params![invoice.cost, invoice.insurer_share, invoice.id],
+    )?;
+    let status = if approve {
+        ApprovalStatus::Approved
+    } else {
+        ApprovalStatus::Rejected
+    };
+    tx.execute(
+        "UPDATE price_overrides SET status = ?, decided_by = ?, decided_at = datetime('now')
+         WHERE id = ?",
+        params![approval_status_to_str(status), decided_by, override_id],
+    )?;
+    tx.commit()?;
+    db.cache().invoices.invalidate();
+    Ok(())
+}
+
+pub fn create_expense(db: &Db, expense: &Expense) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO expenses (department, category, amount, spent_on, payee, note, recorded_by) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        params![
+            expense.department,
+            expense.category,
+            expense.amount,
+            expense.spent_on,
+            expense.payee,
+            expense.note,
+            expense.recorded_by,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_expense(db: &Db, expense: &Expense) -> Result<()> {
+    let conn = db.get()?;
+    let changed = conn.execute(
+        "UPDATE expenses SET department = ?, category = ?, amount = ?, spent_on = ?, payee = ?, note = ? WHERE id = ?",
+        params![
+            expense.department,
+            expense.category,
+            expense.amount,
+            expense.spent_on,
+            expense.payee,
+            expense.note,
+            expense.id,
+        ],
+    )?;
+    if changed == 0 {
+        return Err(anyhow!("Expense #{} no longer exists", expense.id));
+    }
+    Ok(())
+}
+
+pub fn delete_expense(db: &Db, expense_id: i64) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute("DELETE FROM expenses WHERE id = ?", params![expense_id])?;
+    Ok(())
+}
+
+/// Expenses paid between `from` and `to`, oldest first.
+pub fn get_expenses_between(db: &Db, from: &str, to: &str) -> Result<Vec<Expense>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, department, category, amount, spent_on, payee, note, recorded_by
+         FROM expenses WHERE spent_on BETWEEN ? AND ? ORDER BY spent_on, id",
+    )?;
+    let expenses = stmt
+        .query_map(params![from, to], |row| {
+            Ok(Expense {
+                id: row.get(0)?,
+                department: row.get(1)?,
+                category: row.get(2)?,
+                amount: row.get(3)?,
+                spent_on: row.get(4)?,
+                payee: row.get(5)?,
+                note: row.get(6)?,
+                recorded_by: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(expenses)
+}
+
+/// Departments named on any expense, for suggesting one when entering the next.
+pub fn get_expense_departments(db: &Db) -> Result<Vec<String>> {
+    let conn = db.get()?;
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT department FROM expenses ORDER BY department COLLATE NOCASE")?;
+    let departments = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(departments)
+}
+
+/// The total invoiced between `from` and `to`, at the facility's dates.
+pub fn get_invoiced_between(db: &Db, from: &str, to: &str) -> Result<f64> {
+    let conn = db.get()?;
+    let total = conn.query_row(
+        "SELECT COALESCE(SUM(cost), 0) FROM invoices
+         WHERE held = 0 AND date(created_at, ?3) BETWEEN ?1 AND ?2",
+        params![from, to, datetime::sql_offset()],
+        |row| row.get(0),
+    )?;
+    Ok(total)
+}
+
+fn budget_from_row(row: &rusqlite::Row) -> rusqlite::Result<Budget> {
+    Ok(Budget {
+        id: row.get(0)?,
+        month: row.get(1)?,
+        department: row.get(2)?,
+        category: row.get(3)?,
+        amount: row.get(4)?,
+        set_by: row.get(5)?,
+    })
+}
+
+/// The budgets set for `month` (`YYYY-MM`), by department, the whole
+/// department's first.
+pub fn get_budgets(db: &Db, month: &str) -> Result<Vec<Budget>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, month, department, category, amount, set_by FROM budgets
+         WHERE month = ? ORDER BY department COLLATE NOCASE, category IS NOT NULL, category",
+    )?;
+    let budgets = stmt
+        .query_map(params![month], budget_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(budgets)
+}
+
+/// Saves `budget`. A new budget for a month, department and category that
+/// already have one replaces its amount rather than adding a second line.
+pub fn save_budget(db: &Db, budget: &Budget) -> Result<()> {
+    let conn = db.get()?;
+    let existing: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM budgets WHERE month = ? AND department = ? COLLATE NOCASE
+             AND category IS ? AND id != ?",
+            params![budget.month, budget.department, budget.category, budget.id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(id) = existing {
+        if budget.id != 0 {
+            return Err(anyhow!(
+                "{} already has a budget for {}",
+                budget.department,
+                budget.category.as_deref().unwrap_or("the whole department")
+            ));
+        }
+        conn.execute(
+            "UPDATE budgets SET amount = ?, set_by = ? WHERE id = ?",
+            params![budget.amount, budget.set_by, id],
+        )?;
+        return Ok(());
+    }
+    if budget.id == 0 {
+        conn.execute(
+            "INSERT INTO budgets (month, department, category, amount, set_by) VALUES (?, ?, ?, ?, ?)",
+            params![
+                budget.month,
+                budget.department,
+                budget.category,
+                budget.amount,
+                budget.set_by,
+            ],
+        )?;
+        return Ok(());
+    }
+    let changed = conn.execute(
+        "UPDATE budgets SET department = ?, category = ?, amount = ?, set_by = ? WHERE id = ?",
+        params![
+            budget.department,
+            budget.category,
+            budget.amount,
+            budget.set_by,
+            budget.id,
+        ],
+    )?;
+    if changed == 0 {
+        return Err(anyhow!("Budget #{} no longer exists", budget.id));
+    }
+    Ok(())
+}
+
+pub fn delete_budget(db: &Db, budget_id: i64) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute("DELETE FROM budgets WHERE id = ?", params![budget_id])?;
+    Ok(())
+}
+
+/// Copies the budgets of month `from` to month `to` (both `YYYY-MM`),
+/// leaving alone the ones `to` already has. Returns how many were copied.
+pub fn copy_budgets(db: &Db, from: &str, to: &str, set_by: Option<&str>) -> Result<usize> {
+    let conn = db.get()?;
+    let copied = conn.execute(
+        "INSERT INTO budgets (month, department, category, amount, set_by)
+         SELECT ?2, department, category, amount, ?3 FROM budgets AS previous
+         WHERE month = ?1 AND NOT EXISTS (
+             SELECT 1 FROM budgets AS current WHERE current.month = ?2
+             AND current.department = previous.department COLLATE NOCASE
+             AND current.category IS previous.category
+         )",
+        params![from, to, set_by],
+    )?;
+    Ok(copied)
+}
+
+const SESSION_COLUMNS: &str = "s.id, s.cashier, s.opened_at, s.opening_float, s.closed_at,
+     s.counted_cash, s.close_note,
+     (SELECT COALESCE(SUM(amount), 0) FROM payments WHERE session_id = s.id AND method = 'Cash'),
+     (SELECT COALESCE(SUM(amount), 0) FROM payments WHERE session_id = s.id AND method != 'Cash')";
+
+fn session_from_row(row: &rusqlite::Row) -> rusqlite::Result<CashierSession> {
+    Ok(CashierSession {
+        id: row.get(0)?,
+        cashier: row.get(1)?,
+        opened_at: row.get(2)?,
+        opening_float: row.get(3)?,
+        closed_at: row.get(4)?,
+        counted_cash: row.get(5)?,
+        close_note: row.get(6)?,
+        cash_taken: row.get(7)?,
+        other_taken: row.get(8)?,
+    })
+}
+
+/// The session `cashier` has open, if any.
+pub fn get_open_cashier_session(db: &Db, cashier: &str) -> Result<Option<CashierSession>> {
+    let conn = db.get()?;
+    let session = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM cashier_sessions s WHERE cashier = ? AND closed_at IS NULL",
+                SESSION_COLUMNS
+            ),
+            params![cashier],
+            session_from_row,
+        )
+        .optional()?;
+    Ok(session)
+}
+
+/// Opens a session for `cashier` with `opening_float` in the drawer.
+/// A cashier has at most one session open at a time; a unique index keeps
+/// it so even when two workstations open one at once.
+pub fn open_cashier_session(db: &Db, cashier: &str, opening_float: f64) -> Result<i64> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let open: Option<i64> = tx
+        .query_row(
+            "SELECT id FROM cashier_sessions WHERE cashier = ? AND closed_at IS NULL",
+            params![cashier],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(open) = open {
+        return Err(anyhow!(
+            "{} already has session #{} open; close it first",
+            cashier,
+            open
+        ));
+    }
+    tx.execute(
+        "INSERT INTO cashier_sessions (cashier, opened_at, opening_float) VALUES (?, datetime('now'), ?)",
+        params![cashier, opening_float],
+    )?;
+    let id = tx.last_insert_rowid();
+    tx.commit()?;
+    Ok(id)
+}
+
+/// Closes session `session_id` with the cash counted in the drawer.
+pub fn close_cashier_session(
+    db: &Db,
+    session_id: i64,
+    counted_cash: f64,
+    note: Option<&str>,
+) -> Result<()> {
+    let conn = db.get()?;
+    let changed = conn.execute(
+        "UPDATE cashier_sessions SET closed_at = datetime('now'), counted_cash = ?, close_note = ?
+         WHERE id = ? AND closed_at IS NULL",
+        params![counted_cash, note, session_id],
+    )?;
+    if changed == 0 {
+        return Err(anyhow!("Session #{} is not open", session_id));
+    }
+    Ok(())
+}
+
+/// Sessions opened between `from` and `to` at the facility's dates, newest
+/// first; only `cashier`'s when given.
+pub fn get_cashier_sessions_between(
+    db: &Db,
+    from: &str,
+    to: &str,
+    cashier: Option<&str>,
+) -> Result<Vec<CashierSession>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM cashier_sessions s
+         WHERE date(opened_at, ?3) BETWEEN ?1 AND ?2 AND (?4 IS NULL OR cashier = ?4)
+         ORDER BY opened_at DESC, id DESC",
+        SESSION_COLUMNS
+    ))?;
+    let sessions = stmt
+        .query_map(
+            params![from, to, datetime::sql_offset(), cashier],
+            session_from_row,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(sessions)
+}
+
+/// Records `payment`. Cash has to go into an open session's drawer.
+/// The invoices of `patient_id` that `paid` settles in full. Payments are
+/// not made against one invoice, so they settle the oldest invoices first.
+/// Held invoices are not billed yet and wait their turn.
+fn settled_invoices(conn: &Connection, patient_id: i64, paid: f64) -> Result<Vec<Invoice>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM invoices WHERE patient_id = ? AND held = 0 ORDER BY created_at, id",
+        INVOICE_COLUMNS
+    ))?;
+    let invoices = stmt
+        .query_map(params![patient_id], invoice_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut billed = 0.0;
+    Ok(invoices
+        .into_iter()
+        .take_while(|invoice| {
+            billed += invoice.cost;
+            // Within half a cent, so rounding does not keep one open.
+            billed <= paid + 0.005
+        })
+        .collect())
+}
+
+/// Records `payment` and announces each invoice it settles in full. A
+/// payment taken in a session must be taken by the session's cashier while
+/// it is open, and cash is always taken in one.
+pub fn create_payment(db: &Db, payment: &Payment) -> Result<i64> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    match payment.session_id {
+        Some(session_id) => {
+            let cashier: Option<String> = tx
+                .query_row(
+                    "SELECT cashier FROM cashier_sessions WHERE id = ? AND closed_at IS NULL",
+                    params![session_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let Some(cashier) = cashier else {
+                return Err(anyhow!("Session #{} is not open", session_id));
+            };
+            if payment.received_by.as_deref() != Some(cashier.as_str()) {
+                return Err(anyhow!(
+                    "Session #{} belongs to {}; only they can take payments in it",
+                    session_id,
+                    cashier
+                ));
+            }
+        }
+        None if payment.method == PaymentMethod::Cash => {
+            return Err(anyhow!(
+                "Cash can only be taken with a cashier session open"
+            ));
+        }
+        None => {}
+    }
+    tx.execute(
+        "INSERT INTO payments (patient_id, amount, method, paid_at, session_id, received_by, reference)
+         VALUES (?, ?, ?, datetime('now'), ?, ?, ?)",
+        params![
+            payment.patient_id,
+            payment.amount,
+            payment_method_to_str(payment.method),
+            payment.session_id,
+            payment.received_by,
+            payment.reference,
+        ],
+    )?;
+    let id = tx.last_insert_rowid();
+    let paid: f64 = tx.query_row(
+        "SELECT COALESCE(SUM(amount), 0) FROM payments WHERE patient_id = ?",
+        params![payment.patient_id],
+        |row| row.get(0),
+    )?;
+    let already = settled_invoices(&tx, payment.patient_id, paid - payment.amount)?.len();
+    let settled = settled_invoices(&tx, payment.patient_id, paid)?;
+    tx.commit()?;
+    for invoice in settled.into_iter().skip(already) {
+        hooks::fire(db, Event::InvoicePaid, invoice.id, &invoice);
+        webhooks::notify(db, WebhookEvent::InvoicePaid, invoice.id, &invoice);
+    }
+    Ok(id)
+}
+
+fn payment_method_to_str(method: PaymentMethod) -> &'static str {
+    match method {
+        PaymentMethod::Cash => "Cash",
+        PaymentMethod::Card => "Card",
+        PaymentMethod::Transfer => "Transfer",
+        PaymentMethod::Insurance => "Insurance",
+    }
+}
+
+/// Payments taken during session `session_id`, oldest first.
+pub fn get_session_payments(db: &Db, session_id: i64) -> Result<Vec<Payment>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, patient_id, amount, method, paid_at, session_id, received_by, reference
+         FROM payments WHERE session_id = ? ORDER BY paid_at, id",
+    )?;
+    let payments = stmt
+        .query_map(params![session_id], |row| {
+            Ok(Payment {
+                id: row.get(0)?,
+                patient_id: row.get(1)?,
+                amount: row.get(2)?,
+                method: match row.get::<_, String>(3)?.as_str() {
+                    "Cash" => PaymentMethod::Cash,
+                    "Card" => PaymentMethod::Card,
+                    "Transfer" => PaymentMethod::Transfer,
+                    "Insurance" => PaymentMethod::Insurance,
+                    _ => {
+                        return Err(rusqlite::Error::InvalidColumnType(
+                            3,
+                            "method".to_string(),
+                            rusqlite::types::Type::Text,
+                        ))
+                    }
+                },
+                paid_at: row.get(4)?,
+                session_id: row.get(5)?,
+                received_by: row.get(6)?,
+                reference: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(payments)
+}
+
+/// What `patient_id` has been invoiced and has paid in total. Invoices held
+/// for a price approval are not counted yet.
+pub fn get_patient_balance(db: &Db, patient_id: i64) -> Result<(f64, f64)> {
+    let conn = db.get()?;
+    let totals = conn.query_row(
+        "SELECT (SELECT COALESCE(SUM(cost), 0) FROM invoices WHERE patient_id = ?1 AND held = 0),
+                (SELECT COALESCE(SUM(amount), 0) FROM payments WHERE patient_id = ?1)",
+        params![patient_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    Ok(totals)
+}
+
+/// Insurance plans by name, with how many patients each has.
+pub fn get_insurance_plans(db: &Db) -> Result<Vec<InsurancePlan>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.name, p.insurer,
+                (SELECT COUNT(*) FROM patient_insurance m WHERE m.plan_id = p.id)
+         FROM insurance_plans p ORDER BY p.name COLLATE NOCASE",
+    )?;
+    let plans = stmt
+        .query_map([], |row| {
+            Ok(InsurancePlan {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                insurer: row.get(2)?,
+                members: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(plans)
+}
+
+pub fn create_insurance_plan(db: &Db, name: &str, insurer: Option<&str>) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO insurance_plans (name, insurer) VALUES (?, ?)",
+        params![name, insurer],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_insurance_plan(
+    db: &Db,
+    plan_id: i64,
+    name: &str,
+    insurer: Option<&str>,
+) -> Result<()> {
+    let conn = db.get()?;
+    let changed = conn.execute(
+        "UPDATE insurance_plans SET name = ?, insurer = ? WHERE id = ?",
+        params![name, insurer, plan_id],
+    )?;
+    if changed == 0 {
+        return Err(anyhow!("Insurance plan #{} no longer exists", plan_id));
+    }
+    Ok(())
+}
+
+/// Deletes a plan with its coverage. Refused while patients are enrolled.
+pub fn delete_insurance_plan(db: &Db, plan_id: i64) -> Result<()> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction()?;
+    let members: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM patient_insurance WHERE plan_id = ?",
+        params![plan_id],
+        |row| row.get(0),
+    )?;
+    if members > 0 {
+        return Err(anyhow!(
+            "{} patient(s) are still enrolled in the plan",
+            members
+        ));
+    }
+    tx.execute(
+        "DELETE FROM plan_coverage WHERE plan_id = ?",
+        params![plan_id],
+    )?;
+    tx.execute("DELETE FROM insurance_plans WHERE id = ?", params![plan_id])?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// The categories `plan_id` covers.
+pub fn get_plan_coverage(db: &Db, plan_id: i64) -> Result<Vec<PlanCoverage>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT plan_id, category, percent, annual_cap FROM plan_coverage WHERE plan_id = ?",
+    )?;
+    let coverage = stmt
+        .query_map(params![plan_id], |row| {
+            Ok(PlanCoverage {
+                plan_id: row.get(0)?,
+                category: row.get(1)?,
+                percent: row.get(2)?,
+                annual_cap: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(coverage)
+}
+
+/// Sets what a plan pays for a category, replacing what it paid before.
+pub fn set_plan_coverage(db: &Db, coverage: &PlanCoverage) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO plan_coverage (plan_id, category, percent, annual_cap) VALUES (?, ?, ?, ?)
+         ON CONFLICT (plan_id, category) DO UPDATE SET percent = excluded.percent, annual_cap = excluded.annual_cap",
+        params![
+            coverage.plan_id,
+            coverage.category,
+            coverage.percent,
+            coverage.annual_cap,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Stops a plan paying anything for `category`.
+pub fn delete_plan_coverage(db: &Db, plan_id: i64, category: &str) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "DELETE FROM plan_coverage WHERE plan_id = ? AND category = ?",
+        params![plan_id, category],
+    )?;
+    Ok(())
+}
+
+const PATIENT_INSURANCE_QUERY: &str =
+    "SELECT m.patient_id, m.plan_id, m.member_number, p.first_name || ' ' || p.last_name, i.name
+     FROM patient_insurance m JOIN patients p ON p.id = m.patient_id
+     JOIN insurance_plans i ON i.id = m.plan_id";
+
+fn patient_insurance_from_row(row: &rusqlite::Row) -> rusqlite::Result<PatientInsurance> {
+    Ok(PatientInsurance {
+        patient_id: row.get(0)?,
+        plan_id: row.get(1)?,
+        member_number: row.get(2)?,
+        patient_name: row.get(3)?,
+        plan_name: row.get(4)?,
+    })
+}
+
+/// The patients enrolled in `plan_id`, by name.
+pub fn get_plan_members(db: &Db, plan_id: i64) -> Result<Vec<PatientInsurance>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE m.plan_id = ? ORDER BY p.last_name, p.first_name",
+        PATIENT_INSURANCE_QUERY
+    ))?;
+    let members = stmt
+        .query_map(params![plan_id], patient_insurance_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(members)
+}
+
+/// The plan `patient_id` is enrolled in, if any.
+pub fn get_patient_insurance(db: &Db, patient_id: i64) -> Result<Option<PatientInsurance>> {
+    let conn = db.get()?;
+    let insurance = conn
+        .query_row(
+            &format!("{} WHERE m.patient_id = ?", PATIENT_INSURANCE_QUERY),
+            params![patient_id],
+            patient_insurance_from_row,
+        )
+        .optional()?;
+    Ok(insurance)
+}
+
+/// Enrols a patient in a plan, moving them off any plan they were on.
+/// Invoices already raised keep the share worked out for them.
+pub fn enrol_patient(
+    db: &Db,
+    patient_id: i64,
+    plan_id: i64,
+    member_number: Option<&str>,
+) -> Result<()> {
+    let conn = db.get()?;
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM patients WHERE id = ?)",
+        params![patient_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Err(anyhow!("No patient has ID {}", patient_id));
+    }
+    conn.execute(
+        "INSERT INTO patient_insurance (patient_id, plan_id, member_number) VALUES (?, ?, ?)
+         ON CONFLICT (patient_id) DO UPDATE SET plan_id = excluded.plan_id, member_number = excluded.member_number",
+        params![patient_id, plan_id, member_number],
+    )?;
+    Ok(())
+}
+
+pub fn remove_patient_insurance(db: &Db, patient_id: i64) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "DELETE FROM patient_insurance WHERE patient_id = ?",
+        params![patient_id],
+    )?;
+    Ok(())
+}
+
+/// The procedure catalog, by code.
+pub fn get_procedures(db: &Db) -> Result<Vec<Procedure>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare("SELECT id, code, name, price FROM procedures ORDER BY code")?;
+    let procedures = stmt
+        .query_map([], |row| {
+            Ok(Procedure {
+                id: row.get(0)?,
+                code: row.get(1)?,
+                name: row.get(2)?,
+                price: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(procedures)
+}
+
+/// Adds `procedure` to the catalog, or updates the name and price of the
+/// one with the same code. Returns whether it was new.
+pub fn upsert_procedure(db: &Db, procedure: &Procedure) -> Result<bool> {
+    let conn = db.get()?;
+    let updated = conn.execute(
+        "UPDATE procedures SET name = ?, price = ? WHERE code = ?",
+        params![procedure.name, procedure.price, procedure.code],
+    )?;
+    if updated == 0 {
+        conn.execute(
+            "INSERT INTO procedures (code, name, price) VALUES (?, ?, ?)",
+            params![procedure.code, procedure.name, procedure.price],
+        )?;
+    }
+    Ok(updated == 0)
+}
+
+fn waitlist_priority_to_str(priority: WaitlistPriority) -> &'static str {
+    match priority {
+        WaitlistPriority::Urgent => "Urgent",
+        WaitlistPriority::High => "High",
+        WaitlistPriority::Normal => "Normal",
+        WaitlistPriority::Low => "Low",
+    }
+}
+
+fn waitlist_status_to_str(status: WaitlistStatus) -> &'static str {
+    match status {
+        WaitlistStatus::Waiting => "Waiting",
+        WaitlistStatus::Booked => "Booked",
+        WaitlistStatus::Removed => "Removed",
+    }
+}
+
+fn waitlist_entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<WaitlistEntry> {
+    Ok(WaitlistEntry {
+        id: row.get(0)?,
+        patient_id: row.get(1)?,
+        doctor_id: row.get(2)?,
+        procedure: row.get(3)?,
+        priority: match row.get::<_, String>(4)?.as_str() {
+            "Urgent" => WaitlistPriority::Urgent,
+            "High" => WaitlistPriority::High,
+            "Normal" => WaitlistPriority::Normal,
+            "Low" => WaitlistPriority::Low,
+            _ => {
+                return Err(rusqlite::Error::InvalidColumnType(
+                    4,
+                    String::from("Invalid priority value"),
+                    rusqlite::types::Type::Text,
+                ));
+            }
+        },
+        preferred_date: row.get(5)?,
+        notes: row.get(6)?,
+        status: match row.get::<_, String>(7)?.as_str() {
+            "Waiting" => WaitlistStatus::Waiting,
+            "Booked" => WaitlistStatus::Booked,
+            "Removed" => WaitlistStatus::Removed,
+            _ => {
+                return Err(rusqlite::Error::InvalidColumnType(
+                    7,
+                    String::from("Invalid waitlist status value"),
+                    rusqlite::types::Type::Text,
+                ));
+            }
+        },
+        created_at: row.get(8)?,
+    })
+}
+
+const WAITLIST_ORDER: &str = "CASE priority WHEN 'Urgent' THEN 0 WHEN 'High' THEN 1 WHEN 'Normal' THEN 2 ELSE 3 END, created_at, id";
+
+pub fn create_waitlist_entry(db: &Db, entry: &WaitlistEntry) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO waitlist (patient_id, doctor_id, procedure, priority, preferred_date, notes, status, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            entry.patient_id,
+            entry.doctor_id,
+            entry.procedure,
+            waitlist_priority_to_str(entry.priority),
+            entry.preferred_date,
+            entry.notes,
+            waitlist_status_to_str(entry.status),
+            entry.created_at,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn get_all_waitlist_entries(db: &Db) -> Result<Vec<WaitlistEntry>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, patient_id, doctor_id, procedure, priority, preferred_date, notes, status, created_at FROM waitlist ORDER BY {}",
+        WAITLIST_ORDER
+    ))?;
+    let entries = stmt
+        .query_map([], waitlist_entry_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(entries)
+}
+
+pub fn update_waitlist_status(db: &Db, entry_id: i64, status: WaitlistStatus) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "UPDATE waitlist SET status = ? WHERE id = ?",
+        params![waitlist_status_to_str(status), entry_id],
+    )?;
+    Ok(())
+}
+
+pub fn next_waitlisted_patient(
+    db: &Db,
+    doctor_id: i64,
+    procedure: Option<&str>,
+    slot_date: &str,
+) -> Result<Option<WaitlistEntry>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, patient_id, doctor_id, procedure, priority, preferred_date, notes, status, created_at FROM waitlist
+         WHERE status = 'Waiting' AND doctor_id = ?1
+           AND (?2 IS NULL OR procedure = ?2 COLLATE NOCASE)
+           AND (preferred_date IS NULL OR preferred_date <= ?3)
+         ORDER BY {} LIMIT 1",
+        WAITLIST_ORDER
+    ))?;
+    let entry = stmt
+        .query_row(
+            params![doctor_id, procedure, slot_date],
+            waitlist_entry_from_row,
+        )
+        .optional()?;
+    Ok(entry)
+}
+
+pub fn create_birth_record(db: &Db, record: &BirthRecord) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO births (mother_id, newborn_name, sex, birth_time, weight_grams, delivering_doctor_id, notes) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        params![
+            record.mother_id,
+            record.newborn_name,
+            record.sex.label(),
+            record.birth_time,
+            record.weight_grams,
+            record.delivering_doctor_id,
+            record.notes,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    // The birth closes the mother's open pregnancy, if the maternity clinic
+    // was following one.
+    conn.execute(
+        "UPDATE pregnancies SET birth_id = ?, closed_on = ? WHERE id = (
+             SELECT id FROM pregnancies
+             WHERE patient_id = ? AND birth_id IS NULL AND closed_on IS NULL
+             ORDER BY edd LIMIT 1)",
+        params![
+            id,
+            datetime::date_part(&record.birth_time),
+            record.mother_id
+        ],
+    )?;
+    Ok(id)
+}
+
+pub fn get_all_birth_records(db: &Db) -> Result<Vec<BirthRecord>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, mother_id, newborn_name, sex, birth_time, weight_grams, delivering_doctor_id, notes FROM births ORDER BY birth_time DESC",
+    )?;
+    let records = stmt
+        .query_map([], |row| {
+            Ok(BirthRecord {
+                id: row.get(0)?,
+                mother_id: row.get(1)?,
+                newborn_name: row.get(2)?,
+                sex: Gender::from(row.get::<_, String>(3)?),
+                birth_time: row.get(4)?,
+                weight_grams: row.get(5)?,
+                delivering_doctor_id: row.get(6)?,
+                notes: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(records)
+}
+
+pub fn create_death_record(db: &Db, record: &DeathRecord) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO deaths (patient_id, time_of_death, cause, certifying_doctor_id, place, notes) VALUES (?, ?, ?, ?, ?, ?)",
+        params![
+            record.patient_id,
+            record.time_of_death,
+            record.cause,
+            record.certifying_doctor_id,
+            record.place,
+            record.notes,
+        ],
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::SqliteFailure(err, _)
+            if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+        {
+            anyhow!("A death is already registered for this patient")
+        }
+        other => other.into(),
+    })?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn get_all_death_records(db: &Db) -> Result<Vec<DeathRecord>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, patient_id, time_of_death, cause, certifying_doctor_id, place, notes FROM deaths ORDER BY time_of_death DESC",
+    )?;
+    let records = stmt
+        .query_map([], |row| {
+            Ok(DeathRecord {
+                id: row.get(0)?,
+                patient_id: row.get(1)?,
+                time_of_death: row.get(2)?,
+                cause: row.get(3)?,
+                certifying_doctor_id: row.get(4)?,
+                place: row.get(5)?,
+                notes: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(records)
+}
+
+fn document_type_to_str(doc_type: DocumentType) -> &'static str {
+    match doc_type {
+        DocumentType::IdCopy => "IdCopy",
+        DocumentType::ReferralLetter => "ReferralLetter",
+        DocumentType::PastRecord => "PastRecord",
+        DocumentType::LabReport => "LabReport",
+        DocumentType::Other => "Other",
+    }
+}
+
+pub fn create_patient_document(db: &Db, document: &PatientDocument) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO patient_documents (patient_id, doc_type, document_date, file_path, notes, expires_on) VALUES (?, ?, ?, ?, ?, ?)",
+        params![
+            document.patient_id,
+            document_type_to_str(document.doc_type),
+            document.document_date,
+            document.file_path,
+            document.notes,
+            document.expires_on,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+const DOCUMENT_COLUMNS: &str =
+    "id, patient_id, doc_type, document_date, file_path, notes, expires_on";
+
+fn document_from_row(row: &rusqlite::Row) -> rusqlite::Result<PatientDocument> {
+    Ok(PatientDocument {
+        id: row.get(0)?,
+        patient_id: row.get(1)?,
+        doc_type: match row.get::<_, String>(2)?.as_str() {
+            "IdCopy" => DocumentType::IdCopy,
+            "ReferralLetter" => DocumentType::ReferralLetter,
+            "PastRecord" => DocumentType::PastRecord,
+            "LabReport" => DocumentType::LabReport,
+            "Other" => DocumentType::Other,
+            _ => {
+                return Err(rusqlite::Error::InvalidColumnType(
+                    2,
+                    String::from("Invalid document type value"),
+                    rusqlite::types::Type::Text,
+                ));
+            }
+        },
+        document_date: row.get(3)?,
+        file_path: row.get(4)?,
+        notes: row.get(5)?,
+        expires_on: row.get(6)?,
+    })
+}
+
+pub fn get_patient_documents(db: &Db, patient_id: i64) -> Result<Vec<PatientDocument>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM patient_documents WHERE patient_id = ? ORDER BY document_date DESC, id DESC",
+        DOCUMENT_COLUMNS
+    ))?;
+    let documents = stmt
+        .query_map([patient_id], document_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(documents)
+}
+
+/// Every document of every patient that has an expiry date, soonest first.
+pub fn get_expiring_documents(db: &Db) -> Result<Vec<PatientDocument>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM patient_documents WHERE expires_on IS NOT NULL AND expires_on != '' ORDER BY expires_on, id",
+        DOCUMENT_COLUMNS
+    ))?;
+    let documents = stmt
+        .query_map([], document_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(documents)
+}
+
+pub fn delete_patient_document(db: &Db, document_id: i64) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute("DELETE FROM patient_documents WHERE id = ?", [document_id])?;
+    Ok(())
+}
+
+fn report_status_to_str(status: ReportStatus) -> &'static str {
+    match status {
+        ReportStatus::Pending => "Pending",
+        ReportStatus::Preliminary => "Preliminary",
+        ReportStatus::Final => "Final",
+        ReportStatus::Amended => "Amended",
+    }
+}
+
+pub fn create_imaging_study(db: &Db, study: &ImagingStudy) -> Result<i64> {
+    let conn = db.get()?;
+    let taken: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM imaging_studies WHERE accession_number = ?)",
+        [&study.accession_number],
+        |row| row.get(0),
+    )?;
+    if taken {
+        return Err(anyhow!(
+            "Accession number {} is already registered",
+            study.accession_number
+        ));
+    }
+    conn.execute(
+        "INSERT INTO imaging_studies (patient_id, accession_number, modality, study_date, description, order_number, report_status, reference) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            study.patient_id,
+            study.accession_number,
+            study.modality,
+            study.study_date,
+            study.description,
+            study.order_number,
+            report_status_to_str(study.report_status),
+            study.reference,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+const IMAGING_STUDY_COLUMNS: &str = "id, patient_id, accession_number, modality, study_date, description, order_number, report_status, reference";
+
+fn imaging_study_from_row(row: &rusqlite::Row) -> rusqlite::Result<ImagingStudy> {
+    Ok(ImagingStudy {
+        id: row.get(0)?,
+        patient_id: row.get(1)?,
+        accession_number: row.get(2)?,
+        modality: row.get(3)?,
+        study_date: row.get(4)?,
+        description: row.get(5)?,
+        order_number: row.get(6)?,
+        report_status: match row.get::<_, String>(7)?.as_str() {
+            "Pending" => ReportStatus::Pending,
+            "Preliminary" => ReportStatus::Preliminary,
+            "Final" => ReportStatus::Final,
+            "Amended" => ReportStatus::Amended,
+            _ => {
+                return Err(rusqlite::Error::InvalidColumnType(
+                    7,
+                    String::from("Invalid report status value"),
+                    rusqlite::types::Type::Text,
+                ));
+            }
+        },
+        reference: row.get(8)?,
+    })
+}
+
+/// Every study of `patient_id`, newest first.
+pub fn get_patient_imaging_studies(db: &Db, patient_id: i64) -> Result<Vec<ImagingStudy>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM imaging_studies WHERE patient_id = ? ORDER BY study_date DESC, id DESC",
+        IMAGING_STUDY_COLUMNS
+    ))?;
+    let studies = stmt
+        .query_map([patient_id], imaging_study_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(studies)
+}
+
+/// The study registered under `accession_number`, compared case-insensitively
+/// since scanners may send either case.
+pub fn find_imaging_study(db: &Db, accession_number: &str) -> Result<Option<ImagingStudy>> {
+    let conn = db.get()?;
+    let study = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM imaging_studies WHERE accession_number = ? COLLATE NOCASE",
+                IMAGING_STUDY_COLUMNS
+            ),
+            [accession_number],
+            imaging_study_from_row,
+        )
+        .optional()?;
+    Ok(study)
+}
+
+/// Accession and order numbers of every study, for finding a patient by them.
+pub fn get_imaging_numbers(db: &Db) -> Result<Vec<(i64, String)>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT patient_id, accession_number FROM imaging_studies UNION SELECT patient_id, order_number FROM imaging_studies WHERE order_number IS NOT NULL",
+    )?;
+    let numbers = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(numbers)
+}
+
+/// Sets the report status of `study`; a report becoming final tells the
+/// patient their result is ready.
+pub fn update_imaging_report_status(
+    db: &Db,
+    study: &ImagingStudy,
+    status: ReportStatus,
+) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "UPDATE imaging_studies SET report_status = ? WHERE id = ?",
+        params![report_status_to_str(status), study.id],
+    )?;
+    if status == ReportStatus::Final {
+        notify::result_ready(db, study);
+    }
+    Ok(())
+}
+
+pub fn delete_imaging_study(db: &Db, study_id: i64) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute("DELETE FROM imaging_studies WHERE id = ?", [study_id])?;
+    Ok(())
+}
+
+fn appointment_status_to_str(status: AppointmentStatus) -> &'static str {
+    match status {
+        AppointmentStatus::Scheduled => "Scheduled",
+        AppointmentStatus::Completed => "Completed",
+        AppointmentStatus::Cancelled => "Cancelled",
+        AppointmentStatus::NoShow => "NoShow",
+    }
+}
+
+const APPOINTMENT_COLUMNS: &str = "id, patient_id, doctor_id, scheduled_at, duration_minutes, reason, status, cancel_reason, booked_by, checked_in_at";
+
+fn appointment_from_row(row: &rusqlite::Row) -> rusqlite::Result<Appointment> {
+    Ok(Appointment {
+        id: row.get(0)?,
+        patient_id: row.get(1)?,
+        doctor_id: row.get(2)?,
+        scheduled_at: row.get(3)?,
+        duration_minutes: row.get(4)?,
+        reason: row.get(5)?,
+        status: match row.get::<_, String>(6)?.as_str() {
+            "Scheduled" => AppointmentStatus::Scheduled,
+            "Completed" => AppointmentStatus::Completed,
+            "Cancelled" => AppointmentStatus::Cancelled,
+            "NoShow" => AppointmentStatus::NoShow,
+            _ => {
+                return Err(rusqlite::Error::InvalidColumnType(
+                    6,
+                    String::from("Invalid appointment status value"),
+                    rusqlite::types::Type::Text,
+                ));
+            }
+        },
+        cancel_reason: row.get(7)?,
+        booked_by: row.get(8)?,
+        checked_in_at: row.get(9)?,
+    })
+}
+
+pub fn create_appointment(db: &Db, appointment: &Appointment) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO appointments (patient_id, doctor_id, scheduled_at, duration_minutes, reason, status, booked_by) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        params![
+            appointment.patient_id,
+            appointment.doctor_id,
+            appointment.scheduled_at,
+            appointment.duration_minutes,
+            appointment.reason,
+            appointment_status_to_str(appointment.status),
+            appointment.booked_by,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    webhooks::notify(
+        db,
+        WebhookEvent::AppointmentBooked,
+        id,
+        Appointment {
+            id,
+            ..appointment.clone()
+        },
+    );
+    Ok(id)
+}
+
+/// Saves a changed patient, doctor, time, length or reason.
+pub fn update_appointment(db: &Db, appointment: &Appointment) -> Result<()> {
+    let conn = db.get()?;
+    let changed = conn.execute(
+        "UPDATE appointments SET patient_id = ?, doctor_id = ?, scheduled_at = ?, duration_minutes = ?, reason = ? WHERE id = ?",
+        params![
+            appointment.patient_id,
+            appointment.doctor_id,
+            appointment.scheduled_at,
+            appointment.duration_minutes,
+            appointment.reason,
+            appointment.id,
+        ],
+    )?;
+    if changed == 0 {
+        return Err(anyhow!("Appointment #{} no longer exists", appointment.id));
+    }
+    Ok(())
+}
+
+/// Marks an appointment as attended, or back to scheduled.
+pub fn set_appointment_status(
+    db: &Db,
+    appointment_id: i64,
+    status: AppointmentStatus,
+) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "UPDATE appointments SET status = ?, cancel_reason = NULL WHERE id = ?",
+        params![appointment_status_to_str(status), appointment_id],
+    )?;
+    Ok(())
+}
+
+/// Records the patient's arrival now, or clears it when `arrived` is false.
+pub fn check_in_appointment(db: &Db, appointment_id: i64, arrived: bool) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "UPDATE appointments SET checked_in_at = CASE WHEN ? THEN strftime('%Y-%m-%d %H:%M', 'now') END WHERE id = ?",
+        params![arrived, appointment_id],
+    )?;
+    Ok(())
+}
+
+pub fn cancel_appointment(db: &Db, appointment_id: i64, reason: Option<&str>) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "UPDATE appointments SET status = 'Cancelled', cancel_reason = ? WHERE id = ?",
+        params![reason, appointment_id],
+    )?;
+    Ok(())
+}
+
+pub fn get_appointment(db: &Db, appointment_id: i64) -> Result<Appointment> {
+    let conn = db.get()?;
+    let appointment = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM appointments WHERE id = ?",
+                APPOINTMENT_COLUMNS
+            ),
+            params![appointment_id],
+            appointment_from_row,
+        )
+        .optional()?;
+    appointment.ok_or_else(|| anyhow!("Appointment #{} not found", appointment_id))
+}
+
+/// Appointments on the facility's dates `from` to `to` inclusive
+/// (`YYYY-MM-DD`), cancelled ones included, in the order they start.
+pub fn get_appointments_between(db: &Db, from: &str, to: &str) -> Result<Vec<Appointment>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM appointments WHERE date(scheduled_at, ?) BETWEEN ? AND ? ORDER BY scheduled_at, id",
+        APPOINTMENT_COLUMNS
+    ))?;
+    let appointments = stmt
+        .query_map(
+            params![datetime::sql_offset(), from, to],
+            appointment_from_row,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(appointments)
+}
+
+fn teleconsult_status_to_str(status: TeleconsultStatus) -> &'static str {
+    match status {
+        TeleconsultStatus::Scheduled => "Scheduled",
+        TeleconsultStatus::Completed => "Completed",
+        TeleconsultStatus::NoShow => "NoShow",
+        TeleconsultStatus::Cancelled => "Cancelled",
+    }
+}
+
+pub fn create_teleconsultation(db: &Db, consultation: &Teleconsultation) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO teleconsultations (patient_id, doctor_id, scheduled_at, contact, duration_minutes, status, outcome, medical_record_id) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            consultation.patient_id,
+            consultation.doctor_id,
+            consultation.scheduled_at,
+            consultation.contact,
+            consultation.duration_minutes,
+            teleconsult_status_to_str(consultation.status),
+            consultation.outcome,
+            consultation.medical_record_id,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    webhooks::notify(
+        db,
+        WebhookEvent::AppointmentBooked,
+        id,
+        Teleconsultation {
+            id,
+            ..consultation.clone()
+        },
+    );
+    Ok(id)
+}
+
+const TELECONSULTATION_COLUMNS: &str = "id, patient_id, doctor_id, scheduled_at, contact, duration_minutes, status, outcome, medical_record_id, checked_in_at";
+
+fn teleconsultation_from_row(row: &rusqlite::Row) -> rusqlite::Result<Teleconsultation> {
+    Ok(Teleconsultation {
+        id: row.get(0)?,
+        patient_id: row.get(1)?,
+        doctor_id: row.get(2)?,
+        scheduled_at: row.get(3)?,
+        contact: row.get(4)?,
+        duration_minutes: row.get(5)?,
+        status: match row.get::<_, String>(6)?.as_str() {
+            "Scheduled" => TeleconsultStatus::Scheduled,
+            "Completed" => TeleconsultStatus::Completed,
+            "NoShow" => TeleconsultStatus::NoShow,
+            "Cancelled" => TeleconsultStatus::Cancelled,
+            _ => {
+                return Err(rusqlite::Error::InvalidColumnType(
+                    6,
+                    String::from("Invalid teleconsultation status value"),
+                    rusqlite::types::Type::Text,
+                ));
+            }
+        },
+        outcome: row.get(7)?,
+        medical_record_id: row.get(8)?,
+        checked_in_at: row.get(9)?,
+    })
+}
+
+/// The consultations on `date` at the facility.
+pub fn get_teleconsultations_for_day(db: &Db, date: &str) -> Result<Vec<Teleconsultation>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM teleconsultations WHERE date(scheduled_at, ?) = date(?) ORDER BY scheduled_at, id",
+        TELECONSULTATION_COLUMNS
+    ))?;
+    let consultations = stmt
+        .query_map(
+            params![datetime::sql_offset(), date],
+            teleconsultation_from_row,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(consultations)
+}
+
+/// Scheduled consultations of every doctor on the facility's dates `from`
+/// to `to` inclusive, soonest first.
+pub fn get_scheduled_teleconsultations(
+    db: &Db,
+    from: &str,
+    to: &str,
+) -> Result<Vec<Teleconsultation>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM teleconsultations WHERE status = 'Scheduled' AND date(scheduled_at, ?1) BETWEEN date(?2) AND date(?3) ORDER BY scheduled_at, id",
+        TELECONSULTATION_COLUMNS
+    ))?;
+    let consultations = stmt
+        .query_map(
+            params![datetime::sql_offset(), from, to],
+            teleconsultation_from_row,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(consultations)
+}
+
+/// Moves each consultation in `changes`, as (id, doctor id, stored time),
+/// all or nothing. Reminders go out again for the new time.
+pub fn reschedule_teleconsultations(db: &Db, changes: &[(i64, i64, String)]) -> Result<()> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction()?;
+    for (id, doctor_id, scheduled_at) in changes {
+        let updated = tx.execute(
+            "UPDATE teleconsultations SET doctor_id = ?, scheduled_at = ?, reminder_sent_at = NULL
+             WHERE id = ? AND status = 'Scheduled'",
+            params![doctor_id, scheduled_at, id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow!("Consultation {} is no longer scheduled", id));
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Every consultation booked for `patient_id`, oldest first.
+pub fn get_patient_teleconsultations(db: &Db, patient_id: i64) -> Result<Vec<Teleconsultation>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM teleconsultations WHERE patient_id = ? ORDER BY scheduled_at, id",
+        TELECONSULTATION_COLUMNS
+    ))?;
+    let consultations = stmt
+        .query_map([patient_id], teleconsultation_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(consultations)
+}
+
+/// Completed consultations of `doctor_id` that no medical record was written
+/// for yet, oldest first.
+pub fn get_unrecorded_teleconsultations(db: &Db, doctor_id: i64) -> Result<Vec<Teleconsultation>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM teleconsultations WHERE doctor_id = ? AND status = 'Completed' AND medical_record_id IS NULL ORDER BY scheduled_at, id",
+        TELECONSULTATION_COLUMNS
+    ))?;
+    let consultations = stmt
+        .query_map([doctor_id], teleconsultation_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(consultations)
+}
+
+/// Saves the outcome. Completing a consultation stamps its check-out time,
+/// which the utilization report measures consultation length with.
+pub fn update_teleconsultation_outcome(db: &Db, consultation: &Teleconsultation) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "UPDATE teleconsultations SET duration_minutes = ?1, status = ?2, outcome = ?3, medical_record_id = ?4,
+         checked_out_at = CASE WHEN ?2 = 'Completed' THEN COALESCE(checked_out_at, strftime('%Y-%m-%d %H:%M', 'now')) END
+         WHERE id = ?5",
+        params![
+            consultation.duration_minutes,
+            teleconsult_status_to_str(consultation.status),
+            consultation.outcome,
+            consultation.medical_record_id,
+            consultation.id,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Records the patient's arrival now, or clears it when `arrived` is false.
+pub fn check_in_teleconsultation(db: &Db, consultation_id: i64, arrived: bool) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "UPDATE teleconsultations SET checked_in_at = CASE WHEN ? THEN strftime('%Y-%m-%d %H:%M', 'now') END WHERE id = ?",
+        params![arrived, consultation_id],
+    )?;
+    Ok(())
+}
+
+/// Scheduled consultations starting within `hours` that no reminder has
+/// been sent for yet, soonest first.
+pub fn get_consultations_due_reminder(db: &Db, hours: u64) -> Result<Vec<Teleconsultation>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM teleconsultations WHERE status = 'Scheduled' AND reminder_sent_at IS NULL AND datetime(scheduled_at) > datetime('now') AND datetime(scheduled_at) <= datetime('now', '+' || ? || ' hours') ORDER BY scheduled_at, id",
+        TELECONSULTATION_COLUMNS
+    ))?;
+    let consultations = stmt
+        .query_map([hours], teleconsultation_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(consultations)
+}
+
+pub fn mark_reminder_sent(db: &Db, consultation_id: i64) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "UPDATE teleconsultations SET reminder_sent_at = datetime('now') WHERE id = ?",
+        [consultation_id],
+    )?;
+    Ok(())
+}
+
+/// Marks as no-shows the scheduled appointments and consultations nobody
+/// checked in to within `grace_minutes` of their start. Returns how many
+/// were marked.
+pub fn mark_no_shows(db: &Db, grace_minutes: u64) -> Result<usize> {
+    let conn = db.get()?;
+    let mut marked = 0;
+    for table in ["appointments", "teleconsultations"] {
+        marked += conn.execute(
+            &format!(
+                "UPDATE {} SET status = 'NoShow' WHERE status = 'Scheduled' AND checked_in_at IS NULL AND datetime(scheduled_at, '+' || ? || ' minutes') < datetime('now')",
+                table
+            ),
+            params![grace_minutes],
+        )?;
+    }
+    Ok(marked)
+}
+
+/// Attended and missed appointments and consultations per doctor.
+pub fn get_no_show_counts_by_doctor(db: &Db) -> Result<Vec<NoShowCount>> {
+    no_show_counts(db, "doctor_id")
+}
+
+/// Attended and missed appointments and consultations per patient.
+pub fn get_no_show_counts_by_patient(db: &Db) -> Result<Vec<NoShowCount>> {
+    no_show_counts(db, "patient_id")
+}
+
+fn no_show_counts(db: &Db, column: &str) -> Result<Vec<NoShowCount>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {column}, SUM(status = 'Completed'), SUM(status = 'NoShow')
+         FROM (SELECT patient_id, doctor_id, status FROM appointments
+               UNION ALL SELECT patient_id, doctor_id, status FROM teleconsultations)
+         WHERE status IN ('Completed', 'NoShow') GROUP BY {column}"
+    ))?;
+    let counts = stmt
+        .query_map([], |row| {
+            Ok(NoShowCount {
+                id: row.get(0)?,
+                attended: row.get(1)?,
+                no_shows: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(counts)
+}
+
+/// Booked and attended consultations, walk-ins seen, consultation length
+/// and shifts worked per doctor between `from` and `to`, both included.
+pub fn get_doctor_utilization(db: &Db, from: &str, to: &str) -> Result<Vec<DoctorUtilization>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT s.id, s.name,
+            (SELECT COUNT(*) FROM teleconsultations t WHERE t.doctor_id = s.id AND date(t.scheduled_at, ?3) BETWEEN ?1 AND ?2),
+            (SELECT COUNT(*) FROM teleconsultations t WHERE t.doctor_id = s.id AND date(t.scheduled_at, ?3) BETWEEN ?1 AND ?2 AND t.status = 'Completed'),
+            (SELECT COUNT(*) FROM teleconsultations t WHERE t.doctor_id = s.id AND date(t.scheduled_at, ?3) BETWEEN ?1 AND ?2 AND t.status = 'NoShow'),
+            (SELECT COUNT(*) FROM teleconsultations t WHERE t.doctor_id = s.id AND date(t.scheduled_at, ?3) BETWEEN ?1 AND ?2 AND t.status = 'Cancelled'),
+            (SELECT COUNT(*) FROM queue_tokens q WHERE q.doctor_id = s.id AND q.queue_date BETWEEN ?1 AND ?2 AND q.status = 'Done'),
+            (SELECT AVG((julianday(t.checked_out_at) - julianday(t.checked_in_at)) * 1440) FROM teleconsultations t
+             WHERE t.doctor_id = s.id AND date(t.scheduled_at, ?3) BETWEEN ?1 AND ?2
+               AND t.checked_in_at IS NOT NULL AND t.checked_out_at >= t.checked_in_at),
+            (SELECT COUNT(*) FROM shifts sh WHERE sh.staff_id = s.id AND sh.date BETWEEN ?1 AND ?2)
+         FROM staff s WHERE s.role = 'Doctor' ORDER BY s.name, s.id",
+    )?;
+    let rows = stmt
+        .query_map(params![from, to, datetime::sql_offset()], |row| {
+            Ok(DoctorUtilization {
+                doctor_id: row.get(0)?,
+                name: row.get(1)?,
+                booked: row.get(2)?,
+                attended: row.get(3)?,
+                no_shows: row.get(4)?,
+                cancelled: row.get(5)?,
+                walk_ins: row.get(6)?,
+                average_minutes: row.get(7)?,
+                shifts: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Every staff member's work between `from` and `to`, by name. Leave days
+/// are counted within the period only.
+pub fn get_staff_performance(db: &Db, from: &str, to: &str) -> Result<Vec<StaffPerformance>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT s.id, s.name, s.role,
+            (SELECT COUNT(*) FROM shifts sh WHERE sh.staff_id = s.id AND sh.date BETWEEN ?1 AND ?2
+               AND NOT EXISTS (SELECT 1 FROM staff_leave l WHERE l.staff_id = s.id AND sh.date BETWEEN l.start_date AND l.end_date)),
+            (SELECT COALESCE(SUM(julianday(MIN(l.end_date, ?2)) - julianday(MAX(l.start_date, ?1)) + 1), 0) FROM staff_leave l
+             WHERE l.staff_id = s.id AND l.start_date <= ?2 AND l.end_date >= ?1),
+            (SELECT COUNT(*) FROM medical_records m WHERE m.author_id = s.id AND date(m.created_at, ?3) BETWEEN ?1 AND ?2),
+            (SELECT COUNT(*) FROM teleconsultations t WHERE t.doctor_id = s.id AND date(t.scheduled_at, ?3) BETWEEN ?1 AND ?2 AND t.status = 'Completed'),
+            (SELECT COUNT(*) FROM queue_tokens q WHERE q.doctor_id = s.id AND q.queue_date BETWEEN ?1 AND ?2 AND q.status = 'Done'),
+            (SELECT COUNT(*) FROM patient_feedback f WHERE f.doctor_id = s.id AND date(f.created_at, ?3) BETWEEN ?1 AND ?2),
+            (SELECT AVG(f.rating) FROM patient_feedback f WHERE f.doctor_id = s.id AND date(f.created_at, ?3) BETWEEN ?1 AND ?2)
+         FROM staff s ORDER BY s.name, s.id",
+    )?;
+    let rows = stmt
+        .query_map(params![from, to, datetime::sql_offset()], |row| {
+            Ok(StaffPerformance {
+                staff_id: row.get(0)?,
+                name: row.get(1)?,
+                role: row.get(2)?,
+                shifts: row.get(3)?,
+                leave_days: row.get::<_, f64>(4)? as i64,
+                records: row.get(5)?,
+                consultations: row.get(6)?,
+                walk_ins: row.get(7)?,
+                ratings: row.get(8)?,
+                average_rating: row.get(9)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+fn token_status_to_str(status: TokenStatus) -> &'static str {
+    match status {
+        TokenStatus::Waiting => "Waiting",
+        TokenStatus::Serving => "Serving",
+        TokenStatus::Done => "Done",
+        TokenStatus::Skipped => "Skipped",
+    }
+}
+
+fn queue_token_from_row(row: &rusqlite::Row) -> rusqlite::Result<QueueToken> {
+    Ok(QueueToken {
+        id: row.get(0)?,
+        token_number: row.get(1)?,
+        patient_id: row.get(2)?,
+        doctor_id: row.get(3)?,
+        queue_date: row.get(4)?,
+        issued_at: row.get(5)?,
+        called_at: row.get(6)?,
+        status: match row.get::<_, String>(7)?.as_str() {
+            "Waiting" => TokenStatus::Waiting,
+            "Serving" => TokenStatus::Serving,
+            "Done" => TokenStatus::Done,
+            "Skipped" => TokenStatus::Skipped,
+            _ => {
+                return Err(rusqlite::Error::InvalidColumnType(
+                    7,
+                    String::from("Invalid token status value"),
+                    rusqlite::types::Type::Text,
+                ));
+            }
+        },
+    })
+}
+
+const QUEUE_TOKEN_COLUMNS: &str =
+    "id, token_number, patient_id, doctor_id, queue_date, issued_at, called_at, status";
+
+pub fn issue_queue_token(
+    db: &Db,
+    patient_id: i64,
+    doctor_id: i64,
+    queue_date: &str,
+    issued_at: &str,
+) -> Result<QueueToken> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let highest: i64 = tx.query_row(
+        "SELECT COALESCE(MAX(token_number), 0) FROM queue_tokens WHERE doctor_id = ? AND queue_date = ?",
+        params![doctor_id, queue_date],
+        |row| row.get(0),
+    )?;
+    // Tokens restart at 1 for each doctor every day.
+    let sequence = format!("queue_token:{}:{}", doctor_id, queue_date);
+    let token_number = i32::try_from(next_in_sequence(&tx, &sequence, highest)?)?;
+    tx.execute(
+        "INSERT INTO queue_tokens (token_number, patient_id, doctor_id, queue_date, issued_at, status) VALUES (?, ?, ?, ?, ?, 'Waiting')",
+        params![token_number, patient_id, doctor_id, queue_date, issued_at],
+    )?;
+    let id = tx.last_insert_rowid();
+    tx.commit()?;
+    Ok(QueueToken {
+        id,
+        token_number,
+        patient_id,
+        doctor_id,
+        queue_date: queue_date.to_string(),
+        issued_at: issued_at.to_string(),
+        called_at: None,
+        status: TokenStatus::Waiting,
+    })
+}
+
+pub fn get_queue_tokens_for_day(db: &Db, queue_date: &str) -> Result<Vec<QueueToken>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM queue_tokens WHERE queue_date = ? ORDER BY doctor_id, token_number",
+        QUEUE_TOKEN_COLUMNS
+    ))?;
+    let tokens = stmt
+        .query_map([queue_date], queue_token_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tokens)
+}
+
+/// Every token issued on the queue dates `from` to `to` inclusive, by date
+/// and doctor.
+pub fn get_queue_tokens_between(db: &Db, from: &str, to: &str) -> Result<Vec<QueueToken>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM queue_tokens WHERE queue_date BETWEEN ? AND ? ORDER BY queue_date, doctor_id, token_number",
+        QUEUE_TOKEN_COLUMNS
+    ))?;
+    let tokens = stmt
+        .query_map([from, to], queue_token_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tokens)
+}
+
+pub fn update_queue_token_status(db: &Db, token_id: i64, status: TokenStatus) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "UPDATE queue_tokens SET status = ? WHERE id = ?",
+        params![token_status_to_str(status), token_id],
+    )?;
+    Ok(())
+}
+
+pub fn call_next_queue_token(
+    db: &Db,
+    doctor_id: i64,
+    queue_date: &str,
+    called_at: &str,
+) -> Result<Option<QueueToken>> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE queue_tokens SET status = 'Done' WHERE doctor_id = ? AND queue_date = ? AND status = 'Serving'",
+        params![doctor_id, queue_date],
+    )?;
+    let next = tx
+        .query_row(
+            &format!(
+                "SELECT {} FROM queue_tokens WHERE doctor_id = ? AND queue_date = ? AND status = 'Waiting' ORDER BY token_number LIMIT 1",
+                QUEUE_TOKEN_COLUMNS
+            ),
+            params![doctor_id, queue_date],
+            queue_token_from_row,
+        )
+        .optional()?;
+    let next = match next {
+        Some(token) => {
+            tx.execute(
+                "UPDATE queue_tokens SET status = 'Serving', called_at = ? WHERE id = ?",
+                params![called_at, token.id],
+            )?;
+            Some(QueueToken {
+                status: TokenStatus::Serving,
+                called_at: Some(called_at.to_string()),
+                ..token
+            })
+        }
+        None => None,
+    };
+    tx.commit()?;
+    Ok(next)
+}
+
+const FEEDBACK_COLUMNS: &str = "id, patient_id, doctor_id, teleconsultation_id, queue_token_id, rating, comment, created_at, followed_up_at, follow_up_note";
+
+fn feedback_from_row(row: &rusqlite::Row) -> rusqlite::Result<PatientFeedback> {
+    Ok(PatientFeedback {
+        id: row.get(0)?,
+        patient_id: row.get(1)?,
+        doctor_id: row.get(2)?,
+        teleconsultation_id: row.get(3)?,
+        queue_token_id: row.get(4)?,
+        rating: row.get(5)?,
+        comment: row.get(6)?,
+        created_at: row.get(7)?,
+        followed_up_at: row.get(8)?,
+        follow_up_note: row.get(9)?,
+    })
+}
+
+/// Adds the feedback, or changes the rating and comment of feedback already
+/// saved. Returns its ID.
+pub fn save_feedback(db: &Db, feedback: &PatientFeedback) -> Result<i64> {
+    let conn = db.get()?;
+    if feedback.id != 0 {
+        conn.execute(
+            "UPDATE patient_feedback SET rating = ?, comment = ? WHERE id = ?",
+            params![feedback.rating, feedback.comment, feedback.id],
+        )?;
+        return Ok(feedback.id);
+    }
+    conn.execute(
+        "INSERT INTO patient_feedback (patient_id, doctor_id, teleconsultation_id, queue_token_id, rating, comment, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        params![
+            feedback.patient_id,
+            feedback.doctor_id,
+            feedback.teleconsultation_id,
+            feedback.queue_token_id,
+            feedback.rating,
+            feedback.comment,
+            feedback.created_at,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// The feedback given on a consultation or a queue token, if any.
+pub fn get_visit_feedback(
+    db: &Db,
+    teleconsultation_id: Option<i64>,
+    queue_token_id: Option<i64>,
+) -> Result<Option<PatientFeedback>> {
+    let conn = db.get()?;
+    let feedback = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM patient_feedback WHERE teleconsultation_id IS ? AND queue_token_id IS ? ORDER BY id DESC LIMIT 1",
+                FEEDBACK_COLUMNS
+            ),
+            params![teleconsultation_id, queue_token_id],
+            feedback_from_row,
+        )
+        .optional()?;
+    Ok(feedback)
+}
+
+/// Feedback given between `from` and `to`, at the facility's dates.
+pub fn get_feedback_between(db: &Db, from: &str, to: &str) -> Result<Vec<PatientFeedback>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM patient_feedback WHERE date(created_at, ?3) BETWEEN ?1 AND ?2 ORDER BY created_at, id",
+        FEEDBACK_COLUMNS
+    ))?;
+    let feedback = stmt
+        .query_map(params![from, to, datetime::sql_offset()], feedback_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(feedback)
+}
+
+/// Ratings of `max_rating` or lower nobody has followed up yet, oldest
+/// first.
+pub fn get_feedback_to_follow_up(db: &Db, max_rating: i64) -> Result<Vec<PatientFeedback>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM patient_feedback WHERE rating <= ? AND followed_up_at IS NULL ORDER BY created_at, id",
+        FEEDBACK_COLUMNS
+    ))?;
+    let feedback = stmt
+        .query_map(params![max_rating], feedback_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(feedback)
+}
+
+pub fn follow_up_feedback(
+    db: &Db,
+    feedback_id: i64,
+    note: &str,
+    followed_up_at: &str,
+) -> Result<()> {
+    let conn = db.get()?;
+    let updated = conn.execute(
+        "UPDATE patient_feedback SET followed_up_at = ?, follow_up_note = ? WHERE id = ? AND followed_up_at IS NULL",
+        params![followed_up_at, note, feedback_id],
+    )?;
+    if updated == 0 {
+        return Err(anyhow!("Feedback has already been followed up"));
+    }
+    Ok(())
+}
+
+pub fn create_handover_note(db: &Db, note: &HandoverNote) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO handover_notes (ward, handover_date, shift, author_id, critical_patients, pending_tasks, notes, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            note.ward,
+            note.handover_date,
+            note.shift,
+            note.author_id,
+            note.critical_patients,
+            note.pending_tasks,
+            note.notes,
+            note.created_at,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn get_all_handover_notes(db: &Db) -> Result<Vec<HandoverNote>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, ward, handover_date, shift, author_id, critical_patients, pending_tasks, notes, created_at, acknowledged_by, acknowledged_at
+         FROM handover_notes
+         ORDER BY handover_date DESC, CASE shift WHEN 'Night' THEN 0 WHEN 'Afternoon' THEN 1 ELSE 2 END, ward, id DESC",
+    )?;
+    let notes = stmt
+        .query_map([], |row| {
+            Ok(HandoverNote {
+                id: row.get(0)?,
+                ward: row.get(1)?,
+                handover_date: row.get(2)?,
+                shift: row.get(3)?,
+                author_id: row.get(4)?,
+                critical_patients: row.get(5)?,
+                pending_tasks: row.get(6)?,
+                notes: row.get(7)?,
+                created_at: row.get(8)?,
+                acknowledged_by: row.get(9)?,
+                acknowledged_at: row.get(10)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(notes)
+}
+
+pub fn acknowledge_handover_note(
+    db: &Db,
+    note_id: i64,
+    staff_id: i64,
+    acknowledged_at: &str,
+) -> Result<()> {
+    let conn = db.get()?;
+    let updated = conn.execute(
+        "UPDATE handover_notes SET acknowledged_by = ?, acknowledged_at = ? WHERE id = ? AND acknowledged_by IS NULL",
+        params![staff_id, acknowledged_at, note_id],
+    )?;
+    if updated == 0 {
+        return Err(anyhow!("Handover note has already been acknowledged"));
+    }
+    Ok(())
+}
+
+pub fn admit_patient(db: &Db, admission: &Admission) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO admissions (patient_id, ward, bed, admitted_at) VALUES (?, ?, ?, ?)",
+        params![
+            admission.patient_id,
+            admission.ward,
+            admission.bed,
+            admission.admitted_at,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    // The patient has arrived for whatever was planned for them on the ward.
+    conn.execute(
+        "UPDATE planned_admissions SET admission_id = ? WHERE patient_id = ? AND ward = ? AND admission_id IS NULL",
+        params![id, admission.patient_id, admission.ward],
+    )?;
+    hl7::send_adt(
+        db,
+        AdtEvent::Admit,
+        &Admission {
+            id,
+            ..admission.clone()
+        },
+    );
+    Ok(id)
+}
+
+pub fn discharge_patient(db: &Db, admission_id: i64, discharged_at: &str) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "UPDATE admissions SET discharged_at = ? WHERE id = ?",
+        params![discharged_at, admission_id],
+    )?;
+    let admission = conn.query_row(
+        &format!("SELECT {} FROM admissions WHERE id = ?", ADMISSION_COLUMNS),
+        [admission_id],
+        admission_from_row,
+    )?;
+    hl7::send_adt(db, AdtEvent::Discharge, &admission);
+    Ok(())
+}
+
+const ADMISSION_COLUMNS: &str = "id, patient_id, ward, bed, admitted_at, discharged_at, isolation, infection_flags, isolated_since, expected_discharge";
+
+fn admission_from_row(row: &rusqlite::Row) -> rusqlite::Result<Admission> {
+    Ok(Admission {
+        id: row.get(0)?,
+        patient_id: row.get(1)?,
+        ward: row.get(2)?,
+        bed: row.get(3)?,
+        admitted_at: row.get(4)?,
+        discharged_at: row.get(5)?,
+        isolation: row.get(6)?,
+        infection_flags: row.get(7)?,
+        isolated_since: row.get(8)?,
+        expected_discharge: row.get(9)?,
+    })
+}
+
+/// Sets or clears the day a current admission is expected to go home.
+pub fn set_expected_discharge(
+    db: &Db,
+    admission_id: i64,
+    expected_discharge: Option<&str>,
+) -> Result<()> {
+    let conn = db.get()?;
+    let changed = conn.execute(
+        "UPDATE admissions SET expected_discharge = ? WHERE id = ? AND discharged_at IS NULL",
+        params![expected_discharge, admission_id],
+    )?;
+    if changed == 0 {
+        return Err(anyhow!("The patient is no longer admitted"));
+    }
+    Ok(())
+}
+
+pub fn add_planned_admission(db: &Db, planned: &PlannedAdmission) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO planned_admissions (patient_id, ward, planned_date) VALUES (?, ?, ?)",
+        params![planned.patient_id, planned.ward, planned.planned_date],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Planned admissions the patient has not yet arrived for, dated `from`
+/// (`YYYY-MM-DD`) or later, soonest first.
+pub fn get_open_planned_admissions(db: &Db, from: &str) -> Result<Vec<PlannedAdmission>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, patient_id, ward, planned_date, admission_id FROM planned_admissions
+         WHERE admission_id IS NULL AND planned_date >= ? ORDER BY planned_date, ward, id",
+    )?;
+    let planned = stmt
+        .query_map([from], |row| {
+            Ok(PlannedAdmission {
+                id: row.get(0)?,
+                patient_id: row.get(1)?,
+                ward: row.get(2)?,
+                planned_date: row.get(3)?,
+                admission_id: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(planned)
+}
+
+/// Admissions that were planned ahead, to tell them from unplanned ones.
+pub fn get_planned_admission_ids(db: &Db) -> Result<Vec<i64>> {
+    let conn = db.get()?;
+    let mut stmt =
+        conn.prepare("SELECT admission_id FROM planned_admissions WHERE admission_id IS NOT NULL")?;
+    let ids = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+pub fn cancel_planned_admission(db: &Db, planned_id: i64) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "DELETE FROM planned_admissions WHERE id = ? AND admission_id IS NULL",
+        [planned_id],
+    )?;
+    Ok(())
+}
+
+/// Sets or, with `isolation` and `infection_flags` both empty, lifts the
+/// isolation of a current admission.
+pub fn set_isolation(
+    db: &Db,
+    admission_id: i64,
+    isolation: Option<&str>,
+    infection_flags: Option<&str>,
+    isolated_since: Option<&str>,
+) -> Result<()> {
+    let conn = db.get()?;
+    let changed = conn.execute(
+        "UPDATE admissions SET isolation = ?, infection_flags = ?, isolated_since = ? WHERE id = ? AND discharged_at IS NULL",
+        params![isolation, infection_flags, isolated_since, admission_id],
+    )?;
+    if changed == 0 {
+        return Err(anyhow!("The patient is no longer admitted"));
+    }
+    Ok(())
+}
+
+/// Current admissions in isolation or flagged with an infection, by ward
+/// and bed.
+pub fn get_active_isolations(db: &Db) -> Result<Vec<Admission>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM admissions
+         WHERE discharged_at IS NULL AND (isolation IS NOT NULL OR infection_flags IS NOT NULL)
+         ORDER BY ward, bed, admitted_at",
+        ADMISSION_COLUMNS
+    ))?;
+    let admissions = stmt
+        .query_map([], admission_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(admissions)
+}
+
+/// Patients still on a ward, by ward and bed.
+pub fn get_current_admissions(db: &Db) -> Result<Vec<Admission>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM admissions WHERE discharged_at IS NULL ORDER BY ward, bed, admitted_at",
+        ADMISSION_COLUMNS
+    ))?;
+    let admissions = stmt
+        .query_map([], admission_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(admissions)
+}
+
+/// Every stay of every patient, oldest first.
+pub fn get_all_admissions(db: &Db) -> Result<Vec<Admission>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM admissions ORDER BY admitted_at, id",
+        ADMISSION_COLUMNS
+    ))?;
+    let admissions = stmt
+        .query_map([], admission_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(admissions)
+}
+
+/// The diagnosis of each stay by admission id: that of the patient's latest
+/// medical record written before the end of the stay. Stays with no record
+/// are left out.
+pub fn get_admission_diagnoses(db: &Db) -> Result<Vec<(i64, String)>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, diagnosis FROM (
+             SELECT a.id,
+                 (SELECT m.diagnosis FROM medical_records m
+                  WHERE m.patient_id = a.patient_id
+                    AND (m.created_at IS NULL OR m.created_at <= COALESCE(a.discharged_at, datetime('now')))
+                  ORDER BY m.created_at DESC, m.id DESC LIMIT 1) AS diagnosis
+             FROM admissions a
+         ) WHERE diagnosis IS NOT NULL",
+    )?;
+    let diagnoses = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(diagnoses)
+}
+
+/// Every stay of `patient_id`, current or past, oldest first.
+pub fn get_patient_admissions(db: &Db, patient_id: i64) -> Result<Vec<Admission>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM admissions WHERE patient_id = ? ORDER BY admitted_at, id",
+        ADMISSION_COLUMNS
+    ))?;
+    let admissions = stmt
+        .query_map([patient_id], admission_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(admissions)
+}
+
+pub fn create_observation(db: &Db, observation: &Observation) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO observations (patient_id, recorded_at, temperature, pulse, blood_pressure, respiratory_rate, spo2, note) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            observation.patient_id,
+            observation.recorded_at,
+            observation.temperature,
+            observation.pulse,
+            observation.blood_pressure,
+            observation.respiratory_rate,
+            observation.spo2,
+            observation.note,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Every observation of `patient_id`, newest first.
+pub fn get_patient_observations(db: &Db, patient_id: i64) -> Result<Vec<Observation>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, patient_id, recorded_at, temperature, pulse, blood_pressure, respiratory_rate, spo2, note FROM observations WHERE patient_id = ? ORDER BY recorded_at DESC, id DESC",
+    )?;
+    let observations = stmt
+        .query_map([patient_id], |row| {
+            Ok(Observation {
+                id: row.get(0)?,
+                patient_id: row.get(1)?,
+                recorded_at: row.get(2)?,
+                temperature: row.get(3)?,
+                pulse: row.get(4)?,
+                blood_pressure: row.get(5)?,
+                respiratory_rate: row.get(6)?,
+                spo2: row.get(7)?,
+                note: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(observations)
+}
+
+pub fn create_care_plan(db: &Db, plan: &CarePlan) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO care_plans (patient_id, condition, review_interval_days, doctor_id, nurse_id, next_due, last_reviewed) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        params![
+            plan.patient_id,
+            plan.condition,
+            plan.review_interval_days,
+            plan.doctor_id,
+            plan.nurse_id,
+            plan.next_due,
+            plan.last_reviewed,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_care_plan(db: &Db, plan: &CarePlan) -> Result<()> {
+    let conn = db.get()?;
+    let changed = conn.execute(
+        "UPDATE care_plans SET patient_id = ?, condition = ?, review_interval_days = ?, doctor_id = ?, nurse_id = ?, next_due = ?, last_reviewed = ? WHERE id = ?",
+        params![
+            plan.patient_id,
+            plan.condition,
+            plan.review_interval_days,
+            plan.doctor_id,
+            plan.nurse_id,
+            plan.next_due,
+            plan.last_reviewed,
+            plan.id,
+        ],
+    )?;
+    if changed == 0 {
+        return Err(anyhow!("Care plan #{} no longer exists", plan.id));
+    }
+    Ok(())
+}
+
+pub fn delete_care_plan(db: &Db, plan_id: i64) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute("DELETE FROM care_plans WHERE id = ?", params![plan_id])?;
+    Ok(())
+}
+
+const CARE_PLAN_COLUMNS: &str =
+    "id, patient_id, condition, review_interval_days, doctor_id, nurse_id, next_due, last_reviewed";
+
+fn care_plan_from_row(row: &rusqlite::Row) -> rusqlite::Result<CarePlan> {
+    Ok(CarePlan {
+        id: row.get(0)?,
+        patient_id: row.get(1)?,
+        condition: row.get(2)?,
+        review_interval_days: row.get(3)?,
+        doctor_id: row.get(4)?,
+        nurse_id: row.get(5)?,
+        next_due: row.get(6)?,
+        last_reviewed: row.get(7)?,
+    })
+}
+
+/// Every care plan, the one due soonest first.
+pub fn get_all_care_plans(db: &Db) -> Result<Vec<CarePlan>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM care_plans ORDER BY next_due, id",
+        CARE_PLAN_COLUMNS
+    ))?;
+    let plans = stmt
+        .query_map([], care_plan_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(plans)
+}
+
+pub fn create_pregnancy(db: &Db, pregnancy: &Pregnancy) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO pregnancies (patient_id, edd, gravida, para, risk_factors, birth_id, closed_on) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        params![
+            pregnancy.patient_id,
+            pregnancy.edd,
+            pregnancy.gravida,
+            pregnancy.para,
+            pregnancy.risk_factors,
+            pregnancy.birth_id,
+            pregnancy.closed_on,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn update_pregnancy(db: &Db, pregnancy: &Pregnancy) -> Result<()> {
+    let conn = db.get()?;
+    let changed = conn.execute(
+        "UPDATE pregnancies SET patient_id = ?, edd = ?, gravida = ?, para = ?, risk_factors = ?, birth_id = ?, closed_on = ? WHERE id = ?",
+        params![
+            pregnancy.patient_id,
+            pregnancy.edd,
+            pregnancy.gravida,
+            pregnancy.para,
+            pregnancy.risk_factors,
+            pregnancy.birth_id,
+            pregnancy.closed_on,
+            pregnancy.id,
+        ],
+    )?;
+    if changed == 0 {
+        return Err(anyhow!("Pregnancy #{} no longer exists", pregnancy.id));
+    }
+    Ok(())
+}
+
+const PREGNANCY_COLUMNS: &str =
+    "id, patient_id, edd, gravida, para, risk_factors, birth_id, closed_on";
+
+fn pregnancy_from_row(row: &rusqlite::Row) -> rusqlite::Result<Pregnancy> {
+    Ok(Pregnancy {
+        id: row.get(0)?,
+        patient_id: row.get(1)?,
+        edd: row.get(2)?,
+        gravida: row.get(3)?,
+        para: row.get(4)?,
+        risk_factors: row.get(5)?,
+        birth_id: row.get(6)?,
+        closed_on: row.get(7)?,
+    })
+}
+
+/// Every pregnancy on record, the one due soonest first.
+pub fn get_all_pregnancies(db: &Db) -> Result<Vec<Pregnancy>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM pregnancies ORDER BY edd, id",
+        PREGNANCY_COLUMNS
+    ))?;
+    let pregnancies = stmt
+        .query_map([], pregnancy_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(pregnancies)
+}
+
+pub fn create_antenatal_visit(db: &Db, visit: &AntenatalVisit) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO antenatal_visits (pregnancy_id, visit_date, blood_pressure, weight_kg, fundal_height_cm, fetal_heart_rate, staff_id, notes) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            visit.pregnancy_id,
+            visit.visit_date,
+            visit.blood_pressure,
+            visit.weight_kg,
+            visit.fundal_height_cm,
+            visit.fetal_heart_rate,
+            visit.staff_id,
+            visit.notes,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Every antenatal visit, grouped by pregnancy with the latest visit first.
+pub fn get_all_antenatal_visits(db: &Db) -> Result<Vec<AntenatalVisit>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, pregnancy_id, visit_date, blood_pressure, weight_kg, fundal_height_cm, fetal_heart_rate, staff_id, notes
+         FROM antenatal_visits ORDER BY pregnancy_id, visit_date DESC, id DESC",
+    )?;
+    let visits = stmt
+        .query_map([], |row| {
+            Ok(AntenatalVisit {
+                id: row.get(0)?,
+                pregnancy_id: row.get(1)?,
+                visit_date: row.get(2)?,
+                blood_pressure: row.get(3)?,
+                weight_kg: row.get(4)?,
+                fundal_height_cm: row.get(5)?,
+                fetal_heart_rate: row.get(6)?,
+                staff_id: row.get(7)?,
+                notes: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(visits)
+}
+
+pub fn create_prescribing_override(db: &Db, entry: &PrescribingOverride) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO prescribing_overrides (medical_record_id, severity, warning, reason, overridden_by, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+        params![
+            entry.medical_record_id,
+            entry.severity,
+            entry.warning,
+            entry.reason,
+            entry.overridden_by,
+            entry.created_at,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// The warnings overridden on a record, oldest first.
+pub fn get_prescribing_overrides(
+    db: &Db,
+    medical_record_id: i64,
+) -> Result<Vec<PrescribingOverride>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, medical_record_id, severity, warning, reason, overridden_by, created_at
+         FROM prescribing_overrides WHERE medical_record_id = ? ORDER BY id",
+    )?;
+    let overrides = stmt
+        .query_map(params![medical_record_id], |row| {
+            Ok(PrescribingOverride {
+                id: row.get(0)?,
+                medical_record_id: row.get(1)?,
+                severity: row.get(2)?,
+                warning: row.get(3)?,
+                reason: row.get(4)?,
+                overridden_by: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(overrides)
+}
+
+pub fn get_diagnosis_cases(db: &Db) -> Result<Vec<DiagnosisCase>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT m.diagnosis, p.date_of_birth, p.gender, m.created_at
+         FROM medical_records m
+         JOIN patients p ON p.id = m.patient_id",
+    )?;
+    let cases = stmt
+        .query_map([], |row| {
+            let gender = Gender::from(row.get::<_, String>(2)?);
+            Ok(DiagnosisCase {
+                diagnosis: row.get(0)?,
+                date_of_birth: row.get(1)?,
+                gender,
+                recorded_at: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(cases)
+}
+
+/// Where each table keeps the rows about a patient (`?1`), for subject
+/// access requests.
+const SUBJECT_TABLES: &[(&str, &str)] = &[
+    ("patients", "id = ?1"),
+    ("custom_fields", "entity = 'patient' AND entity_id = ?1"),
+    ("medical_records", "patient_id = ?1"),
+    (
+        "prescribing_overrides",
+        "medical_record_id IN (SELECT id FROM medical_records WHERE patient_id = ?1)",
+    ),
+    ("immunizations", "patient_id = ?1"),
+    ("invoices", "patient_id = ?1"),
+    ("payments", "patient_id = ?1"),
+    ("patient_insurance", "patient_id = ?1"),
+    ("waitlist", "patient_id = ?1"),
+    ("births", "mother_id = ?1"),
+    ("deaths", "patient_id = ?1"),
+    ("patient_documents", "patient_id = ?1"),
+    ("imaging_studies", "patient_id = ?1"),
+    ("teleconsultations", "patient_id = ?1"),
+    ("appointments", "patient_id = ?1"),
+    ("queue_tokens", "patient_id = ?1"),
+    ("patient_feedback", "patient_id = ?1"),
+    ("admissions", "patient_id = ?1"),
+    ("planned_admissions", "patient_id = ?1"),
+    ("observations", "patient_id = ?1"),
+    ("care_plans", "patient_id = ?1"),
+    ("pregnancies", "patient_id = ?1"),
+    (
+        "antenatal_visits",
+        "pregnancy_id IN (SELECT id FROM pregnancies WHERE patient_id = ?1)",
+    ),
+    ("research_pseudonyms", "patient_id = ?1"),
+    ("data_requests", "patient_id = ?1"),
+];
+
+/// Every row stored about `patient_id`, table by table, with each row as
+/// a JSON object of its columns.
+pub fn get_subject_data(db: &Db, patient_id: i64) -> Result<Vec<(String, Vec<serde_json::Value>)>> {
+    use rusqlite::types::ValueRef;
+    use serde_json::Value;
+
+    let conn = db.get()?;
+    let mut tables = Vec::new();
+    for (table, filter) in SUBJECT_TABLES {
+        let mut stmt = conn.prepare(&format!("SELECT * FROM {} WHERE {}", table, filter))?;
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+        let rows = stmt
+            .query_map(params![patient_id], |row| {
+                let mut object = serde_json::Map::new();
+                for (index, column) in columns.iter().enumerate() {
+                    let value = match row.get_ref(index)? {
+                        ValueRef::Null => Value::Null,
+                        ValueRef::Integer(i) => Value::from(i),
+                        ValueRef::Real(f) => Value::from(f),
+                        ValueRef::Text(text) => Value::from(String::from_utf8_lossy(text)),
+                        ValueRef::Blob(blob) => Value::from(format!("<{} bytes>", blob.len())),
+                    };
+                    object.insert(column.clone(), value);
+                }
+                Ok(Value::Object(object))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        tables.push((table.to_string(), rows));
+    }
+    Ok(tables)
+}
+
+pub fn create_data_request(db: &Db, request: &DataRequest) -> Result<i64> {
+    let conn = db.get()?;
+    insert_data_request(&conn, request)
+}
+
+fn insert_data_request(conn: &Connection, request: &DataRequest) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO data_requests (patient_id, kind, reason, handled_by, details, created_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+        params![
+            request.patient_id,
+            request.kind,
+            request.reason,
+            request.handled_by,
+            request.details,
+            request.created_at
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// The access requests and erasures of `patient_id`, newest first.
+pub fn get_data_requests(db: &Db, patient_id: i64) -> Result<Vec<DataRequest>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, patient_id, kind, reason, handled_by, details, created_at
+         FROM data_requests WHERE patient_id = ? ORDER BY created_at DESC, id DESC",
+    )?;
+    let requests = stmt
+        .query_map(params![patient_id], |row| {
+            Ok(DataRequest {
                 id: row.get(0)?,
                 patient_id: row.get(1)?,
-                item: row.get(2)?,
+                kind: row.get(2)?,
+                reason: row.get(3)?,
+                handled_by: row.get(4)?,
+                details: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(requests)
+}
+
+/// Carries out the erasure `request` by anonymizing the patient in place:
+/// the name, address, phone, email and day and month of birth go, as do
+/// the names of their newborns, their document references, custom fields
+/// and research pseudonym, and the earlier versions of those rows in the
+/// sync history. Clinical entries keep pointing at the patient row, so
+/// reports and references stay intact.
+pub fn erase_patient(db: &Db, request: &DataRequest) -> Result<()> {
+    let patient_id = request.patient_id;
+    let mut conn = db.get()?;
+    let tx = conn.transaction()?;
+    let photo: Option<String> = tx
+        .query_row(
+            "SELECT photo_path FROM patients WHERE id = ?",
+            params![patient_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+
+    let updated = tx.execute(
+        "UPDATE patients SET first_name = 'Erased', last_name = 'Patient',
+             date_of_birth = substr(date_of_birth, 1, 4) || '-01-01',
+             address = '', city = NULL, region = NULL, postcode = NULL,
+             phone_number = '', email = NULL, photo_path = NULL
+         WHERE id = ?",
+        params![patient_id],
+    )?;
+    if updated == 0 {
+        return Err(anyhow!("No patient with ID {}", patient_id));
+    }
+    tx.execute(
+        "UPDATE births SET newborn_name = 'Erased' WHERE mother_id = ?",
+        params![patient_id],
+    )?;
+    tx.execute(
+        "DELETE FROM patient_documents WHERE patient_id = ?",
+        params![patient_id],
+    )?;
+    tx.execute(
+        "DELETE FROM custom_fields WHERE entity = 'patient' AND entity_id = ?",
+        params![patient_id],
+    )?;
+    tx.execute(
+        "DELETE FROM research_pseudonyms WHERE patient_id = ?",
+        params![patient_id],
+    )?;
+    if let Some(uid) = sync::uid(&tx, "patients", patient_id)? {
+        sync::forget_patient_history(&tx, &uid)?;
+    }
+    insert_data_request(&tx, request)?;
+    tx.commit()?;
+    db.cache().patients.invalidate();
+    if let Some(photo) = photo {
+        let _ = std::fs::remove_file(photo);
+    }
+    Ok(())
+}
+
+/// Every visit and stay for the research dataset. Patients without a
+/// pseudonym get a random one first; it is kept, so the same patient has
+/// the same pseudonym in every export.
+pub fn get_research_encounters(db: &Db) -> Result<Vec<ResearchEncounter>> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT OR IGNORE INTO research_pseudonyms (patient_id, pseudonym)
+         SELECT id, 'P-' || upper(hex(randomblob(6))) FROM patients",
+        [],
+    )?;
+    let mut stmt = conn.prepare(
+        "SELECT r.pseudonym, 'Visit', p.date_of_birth, p.gender, m.created_at, NULL,
+                m.diagnosis, NULL
+         FROM medical_records m
+         JOIN patients p ON p.id = m.patient_id
+         JOIN research_pseudonyms r ON r.patient_id = p.id
+         UNION ALL
+         SELECT r.pseudonym, 'Admission', p.date_of_birth, p.gender, a.admitted_at,
+                a.discharged_at,
+                (SELECT m.diagnosis FROM medical_records m
+                 WHERE m.patient_id = a.patient_id
+                   AND (m.created_at IS NULL OR m.created_at <= COALESCE(a.discharged_at, datetime('now')))
+                 ORDER BY m.created_at DESC, m.id DESC LIMIT 1),
+                a.ward
+         FROM admissions a
+         JOIN patients p ON p.id = a.patient_id
+         JOIN research_pseudonyms r ON r.patient_id = p.id
+         ORDER BY 5, 1",
+    )?;
+    let encounters = stmt
+        .query_map([], |row| {
+            Ok(ResearchEncounter {
+                pseudonym: row.get(0)?,
+                kind: row.get(1)?,
+                date_of_birth: row.get(2)?,
+                gender: row.get(3)?,
+                started_at: row.get(4)?,
+                ended_at: row.get(5)?,
+                diagnosis: row.get(6)?,
+                ward: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(encounters)
+}
+
+/// Every stock item by name, with what the ledger has on hand.
+pub fn get_stock_items(db: &Db) -> Result<Vec<StockItem>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT i.id, i.code, i.name, i.unit, COALESCE(SUM(t.quantity), 0)
+         FROM stock_items i LEFT JOIN inventory_transactions t ON t.item_id = i.id
+         GROUP BY i.id ORDER BY i.name COLLATE NOCASE",
+    )?;
+    let items = stmt
+        .query_map([], |row| {
+            Ok(StockItem {
+                id: row.get(0)?,
+                code: row.get(1)?,
+                name: row.get(2)?,
+                unit: row.get(3)?,
+                on_hand: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// Adds an item with nothing on hand. Codes are matched without regard to
+/// case, so two items cannot share a label.
+pub fn create_stock_item(db: &Db, code: &str, name: &str, unit: &str) -> Result<i64> {
+    let conn = db.get()?;
+    let code = code.trim();
+    let taken: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM stock_items WHERE code = ? COLLATE NOCASE)",
+        params![code],
+        |row| row.get(0),
+    )?;
+    if taken {
+        return Err(anyhow!("An item with code {} already exists", code));
+    }
+    conn.execute(
+        "INSERT INTO stock_items (code, name, unit, created_at) VALUES (?, ?, ?, datetime('now'))",
+        params![code, name.trim(), unit.trim()],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn inventory_kind_to_str(kind: InventoryKind) -> &'static str {
+    match kind {
+        InventoryKind::Receipt => "Receipt",
+        InventoryKind::Issue => "Issue",
+        InventoryKind::Adjustment => "Adjustment",
+    }
+}
+
+fn on_hand(conn: &Connection, item_id: i64) -> Result<i64> {
+    Ok(conn.query_row(
+        "SELECT COALESCE(SUM(quantity), 0) FROM inventory_transactions WHERE item_id = ?",
+        params![item_id],
+        |row| row.get(0),
+    )?)
+}
+
+/// Adds a receipt (positive `quantity`) or an issue (negative) to the
+/// ledger. An issue of more than is on hand is refused.
+pub fn record_stock_movement(
+    db: &Db,
+    item_id: i64,
+    kind: InventoryKind,
+    quantity: i64,
+    reference: Option<&str>,
+    posted_by: &str,
+) -> Result<()> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let on_hand = on_hand(&tx, item_id)?;
+    if on_hand + quantity < 0 {
+        return Err(anyhow!("Only {} on hand", on_hand));
+    }
+    tx.execute(
+        "INSERT INTO inventory_transactions (item_id, kind, quantity, reference, posted_by, posted_at)
+         VALUES (?, ?, ?, ?, ?, datetime('now'))",
+        params![
+            item_id,
+            inventory_kind_to_str(kind),
+            quantity,
+            reference,
+            posted_by
+        ],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// The movements of `item_id`, newest first.
+pub fn get_item_transactions(db: &Db, item_id: i64) -> Result<Vec<InventoryTransaction>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, item_id, kind, quantity, reference, posted_by, posted_at
+         FROM inventory_transactions WHERE item_id = ? ORDER BY posted_at DESC, id DESC",
+    )?;
+    let transactions = stmt
+        .query_map(params![item_id], |row| {
+            Ok(InventoryTransaction {
+                id: row.get(0)?,
+                item_id: row.get(1)?,
+                kind: match row.get::<_, String>(2)?.as_str() {
+                    "Receipt" => InventoryKind::Receipt,
+                    "Issue" => InventoryKind::Issue,
+                    "Adjustment" => InventoryKind::Adjustment,
+                    _ => {
+                        return Err(rusqlite::Error::InvalidColumnType(
+                            2,
+                            String::from("Invalid inventory transaction kind"),
+                            rusqlite::types::Type::Text,
+                        ));
+                    }
+                },
                 quantity: row.get(3)?,
-                cost: row.get(4)?,
+                reference: row.get(4)?,
+                posted_by: row.get(5)?,
+                posted_at: row.get(6)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
-    Ok(invoices)
+    Ok(transactions)
+}
+
+fn stocktake_from_row(row: &rusqlite::Row) -> rusqlite::Result<Stocktake> {
+    Ok(Stocktake {
+        id: row.get(0)?,
+        status: match row.get::<_, String>(1)?.as_str() {
+            "Counting" => StocktakeStatus::Counting,
+            "Posted" => StocktakeStatus::Posted,
+            "Abandoned" => StocktakeStatus::Abandoned,
+            _ => {
+                return Err(rusqlite::Error::InvalidColumnType(
+                    1,
+                    String::from("Invalid stocktake status value"),
+                    rusqlite::types::Type::Text,
+                ));
+            }
+        },
+        started_by: row.get(2)?,
+        started_at: row.get(3)?,
+        posted_by: row.get(4)?,
+        posted_at: row.get(5)?,
+    })
+}
+
+/// Starts a stocktake, freezing what the ledger has on hand of every item
+/// as what the count is expected to find. Only one count may be open.
+pub fn start_stocktake(db: &Db, started_by: &str) -> Result<i64> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let open: Option<i64> = tx
+        .query_row(
+            "SELECT id FROM stocktakes WHERE status = 'Counting'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(open) = open {
+        return Err(anyhow!("Stocktake #{} is still being counted", open));
+    }
+    tx.execute(
+        "INSERT INTO stocktakes (status, started_by, started_at) VALUES ('Counting', ?, datetime('now'))",
+        params![started_by],
+    )?;
+    let stocktake_id = tx.last_insert_rowid();
+    tx.execute(
+        "INSERT INTO stocktake_lines (stocktake_id, item_id, expected)
+         SELECT ?, i.id, COALESCE(SUM(t.quantity), 0)
+         FROM stock_items i LEFT JOIN inventory_transactions t ON t.item_id = i.id
+         GROUP BY i.id",
+        params![stocktake_id],
+    )?;
+    tx.commit()?;
+    Ok(stocktake_id)
+}
+
+/// The stocktake being counted, if there is one.
+pub fn get_open_stocktake(db: &Db) -> Result<Option<Stocktake>> {
+    let conn = db.get()?;
+    let stocktake = conn
+        .query_row(
+            "SELECT id, status, started_by, started_at, posted_by, posted_at
+             FROM stocktakes WHERE status = 'Counting' ORDER BY id DESC LIMIT 1",
+            [],
+            stocktake_from_row,
+        )
+        .optional()?;
+    Ok(stocktake)
+}
+
+/// The items of `stocktake_id` by name.
+pub fn get_stocktake_lines(db: &Db, stocktake_id: i64) -> Result<Vec<StocktakeLine>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT l.item_id, i.code, i.name, i.unit, l.expected, l.counted
+         FROM stocktake_lines l JOIN stock_items i ON i.id = l.item_id
+         WHERE l.stocktake_id = ? ORDER BY i.name COLLATE NOCASE",
+    )?;
+    let lines = stmt
+        .query_map(params![stocktake_id], |row| {
+            Ok(StocktakeLine {
+                item_id: row.get(0)?,
+                code: row.get(1)?,
+                name: row.get(2)?,
+                unit: row.get(3)?,
+                expected: row.get(4)?,
+                counted: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(lines)
+}
+
+/// Adds `quantity` to what has been counted of the item labelled `code`,
+/// so each scan adds one and a miscount is taken back with a negative
+/// quantity. The count never goes below zero. Returns the updated line.
+pub fn count_stock(db: &Db, stocktake_id: i64, code: &str, quantity: i64) -> Result<StocktakeLine> {
+    let conn = db.get()?;
+    let code = code.trim();
+    let updated = conn.execute(
+        "UPDATE stocktake_lines SET counted = MAX(0, COALESCE(counted, 0) + ?)
+         WHERE stocktake_id = ?
+           AND item_id IN (SELECT id FROM stock_items WHERE code = ? COLLATE NOCASE)
+           AND EXISTS (SELECT 1 FROM stocktakes WHERE id = ? AND status = 'Counting')",
+        params![quantity, stocktake_id, code, stocktake_id],
+    )?;
+    if updated == 0 {
+        return Err(anyhow!(
+            "No item with code {} in stocktake #{}",
+            code,
+            stocktake_id
+        ));
+    }
+    let line = get_stocktake_lines(db, stocktake_id)?
+        .into_iter()
+        .find(|line| line.code.eq_ignore_ascii_case(code))
+        .ok_or_else(|| anyhow!("No item with code {}", code))?;
+    Ok(line)
+}
+
+/// Closes `stocktake_id`, adding an adjustment to the ledger for every
+/// counted item that differs from what was expected. Movements recorded
+/// while counting are kept, since the adjustment is the difference from
+/// the frozen quantity. Items nobody counted are left as they are.
+/// Returns how many adjustments were posted.
+pub fn post_stocktake(db: &Db, stocktake_id: i64, posted_by: &str) -> Result<usize> {
+    let mut conn = db.get()?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let closed = tx.execute(
+        "UPDATE stocktakes SET status = 'Posted', posted_by = ?, posted_at = datetime('now')
+         WHERE id = ? AND status = 'Counting'",
+        params![posted_by, stocktake_id],
+    )?;
+    if closed == 0 {
+        return Err(anyhow!("Stocktake #{} is not being counted", stocktake_id));
+    }
+    let adjusted = tx.execute(
+        "INSERT INTO inventory_transactions (item_id, kind, quantity, reference, posted_by, posted_at)
+         SELECT item_id, 'Adjustment', counted - expected, 'Stocktake #' || stocktake_id, ?, datetime('now')
+         FROM stocktake_lines
+         WHERE stocktake_id = ? AND counted IS NOT NULL AND counted != expected",
+        params![posted_by, stocktake_id],
+    )?;
+    tx.commit()?;
+    Ok(adjusted)
 }
 
-pub fn update_invoice(invoice: &Invoice) -> Result<()> {
-    let conn = Connection::open("rustoria.db")?;
+/// Closes `stocktake_id` without touching the ledger.
+pub fn abandon_stocktake(db: &Db, stocktake_id: i64) -> Result<()> {
+    let conn = db.get()?;
     conn.execute(
-        "UPDATE invoices SET patient_id = ?, item = ?, quantity = ?, cost = ? 
-         WHERE id = ?",
-        (
-            &invoice.patient_id,
-            &invoice.item,
-            &invoice.quantity,
-            &invoice.cost,
-            &invoice.id,
-        ),
+        "UPDATE stocktakes SET status = 'Abandoned' WHERE id = ? AND status = 'Counting'",
+        params![stocktake_id],
+    )?;
+    Ok(())
+}
+
+pub fn get_setting(db: &Db, key: &str) -> Result<Option<String>> {
+    let conn = db.get()?;
+    let value = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value)
+}
+
+pub fn set_setting(db: &Db, key: &str, value: &str) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+/// Stores a value computed by a hook script against a patient, invoice or shift.
+pub fn set_custom_field(
+    db: &Db,
+    entity: &str,
+    entity_id: i64,
+    name: &str,
+    value: &str,
+) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO custom_fields (entity, entity_id, name, value) VALUES (?, ?, ?, ?)
+         ON CONFLICT(entity, entity_id, name) DO UPDATE SET value = excluded.value",
+        params![entity, entity_id, name, value],
+    )?;
+    Ok(())
+}
+
+pub fn get_custom_fields(db: &Db, entity: &str, entity_id: i64) -> Result<Vec<(String, String)>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT name, value FROM custom_fields WHERE entity = ? AND entity_id = ? ORDER BY name",
+    )?;
+    let fields = stmt
+        .query_map(params![entity, entity_id], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(fields)
+}
+
+/// Writes a consistent copy of the database to `path`, which must not exist yet.
+/// Copies a few pages at a time so the caller can follow progress or cancel;
+/// a cancelled or failed backup leaves no partial file behind.
+pub fn backup_database(db: &Db, path: &Path, progress: &worker::Progress) -> Result<()> {
+    if path.exists() {
+        return Err(anyhow!("{} already exists", path.display()));
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).context("Failed to create backup directory")?;
+    }
+    let result = copy_database(db, path, progress);
+    if result.is_err() {
+        let _ = std::fs::remove_file(path);
+    }
+    result.with_context(|| format!("Failed to back up database to {}", path.display()))
+}
+
+fn copy_database(db: &Db, path: &Path, progress: &worker::Progress) -> Result<()> {
+    const PAGES_PER_STEP: i32 = 64;
+    let source = db.get()?;
+    let mut target = Connection::open(path)?;
+    let backup = Backup::new(&source, &mut target)?;
+    loop {
+        if progress.is_cancelled() {
+            return Err(anyhow!("Backup cancelled"));
+        }
+        let step = backup.step(PAGES_PER_STEP)?;
+        let pages = backup.progress();
+        let total = pages.pagecount.max(0) as u64;
+        progress.set(total.saturating_sub(pages.remaining.max(0) as u64), total);
+        match step {
+            StepResult::Done => return Ok(()),
+            StepResult::More => {}
+            _ => std::thread::sleep(std::time::Duration::from_millis(50)),
+        }
+    }
+}
+
+/// The home dashboard figures, with the patients `username` pinned.
+pub fn get_dashboard_metrics(
+    db: &Db,
+    today: &str,
+    current_shift: &str,
+    username: Option<&str>,
+) -> Result<DashboardMetrics> {
+    let conn = db.get()?;
+    let count = |sql: &str, args: &[&dyn rusqlite::ToSql]| -> Result<i64> {
+        Ok(conn.query_row(sql, args, |row| row.get(0))?)
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT s.name FROM shifts sh JOIN staff s ON s.id = sh.staff_id
+         WHERE sh.date = ? AND sh.shift = ? ORDER BY s.name",
+    )?;
+    let staff_on_shift = stmt
+        .query_map(params![today, current_shift], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT p.first_name || ' ' || p.last_name FROM pinned_patients pp
+         JOIN patients p ON p.id = pp.patient_id
+         WHERE pp.username = ? ORDER BY pp.pinned_at, pp.patient_id",
     )?;
+    let pinned_patients = stmt
+        .query_map(params![username], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+
+    // An invoice's cost is already the price of all of its quantity.
+    let (invoiced_total, paid_total) = conn.query_row(
+        "SELECT (SELECT COALESCE(SUM(cost), 0.0) FROM invoices WHERE held = 0),
+                (SELECT COALESCE(SUM(amount), 0.0) FROM payments)",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    Ok(DashboardMetrics {
+        tokens_today: count(
+            "SELECT COUNT(*) FROM queue_tokens WHERE queue_date = ?",
+            &[&today],
+        )?,
+        teleconsults_today: count(
+            "SELECT COUNT(*) FROM teleconsultations WHERE date(scheduled_at, ?) = ? AND status = 'Scheduled'",
+            &[&datetime::sql_offset(), &today],
+        )?,
+        appointments_today: count(
+            "SELECT COUNT(*) FROM appointments WHERE date(scheduled_at, ?) = ? AND status IN (?, ?)",
+            &[
+                &datetime::sql_offset(),
+                &today,
+                &appointment_status_to_str(AppointmentStatus::Scheduled),
+                &appointment_status_to_str(AppointmentStatus::Completed),
+            ],
+        )?,
+        admitted_count: count(
+            "SELECT COUNT(DISTINCT patient_id) FROM admissions WHERE discharged_at IS NULL",
+            &[],
+        )?,
+        patient_count: count("SELECT COUNT(*) FROM patients", &[])?,
+        pinned_patients,
+        staff_on_shift,
+        invoiced_total,
+        paid_total,
+        queue_waiting: count(
+            "SELECT COUNT(*) FROM queue_tokens WHERE queue_date = ? AND status = 'Waiting'",
+            &[&today],
+        )?,
+        pending_handovers: count(
+            "SELECT COUNT(*) FROM handover_notes WHERE acknowledged_by IS NULL",
+            &[],
+        )?,
+        urgent_waitlist: count(
+            "SELECT COUNT(*) FROM waitlist WHERE status = 'Waiting' AND priority = 'Urgent'",
+            &[],
+        )?,
+        feedback_to_follow_up: count(
+            "SELECT COUNT(*) FROM patient_feedback WHERE rating <= ? AND followed_up_at IS NULL",
+            &[&config::config().follow_up_rating],
+        )?,
+    })
+}
+
+fn delivery_status_to_str(status: DeliveryStatus) -> &'static str {
+    match status {
+        DeliveryStatus::Pending => "Pending",
+        DeliveryStatus::Delivered => "Delivered",
+        DeliveryStatus::Failed => "Failed",
+    }
+}
+
+const WEBHOOK_DELIVERY_COLUMNS: &str = "id, event, url, payload, status, attempts, next_attempt_at, last_error, created_at, delivered_at";
+
+fn webhook_delivery_from_row(row: &rusqlite::Row) -> rusqlite::Result<WebhookDelivery> {
+    Ok(WebhookDelivery {
+        id: row.get(0)?,
+        event: row.get(1)?,
+        url: row.get(2)?,
+        payload: row.get(3)?,
+        status: match row.get::<_, String>(4)?.as_str() {
+            "Pending" => DeliveryStatus::Pending,
+            "Delivered" => DeliveryStatus::Delivered,
+            "Failed" => DeliveryStatus::Failed,
+            _ => {
+                return Err(rusqlite::Error::InvalidColumnType(
+                    4,
+                    String::from("Invalid delivery status value"),
+                    rusqlite::types::Type::Text,
+                ));
+            }
+        },
+        attempts: row.get(5)?,
+        next_attempt_at: row.get(6)?,
+        last_error: row.get(7)?,
+        created_at: row.get(8)?,
+        delivered_at: row.get(9)?,
+    })
+}
+
+pub fn queue_webhook_delivery(db: &Db, delivery: &WebhookDelivery) -> Result<i64> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO webhook_deliveries (event, url, payload, status, attempts, next_attempt_at, created_at) VALUES (?, ?, ?, ?, 0, ?, ?)",
+        params![
+            delivery.event,
+            delivery.url,
+            delivery.payload,
+            delivery_status_to_str(delivery.status),
+            delivery.next_attempt_at,
+            delivery.created_at,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Pending deliveries whose next attempt is due at `now`, oldest first.
+pub fn get_due_webhook_deliveries(db: &Db, now: &str) -> Result<Vec<WebhookDelivery>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM webhook_deliveries WHERE status = 'Pending' AND next_attempt_at <= ? ORDER BY next_attempt_at, id",
+        WEBHOOK_DELIVERY_COLUMNS
+    ))?;
+    let deliveries = stmt
+        .query_map([now], webhook_delivery_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(deliveries)
+}
+
+/// The latest `limit` deliveries, newest first.
+pub fn get_webhook_deliveries(db: &Db, limit: usize) -> Result<Vec<WebhookDelivery>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM webhook_deliveries ORDER BY id DESC LIMIT ?",
+        WEBHOOK_DELIVERY_COLUMNS
+    ))?;
+    let deliveries = stmt
+        .query_map([limit as i64], webhook_delivery_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(deliveries)
+}
+
+/// Stores the outcome of an attempt: status, attempt count, when to try
+/// again and the error, if any.
+pub fn record_webhook_attempt(db: &Db, delivery: &WebhookDelivery) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "UPDATE webhook_deliveries SET status = ?, attempts = ?, next_attempt_at = ?, last_error = ?, delivered_at = ? WHERE id = ?",
+        params![
+            delivery_status_to_str(delivery.status),
+            delivery.attempts,
+            delivery.next_attempt_at,
+            delivery.last_error,
+            delivery.delivered_at,
+            delivery.id,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Puts a failed delivery back in the queue with a fresh set of retries.
+pub fn requeue_webhook_delivery(db: &Db, delivery_id: i64, now: &str) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "UPDATE webhook_deliveries SET status = 'Pending', attempts = 0, next_attempt_at = ? WHERE id = ? AND status = 'Failed'",
+        params![now, delivery_id],
+    )?;
+    Ok(())
+}
+
+/// Claims a record for `session`, or refreshes the claim's heartbeat, and
+/// clears out claims whose heartbeat stopped a day ago.
+pub fn heartbeat_edit_lock(
+    db: &Db,
+    entity: &str,
+    entity_id: i64,
+    session: &str,
+    username: Option<&str>,
+) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "INSERT INTO edit_locks (entity, entity_id, session, username, heartbeat_at)
+         VALUES (?, ?, ?, ?, datetime('now'))
+         ON CONFLICT(entity, entity_id, session) DO UPDATE SET username = excluded.username, heartbeat_at = excluded.heartbeat_at",
+        params![entity, entity_id, session, username],
+    )?;
+    conn.execute(
+        "DELETE FROM edit_locks WHERE heartbeat_at < datetime('now', '-1 day')",
+        [],
+    )?;
+    Ok(())
+}
+
+pub fn release_edit_lock(db: &Db, entity: &str, entity_id: i64, session: &str) -> Result<()> {
+    let conn = db.get()?;
+    conn.execute(
+        "DELETE FROM edit_locks WHERE entity = ? AND entity_id = ? AND session = ?",
+        params![entity, entity_id, session],
+    )?;
+    Ok(())
+}
+
+/// Who, besides `session`, has claimed the record with a heartbeat in the
+/// last `stale_seconds`.
+pub fn get_other_editors(
+    db: &Db,
+    entity: &str,
+    entity_id: i64,
+    session: &str,
+    stale_seconds: u64,
+) -> Result<Vec<String>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT COALESCE(username, 'someone') FROM edit_locks
+         WHERE entity = ? AND entity_id = ? AND session != ?
+           AND heartbeat_at > datetime('now', '-' || ? || ' seconds')
+         ORDER BY 1",
+    )?;
+    let editors = stmt
+        .query_map(
+            params![entity, entity_id, session, stale_seconds as i64],
+            |row| row.get(0),
+        )?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(editors)
+}
+
+/// The patients `username` pinned, in the order they were pinned.
+pub fn get_pinned_patients(db: &Db, username: &str) -> Result<Vec<i64>> {
+    let conn = db.get()?;
+    let mut stmt = conn.prepare(
+        "SELECT patient_id FROM pinned_patients WHERE username = ? ORDER BY pinned_at, patient_id",
+    )?;
+    let pinned = stmt
+        .query_map(params![username], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<i64>>>()?;
+    Ok(pinned)
+}
+
+pub fn set_patient_pinned(db: &Db, username: &str, patient_id: i64, pinned: bool) -> Result<()> {
+    let conn = db.get()?;
+    if pinned {
+        conn.execute(
+            "INSERT OR IGNORE INTO pinned_patients (username, patient_id, pinned_at) VALUES (?, ?, ?)",
+            params![username, patient_id, datetime::now_text()],
+        )?;
+    } else {
+        conn.execute(
+            "DELETE FROM pinned_patients WHERE username = ? AND patient_id = ?",
+            params![username, patient_id],
+        )?;
+    }
     Ok(())
 }
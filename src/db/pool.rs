@@ -0,0 +1,181 @@
+//! Connections to the database file, kept open between calls so a screen
+//! that runs several queries per refresh does not reopen the file and
+//! reparse the schema each time. The [`Db`] is opened once at startup and
+//! handed to everything that queries it; connections are taken from it and
+//! go back to it when dropped. One is opened whenever none is idle, so
+//! nested and concurrent callers never wait on each other.
+
+use super::cache::Caches;
+use crate::utils::config;
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OpenFlags};
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Idle connections kept open; more are closed when handed back.
+const MAX_IDLE: usize = 4;
+/// How long a statement waits for another instance's write to finish.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Which connections a `data_version` was read from, as numbering differs
+/// between connections, and the version itself.
+pub type DataVersion = (u64, i64);
+
+/// The database file and its open connections. Clones share the same
+/// connections and cached lists.
+#[derive(Clone)]
+pub struct Db(Arc<Shared>);
+
+struct Shared {
+    path: PathBuf,
+    read_only: AtomicBool,
+    /// Whether the journal mode has been set on the file yet.
+    journal_set: AtomicBool,
+    idle: Mutex<Vec<Connection>>,
+    /// Changes when the database is reopened read-only, so connections
+    /// opened before are closed rather than handed out again.
+    generation: AtomicU64,
+    /// Only asked for `data_version`, so it counts every commit made on any
+    /// other connection to the file, in this process or another.
+    watch: Mutex<Option<Connection>>,
+    cache: Caches,
+}
+
+impl Db {
+    /// The database at `path`. Nothing is opened until the first query, which
+    /// also switches the journal to WAL (or back) as the configuration says.
+    pub fn new(path: &Path) -> Db {
+        Db(Arc::new(Shared {
+            path: path.to_path_buf(),
+            read_only: AtomicBool::new(false),
+            journal_set: AtomicBool::new(false),
+            idle: Mutex::new(Vec::new()),
+            generation: AtomicU64::new(0),
+            watch: Mutex::new(None),
+            cache: Caches::new(),
+        }))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0.path
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.0.read_only.load(Ordering::Relaxed)
+    }
+
+    /// Opens every later connection read-only, for a database whose schema
+    /// does not match this build. Writes then fail instead of damaging the
+    /// file.
+    pub fn set_read_only(&self) {
+        if self.0.read_only.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        self.0.generation.fetch_add(1, Ordering::Relaxed);
+        self.0
+            .idle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+        *self.0.watch.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        self.0.cache.invalidate_all();
+    }
+
+    pub(super) fn cache(&self) -> &Caches {
+        &self.0.cache
+    }
+
+    fn connect(&self) -> Result<Connection> {
+        let read_only = self.is_read_only();
+        let conn = if read_only {
+            Connection::open_with_flags(&self.0.path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        } else {
+            Connection::open(&self.0.path)
+        }
+        .context("Failed to open database connection")?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        if !read_only && !self.0.journal_set.swap(true, Ordering::Relaxed) {
+            let mode = if config::config().wal {
+                "WAL"
+            } else {
+                "DELETE"
+            };
+            // Another instance in the middle of a write keeps the current
+            // mode; the next start tries again.
+            let _ = conn.pragma_update(None, "journal_mode", mode);
+        }
+        Ok(conn)
+    }
+
+    /// A number that changes whenever a write is committed to the file,
+    /// whether by this instance or another one sharing it.
+    pub fn data_version(&self) -> Result<DataVersion> {
+        let mut watch = self.0.watch.lock().unwrap_or_else(|e| e.into_inner());
+        let generation = self.0.generation.load(Ordering::Relaxed);
+        let conn = match &mut *watch {
+            Some(conn) => conn,
+            None => watch.insert(self.connect()?),
+        };
+        let version = conn.query_row("PRAGMA data_version", [], |row| row.get(0))?;
+        Ok((generation, version))
+    }
+
+    /// An idle connection, or a new one when all are in use.
+    pub fn get(&self) -> Result<PooledConnection> {
+        let generation = self.0.generation.load(Ordering::Relaxed);
+        let idle = self.0.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        let conn = match idle {
+            Some(conn) => conn,
+            None => self.connect()?,
+        };
+        Ok(PooledConnection {
+            conn: Some(conn),
+            db: self.clone(),
+            generation,
+        })
+    }
+}
+
+/// A connection borrowed from a [`Db`], returned to it on drop.
+pub struct PooledConnection {
+    conn: Option<Connection>,
+    db: Db,
+    generation: u64,
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection already returned")
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.conn.as_mut().expect("connection already returned")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        let Some(conn) = self.conn.take() else {
+            return;
+        };
+        // A transaction left open by a panic is rolled back by closing.
+        if !conn.is_autocommit() {
+            return;
+        }
+        let shared = &self.db.0;
+        if shared.generation.load(Ordering::Relaxed) != self.generation {
+            return;
+        }
+        let mut idle = shared.idle.lock().unwrap_or_else(|e| e.into_inner());
+        if idle.len() < MAX_IDLE {
+            idle.push(conn);
+        }
+    }
+}
@@ -1,7 +1,8 @@
 CREATE TABLE IF NOT EXISTS users (
     id INTEGER PRIMARY KEY, 
     username TEXT NOT NULL UNIQUE,
-    password_hash TEXT NOT NULL
+    password_hash TEXT NOT NULL,
+    staff_id INTEGER
 );
 
 CREATE TABLE IF NOT EXISTS patients (
@@ -35,6 +36,15 @@ CREATE TABLE IF NOT EXISTS shifts (
     FOREIGN KEY (staff_id) REFERENCES staff(id) ON DELETE CASCADE
 );
 
+CREATE TABLE IF NOT EXISTS staff_leave (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    staff_id INTEGER NOT NULL,
+    start_date TEXT NOT NULL,
+    end_date TEXT NOT NULL,
+    reason TEXT,
+    FOREIGN KEY (staff_id) REFERENCES staff(id) ON DELETE CASCADE
+);
+
 CREATE TABLE IF NOT EXISTS medical_records (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     patient_id INTEGER NOT NULL, 
@@ -42,6 +52,7 @@ CREATE TABLE IF NOT EXISTS medical_records (
     nurse_notes TEXT, 
     diagnosis TEXT NOT NULL,
     prescription TEXT,
+    created_at TEXT,
     FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE
 );
 
@@ -52,4 +63,490 @@ CREATE TABLE IF NOT EXISTS invoices (
     quantity INTEGER NOT NULL,
     cost REAL NOT NULL,
     FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE
-);
\ No newline at end of file
+);
+
+CREATE TABLE IF NOT EXISTS expenses (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    department TEXT NOT NULL,
+    category TEXT NOT NULL,
+    amount REAL NOT NULL,
+    spent_on TEXT NOT NULL,
+    payee TEXT,
+    note TEXT,
+    recorded_by TEXT
+);
+CREATE TABLE IF NOT EXISTS budgets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    month TEXT NOT NULL,
+    department TEXT NOT NULL,
+    category TEXT,
+    amount REAL NOT NULL,
+    set_by TEXT
+);
+CREATE TABLE IF NOT EXISTS cashier_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    cashier TEXT NOT NULL,
+    opened_at TEXT NOT NULL,
+    opening_float REAL NOT NULL,
+    closed_at TEXT,
+    counted_cash REAL,
+    close_note TEXT
+);
+CREATE TABLE IF NOT EXISTS payments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    amount REAL NOT NULL,
+    method TEXT NOT NULL,
+    paid_at TEXT NOT NULL,
+    session_id INTEGER,
+    received_by TEXT,
+    reference TEXT,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE,
+    FOREIGN KEY (session_id) REFERENCES cashier_sessions(id) ON DELETE SET NULL
+);
+CREATE TABLE IF NOT EXISTS insurance_plans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    insurer TEXT
+);
+CREATE TABLE IF NOT EXISTS plan_coverage (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    plan_id INTEGER NOT NULL,
+    category TEXT NOT NULL,
+    percent REAL NOT NULL,
+    annual_cap REAL,
+    UNIQUE (plan_id, category),
+    FOREIGN KEY (plan_id) REFERENCES insurance_plans(id) ON DELETE CASCADE
+);
+CREATE TABLE IF NOT EXISTS patient_insurance (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL UNIQUE,
+    plan_id INTEGER NOT NULL,
+    member_number TEXT,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE,
+    FOREIGN KEY (plan_id) REFERENCES insurance_plans(id) ON DELETE CASCADE
+);
+CREATE TABLE IF NOT EXISTS price_overrides (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    invoice_id INTEGER NOT NULL,
+    list_price REAL NOT NULL,
+    requested_price REAL NOT NULL,
+    reason TEXT NOT NULL,
+    requested_by TEXT NOT NULL,
+    requested_at TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'Pending',
+    decided_by TEXT,
+    decided_at TEXT,
+    FOREIGN KEY (invoice_id) REFERENCES invoices(id) ON DELETE CASCADE
+);
+-- Printed invoices and receipts, numbered once when issued so a reprint
+-- carries the same number, with the invoice lines each one covers.
+CREATE TABLE IF NOT EXISTS invoice_documents (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_number TEXT NOT NULL UNIQUE,
+    patient_id INTEGER NOT NULL,
+    receipt INTEGER NOT NULL,
+    issued_on TEXT NOT NULL,
+    issued_by TEXT,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE
+);
+CREATE TABLE IF NOT EXISTS invoice_document_lines (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL,
+    invoice_id INTEGER NOT NULL,
+    UNIQUE (document_id, invoice_id),
+    FOREIGN KEY (document_id) REFERENCES invoice_documents(id) ON DELETE CASCADE,
+    FOREIGN KEY (invoice_id) REFERENCES invoices(id) ON DELETE CASCADE
+);
+CREATE TABLE IF NOT EXISTS procedures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    code TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    price REAL NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS waitlist (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    doctor_id INTEGER NOT NULL,
+    procedure TEXT NOT NULL,
+    priority TEXT NOT NULL,
+    preferred_date TEXT,
+    notes TEXT,
+    status TEXT NOT NULL DEFAULT 'Waiting',
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE,
+    FOREIGN KEY (doctor_id) REFERENCES staff(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS births (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    mother_id INTEGER NOT NULL,
+    newborn_name TEXT NOT NULL,
+    sex TEXT NOT NULL,
+    birth_time TEXT NOT NULL,
+    weight_grams INTEGER,
+    delivering_doctor_id INTEGER NOT NULL,
+    notes TEXT,
+    FOREIGN KEY (mother_id) REFERENCES patients(id),
+    FOREIGN KEY (delivering_doctor_id) REFERENCES staff(id)
+);
+
+CREATE TABLE IF NOT EXISTS deaths (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL UNIQUE,
+    time_of_death TEXT NOT NULL,
+    cause TEXT NOT NULL,
+    certifying_doctor_id INTEGER NOT NULL,
+    place TEXT,
+    notes TEXT,
+    FOREIGN KEY (patient_id) REFERENCES patients(id),
+    FOREIGN KEY (certifying_doctor_id) REFERENCES staff(id)
+);
+
+CREATE TABLE IF NOT EXISTS patient_documents (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    doc_type TEXT NOT NULL,
+    document_date TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    notes TEXT,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS imaging_studies (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    accession_number TEXT NOT NULL,
+    modality TEXT NOT NULL,
+    study_date TEXT NOT NULL,
+    description TEXT,
+    order_number TEXT,
+    report_status TEXT NOT NULL,
+    reference TEXT NOT NULL,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS teleconsultations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    doctor_id INTEGER NOT NULL,
+    scheduled_at TEXT NOT NULL,
+    contact TEXT NOT NULL,
+    duration_minutes INTEGER,
+    status TEXT NOT NULL DEFAULT 'Scheduled',
+    outcome TEXT,
+    medical_record_id INTEGER,
+    checked_in_at TEXT,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE,
+    FOREIGN KEY (doctor_id) REFERENCES staff(id) ON DELETE CASCADE,
+    FOREIGN KEY (medical_record_id) REFERENCES medical_records(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS appointments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    doctor_id INTEGER NOT NULL,
+    scheduled_at TEXT NOT NULL,
+    duration_minutes INTEGER NOT NULL,
+    reason TEXT,
+    status TEXT NOT NULL DEFAULT 'Scheduled',
+    cancel_reason TEXT,
+    booked_by TEXT,
+    checked_in_at TEXT,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE,
+    FOREIGN KEY (doctor_id) REFERENCES staff(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS queue_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token_number INTEGER NOT NULL,
+    patient_id INTEGER NOT NULL,
+    doctor_id INTEGER NOT NULL,
+    queue_date TEXT NOT NULL,
+    issued_at TEXT NOT NULL,
+    called_at TEXT,
+    status TEXT NOT NULL DEFAULT 'Waiting',
+    UNIQUE (doctor_id, queue_date, token_number),
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE,
+    FOREIGN KEY (doctor_id) REFERENCES staff(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS patient_feedback (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    doctor_id INTEGER NOT NULL,
+    teleconsultation_id INTEGER,
+    queue_token_id INTEGER,
+    rating INTEGER NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    comment TEXT,
+    created_at TEXT NOT NULL,
+    followed_up_at TEXT,
+    follow_up_note TEXT,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE,
+    FOREIGN KEY (doctor_id) REFERENCES staff(id) ON DELETE CASCADE,
+    FOREIGN KEY (teleconsultation_id) REFERENCES teleconsultations(id) ON DELETE SET NULL,
+    FOREIGN KEY (queue_token_id) REFERENCES queue_tokens(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS handover_notes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ward TEXT NOT NULL,
+    handover_date TEXT NOT NULL,
+    shift TEXT NOT NULL,
+    author_id INTEGER NOT NULL,
+    critical_patients TEXT NOT NULL,
+    pending_tasks TEXT NOT NULL,
+    notes TEXT,
+    created_at TEXT NOT NULL,
+    acknowledged_by INTEGER,
+    acknowledged_at TEXT,
+    FOREIGN KEY (author_id) REFERENCES staff(id),
+    FOREIGN KEY (acknowledged_by) REFERENCES staff(id)
+);
+
+CREATE TABLE IF NOT EXISTS admissions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    ward TEXT NOT NULL,
+    bed TEXT,
+    admitted_at TEXT NOT NULL,
+    discharged_at TEXT,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS planned_admissions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    ward TEXT NOT NULL,
+    planned_date TEXT NOT NULL,
+    admission_id INTEGER,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE,
+    FOREIGN KEY (admission_id) REFERENCES admissions(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS observations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    recorded_at TEXT NOT NULL,
+    temperature REAL,
+    pulse INTEGER,
+    blood_pressure TEXT,
+    respiratory_rate INTEGER,
+    spo2 INTEGER,
+    note TEXT,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS care_plans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    condition TEXT NOT NULL,
+    review_interval_days INTEGER NOT NULL,
+    doctor_id INTEGER,
+    nurse_id INTEGER,
+    next_due TEXT NOT NULL,
+    last_reviewed TEXT,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE,
+    FOREIGN KEY (doctor_id) REFERENCES staff(id) ON DELETE SET NULL,
+    FOREIGN KEY (nurse_id) REFERENCES staff(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS pregnancies (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    edd TEXT NOT NULL,
+    gravida INTEGER NOT NULL,
+    para INTEGER NOT NULL,
+    risk_factors TEXT,
+    birth_id INTEGER,
+    closed_on TEXT,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE,
+    FOREIGN KEY (birth_id) REFERENCES births(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS antenatal_visits (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pregnancy_id INTEGER NOT NULL,
+    visit_date TEXT NOT NULL,
+    blood_pressure TEXT,
+    weight_kg REAL,
+    fundal_height_cm INTEGER,
+    fetal_heart_rate INTEGER,
+    staff_id INTEGER,
+    notes TEXT,
+    FOREIGN KEY (pregnancy_id) REFERENCES pregnancies(id) ON DELETE CASCADE,
+    FOREIGN KEY (staff_id) REFERENCES staff(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS prescribing_overrides (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    medical_record_id INTEGER NOT NULL,
+    severity TEXT NOT NULL,
+    warning TEXT NOT NULL,
+    reason TEXT,
+    overridden_by TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (medical_record_id) REFERENCES medical_records(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS immunizations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    medical_record_id INTEGER,
+    vaccine TEXT NOT NULL,
+    dose TEXT,
+    lot_number TEXT,
+    given_on TEXT NOT NULL,
+    given_by INTEGER,
+    campaign TEXT,
+    FOREIGN KEY (patient_id) REFERENCES patients(id) ON DELETE CASCADE,
+    FOREIGN KEY (medical_record_id) REFERENCES medical_records(id) ON DELETE SET NULL,
+    FOREIGN KEY (given_by) REFERENCES staff(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS data_requests (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    patient_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    reason TEXT,
+    handled_by TEXT,
+    details TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (patient_id) REFERENCES patients(id)
+);
+
+CREATE TABLE IF NOT EXISTS stock_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    code TEXT NOT NULL,
+    name TEXT NOT NULL,
+    unit TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+-- A count that freezes what the ledger expects of each item when it starts.
+CREATE TABLE IF NOT EXISTS stocktakes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    status TEXT NOT NULL,
+    started_by TEXT NOT NULL,
+    started_at TEXT NOT NULL,
+    posted_by TEXT,
+    posted_at TEXT
+);
+
+CREATE TABLE IF NOT EXISTS stocktake_lines (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    stocktake_id INTEGER NOT NULL,
+    item_id INTEGER NOT NULL,
+    expected INTEGER NOT NULL,
+    counted INTEGER,
+    UNIQUE (stocktake_id, item_id),
+    FOREIGN KEY (stocktake_id) REFERENCES stocktakes(id) ON DELETE CASCADE,
+    FOREIGN KEY (item_id) REFERENCES stock_items(id)
+);
+
+-- The stock ledger: what is on hand is the sum of the quantities, so
+-- movements are only ever added, never edited.
+CREATE TABLE IF NOT EXISTS inventory_transactions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    item_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    quantity INTEGER NOT NULL,
+    reference TEXT,
+    posted_by TEXT NOT NULL,
+    posted_at TEXT NOT NULL,
+    FOREIGN KEY (item_id) REFERENCES stock_items(id)
+);
+
+CREATE INDEX IF NOT EXISTS inventory_transactions_item ON inventory_transactions (item_id);
+
+-- Stable stand-ins for patients in research exports. Kept local, like the
+-- settings, so the link back to a patient never leaves this database.
+CREATE TABLE IF NOT EXISTS research_pseudonyms (
+    patient_id INTEGER PRIMARY KEY,
+    pseudonym TEXT NOT NULL UNIQUE
+);
+
+-- Who has a record open in an update screen. Local to this database: a
+-- claim means nothing on another site.
+CREATE TABLE IF NOT EXISTS edit_locks (
+    entity TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    session TEXT NOT NULL,
+    username TEXT,
+    heartbeat_at TEXT NOT NULL,
+    PRIMARY KEY (entity, entity_id, session)
+);
+
+-- Patients each user keeps at the top of their lists, such as their
+-- current inpatients. Local to this database, like edit_locks.
+CREATE TABLE IF NOT EXISTS pinned_patients (
+    username TEXT NOT NULL,
+    patient_id INTEGER NOT NULL,
+    pinned_at TEXT NOT NULL,
+    PRIMARY KEY (username, patient_id)
+);
+
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+-- Last number handed out by each counter, such as "mrn" or "receipt".
+CREATE TABLE IF NOT EXISTS sequences (
+    name TEXT PRIMARY KEY,
+    value INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS sync_rows (
+    table_name TEXT NOT NULL,
+    local_id INTEGER NOT NULL,
+    uid TEXT NOT NULL UNIQUE,
+    PRIMARY KEY (table_name, local_id)
+);
+
+CREATE TABLE IF NOT EXISTS change_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    origin TEXT NOT NULL,
+    origin_seq INTEGER,
+    table_name TEXT NOT NULL,
+    uid TEXT NOT NULL,
+    operation TEXT NOT NULL,
+    payload TEXT,
+    changed_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS change_log_uid ON change_log (uid);
+CREATE INDEX IF NOT EXISTS change_log_origin ON change_log (origin, origin_seq);
+
+CREATE TABLE IF NOT EXISTS sync_peers (
+    origin TEXT PRIMARY KEY,
+    last_seq INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS sync_guard (
+    active INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event TEXT NOT NULL,
+    url TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TEXT NOT NULL,
+    last_error TEXT,
+    created_at TEXT NOT NULL,
+    delivered_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS webhook_deliveries_due ON webhook_deliveries (status, next_attempt_at);
+
+CREATE TABLE IF NOT EXISTS custom_fields (
+    entity TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (entity, entity_id, name)
+);
@@ -0,0 +1,737 @@
+//! Export/import sync for instances that are rarely online at the same time.
+//!
+//! Triggers record every insert, update and delete of clinical data in
+//! `change_log`, keyed by a random per-row `uid` (kept in `sync_rows`) rather
+//! than the local integer id, and with references to other rows stored as
+//! their uids too. `rustoria --sync <dir>` then merges the change sets other
+//! instances left in `<dir>` and writes this instance's own set there, so two
+//! machines sharing a folder or a USB stick converge.
+//!
+//! Conflicts are settled per row: the change with the later timestamp wins;
+//! on a tie a delete beats an edit, then the higher instance id wins. A change
+//! that refers to a row this instance has never seen is retried after the
+//! rest of the batch and reported if it still cannot be placed.
+
+use super::{assign_missing_mrns, next_patient_id, Db};
+use anyhow::{Context, Result};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the change set file layout changes incompatibly.
+const FORMAT: u32 = 1;
+
+/// Tables whose rows are synced, parents before the tables referring to them.
+/// Login accounts and settings stay local to each instance.
+const SYNCED_TABLES: &[&str] = &[
+    "patients",
+    "staff",
+    "shifts",
+    "staff_leave",
+    "medical_records",
+    "invoices",
+    "expenses",
+    "budgets",
+    "cashier_sessions",
+    "payments",
+    "insurance_plans",
+    "plan_coverage",
+    "patient_insurance",
+    "price_overrides",
+    "invoice_documents",
+    "invoice_document_lines",
+    "waitlist",
+    "births",
+    "deaths",
+    "patient_documents",
+    "imaging_studies",
+    "teleconsultations",
+    "appointments",
+    "queue_tokens",
+    "patient_feedback",
+    "handover_notes",
+    "admissions",
+    "planned_admissions",
+    "observations",
+    "care_plans",
+    "pregnancies",
+    "antenatal_visits",
+    "prescribing_overrides",
+    "immunizations",
+    "data_requests",
+    "stock_items",
+    "stocktakes",
+    "stocktake_lines",
+    "inventory_transactions",
+];
+
+const INSTANCE: &str = "(SELECT value FROM settings WHERE key = 'instance_id')";
+const NOW: &str = "strftime('%Y-%m-%dT%H:%M:%fZ', 'now')";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Operation {
+    Upsert,
+    Delete,
+}
+
+impl Operation {
+    fn as_str(self) -> &'static str {
+        match self {
+            Operation::Upsert => "upsert",
+            Operation::Delete => "delete",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        if value == "delete" {
+            Operation::Delete
+        } else {
+            Operation::Upsert
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Change {
+    origin: String,
+    seq: i64,
+    table: String,
+    uid: String,
+    operation: Operation,
+    changed_at: String,
+    row: Option<Map<String, Value>>,
+}
+
+impl Change {
+    /// Orders competing changes to one row; the greatest wins.
+    fn precedence(&self) -> (&str, Operation, &str) {
+        (&self.changed_at, self.operation, &self.origin)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChangeSet {
+    format: u32,
+    instance: String,
+    exported_at: String,
+    changes: Vec<Change>,
+}
+
+/// What a sync did, printed by `--sync`.
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub exported: usize,
+    pub export_path: PathBuf,
+    pub files: usize,
+    pub applied: usize,
+    pub conflicts: usize,
+    pub skipped: Vec<String>,
+}
+
+impl fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Applied {} change(s) from {} other instance file(s); {} older conflicting change(s) discarded.",
+            self.applied, self.files, self.conflicts
+        )?;
+        write!(
+            f,
+            "Exported {} change(s) to {}.",
+            self.exported,
+            self.export_path.display()
+        )?;
+        if !self.skipped.is_empty() {
+            write!(
+                f,
+                "\n{} change(s) could not be applied:",
+                self.skipped.len()
+            )?;
+            for reason in &self.skipped {
+                write!(f, "\n  - {}", reason)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct TableInfo {
+    columns: Vec<String>,
+    /// Column name to the table it references.
+    parents: HashMap<String, String>,
+}
+
+impl TableInfo {
+    fn read(conn: &Connection, table: &str) -> Result<Self> {
+        let columns = conn
+            .prepare(&format!("PRAGMA table_info({})", table))?
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let parents = conn
+            .prepare(&format!("PRAGMA foreign_key_list({})", table))?
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(3)?, row.get::<_, String>(2)?))
+            })?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(Self { columns, parents })
+    }
+
+    fn data_columns(&self) -> impl Iterator<Item = &String> {
+        self.columns.iter().filter(|column| *column != "id")
+    }
+
+    /// SQL building the JSON payload of `row` (`NEW`, `OLD` or a table alias),
+    /// with references written as uids.
+    fn payload(&self, row: &str) -> String {
+        let fields: Vec<String> = self
+            .data_columns()
+            .map(|column| match self.parents.get(column) {
+                Some(parent) => format!(
+                    "'{}', {}",
+                    column,
+                    uid_of(parent, &format!("{}.{}", row, column))
+                ),
+                None => format!("'{}', {}.{}", column, row, column),
+            })
+            .collect();
+        format!("json_object({})", fields.join(", "))
+    }
+}
+
+fn uid_of(table: &str, local_id: &str) -> String {
+    format!(
+        "(SELECT uid FROM sync_rows WHERE table_name = '{}' AND local_id = {})",
+        table, local_id
+    )
+}
+
+fn log_statement(table: &str, info: &TableInfo, row: &str, operation: Operation) -> String {
+    let payload = match operation {
+        Operation::Upsert => info.payload(row),
+        Operation::Delete => "NULL".to_string(),
+    };
+    format!(
+        "INSERT INTO change_log (origin, table_name, uid, operation, payload, changed_at)
+         VALUES ({}, '{}', {}, '{}', {}, {});",
+        INSTANCE,
+        table,
+        uid_of(table, &format!("{}.id", row)),
+        operation.as_str(),
+        payload,
+        NOW
+    )
+}
+
+/// Gives this database an instance id, (re)creates the change-recording
+/// triggers so they cover every current column, and logs rows that predate
+/// them so the first export carries the existing data.
+pub(super) fn install(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES ('instance_id', lower(hex(randomblob(8))))",
+        [],
+    )?;
+    conn.execute("DELETE FROM sync_guard", [])?;
+
+    for table in SYNCED_TABLES {
+        let info = TableInfo::read(conn, table)?;
+        let guard = "WHEN NOT EXISTS (SELECT 1 FROM sync_guard)";
+        conn.execute_batch(&format!(
+            "DROP TRIGGER IF EXISTS sync_{t}_insert;
+             CREATE TRIGGER sync_{t}_insert AFTER INSERT ON {t} {guard} BEGIN
+                 INSERT OR REPLACE INTO sync_rows (table_name, local_id, uid)
+                 VALUES ('{t}', NEW.id, lower(hex(randomblob(16))));
+                 {insert}
+             END;
+             DROP TRIGGER IF EXISTS sync_{t}_update;
+             CREATE TRIGGER sync_{t}_update AFTER UPDATE ON {t} {guard} BEGIN
+                 {update}
+             END;
+             DROP TRIGGER IF EXISTS sync_{t}_delete;
+             CREATE TRIGGER sync_{t}_delete AFTER DELETE ON {t} {guard} BEGIN
+                 {delete}
+             END;",
+            t = table,
+            guard = guard,
+            insert = log_statement(table, &info, "NEW", Operation::Upsert),
+            update = log_statement(table, &info, "NEW", Operation::Upsert),
+            delete = log_statement(table, &info, "OLD", Operation::Delete),
+        ))
+        .with_context(|| format!("Failed to install sync triggers on {}", table))?;
+
+        conn.execute(
+            &format!(
+                "INSERT INTO sync_rows (table_name, local_id, uid)
+                 SELECT '{t}', id, lower(hex(randomblob(16))) FROM {t}
+                 WHERE id NOT IN (SELECT local_id FROM sync_rows WHERE table_name = '{t}')",
+                t = table
+            ),
+            [],
+        )?;
+        conn.execute(
+            &format!(
+                "INSERT INTO change_log (origin, table_name, uid, operation, payload, changed_at)
+                 SELECT {instance}, '{t}', m.uid, 'upsert', {payload}, {now}
+                 FROM {t} r JOIN sync_rows m ON m.table_name = '{t}' AND m.local_id = r.id
+                 WHERE NOT EXISTS (SELECT 1 FROM change_log c WHERE c.uid = m.uid)
+                 ORDER BY r.id",
+                instance = INSTANCE,
+                t = table,
+                payload = info.payload("r"),
+                now = NOW
+            ),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+pub(super) fn instance_id(conn: &Connection) -> Result<String> {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = 'instance_id'",
+        [],
+        |row| row.get(0),
+    )
+    .context("This database has no sync instance id")
+}
+
+/// Drops every recorded state but the latest of the patient with uid
+/// `patient_uid` and of their newborns' and document rows, so details that
+/// have been erased do not live on in the history. The latest states stay
+/// for the next export to carry. Runs where the erasure is made and again on
+/// each peer that imports its data request.
+pub(super) fn forget_patient_history(conn: &Connection, patient_uid: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM change_log
+         WHERE uid IN (SELECT ?1 UNION
+                       SELECT uid FROM change_log
+                       WHERE (table_name = 'births'
+                              AND json_extract(payload, '$.mother_id') = ?1)
+                          OR (table_name = 'patient_documents'
+                              AND json_extract(payload, '$.patient_id') = ?1))
+           AND id < (SELECT MAX(c.id) FROM change_log c WHERE c.uid = change_log.uid)",
+        params![patient_uid],
+    )?;
+    Ok(())
+}
+
+/// The uid of local row `local_id` of `table`.
+pub(super) fn uid(conn: &Connection, table: &str, local_id: i64) -> Result<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT uid FROM sync_rows WHERE table_name = ? AND local_id = ?",
+            params![table, local_id],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// One recorded state of a row, as kept in `change_log`.
+#[derive(Debug, Clone)]
+pub struct RowVersion {
+    /// The instance the change was made on.
+    pub origin: String,
+    pub changed_at: String,
+    /// The row's columns after the change, or None when it was deleted.
+    /// References are uids, not local ids.
+    pub row: Option<Map<String, Value>>,
+}
+
+/// Every recorded state of row `local_id` of `table`, newest first.
+pub fn history(db: &Db, table: &str, local_id: i64) -> Result<Vec<RowVersion>> {
+    let conn = db.get()?;
+    let versions = conn
+        .prepare(
+            "SELECT c.origin, c.changed_at, c.operation, c.payload
+             FROM change_log c JOIN sync_rows m ON m.uid = c.uid
+             WHERE m.table_name = ? AND m.local_id = ?
+             ORDER BY c.id DESC",
+        )?
+        .query_map(params![table, local_id], |row| {
+            let payload: Option<String> = row.get(3)?;
+            let deleted = Operation::parse(&row.get::<_, String>(2)?) == Operation::Delete;
+            Ok(RowVersion {
+                origin: row.get(0)?,
+                changed_at: row.get(1)?,
+                row: payload
+                    .filter(|_| !deleted)
+                    .and_then(|json| serde_json::from_str(&json).ok()),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(versions)
+}
+
+/// Merges the change sets other instances left in `dir`, then writes this
+/// instance's own change set there as `<instance id>.json`.
+pub fn sync_with(db: &Db, dir: &Path) -> Result<SyncReport> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let mut conn = db.get()?;
+    let instance = instance_id(&conn)?;
+    let mut report = SyncReport::default();
+
+    let incoming = read_change_sets(&conn, dir, &instance, &mut report)?;
+    import(&mut conn, incoming, &mut report)?;
+    super::cache::invalidate_all(db);
+
+    let (path, exported) = export(&conn, dir, &instance)?;
+    report.export_path = path;
+    report.exported = exported;
+    Ok(report)
+}
+
+/// Changes from other instances that this one has not considered yet, oldest
+/// first.
+fn read_change_sets(
+    conn: &Connection,
+    dir: &Path,
+    instance: &str,
+    report: &mut SyncReport,
+) -> Result<Vec<Change>> {
+    let watermarks: HashMap<String, i64> = conn
+        .prepare("SELECT origin, last_seq FROM sync_peers")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+
+    let mut changes = BTreeMap::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        let is_peer = path.extension().is_some_and(|ext| ext == "json")
+            && path.file_stem().is_some_and(|stem| stem != instance);
+        if !is_peer {
+            continue;
+        }
+        let set = fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Ok(serde_json::from_str::<ChangeSet>(&json)?));
+        let set = match set {
+            Ok(set) if set.format == FORMAT => set,
+            Ok(set) => {
+                report.skipped.push(format!(
+                    "{}: change set format {} is not supported by this version",
+                    path.display(),
+                    set.format
+                ));
+                continue;
+            }
+            Err(e) => {
+                report
+                    .skipped
+                    .push(format!("{}: unreadable change set ({})", path.display(), e));
+                continue;
+            }
+        };
+        report.files += 1;
+        for change in set.changes {
+            let seen = watermarks.get(&change.origin).copied().unwrap_or(0);
+            if change.origin != instance && change.seq > seen {
+                changes.insert((change.origin.clone(), change.seq), change);
+            }
+        }
+    }
+
+    let mut changes: Vec<Change> = changes.into_values().collect();
+    changes.sort_by(|a, b| a.precedence().cmp(&b.precedence()));
+    Ok(changes)
+}
+
+enum Outcome {
+    Applied,
+    AlreadyApplied,
+    Superseded,
+    MissingReference,
+    Rejected(String),
+}
+
+fn import(conn: &mut Connection, changes: Vec<Change>, report: &mut SyncReport) -> Result<()> {
+    let tx = conn.transaction()?;
+    // Keeps the triggers from logging the merge as this instance's own edits.
+    tx.execute("INSERT INTO sync_guard (active) VALUES (1)", [])?;
+
+    let mut tables = HashMap::new();
+    for table in SYNCED_TABLES {
+        tables.insert(table.to_string(), TableInfo::read(&tx, table)?);
+    }
+
+    let mut watermarks: HashMap<String, i64> = HashMap::new();
+    for change in &changes {
+        let seq = watermarks.entry(change.origin.clone()).or_default();
+        *seq = (*seq).max(change.seq);
+    }
+
+    let mut erased = Vec::new();
+    let mut pending = changes;
+    loop {
+        let before = pending.len();
+        let mut deferred = Vec::new();
+        for change in pending {
+            let Some(info) = tables.get(&change.table) else {
+                report.skipped.push(format!(
+                    "{} {}: table is not synced",
+                    change.table, change.uid
+                ));
+                continue;
+            };
+            match apply(&tx, info, &change)? {
+                Outcome::Applied => {
+                    report.applied += 1;
+                    if let Some(patient) = erased_patient(&change) {
+                        erased.push(patient);
+                    }
+                }
+                Outcome::AlreadyApplied => {}
+                Outcome::Superseded => report.conflicts += 1,
+                Outcome::MissingReference => deferred.push(change),
+                Outcome::Rejected(reason) => report
+                    .skipped
+                    .push(format!("{} {}: {}", change.table, change.uid, reason)),
+            }
+        }
+        if deferred.is_empty() || deferred.len() == before {
+            for change in deferred {
+                // Retry these next time, once the rows they refer to arrive.
+                let seq = watermarks.entry(change.origin.clone()).or_default();
+                *seq = (*seq).min(change.seq - 1);
+                report.skipped.push(format!(
+                    "{} {}: refers to a record this instance does not have yet",
+                    change.table, change.uid
+                ));
+            }
+            break;
+        }
+        pending = deferred;
+    }
+    // Erasures made elsewhere reach this instance as the anonymized rows plus
+    // their data request; the earlier versions logged here go as well.
+    for patient_uid in erased {
+        forget_patient_history(&tx, &patient_uid)?;
+    }
+
+    for (origin, seq) in watermarks {
+        tx.execute(
+            "INSERT INTO sync_peers (origin, last_seq) VALUES (?, ?)
+             ON CONFLICT(origin) DO UPDATE SET last_seq = MAX(last_seq, excluded.last_seq)",
+            params![origin, seq],
+        )?;
+    }
+    tx.execute("DELETE FROM sync_guard", [])?;
+    assign_missing_mrns(&tx)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// The patient uid of an applied erasure request.
+fn erased_patient(change: &Change) -> Option<String> {
+    let row = change.row.as_ref()?;
+    if change.table != "data_requests" || row.get("kind")?.as_str()? != "Erasure" {
+        return None;
+    }
+    row.get("patient_id")?.as_str().map(str::to_string)
+}
+
+fn local_id(conn: &Connection, table: &str, uid: &str) -> Result<Option<i64>> {
+    Ok(conn
+        .query_row(
+            "SELECT local_id FROM sync_rows WHERE table_name = ? AND uid = ?",
+            params![table, uid],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+fn apply(conn: &Connection, info: &TableInfo, change: &Change) -> Result<Outcome> {
+    let already: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM change_log WHERE origin = ? AND origin_seq = ?)",
+        params![change.origin, change.seq],
+        |row| row.get(0),
+    )?;
+    if already {
+        return Ok(Outcome::AlreadyApplied);
+    }
+
+    let latest = conn
+        .query_row(
+            "SELECT origin, operation, changed_at FROM change_log
+             WHERE uid = ? ORDER BY id DESC LIMIT 1",
+            params![change.uid],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            },
+        )
+        .optional()?;
+    if let Some((origin, operation, changed_at)) = &latest {
+        let local = (
+            changed_at.as_str(),
+            Operation::parse(operation),
+            origin.as_str(),
+        );
+        if local >= change.precedence() {
+            return Ok(Outcome::Superseded);
+        }
+    }
+
+    let table = &change.table;
+    let existing = match local_id(conn, table, &change.uid)? {
+        Some(id) => conn
+            .query_row(
+                &format!("SELECT id FROM {} WHERE id = ?", table),
+                [id],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?,
+        None => None,
+    };
+
+    let written = match change.operation {
+        Operation::Delete => match existing {
+            Some(id) => conn.execute(&format!("DELETE FROM {} WHERE id = ?", table), [id]),
+            None => Ok(0),
+        },
+        Operation::Upsert => {
+            let Some(row) = &change.row else {
+                return Ok(Outcome::Rejected("change has no row data".to_string()));
+            };
+            let mut columns = Vec::new();
+            let mut values = Vec::new();
+            for column in info.data_columns() {
+                let Some(value) = row.get(column) else {
+                    continue;
+                };
+                let value = match (info.parents.get(column), value.as_str()) {
+                    (Some(parent), Some(uid)) => match local_id(conn, parent, uid)? {
+                        Some(id) => SqlValue::Integer(id),
+                        None => return Ok(Outcome::MissingReference),
+                    },
+                    (Some(_), None) => SqlValue::Null,
+                    (None, _) => to_sql(value),
+                };
+                columns.push(column.as_str());
+                values.push(value);
+            }
+            match existing {
+                Some(id) => {
+                    let assignments: Vec<String> =
+                        columns.iter().map(|c| format!("{} = ?", c)).collect();
+                    values.push(SqlValue::Integer(id));
+                    conn.execute(
+                        &format!(
+                            "UPDATE {} SET {} WHERE id = ?",
+                            table,
+                            assignments.join(", ")
+                        ),
+                        params_from_iter(values),
+                    )
+                }
+                None => {
+                    // Patients take their ids from the same counter as
+                    // those registered here; their MRNs come with them.
+                    if table == "patients" {
+                        columns.push("id");
+                        values.push(SqlValue::Integer(next_patient_id(conn)?));
+                    }
+                    let placeholders = vec!["?"; columns.len()].join(", ");
+                    conn.execute(
+                        &format!(
+                            "INSERT INTO {} ({}) VALUES ({})",
+                            table,
+                            columns.join(", "),
+                            placeholders
+                        ),
+                        params_from_iter(values),
+                    )
+                    .and_then(|inserted| {
+                        conn.execute(
+                            "INSERT OR REPLACE INTO sync_rows (table_name, local_id, uid)
+                             VALUES (?, ?, ?)",
+                            params![table, conn.last_insert_rowid(), change.uid],
+                        )?;
+                        Ok(inserted)
+                    })
+                }
+            }
+        }
+    };
+    if let Err(e) = written {
+        return Ok(Outcome::Rejected(e.to_string()));
+    }
+
+    conn.execute(
+        "INSERT INTO change_log (origin, origin_seq, table_name, uid, operation, payload, changed_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        params![
+            change.origin,
+            change.seq,
+            table,
+            change.uid,
+            change.operation.as_str(),
+            change
+                .row
+                .as_ref()
+                .map(|row| Value::Object(row.clone()).to_string()),
+            change.changed_at,
+        ],
+    )?;
+    Ok(Outcome::Applied)
+}
+
+/// Writes the latest change to every row this instance knows about, so a
+/// peer catches up on edits it received from a third instance as well.
+fn export(conn: &Connection, dir: &Path, instance: &str) -> Result<(PathBuf, usize)> {
+    let changes = conn
+        .prepare(
+            "SELECT origin, COALESCE(origin_seq, id), table_name, uid, operation, payload, changed_at
+             FROM change_log
+             WHERE id IN (SELECT MAX(id) FROM change_log GROUP BY uid)
+             ORDER BY id",
+        )?
+        .query_map([], |row| {
+            let payload: Option<String> = row.get(5)?;
+            Ok(Change {
+                origin: row.get(0)?,
+                seq: row.get(1)?,
+                table: row.get(2)?,
+                uid: row.get(3)?,
+                operation: Operation::parse(&row.get::<_, String>(4)?),
+                changed_at: row.get(6)?,
+                row: payload.and_then(|json| serde_json::from_str(&json).ok()),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let exported_at: String = conn.query_row(&format!("SELECT {}", NOW), [], |row| row.get(0))?;
+
+    let count = changes.len();
+    let set = ChangeSet {
+        format: FORMAT,
+        instance: instance.to_string(),
+        exported_at,
+        changes,
+    };
+    let path = dir.join(format!("{}.json", instance));
+    let partial = dir.join(format!("{}.json.partial", instance));
+    fs::write(&partial, serde_json::to_string(&set)?)
+        .with_context(|| format!("Failed to write {}", partial.display()))?;
+    fs::rename(&partial, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok((path, count))
+}
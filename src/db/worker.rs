@@ -0,0 +1,128 @@
+use anyhow::{anyhow, Result};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Instant;
+
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+type Job = Box<dyn FnOnce() + Send>;
+
+static WORKER: OnceLock<Mutex<Sender<Job>>> = OnceLock::new();
+
+/// Starts the worker on first use. Jobs run one at a time, in submission
+/// order, so background writes never contend with each other for SQLite.
+fn worker() -> &'static Mutex<Sender<Job>> {
+    WORKER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        thread::Builder::new()
+            .name("rustoria-db".to_string())
+            .spawn(move || {
+                for job in receiver {
+                    job();
+                }
+            })
+            .expect("Failed to start database worker");
+        Mutex::new(sender)
+    })
+}
+
+/// Progress and cancellation shared between a job and its [`Task`].
+#[derive(Debug, Default)]
+pub struct Progress {
+    done: AtomicU64,
+    total: AtomicU64,
+    cancelled: AtomicBool,
+}
+
+impl Progress {
+    /// Reports how far the job has got; `total` of zero means unknown.
+    pub fn set(&self, done: u64, total: u64) {
+        self.done.store(done, Ordering::Relaxed);
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    /// Jobs that can stop part-way check this between steps.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Handle to a job queued on the database worker.
+pub struct Task<T> {
+    receiver: Receiver<Result<T>>,
+    started: Instant,
+    progress: Arc<Progress>,
+}
+
+impl<T> Task<T> {
+    /// Returns the result once the job has finished, without blocking.
+    pub fn poll(&self) -> Option<Result<T>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow!("Background task was aborted"))),
+        }
+    }
+
+    /// Spinner frame for progress indicators, advancing with elapsed time.
+    pub fn spinner(&self) -> &'static str {
+        SPINNER[(self.started.elapsed().as_millis() / 100) as usize % SPINNER.len()]
+    }
+
+    /// Completed fraction in `0.0..=1.0`, or `None` while the job has not
+    /// reported a total.
+    pub fn ratio(&self) -> Option<f64> {
+        let total = self.progress.total.load(Ordering::Relaxed);
+        let done = self.progress.done.load(Ordering::Relaxed);
+        (total > 0).then(|| (done as f64 / total as f64).clamp(0.0, 1.0))
+    }
+
+    /// Asks the job to stop. Jobs that cannot stop part-way still finish,
+    /// but callers should drop the handle and ignore the result.
+    pub fn cancel(&self) {
+        self.progress.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.progress.is_cancelled()
+    }
+}
+
+/// Queues `job` on the database worker thread and returns a handle to poll.
+pub fn spawn<T, F>(job: F) -> Task<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    spawn_with_progress(move |_| job())
+}
+
+/// Like [`spawn`], for jobs that report progress or honour cancellation.
+pub fn spawn_with_progress<T, F>(job: F) -> Task<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&Progress) -> Result<T> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    let progress = Arc::new(Progress::default());
+    let shared = Arc::clone(&progress);
+    let job: Job = Box::new(move || {
+        let result = if shared.is_cancelled() {
+            Err(anyhow!("Cancelled"))
+        } else {
+            job(&shared)
+        };
+        let _ = sender.send(result);
+    });
+    let sent = worker().lock().unwrap_or_else(|e| e.into_inner()).send(job);
+    if let Err(mpsc::SendError(job)) = sent {
+        job();
+    }
+    Task {
+        receiver,
+        started: Instant::now(),
+        progress,
+    }
+}
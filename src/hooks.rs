@@ -0,0 +1,231 @@
+//! Site scripts run when something happens, written in Rhai and listed under
+//! `[hooks]` in the configuration file:
+//!
+//! ```toml
+//! [hooks]
+//! patient_created = "hooks/patient_created.rhai"
+//! ```
+//!
+//! A script sees the record as the `event` map and can `print` (shown as a
+//! toast), `append_file`/`write_file`, `webhook` a URL with a JSON body, or
+//! `set_field` to store a custom field on the record. Scripts run on their
+//! own thread, so a slow webhook never stalls the UI, and are capped in the
+//! number of operations they may perform.
+//!
+//! `postcode_lookup` is the exception: it runs while a form waits, to fill
+//! in the city and region for a postcode. The script gets the `postcode`
+//! and returns a map such as `#{ city: "Leeds", region: "West Yorkshire" }`,
+//! or `()` for a postcode it does not know; `read_file` lets it keep its
+//! table in a separate file.
+
+use crate::components::toast::{self, Toast};
+use crate::db::{self, Db};
+use crate::utils::{config, post_json};
+use anyhow::{anyhow, Context, Result};
+use rhai::{format_map_as_json, Dynamic, Engine, EvalAltResult, Map, Scope};
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::thread;
+
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// The `[hooks]` key of the postcode lookup script.
+const POSTCODE_LOOKUP: &str = "postcode_lookup";
+
+#[derive(Debug, Clone, Copy)]
+pub enum Event {
+    PatientCreated,
+    /// Payments settle a patient's invoices oldest first; this fires for each
+    /// invoice a payment settles in full.
+    InvoicePaid,
+    ShiftAssigned,
+}
+
+impl Event {
+    /// The key under `[hooks]` and the `event.name` a script sees.
+    fn name(self) -> &'static str {
+        match self {
+            Event::PatientCreated => "patient_created",
+            Event::InvoicePaid => "invoice_paid",
+            Event::ShiftAssigned => "shift_assigned",
+        }
+    }
+
+    /// What custom fields set by the script are attached to.
+    fn entity(self) -> &'static str {
+        match self {
+            Event::PatientCreated => "patient",
+            Event::InvoicePaid => "invoice",
+            Event::ShiftAssigned => "shift",
+        }
+    }
+}
+
+/// Runs the script configured for `event`, if any, in the background. `record`
+/// becomes the script's `event` map, with `id` set to `id` and `name` to the
+/// event name.
+pub fn fire(db: &Db, event: Event, id: i64, record: impl Serialize) {
+    let Some(script) = config::config().hooks.get(event.name()).cloned() else {
+        return;
+    };
+    // Rhai values are not `Send`, so the record crosses threads as JSON.
+    let record = match serde_json::to_value(record) {
+        Ok(record) => record,
+        Err(e) => {
+            report(event, e.into());
+            return;
+        }
+    };
+    let db = db.clone();
+    let spawned = thread::Builder::new()
+        .name("rustoria-hooks".to_string())
+        .spawn(move || {
+            if let Err(e) = run(&db, &script, event, id, record) {
+                report(event, e);
+            }
+        });
+    if let Err(e) = spawned {
+        report(event, e.into());
+    }
+}
+
+fn report(event: Event, error: anyhow::Error) {
+    toast::push(Toast::error(format!(
+        "{} hook failed: {:#}",
+        event.name(),
+        error
+    )));
+}
+
+fn run(db: &Db, script: &Path, event: Event, id: i64, record: serde_json::Value) -> Result<()> {
+    let source = fs::read_to_string(script)
+        .with_context(|| format!("Failed to read {}", script.display()))?;
+
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.on_print(|text| toast::push(Toast::success(text.to_string())));
+    engine.register_fn("append_file", |path: &str, text: &str| {
+        script_result(append_file(Path::new(path), text))
+    });
+    engine.register_fn("write_file", |path: &str, text: &str| {
+        script_result(fs::write(path, text).with_context(|| format!("Failed to write {}", path)))
+    });
+    engine.register_fn("webhook", |url: &str, body: &str| {
+        script_result(post_json(url, body, &[]))
+    });
+    engine.register_fn("webhook", |url: &str, body: Map| {
+        script_result(post_json(url, &format_map_as_json(&body), &[]))
+    });
+    let db = db.clone();
+    engine.register_fn("set_field", move |name: &str, value: Dynamic| {
+        script_result(db::set_custom_field(
+            &db,
+            event.entity(),
+            id,
+            name,
+            &value.to_string(),
+        ))
+    });
+
+    let mut record = rhai::serde::to_dynamic(record)
+        .map_err(|e| anyhow!("{}", e))?
+        .try_cast::<Map>()
+        .unwrap_or_default();
+    record.insert("id".into(), id.into());
+    record.insert("name".into(), event.name().into());
+    let mut scope = Scope::new();
+    scope.push("event", record);
+    engine
+        .run_with_scope(&mut scope, &source)
+        .map_err(|e| anyhow!("{}: {}", script.display(), e))
+}
+
+/// The city and region the `postcode_lookup` script gives for `postcode`,
+/// if one is configured and knows it. Failures are shown as a toast.
+pub fn lookup_postcode(postcode: &str) -> Option<(String, String)> {
+    let postcode = postcode.trim();
+    if postcode.is_empty() {
+        return None;
+    }
+    let script = config::config().hooks.get(POSTCODE_LOOKUP).cloned()?;
+    match run_lookup(&script, postcode) {
+        Ok(found) => found,
+        Err(e) => {
+            toast::push(Toast::error(format!(
+                "{} hook failed: {:#}",
+                POSTCODE_LOOKUP, e
+            )));
+            None
+        }
+    }
+}
+
+fn run_lookup(script: &Path, postcode: &str) -> Result<Option<(String, String)>> {
+    let source = fs::read_to_string(script)
+        .with_context(|| format!("Failed to read {}", script.display()))?;
+
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.register_fn("read_file", |path: &str| {
+        fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path))
+            .map_err(|e| -> Box<EvalAltResult> { format!("{:#}", e).into() })
+    });
+
+    let mut scope = Scope::new();
+    scope.push("postcode", postcode.to_string());
+    let result = engine
+        .eval_with_scope::<Dynamic>(&mut scope, &source)
+        .map_err(|e| anyhow!("{}: {}", script.display(), e))?;
+    if result.is_unit() {
+        return Ok(None);
+    }
+    let found = result
+        .try_cast::<Map>()
+        .ok_or_else(|| anyhow!("{} must return a map or ()", script.display()))?;
+    let part = |key: &str| {
+        found
+            .get(key)
+            .map(|value| value.to_string())
+            .unwrap_or_default()
+    };
+    Ok(Some((part("city"), part("region"))))
+}
+
+fn script_result(result: Result<()>) -> Result<(), Box<EvalAltResult>> {
+    result.map_err(|e| format!("{:#}", e).into())
+}
+
+fn append_file(path: &Path, text: &str) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", text)?;
+    Ok(())
+}
+
+/// Warns about `[hooks]` entries naming events this version does not fire,
+/// so a typo in the configuration file does not go unnoticed.
+pub fn check_config() {
+    let known = [
+        Event::PatientCreated,
+        Event::InvoicePaid,
+        Event::ShiftAssigned,
+    ];
+    for name in config::config().hooks.keys() {
+        if name != POSTCODE_LOOKUP && !known.iter().any(|event| event.name() == name) {
+            toast::push(Toast::error(format!(
+                "Unknown hook \"{}\" in {}",
+                name,
+                config::path().display()
+            )));
+        }
+    }
+}
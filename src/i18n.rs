@@ -0,0 +1,137 @@
+use crate::db::Db;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::{OnceLock, RwLock};
+
+pub const DEFAULT_LOCALE: &str = "en";
+const LOCALE_DIR: &str = "locales";
+
+const EMBEDDED: [(&str, &str); 2] = [
+    ("en", include_str!("locales/en.txt")),
+    ("es", include_str!("locales/es.txt")),
+];
+
+type Catalog = HashMap<&'static str, &'static str>;
+
+static CATALOGS: OnceLock<BTreeMap<&'static str, Catalog>> = OnceLock::new();
+static CURRENT: RwLock<&'static str> = RwLock::new(DEFAULT_LOCALE);
+
+/// Parses `key = value` lines; `#` starts a comment and `\n` in a value is a
+/// line break.
+fn parse(source: &'static str, catalog: &mut Catalog) {
+    for line in source.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim();
+            let value = if value.contains("\\n") {
+                Box::leak(value.replace("\\n", "\n").into_boxed_str())
+            } else {
+                value
+            };
+            catalog.insert(key.trim(), value);
+        }
+    }
+}
+
+/// Built-in catalogs, overlaid with any `locales/<code>.txt` found in the
+/// working directory so deployments can ship or patch translations without
+/// a rebuild.
+fn catalogs() -> &'static BTreeMap<&'static str, Catalog> {
+    CATALOGS.get_or_init(|| {
+        let mut catalogs: BTreeMap<&'static str, Catalog> = BTreeMap::new();
+        for (code, source) in EMBEDDED {
+            parse(source, catalogs.entry(code).or_default());
+        }
+        let entries = fs::read_dir(LOCALE_DIR).into_iter().flatten().flatten();
+        for entry in entries {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("txt") {
+                continue;
+            }
+            let (Some(code), Ok(source)) = (locale_code(&path), fs::read_to_string(&path)) else {
+                continue;
+            };
+            let code: &'static str = Box::leak(code.into_boxed_str());
+            let source: &'static str = Box::leak(source.into_boxed_str());
+            parse(source, catalogs.entry(code).or_default());
+        }
+        catalogs
+    })
+}
+
+fn locale_code(path: &Path) -> Option<String> {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(str::to_string)
+}
+
+/// Looks `key` up in the active locale, falling back to English and finally
+/// to the key itself so a missing translation is visible rather than blank.
+pub fn t(key: &'static str) -> &'static str {
+    let catalogs = catalogs();
+    [locale(), DEFAULT_LOCALE]
+        .iter()
+        .find_map(|code| catalogs.get(code).and_then(|c| c.get(key)))
+        .copied()
+        .unwrap_or(key)
+}
+
+/// [`t`] with `{name}` placeholders replaced from `args`.
+pub fn tf(key: &'static str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    args.iter().fold(t(key).to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+/// The English text of `key`, for matching values saved before they were
+/// looked up by key.
+pub fn english(key: &'static str) -> &'static str {
+    catalogs()
+        .get(DEFAULT_LOCALE)
+        .and_then(|c| c.get(key))
+        .copied()
+        .unwrap_or(key)
+}
+
+pub fn locale() -> &'static str {
+    *CURRENT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn locales() -> Vec<&'static str> {
+    catalogs().keys().copied().collect()
+}
+
+/// The language's own name for itself, e.g. "Español".
+pub fn locale_name(code: &str) -> &'static str {
+    catalogs()
+        .get(code)
+        .and_then(|c| c.get("language.name"))
+        .copied()
+        .unwrap_or_else(|| {
+            catalogs()
+                .get_key_value(code)
+                .map_or(DEFAULT_LOCALE, |(code, _)| code)
+        })
+}
+
+pub fn set_locale(code: &str) {
+    if let Some((code, _)) = catalogs().get_key_value(code) {
+        *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = code;
+    }
+}
+
+/// Applies `RUSTORIA_LOCALE` if set, otherwise the locale saved in settings,
+/// otherwise the configured default.
+pub fn load(db: &Db) {
+    let saved = std::env::var("RUSTORIA_LOCALE")
+        .ok()
+        .or_else(|| crate::db::get_setting(db, "locale").ok().flatten())
+        .or_else(|| crate::utils::config::config().locale.clone());
+    if let Some(code) = saved {
+        set_locale(&code);
+    }
+}
@@ -0,0 +1,436 @@
+//! HL7 FHIR (R4) JSON for sharing a patient's chart with other systems.
+//!
+//! An export is a `collection` Bundle holding the Patient, a Condition per
+//! medical record diagnosis, a Procedure per procedure code on a record, a
+//! MedicationRequest per prescription and current medication, and an
+//! Encounter per teleconsultation and ward stay. Imports
+//! read the Patient resources of a Bundle (or a bare Patient) and skip anyone
+//! already registered under the same name and date of birth.
+
+use crate::db::{self, Db};
+use crate::models::{
+    Admission, ContactPreference, Gender, MedicalRecord, Patient, Procedure, TeleconsultStatus,
+    Teleconsultation,
+};
+use crate::utils::{datetime, procedures};
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+/// Identifier system for the local database IDs carried in exports.
+const ID_SYSTEM: &str = "urn:rustoria:patient";
+
+/// The chart of `patient_id` as a FHIR Bundle.
+pub fn patient_bundle(db: &Db, patient_id: i64) -> Result<Value> {
+    let patient = db::get_patient(db, patient_id)?;
+    let records: Vec<MedicalRecord> = db::get_all_medical_records(db)?
+        .into_iter()
+        .filter(|r| r.patient_id == patient_id)
+        .collect();
+    let consultations = db::get_patient_teleconsultations(db, patient_id)?;
+    let admissions = db::get_patient_admissions(db, patient_id)?;
+    let catalog = db::get_procedures(db)?;
+
+    let mut resources = vec![patient_resource(&patient)];
+    resources.extend(records.iter().map(|r| condition_resource(&patient, r)));
+    resources.extend(records.iter().flat_map(|r| {
+        procedures::codes(r.procedures.as_deref().unwrap_or_default())
+            .into_iter()
+            .enumerate()
+            .map(|(i, code)| {
+                let procedure = procedures::find(&catalog, &code);
+                procedure_resource(&patient, r, i + 1, &code, procedure)
+            })
+            .collect::<Vec<_>>()
+    }));
+    resources.extend(records.iter().filter_map(|r| {
+        let prescription = r.prescription.as_deref()?.trim();
+        (!prescription.is_empty()).then(|| {
+            medication_resource(
+                &patient,
+                &format!("record-{}", r.id),
+                prescription,
+                Some(r.id),
+            )
+        })
+    }));
+    resources.extend(
+        split_medications(patient.current_medications.as_deref())
+            .iter()
+            .enumerate()
+            .map(|(i, m)| medication_resource(&patient, &format!("current-{}", i + 1), m, None)),
+    );
+    resources.extend(
+        consultations
+            .iter()
+            .map(|c| teleconsult_encounter(&patient, c)),
+    );
+    resources.extend(admissions.iter().map(|a| admission_encounter(&patient, a)));
+
+    Ok(json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "timestamp": datetime::format_iso8601(datetime::now()),
+        "entry": resources
+            .into_iter()
+            .map(|resource| json!({ "resource": resource }))
+            .collect::<Vec<_>>(),
+    }))
+}
+
+fn patient_reference(patient: &Patient) -> Value {
+    json!({
+        "reference": format!("Patient/{}", patient.id),
+        "display": format!("{} {}", patient.first_name, patient.last_name),
+    })
+}
+
+fn patient_resource(patient: &Patient) -> Value {
+    let mut telecom = vec![json!({
+        "system": "phone",
+        "value": patient.phone_number,
+    })];
+    if let Some(email) = patient.email.as_deref().filter(|e| !e.trim().is_empty()) {
+        telecom.push(json!({ "system": "email", "value": email }));
+    }
+    json!({
+        "resourceType": "Patient",
+        "id": patient.id.to_string(),
+        "identifier": [{ "system": ID_SYSTEM, "value": patient.id.to_string() }],
+        "name": [{
+            "use": "official",
+            "family": patient.last_name,
+            "given": patient.first_name.split_whitespace().collect::<Vec<_>>(),
+        }],
+        "gender": match patient.gender {
+            Gender::Male => "male",
+            Gender::Female => "female",
+            Gender::PreferNotToSay => "unknown",
+            Gender::Other | Gender::Custom(_) => "other",
+        },
+        "birthDate": patient.date_of_birth,
+        "telecom": telecom,
+        "address": [address(patient)],
+    })
+}
+
+/// The address as text plus whichever structured parts are filled in.
+fn address(patient: &Patient) -> Value {
+    let mut address = json!({ "text": patient.full_address() });
+    if !patient.address.trim().is_empty() {
+        address["line"] = json!([patient.address]);
+    }
+    for (key, value) in [
+        ("city", &patient.city),
+        ("state", &patient.region),
+        ("postalCode", &patient.postcode),
+    ] {
+        if !value.trim().is_empty() {
+            address[key] = json!(value);
+        }
+    }
+    address
+}
+
+fn condition_resource(patient: &Patient, record: &MedicalRecord) -> Value {
+    let mut notes = vec![json!({ "text": record.doctor_notes })];
+    if let Some(nurse_notes) = record
+        .nurse_notes
+        .as_deref()
+        .filter(|n| !n.trim().is_empty())
+    {
+        notes.push(json!({ "text": nurse_notes }));
+    }
+    json!({
+        "resourceType": "Condition",
+        "id": record.id.to_string(),
+        "category": [{
+            "coding": [{
+                "system": "http://terminology.hl7.org/CodeSystem/condition-category",
+                "code": "encounter-diagnosis",
+            }],
+        }],
+        "code": { "text": record.diagnosis },
+        "subject": patient_reference(patient),
+        "note": notes,
+    })
+}
+
+/// The `index`th procedure code on `record`, done for its diagnosis.
+fn procedure_resource(
+    patient: &Patient,
+    record: &MedicalRecord,
+    index: usize,
+    code: &str,
+    procedure: Option<&Procedure>,
+) -> Value {
+    let mut coding = json!({ "code": code });
+    if let Some(procedure) = procedure {
+        coding["display"] = json!(procedure.name);
+    }
+    json!({
+        "resourceType": "Procedure",
+        "id": format!("record-{}-{}", record.id, index),
+        "status": "completed",
+        "code": {
+            "coding": [coding],
+            "text": procedure.map_or(code, |p| p.name.as_str()),
+        },
+        "subject": patient_reference(patient),
+        "reasonReference": [{ "reference": format!("Condition/{}", record.id) }],
+    })
+}
+
+fn medication_resource(
+    patient: &Patient,
+    id: &str,
+    medication: &str,
+    record_id: Option<i64>,
+) -> Value {
+    let mut resource = json!({
+        "resourceType": "MedicationRequest",
+        "id": id,
+        "status": "active",
+        "intent": "order",
+        "medicationCodeableConcept": { "text": medication },
+        "subject": patient_reference(patient),
+    });
+    if let Some(record_id) = record_id {
+        resource["reasonReference"] = json!([{ "reference": format!("Condition/{}", record_id) }]);
+    }
+    resource
+}
+
+fn teleconsult_encounter(patient: &Patient, consultation: &Teleconsultation) -> Value {
+    let status = match consultation.status {
+        TeleconsultStatus::Scheduled if consultation.checked_in_at.is_some() => "arrived",
+        TeleconsultStatus::Scheduled => "planned",
+        TeleconsultStatus::Completed => "finished",
+        TeleconsultStatus::NoShow | TeleconsultStatus::Cancelled => "cancelled",
+    };
+    let mut resource = json!({
+        "resourceType": "Encounter",
+        "id": format!("teleconsult-{}", consultation.id),
+        "status": status,
+        "class": {
+            "system": "http://terminology.hl7.org/CodeSystem/v3-ActCode",
+            "code": "VR",
+            "display": "virtual",
+        },
+        "subject": patient_reference(patient),
+        "participant": [{
+            "individual": { "reference": format!("Practitioner/{}", consultation.doctor_id) },
+        }],
+        "period": { "start": date_time(&consultation.scheduled_at) },
+    });
+    if let Some(minutes) = consultation.duration_minutes {
+        resource["length"] = json!({
+            "value": minutes,
+            "unit": "min",
+            "system": "http://unitsofmeasure.org",
+            "code": "min",
+        });
+    }
+    if let Some(record_id) = consultation.medical_record_id {
+        resource["diagnosis"] =
+            json!([{ "condition": { "reference": format!("Condition/{}", record_id) } }]);
+    }
+    resource
+}
+
+fn admission_encounter(patient: &Patient, admission: &Admission) -> Value {
+    let mut period = json!({ "start": date_time(&admission.admitted_at) });
+    let location = match &admission.bed {
+        Some(bed) => format!("{} bed {}", admission.ward, bed),
+        None => admission.ward.clone(),
+    };
+    if let Some(discharged_at) = &admission.discharged_at {
+        period["end"] = json!(date_time(discharged_at));
+    }
+    json!({
+        "resourceType": "Encounter",
+        "id": format!("admission-{}", admission.id),
+        "status": if admission.discharged_at.is_some() { "finished" } else { "in-progress" },
+        "class": {
+            "system": "http://terminology.hl7.org/CodeSystem/v3-ActCode",
+            "code": "IMP",
+            "display": "inpatient encounter",
+        },
+        "subject": patient_reference(patient),
+        "period": period,
+        "location": [{
+            "location": { "display": location },
+        }],
+    })
+}
+
+/// The free-text medication list, one entry per comma, semicolon or line.
+fn split_medications(medications: Option<&str>) -> Vec<String> {
+    medications
+        .unwrap_or_default()
+        .split([',', ';', '\n'])
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// A stored `YYYY-MM-DD HH:MM[:SS]` UTC timestamp as a FHIR dateTime, which
+/// needs seconds and an offset once a time is given.
+fn date_time(stored: &str) -> String {
+    let stored = stored.trim();
+    match stored.split_once([' ', 'T']) {
+        Some((date, time)) if time.len() == 5 => format!("{}T{}:00Z", date, time),
+        Some((date, time)) if time.len() == 8 => format!("{}T{}Z", date, time),
+        _ => stored.to_string(),
+    }
+}
+
+/// What [`import_patients`] did with the file.
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+impl std::fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Imported {} patient(s), skipped {} already registered",
+            self.imported, self.skipped
+        )
+    }
+}
+
+/// Registers every Patient resource in the FHIR JSON at `path`.
+pub fn import_patients(db: &Db, path: &Path) -> Result<ImportSummary> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let document: Value = serde_json::from_str(&text)
+        .with_context(|| format!("{} is not valid JSON", path.display()))?;
+    let resources: Vec<&Value> = match document["resourceType"].as_str() {
+        Some("Bundle") => document["entry"]
+            .as_array()
+            .map(|entries| entries.iter().map(|e| &e["resource"]).collect())
+            .unwrap_or_default(),
+        Some("Patient") => vec![&document],
+        Some(other) => return Err(anyhow!("Expected a Bundle or Patient, found {}", other)),
+        None => return Err(anyhow!("{} is not a FHIR resource", path.display())),
+    };
+
+    let mut patients = Vec::new();
+    for (i, resource) in resources
+        .into_iter()
+        .filter(|r| r["resourceType"] == "Patient")
+        .enumerate()
+    {
+        patients
+            .push(patient_from_resource(resource).with_context(|| format!("Patient {}", i + 1))?);
+    }
+    if patients.is_empty() {
+        return Err(anyhow!("{} holds no Patient resources", path.display()));
+    }
+
+    let existing = db::get_all_patients(db)?;
+    let mut summary = ImportSummary::default();
+    for patient in patients {
+        let registered = existing.iter().any(|p| {
+            p.first_name.eq_ignore_ascii_case(&patient.first_name)
+                && p.last_name.eq_ignore_ascii_case(&patient.last_name)
+                && p.date_of_birth == patient.date_of_birth
+        });
+        if registered {
+            summary.skipped += 1;
+        } else {
+            db::create_patient(db, &patient)?;
+            summary.imported += 1;
+        }
+    }
+    Ok(summary)
+}
+
+fn patient_from_resource(resource: &Value) -> Result<Patient> {
+    let names = resource["name"].as_array().cloned().unwrap_or_default();
+    let name = names
+        .iter()
+        .find(|n| n["use"] == "official")
+        .or_else(|| names.first())
+        .ok_or_else(|| anyhow!("has no name"))?;
+    let first_name = name["given"]
+        .as_array()
+        .map(|given| {
+            given
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_default();
+    let last_name = name["family"].as_str().unwrap_or_default().to_string();
+    if first_name.is_empty() || last_name.is_empty() {
+        return Err(anyhow!("needs both a given and a family name"));
+    }
+    let date_of_birth = resource["birthDate"]
+        .as_str()
+        .ok_or_else(|| anyhow!("has no birthDate"))?
+        .to_string();
+
+    let telecom = resource["telecom"].as_array().cloned().unwrap_or_default();
+    let contact = |system: &str| {
+        telecom
+            .iter()
+            .find(|t| t["system"] == system)
+            .and_then(|t| t["value"].as_str())
+            .map(str::to_string)
+    };
+    // Structured parts fill the matching fields; an address given only as
+    // text goes in the street line whole.
+    let address = resource["address"]
+        .as_array()
+        .and_then(|a| a.first())
+        .cloned()
+        .unwrap_or_default();
+    let part = |key: &str| address[key].as_str().unwrap_or_default().to_string();
+    let lines = address["line"]
+        .as_array()
+        .map(|lines| {
+            lines
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .unwrap_or_default();
+    let structured = !lines.is_empty()
+        || ["city", "state", "postalCode"]
+            .iter()
+            .any(|key| address[*key].is_string());
+    let street = if structured { lines } else { part("text") };
+
+    Ok(Patient {
+        id: 0,
+        first_name,
+        last_name,
+        date_of_birth,
+        gender: match resource["gender"].as_str() {
+            Some("male") => Gender::Male,
+            Some("female") => Gender::Female,
+            Some("unknown") => Gender::PreferNotToSay,
+            _ => Gender::Other,
+        },
+        address: street,
+        city: part("city"),
+        region: part("state"),
+        postcode: part("postalCode"),
+        phone_number: contact("phone").unwrap_or_default(),
+        email: contact("email"),
+        medical_history: None,
+        allergies: None,
+        current_medications: None,
+        contact_preference: ContactPreference::default(),
+        photo_path: None,
+        mrn: String::new(),
+    })
+}
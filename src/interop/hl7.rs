@@ -0,0 +1,280 @@
+//! HL7 v2.5 ADT messages for systems that still expect them, such as older
+//! lab and radiology systems. An admission sends ADT^A01 and a discharge
+//! ADT^A03, appended to a file and/or sent to an MLLP listener as set under
+//! `[hl7]` in the configuration file:
+//!
+//! ```toml
+//! [hl7]
+//! file = "hl7/adt.hl7"
+//! mllp = "lab.example.org:2575"
+//! ```
+//!
+//! Messages are delivered on their own thread, so an unreachable listener
+//! never stalls the UI; failures are reported as toasts.
+
+use crate::components::toast::{self, Toast};
+use crate::db::{self, Db};
+use crate::models::{Admission, Gender, Patient};
+use crate::utils::config::{self, Hl7Config};
+use crate::utils::datetime;
+use anyhow::{anyhow, Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+const SENDING_APPLICATION: &str = "RUSTORIA";
+const VERSION: &str = "2.5";
+/// MLLP framing: start block, end block and the carriage return after it.
+const START_BLOCK: u8 = 0x0b;
+const END_BLOCK: u8 = 0x1c;
+const CARRIAGE_RETURN: u8 = 0x0d;
+const MLLP_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy)]
+pub enum AdtEvent {
+    /// A01: admit/visit notification.
+    Admit,
+    /// A03: discharge/end visit.
+    Discharge,
+}
+
+impl AdtEvent {
+    fn code(self) -> &'static str {
+        match self {
+            AdtEvent::Admit => "A01",
+            AdtEvent::Discharge => "A03",
+        }
+    }
+}
+
+/// Builds the ADT message for `event` on `admission` and delivers it in the
+/// background, unless no destination is configured.
+pub fn send_adt(db: &Db, event: AdtEvent, admission: &Admission) {
+    let settings = config::config().hl7.clone();
+    if settings.file.is_none() && settings.mllp.is_none() {
+        return;
+    }
+    let message = match db::get_patient(db, admission.patient_id) {
+        Ok(patient) => adt_message(&settings, event, &patient, admission),
+        Err(e) => {
+            report(event, e);
+            return;
+        }
+    };
+    let spawned = thread::Builder::new()
+        .name("rustoria-hl7".to_string())
+        .spawn(move || {
+            if let Some(path) = &settings.file {
+                if let Err(e) = append_message(path, &message) {
+                    report(event, e);
+                }
+            }
+            if let Some(address) = &settings.mllp {
+                if let Err(e) = send_mllp(address, &message) {
+                    report(event, e);
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        report(event, e.into());
+    }
+}
+
+fn report(event: AdtEvent, error: anyhow::Error) {
+    toast::push(Toast::error(format!(
+        "HL7 ADT^{} not sent: {:#}",
+        event.code(),
+        error
+    )));
+}
+
+/// The message as `\r`-separated segments: MSH, EVN, PID and PV1.
+fn adt_message(
+    settings: &Hl7Config,
+    event: AdtEvent,
+    patient: &Patient,
+    admission: &Admission,
+) -> String {
+    let timestamp = datetime::format_compact(datetime::now());
+    // At most 20 characters, unique per admission and second.
+    let control_id = format!("{}{:06}", timestamp, admission.id % 1_000_000);
+
+    let msh = segment(
+        "MSH",
+        &[
+            (2, "^~\\&".to_string()),
+            (3, escape(SENDING_APPLICATION)),
+            (4, escape(&settings.sending_facility)),
+            (5, escape(&settings.receiving_application)),
+            (6, escape(&settings.receiving_facility)),
+            (7, timestamp.clone()),
+            (9, format!("ADT^{}^ADT_{}", event.code(), event.code())),
+            (10, control_id),
+            (11, "P".to_string()),
+            (12, VERSION.to_string()),
+        ],
+    );
+    let evn = segment(
+        "EVN",
+        &[
+            (1, event.code().to_string()),
+            (2, timestamp),
+            (
+                6,
+                hl7_time(match event {
+                    AdtEvent::Admit => &admission.admitted_at,
+                    AdtEvent::Discharge => admission.discharged_at.as_deref().unwrap_or_default(),
+                }),
+            ),
+        ],
+    );
+    let pid = segment(
+        "PID",
+        &[
+            (1, "1".to_string()),
+            (3, format!("{}^^^{}^MR", patient.mrn, SENDING_APPLICATION)),
+            (
+                5,
+                format!(
+                    "{}^{}",
+                    escape(&patient.last_name),
+                    escape(&patient.first_name)
+                ),
+            ),
+            (7, hl7_time(&patient.date_of_birth)),
+            (
+                8,
+                match patient.gender {
+                    Gender::Male => "M",
+                    Gender::Female => "F",
+                    Gender::PreferNotToSay => "U",
+                    Gender::Other | Gender::Custom(_) => "O",
+                }
+                .to_string(),
+            ),
+            (
+                11,
+                format!(
+                    "{}^^{}^{}^{}",
+                    escape(&patient.address),
+                    escape(&patient.city),
+                    escape(&patient.region),
+                    escape(&patient.postcode)
+                ),
+            ),
+            (13, escape(&patient.phone_number)),
+        ],
+    );
+    let mut visit = vec![
+        (1, "1".to_string()),
+        (2, "I".to_string()),
+        (
+            3,
+            format!(
+                "{}^^{}",
+                escape(&admission.ward),
+                escape(admission.bed.as_deref().unwrap_or_default())
+            ),
+        ),
+        (19, admission.id.to_string()),
+        (44, hl7_time(&admission.admitted_at)),
+    ];
+    if let Some(discharged_at) = &admission.discharged_at {
+        visit.push((45, hl7_time(discharged_at)));
+    }
+    let pv1 = segment("PV1", &visit);
+
+    [msh, evn, pid, pv1].join("\r")
+}
+
+/// A segment with `fields` at their HL7 positions and empty fields between.
+/// MSH counts the field separator itself as field 1.
+fn segment(name: &str, fields: &[(usize, String)]) -> String {
+    let first = if name == "MSH" { 2 } else { 1 };
+    let last = fields.iter().map(|(i, _)| *i).max().unwrap_or(first);
+    let mut values = vec![String::new(); last + 1 - first];
+    for (i, value) in fields {
+        values[i - first] = value.clone();
+    }
+    format!("{}|{}", name, values.join("|"))
+}
+
+/// Escapes the HL7 delimiters in free text and drops line breaks, which
+/// would otherwise end the segment.
+fn escape(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\E\\"),
+            '|' => out.push_str("\\F\\"),
+            '^' => out.push_str("\\S\\"),
+            '&' => out.push_str("\\T\\"),
+            '~' => out.push_str("\\R\\"),
+            '\r' | '\n' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// A stored `YYYY-MM-DD[ HH:MM[:SS]]` value in HL7's `YYYYMMDD[HHMM[SS]]` form.
+fn hl7_time(stored: &str) -> String {
+    stored.chars().filter(char::is_ascii_digit).collect()
+}
+
+fn append_message(path: &Path, message: &str) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    write!(file, "{}\r\n", message)?;
+    Ok(())
+}
+
+/// Sends `message` framed for MLLP and waits for the listener's ACK, which
+/// must accept it (`AA` or `CA`).
+fn send_mllp(address: &str, message: &str) -> Result<()> {
+    let target = address
+        .to_socket_addrs()
+        .with_context(|| format!("Invalid MLLP address {}", address))?
+        .next()
+        .ok_or_else(|| anyhow!("{} did not resolve", address))?;
+    let mut stream = TcpStream::connect_timeout(&target, MLLP_TIMEOUT)
+        .with_context(|| format!("Failed to connect to {}", address))?;
+    stream.set_read_timeout(Some(MLLP_TIMEOUT))?;
+    stream.set_write_timeout(Some(MLLP_TIMEOUT))?;
+
+    let mut frame = vec![START_BLOCK];
+    frame.extend_from_slice(message.as_bytes());
+    frame.extend_from_slice(&[END_BLOCK, CARRIAGE_RETURN]);
+    stream.write_all(&frame)?;
+
+    let mut reply = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !reply.ends_with(&[END_BLOCK, CARRIAGE_RETURN]) {
+        let read = stream
+            .read(&mut buffer)
+            .with_context(|| format!("No acknowledgement from {}", address))?;
+        if read == 0 {
+            break;
+        }
+        reply.extend_from_slice(&buffer[..read]);
+    }
+    let reply = String::from_utf8_lossy(&reply);
+    let code = reply
+        .split(['\r', '\n'])
+        .find_map(|segment| segment.strip_prefix("MSA|"))
+        .and_then(|msa| msa.split('|').next())
+        .ok_or_else(|| anyhow!("{} sent no acknowledgement", address))?;
+    match code {
+        "AA" | "CA" => Ok(()),
+        code => Err(anyhow!("{} rejected the message ({})", address, code)),
+    }
+}
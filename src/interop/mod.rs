@@ -0,0 +1,4 @@
+//! Exchange formats for talking to other clinical systems.
+
+pub mod fhir;
+pub mod hl7;
@@ -0,0 +1,805 @@
+use crate::app::{AppState, SelectedApp};
+use crate::plugins;
+use crate::utils::config;
+use std::borrow::Cow;
+
+#[derive(Clone)]
+pub struct KeyBinding {
+    pub keys: Cow<'static, str>,
+    /// Catalog key of what the keys do.
+    pub action: &'static str,
+}
+
+pub struct Keymap {
+    /// Catalog key of the screen's name.
+    pub title: &'static str,
+    pub text_entry: bool,
+    pub bindings: &'static [KeyBinding],
+}
+
+pub const fn bind(keys: &'static str, action: &'static str) -> KeyBinding {
+    KeyBinding {
+        keys: Cow::Borrowed(keys),
+        action,
+    }
+}
+
+const GLOBAL: &[KeyBinding] = &[
+    bind("F1", "help.key.show_this_help"),
+    bind("?", "help.key.show_this_help_screens"),
+    bind("Ctrl+P", "help.key.command_palette_after_login"),
+    bind("Alt+1..5", "help.key.switch_module_tab_inside"),
+    bind("Ctrl+Tab", "help.key.next_module_tab_inside"),
+    bind("Alt+Q", "help.key.start_stop_recording_key"),
+    bind("q", "help.key.start_stop_recording_plain"),
+    bind("Alt+@", "help.key.replay_recorded_key_macro"),
+    bind("@", "help.key.replay_recorded_plain"),
+    bind("F2", "help.key.scan_wristband_sample_label"),
+    bind("F3", "help.key.record_patient_vital_signs"),
+    bind("Ctrl+Q", "help.key.quit_rustoria"),
+    bind("←→ / Home / End", "help.key.move_caret_text_field"),
+    bind("Ctrl+←→", "help.key.jump_word_text_field"),
+    bind("Ctrl+W / Ctrl+Backspace", "help.key.delete_previous_word"),
+];
+
+const LOGIN: &[KeyBinding] = &[
+    bind("Tab / ↑↓", "help.key.switch_between_fields_buttons"),
+    bind("Enter", "help.key.log_activate_button"),
+    bind("Esc", "help.key.quit"),
+];
+
+const REGISTER: &[KeyBinding] = &[
+    bind("Tab / ↑↓", "help.key.switch_between_fields_buttons"),
+    bind("Enter", "help.key.create_account_activate_button"),
+    bind("Esc", "help.key.back_login"),
+];
+
+const HOME: &[KeyBinding] = &[
+    bind("←→", "help.key.switch_between_features_submenu"),
+    bind("↑↓", "help.key.navigate"),
+    bind("Enter", "help.key.open_selected_screen_tile"),
+    bind("Tab", "help.key.cycle_menu_dashboard_logout"),
+    bind("Esc", "help.key.back_feature_list"),
+    bind("n / i / b", "help.key.quick_actions"),
+    bind("1-9", "help.key.reopen_recently_viewed_patient"),
+];
+
+const PATIENT_ADD: &[KeyBinding] = &[
+    bind("Tab", "help.key.switch_focus_between_form"),
+    bind("↑↓", "help.key.switch_fields"),
+    bind("M / F / O / P", "help.key.set_gender_gender_field"),
+    bind("Space", "help.key.next_gender_option_gender"),
+    bind("Ctrl+D", "help.key.pick_date_birth_from"),
+    bind("Enter", "help.key.submit"),
+    bind("Esc", "help.key.back"),
+];
+
+const STAFF_ADD: &[KeyBinding] = &[
+    bind("Tab", "help.key.switch_focus_between_form"),
+    bind("↑↓", "help.key.switch_fields"),
+    bind("D / N / A / T", "help.key.set_role_role_field"),
+    bind("Enter", "help.key.submit"),
+    bind("Esc", "help.key.back"),
+];
+
+const SEARCHABLE_LIST: &[KeyBinding] = &[
+    bind("/ or s", "help.key.search"),
+    bind("↑↓", "help.key.navigate"),
+    bind("Enter", "help.key.view_details"),
+    bind("b", "help.key.back_home"),
+    bind("r", "help.key.refresh"),
+    bind("Tab", "help.key.switch_focus"),
+    bind("Esc", "help.key.cancel_search_back"),
+];
+
+const RECORD_LIST: &[KeyBinding] = &[
+    bind("/ or s", "help.key.search"),
+    bind("↑↓", "help.key.navigate"),
+    bind("Enter", "help.key.view_details"),
+    bind("y", "help.key.copy_notes_clipboard"),
+    bind("Y", "help.key.copy_record_row_tab"),
+    bind("p", "help.key.print_record"),
+    bind("l", "help.key.pharmacy_label_prescription"),
+    bind("h", "help.key.version_history"),
+    bind("a", "help.key.aftercare_instructions_handout"),
+    bind("Enter (history)", "help.key.compare_version_current_record"),
+    bind("Tab (details)", "help.key.focus_next_section"),
+    bind(
+        "↑↓ / PgUp PgDn (details)",
+        "help.key.scroll_focused_section",
+    ),
+    bind("Ctrl+F", "help.key.saved_searches_apply_save"),
+    bind("1-9", "help.key.apply_saved_search"),
+    bind("Ctrl+E", "help.key.export_rows_shown_csv"),
+    bind("Ctrl+O", "help.key.choose_columns_shown"),
+    bind("b", "help.key.back_home"),
+    bind("r", "help.key.refresh"),
+    bind("Tab", "help.key.switch_focus"),
+    bind("Esc", "help.key.cancel_search_back"),
+];
+
+const INVOICE_LIST: &[KeyBinding] = &[
+    bind("/ or s", "help.key.search"),
+    bind("↑↓", "help.key.navigate"),
+    bind("Enter", "help.key.view_details"),
+    bind("p", "help.key.print_invoice"),
+    bind("P (details)", "help.key.print_receipt"),
+    bind("Ctrl+F", "help.key.saved_searches_apply_save"),
+    bind("1-9", "help.key.apply_saved_search"),
+    bind("Ctrl+E", "help.key.export_rows_shown_csv"),
+    bind("Ctrl+O", "help.key.choose_columns_shown"),
+    bind("b", "help.key.back_home"),
+    bind("r", "help.key.refresh"),
+    bind("Tab", "help.key.switch_focus"),
+    bind("Esc", "help.key.cancel_search_back"),
+];
+
+const PATIENT_LIST: &[KeyBinding] = &[
+    bind("/ or s", "help.key.search"),
+    bind("↑↓", "help.key.navigate"),
+    bind("Enter", "help.key.view_details"),
+    bind("p", "help.key.pin_unpin_patient_pinned"),
+    bind("d", "help.key.patient_documents"),
+    bind("a", "help.key.add_document_documents_view"),
+    bind("o", "help.key.open_document_documents_view"),
+    bind("x", "help.key.remove_document_documents_view"),
+    bind("i", "help.key.imaging_studies"),
+    bind("/ (imaging)", "help.key.search_studies"),
+    bind("a (imaging)", "help.key.register_imaging_study"),
+    bind("s (imaging)", "help.key.advance_report_status"),
+    bind("y (imaging)", "help.key.copy_file_pacs_reference"),
+    bind("l (imaging)", "help.key.sample_label"),
+    bind("l", "help.key.wristband_label"),
+    bind("p (label)", "help.key.print_label"),
+    bind("m", "help.key.mark_merging_merge_into"),
+    bind("←→ (compare)", "help.key.pick_surviving_value"),
+    bind("[ ] (compare)", "help.key.pick_every_left_right"),
+    bind("d (compare)", "help.key.only_show_differences"),
+    bind("y", "help.key.copy_phone_number_clipboard"),
+    bind("Y", "help.key.copy_patient_row_tab"),
+    bind("f", "help.key.export_chart_fhir_json"),
+    bind("c", "help.key.take_photo_configured_camera"),
+    bind("Ctrl+F", "help.key.saved_searches_apply_save"),
+    bind("1-9", "help.key.apply_saved_search"),
+    bind("Ctrl+E", "help.key.export_rows_shown_csv"),
+    bind("Ctrl+O", "help.key.choose_columns_shown"),
+    bind("b", "help.key.back_home"),
+    bind("r", "help.key.refresh"),
+    bind("Tab", "help.key.switch_focus"),
+    bind("Esc", "help.key.cancel_search_back"),
+];
+
+const BULK_DELETE: &[KeyBinding] = &[
+    bind("Type", "help.key.search"),
+    bind("↓ / Enter", "help.key.move_results"),
+    bind("Space", "help.key.toggle_selection"),
+    bind("Enter", "help.key.delete_selected"),
+    bind("B", "help.key.bulk_delete"),
+    bind("Esc", "help.key.cancel_search_back"),
+];
+
+const UPDATE: &[KeyBinding] = &[
+    bind("/ or s", "help.key.search"),
+    bind("↑↓", "help.key.navigate"),
+    bind("Enter", "help.key.select_entry_save_field"),
+    bind("E", "help.key.edit_field"),
+    bind("Ctrl+S", "help.key.save_changes"),
+    bind("Esc", "help.key.cancel_editing_back"),
+];
+
+const UNDOABLE_UPDATE: &[KeyBinding] = &[
+    bind("/ or s", "help.key.search"),
+    bind("↑↓", "help.key.navigate"),
+    bind("Enter", "help.key.select_entry_save_field"),
+    bind("E", "help.key.edit_field"),
+    bind("Ctrl+Z", "help.key.undo_last_field_change"),
+    bind("Ctrl+Y", "help.key.redo_field_change"),
+    bind("D", "help.key.discard_all_unsaved_changes"),
+    bind("Ctrl+S", "help.key.save_changes"),
+    bind("Esc", "help.key.cancel_editing_back"),
+];
+
+const PICK_AND_FILL: &[KeyBinding] = &[
+    bind("/ or s", "help.key.search"),
+    bind("↑↓", "help.key.navigate"),
+    bind("Space", "help.key.select"),
+    bind("1-9", "help.key.select_recently_viewed_patient"),
+    bind("Ctrl+N", "help.key.add_new_patient_select"),
+    bind("Enter", "help.key.confirm_submit"),
+    bind("Tab", "help.key.back_selection_switch_focus"),
+    bind("Esc", "help.key.exit"),
+];
+
+const TELECONSULT_SCHEDULE: &[KeyBinding] = &[
+    bind("Tab / ↑↓", "help.key.switch_fields"),
+    bind("Ctrl+N", "help.key.add_new_patient_patient"),
+    bind("Ctrl+D", "help.key.pick_date_from_calendar"),
+    bind("Enter", "help.key.next_field_submit"),
+    bind("Esc", "help.key.back"),
+];
+
+const STAFF_ASSIGN: &[KeyBinding] = &[
+    bind("/ or s", "help.key.search_staff"),
+    bind("↑↓←→", "help.key.navigate_list_calendar"),
+    bind("Tab", "help.key.switch_month"),
+    bind("PgUp / PgDn", "help.key.previous_next_month"),
+    bind("Enter", "help.key.select_staff_date_shift"),
+    bind("v", "help.key.view_assignments"),
+    bind("p", "help.key.print_week_roster_shown"),
+    bind("l", "help.key.register_leave_move_consultations"),
+    bind("x", "help.key.clear_regenerate_shifts_date"),
+    bind("Esc", "help.key.back"),
+];
+
+const RECORD_STATISTICS: &[KeyBinding] = &[
+    bind("Tab / ↑↓", "help.key.navigate_options"),
+    bind("Ctrl+D", "help.key.pick_date_range_period"),
+    bind(
+        "Space / Enter",
+        "help.key.toggle_grouping_suppression_export",
+    ),
+    bind("Enter", "help.key.export_counts_research_dataset"),
+    bind("Esc", "help.key.back"),
+];
+
+const RECORD_EXPIRATIONS: &[KeyBinding] = &[
+    bind("↑↓", "help.key.navigate"),
+    bind("s", "help.key.sort_days_left_type"),
+    bind("d", "help.key.reverse_order"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const RECORD_ISOLATIONS: &[KeyBinding] = &[
+    bind("↑↓", "help.key.navigate"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const RECORD_STAYS: &[KeyBinding] = &[
+    bind("↑↓", "help.key.navigate"),
+    bind("←→", "help.key.previous_next_discharge_month"),
+    bind("a", "help.key.all_discharges_one_month"),
+    bind("Tab", "help.key.group_ward_diagnosis"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const RECORD_INTEGRITY: &[KeyBinding] = &[
+    bind("↑↓", "help.key.navigate"),
+    bind("Enter", "help.key.open_screen_that_fixes"),
+    bind("x", "help.key.remove_selected_shift_confirm"),
+    bind("r", "help.key.rescan"),
+    bind("Esc", "help.key.back"),
+];
+
+const RECORD_WAITING: &[KeyBinding] = &[
+    bind("↑↓", "help.key.navigate"),
+    bind("←→", "help.key.previous_next_month"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const RECORD_FORECAST: &[KeyBinding] = &[
+    bind("↑↓", "help.key.select_ward_planned_admission"),
+    bind("Tab", "help.key.switch_between_wards_planned"),
+    bind("a", "help.key.plan_admission"),
+    bind("x", "help.key.cancel_selected_planned_admission"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const RECORD_SATISFACTION: &[KeyBinding] = &[
+    bind("↑↓", "help.key.navigate"),
+    bind("Tab", "help.key.doctor_service_low_ratings"),
+    bind("Enter", "help.key.record_follow_up_low"),
+    bind("←→", "help.key.previous_next_month"),
+    bind("e", "help.key.export_month_feedback_csv"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const BILLING_EXPENSES: &[KeyBinding] = &[
+    bind("↑↓", "help.key.select_expense"),
+    bind("←→", "help.key.previous_next_month"),
+    bind("a", "help.key.add_expense"),
+    bind("e / Enter", "help.key.edit_expense"),
+    bind("x", "help.key.delete_expense_confirm"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const BILLING_EXPENSE_REPORT: &[KeyBinding] = &[
+    bind("↑↓", "help.key.navigate"),
+    bind("Tab", "help.key.department_category"),
+    bind("←→", "help.key.previous_next_month"),
+    bind("e", "help.key.export_month_expenses_csv"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const BILLING_CASHIER: &[KeyBinding] = &[
+    bind("o", "help.key.open_session_float"),
+    bind("p", "help.key.take_payment_cash_needs"),
+    bind("x", "help.key.close_session_counting_drawer"),
+    bind("↑↓", "help.key.select_payment"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const BILLING_CASHIER_HISTORY: &[KeyBinding] = &[
+    bind("↑↓", "help.key.select_session"),
+    bind("←→", "help.key.previous_next_month"),
+    bind("Ctrl+E", "help.key.export_rows_shown_csv"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const BILLING_INSURANCE: &[KeyBinding] = &[
+    bind("Tab", "help.key.switch_between_plans_coverage"),
+    bind("↑↓", "help.key.select_row"),
+    bind("a", "help.key.add_plan_enrol_patient"),
+    bind("e / Enter", "help.key.edit_plan_coverage_member"),
+    bind("x", "help.key.delete_plan_coverage_membership"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const BILLING_APPROVALS: &[KeyBinding] = &[
+    bind("↑↓", "help.key.select_request"),
+    bind("a", "help.key.approve_discount_administrators_accountants"),
+    bind("x", "help.key.reject_bill_catalog_price"),
+    bind("h", "help.key.switch_between_waiting_decided"),
+    bind("Ctrl+E", "help.key.export_rows_shown_csv"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const BILLING_BUDGET: &[KeyBinding] = &[
+    bind("↑↓", "help.key.select_budget_line"),
+    bind("←→", "help.key.previous_next_month"),
+    bind("a", "help.key.set_budget_administrators"),
+    bind("e / Enter", "help.key.change_budget_budget_unbudgeted"),
+    bind("x", "help.key.remove_budget_confirm"),
+    bind("c", "help.key.copy_last_month_budgets"),
+    bind("Ctrl+E", "help.key.export_rows_shown_csv"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const RECORD_CAMPAIGN: &[KeyBinding] = &[
+    bind("Tab / ↑↓", "help.key.switch_template_fields"),
+    bind("Enter", "help.key.next_field_pick_patients"),
+    bind("/ or s", "help.key.search_patients"),
+    bind("Space", "help.key.tick_untick_patient"),
+    bind("a", "help.key.tick_all_shown_untick"),
+    bind("u", "help.key.untick_patients_already_given"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const PATIENT_COMPLIANCE: &[KeyBinding] = &[
+    bind("/ or s", "help.key.search_patients"),
+    bind("↑↓", "help.key.navigate"),
+    bind("e", "help.key.export_everything_stored_about"),
+    bind("x", "help.key.erase_anonymize_patient_administrators"),
+    bind("Tab (erase)", "help.key.switch_between_reason_confirmation"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const PATIENT_IMPORT: &[KeyBinding] = &[
+    bind("Enter (path)", "help.key.read_check_csv_file"),
+    bind("↑↓", "help.key.navigate"),
+    bind("c", "help.key.import_rows_without_errors"),
+    bind("f", "help.key.choose_another_file"),
+    bind("r", "help.key.read_file_again"),
+    bind("Ctrl+E", "help.key.export_preview_each_row"),
+    bind("Esc", "help.key.back"),
+];
+
+const WAITLIST_ADD: &[KeyBinding] = &[
+    bind("/ or s", "help.key.search_patients"),
+    bind("↑↓", "help.key.navigate"),
+    bind("Enter", "help.key.select_patient_doctor_submit"),
+    bind("←→ or U/H/N/L", "help.key.change_priority"),
+    bind("Esc", "help.key.back"),
+];
+
+const WAITLIST_LIST: &[KeyBinding] = &[
+    bind("/", "help.key.search"),
+    bind("↑↓", "help.key.navigate"),
+    bind("d", "help.key.doctor_filter"),
+    bind("c", "help.key.show_closed_entries"),
+    bind("n", "help.key.suggest_cancelled_slot"),
+    bind("b", "help.key.mark_booked"),
+    bind("x", "help.key.remove"),
+    bind("w", "help.key.back_waiting"),
+    bind("Ctrl+F", "help.key.saved_searches_apply_save"),
+    bind("1-9", "help.key.apply_saved_search"),
+    bind("Ctrl+E", "help.key.export_rows_shown_csv"),
+    bind("Ctrl+O", "help.key.choose_columns_shown"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const BIRTHS: &[KeyBinding] = &[
+    bind("↑↓", "help.key.navigate"),
+    bind("a", "help.key.register_birth"),
+    bind("c", "help.key.write_certificate"),
+    bind("p", "help.key.print_certificate"),
+    bind("e", "help.key.export_statutory_report"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.cancel_back"),
+];
+
+const DEATHS: &[KeyBinding] = &[
+    bind("↑↓", "help.key.navigate"),
+    bind("a", "help.key.register_death"),
+    bind("c", "help.key.write_certificate"),
+    bind("p", "help.key.print_certificate"),
+    bind("e", "help.key.export_statutory_report"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.cancel_back"),
+];
+
+const TELECONSULT_UPCOMING: &[KeyBinding] = &[
+    bind("↑↓", "help.key.navigate"),
+    bind("←→", "help.key.previous_next_day"),
+    bind("t", "help.key.today"),
+    bind("d", "help.key.doctor_filter"),
+    bind("c", "help.key.check_in_undo"),
+    bind("Enter / o", "help.key.record_outcome"),
+    bind("f", "help.key.patient_feedback_completed_consultation"),
+    bind("n", "help.key.no_show_rates_tab"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const TELECONSULT_UTILIZATION: &[KeyBinding] = &[
+    bind("↑↓", "help.key.navigate"),
+    bind("←→", "help.key.previous_next_month"),
+    bind("Ctrl+D", "help.key.pick_date_range"),
+    bind("e", "help.key.export_csv"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const STAFF_PERFORMANCE: &[KeyBinding] = &[
+    bind("↑↓", "help.key.navigate"),
+    bind("←→", "help.key.previous_next_month"),
+    bind("e", "help.key.export_csv"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const QUEUE_ISSUE: &[KeyBinding] = &[
+    bind("Tab / ↑↓", "help.key.navigate"),
+    bind("←→", "help.key.change_doctor"),
+    bind("Enter", "help.key.next_field_issue_token"),
+    bind("Esc", "help.key.back"),
+];
+
+const QUEUE_CONSOLE: &[KeyBinding] = &[
+    bind("←→", "help.key.change_doctor"),
+    bind("n / Enter", "help.key.call_next_token"),
+    bind("c", "help.key.complete_current_token"),
+    bind("s", "help.key.skip_current_token"),
+    bind("f", "help.key.patient_feedback_last_token"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const QUEUE_DISPLAY: &[KeyBinding] = &[
+    bind("r", "help.key.refresh_now"),
+    bind("Esc", "help.key.close_board"),
+];
+
+const HANDOVER_RECORD: &[KeyBinding] = &[
+    bind("Tab / ↑↓", "help.key.navigate"),
+    bind("←→", "help.key.change_shift"),
+    bind("Enter", "help.key.next_field_record_handover"),
+    bind("Esc", "help.key.back"),
+];
+
+const HANDOVER_HISTORY: &[KeyBinding] = &[
+    bind("↑↓", "help.key.select_handover"),
+    bind("←→", "help.key.previous_next_day"),
+    bind("t", "help.key.today"),
+    bind("w", "help.key.ward_filter"),
+    bind("a / Enter", "help.key.acknowledge_incoming_staff"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const WORKLIST_MY_DAY: &[KeyBinding] = &[
+    bind("↑↓", "help.key.select_item"),
+    bind("←→", "help.key.previous_next_doctor"),
+    bind("Enter", "help.key.open_screen_item"),
+    bind("l", "help.key.link_unlink_your_account"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const WORKLIST_WARD_ROUND: &[KeyBinding] = &[
+    bind("↑↓", "help.key.select_patient"),
+    bind("←→", "help.key.previous_next_ward"),
+    bind("v", "help.key.record_vital_signs"),
+    bind("n", "help.key.add_nursing_note"),
+    bind("a", "help.key.admit_patient_prints_wristband"),
+    bind("w", "help.key.wristband_label"),
+    bind("i", "help.key.isolation_precautions_infection_flags"),
+    bind("e", "help.key.expected_discharge_date_used"),
+    bind("x", "help.key.discharge_selected_patient_offers"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const WORKLIST_FOLLOW_UPS: &[KeyBinding] = &[
+    bind("↑↓", "help.key.select_care_plan"),
+    bind("←→", "help.key.filter_assigned_doctor_nurse"),
+    bind("v", "help.key.reviewed_today_moves_next"),
+    bind("a", "help.key.add_care_plan"),
+    bind("e / Enter", "help.key.edit_care_plan"),
+    bind("x", "help.key.close_care_plan"),
+    bind("o", "help.key.show_overdue_follow_ups"),
+    bind("Ctrl+F", "help.key.saved_searches_apply_save"),
+    bind("1-9", "help.key.apply_saved_search"),
+    bind("Ctrl+E", "help.key.export_rows_shown_csv"),
+    bind("Ctrl+O", "help.key.choose_columns_shown"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const MATERNITY_PREGNANCIES: &[KeyBinding] = &[
+    bind("↑↓", "help.key.select_pregnancy"),
+    bind("a", "help.key.add_pregnancy"),
+    bind("e / Enter", "help.key.edit_pregnancy"),
+    bind("v", "help.key.record_antenatal_visit"),
+    bind("x", "help.key.close_pregnancy_without_birth"),
+    bind("c", "help.key.show_closed_pregnancies"),
+    bind("b", "help.key.open_birth_registry"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const MATERNITY_DUE: &[KeyBinding] = &[
+    bind("↑↓", "help.key.select_pregnancy"),
+    bind("←→", "help.key.previous_next_month"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const APPOINTMENT_BOOK: &[KeyBinding] = &[
+    bind("Tab / ↑↓", "help.key.switch_fields"),
+    bind("Ctrl+N", "help.key.add_new_patient_patient"),
+    bind("Ctrl+D", "help.key.pick_date_from_calendar"),
+    bind("Enter", "help.key.next_field_book"),
+    bind("Esc", "help.key.back"),
+];
+
+const APPOINTMENT_LIST: &[KeyBinding] = &[
+    bind("↑↓", "help.key.select_appointment"),
+    bind("←→", "help.key.previous_next_day"),
+    bind("t", "help.key.jump_today"),
+    bind("a", "help.key.book_appointment"),
+    bind("e / Enter", "help.key.change_selected_appointment"),
+    bind("c", "help.key.cancel_selected_appointment"),
+    bind("i", "help.key.check_in_undo"),
+    bind("d", "help.key.mark_attended_back_scheduled"),
+    bind("n", "help.key.no_show_rates_tab"),
+    bind("h", "help.key.show_hide_cancelled_appointments"),
+    bind("Ctrl+E", "help.key.export_list_csv_markdown"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const APPOINTMENT_UPDATE: &[KeyBinding] = &[
+    bind("Enter", "help.key.load_appointment_next_field"),
+    bind("Tab / ↑↓", "help.key.switch_fields"),
+    bind("Ctrl+D", "help.key.pick_date_from_calendar"),
+    bind("Esc", "help.key.back"),
+];
+
+const INVENTORY_STOCK: &[KeyBinding] = &[
+    bind("↑↓", "help.key.select_item"),
+    bind("a", "help.key.add_stock_item"),
+    bind("+ / -", "help.key.receive_issue_selected_item"),
+    bind("h / Enter", "help.key.movements_selected_item"),
+    bind("s", "help.key.open_stocktake"),
+    bind("Ctrl+E", "help.key.export_list_csv_markdown"),
+    bind("r", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+const INVENTORY_STOCKTAKE: &[KeyBinding] = &[
+    bind("s", "help.key.start_stocktake_when_none"),
+    bind("Enter", "help.key.count_scanned_typed_code"),
+    bind("↑↓", "help.key.navigate"),
+    bind("Tab", "help.key.switch_between_count_variance"),
+    bind("Ctrl+S", "help.key.post_adjustments_administrators"),
+    bind("Ctrl+X", "help.key.abandon_stocktake_administrators"),
+    bind("Ctrl+E", "help.key.export_view_csv_markdown"),
+    bind("Esc", "help.key.back_stock"),
+];
+
+const SETTINGS: &[KeyBinding] = &[
+    bind("↑↓", "help.key.preview_theme_language_pick"),
+    bind("Tab", "help.key.switch_between_theme_language"),
+    bind("Enter", "help.key.save_selection_edit_setting"),
+    bind("Esc (editing)", "help.key.discard_edit"),
+    bind("A", "help.key.toggle_accessibility_mode"),
+    bind("B", "help.key.back_up_database_background"),
+    bind("Esc", "help.key.back"),
+];
+
+const WEBHOOK_LOG: &[KeyBinding] = &[
+    bind("↑↓", "help.key.navigate"),
+    bind("Enter", "help.key.show_hide_payload"),
+    bind("T", "help.key.retry_failed_delivery"),
+    bind("R", "help.key.refresh"),
+    bind("Esc", "help.key.back"),
+];
+
+/// The shortcuts that work everywhere, with the keys from the configuration
+/// file in place of the defaults.
+pub fn global_bindings() -> Vec<KeyBinding> {
+    let config = config::config();
+    let keymap = &config.keymap;
+    GLOBAL
+        .iter()
+        .map(|binding| {
+            let configured = match binding.keys.as_ref() {
+                "F1" => &keymap.help,
+                "Ctrl+P" => &keymap.palette,
+                "Ctrl+Q" => &keymap.quit,
+                "Alt+Q" => &keymap.macro_record,
+                "Alt+@" => &keymap.macro_play,
+                "F2" => &keymap.scan,
+                "F3" => &keymap.vitals,
+                _ => return binding.clone(),
+            };
+            KeyBinding {
+                keys: Cow::Owned(configured.clone()),
+                action: binding.action,
+            }
+        })
+        .collect()
+}
+
+pub fn keymap_for(state: AppState) -> Keymap {
+    let (title, text_entry, bindings) = match state {
+        AppState::Login => ("help.screen.login", true, LOGIN),
+        AppState::Register => ("help.screen.create_account", true, REGISTER),
+        AppState::Home | AppState::Init | AppState::Quitting => ("help.screen.home", false, HOME),
+        AppState::Running(selected_app) => match selected_app {
+            SelectedApp::PatientAdd => ("help.screen.add_patient", true, PATIENT_ADD),
+            SelectedApp::PatientList => ("help.screen.patients", true, PATIENT_LIST),
+            SelectedApp::PatientDelete => ("help.screen.delete_patients", true, BULK_DELETE),
+            SelectedApp::PatientCompliance => {
+                ("help.screen.data_protection", true, PATIENT_COMPLIANCE)
+            }
+            SelectedApp::PatientImport => ("help.screen.import_patients", true, PATIENT_IMPORT),
+            SelectedApp::PatientUpdate => ("help.screen.update_patient", true, UNDOABLE_UPDATE),
+            SelectedApp::StaffAdd => ("help.screen.add_staff", true, STAFF_ADD),
+            SelectedApp::StaffAssign => ("help.screen.assign_shifts", true, STAFF_ASSIGN),
+            SelectedApp::StaffList => ("help.screen.staff", true, SEARCHABLE_LIST),
+            SelectedApp::StaffDelete => ("help.screen.delete_staff", true, BULK_DELETE),
+            SelectedApp::StaffUpdate => ("help.screen.update_staff", true, UPDATE),
+            SelectedApp::StaffPerformance => {
+                ("help.screen.staff_performance", false, STAFF_PERFORMANCE)
+            }
+            SelectedApp::RecordStore => ("help.screen.store_medical_record", true, PICK_AND_FILL),
+            SelectedApp::RecordRetrieve => ("help.screen.medical_records", true, RECORD_LIST),
+            SelectedApp::RecordUpdate => {
+                ("help.screen.update_medical_record", true, UNDOABLE_UPDATE)
+            }
+            SelectedApp::RecordDelete => ("help.screen.delete_medical_records", true, BULK_DELETE),
+            SelectedApp::RecordStatistics => (
+                "help.screen.public_health_statistics",
+                true,
+                RECORD_STATISTICS,
+            ),
+            SelectedApp::RecordExpirations => {
+                ("help.screen.expirations", false, RECORD_EXPIRATIONS)
+            }
+            SelectedApp::RecordIsolations => {
+                ("help.screen.active_isolations", false, RECORD_ISOLATIONS)
+            }
+            SelectedApp::RecordStays => ("help.screen.length_of_stay", false, RECORD_STAYS),
+            SelectedApp::RecordIntegrity => ("help.screen.data_integrity", false, RECORD_INTEGRITY),
+            SelectedApp::RecordWaiting => {
+                ("help.screen.queue_waiting_times", false, RECORD_WAITING)
+            }
+            SelectedApp::RecordForecast => ("help.screen.bed_forecast", true, RECORD_FORECAST),
+            SelectedApp::RecordSatisfaction => (
+                "help.screen.patient_satisfaction",
+                true,
+                RECORD_SATISFACTION,
+            ),
+            SelectedApp::RecordCampaign => {
+                ("help.screen.vaccination_campaign", true, RECORD_CAMPAIGN)
+            }
+            SelectedApp::BillingInvoice => ("help.screen.create_invoice", true, PICK_AND_FILL),
+            SelectedApp::BillingView => ("help.screen.invoices", true, INVOICE_LIST),
+            SelectedApp::BillingUpdate => ("help.screen.update_invoice", true, UNDOABLE_UPDATE),
+            SelectedApp::BillingExpenses => ("help.screen.expenses", true, BILLING_EXPENSES),
+            SelectedApp::BillingExpenseReport => (
+                "help.screen.monthly_expense_report",
+                false,
+                BILLING_EXPENSE_REPORT,
+            ),
+            SelectedApp::BillingBudget => ("help.screen.budget_vs_actual", true, BILLING_BUDGET),
+            SelectedApp::BillingCashier => ("help.screen.cash_drawer", true, BILLING_CASHIER),
+            SelectedApp::BillingCashierHistory => (
+                "help.screen.cashier_sessions",
+                false,
+                BILLING_CASHIER_HISTORY,
+            ),
+            SelectedApp::BillingInsurance => {
+                ("help.screen.insurance_plans", true, BILLING_INSURANCE)
+            }
+            SelectedApp::BillingApprovals => {
+                ("help.screen.price_approvals", false, BILLING_APPROVALS)
+            }
+            SelectedApp::WaitlistAdd => ("help.screen.add_to_waitlist", true, WAITLIST_ADD),
+            SelectedApp::WaitlistList => ("help.screen.waitlist", true, WAITLIST_LIST),
+            SelectedApp::RegistryBirths => ("help.screen.birth_registry", true, BIRTHS),
+            SelectedApp::RegistryDeaths => ("help.screen.death_registry", true, DEATHS),
+            SelectedApp::TeleconsultSchedule => (
+                "help.screen.schedule_teleconsultation",
+                true,
+                TELECONSULT_SCHEDULE,
+            ),
+            SelectedApp::TeleconsultUpcoming => (
+                "help.screen.remote_consultations",
+                true,
+                TELECONSULT_UPCOMING,
+            ),
+            SelectedApp::TeleconsultUtilization => (
+                "help.screen.doctor_utilization",
+                false,
+                TELECONSULT_UTILIZATION,
+            ),
+            SelectedApp::QueueIssue => ("help.screen.issue_token", true, QUEUE_ISSUE),
+            SelectedApp::QueueConsole => ("help.screen.doctor_queue_console", true, QUEUE_CONSOLE),
+            SelectedApp::QueueDisplay => ("help.screen.waiting_room_display", false, QUEUE_DISPLAY),
+            SelectedApp::HandoverRecord => ("help.screen.record_handover", true, HANDOVER_RECORD),
+            SelectedApp::HandoverHistory => {
+                ("help.screen.handover_history", false, HANDOVER_HISTORY)
+            }
+            SelectedApp::WorklistMyDay => ("help.screen.my_day", false, WORKLIST_MY_DAY),
+            SelectedApp::WorklistWardRound => ("help.screen.ward_round", true, WORKLIST_WARD_ROUND),
+            SelectedApp::WorklistFollowUps => ("help.screen.follow_ups", true, WORKLIST_FOLLOW_UPS),
+            SelectedApp::MaternityPregnancies => {
+                ("help.screen.maternity", true, MATERNITY_PREGNANCIES)
+            }
+            SelectedApp::MaternityDue => ("help.screen.due_this_month", false, MATERNITY_DUE),
+            SelectedApp::AppointmentBook => {
+                ("help.screen.book_appointment", true, APPOINTMENT_BOOK)
+            }
+            SelectedApp::AppointmentList => ("help.screen.appointments", true, APPOINTMENT_LIST),
+            SelectedApp::AppointmentUpdate => {
+                ("help.screen.change_appointment", true, APPOINTMENT_UPDATE)
+            }
+            SelectedApp::InventoryStock => ("help.screen.stock", false, INVENTORY_STOCK),
+            SelectedApp::InventoryStocktake => ("help.screen.stocktake", true, INVENTORY_STOCKTAKE),
+            SelectedApp::Settings | SelectedApp::SettingsBackup => {
+                ("help.screen.settings", true, SETTINGS)
+            }
+            SelectedApp::WebhookLog => ("help.screen.webhook_deliveries", false, WEBHOOK_LOG),
+            SelectedApp::Plugin(id) => match plugins::get(id) {
+                Some(plugin) => (plugin.name, plugin.text_entry, plugin.bindings),
+                None => ("help.screen.home", false, HOME),
+            },
+            SelectedApp::Hospital | SelectedApp::None | SelectedApp::Quit => {
+                ("help.screen.home", false, HOME)
+            }
+        },
+    };
+    Keymap {
+        title,
+        text_entry,
+        bindings,
+    }
+}
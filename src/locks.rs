@@ -0,0 +1,127 @@
+//! Advisory edit locks, so people sharing a database can see who else has
+//! a record open for editing. Nothing is blocked: an update screen claims
+//! the record it loads, the claim is refreshed every [`HEARTBEAT`] while the
+//! screen keeps it, and a claim whose heartbeat stopped (a crash, a closed
+//! laptop) is ignored once it is [`STALE_AFTER`] old.
+
+use crate::db::{self, Db};
+use crate::ui_state;
+use std::process;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const HEARTBEAT: Duration = Duration::from_secs(10);
+const STALE_AFTER: Duration = Duration::from_secs(30);
+
+struct Held {
+    entity: &'static str,
+    entity_id: i64,
+    /// The other users with the record open, as of the last heartbeat.
+    others: Vec<String>,
+}
+
+struct Registry {
+    held: Vec<Held>,
+    last_beat: Option<Instant>,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    held: Vec::new(),
+    last_beat: None,
+});
+
+/// Tells this running Rustoria apart from others, even ones signed in as
+/// the same user.
+fn session() -> &'static str {
+    static SESSION: OnceLock<String> = OnceLock::new();
+    SESSION.get_or_init(|| {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        format!("{:x}-{:x}", process::id(), nanos)
+    })
+}
+
+/// Refreshes this session's claim on the record and returns who else holds
+/// one. Locks are only advisory, so failures just mean no warning.
+fn beat(db: &Db, entity: &str, entity_id: i64) -> Vec<String> {
+    if db.is_read_only() {
+        return Vec::new();
+    }
+    let user = ui_state::current_user();
+    db::heartbeat_edit_lock(db, entity, entity_id, session(), user.as_deref())
+        .and_then(|()| {
+            db::get_other_editors(db, entity, entity_id, session(), STALE_AFTER.as_secs())
+        })
+        .unwrap_or_default()
+}
+
+/// A claim on one record, held while an update screen has it loaded and
+/// given up when dropped.
+pub struct EditLock {
+    db: Db,
+    entity: &'static str,
+    entity_id: i64,
+}
+
+impl EditLock {
+    /// Claims `entity_id` of `entity` ("patient", "invoice", ...).
+    pub fn claim(db: &Db, entity: &'static str, entity_id: i64) -> Self {
+        let others = beat(db, entity, entity_id);
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        registry.held.push(Held {
+            entity,
+            entity_id,
+            others,
+        });
+        Self {
+            db: db.clone(),
+            entity,
+            entity_id,
+        }
+    }
+
+    /// The other users who have the record open right now.
+    pub fn other_editors(&self) -> Vec<String> {
+        let registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        registry
+            .held
+            .iter()
+            .find(|h| h.entity == self.entity && h.entity_id == self.entity_id)
+            .map(|h| h.others.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for EditLock {
+    fn drop(&mut self) {
+        let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(index) = registry
+            .held
+            .iter()
+            .position(|h| h.entity == self.entity && h.entity_id == self.entity_id)
+        {
+            registry.held.remove(index);
+        }
+        if !self.db.is_read_only() {
+            let _ = db::release_edit_lock(&self.db, self.entity, self.entity_id, session());
+        }
+    }
+}
+
+/// Called on every UI tick; at most every [`HEARTBEAT`] refreshes the
+/// claims this session holds and who else has those records open.
+pub fn tick(db: &Db) {
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    if registry.held.is_empty()
+        || registry
+            .last_beat
+            .is_some_and(|at| at.elapsed() < HEARTBEAT)
+    {
+        return;
+    }
+    registry.last_beat = Some(Instant::now());
+    for held in &mut registry.held {
+        held.others = beat(db, held.entity, held.entity_id);
+    }
+}
@@ -1,34 +1,96 @@
+mod accessibility;
 mod app;
 mod auth;
 mod components;
+mod crash;
 mod db;
+mod hooks;
+mod i18n;
+mod interop;
+mod keymap;
+mod locks;
 mod models;
+mod notify;
+mod plugins;
+mod theme;
 mod tui;
+mod ui_state;
 mod utils;
+mod webhooks;
 
 use anyhow::Result;
 use app::App;
-use crossterm::{
-    event::DisableMouseCapture,
-    terminal::{self, LeaveAlternateScreen},
-};
+use db::{Db, SchemaStatus};
 use ratatui::prelude::{CrosstermBackend, Terminal};
 use std::io;
 use tui::Tui;
 
 fn main() -> Result<()> {
-    let _guard = CleanupGuard;
+    crash::install();
+    utils::datetime::init();
 
-    db::init_db()?;
+    utils::config::load()?;
+    let db = Db::new(db::db_path());
+    if let Some(dir) = utils::arg_value("--sync") {
+        if let SchemaStatus::Older(version) | SchemaStatus::Newer(version) = db::init_db(&db)? {
+            anyhow::bail!(
+                "The database has schema version {} but this build uses version {}; start Rustoria without --sync to upgrade it",
+                version,
+                db::SCHEMA_VERSION
+            );
+        }
+        println!("{}", db::sync::sync_with(&db, dir.as_ref())?);
+        return Ok(());
+    }
+    if let Some(file) = utils::arg_value("--import-fhir") {
+        if let SchemaStatus::Older(version) | SchemaStatus::Newer(version) = db::init_db(&db)? {
+            anyhow::bail!(
+                "The database has schema version {} but this build uses version {}; start Rustoria without --import-fhir to upgrade it",
+                version,
+                db::SCHEMA_VERSION
+            );
+        }
+        println!("{}", interop::fhir::import_patients(&db, file.as_ref())?);
+        return Ok(());
+    }
+    if let Some(file) = utils::arg_value("--import-procedures") {
+        if let SchemaStatus::Older(version) | SchemaStatus::Newer(version) = db::init_db(&db)? {
+            anyhow::bail!(
+                "The database has schema version {} but this build uses version {}; start Rustoria without --import-procedures to upgrade it",
+                version,
+                db::SCHEMA_VERSION
+            );
+        }
+        println!("{}", utils::procedures::import(&db, file.as_ref())?);
+        return Ok(());
+    }
+    theme::load(&db);
+    i18n::load(&db);
+    accessibility::load(&db);
+    plugins::register_builtin();
+    hooks::check_config();
+    webhooks::check_config();
+    utils::datetime::check_config();
+    components::quick_actions::check_config();
 
+    let _guard = CleanupGuard;
     let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
     terminal.clear()?;
 
     let mut tui = Tui::new(terminal);
     tui.init()?;
 
-    let mut app = App::new();
-    let res = app.run(&mut tui);
+    let mut app = App::new(&db);
+    // A locked, damaged or differently versioned database is dealt with on
+    // screen rather than ending the program before the interface starts.
+    app.open_database();
+    let res = if std::env::args().any(|arg| arg == "--display-board") {
+        app.run_display_board(&mut tui)
+    } else if std::env::args().any(|arg| arg == "--kiosk") {
+        app.run_kiosk(&mut tui)
+    } else {
+        app.run(&mut tui)
+    };
 
     tui.exit()?;
 
@@ -42,8 +104,6 @@ struct CleanupGuard;
 
 impl Drop for CleanupGuard {
     fn drop(&mut self) {
-        // Ignore errors during cleanup
-        let _ = terminal::disable_raw_mode();
-        let _ = crossterm::execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+        tui::restore();
     }
 }
@@ -1,10 +1,86 @@
+use crate::utils::config;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Stored and serialized as its label, so a value this build does not know,
+/// written by another site or version, comes back as [`Gender::Custom`]
+/// instead of failing to load.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum Gender {
     Male,
     Female,
     Other,
+    PreferNotToSay,
+    /// One of the site's own options under `genders` in the configuration.
+    Custom(String),
+}
+
+impl Gender {
+    pub fn label(&self) -> &str {
+        match self {
+            Gender::Male => "Male",
+            Gender::Female => "Female",
+            Gender::Other => "Other",
+            Gender::PreferNotToSay => "Prefer not to say",
+            Gender::Custom(label) => label,
+        }
+    }
+
+    /// Every option offered in forms: the built-in ones, then the site's.
+    pub fn options() -> Vec<Gender> {
+        let mut options = vec![
+            Gender::Male,
+            Gender::Female,
+            Gender::Other,
+            Gender::PreferNotToSay,
+        ];
+        for label in &config::config().genders {
+            let label = label.trim();
+            if !label.is_empty()
+                && !options
+                    .iter()
+                    .any(|g| g.label().eq_ignore_ascii_case(label))
+            {
+                options.push(Gender::Custom(label.to_string()));
+            }
+        }
+        options
+    }
+
+    /// Reads an option as typed, case-insensitively; `M`, `F`, `O` and `P`
+    /// stand for the built-in ones. `None` for anything not offered.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        match text.to_lowercase().as_str() {
+            "m" => return Some(Gender::Male),
+            "f" => return Some(Gender::Female),
+            "o" => return Some(Gender::Other),
+            "p" => return Some(Gender::PreferNotToSay),
+            _ => {}
+        }
+        Self::options()
+            .into_iter()
+            .find(|g| g.label().eq_ignore_ascii_case(text))
+    }
+
+    /// The option after this one, wrapping round, for cycling in forms.
+    pub fn next(&self) -> Gender {
+        let options = Self::options();
+        let index = options.iter().position(|g| g == self).map_or(0, |i| i + 1);
+        options[index % options.len()].clone()
+    }
+}
+
+impl From<String> for Gender {
+    fn from(text: String) -> Self {
+        Gender::parse(&text).unwrap_or(Gender::Custom(text))
+    }
+}
+
+impl From<Gender> for String {
+    fn from(gender: Gender) -> Self {
+        gender.label().to_string()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,12 +90,77 @@ pub struct Patient {
     pub last_name: String,
     pub date_of_birth: String,
     pub gender: Gender,
+    /// The street line; the rest of the address is in the fields below.
     pub address: String,
+    #[serde(default)]
+    pub city: String,
+    #[serde(default)]
+    pub region: String,
+    #[serde(default)]
+    pub postcode: String,
     pub phone_number: String,
     pub email: Option<String>,
     pub medical_history: Option<String>,
     pub allergies: Option<String>,
     pub current_medications: Option<String>,
+    /// How reminders and result notifications reach the patient.
+    pub contact_preference: ContactPreference,
+    /// Picture taken with the configured camera; see [`crate::utils::photo`].
+    #[serde(default)]
+    pub photo_path: Option<String>,
+    /// Medical record number printed on labels and read back by the scan
+    /// prompt; see [`crate::utils::barcode::mrn`].
+    #[serde(default)]
+    pub mrn: String,
+}
+
+impl Patient {
+    pub fn full_address(&self) -> String {
+        format_address(&self.address, &self.city, &self.region, &self.postcode)
+    }
+}
+
+/// The street line, city, region and postcode on one line, leaving out the
+/// parts that are blank.
+pub fn format_address(street: &str, city: &str, region: &str, postcode: &str) -> String {
+    [street, city, region, postcode]
+        .iter()
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ContactPreference {
+    #[default]
+    Sms,
+    Email,
+    Both,
+    /// The patient asked not to be sent messages.
+    None,
+}
+
+impl ContactPreference {
+    pub fn label(self) -> &'static str {
+        match self {
+            ContactPreference::Sms => "SMS",
+            ContactPreference::Email => "Email",
+            ContactPreference::Both => "Both",
+            ContactPreference::None => "None",
+        }
+    }
+
+    /// Reads a label as typed in a form, case-insensitively.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().as_str() {
+            "sms" | "s" => Some(ContactPreference::Sms),
+            "email" | "e" => Some(ContactPreference::Email),
+            "both" | "b" => Some(ContactPreference::Both),
+            "none" | "n" => Some(ContactPreference::None),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -28,6 +169,7 @@ pub enum StaffRole {
     Nurse,
     Admin,
     Technician,
+    Accountant,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,7 +179,37 @@ pub struct StaffMember {
     pub role: StaffRole,
     pub phone_number: String,
     pub email: Option<String>,
+    /// The street line; the rest of the address is in the fields below.
     pub address: String,
+    #[serde(default)]
+    pub city: String,
+    #[serde(default)]
+    pub region: String,
+    #[serde(default)]
+    pub postcode: String,
+}
+
+impl StaffMember {
+    pub fn full_address(&self) -> String {
+        format_address(&self.address, &self.city, &self.region, &self.postcode)
+    }
+}
+
+/// Days a staff member is away, `start_date` to `end_date` inclusive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaffLeave {
+    pub id: i64,
+    pub staff_id: i64,
+    pub start_date: String,
+    pub end_date: String,
+    pub reason: Option<String>,
+}
+
+impl StaffLeave {
+    /// Whether the leave covers `date` (`YYYY-MM-DD`).
+    pub fn covers(&self, date: &str) -> bool {
+        self.start_date.as_str() <= date && date <= self.end_date.as_str()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +220,139 @@ pub struct MedicalRecord {
     pub nurse_notes: Option<String>,
     pub diagnosis: String,
     pub prescription: Option<String>,
+    /// Codes from the procedure catalog for what was done, comma separated;
+    /// see [`crate::utils::procedures`].
+    #[serde(default)]
+    pub procedures: Option<String>,
+    /// The staff member linked to the account that wrote the record; set
+    /// when it is created and kept through edits.
+    #[serde(default)]
+    pub author_id: Option<i64>,
+}
+
+/// How a payment was made. Only cash goes through a cashier session's
+/// drawer.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PaymentMethod {
+    Cash,
+    Card,
+    Transfer,
+    Insurance,
+}
+
+impl PaymentMethod {
+    /// The labels of every method, as typed in the payment form.
+    pub const LABELS: [&'static str; 4] = ["Cash", "Card", "Transfer", "Insurance"];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PaymentMethod::Cash => "Cash",
+            PaymentMethod::Card => "Card",
+            PaymentMethod::Transfer => "Transfer",
+            PaymentMethod::Insurance => "Insurance",
+        }
+    }
+
+    /// Reads a label as typed in a form, case-insensitively.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().as_str() {
+            "cash" => Some(PaymentMethod::Cash),
+            "card" => Some(PaymentMethod::Card),
+            "transfer" => Some(PaymentMethod::Transfer),
+            "insurance" => Some(PaymentMethod::Insurance),
+            _ => None,
+        }
+    }
+}
+
+/// Money received from a patient towards their invoices.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Payment {
+    pub id: i64,
+    pub patient_id: i64,
+    pub amount: f64,
+    pub method: PaymentMethod,
+    /// Stored UTC timestamp.
+    pub paid_at: String,
+    /// The cashier session open when it was taken; always set for cash.
+    pub session_id: Option<i64>,
+    /// The account that took it.
+    pub received_by: Option<String>,
+    /// Card slip, transfer or claim number.
+    pub reference: Option<String>,
+}
+
+/// A cashier's turn at the cash drawer, from the float counted in to the
+/// cash counted out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CashierSession {
+    pub id: i64,
+    /// The account that opened it.
+    pub cashier: String,
+    pub opened_at: String,
+    pub opening_float: f64,
+    /// `None` while the session is open.
+    pub closed_at: Option<String>,
+    pub counted_cash: Option<f64>,
+    pub close_note: Option<String>,
+    /// Cash payments taken during the session.
+    pub cash_taken: f64,
+    /// Payments by any other method taken during the session.
+    pub other_taken: f64,
+}
+
+impl CashierSession {
+    /// What the drawer should hold: the float plus the cash taken.
+    pub fn expected_cash(&self) -> f64 {
+        self.opening_float + self.cash_taken
+    }
+
+    /// Counted minus expected once closed: negative when the drawer is short.
+    pub fn variance(&self) -> Option<f64> {
+        self.counted_cash
+            .map(|counted| counted - self.expected_cash())
+    }
+}
+
+/// What an expense was spent on.
+pub const EXPENSE_CATEGORIES: [&str; 8] = [
+    "Supplies",
+    "Pharmacy",
+    "Equipment",
+    "Salaries",
+    "Utilities",
+    "Maintenance",
+    "Services",
+    "Other",
+];
+
+/// Money paid out by a department, the other side of the invoices.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Expense {
+    pub id: i64,
+    pub department: String,
+    /// One of [`EXPENSE_CATEGORIES`].
+    pub category: String,
+    pub amount: f64,
+    pub spent_on: String,
+    pub payee: Option<String>,
+    pub note: Option<String>,
+    /// The account that entered the expense.
+    pub recorded_by: Option<String>,
+}
+
+/// What a department may spend in a month, on one category or, when
+/// `category` is `None`, on everything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Budget {
+    pub id: i64,
+    /// `YYYY-MM`.
+    pub month: String,
+    pub department: String,
+    pub category: Option<String>,
+    pub amount: f64,
+    /// The administrator who set it.
+    pub set_by: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,4 +362,697 @@ pub struct Invoice {
     pub item: String,
     pub quantity: i32,
     pub cost: f64,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// Why the invoice was raised although one for the same item had already
+    /// been billed to the patient that day.
+    #[serde(default)]
+    pub duplicate_note: Option<String>,
+    /// The catalog procedure billed, if the item is one.
+    #[serde(default)]
+    pub procedure_code: Option<String>,
+    /// One of [`SERVICE_CATEGORIES`], which decides what insurance covers.
+    #[serde(default)]
+    pub category: Option<String>,
+    /// What the patient's insurance plan pays of `cost`, worked out when the
+    /// invoice is saved; `None` for a patient without a plan.
+    #[serde(default)]
+    pub insurer_share: Option<f64>,
+    /// Waiting for an approver to decide on a price override; a held
+    /// invoice is not billed.
+    #[serde(default)]
+    pub held: bool,
+}
+
+impl Invoice {
+    /// What is left for the patient to pay after their insurance.
+    pub fn co_pay(&self) -> f64 {
+        self.cost - self.insurer_share.unwrap_or(0.0)
+    }
+}
+
+/// A printed invoice or receipt. It is numbered when first issued and
+/// reprinted under the same number with the same lines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceDocument {
+    pub id: i64,
+    pub document_number: String,
+    pub patient_id: i64,
+    pub receipt: bool,
+    /// The facility's date when it was issued, as `YYYY-MM-DD`.
+    pub issued_on: String,
+    pub issued_by: Option<String>,
+    /// The invoice lines it covers.
+    pub invoice_ids: Vec<i64>,
+}
+
+/// Kinds of service billed, each covered at its own rate by an insurance
+/// plan.
+pub const SERVICE_CATEGORIES: [&str; 7] = [
+    "Consultation",
+    "Procedure",
+    "Laboratory",
+    "Imaging",
+    "Pharmacy",
+    "Room",
+    "Other",
+];
+
+/// An insurance plan patients can be enrolled in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InsurancePlan {
+    pub id: i64,
+    pub name: String,
+    pub insurer: Option<String>,
+    /// Patients enrolled in it.
+    pub members: i64,
+}
+
+/// How much of one service category an insurance plan pays.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanCoverage {
+    pub plan_id: i64,
+    /// One of [`SERVICE_CATEGORIES`].
+    pub category: String,
+    /// Percentage of each invoice the insurer pays.
+    pub percent: f64,
+    /// The most it pays a year for each patient in this category.
+    pub annual_cap: Option<f64>,
+}
+
+/// A patient's enrolment in an insurance plan; a patient has at most one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatientInsurance {
+    pub patient_id: i64,
+    pub plan_id: i64,
+    pub member_number: Option<String>,
+    /// The patient's full name.
+    pub patient_name: String,
+    pub plan_name: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// A price below the catalog's asked for beyond the discount limit. The
+/// invoice is held until an approver decides; a rejected one is billed at
+/// the catalog price.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceOverride {
+    pub id: i64,
+    pub invoice_id: i64,
+    pub patient_id: i64,
+    /// The patient's full name.
+    pub patient_name: String,
+    pub item: String,
+    pub list_price: f64,
+    pub requested_price: f64,
+    pub reason: String,
+    pub requested_by: String,
+    pub requested_at: String,
+    pub status: ApprovalStatus,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<String>,
+}
+
+impl PriceOverride {
+    /// How much is taken off the catalog price, in percent.
+    pub fn discount_percent(&self) -> f64 {
+        discount_percent(self.list_price, self.requested_price)
+    }
+}
+
+/// How much `price` is below `list_price`, in percent.
+pub fn discount_percent(list_price: f64, price: f64) -> f64 {
+    if list_price <= 0.0 {
+        return 0.0;
+    }
+    (list_price - price) / list_price * 100.0
+}
+
+/// A billable procedure in the catalog, such as an ICHI or CPT code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Procedure {
+    pub id: i64,
+    pub code: String,
+    pub name: String,
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WaitlistPriority {
+    Urgent,
+    High,
+    Normal,
+    Low,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum WaitlistStatus {
+    Waiting,
+    Booked,
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaitlistEntry {
+    pub id: i64,
+    pub patient_id: i64,
+    pub doctor_id: i64,
+    pub procedure: String,
+    pub priority: WaitlistPriority,
+    pub preferred_date: Option<String>,
+    pub notes: Option<String>,
+    pub status: WaitlistStatus,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BirthRecord {
+    pub id: i64,
+    pub mother_id: i64,
+    pub newborn_name: String,
+    pub sex: Gender,
+    pub birth_time: String,
+    pub weight_grams: Option<i32>,
+    pub delivering_doctor_id: i64,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeathRecord {
+    pub id: i64,
+    pub patient_id: i64,
+    pub time_of_death: String,
+    pub cause: String,
+    pub certifying_doctor_id: i64,
+    pub place: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DocumentType {
+    IdCopy,
+    ReferralLetter,
+    PastRecord,
+    LabReport,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatientDocument {
+    pub id: i64,
+    pub patient_id: i64,
+    pub doc_type: DocumentType,
+    pub document_date: String,
+    pub file_path: String,
+    pub notes: Option<String>,
+    /// When an ID copy or referral stops being valid, as `YYYY-MM-DD`.
+    #[serde(default)]
+    pub expires_on: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ReportStatus {
+    Pending,
+    Preliminary,
+    Final,
+    Amended,
+}
+
+/// Metadata of an imaging study whose images live in a PACS or on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImagingStudy {
+    pub id: i64,
+    pub patient_id: i64,
+    pub accession_number: String,
+    /// DICOM modality code, like `CT` or `MR`.
+    pub modality: String,
+    pub study_date: String,
+    pub description: Option<String>,
+    /// Number of the radiology order the study was done for.
+    pub order_number: Option<String>,
+    pub report_status: ReportStatus,
+    /// File path, PACS URL or study instance UID of the images.
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TeleconsultStatus {
+    Scheduled,
+    Completed,
+    NoShow,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Teleconsultation {
+    pub id: i64,
+    pub patient_id: i64,
+    pub doctor_id: i64,
+    pub scheduled_at: String,
+    pub contact: String,
+    pub duration_minutes: Option<i32>,
+    pub status: TeleconsultStatus,
+    pub outcome: Option<String>,
+    pub medical_record_id: Option<i64>,
+    /// When the patient joined or arrived, if they have.
+    pub checked_in_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AppointmentStatus {
+    Scheduled,
+    Completed,
+    Cancelled,
+    NoShow,
+}
+
+/// An in-person visit booked with a doctor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Appointment {
+    pub id: i64,
+    pub patient_id: i64,
+    pub doctor_id: i64,
+    /// Stored in UTC; see [`crate::utils::datetime::to_stored`].
+    pub scheduled_at: String,
+    pub duration_minutes: i32,
+    pub reason: Option<String>,
+    pub status: AppointmentStatus,
+    pub cancel_reason: Option<String>,
+    /// The account that booked it.
+    pub booked_by: Option<String>,
+    /// When the patient arrived, if they have.
+    pub checked_in_at: Option<String>,
+}
+
+/// Attended and missed appointments and consultations of one doctor or
+/// patient, for no-show rates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoShowCount {
+    pub id: i64,
+    pub attended: i64,
+    pub no_shows: i64,
+}
+
+/// A patient's rating of a consultation or a walk-in visit, from 1 (very
+/// poor) to 5 (excellent).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatientFeedback {
+    pub id: i64,
+    pub patient_id: i64,
+    pub doctor_id: i64,
+    pub teleconsultation_id: Option<i64>,
+    pub queue_token_id: Option<i64>,
+    pub rating: i64,
+    pub comment: Option<String>,
+    pub created_at: String,
+    /// When someone got back to the patient about a low rating.
+    pub followed_up_at: Option<String>,
+    pub follow_up_note: Option<String>,
+}
+
+/// What one staff member did over a period, for appraisals.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaffPerformance {
+    pub staff_id: i64,
+    pub name: String,
+    pub role: String,
+    /// Shifts on the roster, less those falling on days of leave.
+    pub shifts: i64,
+    pub leave_days: i64,
+    /// Medical records written from an account linked to the staff member.
+    pub records: i64,
+    /// Consultations completed as the doctor.
+    pub consultations: i64,
+    /// Queue tokens seen to the end.
+    pub walk_ins: i64,
+    pub ratings: i64,
+    /// Mean patient rating, 1 to 5, over the feedback given in the period.
+    pub average_rating: Option<f64>,
+}
+
+/// One doctor's consultations, walk-ins and shifts over a period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorUtilization {
+    pub doctor_id: i64,
+    pub name: String,
+    /// Consultations booked in the period, whatever became of them.
+    pub booked: i64,
+    pub attended: i64,
+    pub no_shows: i64,
+    pub cancelled: i64,
+    /// Queue tokens seen to the end.
+    pub walk_ins: i64,
+    /// Mean minutes from check-in to check-out, over the consultations that
+    /// have both.
+    pub average_minutes: Option<f64>,
+    pub shifts: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TokenStatus {
+    Waiting,
+    Serving,
+    Done,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueToken {
+    pub id: i64,
+    pub token_number: i32,
+    pub patient_id: i64,
+    pub doctor_id: i64,
+    pub queue_date: String,
+    pub issued_at: String,
+    pub called_at: Option<String>,
+    pub status: TokenStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandoverNote {
+    pub id: i64,
+    pub ward: String,
+    pub handover_date: String,
+    pub shift: String,
+    pub author_id: i64,
+    pub critical_patients: String,
+    pub pending_tasks: String,
+    pub notes: Option<String>,
+    pub created_at: String,
+    pub acknowledged_by: Option<i64>,
+    pub acknowledged_at: Option<String>,
+}
+
+/// A stay on a ward; current while `discharged_at` is empty.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Admission {
+    pub id: i64,
+    pub patient_id: i64,
+    pub ward: String,
+    pub bed: Option<String>,
+    pub admitted_at: String,
+    pub discharged_at: Option<String>,
+    /// The precautions taken while isolated, one of [`ISOLATION_PRECAUTIONS`].
+    #[serde(default)]
+    pub isolation: Option<String>,
+    /// Infections the patient is flagged with, like "MRSA, COVID-19".
+    #[serde(default)]
+    pub infection_flags: Option<String>,
+    #[serde(default)]
+    pub isolated_since: Option<String>,
+    /// The day (`YYYY-MM-DD`) the patient is expected to go home.
+    #[serde(default)]
+    pub expected_discharge: Option<String>,
+}
+
+/// An admission booked ahead, for the occupancy forecast. Linked to the
+/// admission once the patient arrives on the ward.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedAdmission {
+    pub id: i64,
+    pub patient_id: i64,
+    pub ward: String,
+    pub planned_date: String,
+    pub admission_id: Option<i64>,
+}
+
+pub const ISOLATION_PRECAUTIONS: [&str; 3] = ["Contact", "Droplet", "Airborne"];
+
+impl Admission {
+    pub fn is_isolated(&self) -> bool {
+        self.isolation.is_some() || self.infection_flags.is_some()
+    }
+
+    /// Like "Contact precautions - MRSA", for warnings and the bed board.
+    pub fn isolation_text(&self) -> Option<String> {
+        match (&self.isolation, &self.infection_flags) {
+            (Some(isolation), Some(flags)) => {
+                Some(format!("{} precautions - {}", isolation, flags))
+            }
+            (Some(isolation), None) => Some(format!("{} precautions", isolation)),
+            (None, Some(flags)) => Some(flags.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    /// Given up on after the last retry.
+    Failed,
+}
+
+/// One event queued for, or sent to, a configured webhook.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub event: String,
+    pub url: String,
+    /// The JSON body, kept so retries send exactly the same thing.
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: i64,
+    pub next_attempt_at: String,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+}
+
+/// A set of vital signs, a nursing note, or both, taken at the bedside.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Observation {
+    pub id: i64,
+    pub patient_id: i64,
+    pub recorded_at: String,
+    pub temperature: Option<f64>,
+    pub pulse: Option<i32>,
+    pub blood_pressure: Option<String>,
+    pub respiratory_rate: Option<i32>,
+    pub spo2: Option<i32>,
+    pub note: Option<String>,
+}
+
+impl Observation {
+    pub fn has_vitals(&self) -> bool {
+        self.temperature.is_some()
+            || self.pulse.is_some()
+            || self.blood_pressure.is_some()
+            || self.respiratory_rate.is_some()
+            || self.spo2.is_some()
+    }
+}
+
+/// Follow-up of a chronic condition such as diabetes or hypertension: the
+/// patient is reviewed every `review_interval_days` by the assigned doctor
+/// or nurse, and is overdue once `next_due` has passed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CarePlan {
+    pub id: i64,
+    pub patient_id: i64,
+    pub condition: String,
+    pub review_interval_days: i64,
+    pub doctor_id: Option<i64>,
+    pub nurse_id: Option<i64>,
+    pub next_due: String,
+    pub last_reviewed: Option<String>,
+}
+
+/// A pregnancy followed by the maternity clinic. It stays open until the
+/// birth is registered, which links it through `birth_id`, or it is closed
+/// by hand on `closed_on` (a loss or a transfer of care).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pregnancy {
+    pub id: i64,
+    pub patient_id: i64,
+    /// Estimated date of delivery, `YYYY-MM-DD`.
+    pub edd: String,
+    pub gravida: i64,
+    pub para: i64,
+    pub risk_factors: Option<String>,
+    pub birth_id: Option<i64>,
+    pub closed_on: Option<String>,
+}
+
+impl Pregnancy {
+    pub fn is_open(&self) -> bool {
+        self.birth_id.is_none() && self.closed_on.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AntenatalVisit {
+    pub id: i64,
+    pub pregnancy_id: i64,
+    pub visit_date: String,
+    pub blood_pressure: Option<String>,
+    pub weight_kg: Option<f64>,
+    pub fundal_height_cm: Option<i64>,
+    pub fetal_heart_rate: Option<i64>,
+    pub staff_id: Option<i64>,
+    pub notes: Option<String>,
+}
+
+/// A vaccine dose given to a patient, with the medical record written for
+/// it. Doses given in a campaign such as a flu-shot drive carry its name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Immunization {
+    pub id: i64,
+    pub patient_id: i64,
+    pub medical_record_id: Option<i64>,
+    pub vaccine: String,
+    /// Which dose of the course, as written on the card, e.g. `2` or `Booster`.
+    pub dose: Option<String>,
+    pub lot_number: Option<String>,
+    pub given_on: String,
+    pub given_by: Option<i64>,
+    pub campaign: Option<String>,
+}
+
+/// A prescribing warning the prescriber chose to save through, kept with
+/// the record for audit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrescribingOverride {
+    pub id: i64,
+    pub medical_record_id: i64,
+    pub severity: String,
+    pub warning: String,
+    pub reason: Option<String>,
+    pub overridden_by: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosisCase {
+    pub diagnosis: String,
+    pub date_of_birth: String,
+    pub gender: Gender,
+    pub recorded_at: Option<String>,
+}
+
+/// A subject access request or an erasure, kept as the audit trail of what
+/// was handed out or anonymized, when and by whom.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataRequest {
+    pub id: i64,
+    pub patient_id: i64,
+    /// "Access" or "Erasure".
+    pub kind: String,
+    pub reason: Option<String>,
+    pub handled_by: Option<String>,
+    /// The file an access request was written to, or what an erasure changed.
+    pub details: Option<String>,
+    pub created_at: String,
+}
+
+/// Something the hospital keeps in stock, with what the ledger says is on
+/// hand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockItem {
+    pub id: i64,
+    /// The code on the shelf label, as scanned.
+    pub code: String,
+    pub name: String,
+    pub unit: String,
+    pub on_hand: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum InventoryKind {
+    Receipt,
+    Issue,
+    Adjustment,
+}
+
+/// One movement in the stock ledger. Receipts are positive, issues
+/// negative, and stocktake adjustments either.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryTransaction {
+    pub id: i64,
+    pub item_id: i64,
+    pub kind: InventoryKind,
+    pub quantity: i64,
+    pub reference: Option<String>,
+    pub posted_by: String,
+    pub posted_at: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum StocktakeStatus {
+    Counting,
+    Posted,
+    Abandoned,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stocktake {
+    pub id: i64,
+    pub status: StocktakeStatus,
+    pub started_by: String,
+    pub started_at: String,
+    pub posted_by: Option<String>,
+    pub posted_at: Option<String>,
+}
+
+/// An item in a stocktake: what was on hand when the count started, and
+/// what has been counted so far, if anything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StocktakeLine {
+    pub item_id: i64,
+    pub code: String,
+    pub name: String,
+    pub unit: String,
+    pub expected: i64,
+    pub counted: Option<i64>,
+}
+
+impl StocktakeLine {
+    /// Counted less expected, once the item has been counted.
+    pub fn variance(&self) -> Option<i64> {
+        self.counted.map(|counted| counted - self.expected)
+    }
+}
+
+/// A visit (medical record) or a hospital stay, with the patient known
+/// only by their research pseudonym.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResearchEncounter {
+    pub pseudonym: String,
+    /// "Visit" or "Admission".
+    pub kind: String,
+    pub date_of_birth: String,
+    pub gender: String,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub diagnosis: Option<String>,
+    pub ward: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DashboardMetrics {
+    pub tokens_today: i64,
+    pub teleconsults_today: i64,
+    /// In-person appointments today that were not cancelled or missed.
+    pub appointments_today: i64,
+    /// Patients admitted and not yet discharged.
+    pub admitted_count: i64,
+    pub patient_count: i64,
+    /// Names of the patients the signed-in user pinned.
+    pub pinned_patients: Vec<String>,
+    pub staff_on_shift: Vec<String>,
+    /// Everything invoiced, less held invoices, and everything paid.
+    pub invoiced_total: f64,
+    pub paid_total: f64,
+    pub queue_waiting: i64,
+    pub pending_handovers: i64,
+    pub urgent_waitlist: i64,
+    /// Low ratings nobody has followed up yet.
+    pub feedback_to_follow_up: i64,
 }
@@ -0,0 +1,378 @@
+//! Messages to patients: consultation reminders, notice of a consultation
+//! moved to another time or doctor, and word that an imaging report is
+//! final. Each patient's contact preference picks SMS, email,
+//! both or neither; the gateways are set under `[notify]`:
+//!
+//! ```toml
+//! [notify]
+//! smtp_url = "smtps://smtp.example.org:465"
+//! smtp_user = "clinic@example.org"
+//! smtp_password = "..."
+//! from = "clinic@example.org"
+//! sms_url = "https://sms.example.org/send"
+//! sms_token = "..."
+//! ```
+//!
+//! or `command = "/usr/local/bin/send-message"` to hand every message to a
+//! site script instead. Email and SMS go through `curl`, like webhooks, and
+//! everything is sent on its own thread; failures are reported as toasts.
+
+use crate::components::toast::{self, Toast};
+use crate::db::{self, Db};
+use crate::models::{ContactPreference, ImagingStudy, Patient, Teleconsultation};
+use crate::utils::config::{self, NotifyConfig};
+use crate::utils::datetime;
+use crate::utils::{post_json, CurlConfig};
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::json;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often consultations are checked for reminders that are due.
+const REMINDER_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+static SENDING_REMINDERS: AtomicBool = AtomicBool::new(false);
+static LAST_CHECK: Mutex<Option<Instant>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Channel {
+    Email,
+    Sms,
+}
+
+impl Channel {
+    /// The name passed to `command`.
+    fn name(self) -> &'static str {
+        match self {
+            Channel::Email => "email",
+            Channel::Sms => "sms",
+        }
+    }
+}
+
+struct Message {
+    subject: String,
+    body: String,
+}
+
+/// Whether any gateway is configured at all.
+fn configured(settings: &NotifyConfig) -> bool {
+    settings.command.is_some() || settings.smtp_url.is_some() || settings.sms_url.is_some()
+}
+
+/// Called on every UI tick; at most every [`REMINDER_INTERVAL`] sends the
+/// reminders of consultations starting within `reminder_hours`.
+pub fn tick(db: &Db) {
+    let settings = config::config().notify.clone();
+    if !configured(&settings) || settings.reminder_hours == 0 || db.is_read_only() {
+        return;
+    }
+    let mut last_check = LAST_CHECK.lock().unwrap_or_else(|e| e.into_inner());
+    if last_check.is_some_and(|at| at.elapsed() < REMINDER_INTERVAL) {
+        return;
+    }
+    *last_check = Some(Instant::now());
+    drop(last_check);
+
+    if SENDING_REMINDERS.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let db = db.clone();
+    let spawned = thread::Builder::new()
+        .name("rustoria-reminders".to_string())
+        .spawn(move || {
+            let result = send_reminders(&db, &settings);
+            SENDING_REMINDERS.store(false, Ordering::SeqCst);
+            if let Err(e) = result {
+                report("Reminders not sent", e);
+            }
+        });
+    if let Err(e) = spawned {
+        SENDING_REMINDERS.store(false, Ordering::SeqCst);
+        report("Reminders not sent", e.into());
+    }
+}
+
+/// Tells the patient of `study` that its report is final.
+pub fn result_ready(db: &Db, study: &ImagingStudy) {
+    let settings = config::config().notify.clone();
+    if !configured(&settings) {
+        return;
+    }
+    let study = study.clone();
+    let db = db.clone();
+    let spawned = thread::Builder::new()
+        .name("rustoria-notify".to_string())
+        .spawn(move || {
+            let result = db::get_patient(&db, study.patient_id).and_then(|patient| {
+                let message = result_message(&patient, &study);
+                send(&settings, &patient, &message)
+            });
+            if let Err(e) = result {
+                report(
+                    &format!(
+                        "Result notice for study {} not sent",
+                        study.accession_number
+                    ),
+                    e,
+                );
+            }
+        });
+    if let Err(e) = spawned {
+        report("Result notice not sent", e.into());
+    }
+}
+
+/// Tells the patients of consultations moved in bulk, each given as
+/// (before, after), one after another on a single thread.
+pub fn rescheduled(db: &Db, moves: Vec<(Teleconsultation, Teleconsultation)>) {
+    let settings = config::config().notify.clone();
+    if !configured(&settings) || moves.is_empty() {
+        return;
+    }
+    let db = db.clone();
+    let spawned = thread::Builder::new()
+        .name("rustoria-notify".to_string())
+        .spawn(move || {
+            for (before, after) in &moves {
+                let result = db::get_patient(&db, after.patient_id).and_then(|patient| {
+                    let message = rescheduled_message(&db, &patient, before, after);
+                    send(&settings, &patient, &message)
+                });
+                if let Err(e) = result {
+                    report(
+                        &format!("Notice of moved consultation {} not sent", after.id),
+                        e,
+                    );
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        report("Notices of moved consultations not sent", e.into());
+    }
+}
+
+fn report(what: &str, error: anyhow::Error) {
+    toast::push(Toast::error(format!("{}: {:#}", what, error)));
+}
+
+/// Sends every due reminder. One that cannot be delivered is retried on the
+/// next check, unless the patient has no address for their chosen channel.
+fn send_reminders(db: &Db, settings: &NotifyConfig) -> Result<()> {
+    for consultation in db::get_consultations_due_reminder(db, settings.reminder_hours)? {
+        let patient = db::get_patient(db, consultation.patient_id)?;
+        let message = reminder_message(db, &patient, &consultation);
+        match recipients(&patient) {
+            Ok(recipients) => {
+                let sent = recipients.iter().try_for_each(|(channel, address)| {
+                    deliver(settings, *channel, address, &message)
+                });
+                if let Err(e) = sent {
+                    report(
+                        &format!("Reminder for consultation {} not sent", consultation.id),
+                        e,
+                    );
+                    continue;
+                }
+            }
+            Err(e) => report(
+                &format!("Reminder for consultation {} skipped", consultation.id),
+                e,
+            ),
+        }
+        db::mark_reminder_sent(db, consultation.id)?;
+    }
+    Ok(())
+}
+
+fn reminder_message(db: &Db, patient: &Patient, consultation: &Teleconsultation) -> Message {
+    let doctor = db::get_staff(db, consultation.doctor_id)
+        .map(|doctor| format!(" with {}", doctor.name))
+        .unwrap_or_default();
+    Message {
+        subject: "Appointment reminder".to_string(),
+        body: format!(
+            "Dear {}, this is a reminder of your consultation{} on {}. Contact: {}.",
+            patient.first_name,
+            doctor,
+            datetime::facility_stamp(&consultation.scheduled_at),
+            consultation.contact
+        ),
+    }
+}
+
+fn rescheduled_message(
+    db: &Db,
+    patient: &Patient,
+    before: &Teleconsultation,
+    after: &Teleconsultation,
+) -> Message {
+    let doctor = db::get_staff(db, after.doctor_id)
+        .map(|doctor| format!(" with {}", doctor.name))
+        .unwrap_or_default();
+    Message {
+        subject: "Appointment rescheduled".to_string(),
+        body: format!(
+            "Dear {}, your doctor is unavailable on {}, so your consultation has been moved to {}{}. Contact: {}. Please call the clinic if the new time does not suit you.",
+            patient.first_name,
+            datetime::facility_stamp(&before.scheduled_at),
+            datetime::facility_stamp(&after.scheduled_at),
+            doctor,
+            after.contact
+        ),
+    }
+}
+
+fn result_message(patient: &Patient, study: &ImagingStudy) -> Message {
+    Message {
+        subject: "Your result is ready".to_string(),
+        body: format!(
+            "Dear {}, the report of your {} study of {} (accession {}) is ready. Please contact the clinic to discuss it.",
+            patient.first_name, study.modality, study.study_date, study.accession_number
+        ),
+    }
+}
+
+/// The channels and addresses the patient's contact preference asks for.
+fn recipients(patient: &Patient) -> Result<Vec<(Channel, String)>> {
+    let channels: &[Channel] = match patient.contact_preference {
+        ContactPreference::Sms => &[Channel::Sms],
+        ContactPreference::Email => &[Channel::Email],
+        ContactPreference::Both => &[Channel::Sms, Channel::Email],
+        ContactPreference::None => &[],
+    };
+    channels
+        .iter()
+        .map(|channel| {
+            let address = match channel {
+                Channel::Email => patient.email.clone(),
+                Channel::Sms => Some(patient.phone_number.clone()),
+            };
+            address
+                .filter(|address| !address.trim().is_empty())
+                .map(|address| (*channel, address))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "{} {} has no {} address",
+                        patient.first_name,
+                        patient.last_name,
+                        channel.name()
+                    )
+                })
+        })
+        .collect()
+}
+
+fn send(settings: &NotifyConfig, patient: &Patient, message: &Message) -> Result<()> {
+    for (channel, address) in recipients(patient)? {
+        deliver(settings, channel, &address, message)?;
+    }
+    Ok(())
+}
+
+fn deliver(
+    settings: &NotifyConfig,
+    channel: Channel,
+    address: &str,
+    message: &Message,
+) -> Result<()> {
+    if let Some(command) = &settings.command {
+        return run_command(command, channel, address, message);
+    }
+    match channel {
+        Channel::Email => {
+            let url = settings
+                .smtp_url
+                .as_deref()
+                .ok_or_else(|| anyhow!("No smtp_url is configured for email"))?;
+            send_email(settings, url, address, message)
+        }
+        Channel::Sms => {
+            let url = settings
+                .sms_url
+                .as_deref()
+                .ok_or_else(|| anyhow!("No sms_url is configured for text messages"))?;
+            let authorization = settings
+                .sms_token
+                .as_ref()
+                .map(|token| format!("Bearer {}", token));
+            let headers: Vec<(&str, &str)> = authorization
+                .iter()
+                .map(|value| ("Authorization", value.as_str()))
+                .collect();
+            let body = json!({ "to": address, "body": message.body }).to_string();
+            post_json(url, &body, &headers)
+        }
+    }
+}
+
+fn run_command(command: &str, channel: Channel, address: &str, message: &Message) -> Result<()> {
+    let mut child = Command::new(command)
+        .args([channel.name(), address, &message.subject])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", command))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(message.body.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "{} failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Sends an email with `curl`'s SMTP support, which also handles TLS. The
+/// login goes through a [`CurlConfig`], not the command line.
+fn send_email(settings: &NotifyConfig, url: &str, to: &str, message: &Message) -> Result<()> {
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--max-time", "30", "--url", url])
+        .args(["--mail-from", &settings.from, "--mail-rcpt", to]);
+    let login = match &settings.smtp_user {
+        Some(user) => {
+            let password = settings.smtp_password.as_deref().unwrap_or_default();
+            Some(CurlConfig::new(&[(
+                "user",
+                format!("{}:{}", user, password),
+            )])?)
+        }
+        None => None,
+    };
+    if let Some(config) = &login {
+        command.arg("--config").arg(config.path());
+    }
+    let mut child = command
+        .args(["--upload-file", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl")?;
+    let mail = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+        settings.from, to, message.subject, message.body
+    );
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(mail.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "Email to {} failed: {}",
+            to,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
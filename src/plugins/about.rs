@@ -0,0 +1,131 @@
+//! Built-in example plugin: where this installation keeps its data and which
+//! plugins it runs, for support calls.
+
+use super::{plugins, Plugin};
+use crate::app::SelectedApp;
+use crate::components::Component;
+use crate::db::{self, Db};
+use crate::i18n::t;
+use crate::keymap::{bind, KeyBinding};
+use crate::theme::theme;
+use crate::tui::Frame;
+use crate::utils::config;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+const BINDINGS: &[KeyBinding] = &[bind("Esc / b", "help.key.back")];
+
+pub fn register() {
+    super::register(Plugin {
+        name: "plugin.about",
+        description: "Version, data locations and installed plugins",
+        bindings: BINDINGS,
+        text_entry: false,
+        create: |db| Box::new(About::new(db)),
+    });
+}
+
+struct About {
+    db: Db,
+    instance: String,
+}
+
+impl About {
+    fn new(db: &Db) -> Self {
+        Self {
+            db: db.clone(),
+            instance: db::get_setting(db, "instance_id")
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| "-".to_string()),
+        }
+    }
+}
+
+impl Component for About {
+    fn handle_input(&mut self, event: KeyEvent) -> Result<Option<SelectedApp>> {
+        Ok(match event.code {
+            KeyCode::Esc | KeyCode::Char('b') | KeyCode::Char('B') => Some(SelectedApp::None),
+            _ => None,
+        })
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        frame.render_widget(
+            Block::default().style(Style::default().bg(theme().background)),
+            area,
+        );
+
+        let field = |label: &'static str, value: String| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<16}", t(label)),
+                    Style::default().fg(theme().accent),
+                ),
+                Span::styled(value, Style::default().fg(theme().text)),
+            ])
+        };
+        let mut lines = vec![
+            field(
+                "plugin.about.version",
+                env!("CARGO_PKG_VERSION").to_string(),
+            ),
+            field("plugin.about.config", config::path().display().to_string()),
+            field(
+                "plugin.about.database",
+                self.db.path().display().to_string(),
+            ),
+            field("plugin.about.instance", self.instance.clone()),
+            Line::default(),
+            Line::styled(
+                t("plugin.about.plugins"),
+                Style::default()
+                    .fg(theme().title)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ];
+        lines.extend(plugins().into_iter().map(|plugin| {
+            Line::from(vec![
+                Span::styled(
+                    format!("  • {}", t(plugin.name)),
+                    Style::default().fg(theme().focus),
+                ),
+                Span::styled(
+                    format!(" — {}", plugin.description),
+                    Style::default().fg(theme().text),
+                ),
+            ])
+        }));
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(1)])
+            .margin(1)
+            .split(area);
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(format!(" ℹ️ {} ", t("plugin.about")))
+                    .title_style(
+                        Style::default()
+                            .fg(theme().title)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .border_style(Style::default().fg(theme().border))
+                    .style(Style::default().bg(theme().surface))
+                    .padding(Padding::horizontal(1)),
+            ),
+            layout[0],
+        );
+        frame.render_widget(
+            Paragraph::new(t("plugin.about.help"))
+                .style(Style::default().fg(theme().help))
+                .alignment(Alignment::Center),
+            layout[1],
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! Site-specific screens that plug into the home menu, the command palette
+//! and the help overlay without changes to `App` or `HospitalApp`.
+//!
+//! A plugin is any [`Component`] plus the metadata describing it. To add one,
+//! put its module under `src/plugins/` and register it in [`register_builtin`].
+//! Like the built-in screens, it returns `Some(SelectedApp::None)` from
+//! `handle_input` to go back home, or another `SelectedApp` to open that screen.
+
+use crate::components::Component;
+use crate::db::Db;
+use crate::keymap::KeyBinding;
+use std::sync::RwLock;
+
+mod about;
+
+#[derive(Clone, Copy)]
+pub struct Plugin {
+    /// Label in the home menu and command palette; a translation key works too.
+    pub name: &'static str,
+    /// One-line summary, listed on the About screen.
+    pub description: &'static str,
+    /// Keys listed in the help overlay while the screen is open, each action
+    /// a translation key.
+    pub bindings: &'static [KeyBinding],
+    /// Whether the screen takes typed text, so `?` must not open help.
+    pub text_entry: bool,
+    /// Builds a fresh screen on the database each time it is opened.
+    pub create: fn(&Db) -> Box<dyn Component>,
+}
+
+static REGISTRY: RwLock<Vec<Plugin>> = RwLock::new(Vec::new());
+
+/// Adds `plugin` to the menu. Must run before the home screen is built.
+pub fn register(plugin: Plugin) {
+    REGISTRY
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(plugin);
+}
+
+/// Registered plugins, in menu order; a plugin's position is its id.
+pub fn plugins() -> Vec<Plugin> {
+    REGISTRY.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn get(id: usize) -> Option<Plugin> {
+    REGISTRY
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(id)
+        .copied()
+}
+
+/// Registers the plugins compiled into this build.
+pub fn register_builtin() {
+    about::register();
+}
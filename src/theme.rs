@@ -0,0 +1,168 @@
+use crate::db::Db;
+use ratatui::style::Color;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    pub background: Color,
+    pub surface: Color,
+    pub input: Color,
+    pub dialog: Color,
+    pub highlight: Color,
+    pub border: Color,
+    pub header: Color,
+    pub unfocused: Color,
+    pub focus: Color,
+    pub text: Color,
+    pub title: Color,
+    pub inactive: Color,
+    pub help: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub accent: Color,
+}
+
+pub const DARK: Theme = Theme {
+    name: "dark",
+    background: Color::Rgb(16, 16, 28),
+    surface: Color::Rgb(22, 22, 35),
+    input: Color::Rgb(26, 26, 36),
+    dialog: Color::Rgb(30, 30, 46),
+    highlight: Color::Rgb(40, 40, 60),
+    border: Color::Rgb(75, 75, 120),
+    header: Color::Rgb(80, 60, 130),
+    unfocused: Color::Rgb(140, 140, 200),
+    focus: Color::Rgb(250, 250, 110),
+    text: Color::Rgb(220, 220, 240),
+    title: Color::Rgb(230, 230, 250),
+    inactive: Color::Rgb(180, 180, 200),
+    help: Color::Rgb(140, 140, 170),
+    success: Color::Rgb(140, 219, 140),
+    warning: Color::Rgb(230, 180, 80),
+    error: Color::Rgb(255, 100, 100),
+    accent: Color::Rgb(129, 199, 245),
+};
+
+pub const LIGHT: Theme = Theme {
+    name: "light",
+    background: Color::Rgb(245, 245, 250),
+    surface: Color::Rgb(235, 235, 245),
+    input: Color::Rgb(255, 255, 255),
+    dialog: Color::Rgb(228, 228, 240),
+    highlight: Color::Rgb(210, 210, 235),
+    border: Color::Rgb(150, 150, 190),
+    header: Color::Rgb(190, 180, 230),
+    unfocused: Color::Rgb(110, 110, 160),
+    focus: Color::Rgb(180, 110, 0),
+    text: Color::Rgb(30, 30, 45),
+    title: Color::Rgb(20, 20, 60),
+    inactive: Color::Rgb(100, 100, 120),
+    help: Color::Rgb(110, 110, 130),
+    success: Color::Rgb(30, 130, 60),
+    warning: Color::Rgb(190, 110, 20),
+    error: Color::Rgb(200, 40, 40),
+    accent: Color::Rgb(20, 110, 190),
+};
+
+pub const HIGH_CONTRAST: Theme = Theme {
+    name: "high-contrast",
+    background: Color::Rgb(0, 0, 0),
+    surface: Color::Rgb(0, 0, 0),
+    input: Color::Rgb(0, 0, 0),
+    dialog: Color::Rgb(0, 0, 0),
+    highlight: Color::Rgb(70, 70, 70),
+    border: Color::Rgb(255, 255, 255),
+    header: Color::Rgb(0, 0, 170),
+    unfocused: Color::Rgb(200, 200, 200),
+    focus: Color::Rgb(255, 255, 0),
+    text: Color::Rgb(255, 255, 255),
+    title: Color::Rgb(255, 255, 255),
+    inactive: Color::Rgb(200, 200, 200),
+    help: Color::Rgb(230, 230, 230),
+    success: Color::Rgb(0, 255, 0),
+    warning: Color::Rgb(255, 170, 0),
+    error: Color::Rgb(255, 60, 60),
+    accent: Color::Rgb(0, 255, 255),
+};
+
+pub const SOLARIZED: Theme = Theme {
+    name: "solarized",
+    background: Color::Rgb(0, 43, 54),
+    surface: Color::Rgb(7, 54, 66),
+    input: Color::Rgb(7, 54, 66),
+    dialog: Color::Rgb(7, 54, 66),
+    highlight: Color::Rgb(88, 110, 117),
+    border: Color::Rgb(88, 110, 117),
+    header: Color::Rgb(108, 113, 196),
+    unfocused: Color::Rgb(131, 148, 150),
+    focus: Color::Rgb(181, 137, 0),
+    text: Color::Rgb(147, 161, 161),
+    title: Color::Rgb(238, 232, 213),
+    inactive: Color::Rgb(101, 123, 131),
+    help: Color::Rgb(101, 123, 131),
+    success: Color::Rgb(133, 153, 0),
+    warning: Color::Rgb(203, 75, 22),
+    error: Color::Rgb(220, 50, 47),
+    accent: Color::Rgb(38, 139, 210),
+};
+
+/// 16-colour palette used by accessibility mode regardless of the chosen
+/// theme, so it renders the same on terminals without true colour.
+pub const ACCESSIBLE: Theme = Theme {
+    name: "accessible",
+    background: Color::Black,
+    surface: Color::Black,
+    input: Color::Black,
+    dialog: Color::Black,
+    highlight: Color::Blue,
+    border: Color::White,
+    header: Color::Blue,
+    unfocused: Color::Gray,
+    focus: Color::LightYellow,
+    text: Color::White,
+    title: Color::White,
+    inactive: Color::Gray,
+    help: Color::White,
+    success: Color::LightGreen,
+    warning: Color::Yellow,
+    error: Color::LightRed,
+    accent: Color::LightCyan,
+};
+
+pub const THEMES: [Theme; 4] = [DARK, LIGHT, HIGH_CONTRAST, SOLARIZED];
+
+static CURRENT: RwLock<Theme> = RwLock::new(DARK);
+
+pub fn theme() -> Theme {
+    if crate::accessibility::enabled() {
+        return ACCESSIBLE;
+    }
+    selected_theme()
+}
+
+/// The user's chosen theme, even while accessibility mode overrides it.
+pub fn selected_theme() -> Theme {
+    *CURRENT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn find_theme(name: &str) -> Option<Theme> {
+    THEMES.iter().copied().find(|t| t.name == name)
+}
+
+pub fn set_theme(theme: Theme) {
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = theme;
+}
+
+/// Applies the theme saved in settings, otherwise the configured default.
+pub fn load(db: &Db) {
+    if let Some(saved) = crate::db::get_setting(db, "theme")
+        .ok()
+        .flatten()
+        .or_else(|| crate::utils::config::config().theme.clone())
+        .and_then(|name| find_theme(&name))
+    {
+        set_theme(saved);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::accessibility;
 use anyhow::Result;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture},
@@ -5,7 +6,10 @@ use crossterm::{
     ExecutableCommand,
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::{io, time::Duration};
+use std::{
+    io,
+    time::{Duration, Instant},
+};
 
 #[derive(Debug, Clone)]
 pub enum Event {
@@ -15,9 +19,23 @@ pub enum Event {
 
 pub type Frame<'a> = ratatui::Frame<'a>;
 
+/// Puts the terminal back to normal without a [`Tui`] handle, for cleanup
+/// paths such as drop guards and the panic hook. Errors are ignored because
+/// there is nothing left to report them to.
+pub fn restore() {
+    let _ = terminal::disable_raw_mode();
+    let _ = crossterm::execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        crossterm::cursor::Show
+    );
+}
+
 pub struct Tui {
     terminal: Terminal<CrosstermBackend<io::Stdout>>,
     framerate: f64,
+    last_tick: Instant,
 }
 
 impl Tui {
@@ -25,6 +43,7 @@ impl Tui {
         Self {
             terminal,
             framerate: 30.0,
+            last_tick: Instant::now(),
         }
     }
 
@@ -56,17 +75,26 @@ impl Tui {
     }
 
     pub fn draw(&mut self, render: impl FnOnce(&mut Frame)) -> Result<()> {
-        self.terminal.draw(render)?;
+        self.terminal.draw(|frame| {
+            render(frame);
+            if accessibility::enabled() {
+                accessibility::simplify(frame.buffer_mut());
+            }
+        })?;
         Ok(())
     }
 
-    pub fn next_event(&self) -> Result<Event> {
-        let timeout = Duration::from_secs_f64(1.0 / self.framerate);
+    /// Waits for input until the next tick is due. Ticks are emitted on schedule
+    /// even while keys keep arriving, so timers never starve behind input.
+    pub fn next_event(&mut self) -> Result<Event> {
+        let tick_rate = Duration::from_secs_f64(1.0 / self.framerate);
+        let timeout = tick_rate.saturating_sub(self.last_tick.elapsed());
 
-        if event::poll(timeout)? {
+        if !timeout.is_zero() && event::poll(timeout)? {
             return Ok(Event::Input(event::read()?));
         }
 
+        self.last_tick = Instant::now();
         Ok(Event::Tick)
     }
 }
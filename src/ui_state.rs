@@ -0,0 +1,316 @@
+//! Per-user UI state that survives restarts: the last screen visited and, for
+//! each list screen, its search text, filters and position in the table. Kept
+//! in a small JSON file next to the database rather than in the database so a
+//! read-only or shared database still remembers each terminal's layout.
+//! It also keeps the patients, records and invoices each user opened last,
+//! the searches each user saved by name on a list screen and the columns
+//! each user chose to show in each list.
+
+use crate::app::SelectedApp;
+use crate::models::Patient;
+use anyhow::Result;
+use ratatui::widgets::TableState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::Mutex;
+
+const STATE_FILE: &str = "ui_state.json";
+/// Recently opened items kept per user; one digit key each.
+const RECENT_LIMIT: usize = 9;
+/// Saved searches kept per user and screen; one digit key each.
+pub const SAVED_SEARCH_LIMIT: usize = 9;
+
+/// Remembered state of one list screen.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewState {
+    pub search: String,
+    pub filters: BTreeMap<String, String>,
+    pub selected: Option<usize>,
+    pub offset: usize,
+}
+
+impl ViewState {
+    /// Captures the selection and scroll position of `table`.
+    pub fn with_table(mut self, table: &TableState) -> Self {
+        self.selected = table.selected();
+        self.offset = table.offset();
+        self
+    }
+
+    pub fn with_filter(mut self, name: &str, value: Option<impl ToString>) -> Self {
+        if let Some(value) = value {
+            self.filters.insert(name.to_string(), value.to_string());
+        }
+        self
+    }
+
+    pub fn filter(&self, name: &str) -> Option<&str> {
+        self.filters.get(name).map(String::as_str)
+    }
+
+    /// A table positioned where it was left; callers clamp the selection once
+    /// their rows are loaded.
+    pub fn table(&self) -> TableState {
+        TableState::default()
+            .with_offset(self.offset)
+            .with_selected(self.selected)
+    }
+}
+
+/// A search and its filters saved under a name, such as "pediatric
+/// patients", to be applied again in one keystroke.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub search: String,
+    #[serde(default)]
+    pub filters: BTreeMap<String, String>,
+}
+
+impl SavedSearch {
+    /// The view to apply; the table starts at the top.
+    pub fn view(&self) -> ViewState {
+        ViewState {
+            search: self.search.clone(),
+            filters: self.filters.clone(),
+            ..ViewState::default()
+        }
+    }
+
+    /// The search and filters in brief, for listing the saved searches.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.search.is_empty() {
+            parts.push(format!("\"{}\"", self.search));
+        }
+        parts.extend(
+            self.filters
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, value)),
+        );
+        if parts.is_empty() {
+            "everything".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecentKind {
+    Patient,
+    Record,
+    /// A patient's invoices, which are viewed together.
+    Invoices,
+}
+
+/// Something the user opened, for getting back to it in one keystroke.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recent {
+    pub kind: RecentKind,
+    /// The patient, record or (for invoices) patient id.
+    pub id: i64,
+    pub patient_id: i64,
+    pub label: String,
+}
+
+impl Recent {
+    pub fn patient(patient: &Patient) -> Self {
+        Self {
+            kind: RecentKind::Patient,
+            id: patient.id,
+            patient_id: patient.id,
+            label: format!("{} {}", patient.first_name, patient.last_name),
+        }
+    }
+
+    /// Medical record `record_id` of `patient_id`, named after the patient
+    /// when known.
+    pub fn record(record_id: i64, patient_id: i64, patient: Option<&Patient>) -> Self {
+        let label = match patient {
+            Some(p) => format!("Record #{} ({} {})", record_id, p.first_name, p.last_name),
+            None => format!("Record #{}", record_id),
+        };
+        Self {
+            kind: RecentKind::Record,
+            id: record_id,
+            patient_id,
+            label,
+        }
+    }
+
+    pub fn invoices(patient_id: i64, patient: Option<&Patient>) -> Self {
+        let label = match patient {
+            Some(p) => format!("Invoices ({} {})", p.first_name, p.last_name),
+            None => format!("Invoices (patient {})", patient_id),
+        };
+        Self {
+            kind: RecentKind::Invoices,
+            id: patient_id,
+            patient_id,
+            label,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct UserState {
+    last_app: Option<SelectedApp>,
+    views: BTreeMap<String, ViewState>,
+    recent: Vec<Recent>,
+    /// Saved searches by screen, in the order they were saved.
+    saved_searches: BTreeMap<String, Vec<SavedSearch>>,
+    /// The columns shown by list, named by their headers, once changed from
+    /// the list's defaults.
+    columns: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Default)]
+struct Store {
+    user: Option<String>,
+    users: BTreeMap<String, UserState>,
+}
+
+static STORE: Mutex<Option<Store>> = Mutex::new(None);
+
+fn with_user<T>(f: impl FnOnce(&mut UserState) -> T) -> Option<T> {
+    let mut store = STORE.lock().unwrap_or_else(|e| e.into_inner());
+    let store = store.as_mut()?;
+    let user = store.user.clone()?;
+    Some(f(store.users.entry(user).or_default()))
+}
+
+/// Reads the state file and makes `username` the user whose state is read and
+/// written. A missing or unreadable file starts everyone afresh.
+pub fn load(username: &str) {
+    let users = fs::read_to_string(STATE_FILE)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    *STORE.lock().unwrap_or_else(|e| e.into_inner()) = Some(Store {
+        user: Some(username.to_string()),
+        users,
+    });
+}
+
+/// Writes everyone's state back to the state file.
+pub fn save() -> Result<()> {
+    let store = STORE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(store) = store.as_ref() {
+        fs::write(STATE_FILE, serde_json::to_string_pretty(&store.users)?)?;
+    }
+    Ok(())
+}
+
+/// The logged-in user, as passed to [`load`].
+pub fn current_user() -> Option<String> {
+    let store = STORE.lock().unwrap_or_else(|e| e.into_inner());
+    store.as_ref()?.user.clone()
+}
+
+pub fn last_app() -> Option<SelectedApp> {
+    with_user(|user| user.last_app).flatten()
+}
+
+/// Records the screen to reopen at next login; `None` means the home menu.
+pub fn set_last_app(app: Option<SelectedApp>) {
+    with_user(|user| user.last_app = app);
+}
+
+/// The saved state for `screen`, or an empty one when nothing was saved.
+pub fn view(screen: &str) -> ViewState {
+    with_user(|user| user.views.get(screen).cloned())
+        .flatten()
+        .unwrap_or_default()
+}
+
+pub fn set_view(screen: &str, view: ViewState) {
+    with_user(|user| user.views.insert(screen.to_string(), view));
+}
+
+/// Puts `item` first in the user's recently opened items.
+pub fn add_recent(item: Recent) {
+    with_user(|user| {
+        user.recent
+            .retain(|r| !(r.kind == item.kind && r.id == item.id));
+        user.recent.insert(0, item);
+        user.recent.truncate(RECENT_LIMIT);
+    });
+}
+
+/// The user's recently opened items, most recent first.
+pub fn recent() -> Vec<Recent> {
+    with_user(|user| user.recent.clone()).unwrap_or_default()
+}
+
+/// The patients behind the recently opened items, most recent first.
+pub fn recent_patients() -> Vec<i64> {
+    let mut patients: Vec<i64> = Vec::new();
+    for item in recent() {
+        if !patients.contains(&item.patient_id) {
+            patients.push(item.patient_id);
+        }
+    }
+    patients
+}
+
+/// The user's saved searches on `screen`.
+pub fn saved_searches(screen: &str) -> Vec<SavedSearch> {
+    with_user(|user| user.saved_searches.get(screen).cloned())
+        .flatten()
+        .unwrap_or_default()
+}
+
+/// Saves the search and filters of `view` on `screen` as `name`, replacing a
+/// saved search of the same name. Fails when the screen already has
+/// [`SAVED_SEARCH_LIMIT`] others.
+pub fn save_search(screen: &str, name: &str, view: &ViewState) -> Result<()> {
+    let saved = SavedSearch {
+        name: name.to_string(),
+        search: view.search.clone(),
+        filters: view.filters.clone(),
+    };
+    with_user(|user| {
+        let searches = user.saved_searches.entry(screen.to_string()).or_default();
+        match searches
+            .iter()
+            .position(|s| s.name.eq_ignore_ascii_case(name))
+        {
+            Some(index) => searches[index] = saved,
+            None if searches.len() >= SAVED_SEARCH_LIMIT => {
+                anyhow::bail!("Only {} searches can be saved here", SAVED_SEARCH_LIMIT)
+            }
+            None => searches.push(saved),
+        }
+        Ok(())
+    })
+    .unwrap_or_else(|| Err(anyhow::anyhow!("Nobody is logged in")))?;
+    save()
+}
+
+/// Removes the saved search at `index` on `screen`.
+pub fn delete_saved_search(screen: &str, index: usize) -> Result<()> {
+    with_user(|user| {
+        if let Some(searches) = user.saved_searches.get_mut(screen) {
+            if index < searches.len() {
+                searches.remove(index);
+            }
+            if searches.is_empty() {
+                user.saved_searches.remove(screen);
+            }
+        }
+    });
+    save()
+}
+
+/// The columns the user chose to show in `list`, or `None` for its defaults.
+pub fn shown_columns(list: &str) -> Option<Vec<String>> {
+    with_user(|user| user.columns.get(list).cloned()).flatten()
+}
+
+pub fn set_shown_columns(list: &str, columns: Vec<String>) {
+    with_user(|user| user.columns.insert(list.to_string(), columns));
+}
@@ -1,8 +1,328 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::fs;
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+pub mod aftercare;
+pub mod barcode;
+pub mod calendar;
+pub mod config;
+pub mod contact;
+pub mod datetime;
+pub mod invoice_layout;
+pub mod labels;
+pub mod photo;
+pub mod prescribing;
+pub mod print;
+pub mod procedures;
+pub mod sha256;
+
+const EXPORT_DIR: &str = "exports";
+
+/// The value of a `--name <value>` or `--name=<value>` command-line option.
+pub fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next();
+        }
+        if let Some(value) = arg.strip_prefix(name).and_then(|v| v.strip_prefix('=')) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
 
 #[allow(dead_code)]
 pub fn flush_stdout() -> Result<(), io::Error> {
     io::stdout().flush()?;
     Ok(())
 }
+
+pub fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn csv_line(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|f| csv_escape(f))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Splits one CSV line into fields, undoing [`csv_escape`]'s quoting.
+pub fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Splits CSV text into records, each with the line it starts on (from 1).
+/// A line break inside a quoted field, as [`csv_escape`] writes one, stays
+/// in its record. Each record is split into fields by [`parse_csv_line`].
+pub fn csv_records(text: &str) -> Vec<(usize, String)> {
+    let mut records = Vec::new();
+    let mut record = String::new();
+    let mut start = 1;
+    let mut quoted = false;
+    for (index, line) in text.lines().enumerate() {
+        if quoted {
+            record.push('\n');
+        } else {
+            start = index + 1;
+        }
+        record.push_str(line);
+        quoted ^= line.matches('"').count() % 2 == 1;
+        if !quoted {
+            records.push((start, std::mem::take(&mut record)));
+        }
+    }
+    if quoted {
+        records.push((start, record));
+    }
+    records
+}
+
+pub fn write_export(file_name: &str, contents: &str) -> Result<PathBuf> {
+    fs::create_dir_all(EXPORT_DIR).context("Failed to create exports directory")?;
+    let path = PathBuf::from(EXPORT_DIR).join(file_name);
+    fs::write(&path, contents).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+pub fn open_in_external_viewer(path: &Path) -> Result<()> {
+    if !path.exists() {
+        anyhow::bail!("File not found: {}", path.display());
+    }
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    Ok(())
+}
+
+/// Options for `curl` written to a file only the user can read, so secrets
+/// such as passwords and tokens stay out of its command line, which any
+/// user of the machine can see. The file is removed on drop.
+pub struct CurlConfig {
+    path: PathBuf,
+}
+
+impl CurlConfig {
+    /// Writes `options`, like `("user", "name:password")`, one per line.
+    pub fn new(options: &[(&str, String)]) -> Result<Self> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "rustoria-curl-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut open = fs::OpenOptions::new();
+        open.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut open, 0o600);
+        let mut file = open
+            .open(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        let config = Self { path };
+        for (name, value) in options {
+            let value: String = value.chars().filter(|c| *c != '\r' && *c != '\n').collect();
+            writeln!(
+                file,
+                "{} = \"{}\"",
+                name,
+                value.replace('\\', "\\\\").replace('"', "\\\"")
+            )?;
+        }
+        Ok(config)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for CurlConfig {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// POSTs `body` with `curl`, which every supported platform ships, rather
+/// than carrying an HTTP and TLS stack for the occasional webhook. `headers`
+/// are sent besides `Content-Type: application/json`, through a
+/// [`CurlConfig`] as they may carry tokens.
+pub fn post_json(url: &str, body: &str, headers: &[(&str, &str)]) -> Result<()> {
+    let mut options = vec![("header", "Content-Type: application/json".to_string())];
+    options.extend(
+        headers
+            .iter()
+            .map(|(name, value)| ("header", format!("{}: {}", name, value))),
+    );
+    let config = CurlConfig::new(&options)?;
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--fail", "--max-time", "10"])
+        .arg("--config")
+        .arg(config.path());
+    let mut child = command
+        .args(["--data-binary", "@-", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(body.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Webhook to {} failed: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    let candidates: &[(&str, &[&str])] = if cfg!(target_os = "windows") {
+        &[("clip", &[])]
+    } else if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else {
+        &[
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ]
+    };
+    for (program, args) in candidates {
+        let Ok(mut child) = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .with_context(|| format!("Failed to write to {}", program))?;
+        }
+        let status = child
+            .wait()
+            .with_context(|| format!("Failed to run {}", program))?;
+        if status.success() {
+            return Ok(());
+        }
+    }
+    anyhow::bail!("No clipboard tool available (install wl-copy, xclip or xsel)")
+}
+
+pub fn tsv_line(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|f| f.replace(['\t', '\n', '\r'], " "))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+/// One row of a Markdown table, with pipes escaped so they stay in their cell.
+pub fn markdown_line(fields: &[&str]) -> String {
+    let cells: Vec<String> = fields
+        .iter()
+        .map(|f| f.replace(['\n', '\r'], " ").replace('|', "\\|"))
+        .collect();
+    format!("| {} |", cells.join(" | "))
+}
+
+/// Shortens `text` to at most `width` columns, ending with "…" when cut.
+/// Line breaks become spaces so a cell never spills onto a second line.
+pub fn truncate(text: &str, width: usize) -> String {
+    let text = text.replace(['\n', '\r', '\t'], " ");
+    if text.width() <= width {
+        return text;
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > width - 1 {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push('…');
+    out
+}
+
+/// Truncates `text` to `width` columns and pads it with spaces to exactly that width.
+pub fn pad(text: &str, width: usize) -> String {
+    let text = truncate(text, width);
+    let fill = width.saturating_sub(text.width());
+    format!("{}{}", text, " ".repeat(fill))
+}
+
+/// Like [`pad`] but right-aligned, for numbers and amounts.
+pub fn pad_left(text: &str, width: usize) -> String {
+    let text = truncate(text, width);
+    let fill = width.saturating_sub(text.width());
+    format!("{}{}", " ".repeat(fill), text)
+}
+
+/// Formats an amount with the configured currency symbol.
+pub fn money(amount: f64) -> String {
+    format!("{}{:.2}", config::config().currency, amount)
+}
+
+/// Like [`money`] but with the sign ahead of the currency symbol, for
+/// differences that may go either way.
+pub fn signed_money(amount: f64) -> String {
+    if amount < 0.0 {
+        format!("-{}", money(-amount))
+    } else {
+        money(amount)
+    }
+}
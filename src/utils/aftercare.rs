@@ -0,0 +1,165 @@
+//! Aftercare instructions handed to patients on discharge or at the end of a
+//! visit. A few templates are built in; a site adds its own, or replaces a
+//! built-in one of the same name, by placing `aftercare/<name>.txt` files in
+//! the working directory. Templates may use these fields, filled in from the
+//! patient and the visit:
+//!
+//! `{name}`, `{first_name}`, `{mrn}`, `{date_of_birth}`, `{allergies}`,
+//! `{medications}`, `{diagnosis}`, `{prescription}`, `{ward}` and `{date}`.
+
+use super::datetime;
+use crate::models::Patient;
+use std::fs;
+use std::path::Path;
+
+const TEMPLATE_DIR: &str = "aftercare";
+
+const BUILT_IN: [(&str, &str); 4] = [
+    (
+        "General Discharge",
+        "You were treated for: {diagnosis}
+
+Your medicines: {prescription}
+
+- Take your medicines exactly as prescribed and finish any course of antibiotics.
+- Rest for the next few days and drink plenty of fluids.
+- Keep any follow-up appointment you have been given.
+
+Come back or call us straight away if you feel worse, have a fever above
+38.5 C, new pain, or anything else that worries you.",
+    ),
+    (
+        "Wound Care",
+        "Your wound: {diagnosis}
+
+- Keep the dressing clean and dry for 48 hours.
+- After that you may shower; pat the wound dry and do not soak it.
+- Change the dressing if it becomes wet or dirty.
+- Stitches or clips are usually removed after 7 to 10 days.
+
+Come back if the wound becomes red, hot, swollen, smells or leaks pus, or if
+you develop a fever.",
+    ),
+    (
+        "Head Injury",
+        "You have been seen for a head injury ({diagnosis}).
+
+For the next 48 hours someone should stay with you.
+- Rest and avoid alcohol, sport and driving.
+- Simple pain relief such as paracetamol is fine.
+
+Return IMMEDIATELY if you have: drowsiness that is hard to wake from, repeated
+vomiting, a worsening headache, confusion, fits, weakness, or blood or clear
+fluid from the ears or nose.",
+    ),
+    (
+        "Medication Guidance",
+        "Prescribed for you on {date}: {prescription}
+
+- Read the leaflet that comes with each medicine.
+- Do not stop a medicine without asking us first.
+- Known allergies on your record: {allergies}
+
+Tell any other doctor or pharmacist that you are taking these medicines.",
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    pub name: String,
+    pub body: String,
+}
+
+/// What is known about the visit the handout is for, besides the patient.
+#[derive(Debug, Clone, Default)]
+pub struct Visit {
+    pub diagnosis: Option<String>,
+    pub prescription: Option<String>,
+    pub ward: Option<String>,
+}
+
+/// The built-in templates and the site's own, sorted by name.
+pub fn templates() -> Vec<Template> {
+    let mut templates: Vec<Template> = BUILT_IN
+        .iter()
+        .map(|(name, body)| Template {
+            name: name.to_string(),
+            body: body.to_string(),
+        })
+        .collect();
+    let entries = fs::read_dir(TEMPLATE_DIR).into_iter().flatten().flatten();
+    for entry in entries {
+        let path = entry.path();
+        let (Some(name), Ok(body)) = (template_name(&path), fs::read_to_string(&path)) else {
+            continue;
+        };
+        templates.retain(|t| !t.name.eq_ignore_ascii_case(&name));
+        templates.push(Template { name, body });
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    templates
+}
+
+/// `aftercare/cast_care.txt` is the "Cast Care" template.
+fn template_name(path: &Path) -> Option<String> {
+    if path.extension()? != "txt" {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    let words: Vec<String> = stem
+        .split(['_', '-', ' '])
+        .filter(|w| !w.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        })
+        .collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// `template` filled in for `patient`, under a heading naming the patient,
+/// ready to print or save.
+pub fn handout(template: &Template, patient: &Patient, visit: &Visit) -> String {
+    let or_dash = |value: Option<&str>| {
+        value
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .unwrap_or("-")
+            .to_string()
+    };
+    let name = format!("{} {}", patient.first_name, patient.last_name);
+    let fields = [
+        ("{name}", name.clone()),
+        ("{first_name}", patient.first_name.clone()),
+        ("{mrn}", patient.mrn.clone()),
+        ("{date_of_birth}", patient.date_of_birth.clone()),
+        ("{allergies}", or_dash(patient.allergies.as_deref())),
+        (
+            "{medications}",
+            or_dash(patient.current_medications.as_deref()),
+        ),
+        ("{diagnosis}", or_dash(visit.diagnosis.as_deref())),
+        ("{prescription}", or_dash(visit.prescription.as_deref())),
+        ("{ward}", or_dash(visit.ward.as_deref())),
+        ("{date}", datetime::display_date(datetime::today())),
+    ];
+    let body = fields
+        .iter()
+        .fold(template.body.clone(), |body, (field, value)| {
+            body.replace(field, value)
+        });
+    let heading = template.name.to_uppercase();
+    format!(
+        "{}\n{}\n\n{}  {}  DOB {}\n{}\n\n{}\n",
+        heading,
+        "=".repeat(heading.chars().count()),
+        name,
+        patient.mrn,
+        patient.date_of_birth,
+        datetime::display_date(datetime::today()),
+        body.trim_end()
+    )
+}
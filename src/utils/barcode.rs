@@ -0,0 +1,107 @@
+//! QR codes and Code 128 barcodes for patient wristbands and sample labels,
+//! drawn with block characters so the same lines work on screen and in
+//! printed documents.
+
+use anyhow::{anyhow, Result};
+use qrcode::{Color, EcLevel, QrCode};
+
+/// Bar and space widths of Code 128 symbols 0 to 105; 104 is Start B.
+const CODE128: [&str; 106] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232",
+];
+const START_B: usize = 104;
+const STOP: &str = "2331112";
+/// Blank modules each side of a code, so scanners find its edges.
+const QUIET_ZONE: usize = 10;
+const QR_QUIET_ZONE: usize = 2;
+
+/// The medical record number of patient `number` registered on the instance
+/// with id `instance`, like `MRN-3F9A2C-000042`. The start of the instance
+/// id keeps numbers given out by synced instances apart.
+pub fn mrn(instance: &str, number: i64) -> String {
+    let prefix: String = instance.chars().take(6).collect();
+    format!("MRN-{}-{:06}", prefix.to_ascii_uppercase(), number)
+}
+
+/// `content` as a QR code, two module rows per line of half blocks. Dark
+/// modules are drawn, so the lines need dark ink on a light background.
+pub fn qr_lines(content: &str) -> Result<Vec<String>> {
+    let code = QrCode::with_error_correction_level(content, EcLevel::M)
+        .map_err(|e| anyhow!("Cannot encode {} as a QR code: {}", content, e))?;
+    let width = code.width();
+    let colors = code.to_colors();
+    let size = width + 2 * QR_QUIET_ZONE;
+    let dark = |x: usize, y: usize| {
+        x >= QR_QUIET_ZONE
+            && y >= QR_QUIET_ZONE
+            && x < width + QR_QUIET_ZONE
+            && y < width + QR_QUIET_ZONE
+            && colors[(y - QR_QUIET_ZONE) * width + x - QR_QUIET_ZONE] == Color::Dark
+    };
+    Ok((0..size)
+        .step_by(2)
+        .map(|y| {
+            (0..size)
+                .map(|x| match (dark(x, y), dark(x, y + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// `content` as a Code 128 (set B) barcode `height` lines tall, two modules
+/// per character. Only printable ASCII can be encoded.
+pub fn code128_lines(content: &str, height: usize) -> Result<Vec<String>> {
+    let values = content
+        .chars()
+        .map(|c| match c {
+            ' '..='~' => Ok(c as usize - 32),
+            _ => Err(anyhow!("Cannot encode {:?} in a Code 128 barcode", c)),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let checksum = values
+        .iter()
+        .enumerate()
+        .fold(START_B, |sum, (i, value)| sum + (i + 1) * value)
+        % 103;
+
+    let mut modules = vec![false; QUIET_ZONE];
+    let symbols = std::iter::once(CODE128[START_B])
+        .chain(values.iter().map(|&value| CODE128[value]))
+        .chain([CODE128[checksum], STOP]);
+    for symbol in symbols {
+        for (i, width) in symbol.bytes().enumerate() {
+            let bar = i % 2 == 0;
+            modules.extend(std::iter::repeat_n(bar, (width - b'0') as usize));
+        }
+    }
+    modules.extend(std::iter::repeat_n(false, QUIET_ZONE));
+
+    let line: String = modules
+        .chunks(2)
+        .map(
+            |pair| match (pair[0], pair.get(1).copied().unwrap_or(false)) {
+                (true, true) => '█',
+                (true, false) => '▌',
+                (false, true) => '▐',
+                (false, false) => ' ',
+            },
+        )
+        .collect();
+    Ok(vec![line; height])
+}
@@ -0,0 +1,54 @@
+//! Working-day rules from the configuration: the weekly days off and the
+//! holiday calendar. Calendars, appointment booking and the roster ask here
+//! instead of reading the configuration themselves.
+
+use super::{config, datetime};
+use crate::i18n::{t, tf};
+use time::{Date, Weekday};
+
+/// The name of the holiday on `date`, if it is one. A `"YYYY-MM-DD"` entry
+/// wins over a yearly `"MM-DD"` one.
+pub fn holiday(date: Date) -> Option<String> {
+    let config = config::config();
+    let holidays = &config.holidays;
+    let exact = datetime::format_date(date);
+    holidays
+        .get(&exact)
+        .or_else(|| holidays.get(&exact[5..]))
+        .cloned()
+}
+
+/// Whether `date` falls on one of the configured weekend days, written as
+/// English day names or their first three letters.
+pub fn is_weekend(date: Date) -> bool {
+    let weekday = date.weekday().to_string().to_lowercase();
+    config::config().weekend.iter().any(|day| {
+        let day = day.trim().to_lowercase();
+        day.len() >= 3 && weekday.starts_with(&day)
+    })
+}
+
+/// The name of `weekday` in the active locale.
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Monday => t("calendar.monday"),
+        Weekday::Tuesday => t("calendar.tuesday"),
+        Weekday::Wednesday => t("calendar.wednesday"),
+        Weekday::Thursday => t("calendar.thursday"),
+        Weekday::Friday => t("calendar.friday"),
+        Weekday::Saturday => t("calendar.saturday"),
+        Weekday::Sunday => t("calendar.sunday"),
+    }
+}
+
+/// Why nothing can be booked on `date`, or `None` on a working day.
+pub fn closed_reason(date: Date) -> Option<String> {
+    match holiday(date) {
+        Some(name) => Some(tf("calendar.holiday", &[("date", &date), ("name", &name)])),
+        None if is_weekend(date) => Some(tf(
+            "calendar.weekend",
+            &[("date", &date), ("day", &weekday_name(date.weekday()))],
+        )),
+        None => None,
+    }
+}
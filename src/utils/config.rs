@@ -0,0 +1,474 @@
+//! Machine-wide configuration read from `~/.config/rustoria/config.toml`, or
+//! the file named by `--config <path>`. Every key is optional and falls back
+//! to a sane default, so a missing file simply means "use the defaults"; the
+//! file is only written when settings are saved from the Settings screen.
+
+use anyhow::{Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, OnceLock, RwLock};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// SQLite database file, relative to the working directory unless absolute.
+    pub db_path: PathBuf,
+    /// Whether the database runs in write-ahead-log mode, so screens can
+    /// read while another instance writes. Turn it off for a file on a
+    /// network share, where WAL does not work.
+    pub wal: bool,
+    /// Theme used when none has been chosen in Settings.
+    pub theme: Option<String>,
+    /// Locale used when none has been chosen in Settings or `RUSTORIA_LOCALE`.
+    pub locale: Option<String>,
+    /// How dates are shown, like `"[day]/[month]/[year]"`; the locale's
+    /// format when unset. See [`super::datetime`].
+    pub date_format: Option<String>,
+    /// The facility's time zone: `"UTC"` when unset, `"local"` for the
+    /// system's, or an offset like `"+05:30"`. See [`super::datetime`].
+    pub timezone: Option<String>,
+    /// Symbol put in front of amounts.
+    pub currency: String,
+    /// Minutes without input before the user is logged out; 0 never does.
+    pub session_timeout: u64,
+    /// Minutes after its start before an appointment or consultation nobody
+    /// checked in to is marked as a no-show; 0 never does.
+    pub no_show_grace: u64,
+    /// Minutes in an appointment slot, for counting the slots a doctor left
+    /// idle on shift.
+    pub slot_minutes: u64,
+    /// Minutes from token to doctor the queue report flags days over; 0
+    /// flags none.
+    pub wait_target: u64,
+    /// Patients waiting at once the queue report flags days over; 0 flags
+    /// none.
+    pub queue_target: u64,
+    /// Patient ratings (1 to 5) at or below which feedback is flagged for
+    /// follow-up; 0 flags none.
+    pub follow_up_rating: u8,
+    /// Days of the week nothing is booked on, like `"Saturday"` or `"Sun"`.
+    pub weekend: Vec<String>,
+    /// Gender options offered after Male, Female, Other and Prefer not to
+    /// say, like `"Non-binary"`.
+    pub genders: Vec<String>,
+    pub keymap: KeymapConfig,
+    /// Single keys on the home screen, like `"n"`, to the palette command
+    /// they open, like `"add_patient"`; see
+    /// [`crate::components::quick_actions`].
+    pub quick_actions: BTreeMap<String, String>,
+    /// Where HL7 v2 ADT messages go on admission and discharge; see
+    /// [`crate::interop::hl7`].
+    pub hl7: Hl7Config,
+    /// Event name to the Rhai script run when it happens; see [`crate::hooks`].
+    pub hooks: BTreeMap<String, PathBuf>,
+    /// Holiday name by date, `"YYYY-MM-DD"` for a single year or `"MM-DD"`
+    /// for every year; see [`super::calendar`].
+    pub holidays: BTreeMap<String, String>,
+    /// Beds on each ward by ward name, for the occupancy forecast's
+    /// capacity warnings.
+    pub wards: BTreeMap<String, u32>,
+    /// Endpoints notified of events; see [`crate::webhooks`].
+    pub webhooks: Vec<WebhookConfig>,
+    /// How patients are sent reminders and results; see [`crate::notify`].
+    pub notify: NotifyConfig,
+    /// How wristbands and other labels are printed; see [`super::labels`].
+    pub labels: LabelsConfig,
+    /// What `--kiosk` shows; see [`crate::components::kiosk`].
+    pub kiosk: KioskConfig,
+    /// How patient photos are taken; see [`super::photo`].
+    pub photo: PhotoConfig,
+    /// How printed invoices and receipts are laid out and numbered; see
+    /// [`super::invoice_layout`].
+    pub invoice: InvoiceConfig,
+}
+
+/// Keys for the shortcuts that work on every screen, written like
+/// `"Ctrl+Q"`, `"Alt+H"` or `"F1"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeymapConfig {
+    pub quit: String,
+    pub palette: String,
+    pub help: String,
+    /// Starts and stops recording a key macro.
+    pub macro_record: String,
+    /// Replays the recorded key macro.
+    pub macro_play: String,
+    /// Opens the prompt a barcode scanner types into.
+    pub scan: String,
+    /// Opens the quick vital signs entry.
+    pub vitals: String,
+}
+
+/// An endpoint POSTed a JSON body when one of `events` happens.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Key the body is signed with; the signature is sent as
+    /// `X-Rustoria-Signature: sha256=<hex HMAC>`.
+    #[serde(default)]
+    pub secret: Option<String>,
+    pub events: Vec<String>,
+}
+
+/// Gateways for messages to patients. `command`, when set, sends every
+/// message; otherwise email goes through `smtp_url` and SMS through `sms_url`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    /// Program run with the channel (`email` or `sms`), the address and the
+    /// subject as arguments and the message on standard input.
+    pub command: Option<String>,
+    /// Mail server as `smtp://host:port` or `smtps://host:port`.
+    pub smtp_url: Option<String>,
+    pub smtp_user: Option<String>,
+    pub smtp_password: Option<String>,
+    /// Sender address of emails.
+    pub from: String,
+    /// Endpoint POSTed `{"to", "body"}` for each text message.
+    pub sms_url: Option<String>,
+    /// Sent as `Authorization: Bearer <token>` to `sms_url`.
+    pub sms_token: Option<String>,
+    /// Hours before a consultation its reminder goes out; 0 sends none.
+    pub reminder_hours: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LabelsConfig {
+    pub format: LabelFormat,
+}
+
+/// What labels are sent to the printer as.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelFormat {
+    /// Text and block-character codes, for any printer.
+    #[default]
+    Text,
+    /// ZPL II, sent raw to a Zebra-compatible label printer.
+    Zpl,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KioskConfig {
+    /// Screens shown, in order: `"beds"`, `"queue"` and `"appointments"`.
+    pub screens: Vec<String>,
+    /// Seconds each screen stays up before the next; 0 never moves on.
+    pub rotate: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhotoConfig {
+    /// Program and arguments that take a picture, with `{file}` standing for
+    /// the path to write it to. Empty leaves photo capture off.
+    pub command: Vec<String>,
+    /// Directory pictures are saved in.
+    pub dir: PathBuf,
+    /// File extension the program is asked for, which decides the format
+    /// for most capture tools.
+    pub extension: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InvoiceConfig {
+    pub layout: InvoiceLayout,
+    /// Name printed at the top of every document.
+    pub seller_name: String,
+    pub seller_address: Option<String>,
+    /// VAT number, GSTIN or ABN, whichever the layout asks for.
+    pub tax_number: Option<String>,
+    /// Tax charged on invoice lines, in percent; 0 for exempt services.
+    pub tax_rate: f64,
+    /// Whether catalog prices already include the tax. When they do not,
+    /// it is added as a procedure is billed; invoice costs always include it.
+    pub prices_include_tax: bool,
+    /// How document numbers run; the layout's rule when unset.
+    pub numbering: Option<Numbering>,
+    pub invoice_prefix: String,
+    pub receipt_prefix: String,
+    /// Put in every document number to keep those of synced instances
+    /// apart; the start of the instance id when unset, and left out when
+    /// empty.
+    pub series: Option<String>,
+    /// How far below its catalog price, in percent, anyone may bill an
+    /// item; a bigger discount waits for an administrator or accountant.
+    pub discount_limit: f64,
+}
+
+/// The document layout of a tax regime.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InvoiceLayout {
+    /// Items and a total, with no tax details.
+    #[default]
+    Plain,
+    /// A UK VAT invoice.
+    UkVat,
+    /// An EU VAT invoice under the VAT Directive.
+    EuVat,
+    /// An Indian GST tax invoice, with the tax split into CGST and SGST.
+    InGst,
+    /// An Australian GST tax invoice.
+    AuGst,
+}
+
+/// How invoice and receipt numbers run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Numbering {
+    /// One series that never restarts, like `INV-000042`.
+    Continuous,
+    /// A series per calendar year, like `INV-2026-000042`.
+    Yearly,
+    /// A series per April-to-March financial year, like `INV/26-27/00042`.
+    FinancialYear,
+}
+
+/// Destinations and header fields for outgoing HL7 v2 messages. Nothing is
+/// sent unless `file` or `mllp` is set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hl7Config {
+    /// File each message is appended to.
+    pub file: Option<PathBuf>,
+    /// `host:port` of an MLLP listener each message is sent to.
+    pub mllp: Option<String>,
+    pub sending_facility: String,
+    pub receiving_application: String,
+    pub receiving_facility: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            db_path: PathBuf::from("rustoria.db"),
+            wal: true,
+            theme: None,
+            locale: None,
+            date_format: None,
+            timezone: None,
+            currency: "$".to_string(),
+            session_timeout: 0,
+            no_show_grace: 15,
+            slot_minutes: 30,
+            wait_target: 30,
+            queue_target: 10,
+            follow_up_rating: 2,
+            weekend: vec!["Saturday".to_string(), "Sunday".to_string()],
+            genders: Vec::new(),
+            keymap: KeymapConfig::default(),
+            quick_actions: [
+                ("n", "add_patient"),
+                ("i", "create_invoice"),
+                ("b", "schedule_teleconsult"),
+            ]
+            .into_iter()
+            .map(|(key, command)| (key.to_string(), command.to_string()))
+            .collect(),
+            hl7: Hl7Config::default(),
+            hooks: BTreeMap::new(),
+            holidays: BTreeMap::new(),
+            wards: BTreeMap::new(),
+            webhooks: Vec::new(),
+            notify: NotifyConfig::default(),
+            labels: LabelsConfig::default(),
+            kiosk: KioskConfig::default(),
+            photo: PhotoConfig::default(),
+            invoice: InvoiceConfig::default(),
+        }
+    }
+}
+
+impl Default for InvoiceConfig {
+    fn default() -> Self {
+        Self {
+            layout: InvoiceLayout::Plain,
+            seller_name: "Rustoria Hospital".to_string(),
+            seller_address: None,
+            tax_number: None,
+            tax_rate: 0.0,
+            prices_include_tax: false,
+            numbering: None,
+            invoice_prefix: "INV".to_string(),
+            receipt_prefix: "RCT".to_string(),
+            series: None,
+            discount_limit: 10.0,
+        }
+    }
+}
+
+impl Default for KeymapConfig {
+    fn default() -> Self {
+        Self {
+            quit: "Ctrl+Q".to_string(),
+            palette: "Ctrl+P".to_string(),
+            help: "F1".to_string(),
+            macro_record: "Alt+Q".to_string(),
+            macro_play: "Alt+@".to_string(),
+            scan: "F2".to_string(),
+            vitals: "F3".to_string(),
+        }
+    }
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            command: None,
+            smtp_url: None,
+            smtp_user: None,
+            smtp_password: None,
+            from: "rustoria@localhost".to_string(),
+            sms_url: None,
+            sms_token: None,
+            reminder_hours: 24,
+        }
+    }
+}
+
+impl Default for PhotoConfig {
+    fn default() -> Self {
+        Self {
+            command: Vec::new(),
+            dir: PathBuf::from("photos"),
+            extension: "jpg".to_string(),
+        }
+    }
+}
+
+impl Default for KioskConfig {
+    fn default() -> Self {
+        Self {
+            screens: vec![
+                "beds".to_string(),
+                "queue".to_string(),
+                "appointments".to_string(),
+            ],
+            rotate: 20,
+        }
+    }
+}
+
+impl Default for Hl7Config {
+    fn default() -> Self {
+        Self {
+            file: None,
+            mllp: None,
+            sending_facility: "RUSTORIA".to_string(),
+            receiving_application: String::new(),
+            receiving_facility: String::new(),
+        }
+    }
+}
+
+static CURRENT: LazyLock<RwLock<Arc<Config>>> = LazyLock::new(RwLock::default);
+static PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// The loaded configuration. Cheap to call: callers share it until the next
+/// [`load`] or [`save`] swaps in a new one.
+pub fn config() -> Arc<Config> {
+    Arc::clone(&CURRENT.read().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// The file the configuration is read from and saved to.
+pub fn path() -> &'static Path {
+    PATH.get_or_init(default_path)
+}
+
+fn default_path() -> PathBuf {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".config"))
+        })
+        .unwrap_or_default();
+    base.join("rustoria").join("config.toml")
+}
+
+/// Reads the configuration file. A missing file leaves the defaults in place;
+/// one that exists but cannot be parsed is an error, so a typo is reported
+/// instead of silently ignored.
+pub fn load() -> Result<()> {
+    let path = super::arg_value("--config")
+        .map(PathBuf::from)
+        .unwrap_or_else(default_path);
+    let path = PATH.get_or_init(|| path);
+    let config = match fs::read_to_string(path) {
+        Ok(text) => toml::from_str(&text)
+            .with_context(|| format!("Invalid configuration in {}", path.display()))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Config::default(),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+        }
+    };
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(config);
+    Ok(())
+}
+
+/// Writes `config` to the configuration file and makes it current.
+pub fn save(config: Config) -> Result<()> {
+    let path = path();
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let text = toml::to_string_pretty(&config).context("Failed to serialise configuration")?;
+    fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(config);
+    Ok(())
+}
+
+/// Parses a key such as `"Ctrl+Q"`, `"Alt+Shift+X"`, `"F1"` or `"Esc"`.
+pub fn parse_key(spec: &str) -> Option<(KeyCode, KeyModifiers)> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut parts: Vec<&str> = spec.split('+').map(str::trim).collect();
+    let key = parts.pop()?;
+    for part in parts {
+        modifiers |= match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+    }
+    let code = match key.to_ascii_lowercase().as_str() {
+        "esc" | "escape" => KeyCode::Esc,
+        "enter" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        lower => match lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+            Some(n @ 1..=12) => KeyCode::F(n),
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if modifiers.is_empty() => KeyCode::Char(c),
+                    (Some(c), None) => KeyCode::Char(c.to_ascii_lowercase()),
+                    _ => return None,
+                }
+            }
+        },
+    };
+    Some((code, modifiers))
+}
+
+/// Whether `key` is the key written as `spec`. Letters match either case when
+/// a modifier is held, since terminals disagree on reporting Shift with Ctrl.
+pub fn key_matches(spec: &str, key: &KeyEvent) -> bool {
+    let Some((code, modifiers)) = parse_key(spec) else {
+        return false;
+    };
+    let pressed = match key.code {
+        KeyCode::Char(c) if !modifiers.is_empty() => KeyCode::Char(c.to_ascii_lowercase()),
+        other => other,
+    };
+    pressed == code && key.modifiers == modifiers
+}
@@ -0,0 +1,73 @@
+//! Phone numbers and email addresses as they are stored: phone numbers in an
+//! E.164-like `+442079460000` or bare-digit form, email addresses trimmed and
+//! lowercased, so the same contact always compares and searches the same.
+
+/// Characters accepted between the digits of a typed phone number.
+const PHONE_SEPARATORS: &str = " -().";
+
+/// Whether `value` reads as a phone number: 7 to 15 digits, an optional
+/// leading `+` (or `00`) and separators between them.
+pub fn is_valid_phone(value: &str) -> bool {
+    let value = value.trim();
+    let rest = value.strip_prefix('+').unwrap_or(value);
+    let allowed = rest
+        .chars()
+        .all(|c| c.is_ascii_digit() || PHONE_SEPARATORS.contains(c));
+    let digits = digits(&normalize_phone(value)).len();
+    allowed && (7..=15).contains(&digits)
+}
+
+/// `value` with separators dropped and an international `00` prefix written
+/// as `+`: `"0044 (20) 7946-0000"` becomes `"+442079460000"`.
+pub fn normalize_phone(value: &str) -> String {
+    let value = value.trim();
+    let all = digits(value);
+    if value.starts_with('+') {
+        format!("+{}", all)
+    } else if let Some(international) = all.strip_prefix("00") {
+        format!("+{}", international)
+    } else {
+        all
+    }
+}
+
+/// Whether `value` reads as an email address: one `@`, a dotted domain of
+/// letters, digits and hyphens, and no spaces.
+pub fn is_valid_email(value: &str) -> bool {
+    let value = value.trim();
+    let Some((user, domain)) = value.split_once('@') else {
+        return false;
+    };
+    let user_ok = !user.is_empty()
+        && !user.starts_with('.')
+        && !user.ends_with('.')
+        && !user.contains("..")
+        && user
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~.".contains(c));
+    let labels: Vec<&str> = domain.split('.').collect();
+    let domain_ok = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    user_ok && domain_ok
+}
+
+pub fn normalize_email(value: &str) -> String {
+    value.trim().to_lowercase()
+}
+
+/// Whether a search for `query` finds the phone number `phone`, however
+/// either is punctuated. Queries with fewer than three digits never match,
+/// so names and record numbers are not mistaken for phone numbers.
+pub fn phone_matches(phone: &str, query: &str) -> bool {
+    let wanted = digits(query);
+    wanted.len() >= 3 && digits(phone).contains(&wanted)
+}
+
+fn digits(value: &str) -> String {
+    value.chars().filter(char::is_ascii_digit).collect()
+}
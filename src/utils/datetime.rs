@@ -0,0 +1,259 @@
+//! Parsing and formatting of the dates and times Rustoria stores and shows.
+//!
+//! Everything is stored as text that sorts chronologically: dates as
+//! `YYYY-MM-DD`, times as `YYYY-MM-DD HH:MM` or `YYYY-MM-DD HH:MM:SS` in
+//! UTC. Calendar dates (today, shift and visit dates, birth dates) and the
+//! times people type and read are in the facility's time zone, set by
+//! `timezone` in the configuration. Screens show dates in the locale's
+//! format unless `date_format` overrides it:
+//!
+//! ```toml
+//! timezone = "+05:30"               # or "local", or "UTC" (the default)
+//! date_format = "[day].[month].[year]"
+//! ```
+
+use super::config;
+use crate::components::toast::{self, Toast};
+use crate::i18n::t;
+use std::sync::OnceLock;
+use time::format_description::{self, BorrowedFormatItem};
+use time::macros::format_description;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+/// `YYYY-MM-DD`, how dates are stored and typed.
+pub const DATE: &[BorrowedFormatItem<'static>] = format_description!("[year]-[month]-[day]");
+/// `YYYY-MM-DD HH:MM`, how appointment and registry times are typed.
+pub const DATE_TIME: &[BorrowedFormatItem<'static>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]");
+/// `YYYY-MM-DD HH:MM:SS`, how the time of an event is stored.
+pub const TIMESTAMP: &[BorrowedFormatItem<'static>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+
+/// The current time in UTC, the zone everything is stored in.
+pub fn now() -> OffsetDateTime {
+    OffsetDateTime::now_utc()
+}
+
+static SYSTEM_OFFSET: OnceLock<UtcOffset> = OnceLock::new();
+
+/// Reads the system's UTC offset, for `timezone = "local"`. It can only be
+/// read safely while Rustoria has a single thread, so this runs first thing
+/// at startup and the offset is kept for the rest of the run.
+pub fn init() {
+    SYSTEM_OFFSET.get_or_init(|| UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC));
+}
+
+/// A `timezone` setting: `UTC`, `local`, or an offset from UTC such as
+/// `+05:30`, `-0300` or `UTC+2`.
+pub fn parse_timezone(text: &str) -> Option<UtcOffset> {
+    let text = text.trim().to_ascii_lowercase();
+    match text.as_str() {
+        "" | "utc" | "z" => return Some(UtcOffset::UTC),
+        "local" => return Some(SYSTEM_OFFSET.get().copied().unwrap_or(UtcOffset::UTC)),
+        _ => {}
+    }
+    let offset = text.strip_prefix("utc").unwrap_or(&text);
+    let (sign, digits) = match offset.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some(parts) => parts,
+        None if digits.len() > 2 => digits.split_at(digits.len() - 2),
+        None => (digits, "0"),
+    };
+    let hours: i8 = hours.parse().ok().filter(|h| *h <= 14)?;
+    let minutes: i8 = minutes.parse().ok().filter(|m| *m < 60)?;
+    UtcOffset::from_hms(sign * hours, sign * minutes, 0).ok()
+}
+
+/// Warns about a `timezone` setting that is not understood, which would
+/// otherwise quietly fall back to UTC.
+pub fn check_config() {
+    if let Some(timezone) = config::config()
+        .timezone
+        .as_ref()
+        .filter(|timezone| parse_timezone(timezone).is_none())
+    {
+        toast::push(Toast::error(format!(
+            "Unknown timezone \"{}\" in {}, using UTC",
+            timezone,
+            config::path().display()
+        )));
+    }
+}
+
+/// The facility's offset from UTC; UTC when `timezone` is unset or invalid.
+pub fn offset() -> UtcOffset {
+    config::config()
+        .timezone
+        .as_deref()
+        .and_then(parse_timezone)
+        .unwrap_or(UtcOffset::UTC)
+}
+
+/// The offset as an SQLite modifier like `+330 minutes`, for the date of a
+/// stored time at the facility: `date(scheduled_at, ?)`.
+pub fn sql_offset() -> String {
+    format!("{:+} minutes", offset().whole_minutes())
+}
+
+/// The current time in the facility's time zone.
+pub fn local_now() -> OffsetDateTime {
+    now().to_offset(offset())
+}
+
+/// Today at the facility.
+pub fn today() -> Date {
+    local_now().date()
+}
+
+/// `date` as `YYYY-MM-DD`.
+pub fn format_date(date: Date) -> String {
+    date.format(DATE).unwrap_or_default()
+}
+
+/// `at` as `YYYY-MM-DD HH:MM`.
+pub fn format_date_time(at: OffsetDateTime) -> String {
+    at.format(DATE_TIME).unwrap_or_default()
+}
+
+/// The month of `date` as `YYYY-MM`, how report periods are typed.
+pub fn format_month(date: Date) -> String {
+    date.format(format_description!("[year]-[month]"))
+        .unwrap_or_default()
+}
+
+/// `at` as `YYYY-MM-DD HH:MM:SS`.
+pub fn format_timestamp(at: OffsetDateTime) -> String {
+    at.format(TIMESTAMP).unwrap_or_default()
+}
+
+/// Today as `YYYY-MM-DD`.
+pub fn today_text() -> String {
+    format_date(today())
+}
+
+/// The current time as `YYYY-MM-DD HH:MM:SS`.
+pub fn now_text() -> String {
+    format_timestamp(now())
+}
+
+/// `at` as `YYYY-MM-DDTHH:MM:SSZ`, for FHIR and other ISO 8601 consumers.
+pub fn format_iso8601(at: OffsetDateTime) -> String {
+    at.format(format_description!(
+        "[year]-[month]-[day]T[hour]:[minute]:[second]Z"
+    ))
+    .unwrap_or_default()
+}
+
+/// `at` as `YYYYMMDDHHMMSS`, the HL7 timestamp.
+pub fn format_compact(at: OffsetDateTime) -> String {
+    at.format(format_description!(
+        "[year][month][day][hour][minute][second]"
+    ))
+    .unwrap_or_default()
+}
+
+/// `at` as `YYYYMMDD_HHMMSS`, for the names of backups and reports.
+pub fn file_stamp(at: OffsetDateTime) -> String {
+    at.format(format_description!(
+        "[year][month][day]_[hour][minute][second]"
+    ))
+    .unwrap_or_default()
+}
+
+/// A `YYYY-MM-DD` date; see [`date_part`] for the date of a stored time.
+pub fn parse_date(text: &str) -> Option<Date> {
+    Date::parse(text.trim(), DATE).ok()
+}
+
+/// A `YYYY-MM-DD HH:MM` time.
+pub fn parse_date_time(text: &str) -> Option<PrimitiveDateTime> {
+    PrimitiveDateTime::parse(text.trim(), DATE_TIME).ok()
+}
+
+/// The `YYYY-MM-DD` a stored time starts with.
+pub fn date_part(stamp: &str) -> &str {
+    let stamp = stamp.trim();
+    stamp.get(..10).unwrap_or(stamp)
+}
+
+/// A stored time to the minute, still in UTC, or midnight when only the
+/// date is known; any seconds are dropped.
+pub fn parse_stamp(stamp: &str) -> Option<PrimitiveDateTime> {
+    let stamp = stamp.trim();
+    parse_date_time(stamp.get(..16).unwrap_or(stamp))
+        .or_else(|| parse_date(date_part(stamp)).map(Date::midnight))
+}
+
+/// The `YYYY-MM` a stored date or time falls in, for grouping by month.
+pub fn month_key(stamp: &str) -> Option<String> {
+    parse_date(date_part(stamp)).map(format_month)
+}
+
+/// The `YYYY` a stored date or time falls in, for grouping by year.
+pub fn year_key(stamp: &str) -> Option<String> {
+    parse_date(date_part(stamp)).map(|date| date.year().to_string())
+}
+
+/// A stored UTC time in the facility's time zone, or None for a bare date.
+fn facility_time(stamp: &str) -> Option<OffsetDateTime> {
+    let stamp = stamp.trim();
+    let at = PrimitiveDateTime::parse(stamp, TIMESTAMP)
+        .ok()
+        .or_else(|| parse_date_time(stamp.get(..16)?))?;
+    Some(at.assume_utc().to_offset(offset()))
+}
+
+/// A time typed in the facility's time zone as the UTC `YYYY-MM-DD HH:MM`
+/// it is stored as.
+pub fn to_stored(local: PrimitiveDateTime) -> String {
+    format_date_time(local.assume_offset(offset()).to_offset(UtcOffset::UTC))
+}
+
+/// A stored time as `YYYY-MM-DD HH:MM` in the facility's time zone, for
+/// messages and forms. Text that is not a time is returned as it is.
+pub fn facility_stamp(stamp: &str) -> String {
+    facility_time(stamp).map_or_else(|| stamp.to_string(), format_date_time)
+}
+
+/// The `HH:MM` of a stored time in the facility's time zone, or `""` for a
+/// bare date.
+pub fn time_part(stamp: &str) -> String {
+    facility_time(stamp)
+        .and_then(|at| at.format(format_description!("[hour]:[minute]")).ok())
+        .unwrap_or_default()
+}
+
+/// The facility's date of a stored time, or the date itself.
+pub fn local_date(stamp: &str) -> Option<Date> {
+    facility_time(stamp)
+        .map(|at| at.date())
+        .or_else(|| parse_date(date_part(stamp)))
+}
+
+/// `date` in the configured display format.
+pub fn display_date(date: Date) -> String {
+    let config = config::config();
+    let configured = &config.date_format;
+    let pattern = configured.as_deref().unwrap_or(t("format.date"));
+    format_description::parse(pattern)
+        .ok()
+        .and_then(|format| date.format(&format).ok())
+        .unwrap_or_else(|| format_date(date))
+}
+
+/// A stored date or time in the configured display format, down to the
+/// minute and in the facility's time zone. Text that is not a date is
+/// returned as it is.
+pub fn display_stamp(stamp: &str) -> String {
+    if let Some(at) = facility_time(stamp) {
+        return format!("{} {}", display_date(at.date()), time_part(stamp));
+    }
+    match parse_date(date_part(stamp)) {
+        Some(date) => display_date(date),
+        None => stamp.to_string(),
+    }
+}
@@ -0,0 +1,369 @@
+//! Printed invoices and receipts laid out for the facility's tax regime,
+//! with the registration number, wording and numbering it requires. Chosen
+//! under `[invoice]`:
+//!
+//! ```toml
+//! [invoice]
+//! layout = "uk_vat"
+//! seller_name = "St Mary's Clinic Ltd"
+//! tax_number = "GB123456789"
+//! tax_rate = 20
+//! ```
+
+use super::config::{InvoiceConfig, InvoiceLayout, Numbering};
+use super::{barcode, datetime, money, truncate};
+use crate::db;
+use crate::models::{Invoice, Patient};
+use anyhow::{anyhow, Result};
+use time::{Date, Month};
+
+/// GST rules cap invoice numbers at 16 characters.
+const GST_NUMBER_LIMIT: usize = 16;
+
+impl InvoiceLayout {
+    fn title(self, receipt: bool) -> &'static str {
+        match (self, receipt) {
+            (_, true) => "RECEIPT",
+            (InvoiceLayout::Plain | InvoiceLayout::EuVat, false) => "INVOICE",
+            (InvoiceLayout::UkVat, false) => "VAT INVOICE",
+            (InvoiceLayout::InGst | InvoiceLayout::AuGst, false) => "TAX INVOICE",
+        }
+    }
+
+    /// What the seller's tax registration number is called.
+    fn tax_number_label(self) -> &'static str {
+        match self {
+            InvoiceLayout::Plain => "Tax No",
+            InvoiceLayout::UkVat => "VAT Reg No",
+            InvoiceLayout::EuVat => "VAT ID",
+            InvoiceLayout::InGst => "GSTIN",
+            InvoiceLayout::AuGst => "ABN",
+        }
+    }
+
+    fn tax_name(self) -> &'static str {
+        match self {
+            InvoiceLayout::Plain => "Tax",
+            InvoiceLayout::UkVat | InvoiceLayout::EuVat => "VAT",
+            InvoiceLayout::InGst | InvoiceLayout::AuGst => "GST",
+        }
+    }
+
+    /// The statement an exempt (0%) invoice has to carry.
+    fn exemption_note(self) -> Option<&'static str> {
+        match self {
+            InvoiceLayout::Plain => None,
+            InvoiceLayout::UkVat => {
+                Some("Medical care exempt from VAT (VATA 1994, Schedule 9, Group 7)")
+            }
+            InvoiceLayout::EuVat => {
+                Some("Exempt from VAT under Article 132(1)(b)-(c) of Directive 2006/112/EC")
+            }
+            InvoiceLayout::InGst => {
+                Some("Health care services exempt from GST (Notification 12/2017-CT (Rate))")
+            }
+            InvoiceLayout::AuGst => Some("GST-free health services"),
+        }
+    }
+
+    fn default_numbering(self) -> Numbering {
+        match self {
+            InvoiceLayout::EuVat => Numbering::Yearly,
+            InvoiceLayout::InGst => Numbering::FinancialYear,
+            _ => Numbering::Continuous,
+        }
+    }
+}
+
+fn numbering(settings: &InvoiceConfig) -> Numbering {
+    settings
+        .numbering
+        .unwrap_or_else(|| settings.layout.default_numbering())
+}
+
+/// The April-to-March financial year `date` falls in, like `(2026, 2027)`.
+fn financial_year(date: Date) -> (i32, i32) {
+    if u8::from(date.month()) < u8::from(Month::April) {
+        (date.year() - 1, date.year())
+    } else {
+        (date.year(), date.year() + 1)
+    }
+}
+
+/// The counter a document made on `today` is numbered from. A series that
+/// restarts each year has a counter per year.
+pub fn sequence_name(settings: &InvoiceConfig, receipt: bool, today: Date) -> String {
+    let base = if receipt {
+        db::RECEIPT_SEQUENCE
+    } else {
+        db::INVOICE_SEQUENCE
+    };
+    match numbering(settings) {
+        Numbering::Continuous => base.to_string(),
+        Numbering::Yearly => format!("{}:{}", base, today.year()),
+        Numbering::FinancialYear => {
+            let (start, end) = financial_year(today);
+            format!("{}:{}-{:02}", base, start, end % 100)
+        }
+    }
+}
+
+/// The part of the document numbers of the instance with id `instance`
+/// that no synced instance shares: the configured `series`, or the start of
+/// the instance id as in MRNs.
+fn series(settings: &InvoiceConfig, instance: &str) -> String {
+    match &settings.series {
+        Some(series) => series.trim().to_string(),
+        None => instance.chars().take(6).collect::<String>(),
+    }
+    .to_ascii_uppercase()
+}
+
+/// The printed number of the document `value` of its series on the
+/// instance with id `instance`, such as `INV-3F9A2C-000042`. Counters are
+/// kept by each instance, so the instance's series keeps numbers apart
+/// across synced instances. Fails when the layout's rules reject it.
+pub fn document_number(
+    settings: &InvoiceConfig,
+    instance: &str,
+    value: i64,
+    receipt: bool,
+    today: Date,
+) -> Result<String> {
+    let prefix = if receipt {
+        &settings.receipt_prefix
+    } else {
+        &settings.invoice_prefix
+    };
+    let series = series(settings, instance);
+    let (separator, parts) = match numbering(settings) {
+        Numbering::Continuous => ("-", vec![format!("{:06}", value)]),
+        Numbering::Yearly => ("-", vec![today.year().to_string(), format!("{:06}", value)]),
+        Numbering::FinancialYear => {
+            let (start, end) = financial_year(today);
+            (
+                "/",
+                vec![
+                    format!("{:02}-{:02}", start % 100, end % 100),
+                    format!("{:05}", value),
+                ],
+            )
+        }
+    };
+    let number = [prefix.clone(), series]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .chain(parts)
+        .collect::<Vec<_>>()
+        .join(separator);
+    if settings.layout == InvoiceLayout::InGst {
+        if number.len() > GST_NUMBER_LIMIT {
+            return Err(anyhow!(
+                "{} is longer than the {} characters GST allows; shorten the prefix or the series under [invoice]",
+                number,
+                GST_NUMBER_LIMIT
+            ));
+        }
+        if !number
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '/')
+        {
+            return Err(anyhow!(
+                "GST invoice numbers may only use letters, digits, '-' and '/'"
+            ));
+        }
+    }
+    Ok(number)
+}
+
+impl InvoiceConfig {
+    /// What billing `price` from the catalog costs, with the tax added when
+    /// catalog prices are net of it.
+    pub fn billed(&self, price: f64) -> f64 {
+        if self.prices_include_tax {
+            price
+        } else {
+            price * (1.0 + self.tax_rate / 100.0)
+        }
+    }
+}
+
+/// An invoice line split into the amount before tax and the tax on it.
+struct Line {
+    item: String,
+    quantity: i32,
+    net: f64,
+    tax: f64,
+}
+
+/// Invoice costs are what is billed, so they include the tax.
+fn split_tax(settings: &InvoiceConfig, invoice: &Invoice) -> Line {
+    let net = invoice.cost / (1.0 + settings.tax_rate / 100.0);
+    Line {
+        item: invoice.item.clone(),
+        quantity: invoice.quantity,
+        net,
+        tax: invoice.cost - net,
+    }
+}
+
+/// `rate` written without a pointless fraction, like `20%` or `2.5%`.
+fn percent(rate: f64) -> String {
+    let text = format!("{:.2}", rate);
+    format!("{}%", text.trim_end_matches('0').trim_end_matches('.'))
+}
+
+/// An itemised statement of `invoices`, or a receipt acknowledging payment
+/// of their total, printed as `number` and dated `issued_on`.
+pub fn document(
+    settings: &InvoiceConfig,
+    invoices: &[&Invoice],
+    patient: Option<&Patient>,
+    number: &str,
+    issued_on: Date,
+    receipt: bool,
+) -> String {
+    let layout = settings.layout;
+    let taxed = layout != InvoiceLayout::Plain || settings.tax_rate > 0.0;
+    let width = if taxed { 60 } else { 48 };
+    let date = datetime::display_date(issued_on);
+
+    let mut lines = vec![
+        format!(
+            "{} - {}",
+            settings.seller_name.to_uppercase(),
+            layout.title(receipt)
+        ),
+        "=".repeat(width),
+    ];
+    if let Some(address) = &settings.seller_address {
+        lines.push(address.clone());
+    }
+    if let Some(tax_number) = &settings.tax_number {
+        lines.push(format!("{}: {}", layout.tax_number_label(), tax_number));
+    }
+    if lines.len() > 2 {
+        lines.push(String::new());
+    }
+    lines.push(format!("Number:   {}", number));
+    lines.push(format!("Date:     {}", date));
+    if layout == InvoiceLayout::UkVat && !receipt {
+        lines.push(format!("Tax point: {}", date));
+    }
+    if layout == InvoiceLayout::InGst {
+        lines.push("SAC:      9993 (Human health services)".to_string());
+    }
+    lines.push(format!(
+        "Patient:  {}",
+        patient
+            .map(|p| format!("{} {} (ID {})", p.first_name, p.last_name, p.id))
+            .unwrap_or_else(|| "Unknown patient".to_string())
+    ));
+    lines.push(format!(
+        "Address:  {}",
+        patient.map_or("-".to_string(), |p| p.full_address())
+    ));
+    lines.push(String::new());
+
+    let items: Vec<Line> = invoices.iter().map(|i| split_tax(settings, i)).collect();
+    let net: f64 = items.iter().map(|l| l.net).sum();
+    let tax: f64 = items.iter().map(|l| l.tax).sum();
+    let tax_name = layout.tax_name();
+    // For an insured patient the insurer's part of the total is shown, and
+    // what is left for the patient.
+    let insured = invoices.iter().any(|i| i.insurer_share.is_some());
+    let covered: f64 = invoices.iter().filter_map(|i| i.insurer_share).sum();
+
+    if taxed {
+        lines.push(format!(
+            "{:<24}{:>5}{:>11}{:>8}{:>12}",
+            "Item", "Qty", "Unit", "Rate", "Net"
+        ));
+        lines.push("-".repeat(width));
+        for line in &items {
+            lines.push(format!(
+                "{:<24}{:>5}{:>11}{:>8}{:>12}",
+                truncate(&line.item, 23),
+                line.quantity,
+                money(line.net / f64::from(line.quantity.max(1))),
+                percent(settings.tax_rate),
+                money(line.net)
+            ));
+        }
+        lines.push("-".repeat(width));
+        let total_line = |label: String, amount: f64| format!("{:<46}{:>14}", label, money(amount));
+        lines.push(total_line(format!("Total excl. {}", tax_name), net));
+        if layout == InvoiceLayout::InGst {
+            let half = percent(settings.tax_rate / 2.0);
+            lines.push(total_line(format!("CGST @ {}", half), tax / 2.0));
+            lines.push(total_line(format!("SGST @ {}", half), tax / 2.0));
+        } else {
+            lines.push(total_line(
+                format!("{} @ {}", tax_name, percent(settings.tax_rate)),
+                tax,
+            ));
+        }
+        lines.push(total_line(
+            if receipt {
+                "Amount received".to_string()
+            } else {
+                format!("Total incl. {}", tax_name)
+            },
+            net + tax,
+        ));
+        if insured {
+            lines.push(total_line("Covered by insurance".to_string(), covered));
+            lines.push(total_line(
+                "Patient co-pay".to_string(),
+                net + tax - covered,
+            ));
+        }
+        lines.push(String::new());
+        if settings.tax_rate == 0.0 {
+            if let Some(note) = layout.exemption_note() {
+                lines.push(note.to_string());
+            }
+        } else if layout == InvoiceLayout::AuGst {
+            lines.push("Total price includes GST".to_string());
+        }
+    } else {
+        lines.push(format!("{:<28}{:>6}{:>14}", "Item", "Qty", "Cost"));
+        lines.push("-".repeat(width));
+        for line in &items {
+            lines.push(format!(
+                "{:<28}{:>6}{:>14}",
+                truncate(&line.item, 27),
+                line.quantity,
+                money(line.net)
+            ));
+        }
+        lines.push("-".repeat(width));
+        lines.push(format!(
+            "{:<34}{:>14}",
+            if receipt {
+                "Amount received"
+            } else {
+                "Total due"
+            },
+            money(net)
+        ));
+        if insured {
+            for (label, amount) in [
+                ("Covered by insurance", covered),
+                ("Patient co-pay", net - covered),
+            ] {
+                lines.push(format!("{:<34}{:>14}", label, money(amount)));
+            }
+        }
+    }
+    lines.push(String::new());
+    if receipt {
+        lines.push("Received by: ______________________".to_string());
+        lines.push(String::new());
+    }
+    if let Some(qr) = patient.and_then(|p| barcode::qr_lines(&p.mrn).ok()) {
+        lines.extend(qr);
+        lines.push(String::new());
+    }
+    lines.join("\n")
+}
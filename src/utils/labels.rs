@@ -0,0 +1,186 @@
+//! Label templates: patient wristbands, sample tubes and pharmacy labels.
+//! Each renders either as plain text for an ordinary printer or as ZPL for a
+//! Zebra-compatible label printer, chosen under `[labels]`:
+//!
+//! ```toml
+//! [labels]
+//! format = "zpl"
+//! ```
+
+use super::barcode;
+use super::config::LabelFormat;
+use super::prescribing;
+use crate::models::{Admission, ImagingStudy, MedicalRecord, Patient};
+use anyhow::Result;
+
+/// Dots per inch of the ZPL layouts, the common 8 dots/mm print head.
+const DPI: u32 = 203;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelKind {
+    Wristband,
+    SampleTube,
+    Pharmacy,
+}
+
+impl LabelKind {
+    pub fn title(self) -> &'static str {
+        match self {
+            LabelKind::Wristband => "Wristband Label",
+            LabelKind::SampleTube => "Sample Label",
+            LabelKind::Pharmacy => "Pharmacy Label",
+        }
+    }
+}
+
+/// A filled-in template: the text lines, and the code its QR code and
+/// barcode carry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub kind: LabelKind,
+    pub lines: Vec<String>,
+    pub code: String,
+}
+
+/// The band a patient wears: name, birth date, allergies and, while they are
+/// admitted, their ward and bed. Coded with the record number.
+pub fn wristband(patient: &Patient, admission: Option<&Admission>) -> Label {
+    let mut lines = vec![
+        format!(
+            "{}, {}",
+            patient.last_name.to_uppercase(),
+            patient.first_name
+        ),
+        format!("DOB {}  {}", patient.date_of_birth, patient.mrn),
+    ];
+    if let Some(admission) = admission {
+        lines.push(match &admission.bed {
+            Some(bed) => format!("{}  Bed {}", admission.ward, bed),
+            None => admission.ward.clone(),
+        });
+    }
+    if let Some(allergies) = patient.allergies.as_deref().filter(|a| !a.is_empty()) {
+        lines.push(format!("ALLERGIES: {}", allergies));
+    }
+    Label {
+        kind: LabelKind::Wristband,
+        lines,
+        code: patient.mrn.clone(),
+    }
+}
+
+/// The label on a sample or order, coded with the study's accession number.
+pub fn sample_tube(patient: &Patient, study: &ImagingStudy) -> Label {
+    let mut lines = vec![
+        format!("{} {}", patient.first_name, patient.last_name),
+        format!("DOB {}  {}", patient.date_of_birth, patient.mrn),
+        format!("{} {}", study.modality, study.study_date),
+    ];
+    if let Some(order) = study.order_number.as_deref().filter(|o| !o.is_empty()) {
+        lines.push(format!("Order {}", order));
+    }
+    Label {
+        kind: LabelKind::SampleTube,
+        lines,
+        code: study.accession_number.clone(),
+    }
+}
+
+/// The label on medication handed out for `record`'s prescription, one line
+/// per prescribed item. Coded with the patient's record number.
+pub fn pharmacy(patient: &Patient, record: &MedicalRecord, date: &str) -> Label {
+    let mut lines = vec![
+        format!("{} {}", patient.first_name, patient.last_name),
+        format!("{}  Record #{}", patient.mrn, record.id),
+    ];
+    lines.extend(
+        record
+            .prescription
+            .iter()
+            .flat_map(|p| prescribing::items(p))
+            .map(str::to_string),
+    );
+    lines.push(format!("Dispensed {}", date));
+    Label {
+        kind: LabelKind::Pharmacy,
+        lines,
+        code: patient.mrn.clone(),
+    }
+}
+
+impl Label {
+    /// The document to send to the printer in `format`.
+    pub fn render(&self, format: LabelFormat) -> Result<String> {
+        match format {
+            LabelFormat::Text => self.text(),
+            LabelFormat::Zpl => Ok(self.zpl()),
+        }
+    }
+
+    fn text(&self) -> Result<String> {
+        let mut lines = self.lines.clone();
+        lines.push(String::new());
+        lines.extend(barcode::qr_lines(&self.code)?);
+        lines.push(String::new());
+        lines.extend(barcode::code128_lines(&self.code, 3)?);
+        lines.push(self.code.clone());
+        lines.push(String::new());
+        Ok(lines.join("\n"))
+    }
+
+    /// ZPL II for a label of the template's size: a 1 x 10 inch band printed
+    /// lengthways, a 2 x 1 inch tube label or a 4 x 2 inch pharmacy label.
+    fn zpl(&self) -> String {
+        let mut zpl = vec!["^XA".to_string(), "^CI28".to_string()];
+        match self.kind {
+            LabelKind::Wristband => {
+                zpl.push(format!("^PW{}^LL{}", DPI, DPI * 10));
+                // Rotated fields read along the band, so lines step across it.
+                for (i, line) in self.lines.iter().take(4).enumerate() {
+                    let x = 160 - 36 * i as u32;
+                    zpl.push(format!("^FO{},500^A0R,30,30{}", x, field(line)));
+                }
+                zpl.push(format!(
+                    "^FO30,1300^BQN,2,4{}",
+                    field(&format!("MA,{}", self.code))
+                ));
+                zpl.push(format!("^FO40,1600^BY2^BCR,100,N,N{}", field(&self.code)));
+            }
+            LabelKind::SampleTube => {
+                zpl.push(format!("^PW{}^LL{}", DPI * 2, DPI));
+                for (i, line) in self.lines.iter().take(4).enumerate() {
+                    let y = 10 + 24 * i as u32;
+                    zpl.push(format!("^FO15,{}^A0N,22,22{}", y, field(line)));
+                }
+                zpl.push(format!("^FO15,115^BY1^BCN,60,N,N{}", field(&self.code)));
+                zpl.push(format!(
+                    "^FO300,10^BQN,2,3{}",
+                    field(&format!("MA,{}", self.code))
+                ));
+            }
+            LabelKind::Pharmacy => {
+                zpl.push(format!("^PW{}^LL{}", DPI * 4, DPI * 2));
+                for (i, line) in self.lines.iter().take(10).enumerate() {
+                    let y = 15 + 36 * i as u32;
+                    zpl.push(format!("^FO20,{}^A0N,30,30{}", y, field(line)));
+                }
+                zpl.push(format!(
+                    "^FO640,15^BQN,2,4{}",
+                    field(&format!("MA,{}", self.code))
+                ));
+            }
+        }
+        zpl.push("^XZ".to_string());
+        zpl.join("\n") + "\n"
+    }
+}
+
+/// A ZPL field holding `text`, with the characters ZPL treats as commands
+/// written as hex escapes.
+fn field(text: &str) -> String {
+    let escaped = text
+        .replace('_', "_5F")
+        .replace('^', "_5E")
+        .replace('~', "_7E");
+    format!("^FH^FD{}^FS", escaped)
+}
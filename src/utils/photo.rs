@@ -0,0 +1,202 @@
+//! Patient photos, taken by whatever camera program the site has, configured
+//! under `[photo]`:
+//!
+//! ```toml
+//! [photo]
+//! command = ["fswebcam", "--no-banner", "-r", "640x480", "{file}"]
+//! ```
+//!
+//! `{file}` is replaced with the path the picture is to be written to. PPM
+//! and PGM pictures are previewed as they are; other formats go through
+//! ImageMagick (`magick` or `convert`) when it is installed.
+
+use super::config;
+use super::datetime;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Longest side, in pixels, previews are kept at; more than any details
+/// pane can show in half blocks.
+const PREVIEW_SIZE: usize = 96;
+
+/// Decoded RGB pixels, row by row.
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Nearest-neighbour scaled down so neither side exceeds `max`.
+    fn shrink(self, max: usize) -> Image {
+        if self.width <= max && self.height <= max {
+            return self;
+        }
+        let (width, height) = if self.width >= self.height {
+            (max, (self.height * max / self.width).max(1))
+        } else {
+            ((self.width * max / self.height).max(1), max)
+        };
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| self.pixel(x * self.width / width, y * self.height / height))
+            .collect();
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Runs the configured camera program for a new picture of the patient and
+/// returns where it was saved.
+pub fn capture(patient_id: i64) -> Result<PathBuf> {
+    let config = config::config();
+    let settings = &config.photo;
+    let Some((program, args)) = settings.command.split_first() else {
+        bail!("No camera command is configured under [photo]");
+    };
+    fs::create_dir_all(&settings.dir)
+        .with_context(|| format!("Failed to create {}", settings.dir.display()))?;
+    let path = settings.dir.join(format!(
+        "patient_{}_{}.{}",
+        patient_id,
+        datetime::file_stamp(datetime::now()),
+        settings.extension
+    ));
+    let file = path.to_string_lossy();
+    let output = Command::new(program)
+        .args(args.iter().map(|arg| arg.replace("{file}", &file)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !output.status.success() {
+        bail!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    if !path.exists() {
+        bail!("{} did not save a picture to {}", program, path.display());
+    }
+    Ok(path)
+}
+
+/// Reads the picture at `path`, scaled down for previewing.
+pub fn load_preview(path: &Path) -> Result<Image> {
+    let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let image = match decode_netpbm(&data) {
+        Some(image) => image,
+        None => decode_netpbm(&convert_to_ppm(path)?)
+            .with_context(|| format!("Could not decode {}", path.display()))?,
+    };
+    Ok(image.shrink(PREVIEW_SIZE))
+}
+
+/// Asks ImageMagick for a preview-sized PPM copy of the picture.
+fn convert_to_ppm(path: &Path) -> Result<Vec<u8>> {
+    let size = format!("{0}x{0}>", PREVIEW_SIZE);
+    let mut ran = false;
+    for program in ["magick", "convert"] {
+        let Ok(output) = Command::new(program)
+            .arg(path)
+            .args(["-resize", &size, "ppm:-"])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+        else {
+            continue;
+        };
+        if output.status.success() {
+            return Ok(output.stdout);
+        }
+        ran = true;
+    }
+    if ran {
+        bail!("Could not decode {}", path.display());
+    }
+    bail!("Install ImageMagick to preview pictures that are not PPM or PGM")
+}
+
+/// Decodes plain or binary PPM and PGM, or `None` for anything else.
+fn decode_netpbm(data: &[u8]) -> Option<Image> {
+    let (channels, binary) = match data.get(..2)? {
+        b"P2" => (1, false),
+        b"P3" => (3, false),
+        b"P5" => (1, true),
+        b"P6" => (3, true),
+        _ => return None,
+    };
+    let mut pos = 2;
+    let width = next_number(data, &mut pos)?;
+    let height = next_number(data, &mut pos)?;
+    let max = next_number(data, &mut pos)?;
+    if width == 0 || height == 0 || max == 0 || max > usize::from(u16::MAX) {
+        return None;
+    }
+    let count = width.checked_mul(height)?.checked_mul(channels)?;
+    let samples: Vec<usize> = if binary {
+        // A single whitespace byte separates the header from the samples.
+        pos += 1;
+        if max > 255 {
+            data.get(pos..pos + count * 2)?
+                .chunks_exact(2)
+                .map(|pair| usize::from(u16::from_be_bytes([pair[0], pair[1]])))
+                .collect()
+        } else {
+            data.get(pos..pos + count)?
+                .iter()
+                .map(|&sample| usize::from(sample))
+                .collect()
+        }
+    } else {
+        (0..count)
+            .map(|_| next_number(data, &mut pos))
+            .collect::<Option<_>>()?
+    };
+    let level = |sample: usize| (sample.min(max) * 255 / max) as u8;
+    let pixels = samples
+        .chunks_exact(channels)
+        .map(|pixel| match pixel {
+            [r, g, b] => [level(*r), level(*g), level(*b)],
+            _ => [level(pixel[0]); 3],
+        })
+        .collect();
+    Some(Image {
+        width,
+        height,
+        pixels,
+    })
+}
+
+/// The next decimal number of a netpbm header or plain-text body, skipping
+/// whitespace and `#` comments.
+fn next_number(data: &[u8], pos: &mut usize) -> Option<usize> {
+    loop {
+        match *data.get(*pos)? {
+            b'#' => {
+                while *data.get(*pos)? != b'\n' {
+                    *pos += 1;
+                }
+            }
+            byte if byte.is_ascii_whitespace() => *pos += 1,
+            _ => break,
+        }
+    }
+    let start = *pos;
+    while data.get(*pos).is_some_and(u8::is_ascii_digit) {
+        *pos += 1;
+    }
+    std::str::from_utf8(&data[start..*pos]).ok()?.parse().ok()
+}
@@ -0,0 +1,323 @@
+//! Safety checks run when a prescription is entered: each prescribed item
+//! against the patient's allergies, and against the other items and the
+//! patient's current medications for known interactions.
+//!
+//! Prescriptions and allergy lists are free text, so both are split into
+//! items (one per line or `;` for prescriptions, also `,` for allergies) and
+//! drugs are recognised by name. The tables below are deliberately small;
+//! they catch the common dangerous combinations, not every interaction.
+
+use crate::models::Patient;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Blocks saving until overridden with a reason.
+    Severe,
+    /// Shown for the prescriber to acknowledge.
+    Moderate,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Severe => "Severe",
+            Severity::Moderate => "Moderate",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alert {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Drug classes, so that an allergy or interaction given for a class
+/// catches every drug in it.
+const CLASSES: &[(&str, &[&str])] = &[
+    (
+        "penicillin",
+        &[
+            "penicillin",
+            "amoxicillin",
+            "ampicillin",
+            "flucloxacillin",
+            "piperacillin",
+            "co-amoxiclav",
+        ],
+    ),
+    (
+        "cephalosporin",
+        &[
+            "cefalexin",
+            "cephalexin",
+            "cefuroxime",
+            "ceftriaxone",
+            "cefotaxime",
+            "cefazolin",
+            "ceftazidime",
+        ],
+    ),
+    (
+        "sulfonamide",
+        &["sulfamethoxazole", "co-trimoxazole", "sulfasalazine"],
+    ),
+    (
+        "nsaid",
+        &[
+            "ibuprofen",
+            "naproxen",
+            "diclofenac",
+            "aspirin",
+            "celecoxib",
+            "ketorolac",
+            "indomethacin",
+        ],
+    ),
+    (
+        "opioid",
+        &[
+            "morphine",
+            "codeine",
+            "tramadol",
+            "oxycodone",
+            "fentanyl",
+            "pethidine",
+        ],
+    ),
+    (
+        "macrolide",
+        &["erythromycin", "clarithromycin", "azithromycin"],
+    ),
+    (
+        "ssri",
+        &[
+            "fluoxetine",
+            "sertraline",
+            "citalopram",
+            "escitalopram",
+            "paroxetine",
+        ],
+    ),
+    (
+        "benzodiazepine",
+        &["diazepam", "lorazepam", "midazolam", "alprazolam"],
+    ),
+    (
+        "ace inhibitor",
+        &["lisinopril", "ramipril", "enalapril", "perindopril"],
+    ),
+    (
+        "nitrate",
+        &[
+            "glyceryl trinitrate",
+            "isosorbide mononitrate",
+            "isosorbide dinitrate",
+        ],
+    ),
+];
+
+/// An allergy to the first class is a warning for drugs of the second.
+const CROSS_REACTIONS: &[(&str, &str)] = &[("penicillin", "cephalosporin")];
+
+/// Pairs of drugs or classes that should not be given together, or only
+/// with care.
+const INTERACTIONS: &[(&str, &str, Severity, &str)] = &[
+    (
+        "warfarin",
+        "nsaid",
+        Severity::Severe,
+        "raises the risk of serious bleeding",
+    ),
+    (
+        "warfarin",
+        "macrolide",
+        Severity::Moderate,
+        "can raise the INR; monitor it closely",
+    ),
+    (
+        "simvastatin",
+        "clarithromycin",
+        Severity::Severe,
+        "raises the risk of myopathy and rhabdomyolysis",
+    ),
+    (
+        "methotrexate",
+        "trimethoprim",
+        Severity::Severe,
+        "can cause bone marrow suppression",
+    ),
+    (
+        "methotrexate",
+        "co-trimoxazole",
+        Severity::Severe,
+        "can cause bone marrow suppression",
+    ),
+    (
+        "sildenafil",
+        "nitrate",
+        Severity::Severe,
+        "can cause a dangerous fall in blood pressure",
+    ),
+    (
+        "opioid",
+        "benzodiazepine",
+        Severity::Severe,
+        "risk of profound sedation and respiratory depression",
+    ),
+    (
+        "ssri",
+        "tramadol",
+        Severity::Severe,
+        "risk of serotonin syndrome",
+    ),
+    (
+        "ace inhibitor",
+        "spironolactone",
+        Severity::Moderate,
+        "risk of high potassium; check it after starting",
+    ),
+    (
+        "ace inhibitor",
+        "nsaid",
+        Severity::Moderate,
+        "can worsen kidney function and blunt the blood pressure effect",
+    ),
+    (
+        "lithium",
+        "nsaid",
+        Severity::Moderate,
+        "raises lithium levels",
+    ),
+    (
+        "digoxin",
+        "amiodarone",
+        Severity::Moderate,
+        "raises digoxin levels; consider halving the dose",
+    ),
+];
+
+/// The items of a prescription, one per line or `;`.
+pub fn items(prescription: &str) -> impl Iterator<Item = &str> {
+    prescription
+        .split(['\n', ';'])
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+/// The patient's allergies as a list, without "none" and its variants.
+pub fn allergies(patient: &Patient) -> Vec<String> {
+    patient
+        .allergies
+        .as_deref()
+        .unwrap_or_default()
+        .split(['\n', ';', ','])
+        .map(|a| a.trim().to_lowercase())
+        .filter(|a| !a.is_empty() && !matches!(a.as_str(), "none" | "nka" | "nkda" | "-"))
+        .collect()
+}
+
+/// The drug names and classes `item` is recognised as.
+fn names(item: &str) -> Vec<&'static str> {
+    let item = item.to_lowercase();
+    let mut names = Vec::new();
+    for (class, drugs) in CLASSES {
+        for drug in drugs.iter().filter(|drug| item.contains(*drug)) {
+            names.push(*drug);
+            if !names.contains(class) {
+                names.push(class);
+            }
+        }
+    }
+    for (a, b, _, _) in INTERACTIONS {
+        for term in [a, b] {
+            if item.contains(term) && !names.contains(term) {
+                names.push(term);
+            }
+        }
+    }
+    names
+}
+
+/// `allergy` names `class`, allowing a plural ("NSAIDs").
+fn names_class(allergy: &str, class: &str) -> bool {
+    allergy == class || allergy.strip_suffix('s') == Some(class)
+}
+
+fn allergy_alert(item: &str, names: &[&str], allergy: &str) -> Option<Alert> {
+    let direct = item.to_lowercase().contains(allergy)
+        || names.iter().any(|name| names_class(allergy, name));
+    if direct {
+        return Some(Alert {
+            severity: Severity::Severe,
+            message: format!("{}: the patient is allergic to {}", item, allergy),
+        });
+    }
+    CROSS_REACTIONS
+        .iter()
+        .find(|(from, to)| names_class(allergy, from) && names.contains(to))
+        .map(|(_, to)| Alert {
+            severity: Severity::Moderate,
+            message: format!(
+                "{}: {} allergy on record; some patients also react to {}s",
+                item, allergy, to
+            ),
+        })
+}
+
+/// Everything worth warning about in `prescription` for `patient`, severe
+/// alerts first.
+pub fn check(prescription: &str, patient: &Patient) -> Vec<Alert> {
+    let prescribed: Vec<(&str, Vec<&str>)> = items(prescription)
+        .map(|item| (item, names(item)))
+        .collect();
+    let current: Vec<(&str, Vec<&str>)> = patient
+        .current_medications
+        .as_deref()
+        .map(|meds| {
+            meds.split(['\n', ';', ','])
+                .map(str::trim)
+                .filter(|m| !m.is_empty())
+                .map(|m| (m, names(m)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut alerts = Vec::new();
+    let allergies = allergies(patient);
+    for (item, names) in &prescribed {
+        alerts.extend(
+            allergies
+                .iter()
+                .filter_map(|allergy| allergy_alert(item, names, allergy)),
+        );
+    }
+
+    // Every pair with at least one newly prescribed item.
+    let mut pairs = Vec::new();
+    for (i, first) in prescribed.iter().enumerate() {
+        for second in prescribed[i + 1..].iter().chain(&current) {
+            pairs.push((first, second));
+        }
+    }
+    for ((item_a, names_a), (item_b, names_b)) in pairs {
+        for (a, b, severity, effect) in INTERACTIONS {
+            let interacts = (names_a.contains(a) && names_b.contains(b))
+                || (names_a.contains(b) && names_b.contains(a));
+            if interacts {
+                alerts.push(Alert {
+                    severity: *severity,
+                    message: format!("{} with {}: {}", item_a, item_b, effect),
+                });
+            }
+        }
+    }
+    let mut unique: Vec<Alert> = Vec::new();
+    for alert in alerts {
+        if !unique.contains(&alert) {
+            unique.push(alert);
+        }
+    }
+    unique.sort_by_key(|alert| alert.severity);
+    unique
+}
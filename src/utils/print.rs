@@ -0,0 +1,110 @@
+//! Sends plain-text documents to the operating system's print spooler: CUPS
+//! `lp` on Linux and macOS, `Out-Printer` on Windows.
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Printer {
+    pub name: String,
+    /// Whether this is the system's default destination.
+    pub is_default: bool,
+}
+
+/// The printers the spooler knows about, the system default first.
+pub fn printers() -> Result<Vec<Printer>> {
+    let (names, default) = if cfg!(target_os = "windows") {
+        let names = powershell("Get-Printer | ForEach-Object Name", "")?;
+        let default = powershell(
+            "Get-CimInstance Win32_Printer -Filter 'Default=True' | ForEach-Object Name",
+            "",
+        )
+        .unwrap_or_default();
+        (names, default.trim().to_string())
+    } else {
+        let names = run(Command::new("lpstat").arg("-e"), "")?;
+        // "system default destination: office" or "no system default destination"
+        let default = run(Command::new("lpstat").arg("-d"), "")
+            .ok()
+            .and_then(|out| out.split_once(':').map(|(_, name)| name.trim().to_string()))
+            .unwrap_or_default();
+        (names, default)
+    };
+    let mut printers: Vec<Printer> = names
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| Printer {
+            name: name.to_string(),
+            is_default: name == default,
+        })
+        .collect();
+    printers.sort_by_key(|printer| !printer.is_default);
+    Ok(printers)
+}
+
+/// Queues `text` on `printer` as a job named `title`.
+pub fn print(printer: &str, title: &str, text: &str) -> Result<()> {
+    if cfg!(target_os = "windows") {
+        let script = format!(
+            "$input | Out-Printer -Name '{}'",
+            printer.replace('\'', "''")
+        );
+        powershell(&script, text)?;
+    } else {
+        run(Command::new("lp").args(["-d", printer, "-t", title]), text)?;
+    }
+    Ok(())
+}
+
+/// Queues `data` on `printer` unchanged, for printer languages such as ZPL.
+/// Only CUPS can pass data through untouched.
+pub fn print_raw(printer: &str, title: &str, data: &str) -> Result<()> {
+    if cfg!(target_os = "windows") {
+        anyhow::bail!("Raw printing needs CUPS; print labels as text on Windows");
+    }
+    run(
+        Command::new("lp").args(["-d", printer, "-t", title, "-o", "raw"]),
+        data,
+    )?;
+    Ok(())
+}
+
+fn powershell(script: &str, input: &str) -> Result<String> {
+    run(
+        Command::new("powershell").args(["-NoProfile", "-Command", script]),
+        input,
+    )
+}
+
+/// Runs `command` with `input` on stdin, failing with its stderr when it does.
+fn run(command: &mut Command, input: &str) -> Result<String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Printing is unavailable: failed to run {}", program))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .with_context(|| format!("Failed to write to {}", program))?;
+    }
+    let Output {
+        status,
+        stdout,
+        stderr,
+    } = child
+        .wait_with_output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !status.success() {
+        anyhow::bail!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&stdout).into_owned())
+}
@@ -0,0 +1,132 @@
+//! Procedure codes, such as ICHI or CPT, recorded on medical records next to
+//! the diagnosis and billed on invoices.
+//!
+//! The catalog lives in the `procedures` table and is loaded from a CSV file
+//! of `code,name,price` lines with `rustoria --import-procedures <file>`.
+//! Importing again updates the names and prices of codes already there. The
+//! catalog is reference data and is not synced; each installation imports
+//! the same file.
+
+use crate::db::{self, Db};
+use crate::models::Procedure;
+use crate::utils::parse_csv_line;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// The codes in a record's procedures field, in order and without repeats.
+/// Codes are separated by commas, semicolons or line breaks.
+pub fn codes(text: &str) -> Vec<String> {
+    let mut codes: Vec<String> = Vec::new();
+    for code in text.split([',', ';', '\n']).map(str::trim) {
+        if !code.is_empty() && !codes.iter().any(|c| c.eq_ignore_ascii_case(code)) {
+            codes.push(code.to_string());
+        }
+    }
+    codes
+}
+
+/// `text` with `code` appended, unless it is already listed.
+pub fn add_code(text: &str, code: &str) -> String {
+    let mut codes = codes(text);
+    if !codes.iter().any(|c| c.eq_ignore_ascii_case(code)) {
+        codes.push(code.to_string());
+    }
+    codes.join(", ")
+}
+
+/// The catalog entry for `code`, ignoring case.
+pub fn find<'a>(catalog: &'a [Procedure], code: &str) -> Option<&'a Procedure> {
+    catalog.iter().find(|p| p.code.eq_ignore_ascii_case(code))
+}
+
+/// The codes in `text` that are not in the catalog.
+pub fn unknown_codes(text: &str, catalog: &[Procedure]) -> Vec<String> {
+    codes(text)
+        .into_iter()
+        .filter(|code| find(catalog, code).is_none())
+        .collect()
+}
+
+/// Catalog entries whose code starts with `query` or whose name contains
+/// it, codes first.
+pub fn search<'a>(catalog: &'a [Procedure], query: &str) -> Vec<&'a Procedure> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return catalog.iter().collect();
+    }
+    let (mut by_code, by_name): (Vec<&Procedure>, Vec<&Procedure>) = catalog
+        .iter()
+        .filter(|p| {
+            p.code.to_lowercase().starts_with(&query) || p.name.to_lowercase().contains(&query)
+        })
+        .partition(|p| p.code.to_lowercase().starts_with(&query));
+    by_code.extend(by_name);
+    by_code
+}
+
+/// What [`import`] did with the file.
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub updated: usize,
+    /// Line numbers that had no code or name, or a price that is not a number.
+    pub skipped: Vec<usize>,
+}
+
+impl std::fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Added {} procedure(s), updated {}",
+            self.added, self.updated
+        )?;
+        if !self.skipped.is_empty() {
+            let lines: Vec<String> = self.skipped.iter().map(|n| n.to_string()).collect();
+            write!(f, ", skipped line(s) {}", lines.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Adds or updates every `code,name,price` line of the CSV file at `path`.
+/// A first line starting with `code` is taken as a header, and a missing
+/// price is 0.
+pub fn import(db: &Db, path: &Path) -> Result<ImportSummary> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut summary = ImportSummary::default();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = parse_csv_line(line);
+        let field = |i: usize| fields.get(i).map_or("", |f| f.trim());
+        if index == 0 && field(0).eq_ignore_ascii_case("code") {
+            continue;
+        }
+        let price = match field(2) {
+            "" => Some(0.0),
+            price => price
+                .parse::<f64>()
+                .ok()
+                .filter(|p| p.is_finite() && *p >= 0.0),
+        };
+        let (Some(price), false, false) = (price, field(0).is_empty(), field(1).is_empty()) else {
+            summary.skipped.push(index + 1);
+            continue;
+        };
+        let procedure = Procedure {
+            id: 0,
+            code: field(0).to_string(),
+            name: field(1).to_string(),
+            price,
+        };
+        if db::upsert_procedure(db, &procedure)? {
+            summary.added += 1;
+        } else {
+            summary.updated += 1;
+        }
+    }
+    Ok(summary)
+}
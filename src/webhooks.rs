@@ -0,0 +1,223 @@
+//! Outbound webhooks, listed as `[[webhooks]]` in the configuration file:
+//!
+//! ```toml
+//! [[webhooks]]
+//! url = "https://example.org/rustoria"
+//! secret = "shared-secret"
+//! events = ["appointment_booked", "invoice_paid"]
+//! ```
+//!
+//! Every event is queued in the database before it is sent, so nothing is
+//! lost when an endpoint is down: failed deliveries are retried with a
+//! growing delay until [`MAX_ATTEMPTS`] is reached, and every attempt shows
+//! in the delivery log screen. The body is
+//! `{"event", "id", "occurred_at", "data"}` and, with a secret, is signed as
+//! `X-Rustoria-Signature: sha256=<hex HMAC-SHA256 of the body>`.
+//!
+//! There is no critical lab result event: Rustoria stores no lab results or
+//! their flags, so it has nothing to raise one from.
+
+use crate::components::toast::{self, Toast};
+use crate::db::{self, Db};
+use crate::models::{DeliveryStatus, WebhookDelivery};
+use crate::utils::sha256::{hex, hmac_sha256};
+use crate::utils::{config, datetime, post_json};
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Attempts made before a delivery is marked as failed.
+pub const MAX_ATTEMPTS: i64 = 8;
+/// Longest wait between two attempts.
+const MAX_BACKOFF_MINUTES: i64 = 60;
+/// How often the queue is checked for retries that have come due.
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static LAST_CHECK: Mutex<Option<Instant>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy)]
+pub enum WebhookEvent {
+    AppointmentBooked,
+    /// Sent for each invoice a payment settles in full; payments settle a
+    /// patient's oldest invoices first.
+    InvoicePaid,
+}
+
+const EVENTS: [WebhookEvent; 2] = [WebhookEvent::AppointmentBooked, WebhookEvent::InvoicePaid];
+
+impl WebhookEvent {
+    /// The name listed under `events` and sent as `X-Rustoria-Event`.
+    fn name(self) -> &'static str {
+        match self {
+            WebhookEvent::AppointmentBooked => "appointment_booked",
+            WebhookEvent::InvoicePaid => "invoice_paid",
+        }
+    }
+}
+
+/// Queues `record` for every webhook subscribed to `event` and starts
+/// sending it in the background.
+pub fn notify(db: &Db, event: WebhookEvent, id: i64, record: impl Serialize) {
+    let targets: Vec<_> = config::config()
+        .webhooks
+        .iter()
+        .filter(|w| w.events.iter().any(|e| e == event.name()))
+        .cloned()
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+    let now = datetime::now_text();
+    let payload = match serde_json::to_value(record) {
+        Ok(data) => json!({
+            "event": event.name(),
+            "id": id,
+            "occurred_at": now,
+            "data": data,
+        })
+        .to_string(),
+        Err(e) => {
+            report(e.into());
+            return;
+        }
+    };
+    for target in targets {
+        let queued = db::queue_webhook_delivery(
+            db,
+            &WebhookDelivery {
+                id: 0,
+                event: event.name().to_string(),
+                url: target.url,
+                payload: payload.clone(),
+                status: DeliveryStatus::Pending,
+                attempts: 0,
+                next_attempt_at: now.clone(),
+                last_error: None,
+                created_at: now.clone(),
+                delivered_at: None,
+            },
+        );
+        if let Err(e) = queued {
+            report(e);
+        }
+    }
+    deliver_due(db);
+}
+
+/// Called on every UI tick; sends retries that have come due, checking the
+/// queue at most every [`RETRY_INTERVAL`].
+pub fn tick(db: &Db) {
+    if config::config().webhooks.is_empty() || db.is_read_only() {
+        return;
+    }
+    let mut last_check = LAST_CHECK.lock().unwrap_or_else(|e| e.into_inner());
+    if last_check.is_some_and(|at| at.elapsed() < RETRY_INTERVAL) {
+        return;
+    }
+    *last_check = Some(Instant::now());
+    drop(last_check);
+    deliver_due(db);
+}
+
+/// Sends every due delivery on a background thread, unless a run is
+/// already going.
+pub fn deliver_due(db: &Db) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let db = db.clone();
+    let spawned = thread::Builder::new()
+        .name("rustoria-webhooks".to_string())
+        .spawn(move || {
+            let result = run_queue(&db);
+            RUNNING.store(false, Ordering::SeqCst);
+            if let Err(e) = result {
+                report(e);
+            }
+        });
+    if let Err(e) = spawned {
+        RUNNING.store(false, Ordering::SeqCst);
+        report(e.into());
+    }
+}
+
+fn report(error: anyhow::Error) {
+    toast::push(Toast::error(format!("Webhook failed: {:#}", error)));
+}
+
+fn run_queue(db: &Db) -> Result<()> {
+    let config = config::config();
+    let webhooks = &config.webhooks;
+    for mut delivery in db::get_due_webhook_deliveries(db, &datetime::now_text())? {
+        let target = webhooks.iter().find(|w| w.url == delivery.url);
+        let result = match target {
+            Some(target) => send(target.secret.as_deref(), &delivery),
+            None => Err(anyhow!("{} is no longer configured", delivery.url)),
+        };
+        delivery.attempts += 1;
+        let now = datetime::now();
+        match result {
+            Ok(()) => {
+                delivery.status = DeliveryStatus::Delivered;
+                delivery.delivered_at = Some(datetime::format_timestamp(now));
+                delivery.last_error = None;
+            }
+            Err(e) => {
+                delivery.last_error = Some(format!("{:#}", e));
+                if target.is_none() || delivery.attempts >= MAX_ATTEMPTS {
+                    delivery.status = DeliveryStatus::Failed;
+                    toast::push(Toast::error(format!(
+                        "Webhook {} to {} failed after {} attempt(s)",
+                        delivery.event, delivery.url, delivery.attempts
+                    )));
+                } else {
+                    let minutes = (1i64 << (delivery.attempts - 1)).min(MAX_BACKOFF_MINUTES);
+                    delivery.next_attempt_at =
+                        datetime::format_timestamp(now + time::Duration::minutes(minutes));
+                }
+            }
+        }
+        db::record_webhook_attempt(db, &delivery)?;
+    }
+    Ok(())
+}
+
+fn send(secret: Option<&str>, delivery: &WebhookDelivery) -> Result<()> {
+    let id = delivery.id.to_string();
+    let mut headers = vec![
+        ("X-Rustoria-Event", delivery.event.as_str()),
+        ("X-Rustoria-Delivery", id.as_str()),
+    ];
+    let signature = secret.map(|secret| {
+        format!(
+            "sha256={}",
+            hex(&hmac_sha256(secret.as_bytes(), delivery.payload.as_bytes()))
+        )
+    });
+    if let Some(signature) = &signature {
+        headers.push(("X-Rustoria-Signature", signature.as_str()));
+    }
+    post_json(&delivery.url, &delivery.payload, &headers)
+}
+
+/// Warns about `events` entries this version does not send, so a typo in
+/// the configuration file does not go unnoticed.
+pub fn check_config() {
+    for webhook in &config::config().webhooks {
+        for name in &webhook.events {
+            if !EVENTS.iter().any(|event| event.name() == name) {
+                toast::push(Toast::error(format!(
+                    "Unknown webhook event \"{}\" for {} in {}",
+                    name,
+                    webhook.url,
+                    config::path().display()
+                )));
+            }
+        }
+    }
+}